@@ -522,6 +522,16 @@ fn recurse_into_selection_set(
                     "not_has_substring",
                     "regex",
                     "not_regex",
+                    "=i",
+                    "!=i",
+                    "has_prefix_i",
+                    "not_has_prefix_i",
+                    "has_suffix_i",
+                    "not_has_suffix_i",
+                    "has_substring_i",
+                    "not_has_substring_i",
+                    "regex_i",
+                    "not_regex_i",
                 };
                 let list_only_operators = btreeset! {
                     "contains",
@@ -643,10 +653,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type GitHubActionsImportedStep implements _AnonType5 {
   _AnonField: String
@@ -715,10 +730,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type GitHubActionsImportedStep implements _AnonType5 {
   _AnonField: String
@@ -784,10 +804,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type GitHubActionsImportedStep implements _AnonType5 {
   _AnonField: String
@@ -856,10 +881,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type GitHubActionsRunStep implements _AnonType5 {
   _AnonField: String
@@ -935,10 +965,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type HackerNewsComment implements _AnonType2 {
   _AnonField: String
@@ -991,10 +1026,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type GitHubRepository implements _AnonType2 {
   _AnonField: String
@@ -1,8 +1,15 @@
 use pyo3::{
     create_exception,
+    prelude::*,
     types::{PyModule, PyModuleMethods},
-    Bound, PyResult, Python,
+    PyErr,
 };
+use trustfall_core::{
+    frontend::{error::FrontendError as BaseFrontendError, Diagnostic as BaseDiagnostic},
+    interpreter::error::QueryArgumentsError as BaseQueryArgumentsError,
+};
+
+use crate::value::FieldValue;
 
 create_exception!(_trustfall_internal, InvalidSchemaError, pyo3::exceptions::PyException);
 create_exception!(_trustfall_internal, ParseError, pyo3::exceptions::PyException);
@@ -18,5 +25,150 @@ pub(crate) fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()>
     m.add("FrontendError", py.get_type::<FrontendError>())?;
     m.add("InvalidIRQueryError", py.get_type::<InvalidIRQueryError>())?;
     m.add("QueryArgumentsError", py.get_type::<QueryArgumentsError>())?;
+    m.add_class::<Span>()?;
+    m.add_class::<Diagnostic>()?;
+    m.add_class::<ArgumentTypeMismatch>()?;
     Ok(())
 }
+
+/// A range of the query text, from the start position up to but not including the end position.
+///
+/// Line and column numbers are one-based.
+#[pyclass(frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Span {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+/// A single error in a query, together with the parts of the query responsible for it.
+#[pyclass(frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    code: String,
+    message: String,
+    spans: Vec<Span>,
+}
+
+impl From<BaseDiagnostic> for Diagnostic {
+    fn from(value: BaseDiagnostic) -> Self {
+        let spans = value
+            .spans
+            .into_iter()
+            .map(|span| Span {
+                start_line: span.start.line,
+                start_column: span.start.column,
+                end_line: span.end.line,
+                end_column: span.end.column,
+            })
+            .collect();
+        Self { code: value.code, message: value.message, spans }
+    }
+}
+
+/// A query argument whose value could not be converted to the type the query requires.
+#[pyclass(frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct ArgumentTypeMismatch {
+    argument_name: String,
+    expected_type: String,
+    value: FieldValue,
+}
+
+/// Converts an error produced while parsing the given query into the matching Python exception.
+///
+/// The exception's `diagnostics` attribute describes each error and where in the query it is.
+pub(crate) fn make_frontend_error(err: BaseFrontendError, query: &str) -> PyErr {
+    let diagnostics: Vec<Diagnostic> =
+        BaseDiagnostic::from_frontend_error(&err, query).into_iter().map(Into::into).collect();
+
+    let py_err = match err {
+        BaseFrontendError::ParseError(parse_err) => ParseError::new_err(format!("{parse_err}")),
+        BaseFrontendError::ValidationError(val_err) => {
+            ValidationError::new_err(format!("{val_err}"))
+        }
+        _ => FrontendError::new_err(format!("{err}")),
+    };
+
+    Python::with_gil(|py| {
+        py_err
+            .value(py)
+            .setattr(pyo3::intern!(py, "diagnostics"), diagnostics)
+            .expect("failed to set exception attribute");
+    });
+    py_err
+}
+
+/// Converts a query arguments error into a Python `QueryArgumentsError` exception.
+///
+/// The exception's `missing_arguments`, `unused_arguments`, `type_errors`,
+/// and `invalid_regex_arguments` attributes describe every problem with the provided arguments.
+pub(crate) fn make_query_arguments_error(err: BaseQueryArgumentsError) -> PyErr {
+    let mut missing_arguments = vec![];
+    let mut unused_arguments = vec![];
+    let mut type_errors = vec![];
+    let mut invalid_regex_arguments = vec![];
+    collect_query_arguments_errors(
+        &err,
+        &mut missing_arguments,
+        &mut unused_arguments,
+        &mut type_errors,
+        &mut invalid_regex_arguments,
+    );
+
+    let py_err = QueryArgumentsError::new_err(format!("{err}"));
+    Python::with_gil(|py| {
+        let value = py_err.value(py);
+        value
+            .setattr(pyo3::intern!(py, "missing_arguments"), missing_arguments)
+            .and_then(|_| value.setattr(pyo3::intern!(py, "unused_arguments"), unused_arguments))
+            .and_then(|_| value.setattr(pyo3::intern!(py, "type_errors"), type_errors))
+            .and_then(|_| {
+                value.setattr(pyo3::intern!(py, "invalid_regex_arguments"), invalid_regex_arguments)
+            })
+            .expect("failed to set exception attribute");
+    });
+    py_err
+}
+
+fn collect_query_arguments_errors(
+    err: &BaseQueryArgumentsError,
+    missing_arguments: &mut Vec<String>,
+    unused_arguments: &mut Vec<String>,
+    type_errors: &mut Vec<ArgumentTypeMismatch>,
+    invalid_regex_arguments: &mut Vec<String>,
+) {
+    match err {
+        BaseQueryArgumentsError::MissingArguments(arguments) => {
+            missing_arguments.extend(arguments.iter().map(|argument| argument.name.clone()))
+        }
+        BaseQueryArgumentsError::UnusedArguments(arguments) => {
+            unused_arguments.extend(arguments.iter().map(|argument| argument.name.clone()))
+        }
+        BaseQueryArgumentsError::ArgumentTypeError(name, expected_type, value) => {
+            type_errors.push(ArgumentTypeMismatch {
+                argument_name: name.clone(),
+                expected_type: expected_type.clone(),
+                value: value.clone().into(),
+            })
+        }
+        BaseQueryArgumentsError::InvalidRegexArgument(name, _) => {
+            invalid_regex_arguments.push(name.clone())
+        }
+        // Only described by the exception's message.
+        BaseQueryArgumentsError::UnidentifiableVertices(_) => {}
+        BaseQueryArgumentsError::MultipleErrors(errors) => {
+            for inner in &errors.0 {
+                collect_query_arguments_errors(
+                    inner,
+                    missing_arguments,
+                    unused_arguments,
+                    type_errors,
+                    invalid_regex_arguments,
+                );
+            }
+        }
+    }
+}
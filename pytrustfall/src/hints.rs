@@ -0,0 +1,271 @@
+use std::{collections::BTreeMap, ops::Bound as RangeBound};
+
+use pyo3::prelude::*;
+use trustfall_core::{
+    interpreter::{
+        CandidateValue as BaseCandidateValue, EdgeInfo as BaseEdgeInfo,
+        NeighborInfo as BaseNeighborInfo, Range as BaseRange,
+        ResolveEdgeInfo as BaseResolveEdgeInfo, ResolveInfo as BaseResolveInfo,
+        StringPattern as BaseStringPattern, VertexInfo,
+    },
+    ir::FieldValue as TrustfallFieldValue,
+};
+
+use crate::value::FieldValue;
+
+pub(crate) fn register(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ResolveInfo>()?;
+    m.add_class::<ResolveEdgeInfo>()?;
+    m.add_class::<NeighborInfo>()?;
+    m.add_class::<EdgeInfo>()?;
+    m.add_class::<CandidateValue>()?;
+    m.add_class::<Range>()?;
+    m.add_class::<StringPattern>()?;
+    Ok(())
+}
+
+/// Defines the Python-facing methods shared by all types implementing [`VertexInfo`].
+macro_rules! vertex_info_methods {
+    ($name:ident) => {
+        #[pymethods]
+        impl $name {
+            #[getter]
+            fn vid(&self) -> usize {
+                self.inner.vid().get().get()
+            }
+
+            #[getter]
+            fn coerced_to_type(&self) -> Option<&str> {
+                self.inner.coerced_to_type().map(|name| name.as_ref())
+            }
+
+            fn required_properties(&self) -> Vec<String> {
+                self.inner.required_properties().map(|prop| prop.name.to_string()).collect()
+            }
+
+            fn statically_required_property(&self, name: &str) -> Option<CandidateValue> {
+                self.inner.statically_required_property(name).map(|inner| CandidateValue { inner })
+            }
+
+            fn first_edge(&self, name: &str) -> Option<EdgeInfo> {
+                self.inner.first_edge(name).map(|inner| EdgeInfo { inner })
+            }
+
+            fn first_mandatory_edge(&self, name: &str) -> Option<EdgeInfo> {
+                self.inner.first_mandatory_edge(name).map(|inner| EdgeInfo { inner })
+            }
+
+            fn edges_with_name(&self, name: &str) -> Vec<EdgeInfo> {
+                self.inner.edges_with_name(name).map(|inner| EdgeInfo { inner }).collect()
+            }
+        }
+    };
+}
+
+#[pyclass(frozen)]
+pub struct ResolveInfo {
+    inner: BaseResolveInfo,
+}
+
+impl ResolveInfo {
+    pub(crate) fn new(inner: &BaseResolveInfo) -> Self {
+        Self { inner: inner.clone() }
+    }
+}
+
+vertex_info_methods!(ResolveInfo);
+
+#[pyclass(frozen)]
+pub struct NeighborInfo {
+    inner: BaseNeighborInfo,
+}
+
+vertex_info_methods!(NeighborInfo);
+
+#[pyclass(frozen)]
+pub struct ResolveEdgeInfo {
+    inner: BaseResolveEdgeInfo,
+}
+
+impl ResolveEdgeInfo {
+    pub(crate) fn new(inner: &BaseResolveEdgeInfo) -> Self {
+        Self { inner: inner.clone() }
+    }
+}
+
+#[pymethods]
+impl ResolveEdgeInfo {
+    #[getter]
+    fn eid(&self) -> usize {
+        self.inner.eid().get().get()
+    }
+
+    #[getter]
+    fn origin_vid(&self) -> usize {
+        self.inner.origin_vid().get().get()
+    }
+
+    #[getter]
+    fn destination_vid(&self) -> usize {
+        self.inner.destination_vid().get().get()
+    }
+
+    #[getter]
+    fn destination(&self) -> NeighborInfo {
+        NeighborInfo { inner: self.inner.destination() }
+    }
+
+    #[getter]
+    fn edge(&self) -> EdgeInfo {
+        EdgeInfo { inner: self.inner.edge() }
+    }
+}
+
+#[pyclass(frozen)]
+pub struct EdgeInfo {
+    inner: BaseEdgeInfo,
+}
+
+#[pymethods]
+impl EdgeInfo {
+    #[getter]
+    fn eid(&self) -> usize {
+        self.inner.eid().get().get()
+    }
+
+    #[getter]
+    fn parameters(&self) -> BTreeMap<String, FieldValue> {
+        self.inner.parameters().iter().map(|(k, v)| (k.to_string(), v.clone().into())).collect()
+    }
+
+    #[getter]
+    fn destination(&self) -> NeighborInfo {
+        NeighborInfo { inner: self.inner.destination().clone() }
+    }
+
+    #[getter]
+    fn is_mandatory(&self) -> bool {
+        self.inner.is_mandatory()
+    }
+}
+
+#[pyclass(frozen)]
+pub struct CandidateValue {
+    inner: BaseCandidateValue<TrustfallFieldValue>,
+}
+
+#[pymethods]
+impl CandidateValue {
+    /// One of `"impossible"`, `"single"`, `"multiple"`, `"range"`, `"string_pattern"`, or `"all"`.
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match &self.inner {
+            BaseCandidateValue::Impossible => "impossible",
+            BaseCandidateValue::Single(_) => "single",
+            BaseCandidateValue::Multiple(_) => "multiple",
+            BaseCandidateValue::Range(_) => "range",
+            BaseCandidateValue::StringPattern(_) => "string_pattern",
+            BaseCandidateValue::All => "all",
+            _ => unreachable!("unhandled candidate value: {:?}", self.inner),
+        }
+    }
+
+    #[getter]
+    fn value(&self) -> Option<FieldValue> {
+        match &self.inner {
+            BaseCandidateValue::Single(value) => Some(value.clone().into()),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn values(&self) -> Option<Vec<FieldValue>> {
+        match &self.inner {
+            BaseCandidateValue::Multiple(values) => {
+                Some(values.iter().cloned().map(Into::into).collect())
+            }
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn range(&self) -> Option<Range> {
+        match &self.inner {
+            BaseCandidateValue::Range(range) => Some(Range { inner: range.clone() }),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn pattern(&self) -> Option<StringPattern> {
+        match &self.inner {
+            BaseCandidateValue::StringPattern(pattern) => {
+                Some(StringPattern { inner: pattern.clone() })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[pyclass(frozen)]
+pub struct Range {
+    inner: BaseRange<TrustfallFieldValue>,
+}
+
+fn bound_value(bound: RangeBound<&TrustfallFieldValue>) -> Option<FieldValue> {
+    match bound {
+        RangeBound::Included(value) | RangeBound::Excluded(value) => Some(value.clone().into()),
+        RangeBound::Unbounded => None,
+    }
+}
+
+#[pymethods]
+impl Range {
+    #[getter]
+    fn start(&self) -> Option<FieldValue> {
+        bound_value(self.inner.start_bound())
+    }
+
+    #[getter]
+    fn start_inclusive(&self) -> bool {
+        matches!(self.inner.start_bound(), RangeBound::Included(_))
+    }
+
+    #[getter]
+    fn end(&self) -> Option<FieldValue> {
+        bound_value(self.inner.end_bound())
+    }
+
+    #[getter]
+    fn end_inclusive(&self) -> bool {
+        matches!(self.inner.end_bound(), RangeBound::Included(_))
+    }
+
+    #[getter]
+    fn null_included(&self) -> bool {
+        self.inner.null_included()
+    }
+}
+
+#[pyclass(frozen)]
+pub struct StringPattern {
+    inner: BaseStringPattern<TrustfallFieldValue>,
+}
+
+#[pymethods]
+impl StringPattern {
+    #[getter]
+    fn prefix(&self) -> Option<FieldValue> {
+        self.inner.prefix().cloned().map(Into::into)
+    }
+
+    #[getter]
+    fn suffix(&self) -> Option<FieldValue> {
+        self.inner.suffix().cloned().map(Into::into)
+    }
+
+    #[getter]
+    fn substrings(&self) -> Vec<FieldValue> {
+        self.inner.substrings().iter().cloned().map(Into::into).collect()
+    }
+}
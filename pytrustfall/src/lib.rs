@@ -9,12 +9,14 @@ use pyo3::{
 };
 
 pub mod errors;
+mod hints;
 pub mod shim;
 mod value;
 
 fn _trustfall_internal(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     shim::register(py, m)?;
     errors::register(py, m)?;
+    hints::register(py, m)?;
     Ok(())
 }
 
@@ -3,11 +3,11 @@ use std::{collections::BTreeMap, sync::Arc};
 use pyo3::{
     exceptions::PyStopIteration,
     prelude::*,
-    types::{PyIterator, PyTuple},
+    types::{PyDict, PyIterator, PyTuple},
     wrap_pyfunction,
 };
 use trustfall_core::{
-    frontend::{error::FrontendError, parse},
+    frontend::parse,
     interpreter::{
         execution::interpret_ir, Adapter, AsVertex, ContextIterator as BaseContextIterator,
         ContextOutcomeIterator, DataContext, ResolveEdgeInfo, ResolveInfo, VertexIterator,
@@ -15,7 +15,7 @@ use trustfall_core::{
     ir::{EdgeParameters, FieldValue as TrustfallFieldValue},
 };
 
-use crate::value::FieldValue;
+use crate::{hints, value::FieldValue};
 
 pub(crate) fn register(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Schema>()?;
@@ -62,18 +62,11 @@ pub fn interpret_query(
 ) -> PyResult<ResultIterator> {
     let wrapped_adapter = Arc::from(adapter);
 
-    let indexed_query = parse(&schema.inner, query).map_err(|err| match err {
-        FrontendError::ParseError(parse_err) => {
-            crate::errors::ParseError::new_err(format!("{parse_err}"))
-        }
-        FrontendError::ValidationError(val_err) => {
-            crate::errors::ValidationError::new_err(format!("{val_err}"))
-        }
-        _ => crate::errors::FrontendError::new_err(format!("{err}")),
-    })?;
+    let indexed_query = parse(&schema.inner, query)
+        .map_err(|err| crate::errors::make_frontend_error(err, query))?;
 
     let execution = interpret_ir(wrapped_adapter, indexed_query, arguments)
-        .map_err(|err| crate::errors::QueryArgumentsError::new_err(format!("{err}")))?;
+        .map_err(crate::errors::make_query_arguments_error)?;
     let owned_iter: Box<dyn Iterator<Item = BTreeMap<String, Py<PyAny>>>> =
         Box::new(execution.map(|res| {
             res.iter()
@@ -117,6 +110,19 @@ impl AdapterShim {
     }
 }
 
+/// Hints about the query being executed are passed to the Python adapter
+/// as the `resolve_info` keyword argument.
+fn make_resolve_info_kwargs<'py, T: IntoPyObject<'py>>(
+    py: Python<'py>,
+    resolve_info: T,
+) -> Bound<'py, PyDict> {
+    let kwargs = PyDict::new(py);
+    kwargs
+        .set_item(pyo3::intern!(py, "resolve_info"), resolve_info)
+        .expect("failed to construct resolve_info keyword argument");
+    kwargs
+}
+
 fn make_iterator<'py>(value: &Bound<'py, PyAny>, origin: &'static str) -> Bound<'py, PyIterator> {
     value.try_iter().unwrap_or_else(|e| panic!("{origin} is not an iterable (caused by {e})"))
 }
@@ -187,7 +193,7 @@ impl Adapter<'static> for AdapterShim {
         &self,
         edge_name: &Arc<str>,
         parameters: &EdgeParameters,
-        _resolve_info: &ResolveInfo,
+        resolve_info: &ResolveInfo,
     ) -> VertexIterator<'static, Self::Vertex> {
         Python::with_gil(|py| {
             let parameter_data: BTreeMap<String, Py<PyAny>> = parameters
@@ -209,7 +215,7 @@ impl Adapter<'static> for AdapterShim {
                     py,
                     pyo3::intern!(py, "resolve_starting_vertices"),
                     (edge_name.as_ref(), parameter_data),
-                    None,
+                    Some(&make_resolve_info_kwargs(py, hints::ResolveInfo::new(resolve_info))),
                 )
                 .unwrap();
 
@@ -223,7 +229,7 @@ impl Adapter<'static> for AdapterShim {
         contexts: BaseContextIterator<'static, V>,
         type_name: &Arc<str>,
         property_name: &Arc<str>,
-        _resolve_info: &ResolveInfo,
+        resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'static, V, TrustfallFieldValue> {
         let contexts = ContextIterator::new(contexts);
         Python::with_gil(|py| {
@@ -233,7 +239,7 @@ impl Adapter<'static> for AdapterShim {
                     py,
                     pyo3::intern!(py, "resolve_property"),
                     (contexts, type_name.as_ref(), property_name.as_ref()),
-                    None,
+                    Some(&make_resolve_info_kwargs(py, hints::ResolveInfo::new(resolve_info))),
                 )
                 .unwrap();
 
@@ -257,7 +263,7 @@ impl Adapter<'static> for AdapterShim {
         type_name: &Arc<str>,
         edge_name: &Arc<str>,
         parameters: &EdgeParameters,
-        _resolve_info: &ResolveEdgeInfo,
+        resolve_info: &ResolveEdgeInfo,
     ) -> ContextOutcomeIterator<'static, V, VertexIterator<'static, Self::Vertex>> {
         let contexts = ContextIterator::new(contexts);
         Python::with_gil(|py| {
@@ -280,7 +286,7 @@ impl Adapter<'static> for AdapterShim {
                     py,
                     pyo3::intern!(py, "resolve_neighbors"),
                     (contexts, type_name.as_ref(), edge_name.as_ref(), parameter_data),
-                    None,
+                    Some(&make_resolve_info_kwargs(py, hints::ResolveEdgeInfo::new(resolve_info))),
                 )
                 .unwrap();
 
@@ -302,7 +308,7 @@ impl Adapter<'static> for AdapterShim {
         contexts: BaseContextIterator<'static, V>,
         type_name: &Arc<str>,
         coerce_to_type: &Arc<str>,
-        _resolve_info: &ResolveInfo,
+        resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'static, V, bool> {
         let contexts = ContextIterator::new(contexts);
         Python::with_gil(|py| {
@@ -312,7 +318,7 @@ impl Adapter<'static> for AdapterShim {
                     py,
                     pyo3::intern!(py, "resolve_coercion"),
                     (contexts, type_name.as_ref(), coerce_to_type.as_ref()),
-                    None,
+                    Some(&make_resolve_info_kwargs(py, hints::ResolveInfo::new(resolve_info))),
                 )
                 .unwrap();
 
@@ -3,6 +3,18 @@ from .execution import execute_query
 
 from ._internals import Schema
 
+# Hints about the query being executed, passed to adapter methods
+# as the `resolve_info` keyword argument.
+from ._internals import (
+    CandidateValue,
+    EdgeInfo,
+    NeighborInfo,
+    Range,
+    ResolveEdgeInfo,
+    ResolveInfo,
+    StringPattern,
+)
+
 # Error types:
 # - ParseError, when the provided input doesn't even parse as valid syntax
 # - ValidationError, when the input is syntactically valid but doesn't match the schema
@@ -23,6 +35,12 @@ from ._internals import (
     ValidationError,
 )
 
+# Structured error details:
+# - Diagnostic and Span, in the `diagnostics` attribute of ParseError, ValidationError,
+#   and FrontendError, pointing to the parts of the query responsible for the error.
+# - ArgumentTypeMismatch, in the `type_errors` attribute of QueryArgumentsError.
+from ._internals import ArgumentTypeMismatch, Diagnostic, Span
+
 __all__ = [
     # from .adapter
     "Adapter",
@@ -33,11 +51,21 @@ __all__ = [
     "execute_query",
     #
     # from ._internals (defined in Rust)
+    "ArgumentTypeMismatch",
+    "CandidateValue",
+    "Diagnostic",
+    "EdgeInfo",
     "FrontendError",
     "InvalidIRQueryError",
     "InvalidSchemaError",
+    "NeighborInfo",
     "ParseError",
     "QueryArgumentsError",
+    "Range",
+    "ResolveEdgeInfo",
+    "ResolveInfo",
     "Schema",
+    "Span",
+    "StringPattern",
     "ValidationError",
 ]
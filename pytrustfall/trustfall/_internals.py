@@ -5,7 +5,17 @@ from . import trustfall
 # https://github.com/PyO3/pyo3/issues/1517
 _trustfall_internal = trustfall._trustfall_internal
 AdapterShim = _trustfall_internal.AdapterShim
+ArgumentTypeMismatch = _trustfall_internal.ArgumentTypeMismatch
+CandidateValue = _trustfall_internal.CandidateValue
+Diagnostic = _trustfall_internal.Diagnostic
+EdgeInfo = _trustfall_internal.EdgeInfo
+NeighborInfo = _trustfall_internal.NeighborInfo
+Range = _trustfall_internal.Range
+ResolveEdgeInfo = _trustfall_internal.ResolveEdgeInfo
+ResolveInfo = _trustfall_internal.ResolveInfo
 Schema = _trustfall_internal.Schema
+StringPattern = _trustfall_internal.StringPattern
+Span = _trustfall_internal.Span
 FrontendError = _trustfall_internal.FrontendError
 InvalidIRQueryError = _trustfall_internal.InvalidIRQueryError
 InvalidSchemaError = _trustfall_internal.InvalidSchemaError
@@ -16,12 +26,22 @@ interpret_query = _trustfall_internal.interpret_query
 
 __all__ = [
     "AdapterShim",
+    "ArgumentTypeMismatch",
+    "CandidateValue",
+    "Diagnostic",
+    "EdgeInfo",
+    "NeighborInfo",
+    "Range",
+    "ResolveEdgeInfo",
+    "ResolveInfo",
     "FrontendError",
     "InvalidIRQueryError",
     "InvalidSchemaError",
     "ParseError",
     "QueryArgumentsError",
     "Schema",
+    "Span",
+    "StringPattern",
     "ValidationError",
     "interpret_query",
 ]
@@ -1,16 +1,123 @@
-from typing import Any, Dict, Iterator, Mapping
+from typing import Any, Dict, Iterator, List, Literal, Mapping, Optional
 
-from .adapter import Adapter
+from .adapter import Adapter, FieldValue
 
 class AdapterShim:
     def __init__(self, adapter: Adapter[Any]) -> None: ...
 
-class FrontendError(Exception): ...
+class Range:
+    @property
+    def start(self) -> Optional[FieldValue]: ...
+    @property
+    def start_inclusive(self) -> bool: ...
+    @property
+    def end(self) -> Optional[FieldValue]: ...
+    @property
+    def end_inclusive(self) -> bool: ...
+    @property
+    def null_included(self) -> bool: ...
+
+class StringPattern:
+    @property
+    def prefix(self) -> Optional[FieldValue]: ...
+    @property
+    def suffix(self) -> Optional[FieldValue]: ...
+    @property
+    def substrings(self) -> List[FieldValue]: ...
+
+class CandidateValue:
+    @property
+    def kind(
+        self,
+    ) -> Literal["impossible", "single", "multiple", "range", "string_pattern", "all"]: ...
+    @property
+    def value(self) -> Optional[FieldValue]: ...
+    @property
+    def values(self) -> Optional[List[FieldValue]]: ...
+    @property
+    def range(self) -> Optional[Range]: ...
+    @property
+    def pattern(self) -> Optional[StringPattern]: ...
+
+class EdgeInfo:
+    @property
+    def eid(self) -> int: ...
+    @property
+    def parameters(self) -> Dict[str, FieldValue]: ...
+    @property
+    def destination(self) -> NeighborInfo: ...
+    @property
+    def is_mandatory(self) -> bool: ...
+
+class _VertexInfo:
+    @property
+    def vid(self) -> int: ...
+    @property
+    def coerced_to_type(self) -> Optional[str]: ...
+    def required_properties(self) -> List[str]: ...
+    def statically_required_property(self, name: str) -> Optional[CandidateValue]: ...
+    def first_edge(self, name: str) -> Optional[EdgeInfo]: ...
+    def first_mandatory_edge(self, name: str) -> Optional[EdgeInfo]: ...
+    def edges_with_name(self, name: str) -> List[EdgeInfo]: ...
+
+class ResolveInfo(_VertexInfo): ...
+class NeighborInfo(_VertexInfo): ...
+
+class ResolveEdgeInfo:
+    @property
+    def eid(self) -> int: ...
+    @property
+    def origin_vid(self) -> int: ...
+    @property
+    def destination_vid(self) -> int: ...
+    @property
+    def destination(self) -> NeighborInfo: ...
+    @property
+    def edge(self) -> EdgeInfo: ...
+
+class Span:
+    @property
+    def start_line(self) -> int: ...
+    @property
+    def start_column(self) -> int: ...
+    @property
+    def end_line(self) -> int: ...
+    @property
+    def end_column(self) -> int: ...
+
+class Diagnostic:
+    @property
+    def code(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+    @property
+    def spans(self) -> List[Span]: ...
+
+class ArgumentTypeMismatch:
+    @property
+    def argument_name(self) -> str: ...
+    @property
+    def expected_type(self) -> str: ...
+    @property
+    def value(self) -> FieldValue: ...
+
+class FrontendError(Exception):
+    diagnostics: List[Diagnostic]
+
 class InvalidIRQueryError(Exception): ...
 class InvalidSchemaError(Exception): ...
-class ParseError(Exception): ...
-class QueryArgumentsError(Exception): ...
-class ValidationError(Exception): ...
+
+class ParseError(Exception):
+    diagnostics: List[Diagnostic]
+
+class QueryArgumentsError(Exception):
+    missing_arguments: List[str]
+    unused_arguments: List[str]
+    type_errors: List[ArgumentTypeMismatch]
+    invalid_regex_arguments: List[str]
+
+class ValidationError(Exception):
+    diagnostics: List[Diagnostic]
 
 class Schema:
     def __init__(self, schema_txt: str) -> None: ...
@@ -63,6 +63,9 @@ class Adapter(Generic[Vertex], metaclass=ABCMeta):
         The caller guarantees that:
         - The specified edge is a starting edge in the schema being queried.
         - Any parameters the edge requires per the schema have values provided.
+
+        The `resolve_info` keyword argument is a `ResolveInfo` describing what the query
+        requires of the starting vertices, such as statically-known property values.
         """
 
     @abstractmethod
@@ -95,6 +98,9 @@ class Adapter(Generic[Vertex], metaclass=ABCMeta):
         - Produce contexts in the same order as the input `contexts` iterator produced them.
         - Produce property values whose type matches the property's type defined in the schema.
         - When a context's active vertex is `None`, its property value is `None`.
+
+        The `resolve_info` keyword argument is a `ResolveInfo` describing the vertex
+        whose property is being resolved.
         """
 
     @abstractmethod
@@ -131,6 +137,9 @@ class Adapter(Generic[Vertex], metaclass=ABCMeta):
         - Produce contexts in the same order as the input `contexts` iterator produced them.
         - Each neighboring vertex is of the type specified for that edge in the schema.
         - When a context's active vertex is None, it has an empty neighbors iterator.
+
+        The `resolve_info` keyword argument is a `ResolveEdgeInfo` describing the edge
+        being resolved and what the query requires of the neighboring vertices.
         """
 
     @abstractmethod
@@ -176,4 +185,7 @@ class Adapter(Generic[Vertex], metaclass=ABCMeta):
         - Produce contexts in the same order as the input `contexts` iterator produced them.
         - Each neighboring vertex is of the type specified for that edge in the schema.
         - When a context's active vertex is `None`, its coercion outcome is `False`.
+
+        The `resolve_info` keyword argument is a `ResolveInfo` describing the vertex
+        being coerced.
         """
@@ -140,7 +140,13 @@ class ExecutionTests(unittest.TestCase):
         query = "this isn't valid syntax"
         args: Dict[str, Any] = {}
 
-        self.assertRaises(ParseError, execute_query, NumbersAdapter(), SCHEMA, query, args)
+        with self.assertRaises(ParseError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        [diagnostic] = ctx.exception.diagnostics
+        self.assertEqual("InvalidGraphQL", diagnostic.code)
+        [span] = diagnostic.spans
+        self.assertEqual((1, 1), (span.start_line, span.start_column))
 
     def test_validation_error(self) -> None:
         query = dedent(
@@ -154,7 +160,16 @@ class ExecutionTests(unittest.TestCase):
         )
         args: Dict[str, Any] = {}
 
-        self.assertRaises(ValidationError, execute_query, NumbersAdapter(), SCHEMA, query, args)
+        with self.assertRaises(ValidationError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        [diagnostic] = ctx.exception.diagnostics
+        self.assertEqual("NonExistentPath", diagnostic.code)
+        [span] = diagnostic.spans
+        self.assertEqual(
+            (3, 9, 3, 20),
+            (span.start_line, span.start_column, span.end_line, span.end_column),
+        )
 
     def test_frontend_error(self) -> None:
         query = dedent(
@@ -169,7 +184,15 @@ class ExecutionTests(unittest.TestCase):
         )
         args: Dict[str, Any] = {}
 
-        self.assertRaises(FrontendError, execute_query, NumbersAdapter(), SCHEMA, query, args)
+        with self.assertRaises(FrontendError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        [diagnostic] = ctx.exception.diagnostics
+        self.assertEqual("MultipleOutputsWithSameName", diagnostic.code)
+        self.assertEqual(
+            [(3, 9), (4, 9)],
+            [(span.start_line, span.start_column) for span in diagnostic.spans],
+        )
 
     def test_query_arguments_error(self) -> None:
         query = dedent(
@@ -185,7 +208,12 @@ class ExecutionTests(unittest.TestCase):
             "not_used": 42,
         }
 
-        self.assertRaises(QueryArgumentsError, execute_query, NumbersAdapter(), SCHEMA, query, args)
+        with self.assertRaises(QueryArgumentsError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        self.assertEqual(["required"], ctx.exception.missing_arguments)
+        self.assertEqual(["not_used"], ctx.exception.unused_arguments)
+        self.assertEqual([], ctx.exception.type_errors)
 
     def test_wrong_argument_type_error(self) -> None:
         query = dedent(
@@ -201,7 +229,33 @@ class ExecutionTests(unittest.TestCase):
             "num": "text instead of a number",
         }
 
-        self.assertRaises(QueryArgumentsError, execute_query, NumbersAdapter(), SCHEMA, query, args)
+        with self.assertRaises(QueryArgumentsError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        [type_error] = ctx.exception.type_errors
+        self.assertEqual("num", type_error.argument_name)
+        self.assertEqual("Int!", type_error.expected_type)
+        self.assertEqual("text instead of a number", type_error.value)
+
+    def test_invalid_regex_argument_error(self) -> None:
+        query = dedent(
+            """\
+            {
+                Number(max: 4) {
+                    name @output @filter(op: "regex", value: ["$pattern"])
+                }
+            }
+            """
+        )
+        args: Dict[str, Any] = {
+            "pattern": "(unclosed",
+        }
+
+        with self.assertRaises(QueryArgumentsError) as ctx:
+            execute_query(NumbersAdapter(), SCHEMA, query, args)
+
+        self.assertEqual(["pattern"], ctx.exception.invalid_regex_arguments)
+        self.assertEqual([], ctx.exception.type_errors)
 
     def test_none_value_for_non_nullable_argument_error(self) -> None:
         query = dedent(
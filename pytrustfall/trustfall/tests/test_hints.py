@@ -0,0 +1,205 @@
+from textwrap import dedent
+from typing import Any, Dict, Iterable, Iterator, List, Mapping, Tuple
+import unittest
+
+from .. import Context, FieldValue, ResolveEdgeInfo, ResolveInfo
+from ..execution import execute_query
+from .numbers_adapter import NumbersAdapter, Vertex
+from .test_execution import SCHEMA
+
+
+class RecordingAdapter(NumbersAdapter):
+    """Records the `resolve_info` hints passed to each adapter method."""
+
+    starting_infos: List[ResolveInfo]
+    property_infos: Dict[str, ResolveInfo]
+    neighbor_infos: Dict[str, ResolveEdgeInfo]
+
+    def __init__(self) -> None:
+        self.starting_infos = []
+        self.property_infos = {}
+        self.neighbor_infos = {}
+
+    def resolve_starting_vertices(
+        self,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Vertex]:
+        self.starting_infos.append(kwargs["resolve_info"])
+        return super().resolve_starting_vertices(edge_name, parameters, *args, **kwargs)
+
+    def resolve_property(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        property_name: str,
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], Any]]:
+        self.property_infos[property_name] = kwargs["resolve_info"]
+        return super().resolve_property(contexts, type_name, property_name, *args, **kwargs)
+
+    def resolve_neighbors(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], Iterable[Vertex]]]:
+        self.neighbor_infos[edge_name] = kwargs["resolve_info"]
+        return super().resolve_neighbors(
+            contexts, type_name, edge_name, parameters, *args, **kwargs
+        )
+
+
+def _run_query(query: str, args: Dict[str, Any]) -> RecordingAdapter:
+    adapter = RecordingAdapter()
+    _ = list(execute_query(adapter, SCHEMA, dedent(query), args))
+    return adapter
+
+
+class ResolveInfoTests(unittest.TestCase):
+    def test_single_candidate_value(self) -> None:
+        adapter = _run_query(
+            """\
+            {
+                Number(max: 10) {
+                    value @filter(op: "=", value: ["$expected"]) @output
+                }
+            }
+            """,
+            {"expected": 3},
+        )
+
+        [info] = adapter.starting_infos
+        self.assertEqual(1, info.vid)
+        self.assertIsNone(info.coerced_to_type)
+        self.assertEqual(["value"], info.required_properties())
+        self.assertIsNone(info.statically_required_property("name"))
+
+        candidate = info.statically_required_property("value")
+        assert candidate is not None
+        self.assertEqual("single", candidate.kind)
+        self.assertEqual(3, candidate.value)
+        self.assertIsNone(candidate.values)
+        self.assertIsNone(candidate.range)
+
+        self.assertEqual(1, adapter.property_infos["value"].vid)
+
+    def test_multiple_candidate_values(self) -> None:
+        adapter = _run_query(
+            """\
+            {
+                Number(max: 10) {
+                    value @filter(op: "one_of", value: ["$expected"]) @output
+                }
+            }
+            """,
+            {"expected": [2, 4]},
+        )
+
+        [info] = adapter.starting_infos
+        candidate = info.statically_required_property("value")
+        assert candidate is not None
+        self.assertEqual("multiple", candidate.kind)
+        self.assertEqual([2, 4], candidate.values)
+
+    def test_range_candidate_value(self) -> None:
+        adapter = _run_query(
+            """\
+            {
+                Number(max: 10) {
+                    value @filter(op: ">=", value: ["$low"])
+                          @filter(op: "<", value: ["$high"])
+                          @output
+                }
+            }
+            """,
+            {"low": 2, "high": 5},
+        )
+
+        [info] = adapter.starting_infos
+        candidate = info.statically_required_property("value")
+        assert candidate is not None
+        self.assertEqual("range", candidate.kind)
+
+        candidate_range = candidate.range
+        assert candidate_range is not None
+        self.assertEqual(2, candidate_range.start)
+        self.assertTrue(candidate_range.start_inclusive)
+        self.assertEqual(5, candidate_range.end)
+        self.assertFalse(candidate_range.end_inclusive)
+        self.assertFalse(candidate_range.null_included)
+
+    def test_string_pattern_candidate_value(self) -> None:
+        adapter = _run_query(
+            """\
+            {
+                Number(max: 10) {
+                    name @filter(op: "has_prefix", value: ["$prefix"])
+                         @filter(op: "has_substring", value: ["$substring"])
+                         @output
+                }
+            }
+            """,
+            {"prefix": "t", "substring": "e"},
+        )
+
+        [info] = adapter.starting_infos
+        candidate = info.statically_required_property("name")
+        assert candidate is not None
+        self.assertEqual("string_pattern", candidate.kind)
+        self.assertIsNone(candidate.range)
+
+        pattern = candidate.pattern
+        assert pattern is not None
+        self.assertEqual("t", pattern.prefix)
+        self.assertIsNone(pattern.suffix)
+        self.assertEqual(["e"], pattern.substrings)
+
+    def test_edge_info(self) -> None:
+        adapter = _run_query(
+            """\
+            {
+                Number(max: 4) {
+                    multiple(max: 3) {
+                        value @filter(op: "=", value: ["$expected"]) @output
+                    }
+                    predecessor @optional {
+                        name @output
+                    }
+                }
+            }
+            """,
+            {"expected": 6},
+        )
+
+        [info] = adapter.starting_infos
+        edge = info.first_edge("multiple")
+        assert edge is not None
+        self.assertEqual({"max": 3}, edge.parameters)
+        self.assertTrue(edge.is_mandatory)
+        self.assertEqual(2, edge.destination.vid)
+
+        neighbor_candidate = edge.destination.statically_required_property("value")
+        assert neighbor_candidate is not None
+        self.assertEqual("single", neighbor_candidate.kind)
+        self.assertEqual(6, neighbor_candidate.value)
+
+        self.assertEqual(1, len(info.edges_with_name("predecessor")))
+        self.assertIsNone(info.first_mandatory_edge("predecessor"))
+        self.assertIsNone(info.first_edge("successor"))
+
+        edge_info = adapter.neighbor_infos["multiple"]
+        self.assertEqual(1, edge_info.origin_vid)
+        self.assertEqual(2, edge_info.destination_vid)
+        self.assertEqual(edge.eid, edge_info.eid)
+        self.assertEqual({"max": 3}, edge_info.edge.parameters)
+        self.assertEqual(["value"], edge_info.destination.required_properties())
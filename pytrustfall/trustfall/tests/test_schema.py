@@ -13,8 +13,8 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
 
 type RootSchemaQuery {
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+# Used to extract all queries over the given schema, together with their arguments,
+# from all valid query test data into files used to seed the `execution` fuzz target's corpus.
+
+# Fail on first error, on undefined variables, and on failures in pipelines.
+set -euo pipefail
+
+# Move relative to the top of the repo, so this script can be run from anywhere.
+cd "$(git rev-parse --show-toplevel)/trustfall_testbin"
+
+target_dir="$1"
+schema_name="$2"
+
+cargo_cmd="(cargo run --release corpus_execution \$0 $2 >\$0.tmp)"
+mv_cmd="mv \$0.tmp $target_dir/\$(basename \$0 | cut -d'.' -f1)"
+rm_cmd="rm \$0.tmp"
+
+find ../trustfall_core/test_data/tests/valid_queries -name '*.graphql.ron' | \
+    xargs -n 1 \
+    sh -c "$cargo_cmd && if [ -s \$0.tmp ]; then $mv_cmd; else $rm_cmd; fi"
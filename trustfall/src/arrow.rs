@@ -0,0 +1,235 @@
+//! Query results as [Apache Arrow](https://arrow.apache.org/) record batches.
+//!
+//! [`execute_query_to_arrow`](crate::execute_query_to_arrow) produces a query's results as
+//! [`RecordBatches`]: a stream of record batches with one column per query output,
+//! in order of output names. Batches are built as results are computed, so only one batch
+//! of results is held in memory at a time.
+//!
+//! Output types map to Arrow types as follows:
+//! - `Int` is `Int64`, `Float` is `Float64`, and `Boolean` is `Boolean`.
+//! - `DateTime` is a nanosecond-precision `Timestamp` in UTC.
+//! - `String`, `ID`, enums, and other custom scalars are `Utf8`.
+//! - Lists, including outputs within `@fold`, are `List`s of their items' type.
+//!
+//! Columns are nullable if their outputs' types are. A value that can't be represented in its
+//! column's type ends the stream with an error: for example, an `Int` value above `i64::MAX`,
+//! or a `DateTime` value before the year 1677 or after the year 2262.
+//!
+//! This module requires the `arrow` feature, which isn't enabled by default.
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, ListArray, RecordBatch, RecordBatchOptions,
+    RecordBatchReader, StringArray, TimestampNanosecondArray,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{ArrowError, DataType, Field, Schema as ArrowSchema, SchemaRef, TimeUnit};
+use trustfall_core::ir::Type;
+
+use crate::{FieldValue, QueryOutputs};
+
+/// The results of a query as Arrow record batches, produced by
+/// [`execute_query_to_arrow`](crate::execute_query_to_arrow).
+///
+/// Each batch has the configured number of rows, except the last one which may have fewer.
+/// A query without results produces no batches. The batches' schema is available
+/// via [`RecordBatchReader::schema()`] before any of them are produced.
+pub struct RecordBatches<'vertex> {
+    schema: SchemaRef,
+    outputs: Vec<(Arc<str>, Type)>,
+    results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>,
+    batch_size: usize,
+    finished: bool,
+}
+
+impl<'vertex> RecordBatches<'vertex> {
+    pub(crate) fn new(
+        outputs: &QueryOutputs,
+        results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>,
+        batch_size: usize,
+    ) -> Self {
+        assert!(batch_size > 0, "record batches must have room for at least one row");
+        Self {
+            schema: Arc::new(arrow_schema(outputs)),
+            outputs: outputs
+                .iter()
+                .map(|output| (output.name.clone(), output.value_type.clone()))
+                .collect(),
+            results,
+            batch_size,
+            finished: false,
+        }
+    }
+
+    fn next_batch(&mut self) -> Option<Result<RecordBatch, ArrowError>> {
+        let rows: Vec<_> = self.results.by_ref().take(self.batch_size).collect();
+        if rows.is_empty() {
+            return None;
+        }
+
+        let columns = self.outputs.iter().map(|(name, ty)| {
+            let values: Vec<_> = rows.iter().map(|row| &row[name]).collect();
+            to_array(&values, ty)
+                .map_err(|e| ArrowError::InvalidArgumentError(format!("Output \"{name}\": {e}")))
+        });
+        let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+        Some(columns.collect::<Result<_, _>>().and_then(|columns| {
+            RecordBatch::try_new_with_options(self.schema.clone(), columns, &options)
+        }))
+    }
+}
+
+impl Iterator for RecordBatches<'_> {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let batch = self.next_batch();
+        self.finished = !matches!(batch, Some(Ok(_)));
+        batch
+    }
+}
+
+impl RecordBatchReader for RecordBatches<'_> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl std::fmt::Debug for RecordBatches<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordBatches")
+            .field("schema", &self.schema)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The Arrow schema of the record batches holding the results of a query with these outputs.
+pub fn arrow_schema(outputs: &QueryOutputs) -> ArrowSchema {
+    ArrowSchema::new(
+        outputs
+            .iter()
+            .map(|output| {
+                Field::new(
+                    output.name.as_ref(),
+                    data_type(&output.value_type),
+                    output.value_type.nullable(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn data_type(ty: &Type) -> DataType {
+    match ty.as_list() {
+        Some(item) => DataType::List(Arc::new(list_item_field(&item))),
+        None => match ty.base_type() {
+            "Int" => DataType::Int64,
+            "Float" => DataType::Float64,
+            "Boolean" => DataType::Boolean,
+            "DateTime" => DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+            _ => DataType::Utf8,
+        },
+    }
+}
+
+fn list_item_field(item: &Type) -> Field {
+    Field::new("item", data_type(item), item.nullable())
+}
+
+fn to_array(values: &[&FieldValue], ty: &Type) -> Result<ArrayRef, ArrowError> {
+    let unrepresentable = |value: &FieldValue| {
+        ArrowError::InvalidArgumentError(format!(
+            "value {value:?} cannot be represented as Arrow type {}",
+            data_type(ty)
+        ))
+    };
+
+    if let Some(item) = ty.as_list() {
+        let mut items = vec![];
+        let mut offsets = vec![0];
+        let mut validity = vec![];
+        for &value in values {
+            match value {
+                FieldValue::List(list) => items.extend(list.iter()),
+                FieldValue::Null => {}
+                _ => return Err(unrepresentable(value)),
+            }
+            validity.push(!matches!(value, FieldValue::Null));
+            offsets.push(i32::try_from(items.len()).map_err(|_| {
+                ArrowError::InvalidArgumentError("lists have too many items in total".to_string())
+            })?);
+        }
+
+        let array = ListArray::try_new(
+            Arc::new(list_item_field(&item)),
+            OffsetBuffer::new(offsets.into()),
+            to_array(&items, &item)?,
+            Some(NullBuffer::from(validity)),
+        )?;
+        return Ok(Arc::new(array));
+    }
+
+    let array: ArrayRef = match data_type(ty) {
+        DataType::Int64 => Arc::new(
+            values
+                .iter()
+                .map(|&value| match value {
+                    FieldValue::Null => Ok(None),
+                    FieldValue::Int64(number) => Ok(Some(*number)),
+                    FieldValue::Uint64(number) => {
+                        i64::try_from(*number).map(Some).map_err(|_| unrepresentable(value))
+                    }
+                    _ => Err(unrepresentable(value)),
+                })
+                .collect::<Result<Int64Array, _>>()?,
+        ),
+        DataType::Float64 => Arc::new(
+            values
+                .iter()
+                .map(|&value| match value {
+                    FieldValue::Null => Ok(None),
+                    FieldValue::Float64(number) => Ok(Some(*number)),
+                    _ => Err(unrepresentable(value)),
+                })
+                .collect::<Result<Float64Array, _>>()?,
+        ),
+        DataType::Boolean => Arc::new(
+            values
+                .iter()
+                .map(|&value| match value {
+                    FieldValue::Null => Ok(None),
+                    FieldValue::Boolean(boolean) => Ok(Some(*boolean)),
+                    _ => Err(unrepresentable(value)),
+                })
+                .collect::<Result<BooleanArray, _>>()?,
+        ),
+        DataType::Timestamp(..) => Arc::new(
+            values
+                .iter()
+                .map(|&value| match value {
+                    FieldValue::Null => Ok(None),
+                    FieldValue::DateTime(datetime) => i64::try_from(datetime.unix_nanos())
+                        .map(Some)
+                        .map_err(|_| unrepresentable(value)),
+                    _ => Err(unrepresentable(value)),
+                })
+                .collect::<Result<TimestampNanosecondArray, _>>()?
+                .with_timezone("UTC"),
+        ),
+        _ => Arc::new(
+            values
+                .iter()
+                .map(|&value| match value {
+                    FieldValue::Null => Ok(None),
+                    FieldValue::String(string) | FieldValue::Enum(string) => Ok(Some(&**string)),
+                    _ => Err(unrepresentable(value)),
+                })
+                .collect::<Result<StringArray, _>>()?,
+        ),
+    };
+    Ok(array)
+}
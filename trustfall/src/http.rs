@@ -0,0 +1,621 @@
+//! Serving Trustfall queries over HTTP.
+//!
+//! [`QueryService`] turns the body of a query request into a streamed JSON response,
+//! independently of any particular HTTP server library. It compiles queries with a
+//! [`QueryCache`], and enforces the [`QueryLimits`] set for it: request size, query timeout,
+//! number of results, and number of queries running at the same time.
+//! [`GraphQLService`] does the same for a [`GraphQLFacade`], for use by GraphQL clients.
+//!
+//! This module requires the `http` feature, and [`GraphQLService`] also requires
+//! the `graphql-server` feature. Neither is enabled by default.
+//!
+//! This module doesn't include an HTTP server. Instead, mount a service in the server
+//! an application already uses: [`QueryService::handle_http`] handles requests made with
+//! the `http` crate's types, as used by `hyper`, `axum`, and other server libraries.
+//! Routing, connection handling, and TLS are left to the server. For example, with `hyper`:
+//! ```ignore
+//! let service = &service;
+//! hyper::server::conn::http1::Builder::new()
+//!     .serve_connection(
+//!         hyper_util::rt::TokioIo::new(stream),
+//!         hyper::service::service_fn(move |request| async move {
+//!             Ok::<_, Infallible>(service.handle_http(request).await)
+//!         }),
+//!     )
+//!     .await?;
+//! ```
+//!
+//! Query execution blocks the thread polling the response body, and the body isn't `Send`
+//! since adapters need not be. Serve connections on a thread meant for blocking work,
+//! e.g. one running its own single-threaded runtime. Servers built on other libraries can
+//! call [`QueryService::handle`] with the request body, and write the returned
+//! [`QueryResponse`]'s chunks to the response body as they are produced.
+//!
+//! # Protocol
+//!
+//! A query request is a `POST` whose body is a JSON object with the query text
+//! and the values of its variables:
+//! ```json
+//! { "query": "{ Number(max: 3) { value @output } }", "variables": {} }
+//! ```
+//!
+//! Results are streamed as they are computed, within a JSON object:
+//! ```json
+//! {"results":[
+//! {"value":1},
+//! {"value":2}
+//! ]}
+//! ```
+//!
+//! Requests that fail before any results are produced get an error status code,
+//! together with a body describing the error:
+//! ```json
+//! {"error":{"code":"invalid_query","message":"..."}}
+//! ```
+//!
+//! Queries that time out or exceed their result limit after producing some results have
+//! already sent a successful status code. Their response ends with an `"error"` key instead:
+//! ```json
+//! {"results":[
+//! {"value":1}
+//! ],"error":{"code":"result_limit_exceeded","message":"..."}}
+//! ```
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    error::Error,
+    fmt::{self, Display},
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use serde::{Deserialize, Serialize};
+use trustfall_core::interpreter::{
+    error::QueryCancelled, execution::interpret_ir_with_cancellation,
+};
+
+#[cfg(feature = "graphql-server")]
+use crate::graphql_server::{GraphQLFacade, GraphQLLimits, GraphQLRequest, GraphQLResponse};
+use crate::{
+    provider::Adapter, CancellationToken, FieldValue, QueryCache, Schema, TransparentValue,
+};
+
+/// The number of compiled queries a [`QueryService`] keeps in its cache.
+const QUERY_CACHE_CAPACITY: usize = 256;
+
+/// Limits on the requests a [`QueryService`] handles. Limits that are `None` are not enforced.
+///
+/// By default, request bodies are limited to 1 MiB, and nothing else is limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLimits {
+    /// The maximum size of a request body, in bytes.
+    ///
+    /// Always enforced, since request bodies are read into memory before they are handled.
+    pub max_request_bytes: usize,
+
+    /// The maximum number of results a query may produce.
+    pub max_results: Option<usize>,
+
+    /// How long a query may run before it's stopped.
+    ///
+    /// The timeout is checked each time the adapter produces a vertex,
+    /// so an adapter that blocks indefinitely cannot be interrupted.
+    pub timeout: Option<Duration>,
+
+    /// The maximum number of queries that may run at the same time.
+    /// A query keeps running until its response has been completely produced or dropped.
+    pub max_concurrent_queries: Option<usize>,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self {
+            max_request_bytes: 1024 * 1024,
+            max_results: None,
+            timeout: None,
+            max_concurrent_queries: None,
+        }
+    }
+}
+
+/// The body of a query request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryRequest {
+    /// The text of the query to run.
+    pub query: String,
+
+    /// The values of the query's variables.
+    #[serde(default)]
+    pub variables: BTreeMap<Arc<str>, TransparentValue>,
+}
+
+/// The kinds of errors reported in response to query requests.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request was not a well-formed query request.
+    InvalidRequest,
+
+    /// The request body was larger than [`QueryLimits::max_request_bytes`].
+    RequestTooLarge,
+
+    /// The request used an HTTP method other than `POST`.
+    MethodNotAllowed,
+
+    /// The query is not valid for the schema.
+    InvalidQuery,
+
+    /// The query's variables are missing, unused, or of the wrong type.
+    InvalidVariables,
+
+    /// [`QueryLimits::max_concurrent_queries`] queries were already running.
+    TooManyRequests,
+
+    /// The query ran for longer than [`QueryLimits::timeout`].
+    Timeout,
+
+    /// The query produced more than [`QueryLimits::max_results`] results.
+    ResultLimitExceeded,
+}
+
+impl ErrorCode {
+    /// The HTTP status code for responses to requests that failed with this error.
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorCode::InvalidRequest | ErrorCode::InvalidQuery | ErrorCode::InvalidVariables => {
+                400
+            }
+            ErrorCode::MethodNotAllowed => 405,
+            ErrorCode::RequestTooLarge => 413,
+            ErrorCode::ResultLimitExceeded => 422,
+            ErrorCode::TooManyRequests => 503,
+            ErrorCode::Timeout => 504,
+        }
+    }
+}
+
+/// An error in handling a query request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    /// The HTTP status code for the response to the failed request.
+    pub fn status(&self) -> u16 {
+        self.code.status()
+    }
+
+    /// The JSON body of the response to the failed request.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct ErrorBody<'a> {
+            error: &'a QueryError,
+        }
+        serde_json::to_string(&ErrorBody { error: self }).expect("failed to serialize error")
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Handles query requests over the data provided by an adapter. See the [module docs](self).
+///
+/// The service can be shared between threads if its adapter can.
+#[derive(Debug)]
+pub struct QueryService<AdapterT> {
+    schema: Schema,
+    adapter: Arc<AdapterT>,
+    limits: QueryLimits,
+    cache: QueryCache,
+    running: Arc<AtomicUsize>,
+}
+
+impl<AdapterT: Adapter<'static> + 'static> QueryService<AdapterT> {
+    /// Make a service for queries over the given schema, with the default [`QueryLimits`].
+    pub fn new(schema: Schema, adapter: Arc<AdapterT>) -> Self {
+        Self {
+            schema,
+            adapter,
+            limits: QueryLimits::default(),
+            cache: QueryCache::new(NonZeroUsize::new(QUERY_CACHE_CAPACITY).expect("zero")),
+            running: Default::default(),
+        }
+    }
+
+    /// Set the limits on the requests the service handles.
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The limits on the requests the service handles.
+    pub fn limits(&self) -> &QueryLimits {
+        &self.limits
+    }
+
+    /// Start running the query described by a request body.
+    ///
+    /// Errors found before the query produces its first result are returned here,
+    /// and the query's results are produced by the returned response.
+    pub fn handle(&self, body: &[u8]) -> Result<QueryResponse, QueryError> {
+        let request = parse_body(body, self.limits.max_request_bytes)?;
+        self.handle_request(request)
+    }
+
+    /// Handle a query request made with the `http` crate's types.
+    ///
+    /// The request body is read until it exceeds [`QueryLimits::max_request_bytes`],
+    /// and the query then starts running. Its results are computed as the response body
+    /// is polled, which blocks the polling thread. See the [module docs](self) for details.
+    pub async fn handle_http<B>(
+        &self,
+        request: ::http::Request<B>,
+    ) -> ::http::Response<ResponseBody>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let response = match read_body(request, self.limits.max_request_bytes).await {
+            Ok(body) => self.handle(&body),
+            Err(error) => Err(error),
+        };
+        match response {
+            Ok(response) => json_response(200, ResponseBody(BodyState::Chunks(response))),
+            Err(error) => json_response(error.status(), ResponseBody::full(error.to_json())),
+        }
+    }
+
+    /// Start running the query described by an already-parsed request.
+    ///
+    /// Useful with server libraries that parse JSON request bodies themselves.
+    /// Note that [`QueryLimits::max_request_bytes`] is not checked here.
+    pub fn handle_request(&self, request: QueryRequest) -> Result<QueryResponse, QueryError> {
+        let permit = acquire(&self.running, self.limits.max_concurrent_queries)?;
+        let compiled = self
+            .cache
+            .get_or_compile(&self.schema, &request.query)
+            .map_err(|e| QueryError::new(ErrorCode::InvalidQuery, e.to_string()))?;
+        let variables: BTreeMap<Arc<str>, FieldValue> =
+            request.variables.into_iter().map(|(name, value)| (name, value.into())).collect();
+
+        let token = self.limits.timeout.map(CancellationToken::with_timeout).unwrap_or_default();
+        let mut results = interpret_ir_with_cancellation(
+            self.adapter.clone(),
+            compiled.query().clone(),
+            Arc::new(variables),
+            token,
+        )
+        .map_err(|e| QueryError::new(ErrorCode::InvalidVariables, e.to_string()))?;
+
+        // Queries that time out before producing any results can still get an error status.
+        let first = results.next();
+        if let Some(Err(QueryCancelled)) = first {
+            return Err(timeout());
+        }
+
+        Ok(QueryResponse {
+            first,
+            results,
+            max_results: self.limits.max_results,
+            produced: 0,
+            state: ResponseState::Start,
+            _permit: permit,
+        })
+    }
+}
+
+/// Handles GraphQL requests over the data provided by a [`GraphQLFacade`].
+///
+/// Requests are `POST`s whose JSON body is a [`GraphQLRequest`]. Their GraphQL responses
+/// are sent with a `200 OK` status, even if they contain errors. Requests that exceed
+/// the service's [`QueryLimits`] before they start executing get an error status code,
+/// and a GraphQL response describing the error.
+#[cfg(feature = "graphql-server")]
+#[derive(Debug)]
+pub struct GraphQLService<AdapterT> {
+    facade: GraphQLFacade<AdapterT>,
+    limits: QueryLimits,
+    running: Arc<AtomicUsize>,
+}
+
+#[cfg(feature = "graphql-server")]
+impl<AdapterT: Adapter<'static> + 'static> GraphQLService<AdapterT> {
+    /// Make a service for the facade's GraphQL API, with the default [`QueryLimits`].
+    pub fn new(facade: GraphQLFacade<AdapterT>) -> Self {
+        Self { facade, limits: QueryLimits::default(), running: Default::default() }
+    }
+
+    /// Set the limits on the requests the service handles.
+    ///
+    /// The timeout and result limit apply to each request as a whole,
+    /// across all the fields it queries.
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.facade = self.facade.with_limits(GraphQLLimits {
+            max_results: limits.max_results,
+            timeout: limits.timeout,
+        });
+        self.limits = limits;
+        self
+    }
+
+    /// The limits on the requests the service handles.
+    pub fn limits(&self) -> &QueryLimits {
+        &self.limits
+    }
+
+    /// Execute the GraphQL request described by a request body.
+    pub fn handle(&self, body: &[u8]) -> Result<GraphQLResponse, QueryError> {
+        let request: GraphQLRequest = parse_body(body, self.limits.max_request_bytes)?;
+        let _permit = acquire(&self.running, self.limits.max_concurrent_queries)?;
+        Ok(self.facade.execute(&request))
+    }
+
+    /// Handle a GraphQL request made with the `http` crate's types.
+    ///
+    /// The request is executed to completion before the response is returned.
+    pub async fn handle_http<B>(
+        &self,
+        request: ::http::Request<B>,
+    ) -> ::http::Response<ResponseBody>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let response = match read_body(request, self.limits.max_request_bytes).await {
+            Ok(body) => self.handle(&body),
+            Err(error) => Err(error),
+        };
+        let (status, body) = match response {
+            Ok(response) => {
+                (200, serde_json::to_string(&response).expect("failed to serialize response"))
+            }
+            Err(error) => {
+                let response = serde_json::json!({ "errors": [{ "message": error.message }] });
+                (error.status(), response.to_string())
+            }
+        };
+        json_response(status, ResponseBody::full(body))
+    }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8], max: usize) -> Result<T, QueryError> {
+    if body.len() > max {
+        return Err(request_too_large(max));
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        QueryError::new(ErrorCode::InvalidRequest, format!("invalid request body: {e}"))
+    })
+}
+
+/// Read the body of a `POST` request, without reading more than `max` bytes of it.
+async fn read_body<B>(request: ::http::Request<B>, max: usize) -> Result<Bytes, QueryError>
+where
+    B: Body,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    if request.method() != ::http::Method::POST {
+        return Err(QueryError::new(
+            ErrorCode::MethodNotAllowed,
+            "query requests must use the POST method",
+        ));
+    }
+
+    // Reject bodies that are declared to be too large without waiting for them to arrive.
+    let content_length = request
+        .headers()
+        .get(::http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+    if content_length.is_some_and(|length| length > max as u64) {
+        return Err(request_too_large(max));
+    }
+
+    let body = Limited::new(request.into_body(), max).collect().await.map_err(|e| {
+        if e.is::<LengthLimitError>() {
+            request_too_large(max)
+        } else {
+            QueryError::new(ErrorCode::InvalidRequest, "failed to read the request body")
+        }
+    })?;
+    Ok(body.to_bytes())
+}
+
+fn json_response(status: u16, body: ResponseBody) -> ::http::Response<ResponseBody> {
+    ::http::Response::builder()
+        .status(status)
+        .header(::http::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .expect("failed to build response")
+}
+
+fn acquire(running: &Arc<AtomicUsize>, max: Option<usize>) -> Result<Permit, QueryError> {
+    let max = max.unwrap_or(usize::MAX);
+    running
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| {
+            (running < max).then_some(running + 1)
+        })
+        .map_err(|_| {
+            QueryError::new(
+                ErrorCode::TooManyRequests,
+                format!("the maximum of {max} queries are already running; try again later"),
+            )
+        })?;
+    Ok(Permit(running.clone()))
+}
+
+fn request_too_large(max: usize) -> QueryError {
+    QueryError::new(
+        ErrorCode::RequestTooLarge,
+        format!("the request body is larger than the maximum of {max} bytes"),
+    )
+}
+
+fn timeout() -> QueryError {
+    QueryError::new(ErrorCode::Timeout, "the query took too long, and was stopped")
+}
+
+/// Counts a running query towards [`QueryLimits::max_concurrent_queries`] until dropped.
+#[derive(Debug)]
+struct Permit(Arc<AtomicUsize>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseState {
+    Start,
+    Results,
+    Done,
+}
+
+type ResultRow = BTreeMap<Arc<str>, FieldValue>;
+
+/// The body of a successful response to a query request, produced in chunks
+/// as the query's results are computed. See the [module docs](self) for its format.
+///
+/// The query keeps counting towards [`QueryLimits::max_concurrent_queries`]
+/// until the response is dropped.
+pub struct QueryResponse {
+    first: Option<Result<ResultRow, QueryCancelled>>,
+    results: Box<dyn Iterator<Item = Result<ResultRow, QueryCancelled>>>,
+    max_results: Option<usize>,
+    produced: usize,
+    state: ResponseState,
+    _permit: Permit,
+}
+
+impl fmt::Debug for QueryResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryResponse")
+            .field("produced", &self.produced)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl QueryResponse {
+    fn end_with_error(&mut self, error: QueryError) -> Vec<u8> {
+        self.state = ResponseState::Done;
+        let error = serde_json::to_string(&error).expect("failed to serialize error");
+        format!("\n],\"error\":{error}}}\n").into_bytes()
+    }
+}
+
+impl Iterator for QueryResponse {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.state {
+            ResponseState::Start => {
+                self.state = ResponseState::Results;
+                Some(b"{\"results\":[".to_vec())
+            }
+            ResponseState::Results => {
+                let next = match self.first.take() {
+                    Some(first) => Some(first),
+                    None => self.results.next(),
+                };
+                match next {
+                    None => {
+                        self.state = ResponseState::Done;
+                        Some(b"\n]}\n".to_vec())
+                    }
+                    Some(Err(QueryCancelled)) => Some(self.end_with_error(timeout())),
+                    Some(Ok(_)) if Some(self.produced) == self.max_results => {
+                        let error = QueryError::new(
+                            ErrorCode::ResultLimitExceeded,
+                            format!(
+                                "the query produced more than the maximum of {} results",
+                                self.produced,
+                            ),
+                        );
+                        Some(self.end_with_error(error))
+                    }
+                    Some(Ok(row)) => {
+                        let separator = if self.produced == 0 { "\n" } else { ",\n" };
+                        self.produced += 1;
+                        let row: BTreeMap<Arc<str>, TransparentValue> =
+                            row.into_iter().map(|(name, value)| (name, value.into())).collect();
+                        let row = serde_json::to_string(&row).expect("failed to serialize result");
+                        Some(format!("{separator}{row}").into_bytes())
+                    }
+                }
+            }
+            ResponseState::Done => None,
+        }
+    }
+}
+
+/// The body of a response produced by [`QueryService::handle_http`]
+/// or [`GraphQLService::handle_http`].
+///
+/// Query results are computed as the body is polled, so polling it may block.
+#[derive(Debug)]
+pub struct ResponseBody(BodyState);
+
+#[derive(Debug)]
+enum BodyState {
+    Chunks(QueryResponse),
+    Full(Option<Bytes>),
+}
+
+impl ResponseBody {
+    fn full(body: String) -> Self {
+        Self(BodyState::Full(Some(body.into())))
+    }
+}
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let chunk = match &mut self.get_mut().0 {
+            BodyState::Chunks(response) => response.next().map(Bytes::from),
+            BodyState::Full(body) => body.take(),
+        };
+        Poll::Ready(chunk.map(|chunk| Ok(Frame::data(chunk))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.0 {
+            BodyState::Chunks(response) => response.state == ResponseState::Done,
+            BodyState::Full(body) => body.is_none(),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.0 {
+            BodyState::Chunks(_) => SizeHint::default(),
+            BodyState::Full(body) => {
+                SizeHint::with_exact(body.as_ref().map_or(0, |body| body.len() as u64))
+            }
+        }
+    }
+}
@@ -36,38 +36,141 @@
 #![forbid(unused_lifetimes)]
 #![forbid(elided_lifetimes_in_paths)]
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+mod query_cache;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "async")]
+use futures_core::Stream;
+use serde::{de::DeserializeOwned, Serialize};
 
 /// Components needed to implement data providers.
 pub mod provider {
     pub use trustfall_core::interpreter::basic_adapter::BasicAdapter;
     pub use trustfall_core::interpreter::{
-        Adapter, AsVertex, CandidateValue, ContextIterator, ContextOutcomeIterator, DataContext,
-        DynamicallyResolvedValue, EdgeInfo, QueryInfo, Range, RequiredProperty, ResolveEdgeInfo,
-        ResolveInfo, Typename, VertexInfo, VertexIterator,
+        Adapter, AsVertex, CachingAdapter, CandidateValue, CompositeAdapter, CompositeVertex,
+        ContextIterator, ContextOutcomeIterator, CycleDetectingAdapter, CycleDetectingVertex,
+        DataContext, DynAdapter, DynContextVertex, DynVertex, DynamicallyResolvedValue, EdgeInfo,
+        InstrumentedAdapter, ObjectSafeAdapter, QueryInfo, Range, RequiredProperty,
+        ResolveEdgeInfo, ResolveInfo, ResolverCall, ResolverMetrics, StringPattern, Typename,
+        VertexIdentity, VertexInfo, VertexIterator,
     };
+
+    #[cfg(feature = "async")]
+    pub use trustfall_core::interpreter::AsyncAdapter;
+
+    // Ready-made adapters for common data sources.
+    #[cfg(feature = "csv-adapter")]
+    pub use trustfall_core::interpreter::{CsvAdapter, CsvParseError, CsvTable, CsvVertex};
+    #[cfg(feature = "filesystem-adapter")]
+    pub use trustfall_core::interpreter::{FilesystemAdapter, FilesystemEntry};
+    #[cfg(feature = "graphql-adapter")]
+    pub use trustfall_core::interpreter::{
+        GraphQLAdapter, GraphQLIntrospectionError, GraphQLTransport,
+    };
+    #[cfg(feature = "json-adapter")]
+    pub use trustfall_core::interpreter::{JsonAdapter, JsonSchemaInferenceError, JsonVertex};
     pub use trustfall_core::ir::{EdgeParameters, Eid, Vid};
 
     // Helpers for common operations when building adapters.
     pub use trustfall_core::interpreter::helpers::{
-        check_adapter_invariants, resolve_coercion_using_schema, resolve_coercion_with,
-        resolve_neighbors_with, resolve_property_with, resolve_typename,
+        candidate_to_key_ranges, candidate_to_predicate, candidate_to_sql_condition,
+        check_adapter_invariants, check_adapter_invariants_with_vertex_sample,
+        resolve_coercion_using_schema, resolve_coercion_with, resolve_neighbors_with,
+        resolve_property_with, resolve_typename, KeyRange, KeyRanges, ResolverTable, SqlCondition,
+    };
+    #[cfg(feature = "test-utils")]
+    pub use trustfall_core::interpreter::helpers::{
+        check_adapter_with_random_queries, RandomQueryConfig,
     };
     pub use trustfall_core::{accessor_property, field_property};
 
     // Derive macros for common vertex implementation details.
-    pub use trustfall_derive::{TrustfallEnumVertex, Typename};
+    #[cfg(feature = "derive")]
+    pub use trustfall_derive::{TrustfallEnumVertex, TrustfallProperties, Typename};
+}
+
+/// Adapters that run as plugins, in a separate process from the one executing the query.
+///
+/// Load a plugin with [`PluginAdapter::spawn()`](plugin::PluginAdapter::spawn),
+/// or turn an adapter into a plugin with [`PluginServer`](plugin::PluginServer).
+///
+/// Requires the `plugins` feature, which isn't enabled by default.
+#[cfg(feature = "plugins")]
+pub mod plugin {
+    pub use trustfall_core::interpreter::plugin::{
+        PluginAdapter, PluginConnection, PluginError, PluginProcess, PluginRequest, PluginResponse,
+        PluginServer, PluginVertex, PLUGIN_PROTOCOL_VERSION,
+    };
+}
+
+/// Serving adapters as GraphQL APIs, for use by existing GraphQL clients and tools.
+///
+/// Execute GraphQL requests with [`GraphQLFacade::execute()`](graphql_server::GraphQLFacade::execute),
+/// or handle them as HTTP requests with `http::GraphQLService`.
+///
+/// Requires the `graphql-server` feature, which isn't enabled by default.
+#[cfg(feature = "graphql-server")]
+pub mod graphql_server {
+    pub use trustfall_core::graphql_server::{
+        GraphQLError, GraphQLFacade, GraphQLLimits, GraphQLRequest, GraphQLResponse,
+    };
+}
+
+/// Recording and replaying query execution, e.g. to test queries without network access.
+///
+/// A trace records every adapter operation performed while executing a query.
+/// Record one with [`execute_query_with_trace`], serialize it,
+/// then re-run the query against the recorded data with [`trace::replay_trace`].
+pub mod trace {
+    pub use trustfall_core::interpreter::replay::{assert_interpreted_results, replay_trace};
+    pub use trustfall_core::interpreter::trace::{
+        tap_results, AdapterTap, FunctionCall, Opid, Trace, TraceOp, TraceOpContent, YieldValue,
+    };
 }
 
 // Property values and query variables.
 // Useful both for querying and for implementing data providers.
-pub use trustfall_core::ir::{FieldValue, TransparentValue};
+pub use trustfall_core::ir::{DateTime, FieldValue, TransparentValue};
 
 // Trustfall query schema.
-pub use trustfall_core::schema::{Schema, SchemaAdapter};
+pub use trustfall_core::schema::{Schema, SchemaAdapter, SchemaWarning};
+
+// Converting query results into structs.
+pub use trustfall_core::{OutputStructError, TryIntoStruct};
+
+// Stopping long-running or resource-intensive queries.
+pub use trustfall_core::interpreter::{
+    error::{LimitExceeded, QueryCancelled},
+    CancellationToken, ExecutionLimits,
+};
+
+// Descriptions of how queries are executed.
+pub use trustfall_core::interpreter::explain::{PlanStep, QueryPlan};
+
+// Descriptions of the outputs a query produces.
+pub use trustfall_core::ir::{OutputMetadata, QueryOutputs};
 
-// Trait for converting query results into structs.
-pub use trustfall_core::TryIntoStruct;
+// Queries compiled ahead of time.
+pub use trustfall_core::ir::CompiledQuery;
+#[cfg(feature = "serialization")]
+pub use trustfall_core::ir::CompiledQueryError;
+
+// Caching compiled queries within a process.
+pub use query_cache::{QueryCache, QueryCacheStats};
+
+// Formatting query text in a canonical style.
+pub use trustfall_core::graphql_query::format_query;
 
 /// Run a Trustfall query over the data provider specified by the given schema and adapter.
 pub fn execute_query<'vertex>(
@@ -81,3 +184,321 @@ pub fn execute_query<'vertex>(
 
     Ok(trustfall_core::interpreter::execution::interpret_ir(adapter, parsed_query, vars)?)
 }
+
+/// Parse and validate a Trustfall query ahead of time, so that it can be executed
+/// many times with [`execute_compiled`] without paying that cost again.
+///
+/// With the `serialization` feature, the compiled query can also be serialized with
+/// `CompiledQuery::to_bytes()`, for example at build time, and loaded with
+/// `CompiledQuery::from_bytes()` when needed.
+pub fn compile(schema: &Schema, query: &str) -> anyhow::Result<CompiledQuery> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    Ok(CompiledQuery::new(schema, parsed_query))
+}
+
+/// Run a Trustfall query compiled with [`compile`], over the data provider
+/// specified by the given adapter.
+///
+/// The adapter must implement the schema against which the query was compiled.
+pub fn execute_compiled<'vertex>(
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &CompiledQuery,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>> {
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir(adapter, query.query().clone(), vars)?)
+}
+
+/// Run a Trustfall query, deserializing each of its results into a struct of type `S`.
+///
+/// Before the query runs, the struct's fields are checked against the query's outputs:
+/// each output must have a field of a compatible type, and each field that isn't an `Option`
+/// must have an output. Mismatches are reported up front as an [`OutputStructError`],
+/// instead of while deserializing each result with [`TryIntoStruct`].
+///
+/// The returned iterator panics if a result's value doesn't fit into its struct field,
+/// for example for integers too large for an `i8` field.
+pub fn execute_query_typed<'vertex, S: DeserializeOwned + 'vertex>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<Box<dyn Iterator<Item = S> + 'vertex>> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    trustfall_core::check_output_struct::<S>(&parsed_query.outputs)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    let results =
+        trustfall_core::interpreter::execution::interpret_ir(adapter, parsed_query, vars)?;
+    Ok(Box::new(results.map(|result| {
+        result.try_into_struct().expect("query result value did not fit into its struct field")
+    })))
+}
+
+/// Run a Trustfall query that can be stopped early via the given [`CancellationToken`].
+///
+/// Once the token is cancelled, the query stops loading data from the adapter, and the results
+/// iterator produces a [`QueryCancelled`] error after the results produced so far.
+/// Use [`CancellationToken::with_timeout`] to stop queries that run for too long.
+#[allow(clippy::type_complexity)]
+pub fn execute_query_with_cancellation<'vertex>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+    token: CancellationToken,
+) -> anyhow::Result<
+    Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, QueryCancelled>> + 'vertex>,
+> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_with_cancellation(
+        adapter,
+        parsed_query,
+        vars,
+        token,
+    )?)
+}
+
+/// Run a Trustfall query that is stopped early if it exceeds any of the given [`ExecutionLimits`].
+///
+/// Useful for running untrusted queries. Once a limit is exceeded, the query stops loading data
+/// from the adapter, and the results iterator produces a [`LimitExceeded`] error
+/// after the results produced so far.
+#[allow(clippy::type_complexity)]
+pub fn execute_query_with_limits<'vertex>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+    limits: ExecutionLimits,
+) -> anyhow::Result<
+    Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, LimitExceeded>> + 'vertex>,
+> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_with_limits(
+        adapter,
+        parsed_query,
+        vars,
+        limits,
+    )?)
+}
+
+/// Run a Trustfall query, recording a trace of all the adapter operations it performs.
+///
+/// Produces the query's results together with the trace, which can be serialized
+/// and later replayed with [`trace::replay_trace`] to re-run the query without the adapter.
+/// For example, a trace recorded against a live API can be used in tests
+/// that have no network access.
+#[allow(clippy::type_complexity)]
+pub fn execute_query_with_trace<'vertex, AdapterT>(
+    schema: &Schema,
+    adapter: AdapterT,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<(Vec<BTreeMap<Arc<str>, FieldValue>>, trace::Trace<AdapterT::Vertex>)>
+where
+    AdapterT: provider::Adapter<'vertex> + 'vertex,
+    AdapterT::Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned + 'vertex,
+{
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars: Arc<BTreeMap<Arc<str>, FieldValue>> =
+        Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    let ir_query = parsed_query.ir_query.clone();
+    let arguments: BTreeMap<String, FieldValue> =
+        vars.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+    let tracer = Arc::new(Mutex::new(trace::Trace::new(ir_query.clone(), arguments.clone())));
+    let adapter_tap = Arc::new(trace::AdapterTap::new(adapter, tracer.clone()));
+
+    let results = trustfall_core::interpreter::execution::interpret_ir(
+        adapter_tap.clone(),
+        parsed_query,
+        vars,
+    )?;
+    let results: Vec<_> = trace::tap_results(adapter_tap, results).collect();
+
+    let trace = std::mem::replace(
+        &mut *tracer.lock().expect("trace lock was poisoned"),
+        trace::Trace::new(ir_query, arguments),
+    );
+    Ok((results, trace))
+}
+
+/// Run a Trustfall query using multiple threads, producing all of its results.
+///
+/// The query's starting vertices are split up and processed in parallel,
+/// which speeds up queries over CPU-bound data providers.
+/// The results are the same, and in the same order, as those of [`execute_query`].
+pub fn execute_query_parallel<'vertex, AdapterT>(
+    schema: &Schema,
+    adapter: Arc<AdapterT>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<Vec<BTreeMap<Arc<str>, FieldValue>>>
+where
+    AdapterT: provider::Adapter<'vertex> + Send + Sync + 'vertex,
+    AdapterT::Vertex: Send,
+{
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_parallel(adapter, parsed_query, vars)?)
+}
+
+/// Run a Trustfall query on a separate thread, producing its results via an iterator
+/// that can be sent across threads.
+///
+/// Unlike the iterator returned by [`execute_query`], the returned iterator is [`Send`],
+/// so it can be moved into an async task. Each call spawns a new thread to run the query.
+/// Getting the next result blocks until it's ready, so in async code consume the iterator
+/// where blocking is allowed, such as inside `tokio::task::spawn_blocking()`.
+/// Dropping the iterator cancels the query.
+pub fn execute_query_threaded<AdapterT>(
+    schema: &Schema,
+    adapter: Arc<AdapterT>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<impl Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + Send>
+where
+    AdapterT: provider::Adapter<'static> + Send + Sync + 'static,
+{
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_threaded(adapter, parsed_query, vars)?)
+}
+
+/// Run a batch of Trustfall queries, resolving the starting vertices they share only once.
+///
+/// Returns each query's results, in the order in which the queries were given.
+/// Queries with the same root edge and root edge parameters share a single call to
+/// [`Adapter::resolve_starting_vertices`](provider::Adapter::resolve_starting_vertices),
+/// which speeds up running many queries that start from the same vertices.
+#[allow(clippy::type_complexity)]
+pub fn execute_queries_batch<'vertex, 'a>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    queries: impl IntoIterator<Item = (&'a str, BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>)>,
+) -> anyhow::Result<Vec<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>>> {
+    let queries = queries
+        .into_iter()
+        .map(|(query, variables)| {
+            let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+            let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+            Ok((parsed_query, vars))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_batch(adapter, queries)?)
+}
+
+/// Run a Trustfall query with multiple root fields, combining the results of its root fields.
+///
+/// Each result combines one result of each root field. Root fields may filter on values
+/// tagged in earlier root fields, to only combine results that correspond to each other.
+/// Later root fields run once per combined result of the ones before them, so consider
+/// wrapping the adapter in a [`CachingAdapter`](provider::CachingAdapter).
+pub fn execute_joined_query<'vertex>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>> {
+    let joined_query = trustfall_core::frontend::parse_joined(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_joined(adapter, joined_query, vars)?)
+}
+
+/// Run a Trustfall query over an asynchronous data provider, producing all of its results.
+///
+/// The returned future can be awaited on any async runtime.
+/// While the adapter is awaiting data, the runtime's threads remain free to do other work.
+#[cfg(feature = "async")]
+pub async fn execute_query_async(
+    schema: &Schema,
+    adapter: Arc<impl provider::AsyncAdapter>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<Vec<BTreeMap<Arc<str>, FieldValue>>> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_async(adapter, parsed_query, vars)
+        .await?)
+}
+
+/// Run a Trustfall query over an asynchronous data provider, producing its results as a stream.
+///
+/// Unlike [`execute_query_async`], results are produced as soon as they are available
+/// instead of all at once at the end. The query only makes progress while the stream
+/// is being polled, and dropping the stream stops the query.
+///
+/// The stream is `Send` if the adapter, its vertices, and its resolvers' futures are.
+#[cfg(feature = "async")]
+pub fn execute_query_stream<'a, AdapterT: provider::AsyncAdapter + 'a>(
+    schema: &Schema,
+    adapter: Arc<AdapterT>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<impl Stream<Item = BTreeMap<Arc<str>, FieldValue>> + Unpin + 'a> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::execution::interpret_ir_stream(adapter, parsed_query, vars)?)
+}
+
+/// Run a Trustfall query, producing its results as Apache Arrow record batches
+/// of up to `batch_size` rows each.
+///
+/// Batches are built as the query's results are computed. See the [`arrow`] module
+/// for how the query's output types map to Arrow types.
+///
+/// Requires the `arrow` feature, which isn't enabled by default. Panics if `batch_size` is zero.
+#[cfg(feature = "arrow")]
+pub fn execute_query_to_arrow<'vertex>(
+    schema: &Schema,
+    adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+    batch_size: usize,
+) -> anyhow::Result<arrow::RecordBatches<'vertex>> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let outputs = parsed_query.query_outputs();
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    let results =
+        trustfall_core::interpreter::execution::interpret_ir(adapter, parsed_query, vars)?;
+    Ok(arrow::RecordBatches::new(&outputs, results, batch_size))
+}
+
+/// Describe how a Trustfall query would be executed, without executing it.
+///
+/// The returned plan lists the adapter calls the query requires, which filters
+/// each resolver call can see in its hints, and how `@fold` and `@recurse` edges are expanded.
+/// It is useful for understanding why a query is slow.
+pub fn explain(
+    schema: &Schema,
+    query: &str,
+    variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+) -> anyhow::Result<QueryPlan> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    let vars = Arc::new(variables.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+
+    Ok(trustfall_core::interpreter::explain::explain_ir(parsed_query, vars)?)
+}
+
+/// Describe the outputs a Trustfall query produces, without executing it.
+///
+/// The returned metadata includes each output's name and type, and whether its values
+/// are lists due to being within a `@fold`. It is useful for building response schemas
+/// or column headers for query results.
+pub fn query_outputs(schema: &Schema, query: &str) -> anyhow::Result<QueryOutputs> {
+    let parsed_query = trustfall_core::frontend::parse(schema, query)?;
+    Ok(parsed_query.query_outputs())
+}
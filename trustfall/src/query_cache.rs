@@ -0,0 +1,162 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use crate::{provider, CompiledQuery, FieldValue, Schema};
+
+/// The number of hits, misses, and evictions of a [`QueryCache`] since it was created.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryCacheStats {
+    /// Lookups that found an already-compiled query.
+    pub hits: u64,
+
+    /// Lookups that had to compile the query, including ones where compilation failed.
+    pub misses: u64,
+
+    /// Compiled queries removed from the cache to make room for others.
+    pub evictions: u64,
+
+    /// The number of compiled queries currently in the cache.
+    pub entries: usize,
+}
+
+/// A cache of compiled queries, keyed by query text and the [fingerprint](Schema::fingerprint)
+/// of the schema they were compiled against.
+///
+/// Applications that run the same queries many times, like servers, can use it to only
+/// parse and validate each query once. When full, the least-recently-used query is evicted.
+/// Queries that fail to compile are not cached.
+///
+/// The cache can be shared between threads.
+///
+/// # Example
+/// ```
+/// # use std::{collections::BTreeMap, num::NonZeroUsize};
+/// # use trustfall::{FieldValue, QueryCache, Schema};
+/// # let schema = Schema::parse(r#"
+/// # schema { query: RootSchemaQuery }
+/// # directive @output(name: String) on FIELD
+/// # type RootSchemaQuery { Number: [Number!]! }
+/// # type Number { value: Int! }
+/// # "#).unwrap();
+/// let cache = QueryCache::new(NonZeroUsize::new(100).unwrap());
+///
+/// let query = "{ Number { value @output } }";
+/// let compiled = cache.get_or_compile(&schema, query).expect("valid query");
+/// let compiled_again = cache.get_or_compile(&schema, query).expect("valid query");
+/// assert_eq!(compiled, compiled_again);
+///
+/// let stats = cache.stats();
+/// assert_eq!((1, 1), (stats.hits, stats.misses));
+/// ```
+#[derive(Debug)]
+pub struct QueryCache {
+    capacity: NonZeroUsize,
+    inner: Mutex<CacheState>,
+}
+
+type CacheKey = (u64, Arc<str>);
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Each compiled query, with the tick at which it was last used.
+    entries: HashMap<CacheKey, (CompiledQuery, u64)>,
+
+    /// The key of each cache entry, ordered by when it was last used.
+    recency: BTreeMap<u64, CacheKey>,
+
+    next_tick: u64,
+    stats: QueryCacheStats,
+}
+
+impl CacheState {
+    fn tick(&mut self) -> u64 {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        tick
+    }
+}
+
+impl QueryCache {
+    /// Create an empty cache holding up to `capacity` compiled queries.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self { capacity, inner: Mutex::new(CacheState::default()) }
+    }
+
+    /// The maximum number of compiled queries the cache holds.
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// The cache's hit, miss, and eviction counts, and its current number of entries.
+    pub fn stats(&self) -> QueryCacheStats {
+        let state = self.inner.lock().expect("query cache lock was poisoned");
+        QueryCacheStats { entries: state.entries.len(), ..state.stats }
+    }
+
+    /// Remove all compiled queries from the cache. Its statistics are preserved.
+    pub fn clear(&self) {
+        let mut state = self.inner.lock().expect("query cache lock was poisoned");
+        state.entries.clear();
+        state.recency.clear();
+    }
+
+    /// Get the compiled query for the given query text and schema,
+    /// compiling it with [`compile`](crate::compile) if it isn't cached yet.
+    pub fn get_or_compile(&self, schema: &Schema, query: &str) -> anyhow::Result<CompiledQuery> {
+        let key: CacheKey = (schema.fingerprint(), Arc::from(query));
+
+        {
+            let mut guard = self.inner.lock().expect("query cache lock was poisoned");
+            let state = &mut *guard;
+            let tick = state.tick();
+            if let Some((compiled, last_used)) = state.entries.get_mut(&key) {
+                let compiled = compiled.clone();
+                let previous_tick = std::mem::replace(last_used, tick);
+                state.recency.remove(&previous_tick);
+                state.recency.insert(tick, key);
+                state.stats.hits += 1;
+                return Ok(compiled);
+            }
+            state.stats.misses += 1;
+        }
+
+        // Compile without holding the lock, so other threads can use the cache meanwhile.
+        let compiled = crate::compile(schema, query)?;
+
+        let mut guard = self.inner.lock().expect("query cache lock was poisoned");
+        let state = &mut *guard;
+        let tick = state.tick();
+        if let Some((_, previous_tick)) =
+            state.entries.insert(key.clone(), (compiled.clone(), tick))
+        {
+            // Another thread compiled the same query in the meantime.
+            state.recency.remove(&previous_tick);
+        }
+        state.recency.insert(tick, key);
+
+        while state.entries.len() > self.capacity.get() {
+            let (_, evicted) = state.recency.pop_first().expect("no entries to evict");
+            state.entries.remove(&evicted);
+            state.stats.evictions += 1;
+        }
+
+        Ok(compiled)
+    }
+
+    /// Run a Trustfall query like [`execute_query`](crate::execute_query),
+    /// using this cache to only compile it the first time it is run against this schema.
+    pub fn execute_query<'vertex>(
+        &self,
+        schema: &Schema,
+        adapter: Arc<impl provider::Adapter<'vertex> + 'vertex>,
+        query: &str,
+        variables: BTreeMap<impl Into<Arc<str>>, impl Into<FieldValue>>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex>> {
+        let compiled = self.get_or_compile(schema, query)?;
+        crate::execute_compiled(adapter, &compiled, variables)
+    }
+}
@@ -0,0 +1,156 @@
+#![cfg(all(feature = "arrow", feature = "json-adapter"))]
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow_array::{
+    builder::{ListBuilder, StringBuilder},
+    Array, ArrayRef, Float64Array, Int64Array, RecordBatchReader, StringArray,
+};
+use arrow_schema::{DataType, Field, Schema};
+use trustfall::{execute_query_to_arrow, provider::JsonAdapter, FieldValue};
+
+fn adapter(documents: &str) -> Arc<JsonAdapter> {
+    let documents = JsonAdapter::parse_documents(documents).expect("invalid JSON");
+    Arc::new(JsonAdapter::infer([("Crate", documents)]).expect("failed to infer schema"))
+}
+
+fn string_lists(lists: &[Option<&[&str]>]) -> ArrayRef {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for list in lists {
+        match list {
+            Some(items) => {
+                for item in *items {
+                    builder.values().append_value(item);
+                }
+                builder.append(true);
+            }
+            None => builder.append(false),
+        }
+    }
+    Arc::new(builder.finish())
+}
+
+#[test]
+fn results_are_batched_with_one_column_per_output() {
+    let adapter = adapter(
+        r#"
+        {"name": "trustfall", "stars": 2.5, "tags": ["query"], "authors": [{"name": "Predrag"}]}
+        {"name": "serde", "stars": 9, "tags": [], "authors": [{"name": "David"}, {"name": "Erick"}]}
+        {"name": "anyhow", "stars": null, "tags": null, "authors": []}
+        "#,
+    );
+    let query = r#"
+{
+    Crate {
+        name @output
+        stars @output
+        tags @output
+        authors @fold @transform(op: "count") @output(name: "author_count") {
+            author: name @output
+        }
+    }
+}"#;
+
+    let batches = execute_query_to_arrow(
+        adapter.schema(),
+        adapter.clone(),
+        query,
+        BTreeMap::<&str, FieldValue>::new(),
+        2,
+    )
+    .expect("invalid query");
+
+    let list = |nullable| DataType::List(Arc::new(Field::new("item", DataType::Utf8, nullable)));
+    let expected_schema = Schema::new(vec![
+        Field::new("author", list(true), false),
+        Field::new("author_count", DataType::Int64, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("stars", DataType::Float64, true),
+        Field::new("tags", list(true), true),
+    ]);
+    assert_eq!(Arc::new(expected_schema), batches.schema());
+
+    let batches: Vec<_> = batches.collect::<Result<_, _>>().expect("conversion failed");
+    assert_eq!(vec![2, 1], batches.iter().map(|batch| batch.num_rows()).collect::<Vec<_>>());
+
+    let column = |name: &str| -> Vec<ArrayRef> {
+        batches.iter().map(|batch| batch.column_by_name(name).unwrap().clone()).collect()
+    };
+    assert_eq!(
+        vec![
+            string_lists(&[Some(&["Predrag"]), Some(&["David", "Erick"])]),
+            string_lists(&[Some(&[])]),
+        ],
+        column("author"),
+    );
+    assert_eq!(
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![0])),
+        ],
+        column("author_count"),
+    );
+    assert_eq!(
+        vec![
+            Arc::new(StringArray::from(vec!["trustfall", "serde"])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["anyhow"])),
+        ],
+        column("name"),
+    );
+    assert_eq!(
+        vec![
+            Arc::new(Float64Array::from(vec![2.5, 9.0])) as ArrayRef,
+            Arc::new(Float64Array::from(vec![None])),
+        ],
+        column("stars"),
+    );
+    assert_eq!(
+        vec![string_lists(&[Some(&["query"]), Some(&[])]), string_lists(&[None])],
+        column("tags"),
+    );
+}
+
+#[test]
+fn queries_without_results_produce_no_batches() {
+    let adapter = adapter(r#"{"name": "trustfall"}"#);
+    let query = r#"{ Crate { name @output @filter(op: "=", value: ["$name"]) } }"#;
+
+    let batches = execute_query_to_arrow(
+        adapter.schema(),
+        adapter.clone(),
+        query,
+        BTreeMap::from([("name", "serde")]),
+        10,
+    )
+    .expect("invalid query");
+
+    assert_eq!(1, batches.schema().fields().len());
+    assert_eq!(0, batches.count());
+}
+
+#[test]
+fn unrepresentable_values_end_the_stream_with_an_error() {
+    let adapter = adapter(
+        r#"
+        {"downloads": 1}
+        {"downloads": 18446744073709551615}
+        {"downloads": 2}
+        "#,
+    );
+    let query = r#"{ Crate { downloads @output } }"#;
+
+    let batches: Vec<_> = execute_query_to_arrow(
+        adapter.schema(),
+        adapter.clone(),
+        query,
+        BTreeMap::<&str, FieldValue>::new(),
+        1,
+    )
+    .expect("invalid query")
+    .collect();
+
+    assert_eq!(2, batches.len(), "{batches:?}");
+    assert_eq!(1, batches[0].as_ref().expect("conversion failed").column(0).len());
+    let error = batches[1].as_ref().expect_err("conversion succeeded").to_string();
+    assert!(error.contains("Output \"downloads\""), "{error}");
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use trustfall::{
+    provider::{
+        resolve_neighbors_with, resolve_property_with, AsVertex, BasicAdapter, CandidateValue,
+        ContextIterator, ContextOutcomeIterator, EdgeParameters, ResolveInfo, Typename, VertexInfo,
+        VertexIterator,
+    },
+    FieldValue, Schema,
+};
+
+const SCHEMA: &str = "\
+schema {
+    query: RootSchemaQuery
+}
+directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
+directive @output(name: String) on FIELD
+
+type RootSchemaQuery {
+    Number(max: Int!): [Number!]!
+}
+
+type Number {
+    value: Int!
+    successor: Number!
+}";
+
+#[derive(Debug, Clone)]
+struct Number(i64);
+
+impl Typename for Number {
+    fn typename(&self) -> &'static str {
+        "Number"
+    }
+}
+
+/// Resolves starting vertices using hints, while resolving everything else without them.
+#[derive(Default)]
+struct Adapter {
+    starting_vertex_candidates: Mutex<Vec<Option<CandidateValue<FieldValue>>>>,
+}
+
+impl<'a> BasicAdapter<'a> for Adapter {
+    type Vertex = Number;
+
+    fn resolve_starting_vertices(
+        &self,
+        _edge_name: &str,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'a, Self::Vertex> {
+        let max = parameters["max"].as_i64().expect("max is an Int");
+        Box::new((0..=max).map(Number))
+    }
+
+    fn resolve_starting_vertices_with_hints(
+        &self,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'a, Self::Vertex> {
+        let candidate = resolve_info.statically_required_property("value");
+        self.starting_vertex_candidates.lock().unwrap().push(candidate.clone());
+        match candidate {
+            Some(CandidateValue::Single(FieldValue::Int64(value))) => {
+                Box::new(std::iter::once(Number(value)))
+            }
+            _ => self.resolve_starting_vertices(edge_name, parameters),
+        }
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        _type_name: &str,
+        _property_name: &str,
+    ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+        resolve_property_with(contexts, |vertex| vertex.0.into())
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        _type_name: &str,
+        _edge_name: &str,
+        _parameters: &EdgeParameters,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+        resolve_neighbors_with(contexts, |vertex| Box::new(std::iter::once(Number(vertex.0 + 1))))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        _contexts: ContextIterator<'a, V>,
+        _type_name: &str,
+        _coerce_to_type: &str,
+    ) -> ContextOutcomeIterator<'a, V, bool> {
+        unreachable!("the schema has no subtypes")
+    }
+}
+
+#[test]
+fn hint_aware_hooks_receive_resolve_info() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let adapter = Arc::new(Adapter::default());
+    let query = r#"
+{
+    Number(max: 100) {
+        value @filter(op: "=", value: ["$value"]) @output
+        successor {
+            next: value @output
+        }
+    }
+}"#;
+    let variables: BTreeMap<Arc<str>, FieldValue> =
+        BTreeMap::from([(Arc::from("value"), FieldValue::Int64(42))]);
+
+    let results: Vec<_> = trustfall::execute_query(&schema, adapter.clone(), query, variables)
+        .expect("valid query")
+        .collect();
+
+    let expected: Vec<BTreeMap<Arc<str>, FieldValue>> = vec![BTreeMap::from([
+        (Arc::from("next"), FieldValue::Int64(43)),
+        (Arc::from("value"), FieldValue::Int64(42)),
+    ])];
+    assert_eq!(expected, results);
+    assert_eq!(
+        vec![Some(CandidateValue::Single(FieldValue::Int64(42)))],
+        *adapter.starting_vertex_candidates.lock().unwrap(),
+    );
+}
@@ -0,0 +1,295 @@
+#![cfg(all(feature = "http", feature = "json-adapter"))]
+
+use std::{
+    convert::Infallible,
+    future::Future,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use trustfall::{
+    http::{ErrorCode, QueryLimits, QueryService, ResponseBody},
+    provider::JsonAdapter,
+};
+
+const QUERY: &str = r#"{ Number { value @output @filter(op: "<", value: ["$max"]) } }"#;
+
+fn adapter() -> JsonAdapter {
+    let documents = (1..=3).map(|value| serde_json::json!({ "value": value })).collect();
+    JsonAdapter::infer([("Number", documents)]).expect("failed to infer schema")
+}
+
+fn service() -> QueryService<JsonAdapter> {
+    let adapter = adapter();
+    QueryService::new(adapter.schema().clone(), Arc::new(adapter))
+}
+
+fn request(query: &str, variables: serde_json::Value) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({ "query": query, "variables": variables })).unwrap()
+}
+
+fn run(service: &QueryService<JsonAdapter>, body: &[u8]) -> String {
+    let response = service.handle(body).expect("request failed");
+    String::from_utf8(response.flatten().collect()).unwrap()
+}
+
+#[test]
+fn streams_results() {
+    let service = service();
+    assert_eq!(
+        "{\"results\":[\n{\"value\":1},\n{\"value\":2}\n]}\n",
+        run(&service, &request(QUERY, serde_json::json!({ "max": 3 }))),
+    );
+    assert_eq!(
+        "{\"results\":[\n]}\n",
+        run(&service, &request(QUERY, serde_json::json!({ "max": 0 }))),
+    );
+
+    let body = serde_json::to_vec(&serde_json::json!({ "query": "{ Number { value @output } }" }))
+        .unwrap();
+    let results: serde_json::Value = serde_json::from_str(&run(&service, &body)).unwrap();
+    assert_eq!(
+        serde_json::json!({ "results": [{ "value": 1 }, { "value": 2 }, { "value": 3 }] }),
+        results
+    );
+}
+
+#[test]
+fn reports_structured_errors() {
+    let service =
+        service().with_limits(QueryLimits { max_request_bytes: 200, ..Default::default() });
+
+    let cases = [
+        (b"not json".to_vec(), ErrorCode::InvalidRequest, 400),
+        (br#"{"variables": {}}"#.to_vec(), ErrorCode::InvalidRequest, 400),
+        (
+            request("{ Nonexistent { value @output } }", serde_json::json!({})),
+            ErrorCode::InvalidQuery,
+            400,
+        ),
+        (request(QUERY, serde_json::json!({})), ErrorCode::InvalidVariables, 400),
+        (
+            request(QUERY, serde_json::json!({ "max": 3, "extra": 1 })),
+            ErrorCode::InvalidVariables,
+            400,
+        ),
+        (
+            request(QUERY, serde_json::json!({ "max": "x".repeat(200) })),
+            ErrorCode::RequestTooLarge,
+            413,
+        ),
+    ];
+    for (body, code, status) in cases {
+        let error = service.handle(&body).expect_err("request should have failed");
+        assert_eq!((code, status), (error.code, error.status()), "{error}");
+
+        let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(serde_json::to_value(code).unwrap(), json["error"]["code"]);
+        assert_eq!(error.message, json["error"]["message"]);
+    }
+}
+
+#[test]
+fn result_limit_ends_the_stream_with_an_error() {
+    let service = service().with_limits(QueryLimits { max_results: Some(1), ..Default::default() });
+    let response = run(&service, &request(QUERY, serde_json::json!({ "max": 3 })));
+
+    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(serde_json::json!([{ "value": 1 }]), json["results"]);
+    assert_eq!("result_limit_exceeded", json["error"]["code"]);
+
+    // Queries within the limit are unaffected.
+    assert_eq!(
+        "{\"results\":[\n{\"value\":1}\n]}\n",
+        run(&service, &request(QUERY, serde_json::json!({ "max": 2 }))),
+    );
+}
+
+#[test]
+fn timeout() {
+    let service =
+        service().with_limits(QueryLimits { timeout: Some(Duration::ZERO), ..Default::default() });
+    let error = service
+        .handle(&request(QUERY, serde_json::json!({ "max": 3 })))
+        .expect_err("query should have timed out");
+    assert_eq!((ErrorCode::Timeout, 504), (error.code, error.status()));
+}
+
+#[test]
+fn concurrency_limit() {
+    let service = service()
+        .with_limits(QueryLimits { max_concurrent_queries: Some(1), ..Default::default() });
+    let body = request(QUERY, serde_json::json!({ "max": 3 }));
+
+    let running = service.handle(&body).expect("request failed");
+    let error = service.handle(&body).expect_err("too many queries were running");
+    assert_eq!((ErrorCode::TooManyRequests, 503), (error.code, error.status()));
+
+    // The limit applies until the running query's response is dropped.
+    drop(running);
+    run(&service, &body);
+    run(&service, &body);
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+}
+
+fn http_request(method: &str, body: Vec<u8>) -> http::Request<Full<bytes::Bytes>> {
+    http::Request::builder().method(method).uri("/query").body(Full::from(body)).unwrap()
+}
+
+fn read_response(response: http::Response<ResponseBody>) -> (u16, String) {
+    let status = response.status().as_u16();
+    let body = block_on(response.into_body().collect()).unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[test]
+fn handles_http_requests() {
+    let service = service();
+
+    let request = http_request("POST", request(QUERY, serde_json::json!({ "max": 2 })));
+    let response = block_on(service.handle_http(request));
+    assert_eq!("application/json", response.headers()["content-type"]);
+    assert_eq!((200, "{\"results\":[\n{\"value\":1}\n]}\n".to_string()), read_response(response));
+
+    let response = block_on(service.handle_http(http_request("GET", vec![])));
+    assert_eq!(
+        (405, r#"{"error":{"code":"method_not_allowed","message":"query requests must use the POST method"}}"#.to_string()),
+        read_response(response),
+    );
+
+    let response = block_on(service.handle_http(http_request("POST", b"{}}".to_vec())));
+    assert_eq!(400, read_response(response).0);
+}
+
+#[test]
+fn limits_the_size_of_http_request_bodies() {
+    let service =
+        service().with_limits(QueryLimits { max_request_bytes: 200, ..Default::default() });
+
+    // Bodies that are larger than they claim to be are cut off once they exceed the limit.
+    let body = request(QUERY, serde_json::json!({ "max": "x".repeat(200) }));
+    let mut request = http_request("POST", body);
+    request.headers_mut().insert("content-length", "10".parse().unwrap());
+    let (status, body) = read_response(block_on(service.handle_http(request)));
+    assert_eq!(413, status);
+    assert!(body.contains("request_too_large"), "{body}");
+
+    // Bodies that claim to be too large are rejected without being read.
+    let mut request = http_request("POST", vec![]);
+    request.headers_mut().insert("content-length", "2000000".parse().unwrap());
+    assert_eq!(413, read_response(block_on(service.handle_http(request))).0);
+}
+
+/// Serve each connection made to the listener with hyper, on a single-threaded runtime.
+fn serve_with_hyper<F, Fut>(handler: F) -> String
+where
+    F: Fn(http::Request<hyper::body::Incoming>) -> Fut + Send + 'static,
+    Fut: Future<Output = http::Response<ResponseBody>>,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+    let address = listener.local_addr().unwrap().to_string();
+    listener.set_nonblocking(true).unwrap();
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let handler = &handler;
+            loop {
+                let (stream, _) = listener.accept().await.expect("failed to accept");
+                let service = service_fn(move |request| async move {
+                    Ok::<_, Infallible>(handler(request).await)
+                });
+                let _ = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await;
+            }
+        })
+    });
+    address
+}
+
+fn send(address: &str, request: &str) -> String {
+    let mut stream = TcpStream::connect(address).expect("failed to connect");
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+fn post(address: &str, body: &str) -> String {
+    send(
+        address,
+        &format!(
+            "POST /query HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+            Connection: close\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len(),
+        ),
+    )
+}
+
+#[test]
+fn serves_over_hyper() {
+    let service = Arc::new(service());
+    let address = serve_with_hyper(move |request| {
+        let service = service.clone();
+        async move { service.handle_http(request).await }
+    });
+
+    let body = String::from_utf8(request(QUERY, serde_json::json!({ "max": 2 }))).unwrap();
+    let response = post(&address, &body);
+    let (head, chunks) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{head}");
+    assert!(head.contains("transfer-encoding: chunked"), "{head}");
+    assert_eq!("C\r\n{\"results\":[\r\nC\r\n\n{\"value\":1}\r\n4\r\n\n]}\n\r\n0\r\n\r\n", chunks);
+
+    let response = post(&address, &"x".repeat(2_000_000));
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"), "{response}");
+}
+
+#[cfg(feature = "graphql-server")]
+#[test]
+fn serves_graphql_over_hyper() {
+    use trustfall::{graphql_server::GraphQLFacade, http::GraphQLService};
+
+    let adapter = adapter();
+    let facade = GraphQLFacade::new(adapter.schema().clone(), Arc::new(adapter));
+    let service = Arc::new(
+        GraphQLService::new(facade)
+            .with_limits(QueryLimits { max_results: Some(2), ..Default::default() }),
+    );
+    let address = serve_with_hyper(move |request| {
+        let service = service.clone();
+        async move { service.handle_http(request).await }
+    });
+
+    let body = serde_json::json!({
+        "query": "query($first: Boolean!) { Number { value @include(if: $first) __typename } }",
+        "variables": { "first": true },
+    })
+    .to_string();
+    let response = post(&address, &body);
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{head}");
+    assert_eq!(
+        r#"{"errors":[{"message":"The request produced more than the maximum of 2 results."}]}"#,
+        body,
+    );
+
+    // GraphQL errors are reported in the response, rather than with an error status code.
+    let response = post(&address, r#"{"query": "{ Number { missing } }"}"#);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(response.ends_with(
+        r#"{"errors":[{"message":"Cannot query field \"missing\" on type \"Number\"."}]}"#
+    ));
+
+    let response = post(&address, "not json");
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{response}");
+    assert!(response.contains(r#"{"errors":[{"message":"invalid request body: "#), "{response}");
+}
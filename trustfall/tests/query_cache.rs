@@ -0,0 +1,111 @@
+use std::num::NonZeroUsize;
+
+use trustfall::{QueryCache, QueryCacheStats, Schema};
+
+const SCHEMA: &str = "\
+schema {
+    query: RootSchemaQuery
+}
+directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
+directive @output(name: String) on FIELD
+
+type RootSchemaQuery {
+    Number(max: Int!): [Number!]!
+}
+
+type Number {
+    value: Int!
+    successor: Number!
+}";
+
+const FIRST: &str = "{ Number(max: 3) { value @output } }";
+const SECOND: &str = "{ Number(max: 3) { successor { value @output } } }";
+const THIRD: &str = "{ Number(max: 5) { value @output } }";
+
+fn stats(hits: u64, misses: u64, evictions: u64, entries: usize) -> QueryCacheStats {
+    let mut stats = QueryCacheStats::default();
+    stats.hits = hits;
+    stats.misses = misses;
+    stats.evictions = evictions;
+    stats.entries = entries;
+    stats
+}
+
+fn cache(capacity: usize) -> QueryCache {
+    QueryCache::new(NonZeroUsize::new(capacity).expect("nonzero capacity"))
+}
+
+#[test]
+fn hits_and_misses() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let cache = cache(10);
+
+    let first = cache.get_or_compile(&schema, FIRST).expect("valid query");
+    assert_eq!(stats(0, 1, 0, 1), cache.stats());
+
+    let first_again = cache.get_or_compile(&schema, FIRST).expect("valid query");
+    assert_eq!(first, first_again);
+    assert_eq!(stats(1, 1, 0, 1), cache.stats());
+
+    cache.get_or_compile(&schema, SECOND).expect("valid query");
+    assert_eq!(stats(1, 2, 0, 2), cache.stats());
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let cache = cache(2);
+
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    cache.get_or_compile(&schema, SECOND).expect("valid query");
+
+    // Using the first query makes the second one the least recently used.
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    cache.get_or_compile(&schema, THIRD).expect("valid query");
+    assert_eq!(stats(1, 3, 1, 2), cache.stats());
+
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    assert_eq!(stats(2, 3, 1, 2), cache.stats());
+
+    cache.get_or_compile(&schema, SECOND).expect("valid query");
+    assert_eq!(stats(2, 4, 2, 2), cache.stats());
+}
+
+#[test]
+fn separate_entries_per_schema() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let other_schema =
+        Schema::parse(SCHEMA.replace("value: Int!", "value: Int")).expect("valid schema");
+    let cache = cache(10);
+
+    let compiled = cache.get_or_compile(&schema, FIRST).expect("valid query");
+    let other_compiled = cache.get_or_compile(&other_schema, FIRST).expect("valid query");
+    assert_eq!(schema.fingerprint(), compiled.schema_fingerprint());
+    assert_eq!(other_schema.fingerprint(), other_compiled.schema_fingerprint());
+    assert_eq!(stats(0, 2, 0, 2), cache.stats());
+}
+
+#[test]
+fn errors_are_not_cached() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let cache = cache(10);
+
+    let invalid = "{ Number(max: 3) { nonexistent @output } }";
+    cache.get_or_compile(&schema, invalid).expect_err("invalid query");
+    cache.get_or_compile(&schema, invalid).expect_err("invalid query");
+    assert_eq!(stats(0, 2, 0, 0), cache.stats());
+}
+
+#[test]
+fn clear_preserves_stats() {
+    let schema = Schema::parse(SCHEMA).expect("valid schema");
+    let cache = cache(10);
+
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    cache.clear();
+    assert_eq!(stats(1, 1, 0, 0), cache.stats());
+
+    cache.get_or_compile(&schema, FIRST).expect("valid query");
+    assert_eq!(stats(1, 2, 0, 1), cache.stats());
+}
@@ -0,0 +1,71 @@
+//! # trustfall_cli
+//!
+//! Run Trustfall queries from the command line, over JSON and CSV files,
+//! the filesystem, and adapter plugins.
+//!
+//! The `trustfall` binary validates schemas, compiles queries ahead of time,
+//! and runs queries with their results printed as a table, JSON, JSON Lines, or CSV.
+//! `trustfall repl` starts an interactive session for exploring a data source.
+//! Run `trustfall --help` for details.
+//!
+//! The building blocks of the binary are available here as a library,
+//! for tools that want to offer a similar experience.
+#![forbid(unsafe_code)]
+#![forbid(unused_lifetimes)]
+#![forbid(elided_lifetimes_in_paths)]
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use trustfall::{FieldValue, TransparentValue};
+
+mod output;
+mod repl;
+mod source;
+
+pub use output::{write_results, OutputFormat};
+pub use repl::{Repl, ReplFlow};
+pub use source::{root_edges, DataSource, LoadedSource};
+
+/// Parse a query variable given as `NAME=VALUE`.
+///
+/// The value is parsed as JSON if possible, so `limit=10` sets `limit` to the integer `10`
+/// and `names=["a", "b"]` sets `names` to a list of strings. Values that aren't valid JSON
+/// are taken to be strings, so `name=trustfall` sets `name` to the string `"trustfall"`.
+pub fn parse_variable(variable: &str) -> anyhow::Result<(Arc<str>, FieldValue)> {
+    let (name, value) = variable
+        .split_once('=')
+        .with_context(|| format!("expected `NAME=VALUE`, but got `{variable}`"))?;
+    let name = name.trim().strip_prefix('$').unwrap_or(name.trim());
+    if name.is_empty() {
+        anyhow::bail!("variable `{variable}` has no name");
+    }
+
+    let value = match serde_json::from_str::<TransparentValue>(value) {
+        Ok(value) => value.into(),
+        Err(_) => FieldValue::String(value.into()),
+    };
+    Ok((name.into(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use trustfall::FieldValue;
+
+    use super::parse_variable;
+
+    #[test]
+    fn variables_are_parsed_as_json_or_strings() {
+        assert_eq!(("limit".into(), FieldValue::Int64(10)), parse_variable("limit=10").unwrap());
+        assert_eq!(
+            ("names".into(), FieldValue::List(vec!["a".into(), "b".into()].into())),
+            parse_variable(r#"$names=["a", "b"]"#).unwrap(),
+        );
+        assert_eq!(("name".into(), "trustfall".into()), parse_variable("name=trustfall").unwrap());
+        assert_eq!(("quoted".into(), "x=1".into()), parse_variable(r#"quoted="x=1""#).unwrap());
+        assert_eq!(("empty".into(), "".into()), parse_variable("empty=").unwrap());
+
+        assert!(parse_variable("missing_value").is_err());
+        assert!(parse_variable("=1").is_err());
+    }
+}
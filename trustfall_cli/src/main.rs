@@ -0,0 +1,290 @@
+#![forbid(unsafe_code)]
+#![forbid(unused_lifetimes)]
+#![forbid(elided_lifetimes_in_paths)]
+
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand};
+use trustfall::{compile, CompiledQuery, CompiledQueryError, Schema};
+use trustfall_cli::{parse_variable, root_edges, write_results, DataSource, OutputFormat, Repl};
+
+/// Validate Trustfall schemas, and compile and run Trustfall queries.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check that a schema is valid, and report any likely mistakes in it.
+    ValidateSchema {
+        /// Trustfall schema file to validate.
+        ///
+        /// Usually a file with a ".graphql" or ".gql" extension.
+        #[arg(value_name = "FILE")]
+        schema: PathBuf,
+    },
+
+    /// Check that a query is valid for a schema, and describe its outputs.
+    ///
+    /// With `--output`, the compiled query is saved so that `trustfall run`
+    /// can run it later without parsing and checking it again.
+    Compile {
+        /// Trustfall schema file against which to compile the query.
+        #[arg(short, long, value_name = "FILE")]
+        schema: PathBuf,
+
+        /// File in which to save the compiled query.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// File containing the query to compile, or "-" to read it from standard input.
+        #[arg(value_name = "QUERY")]
+        query: PathBuf,
+    },
+
+    /// Run a query against a data source, printing its results.
+    Run(RunArgs),
+
+    /// Start an interactive session for running queries against a data source.
+    ///
+    /// Queries may span multiple lines, and run as soon as their braces are balanced.
+    /// Enter `:help` in the session for a list of commands, such as for setting query variables.
+    Repl {
+        #[command(flatten)]
+        source: SourceArgs,
+
+        /// The format in which to print query results.
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    #[command(flatten)]
+    source: SourceArgs,
+
+    /// Value of a query variable, in the form `NAME=VALUE`. May be repeated.
+    ///
+    /// Values are parsed as JSON if possible, and are otherwise taken to be strings.
+    #[arg(short, long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(Arc<str>, trustfall::FieldValue)>,
+
+    /// The format in which to print the query's results.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Report how long the query took to compile and execute,
+    /// and how many results it produced, on standard error.
+    #[arg(long)]
+    time: bool,
+
+    /// File containing the query to run, or "-" to read it from standard input.
+    ///
+    /// Queries saved by `trustfall compile --output` are also accepted.
+    #[arg(value_name = "QUERY")]
+    query: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct SourceArgs {
+    #[command(flatten)]
+    kind: SourceKindArgs,
+
+    /// Trustfall schema file describing the data source.
+    ///
+    /// Required for CSV files. Optional for JSON files, whose schema is otherwise
+    /// inferred from their contents. Not used for other data sources,
+    /// which provide their own schema.
+    #[arg(short, long, value_name = "FILE")]
+    schema: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+struct SourceKindArgs {
+    /// Query a JSON or JSON Lines file, whose documents are available at the given root edge,
+    /// in the form `EDGE=PATH`. May be repeated.
+    #[arg(long, value_name = "EDGE=PATH", value_parser = parse_named_path)]
+    json: Vec<(String, PathBuf)>,
+
+    /// Query a CSV file, whose rows are available at the given root edge,
+    /// in the form `EDGE=PATH`. May be repeated. Requires `--schema`.
+    #[arg(long, value_name = "EDGE=PATH", value_parser = parse_named_path)]
+    csv: Vec<(String, PathBuf)>,
+
+    /// Query the files and directories within the given directory.
+    #[arg(long, value_name = "DIR")]
+    filesystem: Option<PathBuf>,
+
+    /// Query an adapter plugin, started by running the given command.
+    ///
+    /// The command's arguments are separated by whitespace, for example
+    /// `--plugin "my-plugin --verbose"`.
+    #[arg(long, value_name = "COMMAND")]
+    plugin: Option<String>,
+}
+
+fn parse_named_path(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((name, path)) if !name.trim().is_empty() && !path.is_empty() => {
+            Ok((name.trim().to_string(), PathBuf::from(path)))
+        }
+        _ => Err(format!("expected `EDGE=PATH`, but got `{value}`")),
+    }
+}
+
+impl SourceArgs {
+    fn data_source(&self) -> anyhow::Result<DataSource> {
+        let SourceKindArgs { json, csv, filesystem, plugin } = &self.kind;
+        let ignored_schema = || {
+            if self.schema.is_some() {
+                anyhow::bail!("--schema is only used with --json and --csv data sources");
+            }
+            Ok(())
+        };
+
+        if !json.is_empty() {
+            Ok(DataSource::Json { collections: json.clone(), schema: self.schema.clone() })
+        } else if !csv.is_empty() {
+            let schema = self.schema.clone().context("--csv requires a --schema")?;
+            Ok(DataSource::Csv { tables: csv.clone(), schema })
+        } else if let Some(root) = filesystem {
+            ignored_schema()?;
+            Ok(DataSource::Filesystem { root: root.clone() })
+        } else if let Some(plugin) = plugin {
+            ignored_schema()?;
+            let mut words = plugin.split_whitespace().map(String::from);
+            let program = words.next().context("--plugin requires a command")?;
+            Ok(DataSource::Plugin { program, args: words.collect() })
+        } else {
+            unreachable!("clap requires a data source")
+        }
+    }
+}
+
+fn read_input(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_end(&mut contents).context("failed to read standard input")?;
+    } else {
+        contents =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    }
+    Ok(contents)
+}
+
+fn read_schema(path: &Path) -> anyhow::Result<Schema> {
+    let schema_text = String::from_utf8(read_input(path)?)
+        .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+    Schema::parse(schema_text).with_context(|| format!("{} is not a valid schema", path.display()))
+}
+
+/// Load a query that was either saved by `trustfall compile --output`, or written by hand.
+fn load_query(schema: &Schema, path: &Path) -> anyhow::Result<CompiledQuery> {
+    let contents = read_input(path)?;
+    match CompiledQuery::from_bytes(schema, &contents) {
+        Ok(query) => Ok(query),
+        Err(CompiledQueryError::NotACompiledQuery) => {
+            let query = String::from_utf8(contents)
+                .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+            compile(schema, &query).context("the query is not valid for the schema")
+        }
+        Err(e) => Err(e).with_context(|| format!("failed to load {}", path.display())),
+    }
+}
+
+fn validate_schema(schema_path: &Path) -> anyhow::Result<()> {
+    let schema = read_schema(schema_path)?;
+    let warnings = schema.analyze();
+    for warning in &warnings {
+        println!("warning: {warning}");
+    }
+
+    let edges = root_edges(&schema);
+    println!(
+        "{} is valid, with {} warning(s) and root edges: {}",
+        schema_path.display(),
+        warnings.len(),
+        edges.into_iter().collect::<Vec<_>>().join(", "),
+    );
+    Ok(())
+}
+
+fn compile_query(
+    schema_path: &Path,
+    query_path: &Path,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let schema = read_schema(schema_path)?;
+    let query = load_query(&schema, query_path)?;
+
+    println!("The query is valid, and has the following outputs:");
+    for output in query.query().query_outputs().iter() {
+        println!("  {}: {}", output.name, output.value_type);
+    }
+
+    if let Some(output) = output {
+        std::fs::write(output, query.to_bytes())
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        println!("Saved the compiled query to {}", output.display());
+    }
+    Ok(())
+}
+
+fn run_query(args: RunArgs) -> anyhow::Result<()> {
+    let source = args.source.data_source()?.load()?;
+
+    let start = Instant::now();
+    let query = load_query(source.schema(), &args.query)?;
+    let compile_time = start.elapsed();
+
+    let columns: Vec<Arc<str>> = query.query().query_outputs().names().cloned().collect();
+    let variables: BTreeMap<Arc<str>, trustfall::FieldValue> = args.variables.into_iter().collect();
+
+    let start = Instant::now();
+    let results = source.execute(&query, variables)?;
+    let stdout = io::stdout().lock();
+    let count = write_results(args.format, &columns, results, io::BufWriter::new(stdout))?;
+    let execution_time = start.elapsed();
+
+    if args.time {
+        let mut stderr = io::stderr().lock();
+        writeln!(
+            stderr,
+            "{count} result(s); compiled in {}, executed in {}",
+            format_duration(compile_time),
+            format_duration(execution_time),
+        )?;
+    }
+    Ok(())
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::ValidateSchema { schema } => validate_schema(&schema),
+        Command::Compile { schema, output, query } => {
+            compile_query(&schema, &query, output.as_deref())
+        }
+        Command::Run(args) => run_query(args),
+        Command::Repl { source, format } => {
+            let source = source.data_source()?.load()?;
+            println!("Connected. Enter a query, or :help for a list of commands.");
+            Repl::new(source).with_format(format).run(io::stdin().lock(), io::stdout().lock())
+        }
+    }
+}
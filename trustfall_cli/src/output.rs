@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, io::Write, sync::Arc};
+
+use trustfall::{FieldValue, TransparentValue};
+
+/// The formats in which query results can be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// A table with a column per output, aligned for reading in a terminal.
+    #[default]
+    Table,
+
+    /// A JSON array with an object per result.
+    Json,
+
+    /// A JSON object per result, one per line.
+    JsonLines,
+
+    /// CSV with a header row naming the outputs.
+    Csv,
+}
+
+/// Write query results in the given format, returning the number of results written.
+///
+/// The `columns` are the names of the query's outputs, in the order in which they should
+/// appear in formats that order them, such as tables and CSV.
+///
+/// Writing stops at the first error among the results, which is returned.
+pub fn write_results(
+    format: OutputFormat,
+    columns: &[Arc<str>],
+    results: impl Iterator<Item = anyhow::Result<BTreeMap<Arc<str>, FieldValue>>>,
+    mut output: impl Write,
+) -> anyhow::Result<usize> {
+    let mut count = 0;
+    match format {
+        OutputFormat::Table => {
+            let rows: Vec<Vec<String>> = results
+                .map(|result| {
+                    result.map(|result| {
+                        columns.iter().map(|column| cell_text(&result[column])).collect()
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?;
+            count = rows.len();
+            write_table(columns, &rows, &mut output)?;
+        }
+        OutputFormat::Json => {
+            write!(output, "[")?;
+            for result in results {
+                let result = result?;
+                let separator = if count == 0 { "" } else { "," };
+                write!(output, "{separator}\n  {}", to_json(result))?;
+                count += 1;
+            }
+            let newline = if count == 0 { "" } else { "\n" };
+            writeln!(output, "{newline}]")?;
+        }
+        OutputFormat::JsonLines => {
+            for result in results {
+                writeln!(output, "{}", to_json(result?))?;
+                count += 1;
+            }
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(output);
+            writer.write_record(columns.iter().map(|column| column.as_ref()))?;
+            for result in results {
+                let result = result?;
+                writer.write_record(columns.iter().map(|column| cell_text(&result[column])))?;
+                count += 1;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(count)
+}
+
+fn to_json(result: BTreeMap<Arc<str>, FieldValue>) -> String {
+    let result: BTreeMap<Arc<str>, TransparentValue> =
+        result.into_iter().map(|(name, value)| (name, value.into())).collect();
+    serde_json::to_string(&result).expect("failed to serialize result")
+}
+
+/// The text of a value within a table cell or CSV field.
+///
+/// Strings appear without quotes and nulls are empty. Other values appear as JSON.
+fn cell_text(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Null => String::new(),
+        FieldValue::String(s) | FieldValue::Enum(s) => s.to_string(),
+        other => serde_json::to_string(&TransparentValue::from(other.clone()))
+            .expect("failed to serialize value"),
+    }
+}
+
+fn write_table(
+    columns: &[Arc<str>],
+    rows: &[Vec<String>],
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .fold(column.chars().count(), usize::max)
+        })
+        .collect();
+
+    let write_row = |output: &mut dyn Write, cells: &mut dyn Iterator<Item = &str>| {
+        let line = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        writeln!(output, "{}", line.trim_end())
+    };
+
+    write_row(output, &mut columns.iter().map(|column| column.as_ref()))?;
+    let separator = widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-");
+    writeln!(output, "{separator}")?;
+    for row in rows {
+        write_row(output, &mut row.iter().map(String::as_str))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use trustfall::FieldValue;
+
+    use super::{write_results, OutputFormat};
+
+    fn results() -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        vec![
+            BTreeMap::from([
+                (Arc::from("name"), "trustfall".into()),
+                (Arc::from("authors"), FieldValue::List(vec!["Predrag".into()].into())),
+                (Arc::from("downloads"), 120.into()),
+            ]),
+            BTreeMap::from([
+                (Arc::from("name"), "serde, the framework".into()),
+                (Arc::from("authors"), FieldValue::List(vec![].into())),
+                (Arc::from("downloads"), FieldValue::Null),
+            ]),
+        ]
+    }
+
+    fn write(format: OutputFormat, results: Vec<BTreeMap<Arc<str>, FieldValue>>) -> String {
+        let columns: Vec<Arc<str>> = vec!["name".into(), "downloads".into(), "authors".into()];
+        let mut output = Vec::new();
+        let count =
+            write_results(format, &columns, results.clone().into_iter().map(Ok), &mut output)
+                .expect("failed to write results");
+        assert_eq!(results.len(), count);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn table() {
+        let expected = "\
+name                 | downloads | authors
+---------------------+-----------+------------
+trustfall            | 120       | [\"Predrag\"]
+serde, the framework |           | []
+";
+        assert_eq!(expected, write(OutputFormat::Table, results()));
+        assert_eq!(
+            "name | downloads | authors\n-----+-----------+--------\n",
+            write(OutputFormat::Table, vec![]),
+        );
+    }
+
+    #[test]
+    fn json() {
+        let expected = r#"[
+  {"authors":["Predrag"],"downloads":120,"name":"trustfall"},
+  {"authors":[],"downloads":null,"name":"serde, the framework"}
+]
+"#;
+        assert_eq!(expected, write(OutputFormat::Json, results()));
+        assert_eq!("[]\n", write(OutputFormat::Json, vec![]));
+    }
+
+    #[test]
+    fn json_lines() {
+        let expected = r#"{"authors":["Predrag"],"downloads":120,"name":"trustfall"}
+{"authors":[],"downloads":null,"name":"serde, the framework"}
+"#;
+        assert_eq!(expected, write(OutputFormat::JsonLines, results()));
+    }
+
+    #[test]
+    fn csv() {
+        let expected = "\
+name,downloads,authors
+trustfall,120,\"[\"\"Predrag\"\"]\"
+\"serde, the framework\",,[]
+";
+        assert_eq!(expected, write(OutputFormat::Csv, results()));
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Write},
+    sync::Arc,
+    time::Instant,
+};
+
+use clap::ValueEnum;
+use trustfall::{compile, FieldValue, TransparentValue};
+use trustfall_lsp::{Analyzer, Position};
+
+use crate::{parse_variable, write_results, LoadedSource, OutputFormat};
+
+const PROMPT: &str = "trustfall> ";
+const CONTINUATION_PROMPT: &str = "       ...> ";
+
+const HELP: &str = "\
+Enter a query over as many lines as needed. It runs once its braces are balanced.
+
+Commands:
+  :set NAME=VALUE   set the value of a query variable, parsed as JSON if possible
+  :unset NAME       remove a query variable
+  :vars             list the query variables and their values
+  :format FORMAT    print results as a table, json, json-lines, or csv
+  :complete [TEXT]  list the edges, properties, types, or directives
+                    that could complete the query entered so far followed by TEXT
+  :cancel           discard the query entered so far
+  :help             show this message
+  :quit             end the session";
+
+/// Whether an interactive session should keep going after handling a line of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplFlow {
+    Continue,
+    Quit,
+}
+
+/// An interactive session for exploring a data source by running queries against it.
+///
+/// Queries may span multiple lines, and run as soon as they are complete.
+/// Lines starting with `:` are commands that set query variables, change the output format,
+/// and so on; `:help` lists them. Mistakes, such as invalid queries, are reported
+/// in the session's output rather than ending the session.
+///
+/// [`Repl::complete`] suggests completions based on the data source's schema,
+/// for use by line editors that support tab completion.
+#[derive(Debug)]
+pub struct Repl {
+    source: LoadedSource,
+    analyzer: Analyzer,
+    variables: BTreeMap<Arc<str>, FieldValue>,
+    format: OutputFormat,
+
+    /// The lines of a query that has not yet been completely entered.
+    pending: String,
+}
+
+impl Repl {
+    pub fn new(source: LoadedSource) -> Self {
+        let analyzer = Analyzer::new(source.schema().clone());
+        Self {
+            source,
+            analyzer,
+            variables: Default::default(),
+            format: OutputFormat::Table,
+            pending: String::new(),
+        }
+    }
+
+    /// Set the format in which query results are printed.
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The values of the query variables set so far.
+    pub fn variables(&self) -> &BTreeMap<Arc<str>, FieldValue> {
+        &self.variables
+    }
+
+    /// The prompt to show before reading the next line of input.
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        }
+    }
+
+    /// The possible completions of the word at the end of the given line of input,
+    /// taking into account the lines of the query entered so far.
+    ///
+    /// Each completion is a whole word, which replaces the partial word at the end of the line.
+    pub fn complete(&self, line: &str) -> Vec<String> {
+        let text = format!("{}{line}", self.pending);
+        let line_number = text.matches('\n').count();
+        let character = text.rsplit('\n').next().unwrap_or_default().chars().count();
+        let word_start = text.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        let partial_word = &text[word_start.len()..];
+
+        let mut completions: Vec<String> = self
+            .analyzer
+            .completions(&text, Position { line: line_number, character })
+            .into_iter()
+            .map(|completion| completion.label)
+            .filter(|label| label.starts_with(partial_word))
+            .collect();
+        completions.sort_unstable();
+        completions.dedup();
+        completions
+    }
+
+    /// Run the session, reading lines from `input` until it ends or the user quits.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> anyhow::Result<()> {
+        write!(output, "{}", self.prompt())?;
+        output.flush()?;
+        for line in input.lines() {
+            if self.handle_line(&line?, &mut output)? == ReplFlow::Quit {
+                return Ok(());
+            }
+            write!(output, "{}", self.prompt())?;
+            output.flush()?;
+        }
+        writeln!(output)?;
+        Ok(())
+    }
+
+    /// Handle a line of input, writing any results or messages to `output`.
+    ///
+    /// Only failures to write to `output` are returned as errors.
+    pub fn handle_line(&mut self, line: &str, output: &mut impl Write) -> anyhow::Result<ReplFlow> {
+        if let Some(command) = line.trim().strip_prefix(':') {
+            return self.handle_command(command, output);
+        }
+        if self.pending.is_empty() && line.trim().is_empty() {
+            return Ok(ReplFlow::Continue);
+        }
+
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        if is_complete_query(&self.pending) {
+            let query = std::mem::take(&mut self.pending);
+            self.run_query(&query, output)?;
+        }
+        Ok(ReplFlow::Continue)
+    }
+
+    fn handle_command(
+        &mut self,
+        command: &str,
+        output: &mut impl Write,
+    ) -> anyhow::Result<ReplFlow> {
+        let (name, argument) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        let argument = argument.trim();
+        match name {
+            "quit" | "exit" => return Ok(ReplFlow::Quit),
+            "help" => writeln!(output, "{HELP}")?,
+            "cancel" => self.pending.clear(),
+            "set" if !argument.is_empty() => match parse_variable(argument) {
+                Ok((name, value)) => {
+                    self.variables.insert(name, value);
+                }
+                Err(e) => writeln!(output, "error: {e}")?,
+            },
+            "vars" | "set" => {
+                for (name, value) in &self.variables {
+                    let value = serde_json::to_string(&TransparentValue::from(value.clone()))?;
+                    writeln!(output, "${name} = {value}")?;
+                }
+            }
+            "unset" => {
+                let name = argument.strip_prefix('$').unwrap_or(argument);
+                if self.variables.remove(name).is_none() {
+                    writeln!(output, "error: variable `{name}` is not set")?;
+                }
+            }
+            "format" => match OutputFormat::from_str(argument, true) {
+                Ok(format) => self.format = format,
+                Err(_) => writeln!(
+                    output,
+                    "error: unknown format `{argument}`; expected table, json, json-lines, or csv"
+                )?,
+            },
+            "complete" => {
+                let completions = self.complete(argument);
+                if completions.is_empty() {
+                    writeln!(output, "no completions")?;
+                } else {
+                    writeln!(output, "{}", completions.join("  "))?;
+                }
+            }
+            _ => writeln!(output, "error: unknown command `:{name}`; enter :help for a list")?,
+        }
+        Ok(ReplFlow::Continue)
+    }
+
+    fn run_query(&self, query: &str, output: &mut impl Write) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let compiled = match compile(self.source.schema(), query) {
+            Ok(compiled) => compiled,
+            Err(e) => {
+                writeln!(output, "error: {e}")?;
+                return Ok(());
+            }
+        };
+        let columns: Vec<Arc<str>> = compiled.query().query_outputs().names().cloned().collect();
+        let results = match self.source.execute(&compiled, self.variables.clone()) {
+            Ok(results) => results,
+            Err(e) => {
+                writeln!(output, "error: {e}")?;
+                return Ok(());
+            }
+        };
+
+        // Errors while producing the results end the query, not the session.
+        let count = match write_results(self.format, &columns, results, &mut *output) {
+            Ok(count) => count,
+            Err(e) => {
+                writeln!(output, "error: {e:#}")?;
+                return Ok(());
+            }
+        };
+        if self.format == OutputFormat::Table {
+            let plural = if count == 1 { "" } else { "s" };
+            let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+            writeln!(output, "({count} result{plural} in {elapsed:.3}ms)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the text has at least one selection set, and all its braces are balanced.
+///
+/// Braces within strings and comments are ignored.
+fn is_complete_query(text: &str) -> bool {
+    let mut depth = 0i64;
+    let mut opened = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '{' => {
+                depth += 1;
+                opened = true;
+            }
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    opened && depth <= 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{DataSource, LoadedSource};
+
+    use super::{is_complete_query, Repl, ReplFlow};
+
+    fn source() -> LoadedSource {
+        let path: PathBuf =
+            [env!("CARGO_MANIFEST_DIR"), "test_data", "crates.jsonl"].iter().collect();
+        DataSource::Json { collections: vec![("Crate".into(), path)], schema: None }
+            .load()
+            .expect("failed to load data source")
+    }
+
+    fn session(repl: &mut Repl, input: &str) -> String {
+        let mut output = Vec::new();
+        repl.run(input.as_bytes(), &mut output).expect("failed to run session");
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn multi_line_queries_with_variables() {
+        let input = r#":set min=100
+{
+    Crate {
+        name @output
+        downloads @output @filter(op: ">=", value: ["$min"])
+        # a comment with a { brace
+        name @filter(op: "!=", value: ["$excluded"])
+    }
+}
+:set excluded="serde"
+:format json-lines
+{ Crate { name @output @filter(op: "!=", value: ["$excluded"]) downloads @filter(op: ">=", value: ["$min"]) } }
+:vars
+"#;
+        let expected = r#"trustfall> trustfall>        ...>        ...>        ...>        ...>        ...>        ...>        ...> error: One or more arguments required by this query were not provided: "excluded" of type String
+trustfall> trustfall> trustfall> {"name":"trustfall"}
+trustfall> $excluded = "serde"
+$min = 100
+trustfall> 
+"#;
+        let mut repl = Repl::new(source());
+        assert_eq!(expected, session(&mut repl, input));
+        assert_eq!(2, repl.variables().len());
+    }
+
+    #[test]
+    fn table_output_and_errors() {
+        let input = "\
+{ Crate { name @output @filter(op: \"=\", value: [\"$name\"]) } }
+:set name=anyhow
+{ Crate { name @output @filter(op: \"=\", value: [\"$name\"]) } }
+{ Crate { nonexistent @output } }
+{ Crate {
+:cancel
+:unset name
+:unset name
+:format xml
+:bogus
+:quit
+{ Crate { name @output } }
+";
+        let mut repl = Repl::new(source());
+        let output = session(&mut repl, input);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0]
+            .contains("error: One or more arguments required by this query were not provided"));
+        assert_eq!("trustfall> trustfall> name", lines[1]);
+        assert_eq!("------", lines[2]);
+        assert_eq!("anyhow", lines[3]);
+        assert!(lines[4].starts_with("(1 result in "), "{}", lines[4]);
+        assert!(lines[5].contains("error:") && lines[5].contains("nonexistent"), "{}", lines[5]);
+        assert_eq!(
+            "trustfall>        ...> trustfall> trustfall> error: variable `name` is not set",
+            lines[6],
+        );
+        assert!(lines[7].starts_with("trustfall> error: unknown format `xml`"), "{}", lines[7]);
+        assert!(lines[8].starts_with("trustfall> error: unknown command `:bogus`"), "{}", lines[8]);
+        assert_eq!("trustfall> ", lines[9]);
+        assert_eq!(10, lines.len());
+    }
+
+    #[test]
+    fn completions_use_the_schema_and_the_pending_query() {
+        let mut repl = Repl::new(source());
+        assert_eq!(vec!["Crate"], repl.complete("{ C"));
+        assert_eq!(vec!["Crate"], repl.complete("{ "));
+        assert!(repl.complete("{ X").is_empty());
+
+        let mut output = Vec::new();
+        assert_eq!(ReplFlow::Continue, repl.handle_line("{", &mut output).unwrap());
+        assert_eq!(ReplFlow::Continue, repl.handle_line("  Crate {", &mut output).unwrap());
+        assert_eq!(vec!["__typename", "authors", "downloads", "name"], repl.complete("    "));
+        assert_eq!(vec!["downloads"], repl.complete("    d"));
+        assert_eq!(vec!["filter", "fold"], repl.complete("    name @f"));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn query_completeness() {
+        assert!(!is_complete_query(""));
+        assert!(!is_complete_query("{ Crate {"));
+        assert!(!is_complete_query("{ Crate { name @output(name: \"}}\") }"));
+        assert!(!is_complete_query("{ Crate { # }}\n"));
+        assert!(is_complete_query("{ Crate { name @output } }"));
+        assert!(is_complete_query("{\n  Crate {\n    name @output\n  }\n}\n"));
+    }
+}
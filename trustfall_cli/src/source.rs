@@ -0,0 +1,271 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::Context;
+use trustfall::{
+    execute_compiled, execute_query,
+    plugin::PluginAdapter,
+    provider::{CsvAdapter, CsvTable, DynAdapter, FilesystemAdapter, JsonAdapter},
+    CompiledQuery, FieldValue, Schema, SchemaAdapter,
+};
+
+type Row = BTreeMap<Arc<str>, FieldValue>;
+
+/// A source of data that queries may be run against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataSource {
+    /// JSON or JSON Lines files, each of which is a collection of documents
+    /// available at the root edge of the given name.
+    ///
+    /// Unless a schema is given, one is inferred from the documents.
+    Json { collections: Vec<(String, PathBuf)>, schema: Option<PathBuf> },
+
+    /// CSV files, each of which is a table whose rows are available
+    /// at the root edge of the given name of the given schema.
+    Csv { tables: Vec<(String, PathBuf)>, schema: PathBuf },
+
+    /// The files and directories within the given directory.
+    Filesystem { root: PathBuf },
+
+    /// An adapter plugin, started by running the given program with the given arguments.
+    Plugin { program: String, args: Vec<String> },
+}
+
+/// A [`DataSource`] that is ready to be queried.
+#[derive(Debug, Clone)]
+pub struct LoadedSource {
+    schema: Schema,
+    adapter: Arc<DynAdapter>,
+
+    /// The plugin providing the data, whose failures are reported as query errors.
+    plugin: Option<Arc<PluginAdapter>>,
+}
+
+impl LoadedSource {
+    /// The schema against which queries over this data source should be written.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The adapter over this data source's data.
+    pub fn adapter(&self) -> &Arc<DynAdapter> {
+        &self.adapter
+    }
+
+    /// Execute the query over this data source's data.
+    ///
+    /// If the data source fails while the query is running, for example because
+    /// its plugin exited, the results end with an error describing the failure.
+    pub fn execute(
+        &self,
+        query: &CompiledQuery,
+        variables: BTreeMap<Arc<str>, FieldValue>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = anyhow::Result<Row>>>> {
+        let results = execute_compiled(self.adapter.clone(), query, variables)?;
+        Ok(match &self.plugin {
+            Some(plugin) => Box::new(
+                plugin
+                    .check_results(results)
+                    .map(|result| result.context("the plugin failed to resolve the query's data")),
+            ),
+            None => Box::new(results.map(Ok)),
+        })
+    }
+}
+
+impl DataSource {
+    /// Read the data source's data and schema, making it ready to be queried.
+    pub fn load(&self) -> anyhow::Result<LoadedSource> {
+        let mut plugin = None;
+        let (schema, adapter) = match self {
+            DataSource::Json { collections, schema } => {
+                let mut documents = Vec::with_capacity(collections.len());
+                for (name, path) in collections {
+                    let contents = read_file(path)?;
+                    let parsed = JsonAdapter::parse_documents(&contents)
+                        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+                    documents.push((name.as_str(), parsed));
+                }
+
+                let adapter = match schema {
+                    Some(schema_path) => {
+                        let schema = read_schema(schema_path)?;
+                        check_root_edges(&schema, collections.iter().map(|(name, _)| name))?;
+                        documents
+                            .into_iter()
+                            .fold(JsonAdapter::new(schema), |adapter, (name, docs)| {
+                                adapter.with_documents(name, docs)
+                            })
+                    }
+                    None => JsonAdapter::infer(documents)
+                        .context("failed to infer a schema from the JSON documents")?,
+                };
+                (adapter.schema().clone(), DynAdapter::new(Arc::new(adapter)))
+            }
+            DataSource::Csv { tables, schema } => {
+                let schema = read_schema(schema)?;
+                check_root_edges(&schema, tables.iter().map(|(name, _)| name))?;
+
+                let mut adapter = CsvAdapter::new(schema);
+                for (name, path) in tables {
+                    let table = CsvTable::parse(&read_file(path)?)
+                        .with_context(|| format!("{} is not valid CSV", path.display()))?;
+                    adapter = adapter.with_table(name.as_str(), table);
+                }
+                (adapter.schema().clone(), DynAdapter::new(Arc::new(adapter)))
+            }
+            DataSource::Filesystem { root } => {
+                if !root.is_dir() {
+                    anyhow::bail!("{} is not a directory", root.display());
+                }
+                let adapter = FilesystemAdapter::new(root);
+                (FilesystemAdapter::schema().clone(), DynAdapter::new(Arc::new(adapter)))
+            }
+            DataSource::Plugin { program, args } => {
+                let mut command = Command::new(program);
+                command.args(args);
+                let adapter = Arc::new(
+                    PluginAdapter::spawn(command)
+                        .with_context(|| format!("failed to load plugin `{program}`"))?,
+                );
+                plugin = Some(adapter.clone());
+                (adapter.schema().clone(), DynAdapter::new(adapter))
+            }
+        };
+
+        #[allow(clippy::arc_with_non_send_sync)]
+        Ok(LoadedSource { schema, adapter: Arc::new(adapter), plugin })
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn read_schema(path: &Path) -> anyhow::Result<Schema> {
+    Schema::parse(read_file(path)?)
+        .with_context(|| format!("{} is not a valid schema", path.display()))
+}
+
+/// Ensure that each of the given names is a root edge of the schema.
+fn check_root_edges<'a>(
+    schema: &Schema,
+    names: impl IntoIterator<Item = &'a String>,
+) -> anyhow::Result<()> {
+    let edges = root_edges(schema);
+    for name in names {
+        if !edges.contains(name) {
+            anyhow::bail!(
+                "`{name}` is not an edge of the schema's root type; expected one of: {}",
+                edges.iter().map(String::as_str).collect::<Vec<_>>().join(", "),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The names of the edges at which queries over the given schema may begin.
+pub fn root_edges(schema: &Schema) -> BTreeSet<String> {
+    static SCHEMA_SCHEMA: OnceLock<Schema> = OnceLock::new();
+    let schema_schema = SCHEMA_SCHEMA
+        .get_or_init(|| Schema::parse(SchemaAdapter::schema_text()).expect("invalid schema"));
+
+    let query = "{ Entrypoint { name @output } }";
+    let variables: BTreeMap<Arc<str>, FieldValue> = BTreeMap::new();
+    execute_query(schema_schema, Arc::new(SchemaAdapter::new(schema)), query, variables)
+        .expect("invalid query")
+        .filter_map(|row| row["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+    use trustfall::{execute_query, FieldValue};
+
+    use super::{root_edges, DataSource};
+
+    type Row = BTreeMap<Arc<str>, FieldValue>;
+
+    fn test_data(name: &str) -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "test_data", name].iter().collect()
+    }
+
+    fn run(source: &DataSource, query: &str, variables: Row) -> Vec<Row> {
+        let loaded = source.load().expect("failed to load data source");
+        execute_query(loaded.schema(), loaded.adapter().clone(), query, variables)
+            .expect("invalid query")
+            .collect()
+    }
+
+    #[test]
+    fn json_with_inferred_schema() {
+        let source = DataSource::Json {
+            collections: vec![("Crate".into(), test_data("crates.jsonl"))],
+            schema: None,
+        };
+        let query = r#"
+{
+    Crate {
+        name @output @filter(op: "has_prefix", value: ["$prefix"])
+        authors @fold @transform(op: "count") @output(name: "author_count")
+    }
+}"#;
+        assert_eq!(
+            vec![BTreeMap::from([
+                (Arc::from("name"), "trustfall".into()),
+                (Arc::from("author_count"), 1u64.into()),
+            ])],
+            run(&source, query, BTreeMap::from([(Arc::from("prefix"), "trust".into())])),
+        );
+    }
+
+    #[test]
+    fn csv_with_schema() {
+        let source = DataSource::Csv {
+            tables: vec![("Crate".into(), test_data("crates.csv"))],
+            schema: test_data("crates.graphql"),
+        };
+        let query = r#"
+{
+    Crate {
+        name @output
+        downloads @filter(op: ">", value: ["$min"])
+    }
+}"#;
+        assert_eq!(
+            vec![
+                BTreeMap::from([(Arc::from("name"), "serde".into())]),
+                BTreeMap::from([(Arc::from("name"), "trustfall".into())]),
+            ],
+            run(&source, query, BTreeMap::from([(Arc::from("min"), 100.into())])),
+        );
+    }
+
+    #[test]
+    fn data_must_match_schema_root_edges() {
+        let source = DataSource::Csv {
+            tables: vec![("Package".into(), test_data("crates.csv"))],
+            schema: test_data("crates.graphql"),
+        };
+        let error = source.load().expect_err("loaded a table that isn't in the schema");
+        assert!(error.to_string().contains("`Package` is not an edge"), "{error}");
+    }
+
+    #[test]
+    fn filesystem() {
+        let source = DataSource::Filesystem { root: test_data("") };
+        let results = run(&source, "{ Root { name @output } }", BTreeMap::new());
+        assert_eq!(vec![BTreeMap::from([(Arc::from("name"), "test_data".into())])], results);
+        assert_eq!(
+            ["Path", "Root"].into_iter().map(String::from).collect::<Vec<_>>(),
+            root_edges(source.load().unwrap().schema()).into_iter().collect::<Vec<_>>(),
+        );
+    }
+}
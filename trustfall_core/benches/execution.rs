@@ -0,0 +1,191 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use maplit::btreemap;
+use trustfall_core::{
+    frontend::parse,
+    interpreter::execution::interpret_ir,
+    ir::{FieldValue, IndexedQuery},
+    numbers_interpreter::NumbersAdapter,
+};
+
+/// A query that expands `depth` edges from each starting vertex,
+/// outputting a property of every vertex along the way.
+fn edge_chain_query(depth: usize) -> String {
+    let mut query = String::from("{ Number(max: 100) { value @output(name: \"value0\")");
+    for level in 1..=depth {
+        query.push_str(&format!(" successor {{ value @output(name: \"value{level}\")"));
+    }
+    query.push_str(&" }".repeat(depth + 2));
+    query
+}
+
+/// Parse the query ahead of time, so the benchmarks only measure its execution.
+fn prepare(adapter: &NumbersAdapter, query: &str) -> Arc<IndexedQuery> {
+    parse(adapter.schema(), query).expect("valid query")
+}
+
+fn run(
+    adapter: &Arc<NumbersAdapter>,
+    query: &Arc<IndexedQuery>,
+    arguments: &Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> usize {
+    interpret_ir(adapter.clone(), query.clone(), arguments.clone())
+        .expect("valid arguments")
+        .count()
+}
+
+/// Each expanded edge splits every context into one context per neighbor,
+/// so these benchmarks are dominated by the cost of splitting contexts
+/// and of recording the vertices they've visited.
+fn context_splitting(c: &mut Criterion) {
+    let adapter = Arc::new(NumbersAdapter::new());
+
+    let mut group = c.benchmark_group("edge_chain");
+    for depth in [1, 4, 16] {
+        let query = prepare(&adapter, &edge_chain_query(depth));
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &query, |b, query| {
+            b.iter(|| run(&adapter, black_box(query), &Default::default()))
+        });
+    }
+    group.finish();
+
+    c.bench_function("edge_fan_out", |b| {
+        let query = r#"
+{
+    Number(max: 30) {
+        value @output
+        multiple(max: 10) {
+            mult: value @output
+            divisor {
+                div: value @output
+            }
+        }
+    }
+}"#;
+        let query = prepare(&adapter, query);
+        b.iter(|| run(&adapter, black_box(&query), &Default::default()))
+    });
+}
+
+/// Folds whose elements are each a multiple of the number being folded over,
+/// so fold sizes grow with `max` while the number of results stays the same.
+fn folds(c: &mut Criterion) {
+    let adapter = Arc::new(NumbersAdapter::new());
+
+    let mut group = c.benchmark_group("fold_size");
+    for max in [1, 10, 100] {
+        let query = format!(
+            r#"
+{{
+    Number(min: 1, max: 20) {{
+        value @output
+        multiple(max: {max}) @fold {{
+            multiple: value @output
+        }}
+    }}
+}}"#
+        );
+        let query = prepare(&adapter, &query);
+        group.bench_with_input(BenchmarkId::from_parameter(max), &query, |b, query| {
+            b.iter(|| run(&adapter, black_box(query), &Default::default()))
+        });
+    }
+    group.finish();
+
+    c.bench_function("fold_count_filter", |b| {
+        let query = r#"
+{
+    Number(min: 1, max: 30) {
+        value @output
+        multiple(max: 30) @fold @transform(op: "count") @filter(op: ">", value: ["$min"]) {
+            multiple: value @output
+        }
+    }
+}"#;
+        let query = prepare(&adapter, query);
+        let arguments = Arc::new(btreemap! { Arc::from("min") => FieldValue::Int64(2) });
+        b.iter(|| run(&adapter, black_box(&query), &arguments))
+    });
+}
+
+fn recursion(c: &mut Criterion) {
+    let adapter = Arc::new(NumbersAdapter::new());
+
+    let mut group = c.benchmark_group("recursion_depth");
+    for depth in [1, 5, 25] {
+        let query = format!(
+            r#"
+{{
+    Number(max: 30) {{
+        start: value @output
+        successor @recurse(depth: {depth}) {{
+            value @output
+        }}
+    }}
+}}"#
+        );
+        let query = prepare(&adapter, &query);
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &query, |b, query| {
+            b.iter(|| run(&adapter, black_box(query), &Default::default()))
+        });
+    }
+    group.finish();
+}
+
+/// Filters of different shapes, all over the same vertices.
+fn filters(c: &mut Criterion) {
+    let adapter = Arc::new(NumbersAdapter::new());
+    let arguments = btreemap! {
+        Arc::from("min") => FieldValue::Int64(100),
+        Arc::from("values") => FieldValue::List((0..1000).step_by(7).map(FieldValue::Int64).collect()),
+        Arc::from("pattern") => FieldValue::String("^t.*e$".into()),
+    };
+
+    let mut group = c.benchmark_group("filter_shape");
+    let queries = [
+        ("none", r#"{ Number(max: 1000) { value @output } }"#),
+        (
+            "comparison",
+            r#"{ Number(max: 1000) { value @output @filter(op: ">", value: ["$min"]) } }"#,
+        ),
+        (
+            "one_of",
+            r#"{ Number(max: 1000) { value @output @filter(op: "one_of", value: ["$values"]) } }"#,
+        ),
+        (
+            "regex",
+            r#"{ Number(max: 1000) { value @output name @filter(op: "regex", value: ["$pattern"]) } }"#,
+        ),
+        (
+            "tag",
+            r#"
+{
+    Number(max: 1000) {
+        value @output @tag
+        successor {
+            next: value @output @filter(op: ">", value: ["%value"])
+        }
+    }
+}"#,
+        ),
+    ];
+    for (name, query) in queries {
+        // Each query only uses some of the arguments.
+        let query = prepare(&adapter, query);
+        let arguments: Arc<BTreeMap<_, _>> = Arc::new(
+            arguments
+                .iter()
+                .filter(|(name, _)| query.ir_query.variables.contains_key(*name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        );
+        group.bench_with_input(BenchmarkId::from_parameter(name), &query, |b, query| {
+            b.iter(|| run(&adapter, black_box(query), &arguments))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, context_splitting, folds, recursion, filters);
+criterion_main!(benches);
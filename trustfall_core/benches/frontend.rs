@@ -0,0 +1,93 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use trustfall_core::{
+    frontend::make_ir_for_query, graphql_query::parse_document, ir::IndexedQuery,
+    numbers_interpreter::NumbersAdapter,
+};
+
+const QUERIES: &[(&str, &str)] = &[
+    ("single_vertex", r#"{ Number(max: 10) { value @output } }"#),
+    (
+        "filters_and_tags",
+        r#"
+{
+    Number(max: 100) {
+        value @output @tag(name: "start") @filter(op: ">", value: ["$min"])
+        name @filter(op: "regex", value: ["$pattern"])
+
+        successor {
+            next: value @output @filter(op: ">", value: ["%start"])
+        }
+    }
+}"#,
+    ),
+    (
+        "folds_and_recursion",
+        r#"
+{
+    Number(max: 100) {
+        value @output
+        multiple(max: 10) @fold @transform(op: "count") @output(name: "multiples") {
+            multiple: value @output
+            primeFactor @fold {
+                factor: value @output
+            }
+        }
+        predecessor @recurse(depth: 3) @optional {
+            ancestor: value @output
+        }
+    }
+}"#,
+    ),
+];
+
+/// Measure each stage of turning query text into an [`IndexedQuery`] separately,
+/// since they have very different costs and are often cached independently.
+fn frontend(c: &mut Criterion) {
+    let adapter = NumbersAdapter::new();
+    let schema = adapter.schema();
+
+    let mut group = c.benchmark_group("parse_graphql");
+    for (name, query) in QUERIES {
+        group.bench_with_input(BenchmarkId::from_parameter(name), query, |b, query| {
+            b.iter(|| async_graphql_parser::parse_query(black_box(query)).expect("valid GraphQL"))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("parse_document");
+    for (name, query) in QUERIES {
+        let document = async_graphql_parser::parse_query(query).expect("valid GraphQL");
+        group.bench_with_input(BenchmarkId::from_parameter(name), &document, |b, document| {
+            b.iter(|| parse_document(black_box(document)).expect("valid query"))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("make_ir");
+    for (name, query) in QUERIES {
+        let document = async_graphql_parser::parse_query(query).expect("valid GraphQL");
+        let query = parse_document(&document).expect("valid query");
+        group.bench_with_input(BenchmarkId::from_parameter(name), &query, |b, query| {
+            b.iter(|| make_ir_for_query(schema, black_box(query)).expect("valid IR"))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("index_ir");
+    for (name, query) in QUERIES {
+        let document = async_graphql_parser::parse_query(query).expect("valid GraphQL");
+        let query = parse_document(&document).expect("valid query");
+        let ir_query = make_ir_for_query(schema, &query).expect("valid IR");
+        group.bench_with_input(BenchmarkId::from_parameter(name), &ir_query, |b, ir_query| {
+            b.iter_batched(
+                || ir_query.clone(),
+                |ir_query| IndexedQuery::try_from(black_box(ir_query)).expect("valid IR"),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, frontend);
+criterion_main!(benches);
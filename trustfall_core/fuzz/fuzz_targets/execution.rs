@@ -0,0 +1,187 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+extern crate trustfall_core;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+
+use trustfall_core::{
+    frontend::parse,
+    interpreter::execution::interpret_ir,
+    ir::{
+        EdgeParameters, Eid, FieldValue, FoldSpecificFieldKind, IRQueryComponent, IndexedQuery,
+        QueryOutputs, Type,
+    },
+    numbers_interpreter::NumbersAdapter,
+};
+
+/// Queries with larger edge parameters take too long to execute to be worth fuzzing.
+const MAX_PARAMETER_VALUE: i64 = 100;
+
+/// Recursions deeper than this take too long to execute to be worth fuzzing.
+const MAX_RECURSION_DEPTH: usize = 10;
+
+/// Only this many results of each query are checked.
+const MAX_RESULTS: usize = 1000;
+
+static ADAPTER: OnceLock<Arc<NumbersAdapter>> = OnceLock::new();
+
+fn get_adapter() -> &'static Arc<NumbersAdapter> {
+    ADAPTER.get_or_init(|| Arc::new(NumbersAdapter::new()))
+}
+
+/// Split the fuzzer's input into a query and its arguments.
+///
+/// The input is the query text, optionally followed by a NUL byte and the query's arguments
+/// in RON format. Arguments that aren't given get a default value based on their type.
+fn parse_input(data: &[u8]) -> Option<(&str, BTreeMap<Arc<str>, FieldValue>)> {
+    let input = std::str::from_utf8(data).ok()?;
+    let (query, arguments) = match input.split_once('\0') {
+        Some((query, arguments)) => {
+            (query, ron::from_str::<BTreeMap<String, FieldValue>>(arguments).ok()?)
+        }
+        None => (input, Default::default()),
+    };
+    Some((query, arguments.into_iter().map(|(name, value)| (name.into(), value)).collect()))
+}
+
+fn default_value(value_type: &Type) -> FieldValue {
+    if let Some(element_type) = value_type.as_list() {
+        return FieldValue::List((1..=3).map(|_| default_value(&element_type)).collect());
+    }
+    match value_type.base_type() {
+        "Int" => FieldValue::Int64(3),
+        "Float" => FieldValue::Float64(3.0),
+        "String" => FieldValue::String("e".into()),
+        "Boolean" => FieldValue::Boolean(true),
+        _ => FieldValue::Null,
+    }
+}
+
+fn has_cheap_parameters(parameters: &EdgeParameters) -> bool {
+    parameters
+        .iter()
+        .all(|(_, value)| value.as_i64().is_none_or(|value| value <= MAX_PARAMETER_VALUE))
+}
+
+fn is_cheap_to_execute(component: &IRQueryComponent) -> bool {
+    component.edges.values().all(|edge| {
+        has_cheap_parameters(&edge.parameters)
+            && edge.recursive.as_ref().is_none_or(|recursive| {
+                recursive.depth.is_some_and(|depth| depth.get() <= MAX_RECURSION_DEPTH)
+            })
+    }) && component
+        .folds
+        .values()
+        .all(|fold| has_cheap_parameters(&fold.parameters) && is_cheap_to_execute(&fold.component))
+}
+
+/// Append the number of elements of the fold whose values are `depth` levels deep
+/// in the given output value.
+///
+/// `@fold` outputs are lists nested one level per enclosing fold, and `@transform(op: "count")`
+/// outputs are the number of elements directly. Either is `null` if the fold is within
+/// an `@optional` that doesn't exist.
+fn collect_element_counts(value: &FieldValue, depth: usize, counts: &mut Vec<Option<u64>>) {
+    match (depth, value) {
+        (0, FieldValue::List(elements)) => counts.push(Some(elements.len() as u64)),
+        (0, FieldValue::Null) => counts.push(None),
+        (0, value) => counts.push(Some(value.as_u64().expect("count is not a valid number"))),
+        (_, FieldValue::List(elements)) => {
+            for element in elements.iter() {
+                collect_element_counts(element, depth - 1, counts);
+            }
+        }
+        (_, FieldValue::Null) => {}
+        (_, value) => unreachable!("folded output value is not a list: {value:?}"),
+    }
+}
+
+/// Check that all outputs of each fold agree on how many elements it has.
+fn check_fold_counts(
+    component: &IRQueryComponent,
+    path: &mut Vec<Eid>,
+    outputs: &QueryOutputs,
+    result: &BTreeMap<Arc<str>, FieldValue>,
+) {
+    for (eid, fold) in &component.folds {
+        path.push(*eid);
+
+        let folded_outputs = outputs
+            .iter()
+            .filter(|output| !output.fold_specific && output.folds.starts_with(path))
+            .map(|output| &output.name);
+        let count_outputs = fold
+            .fold_specific_outputs
+            .iter()
+            .filter(|(_, kind)| matches!(kind, FoldSpecificFieldKind::Count))
+            .map(|(name, _)| name);
+
+        let mut expected_counts: Option<(&Arc<str>, Vec<Option<u64>>)> = None;
+        for name in folded_outputs.chain(count_outputs) {
+            let mut counts = vec![];
+            collect_element_counts(&result[name], path.len() - 1, &mut counts);
+            match &expected_counts {
+                None => expected_counts = Some((name, counts)),
+                Some((expected_name, expected)) => assert_eq!(
+                    expected, &counts,
+                    "outputs {expected_name} and {name} disagree on the elements of {eid:?}: \
+                    {result:?}",
+                ),
+            }
+        }
+
+        check_fold_counts(&fold.component, path, outputs, result);
+        path.pop();
+    }
+}
+
+fn check_result(
+    query: &IndexedQuery,
+    outputs: &QueryOutputs,
+    result: &BTreeMap<Arc<str>, FieldValue>,
+) {
+    assert!(
+        result.keys().eq(outputs.names()),
+        "result columns don't match the query's outputs {:?}: {result:?}",
+        outputs.names().collect::<Vec<_>>(),
+    );
+    for output in outputs {
+        let value = &result[&output.name];
+        assert!(
+            output.value_type.is_valid_value(value),
+            "value {value:?} of output {} is not a valid {}",
+            output.name,
+            output.value_type,
+        );
+    }
+    check_fold_counts(&query.ir_query.root_component, &mut vec![], outputs, result);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((query, mut arguments)) = parse_input(data) else {
+        return;
+    };
+    let adapter = get_adapter();
+    let Ok(query) = parse(adapter.schema(), query) else {
+        return;
+    };
+    let ir_query = &query.ir_query;
+    if !has_cheap_parameters(&ir_query.root_parameters)
+        || !is_cheap_to_execute(&ir_query.root_component)
+    {
+        return;
+    }
+    for (name, value_type) in &ir_query.variables {
+        arguments.entry(name.clone()).or_insert_with(|| default_value(value_type));
+    }
+
+    let outputs = query.query_outputs();
+    let Ok(results) = interpret_ir(adapter.clone(), query.clone(), Arc::new(arguments)) else {
+        return;
+    };
+    for result in results.take(MAX_RESULTS) {
+        check_result(&query, &outputs, &result);
+    }
+});
@@ -0,0 +1,219 @@
+//! Machine-readable descriptions of query errors, pointing to the offending parts of the query.
+use async_graphql_parser::Pos;
+use serde::{Deserialize, Serialize};
+
+use super::error::FrontendError;
+
+/// A range of the query text, from the `start` position up to but not including `end`.
+///
+/// Line and column numbers are one-based, and columns count characters rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: Pos,
+    pub end: Pos,
+}
+
+/// A single error in a query, in a form suitable for editors and other tooling.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The name of the error variant, like `"UnsupportedEdgeFilter"`.
+    pub code: String,
+
+    /// The human-readable error message.
+    pub message: String,
+
+    /// The parts of the query responsible for the error.
+    ///
+    /// May be empty if the error cannot be attributed to any specific part of the query.
+    pub spans: Vec<Span>,
+}
+
+impl Diagnostic {
+    /// Describes the given error produced while parsing the given query text.
+    ///
+    /// Produces one diagnostic for each error contained in the given error value.
+    pub fn from_frontend_error(error: &FrontendError, query: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        collect_diagnostics(error, query, &mut diagnostics);
+        diagnostics
+    }
+}
+
+fn collect_diagnostics(error: &FrontendError, query: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if let FrontendError::MultipleErrors(errors) = error {
+        for inner in &errors.0 {
+            collect_diagnostics(inner, query, diagnostics);
+        }
+        return;
+    }
+
+    let code = match error {
+        FrontendError::ParseError(e) => variant_name(e),
+        FrontendError::FilterTypeError(e) => variant_name(e),
+        FrontendError::ValidationError(e) => variant_name(e),
+        _ => variant_name(error),
+    };
+
+    diagnostics.push(Diagnostic {
+        code,
+        message: error.to_string(),
+        spans: error.positions().into_iter().map(|pos| token_span(query, pos)).collect(),
+    });
+}
+
+/// The name of an error's enum variant, as it appears in its derived `Debug` representation.
+fn variant_name(error: &impl std::fmt::Debug) -> String {
+    let debug = format!("{error:?}");
+    debug.split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or_default().to_string()
+}
+
+/// The span of the token starting at the given position: a directive like `@filter`,
+/// a variable or tag reference, or a name. Any other token is assumed to be a single character.
+fn token_span(query: &str, start: Pos) -> Span {
+    let Some(line) = query.split('\n').nth(start.line.saturating_sub(1)) else {
+        return Span { start, end: start };
+    };
+    let mut chars = line.chars().skip(start.column.saturating_sub(1)).peekable();
+
+    let mut length = 0;
+    if chars.next_if(|c| matches!(c, '@' | '$' | '%')).is_some() {
+        length += 1;
+    }
+    while chars.next_if(|c| c.is_alphanumeric() || *c == '_').is_some() {
+        length += 1;
+    }
+    if length == 0 && chars.peek().is_some() {
+        length = 1;
+    }
+
+    Span { start, end: Pos { line: start.line, column: start.column + length } }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::OnceLock,
+    };
+
+    use async_graphql_parser::Pos;
+    use trustfall_filetests_macros::parameterize;
+
+    use crate::{frontend::parse, schema::Schema, test_types::TestGraphQLQuery};
+
+    use super::{Diagnostic, Span};
+
+    static SCHEMAS: OnceLock<Vec<(&'static str, Schema)>> = OnceLock::new();
+
+    fn get_schema(name: &str) -> &'static Schema {
+        let schemas = SCHEMAS.get_or_init(|| {
+            ["filesystem", "numbers", "nullables", "recurses"]
+                .into_iter()
+                .map(|name| {
+                    let path = format!("test_data/schemas/{name}.graphql");
+                    (name, Schema::parse(fs::read_to_string(path).unwrap()).unwrap())
+                })
+                .collect()
+        });
+        &schemas.iter().find(|(schema_name, _)| *schema_name == name).unwrap().1
+    }
+
+    fn diagnose(schema_name: &str, query: &str) -> Vec<Diagnostic> {
+        let error = parse(get_schema(schema_name), query).expect_err("query was valid");
+        Diagnostic::from_frontend_error(&error, query)
+    }
+
+    fn span(line: usize, start_column: usize, end_column: usize) -> Span {
+        Span { start: Pos { line, column: start_column }, end: Pos { line, column: end_column } }
+    }
+
+    #[test]
+    fn parse_error_span() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output @nonexistent
+    }
+}"#;
+        let diagnostics = diagnose("numbers", query);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("UnrecognizedDirective", diagnostics[0].code);
+        assert_eq!(vec![span(4, 23, 35)], diagnostics[0].spans);
+    }
+
+    #[test]
+    fn edge_filter_span() {
+        let query = r#"
+{
+    Number(max: 10) {
+        successor @filter(op: "is_not_null") {
+            value @output
+        }
+    }
+}"#;
+        let diagnostics = diagnose("numbers", query);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("FilterOnNonOptionalEdge", diagnostics[0].code);
+        assert_eq!(vec![span(4, 9, 18)], diagnostics[0].spans);
+    }
+
+    #[test]
+    fn multiple_errors_make_multiple_diagnostics() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output @fold
+        name @output @recurse(depth: 2)
+    }
+}"#;
+        let diagnostics = diagnose("numbers", query);
+        let described: Vec<_> =
+            diagnostics.iter().map(|d| (d.code.as_str(), d.spans.clone())).collect();
+        assert_eq!(
+            vec![
+                ("UnsupportedDirectiveOnProperty", vec![span(4, 9, 14)]),
+                ("UnsupportedDirectiveOnProperty", vec![span(5, 9, 13)]),
+            ],
+            described,
+        );
+    }
+
+    #[test]
+    fn nonexistent_path_span() {
+        let query = r#"
+{
+    Number(max: 10) {
+        nonexistent @output
+    }
+}"#;
+        let diagnostics = diagnose("numbers", query);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("NonExistentPath", diagnostics[0].code);
+        assert_eq!(vec![span(4, 9, 20)], diagnostics[0].spans);
+    }
+
+    fn check_all_diagnostics_have_spans(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.graphql.ron"));
+
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestGraphQLQuery = ron::from_str(&input_data).unwrap();
+
+        for diagnostic in diagnose(&test_query.schema_name, &test_query.query) {
+            if diagnostic.code != "OtherError" {
+                assert!(!diagnostic.spans.is_empty(), "no spans for {diagnostic:#?}");
+            }
+        }
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/frontend_errors")]
+    fn frontend_errors(base: &Path, stem: &str) {
+        check_all_diagnostics_have_spans(base, stem)
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/parse_errors")]
+    fn parse_errors(base: &Path, stem: &str) {
+        check_all_diagnostics_have_spans(base, stem)
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use async_graphql_parser::Pos;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -17,76 +18,199 @@ pub enum FrontendError {
     ParseError(#[from] crate::graphql_query::error::ParseError),
 
     #[error("Filter on property name \"{0}\" uses undefined tag: %{1}")]
-    UndefinedTagInFilter(String, String),
+    UndefinedTagInFilter(String, String, Pos),
+
+    #[error("Transform on property name \"{0}\" uses undefined tag: %{1}")]
+    UndefinedTagInTransform(String, String, Pos),
 
     #[error(
-        "Filter on property name \"{0}\" uses tag \"{1}\" which is not yet defined at that point \
-        in the query. Please reorder the query components so that the @tag directive \
+        "Filter or transform on property name \"{0}\" uses tag \"{1}\" which is not yet defined \
+        at that point in the query. Please reorder the query components so that the @tag directive \
         comes before all uses of its tagged value."
     )]
-    TagUsedBeforeDefinition(String, String),
+    TagUsedBeforeDefinition(String, String, Pos),
 
     #[error(
-        "Tag \"{1}\" is defined within a @fold but is used outside that @fold in a filter on \
-        property name \"{0}\". This is not supported; if possible, please consider reorganizing \
-        the query so that the tagged values are captured outside the @fold and \
-        their use in @filter moves inside the @fold."
+        "Tag \"{1}\" is defined within a @fold but is used outside that @fold in a filter or \
+        transform on property name \"{0}\". This is not supported; if possible, please consider \
+        reorganizing the query so that the tagged values are captured outside the @fold and \
+        their uses move inside the @fold."
     )]
-    TagUsedOutsideItsFoldedSubquery(String, String),
+    TagUsedOutsideItsFoldedSubquery(String, String, Pos),
 
     #[error(
         "One or more tags were defined in the query but were never used. Please remove these \
         unused @tag directives. Unused tag names: {0:?}"
     )]
-    UnusedTags(Vec<String>),
+    UnusedTags(Vec<String>, Vec<Pos>),
 
     #[error("Multiple fields are being output under the same name: {0:?}")]
-    MultipleOutputsWithSameName(DuplicatedNamesConflict),
+    MultipleOutputsWithSameName(DuplicatedNamesConflict, Vec<Pos>),
 
     #[error("Multiple fields have @tag directives with the same name: {0}")]
-    MultipleTagsWithSameName(String),
+    MultipleTagsWithSameName(String, Pos),
+
+    #[error(
+        "Tag \"{0}\" is used in a different root field than the one that defines it, \
+        but it is defined within an @optional, @fold, or @absent edge. Only tags whose values \
+        are always present can be used across root fields."
+    )]
+    UnsupportedJoinTag(String, Pos),
+
+    #[error(
+        "Tag \"{0}\" of type {1} is used in a different root field, \
+        where a value of incompatible type {2} is required."
+    )]
+    IncompatibleJoinTagType(String, String, String, Pos),
 
     #[error(
         "Tagged fields with an applied @transform must explicitly specify the tag name, like this: \
         @tag(name: \"some_name\"). Affected field: {0}"
     )]
-    ExplicitTagNameRequired(String),
+    ExplicitTagNameRequired(String, Pos),
 
     #[error("Incompatible types encountered in @filter: {0}")]
     FilterTypeError(#[from] FilterTypeError),
 
     #[error("Found {0} applied to \"{1}\" property, which is not supported since that directive can only be applied to edges.")]
-    UnsupportedDirectiveOnProperty(String, String),
+    UnsupportedDirectiveOnProperty(String, String, Pos),
 
     #[error("Found an edge with an @output directive, this is not supported: {0}")]
-    UnsupportedEdgeOutput(String),
+    UnsupportedEdgeOutput(String, Pos),
 
     #[error("Found an edge with an unsupported @filter directive: {0}")]
-    UnsupportedEdgeFilter(String),
+    UnsupportedEdgeFilter(String, Pos),
+
+    #[error(
+        "Found an edge with a @filter directive using the \"{1}\" operator: {0}. \
+        Edges only support the \"is_null\" and \"is_not_null\" filter operators, \
+        which check whether the edge exists."
+    )]
+    UnsupportedEdgeFilterOperator(String, String, Pos),
+
+    #[error(
+        "Found a @filter directive on edge \"{0}\" which is not @optional. The existence of \
+        non-@optional edges is already required, so filtering on it is not meaningful. \
+        Please mark the edge @optional, or remove the @filter directive."
+    )]
+    FilterOnNonOptionalEdge(String, Pos),
+
+    #[error(
+        "Found a @tag directive on edge {0}, which is not folded. Only edges with @fold \
+        support @tag, which tags the number of folded vertices."
+    )]
+    UnsupportedEdgeTag(String, Pos),
+
+    #[error("Found an edge with an unsupported @order directive: {0}")]
+    UnsupportedEdgeOrder(String, Pos),
+
+    #[error(
+        "Property \"{0}\" has an @order directive but is not output exactly once. \
+        Results can only be ordered by a property that has exactly one @output directive."
+    )]
+    OrderWithoutSingleOutput(String, Pos),
+
+    #[error(
+        "Property \"{0}\" has an @order directive but is inside a @fold, which is not supported. \
+        Results can only be ordered by properties that are not within any @fold."
+    )]
+    OrderInsideFold(String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on property \"{1}\", which is not within a @fold. \
+        This transform aggregates the property's values across the elements of a @fold, \
+        so it can only be applied to properties inside a @fold."
+    )]
+    AggregationOutsideFold(String, String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on property \"{1}\" of type {2}. This transform \
+        can only be applied to properties of type Int or Float."
+    )]
+    AggregationOfNonNumericProperty(String, String, String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on folded edge \"{1}\". This transform applies to \
+        property values, so it must be applied to a property inside the @fold instead."
+    )]
+    PropertyTransformOnFoldedEdge(String, String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on property \"{1}\", whose value has type {2} \
+        which is not supported by this transform."
+    )]
+    UnsupportedTransformInputType(String, String, String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on property \"{1}\" with operand \"{2}\" of type {3}, \
+        which is not supported by this transform. Its operand must be of type Int or Float."
+    )]
+    UnsupportedTransformOperandType(String, String, String, String, Pos),
+
+    #[error(
+        "Found @transform(op: \"{0}\") on property \"{1}\". This transform can only be applied \
+        to edges with @fold."
+    )]
+    UnsupportedTransformOnProperty(String, String, Pos),
 
-    #[error("Found an edge with an unsupported @tag directive: {0}")]
-    UnsupportedEdgeTag(String),
+    #[error(
+        "Found a @transform(op: \"{0}\") directive applied to an already-aggregated value of \
+        field \"{1}\". Aggregated values cannot be aggregated again."
+    )]
+    TransformOfAggregatedValue(String, String, Pos),
 
     #[error("Found an unsupported {1} directive on an edge with @fold: {0}")]
-    UnsupportedDirectiveOnFoldedEdge(String, String),
+    UnsupportedDirectiveOnFoldedEdge(String, String, Pos),
+
+    #[error("Found an unsupported {1} directive on an edge with @absent: {0}")]
+    UnsupportedDirectiveOnAbsentEdge(String, String, Pos),
+
+    #[error(
+        "Found an @output directive within edge \"{0}\" which has @absent. \
+        Vertices matched by an @absent edge are never part of the query's results, \
+        so they cannot be output."
+    )]
+    OutputWithinAbsentEdge(String, Pos),
 
     #[error("Missing required edge parameter \"{0}\" on edge {1}")]
-    MissingRequiredEdgeParameter(String, String),
+    MissingRequiredEdgeParameter(String, String, Pos),
 
     #[error("Unexpected edge parameter \"{0}\" on edge {1}")]
-    UnexpectedEdgeParameter(String, String),
+    UnexpectedEdgeParameter(String, String, Pos),
 
     #[error(
         "Invalid value for edge parameter \"{0}\" on edge {1}. \
         Expected a value of type {2}, but got: {3:?}"
     )]
-    InvalidEdgeParameterType(String, String, String, FieldValue),
+    InvalidEdgeParameterType(String, String, String, FieldValue, Pos),
+
+    #[error("Edge parameter \"{0}\" on edge {1} uses undefined tag: %{2}")]
+    UndefinedTagInEdgeParameter(String, String, String, Pos),
+
+    #[error(
+        "Edge parameter \"{0}\" on edge {1} uses tag \"{2}\", which is not defined before \
+        that edge in the query. Tags used as edge parameters must be defined earlier in the query, \
+        outside of that edge and outside of any @fold that does not also contain that edge."
+    )]
+    EdgeParameterTagNotYetDefined(String, String, String, Pos),
+
+    #[error(
+        "Edge parameter \"{0}\" on edge {1} has type {2}, but uses tag \"{3}\" \
+        of incompatible type {4}."
+    )]
+    InvalidEdgeParameterTagType(String, String, String, String, String, Pos),
+
+    #[error(
+        "Edge parameter \"{0}\" on edge {1} uses tag \"{2}\", but edges with @recurse \
+        do not support tagged values as parameters."
+    )]
+    UnsupportedEdgeParameterTagOnRecurse(String, String, String, Pos),
 
     #[error(
         "Invalid use of @recurse on edge \"{0}\". That edge cannot be recursed since it connects \
         two unrelated vertex types: {1} {2}"
     )]
-    RecursingNonRecursableEdge(String, String, String),
+    RecursingNonRecursableEdge(String, String, String, Pos),
 
     #[error(
         "Invalid use of @recurse on edge \"{0}\" in its current location. \
@@ -95,22 +219,44 @@ pub enum FrontendError {
         since the starting vertex might not match that type. To ensure the starting vertex matches \
         the edge's destination type, you could use a type coercion like: ... on {2}"
     )]
-    RecursionToSubtype(String, String, String),
+    RecursionToSubtype(String, String, String, Pos),
 
     // This error type may or may not be reachable in practice.
     // At the time of writing, schemas containing fields with ambiguous origin are disallowed,
     // though they may be allowed in the future. If they are allowed, then this error is reachable.
     #[error("Edge {0} has an ambiguous origin, and cannot be used for recursion.")]
-    AmbiguousOriginEdgeRecursion(String),
+    AmbiguousOriginEdgeRecursion(String, Pos),
 
     #[error(
         "Edge \"{0}\" is used for recursion that requires multiple implicit coercions, \
         which is currently not supported."
     )]
-    EdgeRecursionNeedingMultipleCoercions(String),
+    EdgeRecursionNeedingMultipleCoercions(String, Pos),
+
+    #[error(
+        "The edges in @optional(group: \"{0}\") do not all start from the same vertex. \
+        Only sibling edges can be in the same @optional group."
+    )]
+    OptionalGroupEdgesNotSiblings(String, Pos),
+
+    #[error(
+        "Edge \"{1}\" is in @optional(group: \"{0}\") but also uses @recurse, \
+        which is not supported. Recursion always produces at least its starting vertex, \
+        so the edge always exists."
+    )]
+    RecursiveEdgeInOptionalGroup(String, String, Pos),
 
     #[error("Meta field \"{0}\" is a property but the query uses it as an edge.")]
-    PropertyMetaFieldUsedAsEdge(String),
+    PropertyMetaFieldUsedAsEdge(String, Pos),
+
+    #[error("Variable ${0} is defined with a default value, but is not used in the query.")]
+    UnusedVariableDefinition(String, Pos),
+
+    #[error(
+        "Invalid default value for variable ${0}. \
+        Expected a value of type {1}, but got: {2:?}"
+    )]
+    InvalidVariableDefaultValue(String, String, FieldValue, Pos),
 
     #[error("The query failed to validate against the schema: {0}")]
     ValidationError(#[from] ValidationError),
@@ -119,6 +265,68 @@ pub enum FrontendError {
     OtherError(String),
 }
 
+impl FrontendError {
+    /// The positions in the query text of the parts of the query responsible for the error.
+    ///
+    /// Empty if the error cannot be attributed to any specific part of the query.
+    pub fn positions(&self) -> Vec<Pos> {
+        match self {
+            FrontendError::MultipleErrors(errors) => {
+                errors.0.iter().flat_map(FrontendError::positions).collect()
+            }
+            FrontendError::ParseError(e) => e.position().into_iter().collect(),
+            FrontendError::FilterTypeError(e) => e.positions(),
+            FrontendError::ValidationError(e) => vec![e.position()],
+            FrontendError::OtherError(_) => vec![],
+            FrontendError::UnusedTags(_, positions)
+            | FrontendError::MultipleOutputsWithSameName(_, positions) => positions.clone(),
+            FrontendError::UndefinedTagInFilter(_, _, pos)
+            | FrontendError::UndefinedTagInTransform(_, _, pos)
+            | FrontendError::TagUsedBeforeDefinition(_, _, pos)
+            | FrontendError::TagUsedOutsideItsFoldedSubquery(_, _, pos)
+            | FrontendError::MultipleTagsWithSameName(_, pos)
+            | FrontendError::UnsupportedJoinTag(_, pos)
+            | FrontendError::IncompatibleJoinTagType(_, _, _, pos)
+            | FrontendError::ExplicitTagNameRequired(_, pos)
+            | FrontendError::UnsupportedDirectiveOnProperty(_, _, pos)
+            | FrontendError::UnsupportedEdgeOutput(_, pos)
+            | FrontendError::UnsupportedEdgeFilter(_, pos)
+            | FrontendError::UnsupportedEdgeFilterOperator(_, _, pos)
+            | FrontendError::FilterOnNonOptionalEdge(_, pos)
+            | FrontendError::UnsupportedEdgeTag(_, pos)
+            | FrontendError::UnsupportedEdgeOrder(_, pos)
+            | FrontendError::OrderWithoutSingleOutput(_, pos)
+            | FrontendError::OrderInsideFold(_, pos)
+            | FrontendError::AggregationOutsideFold(_, _, pos)
+            | FrontendError::AggregationOfNonNumericProperty(_, _, _, pos)
+            | FrontendError::PropertyTransformOnFoldedEdge(_, _, pos)
+            | FrontendError::UnsupportedTransformInputType(_, _, _, pos)
+            | FrontendError::UnsupportedTransformOperandType(_, _, _, _, pos)
+            | FrontendError::UnsupportedTransformOnProperty(_, _, pos)
+            | FrontendError::TransformOfAggregatedValue(_, _, pos)
+            | FrontendError::UnsupportedDirectiveOnFoldedEdge(_, _, pos)
+            | FrontendError::UnsupportedDirectiveOnAbsentEdge(_, _, pos)
+            | FrontendError::OutputWithinAbsentEdge(_, pos)
+            | FrontendError::MissingRequiredEdgeParameter(_, _, pos)
+            | FrontendError::UnexpectedEdgeParameter(_, _, pos)
+            | FrontendError::InvalidEdgeParameterType(_, _, _, _, pos)
+            | FrontendError::UndefinedTagInEdgeParameter(_, _, _, pos)
+            | FrontendError::EdgeParameterTagNotYetDefined(_, _, _, pos)
+            | FrontendError::InvalidEdgeParameterTagType(_, _, _, _, _, pos)
+            | FrontendError::UnsupportedEdgeParameterTagOnRecurse(_, _, _, pos)
+            | FrontendError::RecursingNonRecursableEdge(_, _, _, pos)
+            | FrontendError::RecursionToSubtype(_, _, _, pos)
+            | FrontendError::AmbiguousOriginEdgeRecursion(_, pos)
+            | FrontendError::EdgeRecursionNeedingMultipleCoercions(_, pos)
+            | FrontendError::OptionalGroupEdgesNotSiblings(_, pos)
+            | FrontendError::RecursiveEdgeInOptionalGroup(_, _, pos)
+            | FrontendError::PropertyMetaFieldUsedAsEdge(_, pos)
+            | FrontendError::UnusedVariableDefinition(_, pos)
+            | FrontendError::InvalidVariableDefaultValue(_, _, _, pos) => vec![*pos],
+        }
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, thiserror::Error)]
 pub enum FilterTypeError {
@@ -127,57 +335,74 @@ pub enum FilterTypeError {
         incompatible types \"{1}\" and \"{2}\". Please split up the uses that require different \
         types into separate variables."
     )]
-    IncompatibleVariableTypeRequirements(String, String, String),
+    IncompatibleVariableTypeRequirements(String, String, String, Vec<Pos>),
 
     #[error(
         "Filter operation \"{0}\" is applied on non-nullable {1}. \
         The filter's result would always be {2}. Please rewrite the query to avoid this filter."
     )]
-    NonNullableTypeFilteredForNullability(String, String, bool),
+    NonNullableTypeFilteredForNullability(String, String, bool, Pos),
 
     #[error(
         "Filter operation \"{0}\" is comparing values with incompatible type: {1} versus {2}."
     )]
-    TypeMismatchBetweenFilterSubjectAndArgument(String, String, String),
+    TypeMismatchBetweenFilterSubjectAndArgument(String, String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" can only be applied to orderable values, but is applied to {1} \
         which does not support ordering comparisons."
     )]
-    OrderingFilterOperationOnNonOrderableSubject(String, String),
+    OrderingFilterOperationOnNonOrderableSubject(String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" requires an argument that supports ordering comparisons, \
         but is being used with non-orderable {1}."
     )]
-    OrderingFilterOperationWithNonOrderableArgument(String, String),
+    OrderingFilterOperationWithNonOrderableArgument(String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" can only be applied to string values, but is applied to {1} \
         which is not a string."
     )]
-    StringFilterOperationOnNonStringSubject(String, String),
+    StringFilterOperationOnNonStringSubject(String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" requires an argument of string type, but is being used \
         with non-string {1}."
     )]
-    StringFilterOperationOnNonStringArgument(String, String),
+    StringFilterOperationOnNonStringArgument(String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" can only be applied to list values, but is applied to {1} \
         which is not a list."
     )]
-    ListFilterOperationOnNonListSubject(String, String),
+    ListFilterOperationOnNonListSubject(String, String, Pos),
 
     #[error(
         "Filter operation \"{0}\" requires an argument of list type, but is being used \
         with non-list {1}."
     )]
-    ListFilterOperationOnNonListArgument(String, String),
+    ListFilterOperationOnNonListArgument(String, String, Pos),
 }
 
 impl FilterTypeError {
+    /// The positions in the query text of the filters responsible for the error.
+    pub fn positions(&self) -> Vec<Pos> {
+        match self {
+            FilterTypeError::IncompatibleVariableTypeRequirements(_, _, _, positions) => {
+                positions.clone()
+            }
+            FilterTypeError::NonNullableTypeFilteredForNullability(_, _, _, pos)
+            | FilterTypeError::TypeMismatchBetweenFilterSubjectAndArgument(_, _, _, pos)
+            | FilterTypeError::OrderingFilterOperationOnNonOrderableSubject(_, _, pos)
+            | FilterTypeError::OrderingFilterOperationWithNonOrderableArgument(_, _, pos)
+            | FilterTypeError::StringFilterOperationOnNonStringSubject(_, _, pos)
+            | FilterTypeError::StringFilterOperationOnNonStringArgument(_, _, pos)
+            | FilterTypeError::ListFilterOperationOnNonListSubject(_, _, pos)
+            | FilterTypeError::ListFilterOperationOnNonListArgument(_, _, pos) => vec![*pos],
+        }
+    }
+
     fn represent_property_and_type(property_name: &str, property_type: &Type) -> String {
         format!("property \"{property_name}\" of type \"{property_type}\"")
     }
@@ -191,11 +416,13 @@ impl FilterTypeError {
         property_name: &str,
         property_type: &Type,
         filter_outcome: bool,
+        pos: Pos,
     ) -> Self {
         Self::NonNullableTypeFilteredForNullability(
             filter_operator.to_string(),
             Self::represent_property_and_type(property_name, property_type),
             filter_outcome,
+            pos,
         )
     }
 
@@ -205,11 +432,13 @@ impl FilterTypeError {
         property_type: &Type,
         tag_name: &str,
         tag_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::TypeMismatchBetweenFilterSubjectAndArgument(
             filter_operator.to_string(),
             Self::represent_property_and_type(property_name, property_type),
             Self::represent_tag_name_and_type(tag_name, tag_type),
+            pos,
         )
     }
 
@@ -217,10 +446,12 @@ impl FilterTypeError {
         filter_operator: &str,
         property_name: &str,
         property_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::OrderingFilterOperationOnNonOrderableSubject(
             filter_operator.to_string(),
             Self::represent_property_and_type(property_name, property_type),
+            pos,
         )
     }
 
@@ -228,10 +459,12 @@ impl FilterTypeError {
         filter_operator: &str,
         tag_name: &str,
         tag_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::OrderingFilterOperationWithNonOrderableArgument(
             filter_operator.to_string(),
             Self::represent_tag_name_and_type(tag_name, tag_type),
+            pos,
         )
     }
 
@@ -239,10 +472,12 @@ impl FilterTypeError {
         filter_operator: &str,
         property_name: &str,
         property_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::StringFilterOperationOnNonStringSubject(
             filter_operator.to_string(),
             Self::represent_property_and_type(property_name, property_type),
+            pos,
         )
     }
 
@@ -250,10 +485,12 @@ impl FilterTypeError {
         filter_operator: &str,
         tag_name: &str,
         tag_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::StringFilterOperationOnNonStringArgument(
             filter_operator.to_string(),
             Self::represent_tag_name_and_type(tag_name, tag_type),
+            pos,
         )
     }
 
@@ -261,10 +498,12 @@ impl FilterTypeError {
         filter_operator: &str,
         property_name: &str,
         property_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::ListFilterOperationOnNonListSubject(
             filter_operator.to_string(),
             Self::represent_property_and_type(property_name, property_type),
+            pos,
         )
     }
 
@@ -272,10 +511,12 @@ impl FilterTypeError {
         filter_operator: &str,
         tag_name: &str,
         tag_type: &Type,
+        pos: Pos,
     ) -> Self {
         Self::ListFilterOperationOnNonListArgument(
             filter_operator.to_string(),
             Self::represent_tag_name_and_type(tag_name, tag_type),
+            pos,
         )
     }
 }
@@ -283,30 +524,41 @@ impl FilterTypeError {
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct DuplicatedNamesConflict {
     // duplicate output name -> vec (type name, field name) being output under that name
-    // TODO: it may be better to replace the type name with the edge used to get to the type,
-    //       and ideally also add span / Pos info pointing to the specific thing that's the problem.
+    // TODO: it may be better to replace the type name with the edge used to get to the type.
     pub duplicates: BTreeMap<String, Vec<(String, String)>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, thiserror::Error)]
 pub enum ValidationError {
     #[error("The referenced path does not exist in the schema: {0:?}")]
-    NonExistentPath(Vec<String>),
+    NonExistentPath(Vec<String>, Pos),
 
     #[error("The referenced type does not exist in the schema: {0}")]
-    NonExistentType(String),
+    NonExistentType(String, Pos),
 
     #[error(
-        "Attempted to coerce type {0} into type {1}, but type {0} is not an interface. \
-        Only interface types may be coerced to subtypes."
+        "Attempted to coerce type {0} into type {1}, but type {0} is neither an interface \
+        nor a union. Only interface and union types may be coerced to subtypes."
     )]
-    CannotCoerceNonInterfaceType(String, String),
+    CannotCoerceNonInterfaceType(String, String, Pos),
 
     #[error(
         "Attempted to coerce type {0} into type {1}, which is not a subtype of {0}. \
         This is not allowed."
     )]
-    CannotCoerceToUnrelatedType(String, String),
+    CannotCoerceToUnrelatedType(String, String, Pos),
+}
+
+impl ValidationError {
+    /// The position in the query text of the field or type coercion responsible for the error.
+    pub fn position(&self) -> Pos {
+        match self {
+            ValidationError::NonExistentPath(_, pos)
+            | ValidationError::NonExistentType(_, pos)
+            | ValidationError::CannotCoerceNonInterfaceType(_, _, pos)
+            | ValidationError::CannotCoerceToUnrelatedType(_, _, pos) => *pos,
+        }
+    }
 }
 
 impl From<async_graphql_parser::Error> for FrontendError {
@@ -1,3 +1,5 @@
+use async_graphql_parser::Pos;
+
 use crate::{
     graphql_query::directives::{FilterDirective, OperatorArgument},
     ir::{Argument, NamedTypedValue, Operation, Type, VariableRef, Vid},
@@ -18,6 +20,7 @@ pub(super) fn make_filter_expr<LeftT: NamedTypedValue>(
     current_vertex_vid: Vid,
     left_operand: LeftT,
     filter_directive: &FilterDirective,
+    pos: Pos,
 ) -> Result<Operation<LeftT, Argument>, Vec<FrontendError>> {
     let filter_operation = filter_directive
         .operation
@@ -31,6 +34,7 @@ pub(super) fn make_filter_expr<LeftT: NamedTypedValue>(
                             left_operand.named(),
                             left_operand.typed().clone(),
                             &filter_directive.operation,
+                            pos,
                         )
                         .map_err(|e| *e)?,
                     }),
@@ -45,18 +49,21 @@ pub(super) fn make_filter_expr<LeftT: NamedTypedValue>(
                                 return Err(FrontendError::UndefinedTagInFilter(
                                     left_operand.named().to_string(),
                                     tag_name,
+                                    pos,
                                 ));
                             }
                             Err(TagLookupError::TagDefinedInsideFold(tag_name)) => {
                                 return Err(FrontendError::TagUsedOutsideItsFoldedSubquery(
                                     left_operand.named().to_string(),
                                     tag_name,
+                                    pos,
                                 ));
                             }
                             Err(TagLookupError::TagUsedBeforeDefinition(tag_name)) => {
                                 return Err(FrontendError::TagUsedBeforeDefinition(
                                     left_operand.named().to_string(),
                                     tag_name,
+                                    pos,
                                 ))
                             }
                         };
@@ -68,14 +75,18 @@ pub(super) fn make_filter_expr<LeftT: NamedTypedValue>(
         )
         .map_err(|e| vec![e])?;
 
-    // Get the tag name, if one was used.
-    // The tag name is used to improve the diagnostics raised in case of bad query input.
-    let maybe_tag_name = match filter_directive.operation.right() {
-        Some(OperatorArgument::TagRef(tag_name)) => Some(tag_name.as_ref()),
-        _ => None,
-    };
-
-    if let Err(e) = operand_types_valid(&filter_operation, maybe_tag_name) {
+    // Get the tag names, if any were used, in the same order as the operation's arguments.
+    // The tag names are used to improve the diagnostics raised in case of bad query input.
+    let tag_names: Vec<Option<&str>> = filter_directive
+        .operation
+        .arguments()
+        .map(|argument| match argument {
+            OperatorArgument::TagRef(tag_name) => Some(tag_name.as_ref()),
+            _ => None,
+        })
+        .collect();
+
+    if let Err(e) = operand_types_valid(&filter_operation, &tag_names, pos) {
         Err(e.into_iter().map(|x| x.into()).collect())
     } else {
         Ok(filter_operation)
@@ -86,6 +97,7 @@ fn infer_variable_type(
     property_name: &str,
     property_type: Type,
     operation: &Operation<(), OperatorArgument>,
+    pos: Pos,
 ) -> Result<Type, Box<FilterTypeError>> {
     match operation {
         Operation::Equals(..) | Operation::NotEquals(..) => {
@@ -97,7 +109,9 @@ fn infer_variable_type(
         Operation::LessThan(..)
         | Operation::LessThanOrEqual(..)
         | Operation::GreaterThan(..)
-        | Operation::GreaterThanOrEqual(..) => {
+        | Operation::GreaterThanOrEqual(..)
+        | Operation::Between(..)
+        | Operation::NotBetween(..) => {
             // The null value isn't orderable relative to non-null values of its type.
             // Use a type that is structurally the same but non-null at the top level.
             //
@@ -117,6 +131,7 @@ fn infer_variable_type(
                     operation.operation_name(),
                     property_name,
                     &property_type,
+                    pos,
                 )));
             };
 
@@ -137,7 +152,17 @@ fn infer_variable_type(
         | Operation::HasSubstring(..)
         | Operation::NotHasSubstring(..)
         | Operation::RegexMatches(..)
-        | Operation::NotRegexMatches(..) => {
+        | Operation::NotRegexMatches(..)
+        | Operation::EqualsIgnoreCase(..)
+        | Operation::NotEqualsIgnoreCase(..)
+        | Operation::HasPrefixIgnoreCase(..)
+        | Operation::NotHasPrefixIgnoreCase(..)
+        | Operation::HasSuffixIgnoreCase(..)
+        | Operation::NotHasSuffixIgnoreCase(..)
+        | Operation::HasSubstringIgnoreCase(..)
+        | Operation::NotHasSubstringIgnoreCase(..)
+        | Operation::RegexMatchesIgnoreCase(..)
+        | Operation::NotRegexMatchesIgnoreCase(..) => {
             // Filtering operations involving strings only take non-nullable strings as inputs.
             Ok(Type::new_named_type("String", false))
         }
@@ -152,12 +177,10 @@ fn infer_variable_type(
 
 fn operand_types_valid<LeftT: NamedTypedValue>(
     operation: &Operation<LeftT, Argument>,
-    tag_name: Option<&str>,
+    tag_names: &[Option<&str>],
+    pos: Pos,
 ) -> Result<(), Vec<FilterTypeError>> {
-    let left = operation.left();
-    let right = operation.right();
-    let left_type = left.typed();
-    let right_type = right.map(|x| x.typed());
+    let tag_name = tag_names.first().copied().flatten();
 
     // Check the left and right operands match the operator's needs individually.
     // For example:
@@ -171,22 +194,25 @@ fn operand_types_valid<LeftT: NamedTypedValue>(
     //   (maybe non-nullable) list of a maybe-nullable version of the right-hand type.
     match operation {
         Operation::IsNull(_) | Operation::IsNotNull(_) => {
-            validity::nullability_types_valid(operation, tag_name)
+            validity::nullability_types_valid(operation, tag_name, pos)
         }
         Operation::Equals(_, _) | Operation::NotEquals(_, _) => {
-            validity::equality_types_valid(operation, tag_name)
+            validity::equality_types_valid(operation, tag_name, pos)
         }
         Operation::LessThan(_, _)
         | Operation::LessThanOrEqual(_, _)
         | Operation::GreaterThan(_, _)
         | Operation::GreaterThanOrEqual(_, _) => {
-            validity::ordering_types_valid(operation, tag_name)
+            validity::ordering_types_valid(operation, tag_name, pos)
+        }
+        Operation::Between(..) | Operation::NotBetween(..) => {
+            validity::range_types_valid(operation, tag_names, pos)
         }
         Operation::Contains(_, _) | Operation::NotContains(_, _) => {
-            validity::list_containment_types_valid(operation, tag_name)
+            validity::list_containment_types_valid(operation, tag_name, pos)
         }
         Operation::OneOf(_, _) | Operation::NotOneOf(_, _) => {
-            validity::bulk_equality_types_valid(operation, tag_name)
+            validity::bulk_equality_types_valid(operation, tag_name, pos)
         }
         Operation::HasPrefix(_, _)
         | Operation::NotHasPrefix(_, _)
@@ -195,13 +221,25 @@ fn operand_types_valid<LeftT: NamedTypedValue>(
         | Operation::HasSubstring(_, _)
         | Operation::NotHasSubstring(_, _)
         | Operation::RegexMatches(_, _)
-        | Operation::NotRegexMatches(_, _) => {
-            validity::string_operation_types_valid(operation, tag_name)
+        | Operation::NotRegexMatches(_, _)
+        | Operation::EqualsIgnoreCase(_, _)
+        | Operation::NotEqualsIgnoreCase(_, _)
+        | Operation::HasPrefixIgnoreCase(_, _)
+        | Operation::NotHasPrefixIgnoreCase(_, _)
+        | Operation::HasSuffixIgnoreCase(_, _)
+        | Operation::NotHasSuffixIgnoreCase(_, _)
+        | Operation::HasSubstringIgnoreCase(_, _)
+        | Operation::NotHasSubstringIgnoreCase(_, _)
+        | Operation::RegexMatchesIgnoreCase(_, _)
+        | Operation::NotRegexMatchesIgnoreCase(_, _) => {
+            validity::string_operation_types_valid(operation, tag_name, pos)
         }
     }
 }
 
 mod validity {
+    use async_graphql_parser::Pos;
+
     use crate::{
         frontend::error::FilterTypeError,
         ir::{Argument, NamedTypedValue, Operation},
@@ -210,6 +248,7 @@ mod validity {
     pub(super) fn nullability_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
         let left = operation.left();
         let left_type = left.typed();
@@ -223,6 +262,7 @@ mod validity {
                 left.named(),
                 left_type,
                 matches!(operation, Operation::IsNotNull(..)),
+                pos,
             )])
         }
     }
@@ -230,6 +270,7 @@ mod validity {
     pub(super) fn equality_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
         let left = operation.left();
         let right = operation.right();
@@ -255,6 +296,7 @@ mod validity {
                 left_type,
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             )])
         }
     }
@@ -262,65 +304,108 @@ mod validity {
     pub(super) fn ordering_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
+        let mut errors = ordering_property_errors(operation, pos);
+        errors.extend(ordering_argument_errors(
+            operation,
+            operation.right().unwrap(),
+            tag_name,
+            pos,
+        ));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub(super) fn range_types_valid<LeftT: NamedTypedValue>(
+        operation: &Operation<LeftT, Argument>,
+        tag_names: &[Option<&str>],
+        pos: Pos,
+    ) -> Result<(), Vec<FilterTypeError>> {
+        // Each bound of the range must be valid for an ordering filter on its own.
+        let mut errors = ordering_property_errors(operation, pos);
+        for (bound, tag_name) in operation.arguments().zip(tag_names) {
+            errors.extend(ordering_argument_errors(operation, bound, *tag_name, pos));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn ordering_property_errors<LeftT: NamedTypedValue>(
+        operation: &Operation<LeftT, Argument>,
+        pos: Pos,
+    ) -> Vec<FilterTypeError> {
         let left = operation.left();
-        let right = operation.right();
         let left_type = left.typed();
-        let right_type = right.map(|x| x.typed());
 
         // Individually, the operands' types must be non-nullable or list, recursively,
         // versions of orderable types.
-        let right_type = right_type.unwrap();
-
         let mut errors = vec![];
         if !left_type.is_orderable() {
             errors.push(FilterTypeError::non_orderable_property_with_ordering_filter(
                 operation.operation_name(),
                 left.named(),
                 left_type,
+                pos,
             ));
         }
+        errors
+    }
 
-        if !right_type.is_orderable() {
-            // The right argument must be a tag at this point. If it is not a tag
-            // and the second .unwrap() below panics, then our type inference
-            // has inferred an incorrect type for the variable in the argument.
-            let tag = right.unwrap().as_tag().unwrap();
+    fn ordering_argument_errors<LeftT: NamedTypedValue>(
+        operation: &Operation<LeftT, Argument>,
+        right: &Argument,
+        tag_name: Option<&str>,
+        pos: Pos,
+    ) -> Vec<FilterTypeError> {
+        let left = operation.left();
+        let left_type = left.typed();
+        let right_type = right.typed();
 
+        // Variables' types are inferred from the property's type, so they are only invalid
+        // if the property's type is invalid too. That is reported by the property's checks.
+        let Some(tag) = right.as_tag() else {
+            return vec![];
+        };
+
+        let mut errors = vec![];
+        if !right_type.is_orderable() {
             errors.push(FilterTypeError::non_orderable_tag_argument_to_ordering_filter(
                 operation.operation_name(),
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             ));
         }
 
         // For the operands relative to each other, nullability doesn't matter,
         // but the types must be equal to each other.
         if !left_type.equal_ignoring_nullability(right_type) {
-            // The right argument must be a tag at this point. If it is not a tag
-            // and the second .unwrap() below panics, then our type inference
-            // has inferred an incorrect type for the variable in the argument.
-            let tag = right.unwrap().as_tag().unwrap();
-
             errors.push(FilterTypeError::type_mismatch_between_property_and_tag(
                 operation.operation_name(),
                 left.named(),
                 left_type,
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             ));
         }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+        errors
     }
 
     pub(super) fn list_containment_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
         let left = operation.left();
         let right = operation.right();
@@ -334,6 +419,7 @@ mod validity {
                 operation.operation_name(),
                 left.named(),
                 left_type,
+                pos,
             )]
         })?;
 
@@ -355,6 +441,7 @@ mod validity {
                 left_type,
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             )])
         }
     }
@@ -362,6 +449,7 @@ mod validity {
     pub(super) fn bulk_equality_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
         let left = operation.left();
         let right = operation.right();
@@ -383,6 +471,7 @@ mod validity {
                 operation.operation_name(),
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             )])
         }?;
 
@@ -402,6 +491,7 @@ mod validity {
                 left_type,
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             )])
         }
     }
@@ -409,6 +499,7 @@ mod validity {
     pub(super) fn string_operation_types_valid<LeftT: NamedTypedValue>(
         operation: &Operation<LeftT, Argument>,
         tag_name: Option<&str>,
+        pos: Pos,
     ) -> Result<(), Vec<FilterTypeError>> {
         let left = operation.left();
         let right = operation.right();
@@ -423,6 +514,7 @@ mod validity {
                 operation.operation_name(),
                 left.named(),
                 left_type,
+                pos,
             ));
         }
 
@@ -436,6 +528,7 @@ mod validity {
                 operation.operation_name(),
                 tag_name.unwrap(),
                 tag.field_type(),
+                pos,
             ));
         }
 
@@ -0,0 +1,584 @@
+//! Queries with multiple root fields, whose results are joined together.
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use async_graphql_parser::Pos;
+
+use crate::{
+    graphql_query::{
+        directives::{OperatorArgument, OutputDirective, TagDirective, TransformGroup},
+        query::{parse_document_roots, FieldNode},
+    },
+    ir::{IndexedQuery, JoinedQuery, JoinedRoot, Type},
+    schema::Schema,
+};
+
+use super::{
+    error::{DuplicatedNamesConflict, FilterTypeError, FrontendError},
+    make_ir_with_output_positions,
+    util::variable_use_positions,
+};
+
+/// Prefix of the names of the variables and hidden outputs that carry tagged values
+/// from the root field that defines them to later root fields.
+const JOIN_TAG_PREFIX: &str = "__join_tag_";
+
+fn join_tag_name(tag: &str) -> Arc<str> {
+    format!("{JOIN_TAG_PREFIX}{tag}").into()
+}
+
+/// Parses a query string that may have multiple root fields, using a provided [Schema].
+///
+/// Each result of the query combines one result of each of its root fields. Filters within
+/// a root field may use values tagged in earlier root fields, for example to only combine
+/// vertices that have the same name. Otherwise, all combinations of the root fields' results
+/// are produced. Tags used across root fields may not be within an `@optional`, `@fold`,
+/// or `@absent` edge, since their values must always be present.
+///
+/// Root field directives like `@limit`, `@offset`, and `@dedupe` apply to the root field
+/// they're on, and not to the combined results.
+pub fn parse_joined(
+    schema: &Schema,
+    query: impl AsRef<str>,
+) -> Result<Arc<JoinedQuery>, FrontendError> {
+    let document = async_graphql_parser::parse_query(query)?;
+    let roots = parse_document_roots(&document)?;
+    let infos: Vec<RootInfo> = roots
+        .iter()
+        .map(|root| {
+            let mut info = RootInfo::default();
+            info.visit_node(&root.root_field, true);
+            info
+        })
+        .collect();
+    let variable_positions: Vec<_> = roots
+        .iter()
+        .map(|root| variable_use_positions(&root.root_connection, &root.root_field))
+        .collect();
+
+    let mut errors = vec![];
+
+    // Tag names must be unique across the entire query, not just within each root field.
+    let mut tag_roots: BTreeMap<&Arc<str>, usize> = BTreeMap::new();
+    let mut duplicated_tags: BTreeMap<&Arc<str>, Pos> = BTreeMap::new();
+    for (index, info) in infos.iter().enumerate() {
+        for (name, tag) in &info.defined_tags {
+            if tag_roots.insert(name, index).is_some() {
+                duplicated_tags.entry(name).or_insert(tag.position);
+            }
+        }
+    }
+    errors.extend(
+        duplicated_tags
+            .into_iter()
+            .map(|(name, pos)| FrontendError::MultipleTagsWithSameName(name.to_string(), pos)),
+    );
+
+    // Tags used by root fields other than their own are exported by the root field
+    // that defines them, and imported by the ones that use them.
+    let mut exported_tags: Vec<BTreeMap<Arc<str>, bool>> = vec![Default::default(); roots.len()];
+    let mut imported_tags: Vec<BTreeSet<Arc<str>>> = vec![Default::default(); roots.len()];
+    for (index, info) in infos.iter().enumerate() {
+        for (property_name, tag, pos) in &info.used_tags {
+            // Undefined tags are reported when making each root field's IR.
+            let Some(&defining_root) = tag_roots.get(tag) else {
+                continue;
+            };
+
+            match defining_root.cmp(&index) {
+                Ordering::Equal => {}
+                Ordering::Greater => push_new_error(
+                    &mut errors,
+                    FrontendError::TagUsedBeforeDefinition(
+                        property_name.to_string(),
+                        tag.to_string(),
+                        *pos,
+                    ),
+                ),
+                Ordering::Less if !infos[defining_root].defined_tags[tag].always_present => {
+                    let definition = infos[defining_root].defined_tags[tag].position;
+                    push_new_error(
+                        &mut errors,
+                        FrontendError::UnsupportedJoinTag(tag.to_string(), definition),
+                    )
+                }
+                Ordering::Less => {
+                    let used_locally = infos[defining_root]
+                        .used_tags
+                        .iter()
+                        .any(|(_, used_tag, _)| used_tag == tag);
+                    exported_tags[defining_root].insert(tag.clone(), used_locally);
+                    imported_tags[index].insert(tag.clone());
+                }
+            }
+        }
+    }
+
+    // All root fields share the query's variable definitions,
+    // but each one only needs the definitions of the variables it uses.
+    for definition in roots.first().map(|root| root.variable_definitions.as_slice()).unwrap_or(&[])
+    {
+        if !infos.iter().any(|info| info.variables.contains(&definition.name)) {
+            errors.push(FrontendError::UnusedVariableDefinition(
+                definition.name.to_string(),
+                definition.position,
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+
+    let mut queries: Vec<Arc<IndexedQuery>> = Vec::with_capacity(roots.len());
+    let mut output_positions: Vec<BTreeMap<Arc<str>, Vec<Pos>>> = Vec::with_capacity(roots.len());
+    for (index, mut root) in roots.into_iter().enumerate() {
+        root.variable_definitions
+            .retain(|definition| infos[index].variables.contains(&definition.name));
+        let rewriter =
+            JoinRewriter { exported: &exported_tags[index], imported: &imported_tags[index] };
+        rewriter.rewrite_node(&mut root.root_field);
+
+        match make_ir_with_output_positions(schema, &root) {
+            // Freshly-generated IRQuery objects are always valid IndexedQuery objects.
+            Ok((ir_query, positions)) => {
+                queries.push(Arc::new(ir_query.try_into().unwrap()));
+                output_positions.push(positions);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+
+    let hidden_outputs: BTreeSet<Arc<str>> = exported_tags
+        .iter()
+        .flat_map(|exported| exported.keys())
+        .map(|tag| join_tag_name(tag))
+        .collect();
+    let mut variables: BTreeMap<Arc<str>, Type> = BTreeMap::new();
+    let mut variable_defaults = BTreeMap::new();
+    let mut outputs: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    let mut duplicated_output_positions: BTreeMap<String, Vec<Pos>> = BTreeMap::new();
+    let mut joined_roots = Vec::with_capacity(queries.len());
+    for (index, query) in queries.iter().enumerate() {
+        let mut root_imported_tags = BTreeSet::new();
+        for tag in &imported_tags[index] {
+            let name = join_tag_name(tag);
+            let tag_type = &queries[tag_roots[tag]].outputs[&name].value_type;
+            let required_type = &query.ir_query.variables[&name];
+
+            // Tagged values may be null, just like in filters using tags within a root field.
+            if !tag_type.equal_ignoring_nullability(required_type) {
+                let (_, _, first_use) = infos[index]
+                    .used_tags
+                    .iter()
+                    .find(|(_, used_tag, _)| used_tag == tag)
+                    .expect("imported tag was not used");
+                errors.push(FrontendError::IncompatibleJoinTagType(
+                    tag.to_string(),
+                    tag_type.to_string(),
+                    required_type.to_string(),
+                    *first_use,
+                ));
+            }
+            root_imported_tags.insert(name);
+        }
+
+        for (name, variable_type) in &query.ir_query.variables {
+            if root_imported_tags.contains(name) {
+                continue;
+            }
+
+            let existing_type =
+                variables.entry(name.clone()).or_insert_with(|| variable_type.clone());
+            match existing_type.intersect(variable_type) {
+                Some(intersection) => *existing_type = intersection,
+                None => errors.push(
+                    FilterTypeError::IncompatibleVariableTypeRequirements(
+                        name.to_string(),
+                        existing_type.to_string(),
+                        variable_type.to_string(),
+                        variable_positions[..=index]
+                            .iter()
+                            .filter_map(|positions| positions.get(name))
+                            .flatten()
+                            .copied()
+                            .collect(),
+                    )
+                    .into(),
+                ),
+            }
+        }
+        variable_defaults.extend(
+            query
+                .ir_query
+                .variable_defaults
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+
+        for output in query.outputs.values().filter(|output| !hidden_outputs.contains(&output.name))
+        {
+            let component = &query.vids[&output.vid];
+            let field_name = component
+                .outputs
+                .get(&output.name)
+                .map(|field| field.field_name.as_ref())
+                .unwrap_or(output.name.as_ref());
+            outputs.entry(output.name.to_string()).or_default().push((
+                component.vertices[&output.vid].type_name.to_string(),
+                field_name.to_string(),
+            ));
+            duplicated_output_positions
+                .entry(output.name.to_string())
+                .or_default()
+                .extend(output_positions[index].get(&output.name).into_iter().flatten());
+        }
+
+        joined_roots.push(JoinedRoot { query: query.clone(), imported_tags: root_imported_tags });
+    }
+
+    outputs.retain(|_, fields| fields.len() > 1);
+    if !outputs.is_empty() {
+        let positions =
+            outputs.keys().flat_map(|name| &duplicated_output_positions[name]).copied().collect();
+        errors.push(FrontendError::MultipleOutputsWithSameName(
+            DuplicatedNamesConflict { duplicates: outputs },
+            positions,
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+
+    Ok(Arc::new(JoinedQuery { roots: joined_roots, variables, variable_defaults, hidden_outputs }))
+}
+
+/// Tags may be used many times, but each problem with them only needs to be reported once.
+fn push_new_error(errors: &mut Vec<FrontendError>, error: FrontendError) {
+    if !errors.contains(&error) {
+        errors.push(error);
+    }
+}
+
+/// The tags and variables of one root field of the query.
+#[derive(Debug, Default)]
+struct RootInfo {
+    /// The tags defined in the root field.
+    defined_tags: BTreeMap<Arc<str>, DefinedTag>,
+
+    /// The tags used in the root field, each with the name and position of the property using it.
+    used_tags: Vec<(Arc<str>, Arc<str>, Pos)>,
+
+    /// The variables used in the root field.
+    variables: BTreeSet<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DefinedTag {
+    /// Whether the tag's values are always present.
+    always_present: bool,
+    position: Pos,
+}
+
+impl RootInfo {
+    fn visit_node(&mut self, node: &FieldNode, always_present: bool) {
+        let position = node.position;
+        for tag in &node.tag {
+            self.defined_tags
+                .insert(tag_name(tag, node).clone(), DefinedTag { always_present, position });
+        }
+        for argument in node.filter.iter().flat_map(|filter| filter.operation.arguments()) {
+            self.visit_argument(&node.name, position, Some(argument));
+        }
+        if let Some(group) = &node.transform_group {
+            self.visit_transform_group(&node.name, position, group, always_present);
+        }
+
+        for (connection, child) in &node.connections {
+            if let Some(group) = connection.fold.as_ref().and_then(|fold| fold.transform.as_ref()) {
+                self.visit_transform_group(&connection.name, connection.position, group, false);
+            }
+
+            let always_present = always_present
+                && connection.optional.is_none()
+                && connection.fold.is_none()
+                && connection.absent.is_none();
+            self.visit_node(child, always_present);
+        }
+    }
+
+    fn visit_transform_group(
+        &mut self,
+        property_name: &Arc<str>,
+        position: Pos,
+        group: &TransformGroup,
+        always_present: bool,
+    ) {
+        self.visit_argument(property_name, position, group.transform.argument.as_ref());
+        // Transformed values' tags must be named. Unnamed ones are reported when making the IR.
+        for name in group.tag.iter().filter_map(|tag| tag.name.as_ref()) {
+            self.defined_tags.insert(name.clone(), DefinedTag { always_present, position });
+        }
+        for argument in group.filter.iter().flat_map(|filter| filter.operation.arguments()) {
+            self.visit_argument(property_name, position, Some(argument));
+        }
+        if let Some(retransform) = &group.retransform {
+            self.visit_transform_group(property_name, position, retransform, always_present);
+        }
+    }
+
+    fn visit_argument(
+        &mut self,
+        property_name: &Arc<str>,
+        position: Pos,
+        argument: Option<&OperatorArgument>,
+    ) {
+        match argument {
+            Some(OperatorArgument::VariableRef(name)) => {
+                self.variables.insert(name.clone());
+            }
+            Some(OperatorArgument::TagRef(name)) => {
+                self.used_tags.push((property_name.clone(), name.clone(), position));
+            }
+            None => {}
+        }
+    }
+}
+
+fn tag_name<'a>(tag: &'a TagDirective, node: &'a FieldNode) -> &'a Arc<str> {
+    tag.name.as_ref().or(node.alias.as_ref()).unwrap_or(&node.name)
+}
+
+/// Rewrites one root field of the query so that it outputs the tagged values that later
+/// root fields use, and uses variables in place of the tagged values of earlier root fields.
+struct JoinRewriter<'a> {
+    /// The tags this root field exports, and whether they're also used within it.
+    exported: &'a BTreeMap<Arc<str>, bool>,
+
+    /// The tags this root field imports from earlier root fields.
+    imported: &'a BTreeSet<Arc<str>>,
+}
+
+impl JoinRewriter<'_> {
+    fn rewrite_node(&self, node: &mut FieldNode) {
+        let mut hidden_outputs = vec![];
+        let (name, alias) = (&node.name, &node.alias);
+        node.tag.retain(|tag| {
+            let tag = tag.name.as_ref().or(alias.as_ref()).unwrap_or(name);
+            self.export_tag(tag, &mut hidden_outputs)
+        });
+        node.output.extend(hidden_outputs);
+
+        for filter in &mut node.filter {
+            filter.operation =
+                filter.operation.map(|_| (), |argument| self.rewrite_argument(argument));
+        }
+        if let Some(group) = &mut node.transform_group {
+            self.rewrite_transform_group(group);
+        }
+
+        for (connection, child) in &mut node.connections {
+            if let Some(group) = connection.fold.as_mut().and_then(|fold| fold.transform.as_mut()) {
+                self.rewrite_transform_group(group);
+            }
+            self.rewrite_node(child);
+        }
+    }
+
+    fn rewrite_transform_group(&self, group: &mut TransformGroup) {
+        group.transform.argument =
+            group.transform.argument.as_ref().map(|argument| self.rewrite_argument(argument));
+
+        let mut hidden_outputs = vec![];
+        group.tag.retain(|tag| match &tag.name {
+            Some(name) => self.export_tag(name, &mut hidden_outputs),
+            None => true,
+        });
+        group.output.extend(hidden_outputs);
+
+        for filter in &mut group.filter {
+            filter.operation =
+                filter.operation.map(|_| (), |argument| self.rewrite_argument(argument));
+        }
+        if let Some(retransform) = &mut group.retransform {
+            self.rewrite_transform_group(retransform);
+        }
+    }
+
+    /// Outputs the tagged value if it's exported, and returns whether to keep its `@tag`.
+    ///
+    /// Exported tags that aren't used within their own root field would otherwise be
+    /// reported as unused, so their `@tag` directives are replaced by the hidden output.
+    fn export_tag(&self, tag: &Arc<str>, hidden_outputs: &mut Vec<OutputDirective>) -> bool {
+        match self.exported.get(tag) {
+            Some(&used_locally) => {
+                hidden_outputs.push(OutputDirective { name: Some(join_tag_name(tag)) });
+                used_locally
+            }
+            None => true,
+        }
+    }
+
+    fn rewrite_argument(&self, argument: &OperatorArgument) -> OperatorArgument {
+        match argument {
+            OperatorArgument::TagRef(name) if self.imported.contains(name) => {
+                OperatorArgument::VariableRef(join_tag_name(name))
+            }
+            _ => argument.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fs, sync::OnceLock};
+
+    use async_graphql_parser::Pos;
+
+    use crate::{
+        frontend::error::{DuplicatedNamesConflict, FrontendError},
+        schema::Schema,
+    };
+
+    use super::parse_joined;
+
+    static NUMBERS_SCHEMA: OnceLock<Schema> = OnceLock::new();
+
+    fn get_numbers_schema() -> &'static Schema {
+        NUMBERS_SCHEMA.get_or_init(|| {
+            Schema::parse(fs::read_to_string("test_data/schemas/numbers.graphql").unwrap()).unwrap()
+        })
+    }
+
+    fn parse_error(query: &str) -> FrontendError {
+        parse_joined(get_numbers_schema(), query).expect_err("query was valid")
+    }
+
+    #[test]
+    fn tags_become_hidden_outputs_and_variables() {
+        let query = r#"
+{
+    Number(min: 1, max: 3) {
+        value @tag(name: "start") @output
+        name @tag @filter(op: "=", value: ["%name"])
+    }
+    Number(min: 1, max: 10) {
+        end: value @output @filter(op: ">", value: ["%start"])
+    }
+}"#;
+        let joined_query = parse_joined(get_numbers_schema(), query).unwrap();
+        assert_eq!(2, joined_query.roots.len());
+        assert_eq!(
+            vec!["__join_tag_start"],
+            joined_query.hidden_outputs.iter().map(AsRef::as_ref).collect::<Vec<&str>>()
+        );
+        assert!(joined_query.roots[0].imported_tags.is_empty());
+        assert_eq!(
+            vec!["__join_tag_start"],
+            joined_query.roots[1].imported_tags.iter().map(AsRef::as_ref).collect::<Vec<&str>>(),
+        );
+        assert!(joined_query.roots[0].query.outputs.contains_key("__join_tag_start"));
+        assert!(joined_query.variables.is_empty());
+
+        let outputs: Vec<_> = joined_query.outputs().map(|output| output.name.as_ref()).collect();
+        assert_eq!(vec!["value", "end"], outputs);
+    }
+
+    #[test]
+    fn tag_used_before_its_root_field() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        end: value @output @filter(op: ">", value: ["%start"])
+    }
+    Number(min: 1, max: 3) {
+        start: value @tag @output
+    }
+}"#;
+        assert_eq!(
+            FrontendError::TagUsedBeforeDefinition(
+                "value".into(),
+                "start".into(),
+                Pos { line: 4, column: 9 }
+            ),
+            parse_error(query),
+        );
+    }
+
+    #[test]
+    fn optional_tag_used_by_another_root_field() {
+        let query = r#"
+{
+    Number(min: 1, max: 3) {
+        start: value @output
+        predecessor @optional {
+            previous: value @tag
+        }
+    }
+    Number(min: 1, max: 10) {
+        end: value @output @filter(op: ">", value: ["%previous"])
+    }
+}"#;
+        assert_eq!(
+            FrontendError::UnsupportedJoinTag("previous".into(), Pos { line: 6, column: 13 }),
+            parse_error(query),
+        );
+    }
+
+    #[test]
+    fn tag_with_incompatible_type_in_another_root_field() {
+        let query = r#"
+{
+    Number(min: 1, max: 3) {
+        name @tag @output
+    }
+    Number(min: 1, max: 10) {
+        value @output @filter(op: "=", value: ["%name"])
+    }
+}"#;
+        assert_eq!(
+            FrontendError::IncompatibleJoinTagType(
+                "name".into(),
+                "String".into(),
+                "Int".into(),
+                Pos { line: 7, column: 9 }
+            ),
+            parse_error(query),
+        );
+    }
+
+    #[test]
+    fn outputs_with_the_same_name_in_different_root_fields() {
+        let query = r#"
+{
+    Number(min: 1, max: 3) {
+        value @output
+    }
+    Four {
+        value @output
+    }
+}"#;
+        let duplicates = BTreeMap::from([(
+            "value".to_string(),
+            vec![
+                ("Number".to_string(), "value".to_string()),
+                ("Composite".to_string(), "value".to_string()),
+            ],
+        )]);
+        assert_eq!(
+            FrontendError::MultipleOutputsWithSameName(
+                DuplicatedNamesConflict { duplicates },
+                vec![Pos { line: 4, column: 9 }, Pos { line: 7, column: 9 }],
+            ),
+            parse_error(query),
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! Non-fatal checks for queries that are valid, but likely not what their author intended.
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graphql_query::query::{parse_document, FieldConnection, FieldNode, Query},
+    ir::{
+        component_has_outputs, Argument, FieldRef, IRFold, IRQuery, IRQueryComponent, IRVertex,
+        Operation, ValueTransform,
+    },
+    schema::Schema,
+};
+
+use super::{error::FrontendError, make_ir_for_query, util::get_underlying_named_type};
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, thiserror::Error)]
+pub enum LintWarning {
+    #[error(
+        "Edge \"{0}\" uses @recurse(depth: 1), which only produces the starting vertex itself \
+        and its immediate neighbors. If the starting vertex is not meant to be included, \
+        consider using the edge without @recurse instead."
+    )]
+    RecurseDepthOne(String),
+
+    #[error(
+        "Filter with operator \"{1}\" on property \"{0}\" is always satisfied, so it has no effect. \
+        Consider removing it."
+    )]
+    FilterAlwaysTrue(String, String),
+
+    #[error(
+        "Filter with operator \"{1}\" on property \"{0}\" can never be satisfied, so no vertex \
+        can match it. Consider removing or fixing it."
+    )]
+    FilterNeverTrue(String, String),
+
+    #[error(
+        "@fold on edge \"{0}\" has no outputs and none of its aggregated values are filtered \
+        or tagged, so it has no effect on the query's results. Consider removing it."
+    )]
+    FoldWithoutEffect(String),
+
+    #[error("Field \"{1}\" of type \"{0}\" is deprecated: {2}")]
+    Deprecated(String, String, String),
+}
+
+/// Parses the query using the provided [Schema], and checks it for mistakes that
+/// do not prevent it from running but probably make it behave unlike its author intended.
+///
+/// Returns an error if the query is not valid, and otherwise the (possibly empty) list
+/// of warnings about the query, in the order in which the query's components are defined.
+/// Warnings about uses of deprecated properties and edges come first.
+pub fn lint(schema: &Schema, query: impl AsRef<str>) -> Result<Vec<LintWarning>, FrontendError> {
+    let document = async_graphql_parser::parse_query(query)?;
+    let query = parse_document(&document)?;
+    let ir_query = make_ir_for_query(schema, &query)?;
+
+    let mut warnings = deprecation_warnings(schema, &query);
+    warnings.extend(lint_ir(&ir_query));
+    Ok(warnings)
+}
+
+/// Warnings about each use of a deprecated property or edge, in the order they appear in the query.
+pub(super) fn deprecation_warnings(schema: &Schema, query: &Query) -> Vec<LintWarning> {
+    let mut warnings = vec![];
+    find_deprecated_fields(
+        schema,
+        schema.query_type_name(),
+        &query.root_connection,
+        &query.root_field,
+        &mut warnings,
+    );
+    warnings
+}
+
+fn find_deprecated_fields(
+    schema: &Schema,
+    type_name: &str,
+    connection: &FieldConnection,
+    node: &FieldNode,
+    warnings: &mut Vec<LintWarning>,
+) {
+    let field_name = connection.name.as_ref();
+    let Some(defn) = schema.fields.get(&(Arc::from(type_name), connection.name.clone())) else {
+        // The `__typename` meta field isn't defined in the schema, and can't be deprecated.
+        return;
+    };
+    if let Some(reason) = schema.field_deprecation_reason(type_name, field_name) {
+        warnings.push(LintWarning::Deprecated(
+            type_name.to_string(),
+            field_name.to_string(),
+            reason.to_string(),
+        ));
+    }
+
+    let node_type = match &node.coerced_to {
+        Some(coerced_to) => coerced_to.as_ref(),
+        None => get_underlying_named_type(&defn.ty.node).as_str(),
+    };
+    for (child_connection, child) in &node.connections {
+        find_deprecated_fields(schema, node_type, child_connection, child, warnings);
+    }
+}
+
+fn lint_ir(query: &IRQuery) -> Vec<LintWarning> {
+    let mut warnings = vec![];
+    lint_component(query, &query.root_component, &mut warnings);
+    warnings
+}
+
+fn lint_component(query: &IRQuery, component: &IRQueryComponent, warnings: &mut Vec<LintWarning>) {
+    for vertex in component.vertices.values() {
+        lint_vertex_filters(vertex, warnings);
+    }
+
+    for edge in component.edges.values() {
+        if edge.recursive.as_ref().is_some_and(|r| r.depth.is_some_and(|d| d.get() == 1)) {
+            warnings.push(LintWarning::RecurseDepthOne(edge.edge_name.to_string()));
+        }
+    }
+
+    for fold in component.folds.values() {
+        if !fold_has_effect(query, fold) {
+            warnings.push(LintWarning::FoldWithoutEffect(fold.edge_name.to_string()));
+        }
+        lint_component(query, &fold.component, warnings);
+    }
+}
+
+fn lint_vertex_filters(vertex: &IRVertex, warnings: &mut Vec<LintWarning>) {
+    for filter in &vertex.filters {
+        let field = filter.left();
+        let property = field.field_name.to_string();
+        let op = filter.operation_name().to_string();
+
+        // Comparing a property against a tag of its own value at the same vertex.
+        let is_self_comparison = matches!(
+            filter.right(),
+            Some(Argument::Tag(FieldRef::ContextField(tag)))
+                if tag.vertex_id == vertex.vid
+                    && tag.field_name == field.field_name
+                    && tag.transforms == field.transforms
+        );
+        if !is_self_comparison {
+            continue;
+        }
+        match filter {
+            Operation::Equals(..)
+            | Operation::LessThanOrEqual(..)
+            | Operation::GreaterThanOrEqual(..) => {
+                warnings.push(LintWarning::FilterAlwaysTrue(property, op));
+            }
+            Operation::NotEquals(..) | Operation::LessThan(..) | Operation::GreaterThan(..) => {
+                warnings.push(LintWarning::FilterNeverTrue(property, op));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A fold affects the query's results if it is `@absent`, if it produces outputs,
+/// if its aggregated values are filtered, or if they are tagged and used elsewhere in the query.
+fn fold_has_effect(query: &IRQuery, fold: &IRFold) -> bool {
+    !fold.fold_specific_outputs.is_empty()
+        || !fold.post_filters.is_empty()
+        || fold.absent
+        || component_has_outputs(&fold.component)
+        || component_uses_fold_tag(&query.root_component, fold)
+}
+
+fn component_uses_fold_tag(component: &IRQueryComponent, target: &IRFold) -> bool {
+    let is_target_tag = |argument: &Argument| {
+        matches!(
+            argument,
+            Argument::Tag(FieldRef::FoldSpecificField(f)) if f.fold_eid == target.eid
+        )
+    };
+
+    let transforms_use_target_tag = |transforms: &[ValueTransform]| {
+        transforms.iter().filter_map(|transform| transform.operand()).any(is_target_tag)
+    };
+
+    component.vertices.values().any(|v| {
+        v.filters.iter().any(|f| {
+            f.arguments().any(is_target_tag) || transforms_use_target_tag(&f.left().transforms)
+        })
+    }) || component.outputs.values().any(|output| transforms_use_target_tag(&output.transforms))
+        || component.folds.values().any(|fold| {
+            fold.post_filters.iter().any(|f| f.arguments().any(is_target_tag))
+                || fold
+                    .fold_specific_outputs
+                    .values()
+                    .chain(fold.post_filters.iter().map(|f| f.left()))
+                    .any(|kind| transforms_use_target_tag(kind.value_transforms()))
+                || component_uses_fold_tag(&fold.component, target)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::OnceLock};
+
+    use crate::schema::Schema;
+
+    use super::{lint, LintWarning};
+    use crate::frontend::{parse, parse_with_warnings};
+
+    static NUMBERS_SCHEMA: OnceLock<Schema> = OnceLock::new();
+
+    fn get_numbers_schema() -> &'static Schema {
+        NUMBERS_SCHEMA.get_or_init(|| {
+            Schema::parse(fs::read_to_string("test_data/schemas/numbers.graphql").unwrap()).unwrap()
+        })
+    }
+
+    fn lint_numbers_query(query: &str) -> Vec<LintWarning> {
+        lint(get_numbers_schema(), query).expect("query was not valid")
+    }
+
+    #[test]
+    fn clean_query_has_no_warnings() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output @filter(op: ">", value: ["$min"])
+
+        successor @recurse(depth: 3) {
+            succ: value @output
+        }
+
+        multiple(max: 3) @fold @transform(op: "count") @output(name: "multiples")
+    }
+}"#;
+        assert_eq!(Vec::<LintWarning>::new(), lint_numbers_query(query));
+    }
+
+    #[test]
+    fn invalid_query_is_an_error() {
+        let query = r#"
+{
+    Number(max: 10) {
+        nonexistent @output
+    }
+}"#;
+        assert!(lint(get_numbers_schema(), query).is_err());
+    }
+
+    #[test]
+    fn recurse_depth_one() {
+        let query = r#"
+{
+    Number(max: 10) {
+        successor @recurse(depth: 1) {
+            value @output
+        }
+    }
+}"#;
+        assert_eq!(
+            vec![LintWarning::RecurseDepthOne("successor".to_string())],
+            lint_numbers_query(query)
+        );
+    }
+
+    #[test]
+    fn filter_against_own_tagged_value() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output
+              @tag(name: "self")
+              @filter(op: ">=", value: ["%self"])
+              @filter(op: "!=", value: ["%self"])
+
+        successor {
+            value @filter(op: "<", value: ["%self"])
+        }
+    }
+}"#;
+        assert_eq!(
+            vec![
+                LintWarning::FilterAlwaysTrue("value".to_string(), ">=".to_string()),
+                LintWarning::FilterNeverTrue("value".to_string(), "!=".to_string()),
+            ],
+            lint_numbers_query(query)
+        );
+    }
+
+    #[test]
+    fn fold_without_effect() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output
+
+        multiple(max: 3) @fold {
+            value @filter(op: ">", value: ["$min"])
+
+            successor @fold {
+                name @output
+            }
+        }
+
+        predecessor @fold {
+            successor @fold {
+                value @filter(op: ">", value: ["$min"])
+            }
+        }
+    }
+}"#;
+        assert_eq!(
+            vec![
+                LintWarning::FoldWithoutEffect("predecessor".to_string()),
+                LintWarning::FoldWithoutEffect("successor".to_string()),
+            ],
+            lint_numbers_query(query)
+        );
+    }
+
+    #[test]
+    fn fold_with_tagged_count_has_effect() {
+        let query = r#"
+{
+    Number(max: 10) {
+        value @output
+
+        multiple(max: 3) @fold @transform(op: "count") @tag(name: "count")
+
+        successor {
+            value @filter(op: ">", value: ["%count"])
+        }
+    }
+}"#;
+        assert_eq!(Vec::<LintWarning>::new(), lint_numbers_query(query));
+    }
+
+    #[test]
+    fn deprecated_fields_and_edges() {
+        let schema_text = format!(
+            r#"
+schema {{
+    query: RootSchemaQuery
+}}
+{}
+type RootSchemaQuery {{
+    Number(max: Int!): [Number!]!
+    Numbers(max: Int!): [Number!]! @deprecated(reason: "Use `Number` instead.")
+}}
+
+interface Number {{
+    value: Int
+    successor: Number
+}}
+
+type Prime implements Number {{
+    value: Int
+    oldValue: Int @deprecated
+    successor: Number
+    nextPrime: Prime @deprecated(reason: "Use `successor` repeatedly instead.")
+}}
+"#,
+            Schema::ALL_DIRECTIVE_DEFINITIONS,
+        );
+        let schema = Schema::parse(schema_text).unwrap();
+        let query = r#"
+{
+    Numbers(max: 10) {
+        value @output
+
+        successor {
+            ... on Prime {
+                oldValue @output
+                __typename @output
+
+                nextPrime {
+                    next: value @output
+                }
+            }
+        }
+    }
+}"#;
+        let expected = vec![
+            LintWarning::Deprecated(
+                "RootSchemaQuery".to_string(),
+                "Numbers".to_string(),
+                "Use `Number` instead.".to_string(),
+            ),
+            LintWarning::Deprecated(
+                "Prime".to_string(),
+                "oldValue".to_string(),
+                "No longer supported".to_string(),
+            ),
+            LintWarning::Deprecated(
+                "Prime".to_string(),
+                "nextPrime".to_string(),
+                "Use `successor` repeatedly instead.".to_string(),
+            ),
+        ];
+        assert_eq!(expected, lint(&schema, query).unwrap());
+
+        let (indexed_query, warnings) = parse_with_warnings(&schema, query).unwrap();
+        assert_eq!(expected, warnings);
+        assert_eq!(parse(&schema, query).unwrap(), indexed_query);
+
+        let clean_query = query.replace("Numbers", "Number").replace("oldValue", "value2: value");
+        let clean_query = clean_query
+            .replace("nextPrime {\n                    next: value @output\n                }", "");
+        assert_eq!(
+            Vec::<LintWarning>::new(),
+            parse_with_warnings(&schema, &clean_query).unwrap().1
+        );
+    }
+}
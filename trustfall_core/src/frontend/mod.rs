@@ -4,21 +4,23 @@ use std::{collections::BTreeMap, iter::successors, num::NonZeroUsize, sync::Arc}
 
 use async_graphql_parser::{
     types::{ExecutableDocument, FieldDefinition, TypeDefinition, TypeKind},
-    Positioned,
+    Pos, Positioned,
 };
 use filters::make_filter_expr;
 use smallvec::SmallVec;
 
 use crate::{
     graphql_query::{
-        directives::{FilterDirective, FoldGroup, RecurseDirective},
+        directives::{
+            FilterDirective, FoldGroup, OperatorArgument, RecurseDirective, TransformGroup,
+        },
         query::{parse_document, FieldConnection, FieldNode, Query},
     },
     ir::{
-        get_typename_meta_field, Argument, ContextField, EdgeParameters, Eid, FieldRef, FieldValue,
-        FoldSpecificField, FoldSpecificFieldKind, IREdge, IRFold, IRQuery, IRQueryComponent,
-        IRVertex, IndexedQuery, LocalField, Operation, Recursive, TransformationKind, Type, Vid,
-        TYPENAME_META_FIELD,
+        component_has_outputs, get_typename_meta_field, Argument, ContextField, EdgeParameters,
+        Eid, FieldRef, FieldValue, FoldSpecificField, FoldSpecificFieldKind, IREdge, IRFold,
+        IRQuery, IRQueryComponent, IRVertex, IndexedQuery, LocalField, Operation, Recursive,
+        TransformationKind, Type, ValueTransform, VariableRef, Vid, TYPENAME_META_FIELD,
     },
     schema::{get_builtin_scalars, FieldOrigin, Schema},
     util::{BTreeMapTryInsertExt, TryCollectUniqueKey},
@@ -27,18 +29,25 @@ use crate::{
 use self::{
     error::{DuplicatedNamesConflict, FilterTypeError, FrontendError, ValidationError},
     outputs::OutputHandler,
-    tags::TagHandler,
-    util::{get_underlying_named_type, ComponentPath},
+    tags::{TagHandler, TagLookupError},
+    util::{get_underlying_named_type, variable_use_positions, ComponentPath},
     validation::validate_query_against_schema,
 };
 
+mod diagnostic;
 pub mod error;
 mod filters;
+mod join;
+mod lint;
 mod outputs;
 mod tags;
 mod util;
 mod validation;
 
+pub use diagnostic::{Diagnostic, Span};
+pub use join::parse_joined;
+pub use lint::{lint, LintWarning};
+
 /// Parses a query string to the Trustfall IR using a provided
 /// [Schema]. May fail if [parse_to_ir] fails for the provided schema and query.
 pub fn parse(schema: &Schema, query: impl AsRef<str>) -> Result<Arc<IndexedQuery>, FrontendError> {
@@ -53,6 +62,23 @@ pub fn parse(schema: &Schema, query: impl AsRef<str>) -> Result<Arc<IndexedQuery
     Ok(Arc::from(indexed_query))
 }
 
+/// Parses a query string like [parse], also returning warnings about its use of
+/// deprecated properties and edges. The warnings don't prevent the query from running.
+pub fn parse_with_warnings(
+    schema: &Schema,
+    query: impl AsRef<str>,
+) -> Result<(Arc<IndexedQuery>, Vec<LintWarning>), FrontendError> {
+    let document = async_graphql_parser::parse_query(query)?;
+    let q = parse_document(&document)?;
+    let ir_query = make_ir_for_query(schema, &q)?;
+    let warnings = lint::deprecation_warnings(schema, &q);
+
+    // Freshly-generated IRQuery objects are always valid IndexedQuery objects, as in `parse()`.
+    let indexed_query: IndexedQuery = ir_query.try_into().unwrap();
+
+    Ok((Arc::from(indexed_query), warnings))
+}
+
 /// Parses a query string to IR using a [Schema].
 pub fn parse_to_ir<T: AsRef<str>>(schema: &Schema, query: T) -> Result<IRQuery, FrontendError> {
     let document = async_graphql_parser::parse_query(query)?;
@@ -105,10 +131,12 @@ fn get_field_name_and_type_from_schema<'a>(
 fn get_vertex_type_definition_from_schema<'a>(
     schema: &'a Schema,
     vertex_type_name: &str,
+    pos: Pos,
 ) -> Result<&'a TypeDefinition, FrontendError> {
     schema.vertex_types.get(vertex_type_name).ok_or_else(|| {
         FrontendError::ValidationError(ValidationError::NonExistentType(
             vertex_type_name.to_owned(),
+            pos,
         ))
     })
 }
@@ -133,23 +161,41 @@ fn get_edge_definition_from_schema<'a>(
 fn get_vertex_field_definitions<'a>(
     schema: &'a Schema,
     type_name: &str,
-) -> &'a Vec<Positioned<FieldDefinition>> {
+) -> &'a [Positioned<FieldDefinition>] {
     match &schema.vertex_types[type_name].kind {
         TypeKind::Object(o) => &o.fields,
         TypeKind::Interface(i) => &i.fields,
+        TypeKind::Union(_) => &[],
         _ => unreachable!(),
     }
 }
 
 fn make_edge_parameters(
     edge_definition: &FieldDefinition,
-    specified_arguments: &BTreeMap<Arc<str>, FieldValue>,
+    connection: &FieldConnection,
 ) -> Result<EdgeParameters, Vec<FrontendError>> {
     let mut errors: Vec<FrontendError> = vec![];
 
+    let specified_arguments = &connection.arguments;
     let mut edge_arguments: BTreeMap<Arc<str>, FieldValue> = BTreeMap::new();
+    let mut variable_arguments: BTreeMap<Arc<str>, VariableRef> = BTreeMap::new();
     for arg in &edge_definition.arguments {
         let arg_name = arg.node.name.node.as_ref();
+        if let Some(variable_name) = connection.variable_arguments.get(arg_name) {
+            // The variable's value is only known at execution time. Its type must match
+            // the parameter's type, which is checked when the query's arguments are supplied.
+            let variable = VariableRef {
+                variable_name: variable_name.clone(),
+                variable_type: Type::from_type(&arg.node.ty.node),
+            };
+            variable_arguments.insert(arg_name.into(), variable);
+            continue;
+        }
+        if connection.tag_arguments.contains_key(arg_name) {
+            // Tagged values are resolved separately, once all the tags have been defined.
+            continue;
+        }
+
         let specified_value = match specified_arguments.get(arg_name) {
             None => {
                 // Argument value was not specified.
@@ -164,9 +210,9 @@ fn make_edge_parameters(
 
                         // The default value must be a valid type for the parameter,
                         // otherwise the schema itself is invalid.
-                        assert!(Type::from_type(&arg.node.ty.node).is_valid_value(&value));
-
-                        value
+                        Type::from_type(&arg.node.ty.node)
+                            .coerce_input_value(&value)
+                            .expect("invalid default value for edge parameter")
                     })
                     .or({
                         if arg.node.ty.node.nullable {
@@ -177,16 +223,21 @@ fn make_edge_parameters(
                     })
             }
             Some(value) => {
-                // Type-check the supplied value against the schema.
-                if !Type::from_type(&arg.node.ty.node).is_valid_value(value) {
-                    errors.push(FrontendError::InvalidEdgeParameterType(
-                        arg_name.to_string(),
-                        edge_definition.name.node.to_string(),
-                        arg.node.ty.to_string(),
-                        value.clone(),
-                    ));
+                // Type-check the supplied value against the schema,
+                // for example wrapping single values into lists where lists are expected.
+                match Type::from_type(&arg.node.ty.node).coerce_input_value(value) {
+                    Some(coerced) => Some(coerced),
+                    None => {
+                        errors.push(FrontendError::InvalidEdgeParameterType(
+                            arg_name.to_string(),
+                            edge_definition.name.node.to_string(),
+                            arg.node.ty.to_string(),
+                            value.clone(),
+                            connection.position,
+                        ));
+                        Some(value.clone())
+                    }
                 }
-                Some(value.clone())
             }
         };
 
@@ -195,6 +246,7 @@ fn make_edge_parameters(
                 errors.push(FrontendError::MissingRequiredEdgeParameter(
                     arg_name.to_string(),
                     edge_definition.name.node.to_string(),
+                    connection.position,
                 ));
             }
             Some(value) => {
@@ -205,13 +257,22 @@ fn make_edge_parameters(
     }
 
     // Check whether any of the supplied parameters aren't expected by the schema.
-    for specified_argument_name in specified_arguments.keys() {
-        if !edge_arguments.contains_key(specified_argument_name) {
+    let all_specified_arguments = specified_arguments
+        .keys()
+        .chain(connection.variable_arguments.keys())
+        .chain(connection.tag_arguments.keys());
+    for specified_argument_name in all_specified_arguments {
+        if !edge_definition
+            .arguments
+            .iter()
+            .any(|arg| arg.node.name.node.as_str() == specified_argument_name.as_ref())
+        {
             // This edge parameter isn't defined expected in the schema,
             // and it's an error to supply it.
             errors.push(FrontendError::UnexpectedEdgeParameter(
                 specified_argument_name.to_string(),
                 edge_definition.name.node.to_string(),
+                connection.position,
             ))
         }
     }
@@ -219,7 +280,84 @@ fn make_edge_parameters(
     if !errors.is_empty() {
         Err(errors)
     } else {
-        Ok(EdgeParameters::new(Arc::new(edge_arguments)))
+        let mut parameters = EdgeParameters::new(Arc::new(edge_arguments));
+        parameters.variables = variable_arguments;
+        Ok(parameters)
+    }
+}
+
+/// Set the edge parameters whose values are tagged values, like `edge(param: "%tag")`.
+///
+/// The tagged values must be known by the time the edge is expanded, so the tags must be
+/// defined earlier in the query than the edge's destination vertex `to_vid`.
+/// A nullable tagged value may be used for a non-nullable parameter: if the value is null,
+/// the vertex has no neighbors along that edge.
+fn add_edge_parameter_tags(
+    edge_definition: &FieldDefinition,
+    connection: &FieldConnection,
+    tags: &mut TagHandler<'_>,
+    component_path: &ComponentPath,
+    to_vid: Vid,
+    parameters: &mut EdgeParameters,
+) -> Result<(), Vec<FrontendError>> {
+    let mut errors: Vec<FrontendError> = vec![];
+
+    let edge_name = edge_definition.name.node.as_str();
+    for (arg_name, tag_name) in &connection.tag_arguments {
+        let Some(arg) =
+            edge_definition.arguments.iter().find(|arg| arg.node.name.node == arg_name.as_ref())
+        else {
+            // Unexpected parameters were already reported while making the edge's parameters.
+            continue;
+        };
+
+        let tag_field = match tags.reference_tag(tag_name, component_path, to_vid) {
+            Ok(defined_tag) if defined_tag.field.defined_at() < to_vid => defined_tag.field.clone(),
+            Ok(_)
+            | Err(TagLookupError::TagUsedBeforeDefinition(_))
+            | Err(TagLookupError::TagDefinedInsideFold(_)) => {
+                errors.push(FrontendError::EdgeParameterTagNotYetDefined(
+                    arg_name.to_string(),
+                    edge_name.to_string(),
+                    tag_name.to_string(),
+                    connection.position,
+                ));
+                continue;
+            }
+            Err(TagLookupError::UndefinedTag(_)) => {
+                errors.push(FrontendError::UndefinedTagInEdgeParameter(
+                    arg_name.to_string(),
+                    edge_name.to_string(),
+                    tag_name.to_string(),
+                    connection.position,
+                ));
+                continue;
+            }
+        };
+
+        let param_type = Type::from_type(&arg.node.ty.node);
+        if !param_type.with_nullability(true).is_scalar_only_subtype(tag_field.field_type()) {
+            errors.push(FrontendError::InvalidEdgeParameterTagType(
+                arg_name.to_string(),
+                edge_name.to_string(),
+                param_type.to_string(),
+                tag_name.to_string(),
+                tag_field.field_type().to_string(),
+                connection.position,
+            ));
+            continue;
+        }
+
+        if !param_type.nullable() {
+            parameters.required_tags.insert(arg_name.clone());
+        }
+        parameters.tags.insert(arg_name.clone(), tag_field);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
 }
 
@@ -231,9 +369,15 @@ fn make_local_field_filter_expr(
     current_vertex_vid: Vid,
     property_name: &Arc<str>,
     property_type: &Type,
+    transforms: &[ValueTransform],
     filter_directive: &FilterDirective,
+    pos: Pos,
 ) -> Result<Operation<LocalField, Argument>, Vec<FrontendError>> {
-    let left = LocalField { field_name: property_name.clone(), field_type: property_type.clone() };
+    let left = LocalField {
+        field_name: property_name.clone(),
+        field_type: property_type.clone(),
+        transforms: transforms.to_vec(),
+    };
 
     filters::make_filter_expr(
         schema,
@@ -242,10 +386,20 @@ fn make_local_field_filter_expr(
         current_vertex_vid,
         left,
         filter_directive,
+        pos,
     )
 }
 
 pub fn make_ir_for_query(schema: &Schema, query: &Query) -> Result<IRQuery, FrontendError> {
+    make_ir_with_output_positions(schema, query).map(|(ir_query, _)| ir_query)
+}
+
+/// Makes the IR for the query, together with the positions of the fields producing each output.
+#[allow(clippy::type_complexity)]
+fn make_ir_with_output_positions(
+    schema: &Schema,
+    query: &Query,
+) -> Result<(IRQuery, BTreeMap<Arc<str>, Vec<Pos>>), FrontendError> {
     validate_query_against_schema(schema, query)?;
 
     let mut vid_maker = successors(Some(Vid::new(NonZeroUsize::new(1).unwrap())), |x| {
@@ -265,12 +419,13 @@ pub fn make_ir_for_query(schema: &Schema, query: &Query) -> Result<IRQuery, Fron
 
     let root_parameters = make_edge_parameters(
         get_edge_definition_from_schema(schema, schema.query_type_name(), root_field_name),
-        &query.root_connection.arguments,
+        &query.root_connection,
     );
 
     let mut component_path = ComponentPath::new(starting_vid);
     let mut tags = Default::default();
     let mut output_handler = OutputHandler::new(starting_vid, None);
+    let mut root_aggregations = FoldAggregations::new(None);
     let mut root_component = make_query_component(
         schema,
         query,
@@ -279,6 +434,7 @@ pub fn make_ir_for_query(schema: &Schema, query: &Query) -> Result<IRQuery, Fron
         &mut component_path,
         &mut output_handler,
         &mut tags,
+        &mut root_aggregations,
         None,
         starting_vid,
         root_field_pre_coercion_type,
@@ -290,6 +446,16 @@ pub fn make_ir_for_query(schema: &Schema, query: &Query) -> Result<IRQuery, Fron
         errors.extend(e.iter().cloned());
     }
 
+    // The root edge is expanded before any vertex exists, so its parameters can't use tags.
+    for (arg_name, tag_name) in &query.root_connection.tag_arguments {
+        errors.push(FrontendError::EdgeParameterTagNotYetDefined(
+            arg_name.to_string(),
+            root_field_name.to_string(),
+            tag_name.to_string(),
+            query.root_connection.position,
+        ));
+    }
+
     let root_component = match root_component {
         Ok(r) => r,
         Err(e) => {
@@ -298,28 +464,53 @@ pub fn make_ir_for_query(schema: &Schema, query: &Query) -> Result<IRQuery, Fron
         }
     };
     let mut variables: BTreeMap<Arc<str>, Type> = Default::default();
-    if let Err(v) = fill_in_query_variables(&mut variables, &root_component) {
+    let variable_positions = variable_use_positions(&query.root_connection, &query.root_field);
+    if let Ok(root_parameters) = &root_parameters {
+        if let Err(v) = add_variable_uses(
+            &mut variables,
+            &variable_positions,
+            root_parameters.variables.values(),
+        ) {
+            errors.extend(v.into_iter().map(|x| x.into()));
+        }
+    }
+    if let Err(v) = fill_in_query_variables(&mut variables, &variable_positions, &root_component) {
         errors.extend(v.into_iter().map(|x| x.into()));
     }
+    let variable_defaults = match make_variable_defaults(query, &variables) {
+        Ok(defaults) => defaults,
+        Err(e) => {
+            errors.extend(e);
+            Default::default()
+        }
+    };
 
     if let Err(e) = tags.finish() {
-        errors.push(FrontendError::UnusedTags(e.into_iter().map(String::from).collect()));
+        let (names, positions) = e.into_iter().map(|(name, pos)| (String::from(name), pos)).unzip();
+        errors.push(FrontendError::UnusedTags(names, positions));
     }
 
-    let all_outputs = output_handler.finish();
+    let order_by = output_handler.take_orderings();
+    let (all_outputs, output_positions) = output_handler.finish();
     if let Err(duplicates) = check_for_duplicate_output_names(all_outputs) {
         let all_vertices = collect_ir_vertices(&root_component);
-        let errs = make_duplicated_output_names_error(&all_vertices, duplicates);
+        let errs = make_duplicated_output_names_error(&all_vertices, &output_positions, duplicates);
         errors.extend(errs);
     }
 
     if errors.is_empty() {
-        Ok(IRQuery {
+        let ir_query = IRQuery {
             root_name: root_field_name.into(),
             root_parameters: root_parameters.unwrap(),
             root_component: root_component.into(),
             variables,
-        })
+            variable_defaults,
+            order_by,
+            limit: query.limit.map(|limit| limit.count),
+            offset: query.offset.map(|offset| offset.count),
+            dedupe: query.dedupe.is_some(),
+        };
+        Ok((ir_query, output_positions))
     } else {
         Err(errors.into())
     }
@@ -343,29 +534,126 @@ fn collect_ir_vertices_recursive_step(
         .for_each(move |fold| collect_ir_vertices_recursive_step(result, &fold.component))
 }
 
+/// The default values of the query's variables, coerced to the variables' types.
+///
+/// Default values must be valid both for the variable's declared type
+/// and for the type inferred from the variable's uses in the query.
+fn make_variable_defaults(
+    query: &Query,
+    variables: &BTreeMap<Arc<str>, Type>,
+) -> Result<BTreeMap<Arc<str>, FieldValue>, Vec<FrontendError>> {
+    let mut errors = vec![];
+    let mut defaults = BTreeMap::new();
+    for definition in &query.variable_definitions {
+        let Some(variable_type) = variables.get(&definition.name) else {
+            errors.push(FrontendError::UnusedVariableDefinition(
+                definition.name.to_string(),
+                definition.position,
+            ));
+            continue;
+        };
+
+        let value = definition
+            .declared_type
+            .coerce_input_value(&definition.default_value)
+            .ok_or(&definition.declared_type)
+            .and_then(|value| variable_type.coerce_input_value(&value).ok_or(variable_type));
+        match value {
+            Ok(value) => {
+                defaults.insert(definition.name.clone(), value);
+            }
+            Err(expected_type) => errors.push(FrontendError::InvalidVariableDefaultValue(
+                definition.name.to_string(),
+                expected_type.to_string(),
+                definition.default_value.clone(),
+                definition.position,
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(defaults)
+    } else {
+        Err(errors)
+    }
+}
+
 fn fill_in_query_variables(
     variables: &mut BTreeMap<Arc<str>, Type>,
+    variable_positions: &BTreeMap<Arc<str>, Vec<Pos>>,
     component: &IRQueryComponent,
 ) -> Result<(), Vec<FilterTypeError>> {
     let mut errors: Vec<FilterTypeError> = vec![];
 
-    let all_variable_uses = component
-        .vertices
+    // Variables may be used by filters, as operands of the transforms of any value,
+    // and as edge parameters.
+    let mut all_variable_uses = vec![];
+    for parameters in component
+        .edges
         .values()
-        .flat_map(|vertex| &vertex.filters)
-        .map(|filter| filter.right())
-        .chain(
-            component
-                .folds
-                .values()
-                .flat_map(|fold| &fold.post_filters)
-                .map(|filter| filter.right()),
-        )
-        .filter_map(|rhs| match rhs {
-            Some(Argument::Variable(vref)) => Some(vref),
-            _ => None,
-        });
-    for vref in all_variable_uses {
+        .map(|edge| &edge.parameters)
+        .chain(component.folds.values().map(|fold| &fold.parameters))
+    {
+        all_variable_uses.extend(parameters.variables.values());
+        for tag in parameters.tags.values() {
+            add_field_variables(tag, &mut all_variable_uses);
+        }
+    }
+    for filter in component.vertices.values().flat_map(|vertex| &vertex.filters) {
+        add_transform_variables(&filter.left().transforms, &mut all_variable_uses);
+        for argument in filter.arguments() {
+            add_argument_variables(argument, &mut all_variable_uses);
+        }
+    }
+    for output in component.outputs.values() {
+        add_transform_variables(&output.transforms, &mut all_variable_uses);
+    }
+    for fold in component.folds.values() {
+        let fold_specific_fields = fold
+            .post_filters
+            .iter()
+            .map(|filter| filter.left())
+            .chain(fold.fold_specific_outputs.values());
+        for kind in fold_specific_fields {
+            add_fold_specific_field_variables(kind, &mut all_variable_uses);
+        }
+        for argument in fold.post_filters.iter().flat_map(|filter| filter.arguments()) {
+            add_argument_variables(argument, &mut all_variable_uses);
+        }
+        for imported_tag in &fold.imported_tags {
+            add_field_variables(imported_tag, &mut all_variable_uses);
+        }
+    }
+
+    if let Err(e) = add_variable_uses(variables, variable_positions, all_variable_uses) {
+        errors.extend(e);
+    }
+
+    for fold in component.folds.values() {
+        if let Err(e) =
+            fill_in_query_variables(variables, variable_positions, fold.component.as_ref())
+        {
+            errors.extend(e);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Record each use of a variable, checking that all uses of the same variable
+/// require compatible types.
+fn add_variable_uses<'a>(
+    variables: &mut BTreeMap<Arc<str>, Type>,
+    variable_positions: &BTreeMap<Arc<str>, Vec<Pos>>,
+    variable_uses: impl IntoIterator<Item = &'a VariableRef>,
+) -> Result<(), Vec<FilterTypeError>> {
+    let mut errors: Vec<FilterTypeError> = vec![];
+
+    for vref in variable_uses {
         let existing_type = variables
             .entry(vref.variable_name.clone())
             .or_insert_with(|| vref.variable_type.clone());
@@ -379,17 +667,12 @@ fn fill_in_query_variables(
                     vref.variable_name.to_string(),
                     existing_type.to_string(),
                     vref.variable_type.to_string(),
+                    variable_positions.get(&vref.variable_name).cloned().unwrap_or_default(),
                 ));
             }
         }
     }
 
-    for fold in component.folds.values() {
-        if let Err(e) = fill_in_query_variables(variables, fold.component.as_ref()) {
-            errors.extend(e);
-        }
-    }
-
     if errors.is_empty() {
         Ok(())
     } else {
@@ -397,10 +680,47 @@ fn fill_in_query_variables(
     }
 }
 
+fn add_argument_variables<'a>(argument: &'a Argument, variables: &mut Vec<&'a VariableRef>) {
+    match argument {
+        Argument::Variable(vref) => variables.push(vref),
+        Argument::Tag(field) => add_field_variables(field, variables),
+    }
+}
+
+fn add_field_variables<'a>(field: &'a FieldRef, variables: &mut Vec<&'a VariableRef>) {
+    match field {
+        FieldRef::ContextField(field) => add_transform_variables(&field.transforms, variables),
+        FieldRef::FoldSpecificField(field) => {
+            add_fold_specific_field_variables(&field.kind, variables)
+        }
+    }
+}
+
+fn add_fold_specific_field_variables<'a>(
+    kind: &'a FoldSpecificFieldKind,
+    variables: &mut Vec<&'a VariableRef>,
+) {
+    if let Some(aggregated_field) = kind.aggregated_field() {
+        add_transform_variables(&aggregated_field.transforms, variables);
+    }
+    add_transform_variables(kind.value_transforms(), variables);
+}
+
+fn add_transform_variables<'a>(
+    transforms: &'a [ValueTransform],
+    variables: &mut Vec<&'a VariableRef>,
+) {
+    for operand in transforms.iter().filter_map(|transform| transform.operand()) {
+        add_argument_variables(operand, variables);
+    }
+}
+
 fn make_duplicated_output_names_error(
     ir_vertices: &BTreeMap<Vid, IRVertex>,
+    output_positions: &BTreeMap<Arc<str>, Vec<Pos>>,
     duplicates: BTreeMap<Arc<str>, Vec<FieldRef>>,
 ) -> Vec<FrontendError> {
+    let positions = duplicates.keys().flat_map(|name| &output_positions[name]).copied().collect();
     let conflict_info = DuplicatedNamesConflict {
         duplicates: duplicates
             .iter()
@@ -414,11 +734,25 @@ fn make_duplicated_output_names_error(
                         }
                         FieldRef::FoldSpecificField(field) => {
                             let vid = field.fold_root_vid;
-                            match field.kind {
+                            match field.kind.untransformed() {
                                 FoldSpecificFieldKind::Count => (
                                     ir_vertices[&vid].type_name.to_string(),
                                     "fold count value".to_string(),
                                 ),
+                                kind @ (FoldSpecificFieldKind::Sum(aggregated)
+                                | FoldSpecificFieldKind::Min(aggregated)
+                                | FoldSpecificFieldKind::Max(aggregated)
+                                | FoldSpecificFieldKind::Avg(aggregated)) => (
+                                    ir_vertices[&aggregated.vertex_id].type_name.to_string(),
+                                    format!(
+                                        "fold {} of {}",
+                                        kind.transform_suffix(),
+                                        aggregated.field_name
+                                    ),
+                                ),
+                                FoldSpecificFieldKind::Transformed { .. } => {
+                                    unreachable!("nested transformed value")
+                                }
                             }
                         }
                     })
@@ -427,7 +761,7 @@ fn make_duplicated_output_names_error(
             })
             .collect(),
     };
-    vec![FrontendError::MultipleOutputsWithSameName(conflict_info)]
+    vec![FrontendError::MultipleOutputsWithSameName(conflict_info, positions)]
 }
 
 #[allow(clippy::type_complexity)]
@@ -449,6 +783,7 @@ fn make_query_component<'schema, 'query, V, E>(
     component_path: &mut ComponentPath,
     output_handler: &mut OutputHandler<'query>,
     tags: &mut TagHandler<'query>,
+    fold_aggregations: &mut FoldAggregations<'query>,
     parent_vid: Option<Vid>,
     starting_vid: Vid,
     pre_coercion_type: Arc<str>,
@@ -481,6 +816,9 @@ where
     output_handler.begin_subcomponent();
 
     let mut folds: BTreeMap<Eid, Arc<IRFold>> = Default::default();
+
+    // Eid -> connection that represents the fold
+    let mut fold_connections: BTreeMap<Eid, &'query FieldConnection> = Default::default();
     if let Err(e) = fill_in_vertex_data(
         schema,
         query,
@@ -489,11 +827,13 @@ where
         &mut vertices,
         &mut edges,
         &mut folds,
+        &mut fold_connections,
         &mut property_names_by_vertex,
         &mut properties,
         component_path,
         output_handler,
         tags,
+        fold_aggregations,
         None,
         starting_vid,
         pre_coercion_type,
@@ -531,6 +871,7 @@ where
     }
 
     let mut ir_edges: BTreeMap<Eid, Arc<IREdge>> = BTreeMap::new();
+    let mut optional_groups: BTreeMap<Arc<str>, (Vid, Vec<Eid>)> = BTreeMap::new();
     for (eid, (from_vid, to_vid, field_connection)) in edges.iter() {
         let from_vertex_type = &ir_vertices[from_vid].type_name;
         let edge_definition = get_edge_definition_from_schema(
@@ -538,11 +879,59 @@ where
             from_vertex_type.as_ref(),
             field_connection.name.as_ref(),
         );
-        let edge_name = edge_definition.name.node.as_ref().to_owned().into();
-
-        let parameters_result = make_edge_parameters(edge_definition, &field_connection.arguments);
+        let edge_name: Arc<str> = edge_definition.name.node.as_ref().to_owned().into();
+
+        let parameters_result =
+            make_edge_parameters(edge_definition, field_connection).and_then(|mut parameters| {
+                if field_connection.recurse.is_some() {
+                    let errors: Vec<_> = field_connection
+                        .tag_arguments
+                        .iter()
+                        .map(|(arg_name, tag_name)| {
+                            FrontendError::UnsupportedEdgeParameterTagOnRecurse(
+                                arg_name.to_string(),
+                                edge_name.to_string(),
+                                tag_name.to_string(),
+                                field_connection.position,
+                            )
+                        })
+                        .collect();
+                    if !errors.is_empty() {
+                        return Err(errors);
+                    }
+                }
+                add_edge_parameter_tags(
+                    edge_definition,
+                    field_connection,
+                    tags,
+                    component_path,
+                    *to_vid,
+                    &mut parameters,
+                )?;
+                Ok(parameters)
+            });
 
         let optional = field_connection.optional.is_some();
+        if let Some(group) = field_connection.optional.as_ref().and_then(|d| d.group.as_ref()) {
+            if field_connection.recurse.is_some() {
+                errors.push(FrontendError::RecursiveEdgeInOptionalGroup(
+                    group.to_string(),
+                    edge_name.to_string(),
+                    field_connection.position,
+                ));
+            }
+
+            let (group_vid, group_eids) =
+                optional_groups.entry(group.clone()).or_insert_with(|| (*from_vid, vec![]));
+            if group_vid != from_vid {
+                errors.push(FrontendError::OptionalGroupEdgesNotSiblings(
+                    group.to_string(),
+                    field_connection.position,
+                ));
+            }
+            group_eids.push(*eid);
+        }
+
         let recursive = match field_connection.recurse.as_ref() {
             None => None,
             Some(d) => {
@@ -551,8 +940,11 @@ where
                     &ir_vertices[from_vid],
                     edge_definition,
                     d,
+                    field_connection.position,
                 ) {
-                    Ok(coerce_to) => Some(Recursive::new(d.depth, coerce_to)),
+                    Ok(coerce_to) => {
+                        Some(Recursive { depth: d.depth, coerce_to, dedupe: d.dedupe })
+                    }
                     Err(e) => {
                         errors.push(e);
                         None
@@ -561,6 +953,14 @@ where
             }
         };
 
+        let filters = match make_edge_filters(vertices[to_vid].1, optional, recursive.is_some()) {
+            Ok(filters) => filters,
+            Err(e) => {
+                errors.extend(e);
+                vec![]
+            }
+        };
+
         match parameters_result {
             Ok(parameters) => {
                 ir_edges.insert(
@@ -573,6 +973,7 @@ where
                         parameters,
                         optional,
                         recursive,
+                        filters,
                     }
                     .into(),
                 );
@@ -583,6 +984,31 @@ where
         }
     }
 
+    // Like those of other edges, the tagged parameter values of folds are only resolved
+    // once all of this component's tags are defined.
+    for (eid, connection) in fold_connections {
+        if connection.tag_arguments.is_empty() {
+            continue;
+        }
+        let fold = folds.get_mut(&eid).expect("no fold for eid");
+        let edge_definition = get_edge_definition_from_schema(
+            schema,
+            ir_vertices[&fold.from_vid].type_name.as_ref(),
+            connection.name.as_ref(),
+        );
+        let fold = Arc::make_mut(fold);
+        if let Err(e) = add_edge_parameter_tags(
+            edge_definition,
+            connection,
+            tags,
+            component_path,
+            fold.to_vid,
+            &mut fold.parameters,
+        ) {
+            errors.extend(e);
+        }
+    }
+
     if !errors.is_empty() {
         return Err(errors);
     }
@@ -592,12 +1018,18 @@ where
     let component_outputs = match check_for_duplicate_output_names(maybe_duplicated_outputs) {
         Ok(outputs) => outputs,
         Err(duplicates) => {
-            return Err(make_duplicated_output_names_error(&ir_vertices, duplicates))
+            return Err(make_duplicated_output_names_error(
+                &ir_vertices,
+                output_handler.positions(),
+                duplicates,
+            ))
         }
     };
 
-    // TODO: fixme, temporary hack to avoid changing the IRQueryComponent struct
-    let hacked_outputs = component_outputs
+    // Fold-specific values were registered above only so that their names are checked
+    // for duplicates. They are recorded in the `fold_specific_outputs` of their own fold,
+    // so the component's outputs only include property values.
+    let property_outputs = component_outputs
         .into_iter()
         .filter_map(|(k, v)| match v {
             FieldRef::ContextField(c) => Some((k, c)),
@@ -610,7 +1042,11 @@ where
         vertices: ir_vertices,
         edges: ir_edges,
         folds,
-        outputs: hacked_outputs,
+        outputs: property_outputs,
+        optional_groups: optional_groups
+            .into_iter()
+            .map(|(group, (_, eids))| (group, eids))
+            .collect(),
     })
 }
 
@@ -667,6 +1103,7 @@ fn get_recurse_implicit_coercion(
     from_vertex: &IRVertex,
     edge_definition: &FieldDefinition,
     d: &RecurseDirective,
+    pos: Pos,
 ) -> Result<Option<Arc<str>>, FrontendError> {
     let source_type = &from_vertex.type_name;
     let destination_type = get_underlying_named_type(&edge_definition.ty.node).as_ref();
@@ -680,6 +1117,7 @@ fn get_recurse_implicit_coercion(
                 edge_definition.name.node.to_string(),
                 source_type.to_string(),
                 destination_type.to_string(),
+                pos,
             ));
         } else {
             // Case 2, the destination type is a subtype of the source type.
@@ -690,6 +1128,7 @@ fn get_recurse_implicit_coercion(
                 edge_definition.name.node.to_string(),
                 source_type.to_string(),
                 destination_type.to_string(),
+                pos,
             ));
         }
     }
@@ -711,7 +1150,10 @@ fn get_recurse_implicit_coercion(
                 Ok(None)
             } else {
                 // Case 4b, Err() because it's not supported yet.
-                Err(FrontendError::EdgeRecursionNeedingMultipleCoercions(edge_name.to_string()))
+                Err(FrontendError::EdgeRecursionNeedingMultipleCoercions(
+                    edge_name.to_string(),
+                    pos,
+                ))
             }
         }
         None => {
@@ -729,16 +1171,61 @@ fn get_recurse_implicit_coercion(
                     } else {
                         Err(FrontendError::EdgeRecursionNeedingMultipleCoercions(
                             edge_name.to_string(),
+                            pos,
                         ))
                     }
                 }
                 FieldOrigin::MultipleAncestors(multiple) => {
                     // Case 4d, Err() because we can't figure out which implicit coercion to use.
-                    Err(FrontendError::AmbiguousOriginEdgeRecursion(edge_name.to_string()))
+                    Err(FrontendError::AmbiguousOriginEdgeRecursion(edge_name.to_string(), pos))
+                }
+            }
+        }
+    }
+}
+
+/// Edge filters check whether an `@optional` edge exists, so only unary nullability operators
+/// are allowed. `@recurse` edges always exist, since recursion starts at depth 0.
+fn make_edge_filters(
+    edge_field: &FieldNode,
+    optional: bool,
+    recursive: bool,
+) -> Result<Vec<Operation<(), Argument>>, Vec<FrontendError>> {
+    let mut errors = vec![];
+    let mut filters = vec![];
+    let edge_name = edge_field.name.as_ref();
+    let pos = edge_field.position;
+
+    for filter_directive in &edge_field.filter {
+        let operation = &filter_directive.operation;
+        match operation {
+            Operation::IsNull(..) | Operation::IsNotNull(..) => {
+                if recursive {
+                    errors.push(FrontendError::UnsupportedEdgeFilter(edge_name.to_owned(), pos));
+                } else if !optional {
+                    errors.push(FrontendError::FilterOnNonOptionalEdge(edge_name.to_owned(), pos));
+                } else {
+                    filters.push(operation.map(
+                        |_| (),
+                        |_| -> Argument {
+                            unreachable!("unary operation with an argument: {operation:?}")
+                        },
+                    ));
                 }
             }
+            _ => errors.push(FrontendError::UnsupportedEdgeFilterOperator(
+                edge_name.to_owned(),
+                operation.operation_name().to_owned(),
+                pos,
+            )),
         }
     }
+
+    if errors.is_empty() {
+        Ok(filters)
+    } else {
+        Err(errors)
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -762,17 +1249,38 @@ fn make_vertex<'query>(
     // and we should report an error.
     let is_fold_root = component_path.is_component_root(vid);
     if !is_fold_root && !field_node.output.is_empty() {
-        errors.push(FrontendError::UnsupportedEdgeOutput(field_node.name.as_ref().to_owned()));
+        errors.push(FrontendError::UnsupportedEdgeOutput(
+            field_node.name.as_ref().to_owned(),
+            field_node.position,
+        ));
+    }
+
+    // Filters on non-folded edges are checked and recorded as part of the edge's IR.
+    // The remaining component roots are starting edges and folded edges:
+    // for those, edge filters aren't supported.
+    if component_path.is_component_root(vid) && !field_node.filter.is_empty() {
+        errors.push(FrontendError::UnsupportedEdgeFilter(
+            field_node.name.as_ref().to_owned(),
+            field_node.position,
+        ));
     }
 
-    if let Some(first_filter) = field_node.filter.first() {
-        // TODO: If @filter on edges is allowed, tweak this.
-        errors.push(FrontendError::UnsupportedEdgeFilter(field_node.name.as_ref().to_owned()));
+    // A @tag on a folded edge tags the number of folded vertices, and is handled
+    // in the fold creation function. The starting edge is also a component root,
+    // but it is never folded.
+    let is_folded_edge = is_fold_root && component_path.len() > 1;
+    if !is_folded_edge && !field_node.tag.is_empty() {
+        errors.push(FrontendError::UnsupportedEdgeTag(
+            field_node.name.as_ref().to_owned(),
+            field_node.position,
+        ));
     }
 
-    if let Some(first_tag) = field_node.tag.first() {
-        // TODO: If @tag on edges is allowed, tweak this.
-        errors.push(FrontendError::UnsupportedEdgeTag(field_node.name.as_ref().to_owned()));
+    if field_node.order.is_some() {
+        errors.push(FrontendError::UnsupportedEdgeOrder(
+            field_node.name.as_ref().to_owned(),
+            field_node.position,
+        ));
     }
 
     let default_func = || {
@@ -782,8 +1290,11 @@ fn make_vertex<'query>(
         ))
     };
     let mapper_func = |coerced_to_type: Arc<str>| {
-        let coerced_type =
-            get_vertex_type_definition_from_schema(schema, coerced_to_type.as_ref())?;
+        let coerced_type = get_vertex_type_definition_from_schema(
+            schema,
+            coerced_to_type.as_ref(),
+            field_node.position,
+        )?;
         Ok((coerced_type.name.node.as_ref().to_owned().into(), Some(uncoerced_type_name.clone())))
     };
     let (type_name, coerced_from_type) =
@@ -801,21 +1312,55 @@ fn make_vertex<'query>(
             properties.get(&(vid, property_name.clone())).unwrap();
 
         for property_field in property_fields.iter() {
-            for filter_directive in property_field.filter.iter() {
-                match make_local_field_filter_expr(
-                    schema,
-                    component_path,
-                    tags,
-                    vid,
-                    property_name,
-                    property_type,
-                    filter_directive,
-                ) {
-                    Ok(filter_operation) => {
-                        filters.push(filter_operation);
-                    }
-                    Err(e) => {
-                        errors.extend(e);
+            // Filters may apply to the property's value, or to its transformed values.
+            // Errors in the property's transforms are reported while processing its outputs.
+            let transformed_values = property_field
+                .transform_group
+                .as_ref()
+                .and_then(|group| {
+                    make_transform_chain(
+                        component_path,
+                        tags,
+                        vid,
+                        property_name,
+                        property_type,
+                        group,
+                        property_field.position,
+                    )
+                    .ok()
+                })
+                .map(|chain| chain.values)
+                .unwrap_or_default();
+            let filtered_values =
+                std::iter::once((&[][..], property_type, property_field.filter.as_slice())).chain(
+                    transformed_values.iter().map(|value| {
+                        (
+                            value.transforms.as_slice(),
+                            &value.value_type,
+                            value.transform_group.filter.as_slice(),
+                        )
+                    }),
+                );
+
+            for (transforms, value_type, filter_directives) in filtered_values {
+                for filter_directive in filter_directives {
+                    match make_local_field_filter_expr(
+                        schema,
+                        component_path,
+                        tags,
+                        vid,
+                        property_name,
+                        value_type,
+                        transforms,
+                        filter_directive,
+                        property_field.position,
+                    ) {
+                        Ok(filter_operation) => {
+                            filters.push(filter_operation);
+                        }
+                        Err(e) => {
+                            errors.extend(e);
+                        }
                     }
                 }
             }
@@ -839,11 +1384,13 @@ fn fill_in_vertex_data<'schema, 'query, V, E>(
     vertices: &mut BTreeMap<Vid, (Arc<str>, &'query FieldNode)>,
     edges: &mut BTreeMap<Eid, (Vid, Vid, &'query FieldConnection)>,
     folds: &mut BTreeMap<Eid, Arc<IRFold>>,
+    fold_connections: &mut BTreeMap<Eid, &'query FieldConnection>,
     property_names_by_vertex: &mut BTreeMap<Vid, Vec<Arc<str>>>,
     properties: &mut BTreeMap<(Vid, Arc<str>), (Arc<str>, Type, SmallVec<[&'query FieldNode; 1]>)>,
     component_path: &mut ComponentPath,
     output_handler: &mut OutputHandler<'query>,
     tags: &mut TagHandler<'query>,
+    fold_aggregations: &mut FoldAggregations<'query>,
     parent_vid: Option<Vid>,
     current_vid: Vid,
     pre_coercion_type: Arc<str>,
@@ -877,16 +1424,23 @@ where
                 .begin_nested_scope(next_vid, subfield.alias.as_ref().map(|x| x.as_ref()));
 
             if let Some(fold_group) = &connection.fold {
+                let unsupported_directive_error = if connection.absent.is_some() {
+                    FrontendError::UnsupportedDirectiveOnAbsentEdge
+                } else {
+                    FrontendError::UnsupportedDirectiveOnFoldedEdge
+                };
                 if connection.optional.is_some() {
-                    errors.push(FrontendError::UnsupportedDirectiveOnFoldedEdge(
+                    errors.push(unsupported_directive_error(
                         subfield.name.to_string(),
                         "@optional".to_owned(),
+                        connection.position,
                     ));
                 }
                 if connection.recurse.is_some() {
-                    errors.push(FrontendError::UnsupportedDirectiveOnFoldedEdge(
+                    errors.push(unsupported_directive_error(
                         subfield.name.to_string(),
                         "@recurse".to_owned(),
+                        connection.position,
                     ));
                 }
 
@@ -895,7 +1449,7 @@ where
                     post_coercion_type.as_ref(),
                     connection.name.as_ref(),
                 );
-                match make_edge_parameters(edge_definition, &connection.arguments) {
+                match make_edge_parameters(edge_definition, connection) {
                     Ok(edge_parameters) => {
                         match make_fold(
                             schema,
@@ -906,6 +1460,7 @@ where
                             output_handler,
                             tags,
                             fold_group,
+                            connection.absent.is_some(),
                             next_eid,
                             edge_definition.name.node.as_str().to_owned().into(),
                             edge_parameters,
@@ -917,6 +1472,7 @@ where
                         ) {
                             Ok(fold) => {
                                 folds.insert(next_eid, fold.into());
+                                fold_connections.insert(next_eid, connection);
                             }
                             Err(e) => {
                                 errors.extend(e);
@@ -940,11 +1496,13 @@ where
                     vertices,
                     edges,
                     folds,
+                    fold_connections,
                     property_names_by_vertex,
                     properties,
                     component_path,
                     output_handler,
                     tags,
+                    fold_aggregations,
                     Some(current_vid),
                     next_vid,
                     subfield_pre_coercion_type.clone(),
@@ -962,11 +1520,22 @@ where
         {
             // Processing a property.
 
-            // @fold is not allowed on a property
-            if connection.fold.is_some() {
+            // @absent is not allowed on a property
+            if connection.absent.is_some() {
+                errors.push(FrontendError::UnsupportedDirectiveOnProperty(
+                    "@absent".into(),
+                    subfield.name.to_string(),
+                    subfield.position,
+                ));
+            }
+
+            // @fold is not allowed on a property.
+            // Properties with @absent also have a fold group, which was reported above.
+            if connection.fold.is_some() && connection.absent.is_none() {
                 errors.push(FrontendError::UnsupportedDirectiveOnProperty(
                     "@fold".into(),
                     subfield.name.to_string(),
+                    subfield.position,
                 ));
             }
 
@@ -975,6 +1544,7 @@ where
                 errors.push(FrontendError::UnsupportedDirectiveOnProperty(
                     "@optional".into(),
                     subfield.name.to_string(),
+                    subfield.position,
                 ));
             }
 
@@ -983,6 +1553,7 @@ where
                 errors.push(FrontendError::UnsupportedDirectiveOnProperty(
                     "@recurse".into(),
                     subfield.name.to_string(),
+                    subfield.position,
                 ));
             }
 
@@ -1004,12 +1575,13 @@ where
                     (subfield_name, subfield_raw_type.clone(), SmallVec::from([subfield]))
                 });
 
+            let mut output_names: SmallVec<[Arc<str>; 1]> = Default::default();
             for output_directive in &subfield.output {
-                // TODO: handle outputs of non-fold-related transformed fields here.
                 let field_ref = FieldRef::ContextField(ContextField {
                     vertex_id: current_vid,
                     field_name: subfield.name.clone(),
                     field_type: subfield_raw_type.clone(),
+                    transforms: vec![],
                 });
 
                 // The output's name can be either explicit or local (i.e. implicitly prefixed).
@@ -1020,15 +1592,46 @@ where
                 // otherwise. The local name is appended to any prefixes given as aliases
                 // applied to the edges whose scopes enclose the output.
                 if let Some(explicit_name) = output_directive.name.as_ref() {
-                    output_handler
-                        .register_explicitly_named_output(explicit_name.clone(), field_ref);
+                    output_handler.register_explicitly_named_output(
+                        explicit_name.clone(),
+                        field_ref,
+                        subfield.position,
+                    );
+                    output_names.push(explicit_name.clone());
                 } else {
                     let local_name = subfield
                         .alias
                         .as_ref()
                         .map(|x| x.as_ref())
                         .unwrap_or_else(|| subfield.name.as_ref());
-                    output_handler.register_locally_named_output(local_name, None, field_ref);
+                    output_names.push(output_handler.register_locally_named_output(
+                        local_name,
+                        None,
+                        field_ref,
+                        subfield.position,
+                    ));
+                }
+            }
+
+            if let Some(order_directive) = &subfield.order {
+                // Ordering is applied to the final result rows, so it must refer to
+                // a single output value that is present exactly once in each row.
+                if component_path.len() > 1 {
+                    errors.push(FrontendError::OrderInsideFold(
+                        subfield.name.to_string(),
+                        subfield.position,
+                    ));
+                } else if let [output_name] = output_names.as_slice() {
+                    output_handler.register_ordering(
+                        subfield.position,
+                        output_name.clone(),
+                        order_directive.direction,
+                    );
+                } else {
+                    errors.push(FrontendError::OrderWithoutSingleOutput(
+                        subfield.name.to_string(),
+                        subfield.position,
+                    ));
                 }
             }
 
@@ -1049,13 +1652,73 @@ where
                     vertex_id: current_vid,
                     field_name: subfield.name.clone(),
                     field_type: subfield_raw_type.clone(),
+                    transforms: vec![],
                 };
 
-                // TODO: handle tags on non-fold-related transformed fields here
-                if let Err(e) =
-                    tags.register_tag(tag_name, FieldRef::ContextField(tag_field), component_path)
-                {
-                    errors.push(FrontendError::MultipleTagsWithSameName(tag_name.to_string()));
+                if let Err(e) = tags.register_tag(
+                    tag_name,
+                    FieldRef::ContextField(tag_field),
+                    component_path,
+                    subfield.position,
+                ) {
+                    errors.push(FrontendError::MultipleTagsWithSameName(
+                        tag_name.to_string(),
+                        subfield.position,
+                    ));
+                }
+            }
+
+            // A @transform following a @fold on a property belongs to the (invalid) @fold,
+            // which has already been reported above.
+            if let Some(transform_group) =
+                subfield.transform_group.as_ref().filter(|_| connection.fold.is_none())
+            {
+                match make_transform_chain(
+                    component_path,
+                    tags,
+                    current_vid,
+                    &subfield.name,
+                    &subfield_raw_type,
+                    transform_group,
+                    subfield.position,
+                ) {
+                    Ok(chain) => {
+                        for value in &chain.values {
+                            if let Err(e) = register_transformed_value(
+                                output_handler,
+                                tags,
+                                component_path,
+                                current_vid,
+                                subfield,
+                                value,
+                            ) {
+                                errors.extend(e);
+                            }
+                        }
+
+                        if let Some(aggregation) = chain.aggregation {
+                            // The aggregation applies to the last of the transformed values,
+                            // or to the property's own values if it has no value transforms.
+                            let (prior_transforms, aggregated_type) = match chain.values.last() {
+                                Some(value) => (value.transforms.clone(), &value.value_type),
+                                None => (vec![], &subfield_raw_type),
+                            };
+                            if let Err(e) = register_fold_aggregation(
+                                fold_aggregations,
+                                output_handler,
+                                tags,
+                                component_path,
+                                current_vid,
+                                aggregated_type,
+                                prior_transforms,
+                                subfield,
+                                aggregation,
+                            ) {
+                                errors.extend(e);
+                            }
+                        }
+                    }
+                    Err(e) => errors.push(e),
                 }
             }
         } else {
@@ -1070,34 +1733,527 @@ where
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn make_fold<'schema, 'query, V, E>(
-    schema: &'schema Schema,
-    query: &'query Query,
-    vid_maker: &mut V,
-    eid_maker: &mut E,
-    component_path: &mut ComponentPath,
-    output_handler: &mut OutputHandler<'query>,
-    tags: &mut TagHandler<'query>,
-    fold_group: &'query FoldGroup,
-    fold_eid: Eid,
-    edge_name: Arc<str>,
-    edge_parameters: EdgeParameters,
-    parent_vid: Vid,
-    starting_vid: Vid,
-    starting_pre_coercion_type: Arc<str>,
-    starting_post_coercion_type: Arc<str>,
-    starting_field: &'query FieldNode,
-) -> Result<IRFold, Vec<FrontendError>>
-where
-    'schema: 'query,
-    V: Iterator<Item = Vid>,
-    E: Iterator<Item = Eid>,
-{
+/// Aggregations like `@transform(op: "sum")` applied to properties within a `@fold`,
+/// followed by the values produced by any value transforms applied to them.
+///
+/// They are discovered while processing the fold's component, but their filters and tags
+/// are only resolved once the fold itself is complete, since they belong to the fold's parent.
+#[derive(Debug)]
+struct FoldAggregations<'query> {
+    /// The fold's eid and the vid of its root vertex, or `None` outside of any fold.
+    fold: Option<(Eid, Vid)>,
+    aggregations: Vec<FoldAggregation<'query>>,
+}
+
+#[derive(Debug)]
+struct FoldAggregation<'query> {
+    field: FoldSpecificField,
+    output_names: Vec<Arc<str>>,
+    property: &'query FieldNode,
+    transform_group: &'query TransformGroup,
+}
+
+impl<'query> FoldAggregations<'query> {
+    fn new(fold: Option<(Eid, Vid)>) -> Self {
+        Self { fold, aggregations: vec![] }
+    }
+}
+
+fn transform_op_name(kind: &TransformationKind) -> &'static str {
+    match kind {
+        TransformationKind::Count => "count",
+        TransformationKind::Sum => "sum",
+        TransformationKind::Min => "min",
+        TransformationKind::Max => "max",
+        TransformationKind::Avg => "avg",
+        TransformationKind::Lowercase => "lowercase",
+        TransformationKind::Uppercase => "uppercase",
+        TransformationKind::Trim => "trim",
+        TransformationKind::Len => "len",
+        TransformationKind::Abs => "abs",
+        TransformationKind::Add => "+",
+        TransformationKind::Subtract => "-",
+        TransformationKind::Multiply => "*",
+        TransformationKind::Divide => "/",
+    }
+}
+
+/// A property's value after applying one or more value transforms to it,
+/// like `name @transform(op: "lowercase")`.
+#[derive(Debug)]
+struct TransformedValue<'query> {
+    transforms: Vec<ValueTransform>,
+    value_type: Type,
+
+    /// The group of directives applying the last of the transforms,
+    /// which also holds the directives that refer to the transformed value.
+    transform_group: &'query TransformGroup,
+}
+
+/// The chain of `@transform` directives applied to a property.
+#[derive(Debug)]
+struct TransformChain<'query> {
+    /// One value per value transform in the chain, each including all the transforms before it.
+    values: Vec<TransformedValue<'query>>,
+
+    /// The aggregation that ends the chain, if any, like `@transform(op: "sum")`.
+    aggregation: Option<&'query TransformGroup>,
+}
+
+fn make_transform_chain<'query>(
+    component_path: &ComponentPath,
+    tags: &mut TagHandler<'_>,
+    current_vid: Vid,
+    property_name: &str,
+    property_type: &Type,
+    transform_group: &'query TransformGroup,
+    pos: Pos,
+) -> Result<TransformChain<'query>, FrontendError> {
+    let mut values: Vec<TransformedValue<'query>> = vec![];
+    let mut next_group = Some(transform_group);
+    while let Some(transform_group) = next_group {
+        let kind = &transform_group.transform.kind;
+        if kind.is_aggregation() {
+            return Ok(TransformChain { values, aggregation: Some(transform_group) });
+        }
+
+        let (mut transforms, input_type) = match values.last() {
+            Some(value) => (value.transforms.clone(), &value.value_type),
+            None => (vec![], property_type),
+        };
+        let transform = match kind.value_transform() {
+            Some(transform) => transform,
+            None => make_arithmetic_transform(
+                component_path,
+                tags,
+                current_vid,
+                property_name,
+                input_type,
+                transform_group,
+                pos,
+            )?,
+        };
+        let value_type =
+            determine_transformed_field_type(&transform, input_type).ok_or_else(|| {
+                FrontendError::UnsupportedTransformInputType(
+                    transform.op_name().to_string(),
+                    property_name.to_string(),
+                    input_type.to_string(),
+                    pos,
+                )
+            })?;
+        transforms.push(transform);
+        values.push(TransformedValue { transforms, value_type, transform_group });
+
+        next_group = transform_group.retransform.as_deref();
+    }
+
+    Ok(TransformChain { values, aggregation: None })
+}
+
+/// An arithmetic transform like `@transform(op: "*", value: ["$factor"])`,
+/// whose operand is the value of a variable or of a tag defined earlier in the query.
+fn make_arithmetic_transform(
+    component_path: &ComponentPath,
+    tags: &mut TagHandler<'_>,
+    current_vid: Vid,
+    property_name: &str,
+    input_type: &Type,
+    transform_group: &TransformGroup,
+    pos: Pos,
+) -> Result<ValueTransform, FrontendError> {
+    let operand = match transform_group
+        .transform
+        .argument
+        .as_ref()
+        .expect("arithmetic transform without an operand")
+    {
+        OperatorArgument::VariableRef(variable_name) => Argument::Variable(VariableRef {
+            variable_name: variable_name.clone(),
+            variable_type: Type::new_named_type(input_type.base_type(), false),
+        }),
+        OperatorArgument::TagRef(tag_name) => {
+            match tags.reference_tag(tag_name, component_path, current_vid) {
+                Ok(defined_tag) if !is_numeric_type(defined_tag.field.field_type()) => {
+                    return Err(FrontendError::UnsupportedTransformOperandType(
+                        transform_op_name(&transform_group.transform.kind).to_string(),
+                        property_name.to_string(),
+                        format!("%{tag_name}"),
+                        defined_tag.field.field_type().to_string(),
+                        pos,
+                    ))
+                }
+                Ok(defined_tag) => Argument::Tag(defined_tag.field.clone()),
+                Err(TagLookupError::UndefinedTag(tag_name)) => {
+                    return Err(FrontendError::UndefinedTagInTransform(
+                        property_name.to_string(),
+                        tag_name,
+                        pos,
+                    ))
+                }
+                Err(TagLookupError::TagDefinedInsideFold(tag_name)) => {
+                    return Err(FrontendError::TagUsedOutsideItsFoldedSubquery(
+                        property_name.to_string(),
+                        tag_name,
+                        pos,
+                    ))
+                }
+                Err(TagLookupError::TagUsedBeforeDefinition(tag_name)) => {
+                    return Err(FrontendError::TagUsedBeforeDefinition(
+                        property_name.to_string(),
+                        tag_name,
+                        pos,
+                    ))
+                }
+            }
+        }
+    };
+
+    Ok(match transform_group.transform.kind {
+        TransformationKind::Add => ValueTransform::Add(operand),
+        TransformationKind::Subtract => ValueTransform::Subtract(operand),
+        TransformationKind::Multiply => ValueTransform::Multiply(operand),
+        TransformationKind::Divide => ValueTransform::Divide(operand),
+        ref kind => unreachable!("{kind:?} is not an arithmetic transform"),
+    })
+}
+
+/// The type of the value produced by applying the given transform to a value of the given type,
+/// or `None` if the transform does not support values of that type.
+pub(crate) fn determine_transformed_field_type(
+    transform: &ValueTransform,
+    input_type: &Type,
+) -> Option<Type> {
+    let nullable = input_type.nullable();
+    match transform {
+        ValueTransform::Lowercase | ValueTransform::Uppercase | ValueTransform::Trim
+            if !input_type.is_list() && input_type.base_type() == "String" =>
+        {
+            Some(input_type.clone())
+        }
+        ValueTransform::Len if input_type.is_list() || input_type.base_type() == "String" => {
+            Some(Type::new_named_type("Int", nullable))
+        }
+        ValueTransform::Abs if is_numeric_type(input_type) => Some(input_type.clone()),
+        ValueTransform::Add(operand)
+        | ValueTransform::Subtract(operand)
+        | ValueTransform::Multiply(operand)
+        | ValueTransform::Divide(operand)
+            if is_numeric_type(input_type) =>
+        {
+            // Arithmetic produces `null` when it fails, such as on division by zero.
+            let operand_type = match operand {
+                Argument::Tag(tag) => tag.field_type(),
+                Argument::Variable(variable) => &variable.variable_type,
+            };
+            let base_type =
+                if input_type.base_type() == "Float" || operand_type.base_type() == "Float" {
+                    "Float"
+                } else {
+                    "Int"
+                };
+            Some(Type::new_named_type(base_type, true))
+        }
+        _ => None,
+    }
+}
+
+fn is_numeric_type(value_type: &Type) -> bool {
+    !value_type.is_list() && matches!(value_type.base_type(), "Int" | "Float")
+}
+
+fn register_transformed_value<'query>(
+    output_handler: &mut OutputHandler<'query>,
+    tags: &mut TagHandler<'query>,
+    component_path: &ComponentPath,
+    current_vid: Vid,
+    property: &'query FieldNode,
+    value: &TransformedValue<'query>,
+) -> Result<(), Vec<FrontendError>> {
+    let mut errors = vec![];
+    let field = ContextField {
+        vertex_id: current_vid,
+        field_name: property.name.clone(),
+        field_type: value.value_type.clone(),
+        transforms: value.transforms.clone(),
+    };
+
+    for output in &value.transform_group.output {
+        let field_ref = FieldRef::ContextField(field.clone());
+        match output.name.as_ref() {
+            Some(explicit_name) => {
+                output_handler.register_explicitly_named_output(
+                    explicit_name.clone(),
+                    field_ref,
+                    property.position,
+                );
+            }
+            None => {
+                let local_name = property.alias.as_ref().unwrap_or(&property.name);
+                let suffixes: Vec<_> = value.transforms.iter().map(|t| t.output_suffix()).collect();
+                output_handler.register_locally_named_output(
+                    local_name,
+                    Some(&suffixes),
+                    field_ref,
+                    property.position,
+                );
+            }
+        }
+    }
+
+    for tag_directive in &value.transform_group.tag {
+        // The transformed value and the property itself would have the same implicit tag name,
+        // so tags on transformed values must be explicitly named.
+        let Some(tag_name) = tag_directive.name.as_ref() else {
+            errors.push(FrontendError::ExplicitTagNameRequired(
+                property.name.to_string(),
+                property.position,
+            ));
+            continue;
+        };
+        let tag_field = FieldRef::ContextField(field.clone());
+        if let Err(e) = tags.register_tag(tag_name, tag_field, component_path, property.position) {
+            errors.push(FrontendError::MultipleTagsWithSameName(
+                tag_name.to_string(),
+                property.position,
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// A fold-specific value, like the number of folded vertices or an aggregation of
+/// a folded property, together with the group of directives applying to that value.
+#[derive(Debug)]
+struct FoldValue<'query> {
+    field: FoldSpecificField,
+    transform_group: &'query TransformGroup,
+}
+
+/// The fold-specific value, followed by one value per value transform applied to it,
+/// like `@transform(op: "count") @transform(op: "*", value: ["$factor"])`.
+///
+/// Fold-specific values are only available in the fold's parent component,
+/// so that is where the operands of the value transforms are resolved.
+fn make_fold_value_chain<'query>(
+    parent_path: &ComponentPath,
+    tags: &mut TagHandler<'_>,
+    field_name: &str,
+    field: FoldSpecificField,
+    transform_group: &'query TransformGroup,
+    pos: Pos,
+) -> Result<Vec<FoldValue<'query>>, FrontendError> {
+    let mut values = vec![FoldValue { field, transform_group }];
+    while let Some(transform_group) =
+        values.last().and_then(|v| v.transform_group.retransform.as_deref())
+    {
+        let kind = &transform_group.transform.kind;
+        if kind.is_aggregation() {
+            return Err(FrontendError::TransformOfAggregatedValue(
+                transform_op_name(kind).to_string(),
+                field_name.to_string(),
+                pos,
+            ));
+        }
+
+        let prior = &values.last().expect("no prior value").field;
+        let input_type = prior.kind.field_type();
+        let transform = match kind.value_transform() {
+            Some(transform) => transform,
+            None => make_arithmetic_transform(
+                parent_path,
+                tags,
+                prior.fold_root_vid,
+                field_name,
+                input_type,
+                transform_group,
+                pos,
+            )?,
+        };
+        let value_type =
+            determine_transformed_field_type(&transform, input_type).ok_or_else(|| {
+                FrontendError::UnsupportedTransformInputType(
+                    transform.op_name().to_string(),
+                    field_name.to_string(),
+                    input_type.to_string(),
+                    pos,
+                )
+            })?;
+        let field = FoldSpecificField {
+            kind: prior.kind.clone().transformed(transform, value_type),
+            ..prior.clone()
+        };
+        values.push(FoldValue { field, transform_group });
+    }
+
+    Ok(values)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register_fold_aggregation<'query>(
+    fold_aggregations: &mut FoldAggregations<'query>,
+    output_handler: &mut OutputHandler<'query>,
+    tags: &mut TagHandler<'_>,
+    component_path: &ComponentPath,
+    current_vid: Vid,
+    property_type: &Type,
+    prior_transforms: Vec<ValueTransform>,
+    property: &'query FieldNode,
+    transform_group: &'query TransformGroup,
+) -> Result<(), Vec<FrontendError>> {
+    let op_name = transform_op_name(&transform_group.transform.kind);
+    let property_name = property.name.as_ref();
+    let pos = property.position;
+
+    let prior_suffixes: Vec<&str> = prior_transforms
+        .iter()
+        .map(|t| t.output_suffix())
+        .chain(std::iter::once(op_name))
+        .collect();
+    let aggregated_field = ContextField {
+        vertex_id: current_vid,
+        field_name: property.name.clone(),
+        field_type: property_type.clone(),
+        transforms: prior_transforms,
+    };
+    let kind = match transform_group.transform.kind {
+        TransformationKind::Count => {
+            return Err(vec![FrontendError::UnsupportedTransformOnProperty(
+                op_name.to_string(),
+                property_name.to_string(),
+                pos,
+            )]);
+        }
+        TransformationKind::Sum => FoldSpecificFieldKind::Sum(aggregated_field),
+        TransformationKind::Min => FoldSpecificFieldKind::Min(aggregated_field),
+        TransformationKind::Max => FoldSpecificFieldKind::Max(aggregated_field),
+        TransformationKind::Avg => FoldSpecificFieldKind::Avg(aggregated_field),
+        TransformationKind::Lowercase
+        | TransformationKind::Uppercase
+        | TransformationKind::Trim
+        | TransformationKind::Len
+        | TransformationKind::Abs
+        | TransformationKind::Add
+        | TransformationKind::Subtract
+        | TransformationKind::Multiply
+        | TransformationKind::Divide => {
+            unreachable!("value transform {op_name} is not an aggregation")
+        }
+    };
+
+    let Some((fold_eid, fold_root_vid)) = fold_aggregations.fold else {
+        return Err(vec![FrontendError::AggregationOutsideFold(
+            op_name.to_string(),
+            property_name.to_string(),
+            pos,
+        )]);
+    };
+
+    if property_type.is_list() || !matches!(property_type.base_type(), "Int" | "Float") {
+        return Err(vec![FrontendError::AggregationOfNonNumericProperty(
+            op_name.to_string(),
+            property_name.to_string(),
+            property_type.to_string(),
+            pos,
+        )]);
+    }
+
+    let mut parent_path = component_path.clone();
+    parent_path.pop(fold_root_vid);
+    let field = FoldSpecificField { fold_eid, fold_root_vid, kind };
+    let values =
+        make_fold_value_chain(&parent_path, tags, property_name, field, transform_group, pos)
+            .map_err(|e| vec![e])?;
+
+    let mut errors = vec![];
+    for value in values {
+        let FoldValue { field, transform_group } = value;
+        if transform_group.tag.iter().any(|tag| tag.name.is_none()) {
+            errors.push(FrontendError::ExplicitTagNameRequired(property_name.to_string(), pos));
+        }
+
+        // Outputs are registered right away, so that they pick up the prefixes of
+        // the scopes enclosing the property, just like the property's other outputs.
+        let field_ref = FieldRef::FoldSpecificField(field.clone());
+        let suffixes: Vec<&str> = prior_suffixes
+            .iter()
+            .copied()
+            .chain(field.kind.value_transforms().iter().map(|t| t.output_suffix()))
+            .collect();
+        let output_names = transform_group
+            .output
+            .iter()
+            .map(|output| match output.name.as_ref() {
+                Some(explicit_name) => {
+                    output_handler.register_explicitly_named_output(
+                        explicit_name.clone(),
+                        field_ref.clone(),
+                        pos,
+                    );
+                    explicit_name.clone()
+                }
+                None => {
+                    let local_name = property.alias.as_ref().unwrap_or(&property.name);
+                    output_handler.register_locally_named_output(
+                        local_name,
+                        Some(&suffixes),
+                        field_ref.clone(),
+                        pos,
+                    )
+                }
+            })
+            .collect();
+
+        fold_aggregations.aggregations.push(FoldAggregation {
+            field,
+            output_names,
+            property,
+            transform_group,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn make_fold<'schema, 'query, V, E>(
+    schema: &'schema Schema,
+    query: &'query Query,
+    vid_maker: &mut V,
+    eid_maker: &mut E,
+    component_path: &mut ComponentPath,
+    output_handler: &mut OutputHandler<'query>,
+    tags: &mut TagHandler<'query>,
+    fold_group: &'query FoldGroup,
+    absent: bool,
+    fold_eid: Eid,
+    edge_name: Arc<str>,
+    edge_parameters: EdgeParameters,
+    parent_vid: Vid,
+    starting_vid: Vid,
+    starting_pre_coercion_type: Arc<str>,
+    starting_post_coercion_type: Arc<str>,
+    starting_field: &'query FieldNode,
+) -> Result<IRFold, Vec<FrontendError>>
+where
+    'schema: 'query,
+    V: Iterator<Item = Vid>,
+    E: Iterator<Item = Eid>,
+{
     component_path.push(starting_vid);
     tags.begin_subcomponent(starting_vid);
 
     let mut errors = vec![];
+    let mut fold_aggregations = FoldAggregations::new(Some((fold_eid, starting_vid)));
     let component = make_query_component(
         schema,
         query,
@@ -1106,6 +2262,7 @@ where
         component_path,
         output_handler,
         tags,
+        &mut fold_aggregations,
         Some(parent_vid),
         starting_vid,
         starting_pre_coercion_type,
@@ -1114,29 +2271,178 @@ where
     )?;
     component_path.pop(starting_vid);
     let imported_tags = tags.end_subcomponent(starting_vid);
+    let pos = starting_field.position;
+
+    if absent && component_has_outputs(&component) {
+        errors.push(FrontendError::OutputWithinAbsentEdge(starting_field.name.to_string(), pos));
+    }
 
     if !starting_field.output.is_empty() {
         // The edge has @fold @output but no @transform.
         // If it had a @transform then the output would have been in the field's transform group.
-        errors.push(FrontendError::UnsupportedEdgeOutput(starting_field.name.as_ref().to_owned()));
+        errors.push(FrontendError::UnsupportedEdgeOutput(
+            starting_field.name.as_ref().to_owned(),
+            pos,
+        ));
     }
 
     let mut post_filters = vec![];
     let mut fold_specific_outputs = BTreeMap::new();
 
-    if let Some(transform_group) = &fold_group.transform {
-        if transform_group.retransform.is_some() {
-            unimplemented!("re-transforming a @fold @transform value is currently not supported");
+    let count_field = FoldSpecificField {
+        fold_eid,
+        fold_root_vid: starting_vid,
+        kind: FoldSpecificFieldKind::Count,
+    };
+
+    // A @tag directly on the folded edge tags the number of folded vertices.
+    for tag_directive in &starting_field.tag {
+        if let Some(tag_name) = tag_directive.name.as_ref() {
+            let field = FieldRef::FoldSpecificField(count_field.clone());
+            if let Err(e) = tags.register_tag(tag_name, field, component_path, pos) {
+                errors.push(FrontendError::MultipleTagsWithSameName(tag_name.to_string(), pos));
+            }
+        } else {
+            errors.push(FrontendError::ExplicitTagNameRequired(
+                starting_field.name.as_ref().to_owned(),
+                pos,
+            ))
         }
+    }
 
-        let fold_specific_field = match transform_group.transform.kind {
-            TransformationKind::Count => FoldSpecificField {
-                fold_eid,
-                fold_root_vid: starting_vid,
-                kind: FoldSpecificFieldKind::Count,
-            },
+    if let Some(transform_group) = &fold_group.transform {
+        match transform_group.transform.kind {
+            TransformationKind::Count => {}
+            TransformationKind::Sum
+            | TransformationKind::Min
+            | TransformationKind::Max
+            | TransformationKind::Avg
+            | TransformationKind::Lowercase
+            | TransformationKind::Uppercase
+            | TransformationKind::Trim
+            | TransformationKind::Len
+            | TransformationKind::Abs
+            | TransformationKind::Add
+            | TransformationKind::Subtract
+            | TransformationKind::Multiply
+            | TransformationKind::Divide => {
+                errors.push(FrontendError::PropertyTransformOnFoldedEdge(
+                    transform_op_name(&transform_group.transform.kind).to_string(),
+                    starting_field.name.to_string(),
+                    pos,
+                ));
+                return Err(errors);
+            }
         };
-        let field_ref = FieldRef::FoldSpecificField(fold_specific_field.clone());
+        let values = match make_fold_value_chain(
+            component_path,
+            tags,
+            &starting_field.name,
+            count_field,
+            transform_group,
+            pos,
+        ) {
+            Ok(values) => values,
+            Err(e) => {
+                errors.push(e);
+                return Err(errors);
+            }
+        };
+
+        for FoldValue { field: fold_specific_field, transform_group } in values {
+            let field_ref = FieldRef::FoldSpecificField(fold_specific_field.clone());
+
+            for filter_directive in &transform_group.filter {
+                match make_filter_expr(
+                    schema,
+                    component_path,
+                    tags,
+                    starting_vid,
+                    fold_specific_field.kind.clone(),
+                    filter_directive,
+                    pos,
+                ) {
+                    Ok(filter) => post_filters.push(filter),
+                    Err(e) => errors.extend(e),
+                }
+            }
+            for output in &transform_group.output {
+                let final_output_name = match output.name.as_ref() {
+                    Some(explicit_name) => {
+                        output_handler.register_explicitly_named_output(
+                            explicit_name.clone(),
+                            field_ref.clone(),
+                            pos,
+                        );
+                        explicit_name.clone()
+                    }
+                    None => {
+                        let local_name = if starting_field.alias.is_some() {
+                            // The field has an alias already, so don't bother adding the edge name
+                            // to the output name.
+                            ""
+                        } else {
+                            // The field does not have an alias, so use the edge name as the base
+                            // of the name.
+                            starting_field.name.as_ref()
+                        };
+                        let suffixes: Vec<&str> = std::iter::once("count")
+                            .chain(
+                                fold_specific_field
+                                    .kind
+                                    .value_transforms()
+                                    .iter()
+                                    .map(|t| t.output_suffix()),
+                            )
+                            .collect();
+                        output_handler.register_locally_named_output(
+                            local_name,
+                            Some(&suffixes),
+                            field_ref.clone(),
+                            pos,
+                        )
+                    }
+                };
+
+                let prior_output_by_that_name = fold_specific_outputs
+                    .insert(final_output_name.clone(), fold_specific_field.kind.clone());
+                if let Some(prior_output_kind) = prior_output_by_that_name {
+                    errors.push(FrontendError::MultipleOutputsWithSameName(
+                        DuplicatedNamesConflict {
+                            duplicates: btreemap! {
+                                final_output_name.to_string() => vec![
+                                    (starting_field.name.to_string(), prior_output_kind.field_name().to_string()),
+                                    (starting_field.name.to_string(), fold_specific_field.kind.field_name().to_string()),
+                                ]
+                            },
+                        },
+                        output_handler.positions()[&final_output_name].clone(),
+                    ))
+                }
+            }
+            for tag_directive in &transform_group.tag {
+                let tag_name = tag_directive.name.as_ref().map(|x| x.as_ref());
+                if let Some(tag_name) = tag_name {
+                    let field = FieldRef::FoldSpecificField(fold_specific_field.clone());
+
+                    if let Err(e) = tags.register_tag(tag_name, field, component_path, pos) {
+                        errors.push(FrontendError::MultipleTagsWithSameName(
+                            tag_name.to_string(),
+                            pos,
+                        ));
+                    }
+                } else {
+                    errors.push(FrontendError::ExplicitTagNameRequired(
+                        starting_field.name.as_ref().to_owned(),
+                        pos,
+                    ))
+                }
+            }
+        }
+    }
+
+    for aggregation in fold_aggregations.aggregations {
+        let FoldAggregation { field, output_names, property, transform_group } = aggregation;
 
         for filter_directive in &transform_group.filter {
             match make_filter_expr(
@@ -1144,63 +2450,52 @@ where
                 component_path,
                 tags,
                 starting_vid,
-                fold_specific_field.kind,
+                field.kind.clone(),
                 filter_directive,
+                property.position,
             ) {
                 Ok(filter) => post_filters.push(filter),
                 Err(e) => errors.extend(e),
             }
         }
-        for output in &transform_group.output {
-            let final_output_name = match output.name.as_ref() {
-                Some(explicit_name) => {
-                    output_handler
-                        .register_explicitly_named_output(explicit_name.clone(), field_ref.clone());
-                    explicit_name.clone()
-                }
-                None => {
-                    let local_name = if starting_field.alias.is_some() {
-                        // The field has an alias already, so don't bother adding the edge name
-                        // to the output name.
-                        ""
-                    } else {
-                        // The field does not have an alias, so use the edge name as the base
-                        // of the name.
-                        starting_field.name.as_ref()
-                    };
-                    output_handler.register_locally_named_output(
-                        local_name,
-                        Some(&[fold_specific_field.kind.transform_suffix()]),
-                        field_ref.clone(),
-                    )
-                }
-            };
-
+        for output_name in output_names {
             let prior_output_by_that_name =
-                fold_specific_outputs.insert(final_output_name.clone(), fold_specific_field.kind);
+                fold_specific_outputs.insert(output_name.clone(), field.kind.clone());
             if let Some(prior_output_kind) = prior_output_by_that_name {
-                errors.push(FrontendError::MultipleOutputsWithSameName(DuplicatedNamesConflict {
-                    duplicates: btreemap! {
-                        final_output_name.to_string() => vec![
-                            (starting_field.name.to_string(), prior_output_kind.field_name().to_string()),
-                            (starting_field.name.to_string(), fold_specific_field.kind.field_name().to_string()),
-                        ]
-                    }
-                }))
+                let describe = |kind: &FoldSpecificFieldKind| {
+                    let name = kind.aggregated_field().map_or_else(
+                        || starting_field.name.to_string(),
+                        |field| field.field_name.to_string(),
+                    );
+                    (name, kind.field_name().to_string())
+                };
+                errors.push(FrontendError::MultipleOutputsWithSameName(
+                    DuplicatedNamesConflict {
+                        duplicates: btreemap! {
+                            output_name.to_string() => vec![
+                                describe(&prior_output_kind),
+                                describe(&field.kind),
+                            ]
+                        },
+                    },
+                    output_handler.positions()[&output_name].clone(),
+                ))
             }
         }
         for tag_directive in &transform_group.tag {
-            let tag_name = tag_directive.name.as_ref().map(|x| x.as_ref());
-            if let Some(tag_name) = tag_name {
-                let field = FieldRef::FoldSpecificField(fold_specific_field.clone());
-
-                if let Err(e) = tags.register_tag(tag_name, field, component_path) {
-                    errors.push(FrontendError::MultipleTagsWithSameName(tag_name.to_string()));
-                }
-            } else {
-                errors.push(FrontendError::ExplicitTagNameRequired(
-                    starting_field.name.as_ref().to_owned(),
-                ))
+            let tag_name = tag_directive
+                .name
+                .as_ref()
+                .expect("tag name was not explicitly specified")
+                .as_ref();
+            let tag_field = FieldRef::FoldSpecificField(field.clone());
+            if let Err(e) =
+                tags.register_tag(tag_name, tag_field, component_path, property.position)
+            {
+                errors.push(FrontendError::MultipleTagsWithSameName(
+                    tag_name.to_string(),
+                    property.position,
+                ));
             }
         }
     }
@@ -1219,6 +2514,7 @@ where
         imported_tags,
         post_filters,
         fold_specific_outputs,
+        absent,
     })
 }
 
@@ -1226,14 +2522,17 @@ where
 mod tests {
     use std::{
         fs,
+        num::NonZeroUsize,
         path::{Path, PathBuf},
         sync::OnceLock,
     };
 
+    use async_graphql_parser::Pos;
     use trustfall_filetests_macros::parameterize;
 
     use crate::{
-        frontend::make_ir_for_query,
+        frontend::{error::FrontendError, make_ir_for_query, parse_to_ir},
+        ir::{Eid, FieldValue, Type},
         schema::Schema,
         test_types::{TestIRQuery, TestIRQueryResult, TestParsedGraphQLQueryResult},
     };
@@ -1280,6 +2579,322 @@ mod tests {
         assert!(!get_recurses_schema().vertex_types.is_empty());
     }
 
+    #[test]
+    fn list_typed_edge_parameters() {
+        let schema = Schema::parse(
+            r#"
+schema {
+    query: RootSchemaQuery
+}
+directive @output(name: String) on FIELD
+
+type RootSchemaQuery {
+    Item(ids: [String!]!, nested: [[Int]] = 1, maybe: [String]): [Item!]
+}
+
+type Item {
+    name: String
+}"#,
+        )
+        .unwrap();
+        let string = |s: &str| FieldValue::String(s.into());
+        let list = |items: &[FieldValue]| FieldValue::List(items.into());
+
+        let parameters = |query: &str| {
+            let ir_query = parse_to_ir(&schema, format!("{{ {query} {{ name @output }} }}"));
+            ir_query.map(|ir_query| ir_query.root_parameters)
+        };
+
+        let root_parameters = parameters(r#"Item(ids: ["a", "b"], maybe: [null, "c"])"#).unwrap();
+        assert_eq!(Some(&list(&[string("a"), string("b")])), root_parameters.get("ids"));
+        assert_eq!(Some(&list(&[FieldValue::Null, string("c")])), root_parameters.get("maybe"));
+
+        // Single values are accepted where lists are expected, including in default values.
+        let root_parameters = parameters(r#"Item(ids: "a")"#).unwrap();
+        assert_eq!(Some(&list(&[string("a")])), root_parameters.get("ids"));
+        assert_eq!(Some(&list(&[list(&[FieldValue::Int64(1)])])), root_parameters.get("nested"));
+        assert_eq!(Some(&FieldValue::Null), root_parameters.get("maybe"));
+
+        let root_parameters =
+            parameters(r#"Item(ids: [], nested: [[1, null], null, []])"#).unwrap();
+        assert_eq!(Some(&list(&[])), root_parameters.get("ids"));
+        assert_eq!(
+            Some(&list(&[
+                list(&[FieldValue::Int64(1), FieldValue::Null]),
+                FieldValue::Null,
+                list(&[])
+            ])),
+            root_parameters.get("nested")
+        );
+
+        for (query, parameter, expected_type, value) in [
+            (
+                r#"Item(ids: ["a", null])"#,
+                "ids",
+                "[String!]!",
+                list(&[string("a"), FieldValue::Null]),
+            ),
+            (
+                r#"Item(ids: ["a", 1])"#,
+                "ids",
+                "[String!]!",
+                list(&[string("a"), FieldValue::Int64(1)]),
+            ),
+            (
+                r#"Item(ids: ["a"], nested: [1])"#,
+                "nested",
+                "[[Int]]",
+                list(&[FieldValue::Int64(1)]),
+            ),
+            (r#"Item(ids: [A])"#, "ids", "[String!]!", list(&[FieldValue::Enum("A".into())])),
+        ] {
+            let expected = FrontendError::InvalidEdgeParameterType(
+                parameter.to_string(),
+                "Item".to_string(),
+                expected_type.to_string(),
+                value,
+                Pos { line: 1, column: 3 },
+            );
+            assert_eq!(Err(expected), parameters(query), "{query}");
+        }
+    }
+
+    #[test]
+    fn query_outputs_metadata() {
+        let query = r#"
+{
+    Four {
+        value @output
+
+        predecessor @optional {
+            predecessor: value @output
+        }
+
+        multiple(max: 3) @fold @transform(op: "count") @output(name: "count") {
+            multiple: value @output
+
+            divisor @fold @transform(op: "count") @output(name: "divisors") {
+                divisor: name @output
+            }
+        }
+    }
+}"#;
+        let indexed_query = super::parse(get_numbers_schema(), query).expect("not a valid query");
+        let outputs = indexed_query.query_outputs();
+
+        let names: Vec<_> = outputs.names().map(|name| name.as_ref()).collect();
+        assert_eq!(vec!["count", "divisor", "divisors", "multiple", "predecessor", "value"], names);
+
+        let multiple_eid = Eid::new(NonZeroUsize::new(2).unwrap());
+        let divisor_eid = Eid::new(NonZeroUsize::new(3).unwrap());
+        let expected = [
+            ("count", "Int!", vec![], true),
+            ("divisor", "[[String]!]!", vec![multiple_eid, divisor_eid], false),
+            ("divisors", "[Int!]!", vec![multiple_eid], true),
+            ("multiple", "[Int]!", vec![multiple_eid], false),
+            ("predecessor", "Int", vec![], false),
+            ("value", "Int", vec![], false),
+        ];
+        assert_eq!(expected.len(), outputs.len());
+        for (name, value_type, folds, fold_specific) in expected {
+            let output = outputs.get(name).expect("no such output");
+            assert_eq!(name, output.name.as_ref());
+            assert_eq!(Type::parse(value_type).unwrap(), output.value_type, "{name}");
+            assert_eq!(folds, output.folds, "{name}");
+            assert_eq!(fold_specific, output.fold_specific, "{name}");
+            assert_eq!(!output.folds.is_empty(), output.is_folded(), "{name}");
+            assert_eq!(output.value_type.nullable(), output.is_nullable(), "{name}");
+        }
+        assert!(outputs.get("missing").is_none());
+    }
+
+    #[test]
+    fn query_outputs_result_types() {
+        let query = r#"
+{
+    Four {
+        value @output
+        vowelsInName @output
+
+        multiple(max: 3) @fold @transform(op: "count") @output(name: "count") {
+            multiple: name @output
+        }
+    }
+}"#;
+        let indexed_query = super::parse(get_numbers_schema(), query).expect("not a valid query");
+        let outputs = indexed_query.query_outputs();
+
+        let expected_schema = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer" },
+                "multiple": { "type": "array", "items": { "type": ["string", "null"] } },
+                "value": { "type": ["integer", "null"] },
+                "vowelsInName": {
+                    "type": ["array", "null"],
+                    "items": { "type": ["string", "null"] },
+                },
+            },
+            "required": ["count", "multiple", "value", "vowelsInName"],
+            "additionalProperties": false,
+        });
+        assert_eq!(expected_schema, outputs.result_json_schema());
+
+        let expected_interface = "\
+export interface Result {
+  count: number;
+  multiple: (string | null)[];
+  value: number | null;
+  vowelsInName: (string | null)[] | null;
+}
+";
+        assert_eq!(expected_interface, outputs.result_typescript_interface("Result"));
+    }
+
+    /// Formatting a query must not change its meaning, and formatting it again
+    /// must not change it at all.
+    mod formatting {
+        use std::{
+            fs,
+            path::{Path, PathBuf},
+        };
+
+        use trustfall_filetests_macros::parameterize;
+
+        use super::get_schema_by_name;
+        use crate::{
+            frontend::parse_to_ir, graphql_query::format_query, test_types::TestGraphQLQuery,
+        };
+
+        #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+        fn formatted_valid_queries(base: &Path, stem: &str) {
+            let mut input_path = PathBuf::from(base);
+            input_path.push(format!("{stem}.graphql.ron"));
+
+            let input_data = fs::read_to_string(input_path).unwrap();
+            let test_query: TestGraphQLQuery = ron::from_str(&input_data).unwrap();
+            let schema = get_schema_by_name(&test_query.schema_name);
+
+            let formatted = format_query(&test_query.query).expect("failed to format query");
+            assert_eq!(
+                parse_to_ir(schema, &test_query.query).expect("not a valid query"),
+                parse_to_ir(schema, &formatted).expect("formatted query is not valid"),
+                "{formatted}",
+            );
+            assert_eq!(formatted, format_query(&formatted).expect("failed to format query"));
+        }
+    }
+
+    /// Serializing a compiled query must load back into the same query.
+    mod compiled_queries {
+        use std::{
+            fs,
+            path::{Path, PathBuf},
+        };
+
+        use trustfall_filetests_macros::parameterize;
+
+        use super::get_schema_by_name;
+        use crate::{frontend::parse, ir::CompiledQuery, test_types::TestGraphQLQuery};
+
+        #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+        fn compiled_valid_queries(base: &Path, stem: &str) {
+            let mut input_path = PathBuf::from(base);
+            input_path.push(format!("{stem}.graphql.ron"));
+
+            let input_data = fs::read_to_string(input_path).unwrap();
+            let test_query: TestGraphQLQuery = ron::from_str(&input_data).unwrap();
+            let schema = get_schema_by_name(&test_query.schema_name);
+
+            let query = parse(schema, &test_query.query).expect("not a valid query");
+            let compiled = CompiledQuery::new(schema, query);
+            let loaded = CompiledQuery::from_bytes(schema, &compiled.to_bytes())
+                .expect("failed to load compiled query");
+            assert_eq!(compiled, loaded);
+        }
+    }
+
+    /// Printing a query's IR must produce canonically-formatted query text
+    /// that parses back into the same IR.
+    mod query_text {
+        use std::{
+            fs,
+            path::{Path, PathBuf},
+        };
+
+        use trustfall_filetests_macros::parameterize;
+
+        use super::get_schema_by_name;
+        use crate::{
+            frontend::parse_to_ir, graphql_query::format_query, test_types::TestGraphQLQuery,
+        };
+
+        #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+        fn printed_valid_queries(base: &Path, stem: &str) {
+            let mut input_path = PathBuf::from(base);
+            input_path.push(format!("{stem}.graphql.ron"));
+
+            let input_data = fs::read_to_string(input_path).unwrap();
+            let test_query: TestGraphQLQuery = ron::from_str(&input_data).unwrap();
+            let schema = get_schema_by_name(&test_query.schema_name);
+
+            let ir_query = parse_to_ir(schema, &test_query.query).expect("not a valid query");
+            let printed = ir_query.to_query_text();
+            assert_eq!(
+                ir_query,
+                parse_to_ir(schema, &printed).expect("printed query is not valid"),
+                "{printed}",
+            );
+            assert_eq!(printed, format_query(&printed).expect("failed to format query"));
+        }
+
+        #[test]
+        fn printed_query_layout() {
+            let schema = get_schema_by_name("numbers");
+            let query = r#"
+query($max: Int = 10) {
+    Number(max: 5) @limit(count: 3) {
+        value @tag(name: "start") @output @order(direction: "desc")
+        name @transform(op: "len") @filter(op: "<", value: ["$max"])
+
+        predecessor @optional {
+            ... on Prime {
+                pred: value @output
+            }
+        }
+        successor @recurse(depth: 2) {
+            value @filter(op: ">", value: ["%start"])
+        }
+        multiple(max: 3) @fold @transform(op: "count") @output(name: "multiples") {
+            value @transform(op: "sum") @output(name: "total")
+        }
+    }
+}"#;
+            let expected = r#"query($max: Int! = 10) {
+    Number(max: 5, min: 0) @limit(count: 3) {
+        value @tag(name: "value")
+        name @transform(op: "len") @filter(op: "<", value: ["$max"])
+        value @output(name: "value") @order(direction: "desc")
+        predecessor @optional {
+            ... on Prime {
+                value @output(name: "pred")
+            }
+        }
+        successor @recurse(depth: 2) {
+            value @filter(op: ">", value: ["%value"])
+        }
+        multiple(max: 3) @fold @transform(op: "count") @output(name: "multiples") {
+            value @transform(op: "sum") @output(name: "total")
+        }
+    }
+}
+"#;
+            let ir_query = parse_to_ir(schema, query).expect("not a valid query");
+            assert_eq!(expected, ir_query.to_query_text());
+        }
+    }
+
     #[parameterize("trustfall_core/test_data/tests/frontend_errors")]
     fn frontend_errors(base: &Path, stem: &str) {
         parameterizable_tester(base, stem, ".frontend-error.ron")
@@ -1295,6 +2910,16 @@ mod tests {
         parameterizable_tester(base, stem, ".ir.ron")
     }
 
+    fn get_schema_by_name(schema_name: &str) -> &'static Schema {
+        match schema_name {
+            "filesystem" => get_filesystem_schema(),
+            "numbers" => get_numbers_schema(),
+            "nullables" => get_nullables_schema(),
+            "recurses" => get_recurses_schema(),
+            _ => unimplemented!("unrecognized schema name: {schema_name:?}"),
+        }
+    }
+
     fn parameterizable_tester(base: &Path, stem: &str, check_file_suffix: &str) {
         let mut input_path = PathBuf::from(base);
         input_path.push(format!("{stem}.graphql-parsed.ron"));
@@ -1306,13 +2931,7 @@ mod tests {
         }
         let test_query = test_query.unwrap();
 
-        let schema: &Schema = match test_query.schema_name.as_str() {
-            "filesystem" => get_filesystem_schema(),
-            "numbers" => get_numbers_schema(),
-            "nullables" => get_nullables_schema(),
-            "recurses" => get_recurses_schema(),
-            _ => unimplemented!("unrecognized schema name: {:?}", test_query.schema_name),
-        };
+        let schema = get_schema_by_name(&test_query.schema_name);
 
         let mut check_path = PathBuf::from(base);
         check_path.push(format!("{stem}{check_file_suffix}"));
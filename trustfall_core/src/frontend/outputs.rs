@@ -1,6 +1,8 @@
 use std::{collections::BTreeMap, sync::Arc};
 
-use crate::ir::{FieldRef, Vid};
+use async_graphql_parser::Pos;
+
+use crate::ir::{FieldRef, OrderDirection, OutputOrdering, Vid};
 
 #[derive(Debug)]
 pub(super) struct OutputHandler<'query> {
@@ -10,6 +12,8 @@ pub(super) struct OutputHandler<'query> {
     root_prefix: Option<&'query str>,
     component_outputs_stack: Vec<BTreeMap<Arc<str>, Vec<FieldRef>>>,
     global_outputs: BTreeMap<Arc<str>, Vec<FieldRef>>,
+    positions: BTreeMap<Arc<str>, Vec<Pos>>,
+    orderings: Vec<(Pos, OutputOrdering)>,
 }
 
 impl<'query> OutputHandler<'query> {
@@ -21,6 +25,8 @@ impl<'query> OutputHandler<'query> {
             root_prefix,
             component_outputs_stack: Default::default(),
             global_outputs: Default::default(),
+            positions: Default::default(),
+            orderings: Default::default(),
         }
     }
 
@@ -69,7 +75,9 @@ impl<'query> OutputHandler<'query> {
         Arc::from(name)
     }
 
-    fn register_output(&mut self, name: Arc<str>, value: FieldRef) {
+    fn register_output(&mut self, name: Arc<str>, value: FieldRef, position: Pos) {
+        self.positions.entry(name.clone()).or_default().push(position);
+
         self.component_outputs_stack
             .last_mut()
             .expect("stack was unexpectedly empty")
@@ -85,12 +93,13 @@ impl<'query> OutputHandler<'query> {
         local_name: &str,
         transforms: Option<&[&str]>,
         value: FieldRef,
+        position: Pos,
     ) -> Arc<str> {
         let complete_name = self.make_output_name(
             local_name,
             transforms.map(|inner| inner.iter().copied()).into_iter().flatten(),
         );
-        self.register_output(complete_name.clone(), value);
+        self.register_output(complete_name.clone(), value, position);
         complete_name
     }
 
@@ -98,14 +107,42 @@ impl<'query> OutputHandler<'query> {
         &mut self,
         explicit_name: Arc<str>,
         value: FieldRef,
+        position: Pos,
+    ) {
+        self.register_output(explicit_name, value, position)
+    }
+
+    /// The positions of the fields producing each output, in the order they were registered.
+    pub(super) fn positions(&self) -> &BTreeMap<Arc<str>, Vec<Pos>> {
+        &self.positions
+    }
+
+    /// Record that results should be ordered by the given output.
+    ///
+    /// The position of the `@order` directive determines the significance of the ordering:
+    /// orderings that appear earlier in the query take precedence over later ones.
+    pub(super) fn register_ordering(
+        &mut self,
+        position: Pos,
+        output_name: Arc<str>,
+        direction: OrderDirection,
     ) {
-        self.register_output(explicit_name, value)
+        self.orderings.push((position, OutputOrdering { output_name, direction }));
+    }
+
+    pub(super) fn take_orderings(&mut self) -> Vec<OutputOrdering> {
+        let mut orderings = std::mem::take(&mut self.orderings);
+        orderings.sort_by_key(|(position, _)| *position);
+        orderings.into_iter().map(|(_, ordering)| ordering).collect()
     }
 
-    pub(crate) fn finish(self) -> BTreeMap<Arc<str>, Vec<FieldRef>> {
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn finish(
+        self,
+    ) -> (BTreeMap<Arc<str>, Vec<FieldRef>>, BTreeMap<Arc<str>, Vec<Pos>>) {
         assert!(self.vid_stack.is_empty());
         assert!(self.component_outputs_stack.is_empty());
 
-        self.global_outputs
+        (self.global_outputs, self.positions)
     }
 }
@@ -3,6 +3,8 @@ use std::{
     fmt::Debug,
 };
 
+use async_graphql_parser::Pos;
+
 use super::util::ComponentPath;
 use crate::{
     ir::{FieldRef, Vid},
@@ -21,11 +23,12 @@ pub(super) struct TagEntry<'a> {
     pub(super) name: &'a str,
     pub(super) field: FieldRef,
     pub(super) path: ComponentPath,
+    pub(super) position: Pos,
 }
 
 impl<'a> TagEntry<'a> {
-    fn new(name: &'a str, field: FieldRef, path: ComponentPath) -> Self {
-        Self { name, field, path }
+    fn new(name: &'a str, field: FieldRef, path: ComponentPath, position: Pos) -> Self {
+        Self { name, field, path, position }
     }
 }
 
@@ -40,8 +43,9 @@ impl<'a> TagHandler<'a> {
         name: &'a str,
         field: FieldRef,
         path: &ComponentPath,
+        position: Pos,
     ) -> Result<(), BTreeMapOccupiedError<'_, &'a str, TagEntry<'a>>> {
-        self.tags.insert_or_error(name, TagEntry::new(name, field, path.clone()))?;
+        self.tags.insert_or_error(name, TagEntry::new(name, field, path.clone(), position))?;
 
         Ok(())
     }
@@ -80,7 +84,9 @@ impl<'a> TagHandler<'a> {
                 let (component_root, imported_tags) =
                     self.component_imported_tags.get_mut(entry.path.len() - 1).unwrap();
                 assert_eq!(*component_root, importing_component_root);
-                imported_tags.push(entry.field.clone());
+                if !imported_tags.contains(&entry.field) {
+                    imported_tags.push(entry.field.clone());
+                }
             }
 
             self.used_tags.insert(entry.name);
@@ -92,9 +98,14 @@ impl<'a> TagHandler<'a> {
         }
     }
 
-    pub(super) fn finish(self) -> Result<(), BTreeSet<&'a str>> {
-        let unused_tags: BTreeSet<_> =
-            self.tags.keys().copied().filter(|x| !self.used_tags.contains(x)).collect();
+    /// Checks that all tags were used, returning the names and positions of any unused ones.
+    pub(super) fn finish(self) -> Result<(), BTreeMap<&'a str, Pos>> {
+        let unused_tags: BTreeMap<_, _> = self
+            .tags
+            .values()
+            .filter(|entry| !self.used_tags.contains(entry.name))
+            .map(|entry| (entry.name, entry.position))
+            .collect();
         if unused_tags.is_empty() {
             Ok(())
         } else {
@@ -1,9 +1,18 @@
-use std::ops::Index;
+use std::{collections::BTreeMap, ops::Index, sync::Arc};
 
-use async_graphql_parser::types::{BaseType, Type};
+use async_graphql_parser::{
+    types::{BaseType, Type},
+    Pos,
+};
 use async_graphql_value::Name;
 
-use crate::ir::Vid;
+use crate::{
+    graphql_query::{
+        directives::{OperatorArgument, TransformGroup},
+        query::{FieldConnection, FieldNode},
+    },
+    ir::Vid,
+};
 
 /// Retrieves the underlying type name by looping through any [list
 /// types](BaseType::List) until a [named type](Type) is found
@@ -17,6 +26,72 @@ pub(super) fn get_underlying_named_type(t: &Type) -> &Name {
     }
 }
 
+/// The positions of the fields and edges that use each variable, starting at the given edge.
+pub(super) fn variable_use_positions(
+    connection: &FieldConnection,
+    node: &FieldNode,
+) -> BTreeMap<Arc<str>, Vec<Pos>> {
+    let mut positions = BTreeMap::new();
+    add_variable_use_positions(&mut positions, connection, node);
+    positions
+}
+
+fn add_variable_use_positions(
+    positions: &mut BTreeMap<Arc<str>, Vec<Pos>>,
+    connection: &FieldConnection,
+    node: &FieldNode,
+) {
+    for variable in connection.variable_arguments.values() {
+        add_position(positions, variable, connection.position);
+    }
+    if let Some(group) = connection.fold.as_ref().and_then(|fold| fold.transform.as_ref()) {
+        add_transform_group_positions(positions, connection.position, group);
+    }
+
+    for argument in node.filter.iter().flat_map(|filter| filter.operation.arguments()) {
+        add_argument_position(positions, node.position, argument);
+    }
+    if let Some(group) = &node.transform_group {
+        add_transform_group_positions(positions, node.position, group);
+    }
+    for (child_connection, child) in &node.connections {
+        add_variable_use_positions(positions, child_connection, child);
+    }
+}
+
+fn add_transform_group_positions(
+    positions: &mut BTreeMap<Arc<str>, Vec<Pos>>,
+    pos: Pos,
+    group: &TransformGroup,
+) {
+    if let Some(argument) = &group.transform.argument {
+        add_argument_position(positions, pos, argument);
+    }
+    for argument in group.filter.iter().flat_map(|filter| filter.operation.arguments()) {
+        add_argument_position(positions, pos, argument);
+    }
+    if let Some(retransform) = &group.retransform {
+        add_transform_group_positions(positions, pos, retransform);
+    }
+}
+
+fn add_argument_position(
+    positions: &mut BTreeMap<Arc<str>, Vec<Pos>>,
+    pos: Pos,
+    argument: &OperatorArgument,
+) {
+    if let OperatorArgument::VariableRef(name) = argument {
+        add_position(positions, name, pos);
+    }
+}
+
+fn add_position(positions: &mut BTreeMap<Arc<str>, Vec<Pos>>, name: &Arc<str>, pos: Pos) {
+    let uses = positions.entry(name.clone()).or_default();
+    if uses.last() != Some(&pos) {
+        uses.push(pos);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub(super) struct ComponentPath {
     path: Vec<Vid>,
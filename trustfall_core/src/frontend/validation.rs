@@ -44,6 +44,7 @@ fn validate_field<'a>(
         if !node.connections.is_empty() {
             return Err(FrontendError::PropertyMetaFieldUsedAsEdge(
                 TYPENAME_META_FIELD.to_string(),
+                node.position,
             ));
         }
 
@@ -58,6 +59,7 @@ fn validate_field<'a>(
             path.push(&node.name);
             FrontendError::ValidationError(ValidationError::NonExistentPath(
                 path.iter().map(|x| x.to_string()).collect(),
+                node.position,
             ))
         })?;
 
@@ -66,32 +68,30 @@ fn validate_field<'a>(
     let pre_coercion_type_name = get_underlying_named_type(&field_def.ty.node).as_ref();
     let field_type_name = if let Some(coerced) = &node.coerced_to {
         let pre_coercion_type_definition = &schema.vertex_types[pre_coercion_type_name];
-        if let TypeKind::Interface(_) = &pre_coercion_type_definition.kind {
+        if let TypeKind::Interface(_) | TypeKind::Union(_) = &pre_coercion_type_definition.kind {
         } else {
-            // Only interface types may be coerced into other types. This is not an interface.
+            // Only interface and union types may be coerced into other types.
+            // This is neither an interface nor a union.
             return Err(FrontendError::ValidationError(
                 ValidationError::CannotCoerceNonInterfaceType(
                     pre_coercion_type_name.to_string(),
                     coerced.to_string(),
+                    node.position,
                 ),
             ));
         }
 
-        if let Some(post_coercion_type_definition) = schema.vertex_types.get(coerced) {
-            let implemented_interfaces = match &post_coercion_type_definition.kind {
-                TypeKind::Object(o) => &o.implements,
-                TypeKind::Interface(i) => &i.implements,
-                TypeKind::Scalar
-                | TypeKind::Union(_)
-                | TypeKind::Enum(_)
-                | TypeKind::InputObject(_) => unreachable!(),
-            };
-            if !implemented_interfaces.iter().any(|x| x.node.as_ref() == pre_coercion_type_name) {
-                // The specified coerced-to type does not implement the source interface.
+        if schema.vertex_types.contains_key(coerced) {
+            if coerced.as_ref() == pre_coercion_type_name
+                || !schema.is_named_type_subtype(pre_coercion_type_name, coerced)
+            {
+                // The specified coerced-to type does not implement the source interface,
+                // and is not a member of the source union.
                 return Err(FrontendError::ValidationError(
                     ValidationError::CannotCoerceToUnrelatedType(
                         pre_coercion_type_name.to_string(),
                         coerced.to_string(),
+                        node.position,
                     ),
                 ));
             }
@@ -99,6 +99,7 @@ fn validate_field<'a>(
             // The coerced-to type is not part of the schema.
             return Err(FrontendError::ValidationError(ValidationError::NonExistentType(
                 coerced.to_string(),
+                node.position,
             )));
         }
 
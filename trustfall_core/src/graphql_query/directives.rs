@@ -2,26 +2,26 @@
 //! This module contains the logic for parsing Trustfall query directives.
 use std::{collections::HashSet, num::NonZeroUsize, sync::Arc};
 
-use async_graphql_parser::{types::Directive, Positioned};
+use async_graphql_parser::{types::Directive, Pos, Positioned};
 use async_graphql_value::Value;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-use crate::ir::{Operation, TransformationKind};
+use crate::ir::{Operation, OrderDirection, TransformationKind};
 
 use super::error::ParseError;
 
 /// A value passed as an operator argument in a Trustfall query, for example as in
-/// the `value` array of the `@filter` directive (see [FilterDirective]).
+/// the `value` array of the `@filter` directive.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OperatorArgument {
-    /// Reference to a variable provided to the query. Variable names are always
-    /// prefixed with `$`.
+    /// Reference to a variable provided to the query. In query text, variable names are
+    /// always prefixed with `$`, but the prefix is not part of the name stored here.
     VariableRef(Arc<str>),
 
-    /// Reference to a tagged value encountered elsewhere
-    /// in the query and marked with the `@tag` directive -- see [TagDirective].
-    /// Tag names are always prefixed with `%`.
+    /// Reference to a tagged value encountered elsewhere in the query and marked with
+    /// the `@tag` directive. In query text, tag names are always prefixed with `%`,
+    /// but the prefix is not part of the name stored here.
     TagRef(Arc<str>),
 }
 
@@ -77,82 +77,14 @@ impl TryFrom<&Positioned<Directive>> for FilterDirective {
             }
         }
 
-        let mut parsed_args: SmallVec<[OperatorArgument; 2]> = if let Some(value_argument) =
-            value.node.get_argument("value")
-        {
-            let value_list = match &value_argument.node {
-                Value::List(list) => Ok(list),
-                Value::String(argument_value) => Err(ParseError::FilterExpectsListNotString(
-                    op.to_owned(),
-                    argument_value.to_owned(),
-                    value_argument.pos,
-                )),
-                _ => Err(ParseError::InappropriateTypeForDirectiveArgument(
-                    "@filter".to_owned(),
-                    "value".to_owned(),
-                    value_argument.pos,
-                )),
-            }?;
-            value_list
-                    .iter()
-                    .map(|v| match v {
-                        Value::String(s) => {
-                            let (prefix, name) = if s.starts_with('$') || s.starts_with('%') {
-                                s.split_at(1)
-                            } else {
-                                return Err(ParseError::InvalidFilterOperandName(
-                                    s.to_owned(),
-                                    format!("Filter argument was expected to start with '$' or '%' but did not: {s}"),
-                                    value_argument.pos,
-                                ));
-                            };
-
-                            if name.is_empty() {
-                                return Err(ParseError::InvalidFilterOperandName(
-                                    s.to_owned(),
-                                    format!("Filter argument is empty after '{}' prefix.", prefix),
-                                    value_argument.pos,
-                                ));
-                            }
-
-                            let first_char = name.chars().next().unwrap();
-                            if  !first_char.is_ascii_alphabetic() && first_char != '_' {
-                                return Err(ParseError::InvalidFilterOperandName(
-                                    s.to_owned(),
-                                    format!("Filter argument names must start with an ASCII letter or underscore character: {name}"),
-                                    value_argument.pos))
-                            }
-
-                            if name.chars().any(|c| !c.is_ascii_alphanumeric() && c != '_')
-                            {
-                                return Err(ParseError::InvalidFilterOperandName(
-                                    s.to_owned(),
-                                    format!("Filter argument names must only contain ASCII alphanumerics or underscore characters: {name}"),
-                                    value_argument.pos,
-                                ));
-                            }
-
-                            if s.starts_with('$') {
-                                Ok(OperatorArgument::VariableRef(name.into()))
-                            } else if s.starts_with('%') {
-                                Ok(OperatorArgument::TagRef(name.into()))
-                            } else {
-                                unreachable!()
-                            }
-                        }
-                        _ => Err(ParseError::InappropriateTypeForDirectiveArgument(
-                            "@filter".to_owned(),
-                            "value".to_owned(),
-                            value_argument.pos,
-                        )),
-                    })
-                    .collect::<Result<SmallVec<_>, _>>()?
-        } else {
-            SmallVec::new()
+        let mut parsed_args = match value.node.get_argument("value") {
+            Some(value_argument) => parse_operator_arguments("@filter", op, value_argument)?,
+            None => SmallVec::new(),
         };
 
         let expected_arg_count = match op.as_ref() {
             "is_null" | "is_not_null" => 0,
+            "between" | "not_between" => 2,
             _ => 1,
         };
         if parsed_args.len() != expected_arg_count {
@@ -175,6 +107,15 @@ impl TryFrom<&Positioned<Directive>> for FilterDirective {
             "<=" => Ok(Operation::LessThanOrEqual((), parsed_args.pop().unwrap())),
             ">" => Ok(Operation::GreaterThan((), parsed_args.pop().unwrap())),
             ">=" => Ok(Operation::GreaterThanOrEqual((), parsed_args.pop().unwrap())),
+            "between" | "not_between" => {
+                let upper = parsed_args.pop().unwrap();
+                let lower = parsed_args.pop().unwrap();
+                if op == "between" {
+                    Ok(Operation::Between((), lower, upper))
+                } else {
+                    Ok(Operation::NotBetween((), lower, upper))
+                }
+            }
             "contains" => Ok(Operation::Contains((), parsed_args.pop().unwrap())),
             "not_contains" => Ok(Operation::NotContains((), parsed_args.pop().unwrap())),
             "one_of" => Ok(Operation::OneOf((), parsed_args.pop().unwrap())),
@@ -187,6 +128,26 @@ impl TryFrom<&Positioned<Directive>> for FilterDirective {
             "not_has_substring" => Ok(Operation::NotHasSubstring((), parsed_args.pop().unwrap())),
             "regex" => Ok(Operation::RegexMatches((), parsed_args.pop().unwrap())),
             "not_regex" => Ok(Operation::NotRegexMatches((), parsed_args.pop().unwrap())),
+            "=i" => Ok(Operation::EqualsIgnoreCase((), parsed_args.pop().unwrap())),
+            "!=i" => Ok(Operation::NotEqualsIgnoreCase((), parsed_args.pop().unwrap())),
+            "has_prefix_i" => Ok(Operation::HasPrefixIgnoreCase((), parsed_args.pop().unwrap())),
+            "not_has_prefix_i" => {
+                Ok(Operation::NotHasPrefixIgnoreCase((), parsed_args.pop().unwrap()))
+            }
+            "has_suffix_i" => Ok(Operation::HasSuffixIgnoreCase((), parsed_args.pop().unwrap())),
+            "not_has_suffix_i" => {
+                Ok(Operation::NotHasSuffixIgnoreCase((), parsed_args.pop().unwrap()))
+            }
+            "has_substring_i" => {
+                Ok(Operation::HasSubstringIgnoreCase((), parsed_args.pop().unwrap()))
+            }
+            "not_has_substring_i" => {
+                Ok(Operation::NotHasSubstringIgnoreCase((), parsed_args.pop().unwrap()))
+            }
+            "regex_i" => Ok(Operation::RegexMatchesIgnoreCase((), parsed_args.pop().unwrap())),
+            "not_regex_i" => {
+                Ok(Operation::NotRegexMatchesIgnoreCase((), parsed_args.pop().unwrap()))
+            }
             unknown_op_name => Err(ParseError::UnsupportedFilterOperator(
                 unknown_op_name.to_owned(),
                 op_argument.pos,
@@ -196,6 +157,87 @@ impl TryFrom<&Positioned<Directive>> for FilterDirective {
     }
 }
 
+/// Parse the `value` argument of a directive like `@filter`, which is a list of
+/// variable and tag references such as `["$some_variable", "%some_tag"]`.
+fn parse_operator_arguments(
+    directive_name: &str,
+    op: &str,
+    value_argument: &Positioned<Value>,
+) -> Result<SmallVec<[OperatorArgument; 2]>, ParseError> {
+    // Operand errors are described in the same way for every directive, e.g. "Filter argument".
+    let kind = if directive_name == "@filter" { "Filter" } else { "Transform" };
+
+    let value_list = match &value_argument.node {
+        Value::List(list) => Ok(list),
+        Value::String(argument_value) if directive_name == "@filter" => {
+            Err(ParseError::FilterExpectsListNotString(
+                op.to_owned(),
+                argument_value.to_owned(),
+                value_argument.pos,
+            ))
+        }
+        _ => Err(ParseError::InappropriateTypeForDirectiveArgument(
+            directive_name.to_owned(),
+            "value".to_owned(),
+            value_argument.pos,
+        )),
+    }?;
+    value_list
+        .iter()
+        .map(|v| match v {
+            Value::String(s) => {
+                let (prefix, name) = if s.starts_with('$') || s.starts_with('%') {
+                    s.split_at(1)
+                } else {
+                    return Err(ParseError::InvalidFilterOperandName(
+                        s.to_owned(),
+                        format!("{kind} argument was expected to start with '$' or '%' but did not: {s}"),
+                        value_argument.pos,
+                    ));
+                };
+
+                if name.is_empty() {
+                    return Err(ParseError::InvalidFilterOperandName(
+                        s.to_owned(),
+                        format!("{kind} argument is empty after '{}' prefix.", prefix),
+                        value_argument.pos,
+                    ));
+                }
+
+                let first_char = name.chars().next().unwrap();
+                if !first_char.is_ascii_alphabetic() && first_char != '_' {
+                    return Err(ParseError::InvalidFilterOperandName(
+                        s.to_owned(),
+                        format!("{kind} argument names must start with an ASCII letter or underscore character: {name}"),
+                        value_argument.pos,
+                    ));
+                }
+
+                if name.chars().any(|c| !c.is_ascii_alphanumeric() && c != '_') {
+                    return Err(ParseError::InvalidFilterOperandName(
+                        s.to_owned(),
+                        format!("{kind} argument names must only contain ASCII alphanumerics or underscore characters: {name}"),
+                        value_argument.pos,
+                    ));
+                }
+
+                if s.starts_with('$') {
+                    Ok(OperatorArgument::VariableRef(name.into()))
+                } else if s.starts_with('%') {
+                    Ok(OperatorArgument::TagRef(name.into()))
+                } else {
+                    unreachable!()
+                }
+            }
+            _ => Err(ParseError::InappropriateTypeForDirectiveArgument(
+                directive_name.to_owned(),
+                "value".to_owned(),
+                value_argument.pos,
+            )),
+        })
+        .collect()
+}
+
 /// A Trustfall `@output` directive.
 ///
 /// For example, the following Trustfall and Rust would be equivalent:
@@ -279,12 +321,19 @@ impl TryFrom<&Positioned<Directive>> for OutputDirective {
 /// and
 ///
 /// ```ignore
-/// TransformDirective { kind: TransformationKind::Count }
+/// TransformDirective { kind: TransformationKind::Count, argument: None }
 /// ```
+///
+/// Arithmetic transforms take an operand in their `value` argument,
+/// as in `@transform(op: "*", value: ["$factor"])`.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub(crate) struct TransformDirective {
     /// The `op` in a GraphQL `@transform`
     pub kind: TransformationKind,
+
+    /// The operand in the `value` of a GraphQL `@transform`, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument: Option<OperatorArgument>,
 }
 
 impl TryFrom<&Positioned<Directive>> for TransformDirective {
@@ -292,10 +341,16 @@ impl TryFrom<&Positioned<Directive>> for TransformDirective {
 
     fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
         let mut seen_op: bool = false;
+        let mut seen_value: bool = false;
         for (arg_name, _) in &value.node.arguments {
-            if arg_name.node.as_ref() == "op" {
-                if !seen_op {
-                    seen_op = true;
+            let seen = match arg_name.node.as_ref() {
+                "op" => Some(&mut seen_op),
+                "value" => Some(&mut seen_value),
+                _ => None,
+            };
+            if let Some(seen) = seen {
+                if !*seen {
+                    *seen = true;
                 } else {
                     return Err(ParseError::DuplicatedDirectiveArgument(
                         "@transform".to_owned(),
@@ -333,6 +388,19 @@ impl TryFrom<&Positioned<Directive>> for TransformDirective {
 
         let kind = match transform_argument.as_ref() {
             "count" => TransformationKind::Count,
+            "sum" => TransformationKind::Sum,
+            "min" => TransformationKind::Min,
+            "max" => TransformationKind::Max,
+            "avg" => TransformationKind::Avg,
+            "lowercase" => TransformationKind::Lowercase,
+            "uppercase" => TransformationKind::Uppercase,
+            "trim" => TransformationKind::Trim,
+            "len" => TransformationKind::Len,
+            "abs" => TransformationKind::Abs,
+            "+" => TransformationKind::Add,
+            "-" => TransformationKind::Subtract,
+            "*" => TransformationKind::Multiply,
+            "/" => TransformationKind::Divide,
             _ => {
                 return Err(ParseError::UnsupportedTransformOperator(
                     transform_argument.to_string(),
@@ -341,10 +409,255 @@ impl TryFrom<&Positioned<Directive>> for TransformDirective {
             }
         };
 
-        Ok(Self { kind })
+        let value_argument = value.node.get_argument("value");
+        let mut parsed_args = match value_argument {
+            Some(value_argument) => {
+                parse_operator_arguments("@transform", &transform_argument, value_argument)?
+            }
+            None => SmallVec::new(),
+        };
+
+        check_transform_argument_count(
+            &kind,
+            parsed_args.len(),
+            value_argument.map_or(value.pos, |arg| arg.pos),
+        )?;
+
+        Ok(Self { kind, argument: parsed_args.pop() })
     }
 }
 
+/// Arithmetic transforms take exactly one operand, and all other transforms take none.
+pub(crate) fn check_transform_argument_count(
+    kind: &TransformationKind,
+    arg_count: usize,
+    pos: Pos,
+) -> Result<(), ParseError> {
+    let expected_arg_count = match kind {
+        TransformationKind::Add
+        | TransformationKind::Subtract
+        | TransformationKind::Multiply
+        | TransformationKind::Divide => 1,
+        _ => 0,
+    };
+    if arg_count != expected_arg_count {
+        return Err(ParseError::OtherError(
+            format!(
+                "Transform argument count mismatch: expected {expected_arg_count} but found {arg_count}"
+            ),
+            pos,
+        ));
+    }
+    Ok(())
+}
+
+/// A Trustfall `@order` directive.
+///
+/// For example, the following Trustfall and Rust would be equivalent:
+/// ```graphql
+/// @order(direction: "desc")
+/// ```
+///
+/// and
+///
+/// ```ignore
+/// OrderDirective { direction: OrderDirection::Descending }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct OrderDirective {
+    pub direction: OrderDirection,
+}
+
+impl TryFrom<&Positioned<Directive>> for OrderDirective {
+    type Error = ParseError;
+
+    fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
+        let mut seen_direction: bool = false;
+        for (arg_name, _) in &value.node.arguments {
+            if arg_name.node.as_ref() == "direction" {
+                if !seen_direction {
+                    seen_direction = true;
+                } else {
+                    return Err(ParseError::DuplicatedDirectiveArgument(
+                        "@order".to_owned(),
+                        arg_name.node.to_string(),
+                        arg_name.pos,
+                    ));
+                }
+            } else {
+                return Err(ParseError::UnrecognizedDirectiveArgument(
+                    "@order".to_owned(),
+                    arg_name.node.to_string(),
+                    arg_name.pos,
+                ));
+            }
+        }
+
+        let direction_argument_node = value.node.get_argument("direction").ok_or_else(|| {
+            ParseError::MissingRequiredDirectiveArgument(
+                "@order".to_owned(),
+                "direction".to_owned(),
+                value.pos,
+            )
+        })?;
+
+        let direction = match &direction_argument_node.node {
+            Value::String(s) => match s.as_str() {
+                "asc" => OrderDirection::Ascending,
+                "desc" => OrderDirection::Descending,
+                unknown => {
+                    return Err(ParseError::UnsupportedOrderDirection(
+                        unknown.to_owned(),
+                        direction_argument_node.pos,
+                    ))
+                }
+            },
+            _ => {
+                return Err(ParseError::InappropriateTypeForDirectiveArgument(
+                    "@order".to_owned(),
+                    "direction".to_owned(),
+                    direction_argument_node.pos,
+                ))
+            }
+        };
+
+        Ok(Self { direction })
+    }
+}
+
+/// A Trustfall `@limit` directive, applied to the root field of the query.
+///
+/// For example, the following Trustfall and Rust would be equivalent:
+/// ```graphql
+/// @limit(count: 10)
+/// ```
+///
+/// and
+///
+/// ```ignore
+/// LimitDirective { count: NonZeroUsize::new(10usize).unwrap() }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct LimitDirective {
+    pub count: NonZeroUsize,
+}
+
+impl TryFrom<&Positioned<Directive>> for LimitDirective {
+    type Error = ParseError;
+
+    fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
+        let (count, pos) = parse_count_argument("@limit", value)?;
+        let count = NonZeroUsize::new(count).ok_or_else(|| {
+            ParseError::InappropriateTypeForDirectiveArgument(
+                "@limit".to_owned(),
+                "count".to_owned(),
+                pos,
+            )
+        })?;
+
+        Ok(Self { count })
+    }
+}
+
+/// A Trustfall `@offset` directive, applied to the root field of the query.
+///
+/// For example, the following Trustfall and Rust would be equivalent:
+/// ```graphql
+/// @offset(count: 20)
+/// ```
+///
+/// and
+///
+/// ```ignore
+/// OffsetDirective { count: 20 }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct OffsetDirective {
+    pub count: usize,
+}
+
+impl TryFrom<&Positioned<Directive>> for OffsetDirective {
+    type Error = ParseError;
+
+    fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
+        let (count, _) = parse_count_argument("@offset", value)?;
+
+        Ok(Self { count })
+    }
+}
+
+/// A Trustfall `@dedupe` directive, applied to the root field of the query.
+///
+/// It requests that the query produce only distinct result rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct DedupeDirective {}
+
+impl TryFrom<&Positioned<Directive>> for DedupeDirective {
+    type Error = ParseError;
+
+    fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
+        if let Some((first_arg_name, _)) = value.node.arguments.first() {
+            // Found arguments but this directive doesn't take any.
+            return Err(ParseError::UnrecognizedDirectiveArgument(
+                "@dedupe".into(),
+                first_arg_name.node.to_string(),
+                first_arg_name.pos,
+            ));
+        }
+
+        Ok(Self {})
+    }
+}
+
+/// Parse the required non-negative integer `count` argument of a directive,
+/// returning both the value and the position at which it was specified.
+fn parse_count_argument(
+    directive_name: &str,
+    value: &Positioned<Directive>,
+) -> Result<(usize, Pos), ParseError> {
+    let mut seen_count: bool = false;
+    for (arg_name, _) in &value.node.arguments {
+        if arg_name.node.as_ref() == "count" {
+            if !seen_count {
+                seen_count = true;
+            } else {
+                return Err(ParseError::DuplicatedDirectiveArgument(
+                    directive_name.to_owned(),
+                    arg_name.node.to_string(),
+                    arg_name.pos,
+                ));
+            }
+        } else {
+            return Err(ParseError::UnrecognizedDirectiveArgument(
+                directive_name.to_owned(),
+                arg_name.node.to_string(),
+                arg_name.pos,
+            ));
+        }
+    }
+
+    let count_argument = value.node.get_argument("count").ok_or_else(|| {
+        ParseError::MissingRequiredDirectiveArgument(
+            directive_name.to_owned(),
+            "count".to_owned(),
+            value.pos,
+        )
+    })?;
+    let count = match &count_argument.node {
+        Value::Number(n) => n.as_u64().and_then(|v| usize::try_from(v).ok()),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        ParseError::InappropriateTypeForDirectiveArgument(
+            directive_name.to_owned(),
+            "count".to_owned(),
+            count_argument.pos,
+        )
+    })?;
+
+    Ok((count, count_argument.pos))
+}
+
 /// A Trustfall `@tag` directive.
 ///
 /// For example, the following Trustfall and Rust would be equivalent:
@@ -418,17 +731,79 @@ impl TryFrom<&Positioned<Directive>> for TagDirective {
 }
 
 /// A Trustfall `@optional` directive.
+///
+/// For example, the following Trustfall and Rust would be equivalent:
+/// ```graphql
+/// @optional(group: "%group_name")
+/// ```
+///
+/// and
+///
+/// ```ignore
+/// OptionalDirective { group: Some(Arc::new("%group_name"))}
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-pub(crate) struct OptionalDirective {}
+pub(crate) struct OptionalDirective {
+    /// Sibling `@optional` edges in the same group either all exist, or are all `null`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<Arc<str>>,
+}
 
 impl TryFrom<&Positioned<Directive>> for OptionalDirective {
     type Error = ParseError;
 
+    fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
+        let mut seen_group: bool = false;
+        for (arg_name, _) in &value.node.arguments {
+            if arg_name.node.as_ref() == "group" {
+                if !seen_group {
+                    seen_group = true;
+                } else {
+                    return Err(ParseError::DuplicatedDirectiveArgument(
+                        "@optional".to_owned(),
+                        arg_name.node.to_string(),
+                        arg_name.pos,
+                    ));
+                }
+            } else {
+                return Err(ParseError::UnrecognizedDirectiveArgument(
+                    "@optional".to_owned(),
+                    arg_name.node.to_string(),
+                    arg_name.pos,
+                ));
+            }
+        }
+
+        let group = match value.node.get_argument("group") {
+            None => None,
+            Some(group_argument) => match &group_argument.node {
+                Value::String(s) => Some(Arc::from(s.as_str())),
+                _ => {
+                    return Err(ParseError::InappropriateTypeForDirectiveArgument(
+                        "@optional".to_owned(),
+                        "group".to_owned(),
+                        group_argument.pos,
+                    ))
+                }
+            },
+        };
+
+        Ok(Self { group })
+    }
+}
+
+/// A Trustfall `@fold` directive.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct FoldDirective {}
+
+impl TryFrom<&Positioned<Directive>> for FoldDirective {
+    type Error = ParseError;
+
     fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
         if let Some((first_arg_name, _)) = value.node.arguments.first() {
             // Found arguments but this directive doesn't take any.
             return Err(ParseError::UnrecognizedDirectiveArgument(
-                "@optional".into(),
+                "@fold".into(),
                 first_arg_name.node.to_string(),
                 first_arg_name.pos,
             ));
@@ -438,18 +813,22 @@ impl TryFrom<&Positioned<Directive>> for OptionalDirective {
     }
 }
 
-/// A Trustfall `@fold` directive.
+/// A Trustfall `@absent` directive.
+///
+/// It is shorthand for `@fold @transform(op: "count") @filter(op: "=", value: [0])`:
+/// an edge with `@absent` requires that no vertices at the other end of the edge
+/// match the edge's subquery.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-pub(crate) struct FoldDirective {}
+pub(crate) struct AbsentDirective {}
 
-impl TryFrom<&Positioned<Directive>> for FoldDirective {
+impl TryFrom<&Positioned<Directive>> for AbsentDirective {
     type Error = ParseError;
 
     fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
         if let Some((first_arg_name, _)) = value.node.arguments.first() {
             // Found arguments but this directive doesn't take any.
             return Err(ParseError::UnrecognizedDirectiveArgument(
-                "@fold".into(),
+                "@absent".into(),
                 first_arg_name.node.to_string(),
                 first_arg_name.pos,
             ));
@@ -471,43 +850,60 @@ impl TryFrom<&Positioned<Directive>> for FoldDirective {
 /// ```ignore
 /// RecurseDirective { depth: NonZeroUsize::new(1usize)}
 /// ```
+///
+/// Omitting the `depth` argument makes the recursion unbounded,
+/// represented as a `None` depth. With `dedupe: true`, each recursion
+/// skips vertices it has already visited.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub(crate) struct RecurseDirective {
-    pub depth: NonZeroUsize,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "crate::ir::recursion_depth")]
+    pub depth: Option<NonZeroUsize>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dedupe: bool,
 }
 
 impl TryFrom<&Positioned<Directive>> for RecurseDirective {
     type Error = ParseError;
 
     fn try_from(value: &Positioned<Directive>) -> Result<Self, Self::Error> {
-        let mut seen_name: bool = false;
+        let mut seen_names: Vec<&str> = vec![];
         for (arg_name, _) in &value.node.arguments {
-            if arg_name.node.as_ref() == "depth" {
-                if !seen_name {
-                    seen_name = true;
-                } else {
-                    return Err(ParseError::DuplicatedDirectiveArgument(
-                        "@recurse".to_owned(),
-                        arg_name.node.to_string(),
-                        arg_name.pos,
-                    ));
-                }
-            } else {
+            let name = arg_name.node.as_str();
+            if !matches!(name, "depth" | "dedupe") {
                 return Err(ParseError::UnrecognizedDirectiveArgument(
                     "@recurse".to_owned(),
                     arg_name.node.to_string(),
                     arg_name.pos,
                 ));
+            } else if seen_names.contains(&name) {
+                return Err(ParseError::DuplicatedDirectiveArgument(
+                    "@recurse".to_owned(),
+                    arg_name.node.to_string(),
+                    arg_name.pos,
+                ));
             }
+            seen_names.push(name);
         }
 
-        let depth_argument = value.node.get_argument("depth").ok_or_else(|| {
-            ParseError::MissingRequiredDirectiveArgument(
-                "@recurse".to_owned(),
-                "depth".to_owned(),
-                value.pos,
-            )
-        })?;
+        let dedupe = match value.node.get_argument("dedupe") {
+            None => false,
+            Some(dedupe_argument) => match &dedupe_argument.node {
+                Value::Boolean(dedupe) => *dedupe,
+                _ => {
+                    return Err(ParseError::InappropriateTypeForDirectiveArgument(
+                        "@recurse".to_owned(),
+                        "dedupe".to_owned(),
+                        dedupe_argument.pos,
+                    ))
+                }
+            },
+        };
+
+        let Some(depth_argument) = value.node.get_argument("depth") else {
+            // Without a depth limit, the recursion continues until it runs out of vertices.
+            return Ok(Self { depth: None, dedupe });
+        };
         let depth = match &depth_argument.node {
             Value::Number(n) => {
                 n.as_u64().and_then(|v| NonZeroUsize::new(v as usize)).ok_or_else(|| {
@@ -525,7 +921,7 @@ impl TryFrom<&Positioned<Directive>> for RecurseDirective {
             )),
         }?;
 
-        Ok(Self { depth })
+        Ok(Self { depth: Some(depth), dedupe })
     }
 }
 
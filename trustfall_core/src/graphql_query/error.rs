@@ -63,6 +63,9 @@ pub enum ParseError {
     #[error("Unrecognized transform operator: {0}")]
     UnsupportedTransformOperator(String, Pos),
 
+    #[error("Unrecognized @order direction: \"{0}\". Expected either \"asc\" or \"desc\".")]
+    UnsupportedOrderDirection(String, Pos),
+
     #[error("Output name \"{0}\" contains invalid characters: {1:?}")]
     InvalidOutputName(String, Vec<char>, Pos),
 
@@ -89,16 +92,60 @@ pub enum ParseError {
     DuplicatedEdgeParameter(String, String, Pos),
 
     #[error(
-        "Found GraphQL query variable definition(s). \
+        "Found GraphQL query variable definition(s) without a default value. \
     These are not necessary since variables are defined implicitly, \
-    and must be removed."
+    and must be removed or given a default value."
     )]
     VariableDefinitionInQuery(Pos),
 
+    #[error("Variable ${0} is defined more than once.")]
+    DuplicatedVariableDefinition(String, Pos),
+
+    #[error("Variable ${0} has an invalid default value: {1}")]
+    InvalidVariableDefaultValue(String, Value, Pos),
+
     #[error("Unexpected error: {0}")]
     OtherError(String, Pos),
 }
 
+impl ParseError {
+    /// The position in the query text where the error was found, if known.
+    pub fn position(&self) -> Option<Pos> {
+        match self {
+            ParseError::InvalidGraphQL(e) => e.positions().next(),
+            ParseError::UnrecognizedDirective(_, pos)
+            | ParseError::UnsupportedDirectivePosition(_, _, pos)
+            | ParseError::MissingRequiredDirectiveArgument(_, _, pos)
+            | ParseError::UnrecognizedDirectiveArgument(_, _, pos)
+            | ParseError::DuplicatedDirectiveArgument(_, _, pos)
+            | ParseError::InappropriateTypeForDirectiveArgument(_, _, pos)
+            | ParseError::FilterExpectsListNotString(_, _, pos)
+            | ParseError::InvalidFieldArgument(_, _, _, pos)
+            | ParseError::DocumentContainsNonInlineFragments(pos)
+            | ParseError::MultipleOperationsInDocument(pos)
+            | ParseError::MultipleQueryRoots(pos)
+            | ParseError::UnsupportedQueryRoot(_, pos)
+            | ParseError::DirectiveNotInsideQueryRoot(_, pos)
+            | ParseError::DocumentNotAQuery(pos)
+            | ParseError::UnsupportedFilterOperator(_, pos)
+            | ParseError::InvalidFilterOperandName(_, _, pos)
+            | ParseError::UnsupportedTransformOperator(_, pos)
+            | ParseError::UnsupportedOrderDirection(_, pos)
+            | ParseError::InvalidOutputName(_, _, pos)
+            | ParseError::InvalidTagName(_, _, pos)
+            | ParseError::UnsupportedSyntax(_, pos)
+            | ParseError::NestedTypeCoercion(pos)
+            | ParseError::TypeCoercionWithSiblingFields(pos)
+            | ParseError::UnsupportedDuplicatedDirective(_, pos)
+            | ParseError::DuplicatedEdgeParameter(_, _, pos)
+            | ParseError::VariableDefinitionInQuery(pos)
+            | ParseError::DuplicatedVariableDefinition(_, pos)
+            | ParseError::InvalidVariableDefaultValue(_, _, pos)
+            | ParseError::OtherError(_, pos) => Some(*pos),
+        }
+    }
+}
+
 fn fail_serialize_invalid_graphql_error<S: Serializer>(
     _: &async_graphql_parser::Error,
     _: S,
@@ -0,0 +1,444 @@
+//! Formatting Trustfall queries in a canonical style.
+use std::{collections::BTreeMap, fmt::Write};
+
+use async_graphql_parser::{
+    types::{
+        Directive, DocumentOperations, ExecutableDocument, Field, OperationDefinition, Selection,
+        SelectionSet,
+    },
+    Pos, Positioned,
+};
+use async_graphql_value::Value;
+
+use super::{error::ParseError, query::parse_document};
+
+const INDENT: &str = "    ";
+
+/// Format the query text in the canonical style.
+///
+/// Each field and type coercion gets its own line, with four spaces of indentation per level
+/// of nesting. Arguments and directives are laid out on the same line as their field,
+/// with a single space between directives and `name: value` arguments separated by `", "`.
+/// Directives keep their order, since it affects the query's meaning.
+///
+/// Comments are preserved, and so are blank lines between fields,
+/// though multiple consecutive blank lines are collapsed into one.
+///
+/// Returns an error if the query isn't syntactically valid.
+/// The query doesn't need to be valid for any particular schema.
+pub fn format_query(query: &str) -> Result<String, ParseError> {
+    let document = async_graphql_parser::parse_query(query)?;
+    parse_document(&document)?;
+
+    let mut formatter = Formatter::new(query);
+    formatter.write_document(&document);
+    Ok(formatter.finish())
+}
+
+/// A `#` comment in the query text.
+#[derive(Debug)]
+struct Comment {
+    line: usize,
+    text: String,
+
+    /// Whether the comment follows code on the same line, rather than being on its own line.
+    trailing: bool,
+}
+
+/// The parts of the query text that the parsed document doesn't record.
+#[derive(Debug, Default)]
+struct SourceTrivia {
+    comments: Vec<Comment>,
+
+    /// The line of the matching closing brace for each opening brace's position.
+    closing_brace_lines: BTreeMap<(usize, usize), usize>,
+}
+
+impl SourceTrivia {
+    fn scan(query: &str) -> Self {
+        let mut trivia = SourceTrivia::default();
+        let mut open_braces = vec![];
+        let mut line_has_code = false;
+
+        let chars: Vec<char> = query.chars().collect();
+        let (mut line, mut column) = (1, 1);
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            let start = (line, column);
+            let mut consumed = 1;
+            match c {
+                '#' => {
+                    let end = chars[index..]
+                        .iter()
+                        .position(|c| *c == '\n' || *c == '\r')
+                        .map_or(chars.len(), |offset| index + offset);
+                    let text: String = chars[index..end].iter().collect();
+                    trivia.comments.push(Comment {
+                        line,
+                        text: text.trim_end().to_string(),
+                        trailing: line_has_code,
+                    });
+                    consumed = end - index;
+                }
+                '"' => {
+                    line_has_code = true;
+                    consumed = string_length(&chars[index..]);
+                }
+                '{' => {
+                    line_has_code = true;
+                    open_braces.push(start);
+                }
+                '}' => {
+                    line_has_code = true;
+                    if let Some(open) = open_braces.pop() {
+                        trivia.closing_brace_lines.insert(open, line);
+                    }
+                }
+                c if c.is_whitespace() || c == ',' => {}
+                _ => line_has_code = true,
+            }
+
+            for c in &chars[index..index + consumed] {
+                if *c == '\n' {
+                    line += 1;
+                    column = 1;
+                    line_has_code = false;
+                } else {
+                    column += 1;
+                }
+            }
+            index += consumed;
+        }
+
+        trivia
+    }
+
+    /// The line of the closing brace of the selection set at the given position.
+    fn closing_brace_line(&self, pos: Pos) -> Option<usize> {
+        self.closing_brace_lines.range((pos.line, pos.column)..).next().map(|(_, line)| *line)
+    }
+}
+
+/// The number of characters in the string literal at the start of `chars`,
+/// including its quotes.
+fn string_length(chars: &[char]) -> usize {
+    let block_quotes = ['"', '"', '"'];
+    if chars.starts_with(&block_quotes) {
+        let mut index = 3;
+        while index < chars.len() {
+            if chars[index..].starts_with(&block_quotes) {
+                return index + 3;
+            } else if chars[index] == '\\' {
+                index += 1;
+            }
+            index += 1;
+        }
+        chars.len()
+    } else {
+        let mut index = 1;
+        while index < chars.len() {
+            match chars[index] {
+                '"' | '\n' => return index + 1,
+                '\\' => index += 1,
+                _ => {}
+            }
+            index += 1;
+        }
+        chars.len()
+    }
+}
+
+struct Formatter {
+    output: String,
+    trivia: SourceTrivia,
+
+    /// Index of the first comment in `trivia` that hasn't been written yet.
+    next_comment: usize,
+
+    /// The last line of the query text whose contents have been written.
+    last_line: usize,
+}
+
+impl Formatter {
+    fn new(query: &str) -> Self {
+        Self {
+            output: String::new(),
+            trivia: SourceTrivia::scan(query),
+            next_comment: 0,
+            last_line: 0,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.write_comments_before(usize::MAX, 0);
+        self.output
+    }
+
+    /// Write the comments on their own lines before the given line, followed by
+    /// a blank line if the query text has one between them and that line.
+    fn write_comments_before(&mut self, line: usize, depth: usize) {
+        while let Some(comment) = self.trivia.comments.get(self.next_comment) {
+            if comment.line >= line {
+                break;
+            }
+            let separate = !comment.trailing && comment.line > self.last_line + 1;
+            if separate && self.last_line > 0 && !self.output.ends_with("{\n") {
+                self.output.push('\n');
+            }
+            if comment.trailing && self.output.ends_with('\n') {
+                // The code the comment trailed has already ended its line.
+                self.output.pop();
+                self.output.push(' ');
+            } else {
+                self.output.push_str(&INDENT.repeat(depth));
+            }
+            self.output.push_str(&comment.text);
+            self.output.push('\n');
+            self.last_line = self.last_line.max(comment.line);
+            self.next_comment += 1;
+        }
+    }
+
+    /// Start a new line for an item at the given line in the query text.
+    fn start_line(&mut self, line: usize, depth: usize) {
+        self.write_comments_before(line, depth);
+        let after_open_brace = self.output.is_empty() || self.output.ends_with("{\n");
+        if !after_open_brace && line > self.last_line + 1 {
+            self.output.push('\n');
+        }
+        self.output.push_str(&INDENT.repeat(depth));
+        self.last_line = self.last_line.max(line);
+    }
+
+    /// End the current line, including any comment trailing the given line in the query text.
+    fn end_line(&mut self, line: usize) {
+        if let Some(comment) = self.trivia.comments.get(self.next_comment) {
+            if comment.trailing && comment.line == line {
+                self.output.push(' ');
+                self.output.push_str(&comment.text);
+                self.next_comment += 1;
+            }
+        }
+        self.output.push('\n');
+    }
+
+    fn write_document(&mut self, document: &ExecutableDocument) {
+        match &document.operations {
+            DocumentOperations::Single(operation) => self.write_operation(None, operation),
+            DocumentOperations::Multiple(operations) => {
+                for (name, operation) in operations {
+                    self.write_operation(Some(name.as_str()), operation);
+                }
+            }
+        }
+    }
+
+    fn write_operation(&mut self, name: Option<&str>, operation: &Positioned<OperationDefinition>) {
+        let definition = &operation.node;
+        self.start_line(operation.pos.line, 0);
+        if name.is_some() || !definition.variable_definitions.is_empty() {
+            self.output.push_str("query");
+            if let Some(name) = name {
+                write!(self.output, " {name}").expect("write failed");
+            }
+            if !definition.variable_definitions.is_empty() {
+                let variables: Vec<_> = definition
+                    .variable_definitions
+                    .iter()
+                    .map(|variable| {
+                        let variable = &variable.node;
+                        let mut text =
+                            format!("${}: {}", variable.name.node, variable.var_type.node);
+                        if let Some(default_value) = &variable.default_value {
+                            write!(text, " = {}", default_value.node).expect("write failed");
+                        }
+                        text
+                    })
+                    .collect();
+                write!(self.output, "({})", variables.join(", ")).expect("write failed");
+            }
+            self.write_directives(&definition.directives);
+            self.output.push(' ');
+        }
+        self.write_selection_set(&definition.selection_set, operation.pos.line, 0);
+    }
+
+    fn write_selection_set(
+        &mut self,
+        selection_set: &Positioned<SelectionSet>,
+        line: usize,
+        depth: usize,
+    ) {
+        self.output.push('{');
+        self.end_line(line);
+
+        let SelectionSet { items } = &selection_set.node;
+        for item in items {
+            match &item.node {
+                Selection::Field(field) => self.write_field(field, depth + 1),
+                Selection::InlineFragment(fragment) => {
+                    let fragment_pos = fragment.pos;
+                    let fragment = &fragment.node;
+                    self.start_line(fragment_pos.line, depth + 1);
+                    self.output.push_str("...");
+                    if let Some(type_condition) = &fragment.type_condition {
+                        write!(self.output, " on {}", type_condition.node.on.node)
+                            .expect("write failed");
+                    }
+                    self.write_directives(&fragment.directives);
+                    self.output.push(' ');
+                    let header_line = fragment.selection_set.pos.line;
+                    self.write_selection_set(&fragment.selection_set, header_line, depth + 1);
+                }
+                Selection::FragmentSpread(spread) => {
+                    // Rejected when validating the query.
+                    unreachable!("unexpected fragment spread: {spread:?}")
+                }
+            }
+        }
+
+        let closing_line = self
+            .trivia
+            .closing_brace_line(selection_set.pos)
+            .unwrap_or(self.last_line)
+            .max(self.last_line);
+        self.write_comments_before(closing_line, depth + 1);
+        self.output.push_str(&INDENT.repeat(depth));
+        self.output.push('}');
+        self.last_line = closing_line;
+        self.end_line(closing_line);
+    }
+
+    fn write_field(&mut self, field: &Positioned<Field>, depth: usize) {
+        let pos = field.pos;
+        let field = &field.node;
+        self.start_line(pos.line, depth);
+        if let Some(alias) = &field.alias {
+            write!(self.output, "{}: ", alias.node).expect("write failed");
+        }
+        self.output.push_str(&field.name.node);
+        self.write_arguments(&field.arguments);
+        self.write_directives(&field.directives);
+
+        if field.selection_set.node.items.is_empty() {
+            let last_line = field
+                .directives
+                .last()
+                .map_or(pos.line, |directive| directive.pos.line)
+                .max(pos.line);
+            self.last_line = self.last_line.max(last_line);
+            self.end_line(last_line);
+        } else {
+            self.output.push(' ');
+            let header_line = field.selection_set.pos.line;
+            self.last_line = self.last_line.max(header_line);
+            self.write_selection_set(&field.selection_set, header_line, depth);
+        }
+    }
+
+    fn write_arguments(
+        &mut self,
+        arguments: &[(Positioned<async_graphql_value::Name>, Positioned<Value>)],
+    ) {
+        if arguments.is_empty() {
+            return;
+        }
+        let arguments: Vec<_> = arguments
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name.node, value.node))
+            .collect();
+        write!(self.output, "({})", arguments.join(", ")).expect("write failed");
+    }
+
+    fn write_directives(&mut self, directives: &[Positioned<Directive>]) {
+        for directive in directives {
+            write!(self.output, " @{}", directive.node.name.node).expect("write failed");
+            self.write_arguments(&directive.node.arguments);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_query;
+
+    #[test]
+    fn formats_layout_canonically() {
+        let query = r#"
+query  { Number(max :3)@limit(count:5){ value   @output @filter(op:"=",value:["$value"])
+  multiple(max: 4)   @fold  @transform(op: "count") @output(name: "count") { name @output }
+      predecessor{...on Prime { primeName: name @output }}
+}}"#;
+        let expected = r#"{
+    Number(max: 3) @limit(count: 5) {
+        value @output @filter(op: "=", value: ["$value"])
+        multiple(max: 4) @fold @transform(op: "count") @output(name: "count") {
+            name @output
+        }
+        predecessor {
+            ... on Prime {
+                primeName: name @output
+            }
+        }
+    }
+}
+"#;
+        assert_eq!(expected, format_query(query).expect("valid query"));
+        assert_eq!(expected, format_query(expected).expect("valid query"));
+    }
+
+    #[test]
+    fn preserves_comments_and_blank_lines() {
+        let query = r#"
+# Numbers and their successors.
+query Successors($min: Int = 2) {
+    Number(min: 2, max: 10) {  # at most 10 of them
+        value @output @filter(op: ">", value: ["$min"])
+
+
+        # The next number.
+        successor {
+            next: value @output # trailing
+            # before closing brace
+        }
+    }
+}
+# at the end"#;
+        let expected = r#"# Numbers and their successors.
+query Successors($min: Int = 2) {
+    Number(min: 2, max: 10) { # at most 10 of them
+        value @output @filter(op: ">", value: ["$min"])
+
+        # The next number.
+        successor {
+            next: value @output # trailing
+            # before closing brace
+        }
+    }
+}
+# at the end
+"#;
+        assert_eq!(expected, format_query(query).expect("valid query"));
+        assert_eq!(expected, format_query(expected).expect("valid query"));
+    }
+
+    #[test]
+    fn comment_characters_in_strings_are_not_comments() {
+        let query = r##"query($name: String = "#{") {
+    Number(max: 3) { name @filter(op: "=", value: ["$name"]) } }"##;
+        let expected = r##"query($name: String = "#{") {
+    Number(max: 3) {
+        name @filter(op: "=", value: ["$name"])
+    }
+}
+"##;
+        assert_eq!(expected, format_query(query).expect("valid query"));
+    }
+
+    #[test]
+    fn invalid_queries_are_errors() {
+        assert!(format_query("{ Number(max: 3) { value @output }").is_err());
+        assert!(format_query("{ Number { value @nonexistent } }").is_err());
+    }
+}
@@ -7,16 +7,18 @@ use async_graphql_parser::{
     types::{DocumentOperations, ExecutableDocument, Field, OperationType, Selection},
     Pos, Positioned,
 };
+use async_graphql_value::Value;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-use crate::ir::FieldValue;
+use crate::ir::{FieldValue, Type};
 use crate::util::BTreeMapTryInsertExt;
 
 use super::directives::{FoldGroup, TransformDirective, TransformGroup};
 use super::{
     directives::{
-        FilterDirective, FoldDirective, OptionalDirective, OutputDirective, RecurseDirective,
+        AbsentDirective, DedupeDirective, FilterDirective, FoldDirective, LimitDirective,
+        OffsetDirective, OptionalDirective, OrderDirective, OutputDirective, RecurseDirective,
         TagDirective,
     },
     error::ParseError,
@@ -33,6 +35,16 @@ pub(crate) struct FieldConnection {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub(crate) arguments: BTreeMap<Arc<str>, FieldValue>,
 
+    /// Arguments whose values are query variables, like `edge(param: $var)`,
+    /// mapped to the names of those variables.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) variable_arguments: BTreeMap<Arc<str>, Arc<str>>,
+
+    /// Arguments whose values are tagged values, like `edge(param: "%tag")`,
+    /// mapped to the names of those tags.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) tag_arguments: BTreeMap<Arc<str>, Arc<str>>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) optional: Option<OptionalDirective>,
 
@@ -41,6 +53,11 @@ pub(crate) struct FieldConnection {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) fold: Option<FoldGroup>,
+
+    /// Set if the edge has an `@absent` directive. Such edges are also given a `fold` group,
+    /// since `@absent` is shorthand for a `@fold` whose count must be zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) absent: Option<AbsentDirective>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -63,6 +80,9 @@ pub(crate) struct FieldNode {
     #[serde(default, skip_serializing_if = "SmallVec::is_empty")]
     pub(crate) tag: SmallVec<[TagDirective; 0]>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) order: Option<OrderDirective>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) connections: Vec<(FieldConnection, FieldNode)>,
 
@@ -70,18 +90,44 @@ pub(crate) struct FieldNode {
     pub(crate) transform_group: Option<TransformGroup>,
 }
 
+/// A variable declared in the query's operation definition, like `($limit: Int = 10)`.
+///
+/// Variable types are inferred from their uses, so declarations are only needed
+/// to specify a variable's default value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct VariableDefinition {
+    pub(crate) position: Pos,
+    pub(crate) name: Arc<str>,
+    pub(crate) declared_type: Type,
+    pub(crate) default_value: FieldValue,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Query {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) variable_definitions: Vec<VariableDefinition>,
+
     pub(crate) root_connection: FieldConnection,
 
     pub(crate) root_field: FieldNode,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) limit: Option<LimitDirective>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) offset: Option<OffsetDirective>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dedupe: Option<DedupeDirective>,
 }
 
 #[derive(Debug, Clone)]
-enum ParsedDirective {
+pub(crate) enum ParsedDirective {
+    Absent(AbsentDirective, Pos),
     Filter(FilterDirective, Pos),
     Fold(FoldDirective, Pos),
     Optional(OptionalDirective, Pos),
+    Order(OrderDirective, Pos),
     Output(OutputDirective, Pos),
     Recurse(RecurseDirective, Pos),
     Tag(TagDirective, Pos),
@@ -89,11 +135,13 @@ enum ParsedDirective {
 }
 
 impl ParsedDirective {
-    fn kind(&self) -> &str {
+    pub(crate) fn kind(&self) -> &str {
         match self {
+            ParsedDirective::Absent(..) => "@absent",
             ParsedDirective::Filter(..) => "@filter",
             ParsedDirective::Fold(..) => "@fold",
             ParsedDirective::Optional(..) => "@optional",
+            ParsedDirective::Order(..) => "@order",
             ParsedDirective::Output(..) => "@output",
             ParsedDirective::Recurse(..) => "@recurse",
             ParsedDirective::Tag(..) => "@tag",
@@ -101,11 +149,13 @@ impl ParsedDirective {
         }
     }
 
-    fn pos(&self) -> Pos {
+    pub(crate) fn pos(&self) -> Pos {
         match self {
+            ParsedDirective::Absent(_, pos) => *pos,
             ParsedDirective::Filter(_, pos) => *pos,
             ParsedDirective::Fold(_, pos) => *pos,
             ParsedDirective::Optional(_, pos) => *pos,
+            ParsedDirective::Order(_, pos) => *pos,
             ParsedDirective::Output(_, pos) => *pos,
             ParsedDirective::Recurse(_, pos) => *pos,
             ParsedDirective::Tag(_, pos) => *pos,
@@ -114,11 +164,36 @@ impl ParsedDirective {
     }
 }
 
-/// Attempts to extract the query root from an [ExecutableDocument]
+/// Attempts to extract the query operation and its root from an [ExecutableDocument]
 ///
 /// May return [ParseError] if the query is empty, there is no query root, or
 /// the query root is not formatted properly
-fn try_get_query_root(document: &ExecutableDocument) -> Result<&Positioned<Field>, ParseError> {
+fn try_get_query_root(
+    document: &ExecutableDocument,
+) -> Result<(&OperationDefinition, &Positioned<Field>), ParseError> {
+    let (operation, root_items) = try_get_query_operation(document)?;
+    if root_items.len() != 1 {
+        return Err(ParseError::MultipleQueryRoots(root_items[1].pos));
+    }
+
+    Ok((operation, root_field(&root_items[0])?))
+}
+
+/// Attempts to extract the query operation and all its root fields from an [ExecutableDocument]
+///
+/// May return [ParseError] if the query is empty, or any query root is not formatted properly
+fn try_get_query_roots(
+    document: &ExecutableDocument,
+) -> Result<(&OperationDefinition, Vec<&Positioned<Field>>), ParseError> {
+    let (operation, root_items) = try_get_query_operation(document)?;
+    let roots = root_items.iter().map(root_field).collect::<Result<_, _>>()?;
+
+    Ok((operation, roots))
+}
+
+fn try_get_query_operation(
+    document: &ExecutableDocument,
+) -> Result<(&OperationDefinition, &[Positioned<Selection>]), ParseError> {
     if let Some(v) = document.fragments.values().next() {
         return Err(ParseError::DocumentContainsNonInlineFragments(v.pos));
     }
@@ -133,7 +208,7 @@ fn try_get_query_root(document: &ExecutableDocument) -> Result<&Positioned<Field
                         .pos,
                 ))
             } else if let Some(node) = mult.values().next() {
-                parse_operation_definition(node)
+                Ok((&node.node, parse_operation_definition(node)?))
             } else {
                 // This should be unreachable if someone is using the library correctly
                 unreachable!(
@@ -143,21 +218,24 @@ fn try_get_query_root(document: &ExecutableDocument) -> Result<&Positioned<Field
                 )
             }
         }
-        DocumentOperations::Single(op) => parse_operation_definition(op),
+        DocumentOperations::Single(op) => Ok((&op.node, parse_operation_definition(op)?)),
     }
 }
 
 fn parse_operation_definition(
     op: &Positioned<OperationDefinition>,
-) -> Result<&Positioned<Field>, ParseError> {
+) -> Result<&[Positioned<Selection>], ParseError> {
     let root_node = &op.node;
 
     if root_node.ty != OperationType::Query {
         return Err(ParseError::DocumentNotAQuery(op.pos));
     }
 
-    if let Some(first_variable_definition) = root_node.variable_definitions.first() {
-        return Err(ParseError::VariableDefinitionInQuery(first_variable_definition.pos));
+    // Variable definitions are only useful for specifying default values.
+    if let Some(variable_definition) =
+        root_node.variable_definitions.iter().find(|v| v.node.default_value.is_none())
+    {
+        return Err(ParseError::VariableDefinitionInQuery(variable_definition.pos));
     }
     if let Some(first_directive) = root_node.directives.first() {
         return Err(ParseError::DirectiveNotInsideQueryRoot(
@@ -166,30 +244,61 @@ fn parse_operation_definition(
         ));
     }
 
-    let root_selection_set = &root_node.selection_set.node;
-    let root_items = &root_selection_set.items;
-    if root_items.len() != 1 {
-        return Err(ParseError::MultipleQueryRoots(root_items[1].pos));
+    Ok(&root_node.selection_set.node.items)
+}
+
+fn root_field(root_item: &Positioned<Selection>) -> Result<&Positioned<Field>, ParseError> {
+    match &root_item.node {
+        Selection::Field(positioned_field) => Ok(positioned_field),
+        Selection::FragmentSpread(fs) => {
+            Err(ParseError::UnsupportedQueryRoot("a fragment spread".to_string(), fs.pos))
+        }
+        Selection::InlineFragment(inl) => {
+            Err(ParseError::UnsupportedQueryRoot("an inline fragment".to_string(), inl.pos))
+        }
     }
+}
 
-    if let Some(root_node) = root_items.first() {
-        match &root_node.node {
-            Selection::Field(positioned_field) => Ok(positioned_field),
-            Selection::FragmentSpread(fs) => {
-                Err(ParseError::UnsupportedQueryRoot("a fragment spread".to_string(), fs.pos))
-            }
-            Selection::InlineFragment(inl) => {
-                Err(ParseError::UnsupportedQueryRoot("an inline fragment".to_string(), inl.pos))
-            }
+fn make_variable_definitions(
+    operation: &OperationDefinition,
+) -> Result<Vec<VariableDefinition>, ParseError> {
+    let mut names = BTreeMap::new();
+    let mut definitions = vec![];
+    for variable in &operation.variable_definitions {
+        let name: Arc<str> = variable.node.name.node.as_str().into();
+        if names.insert_or_error(name.clone(), ()).is_err() {
+            return Err(ParseError::DuplicatedVariableDefinition(name.to_string(), variable.pos));
         }
-    } else {
-        unreachable!(
-            "Found a root_node with no items. \
-            This should have been caught in a previous selection statement, this is a bug. \
-            Please report it at \
-            https://github.com/obi1kenobi/trustfall/"
-        )
+        if let Some(directive) = variable.node.directives.first() {
+            return Err(ParseError::UnsupportedDirectivePosition(
+                format!("@{}", directive.node.name.node),
+                "directives cannot be applied to variable definitions".to_string(),
+                directive.pos,
+            ));
+        }
+
+        let default_value = variable
+            .node
+            .default_value
+            .as_ref()
+            .expect("variable definitions without default values were already rejected");
+        let default_value = FieldValue::try_from(default_value.node.clone()).map_err(|_| {
+            ParseError::InvalidVariableDefaultValue(
+                name.to_string(),
+                default_value.node.clone().into_value(),
+                default_value.pos,
+            )
+        })?;
+
+        definitions.push(VariableDefinition {
+            position: variable.pos,
+            name,
+            declared_type: Type::from_type(&variable.node.var_type.node),
+            default_value,
+        });
     }
+
+    Ok(definitions)
 }
 
 fn make_directives(
@@ -219,6 +328,10 @@ fn make_directives(
                 let parsed = OptionalDirective::try_from(directive)?;
                 parsed_directives.push(ParsedDirective::Optional(parsed, directive.pos));
             }
+            "order" => {
+                let parsed = OrderDirective::try_from(directive)?;
+                parsed_directives.push(ParsedDirective::Order(parsed, directive.pos));
+            }
             "recurse" => {
                 let parsed = RecurseDirective::try_from(directive)?;
                 parsed_directives.push(ParsedDirective::Recurse(parsed, directive.pos));
@@ -227,6 +340,19 @@ fn make_directives(
                 let parsed = FoldDirective::try_from(directive)?;
                 parsed_directives.push(ParsedDirective::Fold(parsed, directive.pos));
             }
+            "absent" => {
+                let parsed = AbsentDirective::try_from(directive)?;
+                parsed_directives.push(ParsedDirective::Absent(parsed, directive.pos));
+            }
+            "limit" | "offset" | "dedupe" => {
+                // These directives apply to the query as a whole, so they are only valid
+                // on the root field. Those uses are handled in `parse_document()`.
+                return Err(ParseError::UnsupportedDirectivePosition(
+                    format!("@{}", directive.node.name.node),
+                    "this directive can only be applied to the root field of the query".to_string(),
+                    directive.pos,
+                ));
+            }
             _ => {
                 return Err(ParseError::UnrecognizedDirective(
                     directive.node.name.node.to_string(),
@@ -291,20 +417,69 @@ fn make_field_node(field: &Positioned<Field>) -> Result<FieldNode, ParseError> {
         _ => (None, &field.node.selection_set),
     };
 
+    let mut vertex = make_vertex_node(
+        field.pos,
+        name.as_ref().into(),
+        alias.map(|x| x.as_ref().into()),
+        coerced_to.map(|x| x.as_ref().into()),
+        make_directives(&field.node.directives)?,
+    )?;
+
+    for selection in field_selections.node.items.iter() {
+        match &selection.node {
+            Selection::FragmentSpread(_) => {
+                return Err(ParseError::UnsupportedSyntax(
+                    "fragment spread".to_string(),
+                    selection.pos,
+                ));
+            }
+            Selection::InlineFragment(_) => {
+                return Err(ParseError::NestedTypeCoercion(selection.pos));
+            }
+            Selection::Field(f) => {
+                let edge = make_field_connection(f)?;
+                vertex.connections.push((edge, make_field_node(f)?));
+            }
+        }
+    }
+
+    Ok(vertex)
+}
+
+/// Assemble a vertex from its field's directives. The edges selected within it
+/// are added to its `connections` afterward.
+pub(crate) fn make_vertex_node(
+    position: Pos,
+    name: Arc<str>,
+    alias: Option<Arc<str>>,
+    coerced_to: Option<Arc<str>>,
+    directives: Vec<ParsedDirective>,
+) -> Result<FieldNode, ParseError> {
     let mut filter: SmallVec<[FilterDirective; 1]> = Default::default();
     let mut output: SmallVec<[OutputDirective; 1]> = Default::default();
     let mut tag: SmallVec<[TagDirective; 0]> = Default::default();
+    let mut order: Option<OrderDirective> = None;
 
-    let directives = make_directives(&field.node.directives)?;
     let mut directives_iter = directives.into_iter();
     let maybe_transform = loop {
         match directives_iter.next() {
             Some(ParsedDirective::Filter(f, _)) => filter.push(f),
             Some(ParsedDirective::Output(o, _)) => output.push(o),
             Some(ParsedDirective::Tag(t, _)) => tag.push(t),
+            Some(ParsedDirective::Order(o, pos)) => {
+                if order.is_none() {
+                    order = Some(o);
+                } else {
+                    return Err(ParseError::UnsupportedDuplicatedDirective(
+                        "@order".to_owned(),
+                        pos,
+                    ));
+                }
+            }
             Some(ParsedDirective::Transform(t, _)) => break Some(t),
             Some(
-                ParsedDirective::Optional(..)
+                ParsedDirective::Absent(..)
+                | ParsedDirective::Optional(..)
                 | ParsedDirective::Fold(..)
                 | ParsedDirective::Recurse(..),
             ) => {
@@ -320,69 +495,100 @@ fn make_field_node(field: &Positioned<Field>) -> Result<FieldNode, ParseError> {
         None
     };
 
-    let mut connections: Vec<(FieldConnection, FieldNode)> = vec![];
-    for selection in field_selections.node.items.iter() {
-        match &selection.node {
-            Selection::FragmentSpread(_) => {
-                return Err(ParseError::UnsupportedSyntax(
-                    "fragment spread".to_string(),
-                    selection.pos,
-                ));
-            }
-            Selection::InlineFragment(_) => {
-                return Err(ParseError::NestedTypeCoercion(selection.pos));
-            }
-            Selection::Field(f) => {
-                let edge = make_field_connection(f)?;
-                let vertex = make_field_node(f)?;
-                connections.push((edge, vertex));
-            }
-        }
-    }
-
     Ok(FieldNode {
-        position: field.pos,
-        name: name.as_ref().to_owned().into(),
-        alias: alias.map(|x| x.as_ref().to_owned().into()),
-        coerced_to: coerced_to.map(|x| x.as_ref().to_owned().into()),
+        position,
+        name,
+        alias,
+        coerced_to,
         filter,
         transform_group,
         output,
         tag,
-        connections,
+        order,
+        connections: vec![],
     })
 }
 
 fn make_field_connection(field: &Positioned<Field>) -> Result<FieldConnection, ParseError> {
-    let arguments = field.node.arguments.iter().try_fold(
-        BTreeMap::new(),
-        |mut acc, (name, value)| -> Result<BTreeMap<Arc<str>, FieldValue>, ParseError> {
-            acc.insert_or_error(
-                name.node.as_ref().to_owned().into(),
-                FieldValue::try_from(value.node.clone()).map_err(|_| {
-                    ParseError::InvalidFieldArgument(
-                        field.node.name.node.to_string(),
-                        name.node.to_string(),
-                        value.node.clone(),
-                        value.pos,
-                    )
-                })?,
-            )
-            .map_err(|e| {
-                ParseError::DuplicatedEdgeParameter(
-                    e.entry.key().to_string(),
+    let mut arguments: BTreeMap<Arc<str>, FieldValue> = BTreeMap::new();
+    let mut variable_arguments: BTreeMap<Arc<str>, Arc<str>> = BTreeMap::new();
+    let mut tag_arguments: BTreeMap<Arc<str>, Arc<str>> = BTreeMap::new();
+    for (name, value) in &field.node.arguments {
+        let argument_name: Arc<str> = name.node.as_ref().into();
+        if arguments.contains_key(&argument_name)
+            || variable_arguments.contains_key(&argument_name)
+            || tag_arguments.contains_key(&argument_name)
+        {
+            return Err(ParseError::DuplicatedEdgeParameter(
+                argument_name.to_string(),
+                field.node.name.node.to_string(),
+                value.pos,
+            ));
+        }
+
+        if let Value::Variable(variable_name) = &value.node {
+            variable_arguments.insert(argument_name, variable_name.as_ref().into());
+        } else if let Some(tag_name) = tag_reference(&value.node) {
+            tag_arguments.insert(argument_name, tag_name.into());
+        } else {
+            let argument_value = FieldValue::try_from(value.node.clone()).map_err(|_| {
+                ParseError::InvalidFieldArgument(
                     field.node.name.node.to_string(),
+                    name.node.to_string(),
+                    value.node.clone(),
                     value.pos,
                 )
             })?;
-            Ok(acc)
-        },
-    )?;
+            arguments.insert(argument_name, argument_value);
+        }
+    }
 
+    make_edge_connection(
+        field.pos,
+        field.node.name.node.as_ref().into(),
+        field.node.alias.as_ref().map(|p| p.node.as_ref().into()),
+        arguments,
+        variable_arguments,
+        tag_arguments,
+        make_directives(&field.node.directives)?,
+        !field.node.selection_set.node.items.is_empty(),
+    )
+}
+
+/// Get the tag name referenced by an edge argument like `"%tag"`, if the argument is one.
+///
+/// Only strings consisting of `%` followed by a valid tag name are tag references,
+/// so other strings that happen to start with `%` remain ordinary string values.
+fn tag_reference(value: &Value) -> Option<&str> {
+    let Value::String(value) = value else {
+        return None;
+    };
+    let name = value.strip_prefix('%')?;
+    let first_char = name.chars().next()?;
+    let is_valid_name = (first_char.is_ascii_alphabetic() || first_char == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_valid_name.then_some(name)
+}
+
+/// Assemble an edge from its field's arguments and directives.
+///
+/// Fields without selections are properties rather than edges, and directives like `@transform`
+/// apply to the property itself instead of to the edge.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn make_edge_connection(
+    position: Pos,
+    name: Arc<str>,
+    alias: Option<Arc<str>>,
+    arguments: BTreeMap<Arc<str>, FieldValue>,
+    variable_arguments: BTreeMap<Arc<str>, Arc<str>>,
+    tag_arguments: BTreeMap<Arc<str>, Arc<str>>,
+    directives: Vec<ParsedDirective>,
+    has_selections: bool,
+) -> Result<FieldConnection, ParseError> {
     let mut optional: Option<OptionalDirective> = None;
     let mut recurse: Option<RecurseDirective> = None;
+    let mut absent: Option<AbsentDirective> = None;
 
-    let directives = make_directives(&field.node.directives)?;
     let mut directives_iter = directives.into_iter();
     let maybe_fold = loop {
         match directives_iter.next() {
@@ -406,7 +612,31 @@ fn make_field_connection(field: &Positioned<Field>) -> Result<FieldConnection, P
                     ));
                 }
             }
+            Some(ParsedDirective::Absent(abs, pos)) => {
+                if absent.is_none() {
+                    absent = Some(abs);
+                } else {
+                    return Err(ParseError::UnsupportedDuplicatedDirective(
+                        "@absent".to_owned(),
+                        pos,
+                    ));
+                }
+            }
+            Some(ParsedDirective::Fold(_, pos)) if absent.is_some() => {
+                return Err(ParseError::UnsupportedDirectivePosition(
+                    "@fold".to_owned(),
+                    "this directive cannot be combined with @absent, \
+                    which already folds the edge"
+                        .to_owned(),
+                    pos,
+                ));
+            }
             Some(ParsedDirective::Fold(fold, _)) => break Some(fold),
+            Some(ParsedDirective::Transform(..)) if !has_selections => {
+                // Fields without selections are properties, not edges. Their transforms
+                // are part of the property's own directives, not of the edge's fold group.
+                break None;
+            }
             Some(ParsedDirective::Transform(_, pos)) => {
                 return Err(ParseError::UnsupportedDirectivePosition(
                     "@transform".to_owned(),
@@ -418,6 +648,7 @@ fn make_field_connection(field: &Positioned<Field>) -> Result<FieldConnection, P
             }
             Some(
                 ParsedDirective::Filter(..)
+                | ParsedDirective::Order(..)
                 | ParsedDirective::Output(..)
                 | ParsedDirective::Tag(..),
             ) => {}
@@ -427,18 +658,23 @@ fn make_field_connection(field: &Positioned<Field>) -> Result<FieldConnection, P
 
     let fold_group = if let Some(fold) = maybe_fold {
         Some(make_fold_group(fold, &mut directives_iter)?)
+    } else if absent.is_some() {
+        Some(FoldGroup { fold: FoldDirective {}, transform: None })
     } else {
         None
     };
 
     Ok(FieldConnection {
-        position: field.pos,
-        name: field.node.name.node.as_ref().to_owned().into(),
-        alias: field.node.alias.as_ref().map(|p| p.node.as_ref().to_owned().into()),
+        position,
+        name,
+        alias,
         arguments,
+        variable_arguments,
+        tag_arguments,
         optional,
         recurse,
         fold: fold_group,
+        absent,
     })
 }
 
@@ -446,24 +682,27 @@ fn make_fold_group(
     fold: FoldDirective,
     directive_iter: &mut impl Iterator<Item = ParsedDirective>,
 ) -> Result<FoldGroup, ParseError> {
-    let transform_group = if let Some(directive) = directive_iter.next() {
-        match directive {
-            ParsedDirective::Transform(transform, _) => {
-                Some(make_transform_group(transform, directive_iter)?)
+    let transform_group = loop {
+        match directive_iter.next() {
+            Some(ParsedDirective::Transform(transform, _)) => {
+                break Some(make_transform_group(transform, directive_iter)?)
+            }
+            Some(ParsedDirective::Tag(..)) => {
+                // A @tag on the folded edge tags the number of folded vertices.
+                // It was already recorded among the directives of the edge's vertex.
             }
-            ParsedDirective::Fold(_, pos) => {
+            Some(ParsedDirective::Fold(_, pos)) => {
                 return Err(ParseError::UnsupportedDuplicatedDirective("@fold".to_string(), pos));
             }
-            _ => {
+            Some(directive) => {
                 return Err(ParseError::UnsupportedDirectivePosition(
                     directive.kind().to_string(),
                     "this directive cannot appear after a @fold directive".to_string(),
                     directive.pos(),
                 ))
             }
+            None => break None,
         }
-    } else {
-        None
     };
 
     Ok(FoldGroup { fold, transform: transform_group })
@@ -486,8 +725,10 @@ fn make_transform_group(
                 ParsedDirective::Transform(xform, _) => {
                     break Some(Box::new(make_transform_group(xform, directive_iter)?));
                 }
-                ParsedDirective::Fold(..)
+                ParsedDirective::Absent(..)
+                | ParsedDirective::Fold(..)
                 | ParsedDirective::Optional(..)
+                | ParsedDirective::Order(..)
                 | ParsedDirective::Recurse(..) => {
                     return Err(ParseError::UnsupportedDirectivePosition(
                         directive.kind().to_string(),
@@ -510,23 +751,87 @@ fn make_transform_group(
 
 /// Parses a query document. May fail if there is no query root.
 pub fn parse_document(document: &ExecutableDocument) -> Result<Query, ParseError> {
-    let query_root = try_get_query_root(document)?;
+    let (operation, query_root) = try_get_query_root(document)?;
+    make_query(operation, query_root)
+}
 
-    if let Some(dir) = query_root.node.directives.first() {
-        return Err(ParseError::DirectiveNotInsideQueryRoot(
-            dir.node.name.node.to_string(),
-            dir.pos,
-        ));
+/// Parses a query document that may have multiple root fields, one [Query] per root field.
+///
+/// Each of the resulting queries has all the variable definitions of the document.
+pub fn parse_document_roots(document: &ExecutableDocument) -> Result<Vec<Query>, ParseError> {
+    let (operation, query_roots) = try_get_query_roots(document)?;
+    query_roots.into_iter().map(|query_root| make_query(operation, query_root)).collect()
+}
+
+fn make_query(
+    operation: &OperationDefinition,
+    query_root: &Positioned<Field>,
+) -> Result<Query, ParseError> {
+    let variable_definitions = make_variable_definitions(operation)?;
+
+    let mut limit: Option<LimitDirective> = None;
+    let mut offset: Option<OffsetDirective> = None;
+    let mut dedupe: Option<DedupeDirective> = None;
+    for dir in &query_root.node.directives {
+        match dir.node.name.node.as_str() {
+            "limit" => {
+                if limit.is_some() {
+                    return Err(ParseError::UnsupportedDuplicatedDirective(
+                        "@limit".to_owned(),
+                        dir.pos,
+                    ));
+                }
+                limit = Some(LimitDirective::try_from(dir)?);
+            }
+            "offset" => {
+                if offset.is_some() {
+                    return Err(ParseError::UnsupportedDuplicatedDirective(
+                        "@offset".to_owned(),
+                        dir.pos,
+                    ));
+                }
+                offset = Some(OffsetDirective::try_from(dir)?);
+            }
+            "dedupe" => {
+                if dedupe.is_some() {
+                    return Err(ParseError::UnsupportedDuplicatedDirective(
+                        "@dedupe".to_owned(),
+                        dir.pos,
+                    ));
+                }
+                dedupe = Some(DedupeDirective::try_from(dir)?);
+            }
+            _ => {
+                return Err(ParseError::DirectiveNotInsideQueryRoot(
+                    dir.node.name.node.to_string(),
+                    dir.pos,
+                ));
+            }
+        }
     }
 
+    // The root field's directives apply to the query as a whole and have been handled above.
+    // Remove them so they aren't mistaken for directives on the starting edge or vertex.
+    let stripped_query_root;
+    let query_root = if query_root.node.directives.is_empty() {
+        query_root
+    } else {
+        stripped_query_root = Positioned::new(
+            Field { directives: vec![], ..query_root.node.clone() },
+            query_root.pos,
+        );
+        &stripped_query_root
+    };
+
     let root_connection = make_field_connection(query_root)?;
     assert!(root_connection.optional.is_none());
     assert!(root_connection.recurse.is_none());
     assert!(root_connection.fold.is_none());
+    assert!(root_connection.absent.is_none());
 
     let root_field = make_field_node(query_root)?;
 
-    Ok(Query { root_connection, root_field })
+    Ok(Query { variable_definitions, root_connection, root_field, limit, offset, dedupe })
 }
 
 #[cfg(test)]
@@ -0,0 +1,548 @@
+//! Resolving GraphQL introspection queries using the Trustfall schema's definitions.
+use std::{collections::BTreeSet, sync::Arc};
+
+use async_graphql_parser::types::{BaseType, Field, FieldDefinition, Type, TypeKind};
+use async_graphql_value::ConstValue;
+use serde_json::Value as JsonValue;
+
+use crate::schema::{get_builtin_scalars, get_deprecation_reason, get_vertex_type_fields};
+
+use super::{Context, GraphQLError, ResponseValue};
+
+/// Resolve the `__schema` or `__type` field of the root query type.
+pub(super) fn resolve_root<'a>(
+    context: &Context<'a>,
+    fields: &[&'a Field],
+) -> Result<ResponseValue, GraphQLError> {
+    let introspector = Introspector { context };
+    let field = fields[0];
+    if field.name.node == "__schema" {
+        return introspector.complete(&Node::Schema, fields);
+    }
+
+    let name = match field.get_argument("name").map(|name| context.resolve_value(&name.node)) {
+        Some(Ok(Some(ConstValue::String(name)))) => name,
+        Some(Err(e)) => return Err(e),
+        _ => {
+            return Err(GraphQLError::new("Field \"__type\" requires a String \"name\" argument."))
+        }
+    };
+    if introspector.kind(&name).is_some() {
+        introspector.complete(&Node::Type(TypeRef::Named(name)), fields)
+    } else {
+        Ok(ResponseValue::Leaf(JsonValue::Null))
+    }
+}
+
+/// A value of one of the introspection types, such as `__Type`.
+#[derive(Debug, Clone)]
+enum Node<'a> {
+    Schema,
+    Type(TypeRef),
+    Field { type_name: &'a str, definition: &'a FieldDefinition },
+    InputValue(InputValue),
+    Directive(&'static BuiltinDirective),
+}
+
+impl Node<'_> {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Node::Schema => "__Schema",
+            Node::Type(_) => "__Type",
+            Node::Field { .. } => "__Field",
+            Node::InputValue(_) => "__InputValue",
+            Node::Directive(_) => "__Directive",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TypeRef {
+    Named(String),
+    NonNull(Box<TypeRef>),
+    List(Box<TypeRef>),
+}
+
+impl From<&Type> for TypeRef {
+    fn from(ty: &Type) -> Self {
+        let nullable = match &ty.base {
+            BaseType::Named(name) => TypeRef::Named(name.to_string()),
+            BaseType::List(inner) => TypeRef::List(Box::new(inner.as_ref().into())),
+        };
+        if ty.nullable {
+            nullable
+        } else {
+            TypeRef::NonNull(Box::new(nullable))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct InputValue {
+    name: String,
+    description: Option<String>,
+    ty: TypeRef,
+    default_value: Option<String>,
+}
+
+#[derive(Debug)]
+struct BuiltinDirective {
+    name: &'static str,
+    description: &'static str,
+    locations: &'static [&'static str],
+    /// The directive's only argument, as `(name, type, default value, description)`.
+    argument: (&'static str, &'static str, Option<&'static str>, &'static str),
+}
+
+/// The directives that GraphQL requests may use. Trustfall's own directives, like `@filter`,
+/// are only meaningful in Trustfall queries, so they aren't part of the GraphQL API.
+const BUILTIN_DIRECTIVES: &[BuiltinDirective] = &[
+    BuiltinDirective {
+        name: "include",
+        description: "Directs the executor to include this field or fragment only when the `if` \
+            argument is true.",
+        locations: &["FIELD", "FRAGMENT_SPREAD", "INLINE_FRAGMENT"],
+        argument: ("if", "Boolean!", None, "Included when true."),
+    },
+    BuiltinDirective {
+        name: "skip",
+        description: "Directs the executor to skip this field or fragment when the `if` \
+            argument is true.",
+        locations: &["FIELD", "FRAGMENT_SPREAD", "INLINE_FRAGMENT"],
+        argument: ("if", "Boolean!", None, "Skipped when true."),
+    },
+    BuiltinDirective {
+        name: "deprecated",
+        description: "Marks an element of a GraphQL schema as no longer supported.",
+        locations: &["FIELD_DEFINITION", "ARGUMENT_DEFINITION"],
+        argument: (
+            "reason",
+            "String",
+            Some("\"No longer supported\""),
+            "Explains why this element was deprecated.",
+        ),
+    },
+];
+
+/// The result of resolving a field of an introspection type, before its subfields are selected.
+enum Resolved<'a> {
+    Leaf(JsonValue),
+    Object(Option<Node<'a>>),
+    List(Option<Vec<Node<'a>>>),
+}
+
+struct Introspector<'a, 'c> {
+    context: &'c Context<'a>,
+}
+
+impl<'a> Introspector<'a, '_> {
+    fn complete(
+        &self,
+        node: &Node<'a>,
+        fields: &[&'a Field],
+    ) -> Result<ResponseValue, GraphQLError> {
+        let mut object = vec![];
+        for (key, fields) in self.context.collect_subfields(node.type_name(), fields)? {
+            let name = fields[0].name.node.as_str();
+            let value = match name {
+                "__typename" => ResponseValue::Leaf(node.type_name().into()),
+                _ => match self.resolve(node, name, fields[0])? {
+                    Resolved::Leaf(value) => ResponseValue::Leaf(value),
+                    Resolved::Object(None) | Resolved::List(None) => {
+                        ResponseValue::Leaf(JsonValue::Null)
+                    }
+                    Resolved::Object(Some(node)) => self.complete(&node, &fields)?,
+                    Resolved::List(Some(nodes)) => ResponseValue::List(
+                        nodes
+                            .iter()
+                            .map(|node| self.complete(node, &fields))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                },
+            };
+            object.push((key, value));
+        }
+        Ok(ResponseValue::Object(object))
+    }
+
+    fn resolve(
+        &self,
+        node: &Node<'a>,
+        name: &str,
+        field: &'a Field,
+    ) -> Result<Resolved<'a>, GraphQLError> {
+        let schema = self.context.schema;
+        let resolved = match (node, name) {
+            (Node::Schema, "description") => Resolved::Leaf(JsonValue::Null),
+            (Node::Schema, "types") => {
+                let names: BTreeSet<&str> = get_builtin_scalars()
+                    .iter()
+                    .copied()
+                    .chain(schema.scalars.keys().map(Arc::as_ref))
+                    .chain(schema.vertex_types.keys().map(Arc::as_ref))
+                    .collect();
+                Resolved::List(Some(
+                    names.into_iter().map(|name| Node::Type(TypeRef::Named(name.into()))).collect(),
+                ))
+            }
+            (Node::Schema, "queryType") => {
+                Resolved::Object(Some(Node::Type(TypeRef::Named(schema.query_type_name().into()))))
+            }
+            (Node::Schema, "mutationType" | "subscriptionType") => Resolved::Object(None),
+            (Node::Schema, "directives") => {
+                Resolved::List(Some(BUILTIN_DIRECTIVES.iter().map(Node::Directive).collect()))
+            }
+
+            (Node::Type(TypeRef::Named(type_name)), _) => {
+                self.resolve_type(type_name, name, field)?
+            }
+            (Node::Type(TypeRef::NonNull(inner) | TypeRef::List(inner)), _) => match name {
+                "kind" => Resolved::Leaf(
+                    if matches!(node, Node::Type(TypeRef::NonNull(_))) {
+                        "NON_NULL"
+                    } else {
+                        "LIST"
+                    }
+                    .into(),
+                ),
+                "ofType" => Resolved::Object(Some(Node::Type(inner.as_ref().clone()))),
+                "name" | "description" | "specifiedByURL" | "isOneOf" => {
+                    Resolved::Leaf(JsonValue::Null)
+                }
+                "fields" | "interfaces" | "possibleTypes" | "enumValues" | "inputFields" => {
+                    Resolved::List(None)
+                }
+                _ => return Err(unknown_field(node, name)),
+            },
+
+            (Node::Field { type_name, definition }, _) => match name {
+                "name" => Resolved::Leaf(definition.name.node.as_str().into()),
+                "description" => {
+                    Resolved::Leaf(schema.field_docs(type_name, &definition.name.node).into())
+                }
+                "args" => Resolved::List(Some(
+                    definition
+                        .arguments
+                        .iter()
+                        .map(|argument| {
+                            let argument = &argument.node;
+                            Node::InputValue(InputValue {
+                                name: argument.name.node.to_string(),
+                                description: argument
+                                    .description
+                                    .as_ref()
+                                    .map(|docs| docs.node.clone()),
+                                ty: (&argument.ty.node).into(),
+                                default_value: argument
+                                    .default_value
+                                    .as_ref()
+                                    .map(|value| value.node.to_string()),
+                            })
+                        })
+                        .collect(),
+                )),
+                "type" => Resolved::Object(Some(Node::Type((&definition.ty.node).into()))),
+                "isDeprecated" => {
+                    Resolved::Leaf(get_deprecation_reason(definition).is_some().into())
+                }
+                "deprecationReason" => Resolved::Leaf(get_deprecation_reason(definition).into()),
+                _ => return Err(unknown_field(node, name)),
+            },
+
+            (Node::InputValue(input), _) => match name {
+                "name" => Resolved::Leaf(input.name.as_str().into()),
+                "description" => Resolved::Leaf(input.description.clone().into()),
+                "type" => Resolved::Object(Some(Node::Type(input.ty.clone()))),
+                "defaultValue" => Resolved::Leaf(input.default_value.clone().into()),
+                "isDeprecated" => Resolved::Leaf(false.into()),
+                "deprecationReason" => Resolved::Leaf(JsonValue::Null),
+                _ => return Err(unknown_field(node, name)),
+            },
+
+            (Node::Directive(directive), _) => match name {
+                "name" => Resolved::Leaf(directive.name.into()),
+                "description" => Resolved::Leaf(directive.description.into()),
+                "locations" => Resolved::Leaf(directive.locations.into()),
+                "args" => {
+                    let (name, ty, default_value, description) = directive.argument;
+                    let ty = Type::new(ty).expect("invalid builtin directive argument type");
+                    Resolved::List(Some(vec![Node::InputValue(InputValue {
+                        name: name.to_string(),
+                        description: Some(description.to_string()),
+                        ty: (&ty).into(),
+                        default_value: default_value.map(String::from),
+                    })]))
+                }
+                "isRepeatable" => Resolved::Leaf(false.into()),
+                _ => return Err(unknown_field(node, name)),
+            },
+
+            (Node::Schema, _) => return Err(unknown_field(node, name)),
+        };
+        Ok(resolved)
+    }
+
+    fn resolve_type(
+        &self,
+        type_name: &str,
+        name: &str,
+        field: &'a Field,
+    ) -> Result<Resolved<'a>, GraphQLError> {
+        let schema = self.context.schema;
+        let vertex_type = schema.vertex_types.get_key_value(type_name);
+        let kind = self.kind(type_name).expect("introspected an unknown type");
+        let resolved = match name {
+            "kind" => Resolved::Leaf(kind.into()),
+            "name" => Resolved::Leaf(type_name.into()),
+            "description" => Resolved::Leaf(schema.type_docs(type_name).into()),
+            "fields" => match vertex_type {
+                Some((type_name, definition)) if matches!(kind, "OBJECT" | "INTERFACE") => {
+                    let include_deprecated = match field.get_argument("includeDeprecated") {
+                        Some(value) => matches!(
+                            self.context.resolve_value(&value.node)?,
+                            Some(ConstValue::Boolean(true))
+                        ),
+                        None => false,
+                    };
+                    Resolved::List(Some(
+                        get_vertex_type_fields(definition)
+                            .iter()
+                            .map(|field| &field.node)
+                            .filter(|field| {
+                                include_deprecated || get_deprecation_reason(field).is_none()
+                            })
+                            .map(|definition| Node::Field { type_name, definition })
+                            .collect(),
+                    ))
+                }
+                _ => Resolved::List(None),
+            },
+            "interfaces" => match kind {
+                "OBJECT" | "INTERFACE" => Resolved::List(Some(
+                    schema
+                        .vertex_type_implements(type_name)
+                        .iter()
+                        .map(|name| Node::Type(TypeRef::Named(name.node.to_string())))
+                        .collect(),
+                )),
+                _ => Resolved::List(None),
+            },
+            "possibleTypes" => match kind {
+                "INTERFACE" | "UNION" => Resolved::List(Some(
+                    schema
+                        .subtypes(type_name)
+                        .into_iter()
+                        .flatten()
+                        .filter(|&subtype| self.kind(subtype) == Some("OBJECT"))
+                        .map(|subtype| Node::Type(TypeRef::Named(subtype.into())))
+                        .collect(),
+                )),
+                _ => Resolved::List(None),
+            },
+            "enumValues" | "inputFields" => Resolved::List(None),
+            "ofType" => Resolved::Object(None),
+            "specifiedByURL" | "isOneOf" => Resolved::Leaf(JsonValue::Null),
+            _ => return Err(unknown_field(&Node::Type(TypeRef::Named(type_name.into())), name)),
+        };
+        Ok(resolved)
+    }
+
+    /// The introspection `__TypeKind` of the named type, or `None` if there's no such type.
+    fn kind(&self, type_name: &str) -> Option<&'static str> {
+        let schema = self.context.schema;
+        if let Some(definition) = schema.vertex_types.get(type_name) {
+            Some(match &definition.kind {
+                TypeKind::Object(_) => "OBJECT",
+                TypeKind::Interface(_) => "INTERFACE",
+                TypeKind::Union(_) => "UNION",
+                _ => unreachable!("unexpected vertex type definition: {definition:?}"),
+            })
+        } else if schema.scalars.contains_key(type_name)
+            || get_builtin_scalars().contains(type_name)
+        {
+            Some("SCALAR")
+        } else {
+            None
+        }
+    }
+}
+
+fn unknown_field(node: &Node<'_>, name: &str) -> GraphQLError {
+    GraphQLError::new(format!("Cannot query field \"{name}\" on type \"{}\".", node.type_name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::super::tests::execute;
+
+    #[test]
+    fn types() {
+        let query = r#"
+{
+    number: __type(name: "Number") {
+        kind
+        name
+        interfaces { name }
+        possibleTypes { name }
+        fields(includeDeprecated: true) {
+            name
+            args { name type { kind name ofType { name } } defaultValue }
+            type { kind ofType { kind name } }
+            isDeprecated
+        }
+    }
+    factorizable: __type(name: "Factorizable") { kind possibleTypes { name } fields { name } }
+    int: __type(name: "Int") { kind name description fields { name } }
+    missing: __type(name: "Missing") { name }
+}"#;
+        let response = execute(query);
+        let data = &response["data"];
+        assert_eq!(json!("INTERFACE"), data["number"]["kind"], "{response}");
+        assert_eq!(json!([{ "name": "Named" }]), data["number"]["interfaces"]);
+        assert_eq!(
+            json!([{ "name": "Composite" }, { "name": "Neither" }, { "name": "Prime" }]),
+            data["number"]["possibleTypes"],
+        );
+
+        let fields = data["number"]["fields"].as_array().unwrap();
+        let multiple = fields.iter().find(|field| field["name"] == "multiple").unwrap();
+        assert_eq!(
+            json!({
+                "name": "multiple",
+                "args": [{
+                    "name": "max",
+                    "type": { "kind": "NON_NULL", "name": null, "ofType": { "name": "Int" } },
+                    "defaultValue": null,
+                }],
+                "type": { "kind": "LIST", "ofType": { "kind": "NON_NULL", "name": null } },
+                "isDeprecated": false,
+            }),
+            *multiple,
+        );
+
+        assert_eq!(json!("UNION"), data["factorizable"]["kind"]);
+        assert_eq!(json!(null), data["factorizable"]["fields"]);
+        assert_eq!(
+            json!({ "kind": "SCALAR", "name": "Int", "description": null, "fields": null }),
+            data["int"],
+        );
+        assert_eq!(json!(null), data["missing"]);
+    }
+
+    #[test]
+    fn schema() {
+        let query = r#"
+{
+    __schema {
+        __typename
+        queryType { name fields { name } }
+        mutationType { name }
+        types { name }
+        directives { name args { name defaultValue } }
+    }
+}"#;
+        let response = execute(query);
+        let schema = &response["data"]["__schema"];
+        assert_eq!(json!("__Schema"), schema["__typename"], "{response}");
+        assert_eq!(json!("RootSchemaQuery"), schema["queryType"]["name"]);
+        assert_eq!(json!(null), schema["mutationType"]);
+
+        let root_edges = schema["queryType"]["fields"].as_array().unwrap();
+        assert!(root_edges.contains(&json!({ "name": "Number" })));
+        assert!(!root_edges.contains(&json!({ "name": "__schema" })));
+
+        let types = schema["types"].as_array().unwrap();
+        for name in ["Boolean", "Composite", "Factorizable", "Int", "Number", "RootSchemaQuery"] {
+            assert!(types.contains(&json!({ "name": name })), "missing type {name}");
+        }
+        assert!(!types.iter().any(|ty| ty["name"].as_str().unwrap().starts_with("__")));
+
+        assert_eq!(
+            json!([
+                { "name": "include", "args": [{ "name": "if", "defaultValue": null }] },
+                { "name": "skip", "args": [{ "name": "if", "defaultValue": null }] },
+                {
+                    "name": "deprecated",
+                    "args": [{ "name": "reason", "defaultValue": "\"No longer supported\"" }],
+                },
+            ]),
+            schema["directives"],
+        );
+    }
+
+    #[test]
+    fn full_introspection_query() {
+        let query = r#"
+query IntrospectionQuery {
+    __schema {
+        queryType { name }
+        mutationType { name }
+        subscriptionType { name }
+        types { ...FullType }
+        directives {
+            name
+            description
+            locations
+            isRepeatable
+            args { ...InputValue }
+        }
+    }
+}
+
+fragment FullType on __Type {
+    kind
+    name
+    description
+    specifiedByURL
+    fields(includeDeprecated: true) {
+        name
+        description
+        args { ...InputValue }
+        type { ...TypeRef }
+        isDeprecated
+        deprecationReason
+    }
+    inputFields { ...InputValue }
+    interfaces { ...TypeRef }
+    enumValues(includeDeprecated: true) { name }
+    possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+    name
+    description
+    type { ...TypeRef }
+    defaultValue
+}
+
+fragment TypeRef on __Type {
+    kind
+    name
+    ofType { kind name ofType { kind name ofType { kind name } } }
+}"#;
+        let response = execute(query);
+        assert!(response.get("errors").is_none(), "{response}");
+
+        let types = response["data"]["__schema"]["types"].as_array().unwrap();
+        let prime = types.iter().find(|ty| ty["name"] == "Prime").unwrap();
+        assert_eq!(json!("OBJECT"), prime["kind"]);
+        assert_eq!(json!(null), prime["possibleTypes"]);
+        assert_eq!(
+            json!([
+                { "kind": "INTERFACE", "name": "Number", "ofType": null },
+                { "kind": "INTERFACE", "name": "Named", "ofType": null },
+            ]),
+            prime["interfaces"],
+        );
+    }
+
+    #[test]
+    fn unknown_fields() {
+        assert_eq!(
+            json!({ "errors": [{ "message": "Cannot query field \"nope\" on type \"__Schema\"." }] }),
+            execute("{ __schema { nope } }"),
+        );
+    }
+}
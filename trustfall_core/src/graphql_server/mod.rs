@@ -0,0 +1,682 @@
+//! Serving a Trustfall schema and adapter as a GraphQL API.
+//!
+//! Trustfall schemas are written in GraphQL's schema language, so they also describe
+//! a GraphQL API over the same data. [`GraphQLFacade`] executes GraphQL requests against
+//! such an API, which lets existing GraphQL clients and tools query Trustfall data sources.
+//! Introspection is supported, so tools like GraphiQL can explore the schema.
+//!
+//! Each field of the query's root type is translated into a Trustfall query starting at
+//! the root edge of the same name. Properties in the field's selection set become outputs,
+//! and edges become `@fold`s, so that each result of the Trustfall query becomes one object
+//! of the GraphQL response, with its neighbors nested within it. For example, this request:
+//! ```graphql
+//! { Number(max: 3) { value successor { value } } }
+//! ```
+//! becomes the Trustfall query:
+//! ```graphql
+//! {
+//!     Number(max: 3) {
+//!         __typename @output(name: "o0")
+//!         value @output(name: "o1")
+//!         successor @fold {
+//!             __typename @output(name: "o2")
+//!             value @output(name: "o3")
+//!         }
+//!     }
+//! }
+//! ```
+//! The `__typename` outputs count the neighbors at each edge, even if none of their fields
+//! end up being selected due to `@skip` or `@include` directives.
+//!
+//! Only queries are supported, as Trustfall is read-only. Fragments must not narrow
+//! the type of the vertex they apply to, since a Trustfall type coercion would filter out
+//! vertices of other types instead of omitting the fragment's fields for them.
+//! Fields whose values are fetched by a translated query are requested as a whole,
+//! so an error in any of them fails the entire request.
+//!
+//! Set [`GraphQLLimits`] with [`GraphQLFacade::with_limits()`] to bound the time and number
+//! of results of each request, e.g. when serving requests from untrusted clients.
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use async_graphql_parser::{
+    parse_query,
+    types::{
+        Directive, ExecutableDocument, Field, FragmentDefinition, OperationDefinition,
+        OperationType, Selection, SelectionSet, VariableDefinition,
+    },
+    Positioned,
+};
+use async_graphql_value::{ConstValue, Name, Value};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+    frontend,
+    interpreter::{
+        error::QueryCancelled, execution::interpret_ir_with_cancellation, Adapter,
+        CancellationToken,
+    },
+    schema::Schema,
+};
+
+mod introspection;
+mod translation;
+
+/// A GraphQL request, as sent by GraphQL clients in the body of an HTTP `POST` request.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GraphQLRequest {
+    /// The text of the GraphQL document containing the operation to execute.
+    pub query: String,
+
+    /// The values of the operation's variables.
+    #[serde(default)]
+    pub variables: Option<Map<String, JsonValue>>,
+
+    /// Which of the document's operations to execute.
+    /// Only required if the document contains more than one operation.
+    #[serde(default, rename = "operationName")]
+    pub operation_name: Option<String>,
+}
+
+impl GraphQLRequest {
+    /// A request for the document's only operation, without any variables.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self { query: query.into(), variables: None, operation_name: None }
+    }
+}
+
+/// An error reported in a GraphQL response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphQLError {
+    pub message: String,
+}
+
+impl GraphQLError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+/// The response to a GraphQL request.
+///
+/// Serializes to the JSON of a GraphQL response, with a `data` key with the requested data
+/// if the request succeeded, or an `errors` key with a list of errors if it failed.
+/// The keys of response objects are in the order in which their fields were selected.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphQLResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<ResponseValue>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<GraphQLError>,
+}
+
+impl GraphQLResponse {
+    pub fn errors(&self) -> &[GraphQLError] {
+        &self.errors
+    }
+}
+
+/// A value within a GraphQL response, whose objects preserve the order of their keys.
+#[derive(Debug, Clone, PartialEq)]
+enum ResponseValue {
+    Leaf(JsonValue),
+    List(Vec<ResponseValue>),
+    Object(Vec<(String, ResponseValue)>),
+}
+
+impl Serialize for ResponseValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ResponseValue::Leaf(value) => value.serialize(serializer),
+            ResponseValue::List(values) => values.serialize(serializer),
+            ResponseValue::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Limits on the work done to execute each GraphQL request.
+/// Limits that are `None` are not enforced, and none are enforced by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphQLLimits {
+    /// The maximum number of results a request's Trustfall queries may produce, in total.
+    pub max_results: Option<usize>,
+
+    /// How long a request may run before it's stopped.
+    ///
+    /// The timeout is checked each time the adapter produces a vertex,
+    /// so an adapter that blocks indefinitely cannot be interrupted.
+    pub timeout: Option<Duration>,
+}
+
+/// Executes GraphQL requests over a Trustfall schema and adapter. See the [module docs](self).
+#[derive(Debug)]
+pub struct GraphQLFacade<AdapterT> {
+    schema: Schema,
+    adapter: Arc<AdapterT>,
+    limits: GraphQLLimits,
+}
+
+/// The work a request has done so far, checked against the facade's [`GraphQLLimits`].
+struct Budget {
+    token: CancellationToken,
+    max_results: Option<usize>,
+    produced: usize,
+}
+
+impl<'vertex, AdapterT: Adapter<'vertex> + 'vertex> GraphQLFacade<AdapterT> {
+    pub fn new(schema: Schema, adapter: Arc<AdapterT>) -> Self {
+        Self { schema, adapter, limits: GraphQLLimits::default() }
+    }
+
+    /// Set the limits on the work done to execute each request.
+    pub fn with_limits(mut self, limits: GraphQLLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn limits(&self) -> &GraphQLLimits {
+        &self.limits
+    }
+
+    /// Execute a request, producing its GraphQL response.
+    pub fn execute(&self, request: &GraphQLRequest) -> GraphQLResponse {
+        match self.execute_operation(request) {
+            Ok(data) => GraphQLResponse { data: Some(data), errors: vec![] },
+            Err(error) => GraphQLResponse { data: None, errors: vec![error] },
+        }
+    }
+
+    fn execute_operation(&self, request: &GraphQLRequest) -> Result<ResponseValue, GraphQLError> {
+        let document = parse_query(&request.query).map_err(|e| GraphQLError::new(e.to_string()))?;
+        let operation = select_operation(&document, request.operation_name.as_deref())?;
+        if operation.ty != OperationType::Query {
+            return Err(GraphQLError::new(format!(
+                "Only queries are supported, but the operation is a {}.",
+                operation.ty
+            )));
+        }
+
+        let variables =
+            coerce_variables(&operation.variable_definitions, request.variables.as_ref())?;
+        let context = Context { schema: &self.schema, fragments: &document, variables };
+
+        let mut budget = Budget {
+            token: self.limits.timeout.map(CancellationToken::with_timeout).unwrap_or_default(),
+            max_results: self.limits.max_results,
+            produced: 0,
+        };
+        let root_type = self.schema.query_type_name();
+        let mut data = vec![];
+        for (key, fields) in context.collect_fields(root_type, [&operation.selection_set.node])? {
+            let value = match fields[0].name.node.as_str() {
+                "__typename" => ResponseValue::Leaf(root_type.into()),
+                "__schema" | "__type" => introspection::resolve_root(&context, &fields)?,
+                _ => self.execute_root_field(&context, &fields, &mut budget)?,
+            };
+            data.push((key, value));
+        }
+        Ok(ResponseValue::Object(data))
+    }
+
+    fn execute_root_field(
+        &self,
+        context: &Context<'_>,
+        fields: &[&Field],
+        budget: &mut Budget,
+    ) -> Result<ResponseValue, GraphQLError> {
+        let root = translation::translate_root_field(context, fields)?;
+        let query = frontend::parse(&self.schema, &root.query).map_err(|e| {
+            GraphQLError::new(format!(
+                "Field \"{}\" could not be executed: {e}",
+                fields[0].response_key().node
+            ))
+        })?;
+        let results = interpret_ir_with_cancellation(
+            self.adapter.clone(),
+            query,
+            Arc::new(BTreeMap::new()),
+            budget.token.clone(),
+        )
+        .map_err(|e| GraphQLError::new(e.to_string()))?;
+
+        let mut objects = results.map(|row| {
+            let row = row.map_err(|QueryCancelled| {
+                GraphQLError::new("The request took too long, and was stopped.")
+            })?;
+            if Some(budget.produced) == budget.max_results {
+                return Err(GraphQLError::new(format!(
+                    "The request produced more than the maximum of {} results.",
+                    budget.produced
+                )));
+            }
+            budget.produced += 1;
+            Ok(translation::assemble(&root.selections, &row))
+        });
+        if root.is_list {
+            Ok(ResponseValue::List(objects.collect::<Result<_, _>>()?))
+        } else {
+            Ok(objects.next().transpose()?.unwrap_or(ResponseValue::Leaf(JsonValue::Null)))
+        }
+    }
+}
+
+fn select_operation<'a>(
+    document: &'a ExecutableDocument,
+    operation_name: Option<&str>,
+) -> Result<&'a OperationDefinition, GraphQLError> {
+    let mut operations = document.operations.iter();
+    let operation =
+        match operation_name {
+            Some(name) => operations
+                .find(|(operation, _)| operation.map(Name::as_str) == Some(name))
+                .ok_or_else(|| GraphQLError::new(format!("Unknown operation named \"{name}\".")))?,
+            None => {
+                let operation = operations.next().expect("documents have at least one operation");
+                if operations.next().is_some() {
+                    return Err(GraphQLError::new(
+                        "Must provide operation name if query contains multiple operations.",
+                    ));
+                }
+                operation
+            }
+        };
+    Ok(&operation.1.node)
+}
+
+fn coerce_variables(
+    definitions: &[Positioned<VariableDefinition>],
+    provided: Option<&Map<String, JsonValue>>,
+) -> Result<BTreeMap<Name, ConstValue>, GraphQLError> {
+    let mut variables = BTreeMap::new();
+    for definition in definitions {
+        let definition = &definition.node;
+        let name = &definition.name.node;
+        let value = match provided.and_then(|provided| provided.get(name.as_str())) {
+            Some(value) => ConstValue::from_json(value.clone()).map_err(|e| {
+                GraphQLError::new(format!("Variable \"${name}\" has an invalid value: {e}"))
+            })?,
+            None => match definition.default_value() {
+                Some(default) => default.clone(),
+                None if !definition.var_type.node.nullable => {
+                    return Err(GraphQLError::new(format!(
+                        "Variable \"${name}\" of required type \"{}\" was not provided.",
+                        definition.var_type.node
+                    )));
+                }
+                None => continue,
+            },
+        };
+        variables.insert(name.clone(), value);
+    }
+    Ok(variables)
+}
+
+/// What's needed to interpret the selections of a GraphQL operation.
+struct Context<'a> {
+    schema: &'a Schema,
+    fragments: &'a ExecutableDocument,
+    variables: BTreeMap<Name, ConstValue>,
+}
+
+/// Fields grouped by their response key, in the order in which the keys first appear.
+type FieldGroups<'a> = Vec<(String, Vec<&'a Field>)>;
+
+impl<'a> Context<'a> {
+    /// Gather the fields selected by the given selection sets of a value of the named type,
+    /// following fragments and applying `@skip` and `@include` directives.
+    fn collect_fields(
+        &self,
+        type_name: &str,
+        selection_sets: impl IntoIterator<Item = &'a SelectionSet>,
+    ) -> Result<FieldGroups<'a>, GraphQLError> {
+        let mut fields = vec![];
+        let mut visited_fragments = vec![];
+        for selection_set in selection_sets {
+            self.collect_fields_into(
+                type_name,
+                selection_set,
+                &mut fields,
+                &mut visited_fragments,
+            )?;
+        }
+        Ok(fields)
+    }
+
+    /// Gather the fields selected within any of the given fields, which share a response key.
+    fn collect_subfields(
+        &self,
+        type_name: &str,
+        fields: &[&'a Field],
+    ) -> Result<FieldGroups<'a>, GraphQLError> {
+        self.collect_fields(type_name, fields.iter().map(|field| &field.selection_set.node))
+    }
+
+    fn collect_fields_into(
+        &self,
+        type_name: &str,
+        selection_set: &'a SelectionSet,
+        fields: &mut FieldGroups<'a>,
+        visited_fragments: &mut Vec<&'a str>,
+    ) -> Result<(), GraphQLError> {
+        for selection in &selection_set.items {
+            if !self.should_include(selection.node.directives())? {
+                continue;
+            }
+            match &selection.node {
+                Selection::Field(field) => {
+                    let key = field.node.response_key().node.as_str();
+                    match fields.iter_mut().find(|(existing, _)| existing == key) {
+                        Some((_, group)) => group.push(&field.node),
+                        None => fields.push((key.to_string(), vec![&field.node])),
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.as_str();
+                    if visited_fragments.contains(&name) {
+                        continue;
+                    }
+                    visited_fragments.push(name);
+
+                    let fragment: &FragmentDefinition = &self
+                        .fragments
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| GraphQLError::new(format!("Unknown fragment \"{name}\".")))?
+                        .node;
+                    self.check_type_condition(type_name, &fragment.type_condition.node.on.node)?;
+                    self.collect_fields_into(
+                        type_name,
+                        &fragment.selection_set.node,
+                        fields,
+                        visited_fragments,
+                    )?;
+                }
+                Selection::InlineFragment(fragment) => {
+                    if let Some(condition) = &fragment.node.type_condition {
+                        self.check_type_condition(type_name, &condition.node.on.node)?;
+                    }
+                    self.collect_fields_into(
+                        type_name,
+                        &fragment.node.selection_set.node,
+                        fields,
+                        visited_fragments,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a fragment with the given type condition applies to every value
+    /// of the named type.
+    fn check_type_condition(&self, type_name: &str, condition: &str) -> Result<(), GraphQLError> {
+        let is_vertex_type = |name: &str| self.schema.vertex_types.contains_key(name);
+        if condition == type_name
+            || (is_vertex_type(type_name)
+                && is_vertex_type(condition)
+                && self.schema.is_named_type_subtype(condition, type_name))
+        {
+            return Ok(());
+        }
+
+        if is_vertex_type(type_name)
+            && is_vertex_type(condition)
+            && self.schema.is_named_type_subtype(type_name, condition)
+        {
+            Err(GraphQLError::new(format!(
+                "Fragments on type \"{condition}\" within a selection of type \"{type_name}\" \
+                are not supported, since they only apply to some of the selected values."
+            )))
+        } else {
+            Err(GraphQLError::new(format!(
+                "Fragment cannot be spread here as objects of type \"{type_name}\" \
+                can never be of type \"{condition}\"."
+            )))
+        }
+    }
+
+    fn should_include(&self, directives: &[Positioned<Directive>]) -> Result<bool, GraphQLError> {
+        for directive in directives {
+            let directive = &directive.node;
+            let name = directive.name.node.as_str();
+            let included_when = match name {
+                "skip" => false,
+                "include" => true,
+                _ => return Err(GraphQLError::new(format!("Unknown directive \"@{name}\"."))),
+            };
+            let condition = directive
+                .get_argument("if")
+                .map(|value| self.resolve_value(&value.node))
+                .transpose()?
+                .flatten();
+            match condition {
+                Some(ConstValue::Boolean(condition)) if condition != included_when => {
+                    return Ok(false)
+                }
+                Some(ConstValue::Boolean(_)) => {}
+                _ => {
+                    return Err(GraphQLError::new(format!(
+                        "Directive \"@{name}\" requires a Boolean \"if\" argument."
+                    )))
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// The value of an argument, with variables replaced by their values.
+    ///
+    /// Arguments whose value is a variable that wasn't provided are treated as absent,
+    /// so `None` is returned for them.
+    fn resolve_value(&self, value: &Value) -> Result<Option<ConstValue>, GraphQLError> {
+        if let Value::Variable(name) = value {
+            return Ok(self.variables.get(name).cloned());
+        }
+        value
+            .clone()
+            .into_const_with(|name| match self.variables.get(&name) {
+                Some(value) => Ok(value.clone()),
+                None => Ok(ConstValue::Null),
+            })
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc, time::Duration};
+
+    use serde_json::json;
+
+    use crate::{numbers_interpreter::NumbersAdapter, schema::Schema};
+
+    use super::{GraphQLFacade, GraphQLLimits, GraphQLRequest};
+
+    fn facade() -> GraphQLFacade<NumbersAdapter> {
+        let schema = Schema::parse(
+            fs::read_to_string("test_data/schemas/numbers.graphql").expect("failed to read schema"),
+        )
+        .expect("invalid schema");
+        GraphQLFacade::new(schema.clone(), Arc::new(NumbersAdapter::new()))
+    }
+
+    pub(super) fn execute(query: &str) -> serde_json::Value {
+        serde_json::to_value(facade().execute(&GraphQLRequest::new(query))).unwrap()
+    }
+
+    fn error(query: &str) -> String {
+        let response = execute(query);
+        response["errors"][0]["message"]
+            .as_str()
+            .unwrap_or_else(|| panic!("expected an error, got {response}"))
+            .to_string()
+    }
+
+    #[test]
+    fn nested_selections() {
+        let query = r#"
+query Numbers($max: Int!) {
+    numbers: Number(min: 2, max: $max) {
+        value
+        ...Names
+        next: successor { value }
+        multiple(max: 3) { value }
+    }
+    zero: Zero { value predecessor { value } }
+    __typename
+}
+
+fragment Names on Named {
+    name
+}"#;
+        let request = GraphQLRequest {
+            variables: Some(json!({ "max": 3 }).as_object().unwrap().clone()),
+            ..GraphQLRequest::new(query)
+        };
+        let expected = json!({
+            "data": {
+                "numbers": [
+                    {
+                        "value": 2,
+                        "name": "two",
+                        "next": { "value": 3 },
+                        "multiple": [{ "value": 4 }, { "value": 6 }],
+                    },
+                    {
+                        "value": 3,
+                        "name": "three",
+                        "next": { "value": 4 },
+                        "multiple": [{ "value": 6 }, { "value": 9 }],
+                    },
+                ],
+                "zero": { "value": 0, "predecessor": null },
+                "__typename": "RootSchemaQuery",
+            }
+        });
+        assert_eq!(expected, serde_json::to_value(facade().execute(&request)).unwrap());
+    }
+
+    #[test]
+    fn responses_preserve_selection_order() {
+        let response = facade().execute(&GraphQLRequest::new("{ Two { value name __typename } }"));
+        assert_eq!(
+            r#"{"data":{"Two":{"value":2,"name":"two","__typename":"Prime"}}}"#,
+            serde_json::to_string(&response).unwrap(),
+        );
+    }
+
+    #[test]
+    fn aliases_directives_and_nested_edges() {
+        let query = r#"
+{
+    One {
+        __typename
+        a: value
+        b: value
+        skipped: name @skip(if: true)
+        included: name @include(if: true)
+        successor {
+            successor { ... on Number { value } }
+            ... @include(if: false) { name }
+        }
+        successor { __typename }
+    }
+}"#;
+        let expected = json!({
+            "data": {
+                "One": {
+                    "__typename": "Neither",
+                    "a": 1,
+                    "b": 1,
+                    "included": "one",
+                    "successor": { "successor": { "value": 3 }, "__typename": "Prime" },
+                }
+            }
+        });
+        assert_eq!(expected, execute(query));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "Cannot query field \"nonexistent\" on type \"Number\".",
+            error("{ Zero { nonexistent } }"),
+        );
+        assert_eq!(
+            "Field \"successor\" of type \"Number!\" must have a selection of subfields.",
+            error("{ Zero { successor } }"),
+        );
+        assert_eq!(
+            "Field \"value\" of type \"Int\" must not have a selection of subfields.",
+            error("{ Zero { value { x } } }"),
+        );
+        assert_eq!(
+            "Variable \"$max\" of required type \"Int!\" was not provided.",
+            error("query($max: Int!) { Number(max: $max) { value } }"),
+        );
+        assert_eq!(
+            "Only queries are supported, but the operation is a mutation.",
+            error("mutation { Zero { value } }"),
+        );
+        assert_eq!(
+            "Must provide operation name if query contains multiple operations.",
+            error("query A { Zero { value } } query B { One { value } }"),
+        );
+        assert!(
+            error("{ Zero { ... on Prime { value } } }").contains("are not supported"),
+            "{}",
+            error("{ Zero { ... on Prime { value } } }"),
+        );
+        assert!(error("{ Zero { ...Missing } }").contains("Unknown fragment"));
+        assert!(error("{ Zero { value @output } }").contains("Unknown directive"));
+        assert!(error("{ Number { value } }").contains("Field \"Number\" could not be executed"));
+        assert!(error("{ Zero { value ").contains("expected"));
+    }
+
+    #[test]
+    fn operation_names() {
+        let request = GraphQLRequest {
+            operation_name: Some("B".into()),
+            ..GraphQLRequest::new("query A { Zero { value } } query B { One { value } }")
+        };
+        let response = facade().execute(&request);
+        assert!(response.errors().is_empty());
+        assert_eq!(
+            json!({ "data": { "One": { "value": 1 } } }),
+            serde_json::to_value(response).unwrap()
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let query = "{ small: Number(max: 2) { value } large: Number(max: 5) { value } }";
+        let limits = GraphQLLimits { max_results: Some(4), ..Default::default() };
+        let response = facade().with_limits(limits).execute(&GraphQLRequest::new(query));
+        assert_eq!(
+            json!({ "errors": [{ "message": "The request produced more than the maximum of 4 results." }] }),
+            serde_json::to_value(response).unwrap(),
+        );
+
+        let limits = GraphQLLimits { max_results: Some(9), ..Default::default() };
+        let response = facade().with_limits(limits).execute(&GraphQLRequest::new(query));
+        assert!(response.errors().is_empty());
+
+        let limits = GraphQLLimits { timeout: Some(Duration::ZERO), ..Default::default() };
+        let response = facade().with_limits(limits).execute(&GraphQLRequest::new(query));
+        assert_eq!(
+            json!({ "errors": [{ "message": "The request took too long, and was stopped." }] }),
+            serde_json::to_value(response).unwrap(),
+        );
+    }
+}
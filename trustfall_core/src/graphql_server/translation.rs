@@ -0,0 +1,232 @@
+//! Translating the fields of a GraphQL query into Trustfall queries,
+//! and assembling their results into GraphQL response objects.
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use async_graphql_parser::types::{BaseType, Field, Type};
+
+use crate::ir::{FieldValue, TransparentValue};
+
+use super::{Context, GraphQLError, ResponseValue};
+
+/// A field selected on a vertex, and where its value is found in the Trustfall query's results.
+#[derive(Debug)]
+pub(super) enum Selected {
+    /// A property or `__typename`, whose value is the named output.
+    Output { key: String, output: Arc<str> },
+
+    /// An edge, whose neighbors are `@fold`-ed into lists of their outputs' values.
+    ///
+    /// Each neighbor outputs its `__typename` as `count`, so the length of that list
+    /// is the number of neighbors even if none of their fields are selected.
+    Neighbors { key: String, count: Arc<str>, is_list: bool, selections: Vec<Selected> },
+}
+
+/// The Trustfall query for a field of the GraphQL query's root type.
+#[derive(Debug)]
+pub(super) struct RootField {
+    pub(super) query: String,
+    pub(super) is_list: bool,
+    pub(super) selections: Vec<Selected>,
+}
+
+pub(super) fn translate_root_field<'a>(
+    context: &Context<'a>,
+    fields: &[&'a Field],
+) -> Result<RootField, GraphQLError> {
+    let root_type = context.schema.query_type_name();
+    let mut translator = Translator { context, query: String::from("{\n"), outputs: 0 };
+
+    let (edge, vertex_type, is_list) = translator.translate_edge(root_type, fields)?;
+    translator.write_line(1, &format!("{edge} {{"));
+    // Ensures that the query has an output even if none of the vertex's fields are selected.
+    translator.next_output(2);
+    let selections = translator.translate_selections(vertex_type, fields, 2)?;
+    translator.write_line(1, "}");
+    translator.query.push_str("}\n");
+
+    Ok(RootField { query: translator.query, is_list, selections })
+}
+
+struct Translator<'a, 'c> {
+    context: &'c Context<'a>,
+    query: String,
+    outputs: usize,
+}
+
+impl<'a> Translator<'a, '_> {
+    fn write_line(&mut self, depth: usize, line: &str) {
+        writeln!(self.query, "{:indent$}{line}", "", indent = depth * 4).expect("write failed");
+    }
+
+    /// Output the `__typename` of the current vertex under a new output name.
+    fn next_output(&mut self, depth: usize) -> Arc<str> {
+        let output: Arc<str> = Arc::from(format!("o{}", self.outputs));
+        self.outputs += 1;
+        self.write_line(depth, &format!("__typename @output(name: \"{output}\")"));
+        output
+    }
+
+    fn translate_selections(
+        &mut self,
+        type_name: &str,
+        fields: &[&'a Field],
+        depth: usize,
+    ) -> Result<Vec<Selected>, GraphQLError> {
+        let mut selections = vec![];
+        for (key, fields) in self.context.collect_subfields(type_name, fields)? {
+            let name = fields[0].name.node.as_str();
+            if fields.iter().any(|field| field.name.node != name) {
+                return Err(GraphQLError::new(format!(
+                    "Fields \"{key}\" conflict because they select different fields."
+                )));
+            }
+
+            if name == "__typename" {
+                let output = self.next_output(depth);
+                selections.push(Selected::Output { key, output });
+                continue;
+            }
+
+            let definition = self.field_type(type_name, name)?;
+            if self.context.schema.vertex_types.contains_key(base_type_name(definition)) {
+                let (edge, vertex_type, is_list) = self.translate_edge(type_name, &fields)?;
+                self.write_line(depth, &format!("{edge} @fold {{"));
+                let count = self.next_output(depth + 1);
+                let neighbor_selections =
+                    self.translate_selections(vertex_type, &fields, depth + 1)?;
+                self.write_line(depth, "}");
+                selections.push(Selected::Neighbors {
+                    key,
+                    count,
+                    is_list,
+                    selections: neighbor_selections,
+                });
+            } else {
+                if fields.iter().any(|field| !field.selection_set.node.items.is_empty()) {
+                    return Err(GraphQLError::new(format!(
+                        "Field \"{name}\" of type \"{definition}\" must not have a selection \
+                        of subfields."
+                    )));
+                }
+                if let Some((argument, _)) = fields[0].arguments.first() {
+                    return Err(GraphQLError::new(format!(
+                        "Unknown argument \"{}\" on field \"{type_name}.{name}\".",
+                        argument.node
+                    )));
+                }
+
+                let output: Arc<str> = Arc::from(format!("o{}", self.outputs));
+                self.outputs += 1;
+                self.write_line(depth, &format!("{name} @output(name: \"{output}\")"));
+                selections.push(Selected::Output { key, output });
+            }
+        }
+        Ok(selections)
+    }
+
+    /// The name and arguments of an edge as written in the Trustfall query,
+    /// together with the type of its neighbors and whether the edge is list-typed.
+    fn translate_edge(
+        &self,
+        type_name: &str,
+        fields: &[&'a Field],
+    ) -> Result<(String, &'a str, bool), GraphQLError> {
+        let field = fields[0];
+        let name = field.name.node.as_str();
+        let definition: &'a Type = self.field_type(type_name, name)?;
+        if fields.iter().all(|field| field.selection_set.node.items.is_empty()) {
+            return Err(GraphQLError::new(format!(
+                "Field \"{name}\" of type \"{definition}\" must have a selection of subfields."
+            )));
+        }
+
+        let mut arguments = vec![];
+        for (argument, value) in &field.arguments {
+            if let Some(value) = self.context.resolve_value(&value.node)? {
+                arguments.push(format!("{}: {value}", argument.node));
+            }
+        }
+        let edge = if arguments.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name}({})", arguments.join(", "))
+        };
+        Ok((edge, base_type_name(definition), matches!(definition.base, BaseType::List(_))))
+    }
+
+    fn field_type(&self, type_name: &str, field_name: &str) -> Result<&'a Type, GraphQLError> {
+        self.context
+            .schema
+            .fields
+            .get(&(Arc::from(type_name), Arc::from(field_name)))
+            .map(|definition| &definition.ty.node)
+            .ok_or_else(|| {
+                GraphQLError::new(format!(
+                    "Cannot query field \"{field_name}\" on type \"{type_name}\"."
+                ))
+            })
+    }
+}
+
+pub(super) fn base_type_name(ty: &Type) -> &str {
+    match &ty.base {
+        BaseType::Named(name) => name.as_str(),
+        BaseType::List(inner) => base_type_name(inner),
+    }
+}
+
+/// Build the response object for one result of a translated query.
+pub(super) fn assemble(
+    selections: &[Selected],
+    row: &BTreeMap<Arc<str>, FieldValue>,
+) -> ResponseValue {
+    let object = selections.iter().map(|selected| match selected {
+        Selected::Output { key, output } => {
+            let value = TransparentValue::from(row[output].clone());
+            let value = serde_json::to_value(value).expect("values are valid JSON");
+            (key.clone(), ResponseValue::Leaf(value))
+        }
+        Selected::Neighbors { key, count, is_list, selections } => {
+            let mut outputs = vec![];
+            output_names(selections, &mut outputs);
+            let neighbors = (0..folded(&row[count]).len()).map(|index| {
+                let neighbor_row = outputs
+                    .iter()
+                    .map(|output| {
+                        let value = folded(&row[output]).get(index).cloned();
+                        (output.clone(), value.unwrap_or(FieldValue::Null))
+                    })
+                    .collect();
+                assemble(selections, &neighbor_row)
+            });
+
+            let value = if *is_list {
+                ResponseValue::List(neighbors.collect())
+            } else {
+                neighbors.into_iter().next().unwrap_or(ResponseValue::Leaf(serde_json::Value::Null))
+            };
+            (key.clone(), value)
+        }
+    });
+    ResponseValue::Object(object.collect())
+}
+
+/// The names of all outputs within a vertex's selections, including those of its neighbors.
+fn output_names(selections: &[Selected], outputs: &mut Vec<Arc<str>>) {
+    for selected in selections {
+        match selected {
+            Selected::Output { output, .. } => outputs.push(output.clone()),
+            Selected::Neighbors { count, selections, .. } => {
+                outputs.push(count.clone());
+                output_names(selections, outputs);
+            }
+        }
+    }
+}
+
+fn folded(value: &FieldValue) -> &[FieldValue] {
+    match value {
+        FieldValue::List(values) => values,
+        _ => &[],
+    }
+}
@@ -0,0 +1,1182 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt::Debug,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::ir::{EdgeParameters, Eid, FieldValue, IndexedQuery};
+
+use super::{
+    error::QueryArgumentsError,
+    execution::{check_vertex_identifier, interpret_ir},
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, InterpretedQuery, ResolveEdgeInfo,
+    ResolveInfo, VertexIdentifier, VertexIterator,
+};
+
+/// The maximum number of vertices passed to a single [`AsyncAdapter`] resolver call.
+const BATCH_SIZE: usize = 64;
+
+/// The maximum number of [`AsyncAdapter`] resolver calls awaited at the same time.
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+type ResultRow = BTreeMap<Arc<str>, FieldValue>;
+
+/// A variant of the [`Adapter`] trait for data sources that are best accessed asynchronously,
+/// such as network APIs.
+///
+/// Queries over an `AsyncAdapter` are executed with [`interpret_ir_async()`] / the
+/// `execute_query_async()` function of the `trustfall` crate, or with [`interpret_ir_stream()`] /
+/// the `execute_query_stream()` function to get the results as a [`Stream`].
+/// While the adapter awaits data, the thread driving the query future is free
+/// to make progress on other tasks.
+///
+/// Compared to [`Adapter`], resolvers receive *batches of vertices* instead of iterators of
+/// query contexts, and return their outcomes eagerly. Each batch holds at most a few dozen
+/// vertices, making it a natural unit of work for batched network requests.
+///
+/// Vertices never need to be `None` in any of these methods:
+/// contexts without an active vertex are handled automatically, as required by [`Adapter`].
+///
+/// The query is executed on the task that polls the query future, without spawning threads,
+/// so neither the adapter nor its futures need to be thread-safe. This makes `AsyncAdapter`
+/// usable on single-threaded runtimes and in WebAssembly. The query future is `Send`
+/// whenever the adapter, its vertices, and the futures returned by its resolvers are.
+///
+/// Execution proceeds in passes over the query. Each pass resolves everything it can with
+/// the outcomes available so far, and requests all the outcomes it is missing; those requests
+/// are then awaited up to 16 at a time. Every vertex is only resolved once at each place
+/// in the query, and the number of passes grows with the depth of the query rather than
+/// with the amount of data. All outcomes are kept until the query ends.
+pub trait AsyncAdapter {
+    /// The type of vertices in the dataset this adapter queries.
+    type Vertex: Clone + Debug;
+
+    /// Produce the vertices for the specified starting edge.
+    ///
+    /// Analogous to [`Adapter::resolve_starting_vertices`], except that
+    /// the vertices are produced all at once.
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> impl Future<Output = Vec<Self::Vertex>>;
+
+    /// Resolve the value of a vertex property for each of the given vertices.
+    ///
+    /// Analogous to [`Adapter::resolve_property`]. The returned `Vec` must contain
+    /// exactly one value per input vertex, in the same order as the input vertices.
+    fn resolve_property(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> impl Future<Output = Vec<FieldValue>>;
+
+    /// Resolve the neighboring vertices across an edge, for each of the given vertices.
+    ///
+    /// Analogous to [`Adapter::resolve_neighbors`]. The returned `Vec` must contain
+    /// exactly one `Vec` of neighbors per input vertex, in the same order as the input vertices.
+    fn resolve_neighbors(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> impl Future<Output = Vec<Vec<Self::Vertex>>>;
+
+    /// Attempt to coerce each of the given vertices to a subtype.
+    ///
+    /// Analogous to [`Adapter::resolve_coercion`]. The returned `Vec` must contain
+    /// exactly one outcome per input vertex, in the same order as the input vertices.
+    fn resolve_coercion(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> impl Future<Output = Vec<bool>>;
+
+    /// Tell apart the vertices this adapter produces, if possible.
+    ///
+    /// Analogous to [`Adapter::vertex_identifier`]. The default implementation returns `None`,
+    /// and executing queries that use `@recurse(dedupe: true)` then returns a
+    /// [`QueryArgumentsError::UnidentifiableVertices`] error.
+    fn vertex_identifier<'vertex>(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>>
+    where
+        Self::Vertex: 'vertex,
+    {
+        None
+    }
+}
+
+/// Execute a query over an [`AsyncAdapter`], producing all of the query's results.
+///
+/// The query's arguments are validated before any adapter methods are called,
+/// the same way as in [`interpret_ir()`].
+pub async fn interpret_ir_async<'a, AdapterT: AsyncAdapter + 'a>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<Vec<BTreeMap<Arc<str>, FieldValue>>, QueryArgumentsError> {
+    let mut stream = interpret_ir_stream(adapter, indexed_query, arguments)?;
+
+    let mut rows = vec![];
+    while let Some(row) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Execute a query over an [`AsyncAdapter`], producing a [`Stream`] of the query's results.
+///
+/// The query's arguments are validated before any adapter methods are called,
+/// the same way as in [`interpret_ir()`].
+///
+/// Execution only makes progress while the stream is being polled, and only runs ahead
+/// of the results the stream has produced as far as it can without awaiting the adapter.
+/// Dropping the stream stops the query's execution.
+pub fn interpret_ir_stream<'a, AdapterT: AsyncAdapter + 'a>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<impl Stream<Item = BTreeMap<Arc<str>, FieldValue>> + Unpin + 'a, QueryArgumentsError> {
+    InterpretedQuery::from_query_and_arguments(indexed_query.clone(), arguments.clone())?;
+    check_vertex_identifier(&indexed_query, adapter.vertex_identifier().is_some())?;
+
+    let rows: Arc<Mutex<VecDeque<ResultRow>>> = Default::default();
+    let execution = execute(adapter, indexed_query, arguments, rows.clone(), Default::default());
+    Ok(QueryResultStream { rows, execution: Some(Box::pin(execution)) })
+}
+
+/// Identifies a vertex produced by the [`AsyncAdapter`] over the course of executing a query.
+type VertexId = u64;
+
+/// A vertex produced by the [`AsyncAdapter`], together with an ID that identifies it
+/// in every pass over the query.
+#[derive(Debug, Clone)]
+struct Tracked<Vertex> {
+    id: VertexId,
+    vertex: Vertex,
+}
+
+/// Identifies a [`Site`] by the order in which query execution first reached it.
+type SiteId = usize;
+
+/// A place in the query where resolvers are called. Resolving the same vertex
+/// at the same site always produces the same outcome.
+///
+/// Neighbors are keyed by edge rather than by type and edge name, since adapters may use
+/// each edge's hints to leave out neighbors that the query would discard anyway.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Site {
+    StartingVertices {
+        edge_name: Arc<str>,
+        parameters: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    },
+    Property {
+        type_name: Arc<str>,
+        property_name: Arc<str>,
+    },
+    Neighbors {
+        eid: Eid,
+        type_name: Arc<str>,
+        edge_name: Arc<str>,
+        parameters: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    },
+    Coercion {
+        type_name: Arc<str>,
+        coerce_to_type: Arc<str>,
+    },
+}
+
+/// The resolver outcome for one vertex at a site.
+enum Outcome<Vertex> {
+    Property(FieldValue),
+    Neighbors(Arc<[Tracked<Vertex>]>),
+    Coercion(bool),
+}
+
+/// The resolver outcomes collected over the course of executing a query.
+///
+/// Query execution is synchronous, so it cannot await the adapter's futures. Instead, the query
+/// is executed in passes, each of which uses the outcomes recorded by the passes before it.
+/// When an outcome isn't available yet, the pass requests it and carries on with a placeholder
+/// outcome in its place, so that it requests every outcome it can. Once the pass is done,
+/// its requests are awaited concurrently and their outcomes are recorded.
+///
+/// Every pass resolves at least one more step of each part of the query that isn't done yet,
+/// so the number of passes depends on the shape of the query rather than the amount of data.
+/// Execution is deterministic given the same outcomes, so until a pass uses its first placeholder,
+/// it produces the same results in the same order as the final pass does.
+struct ReplayState<Vertex> {
+    sites: HashMap<Site, SiteId>,
+    starting_vertices: HashMap<SiteId, Arc<[Tracked<Vertex>]>>,
+    outcomes: HashMap<(SiteId, VertexId), Outcome<Vertex>>,
+    next_vertex_id: VertexId,
+
+    /// The outcomes requested by the current pass, by site.
+    requests: BTreeMap<SiteId, Request<Vertex>>,
+
+    /// Whether the current pass has used any placeholders, making its later results unreliable.
+    incomplete: bool,
+
+    /// The number of passes made over the query so far.
+    passes: usize,
+}
+
+impl<Vertex> Default for ReplayState<Vertex> {
+    fn default() -> Self {
+        Self {
+            sites: HashMap::new(),
+            starting_vertices: HashMap::new(),
+            outcomes: HashMap::new(),
+            next_vertex_id: 0,
+            requests: BTreeMap::new(),
+            incomplete: false,
+            passes: 0,
+        }
+    }
+}
+
+impl<Vertex: Clone> ReplayState<Vertex> {
+    fn site_id(&mut self, site: &Site) -> SiteId {
+        let next_id = self.sites.len();
+        *self.sites.entry(site.clone()).or_insert(next_id)
+    }
+
+    /// Request the outcome of resolving the vertex at the site.
+    fn request(
+        &mut self,
+        site_id: SiteId,
+        site: &Site,
+        info: &Info,
+        vertex: Option<&Tracked<Vertex>>,
+    ) {
+        self.incomplete = true;
+        let request = self.requests.entry(site_id).or_insert_with(|| Request {
+            site: site.clone(),
+            info: info.clone(),
+            vertices: vec![],
+            requested: HashSet::new(),
+        });
+        if let Some(vertex) = vertex {
+            if request.requested.insert(vertex.id) {
+                request.vertices.push(vertex.clone());
+            }
+        }
+    }
+
+    fn track(&mut self, vertices: Vec<Vertex>) -> Arc<[Tracked<Vertex>]> {
+        vertices
+            .into_iter()
+            .map(|vertex| {
+                let id = self.next_vertex_id;
+                self.next_vertex_id += 1;
+                Tracked { id, vertex }
+            })
+            .collect()
+    }
+
+    fn record(&mut self, site_id: SiteId, fulfilled: Fulfilled<Vertex>) {
+        match fulfilled {
+            Fulfilled::StartingVertices(vertices) => {
+                let vertices = self.track(vertices);
+                self.starting_vertices.insert(site_id, vertices);
+            }
+            Fulfilled::Properties(ids, values) => {
+                for (id, value) in ids.into_iter().zip(values) {
+                    self.outcomes.insert((site_id, id), Outcome::Property(value));
+                }
+            }
+            Fulfilled::Neighbors(ids, neighbors) => {
+                for (id, neighbors) in ids.into_iter().zip(neighbors) {
+                    let neighbors = self.track(neighbors);
+                    self.outcomes.insert((site_id, id), Outcome::Neighbors(neighbors));
+                }
+            }
+            Fulfilled::Coercions(ids, can_coerce) => {
+                for (id, can_coerce) in ids.into_iter().zip(can_coerce) {
+                    self.outcomes.insert((site_id, id), Outcome::Coercion(can_coerce));
+                }
+            }
+        }
+    }
+}
+
+/// Run the query to completion, pushing its results onto `rows`.
+///
+/// Whenever execution needs to await the adapter, the results produced so far are handed
+/// to the stream first. Each pass over the query skips the results of the previous passes.
+async fn execute<AdapterT: AsyncAdapter>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    rows: Arc<Mutex<VecDeque<ResultRow>>>,
+    state: Arc<Mutex<ReplayState<AdapterT::Vertex>>>,
+) {
+    let mut produced = 0;
+    loop {
+        let new_rows = run_pass(
+            &state,
+            adapter.vertex_identifier(),
+            indexed_query.clone(),
+            arguments.clone(),
+            produced,
+        );
+        if !new_rows.is_empty() {
+            produced += new_rows.len();
+            rows.lock().expect("rows lock was poisoned").extend(new_rows);
+            wait_for_stream().await;
+        }
+
+        let requests =
+            std::mem::take(&mut state.lock().expect("replay state lock was poisoned").requests);
+        if requests.is_empty() {
+            return;
+        }
+
+        let mut batches = requests
+            .into_iter()
+            .flat_map(|(site_id, request)| request.into_batches(site_id))
+            .map(|batch| Box::pin(batch.fulfill(adapter.as_ref())));
+        let mut in_flight: Vec<_> = batches.by_ref().take(MAX_CONCURRENT_REQUESTS).collect();
+        poll_fn(|cx| {
+            let mut index = 0;
+            while index < in_flight.len() {
+                let Poll::Ready((site_id, fulfilled)) = in_flight[index].as_mut().poll(cx) else {
+                    index += 1;
+                    continue;
+                };
+                state.lock().expect("replay state lock was poisoned").record(site_id, fulfilled);
+                match batches.next() {
+                    Some(batch) => in_flight[index] = batch,
+                    None => {
+                        drop(in_flight.swap_remove(index));
+                    }
+                }
+            }
+            if in_flight.is_empty() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+}
+
+/// Execute the query once, using the outcomes recorded so far and requesting the missing ones.
+/// Returns the results after the first `skip` ones, which were produced by earlier passes,
+/// up to the first result that may depend on placeholder outcomes.
+fn run_pass<'a, Vertex: Clone + Debug + 'a>(
+    state: &Arc<Mutex<ReplayState<Vertex>>>,
+    identifier: Option<VertexIdentifier<'a, Vertex>>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    skip: usize,
+) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+    {
+        let mut state = state.lock().expect("replay state lock was poisoned");
+        state.incomplete = false;
+        state.passes += 1;
+    }
+
+    let identifier = identifier.map(|identifier| identifier.map(|t: &Tracked<Vertex>| &t.vertex));
+    let adapter = Arc::new(ReplayAdapter { state: state.clone(), identifier });
+    let results = interpret_ir(adapter, indexed_query, arguments)
+        .expect("query arguments were already validated");
+    let mut rows = vec![];
+    for row in results {
+        // Keep executing even once results are unreliable, to request every outcome we can.
+        if !state.lock().expect("replay state lock was poisoned").incomplete {
+            rows.push(row);
+        }
+    }
+    rows.split_off(skip.min(rows.len()))
+}
+
+/// Returns `Pending` once, without scheduling a wake-up:
+/// the stream polls the query again once it has produced the results queued so far.
+async fn wait_for_stream() {
+    let mut waited = false;
+    poll_fn(|_| {
+        if waited {
+            Poll::Ready(())
+        } else {
+            waited = true;
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// The results of a query executed over an [`AsyncAdapter`], produced by [`interpret_ir_stream()`].
+///
+/// The adapter's resolvers are awaited as part of polling this stream, so it can be
+/// consumed on any async runtime. `ExecutionT` is the future executing the query.
+pub struct QueryResultStream<ExecutionT> {
+    rows: Arc<Mutex<VecDeque<ResultRow>>>,
+    execution: Option<Pin<Box<ExecutionT>>>,
+}
+
+impl<ExecutionT> Debug for QueryResultStream<ExecutionT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryResultStream")
+            .field("finished", &self.execution.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<ExecutionT: Future<Output = ()>> Stream for QueryResultStream<ExecutionT> {
+    type Item = BTreeMap<Arc<str>, FieldValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(row) = this.rows.lock().expect("rows lock was poisoned").pop_front() {
+                return Poll::Ready(Some(row));
+            }
+
+            let Some(execution) = this.execution.as_mut() else {
+                return Poll::Ready(None);
+            };
+            if execution.as_mut().poll(cx).is_ready() {
+                this.execution = None;
+            } else if this.rows.lock().expect("rows lock was poisoned").is_empty() {
+                // The query is awaiting the adapter, which will wake the task when it's ready.
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+/// The hints passed to the resolver that requested an outcome.
+#[derive(Clone)]
+enum Info {
+    Vertex(ResolveInfo),
+    Edge(ResolveEdgeInfo),
+}
+
+/// The outcomes a pass over the query requested at one site.
+struct Request<Vertex> {
+    site: Site,
+    info: Info,
+    vertices: Vec<Tracked<Vertex>>,
+    requested: HashSet<VertexId>,
+}
+
+impl<Vertex> Request<Vertex> {
+    fn into_batches(self, site_id: SiteId) -> impl Iterator<Item = Batch<Vertex>> {
+        let Self { site, info, mut vertices, .. } = self;
+        let batch_count = vertices.len().div_ceil(BATCH_SIZE).max(1);
+        (0..batch_count).map(move |_| {
+            let rest = vertices.split_off(BATCH_SIZE.min(vertices.len()));
+            let vertices = std::mem::replace(&mut vertices, rest);
+            Batch { site_id, site: site.clone(), info: info.clone(), vertices }
+        })
+    }
+}
+
+/// A single resolver call to make on the [`AsyncAdapter`].
+struct Batch<Vertex> {
+    site_id: SiteId,
+    site: Site,
+    info: Info,
+    vertices: Vec<Tracked<Vertex>>,
+}
+
+/// The outcomes of a [`Batch`], with the IDs of the vertices they are for.
+enum Fulfilled<Vertex> {
+    StartingVertices(Vec<Vertex>),
+    Properties(Vec<VertexId>, Vec<FieldValue>),
+    Neighbors(Vec<VertexId>, Vec<Vec<Vertex>>),
+    Coercions(Vec<VertexId>, Vec<bool>),
+}
+
+impl<Vertex> Batch<Vertex> {
+    async fn fulfill<AdapterT: AsyncAdapter<Vertex = Vertex>>(
+        self,
+        adapter: &AdapterT,
+    ) -> (SiteId, Fulfilled<Vertex>) {
+        let (ids, vertices): (Vec<_>, Vec<_>) =
+            self.vertices.into_iter().map(|tracked| (tracked.id, tracked.vertex)).unzip();
+        let expected = ids.len();
+        let (produced, fulfilled) = match (self.site, &self.info) {
+            (Site::StartingVertices { edge_name, parameters }, Info::Vertex(resolve_info)) => {
+                let parameters = EdgeParameters::new(parameters);
+                let vertices =
+                    adapter.resolve_starting_vertices(&edge_name, &parameters, resolve_info).await;
+                return (self.site_id, Fulfilled::StartingVertices(vertices));
+            }
+            (Site::Property { type_name, property_name }, Info::Vertex(resolve_info)) => {
+                let values = adapter
+                    .resolve_property(vertices, &type_name, &property_name, resolve_info)
+                    .await;
+                (values.len(), Fulfilled::Properties(ids, values))
+            }
+            (
+                Site::Neighbors { type_name, edge_name, parameters, .. },
+                Info::Edge(resolve_info),
+            ) => {
+                let parameters = EdgeParameters::new(parameters);
+                let neighbors = adapter
+                    .resolve_neighbors(vertices, &type_name, &edge_name, &parameters, resolve_info)
+                    .await;
+                (neighbors.len(), Fulfilled::Neighbors(ids, neighbors))
+            }
+            (Site::Coercion { type_name, coerce_to_type }, Info::Vertex(resolve_info)) => {
+                let can_coerce = adapter
+                    .resolve_coercion(vertices, &type_name, &coerce_to_type, resolve_info)
+                    .await;
+                (can_coerce.len(), Fulfilled::Coercions(ids, can_coerce))
+            }
+            _ => unreachable!("request hints don't match the kind of resolver"),
+        };
+        assert_eq!(
+            expected, produced,
+            "AsyncAdapter produced {produced} outcomes for a batch of {expected} vertices",
+        );
+        (self.site_id, fulfilled)
+    }
+}
+
+/// A synchronous [`Adapter`] that replays the outcomes recorded in the [`ReplayState`],
+/// and requests the outcomes that haven't been recorded yet.
+struct ReplayAdapter<'a, Vertex> {
+    state: Arc<Mutex<ReplayState<Vertex>>>,
+    identifier: Option<VertexIdentifier<'a, Tracked<Vertex>>>,
+}
+
+fn replay_recorded<'a, Vertex, V, OutcomeT>(
+    state: Arc<Mutex<ReplayState<Vertex>>>,
+    contexts: ContextIterator<'a, V>,
+    site: Site,
+    info: Info,
+    default_outcome: impl Fn() -> OutcomeT + 'a,
+    replay: impl Fn(&Outcome<Vertex>) -> OutcomeT + 'a,
+) -> ContextOutcomeIterator<'a, V, OutcomeT>
+where
+    Vertex: Clone + 'a,
+    V: AsVertex<Tracked<Vertex>> + 'a,
+    OutcomeT: 'a,
+{
+    let site_id = state.lock().expect("replay state lock was poisoned").site_id(&site);
+    Box::new(contexts.map(move |ctx| {
+        let Some(vertex) = ctx.active_vertex::<Tracked<Vertex>>() else {
+            return (ctx, default_outcome());
+        };
+
+        let mut state = state.lock().expect("replay state lock was poisoned");
+        match state.outcomes.get(&(site_id, vertex.id)) {
+            Some(outcome) => {
+                let outcome = replay(outcome);
+                drop(state);
+                (ctx, outcome)
+            }
+            None => {
+                // Until the outcome is available, the rest of this pass uses a placeholder.
+                state.request(site_id, &site, &info, Some(vertex));
+                drop(state);
+                (ctx, default_outcome())
+            }
+        }
+    }))
+}
+
+fn replay_vertices<'a, Vertex: Clone + 'a>(
+    vertices: &Arc<[Tracked<Vertex>]>,
+) -> VertexIterator<'a, Tracked<Vertex>> {
+    let vertices = vertices.clone();
+    Box::new((0..vertices.len()).map(move |index| vertices[index].clone()))
+}
+
+impl<'a, Vertex: Clone + Debug + 'a> Adapter<'a> for ReplayAdapter<'a, Vertex> {
+    type Vertex = Tracked<Vertex>;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'a, Self::Vertex> {
+        let site = Site::StartingVertices {
+            edge_name: edge_name.clone(),
+            parameters: parameters.contents.clone(),
+        };
+        let mut state = self.state.lock().expect("replay state lock was poisoned");
+        let site_id = state.site_id(&site);
+        match state.starting_vertices.get(&site_id) {
+            Some(vertices) => replay_vertices(vertices),
+            None => {
+                state.request(site_id, &site, &Info::Vertex(resolve_info.clone()), None);
+                Box::new(std::iter::empty())
+            }
+        }
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+        replay_recorded(
+            self.state.clone(),
+            contexts,
+            Site::Property { type_name: type_name.clone(), property_name: property_name.clone() },
+            Info::Vertex(resolve_info.clone()),
+            || FieldValue::Null,
+            |outcome| match outcome {
+                Outcome::Property(value) => value.clone(),
+                _ => unreachable!("recorded outcome is not a property value"),
+            },
+        )
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+        replay_recorded(
+            self.state.clone(),
+            contexts,
+            Site::Neighbors {
+                eid: resolve_info.eid(),
+                type_name: type_name.clone(),
+                edge_name: edge_name.clone(),
+                parameters: parameters.contents.clone(),
+            },
+            Info::Edge(resolve_info.clone()),
+            || -> VertexIterator<'a, Self::Vertex> { Box::new(std::iter::empty()) },
+            |outcome| match outcome {
+                Outcome::Neighbors(neighbors) => replay_vertices(neighbors),
+                _ => unreachable!("recorded outcome is not of neighbors"),
+            },
+        )
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'a, V, bool> {
+        replay_recorded(
+            self.state.clone(),
+            contexts,
+            Site::Coercion { type_name: type_name.clone(), coerce_to_type: coerce_to_type.clone() },
+            Info::Vertex(resolve_info.clone()),
+            || false,
+            |outcome| match outcome {
+                Outcome::Coercion(can_coerce) => *can_coerce,
+                _ => unreachable!("recorded outcome is not of a coercion"),
+            },
+        )
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'a, Self::Vertex>> {
+        self.identifier.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::{BTreeMap, VecDeque},
+        fs,
+        future::{poll_fn, Future},
+        path::{Path, PathBuf},
+        pin::{pin, Pin},
+        rc::Rc,
+        sync::{Arc, Mutex, OnceLock},
+        task::{Context, Poll, Wake},
+        thread::{self, Thread},
+    };
+
+    use futures_core::Stream;
+    use trustfall_filetests_macros::parameterize;
+
+    use super::{
+        execute, interpret_ir_async, interpret_ir_stream, AsyncAdapter, QueryResultStream,
+        ReplayState,
+    };
+    use crate::{
+        interpreter::{
+            Adapter, ContextIterator, DataContext, ResolveEdgeInfo, ResolveInfo, VertexIdentifier,
+            VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue, IndexedQuery},
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
+        test_types::{TestIRQueryResult, TestInterpreterOutputData},
+    };
+
+    /// Wraps the synchronous numbers adapter, yielding to the executor before resolving anything.
+    #[derive(Default)]
+    struct AsyncNumbersAdapter {
+        inner: NumbersAdapter,
+    }
+
+    async fn yield_once() {
+        let mut yielded = false;
+        std::future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn make_contexts(vertices: Vec<NumbersVertex>) -> ContextIterator<'static, NumbersVertex> {
+        Box::new(vertices.into_iter().map(|v| DataContext::new(Some(v))))
+    }
+
+    impl AsyncAdapter for AsyncNumbersAdapter {
+        type Vertex = NumbersVertex;
+
+        async fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<Self::Vertex> {
+            yield_once().await;
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info).collect()
+        }
+
+        async fn resolve_property(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<FieldValue> {
+            yield_once().await;
+            self.inner
+                .resolve_property(make_contexts(vertices), type_name, property_name, resolve_info)
+                .map(|(_, value)| value)
+                .collect()
+        }
+
+        async fn resolve_neighbors(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> Vec<Vec<Self::Vertex>> {
+            yield_once().await;
+            self.inner
+                .resolve_neighbors(
+                    make_contexts(vertices),
+                    type_name,
+                    edge_name,
+                    parameters,
+                    resolve_info,
+                )
+                .map(|(_, neighbors): (_, VertexIterator<'static, _>)| neighbors.collect())
+                .collect()
+        }
+
+        async fn resolve_coercion(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<bool> {
+            yield_once().await;
+            self.inner
+                .resolve_coercion(make_contexts(vertices), type_name, coerce_to_type, resolve_info)
+                .map(|(_, can_coerce)| can_coerce)
+                .collect()
+        }
+
+        fn vertex_identifier<'vertex>(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>>
+        where
+            Self::Vertex: 'vertex,
+        {
+            self.inner.vertex_identifier()
+        }
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    fn get_adapter() -> Arc<AsyncNumbersAdapter> {
+        static ADAPTER: OnceLock<Arc<AsyncNumbersAdapter>> = OnceLock::new();
+        ADAPTER
+            .get_or_init(|| Arc::new(AsyncNumbersAdapter { inner: NumbersAdapter::new() }))
+            .clone()
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.ir.ron"));
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+        let test_query = test_query.unwrap();
+        if test_query.schema_name != "numbers" {
+            return;
+        }
+
+        let mut output_path = PathBuf::from(base);
+        output_path.push(format!("{stem}.output.ron"));
+        let output_data = fs::read_to_string(output_path).unwrap();
+        let expected_outputs: TestInterpreterOutputData = ron::from_str(&output_data).unwrap();
+
+        let arguments: BTreeMap<Arc<str>, FieldValue> =
+            test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+        let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+
+        let results =
+            block_on(interpret_ir_async(get_adapter(), indexed_query.into(), arguments.into()))
+                .expect("execution error");
+
+        assert_eq!(expected_outputs.results, results);
+    }
+
+    #[test]
+    fn invalid_arguments_are_reported_before_execution() {
+        let test_query: TestIRQueryResult = ron::from_str(
+            &fs::read_to_string("test_data/tests/valid_queries/filter_op_greater_than.ir.ron")
+                .unwrap(),
+        )
+        .unwrap();
+        let indexed_query: IndexedQuery = test_query.unwrap().ir_query.try_into().unwrap();
+
+        let outcome = block_on(interpret_ir_async(
+            get_adapter(),
+            indexed_query.into(),
+            Arc::new(BTreeMap::new()),
+        ));
+        assert!(outcome.is_err(), "{outcome:?}");
+    }
+
+    #[test]
+    fn dropping_the_query_future_stops_execution() {
+        let query = r#"
+{
+    Number(min: 0, max: 1000) {
+        value @output
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().inner.schema(), query).unwrap();
+
+        let mut future =
+            Box::pin(interpret_ir_async(get_adapter(), indexed_query, Arc::new(BTreeMap::new())));
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&future);
+
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        // The starting vertices and their values are still being resolved.
+        for _ in 0..2 {
+            assert!(future.as_mut().poll(&mut cx).is_pending());
+        }
+
+        drop(future);
+    }
+
+    #[test]
+    fn stream_produces_results_before_execution_finishes() {
+        let query = r#"
+{
+    Number(min: 0, max: 1000) {
+        value @output
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().inner.schema(), query).unwrap();
+
+        let mut stream =
+            interpret_ir_stream(get_adapter(), indexed_query, Arc::new(BTreeMap::new())).unwrap();
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&stream);
+
+        let values: Vec<_> = (0..10)
+            .map(|_| {
+                let row = block_on(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)))
+                    .expect("stream ended early");
+                row["value"].clone()
+            })
+            .collect();
+        let expected: Vec<_> = (0..10).map(FieldValue::Int64).collect();
+        assert_eq!(expected, values);
+
+        // The remaining results are never produced: dropping the stream stops execution.
+        drop(stream);
+    }
+
+    /// An adapter that isn't thread-safe, and counts the calls made to its resolvers.
+    struct LocalAdapter {
+        inner: AsyncNumbersAdapter,
+        calls: RefCell<Vec<String>>,
+        _not_send: Rc<()>,
+    }
+
+    impl AsyncAdapter for LocalAdapter {
+        type Vertex = NumbersVertex;
+
+        async fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<Self::Vertex> {
+            self.calls.borrow_mut().push(format!("{edge_name}"));
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info).await
+        }
+
+        async fn resolve_property(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<FieldValue> {
+            self.calls
+                .borrow_mut()
+                .push(format!("{type_name}.{property_name} x{}", vertices.len()));
+            self.inner.resolve_property(vertices, type_name, property_name, resolve_info).await
+        }
+
+        async fn resolve_neighbors(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> Vec<Vec<Self::Vertex>> {
+            self.calls.borrow_mut().push(format!("{type_name}.{edge_name} x{}", vertices.len()));
+            self.inner
+                .resolve_neighbors(vertices, type_name, edge_name, parameters, resolve_info)
+                .await
+        }
+
+        async fn resolve_coercion(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<bool> {
+            self.calls.borrow_mut().push(format!("{type_name} as {coerce_to_type}"));
+            self.inner.resolve_coercion(vertices, type_name, coerce_to_type, resolve_info).await
+        }
+    }
+
+    #[test]
+    fn executes_on_the_calling_thread_with_each_batch_resolved_once() {
+        let query = r#"
+{
+    Number(min: 0, max: 99) {
+        value @output
+        successor {
+            next: value @output @filter(op: "<", value: ["$max"])
+        }
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().inner.schema(), query).unwrap();
+        #[allow(clippy::arc_with_non_send_sync)]
+        let adapter = Arc::new(LocalAdapter {
+            inner: AsyncNumbersAdapter { inner: NumbersAdapter::new() },
+            calls: Default::default(),
+            _not_send: Rc::new(()),
+        });
+
+        let arguments = BTreeMap::from([(Arc::from("max"), FieldValue::Int64(3))]);
+        let results =
+            block_on(interpret_ir_async(adapter.clone(), indexed_query, arguments.into()))
+                .expect("execution error");
+        let expected: Vec<_> = (0..2)
+            .map(|value| {
+                BTreeMap::from([
+                    (Arc::from("value"), FieldValue::Int64(value)),
+                    (Arc::from("next"), FieldValue::Int64(value + 1)),
+                ])
+            })
+            .collect();
+        assert_eq!(expected, results);
+
+        // The 100 starting vertices are resolved in batches of 64 and 36,
+        // and no vertex is resolved more than once at the same place in the query.
+        assert_eq!(
+            vec![
+                "Number",
+                "Number.successor x64",
+                "Number.successor x36",
+                "Number.value x64",
+                "Number.value x36",
+                "Number.value x2",
+            ],
+            *adapter.calls.borrow(),
+        );
+    }
+
+    /// An adapter that counts its resolver calls, and how many of them are awaited at once.
+    #[derive(Default)]
+    struct CountingAdapter {
+        inner: AsyncNumbersAdapter,
+        calls: Cell<usize>,
+        in_flight: Cell<usize>,
+        max_in_flight: Cell<usize>,
+    }
+
+    impl CountingAdapter {
+        async fn count<T>(&self, resolve: impl Future<Output = T>) -> T {
+            self.calls.set(self.calls.get() + 1);
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.max_in_flight.set(self.max_in_flight.get().max(self.in_flight.get()));
+            let outcome = resolve.await;
+            self.in_flight.set(self.in_flight.get() - 1);
+            outcome
+        }
+    }
+
+    impl AsyncAdapter for CountingAdapter {
+        type Vertex = NumbersVertex;
+
+        async fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<Self::Vertex> {
+            self.count(self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info))
+                .await
+        }
+
+        async fn resolve_property(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<FieldValue> {
+            self.count(self.inner.resolve_property(
+                vertices,
+                type_name,
+                property_name,
+                resolve_info,
+            ))
+            .await
+        }
+
+        async fn resolve_neighbors(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> Vec<Vec<Self::Vertex>> {
+            self.count(self.inner.resolve_neighbors(
+                vertices,
+                type_name,
+                edge_name,
+                parameters,
+                resolve_info,
+            ))
+            .await
+        }
+
+        async fn resolve_coercion(
+            &self,
+            vertices: Vec<Self::Vertex>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> Vec<bool> {
+            self.count(self.inner.resolve_coercion(
+                vertices,
+                type_name,
+                coerce_to_type,
+                resolve_info,
+            ))
+            .await
+        }
+    }
+
+    #[test]
+    fn passes_grow_with_query_depth_rather_than_adapter_calls() {
+        let query = r#"
+{
+    Number(min: 0, max: 999) {
+        value @output
+        successor {
+            successor {
+                next: value @output
+            }
+        }
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().inner.schema(), query).unwrap();
+        #[allow(clippy::arc_with_non_send_sync)]
+        let adapter = Arc::new(CountingAdapter::default());
+        let rows: Arc<Mutex<VecDeque<_>>> = Default::default();
+        let state = Arc::new(Mutex::new(ReplayState::default()));
+        let execution = execute(
+            adapter.clone(),
+            indexed_query,
+            Default::default(),
+            rows.clone(),
+            state.clone(),
+        );
+        let mut stream = QueryResultStream { rows, execution: Some(Box::pin(execution)) };
+
+        let mut rows = vec![];
+        while let Some(row) = block_on(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))) {
+            rows.push(row);
+        }
+        assert_eq!(1000, rows.len());
+        assert_eq!(FieldValue::Int64(999), rows[999]["value"]);
+        assert_eq!(FieldValue::Int64(1001), rows[999]["next"]);
+
+        // One call for the starting vertices, then 16 batches for each of the two edges
+        // and the two properties: each batch is resolved exactly once.
+        assert_eq!(1 + 4 * 16, adapter.calls.get());
+        assert_eq!(16, adapter.max_in_flight.get());
+
+        // The starting vertices, each of the two edges, and the properties each take one pass,
+        // and a final pass produces the results.
+        assert_eq!(5, state.lock().unwrap().passes);
+    }
+}
@@ -4,7 +4,7 @@ use crate::ir::{EdgeParameters, FieldValue};
 
 use super::{
     helpers::resolve_property_with, Adapter, AsVertex, ContextIterator, ContextOutcomeIterator,
-    ResolveEdgeInfo, ResolveInfo, Typename, VertexIterator,
+    ResolveEdgeInfo, ResolveInfo, Typename, VertexIdentifier, VertexIterator,
 };
 
 /// A simplified variant of the [`Adapter`] trait.
@@ -18,6 +18,12 @@ use super::{
 ///
 /// The easiest way to implement this trait is with the `Vertex` associated type set
 /// to an enum that is `#[derive(Debug, Clone, TrustfallEnumVertex)]`.
+///
+/// Adapters that want to use query hints for only a few edges, e.g. to push down
+/// filtering to the underlying data source, can override
+/// [`BasicAdapter::resolve_starting_vertices_with_hints`] and
+/// [`BasicAdapter::resolve_neighbors_with_hints`] for those edges,
+/// without having to implement the full [`Adapter`] trait.
 pub trait BasicAdapter<'vertex> {
     /// The type of vertices in the dataset this adapter queries.
     /// It's frequently a good idea to use an Rc<...> type for cheaper cloning here.
@@ -154,6 +160,45 @@ pub trait BasicAdapter<'vertex> {
         coerce_to_type: &str,
     ) -> ContextOutcomeIterator<'vertex, V, bool>;
 
+    /// Same as [`BasicAdapter::resolve_starting_vertices`], but also receives
+    /// the [`ResolveInfo`] with hints about how the query will use the starting vertices.
+    ///
+    /// Override this method to use those hints, e.g. to only load vertices whose properties
+    /// match the query's filters. The default implementation ignores the hints
+    /// and calls [`BasicAdapter::resolve_starting_vertices`].
+    ///
+    /// The caller's guarantees and the returned iterator's required properties are the same as
+    /// for [`BasicAdapter::resolve_starting_vertices`]. In particular, hints are best-effort:
+    /// the query still applies its own filters to the vertices this method returns.
+    fn resolve_starting_vertices_with_hints(
+        &self,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+        _resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        self.resolve_starting_vertices(edge_name, parameters)
+    }
+
+    /// Same as [`BasicAdapter::resolve_neighbors`], but also receives
+    /// the [`ResolveEdgeInfo`] with hints about how the query will use the neighboring vertices.
+    ///
+    /// Override this method to use those hints, e.g. to only load neighbors whose properties
+    /// match the query's filters. The default implementation ignores the hints
+    /// and calls [`BasicAdapter::resolve_neighbors`].
+    ///
+    /// The caller's guarantees and the returned iterator's required properties are the same as
+    /// for [`BasicAdapter::resolve_neighbors`].
+    fn resolve_neighbors_with_hints<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &str,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+        _resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        self.resolve_neighbors(contexts, type_name, edge_name, parameters)
+    }
+
     /// Resolve the `__typename` special property over an iterator of query contexts.
     ///
     /// Each [`DataContext`] in the `contexts` argument has an active vertex,
@@ -208,6 +253,14 @@ pub trait BasicAdapter<'vertex> {
     ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
         resolve_property_with(contexts, |vertex| vertex.typename().into())
     }
+
+    /// Tell apart the vertices this adapter produces, if possible.
+    ///
+    /// Equivalent to [`Adapter::vertex_identifier()`], whose documentation has more details.
+    /// The default implementation returns `None`.
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        None
+    }
 }
 
 impl<'vertex, T> Adapter<'vertex> for T
@@ -220,9 +273,14 @@ where
         &self,
         edge_name: &std::sync::Arc<str>,
         parameters: &EdgeParameters,
-        _resolve_info: &ResolveInfo,
+        resolve_info: &ResolveInfo,
     ) -> VertexIterator<'vertex, Self::Vertex> {
-        <Self as BasicAdapter>::resolve_starting_vertices(self, edge_name.as_ref(), parameters)
+        <Self as BasicAdapter>::resolve_starting_vertices_with_hints(
+            self,
+            edge_name.as_ref(),
+            parameters,
+            resolve_info,
+        )
     }
 
     fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
@@ -250,14 +308,15 @@ where
         type_name: &std::sync::Arc<str>,
         edge_name: &std::sync::Arc<str>,
         parameters: &EdgeParameters,
-        _resolve_info: &ResolveEdgeInfo,
+        resolve_info: &ResolveEdgeInfo,
     ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
-        <Self as BasicAdapter>::resolve_neighbors(
+        <Self as BasicAdapter>::resolve_neighbors_with_hints(
             self,
             contexts,
             type_name.as_ref(),
             edge_name.as_ref(),
             parameters,
+            resolve_info,
         )
     }
 
@@ -275,4 +334,8 @@ where
             coerce_to_type.as_ref(),
         )
     }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        <Self as BasicAdapter>::vertex_identifier(self)
+    }
 }
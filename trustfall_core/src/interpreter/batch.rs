@@ -0,0 +1,338 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    iter::Fuse,
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::ir::{FieldValue, IRQuery, IRQueryComponent, IRVertex, IndexedQuery};
+
+use super::{
+    error::QueryArgumentsError,
+    execution::{compute_final_results, resolve_starting_vertices},
+    Adapter, InterpretedQuery, ResolveInfo, VertexIterator,
+};
+
+/// Run a batch of queries, resolving the starting vertices they have in common only once.
+///
+/// Queries that start at the same root edge with the same parameter values share
+/// their starting vertices: the adapter resolves them once, and each of those queries
+/// then processes all of them. Vertices are resolved lazily as the queries' results
+/// are consumed, and are only kept in memory until every query sharing them has used them.
+///
+/// When resolving shared starting vertices, the adapter's [`ResolveInfo`] describes
+/// only what the sharing queries have in common: their root edge, and the type coercion
+/// of its vertices if they all apply the same one. Hints about the rest of any one query,
+/// like its filters, aren't available since they don't apply to the other queries.
+/// Queries that don't share starting vertices with any other query are unaffected.
+///
+/// Returns each query's results, in the order in which the queries were given.
+/// They are the same as the results returned by [`interpret_ir`] for each query.
+/// To also resolve each vertex's properties and neighbors only once across all the queries,
+/// use this together with a [`CachingAdapter`].
+///
+/// [`interpret_ir`]: super::execution::interpret_ir
+/// [`CachingAdapter`]: super::CachingAdapter
+#[allow(clippy::type_complexity)]
+pub fn interpret_ir_batch<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    queries: impl IntoIterator<Item = (Arc<IndexedQuery>, Arc<BTreeMap<Arc<str>, FieldValue>>)>,
+) -> Result<
+    Vec<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>>,
+    QueryArgumentsError,
+> {
+    let queries = queries
+        .into_iter()
+        .map(|(indexed_query, arguments)| {
+            InterpretedQuery::from_query_and_arguments(indexed_query, arguments)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Group the queries' indexes by the starting vertices they share.
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for (index, query) in queries.iter().enumerate() {
+        let group =
+            groups.iter_mut().find(|group| shares_starting_vertices(&queries[group[0]], query));
+        match group {
+            Some(group) => group.push(index),
+            None => groups.push(vec![index]),
+        }
+    }
+
+    let mut queries: Vec<_> = queries.into_iter().map(Some).collect();
+    let mut results: Vec<_> = queries.iter().map(|_| None).collect();
+    for group in groups {
+        let group_queries: Vec<_> = group
+            .iter()
+            .map(|index| queries[*index].take().expect("query is in more than one group"))
+            .collect();
+
+        if group_queries.len() == 1 {
+            let query = group_queries.into_iter().next().expect("no query");
+            let (starting_vertices, query) = resolve_starting_vertices(adapter.as_ref(), query);
+            results[group[0]] =
+                Some(compute_final_results(adapter.clone(), query, starting_vertices));
+            continue;
+        }
+
+        let prefix = shared_prefix(&group_queries);
+        let ir_query = &prefix.indexed_query.ir_query;
+        let root_vid = ir_query.root_component.root;
+        let root_edge = ir_query.root_name.clone();
+        let root_edge_parameters = ir_query.root_parameters.clone();
+        let resolve_info = ResolveInfo::new(prefix, root_vid, false);
+        let starting_vertices =
+            adapter.resolve_starting_vertices(&root_edge, &root_edge_parameters, &resolve_info);
+
+        let shared = Rc::new(RefCell::new(SharedVertices::new(starting_vertices, group.len())));
+        for (reader, (index, query)) in group.into_iter().zip(group_queries).enumerate() {
+            let starting_vertices =
+                Box::new(SharedVerticesReader { shared: shared.clone(), reader });
+            results[index] = Some(compute_final_results(adapter.clone(), query, starting_vertices));
+        }
+    }
+
+    Ok(results.into_iter().map(|results| results.expect("query is not in any group")).collect())
+}
+
+fn shares_starting_vertices(left: &InterpretedQuery, right: &InterpretedQuery) -> bool {
+    let left = &left.indexed_query.ir_query;
+    let right = &right.indexed_query.ir_query;
+    left.root_name == right.root_name && left.root_parameters == right.root_parameters
+}
+
+/// Make a query consisting of only the root vertex of the given queries,
+/// which must all share their starting vertices.
+///
+/// The root vertex is coerced to a subtype if every query applies the same coercion to it.
+fn shared_prefix(queries: &[InterpretedQuery]) -> InterpretedQuery {
+    let root_vertex = |query: &InterpretedQuery| -> IRVertex {
+        let root_component = &query.indexed_query.ir_query.root_component;
+        root_component.vertices[&root_component.root].clone()
+    };
+    let first_vertex = root_vertex(&queries[0]);
+    let same_coercion = queries.iter().map(root_vertex).all(|vertex| {
+        vertex.type_name == first_vertex.type_name
+            && vertex.coerced_from_type == first_vertex.coerced_from_type
+    });
+    let vertex = if same_coercion {
+        IRVertex { filters: vec![], ..first_vertex }
+    } else {
+        IRVertex {
+            vid: first_vertex.vid,
+            type_name: first_vertex.coerced_from_type.unwrap_or(first_vertex.type_name),
+            coerced_from_type: None,
+            filters: vec![],
+        }
+    };
+
+    let first_query = &queries[0].indexed_query.ir_query;
+    let ir_query = IRQuery {
+        root_name: first_query.root_name.clone(),
+        root_parameters: first_query.root_parameters.clone(),
+        root_component: Arc::new(IRQueryComponent {
+            root: vertex.vid,
+            vertices: btreemap! { vertex.vid => vertex },
+            edges: Default::default(),
+            folds: Default::default(),
+            outputs: Default::default(),
+            optional_groups: Default::default(),
+        }),
+        variables: Default::default(),
+        variable_defaults: Default::default(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        dedupe: false,
+    };
+    let indexed_query = ir_query.try_into().expect("shared prefix is not a valid query");
+    InterpretedQuery::from_query_and_arguments(Arc::new(indexed_query), Default::default())
+        .expect("shared prefix requires arguments")
+}
+
+/// Starting vertices shared by several queries, each of which reads all of them in order.
+///
+/// Vertices are only resolved once some query reads them, and are buffered
+/// until every query has read them.
+struct SharedVertices<'query, Vertex> {
+    source: Fuse<VertexIterator<'query, Vertex>>,
+
+    /// Vertices that some, but not all, queries have read.
+    buffer: VecDeque<Vertex>,
+
+    /// The number of vertices that all queries have read, and that are no longer buffered.
+    buffer_start: usize,
+
+    /// The number of vertices each query has read.
+    /// Queries that won't read any more vertices are treated as having read all of them.
+    positions: Vec<usize>,
+}
+
+impl<'query, Vertex> SharedVertices<'query, Vertex> {
+    fn new(source: VertexIterator<'query, Vertex>, readers: usize) -> Self {
+        Self {
+            source: source.fuse(),
+            buffer: Default::default(),
+            buffer_start: 0,
+            positions: vec![0; readers],
+        }
+    }
+
+    fn finish(&mut self, reader: usize) {
+        self.positions[reader] = usize::MAX;
+        self.discard_read_vertices();
+    }
+
+    fn discard_read_vertices(&mut self) {
+        let read_by_all = self.positions.iter().copied().min().unwrap_or(usize::MAX);
+        while self.buffer_start < read_by_all && self.buffer.pop_front().is_some() {
+            self.buffer_start += 1;
+        }
+    }
+}
+
+impl<Vertex: Clone> SharedVertices<'_, Vertex> {
+    fn next(&mut self, reader: usize) -> Option<Vertex> {
+        let index = self.positions[reader] - self.buffer_start;
+        let vertex = match self.buffer.get(index) {
+            Some(vertex) => vertex.clone(),
+            None => {
+                let vertex = self.source.next()?;
+                self.buffer.push_back(vertex.clone());
+                vertex
+            }
+        };
+        self.positions[reader] += 1;
+        self.discard_read_vertices();
+        Some(vertex)
+    }
+}
+
+/// One query's view of the [`SharedVertices`] it shares with other queries.
+struct SharedVerticesReader<'query, Vertex> {
+    shared: Rc<RefCell<SharedVertices<'query, Vertex>>>,
+    reader: usize,
+}
+
+impl<Vertex: Clone> Iterator for SharedVerticesReader<'_, Vertex> {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.shared.borrow_mut().next(self.reader)
+    }
+}
+
+impl<Vertex> Drop for SharedVerticesReader<'_, Vertex> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().finish(self.reader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{
+        frontend::parse,
+        interpreter::{
+            execution::interpret_ir, InstrumentedAdapter, ResolverCall, ResolverMetrics,
+        },
+        ir::FieldValue,
+        numbers_interpreter::NumbersAdapter,
+    };
+
+    use super::{interpret_ir_batch, SharedVertices, SharedVerticesReader};
+
+    #[test]
+    fn batched_queries_share_starting_vertices() {
+        let queries = [
+            (
+                r#"{ Number(max: 10) { value @output @filter(op: ">", value: ["$min"]) } }"#,
+                btreemap! { Arc::from("min") => FieldValue::Int64(4) },
+            ),
+            (
+                r#"{ Number(max: 10) @limit(count: 2) { ... on Prime { value @output } } }"#,
+                btreemap! {},
+            ),
+            (r#"{ Number(max: 5) { name @output } }"#, btreemap! {}),
+            (
+                r#"{ Number(max: 10) @limit(count: 3) { ... on Composite { value @output } } }"#,
+                btreemap! {},
+            ),
+            (r#"{ Two { value @output } }"#, btreemap! {}),
+        ];
+        let adapter = Arc::new(NumbersAdapter::new());
+        let queries: Vec<_> = queries
+            .into_iter()
+            .map(|(query, arguments)| {
+                (parse(adapter.schema(), query).expect("valid query"), Arc::new(arguments))
+            })
+            .collect();
+
+        let expected: Vec<Vec<_>> = queries
+            .iter()
+            .map(|(query, arguments)| {
+                interpret_ir(adapter.clone(), query.clone(), arguments.clone())
+                    .expect("valid arguments")
+                    .collect()
+            })
+            .collect();
+        assert!(expected.iter().all(|results| !results.is_empty()));
+
+        let reported: Arc<Mutex<Vec<ResolverMetrics>>> = Default::default();
+        let recorded = reported.clone();
+        let instrumented = Arc::new(InstrumentedAdapter::from_arc(adapter, move |metrics| {
+            recorded.lock().unwrap().push(metrics.clone());
+        }));
+
+        // Consume the results in an interleaved order, that's different from the queries' order.
+        let mut batch = interpret_ir_batch(instrumented, queries).expect("valid arguments");
+        let mut results: Vec<Vec<_>> = batch.iter().map(|_| vec![]).collect();
+        for _ in 0..2 {
+            for (query_results, iterator) in results.iter_mut().zip(&mut batch).rev() {
+                query_results.extend(iterator.next());
+            }
+        }
+        for (query_results, iterator) in results.iter_mut().zip(batch) {
+            query_results.extend(iterator);
+        }
+        assert_eq!(expected, results);
+
+        let starting_edges: Vec<_> = reported
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|metrics| match &metrics.call {
+                ResolverCall::StartingVertices { edge_name } => Some(edge_name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(3, starting_edges.len(), "{starting_edges:?}");
+    }
+
+    #[test]
+    fn shared_vertices_are_buffered_until_all_readers_use_them() {
+        let shared = Rc::new(RefCell::new(SharedVertices::new(Box::new(0..10), 2)));
+        let mut first = SharedVerticesReader { shared: shared.clone(), reader: 0 };
+        let mut second = SharedVerticesReader { shared: shared.clone(), reader: 1 };
+
+        assert_eq!(vec![0, 1, 2], first.by_ref().take(3).collect::<Vec<_>>());
+        assert_eq!(3, shared.borrow().buffer.len());
+
+        assert_eq!(vec![0, 1], second.by_ref().take(2).collect::<Vec<_>>());
+        assert_eq!(1, shared.borrow().buffer.len());
+
+        // Readers that are dropped no longer hold on to buffered vertices.
+        drop(first);
+        assert_eq!(1, shared.borrow().buffer.len());
+        assert_eq!(Some(2), second.next());
+        assert_eq!(0, shared.borrow().buffer.len());
+        assert_eq!((3..10).collect::<Vec<_>>(), second.collect::<Vec<_>>());
+        assert_eq!(0, shared.borrow().buffer.len());
+    }
+}
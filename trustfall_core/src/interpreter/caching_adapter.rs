@@ -0,0 +1,741 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use crate::ir::{EdgeParameters, FieldValue, IndexedQuery};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, DataContext, RequiredProperty,
+    ResolveEdgeInfo, ResolveInfo, VertexIdentifier, VertexIterator,
+};
+
+/// The maximum number of contexts whose outcomes are looked up in the cache at once.
+/// Contexts whose outcomes aren't cached are passed to the underlying adapter together.
+const BATCH_SIZE: usize = 64;
+
+/// An [`Adapter`] that remembers the properties, neighbors, and type coercion outcomes
+/// resolved by another adapter, reusing them across all the queries it executes.
+///
+/// This is useful when running many queries over the same unchanging data:
+/// each property, edge, and type coercion of a vertex is only resolved once,
+/// no matter how many queries need it. Outcomes are keyed by vertex identity,
+/// as defined by the vertex type's [`Hash`] and [`Eq`] implementations.
+///
+/// Starting vertices are not cached, and neither are the outcomes for vertices
+/// that the underlying adapter has not yet been asked about.
+///
+/// Adapters may use the [`ResolveEdgeInfo`] hints to only produce the neighbors
+/// that the query could use, so cached neighbors are only reused when the same query
+/// with the same arguments expands the same edge again. Neighbors are only cached once
+/// all of a vertex's neighbors have been produced, so edges whose neighbors aren't all
+/// needed by the query are resolved again next time. Every produced neighbor is kept
+/// until then, so edges with huge or infinite numbers of neighbors should not be cached.
+/// Edges are never cached in queries that filter on tagged values, since adapters
+/// may use those values to produce different neighbors for the same vertex.
+///
+/// The cache is never invalidated automatically. If the underlying data may change,
+/// call [`CachingAdapter::clear_cache`] or use a new `CachingAdapter`.
+pub struct CachingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: Hash + Eq,
+{
+    inner: Arc<AdapterT>,
+    cache: Arc<Mutex<Cache<AdapterT::Vertex>>>,
+    _phantom: PhantomData<&'vertex ()>,
+}
+
+/// A pair of names, such as a type name and the name of one of that type's properties.
+type NamePair = (Arc<str>, Arc<str>);
+
+/// The neighbors of each vertex along an edge with particular parameter values.
+type Neighbors<Vertex> = HashMap<Vertex, Vec<Vertex>>;
+
+/// The neighbors resolved along an edge for one combination of parameters and hints.
+struct NeighborsEntry<Vertex> {
+    parameters: EdgeParameters,
+    resolve_info: ResolveEdgeInfo,
+    neighbors: Arc<Mutex<Neighbors<Vertex>>>,
+}
+
+struct Cache<Vertex> {
+    /// Keyed by type name and property name.
+    properties: HashMap<NamePair, HashMap<Vertex, FieldValue>>,
+
+    /// Keyed by type name and edge name, then by the edge's parameters and hints.
+    neighbors: HashMap<NamePair, Vec<NeighborsEntry<Vertex>>>,
+
+    /// Keyed by the type name and the name of the type being coerced to.
+    coercions: HashMap<NamePair, HashMap<Vertex, bool>>,
+}
+
+impl<Vertex> Default for Cache<Vertex> {
+    fn default() -> Self {
+        Self {
+            properties: Default::default(),
+            neighbors: Default::default(),
+            coercions: Default::default(),
+        }
+    }
+}
+
+impl<'vertex, AdapterT> CachingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: Hash + Eq,
+{
+    /// Wrap an adapter, caching the outcomes it resolves.
+    pub fn new(adapter: AdapterT) -> Self {
+        Self::from_arc(Arc::new(adapter))
+    }
+
+    /// Wrap an adapter that is also used elsewhere, sharing it instead of taking ownership.
+    pub fn from_arc(adapter: Arc<AdapterT>) -> Self {
+        Self { inner: adapter, cache: Default::default(), _phantom: PhantomData }
+    }
+
+    /// The adapter whose outcomes are being cached.
+    pub fn inner(&self) -> &AdapterT {
+        &self.inner
+    }
+
+    /// Forget all cached outcomes, so that they are resolved again when next needed.
+    pub fn clear_cache(&self) {
+        *self.lock_cache() = Cache::default();
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, Cache<AdapterT::Vertex>> {
+        self.cache.lock().expect("cache lock was poisoned")
+    }
+}
+
+impl<'vertex, AdapterT> Debug for CachingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + Debug,
+    AdapterT::Vertex: Hash + Eq,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingAdapter").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+/// Produce the outcome for each context, from the cache if possible.
+///
+/// Contexts are processed in batches. The contexts in each batch whose outcomes aren't cached
+/// are resolved together by a single call to `resolve`, which is responsible for caching them.
+fn resolve_with_cache<'vertex, Vertex, V, OutcomeT>(
+    contexts: ContextIterator<'vertex, V>,
+    default_outcome: impl Fn() -> OutcomeT + 'vertex,
+    mut cached: impl FnMut(&Vertex) -> Option<OutcomeT> + 'vertex,
+    mut resolve: impl FnMut(ContextIterator<'vertex, V>) -> Vec<(DataContext<V>, OutcomeT)> + 'vertex,
+) -> ContextOutcomeIterator<'vertex, V, OutcomeT>
+where
+    Vertex: Clone + Debug + 'vertex,
+    V: AsVertex<Vertex> + 'vertex,
+    OutcomeT: 'vertex,
+{
+    let mut contexts = contexts.peekable();
+    let batches = std::iter::from_fn(move || {
+        contexts.peek()?;
+
+        let mut resolved: Vec<Option<(DataContext<V>, OutcomeT)>> = vec![];
+        let mut uncached = vec![];
+        for ctx in contexts.by_ref().take(BATCH_SIZE) {
+            let outcome = match ctx.active_vertex::<Vertex>() {
+                None => Some(default_outcome()),
+                Some(vertex) => cached(vertex),
+            };
+            match outcome {
+                Some(outcome) => resolved.push(Some((ctx, outcome))),
+                None => {
+                    resolved.push(None);
+                    uncached.push(ctx);
+                }
+            }
+        }
+
+        let mut outcomes = if uncached.is_empty() {
+            vec![].into_iter()
+        } else {
+            resolve(Box::new(uncached.into_iter())).into_iter()
+        };
+        let batch: Vec<_> = resolved
+            .into_iter()
+            .map(|slot| {
+                slot.unwrap_or_else(|| {
+                    outcomes.next().expect("adapter produced fewer outcomes than contexts")
+                })
+            })
+            .collect();
+        Some(batch)
+    });
+
+    Box::new(batches.flatten())
+}
+
+/// Whether any of the query's vertices filter on a tagged value. Adapters can use
+/// such filters to produce neighbors based on tagged values, not just on the vertex itself.
+fn filters_on_tagged_values(query: &IndexedQuery) -> bool {
+    query.vids.iter().any(|(vid, component)| {
+        component.vertices[vid]
+            .filters
+            .iter()
+            .any(|filter| filter.arguments().any(|argument| argument.as_tag().is_some()))
+    })
+}
+
+/// Produces a vertex's neighbors, caching them once all of them have been produced.
+struct CachingNeighbors<'vertex, Vertex> {
+    neighbors: VertexIterator<'vertex, Vertex>,
+    vertex: Vertex,
+    produced: Vec<Vertex>,
+    cache: Option<Arc<Mutex<Neighbors<Vertex>>>>,
+}
+
+impl<Vertex: Hash + Eq + Clone> Iterator for CachingNeighbors<'_, Vertex> {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.neighbors.next() {
+            Some(neighbor) => {
+                if self.cache.is_some() {
+                    self.produced.push(neighbor.clone());
+                }
+                Some(neighbor)
+            }
+            None => {
+                if let Some(cache) = self.cache.take() {
+                    let produced = std::mem::take(&mut self.produced);
+                    let mut cache = cache.lock().expect("cache lock was poisoned");
+                    cache.insert(self.vertex.clone(), produced);
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<'vertex, AdapterT> Adapter<'vertex> for CachingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + 'vertex,
+    AdapterT::Vertex: Hash + Eq,
+{
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let key = (type_name.clone(), property_name.clone());
+        let cache = self.cache.clone();
+        let cache_ref = self.cache.clone();
+        let lookup_key = key.clone();
+        let inner = self.inner.clone();
+        let type_name = type_name.clone();
+        let property_name = property_name.clone();
+        let resolve_info = resolve_info.clone();
+
+        resolve_with_cache(
+            contexts,
+            || FieldValue::Null,
+            move |vertex| {
+                let cache = cache_ref.lock().expect("cache lock was poisoned");
+                cache.properties.get(&lookup_key)?.get(vertex).cloned()
+            },
+            move |uncached| {
+                let resolved: Vec<_> = inner
+                    .resolve_property(uncached, &type_name, &property_name, &resolve_info)
+                    .collect();
+
+                let mut cache = cache.lock().expect("cache lock was poisoned");
+                let values = cache.properties.entry(key.clone()).or_default();
+                for (ctx, value) in &resolved {
+                    if let Some(vertex) = ctx.active_vertex::<Self::Vertex>() {
+                        values.insert(vertex.clone(), value.clone());
+                    }
+                }
+                resolved
+            },
+        )
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let keys: Vec<_> =
+            properties.iter().map(|property| (type_name.clone(), property.name.clone())).collect();
+        let lookup_keys = keys.clone();
+        let cache = self.cache.clone();
+        let cache_ref = self.cache.clone();
+        let inner = self.inner.clone();
+        let type_name = type_name.clone();
+        let properties = properties.to_vec();
+        let resolve_info = resolve_info.clone();
+
+        let count = properties.len();
+        resolve_with_cache(
+            contexts,
+            move || vec![FieldValue::Null; count],
+            move |vertex| {
+                let cache = cache_ref.lock().expect("cache lock was poisoned");
+                lookup_keys
+                    .iter()
+                    .map(|key| cache.properties.get(key)?.get(vertex).cloned())
+                    .collect()
+            },
+            move |uncached| {
+                let resolved: Vec<_> = inner
+                    .resolve_properties(uncached, &type_name, &properties, &resolve_info)
+                    .collect();
+
+                let mut cache = cache.lock().expect("cache lock was poisoned");
+                for (index, key) in keys.iter().enumerate() {
+                    let values = cache.properties.entry(key.clone()).or_default();
+                    for (ctx, property_values) in &resolved {
+                        if let Some(vertex) = ctx.active_vertex::<Self::Vertex>() {
+                            values.insert(vertex.clone(), property_values[index].clone());
+                        }
+                    }
+                }
+                resolved
+            },
+        )
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        if filters_on_tagged_values(resolve_info.indexed_query()) {
+            return self.inner.resolve_neighbors(
+                contexts,
+                type_name,
+                edge_name,
+                parameters,
+                resolve_info,
+            );
+        }
+
+        let cached_neighbors = {
+            let mut cache = self.lock_cache();
+            let entries =
+                cache.neighbors.entry((type_name.clone(), edge_name.clone())).or_default();
+            let position = entries.iter().position(|entry| {
+                &entry.parameters == parameters && &entry.resolve_info == resolve_info
+            });
+            let position = position.unwrap_or_else(|| {
+                entries.push(NeighborsEntry {
+                    parameters: parameters.clone(),
+                    resolve_info: resolve_info.clone(),
+                    neighbors: Default::default(),
+                });
+                entries.len() - 1
+            });
+            entries[position].neighbors.clone()
+        };
+        let lookup_neighbors = cached_neighbors.clone();
+        let inner = self.inner.clone();
+        let type_name = type_name.clone();
+        let edge_name = edge_name.clone();
+        let parameters = parameters.clone();
+        let resolve_info = resolve_info.clone();
+
+        resolve_with_cache(
+            contexts,
+            || -> VertexIterator<'vertex, Self::Vertex> { Box::new(std::iter::empty()) },
+            move |vertex| {
+                let neighbors = lookup_neighbors.lock().expect("cache lock was poisoned");
+                let neighbors = neighbors.get(vertex)?.clone();
+                Some(Box::new(neighbors.into_iter()))
+            },
+            move |uncached| {
+                inner
+                    .resolve_neighbors(uncached, &type_name, &edge_name, &parameters, &resolve_info)
+                    .map(|(ctx, neighbors)| {
+                        let neighbors: VertexIterator<'vertex, Self::Vertex> =
+                            match ctx.active_vertex::<Self::Vertex>() {
+                                Some(vertex) => Box::new(CachingNeighbors {
+                                    neighbors,
+                                    vertex: vertex.clone(),
+                                    produced: vec![],
+                                    cache: Some(cached_neighbors.clone()),
+                                }),
+                                None => neighbors,
+                            };
+                        (ctx, neighbors)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let key = (type_name.clone(), coerce_to_type.clone());
+        let lookup_key = key.clone();
+        let cache = self.cache.clone();
+        let cache_ref = self.cache.clone();
+        let inner = self.inner.clone();
+        let type_name = type_name.clone();
+        let coerce_to_type = coerce_to_type.clone();
+        let resolve_info = resolve_info.clone();
+
+        resolve_with_cache(
+            contexts,
+            || false,
+            move |vertex| {
+                let cache = cache_ref.lock().expect("cache lock was poisoned");
+                cache.coercions.get(&lookup_key)?.get(vertex).copied()
+            },
+            move |uncached| {
+                let resolved: Vec<_> = inner
+                    .resolve_coercion(uncached, &type_name, &coerce_to_type, &resolve_info)
+                    .collect();
+
+                let mut cache = cache.lock().expect("cache lock was poisoned");
+                let outcomes = cache.coercions.entry(key.clone()).or_default();
+                for (ctx, can_coerce) in &resolved {
+                    if let Some(vertex) = ctx.active_vertex::<Self::Vertex>() {
+                        outcomes.insert(vertex.clone(), *can_coerce);
+                    }
+                }
+                resolved
+            },
+        )
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, OnceLock,
+        },
+    };
+
+    use trustfall_filetests_macros::parameterize;
+
+    use super::CachingAdapter;
+    use crate::{
+        interpreter::VertexInfo,
+        interpreter::{
+            execution::interpret_ir, Adapter, AsVertex, ContextIterator, ContextOutcomeIterator,
+            ResolveEdgeInfo, ResolveInfo, VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue, IndexedQuery},
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
+        test_types::{TestIRQueryResult, TestInterpreterOutputData},
+    };
+
+    /// Wraps the numbers adapter, counting the vertices passed to its resolvers.
+    #[derive(Debug, Default)]
+    struct CountingAdapter {
+        inner: NumbersAdapter,
+        resolved_vertices: Arc<AtomicUsize>,
+    }
+
+    impl CountingAdapter {
+        fn count<'a, V: AsVertex<NumbersVertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+        ) -> ContextIterator<'a, V> {
+            let resolved_vertices = self.resolved_vertices.clone();
+            Box::new(contexts.inspect(move |ctx| {
+                if ctx.active_vertex::<NumbersVertex>().is_some() {
+                    resolved_vertices.fetch_add(1, Ordering::Relaxed);
+                }
+            }))
+        }
+
+        fn resolved_vertices(&self) -> usize {
+            self.resolved_vertices.load(Ordering::Relaxed)
+        }
+    }
+
+    impl<'a> Adapter<'a> for CountingAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'a, Self::Vertex> {
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+            self.inner.resolve_property(
+                self.count(contexts),
+                type_name,
+                property_name,
+                resolve_info,
+            )
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+            self.inner.resolve_neighbors(
+                self.count(contexts),
+                type_name,
+                edge_name,
+                parameters,
+                resolve_info,
+            )
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, bool> {
+            self.inner.resolve_coercion(
+                self.count(contexts),
+                type_name,
+                coerce_to_type,
+                resolve_info,
+            )
+        }
+    }
+
+    /// Wraps the numbers adapter, only producing the first `multiple` of a number
+    /// when the query filters on the multiple's value, as if it pushed down that filter.
+    #[derive(Debug, Default)]
+    struct PushdownAdapter {
+        inner: NumbersAdapter,
+    }
+
+    impl<'a> Adapter<'a> for PushdownAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'a, Self::Vertex> {
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+            let neighbors = self.inner.resolve_neighbors(
+                contexts,
+                type_name,
+                edge_name,
+                parameters,
+                resolve_info,
+            );
+            if edge_name.as_ref() == "multiple"
+                && resolve_info.destination().statically_required_property("value").is_some()
+            {
+                Box::new(neighbors.map(|(ctx, neighbors)| -> (_, VertexIterator<'a, _>) {
+                    (ctx, Box::new(neighbors.take(1)))
+                }))
+            } else {
+                neighbors
+            }
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, bool> {
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+        }
+    }
+
+    fn get_adapter() -> Arc<NumbersAdapter> {
+        static ADAPTER: OnceLock<Arc<NumbersAdapter>> = OnceLock::new();
+        ADAPTER.get_or_init(|| Arc::new(NumbersAdapter::new())).clone()
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.ir.ron"));
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+        let test_query = test_query.unwrap();
+        if test_query.schema_name != "numbers" {
+            return;
+        }
+
+        let mut output_path = PathBuf::from(base);
+        output_path.push(format!("{stem}.output.ron"));
+        let output_data = fs::read_to_string(output_path).unwrap();
+        let expected_outputs: TestInterpreterOutputData = ron::from_str(&output_data).unwrap();
+
+        let arguments: Arc<BTreeMap<Arc<str>, FieldValue>> =
+            Arc::new(test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect());
+        let indexed_query: Arc<IndexedQuery> = Arc::new(test_query.ir_query.try_into().unwrap());
+
+        // The second execution reuses the outcomes cached by the first one,
+        // and must produce the same results.
+        let adapter = Arc::new(CachingAdapter::from_arc(get_adapter()));
+        for _ in 0..2 {
+            let results: Vec<_> =
+                interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                    .expect("execution error")
+                    .collect();
+            assert_eq!(expected_outputs.results, results);
+        }
+    }
+
+    #[test]
+    fn repeated_queries_use_cached_outcomes() {
+        let query = r#"
+{
+    Number(min: 0, max: 10) {
+        value @output
+        name @output
+
+        successor {
+            ... on Composite {
+                primeFactor {
+                    factor: value @output
+                }
+            }
+        }
+    }
+}"#;
+        let counting_adapter = Arc::new(CountingAdapter::default());
+        let indexed_query = crate::frontend::parse(counting_adapter.inner.schema(), query).unwrap();
+        let adapter = Arc::new(CachingAdapter::from_arc(counting_adapter.clone()));
+        let arguments = Arc::new(BTreeMap::new());
+
+        let first: Vec<_> = interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+            .unwrap()
+            .collect();
+        let resolved_vertices = counting_adapter.resolved_vertices();
+        assert!(resolved_vertices > 0);
+
+        let second: Vec<_> =
+            interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                .unwrap()
+                .collect();
+        assert_eq!(first, second);
+        assert_eq!(resolved_vertices, counting_adapter.resolved_vertices());
+
+        // After clearing the cache, everything is resolved again.
+        adapter.clear_cache();
+        let third: Vec<_> = interpret_ir(adapter, indexed_query, arguments).unwrap().collect();
+        assert_eq!(first, third);
+        assert_eq!(2 * resolved_vertices, counting_adapter.resolved_vertices());
+    }
+
+    #[test]
+    fn neighbors_produced_using_hints_are_not_reused_by_other_queries() {
+        let adapter = Arc::new(CachingAdapter::new(PushdownAdapter::default()));
+        let schema = adapter.inner().inner.schema();
+        let run = |query: &str, arguments: BTreeMap<Arc<str>, FieldValue>| -> Vec<FieldValue> {
+            let indexed_query = crate::frontend::parse(schema, query).unwrap();
+            interpret_ir(adapter.clone(), indexed_query, Arc::new(arguments))
+                .unwrap()
+                .map(|mut row| row.remove("value").unwrap())
+                .collect()
+        };
+
+        let filtered = r#"
+{
+    Two {
+        multiple(max: 5) {
+            value @filter(op: "=", value: ["$value"]) @output
+        }
+    }
+}"#;
+        let arguments = btreemap! { Arc::from("value") => FieldValue::Int64(4) };
+        assert_eq!(vec![FieldValue::Int64(4)], run(filtered, arguments));
+
+        let unfiltered = "{ Two { multiple(max: 5) { value @output } } }";
+        let all_multiples: Vec<_> = [4, 6, 8, 10].into_iter().map(FieldValue::Int64).collect();
+        assert_eq!(all_multiples, run(unfiltered, BTreeMap::new()));
+    }
+
+    #[test]
+    fn partially_produced_neighbors_are_not_cached() {
+        let adapter = Arc::new(CachingAdapter::new(NumbersAdapter::new()));
+        let query = "{ Two { multiple(max: 5) { value @output } } }";
+        let indexed_query = crate::frontend::parse(adapter.inner().schema(), query).unwrap();
+        let arguments = Arc::new(BTreeMap::new());
+
+        let first: Vec<_> = interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+            .unwrap()
+            .take(1)
+            .collect();
+        assert_eq!(1, first.len());
+
+        let all: Vec<_> = interpret_ir(adapter, indexed_query, arguments).unwrap().collect();
+        assert_eq!(4, all.len());
+    }
+}
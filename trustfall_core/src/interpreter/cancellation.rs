@@ -0,0 +1,267 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::ir::{EdgeParameters, FieldValue};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo,
+    ResolveInfo, VertexIdentifier, VertexIterator,
+};
+
+/// A handle for stopping the execution of a query before it has produced all its results.
+///
+/// Clones of a token share the same state: cancelling any one of them cancels them all.
+/// This allows a query to be cancelled from another thread, for example in response
+/// to a user request or a shutdown signal. Once cancelled, a token stays cancelled.
+///
+/// Use a token with [`interpret_ir_with_cancellation()`] or the `execute_query_with_cancellation()`
+/// function of the `trustfall` crate.
+///
+/// [`interpret_ir_with_cancellation()`]: super::execution::interpret_ir_with_cancellation
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Make a token that is only cancelled by calling [`CancellationToken::cancel()`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make a token that is cancelled automatically once the given amount of time has passed.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_deadline(Instant::now() + timeout)
+    }
+
+    /// Make a token that is cancelled automatically at the given point in time.
+    pub fn with_deadline(deadline: Instant) -> Self {
+        Self {
+            state: Arc::new(TokenState {
+                cancelled: AtomicBool::new(false),
+                deadline: Some(deadline),
+            }),
+        }
+    }
+
+    /// Cancel this token, and all its clones.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether this token has been cancelled, or its deadline has passed.
+    pub fn is_cancelled(&self) -> bool {
+        if self.state.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        match self.state.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                self.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Stop producing items once the token is cancelled, without asking the iterator for more.
+fn stop_when_cancelled<'a, T: 'a>(
+    mut iterator: Box<dyn Iterator<Item = T> + 'a>,
+    token: CancellationToken,
+) -> Box<dyn Iterator<Item = T> + 'a> {
+    Box::new(std::iter::from_fn(move || if token.is_cancelled() { None } else { iterator.next() }))
+}
+
+/// An [`Adapter`] that stops producing vertices once its [`CancellationToken`] is cancelled.
+///
+/// Starting vertices and neighbors are cut short, which quickly starves query execution
+/// of further work. Every context still gets an outcome, as the interpreter requires,
+/// but the outcomes produced after cancellation may be incomplete. Query results computed
+/// after cancellation must therefore be discarded.
+pub(super) struct CancellableAdapter<AdapterT> {
+    inner: Arc<AdapterT>,
+    token: CancellationToken,
+}
+
+impl<AdapterT> CancellableAdapter<AdapterT> {
+    pub(super) fn new(inner: Arc<AdapterT>, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+}
+
+impl<'vertex, AdapterT: Adapter<'vertex> + 'vertex> Adapter<'vertex>
+    for CancellableAdapter<AdapterT>
+{
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        if self.token.is_cancelled() {
+            return Box::new(std::iter::empty());
+        }
+        stop_when_cancelled(
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info),
+            self.token.clone(),
+        )
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        self.inner.resolve_properties(contexts, type_name, properties, resolve_info)
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let token = self.token.clone();
+        let neighbors =
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info);
+        Box::new(neighbors.map(move |(ctx, neighbors)| {
+            let neighbors: VertexIterator<'vertex, Self::Vertex> = if token.is_cancelled() {
+                Box::new(std::iter::empty())
+            } else {
+                stop_when_cancelled(neighbors, token.clone())
+            };
+            (ctx, neighbors)
+        }))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+    use super::CancellationToken;
+    use crate::{
+        interpreter::{
+            error::QueryCancelled,
+            execution::{interpret_ir, interpret_ir_with_cancellation},
+        },
+        ir::FieldValue,
+        numbers_interpreter::NumbersAdapter,
+    };
+
+    const QUERY: &str = r#"
+{
+    Number(min: 0, max: 100) {
+        value @output
+
+        multiple(max: 3) {
+            multiple: value @output
+        }
+    }
+}"#;
+
+    fn run_query(
+        token: CancellationToken,
+        cancel_after: Option<usize>,
+    ) -> Vec<Result<BTreeMap<Arc<str>, FieldValue>, QueryCancelled>> {
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), QUERY).unwrap();
+        let results = interpret_ir_with_cancellation(
+            adapter,
+            indexed_query,
+            Arc::new(BTreeMap::new()),
+            token.clone(),
+        )
+        .unwrap();
+
+        let mut produced = vec![];
+        for (index, row) in results.enumerate() {
+            if Some(index) == cancel_after {
+                token.cancel();
+            }
+            produced.push(row);
+        }
+        produced
+    }
+
+    #[test]
+    fn uncancelled_query_produces_all_results() {
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), QUERY).unwrap();
+        let expected: Vec<_> = interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new()))
+            .unwrap()
+            .map(Ok)
+            .collect();
+        assert!(!expected.is_empty());
+
+        assert_eq!(expected, run_query(CancellationToken::new(), None));
+        assert_eq!(
+            expected,
+            run_query(CancellationToken::with_timeout(Duration::from_secs(3600)), None)
+        );
+    }
+
+    #[test]
+    fn cancellation_stops_the_query() {
+        let expected = run_query(CancellationToken::new(), None);
+
+        let produced = run_query(CancellationToken::new(), Some(5));
+        let (last, partial) = produced.split_last().unwrap();
+        assert_eq!(&Err(QueryCancelled), last);
+        assert_eq!(&expected[..partial.len()], partial);
+        assert!(partial.len() < expected.len());
+    }
+
+    #[test]
+    fn already_cancelled_query_produces_no_results() {
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(vec![Err(QueryCancelled)], run_query(token, None));
+
+        let expired = CancellationToken::with_timeout(Duration::ZERO);
+        assert!(expired.is_cancelled());
+        assert_eq!(vec![Err(QueryCancelled)], run_query(expired, None));
+    }
+}
@@ -0,0 +1,484 @@
+use std::{collections::HashSet, fmt::Debug, marker::PhantomData, sync::Arc};
+
+use crate::{
+    ir::{EdgeParameters, FieldValue},
+    schema::{error::SchemaMergeError, Schema},
+};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, DataContext, RequiredProperty,
+    ResolveEdgeInfo, ResolveInfo, Typename, VertexIdentifier, VertexIdentity, VertexIterator,
+};
+
+/// A vertex of a [`CompositeAdapter`], produced by one of its two sub-adapters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CompositeVertex<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L: Typename, R: Typename> Typename for CompositeVertex<L, R> {
+    fn typename(&self) -> &'static str {
+        match self {
+            CompositeVertex::Left(vertex) => vertex.typename(),
+            CompositeVertex::Right(vertex) => vertex.typename(),
+        }
+    }
+}
+
+impl<L: VertexIdentity, R: VertexIdentity> VertexIdentity for CompositeVertex<L, R> {
+    type Identity = CompositeVertex<L::Identity, R::Identity>;
+
+    fn identity(&self) -> Self::Identity {
+        match self {
+            CompositeVertex::Left(vertex) => CompositeVertex::Left(vertex.identity()),
+            CompositeVertex::Right(vertex) => CompositeVertex::Right(vertex.identity()),
+        }
+    }
+}
+
+/// An [`Adapter`] that queries the data of two other adapters through a single schema.
+///
+/// The composite adapter's schema is the [merge](Schema::merge) of its sub-adapters' schemas.
+/// Each root edge and vertex type is handled by the sub-adapter whose schema defines it.
+/// To combine more than two adapters, use a `CompositeAdapter` as a sub-adapter
+/// of another `CompositeAdapter`, passing along its [`CompositeAdapter::schema()`].
+///
+/// Since the sub-adapters' schemas are merged without connecting them to each other,
+/// each query is answered entirely by the sub-adapter that owns its root edge.
+pub struct CompositeAdapter<'vertex, LeftT, RightT>
+where
+    LeftT: Adapter<'vertex>,
+    RightT: Adapter<'vertex>,
+{
+    schema: Schema,
+    left: Arc<LeftT>,
+    right: Arc<RightT>,
+    left_root_edges: HashSet<Arc<str>>,
+    left_vertex_types: HashSet<Arc<str>>,
+    _phantom: PhantomData<&'vertex ()>,
+}
+
+impl<'vertex, LeftT, RightT> CompositeAdapter<'vertex, LeftT, RightT>
+where
+    LeftT: Adapter<'vertex>,
+    RightT: Adapter<'vertex>,
+{
+    /// Combine two adapters along with the schemas they implement.
+    ///
+    /// Fails if the schemas cannot be merged, for example
+    /// because both of them define the same vertex type or root edge.
+    pub fn new(
+        left: Arc<LeftT>,
+        left_schema: &Schema,
+        right: Arc<RightT>,
+        right_schema: &Schema,
+    ) -> Result<Self, SchemaMergeError> {
+        let schema = Schema::merge(&[left_schema.clone(), right_schema.clone()])?;
+        let left_root_edges = left_schema
+            .query_type
+            .fields
+            .iter()
+            .map(|field| Arc::from(field.node.name.node.as_str()))
+            .collect();
+        let left_query_type = left_schema.query_type_name();
+        let left_vertex_types = left_schema
+            .vertex_types
+            .keys()
+            .filter(|name| name.as_ref() != left_query_type)
+            .cloned()
+            .collect();
+
+        Ok(Self { schema, left, right, left_root_edges, left_vertex_types, _phantom: PhantomData })
+    }
+
+    /// The merged schema of both sub-adapters, against which queries should be written.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The first sub-adapter.
+    pub fn left(&self) -> &Arc<LeftT> {
+        &self.left
+    }
+
+    /// The second sub-adapter.
+    pub fn right(&self) -> &Arc<RightT> {
+        &self.right
+    }
+
+    fn is_left_type(&self, type_name: &str) -> bool {
+        self.left_vertex_types.contains(type_name)
+    }
+}
+
+/// A query context's vertex, viewed as a vertex of the first sub-adapter.
+#[derive(Debug, Clone)]
+struct LeftVertex<V, L, R>(V, PhantomData<fn() -> (L, R)>);
+
+/// A query context's vertex, viewed as a vertex of the second sub-adapter.
+#[derive(Debug, Clone)]
+struct RightVertex<V, L, R>(V, PhantomData<fn() -> (L, R)>);
+
+impl<V, L, R> LeftVertex<V, L, R> {
+    fn new(vertex: V) -> Self {
+        Self(vertex, PhantomData)
+    }
+
+    fn into_inner(self) -> V {
+        self.0
+    }
+}
+
+impl<V, L, R> RightVertex<V, L, R> {
+    fn new(vertex: V) -> Self {
+        Self(vertex, PhantomData)
+    }
+
+    fn into_inner(self) -> V {
+        self.0
+    }
+}
+
+impl<V, L, R> AsVertex<L> for LeftVertex<V, L, R>
+where
+    V: AsVertex<CompositeVertex<L, R>>,
+    L: Debug + Clone,
+    R: Debug + Clone,
+{
+    fn as_vertex(&self) -> Option<&L> {
+        match self.0.as_vertex()? {
+            CompositeVertex::Left(vertex) => Some(vertex),
+            CompositeVertex::Right(..) => None,
+        }
+    }
+
+    fn into_vertex(self) -> Option<L> {
+        match self.0.into_vertex()? {
+            CompositeVertex::Left(vertex) => Some(vertex),
+            CompositeVertex::Right(..) => None,
+        }
+    }
+}
+
+impl<V, L, R> AsVertex<R> for RightVertex<V, L, R>
+where
+    V: AsVertex<CompositeVertex<L, R>>,
+    L: Debug + Clone,
+    R: Debug + Clone,
+{
+    fn as_vertex(&self) -> Option<&R> {
+        match self.0.as_vertex()? {
+            CompositeVertex::Right(vertex) => Some(vertex),
+            CompositeVertex::Left(..) => None,
+        }
+    }
+
+    fn into_vertex(self) -> Option<R> {
+        match self.0.into_vertex()? {
+            CompositeVertex::Right(vertex) => Some(vertex),
+            CompositeVertex::Left(..) => None,
+        }
+    }
+}
+
+/// Pass contexts to a sub-adapter's resolver, converting their vertices to the wrapper type `W`
+/// that the sub-adapter understands, then converting them back along with the outcomes.
+pub(super) fn dispatch<'vertex, V, W, InnerOutcomeT, OutcomeT>(
+    contexts: ContextIterator<'vertex, V>,
+    mut wrap: fn(V) -> W,
+    mut unwrap: fn(W) -> V,
+    resolve: impl FnOnce(
+        ContextIterator<'vertex, W>,
+    ) -> ContextOutcomeIterator<'vertex, W, InnerOutcomeT>,
+    convert: impl Fn(InnerOutcomeT) -> OutcomeT + 'vertex,
+) -> ContextOutcomeIterator<'vertex, V, OutcomeT>
+where
+    V: 'vertex,
+    W: 'vertex,
+    InnerOutcomeT: 'vertex,
+{
+    let contexts = Box::new(contexts.map(move |ctx: DataContext<V>| ctx.map(&mut wrap)));
+    Box::new(resolve(contexts).map(move |(ctx, outcome)| (ctx.map(&mut unwrap), convert(outcome))))
+}
+
+impl<'vertex, LeftT, RightT> Adapter<'vertex> for CompositeAdapter<'vertex, LeftT, RightT>
+where
+    LeftT: Adapter<'vertex> + 'vertex,
+    RightT: Adapter<'vertex> + 'vertex,
+{
+    type Vertex = CompositeVertex<LeftT::Vertex, RightT::Vertex>;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        if self.left_root_edges.contains(edge_name) {
+            Box::new(
+                self.left
+                    .resolve_starting_vertices(edge_name, parameters, resolve_info)
+                    .map(CompositeVertex::Left),
+            )
+        } else {
+            Box::new(
+                self.right
+                    .resolve_starting_vertices(edge_name, parameters, resolve_info)
+                    .map(CompositeVertex::Right),
+            )
+        }
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        if self.is_left_type(type_name) {
+            dispatch(
+                contexts,
+                LeftVertex::new,
+                LeftVertex::into_inner,
+                |contexts| {
+                    self.left.resolve_property(contexts, type_name, property_name, resolve_info)
+                },
+                std::convert::identity,
+            )
+        } else {
+            dispatch(
+                contexts,
+                RightVertex::new,
+                RightVertex::into_inner,
+                |contexts| {
+                    self.right.resolve_property(contexts, type_name, property_name, resolve_info)
+                },
+                std::convert::identity,
+            )
+        }
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        if self.is_left_type(type_name) {
+            dispatch(
+                contexts,
+                LeftVertex::new,
+                LeftVertex::into_inner,
+                |contexts| {
+                    self.left.resolve_properties(contexts, type_name, properties, resolve_info)
+                },
+                std::convert::identity,
+            )
+        } else {
+            dispatch(
+                contexts,
+                RightVertex::new,
+                RightVertex::into_inner,
+                |contexts| {
+                    self.right.resolve_properties(contexts, type_name, properties, resolve_info)
+                },
+                std::convert::identity,
+            )
+        }
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        if self.is_left_type(type_name) {
+            dispatch(
+                contexts,
+                LeftVertex::new,
+                LeftVertex::into_inner,
+                |contexts| {
+                    self.left.resolve_neighbors(
+                        contexts,
+                        type_name,
+                        edge_name,
+                        parameters,
+                        resolve_info,
+                    )
+                },
+                |neighbors| -> VertexIterator<'vertex, Self::Vertex> {
+                    Box::new(neighbors.map(CompositeVertex::Left))
+                },
+            )
+        } else {
+            dispatch(
+                contexts,
+                RightVertex::new,
+                RightVertex::into_inner,
+                |contexts| {
+                    self.right.resolve_neighbors(
+                        contexts,
+                        type_name,
+                        edge_name,
+                        parameters,
+                        resolve_info,
+                    )
+                },
+                |neighbors| -> VertexIterator<'vertex, Self::Vertex> {
+                    Box::new(neighbors.map(CompositeVertex::Right))
+                },
+            )
+        }
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        if self.is_left_type(type_name) {
+            dispatch(
+                contexts,
+                LeftVertex::new,
+                LeftVertex::into_inner,
+                |contexts| {
+                    self.left.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+                },
+                std::convert::identity,
+            )
+        } else {
+            dispatch(
+                contexts,
+                RightVertex::new,
+                RightVertex::into_inner,
+                |contexts| {
+                    self.right.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+                },
+                std::convert::identity,
+            )
+        }
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        let left = self.left.vertex_identifier()?;
+        let right = self.right.vertex_identifier()?;
+        Some(VertexIdentifier::from_visitors(move || {
+            let mut visit_left = left.start_visiting();
+            let mut visit_right = right.start_visiting();
+            Box::new(move |vertex| match vertex {
+                CompositeVertex::Left(vertex) => visit_left(vertex),
+                CompositeVertex::Right(vertex) => visit_right(vertex),
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::CompositeAdapter;
+    use crate::{
+        filesystem_interpreter::FilesystemInterpreter,
+        interpreter::{execution::interpret_ir, Adapter},
+        ir::FieldValue,
+        numbers_interpreter::NumbersAdapter,
+        schema::{error::SchemaMergeError, Schema},
+    };
+
+    type Row = BTreeMap<Arc<str>, FieldValue>;
+
+    fn run_query<'a, AdapterT: Adapter<'a> + 'a>(
+        adapter: Arc<AdapterT>,
+        schema: &Schema,
+        query: &str,
+    ) -> Vec<Row> {
+        let indexed_query = crate::frontend::parse(schema, query).expect("not a valid query");
+        interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new()))
+            .expect("execution error")
+            .collect()
+    }
+
+    fn filesystem_schema() -> Schema {
+        Schema::parse(include_str!("../../test_data/schemas/filesystem.graphql"))
+            .expect("valid schema")
+    }
+
+    const NUMBERS_QUERY: &str = r#"
+{
+    Number(min: 2, max: 12) {
+        ... on Composite {
+            value @output
+            primeFactor {
+                factor: value @output
+            }
+        }
+    }
+}"#;
+
+    const FILESYSTEM_QUERY: &str = r#"
+{
+    OriginDirectory {
+        out_Directory_ContainsFile {
+            name @output @filter(op: "=", value: ["$name"])
+            extension @output
+        }
+    }
+}"#;
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn composite_adapter_dispatches_to_sub_adapters() {
+        let numbers = Arc::new(NumbersAdapter::new());
+        let filesystem = Arc::new(FilesystemInterpreter::new(".".to_owned()));
+        let composite = Arc::new(
+            CompositeAdapter::new(
+                numbers.clone(),
+                numbers.schema(),
+                filesystem.clone(),
+                &filesystem_schema(),
+            )
+            .expect("schemas could not be merged"),
+        );
+
+        let expected = run_query(numbers.clone(), numbers.schema(), NUMBERS_QUERY);
+        assert!(!expected.is_empty());
+        assert_eq!(expected, run_query(composite.clone(), composite.schema(), NUMBERS_QUERY));
+
+        let query = crate::frontend::parse(composite.schema(), FILESYSTEM_QUERY).unwrap();
+        let arguments = Arc::new(btreemap! { "name".into() => "Cargo.toml".into() });
+        let expected: Vec<_> =
+            interpret_ir(filesystem, query.clone(), arguments.clone()).unwrap().collect();
+        assert_eq!(1, expected.len());
+        let actual: Vec<_> = interpret_ir(composite, query, arguments).unwrap().collect();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn composite_adapter_rejects_overlapping_schemas() {
+        let numbers = Arc::new(NumbersAdapter::new());
+        let error = CompositeAdapter::new(
+            numbers.clone(),
+            numbers.schema(),
+            numbers.clone(),
+            numbers.schema(),
+        )
+        .map(|_| ())
+        .expect_err("merged two copies of the same schema");
+        assert!(
+            matches!(
+                error,
+                SchemaMergeError::DuplicateRootEdge(..) | SchemaMergeError::DuplicateVertexType(..)
+            ),
+            "{error:?}"
+        );
+    }
+}
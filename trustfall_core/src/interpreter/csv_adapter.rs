@@ -0,0 +1,607 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_graphql_parser::types::BaseType;
+
+use crate::{
+    ir::{EdgeParameters, FieldValue},
+    schema::Schema,
+};
+
+use super::{
+    helpers::{resolve_coercion_with, resolve_property_with},
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
+    VertexIterator,
+};
+
+/// An [`Adapter`] over tables of CSV data, where each row is a vertex.
+///
+/// Each table is a root edge of the schema, which points to the type of the table's rows.
+/// Row properties are read from the column of the same name, unless mapped to a different
+/// column with [`CsvTable::map_column`]. Properties without a matching column are `null`.
+///
+/// Column values are converted to the property's type in the schema:
+/// - Empty values are `null`, regardless of the property's type.
+/// - `Int` and `Float` properties are parsed as numbers, and `Boolean` properties
+///   are parsed from `true` or `false` in any capitalization.
+///   Values that fail to parse are `null`.
+/// - Properties of all other types are strings. List-typed properties aren't supported,
+///   and are always `null`.
+///
+/// # Examples
+/// ```
+/// # use std::{collections::BTreeMap, sync::Arc};
+/// # use trustfall_core::{
+/// #     frontend::parse,
+/// #     interpreter::{execution::interpret_ir, CsvAdapter, CsvTable},
+/// #     schema::Schema,
+/// # };
+/// let schema = Schema::parse(format!(
+///     "schema {{ query: RootSchemaQuery }}\n{}\n\
+///     type RootSchemaQuery {{ Airport: [Airport!]! }}\n\
+///     type Airport {{ code: String!  elevation: Int }}",
+///     Schema::ALL_DIRECTIVE_DEFINITIONS,
+/// ))
+/// .expect("invalid schema");
+///
+/// let table = CsvTable::parse("station_id,elevation\nKSFO,4\nKDEN,1655\n")
+///     .expect("invalid CSV")
+///     .map_column("code", "station_id");
+/// let adapter = CsvAdapter::new(schema).with_table("Airport", table);
+///
+/// let query = r#"
+/// {
+///     Airport {
+///         code @output
+///         elevation @filter(op: ">", value: ["$min_elevation"])
+///     }
+/// }"#;
+/// let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+/// let arguments = Arc::new(BTreeMap::from([(Arc::from("min_elevation"), 1000.into())]));
+/// let results: Vec<_> = interpret_ir(Arc::new(adapter), indexed_query, arguments)
+///     .expect("invalid arguments")
+///     .collect();
+///
+/// assert_eq!(results, vec![BTreeMap::from([(Arc::from("code"), "KDEN".into())])]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvAdapter {
+    schema: Arc<Schema>,
+    tables: BTreeMap<Arc<str>, Arc<CsvTable>>,
+}
+
+/// A table of CSV data: a header row naming the columns, and rows of values for those columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvTable {
+    columns: Vec<Arc<str>>,
+
+    /// Properties read from a column whose name differs from the property's name.
+    property_columns: BTreeMap<Arc<str>, usize>,
+
+    rows: Vec<Vec<String>>,
+}
+
+/// A vertex produced by [`CsvAdapter`]: a row of one of its tables.
+#[derive(Debug, Clone)]
+pub struct CsvVertex {
+    typename: Arc<str>,
+    table: Arc<CsvTable>,
+    row: usize,
+}
+
+/// Errors that may arise while reading CSV data.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CsvParseError {
+    #[error("The CSV data has no header row.")]
+    MissingHeaderRow,
+
+    #[error("The quoted value starting on line {0} is never closed.")]
+    UnterminatedQuote(usize),
+
+    #[error("Unexpected quote character on line {0}: only entire values may be quoted.")]
+    UnexpectedQuote(usize),
+
+    #[error("The row on line {0} has {2} values, but the header row has {1} columns.")]
+    MismatchedValueCount(usize, usize, usize),
+}
+
+impl CsvAdapter {
+    /// Make an adapter over the given schema, without any tables yet.
+    ///
+    /// Use [`CsvAdapter::with_table`] to add tables to it.
+    pub fn new(schema: Schema) -> Self {
+        Self { schema: Arc::new(schema), tables: Default::default() }
+    }
+
+    /// Add a table whose rows are the vertices of the given root edge of the schema.
+    ///
+    /// Panics if the schema has no such root edge.
+    pub fn with_table(mut self, edge_name: impl Into<Arc<str>>, table: CsvTable) -> Self {
+        let edge_name = edge_name.into();
+        let query_type = self.schema.query_type_name();
+        assert!(
+            self.schema.fields.contains_key(&(Arc::from(query_type), edge_name.clone())),
+            "table {edge_name} is not an edge of the schema's root type {query_type}",
+        );
+
+        self.tables.insert(edge_name, Arc::new(table));
+        self
+    }
+
+    /// The schema this adapter serves.
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+impl CsvTable {
+    /// Parse comma-separated values, whose first row names the columns.
+    ///
+    /// Values may be quoted with `"`, in which case they may contain commas, line breaks,
+    /// and quote characters escaped by doubling them. Blank lines are ignored.
+    pub fn parse(input: &str) -> Result<Self, CsvParseError> {
+        Self::parse_with_delimiter(input, ',')
+    }
+
+    /// Parse delimiter-separated values, such as tab-separated values,
+    /// whose first row names the columns.
+    ///
+    /// See [`CsvTable::parse`] for the supported format.
+    pub fn parse_with_delimiter(input: &str, delimiter: char) -> Result<Self, CsvParseError> {
+        let mut records = parse_records(input, delimiter)?.into_iter();
+        let (_, header) = records.next().ok_or(CsvParseError::MissingHeaderRow)?;
+        let rows = records
+            .map(|(line, record)| {
+                if record.len() == header.len() {
+                    Ok(record)
+                } else {
+                    Err(CsvParseError::MismatchedValueCount(line, header.len(), record.len()))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_rows(header, rows))
+    }
+
+    /// Make a table out of already-parsed column names and rows.
+    ///
+    /// Panics if any row has a different number of values than there are columns.
+    pub fn from_rows(
+        columns: impl IntoIterator<Item = impl Into<Arc<str>>>,
+        rows: impl IntoIterator<Item = Vec<String>>,
+    ) -> Self {
+        let columns: Vec<Arc<str>> = columns.into_iter().map(Into::into).collect();
+        let rows: Vec<_> = rows.into_iter().collect();
+        for row in &rows {
+            assert_eq!(
+                columns.len(),
+                row.len(),
+                "row {row:?} does not have a value for each of the columns {columns:?}",
+            );
+        }
+
+        Self { columns, property_columns: Default::default(), rows }
+    }
+
+    /// Read the given property from the named column, instead of the column of the same name.
+    ///
+    /// Panics if the table has no such column.
+    pub fn map_column(mut self, property: impl Into<Arc<str>>, column: &str) -> Self {
+        let index = self
+            .columns
+            .iter()
+            .position(|name| name.as_ref() == column)
+            .unwrap_or_else(|| panic!("table has no column {column}: {:?}", self.columns));
+        self.property_columns.insert(property.into(), index);
+        self
+    }
+
+    /// The names of the table's columns.
+    #[inline]
+    pub fn columns(&self) -> &[Arc<str>] {
+        &self.columns
+    }
+
+    /// The number of rows in the table, not including its header row.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the table has no rows other than its header row.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn column_for_property(&self, property: &str) -> Option<usize> {
+        self.property_columns
+            .get(property)
+            .copied()
+            .or_else(|| self.columns.iter().position(|name| name.as_ref() == property))
+    }
+}
+
+impl CsvVertex {
+    /// The name of this vertex's type in the schema.
+    #[inline]
+    pub fn typename(&self) -> &str {
+        &self.typename
+    }
+
+    /// The value of this row in the named column, if the table has such a column.
+    pub fn get(&self, column: &str) -> Option<&str> {
+        let index = self.table.columns.iter().position(|name| name.as_ref() == column)?;
+        Some(self.table.rows[self.row][index].as_str())
+    }
+
+    fn property(&self, property: &str, property_type: PropertyType) -> FieldValue {
+        let Some(index) = self.table.column_for_property(property) else {
+            return FieldValue::Null;
+        };
+        let value = self.table.rows[self.row][index].as_str();
+        if value.is_empty() {
+            return FieldValue::Null;
+        }
+
+        match property_type {
+            PropertyType::Int => value
+                .parse::<i64>()
+                .map(FieldValue::Int64)
+                .or_else(|_| value.parse::<u64>().map(FieldValue::Uint64))
+                .unwrap_or(FieldValue::Null),
+            PropertyType::Float => match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => FieldValue::Float64(number),
+                _ => FieldValue::Null,
+            },
+            PropertyType::Boolean => {
+                if value.eq_ignore_ascii_case("true") {
+                    FieldValue::Boolean(true)
+                } else if value.eq_ignore_ascii_case("false") {
+                    FieldValue::Boolean(false)
+                } else {
+                    FieldValue::Null
+                }
+            }
+            PropertyType::String => value.into(),
+            PropertyType::List => FieldValue::Null,
+        }
+    }
+}
+
+impl<'vertex> Adapter<'vertex> for CsvAdapter {
+    type Vertex = CsvVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        _resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let query_type = self.schema.query_type_name();
+        let field = &self.schema.fields[&(Arc::from(query_type), edge_name.clone())];
+        let mut base_type = &field.ty.node.base;
+        let typename: Arc<str> = loop {
+            match base_type {
+                BaseType::Named(name) => break Arc::from(name.as_str()),
+                BaseType::List(inner) => base_type = &inner.base,
+            }
+        };
+
+        let Some(table) = self.tables.get(edge_name).cloned() else {
+            return Box::new(std::iter::empty());
+        };
+        Box::new((0..table.rows.len()).map(move |row| CsvVertex {
+            typename: typename.clone(),
+            table: table.clone(),
+            row,
+        }))
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            return resolve_property_with(contexts, |vertex: &CsvVertex| {
+                vertex.typename.clone().into()
+            });
+        }
+
+        let field = self
+            .schema
+            .fields
+            .get(&(type_name.clone(), property_name.clone()))
+            .unwrap_or_else(|| panic!("type {type_name} has no property {property_name}"));
+        let property_type = match &field.ty.node.base {
+            BaseType::List(..) => PropertyType::List,
+            BaseType::Named(name) => match name.as_str() {
+                "Int" => PropertyType::Int,
+                "Float" => PropertyType::Float,
+                "Boolean" => PropertyType::Boolean,
+                _ => PropertyType::String,
+            },
+        };
+
+        let property_name = property_name.clone();
+        resolve_property_with(contexts, move |vertex: &CsvVertex| {
+            vertex.property(&property_name, property_type)
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        _contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        _resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        unimplemented!("CSV rows have no edges, but edge {edge_name} on type {type_name} was used")
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        _type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let coerce_to_type = coerce_to_type.clone();
+        let schema = self.schema.clone();
+        resolve_coercion_with(contexts, move |vertex: &CsvVertex| {
+            schema.is_named_type_subtype(&coerce_to_type, &vertex.typename)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PropertyType {
+    Int,
+    Float,
+    Boolean,
+    String,
+    List,
+}
+
+/// Split delimiter-separated values into records, each paired with the line on which it starts.
+fn parse_records(input: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, CsvParseError> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut field_quoted = false;
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut quote_line = 1;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !field_quoted => {
+                in_quotes = true;
+                field_quoted = true;
+                quote_line = line;
+            }
+            '"' => return Err(CsvParseError::UnexpectedQuote(line)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                // Blank lines don't contain any records.
+                if !record.is_empty() || !field.is_empty() || field_quoted {
+                    record.push(std::mem::take(&mut field));
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                field_quoted = false;
+                line += 1;
+                record_line = line;
+            }
+            _ if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                field_quoted = false;
+            }
+            _ if field_quoted => return Err(CsvParseError::UnexpectedQuote(line)),
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(CsvParseError::UnterminatedQuote(quote_line));
+    }
+    if !record.is_empty() || !field.is_empty() || field_quoted {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::{CsvAdapter, CsvParseError, CsvTable};
+    use crate::{
+        frontend::parse,
+        interpreter::{execution::interpret_ir, helpers::check_adapter_invariants},
+        ir::FieldValue,
+        schema::Schema,
+    };
+
+    fn schema() -> Schema {
+        Schema::parse(format!(
+            "\
+schema {{ query: RootSchemaQuery }}
+{}
+
+type RootSchemaQuery {{
+    Station: [Station!]!
+    Reading: [Reading!]!
+}}
+
+type Station {{
+    id: String!
+    name: String
+    elevation: Int
+    active: Boolean
+    tags: [String]
+}}
+
+type Reading {{
+    station: String!
+    temperature: Float
+}}
+",
+            Schema::ALL_DIRECTIVE_DEFINITIONS,
+        ))
+        .expect("invalid schema")
+    }
+
+    fn adapter() -> CsvAdapter {
+        let stations = CsvTable::parse(
+            "\
+id,station name,elevation,active,tags
+KSFO,\"San Francisco, CA\",4,true,x
+KDEN,Denver,1655,FALSE,
+KXYZ,,unknown,maybe,y
+",
+        )
+        .unwrap()
+        .map_column("name", "station name");
+        let readings = CsvTable::parse_with_delimiter(
+            "station\ttemperature\r\nKSFO\t14.5\r\nKDEN\t-3\r\n\r\nKDEN\tinf\r\n",
+            '\t',
+        )
+        .unwrap();
+
+        CsvAdapter::new(schema()).with_table("Station", stations).with_table("Reading", readings)
+    }
+
+    fn run_query(query: &str) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        let adapter = adapter();
+        let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+        interpret_ir(Arc::new(adapter), indexed_query, Arc::new(BTreeMap::new()))
+            .expect("invalid arguments")
+            .collect()
+    }
+
+    #[test]
+    fn parse_quoted_values() {
+        let table =
+            CsvTable::parse("a,b\n\"one, two\",\"say \"\"hi\"\"\"\n\"multi\nline\",\"\"\nlast,row")
+                .unwrap();
+        assert_eq!(
+            CsvTable::from_rows(
+                ["a", "b"],
+                [
+                    vec!["one, two".to_string(), "say \"hi\"".to_string()],
+                    vec!["multi\nline".to_string(), "".to_string()],
+                    vec!["last".to_string(), "row".to_string()],
+                ],
+            ),
+            table,
+        );
+        assert_eq!(3, table.len());
+        assert_eq!(["a", "b"], table.columns().iter().map(|c| c.as_ref()).collect::<Vec<_>>()[..]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Err(CsvParseError::MissingHeaderRow), CsvTable::parse("\n\n"));
+        assert_eq!(Err(CsvParseError::UnterminatedQuote(2)), CsvTable::parse("a\n\"never\nclosed"));
+        assert_eq!(Err(CsvParseError::UnexpectedQuote(2)), CsvTable::parse("a\nin\"side"));
+        assert_eq!(Err(CsvParseError::UnexpectedQuote(2)), CsvTable::parse("a\n\"quoted\"after"));
+        assert_eq!(
+            Err(CsvParseError::MismatchedValueCount(3, 2, 1)),
+            CsvTable::parse("a,b\n1,2\n3\n"),
+        );
+    }
+
+    #[test]
+    fn properties_are_converted_to_schema_types() {
+        let results = run_query(
+            r#"
+        {
+            Station {
+                id @output
+                name @output
+                elevation @output
+                active @output
+                tags @output
+            }
+        }"#,
+        );
+
+        let row = |id: &str, name: FieldValue, elevation: FieldValue, active: FieldValue| {
+            BTreeMap::from([
+                (Arc::from("id"), id.into()),
+                (Arc::from("name"), name),
+                (Arc::from("elevation"), elevation),
+                (Arc::from("active"), active),
+                (Arc::from("tags"), FieldValue::Null),
+            ])
+        };
+        assert_eq!(
+            vec![
+                row("KSFO", "San Francisco, CA".into(), 4.into(), true.into()),
+                row("KDEN", "Denver".into(), 1655.into(), false.into()),
+                row("KXYZ", FieldValue::Null, FieldValue::Null, FieldValue::Null),
+            ],
+            results,
+        );
+    }
+
+    #[test]
+    fn filter_on_converted_values() {
+        let results = run_query(
+            r#"
+        {
+            Reading {
+                station @output
+                temperature @output @filter(op: "is_not_null")
+            }
+        }"#,
+        );
+
+        assert_eq!(
+            vec![
+                BTreeMap::from([
+                    (Arc::from("station"), "KSFO".into()),
+                    (Arc::from("temperature"), FieldValue::Float64(14.5)),
+                ]),
+                BTreeMap::from([
+                    (Arc::from("station"), "KDEN".into()),
+                    (Arc::from("temperature"), FieldValue::Float64(-3.0)),
+                ]),
+            ],
+            results,
+        );
+    }
+
+    #[test]
+    fn adapter_invariants() {
+        let adapter = adapter();
+        let schema = adapter.schema().clone();
+        check_adapter_invariants(&schema, adapter);
+    }
+}
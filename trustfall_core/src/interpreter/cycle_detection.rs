@@ -0,0 +1,490 @@
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+
+use crate::ir::{EdgeParameters, Eid, FieldValue};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, DataContext, RequiredProperty,
+    ResolveEdgeInfo, ResolveInfo, Typename, VertexIdentifier, VertexIdentity, VertexIterator,
+};
+
+/// A vertex of a [`CycleDetectingAdapter`], remembering how `@recurse` reached it.
+#[derive(Debug, Clone)]
+pub struct CycleDetectingVertex<V: VertexIdentity> {
+    vertex: V,
+    path: Option<Arc<RecursionPath<V::Identity>>>,
+}
+
+/// The identities of the vertices from which a `@recurse` edge was expanded
+/// to reach a vertex, nearest first.
+#[derive(Debug)]
+struct RecursionPath<K> {
+    eid: Eid,
+    identity: K,
+    parent: Option<Arc<RecursionPath<K>>>,
+}
+
+impl<K: Eq> RecursionPath<K> {
+    /// Whether the recursion may continue from this path's last vertex to a neighbor.
+    fn may_visit(&self, neighbor: K) -> bool {
+        !self.iter().any(|identity| *identity == neighbor)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &K> + '_ {
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.parent.as_deref();
+            Some(&current.identity)
+        })
+    }
+}
+
+impl<V: VertexIdentity> CycleDetectingVertex<V> {
+    fn new(vertex: V) -> Self {
+        Self { vertex, path: None }
+    }
+
+    /// The underlying adapter's vertex.
+    pub fn vertex(&self) -> &V {
+        &self.vertex
+    }
+
+    /// Unwrap the underlying adapter's vertex.
+    pub fn into_inner(self) -> V {
+        self.vertex
+    }
+}
+
+impl<V: VertexIdentity + Typename> Typename for CycleDetectingVertex<V> {
+    fn typename(&self) -> &'static str {
+        self.vertex.typename()
+    }
+}
+
+impl<V: VertexIdentity> VertexIdentity for CycleDetectingVertex<V> {
+    type Identity = V::Identity;
+
+    fn identity(&self) -> Self::Identity {
+        self.vertex.identity()
+    }
+}
+
+/// An [`Adapter`] that stops `@recurse` from following cycles in the underlying adapter's data.
+///
+/// A recursive edge expansion normally continues through any vertex it reaches,
+/// so recursing without a depth limit along a cycle never ends. This adapter tracks
+/// the vertices along each recursion path and omits any neighbor already on that path,
+/// as determined by the vertex type's [`VertexIdentity`] implementation. Vertices reachable
+/// through several distinct paths are still produced once per path.
+///
+/// This adapter also tells vertices apart using [`VertexIdentity`], so queries over it
+/// may use `@recurse(dedupe: true)` even if the underlying adapter doesn't implement
+/// [`Adapter::vertex_identifier()`].
+///
+/// Only `@recurse` edges are affected. All other edges, properties, and type coercions
+/// are resolved by the underlying adapter as usual.
+pub struct CycleDetectingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: VertexIdentity,
+{
+    inner: Arc<AdapterT>,
+    _phantom: PhantomData<&'vertex ()>,
+}
+
+impl<'vertex, AdapterT> CycleDetectingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: VertexIdentity,
+{
+    /// Wrap an adapter, detecting cycles while recursing through its data.
+    pub fn new(adapter: Arc<AdapterT>) -> Self {
+        Self { inner: adapter, _phantom: PhantomData }
+    }
+
+    /// The adapter whose data is being recursed through.
+    pub fn inner(&self) -> &AdapterT {
+        &self.inner
+    }
+}
+
+impl<'vertex, AdapterT> Debug for CycleDetectingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + Debug,
+    AdapterT::Vertex: VertexIdentity,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CycleDetectingAdapter").field("inner", &self.inner).finish()
+    }
+}
+
+/// A query context's vertex, viewed as a vertex of the underlying adapter.
+#[derive(Debug, Clone)]
+struct InnerVertex<V, Inner>(V, PhantomData<fn() -> Inner>);
+
+impl<V, Inner> InnerVertex<V, Inner> {
+    fn new(vertex: V) -> Self {
+        Self(vertex, PhantomData)
+    }
+
+    fn into_inner(self) -> V {
+        self.0
+    }
+}
+
+impl<V, Inner> AsVertex<Inner> for InnerVertex<V, Inner>
+where
+    V: AsVertex<CycleDetectingVertex<Inner>>,
+    Inner: VertexIdentity + Debug + Clone,
+{
+    fn as_vertex(&self) -> Option<&Inner> {
+        self.0.as_vertex().map(|vertex| &vertex.vertex)
+    }
+
+    fn into_vertex(self) -> Option<Inner> {
+        self.0.into_vertex().map(|vertex| vertex.vertex)
+    }
+}
+
+/// Pass contexts to the underlying adapter's resolver, then restore their original vertices.
+fn forward<'vertex, V, Inner, OutcomeT>(
+    contexts: ContextIterator<'vertex, V>,
+    resolve: impl FnOnce(
+        ContextIterator<'vertex, InnerVertex<V, Inner>>,
+    ) -> ContextOutcomeIterator<'vertex, InnerVertex<V, Inner>, OutcomeT>,
+) -> ContextOutcomeIterator<'vertex, V, OutcomeT>
+where
+    V: 'vertex,
+    Inner: 'vertex,
+    OutcomeT: 'vertex,
+{
+    let contexts = Box::new(contexts.map(|ctx: DataContext<V>| ctx.map(&mut InnerVertex::new)));
+    Box::new(
+        resolve(contexts).map(|(ctx, outcome)| (ctx.map(&mut InnerVertex::into_inner), outcome)),
+    )
+}
+
+impl<'vertex, AdapterT> Adapter<'vertex> for CycleDetectingAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + 'vertex,
+    AdapterT::Vertex: VertexIdentity,
+    <AdapterT::Vertex as VertexIdentity>::Identity: 'vertex,
+{
+    type Vertex = CycleDetectingVertex<AdapterT::Vertex>;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        Box::new(
+            self.inner
+                .resolve_starting_vertices(edge_name, parameters, resolve_info)
+                .map(CycleDetectingVertex::new),
+        )
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        forward(contexts, |contexts| {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        })
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        forward(contexts, |contexts| {
+            self.inner.resolve_properties(contexts, type_name, properties, resolve_info)
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let neighbors = forward(contexts, |contexts| {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        });
+
+        let edge = resolve_info.edge();
+        if edge.recursive().is_none() {
+            return Box::new(neighbors.map(|(ctx, neighbors)| {
+                let neighbors: VertexIterator<'vertex, Self::Vertex> =
+                    Box::new(neighbors.map(CycleDetectingVertex::new));
+                (ctx, neighbors)
+            }));
+        }
+
+        let eid = edge.eid();
+        Box::new(neighbors.map(move |(ctx, neighbors)| {
+            let Some(origin) = ctx.active_vertex::<Self::Vertex>() else {
+                let neighbors: VertexIterator<'vertex, Self::Vertex> =
+                    Box::new(neighbors.map(CycleDetectingVertex::new));
+                return (ctx, neighbors);
+            };
+
+            // Continue the origin's recursion path if it was reached by this same edge,
+            // or start a new path if the recursion starts at the origin.
+            let parent = origin.path.as_ref().filter(|path| path.eid == eid).cloned();
+            let path = Arc::new(RecursionPath { eid, identity: origin.identity(), parent });
+            let current_path = path.clone();
+            let neighbors: VertexIterator<'vertex, Self::Vertex> = Box::new(
+                neighbors.filter(move |neighbor| current_path.may_visit(neighbor.identity())).map(
+                    move |neighbor| CycleDetectingVertex {
+                        vertex: neighbor,
+                        path: Some(path.clone()),
+                    },
+                ),
+            );
+            (ctx, neighbors)
+        }))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        forward(contexts, |contexts| {
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+        })
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        Some(VertexIdentifier::from_vertex_identity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::CycleDetectingAdapter;
+    use crate::{
+        frontend::parse,
+        interpreter::{
+            error::QueryArgumentsError,
+            execution::interpret_ir,
+            helpers::{resolve_neighbors_with, resolve_property_with},
+            Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo,
+            ResolveInfo, VertexIdentity, VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue},
+        numbers_interpreter::NumbersAdapter,
+        schema::Schema,
+    };
+
+    /// A graph with a cycle `0 -> 1 -> 2 -> 0` and a shortcut `0 -> 2`.
+    #[derive(Debug)]
+    struct CyclicGraphAdapter;
+
+    impl CyclicGraphAdapter {
+        fn schema() -> Schema {
+            Schema::parse(format!(
+                "schema {{ query: RootSchemaQuery }}\n{}\n\
+                type RootSchemaQuery {{ Node(value: Int!): Node! }}\n\
+                type Node {{ value: Int!  next: [Node!]! }}",
+                Schema::ALL_DIRECTIVE_DEFINITIONS
+            ))
+            .expect("invalid schema")
+        }
+    }
+
+    impl VertexIdentity for i64 {
+        type Identity = i64;
+
+        fn identity(&self) -> Self::Identity {
+            *self
+        }
+    }
+
+    impl<'a> Adapter<'a> for CyclicGraphAdapter {
+        type Vertex = i64;
+
+        fn resolve_starting_vertices(
+            &self,
+            _edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            _resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'a, Self::Vertex> {
+            let value = parameters["value"].as_i64().expect("not an integer");
+            Box::new(std::iter::once(value))
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            _type_name: &Arc<str>,
+            _property_name: &Arc<str>,
+            _resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+            resolve_property_with(contexts, |vertex| FieldValue::Int64(*vertex))
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            _type_name: &Arc<str>,
+            _edge_name: &Arc<str>,
+            _parameters: &EdgeParameters,
+            _resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+            resolve_neighbors_with(contexts, |vertex| match vertex {
+                0 => Box::new([1, 2].into_iter()),
+                1 => Box::new(std::iter::once(2)),
+                _ => Box::new(std::iter::once(0)),
+            })
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            _contexts: ContextIterator<'a, V>,
+            _type_name: &Arc<str>,
+            _coerce_to_type: &Arc<str>,
+            _resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, bool> {
+            unreachable!("the schema has no subtypes")
+        }
+    }
+
+    fn run_query(query: &str) -> Vec<(i64, i64)> {
+        let schema = CyclicGraphAdapter::schema();
+        let adapter = Arc::new(CycleDetectingAdapter::new(Arc::new(CyclicGraphAdapter)));
+        let indexed_query = parse(&schema, query).expect("invalid query");
+        let mut results: Vec<_> = interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new()))
+            .expect("invalid arguments")
+            .map(|row| {
+                (
+                    row["start"].as_i64().expect("not an integer"),
+                    row["reached"].as_i64().expect("not an integer"),
+                )
+            })
+            .collect();
+        results.sort_unstable();
+        results
+    }
+
+    #[test]
+    fn unbounded_recursion_stops_at_cycles() {
+        let query = r#"
+{
+    Node(value: 0) {
+        start: value @output
+        next @recurse {
+            reached: value @output
+        }
+    }
+}"#;
+
+        // Each path that doesn't revisit a vertex is followed once: 0, 0-1, 0-1-2, and 0-2.
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2), (0, 2)], run_query(query));
+    }
+
+    #[test]
+    fn cycles_are_detected_separately_for_each_recursion() {
+        let query = r#"
+{
+    Node(value: 1) {
+        start: value @output
+        next {
+            next @recurse {
+                reached: value @output
+            }
+        }
+    }
+}"#;
+
+        // Vertex 1 was reached before the recursion started, so it may be revisited.
+        assert_eq!(vec![(1, 0), (1, 1), (1, 2)], run_query(query));
+    }
+
+    #[test]
+    fn deduplicated_recursion_visits_each_vertex_once() {
+        let query = r#"
+{
+    Node(value: 0) {
+        start: value @output
+        next @recurse(dedupe: true) {
+            reached: value @output
+        }
+    }
+}"#;
+
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2)], run_query(query));
+    }
+
+    #[test]
+    fn deduplicated_recursion_requires_an_adapter_that_identifies_vertices() {
+        let adapter = Arc::new(CyclicGraphAdapter);
+        let query = r#"
+{
+    Node(value: 0) {
+        next @recurse(dedupe: true) {
+            value @output
+        }
+    }
+}"#;
+        let indexed_query = parse(&CyclicGraphAdapter::schema(), query).expect("invalid query");
+        let Err(error) = interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new())) else {
+            panic!("executing the query did not fail");
+        };
+        assert_eq!(QueryArgumentsError::UnidentifiableVertices("next".to_string()), error);
+    }
+
+    #[test]
+    fn bounded_recursion_also_stops_at_cycles() {
+        let query = r#"
+{
+    Node(value: 2) {
+        start: value @output
+        next @recurse(depth: 5) {
+            reached: value @output
+        }
+    }
+}"#;
+
+        assert_eq!(vec![(2, 0), (2, 1), (2, 2)], run_query(query));
+    }
+
+    #[test]
+    fn deduplication_uses_vertex_identity() {
+        let adapter = Arc::new(NumbersAdapter::new());
+        let query = r#"
+{
+    Number(min: 12, max: 12) {
+        ... on Composite {
+            divisor @recurse(dedupe: true) {
+                value @output
+            }
+        }
+    }
+}"#;
+        let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+        let adapter = Arc::new(CycleDetectingAdapter::new(adapter));
+        let mut values: Vec<_> = interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new()))
+            .expect("invalid arguments")
+            .map(|row| row["value"].as_i64().expect("not an integer"))
+            .collect();
+        values.sort_unstable();
+
+        // Without deduplication, divisors like 2 would be reached through 12, 6, and 4.
+        assert_eq!(vec![1, 2, 3, 4, 6, 12], values);
+    }
+}
@@ -0,0 +1,536 @@
+use std::{any::Any, fmt::Debug, sync::Arc};
+
+use crate::ir::{EdgeParameters, FieldValue};
+
+use super::{
+    composite_adapter::dispatch, Adapter, AsVertex, ContextIterator, ContextOutcomeIterator,
+    RequiredProperty, ResolveEdgeInfo, ResolveInfo, VertexIdentifier, VertexIterator,
+};
+
+/// A vertex whose type has been erased, produced by a [`DynAdapter`].
+///
+/// Cloning a `DynVertex` is cheap, since the underlying vertex is reference-counted.
+#[derive(Clone)]
+pub struct DynVertex(Arc<dyn AnyVertex>);
+
+impl DynVertex {
+    /// Erase the type of the given vertex.
+    pub fn new<T: Debug + 'static>(vertex: T) -> Self {
+        Self(Arc::new(vertex))
+    }
+
+    /// Get a reference to the underlying vertex, if it is of type `T`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        (*self.0).as_any().downcast_ref()
+    }
+}
+
+impl Debug for DynVertex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+trait AnyVertex: Debug {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Debug + 'static> AnyVertex for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A query context's vertex as seen by an [`ObjectSafeAdapter`], whose type has been erased.
+///
+/// It dereferences to the [`DynVertex`] the context holds, if any.
+pub struct DynContextVertex(Box<dyn AnyContextVertex>);
+
+impl DynContextVertex {
+    fn new<V: AsVertex<DynVertex> + 'static>(vertex: V) -> Self {
+        Self(Box::new(vertex))
+    }
+
+    fn into_inner<V: AsVertex<DynVertex> + 'static>(self) -> V {
+        *self
+            .0
+            .into_any()
+            .downcast()
+            .expect("adapter produced a context vertex that it was not given")
+    }
+}
+
+impl Clone for DynContextVertex {
+    fn clone(&self) -> Self {
+        Self((*self.0).clone_boxed())
+    }
+}
+
+impl Debug for DynContextVertex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl AsVertex<DynVertex> for DynContextVertex {
+    fn as_vertex(&self) -> Option<&DynVertex> {
+        (*self.0).vertex()
+    }
+
+    fn into_vertex(self) -> Option<DynVertex> {
+        (*self.0).vertex().cloned()
+    }
+}
+
+trait AnyContextVertex: Debug {
+    fn vertex(&self) -> Option<&DynVertex>;
+
+    fn clone_boxed(&self) -> Box<dyn AnyContextVertex>;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<V: AsVertex<DynVertex> + 'static> AnyContextVertex for V {
+    fn vertex(&self) -> Option<&DynVertex> {
+        self.as_vertex()
+    }
+
+    fn clone_boxed(&self) -> Box<dyn AnyContextVertex> {
+        Box::new(self.clone())
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A [`DynContextVertex`], viewed as a vertex of the adapter whose vertex type is `T`.
+#[derive(Debug, Clone)]
+struct Downcast<T>(DynContextVertex, std::marker::PhantomData<fn() -> T>);
+
+impl<T> Downcast<T> {
+    fn new(vertex: DynContextVertex) -> Self {
+        Self(vertex, std::marker::PhantomData)
+    }
+
+    fn into_inner(self) -> DynContextVertex {
+        self.0
+    }
+}
+
+impl<T: Debug + Clone + 'static> AsVertex<T> for Downcast<T> {
+    fn as_vertex(&self) -> Option<&T> {
+        AsVertex::<DynVertex>::as_vertex(&self.0)?.downcast_ref()
+    }
+
+    fn into_vertex(self) -> Option<T> {
+        self.as_vertex().cloned()
+    }
+}
+
+/// An object-safe counterpart of the [`Adapter`] trait, whose vertex type is erased.
+///
+/// [`Adapter`] isn't object-safe, since its resolvers are generic over the vertex type
+/// of the query contexts they receive. This trait is implemented for every [`Adapter`],
+/// so adapters of different types can be stored as `Arc<dyn ObjectSafeAdapter>`
+/// and chosen at runtime. Use [`DynAdapter`] to execute queries over them.
+///
+/// Vertices that the underlying adapter doesn't recognize, such as vertices produced
+/// by a different adapter, are treated as if the context had no active vertex.
+pub trait ObjectSafeAdapter {
+    /// Type-erased equivalent of [`Adapter::resolve_starting_vertices()`].
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'static, DynVertex>;
+
+    /// Type-erased equivalent of [`Adapter::resolve_property()`].
+    fn resolve_property(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, FieldValue>;
+
+    /// Type-erased equivalent of [`Adapter::resolve_properties()`].
+    fn resolve_properties(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, Vec<FieldValue>>;
+
+    /// Type-erased equivalent of [`Adapter::resolve_neighbors()`].
+    fn resolve_neighbors(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, VertexIterator<'static, DynVertex>>;
+
+    /// Type-erased equivalent of [`Adapter::resolve_coercion()`].
+    fn resolve_coercion(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, bool>;
+
+    /// Type-erased equivalent of [`Adapter::vertex_identifier()`].
+    ///
+    /// The default implementation returns `None`.
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'static, DynVertex>> {
+        None
+    }
+}
+
+impl<AdapterT: Adapter<'static>> ObjectSafeAdapter for AdapterT {
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'static, DynVertex> {
+        Box::new(
+            Adapter::resolve_starting_vertices(self, edge_name, parameters, resolve_info)
+                .map(DynVertex::new),
+        )
+    }
+
+    fn resolve_property(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, FieldValue> {
+        dispatch(
+            contexts,
+            Downcast::<AdapterT::Vertex>::new,
+            Downcast::into_inner,
+            |contexts| {
+                Adapter::resolve_property(self, contexts, type_name, property_name, resolve_info)
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn resolve_properties(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, Vec<FieldValue>> {
+        dispatch(
+            contexts,
+            Downcast::<AdapterT::Vertex>::new,
+            Downcast::into_inner,
+            |contexts| {
+                Adapter::resolve_properties(self, contexts, type_name, properties, resolve_info)
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn resolve_neighbors(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, VertexIterator<'static, DynVertex>> {
+        dispatch(
+            contexts,
+            Downcast::<AdapterT::Vertex>::new,
+            Downcast::into_inner,
+            |contexts| {
+                Adapter::resolve_neighbors(
+                    self,
+                    contexts,
+                    type_name,
+                    edge_name,
+                    parameters,
+                    resolve_info,
+                )
+            },
+            |neighbors| -> VertexIterator<'static, DynVertex> {
+                Box::new(neighbors.map(DynVertex::new))
+            },
+        )
+    }
+
+    fn resolve_coercion(
+        &self,
+        contexts: ContextIterator<'static, DynContextVertex>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, DynContextVertex, bool> {
+        dispatch(
+            contexts,
+            Downcast::<AdapterT::Vertex>::new,
+            Downcast::into_inner,
+            |contexts| {
+                Adapter::resolve_coercion(self, contexts, type_name, coerce_to_type, resolve_info)
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'static, DynVertex>> {
+        let identifier = Adapter::vertex_identifier(self)?;
+        Some(identifier.map(|vertex: &DynVertex| {
+            vertex.downcast_ref().expect("vertex was not produced by this adapter")
+        }))
+    }
+}
+
+/// An [`Adapter`] whose type has been erased, with [`DynVertex`] as its vertex type.
+///
+/// Allows choosing among adapters of different types at runtime, for example
+/// when adapters are registered dynamically by plugins. Cloning a `DynAdapter`
+/// is cheap, since the underlying adapter is reference-counted.
+#[derive(Clone)]
+pub struct DynAdapter {
+    inner: Arc<dyn ObjectSafeAdapter>,
+}
+
+impl DynAdapter {
+    /// Erase the type of the given adapter.
+    pub fn new(adapter: Arc<impl Adapter<'static> + 'static>) -> Self {
+        Self { inner: adapter }
+    }
+
+    /// The underlying adapter, as an object-safe trait object.
+    pub fn inner(&self) -> &Arc<dyn ObjectSafeAdapter> {
+        &self.inner
+    }
+}
+
+impl From<Arc<dyn ObjectSafeAdapter>> for DynAdapter {
+    fn from(inner: Arc<dyn ObjectSafeAdapter>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Debug for DynAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynAdapter").finish_non_exhaustive()
+    }
+}
+
+impl Adapter<'static> for DynAdapter {
+    type Vertex = DynVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'static, Self::Vertex> {
+        self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'static>(
+        &self,
+        contexts: ContextIterator<'static, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, V, FieldValue> {
+        dispatch(
+            contexts,
+            DynContextVertex::new,
+            DynContextVertex::into_inner,
+            |contexts| {
+                self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'static>(
+        &self,
+        contexts: ContextIterator<'static, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, V, Vec<FieldValue>> {
+        dispatch(
+            contexts,
+            DynContextVertex::new,
+            DynContextVertex::into_inner,
+            |contexts| self.inner.resolve_properties(contexts, type_name, properties, resolve_info),
+            std::convert::identity,
+        )
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'static>(
+        &self,
+        contexts: ContextIterator<'static, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'static, V, VertexIterator<'static, Self::Vertex>> {
+        dispatch(
+            contexts,
+            DynContextVertex::new,
+            DynContextVertex::into_inner,
+            |contexts| {
+                self.inner.resolve_neighbors(
+                    contexts,
+                    type_name,
+                    edge_name,
+                    parameters,
+                    resolve_info,
+                )
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'static>(
+        &self,
+        contexts: ContextIterator<'static, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, V, bool> {
+        dispatch(
+            contexts,
+            DynContextVertex::new,
+            DynContextVertex::into_inner,
+            |contexts| {
+                self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+            },
+            std::convert::identity,
+        )
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'static, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use trustfall_filetests_macros::parameterize;
+
+    use super::{DynAdapter, ObjectSafeAdapter};
+    use crate::{
+        interpreter::execution::interpret_ir,
+        ir::{FieldValue, IndexedQuery},
+        nullables_interpreter::NullablesAdapter,
+        numbers_interpreter::NumbersAdapter,
+        schema::Schema,
+        test_types::{TestIRQueryResult, TestInterpreterOutputData},
+    };
+
+    #[allow(clippy::arc_with_non_send_sync)]
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.ir.ron"));
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+        let test_query = test_query.unwrap();
+        let adapter = match test_query.schema_name.as_str() {
+            "numbers" => DynAdapter::new(Arc::new(NumbersAdapter::new())),
+            "nullables" => DynAdapter::new(Arc::new(NullablesAdapter)),
+            _ => return,
+        };
+
+        let mut output_path = PathBuf::from(base);
+        output_path.push(format!("{stem}.output.ron"));
+        let output_data = fs::read_to_string(output_path).unwrap();
+        let expected_outputs: TestInterpreterOutputData = ron::from_str(&output_data).unwrap();
+
+        let arguments: BTreeMap<Arc<str>, FieldValue> =
+            test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+        let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+
+        let results: Vec<_> =
+            interpret_ir(Arc::new(adapter), indexed_query.into(), arguments.into())
+                .expect("execution error")
+                .collect();
+        assert_eq!(expected_outputs.results, results);
+    }
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn adapters_can_be_chosen_at_runtime() {
+        let numbers = NumbersAdapter::new();
+        let numbers_schema = numbers.schema().clone();
+        let nullables_schema =
+            Schema::parse(include_str!("../../test_data/schemas/nullables.graphql")).unwrap();
+
+        let registry: BTreeMap<&str, (Schema, Arc<dyn ObjectSafeAdapter>)> = btreemap! {
+            "numbers" => (numbers_schema, Arc::new(numbers) as Arc<dyn ObjectSafeAdapter>),
+            "nullables" => (nullables_schema, Arc::new(NullablesAdapter) as Arc<dyn ObjectSafeAdapter>),
+        };
+
+        let run = |name: &str, query: &str| -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+            let (schema, adapter) = &registry[name];
+            let indexed_query = crate::frontend::parse(schema, query).unwrap();
+            let adapter = Arc::new(DynAdapter::from(adapter.clone()));
+            interpret_ir(adapter, indexed_query, Default::default()).unwrap().collect()
+        };
+
+        let numbers_results = run(
+            "numbers",
+            r#"
+{
+    Number(min: 4, max: 4) {
+        value @output
+        predecessor {
+            previous: value @output
+        }
+    }
+}"#,
+        );
+        assert_eq!(
+            vec![btreemap! {
+                "value".into() => FieldValue::Int64(4),
+                "previous".into() => FieldValue::Int64(3),
+            }],
+            numbers_results,
+        );
+
+        let nullables_results = run(
+            "nullables",
+            r#"
+{
+    MainType {
+        nonNullString @output
+        nonNullNeighbor {
+            neighbor: nonNullString @output
+        }
+    }
+}"#,
+        );
+        assert_eq!(
+            vec![btreemap! {
+                "nonNullString".into() => "zero".into(),
+                "neighbor".into() => "one".into(),
+            }],
+            nullables_results,
+        );
+    }
+}
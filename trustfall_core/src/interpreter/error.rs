@@ -1,14 +1,25 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{ir::FieldValue, util::DisplayVec};
+use crate::{
+    ir::{FieldValue, Type},
+    util::DisplayVec,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, thiserror::Error)]
 pub enum QueryArgumentsError {
-    #[error("One or more arguments required by this query were not provided: {0:?}")]
-    MissingArguments(Vec<String>),
+    #[error(
+        "One or more arguments required by this query were not provided: {}",
+        comma_separated(.0)
+    )]
+    MissingArguments(Vec<MissingArgument>),
 
-    #[error("One or more of the provided arguments are not used in this query: {0:?}")]
-    UnusedArguments(Vec<String>),
+    #[error(
+        "One or more of the provided arguments are not used in this query: {}",
+        comma_separated(.0)
+    )]
+    UnusedArguments(Vec<UnusedArgument>),
 
     #[error(
         "The query requires argument \"{0}\" to have type {1}, but the provided value cannot be \
@@ -16,10 +27,73 @@ pub enum QueryArgumentsError {
     )]
     ArgumentTypeError(String, String, FieldValue),
 
+    #[error(
+        "The query uses argument \"{0}\" as a regex pattern, but the provided value is not \
+        a valid regex: {1}"
+    )]
+    InvalidRegexArgument(String, String),
+
+    #[error(
+        "The query uses @recurse(dedupe: true) on edge \"{0}\", but the adapter cannot tell \
+        its vertices apart: it does not implement Adapter::vertex_identifier()"
+    )]
+    UnidentifiableVertices(String),
+
     #[error("Multiple argument errors: {0}")]
     MultipleErrors(DisplayVec<QueryArgumentsError>),
 }
 
+/// A variable used in the query, for which no argument was provided.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MissingArgument {
+    /// The name of the variable.
+    pub name: String,
+
+    /// The type of value the query requires for this argument.
+    pub expected_type: Type,
+
+    /// An unused argument with a similar name, which may have been meant as this argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Display for MissingArgument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" of type {}", self.name, self.expected_type)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (an argument named \"{suggestion}\" was provided instead)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A provided argument that doesn't correspond to any variable used in the query.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnusedArgument {
+    /// The name of the argument.
+    pub name: String,
+
+    /// A missing argument with a similar name, which this argument may have been meant as.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Display for UnusedArgument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.name)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean \"{suggestion}\"?)")?;
+        }
+        Ok(())
+    }
+}
+
+fn comma_separated(items: &[impl Display]) -> String {
+    items.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
 impl From<Vec<QueryArgumentsError>> for QueryArgumentsError {
     fn from(v: Vec<QueryArgumentsError>) -> Self {
         assert!(!v.is_empty());
@@ -30,3 +104,27 @@ impl From<Vec<QueryArgumentsError>> for QueryArgumentsError {
         }
     }
 }
+
+/// Query execution was stopped by its [`CancellationToken`](super::CancellationToken)
+/// before the query produced all its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Query execution was cancelled before all results were produced")]
+pub struct QueryCancelled;
+
+/// Query execution was stopped because the query exceeded one of its
+/// [`ExecutionLimits`](super::ExecutionLimits). Holds the value of the exceeded limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum LimitExceeded {
+    #[error("The query produced more than {0} vertices")]
+    MaxContexts(usize),
+
+    #[error("A @fold in the query had more than {0} elements")]
+    MaxFoldElements(usize),
+
+    #[error("The query's @recurse edges produced more than {0} vertices")]
+    MaxRecursionExpansions(usize),
+
+    #[error("The query required more than {0} resolver calls")]
+    MaxResolverCalls(usize),
+}
@@ -1,27 +1,47 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
     fmt::Debug,
-    sync::Arc,
+    num::NonZeroUsize,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        Arc, Mutex,
+    },
 };
 
 use crate::{
     ir::{
-        Argument, ContextField, EdgeParameters, Eid, FieldRef, FieldValue, FoldSpecificFieldKind,
-        IREdge, IRFold, IRQueryComponent, IRVertex, IndexedQuery, LocalField, Operation, Recursive,
-        Vid,
+        Argument, ContextField, EdgeKind, EdgeParameters, Eid, FieldRef, FieldValue,
+        FoldSpecificFieldKind, IREdge, IRFold, IRQueryComponent, IRVertex, IndexedQuery,
+        LocalField, Operation, OrderDirection, OutputOrdering, Recursive, ValueTransform, Vid,
     },
     util::BTreeMapTryInsertExt,
 };
 
 use super::{
-    error::QueryArgumentsError, filtering::apply_filter, Adapter, AsVertex, ContextIterator,
-    ContextOutcomeIterator, DataContext, InterpretedQuery, ResolveEdgeInfo, ResolveInfo,
-    TaggedValue, ValueOrVec, VertexIterator,
+    cancellation::CancellableAdapter,
+    error::{LimitExceeded, QueryArgumentsError, QueryCancelled},
+    filtering::{apply_filter, RegexCache},
+    limits::{LimitTracker, LimitedAdapter},
+    Adapter, AsVertex, CancellationToken, ContextIterator, ContextOutcomeIterator, DataContext,
+    ExecutionLimits, InterpretedQuery, RequiredProperty, ResolveEdgeInfo, ResolveInfo, TaggedValue,
+    ValueOrVec, VertexIdentifier, VertexIterator,
 };
 
+#[cfg(feature = "async")]
+pub use super::async_adapter::{interpret_ir_async, interpret_ir_stream, QueryResultStream};
+pub use super::batch::interpret_ir_batch;
+pub use super::join::interpret_joined;
+pub use super::schedule::interpret_ir_debug;
+pub use super::threaded::{interpret_ir_threaded, ThreadedQueryResults};
+
 #[derive(Debug, Clone)]
 pub(super) struct QueryCarrier {
     pub(in crate::interpreter) query: Option<InterpretedQuery>,
+    pub(in crate::interpreter) regex_cache: RegexCache,
 }
 
 #[allow(clippy::type_complexity)]
@@ -30,29 +50,320 @@ pub fn interpret_ir<'query, AdapterT: Adapter<'query> + 'query>(
     indexed_query: Arc<IndexedQuery>,
     arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
 ) -> Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>, QueryArgumentsError>
+{
+    // With the `tracing` feature, the query and each resolver call get their own span.
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!("interpret_ir", root_edge = %indexed_query.ir_query.root_name);
+    #[cfg(feature = "tracing")]
+    let _entered = span.enter();
+    #[cfg(feature = "tracing")]
+    let adapter = Arc::new(super::spans::TracingAdapter::new(adapter));
+
+    let query = InterpretedQuery::from_query_and_arguments(indexed_query, arguments)?;
+    check_vertex_identifier(&query.indexed_query, adapter.vertex_identifier().is_some())?;
+    let (starting_vertices, query) = resolve_starting_vertices(adapter.as_ref(), query);
+    let results = compute_final_results(adapter, query, starting_vertices);
+
+    #[cfg(feature = "tracing")]
+    let results = super::spans::in_span(results, span.clone());
+    Ok(results)
+}
+
+/// Ensure that the adapter can tell its vertices apart, if the query's `@recurse(dedupe: true)`
+/// edges need it to.
+pub(super) fn check_vertex_identifier(
+    indexed_query: &IndexedQuery,
+    identifies_vertices: bool,
+) -> Result<(), QueryArgumentsError> {
+    if identifies_vertices {
+        return Ok(());
+    }
+
+    let dedupe_edge = indexed_query.eids.values().find_map(|edge| match edge {
+        EdgeKind::Regular(edge) if edge.recursive.as_ref().is_some_and(|r| r.dedupe) => Some(edge),
+        _ => None,
+    });
+    match dedupe_edge {
+        Some(edge) => Err(QueryArgumentsError::UnidentifiableVertices(edge.edge_name.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Run the query, stopping early if the given [`CancellationToken`] is cancelled.
+///
+/// The token is checked each time a starting vertex or neighbor vertex is produced.
+/// Once it's cancelled, the query stops loading vertices from the adapter and the results
+/// iterator produces a single [`QueryCancelled`] error, after all the results produced
+/// before cancellation. All results produced before that error are complete and correct.
+#[allow(clippy::type_complexity)]
+pub fn interpret_ir_with_cancellation<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    token: CancellationToken,
+) -> Result<
+    Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, QueryCancelled>> + 'query>,
+    QueryArgumentsError,
+> {
+    let adapter = Arc::new(CancellableAdapter::new(adapter, token.clone()));
+    let results = interpret_ir(adapter, indexed_query, arguments)?;
+    Ok(results_until_cancelled(results, token, || QueryCancelled))
+}
+
+/// Run the query, stopping early if it exceeds any of the given [`ExecutionLimits`].
+///
+/// Once a limit is exceeded, the query stops loading vertices from the adapter and the results
+/// iterator produces a single [`LimitExceeded`] error describing the first exceeded limit,
+/// after all the results produced before that point. Those results are complete and correct.
+#[allow(clippy::type_complexity)]
+pub fn interpret_ir_with_limits<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    limits: ExecutionLimits,
+) -> Result<
+    Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, LimitExceeded>> + 'query>,
+    QueryArgumentsError,
+> {
+    let tracker = Arc::new(LimitTracker::new(limits));
+    let token = tracker.token().clone();
+    let adapter = Arc::new(LimitedAdapter::new(adapter, indexed_query.clone(), tracker.clone()));
+    let adapter = Arc::new(CancellableAdapter::new(adapter, token.clone()));
+    let results = interpret_ir(adapter, indexed_query, arguments)?;
+    Ok(results_until_cancelled(results, token, move || {
+        tracker.exceeded().expect("token was cancelled without exceeding a limit")
+    }))
+}
+
+/// Produce the results, ending with the error from `cancelled` if the token is cancelled.
+///
+/// Results computed after cancellation may be missing data, so they are discarded.
+/// Cancellation is permanent, so results produced before it was noticed are unaffected.
+#[allow(clippy::type_complexity)]
+fn results_until_cancelled<'query, E: 'query>(
+    mut results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>,
+    token: CancellationToken,
+    cancelled: impl Fn() -> E + 'query,
+) -> Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, E>> + 'query> {
+    let mut finished = false;
+    Box::new(std::iter::from_fn(move || {
+        if finished {
+            return None;
+        }
+        let row = if token.is_cancelled() { None } else { results.next() };
+        if token.is_cancelled() {
+            finished = true;
+            return Some(Err(cancelled()));
+        }
+        finished = row.is_none();
+        row.map(Ok)
+    }))
+}
+
+/// Run the query on multiple threads, each processing a share of the query's starting vertices.
+///
+/// The starting vertices are resolved on the calling thread, then split into chunks
+/// that are processed independently on worker threads. The results are the same as,
+/// and in the same order as, the results of [`interpret_ir`].
+///
+/// Unlike [`interpret_ir`], this function computes all the query's results before returning.
+#[allow(clippy::type_complexity)]
+pub fn interpret_ir_parallel<'query, AdapterT>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<Vec<BTreeMap<Arc<str>, FieldValue>>, QueryArgumentsError>
+where
+    AdapterT: Adapter<'query> + Send + Sync + 'query,
+    AdapterT::Vertex: Send,
 {
     let query = InterpretedQuery::from_query_and_arguments(indexed_query, arguments)?;
-    let root_vid = query.indexed_query.ir_query.root_component.root;
+    check_vertex_identifier(&query.indexed_query, adapter.vertex_identifier().is_some())?;
+    let (starting_vertices, query) = resolve_starting_vertices(adapter.as_ref(), query);
+    let starting_vertices: Vec<_> = starting_vertices.collect();
 
     let ir_query = &query.indexed_query.ir_query;
-    let root_edge = &ir_query.root_name;
-    let root_edge_parameters = &ir_query.root_parameters;
+    let offset = ir_query.offset.unwrap_or(0);
+
+    // Without `@order`, the results are produced in order of their starting vertices,
+    // so no chunk needs to produce more results than could be part of the final output.
+    // With `@dedupe`, a chunk's results may be duplicates of other chunks' results,
+    // so each chunk's results may all be needed.
+    let chunk_result_limit = match ir_query.limit {
+        Some(limit) if ir_query.order_by.is_empty() && !ir_query.dedupe => {
+            Some(limit.get().saturating_add(offset))
+        }
+        _ => None,
+    };
 
-    let mut carrier = QueryCarrier { query: None };
+    // Use more chunks than threads, so that threads that happen to get quicker chunks
+    // can pick up more work instead of sitting idle.
+    let thread_count = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk_size = starting_vertices.len().div_ceil(thread_count * 4).max(1);
+    let mut remaining_vertices = starting_vertices.into_iter().peekable();
+    let mut chunks = vec![];
+    while remaining_vertices.peek().is_some() {
+        chunks.push(Mutex::new(remaining_vertices.by_ref().take(chunk_size).collect::<Vec<_>>()));
+    }
+    let next_chunk = AtomicUsize::new(0);
+
+    let mut chunk_results: Vec<(usize, Vec<BTreeMap<Arc<str>, FieldValue>>)> =
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..thread_count.min(chunks.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut worker_results = vec![];
+                        loop {
+                            let chunk_index = next_chunk.fetch_add(1, AtomicOrdering::Relaxed);
+                            let Some(chunk) = chunks.get(chunk_index) else {
+                                break;
+                            };
+                            let vertices = std::mem::take(
+                                &mut *chunk.lock().expect("chunk mutex was poisoned"),
+                            );
+
+                            let results = compute_results(
+                                adapter.clone(),
+                                query.clone(),
+                                Box::new(vertices.into_iter()),
+                            );
+                            let results = match chunk_result_limit {
+                                Some(limit) => results.take(limit).collect(),
+                                None => results.collect(),
+                            };
+                            worker_results.push((chunk_index, results));
+                        }
+                        worker_results
+                    })
+                })
+                .collect();
 
-    let resolve_info = ResolveInfo::new(query.clone(), root_vid, false);
+            workers
+                .into_iter()
+                .flat_map(|worker| match worker.join() {
+                    Ok(results) => results,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        });
+    chunk_results.sort_unstable_by_key(|(chunk_index, _)| *chunk_index);
 
-    let mut iterator: ContextIterator<'query, AdapterT::Vertex> = Box::new(
-        adapter
-            .resolve_starting_vertices(root_edge, root_edge_parameters, &resolve_info)
-            .map(|x| DataContext::new(Some(x))),
-    );
-    carrier.query = Some(resolve_info.into_inner());
+    let mut results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>>> =
+        Box::new(chunk_results.into_iter().flat_map(|(_, results)| results));
+    if ir_query.dedupe {
+        results = dedupe_results(results);
+    }
+    let results = if ir_query.order_by.is_empty() {
+        results.collect::<Vec<_>>()
+    } else {
+        order_results(results, &ir_query.order_by)
+    };
+    let results = results.into_iter().skip(offset);
+    Ok(match ir_query.limit {
+        Some(limit) => results.take(limit.get()).collect(),
+        None => results.collect(),
+    })
+}
+
+pub(super) fn resolve_starting_vertices<'query, AdapterT: Adapter<'query>>(
+    adapter: &AdapterT,
+    query: InterpretedQuery,
+) -> (VertexIterator<'query, AdapterT::Vertex>, InterpretedQuery) {
+    let ir_query = &query.indexed_query.ir_query;
+    let root_vid = ir_query.root_component.root;
+    let root_edge = ir_query.root_name.clone();
+    let root_edge_parameters = ir_query.root_parameters.clone();
+
+    let resolve_info = ResolveInfo::new(query, root_vid, false);
+    let starting_vertices =
+        adapter.resolve_starting_vertices(&root_edge, &root_edge_parameters, &resolve_info);
+    (starting_vertices, resolve_info.into_inner())
+}
+
+/// Compute the query's result rows that stem from the given starting vertices.
+///
+/// Does not apply the query's `@order`, `@limit`, and `@offset` directives.
+fn compute_results<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    query: InterpretedQuery,
+    starting_vertices: VertexIterator<'query, AdapterT::Vertex>,
+) -> Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query> {
+    let component = query.indexed_query.ir_query.root_component.clone();
+    let mut carrier = QueryCarrier { query: Some(query), regex_cache: RegexCache::default() };
+
+    let iterator: ContextIterator<'query, AdapterT::Vertex> =
+        Box::new(starting_vertices.map(|x| DataContext::new(Some(x))));
+    let iterator = compute_component(adapter.clone(), &mut carrier, &component, iterator);
+
+    construct_outputs(adapter.as_ref(), &mut carrier, iterator)
+}
+
+/// Compute the query's result rows that stem from the given starting vertices,
+/// applying the query's `@dedupe`, `@order`, `@offset`, and `@limit` directives.
+pub(super) fn compute_final_results<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    query: InterpretedQuery,
+    starting_vertices: VertexIterator<'query, AdapterT::Vertex>,
+) -> Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query> {
+    let order_by = query.indexed_query.ir_query.order_by.clone();
+    let limit = query.indexed_query.ir_query.limit;
+    let offset = query.indexed_query.ir_query.offset;
+    let dedupe = query.indexed_query.ir_query.dedupe;
+    let mut results = compute_results(adapter, query, starting_vertices);
+    if dedupe {
+        results = dedupe_results(results);
+    }
+    if !order_by.is_empty() {
+        // Sorting requires buffering all results. Defer that until the first result
+        // is requested, so that calling this function doesn't execute the whole query.
+        results = Box::new(
+            std::iter::once(results).flat_map(move |results| order_results(results, &order_by)),
+        );
+    }
+    if let Some(offset) = offset {
+        results = Box::new(results.skip(offset));
+    }
+    if let Some(limit) = limit {
+        results = Box::new(results.take(limit.get()));
+    }
+    results
+}
 
-    let component = &ir_query.root_component;
-    iterator = compute_component(adapter.clone(), &mut carrier, component, iterator);
+/// Discard result rows identical to any previously produced row, as requested by `@dedupe`.
+///
+/// The first occurrence of each row is kept, so the relative order of results is unchanged.
+/// All distinct rows produced so far are remembered in order to recognize their duplicates.
+fn dedupe_results<'query>(
+    results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>,
+) -> Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query> {
+    let mut seen: HashSet<BTreeMap<Arc<str>, FieldValue>> = Default::default();
+    Box::new(results.filter(move |row| seen.insert(row.clone())))
+}
 
-    Ok(construct_outputs(adapter.as_ref(), &mut carrier, iterator))
+/// Buffer all result rows and sort them according to the query's `@order` directives.
+///
+/// The sort is stable: rows whose ordered outputs are all equal are produced
+/// in the same relative order in which they were originally computed.
+fn order_results<'query>(
+    results: Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>,
+    order_by: &[OutputOrdering],
+) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+    let mut rows: Vec<_> = results.collect();
+    rows.sort_by(|left, right| {
+        order_by
+            .iter()
+            .map(|ordering| {
+                let name = &ordering.output_name;
+                let comparison = left[name].partial_cmp(&right[name]).unwrap_or(Ordering::Equal);
+                match ordering.direction {
+                    OrderDirection::Ascending => comparison,
+                    OrderDirection::Descending => comparison.reverse(),
+                }
+            })
+            .find(|comparison| comparison.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    rows
 }
 
 fn coerce_if_needed<'query, AdapterT: Adapter<'query>>(
@@ -127,6 +438,13 @@ fn compute_component<'query, AdapterT: Adapter<'query> + 'query>(
 
     let mut visited_vids: BTreeSet<Vid> = btreeset! {component_root_vid};
 
+    // The existence of each `@optional` group is checked just before expanding its first edge.
+    let optional_groups_by_first_edge: BTreeMap<Eid, &[Eid]> = component
+        .optional_groups
+        .values()
+        .filter_map(|group| group.first().map(|first| (*first, group.as_slice())))
+        .collect();
+
     let mut edge_iter = component.edges.values();
     let mut fold_iter = component.folds.values();
     let mut next_edge = edge_iter.next();
@@ -166,8 +484,13 @@ fn compute_component<'query, AdapterT: Adapter<'query> + 'query>(
             assert!(!from_vid_unvisited);
             assert!(to_vid_unvisited);
 
+            if let Some(group) = optional_groups_by_first_edge.get(&edge.eid) {
+                iterator =
+                    check_optional_group_existence(&adapter, carrier, component, group, iterator);
+            }
+
             iterator = expand_edge(
-                adapter.as_ref(),
+                &adapter,
                 carrier,
                 component,
                 edge.from_vid,
@@ -188,42 +511,17 @@ fn construct_outputs<'query, AdapterT: Adapter<'query>>(
     carrier: &mut QueryCarrier,
     iterator: ContextIterator<'query, AdapterT::Vertex>,
 ) -> Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query> {
-    let mut query = carrier.query.take().expect("query was not returned");
-
+    let query = carrier.query.as_ref().expect("query was not returned");
     let root_component = query.indexed_query.ir_query.root_component.clone();
-    let mut output_names: Vec<Arc<str>> =
-        query.indexed_query.ir_query.root_component.outputs.keys().cloned().collect();
-    output_names.sort_unstable(); // to ensure deterministic resolve_property() ordering
-
-    let mut output_iterator = iterator;
-
-    for output_name in output_names.iter() {
-        let context_field = &root_component.outputs[output_name];
-        let vertex_id = context_field.vertex_id;
-
-        let moved_iterator = Box::new(output_iterator.map(move |context| {
-            let new_vertex = context.vertices[&vertex_id].clone();
-            context.move_to_vertex(new_vertex)
-        }));
-
-        let resolve_info = ResolveInfo::new(query, vertex_id, true);
+    let expected_output_names: BTreeSet<_> = query.indexed_query.outputs.keys().cloned().collect();
 
-        let type_name = &root_component.vertices[&vertex_id].type_name;
-        let field_data_iterator = adapter.resolve_property(
-            moved_iterator,
-            type_name,
-            &context_field.field_name,
-            &resolve_info,
-        );
-        query = resolve_info.into_inner();
+    let mut output_names: Vec<Arc<str>> = root_component.outputs.keys().cloned().collect();
+    output_names.sort_unstable(); // to ensure deterministic resolve_property() ordering
 
-        output_iterator = Box::new(field_data_iterator.map(|(mut context, value)| {
-            context.values.push(value);
-            context
-        }));
-    }
-    let expected_output_names: BTreeSet<_> = query.indexed_query.outputs.keys().cloned().collect();
-    carrier.query = Some(query);
+    let output_fields: Vec<_> =
+        output_names.iter().map(|name| &root_component.outputs[name]).collect();
+    let output_iterator =
+        resolve_fields(adapter, carrier, &root_component, &output_fields, iterator);
 
     Box::new(output_iterator.map(move |mut context| {
         assert!(
@@ -246,6 +544,110 @@ fn construct_outputs<'query, AdapterT: Adapter<'query>>(
     }))
 }
 
+/// Resolve the values of the given fields, pushing them onto each context's values
+/// in the same order as the fields.
+///
+/// Properties of the same vertex are resolved together with a single `resolve_properties()`
+/// call, and a property used by multiple fields is only resolved once.
+///
+/// This does not restore the prior active vertex after resolving the fields,
+/// so it is only suitable for computing outputs.
+fn resolve_fields<'query, AdapterT: Adapter<'query>>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    fields: &[&ContextField],
+    mut iterator: ContextIterator<'query, AdapterT::Vertex>,
+) -> ContextIterator<'query, AdapterT::Vertex> {
+    // Group the properties by vertex, in the order in which the vertices are first needed.
+    let mut batches: Vec<(Vid, Vec<RequiredProperty>)> = vec![];
+    let mut locations = Vec::with_capacity(fields.len());
+    for field in fields {
+        let batch_index =
+            batches.iter().position(|(vid, _)| *vid == field.vertex_id).unwrap_or_else(|| {
+                batches.push((field.vertex_id, vec![]));
+                batches.len() - 1
+            });
+        let properties = &mut batches[batch_index].1;
+        let property_index =
+            properties.iter().position(|p| p.name == field.field_name).unwrap_or_else(|| {
+                properties.push(RequiredProperty::new(field.field_name.clone()));
+                properties.len() - 1
+            });
+        locations.push((batch_index, property_index));
+    }
+
+    // The resolved values are appended to the context's values batch by batch.
+    // Find where each field's value ends up.
+    let mut batch_offsets = Vec::with_capacity(batches.len());
+    let mut resolved_count = 0;
+    for (_, properties) in &batches {
+        batch_offsets.push(resolved_count);
+        resolved_count += properties.len();
+    }
+    let value_indexes: Vec<usize> = locations
+        .into_iter()
+        .map(|(batch_index, property_index)| batch_offsets[batch_index] + property_index)
+        .collect();
+
+    // The operands of the fields' transforms are pushed first, so they end up
+    // right before the resolved values.
+    for field in fields {
+        iterator =
+            push_transform_operands(adapter, carrier, component, None, &field.transforms, iterator);
+    }
+
+    for (vertex_id, properties) in batches {
+        let moved_iterator = Box::new(iterator.map(move |context| {
+            let new_vertex = context.vertices[&vertex_id].clone();
+            context.move_to_vertex(new_vertex)
+        }));
+
+        let query = carrier.query.take().expect("query was not returned");
+        let resolve_info = ResolveInfo::new(query, vertex_id, true);
+        let type_name = &component.vertices[&vertex_id].type_name;
+        iterator = if let [property] = properties.as_slice() {
+            // Push the value directly, to avoid allocating a `Vec` for every resolved property.
+            Box::new(
+                adapter
+                    .resolve_property(moved_iterator, type_name, &property.name, &resolve_info)
+                    .map(|(mut context, value)| {
+                        context.values.push(value);
+                        context
+                    }),
+            )
+        } else {
+            Box::new(
+                adapter
+                    .resolve_properties(moved_iterator, type_name, &properties, &resolve_info)
+                    .map(|(mut context, values)| {
+                        context.values.extend(values);
+                        context
+                    }),
+            )
+        };
+        carrier.query = Some(resolve_info.into_inner());
+    }
+
+    let transforms: Vec<Vec<ValueTransform>> =
+        fields.iter().map(|field| field.transforms.clone()).collect();
+    Box::new(iterator.map(move |mut context| {
+        let resolved = context.values.split_off(context.values.len() - resolved_count);
+        let mut operands = vec![];
+        for transforms in transforms.iter().rev() {
+            operands.push(pop_transform_operands(&mut context, transforms));
+        }
+        context.values.extend(
+            value_indexes.iter().zip(&transforms).zip(operands.iter().rev()).map(
+                |((index, transforms), operands)| {
+                    transform_value(resolved[*index].clone(), transforms, operands)
+                },
+            ),
+        );
+        context
+    }))
+}
+
 /// Extracts numeric [`FieldValue`] into a `usize`, clamping negative numbers to 0.
 /// Returns `None` on `FieldValue::Null`, and panics otherwise.
 fn usize_from_field_value(field_value: &FieldValue) -> Option<usize> {
@@ -261,26 +663,37 @@ fn usize_from_field_value(field_value: &FieldValue) -> Option<usize> {
         | FieldValue::List(_)
         | FieldValue::Enum(_)
         | FieldValue::Boolean(_)
-        | FieldValue::String(_) => {
+        | FieldValue::String(_)
+        | FieldValue::DateTime(_) => {
             panic!("got field value {field_value:#?} in usize_from_field_value which should only ever get Int64, Uint64, or Null")
         }
+        #[cfg(feature = "num-bigint")]
+        FieldValue::BigInt(_) => {
+            unreachable!("fold counts are compared against Int values, not {field_value:#?}")
+        }
     }
 }
 
 /// If this IRFold has a filter on the folded element count, and that filter imposes
 /// a max size that can be statically determined, return that max size so it can
 /// be used for further optimizations. Otherwise, return None.
-fn get_max_fold_count_limit(carrier: &mut QueryCarrier, fold: &IRFold) -> Option<usize> {
+fn get_max_fold_count_limit(query: &InterpretedQuery, fold: &IRFold) -> Option<usize> {
+    if fold.absent {
+        // An `@absent` fold is discarded as soon as it is found to have any elements.
+        return Some(0);
+    }
+
     let mut result: Option<usize> = None;
 
-    let query_arguments = &carrier.query.as_ref().expect("query was not returned").arguments;
+    let query_arguments = &query.arguments;
     for post_fold_filter in fold.post_filters.iter() {
         let next_limit = match post_fold_filter {
             Operation::Equals(FoldSpecificFieldKind::Count, Argument::Variable(var_ref))
             | Operation::LessThanOrEqual(
                 FoldSpecificFieldKind::Count,
                 Argument::Variable(var_ref),
-            ) => {
+            )
+            | Operation::Between(FoldSpecificFieldKind::Count, _, Argument::Variable(var_ref)) => {
                 let variable_value =
                     usize_from_field_value(&query_arguments[&var_ref.variable_name])
                         .expect("for field value to be coercible to usize");
@@ -322,54 +735,145 @@ fn get_max_fold_count_limit(carrier: &mut QueryCarrier, fold: &IRFold) -> Option
     result
 }
 
-/// If this IRFold has a filter on the folded element count, and that filter imposes
-/// a min size that can be statically determined, return that min size so it can
-/// be used for further optimizations. Otherwise, return None.
-fn get_min_fold_count_limit(carrier: &mut QueryCarrier, fold: &IRFold) -> Option<usize> {
-    let mut result: Option<usize> = None;
+/// How many elements of each instance of this fold can affect the query's results,
+/// if that number is limited. The interpreter doesn't compute any elements past that number.
+pub(super) fn get_fold_element_limit(query: &InterpretedQuery, fold: &IRFold) -> Option<usize> {
+    if query.indexed_query.count_only_folds.contains(&fold.eid) {
+        FoldCountFilters::new(fold, &query.arguments).element_limit()
+    } else {
+        // One more element than the max size is needed to know the max size is exceeded.
+        get_max_fold_count_limit(query, fold).map(|max_fold_size| max_fold_size.saturating_add(1))
+    }
+}
 
-    let query_arguments = &carrier.query.as_ref().expect("query was not returned").arguments;
-    for post_fold_filter in fold.post_filters.iter() {
-        let next_limit = match post_fold_filter {
-            Operation::GreaterThanOrEqual(
-                FoldSpecificFieldKind::Count,
-                Argument::Variable(var_ref),
-            ) => {
-                let variable_value =
-                    usize_from_field_value(&query_arguments[&var_ref.variable_name])
-                        .expect("for field value to be coercible to usize");
-                Some(variable_value)
-            }
-            Operation::GreaterThan(FoldSpecificFieldKind::Count, Argument::Variable(var_ref)) => {
-                let variable_value =
-                    usize_from_field_value(&query_arguments[&var_ref.variable_name])
-                        .expect("for field value to be coercible to usize");
-                Some(variable_value.saturating_add(1))
-            }
-            // If we don't know how many elements of the fold are required
-            // to satisfy this filter, fail early.
-            _ => return None,
-        };
+/// The filters on the element count of a fold whose contents are not otherwise observed,
+/// with their arguments resolved ahead of time wherever possible.
+///
+/// Knowing which elements are in such a fold is unnecessary, so the fold only needs to be
+/// expanded until the outcome of these filters is certain.
+struct FoldCountFilters {
+    /// Arguments that come from tagged values are `None`, since they can differ between folds.
+    filters: Vec<Operation<(), Option<FieldValue>>>,
+}
 
-        match (result, next_limit) {
-            (None, _) => result = next_limit,
-            (Some(l), Some(r)) if l < r => result = next_limit,
-            _ => {}
+impl FoldCountFilters {
+    fn new(fold: &IRFold, query_arguments: &BTreeMap<Arc<str>, FieldValue>) -> Self {
+        let mut filters: Vec<_> = fold
+            .post_filters
+            .iter()
+            .map(|filter| {
+                filter.map(
+                    |_| (),
+                    |argument| argument.evaluate_statically(query_arguments).map(Cow::into_owned),
+                )
+            })
+            .collect();
+        if fold.absent {
+            filters.push(Operation::Equals((), Some(FieldValue::Uint64(0))));
         }
+
+        Self { filters }
     }
 
-    result
+    /// The number of elements after which the outcome of the filters is certain, if any.
+    fn element_limit(&self) -> Option<usize> {
+        // The outcome of a filter can only become certain once the count reaches
+        // or exceeds one of the filter's arguments, so only those counts need checking.
+        let mut candidates: Vec<usize> = self
+            .filters
+            .iter()
+            .flat_map(|filter| filter.arguments().filter_map(Option::as_ref))
+            .flat_map(|value| value.as_slice().unwrap_or(std::slice::from_ref(value)))
+            .filter_map(|value| {
+                value.as_i64().map(i128::from).or_else(|| value.as_u64().map(i128::from))
+            })
+            .flat_map(|bound| [bound, bound + 1])
+            .filter_map(|count| usize::try_from(count.max(0)).ok())
+            .collect();
+        candidates.push(0);
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates.into_iter().find(|count| self.outcome(*count).is_some())
+    }
+
+    /// Whether a fold with at least `count` elements is certain to pass all the filters,
+    /// or certain to fail at least one. `None` if that depends on the fold's remaining elements.
+    fn outcome(&self, count: usize) -> Option<bool> {
+        let mut all_certain_to_pass = true;
+        for filter in &self.filters {
+            match fold_count_filter_outcome(filter, count) {
+                Some(false) => return Some(false),
+                Some(true) => {}
+                None => all_certain_to_pass = false,
+            }
+        }
+
+        all_certain_to_pass.then_some(true)
+    }
+}
+
+/// Whether a fold with at least `count` elements is certain to pass this filter on its count,
+/// or certain to fail it. `None` if that depends on the fold's remaining elements,
+/// or if the filter's argument isn't known ahead of time.
+fn fold_count_filter_outcome(
+    filter: &Operation<(), Option<FieldValue>>,
+    count: usize,
+) -> Option<bool> {
+    let count = count as i128;
+    let bound = |value: &FieldValue| {
+        value.as_i64().map(i128::from).or_else(|| value.as_u64().map(i128::from))
+    };
+    // The largest count in the list, or `None` if no count can be in the list.
+    let max_bound =
+        |value: &FieldValue| value.as_slice().map(|values| values.iter().filter_map(bound).max());
+
+    match filter {
+        Operation::Equals(_, Some(value)) | Operation::LessThanOrEqual(_, Some(value)) => {
+            (count > bound(value)?).then_some(false)
+        }
+        Operation::LessThan(_, Some(value)) => (count >= bound(value)?).then_some(false),
+        Operation::NotEquals(_, Some(value)) | Operation::GreaterThan(_, Some(value)) => {
+            (count > bound(value)?).then_some(true)
+        }
+        Operation::GreaterThanOrEqual(_, Some(value)) => (count >= bound(value)?).then_some(true),
+        Operation::Between(_, _, Some(upper)) => (count > bound(upper)?).then_some(false),
+        Operation::NotBetween(_, _, Some(upper)) => (count > bound(upper)?).then_some(true),
+        Operation::OneOf(_, Some(value)) => match max_bound(value)? {
+            Some(max) => (count > max).then_some(false),
+            None => Some(false),
+        },
+        Operation::NotOneOf(_, Some(value)) => match max_bound(value)? {
+            Some(max) => (count > max).then_some(true),
+            None => Some(true),
+        },
+        _ => None,
+    }
 }
 
 fn collect_fold_elements<'query, Vertex: Clone + Debug + 'query>(
     mut iterator: ContextIterator<'query, Vertex>,
     max_fold_count_limit: &Option<usize>,
-    min_fold_count_limit: &Option<usize>,
+    count_filters: Option<&FoldCountFilters>,
 ) -> Option<Vec<DataContext<Vertex>>> {
-    // If we must collect the fold up to our upperbound of `max_fold_count_limit`,
-    // then we won't use our lowerbound of `min_fold_count_limit`, as by definition
-    // the upperbound will be larger than the lowerbound.
-    if let Some(max_fold_count_limit) = max_fold_count_limit {
+    if let Some(count_filters) = count_filters {
+        // Only the number of elements in this fold matters, and only to its filters.
+        // Stop pulling elements from `iterator` as soon as the filters' outcome is certain:
+        // if they are certain to pass, the elements collected so far also pass them.
+        let mut fold_elements = vec![];
+        loop {
+            match count_filters.outcome(fold_elements.len()) {
+                Some(true) => break,
+                Some(false) => return None,
+                None => match iterator.next() {
+                    Some(element) => fold_elements.push(element),
+                    None => break,
+                },
+            }
+        }
+
+        Some(fold_elements)
+    } else if let Some(max_fold_count_limit) = max_fold_count_limit {
         // If this fold has more than `max_fold_count_limit` elements,
         // it will get filtered out by a post-fold filter.
         // Pulling elements from `iterator` causes computations and data fetches to happen,
@@ -398,16 +902,9 @@ fn collect_fold_elements<'query, Vertex: Clone + Debug + 'query>(
 
         Some(fold_elements)
     } else {
-        let collected = match min_fold_count_limit {
-            // Some queries may be able to be optimized by only partially expanding the fold,
-            // just enough to check any filters that may be applied to the fold count.
-            Some(min_fold_count_limit) => iterator.take(*min_fold_count_limit).collect(),
-            // We weren't able to find any early-termination condition for materializing the fold,
-            // so materialize the whole thing and return it.
-            _ => iterator.collect(),
-        };
-
-        Some(collected)
+        // We weren't able to find any early-termination condition for materializing the fold,
+        // so materialize the whole thing and return it.
+        Some(iterator.collect())
     }
 }
 
@@ -425,8 +922,16 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
         match &imported_field {
             FieldRef::ContextField(field) => {
                 let vertex_id = field.vertex_id;
+                let operands_iterator = push_transform_operands(
+                    adapter.as_ref(),
+                    carrier,
+                    parent_component,
+                    None,
+                    &field.transforms,
+                    iterator,
+                );
                 let activated_vertex_iterator: ContextIterator<'query, AdapterT::Vertex> =
-                    Box::new(iterator.map(move |x| x.activate_vertex(&vertex_id)));
+                    Box::new(operands_iterator.map(move |x| x.activate_vertex(&vertex_id)));
 
                 let field_vertex = &parent_component.vertices[&field.vertex_id];
                 let type_name = &field_vertex.type_name;
@@ -443,11 +948,13 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
                 carrier.query = Some(resolve_info.into_inner());
 
                 let cloned_field = imported_field.clone();
+                let transforms = field.transforms.clone();
                 iterator = Box::new(context_and_value_iterator.map(move |(mut context, value)| {
                     // Check whether the tagged value is coming from an `@optional` scope
                     // that did not exist, in order to satisfy its filtering semantics.
+                    let operands = pop_transform_operands(&mut context, &transforms);
                     let tag_value = if context.vertices[&vertex_id].is_some() {
-                        TaggedValue::Some(value)
+                        TaggedValue::Some(transform_value(value, &transforms, &operands))
                     } else {
                         TaggedValue::NonexistentOptional
                     };
@@ -460,6 +967,9 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
                 let fold_eid = fold_specific_field.fold_eid;
                 iterator = Box::new(
                     compute_fold_specific_field_with_separate_value(
+                        adapter.as_ref(),
+                        carrier,
+                        parent_component,
                         fold_specific_field.fold_eid,
                         &fold_specific_field.kind,
                         iterator,
@@ -479,17 +989,18 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
         Box::new(iterator.map(move |x| x.activate_vertex(&expanding_from_vid)));
     let type_name = &expanding_from.type_name;
 
-    let query = carrier.query.take().expect("query was not returned");
-    let resolve_info = ResolveEdgeInfo::new(query, expanding_from_vid, fold.to_vid, fold.eid);
-
-    let edge_iterator = adapter.resolve_neighbors(
+    let edge_iterator = resolve_neighbors_with_parameters(
+        &adapter,
+        carrier,
+        parent_component,
         activated_vertex_iterator,
         type_name,
         &fold.edge_name,
         &fold.parameters,
-        &resolve_info,
+        expanding_from_vid,
+        fold.to_vid,
+        fold.eid,
     );
-    carrier.query = Some(resolve_info.into_inner());
 
     // Materialize the full fold data.
     // These values are moved into the closure.
@@ -497,41 +1008,23 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
     let mut cloned_carrier = carrier.clone();
     let fold_component = fold.component.clone();
     let fold_eid = fold.eid;
-    let max_fold_size = get_max_fold_count_limit(carrier, fold.as_ref());
+    let query = carrier.query.as_ref().expect("query was not returned");
+    let max_fold_size = get_max_fold_count_limit(query, fold.as_ref());
 
-    // Queries that do not observe the fold count nor any fold contents may be able to
-    // be optimized by only partially expanding the fold, just enough to check any filters
-    // that may be applied to the fold count.
+    // Queries that observe a fold only through filters on its count can be optimized
+    // by only partially expanding the fold, just enough to know the outcome of those filters.
     //
     // For example, if `@filter(op: ">", value: ["$ten"])` is our only filter on the count
     // of the fold, we can stop computing the rest of the fold after seeing we have 11 elements.
-    let min_fold_size =
-        if let Some(min_fold_size) = get_min_fold_count_limit(carrier, fold.as_ref()) {
-            let no_outputs_in_fold = fold.component.outputs.is_empty();
-            let has_output_on_fold_count =
-                fold.fold_specific_outputs.values().any(|x| *x == FoldSpecificFieldKind::Count);
-            let has_tag_on_fold_count = parent_component.vertices.values().any(|vertex| {
-                vertex.filters.iter().any(|filter| {
-                    let Some(Argument::Tag(FieldRef::FoldSpecificField(tagged_fold_count))) =
-                        filter.right()
-                    else {
-                        return false;
-                    };
-
-                    tagged_fold_count.fold_root_vid == fold.to_vid
-                        && tagged_fold_count.fold_eid == fold.eid
-                        && tagged_fold_count.kind == FoldSpecificFieldKind::Count
-                })
-            });
-
-            if no_outputs_in_fold && !has_output_on_fold_count && !has_tag_on_fold_count {
-                Some(min_fold_size)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+    //
+    // Aggregations over folded property values observe the fold contents,
+    // so they also require the full fold.
+    let aggregated_fields = get_aggregated_fields(parent_component, fold.as_ref());
+    let count_filters = query
+        .indexed_query
+        .count_only_folds
+        .contains(&fold.eid)
+        .then(|| FoldCountFilters::new(fold.as_ref(), &query.arguments));
 
     let moved_fold = fold.clone();
     let folded_iterator = edge_iterator.filter_map(move |(mut context, neighbors)| {
@@ -556,11 +1049,53 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
         let fold_elements = if fold_exists {
             // N.B.: Note the `?` at the end here!
             //       This lets us early-discard folds that failed a post-processing filter.
-            Some(collect_fold_elements(computed_iterator, &max_fold_size, &min_fold_size)?)
+            Some(collect_fold_elements(computed_iterator, &max_fold_size, count_filters.as_ref())?)
         } else {
             None
         };
 
+        // Resolve the values of any folded properties that are aggregated,
+        // e.g. by `@transform(op: "sum")`.
+        if let Some(elements) = fold_elements.as_ref() {
+            for aggregated_field in &aggregated_fields {
+                let vertex_id = aggregated_field.vertex_id;
+                let operands_iterator = push_transform_operands(
+                    cloned_adapter.as_ref(),
+                    &mut cloned_carrier,
+                    &fold_component,
+                    None,
+                    &aggregated_field.transforms,
+                    Box::new(elements.clone().into_iter()),
+                );
+                let moved_iterator = Box::new(operands_iterator.map(move |context| {
+                    let new_vertex = context.vertices[&vertex_id].clone();
+                    context.move_to_vertex(new_vertex)
+                }));
+
+                let query = cloned_carrier.query.take().expect("query was not returned");
+                let resolve_info = ResolveInfo::new(query, vertex_id, true);
+                let values = cloned_adapter
+                    .resolve_property(
+                        moved_iterator,
+                        &fold_component.vertices[&vertex_id].type_name,
+                        &aggregated_field.field_name,
+                        &resolve_info,
+                    )
+                    .map(|(mut context, value)| {
+                        let transforms = &aggregated_field.transforms;
+                        let operands = pop_transform_operands(&mut context, transforms);
+                        transform_value(value, transforms, &operands)
+                    })
+                    .collect();
+                cloned_carrier.query = Some(resolve_info.into_inner());
+
+                context
+                    .folded_property_values
+                    .insert_or_error((fold_eid, aggregated_field.clone().into()), values)
+                    .unwrap();
+            }
+        }
+
         context.folded_contexts.insert_or_error(fold_eid, fold_elements).unwrap();
 
         // Remove no-longer-needed imported tags.
@@ -590,6 +1125,33 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
     let mut output_names: Vec<Arc<str>> = fold.component.outputs.keys().cloned().collect();
     output_names.sort_unstable(); // to ensure deterministic resolve_property() ordering
 
+    // Compute the fold-specific outputs, like the fold's count.
+    // If the @fold is inside an @optional that doesn't exist, its fold-specific outputs
+    // are `null` rather than their usual values for empty folds, even when transformed.
+    for (output_name, fold_specific_field) in &fold.fold_specific_outputs {
+        let output_name = output_name.clone();
+        post_filtered_iterator = Box::new(
+            compute_fold_specific_field_with_separate_value(
+                adapter.as_ref(),
+                carrier,
+                parent_component,
+                fold_eid,
+                fold_specific_field,
+                post_filtered_iterator,
+            )
+            .map(move |(mut ctx, tagged_value)| {
+                let value = match tagged_value {
+                    TaggedValue::Some(value) => Some(ValueOrVec::Value(value)),
+                    TaggedValue::NonexistentOptional => None,
+                };
+                ctx.folded_values
+                    .insert_or_error((fold_eid, output_name.clone()), value)
+                    .expect("this fold output was already computed");
+                ctx
+            }),
+        );
+    }
+
     let cloned_adapter = adapter.clone();
     let mut cloned_carrier = carrier.clone();
     let fold_component = fold.component.clone();
@@ -603,21 +1165,6 @@ fn compute_fold<'query, AdapterT: Adapter<'query> + 'query>(
 mismatch on whether the fold below {expanding_from_vid:?} was inside an `@optional`: {ctx:?}",
         );
 
-        // Add any fold-specific field outputs to the context's folded values.
-        for (output_name, fold_specific_field) in &fold.fold_specific_outputs {
-            // If the @fold is inside an @optional that doesn't exist,
-            // its outputs should be `null` rather than empty lists (the usual for empty folds).
-            // Transformed outputs should also be `null` rather than their usual transformed defaults.
-            let value = fold_elements.as_ref().map(|elements| match fold_specific_field {
-                FoldSpecificFieldKind::Count => {
-                    ValueOrVec::Value(FieldValue::Uint64(elements.len() as u64))
-                }
-            });
-            ctx.folded_values
-                .insert_or_error((fold_eid, output_name.clone()), value)
-                .expect("this fold output was already computed");
-        }
-
         // Prepare empty vectors for all the outputs from this @fold component.
         // If the fold-root vertex didn't exist, the default is `null` instead.
         let default_value =
@@ -646,35 +1193,18 @@ mismatch on whether the fold below {expanding_from_vid:?} was inside an `@option
             }
         } else {
             // Iterate through the elements of the fold and get the values we need.
-            let mut output_iterator: ContextIterator<'query, AdapterT::Vertex> = Box::new(
+            let elements_iterator: ContextIterator<'query, AdapterT::Vertex> = Box::new(
                 fold_elements.as_ref().expect("fold did not contain elements").clone().into_iter(),
             );
-            for output_name in output_names.iter() {
-                // This is a slimmed-down version of computing a context field:
-                // - it does not restore the prior active vertex after getting each value
-                // - it already knows that the context field is guaranteed to exist
-                let context_field = &fold.component.outputs[output_name.as_ref()];
-                let vertex_id = context_field.vertex_id;
-                let moved_iterator = Box::new(output_iterator.map(move |context| {
-                    let new_vertex = context.vertices[&vertex_id].clone();
-                    context.move_to_vertex(new_vertex)
-                }));
-
-                let query = cloned_carrier.query.take().expect("query was not returned");
-                let resolve_info = ResolveInfo::new(query, vertex_id, true);
-                let field_data_iterator = cloned_adapter.resolve_property(
-                    moved_iterator,
-                    &fold.component.vertices[&vertex_id].type_name,
-                    &context_field.field_name,
-                    &resolve_info,
-                );
-                cloned_carrier.query = Some(resolve_info.into_inner());
-
-                output_iterator = Box::new(field_data_iterator.map(|(mut context, value)| {
-                    context.values.push(value);
-                    context
-                }));
-            }
+            let output_fields: Vec<_> =
+                output_names.iter().map(|name| &fold_component.outputs[name]).collect();
+            let output_iterator = resolve_fields(
+                cloned_adapter.as_ref(),
+                &mut cloned_carrier,
+                &fold_component,
+                &output_fields,
+                elements_iterator,
+            );
 
             for mut folded_context in output_iterator {
                 for (key, value) in folded_context.folded_values {
@@ -750,7 +1280,7 @@ fn apply_fold_specific_filter<'query, AdapterT: Adapter<'query>>(
     iterator: ContextIterator<'query, AdapterT::Vertex>,
 ) -> ContextIterator<'query, AdapterT::Vertex> {
     let fold_specific_field = filter.left();
-    let field_iterator = Box::new(compute_fold_specific_field_with_separate_value(fold.eid, fold_specific_field, iterator).map(|(mut ctx, tagged_value)| {
+    let field_iterator = Box::new(compute_fold_specific_field_with_separate_value(adapter, carrier, component, fold.eid, fold_specific_field, iterator).map(|(mut ctx, tagged_value)| {
         let value = match tagged_value {
             TaggedValue::Some(value) => value,
             TaggedValue::NonexistentOptional => {
@@ -785,6 +1315,14 @@ pub(super) fn compute_context_field_with_separate_value<
     let vertex_id = context_field.vertex_id;
 
     if let Some(vertex) = component.vertices.get(&vertex_id) {
+        let iterator = push_transform_operands(
+            adapter,
+            carrier,
+            component,
+            None,
+            &context_field.transforms,
+            iterator,
+        );
         let moved_iterator = iterator.map(move |mut context| {
             let active_vertex = context.active_vertex.clone();
             let new_vertex = context.vertices[&vertex_id].clone();
@@ -796,6 +1334,7 @@ pub(super) fn compute_context_field_with_separate_value<
         let query = carrier.query.take().expect("query was not returned");
         let resolve_info = ResolveInfo::new(query, vertex_id, true);
 
+        let transforms = context_field.transforms.clone();
         let context_and_value_iterator = adapter
             .resolve_property(
                 Box::new(moved_iterator),
@@ -804,8 +1343,9 @@ pub(super) fn compute_context_field_with_separate_value<
                 &resolve_info,
             )
             .map(move |(mut context, value)| {
+                let operands = pop_transform_operands(&mut context, &transforms);
                 let tagged_value = if context.vertices[&vertex_id].is_some() {
-                    TaggedValue::Some(value)
+                    TaggedValue::Some(transform_value(value, &transforms, &operands))
                 } else {
                     // The value is coming from an @optional scope that didn't exist.
                     TaggedValue::NonexistentOptional
@@ -830,35 +1370,192 @@ pub(super) fn compute_context_field_with_separate_value<
     }
 }
 
+/// Compute the value of a fold-specific field, like the fold's count, for each context.
+///
+/// The fold's elements must already be recorded in each context.
 pub(super) fn compute_fold_specific_field_with_separate_value<
     'query,
-    Vertex: Clone + Debug + 'query,
+    AdapterT: Adapter<'query>,
+    V: AsVertex<AdapterT::Vertex> + 'query,
 >(
-    fold_eid: Eid,
-    fold_specific_field: &FoldSpecificFieldKind,
-    iterator: ContextIterator<'query, Vertex>,
-) -> ContextOutcomeIterator<'query, Vertex, TaggedValue> {
-    match fold_specific_field {
-        FoldSpecificFieldKind::Count => Box::new(iterator.map(move |ctx| {
-            // TODO: Ensure output type inference handles this correctly too...
-            let folded_contexts = ctx.folded_contexts[&fold_eid].as_ref();
-            let value = match folded_contexts {
-                None => TaggedValue::NonexistentOptional,
-                Some(v) => TaggedValue::Some(FieldValue::Uint64(v.len() as u64)),
-            };
-            (ctx, value)
-        })),
-    }
-}
-
-pub(super) fn compute_local_field_with_separate_value<'query, AdapterT: Adapter<'query>>(
     adapter: &AdapterT,
     carrier: &mut QueryCarrier,
     component: &IRQueryComponent,
-    current_vid: Vid,
+    fold_eid: Eid,
+    fold_specific_field: &FoldSpecificFieldKind,
+    iterator: ContextIterator<'query, V>,
+) -> ContextOutcomeIterator<'query, V, TaggedValue> {
+    let transforms = fold_specific_field.value_transforms().to_vec();
+    let iterator =
+        push_transform_operands(adapter, carrier, component, None, &transforms, iterator);
+
+    let kind = fold_specific_field.untransformed().clone();
+    Box::new(iterator.map(move |mut ctx| {
+        let operands = pop_transform_operands(&mut ctx, &transforms);
+        let value = match ctx.folded_contexts[&fold_eid].as_ref() {
+            None => TaggedValue::NonexistentOptional,
+            Some(elements) => {
+                let value = match &kind {
+                    FoldSpecificFieldKind::Count => FieldValue::Uint64(elements.len() as u64),
+                    kind => aggregate_folded_values(kind, &ctx.folded_property_values, fold_eid),
+                };
+                TaggedValue::Some(transform_value(value, &transforms, &operands))
+            }
+        };
+        (ctx, value)
+    }))
+}
+
+/// Find the folded properties whose values are aggregated, e.g. by `@transform(op: "sum")`.
+///
+/// Aggregated values may be output or filtered on the fold itself, or tagged and then used
+/// elsewhere in the fold's parent component, including within its other folds
+/// and as the operands of value transforms.
+pub(super) fn get_aggregated_fields(
+    parent_component: &IRQueryComponent,
+    fold: &IRFold,
+) -> Vec<ContextField> {
+    let transforms = parent_component
+        .vertices
+        .values()
+        .flat_map(|vertex| vertex.filters.iter().map(|filter| &filter.left().transforms))
+        .chain(parent_component.outputs.values().map(|output| &output.transforms))
+        .map(Vec::as_slice)
+        .chain(parent_component.folds.values().flat_map(|other_fold| {
+            other_fold
+                .fold_specific_outputs
+                .values()
+                .chain(other_fold.post_filters.iter().map(|filter| filter.left()))
+                .map(|kind| kind.value_transforms())
+        }));
+    let tagged_kinds = parent_component
+        .vertices
+        .values()
+        .flat_map(|vertex| vertex.filters.iter().flat_map(|filter| filter.arguments()))
+        .chain(parent_component.folds.values().flat_map(|other_fold| {
+            other_fold.post_filters.iter().flat_map(|filter| filter.arguments())
+        }))
+        .chain(transforms.flatten().filter_map(|transform| transform.operand()))
+        .filter_map(|argument| match argument {
+            Argument::Tag(FieldRef::FoldSpecificField(field)) => Some(field),
+            _ => None,
+        })
+        .chain(parent_component.folds.values().flat_map(|other_fold| {
+            other_fold.imported_tags.iter().filter_map(|field_ref| match field_ref {
+                FieldRef::FoldSpecificField(field) => Some(field),
+                FieldRef::ContextField(_) => None,
+            })
+        }))
+        .filter(|field| field.fold_eid == fold.eid)
+        .map(|field| &field.kind);
+
+    let mut aggregated_fields: Vec<ContextField> = fold
+        .fold_specific_outputs
+        .values()
+        .chain(fold.post_filters.iter().map(|filter| filter.left()))
+        .chain(tagged_kinds)
+        .filter_map(|kind| kind.aggregated_field())
+        .cloned()
+        .collect();
+    aggregated_fields.sort_unstable_by(|a, b| {
+        (a.vertex_id, &a.field_name, &a.transforms).cmp(&(
+            b.vertex_id,
+            &b.field_name,
+            &b.transforms,
+        ))
+    });
+    aggregated_fields.dedup();
+    aggregated_fields
+}
+
+/// Compute the value of an aggregation over folded property values.
+///
+/// `null` values are ignored. Sums of no values are zero,
+/// while the other aggregations of no values are `null`.
+fn aggregate_folded_values(
+    kind: &FoldSpecificFieldKind,
+    folded_property_values: &BTreeMap<(Eid, FieldRef), Vec<FieldValue>>,
+    fold_eid: Eid,
+) -> FieldValue {
+    let field = kind.aggregated_field().expect("not an aggregation");
+    let values = folded_property_values[&(fold_eid, field.clone().into())]
+        .iter()
+        .filter(|value| !matches!(value, FieldValue::Null));
+    let is_int = field.field_type.base_type() == "Int";
+
+    match kind {
+        FoldSpecificFieldKind::Sum(_) if is_int => {
+            let sum: i128 = values.map(integer_value).sum();
+            if let Ok(sum) = i64::try_from(sum) {
+                FieldValue::Int64(sum)
+            } else {
+                FieldValue::Uint64(u64::try_from(sum).expect("sum of folded values overflowed"))
+            }
+        }
+        FoldSpecificFieldKind::Sum(_) => FieldValue::Float64(values.map(float_value).sum()),
+        FoldSpecificFieldKind::Min(_) => values
+            .min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .cloned()
+            .unwrap_or(FieldValue::Null),
+        FoldSpecificFieldKind::Max(_) => values
+            .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .cloned()
+            .unwrap_or(FieldValue::Null),
+        FoldSpecificFieldKind::Avg(_) => {
+            let (count, sum) = values.fold((0usize, 0.0f64), |(count, sum), value| {
+                let value = if is_int { integer_value(value) as f64 } else { float_value(value) };
+                (count + 1, sum + value)
+            });
+            if count == 0 {
+                FieldValue::Null
+            } else {
+                FieldValue::Float64(sum / count as f64)
+            }
+        }
+        FoldSpecificFieldKind::Count | FoldSpecificFieldKind::Transformed { .. } => {
+            unreachable!("not an aggregation: {kind:?}")
+        }
+    }
+}
+
+fn integer_value(value: &FieldValue) -> i128 {
+    match value {
+        FieldValue::Int64(i) => i128::from(*i),
+        FieldValue::Uint64(u) => i128::from(*u),
+        _ => unreachable!("expected an integer value: {value:?}"),
+    }
+}
+
+fn float_value(value: &FieldValue) -> f64 {
+    match value {
+        FieldValue::Float64(f) => *f,
+        FieldValue::Int64(i) => *i as f64,
+        FieldValue::Uint64(u) => *u as f64,
+        _ => unreachable!("expected a numeric value: {value:?}"),
+    }
+}
+
+pub(super) fn compute_local_field_with_separate_value<
+    'query,
+    AdapterT: Adapter<'query>,
+    V: AsVertex<AdapterT::Vertex> + 'query,
+>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    current_vid: Vid,
     local_field: &LocalField,
-    iterator: ContextIterator<'query, AdapterT::Vertex>,
-) -> ContextOutcomeIterator<'query, AdapterT::Vertex, FieldValue> {
+    iterator: ContextIterator<'query, V>,
+) -> ContextOutcomeIterator<'query, V, FieldValue> {
+    let iterator = push_transform_operands(
+        adapter,
+        carrier,
+        component,
+        Some(current_vid),
+        &local_field.transforms,
+        iterator,
+    );
+
     let type_name = &component.vertices[&current_vid].type_name;
     let query = carrier.query.take().expect("query was not returned");
     let resolve_info = ResolveInfo::new(query, current_vid, true);
@@ -867,7 +1564,194 @@ pub(super) fn compute_local_field_with_separate_value<'query, AdapterT: Adapter<
         adapter.resolve_property(iterator, type_name, &local_field.field_name, &resolve_info);
     carrier.query = Some(resolve_info.into_inner());
 
-    context_and_value_iterator
+    if local_field.transforms.is_empty() {
+        context_and_value_iterator
+    } else {
+        let transforms = local_field.transforms.clone();
+        Box::new(context_and_value_iterator.map(move |(mut context, value)| {
+            let operands = pop_transform_operands(&mut context, &transforms);
+            (context, transform_value(value, &transforms, &operands))
+        }))
+    }
+}
+
+/// Push the values of the operands of the given transforms onto each context's values,
+/// such as the value of the variable in `@transform(op: "*", value: ["$factor"])`.
+///
+/// The operands are pushed in the order of their transforms, so that once the transformed
+/// property's value is known, they may be removed with [`pop_transform_operands()`].
+/// If `local_vid` is set, tags on that vertex are resolved on each context's active vertex,
+/// since that vertex may not have been recorded in the context yet.
+fn push_transform_operands<
+    'query,
+    AdapterT: Adapter<'query>,
+    V: AsVertex<AdapterT::Vertex> + 'query,
+>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    local_vid: Option<Vid>,
+    transforms: &[ValueTransform],
+    mut iterator: ContextIterator<'query, V>,
+) -> ContextIterator<'query, V> {
+    for operand in transforms.iter().filter_map(|transform| transform.operand()) {
+        let operand_iterator: ContextOutcomeIterator<'query, V, TaggedValue> = match operand {
+            Argument::Variable(var) => {
+                let query_arguments =
+                    &carrier.query.as_ref().expect("query was not returned").arguments;
+                let value = query_arguments[var.variable_name.as_ref()].clone();
+                Box::new(iterator.map(move |context| (context, TaggedValue::Some(value.clone()))))
+            }
+            Argument::Tag(FieldRef::ContextField(field)) if Some(field.vertex_id) == local_vid => {
+                let local_equivalent_field = LocalField {
+                    field_name: field.field_name.clone(),
+                    field_type: field.field_type.clone(),
+                    transforms: field.transforms.clone(),
+                };
+                Box::new(
+                    compute_local_field_with_separate_value(
+                        adapter,
+                        carrier,
+                        component,
+                        field.vertex_id,
+                        &local_equivalent_field,
+                        iterator,
+                    )
+                    .map(|(context, value)| (context, TaggedValue::Some(value))),
+                )
+            }
+            Argument::Tag(FieldRef::ContextField(field)) => {
+                compute_context_field_with_separate_value(
+                    adapter, carrier, component, field, iterator,
+                )
+            }
+            Argument::Tag(field_ref @ FieldRef::FoldSpecificField(fold_field)) => {
+                if component.folds.contains_key(&fold_field.fold_eid) {
+                    compute_fold_specific_field_with_separate_value(
+                        adapter,
+                        carrier,
+                        component,
+                        fold_field.fold_eid,
+                        &fold_field.kind,
+                        iterator,
+                    )
+                } else {
+                    // This value represents an imported tag value from an outer component.
+                    let cloned_ref = field_ref.clone();
+                    Box::new(iterator.map(move |context| {
+                        let value = context.imported_tags[&cloned_ref].clone();
+                        (context, value)
+                    }))
+                }
+            }
+        };
+
+        iterator = Box::new(operand_iterator.map(|(mut context, value)| {
+            // Tags from `@optional` scopes that don't exist make the transformed value `null`.
+            context.values.push(match value {
+                TaggedValue::Some(value) => value,
+                TaggedValue::NonexistentOptional => FieldValue::Null,
+            });
+            context
+        }));
+    }
+
+    iterator
+}
+
+/// Remove the values pushed by [`push_transform_operands()`] for the given transforms.
+fn pop_transform_operands<V>(
+    context: &mut DataContext<V>,
+    transforms: &[ValueTransform],
+) -> Vec<FieldValue> {
+    let operand_count = transforms.iter().filter(|t| t.operand().is_some()).count();
+    context.values.split_off(context.values.len() - operand_count)
+}
+
+/// Apply the given value transforms, in order, to a property's value.
+///
+/// The `operands` are the values of the transforms' operands, in order.
+/// `null` values remain `null` regardless of the transforms applied to them.
+fn transform_value(
+    value: FieldValue,
+    transforms: &[ValueTransform],
+    operands: &[FieldValue],
+) -> FieldValue {
+    let mut operands = operands.iter();
+    transforms.iter().fold(value, |value, transform| match (transform, value) {
+        (ValueTransform::Add(..), value) => {
+            arithmetic(value, operands.next(), i128::checked_add, |l, r| l + r)
+        }
+        (ValueTransform::Subtract(..), value) => {
+            arithmetic(value, operands.next(), i128::checked_sub, |l, r| l - r)
+        }
+        (ValueTransform::Multiply(..), value) => {
+            arithmetic(value, operands.next(), i128::checked_mul, |l, r| l * r)
+        }
+        (ValueTransform::Divide(..), value) => {
+            arithmetic(value, operands.next(), i128::checked_div, |l, r| l / r)
+        }
+        (_, FieldValue::Null) => FieldValue::Null,
+        (ValueTransform::Lowercase, FieldValue::String(s)) => {
+            FieldValue::String(s.to_lowercase().into())
+        }
+        (ValueTransform::Uppercase, FieldValue::String(s)) => {
+            FieldValue::String(s.to_uppercase().into())
+        }
+        (ValueTransform::Trim, FieldValue::String(s)) => FieldValue::String(s.trim().into()),
+        (ValueTransform::Len, FieldValue::String(s)) => FieldValue::Int64(s.chars().count() as i64),
+        (ValueTransform::Len, FieldValue::List(l)) => FieldValue::Int64(l.len() as i64),
+        (ValueTransform::Abs, FieldValue::Int64(i)) => match i.checked_abs() {
+            Some(abs) => FieldValue::Int64(abs),
+            None => FieldValue::Uint64(i.unsigned_abs()),
+        },
+        (ValueTransform::Abs, value @ FieldValue::Uint64(_)) => value,
+        (ValueTransform::Abs, FieldValue::Float64(f)) => FieldValue::Float64(f.abs()),
+        (transform, value) => {
+            unreachable!("unexpected value for transform {transform:?}: {value:?}")
+        }
+    })
+}
+
+/// Apply an arithmetic operation to a value and its operand.
+///
+/// Integers are combined with `int_op` and produce an integer, while any other numbers are
+/// combined with `float_op` and produce a float. The result is `null` if either input is `null`,
+/// or if the operation fails, such as due to overflow or division by zero.
+fn arithmetic(
+    value: FieldValue,
+    operand: Option<&FieldValue>,
+    int_op: impl Fn(i128, i128) -> Option<i128>,
+    float_op: impl Fn(f64, f64) -> f64,
+) -> FieldValue {
+    let operand = operand.expect("arithmetic transform operand was not computed");
+    let as_int = |value: &FieldValue| match value {
+        FieldValue::Int64(i) => Some(i128::from(*i)),
+        FieldValue::Uint64(u) => Some(i128::from(*u)),
+        _ => None,
+    };
+
+    match (&value, operand) {
+        (FieldValue::Null, _) | (_, FieldValue::Null) => FieldValue::Null,
+        _ => match (as_int(&value), as_int(operand)) {
+            (Some(left), Some(right)) => int_op(left, right)
+                .and_then(|result| {
+                    i64::try_from(result)
+                        .map(FieldValue::Int64)
+                        .or_else(|_| u64::try_from(result).map(FieldValue::Uint64))
+                        .ok()
+                })
+                .unwrap_or(FieldValue::Null),
+            _ => {
+                let result = float_op(float_value(&value), float_value(operand));
+                if result.is_finite() {
+                    FieldValue::Float64(result)
+                } else {
+                    FieldValue::Null
+                }
+            }
+        },
+    }
 }
 
 fn compute_local_field<'query, AdapterT: Adapter<'query>>(
@@ -962,7 +1846,7 @@ impl<'query, Vertex: Clone + Debug + 'query> Iterator for EdgeExpander<'query, V
 }
 
 fn expand_edge<'query, AdapterT: Adapter<'query> + 'query>(
-    adapter: &AdapterT,
+    adapter: &Arc<AdapterT>,
     carrier: &mut QueryCarrier,
     component: &IRQueryComponent,
     expanding_from_vid: Vid,
@@ -970,7 +1854,7 @@ fn expand_edge<'query, AdapterT: Adapter<'query> + 'query>(
     edge: &IREdge,
     iterator: ContextIterator<'query, AdapterT::Vertex>,
 ) -> ContextIterator<'query, AdapterT::Vertex> {
-    let expanded_iterator = if let Some(recursive) = &edge.recursive {
+    let mut expanded_iterator = if let Some(recursive) = &edge.recursive {
         expand_recursive_edge(
             adapter,
             carrier,
@@ -998,8 +1882,12 @@ fn expand_edge<'query, AdapterT: Adapter<'query> + 'query>(
         )
     };
 
+    for filter_expr in edge.filters.iter() {
+        expanded_iterator = apply_edge_filter(edge, filter_expr, expanded_iterator);
+    }
+
     perform_entry_into_new_vertex(
-        adapter,
+        adapter.as_ref(),
         carrier,
         component,
         &component.vertices[&expanding_to_vid],
@@ -1008,10 +1896,10 @@ fn expand_edge<'query, AdapterT: Adapter<'query> + 'query>(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn expand_non_recursive_edge<'query, AdapterT: Adapter<'query>>(
-    adapter: &AdapterT,
+fn expand_non_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: &Arc<AdapterT>,
     carrier: &mut QueryCarrier,
-    _component: &IRQueryComponent,
+    component: &IRQueryComponent,
     expanding_from: &IRVertex,
     expanding_to: &IRVertex,
     edge_id: Eid,
@@ -1022,26 +1910,247 @@ fn expand_non_recursive_edge<'query, AdapterT: Adapter<'query>>(
 ) -> ContextIterator<'query, AdapterT::Vertex> {
     let expanding_from_vid = expanding_from.vid;
     let expanding_vertex_iterator: ContextIterator<'query, AdapterT::Vertex> =
-        Box::new(iterator.map(move |x| x.activate_vertex(&expanding_from_vid)));
-
-    let type_name = &expanding_from.type_name;
-    let query = carrier.query.take().expect("query was not returned");
-    let resolve_info = ResolveEdgeInfo::new(query, expanding_from_vid, expanding_to.vid, edge_id);
+        Box::new(iterator.map(move |context| {
+            let mut context = context.activate_vertex(&expanding_from_vid);
+            if context.missing_optional_edges.remove(&edge_id) {
+                // Another edge in this edge's `@optional` group does not exist,
+                // so this edge must not exist either.
+                context = context.move_to_vertex(None);
+            }
+            context
+        }));
 
-    let edge_iterator = adapter.resolve_neighbors(
+    let edge_iterator = resolve_neighbors_with_parameters(
+        adapter,
+        carrier,
+        component,
         expanding_vertex_iterator,
-        type_name,
+        &expanding_from.type_name,
         edge_name,
         edge_parameters,
-        &resolve_info,
+        expanding_from_vid,
+        expanding_to.vid,
+        edge_id,
     );
-    carrier.query = Some(resolve_info.into_inner());
 
     Box::new(edge_iterator.flat_map(move |(context, neighbor_iterator)| {
         EdgeExpander::new(context, neighbor_iterator, is_optional)
     }))
 }
 
+/// Resolve the neighbors of each context's active vertex along the edge
+/// from `from_vid` to `to_vid` with ID `eid`.
+///
+/// Edge parameters whose values are tagged values may have a different value for each context.
+/// If the edge has such parameters, consecutive contexts with the same tagged values are
+/// grouped together, and each group's neighbors are resolved by a single adapter call
+/// with those parameters set to its values. Groups are formed lazily: the adapter pulls
+/// the group's contexts at its own pace, and each context is only computed once the adapter
+/// asks for it. The adapter's calls therefore interleave with the rest of the query's
+/// execution exactly as they would for an edge without tagged parameters.
+/// Tagged values from an `@optional` scope that doesn't exist are null. If a non-nullable
+/// parameter's tagged value is null, the context's vertex has no neighbors along the edge.
+#[allow(clippy::too_many_arguments)]
+fn resolve_neighbors_with_parameters<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: &Arc<AdapterT>,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    iterator: ContextIterator<'query, AdapterT::Vertex>,
+    type_name: &Arc<str>,
+    edge_name: &Arc<str>,
+    parameters: &EdgeParameters,
+    from_vid: Vid,
+    to_vid: Vid,
+    eid: Eid,
+) -> ContextOutcomeIterator<'query, AdapterT::Vertex, VertexIterator<'query, AdapterT::Vertex>> {
+    if parameters.tags.is_empty() {
+        let query = carrier.query.take().expect("query was not returned");
+        let resolve_info = ResolveEdgeInfo::new(query, from_vid, to_vid, eid);
+        let edge_iterator =
+            adapter.resolve_neighbors(iterator, type_name, edge_name, parameters, &resolve_info);
+        carrier.query = Some(resolve_info.into_inner());
+        return edge_iterator;
+    }
+
+    // Compute each context's tagged values, in the order of the parameters they are for.
+    let mut iterator = iterator;
+    for tag in parameters.tags.values() {
+        iterator = Box::new(
+            compute_tag_with_separate_value(adapter.as_ref(), carrier, component, tag, iterator)
+                .map(|(mut context, tagged_value)| {
+                    context.values.push(match tagged_value {
+                        TaggedValue::Some(value) => value,
+                        TaggedValue::NonexistentOptional => FieldValue::Null,
+                    });
+                    context
+                }),
+        );
+    }
+
+    let tag_count = parameters.tags.len();
+    let contexts = iterator
+        .map(move |mut context| {
+            let values = context.values.split_off(context.values.len() - tag_count);
+            (context, values)
+        })
+        .peekable();
+    let contexts = Rc::new(RefCell::new(contexts));
+
+    let adapter = adapter.clone();
+    let query = carrier.query.clone().expect("query was not returned");
+    let type_name = type_name.clone();
+    let edge_name = edge_name.clone();
+    let parameters = parameters.clone();
+    let groups = std::iter::from_fn(move || {
+        // The previous group's outcomes have all been produced, so the adapter has pulled
+        // all its contexts. The next context, if any, starts a new group.
+        let values = contexts.borrow_mut().peek()?.1.clone();
+        let group: ContextIterator<'query, AdapterT::Vertex> = {
+            let contexts = contexts.clone();
+            let values = values.clone();
+            Box::new(std::iter::from_fn(move || {
+                let mut contexts = contexts.borrow_mut();
+                contexts.next_if(|(_, next_values)| next_values == &values).map(|(ctx, _)| ctx)
+            }))
+        };
+
+        let missing_required_value = parameters.tags.keys().zip(&values).any(|(name, value)| {
+            value == &FieldValue::Null && parameters.required_tags.contains(name)
+        });
+        let outcomes: ContextOutcomeIterator<
+            'query,
+            AdapterT::Vertex,
+            VertexIterator<'query, AdapterT::Vertex>,
+        > = if missing_required_value {
+            Box::new(group.map(|context| {
+                let no_neighbors: VertexIterator<'query, AdapterT::Vertex> =
+                    Box::new(std::iter::empty());
+                (context, no_neighbors)
+            }))
+        } else {
+            let parameters = parameters.with_tag_values(values);
+            let resolve_info = ResolveEdgeInfo::new(query.clone(), from_vid, to_vid, eid);
+            adapter.resolve_neighbors(
+                group,
+                &type_name,
+                &edge_name,
+                &parameters,
+                &resolve_info,
+            )
+        };
+        Some(outcomes)
+    });
+
+    Box::new(groups.flatten())
+}
+
+/// Compute the value of a tag for each context, like the value of a `@filter` operand.
+fn compute_tag_with_separate_value<'query, AdapterT: Adapter<'query>>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    tag: &FieldRef,
+    iterator: ContextIterator<'query, AdapterT::Vertex>,
+) -> ContextOutcomeIterator<'query, AdapterT::Vertex, TaggedValue> {
+    match tag {
+        FieldRef::ContextField(field) => {
+            compute_context_field_with_separate_value(adapter, carrier, component, field, iterator)
+        }
+        FieldRef::FoldSpecificField(field) if component.folds.contains_key(&field.fold_eid) => {
+            compute_fold_specific_field_with_separate_value(
+                adapter,
+                carrier,
+                component,
+                field.fold_eid,
+                &field.kind,
+                iterator,
+            )
+        }
+        FieldRef::FoldSpecificField(_) => {
+            // This value represents an imported tag value from an outer component.
+            // Grab its value from the context itself.
+            let tag = tag.clone();
+            Box::new(iterator.map(move |context| {
+                let value = context.imported_tags[&tag].clone();
+                (context, value)
+            }))
+        }
+    }
+}
+
+/// Check whether every edge in an `@optional(group: "...")` exists.
+///
+/// If any of them doesn't, all the group's edges are recorded as missing in the context,
+/// so that expanding them later treats them as nonexistent.
+fn check_optional_group_existence<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: &Arc<AdapterT>,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    group: &[Eid],
+    mut iterator: ContextIterator<'query, AdapterT::Vertex>,
+) -> ContextIterator<'query, AdapterT::Vertex> {
+    for eid in group {
+        let edge = &component.edges[eid];
+        let edge_id = edge.eid;
+        let from_vid = edge.from_vid;
+        let expanding_vertex_iterator: ContextIterator<'query, AdapterT::Vertex> =
+            Box::new(iterator.map(move |context| {
+                let context = context.activate_vertex(&from_vid);
+                if context.missing_optional_edges.contains(&edge_id) {
+                    // We already know the group doesn't exist. No need to check this edge.
+                    context.move_to_vertex(None)
+                } else {
+                    context
+                }
+            }));
+
+        let edge_iterator = resolve_neighbors_with_parameters(
+            adapter,
+            carrier,
+            component,
+            expanding_vertex_iterator,
+            &component.vertices[&from_vid].type_name,
+            &edge.edge_name,
+            &edge.parameters,
+            from_vid,
+            edge.to_vid,
+            edge_id,
+        );
+
+        let group = group.to_vec();
+        iterator = Box::new(edge_iterator.map(move |(mut context, mut neighbors)| {
+            if context.active_vertex.is_some() && neighbors.next().is_none() {
+                context.missing_optional_edges.extend(group.iter().copied());
+            }
+            context
+        }));
+    }
+
+    iterator
+}
+
+/// Edge filters check whether the edge exists, i.e. whether its expansion produced a vertex.
+///
+/// Just like other filters, they are satisfied within an `@optional` scope that doesn't exist.
+/// This is the case when the edge's own origin vertex doesn't exist.
+fn apply_edge_filter<'query, Vertex: Clone + Debug + 'query>(
+    edge: &IREdge,
+    filter: &Operation<(), Argument>,
+    iterator: ContextIterator<'query, Vertex>,
+) -> ContextIterator<'query, Vertex> {
+    let from_vid = edge.from_vid;
+    let require_existence = match filter {
+        Operation::IsNull(..) => false,
+        Operation::IsNotNull(..) => true,
+        _ => unreachable!("unexpected edge filter operation: {filter:?}"),
+    };
+
+    Box::new(iterator.filter(move |context| {
+        let origin_exists = context.vertices[&from_vid].is_some();
+        !origin_exists || context.active_vertex.is_some() == require_existence
+    }))
+}
+
 /// Apply all the operations needed at entry into a new vertex:
 /// - coerce the type, if needed
 /// - apply all local filters
@@ -1067,7 +2176,7 @@ fn perform_entry_into_new_vertex<'query, AdapterT: Adapter<'query>>(
 
 #[allow(clippy::too_many_arguments)]
 fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
-    adapter: &AdapterT,
+    adapter: &Arc<AdapterT>,
     carrier: &mut QueryCarrier,
     component: &IRQueryComponent,
     expanding_from: &IRVertex,
@@ -1078,6 +2187,26 @@ fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
     recursive: &Recursive,
     iterator: ContextIterator<'query, AdapterT::Vertex>,
 ) -> ContextIterator<'query, AdapterT::Vertex> {
+    let identifier = recursive.dedupe.then(|| {
+        adapter
+            .vertex_identifier()
+            .expect("adapter without a vertex identifier was allowed to execute a dedupe query")
+    });
+    let Some(max_depth) = recursive.depth.filter(|_| identifier.is_none()) else {
+        return Box::new(UnboundedRecursionIterator::new(
+            adapter.clone(),
+            carrier.clone(),
+            expanding_from,
+            expanding_to,
+            edge_id,
+            edge_name,
+            edge_parameters,
+            recursive,
+            identifier,
+            iterator,
+        ));
+    };
+
     let expanding_from_vid = expanding_from.vid;
     let mut recursion_iterator: ContextIterator<'query, AdapterT::Vertex> =
         Box::new(iterator.map(move |mut context| {
@@ -1089,9 +2218,8 @@ fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
             context.activate_vertex(&expanding_from_vid)
         }));
 
-    let max_depth = usize::from(recursive.depth);
     recursion_iterator = perform_one_recursive_edge_expansion(
-        adapter,
+        adapter.as_ref(),
         carrier,
         component,
         &expanding_from.type_name,
@@ -1107,7 +2235,7 @@ fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
         expanding_to.coerced_from_type.as_ref().unwrap_or(&expanding_to.type_name);
     let recursing_from = recursive.coerce_to.as_ref().unwrap_or(edge_endpoint_type);
 
-    for _ in 2..=max_depth {
+    for _ in 2..=max_depth.get() {
         if let Some(coerce_to) = recursive.coerce_to.as_ref() {
             let query = carrier.query.take().expect("query was not returned");
             let resolve_info = ResolveInfo::new(query, expanding_from_vid, false);
@@ -1136,7 +2264,7 @@ fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
         }
 
         recursion_iterator = perform_one_recursive_edge_expansion(
-            adapter,
+            adapter.as_ref(),
             carrier,
             component,
             recursing_from,
@@ -1152,6 +2280,209 @@ fn expand_recursive_edge<'query, AdapterT: Adapter<'query> + 'query>(
     post_process_recursive_expansion(recursion_iterator)
 }
 
+/// The number of contexts whose neighbors are resolved together by unbounded recursion.
+const UNBOUNDED_RECURSION_BATCH_SIZE: usize = 64;
+
+/// Expands a recursive edge without a depth limit, until no more vertices are reachable.
+///
+/// Unlike recursion with a fixed depth, the number of expansions isn't known ahead of time,
+/// so they can't be set up as a fixed chain of iterators. Instead, the recursion from
+/// each starting context is expanded in turn: the vertices it discovers are taken in batches,
+/// deepest first, until none remain. Each batch's neighbors are resolved eagerly,
+/// and each context is produced once its neighbors have been discovered.
+///
+/// Recursion along a cycle in the data never ends. Such recursion may be stopped by
+/// a [`CycleDetectingAdapter`](super::CycleDetectingAdapter), by `@recurse(dedupe: true)`,
+/// or by execution limits.
+///
+/// Recursion with `dedupe` is expanded this way even if it has a depth limit, since it has
+/// to keep track of all the vertices each recursion has visited. A vertex first reached
+/// at the maximum depth is not expanded further, even if it is also reachable
+/// by a shorter path.
+struct UnboundedRecursionIterator<'query, AdapterT: Adapter<'query>> {
+    adapter: Arc<AdapterT>,
+    carrier: QueryCarrier,
+    expanding_from_vid: Vid,
+    expanding_to_vid: Vid,
+    edge_id: Eid,
+    edge_name: Arc<str>,
+    edge_parameters: EdgeParameters,
+    starting_type: Arc<str>,
+    edge_endpoint_type: Arc<str>,
+    coerce_to: Option<Arc<str>>,
+    max_depth: Option<usize>,
+    identifier: Option<VertexIdentifier<'query, AdapterT::Vertex>>,
+    #[allow(clippy::type_complexity)]
+    visit: Option<Box<dyn FnMut(&AdapterT::Vertex) -> bool + 'query>>,
+    upstream: ContextIterator<'query, AdapterT::Vertex>,
+
+    /// Contexts whose neighbors are yet to be discovered, with their depth in the recursion.
+    pending: Vec<(DataContext<AdapterT::Vertex>, usize)>,
+    ready: VecDeque<DataContext<AdapterT::Vertex>>,
+}
+
+impl<'query, AdapterT: Adapter<'query> + 'query> UnboundedRecursionIterator<'query, AdapterT> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        adapter: Arc<AdapterT>,
+        carrier: QueryCarrier,
+        expanding_from: &IRVertex,
+        expanding_to: &IRVertex,
+        edge_id: Eid,
+        edge_name: &Arc<str>,
+        edge_parameters: &EdgeParameters,
+        recursive: &Recursive,
+        identifier: Option<VertexIdentifier<'query, AdapterT::Vertex>>,
+        iterator: ContextIterator<'query, AdapterT::Vertex>,
+    ) -> Self {
+        let expanding_from_vid = expanding_from.vid;
+        let edge_endpoint_type =
+            expanding_to.coerced_from_type.as_ref().unwrap_or(&expanding_to.type_name).clone();
+        Self {
+            adapter,
+            carrier,
+            expanding_from_vid,
+            expanding_to_vid: expanding_to.vid,
+            edge_id,
+            edge_name: edge_name.clone(),
+            edge_parameters: edge_parameters.clone(),
+            starting_type: expanding_from.type_name.clone(),
+            edge_endpoint_type,
+            coerce_to: recursive.coerce_to.clone(),
+            max_depth: recursive.depth.map(NonZeroUsize::get),
+            identifier,
+            visit: None,
+            upstream: Box::new(
+                iterator.map(move |context| context.activate_vertex(&expanding_from_vid)),
+            ),
+            pending: Default::default(),
+            ready: Default::default(),
+        }
+    }
+
+    /// Coerce contexts found by prior expansions to the type from which recursion continues.
+    ///
+    /// Contexts that can't be coerced don't have the recursive edge. They are still produced,
+    /// but are not expanded any further.
+    fn coerce_batch(
+        &mut self,
+        batch: Vec<(DataContext<AdapterT::Vertex>, usize)>,
+    ) -> Vec<(DataContext<AdapterT::Vertex>, usize)> {
+        let Some(coerce_to) = &self.coerce_to else {
+            return batch;
+        };
+
+        let query = self.carrier.query.take().expect("query was not returned");
+        let resolve_info = ResolveInfo::new(query, self.expanding_from_vid, false);
+        let (contexts, depths): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+        let coercion_iter = self.adapter.resolve_coercion(
+            Box::new(contexts.into_iter()),
+            &self.edge_endpoint_type,
+            coerce_to,
+            &resolve_info,
+        );
+
+        let mut coerced = vec![];
+        for ((context, can_coerce), depth) in coercion_iter.zip(depths) {
+            if can_coerce {
+                coerced.push((context, depth));
+            } else {
+                self.ready.push_back(context);
+            }
+        }
+        self.carrier.query = Some(resolve_info.into_inner());
+
+        coerced
+    }
+
+    fn expand_batch(
+        &mut self,
+        batch: Vec<(DataContext<AdapterT::Vertex>, usize)>,
+        from_type: Arc<str>,
+    ) {
+        let query = self.carrier.query.take().expect("query was not returned");
+        let resolve_info = ResolveEdgeInfo::new(
+            query,
+            self.expanding_from_vid,
+            self.expanding_to_vid,
+            self.edge_id,
+        );
+        let (contexts, depths): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+        let edge_iterator = self.adapter.resolve_neighbors(
+            Box::new(contexts.into_iter()),
+            &from_type,
+            &self.edge_name,
+            &self.edge_parameters,
+            &resolve_info,
+        );
+
+        let mut discovered = vec![];
+        for ((context, neighbors), depth) in edge_iterator.zip(depths) {
+            for neighbor in neighbors {
+                // If this assertion trips, the adapter's resolve_neighbors() implementation
+                // illegally returned neighbors for a non-existent vertex.
+                assert!(context.active_vertex.is_some());
+                if self.visit.as_mut().is_some_and(|visit| !visit(&neighbor)) {
+                    // With `dedupe`, vertices this recursion already visited are omitted.
+                    continue;
+                }
+                discovered.push((context.split_and_move_to_vertex(Some(neighbor)), depth + 1));
+            }
+            self.ready.push_back(context);
+        }
+        self.carrier.query = Some(resolve_info.into_inner());
+
+        // Discovered vertices are pushed in reverse, so they are popped in their original order.
+        self.pending.extend(discovered.into_iter().rev());
+    }
+}
+
+impl<'query, AdapterT: Adapter<'query> + 'query> Iterator
+    for UnboundedRecursionIterator<'query, AdapterT>
+{
+    type Item = DataContext<AdapterT::Vertex>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(context) = self.ready.pop_front() {
+                return Some(context);
+            }
+
+            if !self.pending.is_empty() {
+                let batch_start = self.pending.len().saturating_sub(UNBOUNDED_RECURSION_BATCH_SIZE);
+                let mut batch = self.pending.split_off(batch_start);
+                batch.reverse();
+
+                // Vertices at the maximum depth are produced without expanding them further.
+                let max_depth = self.max_depth;
+                let (leaves, batch): (Vec<_>, Vec<_>) =
+                    batch.into_iter().partition(|(_, depth)| Some(*depth) == max_depth);
+                self.ready.extend(leaves.into_iter().map(|(context, _)| context));
+                if batch.is_empty() {
+                    continue;
+                }
+
+                let batch = self.coerce_batch(batch);
+                let recursing_from = self.coerce_to.as_ref().unwrap_or(&self.edge_endpoint_type);
+                self.expand_batch(batch, recursing_from.clone());
+            } else if let Some(context) = self.upstream.next() {
+                // Each recursion runs to completion before the next one starts,
+                // so that the results of each starting context are produced together.
+                if let Some(identifier) = &self.identifier {
+                    let mut visit = identifier.start_visiting();
+                    if let Some(vertex) = &context.active_vertex {
+                        visit(vertex);
+                    }
+                    self.visit = Some(visit);
+                }
+                self.expand_batch(vec![(context, 0)], self.starting_type.clone());
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn perform_one_recursive_edge_expansion<'query, AdapterT: Adapter<'query>>(
     adapter: &AdapterT,
@@ -1348,6 +2679,56 @@ mod tests {
         assert_eq!(check_parsed, constructed_test_item);
     }
 
+    mod parallel_execution {
+        use std::{
+            collections::BTreeMap,
+            fs,
+            path::{Path, PathBuf},
+            sync::Arc,
+        };
+
+        use trustfall_filetests_macros::parameterize;
+
+        use crate::{
+            interpreter::execution::interpret_ir_parallel,
+            ir::{FieldValue, IndexedQuery},
+            numbers_interpreter::NumbersAdapter,
+            test_types::{TestIRQueryResult, TestInterpreterOutputData},
+        };
+
+        #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+        fn parameterized_parallel_execution_tester(base: &Path, stem: &str) {
+            let mut input_path = PathBuf::from(base);
+            input_path.push(format!("{stem}.ir.ron"));
+
+            let input_data = fs::read_to_string(input_path).unwrap();
+            let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+            let test_query = test_query.unwrap();
+            if test_query.schema_name != "numbers" {
+                // The other test adapters are not thread-safe.
+                return;
+            }
+
+            let mut check_path = PathBuf::from(base);
+            check_path.push(format!("{stem}.output.ron"));
+            let check_data = fs::read_to_string(check_path).unwrap();
+            let expected_output_data: TestInterpreterOutputData =
+                ron::from_str(&check_data).unwrap();
+
+            let arguments: BTreeMap<Arc<str>, FieldValue> =
+                test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+            let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+            let results = interpret_ir_parallel(
+                Arc::new(NumbersAdapter::new()),
+                Arc::new(indexed_query),
+                Arc::new(arguments),
+            )
+            .unwrap();
+
+            assert_eq!(expected_output_data.results, results);
+        }
+    }
+
     mod batching_fuzzer_repro_cases {
         use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, sync::Arc};
 
@@ -1551,4 +2932,275 @@ mod tests {
             run_test(input_file, batch_sequences);
         }
     }
+    mod batched_property_resolution {
+        use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
+
+        use crate::{
+            frontend::parse,
+            interpreter::{
+                execution::interpret_ir, Adapter, AsVertex, ContextIterator,
+                ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo, ResolveInfo, VertexInfo,
+                VertexIterator,
+            },
+            ir::{EdgeParameters, FieldValue, Vid},
+            numbers_interpreter::NumbersAdapter,
+        };
+
+        /// Resolves each batch of properties with a single pass over its contexts,
+        /// and records the batches it was asked for.
+        struct BatchRecordingAdapter {
+            inner: NumbersAdapter,
+            batches: RefCell<Vec<(Vid, Vec<Arc<str>>)>>,
+        }
+
+        impl<'a> Adapter<'a> for BatchRecordingAdapter {
+            type Vertex = <NumbersAdapter as Adapter<'a>>::Vertex;
+
+            fn resolve_starting_vertices(
+                &self,
+                edge_name: &Arc<str>,
+                parameters: &EdgeParameters,
+                resolve_info: &ResolveInfo,
+            ) -> VertexIterator<'a, Self::Vertex> {
+                self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+            }
+
+            fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &Arc<str>,
+                property_name: &Arc<str>,
+                resolve_info: &ResolveInfo,
+            ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+                self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+            }
+
+            fn resolve_properties<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &Arc<str>,
+                properties: &[RequiredProperty],
+                resolve_info: &ResolveInfo,
+            ) -> ContextOutcomeIterator<'a, V, Vec<FieldValue>> {
+                self.batches.borrow_mut().push((
+                    resolve_info.vid(),
+                    properties.iter().map(|property| property.name.clone()).collect(),
+                ));
+
+                let contexts: Vec<_> = contexts.collect();
+                let mut rows: Vec<Vec<FieldValue>> = vec![vec![]; contexts.len()];
+                for property in properties {
+                    let values = self.inner.resolve_property(
+                        Box::new(contexts.clone().into_iter()),
+                        type_name,
+                        &property.name,
+                        resolve_info,
+                    );
+                    for (row, (_, value)) in rows.iter_mut().zip(values) {
+                        row.push(value);
+                    }
+                }
+
+                Box::new(contexts.into_iter().zip(rows))
+            }
+
+            fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &Arc<str>,
+                edge_name: &Arc<str>,
+                parameters: &EdgeParameters,
+                resolve_info: &ResolveEdgeInfo,
+            ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+                self.inner.resolve_neighbors(
+                    contexts,
+                    type_name,
+                    edge_name,
+                    parameters,
+                    resolve_info,
+                )
+            }
+
+            fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &Arc<str>,
+                coerce_to_type: &Arc<str>,
+                resolve_info: &ResolveInfo,
+            ) -> ContextOutcomeIterator<'a, V, bool> {
+                self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+            }
+        }
+
+        fn run_query(
+            adapter: Arc<impl Adapter<'static> + 'static>,
+            query: &str,
+        ) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+            let indexed_query = parse(NumbersAdapter::new().schema(), query).unwrap();
+            interpret_ir(adapter, indexed_query, Default::default()).unwrap().collect()
+        }
+
+        #[test]
+        fn outputs_of_the_same_vertex_are_resolved_together() {
+            let query = r#"
+{
+    Number(min: 1, max: 4) {
+        name @output
+        value @output
+        loud: name @output @transform(op: "uppercase")
+
+        successor {
+            next: value @output
+        }
+
+        multiple(max: 3) @fold {
+            multiple_name: name @output
+            multiple_value: value @output
+        }
+    }
+}"#;
+
+            #[allow(clippy::arc_with_non_send_sync)]
+            let adapter = Arc::new(BatchRecordingAdapter {
+                inner: NumbersAdapter::new(),
+                batches: Default::default(),
+            });
+            let batched_results = run_query(adapter.clone(), query);
+            let expected_results = run_query(Arc::new(NumbersAdapter::new()), query);
+            assert_eq!(expected_results, batched_results);
+
+            // The `name` property is only resolved once, even though two outputs use it.
+            // The `successor` vertex only needs one property, so it isn't batched.
+            // The number 1 has no multiples, so only the other three folds resolve properties.
+            let root = Vid::new(1.try_into().unwrap());
+            let multiple = Vid::new(3.try_into().unwrap());
+            let mut expected_batches = vec![(root, vec![Arc::from("name"), Arc::from("value")])];
+            expected_batches.extend(
+                std::iter::repeat((multiple, vec![Arc::from("name"), Arc::from("value")])).take(3),
+            );
+            assert_eq!(expected_batches, adapter.batches.take());
+        }
+    }
+
+    #[test]
+    fn contexts_with_tagged_edge_parameters_are_not_pulled_ahead() {
+        use std::sync::Mutex;
+
+        use crate::{
+            frontend::parse,
+            interpreter::{
+                execution::interpret_ir,
+                trace::{tap_results, AdapterTap, FunctionCall, Trace, TraceOpContent, YieldValue},
+            },
+            ir::EdgeKind,
+            numbers_interpreter::NumbersAdapter,
+        };
+
+        // All the `multiple` edge's contexts have the same tagged value, so their neighbors
+        // are resolved by a single adapter call.
+        let query = r#"
+{
+    One {
+        successor {
+            value @tag(name: "max")
+
+            successor @recurse(depth: 3) {
+                value @output
+
+                multiple(max: "%max") {
+                    multiple: value @output
+                }
+            }
+        }
+    }
+}"#;
+        let adapter = NumbersAdapter::new();
+        let indexed_query = parse(adapter.schema(), query).unwrap();
+        let multiple_eid = indexed_query
+            .eids
+            .iter()
+            .find_map(|(eid, edge)| match edge {
+                EdgeKind::Regular(edge) if edge.edge_name.as_ref() == "multiple" => Some(*eid),
+                _ => None,
+            })
+            .unwrap();
+
+        let tracer =
+            Arc::new(Mutex::new(Trace::new(indexed_query.ir_query.clone(), BTreeMap::new())));
+        let adapter_tap = Arc::new(AdapterTap::new(adapter, tracer.clone()));
+        let results = interpret_ir(adapter_tap.clone(), indexed_query, Default::default()).unwrap();
+        assert_eq!(5, tap_results(adapter_tap, results).count());
+
+        let trace = tracer.lock().unwrap();
+        let calls: Vec<_> = trace
+            .ops
+            .values()
+            .filter(|op| {
+                matches!(
+                    &op.content,
+                    TraceOpContent::Call(FunctionCall::ResolveNeighbors(_, _, eid))
+                        if *eid == multiple_eid
+                )
+            })
+            .map(|op| op.opid)
+            .collect();
+        assert_eq!(1, calls.len(), "{calls:?}");
+
+        // Each context is pulled into the adapter call only after the previous context's
+        // neighbors were produced and the query's results computed from them.
+        let steps: Vec<_> = trace
+            .ops
+            .values()
+            .filter_map(|op| match &op.content {
+                TraceOpContent::YieldInto(..) if op.parent_opid == Some(calls[0]) => Some("into"),
+                TraceOpContent::YieldFrom(YieldValue::ResolveNeighborsOuter(..))
+                    if op.parent_opid == Some(calls[0]) =>
+                {
+                    Some("from")
+                }
+                TraceOpContent::ProduceQueryResult(..) => Some("result"),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "into", "from", "result", // 2
+                "into", "from", "result", // 3
+                "into", "from", "result", "result", // 4
+                "into", "from", "result", // 5
+            ],
+            steps,
+        );
+    }
+
+    #[test]
+    fn fold_count_filters_are_decided_as_soon_as_possible() {
+        use super::FoldCountFilters;
+        use crate::ir::Operation;
+
+        let two = Some(FieldValue::Int64(2));
+        let filters =
+            |filters: Vec<Operation<(), Option<FieldValue>>>| FoldCountFilters { filters };
+
+        let at_least_two = filters(vec![Operation::GreaterThanOrEqual((), two.clone())]);
+        assert_eq!([None, None, Some(true)], [0, 1, 2].map(|k| at_least_two.outcome(k)));
+
+        let exactly_two = filters(vec![Operation::Equals((), two.clone())]);
+        assert_eq!([None, None, Some(false)], [0, 2, 3].map(|k| exactly_two.outcome(k)));
+
+        let not_two = filters(vec![Operation::NotEquals((), two.clone())]);
+        assert_eq!([None, Some(true)], [2, 3].map(|k| not_two.outcome(k)));
+
+        let not_one_of = filters(vec![Operation::NotOneOf((), Some(vec![0, 5].into()))]);
+        assert_eq!([None, Some(true)], [5, 6].map(|k| not_one_of.outcome(k)));
+
+        // Negative arguments are compared exactly, not clamped to zero.
+        let more_than_negative = filters(vec![Operation::GreaterThan((), Some((-1).into()))]);
+        assert_eq!(Some(true), more_than_negative.outcome(0));
+
+        // Filters whose arguments aren't known ahead of time are only decided
+        // if another filter already fails.
+        let tagged = filters(vec![Operation::LessThan((), None), Operation::LessThan((), two)]);
+        assert_eq!([None, Some(false)], [0, 2].map(|k| tagged.outcome(k)));
+    }
 }
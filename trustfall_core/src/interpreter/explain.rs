@@ -0,0 +1,536 @@
+//! Describe how the interpreter would execute a query, without executing it.
+//!
+//! The resulting [`QueryPlan`] lists the adapter calls the interpreter makes,
+//! in the order in which their results flow through the query.
+//! It is serializable, so it can be saved alongside slow queries for later inspection.
+use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ir::{
+    Argument, ContextField, EdgeParameters, Eid, FieldRef, FieldValue, FoldSpecificFieldKind,
+    IREdge, IRFold, IRQueryComponent, IRVertex, IndexedQuery, LocalField, Operation,
+    OutputOrdering, Vid,
+};
+
+use super::{error::QueryArgumentsError, execution::get_aggregated_fields, InterpretedQuery};
+
+/// A description of how the interpreter executes a query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryPlan {
+    /// The steps of the plan, in the order in which the query's data flows through them.
+    ///
+    /// Execution is lazy, so a step's adapter call only receives the data
+    /// that made it through all the steps before it.
+    pub steps: Vec<PlanStep>,
+
+    /// The values of the query's variables, which some filters use as arguments.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arguments: BTreeMap<Arc<str>, FieldValue>,
+
+    /// Outputs by which the query's results are sorted, from most to least significant.
+    ///
+    /// Sorting requires buffering all of the query's results.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order_by: Vec<OutputOrdering>,
+
+    /// The number of results to skip, applied after sorting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+
+    /// The maximum number of results to produce, applied after sorting and skipping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<NonZeroUsize>,
+
+    /// Whether duplicate results are discarded, before skipping and limiting.
+    ///
+    /// Discarding duplicates requires remembering all the distinct results produced so far.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dedupe: bool,
+}
+
+/// A single step of a [`QueryPlan`].
+///
+/// The `destination_filters` of steps that resolve vertices are the filters
+/// the interpreter applies to the newly-resolved vertices. They are visible to the adapter
+/// through the resolver call's hints, so the adapter may use them to avoid producing
+/// vertices that would be discarded anyway.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlanStep {
+    /// A `resolve_starting_vertices()` call that produces the query's root vertices.
+    ResolveStartingVertices {
+        vid: Vid,
+        edge_name: Arc<str>,
+
+        #[serde(default, skip_serializing_if = "EdgeParameters::is_empty")]
+        parameters: EdgeParameters,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        destination_filters: Vec<Operation<LocalField, Argument>>,
+    },
+
+    /// A `resolve_neighbors()` call that expands an edge.
+    ///
+    /// If any of the edge's parameters are tagged values, which are produced by
+    /// the `ResolveProperty` steps that precede this step, the call is made separately
+    /// for each vertex, with that vertex's tagged values.
+    ResolveNeighbors {
+        eid: Eid,
+        from_vid: Vid,
+        to_vid: Vid,
+        type_name: Arc<str>,
+        edge_name: Arc<str>,
+
+        #[serde(default, skip_serializing_if = "EdgeParameters::is_empty")]
+        parameters: EdgeParameters,
+
+        /// Whether the edge is `@optional`.
+        optional: bool,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        destination_filters: Vec<Operation<LocalField, Argument>>,
+    },
+
+    /// A `resolve_coercion()` call that discards vertices not of the given type.
+    ResolveCoercion { vid: Vid, from_type: Arc<str>, to_type: Arc<str> },
+
+    /// A `resolve_property()` call that produces a property's value for each vertex.
+    ResolveProperty { vid: Vid, type_name: Arc<str>, property: Arc<str> },
+
+    /// A `resolve_properties()` call that produces several properties' values for each vertex.
+    ResolveProperties { vid: Vid, type_name: Arc<str>, properties: Vec<Arc<str>> },
+
+    /// Discard vertices whose property values don't satisfy the filter.
+    ///
+    /// The property values, as well as any tagged values that are its arguments,
+    /// are produced by the `ResolveProperty` steps that precede this step.
+    FilterVertex { vid: Vid, filter: Operation<LocalField, Argument> },
+
+    /// Check whether all the edges in an `@optional(group: "...")` exist, by expanding each one.
+    ///
+    /// If any of them doesn't exist, the group's edges are all treated as nonexistent
+    /// when they are expanded in later steps.
+    CheckOptionalGroup { group: Arc<str>, checks: Vec<PlanStep> },
+
+    /// Discard results that don't satisfy a filter on the existence of an `@optional` edge.
+    FilterEdge { eid: Eid, filter: Operation<(), Argument> },
+
+    /// Expand a `@recurse` edge.
+    ///
+    /// Each expansion moves one level deeper into the recursion, starting from
+    /// the vertices produced by the prior expansion. The vertex at the start of
+    /// the recursion is included in the results as well, at depth zero.
+    /// Unbounded recursion has no `depth`, and repeats its last expansion
+    /// until no more vertices are reachable.
+    Recurse {
+        eid: Eid,
+        from_vid: Vid,
+        to_vid: Vid,
+        edge_name: Arc<str>,
+        depth: Option<NonZeroUsize>,
+        expansions: Vec<PlanStep>,
+    },
+
+    /// Expand a `@fold` edge, collecting its contents into a single value per origin vertex.
+    Fold {
+        eid: Eid,
+        from_vid: Vid,
+        to_vid: Vid,
+        type_name: Arc<str>,
+        edge_name: Arc<str>,
+
+        #[serde(default, skip_serializing_if = "EdgeParameters::is_empty")]
+        parameters: EdgeParameters,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        destination_filters: Vec<Operation<LocalField, Argument>>,
+
+        /// The steps that compute the folded data.
+        ///
+        /// They are performed separately for the contents of each fold.
+        steps: Vec<PlanStep>,
+
+        /// The steps that compute the fold's outputs.
+        ///
+        /// They are only performed for non-empty folds that satisfy all the `FilterFold` steps
+        /// that follow this step.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        output_steps: Vec<PlanStep>,
+
+        /// Whether this fold comes from an `@absent` edge. If so, results are discarded
+        /// as soon as their fold is found to have any elements.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        absent: bool,
+    },
+
+    /// Discard results whose fold doesn't satisfy the filter, such as on its count of elements.
+    FilterFold { eid: Eid, filter: Operation<FoldSpecificFieldKind, Argument> },
+}
+
+/// Describe how the interpreter would execute the given query with the given arguments.
+///
+/// The query is not executed. No adapter is needed, and none of its methods are called.
+pub fn explain_ir(
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<QueryPlan, QueryArgumentsError> {
+    let query = InterpretedQuery::from_query_and_arguments(indexed_query, arguments)?;
+    let ir_query = &query.indexed_query.ir_query;
+    let component = &ir_query.root_component;
+
+    let mut steps = vec![PlanStep::ResolveStartingVertices {
+        vid: component.root,
+        edge_name: ir_query.root_name.clone(),
+        parameters: ir_query.root_parameters.clone(),
+        destination_filters: component.vertices[&component.root].filters.clone(),
+    }];
+    plan_component(component, &mut steps);
+    plan_outputs(component, &mut steps);
+
+    Ok(QueryPlan {
+        steps,
+        arguments: query.arguments.as_ref().clone(),
+        order_by: ir_query.order_by.clone(),
+        offset: ir_query.offset,
+        limit: ir_query.limit,
+        dedupe: ir_query.dedupe,
+    })
+}
+
+fn plan_component(component: &IRQueryComponent, steps: &mut Vec<PlanStep>) {
+    plan_entry_into_vertex(component, &component.vertices[&component.root], steps);
+
+    // Edges and folds are processed in increasing order of their IDs,
+    // the same order in which they appear in the query.
+    let mut eids: Vec<Eid> =
+        component.edges.keys().chain(component.folds.keys()).copied().collect();
+    eids.sort_unstable();
+
+    for eid in eids {
+        if let Some(edge) = component.edges.get(&eid) {
+            let group =
+                component.optional_groups.iter().find(|(_, eids)| eids.first() == Some(&eid));
+            if let Some((group_name, group_eids)) = group {
+                let checks = group_eids
+                    .iter()
+                    .flat_map(|eid| {
+                        let edge = &component.edges[eid];
+                        let from_type = &component.vertices[&edge.from_vid].type_name;
+                        let mut checks = vec![];
+                        plan_parameter_tags(component, &edge.parameters, &mut checks);
+                        checks.push(resolve_neighbors_step(
+                            edge,
+                            from_type,
+                            &component.vertices[&edge.to_vid],
+                        ));
+                        checks
+                    })
+                    .collect();
+                steps.push(PlanStep::CheckOptionalGroup { group: group_name.clone(), checks });
+            }
+            plan_edge(component, edge, steps);
+        } else {
+            plan_fold(component, &component.folds[&eid], steps);
+        }
+    }
+}
+
+fn plan_entry_into_vertex(
+    component: &IRQueryComponent,
+    vertex: &IRVertex,
+    steps: &mut Vec<PlanStep>,
+) {
+    if let Some(coerced_from) = &vertex.coerced_from_type {
+        steps.push(PlanStep::ResolveCoercion {
+            vid: vertex.vid,
+            from_type: coerced_from.clone(),
+            to_type: vertex.type_name.clone(),
+        });
+    }
+
+    for filter in &vertex.filters {
+        steps.push(resolve_property_step(vertex, &filter.left().field_name));
+        for argument in filter.arguments() {
+            plan_filter_argument(component, argument, steps);
+        }
+        steps.push(PlanStep::FilterVertex { vid: vertex.vid, filter: filter.clone() });
+    }
+}
+
+fn plan_filter_argument(
+    component: &IRQueryComponent,
+    argument: &Argument,
+    steps: &mut Vec<PlanStep>,
+) {
+    if let Argument::Tag(FieldRef::ContextField(field)) = argument {
+        // Tags from outer components were already resolved before entering this one.
+        if let Some(tagged_vertex) = component.vertices.get(&field.vertex_id) {
+            steps.push(resolve_property_step(tagged_vertex, &field.field_name));
+        }
+    }
+}
+
+fn plan_parameter_tags(
+    component: &IRQueryComponent,
+    parameters: &EdgeParameters,
+    steps: &mut Vec<PlanStep>,
+) {
+    for (_, tag) in parameters.tags() {
+        if let FieldRef::ContextField(field) = tag {
+            // Tags from outer components were already resolved before entering this one.
+            if let Some(tagged_vertex) = component.vertices.get(&field.vertex_id) {
+                steps.push(resolve_property_step(tagged_vertex, &field.field_name));
+            }
+        }
+    }
+}
+
+fn plan_edge(component: &IRQueryComponent, edge: &IREdge, steps: &mut Vec<PlanStep>) {
+    let from_vertex = &component.vertices[&edge.from_vid];
+    let to_vertex = &component.vertices[&edge.to_vid];
+
+    if let Some(recursive) = &edge.recursive {
+        // Recursion may start from a vertex whose type was coerced, and then continue
+        // from vertices of the edge's endpoint type, optionally after another coercion.
+        let edge_endpoint_type =
+            to_vertex.coerced_from_type.as_ref().unwrap_or(&to_vertex.type_name);
+        let recursing_from = recursive.coerce_to.as_ref().unwrap_or(edge_endpoint_type);
+
+        let mut expansions = vec![resolve_neighbors_step(edge, &from_vertex.type_name, to_vertex)];
+        // Unbounded recursion repeats the same expansion until no more vertices are reachable,
+        // so it's shown as a single repeated expansion.
+        let max_depth = recursive.depth.map_or(2, NonZeroUsize::get);
+        for _ in 2..=max_depth {
+            if let Some(coerce_to) = &recursive.coerce_to {
+                expansions.push(PlanStep::ResolveCoercion {
+                    vid: edge.from_vid,
+                    from_type: edge_endpoint_type.clone(),
+                    to_type: coerce_to.clone(),
+                });
+            }
+            expansions.push(resolve_neighbors_step(edge, recursing_from, to_vertex));
+        }
+
+        steps.push(PlanStep::Recurse {
+            eid: edge.eid,
+            from_vid: edge.from_vid,
+            to_vid: edge.to_vid,
+            edge_name: edge.edge_name.clone(),
+            depth: recursive.depth,
+            expansions,
+        });
+    } else {
+        plan_parameter_tags(component, &edge.parameters, steps);
+        steps.push(resolve_neighbors_step(edge, &from_vertex.type_name, to_vertex));
+    }
+
+    for filter in &edge.filters {
+        steps.push(PlanStep::FilterEdge { eid: edge.eid, filter: filter.clone() });
+    }
+
+    plan_entry_into_vertex(component, to_vertex, steps);
+}
+
+fn plan_fold(parent_component: &IRQueryComponent, fold: &IRFold, steps: &mut Vec<PlanStep>) {
+    // Tagged values used inside the fold are resolved before expanding it.
+    for imported_tag in &fold.imported_tags {
+        if let FieldRef::ContextField(field) = imported_tag {
+            let vertex = &parent_component.vertices[&field.vertex_id];
+            steps.push(resolve_property_step(vertex, &field.field_name));
+        }
+    }
+
+    let component = &fold.component;
+    let mut fold_steps = vec![];
+    plan_component(component, &mut fold_steps);
+    for aggregated_field in get_aggregated_fields(parent_component, fold) {
+        let ContextField { vertex_id, field_name, .. } = aggregated_field;
+        fold_steps.push(resolve_property_step(&component.vertices[&vertex_id], &field_name));
+    }
+
+    let mut output_steps = vec![];
+    plan_outputs(component, &mut output_steps);
+
+    plan_parameter_tags(parent_component, &fold.parameters, steps);
+
+    let expanding_from = &parent_component.vertices[&fold.from_vid];
+    steps.push(PlanStep::Fold {
+        eid: fold.eid,
+        from_vid: fold.from_vid,
+        to_vid: fold.to_vid,
+        type_name: expanding_from.type_name.clone(),
+        edge_name: fold.edge_name.clone(),
+        parameters: fold.parameters.clone(),
+        destination_filters: component.vertices[&component.root].filters.clone(),
+        steps: fold_steps,
+        output_steps,
+        absent: fold.absent,
+    });
+
+    for filter in &fold.post_filters {
+        for argument in filter.arguments() {
+            plan_filter_argument(parent_component, argument, steps);
+        }
+        steps.push(PlanStep::FilterFold { eid: fold.eid, filter: filter.clone() });
+    }
+}
+
+fn plan_outputs(component: &IRQueryComponent, steps: &mut Vec<PlanStep>) {
+    // Outputs are resolved in order of their names, with all the properties needed
+    // from the same vertex resolved together.
+    let mut batches: Vec<(Vid, Vec<Arc<str>>)> = vec![];
+    for output in component.outputs.values() {
+        match batches.iter_mut().find(|(vid, _)| *vid == output.vertex_id) {
+            Some((_, properties)) => {
+                if !properties.contains(&output.field_name) {
+                    properties.push(output.field_name.clone());
+                }
+            }
+            None => batches.push((output.vertex_id, vec![output.field_name.clone()])),
+        }
+    }
+
+    for (vid, mut properties) in batches {
+        let vertex = &component.vertices[&vid];
+        if properties.len() == 1 {
+            steps.push(resolve_property_step(vertex, &properties.pop().expect("no property")));
+        } else {
+            steps.push(PlanStep::ResolveProperties {
+                vid,
+                type_name: vertex.type_name.clone(),
+                properties,
+            });
+        }
+    }
+}
+
+fn resolve_neighbors_step(edge: &IREdge, type_name: &Arc<str>, to_vertex: &IRVertex) -> PlanStep {
+    PlanStep::ResolveNeighbors {
+        eid: edge.eid,
+        from_vid: edge.from_vid,
+        to_vid: edge.to_vid,
+        type_name: type_name.clone(),
+        edge_name: edge.edge_name.clone(),
+        parameters: edge.parameters.clone(),
+        optional: edge.optional,
+        destination_filters: to_vertex.filters.clone(),
+    }
+}
+
+fn resolve_property_step(vertex: &IRVertex, property: &Arc<str>) -> PlanStep {
+    PlanStep::ResolveProperty {
+        vid: vertex.vid,
+        type_name: vertex.type_name.clone(),
+        property: property.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::Debug,
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use serde::{de::DeserializeOwned, Serialize};
+    use trustfall_filetests_macros::parameterize;
+
+    use super::{explain_ir, PlanStep};
+    use crate::{
+        filesystem_interpreter::FilesystemVertex,
+        interpreter::trace::{FunctionCall, TraceOpContent},
+        ir::IndexedQuery,
+        nullables_interpreter::NullablesVertex,
+        numbers_interpreter::NumbersVertex,
+        test_types::{TestIRQuery, TestIRQueryResult, TestInterpreterOutputTrace},
+    };
+
+    fn collect_calls(steps: &[PlanStep], calls: &mut Vec<FunctionCall>) {
+        for step in steps {
+            match step {
+                PlanStep::ResolveStartingVertices { vid, .. } => {
+                    calls.push(FunctionCall::ResolveStartingVertices(*vid));
+                }
+                PlanStep::ResolveNeighbors { eid, from_vid, type_name, .. } => {
+                    calls.push(FunctionCall::ResolveNeighbors(*from_vid, type_name.clone(), *eid));
+                }
+                PlanStep::ResolveCoercion { vid, from_type, to_type } => {
+                    calls.push(FunctionCall::ResolveCoercion(
+                        *vid,
+                        from_type.clone(),
+                        to_type.clone(),
+                    ));
+                }
+                PlanStep::ResolveProperty { vid, type_name, property } => {
+                    calls.push(FunctionCall::ResolveProperty(
+                        *vid,
+                        type_name.clone(),
+                        property.clone(),
+                    ));
+                }
+                PlanStep::ResolveProperties { vid, type_name, properties } => {
+                    // Traced adapters record batched properties as separate calls.
+                    calls.extend(properties.iter().map(|property| {
+                        FunctionCall::ResolveProperty(*vid, type_name.clone(), property.clone())
+                    }));
+                }
+                PlanStep::Recurse { expansions, .. } => collect_calls(expansions, calls),
+                PlanStep::CheckOptionalGroup { checks, .. } => collect_calls(checks, calls),
+                PlanStep::Fold { eid, from_vid, type_name, steps, output_steps, .. } => {
+                    calls.push(FunctionCall::ResolveNeighbors(*from_vid, type_name.clone(), *eid));
+                    collect_calls(steps, calls);
+                    collect_calls(output_steps, calls);
+                }
+                PlanStep::FilterVertex { .. }
+                | PlanStep::FilterEdge { .. }
+                | PlanStep::FilterFold { .. } => {}
+            }
+        }
+    }
+
+    fn check_plan_covers_trace<Vertex>(test_query: TestIRQuery, trace_data: &str)
+    where
+        Vertex: Debug + Clone + PartialEq + Eq + Serialize + DeserializeOwned,
+    {
+        let test_data: TestInterpreterOutputTrace<Vertex> =
+            ron::from_str(trace_data).expect("failed to parse trace file");
+
+        let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+        let arguments = test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+        let plan = explain_ir(Arc::new(indexed_query), Arc::new(arguments)).unwrap();
+
+        let mut planned_calls = vec![];
+        collect_calls(&plan.steps, &mut planned_calls);
+
+        // Execution is lazy, so it may skip some of the planned calls.
+        // It must never make a call that isn't part of the plan.
+        for op in test_data.trace.ops.values() {
+            if let TraceOpContent::Call(call) = &op.content {
+                assert!(planned_calls.contains(call), "{call:?} not in plan {plan:#?}");
+            }
+        }
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut trace_path = PathBuf::from(base);
+        trace_path.push(format!("{stem}.trace.ron"));
+        let trace_data = fs::read_to_string(trace_path).unwrap();
+
+        let mut ir_path = PathBuf::from(base);
+        ir_path.push(format!("{stem}.ir.ron"));
+        let ir_data = fs::read_to_string(ir_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&ir_data).unwrap();
+        let test_query = test_query.unwrap();
+
+        match test_query.schema_name.as_str() {
+            "filesystem" => check_plan_covers_trace::<FilesystemVertex>(test_query, &trace_data),
+            "numbers" => check_plan_covers_trace::<NumbersVertex>(test_query, &trace_data),
+            "nullables" => check_plan_covers_trace::<NullablesVertex>(test_query, &trace_data),
+            _ => unreachable!("{}", test_query.schema_name),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    fs::{self, Metadata},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    ir::{EdgeParameters, FieldValue},
+    schema::Schema,
+};
+
+use super::{
+    helpers::{candidate_to_predicate, resolve_coercion_using_schema, resolve_neighbors_with},
+    helpers::{resolve_property_with, resolve_typename},
+    Adapter, AsVertex, CandidateValue, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo,
+    ResolveInfo, Typename, VertexInfo, VertexIterator,
+};
+
+#[cfg(test)]
+mod tests;
+
+static SCHEMA: OnceLock<Schema> = OnceLock::new();
+
+/// A Trustfall adapter for querying the files, directories, and symbolic links
+/// within a directory, much like the `find` command.
+///
+/// The schema matching this adapter is in the adjacent
+/// [`schema.graphql` file](https://github.com/obi1kenobi/trustfall/blob/main/trustfall_core/src/interpreter/filesystem_adapter/schema.graphql),
+/// and is also available via the [`FilesystemAdapter::schema_text()`] function.
+///
+/// Directory entries are listed in order of their names. Filters on the `name` of entries
+/// are applied while listing the directory, so entries whose names don't match
+/// are never inspected. Looking up entries by specific names, such as with `=` or `one_of`
+/// filters, doesn't list the directory at all.
+///
+/// Entries that can't be inspected, for example due to insufficient permissions,
+/// are skipped.
+///
+/// # Example
+///
+/// Find the Rust source files within the `src` directory:
+/// ```rust
+/// # use std::sync::Arc;
+/// # use trustfall_core::{
+/// #     frontend::parse,
+/// #     interpreter::{execution::interpret_ir, FilesystemAdapter},
+/// # };
+/// let query = r#"
+/// {
+///     Root {
+///         subdirectory {
+///             name @filter(op: "=", value: ["$dir"])
+///
+///             file {
+///                 name @output
+///                 extension @filter(op: "=", value: ["$ext"])
+///             }
+///         }
+///     }
+/// }"#;
+/// let arguments = Arc::new(
+///     [(Arc::from("dir"), "src".into()), (Arc::from("ext"), "rs".into())].into(),
+/// );
+///
+/// let adapter = Arc::new(FilesystemAdapter::new("."));
+/// let indexed_query = parse(FilesystemAdapter::schema(), query).expect("not a valid query");
+/// for row in interpret_ir(adapter, indexed_query, arguments).expect("invalid arguments") {
+///     println!("{}", row["name"].as_str().expect("not a string"));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FilesystemAdapter {
+    root: Arc<Path>,
+}
+
+/// A vertex produced by [`FilesystemAdapter`]: a file, directory, or symbolic link.
+#[derive(Debug, Clone)]
+pub struct FilesystemEntry {
+    path: Arc<Path>,
+    metadata: Metadata,
+}
+
+impl FilesystemAdapter {
+    /// Make an adapter for querying the contents of the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into().into() }
+    }
+
+    /// The schema describing the filesystem.
+    ///
+    /// Queries on this adapter must conform to this schema.
+    pub fn schema_text() -> &'static str {
+        include_str!("./schema.graphql")
+    }
+
+    /// The parsed form of [`FilesystemAdapter::schema_text()`].
+    pub fn schema() -> &'static Schema {
+        SCHEMA.get_or_init(|| Schema::parse(Self::schema_text()).expect("not a valid schema"))
+    }
+}
+
+impl FilesystemEntry {
+    fn load(path: PathBuf) -> Option<Self> {
+        let metadata = fs::symlink_metadata(&path).ok()?;
+        Some(Self { path: path.into(), metadata })
+    }
+
+    /// The entry's path, starting with the path of the adapter's root directory.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The entry's metadata. Symbolic links are not followed.
+    #[inline]
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// The entry's name: the last component of its path.
+    ///
+    /// For paths without a final name component, such as `.`, this is the whole path.
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or(self.path.as_os_str()).to_string_lossy().into_owned()
+    }
+
+    fn parent(&self, root: &Path) -> Option<Self> {
+        if self.path.as_ref() == root {
+            return None;
+        }
+        self.path.parent().and_then(|parent| Self::load(parent.to_path_buf()))
+    }
+
+    /// Entries within this directory, in order of their names.
+    ///
+    /// Only entries whose names may match the given candidate values are inspected.
+    fn children(&self, names: Option<CandidateValue<FieldValue>>) -> Vec<Self> {
+        if !self.metadata.is_dir() {
+            return vec![];
+        }
+
+        match names {
+            Some(CandidateValue::Impossible) => vec![],
+            Some(CandidateValue::Single(name)) => self.children_named(vec![name]),
+            Some(CandidateValue::Multiple(names)) => self.children_named(names),
+            names => {
+                let predicate = candidate_to_predicate(names.unwrap_or(CandidateValue::All));
+                let Ok(listing) = fs::read_dir(&self.path) else {
+                    return vec![];
+                };
+
+                let mut children: Vec<_> = listing
+                    .filter_map(|entry| {
+                        let entry = entry.ok()?;
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        predicate(&name.into()).then(|| Self::load(entry.path())).flatten()
+                    })
+                    .collect();
+                children.sort_unstable_by(|l, r| l.path.cmp(&r.path));
+                children
+            }
+        }
+    }
+
+    fn children_named(&self, names: Vec<FieldValue>) -> Vec<Self> {
+        let mut names: Vec<_> = names
+            .iter()
+            .filter_map(FieldValue::as_str)
+            .filter(|name| {
+                // Names with path separators, or that are `.` or `..`, can't be of children.
+                let mut components = Path::new(name).components();
+                matches!(components.next(), Some(Component::Normal(..)))
+                    && components.next().is_none()
+                    && !name.contains(std::path::is_separator)
+            })
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names.into_iter().filter_map(|name| Self::load(self.path.join(name))).collect()
+    }
+
+    fn resolve_link(&self) -> Option<Self> {
+        let target = fs::read_link(&self.path).ok()?;
+        let resolved = match self.path.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+        Self::load(resolved)
+    }
+
+    fn property(&self, property_name: &str) -> FieldValue {
+        match property_name {
+            "name" => self.name().into(),
+            "path" => self.path.to_string_lossy().into_owned().into(),
+            "size" => self.metadata.len().into(),
+            "modified" => self.metadata.modified().ok().into(),
+            "readonly" => self.metadata.permissions().readonly().into(),
+            "permissions" => {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    (self.metadata.permissions().mode() & 0o7777).into()
+                }
+                #[cfg(not(unix))]
+                {
+                    FieldValue::Null
+                }
+            }
+            "extension" => self
+                .path
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned().into())
+                .unwrap_or(FieldValue::Null),
+            "target" => fs::read_link(&self.path)
+                .map(|target| target.to_string_lossy().into_owned().into())
+                .unwrap_or(FieldValue::Null),
+            _ => unreachable!("unexpected property {property_name}"),
+        }
+    }
+}
+
+impl Typename for FilesystemEntry {
+    fn typename(&self) -> &'static str {
+        let file_type = self.metadata.file_type();
+        if file_type.is_dir() {
+            "Directory"
+        } else if file_type.is_symlink() {
+            "Symlink"
+        } else {
+            "File"
+        }
+    }
+}
+
+impl<'a> Adapter<'a> for FilesystemAdapter {
+    type Vertex = FilesystemEntry;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        _resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'a, Self::Vertex> {
+        let entry = match edge_name.as_ref() {
+            "Root" => FilesystemEntry::load(self.root.to_path_buf())
+                .filter(|entry| entry.metadata.is_dir()),
+            "Path" => {
+                let path = parameters["path"].as_str().expect("path was not a string");
+                FilesystemEntry::load(self.root.join(path))
+            }
+            _ => unreachable!("unexpected starting edge {edge_name}"),
+        };
+        Box::new(entry.into_iter())
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            return resolve_typename(contexts, Self::schema(), type_name);
+        }
+
+        let property_name = property_name.clone();
+        resolve_property_with(contexts, move |vertex: &FilesystemEntry| {
+            vertex.property(&property_name)
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+        match (type_name.as_ref(), edge_name.as_ref()) {
+            (_, "parent") => {
+                let root = self.root.clone();
+                resolve_neighbors_with(contexts, move |vertex: &FilesystemEntry| {
+                    Box::new(vertex.parent(&root).into_iter())
+                })
+            }
+            ("Directory", "child" | "file" | "subdirectory") => {
+                let names = resolve_info.destination().statically_required_property("name");
+                let kind = match edge_name.as_ref() {
+                    "file" => Some("File"),
+                    "subdirectory" => Some("Directory"),
+                    _ => None,
+                };
+                resolve_neighbors_with(contexts, move |vertex: &FilesystemEntry| {
+                    let children = vertex.children(names.clone()).into_iter();
+                    Box::new(
+                        children.filter(move |child| {
+                            kind.map_or(true, |kind| child.typename() == kind)
+                        }),
+                    )
+                })
+            }
+            ("Symlink", "resolvesTo") => {
+                resolve_neighbors_with(contexts, |vertex: &FilesystemEntry| {
+                    Box::new(vertex.resolve_link().into_iter())
+                })
+            }
+            _ => unreachable!("unexpected edge {edge_name} on type {type_name}"),
+        }
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        _type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'a, V, bool> {
+        resolve_coercion_using_schema(contexts, Self::schema(), coerce_to_type)
+    }
+}
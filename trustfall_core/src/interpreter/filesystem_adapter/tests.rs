@@ -0,0 +1,264 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::FilesystemAdapter;
+use crate::{
+    frontend::parse,
+    interpreter::{execution::interpret_ir, helpers::check_adapter_invariants},
+    ir::{DateTime, FieldValue},
+};
+
+/// A directory with known contents, deleted when dropped.
+///
+/// ```text
+/// a.txt
+/// b.rs
+/// sub/
+///     c.txt
+/// link -> a.txt  (Unix only)
+/// ```
+struct TestDirectory(PathBuf);
+
+impl TestDirectory {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir()
+            .join(format!("trustfall-filesystem-adapter-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub")).expect("failed to create directory");
+        fs::write(root.join("a.txt"), "hello").expect("failed to write file");
+        fs::write(root.join("b.rs"), "fn main() {}").expect("failed to write file");
+        fs::write(root.join("sub").join("c.txt"), "").expect("failed to write file");
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("a.txt", root.join("link")).expect("failed to create symlink");
+
+        Self(root)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn run_query(
+        &self,
+        query: &str,
+        arguments: BTreeMap<Arc<str>, FieldValue>,
+    ) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        let adapter = Arc::new(FilesystemAdapter::new(self.path()));
+        let indexed_query = parse(FilesystemAdapter::schema(), query).expect("not a valid query");
+        interpret_ir(adapter, indexed_query, Arc::new(arguments))
+            .expect("invalid arguments")
+            .collect()
+    }
+}
+
+impl Drop for TestDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn names(rows: &[BTreeMap<Arc<str>, FieldValue>]) -> Vec<&str> {
+    rows.iter().map(|row| row["name"].as_str().expect("not a string")).collect()
+}
+
+#[test]
+fn enforce_adapter_invariants() {
+    let directory = TestDirectory::new("invariants");
+    let adapter = FilesystemAdapter::new(directory.path());
+    check_adapter_invariants(FilesystemAdapter::schema(), adapter);
+}
+
+#[test]
+fn children_are_listed_in_order() {
+    let directory = TestDirectory::new("children");
+    let rows = directory.run_query(
+        r#"
+{
+    Root {
+        child {
+            name @output
+            kind: __typename @output
+            size @output
+        }
+    }
+}"#,
+        BTreeMap::new(),
+    );
+
+    let sub_size = fs::metadata(directory.path().join("sub")).expect("no metadata").len();
+    let mut expected = vec![
+        ("a.txt", "File", FieldValue::Uint64(5)),
+        ("b.rs", "File", FieldValue::Uint64(12)),
+        ("sub", "Directory", sub_size.into()),
+    ];
+    if cfg!(unix) {
+        expected.insert(2, ("link", "Symlink", FieldValue::Uint64(5)));
+    }
+    let actual: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            (row["name"].as_str().unwrap(), row["kind"].as_str().unwrap(), row["size"].clone())
+        })
+        .collect();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn name_filters_select_children() {
+    let directory = TestDirectory::new("name_filters");
+    let query = r#"
+{
+    Root {
+        file {
+            name @output @filter(op: "one_of", value: ["$names"])
+        }
+    }
+}"#;
+    let run = |names: &[&str]| {
+        let names: FieldValue = names.to_vec().into();
+        directory.run_query(query, BTreeMap::from([(Arc::from("names"), names)]))
+    };
+
+    assert_eq!(vec!["a.txt", "b.rs"], names(&run(&["b.rs", "missing", "a.txt"])));
+    assert_eq!(vec!["a.txt"], names(&run(&["a.txt", "a.txt"])));
+    assert_eq!(Vec::<&str>::new(), names(&run(&["sub"])));
+    assert_eq!(Vec::<&str>::new(), names(&run(&["sub/c.txt", ".", ".."])));
+
+    let rows = directory.run_query(
+        r#"
+{
+    Root {
+        child {
+            name @output @filter(op: "regex", value: ["$pattern"])
+        }
+    }
+}"#,
+        BTreeMap::from([(Arc::from("pattern"), "^[a-b]\\.".into())]),
+    );
+    assert_eq!(vec!["a.txt", "b.rs"], names(&rows));
+}
+
+#[test]
+fn path_and_parent() {
+    let directory = TestDirectory::new("parent");
+    let rows = directory.run_query(
+        r#"
+{
+    Path(path: "sub/c.txt") {
+        ... on File {
+            name @output
+            path @output
+            extension @output
+
+            parent {
+                parent: name @output
+
+                parent {
+                    grandparent: path @output
+
+                    parent @optional {
+                        beyond_root: path @output
+                    }
+                }
+            }
+        }
+    }
+}"#,
+        BTreeMap::new(),
+    );
+
+    let root = directory.path().to_string_lossy().into_owned();
+    let expected = BTreeMap::from([
+        (Arc::from("name"), "c.txt".into()),
+        (Arc::from("extension"), "txt".into()),
+        (
+            Arc::from("path"),
+            directory.path().join("sub").join("c.txt").to_string_lossy().into_owned().into(),
+        ),
+        (Arc::from("parent"), "sub".into()),
+        (Arc::from("grandparent"), root.into()),
+        (Arc::from("beyond_root"), FieldValue::Null),
+    ]);
+    assert_eq!(vec![expected], rows);
+
+    let rows = directory.run_query(
+        r#"
+{
+    Path(path: "missing") {
+        name @output
+    }
+}"#,
+        BTreeMap::new(),
+    );
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn modification_times_are_datetimes() {
+    let directory = TestDirectory::new("modified");
+    let modified = fs::metadata(directory.path().join("a.txt"))
+        .and_then(|metadata| metadata.modified())
+        .expect("no modification time");
+    let query = r#"
+{
+    Root {
+        file {
+            name @output
+            modified @output @filter(op: "<", value: ["$before"])
+        }
+    }
+}"#;
+    let run = |before: DateTime| {
+        directory.run_query(query, BTreeMap::from([(Arc::from("before"), before.into())]))
+    };
+
+    let rows = run(DateTime::from_unix_seconds(i64::MAX));
+    assert_eq!(vec!["a.txt", "b.rs"], names(&rows));
+    assert_eq!(FieldValue::from(modified), rows[0]["modified"]);
+    assert!(run(DateTime::UNIX_EPOCH).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_and_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let directory = TestDirectory::new("symlinks");
+    fs::set_permissions(directory.path().join("a.txt"), fs::Permissions::from_mode(0o440))
+        .expect("failed to set permissions");
+
+    let rows = directory.run_query(
+        r#"
+{
+    Root {
+        child {
+            ... on Symlink {
+                name @output
+                target @output
+
+                resolvesTo {
+                    resolved: name @output
+                    permissions @output
+                    readonly @output
+                }
+            }
+        }
+    }
+}"#,
+        BTreeMap::new(),
+    );
+
+    let expected = BTreeMap::from([
+        (Arc::from("name"), "link".into()),
+        (Arc::from("target"), "a.txt".into()),
+        (Arc::from("resolved"), "a.txt".into()),
+        (Arc::from("permissions"), 0o440u32.into()),
+        (Arc::from("readonly"), true.into()),
+    ]);
+    assert_eq!(vec![expected], rows);
+}
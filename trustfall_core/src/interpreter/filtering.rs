@@ -1,6 +1,11 @@
-use std::{fmt::Debug, mem};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    mem,
+    sync::{Arc, Mutex},
+};
 
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 
 use crate::ir::{Argument, FieldRef, FieldValue, IRQueryComponent, LocalField, Operation, Vid};
 
@@ -41,6 +46,8 @@ pub(super) fn equals(left: &FieldValue, right: &FieldValue) -> bool {
                     false
                 }
             }
+            #[cfg(feature = "num-bigint")]
+            (FieldValue::BigInt(_), _) | (_, FieldValue::BigInt(_)) => left == right,
             _ => false,
         }
     }
@@ -57,6 +64,9 @@ macro_rules! make_comparison_op_func {
                 (FieldValue::Int64(l), FieldValue::Int64(r)) => l $op r,
                 (FieldValue::Uint64(l), FieldValue::Uint64(r)) => l $op r,
                 (FieldValue::Float64(l), FieldValue::Float64(r)) => l $op r,
+                (FieldValue::DateTime(l), FieldValue::DateTime(r)) => l $op r,
+                #[cfg(feature = "num-bigint")]
+                (FieldValue::BigInt(l), FieldValue::BigInt(r)) => l $op r,
                 _ => $slow_path_handler(left, right),
             }
         }
@@ -90,6 +100,10 @@ macro_rules! make_greater_than_func_slow_path {
                         unreachable!("values {:?} and {:?}", left, right)
                     }
                 }
+                // Comparisons that involve `BigInt` values use its `PartialOrd` implementation,
+                // which handles mixing them with other integers.
+                #[cfg(feature = "num-bigint")]
+                (FieldValue::BigInt(_), _) | (_, FieldValue::BigInt(_)) => left $op right,
                 _ => unreachable!("values {:?} and {:?}", left, right)
             }
         }
@@ -123,6 +137,10 @@ macro_rules! make_less_than_func_slow_path {
                         unreachable!("values {:?} and {:?}", left, right)
                     }
                 }
+                // Comparisons that involve `BigInt` values use its `PartialOrd` implementation,
+                // which handles mixing them with other integers.
+                #[cfg(feature = "num-bigint")]
+                (FieldValue::BigInt(_), _) | (_, FieldValue::BigInt(_)) => left $op right,
                 _ => unreachable!("values {:?} and {:?}", left, right)
             }
         }
@@ -171,13 +189,45 @@ pub(super) fn has_suffix(left: &FieldValue, right: &FieldValue) -> bool {
     }
 }
 
+macro_rules! make_ignore_case_string_op_func {
+    ( $func: ident, $method: ident ) => {
+        #[inline(always)]
+        pub(super) fn $func(left: &FieldValue, right: &FieldValue) -> bool {
+            match (left, right) {
+                (FieldValue::String(l), FieldValue::String(r)) => {
+                    l.to_lowercase().$method(r.to_lowercase().as_str())
+                }
+                (FieldValue::Null, FieldValue::String(_))
+                | (FieldValue::String(_), FieldValue::Null)
+                | (FieldValue::Null, FieldValue::Null) => false,
+                _ => unreachable!("{:?} {:?}", left, right),
+            }
+        }
+    };
+}
+
+#[inline(always)]
+pub(super) fn equals_ignore_case(left: &FieldValue, right: &FieldValue) -> bool {
+    match (left, right) {
+        (FieldValue::String(l), FieldValue::String(r)) => l.to_lowercase() == r.to_lowercase(),
+        (FieldValue::Null, FieldValue::String(_))
+        | (FieldValue::String(_), FieldValue::Null)
+        | (FieldValue::Null, FieldValue::Null) => false,
+        _ => unreachable!("{:?} {:?}", left, right),
+    }
+}
+
+make_ignore_case_string_op_func!(has_prefix_ignore_case, starts_with);
+make_ignore_case_string_op_func!(has_suffix_ignore_case, ends_with);
+make_ignore_case_string_op_func!(has_substring_ignore_case, contains);
+
 #[inline(always)]
 pub(super) fn one_of(left: &FieldValue, right: &FieldValue) -> bool {
     match right {
         FieldValue::Null => false,
         FieldValue::List(v) => {
             for value in v.iter() {
-                if left == value {
+                if equals(left, value) {
                     return true;
                 }
             }
@@ -192,30 +242,78 @@ pub(super) fn contains(left: &FieldValue, right: &FieldValue) -> bool {
     one_of(right, left)
 }
 
-/// Implement checking a value against a regex pattern.
+/// The compiled regex patterns used by the `regex` filters of one query execution.
 ///
-/// This function should be used when checking a regex filter that uses a tag in the filter,
-/// since it will recompile the regex for each check, and this is slow. For regex checks against
-/// a runtime parameter, the optimized variant of this function should be called,
-/// with a precompiled regex pattern matching the runtime parameter value.
-#[inline(always)]
-pub(super) fn regex_matches_slow_path(left: &FieldValue, right: &FieldValue) -> bool {
-    match (left, right) {
-        (FieldValue::String(l), FieldValue::String(r)) => {
-            // Bad regex values can happen in ways that can't be prevented,
-            // for example: when using a tag argument and the tagged value isn't a valid regex.
-            // In such cases, we declare that the regex doesn't match.
-            Regex::new(r).map(|pattern| pattern.is_match(l)).unwrap_or(false)
-        }
-        (FieldValue::Null, FieldValue::Null)
-        | (FieldValue::Null, FieldValue::String(_))
-        | (FieldValue::String(_), FieldValue::Null) => false,
-        _ => unreachable!("{:?} {:?}", left, right),
+/// The same filter may be set up many times while a query runs, for example once per
+/// folded-over vertex when it's inside a `@fold`, and filters using tagged values may
+/// see the same pattern many times over. Each distinct pattern is compiled only once,
+/// and the compiled regex is then shared by all its uses.
+///
+/// When tagged values produce many distinct patterns, the least-recently-used pattern
+/// is evicted to make room for each new one, so patterns still in use stay compiled.
+///
+/// Cloning the cache is cheap, and produces a handle to the same cached patterns.
+#[derive(Debug, Clone, Default)]
+pub(super) struct RegexCache {
+    inner: Arc<Mutex<RegexCacheState>>,
+}
+
+/// A pattern, and whether it's matched case-insensitively.
+type RegexCacheKey = (Arc<str>, bool);
+
+#[derive(Debug, Default)]
+struct RegexCacheState {
+    /// Each compiled pattern, with the tick at which it was last used.
+    compiled: HashMap<RegexCacheKey, (Regex, u64)>,
+
+    /// The key of each compiled pattern, ordered by when it was last used.
+    recency: BTreeMap<u64, RegexCacheKey>,
+
+    next_tick: u64,
+}
+
+impl RegexCache {
+    /// The maximum number of compiled patterns the cache holds.
+    const MAX_PATTERNS: usize = 1024;
+
+    /// Get the compiled regex for the pattern.
+    ///
+    /// Panics if the pattern is not a valid regex. Query arguments are checked to be valid
+    /// regexes before the query runs, but that can't be done for tagged values and the values
+    /// a joined query imports from earlier root fields. If such a value isn't a valid regex,
+    /// no row can be said to match or not match it, so the query cannot continue.
+    pub(super) fn get(&self, pattern: &Arc<str>, case_insensitive: bool) -> Regex {
+        let mut guard = self.inner.lock().expect("regex cache lock was poisoned");
+        let state = &mut *guard;
+        let key: RegexCacheKey = (pattern.clone(), case_insensitive);
+        let tick = state.next_tick;
+        state.next_tick += 1;
+
+        if let Some((regex, last_used)) = state.compiled.get_mut(&key) {
+            let previous_tick = mem::replace(last_used, tick);
+            state.recency.remove(&previous_tick);
+            state.recency.insert(tick, key);
+            return regex.clone();
+        }
+
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .unwrap_or_else(|e| panic!("the value {pattern:?} is not a valid regex pattern: {e}"));
+        state.compiled.insert(key.clone(), (regex.clone(), tick));
+        state.recency.insert(tick, key);
+
+        if state.compiled.len() > Self::MAX_PATTERNS {
+            let (_, evicted) = state.recency.pop_first().expect("no patterns to evict");
+            state.compiled.remove(&evicted);
+        }
+        regex
     }
 }
 
+/// Implement checking a value against a precompiled regex pattern.
 #[inline(always)]
-pub(super) fn regex_matches_optimized(left: &FieldValue, regex: &Regex) -> bool {
+pub(super) fn regex_matches(left: &FieldValue, regex: &Regex) -> bool {
     match left {
         FieldValue::String(l) => regex.is_match(l),
         FieldValue::Null => false,
@@ -271,28 +369,159 @@ pub(super) fn apply_filter<'query, AdapterT: Adapter<'query>>(
     // TODO: implement more efficient filtering with:
     //       - type awareness: we know the type of the field being filtered,
     //         and we probably know (or can infer) the type of the filtering argument(s)
-    //       - when using tagged values as regexes, adjacent tag values are likely to be equal
-    //         due to expansion rules, so keep the previous regex around and reuse if possible
-    //         instead of rebuilding
     //       - turn "in_collection" filter arguments into sets if possible
+    if let Operation::Between(_, lower, upper) | Operation::NotBetween(_, lower, upper) = filter {
+        return apply_range_filter(
+            adapter,
+            carrier,
+            component,
+            current_vid,
+            matches!(filter, Operation::NotBetween(..)),
+            (lower, upper),
+            iterator,
+        );
+    }
+
     match filter.right() {
         Some(Argument::Variable(var)) => {
             let query_arguments =
                 &carrier.query.as_ref().expect("query was not returned").arguments;
             let right_value = query_arguments[var.variable_name.as_ref()].to_owned();
-            apply_filter_with_static_argument_value(filter, right_value, iterator)
+            apply_filter_with_static_argument_value(
+                filter,
+                right_value,
+                &carrier.regex_cache,
+                iterator,
+            )
+        }
+        Some(argument @ Argument::Tag(..)) => {
+            let argument_value_iterator = compute_argument_with_separate_value(
+                adapter,
+                carrier,
+                component,
+                current_vid,
+                argument,
+                iterator,
+            );
+            apply_filter_with_tagged_argument_value(
+                filter,
+                carrier.regex_cache.clone(),
+                argument_value_iterator,
+            )
         }
-        Some(Argument::Tag(FieldRef::ContextField(context_field))) => {
+        None => unreachable!(
+            "no argument present for filter, but not handled in unary filters fn: {filter:?}"
+        ),
+    }
+}
+
+/// Apply a `between` or `not_between` filter, whose bounds may each be a variable or a tag.
+///
+/// Like other filters, it is satisfied if either bound is a tag from an `@optional` scope
+/// that doesn't exist. Otherwise, null values are neither between nor outside any bounds,
+/// just like null values are neither less than nor greater than any value.
+fn apply_range_filter<'query, AdapterT: Adapter<'query>>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    current_vid: Vid,
+    negated: bool,
+    (lower, upper): (&Argument, &Argument),
+    iterator: ContextIterator<'query, AdapterT::Vertex>,
+) -> ContextIterator<'query, AdapterT::Vertex> {
+    // Keep each context's lower bound on its value stack while computing its upper bound.
+    let with_lower_bound = compute_argument_with_separate_value(
+        adapter,
+        carrier,
+        component,
+        current_vid,
+        lower,
+        iterator,
+    );
+    let iterator: ContextIterator<'query, AdapterT::Vertex> =
+        Box::new(with_lower_bound.map(|(mut ctx, lower_value)| {
+            match lower_value {
+                TaggedValue::Some(value) => {
+                    ctx.values.push(value);
+                    ctx.values.push(FieldValue::Boolean(true));
+                }
+                TaggedValue::NonexistentOptional => ctx.values.push(FieldValue::Boolean(false)),
+            }
+            ctx
+        }));
+    let with_both_bounds = compute_argument_with_separate_value(
+        adapter,
+        carrier,
+        component,
+        current_vid,
+        upper,
+        iterator,
+    );
+
+    Box::new(with_both_bounds.filter_map(move |(mut ctx, upper_value)| {
+        let lower_value = match ctx.values.pop().expect("no value present") {
+            FieldValue::Boolean(true) => {
+                TaggedValue::Some(ctx.values.pop().expect("no value present"))
+            }
+            FieldValue::Boolean(false) => TaggedValue::NonexistentOptional,
+            other => unreachable!("unexpected lower bound marker: {other:?}"),
+        };
+        let left_value = ctx.values.pop().expect("no value present");
+        let (TaggedValue::Some(lower_value), TaggedValue::Some(upper_value)) =
+            (lower_value, upper_value)
+        else {
+            return Some(ctx);
+        };
+
+        let outcome = if negated {
+            not_between(&left_value, &lower_value, &upper_value)
+        } else {
+            between(&left_value, &lower_value, &upper_value)
+        };
+        (ctx.within_nonexistent_optional() || outcome).then_some(ctx)
+    }))
+}
+
+#[inline(always)]
+pub(super) fn between(value: &FieldValue, lower: &FieldValue, upper: &FieldValue) -> bool {
+    greater_than_or_equal(value, lower) && less_than_or_equal(value, upper)
+}
+
+#[inline(always)]
+pub(super) fn not_between(value: &FieldValue, lower: &FieldValue, upper: &FieldValue) -> bool {
+    less_than(value, lower) || greater_than(value, upper)
+}
+
+/// Compute the value of a filter argument for each context, separately from the context itself.
+///
+/// Query variables have the same value for every context.
+fn compute_argument_with_separate_value<'query, AdapterT: Adapter<'query>>(
+    adapter: &AdapterT,
+    carrier: &mut QueryCarrier,
+    component: &IRQueryComponent,
+    current_vid: Vid,
+    argument: &Argument,
+    iterator: ContextIterator<'query, AdapterT::Vertex>,
+) -> ContextOutcomeIterator<'query, AdapterT::Vertex, TaggedValue> {
+    match argument {
+        Argument::Variable(var) => {
+            let query_arguments =
+                &carrier.query.as_ref().expect("query was not returned").arguments;
+            let value = query_arguments[var.variable_name.as_ref()].to_owned();
+            Box::new(iterator.map(move |ctx| (ctx, TaggedValue::Some(value.clone()))))
+        }
+        Argument::Tag(FieldRef::ContextField(context_field)) => {
             // TODO: Benchmark if it would be faster to duplicate the filtering code to special-case
             //       the situation when the tag is always known to exist, so we don't have to unwrap
             //       a TaggedValue enum, because we know it would be TaggedValue::Some.
-            let argument_value_iterator = if context_field.vertex_id == current_vid {
+            if context_field.vertex_id == current_vid {
                 // This tag is from the vertex we're currently filtering. That means the field
                 // whose value we want to get is actually local, so there's no need to compute it
                 // using the more expensive approach we use for non-local fields.
                 let local_equivalent_field = LocalField {
                     field_name: context_field.field_name.clone(),
                     field_type: context_field.field_type.clone(),
+                    transforms: context_field.transforms.clone(),
                 };
                 Box::new(
                     compute_local_field_with_separate_value(
@@ -313,12 +542,14 @@ pub(super) fn apply_filter<'query, AdapterT: Adapter<'query>>(
                     context_field,
                     iterator,
                 )
-            };
-            apply_filter_with_tagged_argument_value(filter, argument_value_iterator)
+            }
         }
-        Some(Argument::Tag(field_ref @ FieldRef::FoldSpecificField(fold_field))) => {
-            let argument_value_iterator = if component.folds.contains_key(&fold_field.fold_eid) {
+        Argument::Tag(field_ref @ FieldRef::FoldSpecificField(fold_field)) => {
+            if component.folds.contains_key(&fold_field.fold_eid) {
                 compute_fold_specific_field_with_separate_value(
+                    adapter,
+                    carrier,
+                    component,
                     fold_field.fold_eid,
                     &fold_field.kind,
                     iterator,
@@ -331,12 +562,8 @@ pub(super) fn apply_filter<'query, AdapterT: Adapter<'query>>(
                     let right_value = ctx.imported_tags[&cloned_ref].clone();
                     (ctx, right_value)
                 }))
-            };
-            apply_filter_with_tagged_argument_value(filter, argument_value_iterator)
+            }
         }
-        None => unreachable!(
-            "no argument present for filter, but not handled in unary filters fn: {filter:?}"
-        ),
     }
 }
 
@@ -379,6 +606,45 @@ fn apply_filter_op_with_tagged_argument<
     }))
 }
 
+/// Apply a regex filter whose pattern is a tagged value, so it may differ between contexts.
+///
+/// Adjacent contexts often have the same pattern, for example because they were produced
+/// by expanding the same edge. The most recently used regex is kept around for reuse,
+/// and other patterns are looked up in the query's [`RegexCache`].
+fn apply_regex_filter_with_tagged_argument<'query, Vertex: Debug + Clone + 'query>(
+    regex_cache: RegexCache,
+    case_insensitive: bool,
+    negated: bool,
+    iterator: ContextOutcomeIterator<'query, Vertex, TaggedValue>,
+) -> ContextIterator<'query, Vertex> {
+    let mut last_used: Option<(Arc<str>, Regex)> = None;
+    Box::new(iterator.filter_map(move |(mut ctx, tagged_value)| {
+        let left_value = ctx.values.pop().expect("no value present");
+        let TaggedValue::Some(right_value) = tagged_value else {
+            return Some(ctx);
+        };
+        if ctx.within_nonexistent_optional() {
+            return Some(ctx);
+        }
+
+        let is_match = match (&left_value, &right_value) {
+            (FieldValue::String(_), FieldValue::String(pattern)) => {
+                if last_used.as_ref().map_or(true, |(last_pattern, _)| last_pattern != pattern) {
+                    last_used = Some((pattern.clone(), regex_cache.get(pattern, case_insensitive)));
+                }
+                let (_, regex) = last_used.as_ref().expect("no regex was compiled");
+                regex_matches(&left_value, regex)
+            }
+            (FieldValue::Null, FieldValue::Null)
+            | (FieldValue::Null, FieldValue::String(_))
+            | (FieldValue::String(_), FieldValue::Null) => false,
+            _ => unreachable!("{:?} {:?}", left_value, right_value),
+        };
+
+        (is_match != negated).then_some(ctx)
+    }))
+}
+
 #[inline(always)]
 fn apply_filter_op<
     'query,
@@ -403,6 +669,7 @@ fn apply_filter_op<
 fn apply_filter_with_static_argument_value<'query, Vertex: Debug + Clone + 'query>(
     filter: &Operation<(), &Argument>,
     right_value: FieldValue,
+    regex_cache: &RegexCache,
     iterator: ContextIterator<'query, Vertex>,
 ) -> ContextIterator<'query, Vertex> {
     match filter {
@@ -454,25 +721,66 @@ fn apply_filter_with_static_argument_value<'query, Vertex: Debug + Clone + 'quer
         Operation::NotHasSubstring(_, _) => {
             apply_filter_op_with_static_argument(right_value, not!(has_substring), iterator)
         }
-        Operation::RegexMatches(_, _) => {
-            let pattern =
-                Regex::new(right_value.as_str().expect("regex argument was not a string"))
-                    .expect("regex argument was not a valid regex");
-            apply_filter_op_with_static_argument(pattern, regex_matches_optimized, iterator)
+        Operation::RegexMatches(_, _)
+        | Operation::NotRegexMatches(_, _)
+        | Operation::RegexMatchesIgnoreCase(_, _)
+        | Operation::NotRegexMatchesIgnoreCase(_, _) => {
+            let FieldValue::String(pattern) = &right_value else {
+                unreachable!("regex argument was not a string: {right_value:?}");
+            };
+            let case_insensitive = matches!(
+                filter,
+                Operation::RegexMatchesIgnoreCase(..) | Operation::NotRegexMatchesIgnoreCase(..)
+            );
+            let regex = regex_cache.get(pattern, case_insensitive);
+            if matches!(filter, Operation::RegexMatches(..) | Operation::RegexMatchesIgnoreCase(..))
+            {
+                apply_filter_op_with_static_argument(regex, regex_matches, iterator)
+            } else {
+                apply_filter_op_with_static_argument(regex, not!(regex_matches), iterator)
+            }
+        }
+        Operation::EqualsIgnoreCase(_, _) => {
+            apply_filter_op_with_static_argument(right_value, equals_ignore_case, iterator)
         }
-        Operation::NotRegexMatches(_, _) => {
-            let pattern =
-                Regex::new(right_value.as_str().expect("regex argument was not a string"))
-                    .expect("regex argument was not a valid regex");
-            apply_filter_op_with_static_argument(pattern, not!(regex_matches_optimized), iterator)
+        Operation::NotEqualsIgnoreCase(_, _) => {
+            apply_filter_op_with_static_argument(right_value, not!(equals_ignore_case), iterator)
         }
+        Operation::HasPrefixIgnoreCase(_, _) => {
+            apply_filter_op_with_static_argument(right_value, has_prefix_ignore_case, iterator)
+        }
+        Operation::NotHasPrefixIgnoreCase(_, _) => apply_filter_op_with_static_argument(
+            right_value,
+            not!(has_prefix_ignore_case),
+            iterator,
+        ),
+        Operation::HasSuffixIgnoreCase(_, _) => {
+            apply_filter_op_with_static_argument(right_value, has_suffix_ignore_case, iterator)
+        }
+        Operation::NotHasSuffixIgnoreCase(_, _) => apply_filter_op_with_static_argument(
+            right_value,
+            not!(has_suffix_ignore_case),
+            iterator,
+        ),
+        Operation::HasSubstringIgnoreCase(_, _) => {
+            apply_filter_op_with_static_argument(right_value, has_substring_ignore_case, iterator)
+        }
+        Operation::NotHasSubstringIgnoreCase(_, _) => apply_filter_op_with_static_argument(
+            right_value,
+            not!(has_substring_ignore_case),
+            iterator,
+        ),
 
-        Operation::IsNull(_) | Operation::IsNotNull(_) => unreachable!("{filter:?}"),
+        Operation::IsNull(_)
+        | Operation::IsNotNull(_)
+        | Operation::Between(..)
+        | Operation::NotBetween(..) => unreachable!("{filter:?}"),
     }
 }
 
 fn apply_filter_with_tagged_argument_value<'query, Vertex: Debug + Clone + 'query>(
     filter: &Operation<(), &Argument>,
+    regex_cache: RegexCache,
     argument_value_iterator: ContextOutcomeIterator<'query, Vertex, TaggedValue>,
 ) -> ContextIterator<'query, Vertex> {
     match filter {
@@ -524,14 +832,61 @@ fn apply_filter_with_tagged_argument_value<'query, Vertex: Debug + Clone + 'quer
         Operation::NotHasSubstring(_, _) => {
             apply_filter_op_with_tagged_argument(not!(has_substring), argument_value_iterator)
         }
-        Operation::RegexMatches(_, _) => {
-            apply_filter_op_with_tagged_argument(regex_matches_slow_path, argument_value_iterator)
+        Operation::RegexMatches(_, _) => apply_regex_filter_with_tagged_argument(
+            regex_cache,
+            false,
+            false,
+            argument_value_iterator,
+        ),
+        Operation::NotRegexMatches(_, _) => apply_regex_filter_with_tagged_argument(
+            regex_cache,
+            false,
+            true,
+            argument_value_iterator,
+        ),
+        Operation::RegexMatchesIgnoreCase(_, _) => apply_regex_filter_with_tagged_argument(
+            regex_cache,
+            true,
+            false,
+            argument_value_iterator,
+        ),
+        Operation::NotRegexMatchesIgnoreCase(_, _) => apply_regex_filter_with_tagged_argument(
+            regex_cache,
+            true,
+            true,
+            argument_value_iterator,
+        ),
+        Operation::EqualsIgnoreCase(_, _) => {
+            apply_filter_op_with_tagged_argument(equals_ignore_case, argument_value_iterator)
+        }
+        Operation::NotEqualsIgnoreCase(_, _) => {
+            apply_filter_op_with_tagged_argument(not!(equals_ignore_case), argument_value_iterator)
         }
-        Operation::NotRegexMatches(_, _) => apply_filter_op_with_tagged_argument(
-            not!(regex_matches_slow_path),
+        Operation::HasPrefixIgnoreCase(_, _) => {
+            apply_filter_op_with_tagged_argument(has_prefix_ignore_case, argument_value_iterator)
+        }
+        Operation::NotHasPrefixIgnoreCase(_, _) => apply_filter_op_with_tagged_argument(
+            not!(has_prefix_ignore_case),
             argument_value_iterator,
         ),
-        Operation::IsNull(_) | Operation::IsNotNull(_) => unreachable!("{filter:?}"),
+        Operation::HasSuffixIgnoreCase(_, _) => {
+            apply_filter_op_with_tagged_argument(has_suffix_ignore_case, argument_value_iterator)
+        }
+        Operation::NotHasSuffixIgnoreCase(_, _) => apply_filter_op_with_tagged_argument(
+            not!(has_suffix_ignore_case),
+            argument_value_iterator,
+        ),
+        Operation::HasSubstringIgnoreCase(_, _) => {
+            apply_filter_op_with_tagged_argument(has_substring_ignore_case, argument_value_iterator)
+        }
+        Operation::NotHasSubstringIgnoreCase(_, _) => apply_filter_op_with_tagged_argument(
+            not!(has_substring_ignore_case),
+            argument_value_iterator,
+        ),
+        Operation::IsNull(_)
+        | Operation::IsNotNull(_)
+        | Operation::Between(..)
+        | Operation::NotBetween(..) => unreachable!("{filter:?}"),
     }
 }
 
@@ -540,7 +895,11 @@ mod tests {
     use std::sync::Arc;
 
     use crate::{
-        interpreter::filtering::{equals, greater_than_or_equal, less_than, less_than_or_equal},
+        interpreter::filtering::{
+            contains, equals, equals_ignore_case, greater_than_or_equal, has_prefix_ignore_case,
+            has_substring_ignore_case, has_suffix_ignore_case, less_than, less_than_or_equal,
+            one_of, regex_matches, RegexCache,
+        },
         ir::FieldValue,
     };
 
@@ -658,6 +1017,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_datetime_comparisons() {
+        let datetime = |text: &str| FieldValue::DateTime(text.parse().expect("invalid date-time"));
+        let earlier = datetime("2024-01-31T23:00:00-02:00");
+        let later = datetime("2024-02-01T01:00:00.5Z");
+
+        assert!(less_than(&earlier, &later));
+        assert!(less_than_or_equal(&earlier, &later));
+        assert!(greater_than(&later, &earlier));
+        assert!(!greater_than_or_equal(&earlier, &later));
+        assert!(!equals(&earlier, &later));
+
+        // The same instant written with different UTC offsets is the same value.
+        let same_instant = datetime("2024-02-01T01:00:00Z");
+        assert!(equals(&earlier, &same_instant));
+        assert!(less_than_or_equal(&earlier, &same_instant));
+        assert!(!less_than(&earlier, &same_instant));
+
+        assert!(!less_than(&earlier, &FieldValue::Null));
+        assert!(!greater_than(&FieldValue::Null, &later));
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_bigint_comparisons() {
+        let big = FieldValue::from(num_bigint::BigInt::from(u128::MAX));
+        let small_big = FieldValue::from(num_bigint::BigInt::from(-2));
+        let test_data = [
+            (big.clone(), FieldValue::Uint64(u64::MAX), true),
+            (big.clone(), FieldValue::Int64(-1), true),
+            (FieldValue::Int64(-1), small_big.clone(), true),
+            (FieldValue::Uint64(0), small_big.clone(), true),
+            (big.clone(), small_big.clone(), true),
+            (small_big.clone(), FieldValue::Int64(-2), false),
+        ];
+
+        for (left, right, expected_outcome) in test_data {
+            assert_eq!(expected_outcome, greater_than(&left, &right), "{left:?} > {right:?}");
+            assert_eq!(expected_outcome, less_than(&right, &left), "{right:?} < {left:?}");
+            assert!(greater_than_or_equal(&left, &right), "{left:?} >= {right:?}");
+            assert!(less_than_or_equal(&right, &left), "{right:?} <= {left:?}");
+            assert_eq!(!expected_outcome, equals(&left, &right), "{left:?} = {right:?}");
+            assert_eq!(!expected_outcome, equals(&right, &left), "{right:?} = {left:?}");
+        }
+
+        assert!(!less_than(&big, &FieldValue::Null));
+        assert!(!equals(&big, &FieldValue::Null));
+    }
+
     #[test]
     fn test_mixed_list_equality_comparison() {
         let test_data = [
@@ -708,4 +1116,107 @@ mod tests {
             assert_eq!(expected_outcome, equals(&right, &left), "{right:?} = {left:?}",);
         }
     }
+
+    #[test]
+    fn test_list_membership_comparisons() {
+        let list = FieldValue::List(Arc::new([FieldValue::Uint64(1), FieldValue::Null]));
+        let test_data = [
+            (FieldValue::Int64(1), true),
+            (FieldValue::Uint64(1), true),
+            (FieldValue::Int64(-1), false),
+            (FieldValue::Null, true),
+        ];
+
+        for (value, expected_outcome) in test_data {
+            assert_eq!(expected_outcome, one_of(&value, &list), "{value:?} one_of {list:?}");
+            assert_eq!(expected_outcome, contains(&list, &value), "{list:?} contains {value:?}");
+        }
+
+        // A null list has no elements, not even null ones.
+        assert!(!one_of(&FieldValue::Null, &FieldValue::Null));
+        assert!(!contains(&FieldValue::Null, &FieldValue::Null));
+    }
+
+    #[test]
+    fn test_regex_cache() {
+        let cache = RegexCache::default();
+        let pattern: Arc<str> = Arc::from("^f.+teen$");
+        let fifteen = FieldValue::String("fifteen".into());
+        let shouting = FieldValue::String("FIFTEEN".into());
+
+        let regex = cache.get(&pattern, false);
+        assert!(regex_matches(&fifteen, &regex));
+        assert!(!regex_matches(&shouting, &regex));
+        assert!(!regex_matches(&FieldValue::Null, &regex));
+
+        // Case-insensitive patterns are compiled and cached separately.
+        let regex = cache.get(&pattern, true);
+        assert!(regex_matches(&fifteen, &regex));
+        assert!(regex_matches(&shouting, &regex));
+
+        // Handles to the same cache share its compiled patterns.
+        let shared = cache.clone();
+        assert!(regex_matches(&shouting, &shared.get(&pattern, true)));
+        assert!(!regex_matches(&shouting, &shared.get(&pattern, false)));
+        assert_eq!(2, cache.inner.lock().unwrap().compiled.len());
+    }
+
+    #[test]
+    fn test_regex_cache_evicts_least_recently_used_patterns() {
+        let cache = RegexCache::default();
+        let patterns: Vec<Arc<str>> =
+            (0..=RegexCache::MAX_PATTERNS).map(|i| Arc::from(format!("^{i}$"))).collect();
+        let (newest, older) = patterns.split_last().unwrap();
+        for pattern in older {
+            cache.get(pattern, false);
+        }
+
+        // Using the first pattern again makes the second one the least recently used,
+        // so it's the one evicted to make room for a new pattern.
+        cache.get(&patterns[0], false);
+        cache.get(newest, false);
+
+        let state = cache.inner.lock().unwrap();
+        assert_eq!(RegexCache::MAX_PATTERNS, state.compiled.len());
+        assert_eq!(RegexCache::MAX_PATTERNS, state.recency.len());
+        assert!(state.compiled.contains_key(&(patterns[0].clone(), false)));
+        assert!(!state.compiled.contains_key(&(patterns[1].clone(), false)));
+        assert!(state.compiled.contains_key(&(newest.clone(), false)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the value \"(unclosed\" is not a valid regex pattern")]
+    fn test_regex_cache_rejects_invalid_patterns() {
+        RegexCache::default().get(&Arc::from("(unclosed"), false);
+    }
+
+    #[test]
+    fn test_ignore_case_string_comparisons() {
+        let test_data = [
+            ("Hello World", "hello world", true, true, true, true),
+            ("Hello World", "HELLO", false, true, false, true),
+            ("Hello World", "wORLD", false, false, true, true),
+            ("Hello World", "LO wo", false, false, false, true),
+            ("Straße", "STRASSE", false, false, false, false),
+            ("ÉCOLE", "école", true, true, true, true),
+            ("Hello", "hello world", false, false, false, false),
+        ];
+
+        for (left, right, equals, prefix, suffix, substring) in test_data {
+            let left = FieldValue::String(left.into());
+            let right = FieldValue::String(right.into());
+            assert_eq!(equals, equals_ignore_case(&left, &right), "{left:?} =i {right:?}");
+            assert_eq!(prefix, has_prefix_ignore_case(&left, &right), "{left:?} {right:?}");
+            assert_eq!(suffix, has_suffix_ignore_case(&left, &right), "{left:?} {right:?}");
+            assert_eq!(substring, has_substring_ignore_case(&left, &right), "{left:?} {right:?}");
+        }
+
+        let value = FieldValue::String("value".into());
+        for (left, right) in [(&FieldValue::Null, &value), (&value, &FieldValue::Null)] {
+            assert!(!equals_ignore_case(left, right));
+            assert!(!has_prefix_ignore_case(left, right));
+            assert!(!has_suffix_ignore_case(left, right));
+            assert!(!has_substring_ignore_case(left, right));
+        }
+    }
 }
@@ -0,0 +1,1024 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::Write,
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    ir::{EdgeParameters, FieldValue, TransparentValue},
+    schema::{error::InvalidSchemaError, get_builtin_scalars, Schema},
+};
+
+use super::{
+    helpers::{resolve_coercion_with, resolve_neighbors_with, resolve_property_with},
+    json_adapter::{field_type_name, json_to_field_value},
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, JsonVertex, ResolveEdgeInfo,
+    ResolveInfo, VertexInfo, VertexIterator,
+};
+
+/// Sends GraphQL requests to a remote GraphQL API on behalf of a [`GraphQLAdapter`].
+///
+/// Implement this with the HTTP client of your choice.
+/// Closures with the same signature as [`GraphQLTransport::execute`] implement it too.
+pub trait GraphQLTransport {
+    /// Execute the GraphQL query with the given variables,
+    /// returning the API's JSON response such as `{"data": {...}}`.
+    fn execute(
+        &self,
+        query: &str,
+        variables: &Map<String, Value>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>>;
+}
+
+impl<F> GraphQLTransport for F
+where
+    F: Fn(&str, &Map<String, Value>) -> Result<Value, Box<dyn Error + Send + Sync>>,
+{
+    fn execute(
+        &self,
+        query: &str,
+        variables: &Map<String, Value>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self(query, variables)
+    }
+}
+
+/// An [`Adapter`] that queries a remote GraphQL API.
+///
+/// The adapter's schema is derived from the API's introspection schema,
+/// as returned by running [`GraphQLAdapter::INTROSPECTION_QUERY`] against the API:
+/// - Object and interface types become vertex types. Union types become interfaces
+///   without fields, implemented by each of their members.
+/// - Fields of scalar and enum types become properties. Enums and custom scalars
+///   are represented as `String` values.
+/// - Fields of object, interface, and union types become edges, whose scalar and enum
+///   arguments are the edge's parameters. Parameters with default values in the remote schema
+///   are nullable, and the remote default applies when they are `null`.
+/// - Fields that require arguments Trustfall can't supply, such as input objects,
+///   are left out, as are properties with required arguments.
+///
+/// Each query is translated into a single request to the remote API, made when the query
+/// starts executing. The request only selects the properties and edges the query uses,
+/// and passes edge parameters as GraphQL variables. Query filters are applied locally,
+/// so that filtering is only pushed down to the API through edge parameters.
+/// Bounded `@recurse` edges are expanded to their maximum depth,
+/// and unbounded ones are not supported.
+///
+/// Vertices are the JSON objects within the API's response. Combine this adapter with others,
+/// for example using [`CompositeAdapter`](super::CompositeAdapter), to join remote data
+/// with local sources within a single query.
+///
+/// Failed requests, and responses that report errors, cause a panic.
+///
+/// # Examples
+/// ```
+/// # use std::{collections::BTreeMap, error::Error, sync::Arc};
+/// # use serde_json::{json, Map, Value};
+/// # use trustfall_core::{
+/// #     frontend::parse,
+/// #     interpreter::{execution::interpret_ir, GraphQLAdapter},
+/// # };
+/// # let introspection = json!({"__schema": {
+/// #     "queryType": {"name": "Query"},
+/// #     "types": [
+/// #         {"kind": "OBJECT", "name": "Query", "fields": [{
+/// #             "name": "repository",
+/// #             "args": [{"name": "name", "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "String"}}}],
+/// #             "type": {"kind": "OBJECT", "name": "Repository"}
+/// #         }]},
+/// #         {"kind": "OBJECT", "name": "Repository", "fields": [
+/// #             {"name": "stars", "args": [], "type": {"kind": "SCALAR", "name": "Int"}}
+/// #         ]},
+/// #         {"kind": "SCALAR", "name": "String"},
+/// #         {"kind": "SCALAR", "name": "Int"}
+/// #     ]
+/// # }});
+/// let transport = |query: &str,
+///                  variables: &Map<String, Value>|
+///  -> Result<Value, Box<dyn Error + Send + Sync>> {
+///     // Send the query and variables to the API using an HTTP client of your choice,
+///     // then return the API's response.
+///     # let _ = query;
+///     # assert_eq!(Some(&json!("trustfall")), variables.get("root_name"));
+///     # Ok(json!({"data": {"root": {"__typename": "Repository", "stars": 2000}}}))
+/// };
+/// let adapter = GraphQLAdapter::from_introspection(&introspection, transport)
+///     .expect("invalid introspection response");
+///
+/// let query = r#"
+/// {
+///     repository(name: "trustfall") {
+///         stars @output
+///     }
+/// }"#;
+/// let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+/// let results: Vec<_> = interpret_ir(Arc::new(adapter), indexed_query, Arc::new(BTreeMap::new()))
+///     .expect("invalid arguments")
+///     .collect();
+///
+/// assert_eq!(results[0]["stars"], 2000.into());
+/// ```
+#[derive(Clone)]
+pub struct GraphQLAdapter {
+    schema: Arc<Schema>,
+    schema_text: String,
+
+    /// The edges of each vertex type, by name, including the root query type's edges.
+    edges: RemoteEdges,
+    transport: Arc<dyn GraphQLTransport + Send + Sync>,
+}
+
+impl std::fmt::Debug for GraphQLAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphQLAdapter")
+            .field("schema_text", &self.schema_text)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Errors that may arise while deriving a schema from a GraphQL introspection response.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GraphQLIntrospectionError {
+    #[error("The introspection response has no \"__schema\" key.")]
+    MissingSchema,
+
+    #[error("The introspection response is invalid: {0}")]
+    InvalidIntrospection(String),
+
+    #[error("The schema derived from the introspection response is invalid: {0}")]
+    InvalidSchema(InvalidSchemaError),
+}
+
+/// The edges of each vertex type, by name.
+type RemoteEdges = BTreeMap<Arc<str>, BTreeMap<Arc<str>, RemoteEdge>>;
+
+#[derive(Debug, Clone)]
+struct RemoteEdge {
+    target: Arc<str>,
+    arguments: Vec<RemoteArgument>,
+}
+
+#[derive(Debug, Clone)]
+struct RemoteArgument {
+    name: Arc<str>,
+
+    /// The argument's type in the remote schema, for declaring variables that hold its value.
+    ty: String,
+    has_default: bool,
+}
+
+impl GraphQLAdapter {
+    /// The GraphQL query whose response [`GraphQLAdapter::from_introspection()`] expects.
+    pub const INTROSPECTION_QUERY: &'static str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    types {
+      kind
+      name
+      fields(includeDeprecated: true) {
+        name
+        args { name type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      interfaces { name }
+      possibleTypes { name }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType { kind name ofType { kind name ofType { kind name } } }
+        }
+      }
+    }
+  }
+}
+"#;
+
+    /// Make an adapter for the API described by the given introspection response,
+    /// which will send its requests through the given transport.
+    ///
+    /// The response may be either the full response to [`GraphQLAdapter::INTROSPECTION_QUERY`],
+    /// or just its `data` value.
+    pub fn from_introspection(
+        introspection: &Value,
+        transport: impl GraphQLTransport + Send + Sync + 'static,
+    ) -> Result<Self, GraphQLIntrospectionError> {
+        let schema_value = introspection
+            .get("data")
+            .unwrap_or(introspection)
+            .get("__schema")
+            .ok_or(GraphQLIntrospectionError::MissingSchema)?;
+        let introspected = IntrospectionSchema::deserialize(schema_value)
+            .map_err(|e| GraphQLIntrospectionError::InvalidIntrospection(e.to_string()))?;
+
+        let (schema_text, edges) = introspected.convert();
+        let schema =
+            Schema::parse(&schema_text).map_err(GraphQLIntrospectionError::InvalidSchema)?;
+
+        Ok(Self { schema: Arc::new(schema), schema_text, edges, transport: Arc::new(transport) })
+    }
+
+    /// The schema derived from the remote API's schema. Queries must conform to it.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The text of [`GraphQLAdapter::schema()`].
+    pub fn schema_text(&self) -> &str {
+        &self.schema_text
+    }
+}
+
+impl<'vertex> Adapter<'vertex> for GraphQLAdapter {
+    type Vertex = JsonVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let query_type: Arc<str> = Arc::from(self.schema.query_type_name());
+        let edge = &self.edges[&query_type][edge_name];
+
+        let mut builder = QueryBuilder { edges: &self.edges, variables: BTreeMap::new() };
+        let arguments = builder.arguments("root", edge, parameters);
+        let selection = builder.select(resolve_info, &edge.target);
+        let (query, variables) = builder.finish(edge_name, &arguments, &selection);
+
+        let response = self
+            .transport
+            .execute(&query, &variables)
+            .unwrap_or_else(|e| panic!("GraphQL request failed: {e}"));
+        if let Some(errors) = response.get("errors").filter(|errors| match errors {
+            Value::Array(errors) => !errors.is_empty(),
+            errors => !errors.is_null(),
+        }) {
+            panic!("GraphQL request returned errors: {errors}");
+        }
+
+        let data = match response.get("data") {
+            Some(data @ Value::Object(..)) => data.clone(),
+            _ => panic!("GraphQL response has no data: {response}"),
+        };
+        let root = JsonVertex::new(query_type, Arc::new(data), Arc::from(""));
+        Box::new(root.neighbors("root", &edge.target, &self.schema).into_iter())
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            return resolve_property_with(contexts, |vertex: &JsonVertex| vertex.typename().into());
+        }
+
+        let property_type = field_type_name(&self.schema, type_name, property_name);
+        let numbers_as_floats = property_type.as_ref() == "Float";
+        let as_strings = matches!(property_type.as_ref(), "String" | "ID");
+        let property_name = property_name.clone();
+        resolve_property_with(contexts, move |vertex: &JsonVertex| {
+            match vertex.value().get(property_name.as_ref()) {
+                None => FieldValue::Null,
+                Some(value) if as_strings => string_field_value(value),
+                Some(value) => json_to_field_value(value, numbers_as_floats),
+            }
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let neighbor_type = field_type_name(&self.schema, type_name, edge_name);
+        let alias = edge_alias(resolve_info.eid().0.get());
+        let schema = self.schema.clone();
+        resolve_neighbors_with(contexts, move |vertex: &JsonVertex| {
+            Box::new(vertex.neighbors(&alias, &neighbor_type, &schema).into_iter())
+        })
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        _type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let coerce_to_type = coerce_to_type.clone();
+        let schema = self.schema.clone();
+        resolve_coercion_with(contexts, move |vertex: &JsonVertex| {
+            schema.is_named_type_subtype(&coerce_to_type, vertex.typename())
+        })
+    }
+}
+
+/// The alias under which the remote query selects the edge with the given ID.
+fn edge_alias(eid: usize) -> String {
+    format!("e{eid}")
+}
+
+/// Convert a value of a `String` or `ID` property. Remote APIs may represent these,
+/// and the custom scalars mapped to them, as other kinds of JSON values.
+fn string_field_value(value: &Value) -> FieldValue {
+    match value {
+        Value::Null => FieldValue::Null,
+        Value::String(s) => s.as_str().into(),
+        Value::Array(elements) => {
+            FieldValue::List(elements.iter().map(string_field_value).collect())
+        }
+        other => other.to_string().into(),
+    }
+}
+
+/// The fields a remote query selects for a vertex,
+/// grouped by the type within whose inline fragment they are selected.
+#[derive(Debug, Clone, Default)]
+struct Selection(BTreeMap<Arc<str>, BTreeSet<String>>);
+
+impl Selection {
+    fn merge(&mut self, other: Selection) {
+        for (type_name, fields) in other.0 {
+            self.0.entry(type_name).or_default().extend(fields);
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::from("{ __typename");
+        for (type_name, fields) in &self.0 {
+            if !fields.is_empty() {
+                write!(text, " ... on {type_name} {{").expect("write failed");
+                for field in fields {
+                    write!(text, " {field}").expect("write failed");
+                }
+                text.push_str(" }");
+            }
+        }
+        text.push_str(" }");
+        text
+    }
+}
+
+/// Translates the part of a Trustfall query starting at a root edge into a remote query.
+struct QueryBuilder<'a> {
+    edges: &'a RemoteEdges,
+
+    /// The remote query's variables, with their types and values.
+    variables: BTreeMap<String, (String, Value)>,
+}
+
+impl QueryBuilder<'_> {
+    /// The argument list for an edge, binding its parameters to variables with the given prefix.
+    fn arguments(
+        &mut self,
+        prefix: &str,
+        edge: &RemoteEdge,
+        parameters: &EdgeParameters,
+    ) -> String {
+        let mut arguments = vec![];
+        for argument in &edge.arguments {
+            let value = parameters.get(&argument.name).cloned().unwrap_or_default();
+            if matches!(value, FieldValue::Null) && argument.has_default {
+                // Leave out the argument, so that its remote default value applies.
+                continue;
+            }
+
+            let variable = format!("{prefix}_{}", argument.name);
+            arguments.push(format!("{}: ${variable}", argument.name));
+            let value = serde_json::to_value(TransparentValue::from(value))
+                .expect("failed to serialize edge parameter");
+            self.variables.insert(variable, (argument.ty.clone(), value));
+        }
+
+        if arguments.is_empty() {
+            String::new()
+        } else {
+            format!("({})", arguments.join(", "))
+        }
+    }
+
+    /// The fields to select for the vertex described by `info`,
+    /// which is reached through an edge pointing to `edge_type`.
+    fn select(&mut self, info: &impl VertexInfo, edge_type: &Arc<str>) -> Selection {
+        let vertex_type = info.coerced_to_type().unwrap_or(edge_type).clone();
+        let mut fields: BTreeSet<String> = info
+            .required_properties()
+            .filter(|property| property.name.as_ref() != "__typename")
+            .map(|property| property.name.to_string())
+            .collect();
+        let mut recursed = Selection::default();
+
+        for (edge_name, edge) in self.edges.get(&vertex_type).into_iter().flatten() {
+            for edge_info in info.edges_with_name(edge_name) {
+                let alias = edge_alias(edge_info.eid().0.get());
+                let arguments = self.arguments(&alias, edge, edge_info.parameters());
+                let destination = self.select(edge_info.destination(), &edge.target);
+                let edge_field = |selection: &Selection| {
+                    format!("{alias}: {edge_name}{arguments} {}", selection.render())
+                };
+
+                match edge_info.recursive() {
+                    None => {
+                        fields.insert(edge_field(&destination));
+                    }
+                    Some(recursive) => {
+                        let depth = recursive
+                            .depth
+                            .expect("GraphQLAdapter does not support unbounded @recurse")
+                            .get();
+
+                        let mut nested = destination.clone();
+                        for _ in 1..depth {
+                            let mut level = destination.clone();
+                            level
+                                .0
+                                .entry(vertex_type.clone())
+                                .or_default()
+                                .insert(edge_field(&nested));
+                            nested = level;
+                        }
+                        fields.insert(edge_field(&nested));
+
+                        // Recursion includes the current vertex itself at depth zero,
+                        // so it needs the same fields as the recursed-to vertices.
+                        recursed.merge(destination);
+                    }
+                }
+            }
+        }
+
+        let mut selection = Selection(BTreeMap::from([(vertex_type, fields)]));
+        selection.merge(recursed);
+        selection
+    }
+
+    /// The remote query's text and its variables' values.
+    fn finish(
+        self,
+        root_edge: &str,
+        arguments: &str,
+        selection: &Selection,
+    ) -> (String, Map<String, Value>) {
+        let mut query = String::from("query");
+        if !self.variables.is_empty() {
+            let declarations: Vec<_> =
+                self.variables.iter().map(|(name, (ty, _))| format!("${name}: {ty}")).collect();
+            write!(query, "({})", declarations.join(", ")).expect("write failed");
+        }
+        write!(query, " {{ root: {root_edge}{arguments} {} }}", selection.render())
+            .expect("write failed");
+
+        let variables =
+            self.variables.into_iter().map(|(name, (_, value))| (name, value)).collect();
+        (query, variables)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionSchema {
+    query_type: NamedTypeRef,
+    types: Vec<IntrospectionType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedTypeRef {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionType {
+    kind: TypeKind,
+    name: String,
+    #[serde(default)]
+    fields: Option<Vec<IntrospectionField>>,
+    #[serde(default)]
+    interfaces: Option<Vec<NamedTypeRef>>,
+    #[serde(default)]
+    possible_types: Option<Vec<NamedTypeRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionField {
+    name: String,
+    #[serde(default)]
+    args: Vec<IntrospectionInputValue>,
+    #[serde(rename = "type")]
+    ty: TypeRef,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionInputValue {
+    name: String,
+    #[serde(rename = "type")]
+    ty: TypeRef,
+    #[serde(default)]
+    default_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: TypeKind,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    of_type: Option<Box<TypeRef>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum TypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+    List,
+    NonNull,
+}
+
+impl TypeRef {
+    /// The named type at the core of this type, with its kind.
+    fn base(&self) -> (TypeKind, &str) {
+        match (&self.kind, &self.of_type) {
+            (TypeKind::List | TypeKind::NonNull, Some(inner)) => inner.base(),
+            _ => (self.kind, self.name.as_deref().unwrap_or_default()),
+        }
+    }
+
+    fn list_depth(&self) -> usize {
+        match (&self.kind, &self.of_type) {
+            (TypeKind::List, Some(inner)) => 1 + inner.list_depth(),
+            (TypeKind::NonNull, Some(inner)) => inner.list_depth(),
+            _ => 0,
+        }
+    }
+
+    /// This type, written with the given name in place of its named type.
+    fn render_with_base(&self, base: &str) -> String {
+        match (&self.kind, &self.of_type) {
+            (TypeKind::List, Some(inner)) => format!("[{}]", inner.render_with_base(base)),
+            (TypeKind::NonNull, Some(inner)) => format!("{}!", inner.render_with_base(base)),
+            _ => base.to_string(),
+        }
+    }
+
+    fn render(&self) -> String {
+        self.render_with_base(self.base().1)
+    }
+
+    /// The name of the Trustfall type representing this type's scalar or enum values.
+    fn property_base(&self) -> Option<&str> {
+        match self.base() {
+            (TypeKind::Scalar, name) if get_builtin_scalars().contains(name) => Some(name),
+            (TypeKind::Scalar | TypeKind::Enum, _) => Some("String"),
+            _ => None,
+        }
+    }
+}
+
+impl IntrospectionInputValue {
+    fn is_required(&self) -> bool {
+        self.ty.kind == TypeKind::NonNull && self.default_value.is_none()
+    }
+}
+
+impl IntrospectionSchema {
+    /// The text of the equivalent Trustfall schema, and the edges it defines.
+    fn convert(&self) -> (String, RemoteEdges) {
+        let query_type = self.query_type.name.as_str();
+        let vertex_types: Vec<_> = self
+            .types
+            .iter()
+            .filter(|ty| {
+                matches!(ty.kind, TypeKind::Object | TypeKind::Interface | TypeKind::Union)
+                    && !ty.name.starts_with("__")
+            })
+            .collect();
+
+        // Unions become interfaces, which their members implement.
+        let mut unions_of_member: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for ty in vertex_types.iter().filter(|ty| ty.kind == TypeKind::Union) {
+            for member in ty.possible_types.iter().flatten() {
+                unions_of_member.entry(member.name.as_str()).or_default().push(&ty.name);
+            }
+        }
+
+        let mut text = format!("schema {{\n    query: {query_type}\n}}\n");
+        let mut edges: RemoteEdges = BTreeMap::new();
+        for ty in vertex_types {
+            let keyword = if ty.kind == TypeKind::Object { "type" } else { "interface" };
+            write!(text, "\n{keyword} {}", ty.name).expect("write failed");
+
+            let mut implements: Vec<&str> = vec![];
+            if ty.name != query_type {
+                implements.extend(ty.interfaces.iter().flatten().map(|i| i.name.as_str()));
+                implements.extend(unions_of_member.get(ty.name.as_str()).into_iter().flatten());
+            }
+            if !implements.is_empty() {
+                write!(text, " implements {}", implements.join(" & ")).expect("write failed");
+            }
+
+            let type_edges = edges.entry(Arc::from(ty.name.as_str())).or_default();
+            let mut fields = String::new();
+            for field in ty.fields.iter().flatten() {
+                if field.name.starts_with("__") {
+                    continue;
+                }
+
+                if let Some(base) = field.ty.property_base() {
+                    if ty.name != query_type && !field.args.iter().any(|arg| arg.is_required()) {
+                        writeln!(fields, "    {}: {}", field.name, field.ty.render_with_base(base))
+                            .expect("write failed");
+                    }
+                    continue;
+                }
+
+                let (_, target) = field.ty.base();
+                if field.ty.list_depth() > 1 || target == query_type || target.starts_with("__") {
+                    continue;
+                }
+
+                if field
+                    .args
+                    .iter()
+                    .any(|arg| arg.is_required() && arg.ty.property_base().is_none())
+                {
+                    continue;
+                }
+
+                // Arguments of other kinds, like input objects, are optional and left out.
+                let mut parameters = vec![];
+                let mut arguments = vec![];
+                for arg in &field.args {
+                    if let Some(base) = arg.ty.property_base() {
+                        let mut parameter_type = arg.ty.render_with_base(base);
+                        if arg.default_value.is_some() {
+                            parameter_type = parameter_type.trim_end_matches('!').to_string();
+                        }
+                        parameters.push(format!("{}: {parameter_type}", arg.name));
+                        arguments.push(RemoteArgument {
+                            name: Arc::from(arg.name.as_str()),
+                            ty: arg.ty.render(),
+                            has_default: arg.default_value.is_some(),
+                        });
+                    }
+                }
+
+                let parameters = if parameters.is_empty() {
+                    String::new()
+                } else {
+                    format!("({})", parameters.join(", "))
+                };
+                writeln!(fields, "    {}{parameters}: {}", field.name, field.ty.render())
+                    .expect("write failed");
+                type_edges.insert(
+                    Arc::from(field.name.as_str()),
+                    RemoteEdge { target: Arc::from(target), arguments },
+                );
+            }
+
+            if fields.is_empty() {
+                text.push('\n');
+            } else {
+                write!(text, " {{\n{fields}}}\n").expect("write failed");
+            }
+        }
+
+        (text, edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::{json, Map, Value};
+
+    use super::{GraphQLAdapter, GraphQLIntrospectionError};
+    use crate::{
+        frontend::parse,
+        interpreter::{execution::interpret_ir, helpers::check_adapter_invariants},
+        ir::FieldValue,
+        schema::Schema,
+    };
+
+    type Requests = Arc<Mutex<Vec<(String, Map<String, Value>)>>>;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({"kind": kind, "name": name})
+    }
+
+    fn non_null(inner: Value) -> Value {
+        json!({"kind": "NON_NULL", "ofType": inner})
+    }
+
+    fn list(inner: Value) -> Value {
+        json!({"kind": "LIST", "ofType": inner})
+    }
+
+    fn field(name: &str, ty: Value, args: Value) -> Value {
+        json!({"name": name, "type": ty, "args": args})
+    }
+
+    fn arg(name: &str, ty: Value, default_value: Option<&str>) -> Value {
+        json!({"name": name, "type": ty, "defaultValue": default_value})
+    }
+
+    fn introspection() -> Value {
+        let string = || named("SCALAR", "String");
+        let user = || named("OBJECT", "User");
+        json!({"data": {"__schema": {
+            "queryType": {"name": "Query"},
+            "types": [
+                {"kind": "OBJECT", "name": "Query", "interfaces": [], "fields": [
+                    field("users", non_null(list(non_null(user()))), json!([
+                        arg("first", named("SCALAR", "Int"), Some("10")),
+                        arg("role", named("ENUM", "Role"), None),
+                    ])),
+                    field("search", list(named("UNION", "SearchResult")), json!([
+                        arg("text", non_null(string()), None),
+                    ])),
+                    field("filtered", list(user()), json!([
+                        arg("where", non_null(named("INPUT_OBJECT", "UserFilter")), None),
+                    ])),
+                    field("version", string(), json!([])),
+                ]},
+                {"kind": "INTERFACE", "name": "Node", "interfaces": [], "fields": [
+                    field("id", non_null(named("SCALAR", "ID")), json!([])),
+                ]},
+                {"kind": "OBJECT", "name": "User", "interfaces": [named("INTERFACE", "Node")], "fields": [
+                    field("id", non_null(named("SCALAR", "ID")), json!([])),
+                    field("name", string(), json!([])),
+                    field("role", named("ENUM", "Role"), json!([])),
+                    field("joined", named("SCALAR", "DateTime"), json!([])),
+                    field("avatar", string(), json!([arg("size", non_null(named("SCALAR", "Int")), None)])),
+                    field("manager", user(), json!([])),
+                    field("friends", non_null(list(non_null(user()))), json!([
+                        arg("first", named("SCALAR", "Int"), None),
+                        arg("order", named("INPUT_OBJECT", "Order"), None),
+                    ])),
+                ]},
+                {"kind": "OBJECT", "name": "Team", "interfaces": [], "fields": [
+                    field("name", string(), json!([])),
+                ]},
+                {"kind": "UNION", "name": "SearchResult", "possibleTypes": [user(), named("OBJECT", "Team")]},
+                {"kind": "ENUM", "name": "Role"},
+                {"kind": "SCALAR", "name": "DateTime"},
+                {"kind": "INPUT_OBJECT", "name": "UserFilter"},
+                {"kind": "OBJECT", "name": "__Schema", "interfaces": [], "fields": []},
+            ],
+        }}})
+    }
+
+    /// An adapter whose transport records the requests it receives,
+    /// and responds with the given response.
+    fn adapter(response: Value) -> (GraphQLAdapter, Requests) {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let recorded = requests.clone();
+        let transport = move |query: &str,
+                              variables: &Map<String, Value>|
+              -> Result<Value, Box<dyn Error + Send + Sync>> {
+            recorded.lock().unwrap().push((query.to_string(), variables.clone()));
+            Ok(response.clone())
+        };
+        let adapter = GraphQLAdapter::from_introspection(&introspection(), transport)
+            .expect("invalid introspection");
+        (adapter, requests)
+    }
+
+    fn run_query(
+        adapter: GraphQLAdapter,
+        query: &str,
+        arguments: BTreeMap<Arc<str>, FieldValue>,
+    ) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+        interpret_ir(Arc::new(adapter), indexed_query, Arc::new(arguments))
+            .expect("invalid arguments")
+            .collect()
+    }
+
+    #[test]
+    fn converted_schema() {
+        let (adapter, _) = adapter(json!({}));
+        let expected = "\
+schema {
+    query: Query
+}
+
+type Query {
+    users(first: Int, role: String): [User!]!
+    search(text: String!): [SearchResult]
+}
+
+interface Node {
+    id: ID!
+}
+
+type User implements Node & SearchResult {
+    id: ID!
+    name: String
+    role: String
+    joined: String
+    manager: User
+    friends(first: Int): [User!]!
+}
+
+type Team implements SearchResult {
+    name: String
+}
+
+interface SearchResult
+";
+        similar_asserts::assert_eq!(expected, adapter.schema_text());
+        Schema::parse(adapter.schema_text()).expect("not a valid schema");
+    }
+
+    #[test]
+    fn invalid_introspection() {
+        let transport = |_: &str,
+                         _: &Map<String, Value>|
+         -> Result<Value, Box<dyn Error + Send + Sync>> { Ok(Value::Null) };
+        assert_eq!(
+            GraphQLIntrospectionError::MissingSchema,
+            GraphQLAdapter::from_introspection(&json!({"data": {}}), transport).unwrap_err(),
+        );
+        assert!(matches!(
+            GraphQLAdapter::from_introspection(&json!({"__schema": {"types": []}}), transport),
+            Err(GraphQLIntrospectionError::InvalidIntrospection(..)),
+        ));
+    }
+
+    #[test]
+    fn query_translation_and_results() {
+        let response = json!({"data": {"root": [
+            {"__typename": "User", "id": 1, "name": "alice", "joined": "2020-01-01", "e1": [
+                {"__typename": "User", "name": "bob"},
+                {"__typename": "User", "name": "carol"},
+            ]},
+            {"__typename": "User", "id": "2", "name": "dave", "joined": 1600000000, "e1": []},
+        ]}});
+        let (adapter, requests) = adapter(response);
+
+        let query = r#"
+{
+    users(role: "ADMIN") {
+        id @output
+        name @output @filter(op: "!=", value: ["$excluded"])
+        joined @output
+
+        friends(first: 2) {
+            friend: name @output
+        }
+    }
+}"#;
+        let rows =
+            run_query(adapter, query, BTreeMap::from([(Arc::from("excluded"), "dave".into())]));
+
+        let (query, variables) = requests.lock().unwrap().pop().expect("no request was made");
+        assert_eq!(
+            "query($e1_first: Int, $root_role: Role) { root: users(role: $root_role) \
+            { __typename ... on User { e1: friends(first: $e1_first) \
+            { __typename ... on User { name } } id joined name } } }",
+            query,
+        );
+        assert_eq!(
+            Map::from_iter([
+                ("e1_first".to_string(), json!(2)),
+                ("root_role".to_string(), json!("ADMIN")),
+            ]),
+            variables,
+        );
+
+        let row = |friend: &str| {
+            BTreeMap::from([
+                (Arc::from("id"), "1".into()),
+                (Arc::from("name"), "alice".into()),
+                (Arc::from("joined"), "2020-01-01".into()),
+                (Arc::from("friend"), friend.into()),
+            ])
+        };
+        assert_eq!(vec![row("bob"), row("carol")], rows);
+    }
+
+    #[test]
+    fn union_coercion_and_recursion() {
+        let response = json!({"data": {"root": [
+            {"__typename": "Team", "name": "core"},
+            {"__typename": "User", "name": "alice", "e1": {
+                "__typename": "User", "name": "bob", "e1": {"__typename": "User", "name": "carol"},
+            }},
+        ]}});
+        let (adapter, requests) = adapter(response);
+
+        let query = r#"
+{
+    search(text: "a") {
+        ... on User {
+            kind: __typename @output
+
+            manager @recurse(depth: 2) {
+                name @output
+            }
+        }
+    }
+}"#;
+        let rows = run_query(adapter, query, BTreeMap::new());
+
+        let (query, _) = requests.lock().unwrap().pop().expect("no request was made");
+        assert_eq!(
+            "query($root_text: String!) { root: search(text: $root_text) \
+            { __typename ... on User { e1: manager { __typename ... on User \
+            { e1: manager { __typename ... on User { name } } name } } name } } }",
+            query,
+        );
+
+        let names: Vec<_> = rows.iter().map(|row| (&row["kind"], &row["name"])).collect();
+        let user = FieldValue::from("User");
+        assert_eq!(
+            vec![(&user, &"alice".into()), (&user, &"bob".into()), (&user, &"carol".into())],
+            names,
+        );
+    }
+
+    #[test]
+    fn properties_tagged_for_folds_are_selected() {
+        let response = json!({"data": {"root": [
+            {"__typename": "User", "name": "alice", "e1": [
+                {"__typename": "User", "name": "alice"},
+                {"__typename": "User", "name": "bob"},
+            ]},
+        ]}});
+        let (adapter, requests) = adapter(response);
+
+        let query = r#"
+{
+    users {
+        name @tag
+        friends @fold @transform(op: "count") @output(name: "namesakes") {
+            name @filter(op: "=", value: ["%name"])
+        }
+    }
+}"#;
+        let rows = run_query(adapter, query, BTreeMap::new());
+
+        let (query, _) = requests.lock().unwrap().pop().expect("no request was made");
+        assert_eq!(
+            "query($e1_first: Int, $root_role: Role) { root: users(role: $root_role) \
+            { __typename ... on User \
+            { e1: friends(first: $e1_first) { __typename ... on User { name } } name } } }",
+            query,
+        );
+        assert_eq!(vec![BTreeMap::from([(Arc::from("namesakes"), 1u64.into())])], rows);
+    }
+
+    #[test]
+    #[should_panic(expected = "GraphQL request returned errors")]
+    fn remote_errors_panic() {
+        let (adapter, _) = adapter(json!({"data": null, "errors": [{"message": "oops"}]}));
+        run_query(adapter, "{ users { name @output } }", BTreeMap::new());
+    }
+
+    #[test]
+    fn adapter_invariants() {
+        let (adapter, _) = adapter(json!({"data": {"root": null}}));
+        let schema = adapter.schema().clone();
+        check_adapter_invariants(&schema, adapter);
+    }
+}
@@ -0,0 +1,410 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData, rc::Rc, sync::Arc};
+
+use crate::ir::{Argument, EdgeParameters, FieldValue, Operation};
+
+use super::{
+    Adapter, AsVertex, CandidateValue, ContextIterator, ContextOutcomeIterator, DataContext,
+    RequiredProperty, ResolveEdgeInfo, ResolveInfo, VertexIdentifier, VertexInfo, VertexIterator,
+};
+
+/// An [`Adapter`] that joins sibling edges on tagged values using a hash table,
+/// instead of scanning an edge's neighbors again for each tagged value.
+///
+/// Consider a query that matches up the neighbors along two edges of the same vertex:
+/// ```graphql
+/// {
+///     Email {
+///         sender {
+///             address @tag(name: "sender")
+///         }
+///         recipient {
+///             address @filter(op: "=", value: ["%sender"])
+///         }
+///     }
+/// }
+/// ```
+/// Each email's `recipient` edge is resolved once per sender, and each time
+/// all its recipients are checked against that sender's address. With this adapter,
+/// an email's recipients are resolved only the first time. If the same email comes up again,
+/// its recipients' addresses are resolved once and indexed, and that index is probed
+/// for each later sender instead.
+///
+/// This applies to edges whose destination has an `=` or `one_of` filter using a tagged value,
+/// when the filter's property is not transformed and the destination has no type coercion.
+/// Other edges, as well as properties and type coercions, are resolved by the wrapped adapter
+/// as usual. Each edge's neighbors and index are only kept while consecutive contexts
+/// have the same origin vertex, as defined by the vertex type's [`Hash`] and [`Eq`].
+///
+/// The wrapped adapter must resolve the same neighbors for a vertex in every context.
+/// In particular, it must not narrow them down based on that context's
+/// [`dynamically_required_property()`](super::VertexInfo::dynamically_required_property) value,
+/// since other contexts reuse those neighbors.
+pub struct HashJoinAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: Hash + Eq,
+{
+    inner: Arc<AdapterT>,
+    _phantom: PhantomData<&'vertex ()>,
+}
+
+impl<'vertex, AdapterT> HashJoinAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: Hash + Eq,
+{
+    /// Wrap an adapter, joining sibling edges on tagged values where possible.
+    pub fn new(adapter: AdapterT) -> Self {
+        Self::from_arc(Arc::new(adapter))
+    }
+
+    /// Wrap an adapter that is also used elsewhere, sharing it instead of taking ownership.
+    pub fn from_arc(adapter: Arc<AdapterT>) -> Self {
+        Self { inner: adapter, _phantom: PhantomData }
+    }
+
+    /// The adapter whose edges are being joined.
+    pub fn inner(&self) -> &AdapterT {
+        &self.inner
+    }
+}
+
+impl<'vertex, AdapterT> Debug for HashJoinAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + Debug,
+    AdapterT::Vertex: Hash + Eq,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashJoinAdapter").field("inner", &self.inner).finish()
+    }
+}
+
+/// The property of the edge's destination vertex that must match a tagged value, if any.
+fn join_property(resolve_info: &ResolveEdgeInfo) -> Option<Arc<str>> {
+    let destination = resolve_info.destination_vertex();
+    if destination.coerced_from_type.is_some() {
+        // Not all neighbors have the coerced type's properties.
+        return None;
+    }
+
+    destination.filters.iter().find_map(|filter| match filter {
+        Operation::Equals(field, Argument::Tag(..))
+        | Operation::OneOf(field, Argument::Tag(..))
+            if field.transforms.is_empty() =>
+        {
+            Some(field.field_name.clone())
+        }
+        _ => None,
+    })
+}
+
+/// The neighbors of the most recent origin vertex, indexed by their property value once needed.
+struct OriginNeighbors<Vertex> {
+    origin: Vertex,
+    neighbors: Rc<[Vertex]>,
+    index: Option<HashMap<FieldValue, Vec<usize>>>,
+}
+
+struct JoinProbe<'vertex, AdapterT: Adapter<'vertex>> {
+    inner: Arc<AdapterT>,
+    type_name: Arc<str>,
+    edge_name: Arc<str>,
+    parameters: EdgeParameters,
+    resolve_info: ResolveEdgeInfo,
+    property_type: Arc<str>,
+    property: Arc<str>,
+    property_resolve_info: ResolveInfo,
+    current: Option<OriginNeighbors<AdapterT::Vertex>>,
+}
+
+impl<'vertex, AdapterT> JoinProbe<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex>,
+    AdapterT::Vertex: Hash + Eq + 'vertex,
+{
+    fn neighbors<V: AsVertex<AdapterT::Vertex> + 'vertex>(
+        &mut self,
+        ctx: DataContext<V>,
+        candidate: CandidateValue<FieldValue>,
+    ) -> (DataContext<V>, VertexIterator<'vertex, AdapterT::Vertex>) {
+        let Some(origin) = ctx.active_vertex::<AdapterT::Vertex>().cloned() else {
+            return (ctx, Box::new(std::iter::empty()));
+        };
+
+        let current = match &mut self.current {
+            Some(current) if current.origin == origin => current,
+            _ => {
+                // The first time we see an origin vertex, its neighbors are resolved as usual.
+                // Building an index only pays off if the same vertex comes up again.
+                let (ctx, neighbors) = self
+                    .inner
+                    .resolve_neighbors(
+                        Box::new(std::iter::once(ctx)),
+                        &self.type_name,
+                        &self.edge_name,
+                        &self.parameters,
+                        &self.resolve_info,
+                    )
+                    .next()
+                    .expect("adapter produced no outcome for a context");
+                let neighbors: Rc<[AdapterT::Vertex]> = neighbors.collect();
+                self.current =
+                    Some(OriginNeighbors { origin, neighbors: neighbors.clone(), index: None });
+                return (ctx, all_neighbors(neighbors));
+            }
+        };
+
+        let values = match candidate {
+            CandidateValue::Impossible => return (ctx, Box::new(std::iter::empty())),
+            CandidateValue::Single(value) => vec![value],
+            CandidateValue::Multiple(values) => values,
+            _ => return (ctx, all_neighbors(current.neighbors.clone())),
+        };
+        let index = current.index.get_or_insert_with(|| {
+            index_neighbors(
+                self.inner.as_ref(),
+                &self.property_type,
+                &self.property,
+                &self.property_resolve_info,
+                &current.neighbors,
+            )
+        });
+
+        // Produce the matching neighbors in their original order, each only once.
+        let mut selected: Vec<usize> =
+            values.iter().filter_map(|value| index.get(value)).flatten().copied().collect();
+        selected.sort_unstable();
+        selected.dedup();
+
+        let neighbors = current.neighbors.clone();
+        (ctx, Box::new(selected.into_iter().map(move |i| neighbors[i].clone())))
+    }
+}
+
+fn all_neighbors<'vertex, Vertex: Clone + 'vertex>(
+    neighbors: Rc<[Vertex]>,
+) -> VertexIterator<'vertex, Vertex> {
+    Box::new((0..neighbors.len()).map(move |i| neighbors[i].clone()))
+}
+
+/// Map each value of the property to the positions of the neighbors that have it.
+fn index_neighbors<'vertex, AdapterT: Adapter<'vertex>>(
+    adapter: &AdapterT,
+    type_name: &Arc<str>,
+    property: &Arc<str>,
+    resolve_info: &ResolveInfo,
+    neighbors: &[AdapterT::Vertex],
+) -> HashMap<FieldValue, Vec<usize>> {
+    let contexts: Vec<_> =
+        neighbors.iter().map(|neighbor| DataContext::new(Some(neighbor.clone()))).collect();
+
+    let mut index: HashMap<FieldValue, Vec<usize>> = HashMap::new();
+    for (position, (_, value)) in adapter
+        .resolve_property(Box::new(contexts.into_iter()), type_name, property, resolve_info)
+        .enumerate()
+    {
+        index.entry(value).or_default().push(position);
+    }
+    index
+}
+
+impl<'vertex, AdapterT> Adapter<'vertex> for HashJoinAdapter<'vertex, AdapterT>
+where
+    AdapterT: Adapter<'vertex> + 'vertex,
+    AdapterT::Vertex: Hash + Eq,
+{
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        self.inner.resolve_properties(contexts, type_name, properties, resolve_info)
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let destination = resolve_info.destination();
+        let join = join_property(resolve_info).and_then(|property| {
+            destination.dynamically_required_property(&property).map(|value| (property, value))
+        });
+        let Some((property, tagged_value)) = join else {
+            return self.inner.resolve_neighbors(
+                contexts,
+                type_name,
+                edge_name,
+                parameters,
+                resolve_info,
+            );
+        };
+
+        let candidates = tagged_value.resolve(self.inner.as_ref(), contexts);
+        let mut probe = JoinProbe {
+            inner: self.inner.clone(),
+            type_name: type_name.clone(),
+            edge_name: edge_name.clone(),
+            parameters: parameters.clone(),
+            resolve_info: resolve_info.clone(),
+            property_type: resolve_info.destination_vertex().type_name.clone(),
+            property,
+            property_resolve_info: ResolveInfo::new(
+                resolve_info.clone().into_inner(),
+                resolve_info.destination_vid(),
+                true,
+            ),
+            current: None,
+        };
+        Box::new(candidates.map(move |(ctx, candidate)| probe.neighbors(ctx, candidate)))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::HashJoinAdapter;
+    use crate::{
+        interpreter::{
+            execution::interpret_ir, InstrumentedAdapter, ResolverCall, ResolverMetrics,
+        },
+        ir::{FieldValue, IndexedQuery},
+        numbers_interpreter::NumbersAdapter,
+    };
+
+    /// The number of contexts processed by all calls resolving the given edge.
+    fn count_edge_contexts(
+        indexed_query: &Arc<IndexedQuery>,
+        hash_join: bool,
+        edge: &str,
+    ) -> (Vec<BTreeMap<Arc<str>, FieldValue>>, usize) {
+        let reported: Arc<Mutex<Vec<ResolverMetrics>>> = Default::default();
+        let recorded = reported.clone();
+        let instrumented = InstrumentedAdapter::new(NumbersAdapter::new(), move |metrics| {
+            recorded.lock().unwrap().push(metrics.clone());
+        });
+        let arguments = Arc::new(BTreeMap::new());
+        let results: Vec<_> = if hash_join {
+            let adapter = Arc::new(HashJoinAdapter::new(instrumented));
+            interpret_ir(adapter, indexed_query.clone(), arguments).unwrap().collect()
+        } else {
+            interpret_ir(Arc::new(instrumented), indexed_query.clone(), arguments)
+                .unwrap()
+                .collect()
+        };
+
+        let contexts = reported
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|metrics| {
+                matches!(&metrics.call, ResolverCall::Neighbors { edge_name, .. } if edge_name.as_ref() == edge)
+            })
+            .map(|metrics| metrics.contexts)
+            .sum();
+        (results, contexts)
+    }
+
+    #[test]
+    fn sibling_edges_joined_on_tag() {
+        let query = r#"
+{
+    Number(min: 4, max: 12) {
+        ... on Composite {
+            value @output
+
+            divisor {
+                divisor: value @output @tag
+            }
+            primeFactor {
+                factor: value @output @filter(op: "=", value: ["%divisor"])
+            }
+        }
+    }
+}"#;
+        let schema = NumbersAdapter::new().schema().clone();
+        let indexed_query = crate::frontend::parse(&schema, query).unwrap();
+
+        let (expected, scanned_contexts) =
+            count_edge_contexts(&indexed_query, false, "primeFactor");
+        let (results, joined_contexts) = count_edge_contexts(&indexed_query, true, "primeFactor");
+
+        // The results are the same and in the same order, but each composite number's
+        // prime factors are only resolved once instead of once per divisor.
+        assert_eq!(expected, results);
+        assert!(!results.is_empty());
+        assert_eq!(6, joined_contexts);
+        assert!(scanned_contexts > joined_contexts, "{scanned_contexts} {joined_contexts}");
+    }
+
+    #[test]
+    fn optional_edges_are_not_joined() {
+        let query = r#"
+{
+    Number(min: 4, max: 12) {
+        ... on Composite {
+            value @output
+
+            divisor {
+                divisor: value @output @tag
+            }
+            primeFactor @optional {
+                factor: value @output @filter(op: "=", value: ["%divisor"])
+            }
+        }
+    }
+}"#;
+        let schema = NumbersAdapter::new().schema().clone();
+        let indexed_query = crate::frontend::parse(&schema, query).unwrap();
+
+        let (expected, scanned_contexts) =
+            count_edge_contexts(&indexed_query, false, "primeFactor");
+        let (results, joined_contexts) = count_edge_contexts(&indexed_query, true, "primeFactor");
+        assert_eq!(expected, results);
+        assert_eq!(scanned_contexts, joined_contexts);
+    }
+}
@@ -88,6 +88,69 @@ pub fn check_adapter_invariants<'a, A: Adapter<'a>>(schema: &Schema, adapter: A)
     check_type_coercions_are_implemented(&meta_schema, schema_adapter, &adapter, sample_size);
 }
 
+/// Run the checks of [`check_adapter_invariants()`], and then also check the adapter's
+/// type coercions against a sample of real vertices.
+///
+/// Up to `sample_size` vertices are resolved from each of the schema's starting edges.
+/// For each such vertex, this checks that:
+/// - Its `__typename` is a subtype of the starting edge's type.
+/// - [`resolve_coercion()`][coerc] coerces it from the starting edge's type to each of that
+///   type's subtypes if and only if its `__typename` is that subtype or one of its own subtypes.
+///
+/// Failure to uphold any of these invariants will cause a panic whose message names
+/// the offending types.
+///
+/// # Example
+///
+/// ```rust
+/// # use trustfall_core::{
+/// #    interpreter::helpers::check_adapter_invariants_with_vertex_sample,
+/// #    schema::{Schema, SchemaAdapter},
+/// # };
+/// #
+/// # fn get_schema_and_adapter() -> (Schema, SchemaAdapter<'static>) {
+/// #     let schema = Schema::parse(SchemaAdapter::schema_text()).expect("not a valid schema");
+/// #     let adapter = SchemaAdapter::new(Box::leak(Box::new(schema.clone())));
+/// #     (schema, adapter)
+/// # }
+/// #
+/// #[test]
+/// fn ensure_adapter_satisfies_invariants() {
+///     let (schema, adapter) = get_schema_and_adapter();
+///     check_adapter_invariants_with_vertex_sample(&schema, adapter, 16);
+/// }
+/// ```
+///
+/// # Limitations
+///
+/// Only vertices produced by starting edges are checked. Starting edges are resolved
+/// using the default values of all their parameters, and starting edges that take any
+/// non-nullable parameters without specified default values are not checked.
+///
+/// [coerc]: Adapter::resolve_coercion
+pub fn check_adapter_invariants_with_vertex_sample<'a, A: Adapter<'a>>(
+    schema: &Schema,
+    adapter: A,
+    sample_size: usize,
+) {
+    let schema_adapter = Arc::new(SchemaAdapter::new(schema));
+    let meta_schema = Schema::parse(SchemaAdapter::schema_text()).expect("invalid schema");
+
+    // The non-reordering invariant is checked with the same number of contexts
+    // as in `check_adapter_invariants()`.
+    let context_count = 8;
+
+    check_properties_are_implemented(&meta_schema, schema_adapter.clone(), &adapter, context_count);
+    check_edges_are_implemented(&meta_schema, schema_adapter.clone(), &adapter, context_count);
+    check_type_coercions_are_implemented(
+        &meta_schema,
+        schema_adapter.clone(),
+        &adapter,
+        context_count,
+    );
+    check_coercions_match_typename(schema, &meta_schema, schema_adapter, &adapter, sample_size);
+}
+
 fn make_contexts<V: Debug + Clone>(count: usize) -> Vec<DataContext<V>> {
     let mut result = Vec::with_capacity(count);
     for i in 0..=count {
@@ -118,6 +181,25 @@ fn get_context_order_values<V: Debug + Clone>(ctxs: &[DataContext<V>]) -> Vec<i6
         .collect()
 }
 
+/// Convert the serialized default values of an edge's parameters into edge arguments.
+///
+/// Returns `None` if any parameter doesn't have a default value.
+fn parse_parameter_defaults(
+    parameter_names: Vec<Arc<str>>,
+    parameter_defaults: Vec<Option<String>>,
+) -> Option<Arc<BTreeMap<Arc<str>, FieldValue>>> {
+    parameter_names
+        .into_iter()
+        .zip(parameter_defaults)
+        .map(|(name, value)| {
+            let transparent_value: TransparentValue =
+                serde_json::from_str(&value?).expect("invalid serialized content");
+            Some((name, FieldValue::from(transparent_value)))
+        })
+        .collect::<Option<BTreeMap<_, _>>>()
+        .map(Arc::new)
+}
+
 fn run_query<'a, A: Adapter<'a> + 'a, T: serde::de::DeserializeOwned>(
     schema: &Schema,
     adapter: Arc<A>,
@@ -165,15 +247,22 @@ fn make_resolve_info_for_property_check(
             },
             edges: Default::default(),
             folds: Default::default(),
+            optional_groups: Default::default(),
             outputs: btreemap! {
                 property_name.clone() => ContextField {
                     vertex_id: vid,
                     field_name: property_name.clone(),
                     field_type: Type::parse(property_type).expect("not a valid type"),
+                    transforms: vec![],
                 }
             },
         }),
         variables: Default::default(),
+        variable_defaults: Default::default(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        dedupe: false,
     };
     let query = InterpretedQuery::from_query_and_arguments(
         Arc::new(query.try_into().expect("not a valid query")),
@@ -320,18 +409,26 @@ fn make_resolve_edge_info_for_edge_check(
                     parameters: parameters.clone(),
                     optional: false,
                     recursive: None,
+                    filters: vec![],
                 }),
             },
             folds: Default::default(),
+            optional_groups: Default::default(),
             outputs: btreemap! {
                 property_name.clone() => ContextField {
                     vertex_id: vid,
                     field_name: property_name,
                     field_type: Type::parse("String!").expect("not a valid type"),
+                    transforms: vec![],
                 }
             },
         }),
         variables: Default::default(),
+        variable_defaults: Default::default(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        dedupe: false,
     };
     let query = InterpretedQuery::from_query_and_arguments(
         Arc::new(query.try_into().expect("not a valid query")),
@@ -389,31 +486,13 @@ fn check_edges_are_implemented<'a, A: Adapter<'a>>(
         let type_name = output.type_name;
         let edge_name = output.edge_name;
 
-        let parameter_defaults: Vec<_> = output
-            .parameter_default
-            .into_iter()
-            .map(|value| {
-                value.map(|content| {
-                    let transparent_value: TransparentValue =
-                        serde_json::from_str(&content).expect("invalid serialized content");
-                    FieldValue::from(transparent_value)
-                })
-            })
-            .collect();
-        if parameter_defaults.contains(&Option::None) {
+        let Some(edge_parameters) =
+            parse_parameter_defaults(output.parameter_name, output.parameter_default)
+        else {
             // This edge has a parameter without a default value.
             // We can't check it since we don't know what values are valid to provide.
             continue;
-        }
-
-        let edge_parameters: Arc<BTreeMap<Arc<str>, FieldValue>> = Arc::new(
-            output
-                .parameter_name
-                .into_iter()
-                .zip(parameter_defaults)
-                .map(|(name, value)| (name, value.expect("parameter has a default")))
-                .collect(),
-        );
+        };
         let parameters = EdgeParameters::new(edge_parameters.clone());
 
         let contexts = Box::new(initial_contexts.clone().into_iter());
@@ -492,15 +571,22 @@ fn make_resolve_info_for_type_coercion(
             },
             edges: Default::default(),
             folds: Default::default(),
+            optional_groups: Default::default(),
             outputs: btreemap! {
                 typename_property.clone() => ContextField {
                     vertex_id: vid,
                     field_name: typename_property.clone(),
                     field_type: Type::parse("String!").expect("not a valid type"),
+                    transforms: vec![],
                 }
             },
         }),
         variables: Default::default(),
+        variable_defaults: Default::default(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        dedupe: false,
     };
     let query = InterpretedQuery::from_query_and_arguments(
         Arc::new(query.try_into().expect("not a valid query")),
@@ -577,3 +663,164 @@ fn check_type_coercions_are_implemented<'a, A: Adapter<'a>>(
         )
     }
 }
+
+/// Construct a *believable* [`ResolveInfo`] for resolving a starting edge.
+///
+/// Similar to [`make_resolve_info_for_property_check`], but for starting vertices.
+fn make_resolve_info_for_starting_edge(
+    edge_name: &Arc<str>,
+    parameters: &EdgeParameters,
+    target_type: &Arc<str>,
+) -> ResolveInfo {
+    let vid = Vid::new(NonZeroUsize::new(1).expect("invalid Vid"));
+    let property_name: Arc<str> = Arc::from("__typename");
+    let query = IRQuery {
+        root_name: edge_name.clone(),
+        root_parameters: parameters.clone(),
+        root_component: Arc::new(IRQueryComponent {
+            root: vid,
+            vertices: btreemap! {
+                vid => IRVertex {
+                    vid,
+                    type_name: target_type.clone(),
+                    coerced_from_type: None,
+                    filters: vec![],
+                },
+            },
+            edges: Default::default(),
+            folds: Default::default(),
+            optional_groups: Default::default(),
+            outputs: btreemap! {
+                property_name.clone() => ContextField {
+                    vertex_id: vid,
+                    field_name: property_name,
+                    field_type: Type::parse("String!").expect("not a valid type"),
+                    transforms: vec![],
+                }
+            },
+        }),
+        variables: Default::default(),
+        variable_defaults: Default::default(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        dedupe: false,
+    };
+    let query = InterpretedQuery::from_query_and_arguments(
+        Arc::new(query.try_into().expect("not a valid query")),
+        Arc::new(BTreeMap::new()),
+    )
+    .expect("not a valid query");
+    ResolveInfo::new(query, vid, true)
+}
+
+fn check_coercions_match_typename<'a, A: Adapter<'a>>(
+    schema: &Schema,
+    meta_schema: &Schema,
+    schema_adapter: Arc<SchemaAdapter<'_>>,
+    adapter_under_test: &A,
+    sample_size: usize,
+) {
+    let query = r#"
+{
+    Entrypoint {
+        edge_name: name @output
+
+        parameter_: parameter @fold {
+            name @output
+            default @output
+        }
+
+        target {
+            target_type: name @output
+        }
+    }
+}
+"#;
+    let variables: BTreeMap<Arc<str>, FieldValue> = Default::default();
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct Output {
+        edge_name: Arc<str>,
+        parameter_name: Vec<Arc<str>>,
+        parameter_default: Vec<Option<String>>,
+        target_type: Arc<str>,
+    }
+
+    let typename_property: Arc<str> = Arc::from("__typename");
+
+    for output in
+        run_query::<SchemaAdapter<'_>, Output>(meta_schema, schema_adapter, query, variables)
+    {
+        let edge_name = &output.edge_name;
+        let edge_type = &output.target_type;
+        let Some(edge_parameters) =
+            parse_parameter_defaults(output.parameter_name, output.parameter_default)
+        else {
+            // This edge has a parameter without a default value.
+            // We can't check it since we don't know what values are valid to provide.
+            continue;
+        };
+        let parameters = EdgeParameters::new(edge_parameters);
+
+        let resolve_info = make_resolve_info_for_starting_edge(edge_name, &parameters, edge_type);
+        let vertices: Vec<_> = adapter_under_test
+            .resolve_starting_vertices(edge_name, &parameters, &resolve_info)
+            .take(sample_size)
+            .collect();
+
+        let resolve_info =
+            make_resolve_info_for_property_check(edge_type, &typename_property, "String!");
+        let contexts = Box::new(vertices.clone().into_iter().map(|v| DataContext::new(Some(v))));
+        let typenames: Vec<Arc<str>> = adapter_under_test
+            .resolve_property(contexts, edge_type, &typename_property, &resolve_info)
+            .map(|(_, value)| match value {
+                FieldValue::String(typename) => typename,
+                _ => panic!(
+                    "resolve_property() produced {value:?} instead of a string \
+                    for the __typename of a vertex of type '{edge_type}'"
+                ),
+            })
+            .collect();
+
+        for typename in &typenames {
+            assert!(
+                schema.is_named_type_subtype(edge_type, typename),
+                "vertex produced by starting edge '{edge_name}' has __typename '{typename}', \
+                which is not a subtype of the edge's type '{edge_type}'"
+            );
+        }
+
+        let strict_subtypes = schema
+            .subtypes(edge_type)
+            .expect("edge type is a vertex type")
+            .filter(|subtype| *subtype != edge_type.as_ref());
+        for coerce_to in strict_subtypes {
+            let coerce_to: Arc<str> = Arc::from(coerce_to);
+            let resolve_info =
+                make_resolve_info_for_type_coercion(edge_type, &coerce_to, &typename_property);
+            let contexts =
+                Box::new(vertices.clone().into_iter().map(|v| DataContext::new(Some(v))));
+            let outcomes = adapter_under_test
+                .resolve_coercion(contexts, edge_type, &coerce_to, &resolve_info)
+                .map(|(_, outcome)| outcome);
+
+            for (typename, outcome) in typenames.iter().zip(outcomes) {
+                let expected = schema.is_named_type_subtype(&coerce_to, typename);
+                if outcome && !expected {
+                    panic!(
+                        "resolve_coercion() coerced a vertex with __typename '{typename}' \
+                        from type '{edge_type}' to '{coerce_to}', \
+                        but '{typename}' is not a subtype of '{coerce_to}'"
+                    );
+                } else if !outcome && expected {
+                    panic!(
+                        "resolve_coercion() did not coerce a vertex with __typename \
+                        '{typename}' from type '{edge_type}' to '{coerce_to}', \
+                        even though '{typename}' is a subtype of '{coerce_to}'"
+                    );
+                }
+            }
+        }
+    }
+}
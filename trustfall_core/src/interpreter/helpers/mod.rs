@@ -5,11 +5,22 @@ use crate::{ir::FieldValue, schema::Schema};
 use super::{AsVertex, ContextIterator, ContextOutcomeIterator, Typename, VertexIterator};
 
 mod correctness;
+mod pushdown;
+#[cfg(feature = "test-utils")]
+mod random_queries;
+mod resolver_table;
 
 #[cfg(test)]
 mod tests;
 
-pub use correctness::check_adapter_invariants;
+pub use correctness::{check_adapter_invariants, check_adapter_invariants_with_vertex_sample};
+pub use pushdown::{
+    candidate_to_key_ranges, candidate_to_predicate, candidate_to_sql_condition, KeyRange,
+    KeyRanges, SqlCondition,
+};
+#[cfg(feature = "test-utils")]
+pub use random_queries::{check_adapter_with_random_queries, RandomQueryConfig};
+pub use resolver_table::ResolverTable;
 
 /// Helper for implementing [`BasicAdapter::resolve_property`] and equivalents.
 ///
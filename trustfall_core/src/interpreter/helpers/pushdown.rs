@@ -0,0 +1,271 @@
+use std::ops::Bound;
+
+use crate::{
+    interpreter::{CandidateValue, Range},
+    ir::FieldValue,
+};
+
+/// A SQL boolean condition together with the parameter values it binds.
+///
+/// The condition uses `?` placeholders, and `parameters` holds the values
+/// for those placeholders in the order they appear in the condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlCondition {
+    pub condition: String,
+    pub parameters: Vec<FieldValue>,
+}
+
+/// Convert candidate values for a property into a SQL `WHERE` condition on the given column.
+///
+/// The column is inserted into the condition verbatim.
+/// Quote or qualify it as needed before passing it in.
+///
+/// Follows SQL's `NULL` semantics: comparisons never match `NULL`, so a `NULL` candidate
+/// becomes an explicit `IS NULL` check. Candidates that match everything or nothing
+/// become `1 = 1` and `1 = 0` respectively. String patterns become `LIKE` conditions
+/// that use `\` as the escape character.
+///
+/// # Examples
+/// ```
+/// # use trustfall_core::{
+/// #     interpreter::{helpers::candidate_to_sql_condition, CandidateValue},
+/// #     ir::FieldValue,
+/// # };
+/// let candidate = CandidateValue::Multiple(vec![FieldValue::Int64(1), FieldValue::Null]);
+/// let sql = candidate_to_sql_condition("user_id", &candidate);
+///
+/// assert_eq!("(user_id IN (?) OR user_id IS NULL)", sql.condition);
+/// assert_eq!(vec![FieldValue::Int64(1)], sql.parameters);
+/// ```
+pub fn candidate_to_sql_condition(
+    column: &str,
+    candidate: &CandidateValue<FieldValue>,
+) -> SqlCondition {
+    let mut parameters = vec![];
+    let condition = match candidate {
+        CandidateValue::Impossible => "1 = 0".to_string(),
+        CandidateValue::All => "1 = 1".to_string(),
+        CandidateValue::Single(FieldValue::Null) => format!("{column} IS NULL"),
+        CandidateValue::Single(value) => {
+            parameters.push(value.clone());
+            format!("{column} = ?")
+        }
+        CandidateValue::Multiple(values) => {
+            parameters.extend(values.iter().filter(|v| !matches!(v, FieldValue::Null)).cloned());
+            let null_included = values.iter().any(|v| matches!(v, FieldValue::Null));
+            let placeholders = vec!["?"; parameters.len()].join(", ");
+            match (parameters.is_empty(), null_included) {
+                (true, false) => "1 = 0".to_string(),
+                (true, true) => format!("{column} IS NULL"),
+                (false, false) => format!("{column} IN ({placeholders})"),
+                (false, true) => format!("({column} IN ({placeholders}) OR {column} IS NULL)"),
+            }
+        }
+        CandidateValue::Range(range) => {
+            let mut comparisons = vec![];
+            for (bound, inclusive, exclusive) in
+                [(range.start_bound(), ">=", ">"), (range.end_bound(), "<=", "<")]
+            {
+                match bound {
+                    Bound::Included(value) => {
+                        parameters.push(value.clone());
+                        comparisons.push(format!("{column} {inclusive} ?"));
+                    }
+                    Bound::Excluded(value) => {
+                        parameters.push(value.clone());
+                        comparisons.push(format!("{column} {exclusive} ?"));
+                    }
+                    Bound::Unbounded => {}
+                }
+            }
+
+            match (comparisons.is_empty(), range.null_included()) {
+                (true, false) => format!("{column} IS NOT NULL"),
+                (true, true) => "1 = 1".to_string(),
+                (false, false) => comparisons.join(" AND "),
+                (false, true) => format!("({} OR {column} IS NULL)", comparisons.join(" AND ")),
+            }
+        }
+        CandidateValue::StringPattern(pattern) => {
+            let likes = pattern
+                .prefix()
+                .map(|prefix| format!("{}%", escape_like_pattern(prefix)))
+                .into_iter()
+                .chain(pattern.suffix().map(|suffix| format!("%{}", escape_like_pattern(suffix))))
+                .chain(
+                    pattern
+                        .substrings()
+                        .iter()
+                        .map(|substring| format!("%{}%", escape_like_pattern(substring))),
+                );
+
+            let mut comparisons = vec![];
+            for like in likes {
+                parameters.push(FieldValue::String(like.into()));
+                comparisons.push(format!("{column} LIKE ? ESCAPE '\\'"));
+            }
+
+            if comparisons.is_empty() {
+                format!("{column} IS NOT NULL")
+            } else {
+                comparisons.join(" AND ")
+            }
+        }
+    };
+
+    SqlCondition { condition, parameters }
+}
+
+fn escape_like_pattern(value: &FieldValue) -> String {
+    let value = value.as_str().expect("string pattern contained a non-string value");
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Convert candidate values for a property into a predicate that checks property values.
+///
+/// Useful for filtering vertices as early as possible,
+/// for example while paging through an API that can't apply the filter itself.
+///
+/// # Examples
+/// ```
+/// # use trustfall_core::{
+/// #     interpreter::{helpers::candidate_to_predicate, CandidateValue},
+/// #     ir::FieldValue,
+/// # };
+/// let candidate = CandidateValue::Multiple(vec![FieldValue::Int64(1), FieldValue::Int64(3)]);
+/// let predicate = candidate_to_predicate(candidate);
+///
+/// assert!(predicate(&FieldValue::Int64(3)));
+/// assert!(!predicate(&FieldValue::Int64(2)));
+/// ```
+pub fn candidate_to_predicate(
+    candidate: CandidateValue<FieldValue>,
+) -> impl Fn(&FieldValue) -> bool {
+    move |value| match &candidate {
+        CandidateValue::Impossible => false,
+        CandidateValue::Single(single) => single == value,
+        CandidateValue::Multiple(multiple) => multiple.contains(value),
+        CandidateValue::Range(range) => range.contains(value),
+        CandidateValue::StringPattern(pattern) => pattern.matches(value),
+        CandidateValue::All => true,
+    }
+}
+
+/// A contiguous range of non-`null` keys, such as for a range scan over a sorted index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRange<T> {
+    pub start: Bound<T>,
+    pub end: Bound<T>,
+}
+
+impl<T: PartialOrd> KeyRange<T> {
+    /// A range containing exactly one key.
+    pub fn point(key: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { start: Bound::Included(key.clone()), end: Bound::Included(key) }
+    }
+
+    /// Whether the range contains exactly one key.
+    pub fn is_point(&self) -> bool {
+        matches!((&self.start, &self.end), (Bound::Included(l), Bound::Included(r)) if l == r)
+    }
+
+    /// Checks whether the specified key is part of the range.
+    pub fn contains(&self, key: &T) -> bool {
+        (match &self.start {
+            Bound::Included(start) => start <= key,
+            Bound::Excluded(start) => start < key,
+            Bound::Unbounded => true,
+        }) && (match &self.end {
+            Bound::Included(end) => key <= end,
+            Bound::Excluded(end) => key < end,
+            Bound::Unbounded => true,
+        })
+    }
+}
+
+/// The key ranges to scan to find all values matching a property's candidate values.
+///
+/// Key ranges never include `null`. Whether `null` values also need to be looked up
+/// is reported separately in `null_included`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRanges<T> {
+    /// Non-overlapping key ranges to scan, in ascending order.
+    pub ranges: Vec<KeyRange<T>>,
+
+    /// Whether `null` values also match the candidate.
+    pub null_included: bool,
+}
+
+/// Convert candidate values for a property into key ranges, such as for sorted index scans.
+///
+/// String patterns with a prefix become a range starting at that prefix, and other patterns
+/// become the full range. In both cases, the ranges may contain keys that don't match
+/// the pattern, so check the keys found by the scan with [`candidate_to_predicate`].
+///
+/// # Examples
+/// ```
+/// # use trustfall_core::{
+/// #     interpreter::{helpers::{candidate_to_key_ranges, KeyRange}, CandidateValue},
+/// #     ir::FieldValue,
+/// # };
+/// let candidate = CandidateValue::Multiple(vec![FieldValue::Int64(3), FieldValue::Int64(1)]);
+/// let key_ranges = candidate_to_key_ranges(&candidate);
+///
+/// assert_eq!(
+///     vec![KeyRange::point(FieldValue::Int64(1)), KeyRange::point(FieldValue::Int64(3))],
+///     key_ranges.ranges,
+/// );
+/// assert!(!key_ranges.null_included);
+/// ```
+pub fn candidate_to_key_ranges(candidate: &CandidateValue<FieldValue>) -> KeyRanges<FieldValue> {
+    match candidate {
+        CandidateValue::Impossible => KeyRanges { ranges: vec![], null_included: false },
+        CandidateValue::All => KeyRanges {
+            ranges: vec![KeyRange { start: Bound::Unbounded, end: Bound::Unbounded }],
+            null_included: true,
+        },
+        CandidateValue::Single(FieldValue::Null) => {
+            KeyRanges { ranges: vec![], null_included: true }
+        }
+        CandidateValue::Single(value) => {
+            KeyRanges { ranges: vec![KeyRange::point(value.clone())], null_included: false }
+        }
+        CandidateValue::Multiple(values) => {
+            let mut keys: Vec<_> =
+                values.iter().filter(|v| !matches!(v, FieldValue::Null)).collect();
+            keys.sort_unstable_by(|l, r| l.partial_cmp(r).expect("incomparable values"));
+            keys.dedup();
+            KeyRanges {
+                ranges: keys.into_iter().cloned().map(KeyRange::point).collect(),
+                null_included: values.iter().any(|v| matches!(v, FieldValue::Null)),
+            }
+        }
+        CandidateValue::Range(range) => range_to_key_ranges(range),
+        CandidateValue::StringPattern(pattern) => {
+            let start = pattern.prefix().cloned().map_or(Bound::Unbounded, Bound::Included);
+            KeyRanges {
+                ranges: vec![KeyRange { start, end: Bound::Unbounded }],
+                null_included: false,
+            }
+        }
+    }
+}
+
+fn range_to_key_ranges(range: &Range<FieldValue>) -> KeyRanges<FieldValue> {
+    let ranges = if range.degenerate() {
+        vec![]
+    } else {
+        vec![KeyRange { start: range.start_bound().cloned(), end: range.end_bound().cloned() }]
+    };
+    KeyRanges { ranges, null_included: range.null_included() }
+}
@@ -0,0 +1,432 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use async_graphql_parser::{types::FieldDefinition, Positioned};
+
+use crate::{
+    interpreter::{execution::interpret_ir, Adapter},
+    ir::{FieldValue, Type},
+    schema::{get_vertex_type_fields, Schema},
+};
+
+/// Settings for [`check_adapter_with_random_queries()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomQueryConfig {
+    /// Seed for generating queries: the same seed and schema always produce the same queries.
+    pub seed: u64,
+
+    /// How many random queries to generate and execute.
+    pub query_count: usize,
+
+    /// The maximum number of edges between the query's starting vertex and any other vertex.
+    pub max_depth: usize,
+
+    /// The maximum number of results of each query to check.
+    ///
+    /// Coercion correctness is only checked for queries with at most this many results,
+    /// since it requires comparing all the results of two queries.
+    pub max_results: usize,
+}
+
+impl Default for RandomQueryConfig {
+    fn default() -> Self {
+        Self { seed: 0, query_count: 100, max_depth: 2, max_results: 100 }
+    }
+}
+
+/// Execute randomly-generated queries against an adapter, checking their results
+/// for adapter bugs.
+///
+/// This is a deeper complement to [`check_adapter_invariants()`](super::check_adapter_invariants):
+/// instead of checking each adapter method in isolation, it checks that the results of
+/// whole queries are consistent with the schema. It checks the following invariants:
+/// - Each vertex's `__typename` is a type in the schema, and is a subtype of
+///   the type of the edge that produced the vertex, or of the type it was coerced to.
+/// - Each property value is valid for the property's type in the schema.
+/// - Coercions are correct: a query coercing a vertex to a subtype produces exactly
+///   the results of the same query without the coercion whose vertex's `__typename`
+///   is that subtype or one of its own subtypes.
+///
+/// Failure to uphold any of the above invariants will cause a panic whose message
+/// includes the query that found the problem.
+///
+/// # Example
+///
+/// This function would normally be used in a test case so that broken adapter invariants
+/// cause test failures:
+/// ```rust
+/// # use trustfall_core::{
+/// #    interpreter::helpers::{check_adapter_with_random_queries, RandomQueryConfig},
+/// #    schema::{Schema, SchemaAdapter},
+/// # };
+/// #
+/// # fn get_schema_and_adapter() -> (Schema, SchemaAdapter<'static>) {
+/// #     let schema = Schema::parse(SchemaAdapter::schema_text()).expect("not a valid schema");
+/// #     let adapter = SchemaAdapter::new(Box::leak(Box::new(schema.clone())));
+/// #     (schema, adapter)
+/// # }
+/// #
+/// #[test]
+/// fn ensure_adapter_produces_valid_results() {
+///     let (schema, adapter) = get_schema_and_adapter();
+///     check_adapter_with_random_queries(&schema, adapter, RandomQueryConfig::default());
+/// }
+/// ```
+///
+/// # Limitations
+///
+/// Edge parameters that are nullable or have default values are never specified.
+/// Other `Int` and `Boolean` parameters get small random values, and edges that take
+/// non-nullable parameters of any other type without default values are never used.
+///
+/// The coercion check assumes the adapter is deterministic, i.e. that running
+/// the same query twice produces the same results.
+pub fn check_adapter_with_random_queries<'a, A: Adapter<'a> + 'a>(
+    schema: &Schema,
+    adapter: A,
+    config: RandomQueryConfig,
+) {
+    let adapter = Arc::new(adapter);
+    let mut rng = Rng(config.seed);
+
+    let root_edges: Vec<_> = schema
+        .query_type
+        .fields
+        .iter()
+        .filter(|field| generate_arguments(&mut Rng(0), &field.node).is_some())
+        .collect();
+    assert!(
+        !root_edges.is_empty(),
+        "all the schema's starting edges have parameters that can't be generated"
+    );
+
+    for _ in 0..config.query_count {
+        let field = &root_edges[rng.below(root_edges.len())].node;
+        let mut vertex_count = 0;
+        let root = generate_edge(schema, &mut rng, &config, field, 0, &mut vertex_count)
+            .expect("root edge parameters can be generated");
+
+        check_results(schema, adapter.clone(), &config, &root);
+        check_coercions(schema, adapter.clone(), &config, &root);
+    }
+}
+
+/// A tiny deterministic pseudorandom number generator (SplitMix64),
+/// so that generated queries only depend on the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random number in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns `true` with probability `percent / 100`.
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QueryEdge {
+    name: Arc<str>,
+
+    /// The edge's arguments, already formatted as query text, e.g. `(max: 3)`.
+    arguments: String,
+    vertex: QueryVertex,
+}
+
+#[derive(Debug, Clone)]
+struct QueryVertex {
+    /// Identifies the vertex's outputs, which are prefixed with `v{id}_`.
+    id: usize,
+
+    /// The vertex's type according to the edge that produced it.
+    edge_type: Arc<str>,
+    coerced_to: Option<Arc<str>>,
+
+    /// The names and schema types of the vertex's properties that are output.
+    properties: Vec<(Arc<str>, Type)>,
+    edges: Vec<QueryEdge>,
+}
+
+impl QueryVertex {
+    fn typename_output(&self) -> String {
+        format!("v{}___typename", self.id)
+    }
+
+    fn property_output(&self, property: &str) -> String {
+        format!("v{}_{property}", self.id)
+    }
+
+    /// The most specific type the vertex is known to have.
+    fn static_type(&self) -> &str {
+        self.coerced_to.as_deref().unwrap_or(&self.edge_type)
+    }
+}
+
+/// Arguments for the edge's parameters, or `None` if some required parameter
+/// has a type we can't generate values for.
+fn generate_arguments(rng: &mut Rng, field: &FieldDefinition) -> Option<String> {
+    let mut arguments = vec![];
+    for parameter in &field.arguments {
+        let parameter = &parameter.node;
+        if parameter.default_value.is_some() || parameter.ty.node.nullable {
+            continue;
+        }
+        let parameter_type = Type::from_type(&parameter.ty.node);
+        let value = match parameter_type.base_type() {
+            _ if parameter_type.is_list() => return None,
+            "Int" => (rng.below(6) as i64).to_string(),
+            "Boolean" => rng.chance(50).to_string(),
+            _ => return None,
+        };
+        arguments.push(format!("{}: {value}", parameter.name.node));
+    }
+
+    if arguments.is_empty() {
+        Some(String::new())
+    } else {
+        Some(format!("({})", arguments.join(", ")))
+    }
+}
+
+fn generate_edge(
+    schema: &Schema,
+    rng: &mut Rng,
+    config: &RandomQueryConfig,
+    field: &FieldDefinition,
+    depth: usize,
+    vertex_count: &mut usize,
+) -> Option<QueryEdge> {
+    let arguments = generate_arguments(rng, field)?;
+    let edge_type: Arc<str> = Type::from_type(&field.ty.node).base_type().into();
+
+    let id = *vertex_count;
+    *vertex_count += 1;
+
+    let strict_subtypes: Vec<&str> = schema
+        .subtypes(&edge_type)
+        .expect("edge type is a vertex type")
+        .filter(|name| *name != edge_type.as_ref())
+        .collect();
+    let coerced_to = (!strict_subtypes.is_empty() && rng.chance(40))
+        .then(|| strict_subtypes[rng.below(strict_subtypes.len())].into());
+
+    let mut vertex = QueryVertex { id, edge_type, coerced_to, properties: vec![], edges: vec![] };
+
+    let fields: &[Positioned<FieldDefinition>] =
+        get_vertex_type_fields(&schema.vertex_types[vertex.static_type()]);
+    for field in fields {
+        let field = &field.node;
+        let field_type = Type::from_type(&field.ty.node);
+        if schema.vertex_types.contains_key(field_type.base_type()) {
+            if depth < config.max_depth && vertex.edges.len() < 2 && rng.chance(30) {
+                vertex.edges.extend(generate_edge(
+                    schema,
+                    rng,
+                    config,
+                    field,
+                    depth + 1,
+                    vertex_count,
+                ));
+            }
+        } else if rng.chance(50) {
+            vertex.properties.push((field.name.node.as_str().into(), field_type));
+        }
+    }
+
+    Some(QueryEdge { name: field.name.node.as_str().into(), arguments, vertex })
+}
+
+fn write_edge(query: &mut String, edge: &QueryEdge, indent: usize) {
+    let vertex = &edge.vertex;
+    let mut indent = indent;
+    writeln!(query, "{:indent$}{}{} {{", "", edge.name, edge.arguments).expect("write failed");
+    if let Some(coerced_to) = &vertex.coerced_to {
+        indent += 4;
+        writeln!(query, "{:indent$}... on {coerced_to} {{", "").expect("write failed");
+    }
+
+    let inner = indent + 4;
+    writeln!(query, "{:inner$}__typename @output(name: \"{}\")", "", vertex.typename_output())
+        .expect("write failed");
+    for (property, _) in &vertex.properties {
+        let output = vertex.property_output(property);
+        writeln!(query, "{:inner$}{property} @output(name: \"{output}\")", "")
+            .expect("write failed");
+    }
+    for edge in &vertex.edges {
+        write_edge(query, edge, inner);
+    }
+
+    if vertex.coerced_to.is_some() {
+        writeln!(query, "{:indent$}}}", "").expect("write failed");
+        indent -= 4;
+    }
+    writeln!(query, "{:indent$}}}", "").expect("write failed");
+}
+
+fn to_query_text(root: &QueryEdge) -> String {
+    let mut query = String::from("{\n");
+    write_edge(&mut query, root, 4);
+    query.push_str("}\n");
+    query
+}
+
+/// Run the query, returning up to `limit` of its results
+/// and whether the query has more results than that.
+fn run_query<'a, A: Adapter<'a> + 'a>(
+    schema: &Schema,
+    adapter: Arc<A>,
+    query: &str,
+    limit: usize,
+) -> (Vec<BTreeMap<Arc<str>, FieldValue>>, bool) {
+    let indexed = crate::frontend::parse(schema, query)
+        .unwrap_or_else(|e| panic!("generated an invalid query: {e}\n{query}"));
+    let mut results = interpret_ir(adapter, indexed, Default::default())
+        .unwrap_or_else(|e| panic!("execution error: {e}\n{query}"));
+    let collected: Vec<_> = results.by_ref().take(limit).collect();
+    let has_more = results.next().is_some();
+    (collected, has_more)
+}
+
+fn check_results<'a, A: Adapter<'a> + 'a>(
+    schema: &Schema,
+    adapter: Arc<A>,
+    config: &RandomQueryConfig,
+    root: &QueryEdge,
+) {
+    let query = to_query_text(root);
+    let (results, _) = run_query(schema, adapter, &query, config.max_results);
+    for result in &results {
+        check_vertex(schema, &query, result, &root.vertex);
+    }
+}
+
+fn check_vertex(
+    schema: &Schema,
+    query: &str,
+    result: &BTreeMap<Arc<str>, FieldValue>,
+    vertex: &QueryVertex,
+) {
+    let typename = match &result[vertex.typename_output().as_str()] {
+        FieldValue::String(typename) => typename,
+        value => panic!("__typename value {value:?} is not a string\n{query}"),
+    };
+    assert!(
+        schema.vertex_types.contains_key(typename.as_ref()),
+        "__typename value {typename} is not a type in the schema\n{query}"
+    );
+    assert!(
+        schema.is_named_type_subtype(vertex.static_type(), typename),
+        "vertex of type {} has __typename {typename}, which is not one of its subtypes\n{query}",
+        vertex.static_type(),
+    );
+
+    for (property, property_type) in &vertex.properties {
+        let value = &result[vertex.property_output(property).as_str()];
+        assert!(
+            property_type.is_valid_value(value),
+            "value {value:?} of property {property} on type {typename} is not \
+            a valid {property_type}\n{query}",
+        );
+    }
+
+    for edge in &vertex.edges {
+        check_vertex(schema, query, result, &edge.vertex);
+    }
+}
+
+/// For each coerced vertex, compare the results of the query leading up to that vertex
+/// with and without the coercion.
+fn check_coercions<'a, A: Adapter<'a> + 'a>(
+    schema: &Schema,
+    adapter: Arc<A>,
+    config: &RandomQueryConfig,
+    root: &QueryEdge,
+) {
+    let mut path = vec![];
+    collect_coercion_paths(root, &mut path, &mut |path| {
+        let coerced = path_query(path);
+        let uncoerced = {
+            let mut path = path.to_vec();
+            path.last_mut().expect("path is not empty").vertex.coerced_to = None;
+            path_query(&path)
+        };
+
+        let (coerced_results, coerced_has_more) =
+            run_query(schema, adapter.clone(), &coerced, config.max_results);
+        let (uncoerced_results, uncoerced_has_more) =
+            run_query(schema, adapter.clone(), &uncoerced, config.max_results);
+        if coerced_has_more || uncoerced_has_more {
+            return;
+        }
+
+        let vertex = &path.last().expect("path is not empty").vertex;
+        let coerced_to = vertex.coerced_to.as_deref().expect("vertex is coerced");
+        let typename_output = vertex.typename_output();
+        let mut expected: Vec<_> = uncoerced_results
+            .into_iter()
+            .filter(|result| {
+                result[typename_output.as_str()]
+                    .as_str()
+                    .is_some_and(|typename| schema.is_named_type_subtype(coerced_to, typename))
+            })
+            .collect();
+
+        for result in coerced_results {
+            let position = expected.iter().position(|r| r == &result).unwrap_or_else(|| {
+                panic!(
+                    "coercion to {coerced_to} produced result {result:?} which isn't \
+                    produced without the coercion\n{coerced}"
+                )
+            });
+            expected.swap_remove(position);
+        }
+        assert!(
+            expected.is_empty(),
+            "coercion to {coerced_to} is missing results {expected:?}\n{coerced}"
+        );
+    });
+}
+
+/// Call `visit` with the path from the root to each coerced vertex.
+fn collect_coercion_paths(
+    edge: &QueryEdge,
+    path: &mut Vec<QueryEdge>,
+    visit: &mut impl FnMut(&[QueryEdge]),
+) {
+    path.push(edge.clone());
+    if edge.vertex.coerced_to.is_some() {
+        visit(path);
+    }
+    for next in &edge.vertex.edges {
+        collect_coercion_paths(next, path, visit);
+    }
+    path.pop();
+}
+
+/// A query that only expands the edges in the given path, outputting each vertex's `__typename`.
+fn path_query(path: &[QueryEdge]) -> String {
+    let root = path
+        .iter()
+        .rev()
+        .fold(None, |next: Option<QueryEdge>, edge| {
+            let vertex = QueryVertex {
+                properties: vec![],
+                edges: next.into_iter().collect(),
+                ..edge.vertex.clone()
+            };
+            Some(QueryEdge { vertex, ..edge.clone() })
+        })
+        .expect("path is not empty");
+    to_query_text(&root)
+}
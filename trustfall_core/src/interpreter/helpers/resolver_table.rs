@@ -0,0 +1,202 @@
+use std::{collections::HashMap, fmt::Debug, rc::Rc, sync::Arc};
+
+use crate::{
+    interpreter::{AsVertex, ContextIterator, ContextOutcomeIterator, VertexIterator},
+    ir::{EdgeParameters, FieldValue},
+};
+
+use super::{resolve_coercion_with, resolve_neighbors_with, resolve_property_with};
+
+type PropertyResolver<'vertex, Vertex> = Rc<dyn Fn(&Vertex) -> FieldValue + 'vertex>;
+type EdgeResolver<'vertex, Vertex> =
+    Rc<dyn Fn(&Vertex, &EdgeParameters) -> VertexIterator<'vertex, Vertex> + 'vertex>;
+type CoercionResolver<'vertex, Vertex> = Rc<dyn Fn(&Vertex) -> bool + 'vertex>;
+
+/// Resolvers keyed by type name and then by field name, so they can be looked up by `&str`.
+type Table<T> = HashMap<Arc<str>, HashMap<Arc<str>, T>>;
+
+/// A table of per-type resolvers for properties, edges, and type coercions,
+/// for implementing [`Adapter`](crate::interpreter::Adapter) methods without
+/// large `match` statements over type and field names.
+///
+/// Resolvers are registered for a specific `(type, field)` pair, and looked up
+/// by hashing rather than by string comparisons when the adapter method is called.
+/// Each resolver is then applied to the vertices in the input context iterator, one at a time,
+/// like [`resolve_property_with`], [`resolve_neighbors_with`], and [`resolve_coercion_with`].
+///
+/// Resolvers are looked up by the exact type name passed to the adapter method.
+/// A field defined on an interface may be resolved on the interface type itself
+/// or on any of its implementers, so it must be registered for each such type
+/// the adapter's schema allows queries to use.
+///
+/// # Example
+/// ```
+/// # use trustfall_core::{
+/// #     interpreter::{helpers::ResolverTable, ContextIterator, ContextOutcomeIterator},
+/// #     ir::FieldValue,
+/// # };
+/// #[derive(Debug, Clone)]
+/// struct User {
+///     name: String,
+///     friends: Vec<User>,
+/// }
+///
+/// let table = ResolverTable::new()
+///     .property("User", "name", |user: &User| user.name.clone().into())
+///     .edge("User", "friend", |user: &User, _parameters| {
+///         Box::new(user.friends.clone().into_iter())
+///     });
+///
+/// # fn resolve_property<'a>(
+/// #     table: &ResolverTable<'a, User>,
+/// #     contexts: ContextIterator<'a, User>,
+/// # ) -> ContextOutcomeIterator<'a, User, FieldValue> {
+/// // In the implementation of `Adapter::resolve_property()`:
+/// table.resolve_property(contexts, "User", "name")
+/// # }
+/// ```
+pub struct ResolverTable<'vertex, Vertex> {
+    properties: Table<PropertyResolver<'vertex, Vertex>>,
+    edges: Table<EdgeResolver<'vertex, Vertex>>,
+    coercions: Table<CoercionResolver<'vertex, Vertex>>,
+}
+
+impl<'vertex, Vertex> Debug for ResolverTable<'vertex, Vertex> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn sorted<T>(table: &Table<T>) -> Vec<(&str, &str)> {
+            let mut keys: Vec<_> = table
+                .iter()
+                .flat_map(|(type_name, fields)| {
+                    fields.keys().map(move |field_name| (type_name.as_ref(), field_name.as_ref()))
+                })
+                .collect();
+            keys.sort_unstable();
+            keys
+        }
+
+        f.debug_struct("ResolverTable")
+            .field("properties", &sorted(&self.properties))
+            .field("edges", &sorted(&self.edges))
+            .field("coercions", &sorted(&self.coercions))
+            .finish()
+    }
+}
+
+impl<'vertex, Vertex> Default for ResolverTable<'vertex, Vertex> {
+    fn default() -> Self {
+        Self { properties: HashMap::new(), edges: HashMap::new(), coercions: HashMap::new() }
+    }
+}
+
+impl<'vertex, Vertex: Debug + Clone + 'vertex> ResolverTable<'vertex, Vertex> {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the resolver for the named property of the named type,
+    /// replacing any previously-registered resolver for it.
+    pub fn property(
+        mut self,
+        type_name: impl Into<Arc<str>>,
+        property_name: impl Into<Arc<str>>,
+        resolver: impl Fn(&Vertex) -> FieldValue + 'vertex,
+    ) -> Self {
+        self.properties
+            .entry(type_name.into())
+            .or_default()
+            .insert(property_name.into(), Rc::new(resolver));
+        self
+    }
+
+    /// Register the resolver for the named edge of the named type,
+    /// replacing any previously-registered resolver for it.
+    ///
+    /// The resolver is given the edge's parameters together with each vertex.
+    pub fn edge(
+        mut self,
+        type_name: impl Into<Arc<str>>,
+        edge_name: impl Into<Arc<str>>,
+        resolver: impl Fn(&Vertex, &EdgeParameters) -> VertexIterator<'vertex, Vertex> + 'vertex,
+    ) -> Self {
+        self.edges.entry(type_name.into()).or_default().insert(edge_name.into(), Rc::new(resolver));
+        self
+    }
+
+    /// Register the resolver for coercing vertices of the named type to the named subtype,
+    /// replacing any previously-registered resolver for it.
+    pub fn coercion(
+        mut self,
+        type_name: impl Into<Arc<str>>,
+        coerce_to_type: impl Into<Arc<str>>,
+        resolver: impl Fn(&Vertex) -> bool + 'vertex,
+    ) -> Self {
+        self.coercions
+            .entry(type_name.into())
+            .or_default()
+            .insert(coerce_to_type.into(), Rc::new(resolver));
+        self
+    }
+
+    /// Resolve the named property of the named type, using its registered resolver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no resolver is registered for this property of this type.
+    pub fn resolve_property<V: AsVertex<Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &str,
+        property_name: &str,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let resolver = lookup(&self.properties, type_name, property_name).unwrap_or_else(|| {
+            unreachable!(
+                "attempted to read property '{property_name}' on unexpected type: {type_name}"
+            )
+        });
+        resolve_property_with(contexts, move |vertex| resolver(vertex))
+    }
+
+    /// Resolve the named edge of the named type, using its registered resolver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no resolver is registered for this edge of this type.
+    pub fn resolve_neighbors<V: AsVertex<Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &str,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Vertex>> {
+        let resolver = lookup(&self.edges, type_name, edge_name).unwrap_or_else(|| {
+            unreachable!("attempted to resolve edge '{edge_name}' on unexpected type: {type_name}")
+        });
+        let parameters = parameters.clone();
+        resolve_neighbors_with(contexts, move |vertex| resolver(vertex, &parameters))
+    }
+
+    /// Resolve the coercion of the named type to the named subtype,
+    /// using its registered resolver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no resolver is registered for this coercion.
+    pub fn resolve_coercion<V: AsVertex<Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &str,
+        coerce_to_type: &str,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let resolver = lookup(&self.coercions, type_name, coerce_to_type).unwrap_or_else(|| {
+            unreachable!(
+                "attempted to coerce type '{type_name}' to unexpected type: {coerce_to_type}"
+            )
+        });
+        resolve_coercion_with(contexts, move |vertex| resolver(vertex))
+    }
+}
+
+fn lookup<T: Clone>(table: &Table<T>, type_name: &str, field_name: &str) -> Option<T> {
+    table.get(type_name)?.get(field_name).cloned()
+}
@@ -27,10 +27,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type RootSchemaQuery {
     Vertex: Vertex!
@@ -772,3 +777,478 @@ mod correctness {
         }
     }
 }
+
+mod vertex_sample {
+    use std::sync::Arc;
+
+    use crate::{
+        interpreter::{
+            helpers::check_adapter_invariants_with_vertex_sample, Adapter, AsVertex,
+            ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo, VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue},
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
+    };
+
+    #[test]
+    fn vertex_sample_checker_approves_valid_adapter() {
+        let adapter = NumbersAdapter::new();
+        let schema = adapter.schema().clone();
+
+        check_adapter_invariants_with_vertex_sample(&schema, adapter, 16);
+    }
+
+    /// Wraps [`NumbersAdapter`], overriding the outcome of coercions of existing vertices.
+    struct BrokenAdapter {
+        inner: NumbersAdapter,
+        coercion_outcome: bool,
+    }
+
+    impl<'a> Adapter<'a> for BrokenAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'a, Self::Vertex> {
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            _type_name: &Arc<str>,
+            _coerce_to_type: &Arc<str>,
+            _resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, bool> {
+            let outcome = self.coercion_outcome;
+            Box::new(contexts.map(move |ctx| {
+                let exists = ctx.active_vertex().is_some();
+                (ctx, exists && outcome)
+            }))
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "resolve_coercion() coerced a vertex with __typename 'Neither' \
+                               from type 'Number' to 'Composite', \
+                               but 'Neither' is not a subtype of 'Composite'")]
+    fn coercion_to_unrelated_type() {
+        let inner = NumbersAdapter::new();
+        let schema = inner.schema().clone();
+        let adapter = BrokenAdapter { inner, coercion_outcome: true };
+
+        check_adapter_invariants_with_vertex_sample(&schema, adapter, 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "resolve_coercion() did not coerce a vertex with __typename \
+                               'Neither' from type 'Number' to 'Neither', \
+                               even though 'Neither' is a subtype of 'Neither'")]
+    fn missing_coercion_to_own_type() {
+        let inner = NumbersAdapter::new();
+        let schema = inner.schema().clone();
+        let adapter = BrokenAdapter { inner, coercion_outcome: false };
+
+        check_adapter_invariants_with_vertex_sample(&schema, adapter, 16);
+    }
+}
+
+mod resolver_table {
+    use crate::{
+        interpreter::{helpers::ResolverTable, DataContext},
+        ir::{EdgeParameters, FieldValue},
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Number(i64);
+
+    fn make_table() -> ResolverTable<'static, Number> {
+        let mut table = ResolverTable::new();
+        for type_name in ["Number", "Even"] {
+            table = table.property(type_name, "value", |vertex: &Number| vertex.0.into()).edge(
+                type_name,
+                "successor",
+                |vertex: &Number, _: &EdgeParameters| {
+                    Box::new(std::iter::once(Number(vertex.0 + 1)))
+                },
+            );
+        }
+        table.coercion("Number", "Even", |vertex: &Number| vertex.0 % 2 == 0)
+    }
+
+    fn make_contexts() -> Box<dyn Iterator<Item = DataContext<Number>>> {
+        Box::new(
+            [Some(Number(1)), None, Some(Number(2))]
+                .into_iter()
+                .map(DataContext::new)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    #[test]
+    fn resolvers_are_dispatched_by_type_and_field() {
+        let table = make_table();
+
+        let values: Vec<_> = table
+            .resolve_property(make_contexts(), "Even", "value")
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(vec![FieldValue::Int64(1), FieldValue::Null, FieldValue::Int64(2)], values);
+
+        let neighbors: Vec<Vec<_>> = table
+            .resolve_neighbors(make_contexts(), "Number", "successor", &EdgeParameters::default())
+            .map(|(_, neighbors)| neighbors.collect())
+            .collect();
+        assert_eq!(vec![vec![Number(2)], vec![], vec![Number(3)]], neighbors);
+
+        let coercions: Vec<_> = table
+            .resolve_coercion(make_contexts(), "Number", "Even")
+            .map(|(_, outcome)| outcome)
+            .collect();
+        assert_eq!(vec![false, false, true], coercions);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to read property 'name' on unexpected type: Number")]
+    fn missing_resolver() {
+        let _ = make_table().resolve_property(make_contexts(), "Number", "name");
+    }
+}
+
+mod pushdown {
+    use std::ops::Bound;
+
+    use crate::{
+        interpreter::{
+            helpers::{
+                candidate_to_key_ranges, candidate_to_predicate, candidate_to_sql_condition,
+                KeyRange, KeyRanges,
+            },
+            CandidateValue, Range, StringPattern,
+        },
+        ir::FieldValue,
+    };
+
+    fn range_candidate(
+        start: Bound<i64>,
+        end: Bound<i64>,
+        null_included: bool,
+    ) -> CandidateValue<FieldValue> {
+        CandidateValue::Range(Range::new(
+            start.map(FieldValue::Int64),
+            end.map(FieldValue::Int64),
+            null_included,
+        ))
+    }
+
+    #[test]
+    fn sql_conditions() {
+        let one = FieldValue::Int64(1);
+        let two = FieldValue::Int64(2);
+        let test_cases = [
+            (CandidateValue::Impossible, "1 = 0", vec![]),
+            (CandidateValue::All, "1 = 1", vec![]),
+            (CandidateValue::Single(FieldValue::Null), "x IS NULL", vec![]),
+            (CandidateValue::Single(one.clone()), "x = ?", vec![one.clone()]),
+            (
+                CandidateValue::Multiple(vec![one.clone(), two.clone()]),
+                "x IN (?, ?)",
+                vec![one.clone(), two.clone()],
+            ),
+            (
+                CandidateValue::Multiple(vec![FieldValue::Null, two.clone()]),
+                "(x IN (?) OR x IS NULL)",
+                vec![two.clone()],
+            ),
+            (
+                range_candidate(Bound::Included(1), Bound::Excluded(2), false),
+                "x >= ? AND x < ?",
+                vec![one.clone(), two.clone()],
+            ),
+            (
+                range_candidate(Bound::Excluded(1), Bound::Unbounded, true),
+                "(x > ? OR x IS NULL)",
+                vec![one.clone()],
+            ),
+            (range_candidate(Bound::Unbounded, Bound::Unbounded, false), "x IS NOT NULL", vec![]),
+        ];
+
+        for (candidate, condition, parameters) in test_cases {
+            let sql = candidate_to_sql_condition("x", &candidate);
+            assert_eq!(condition, sql.condition, "{candidate:?}");
+            assert_eq!(parameters, sql.parameters, "{candidate:?}");
+        }
+    }
+
+    #[test]
+    fn predicates_match_candidates() {
+        let values = [
+            FieldValue::Null,
+            FieldValue::Int64(0),
+            FieldValue::Int64(1),
+            FieldValue::Uint64(2),
+            FieldValue::Int64(3),
+        ];
+        let test_cases = [
+            (CandidateValue::Impossible, vec![false, false, false, false, false]),
+            (CandidateValue::All, vec![true, true, true, true, true]),
+            (CandidateValue::Single(FieldValue::Uint64(1)), vec![false, false, true, false, false]),
+            (
+                CandidateValue::Multiple(vec![FieldValue::Null, FieldValue::Int64(2)]),
+                vec![true, false, false, true, false],
+            ),
+            (
+                range_candidate(Bound::Excluded(0), Bound::Included(2), true),
+                vec![true, false, true, true, false],
+            ),
+        ];
+
+        for (candidate, expected) in test_cases {
+            let predicate = candidate_to_predicate(candidate.clone());
+            let actual: Vec<_> = values.iter().map(&predicate).collect();
+            assert_eq!(expected, actual, "{candidate:?}");
+        }
+    }
+
+    #[test]
+    fn key_ranges() {
+        let point = |value| KeyRange::point(FieldValue::Int64(value));
+        let test_cases = [
+            (CandidateValue::Impossible, KeyRanges { ranges: vec![], null_included: false }),
+            (
+                CandidateValue::All,
+                KeyRanges {
+                    ranges: vec![KeyRange { start: Bound::Unbounded, end: Bound::Unbounded }],
+                    null_included: true,
+                },
+            ),
+            (
+                CandidateValue::Single(FieldValue::Null),
+                KeyRanges { ranges: vec![], null_included: true },
+            ),
+            (
+                CandidateValue::Multiple(vec![
+                    FieldValue::Int64(3),
+                    FieldValue::Null,
+                    FieldValue::Int64(1),
+                    FieldValue::Int64(3),
+                ]),
+                KeyRanges { ranges: vec![point(1), point(3)], null_included: true },
+            ),
+            (
+                range_candidate(Bound::Included(1), Bound::Excluded(5), false),
+                KeyRanges {
+                    ranges: vec![KeyRange {
+                        start: Bound::Included(FieldValue::Int64(1)),
+                        end: Bound::Excluded(FieldValue::Int64(5)),
+                    }],
+                    null_included: false,
+                },
+            ),
+            (
+                range_candidate(Bound::Included(5), Bound::Excluded(5), true),
+                KeyRanges { ranges: vec![], null_included: true },
+            ),
+        ];
+
+        for (candidate, expected) in test_cases {
+            assert_eq!(expected, candidate_to_key_ranges(&candidate), "{candidate:?}");
+        }
+
+        assert!(point(1).is_point());
+        assert!(point(1).contains(&FieldValue::Uint64(1)));
+        assert!(!point(1).contains(&FieldValue::Int64(2)));
+    }
+
+    #[test]
+    fn string_patterns() {
+        let string = |value: &str| FieldValue::String(value.into());
+        let prefix_only =
+            CandidateValue::StringPattern(StringPattern::new(Some(string("ab")), None, vec![]));
+        let everything = CandidateValue::StringPattern(StringPattern::new(
+            Some(string("a%")),
+            Some(string("_z")),
+            vec![string("m\\n")],
+        ));
+
+        let sql = candidate_to_sql_condition("x", &prefix_only);
+        assert_eq!("x LIKE ? ESCAPE '\\'", sql.condition);
+        assert_eq!(vec![string("ab%")], sql.parameters);
+
+        let sql = candidate_to_sql_condition("x", &everything);
+        assert_eq!(
+            "x LIKE ? ESCAPE '\\' AND x LIKE ? ESCAPE '\\' AND x LIKE ? ESCAPE '\\'",
+            sql.condition,
+        );
+        assert_eq!(vec![string("a\\%%"), string("%\\_z"), string("%m\\\\n%")], sql.parameters);
+
+        let values = [
+            FieldValue::Null,
+            FieldValue::Int64(1),
+            string("abc"),
+            string("a%m\\n_z"),
+            string("b"),
+        ];
+        let predicate = candidate_to_predicate(prefix_only.clone());
+        let actual: Vec<_> = values.iter().map(&predicate).collect();
+        assert_eq!(vec![false, false, true, false, false], actual);
+        let predicate = candidate_to_predicate(everything.clone());
+        let actual: Vec<_> = values.iter().map(&predicate).collect();
+        assert_eq!(vec![false, false, false, true, false], actual);
+
+        assert_eq!(
+            KeyRanges {
+                ranges: vec![KeyRange {
+                    start: Bound::Included(string("ab")),
+                    end: Bound::Unbounded
+                }],
+                null_included: false,
+            },
+            candidate_to_key_ranges(&prefix_only),
+        );
+        let suffix_only =
+            CandidateValue::StringPattern(StringPattern::new(None, Some(string("z")), vec![]));
+        assert_eq!(
+            KeyRanges {
+                ranges: vec![KeyRange { start: Bound::Unbounded, end: Bound::Unbounded }],
+                null_included: false,
+            },
+            candidate_to_key_ranges(&suffix_only),
+        );
+    }
+}
+
+#[cfg(feature = "test-utils")]
+mod random_queries {
+    use std::sync::Arc;
+
+    use crate::{
+        interpreter::{
+            helpers::{check_adapter_with_random_queries, RandomQueryConfig},
+            Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo,
+            ResolveInfo, VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue},
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
+    };
+
+    #[test]
+    fn random_query_checker_approves_valid_adapter() {
+        let adapter = NumbersAdapter::new();
+        let schema = adapter.schema().clone();
+
+        for seed in 0..3 {
+            let config = RandomQueryConfig { seed, ..Default::default() };
+            check_adapter_with_random_queries(&schema, NumbersAdapter::new(), config);
+        }
+    }
+
+    /// Wraps [`NumbersAdapter`], optionally breaking its property values and coercions.
+    struct BrokenAdapter {
+        inner: NumbersAdapter,
+        stringly_typed_values: bool,
+        always_coerce: bool,
+    }
+
+    impl<'a> Adapter<'a> for BrokenAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'a, Self::Vertex> {
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+            let resolved =
+                self.inner.resolve_property(contexts, type_name, property_name, resolve_info);
+            if self.stringly_typed_values && property_name.as_ref() == "value" {
+                Box::new(resolved.map(|(ctx, value)| match value {
+                    FieldValue::Int64(value) => (ctx, value.to_string().into()),
+                    value => (ctx, value),
+                }))
+            } else {
+                resolved
+            }
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+            &self,
+            contexts: ContextIterator<'a, V>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'a, V, bool> {
+            if self.always_coerce {
+                Box::new(contexts.map(|ctx| (ctx, true)))
+            } else {
+                self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a valid Int")]
+    fn property_of_the_wrong_type() {
+        let inner = NumbersAdapter::new();
+        let schema = inner.schema().clone();
+        let adapter = BrokenAdapter { inner, stringly_typed_values: true, always_coerce: false };
+
+        check_adapter_with_random_queries(&schema, adapter, RandomQueryConfig::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "which isn't produced without the coercion")]
+    fn coercion_that_always_succeeds() {
+        let inner = NumbersAdapter::new();
+        let schema = inner.schema().clone();
+        let adapter = BrokenAdapter { inner, stringly_typed_values: false, always_coerce: true };
+
+        check_adapter_with_random_queries(&schema, adapter, RandomQueryConfig::default());
+    }
+}
@@ -29,6 +29,10 @@ pub enum CandidateValue<T> {
     /// the range may include or exclude the `null` value.
     Range(Range<T>),
 
+    /// The property's value is a string with a particular prefix, suffix, and/or substrings,
+    /// as required by filters like `has_prefix`. The `null` value is never included.
+    StringPattern(StringPattern<T>),
+
     /// We've detected no constraints on the value of this property.
     All,
 }
@@ -43,6 +47,7 @@ impl<T: Clone> CandidateValue<&T> {
                 CandidateValue::Multiple(m.iter().copied().cloned().collect())
             }
             CandidateValue::Range(r) => CandidateValue::Range(r.cloned()),
+            CandidateValue::StringPattern(p) => CandidateValue::StringPattern(p.cloned()),
             CandidateValue::All => CandidateValue::All,
         }
     }
@@ -56,6 +61,7 @@ impl<T: Clone> CandidateValue<T> {
             CandidateValue::Single(s) => CandidateValue::Single(s),
             CandidateValue::Multiple(m) => CandidateValue::Multiple(m.iter().collect()),
             CandidateValue::Range(r) => CandidateValue::Range(r.as_ref()),
+            CandidateValue::StringPattern(p) => CandidateValue::StringPattern(p.as_ref()),
             CandidateValue::All => CandidateValue::All,
         }
     }
@@ -70,6 +76,9 @@ impl<T: Clone> CandidateValue<Cow<'_, T>> {
                 CandidateValue::Multiple(mult.into_iter().map(|x| x.into_owned()).collect())
             }
             CandidateValue::Range(range) => CandidateValue::Range(range.into_owned()),
+            CandidateValue::StringPattern(pattern) => {
+                CandidateValue::StringPattern(pattern.into_owned())
+            }
             CandidateValue::All => CandidateValue::All,
         }
     }
@@ -82,6 +91,9 @@ impl<T: Clone> CandidateValue<Cow<'_, T>> {
                 CandidateValue::Multiple(mult.iter().map(|x| x.as_ref()).collect())
             }
             CandidateValue::Range(range) => CandidateValue::Range(range.as_deref()),
+            CandidateValue::StringPattern(pattern) => {
+                CandidateValue::StringPattern(pattern.as_deref())
+            }
             CandidateValue::All => CandidateValue::All,
         }
     }
@@ -96,6 +108,9 @@ impl<T: Clone + PartialEq> PartialEq<CandidateValue<&T>> for CandidateValue<Cow<
                 mult_l.len() == mult_r.len() && mult_l.iter().zip(mult_r.iter()).all_equal()
             }
             (CandidateValue::Range(l), CandidateValue::Range(r)) => l.as_deref() == *r,
+            (CandidateValue::StringPattern(l), CandidateValue::StringPattern(r)) => {
+                l.as_deref() == *r
+            }
             (CandidateValue::All, CandidateValue::All) => true,
             _ => false,
         }
@@ -111,6 +126,9 @@ impl<T: Clone + PartialEq> PartialEq<CandidateValue<T>> for CandidateValue<Cow<'
                 mult_l.len() == mult_r.len() && mult_l.iter().zip(mult_r.iter()).all_equal()
             }
             (CandidateValue::Range(l), CandidateValue::Range(r)) => l.as_deref() == r.as_ref(),
+            (CandidateValue::StringPattern(l), CandidateValue::StringPattern(r)) => {
+                l.as_deref() == r.as_ref()
+            }
             (CandidateValue::All, CandidateValue::All) => true,
             _ => false,
         }
@@ -118,7 +136,10 @@ impl<T: Clone + PartialEq> PartialEq<CandidateValue<T>> for CandidateValue<Cow<'
 }
 
 impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue + Default> CandidateValue<T> {
-    pub(super) fn intersect(&mut self, mut other: CandidateValue<T>) {
+    pub(super) fn intersect(&mut self, mut other: CandidateValue<T>)
+    where
+        T: Borrow<FieldValue>,
+    {
         match self {
             Self::Impossible => {} // still impossible
             Self::Single(val) => {
@@ -141,6 +162,11 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue + Default> C
                             *self = CandidateValue::Impossible;
                         }
                     }
+                    Self::StringPattern(pattern) => {
+                        if !pattern.matches((*val).borrow()) {
+                            *self = CandidateValue::Impossible;
+                        }
+                    }
                     Self::All => {} // self is unchanged.
                 }
             }
@@ -168,6 +194,9 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue + Default> C
                             unreachable!("expected only Multiple or Range in this branch, but got: {other:?}");
                         }
                     }
+                    Self::StringPattern(pattern) => {
+                        multiple.retain(|value| pattern.matches(value.borrow()));
+                    }
                     Self::All => {} // self is unchanged.
                 }
             }
@@ -182,6 +211,34 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue + Default> C
                     *self = other;
                 }
             }
+            Self::StringPattern(pattern) => match other {
+                Self::Impossible => *self = CandidateValue::Impossible,
+                Self::Range(mut range) => {
+                    // Patterns and ranges can't be combined into a single candidate.
+                    // Unless the range only excludes `null`, which patterns already do,
+                    // prefer the range since more kinds of data sources can use it.
+                    // Either way, the candidate still includes every value allowed by both.
+                    if range.start_bound() != Bound::Unbounded
+                        || range.end_bound() != Bound::Unbounded
+                    {
+                        range.null_included = false;
+                        *self = Self::Range(range);
+                    }
+                }
+                Self::StringPattern(other) => {
+                    if !pattern.intersect(other) {
+                        *self = CandidateValue::Impossible;
+                    }
+                }
+                Self::All => {} // self is unchanged.
+                Self::Single(_) | Self::Multiple(_) => {
+                    // We've already handled this case, just with operands reversed.
+                    let mut placeholder = CandidateValue::All;
+                    std::mem::swap(self, &mut placeholder);
+                    other.intersect(placeholder);
+                    *self = other;
+                }
+            },
             Self::All => {
                 // Whatever the other candidate was. It can't be any wider than Self::All.
                 *self = other;
@@ -228,6 +285,9 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue + Default> C
                 }
                 self.normalize();
             }
+            CandidateValue::StringPattern(_) => {
+                // Patterns already exclude null, and can't exclude individual values.
+            }
             CandidateValue::All => {
                 // We can only meaningfully exclude null values from the full range.
                 //
@@ -307,6 +367,147 @@ impl NullableValue for Cow<'_, FieldValue> {
     }
 }
 
+/// Requirements on a string value: a prefix and a suffix it must have,
+/// and substrings it must contain. Any of them may be absent.
+///
+/// String values that satisfy all the requirements are said to match the pattern.
+/// The `null` value never matches any pattern.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringPattern<T> {
+    prefix: Option<T>,
+    suffix: Option<T>,
+    substrings: Vec<T>,
+}
+
+impl<T> StringPattern<T> {
+    pub(crate) fn new(prefix: Option<T>, suffix: Option<T>, substrings: Vec<T>) -> Self {
+        Self { prefix, suffix, substrings }
+    }
+
+    pub(crate) fn with_prefix(prefix: T) -> Self {
+        Self { prefix: Some(prefix), suffix: None, substrings: vec![] }
+    }
+
+    pub(crate) fn with_suffix(suffix: T) -> Self {
+        Self { prefix: None, suffix: Some(suffix), substrings: vec![] }
+    }
+
+    pub(crate) fn with_substring(substring: T) -> Self {
+        Self { prefix: None, suffix: None, substrings: vec![substring] }
+    }
+
+    /// The value the string must start with, if any.
+    #[inline]
+    pub fn prefix(&self) -> Option<&T> {
+        self.prefix.as_ref()
+    }
+
+    /// The value the string must end with, if any.
+    #[inline]
+    pub fn suffix(&self) -> Option<&T> {
+        self.suffix.as_ref()
+    }
+
+    /// Values the string must contain. May be empty.
+    #[inline]
+    pub fn substrings(&self) -> &[T] {
+        &self.substrings
+    }
+
+    /// Converts from `&StringPattern<T>` to `StringPattern<&T>`.
+    pub fn as_ref(&self) -> StringPattern<&T> {
+        StringPattern {
+            prefix: self.prefix.as_ref(),
+            suffix: self.suffix.as_ref(),
+            substrings: self.substrings.iter().collect(),
+        }
+    }
+}
+
+impl<T: Clone> StringPattern<&T> {
+    /// Converts from `StringPattern<&T>` to `StringPattern<T>`.
+    pub fn cloned(&self) -> StringPattern<T> {
+        StringPattern {
+            prefix: self.prefix.cloned(),
+            suffix: self.suffix.cloned(),
+            substrings: self.substrings.iter().copied().cloned().collect(),
+        }
+    }
+}
+
+impl<T: Clone> StringPattern<Cow<'_, T>> {
+    fn into_owned(self) -> StringPattern<T> {
+        StringPattern {
+            prefix: self.prefix.map(Cow::into_owned),
+            suffix: self.suffix.map(Cow::into_owned),
+            substrings: self.substrings.into_iter().map(Cow::into_owned).collect(),
+        }
+    }
+
+    fn as_deref(&self) -> StringPattern<&T> {
+        StringPattern {
+            prefix: self.prefix.as_deref(),
+            suffix: self.suffix.as_deref(),
+            substrings: self.substrings.iter().map(AsRef::as_ref).collect(),
+        }
+    }
+}
+
+impl<T: Borrow<FieldValue>> StringPattern<T> {
+    /// Checks whether the specified value matches the pattern.
+    pub fn matches(&self, value: &FieldValue) -> bool {
+        let Some(value) = value.as_str() else {
+            return false;
+        };
+        self.prefix.as_ref().map_or(true, |prefix| value.starts_with(pattern_str(prefix)))
+            && self.suffix.as_ref().map_or(true, |suffix| value.ends_with(pattern_str(suffix)))
+            && self.substrings.iter().all(|substring| value.contains(pattern_str(substring)))
+    }
+
+    /// Narrow the pattern to only match strings that also match the other pattern.
+    /// Returns `false` if no string can match both patterns.
+    fn intersect(&mut self, other: StringPattern<T>) -> bool {
+        fn narrow<T: Borrow<FieldValue>>(
+            current: &mut Option<T>,
+            other: Option<T>,
+            is_narrower: impl Fn(&str, &str) -> bool,
+        ) -> bool {
+            match (current.as_ref(), other) {
+                (_, None) => true,
+                (None, other) => {
+                    *current = other;
+                    true
+                }
+                (Some(existing), Some(other)) => {
+                    let (existing_str, other_str) = (pattern_str(existing), pattern_str(&other));
+                    if is_narrower(other_str, existing_str) {
+                        *current = Some(other);
+                        true
+                    } else {
+                        is_narrower(existing_str, other_str)
+                    }
+                }
+            }
+        }
+
+        let prefix_ok = narrow(&mut self.prefix, other.prefix, |l, r| l.starts_with(r));
+        let suffix_ok = narrow(&mut self.suffix, other.suffix, |l, r| l.ends_with(r));
+        for substring in other.substrings {
+            let substring_str = pattern_str(&substring);
+            if !self.substrings.iter().any(|existing| pattern_str(existing) == substring_str) {
+                self.substrings.push(substring);
+            }
+        }
+
+        prefix_ok && suffix_ok
+    }
+}
+
+fn pattern_str<T: Borrow<FieldValue>>(value: &T) -> &str {
+    value.borrow().as_str().expect("string pattern contained a non-string value")
+}
+
 /// A range of values. Both its endpoints may be included or excluded in the range, or unbounded.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -377,7 +578,7 @@ impl<T> Range<T> {
 }
 
 impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue> Range<T> {
-    pub(super) fn new(start: Bound<T>, end: Bound<T>, null_included: bool) -> Self {
+    pub(crate) fn new(start: Bound<T>, end: Bound<T>, null_included: bool) -> Self {
         match &start {
             Bound::Included(v) | Bound::Excluded(v) => {
                 assert!(!v.is_null(), "cannot bound range with null value")
@@ -393,7 +594,7 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue> Range<T> {
         Self { start, end, null_included }
     }
 
-    pub(super) fn with_start(start: Bound<T>, null_included: bool) -> Self {
+    pub(crate) fn with_start(start: Bound<T>, null_included: bool) -> Self {
         match &start {
             Bound::Included(v) | Bound::Excluded(v) => {
                 assert!(!v.is_null(), "cannot bound range with null value")
@@ -403,7 +604,7 @@ impl<T: Debug + Clone + PartialEq + Eq + PartialOrd + NullableValue> Range<T> {
         Self { start, end: Bound::Unbounded, null_included }
     }
 
-    pub(super) fn with_end(end: Bound<T>, null_included: bool) -> Self {
+    pub(crate) fn with_end(end: Bound<T>, null_included: bool) -> Self {
         match &end {
             Bound::Included(v) | Bound::Excluded(v) => {
                 assert!(!v.is_null(), "cannot bound range with null value")
@@ -845,6 +1046,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn candidate_intersecting_string_patterns() {
+        use super::Range as R;
+        use super::StringPattern as P;
+        use CandidateValue::*;
+        let ab = FieldValue::String("ab".into());
+        let abc = FieldValue::String("abc".into());
+        let b = FieldValue::String("b".into());
+        let bc = FieldValue::String("bc".into());
+        let c = FieldValue::String("c".into());
+        let one = FieldValue::Int64(1);
+
+        let test_cases = [
+            // Values must match the pattern.
+            (Single(&abc), StringPattern(P::with_prefix(&ab)), Single(&abc)),
+            (Single(&bc), StringPattern(P::with_prefix(&ab)), Impossible),
+            (Single(&FieldValue::NULL), StringPattern(P::with_substring(&b)), Impossible),
+            (StringPattern(P::with_suffix(&c)), Single(&abc), Single(&abc)),
+            (
+                Multiple(vec![&ab, &abc, &bc, &FieldValue::NULL]),
+                StringPattern(P::with_suffix(&bc)),
+                Multiple(vec![&abc, &bc]),
+            ),
+            (StringPattern(P::with_substring(&b)), Multiple(vec![&ab, &c, &one]), Single(&ab)),
+            //
+            // Patterns combine, as long as prefixes and suffixes are compatible.
+            (
+                StringPattern(P::with_prefix(&ab)),
+                StringPattern(P::with_prefix(&abc)),
+                StringPattern(P::with_prefix(&abc)),
+            ),
+            (
+                StringPattern(P::with_suffix(&bc)),
+                StringPattern(P::with_suffix(&c)),
+                StringPattern(P::with_suffix(&bc)),
+            ),
+            (StringPattern(P::with_prefix(&ab)), StringPattern(P::with_prefix(&bc)), Impossible),
+            (StringPattern(P::with_suffix(&ab)), StringPattern(P::with_suffix(&c)), Impossible),
+            (
+                StringPattern(P::with_prefix(&ab)),
+                StringPattern(P::with_suffix(&c)),
+                StringPattern(P::new(Some(&ab), Some(&c), vec![])),
+            ),
+            (
+                StringPattern(P::new(None, None, vec![&b, &c])),
+                StringPattern(P::with_substring(&b)),
+                StringPattern(P::new(None, None, vec![&b, &c])),
+            ),
+            //
+            // Patterns and ranges don't combine, so bounded ranges take priority.
+            (
+                StringPattern(P::with_prefix(&ab)),
+                Range(R::with_start(Bound::Included(&b), true)),
+                Range(R::with_start(Bound::Included(&b), false)),
+            ),
+            (
+                Range(R::with_end(Bound::Excluded(&b), false)),
+                StringPattern(P::with_prefix(&ab)),
+                Range(R::with_end(Bound::Excluded(&b), false)),
+            ),
+            (
+                Range(R::full()),
+                StringPattern(P::with_prefix(&ab)),
+                StringPattern(P::with_prefix(&ab)),
+            ),
+            (
+                StringPattern(P::with_prefix(&ab)),
+                Range(R::full_non_null()),
+                StringPattern(P::with_prefix(&ab)),
+            ),
+            //
+            // All and Impossible work as usual.
+            (All, StringPattern(P::with_prefix(&ab)), StringPattern(P::with_prefix(&ab))),
+            (StringPattern(P::with_prefix(&ab)), All, StringPattern(P::with_prefix(&ab))),
+            (StringPattern(P::with_prefix(&ab)), Impossible, Impossible),
+            (Impossible, StringPattern(P::with_prefix(&ab)), Impossible),
+        ];
+
+        for (original, intersected, expected) in test_cases {
+            let mut base = original.clone();
+            base.intersect(intersected.clone());
+            assert_eq!(expected, base, "{original:?} + {intersected:?} = {base:?} != {expected:?}");
+        }
+    }
+
     #[test]
     fn candidate_excluding_value() {
         use super::super::Range as R;
@@ -973,6 +1259,47 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn candidate_ranges_mixing_bigint_and_other_integers() {
+        use super::super::Range as R;
+        use CandidateValue::*;
+        let one = FieldValue::Int64(1);
+        let big = FieldValue::from(num_bigint::BigInt::from(u128::MAX));
+        let small_big = FieldValue::from(num_bigint::BigInt::from(1));
+
+        let test_cases = [
+            (
+                Range(R::with_start(Bound::Included(&one), false)),
+                Range(R::with_end(Bound::Excluded(&big), true)),
+                Range(R::new(Bound::Included(&one), Bound::Excluded(&big), false)),
+            ),
+            (
+                Range(R::with_start(Bound::Included(&big), false)),
+                Range(R::with_start(Bound::Excluded(&one), false)),
+                Range(R::with_start(Bound::Included(&big), false)),
+            ),
+            (
+                Range(R::with_end(Bound::Included(&big), false)),
+                Range(R::with_start(Bound::Included(&small_big), false)),
+                Range(R::new(Bound::Included(&small_big), Bound::Included(&big), false)),
+            ),
+            (Single(&one), Range(R::with_start(Bound::Excluded(&small_big), false)), Impossible),
+            (Single(&one), Single(&small_big), Single(&one)),
+            (Range(R::with_end(Bound::Excluded(&one), true)), Single(&big), Impossible),
+        ];
+
+        for (original, intersected, expected) in test_cases {
+            let mut base = original.clone();
+            base.intersect(intersected.clone());
+            assert_eq!(expected, base, "{original:?} + {intersected:?} = {base:?} != {expected:?}");
+        }
+
+        let mut base = Range(R::with_start(Bound::Included(&small_big), false));
+        base.exclude_single_value(&one);
+        assert_eq!(Range(R::with_start(Bound::Excluded(&small_big), false)), base);
+    }
+
     #[test]
     fn candidate_direct_normalization() {
         use super::Range as R;
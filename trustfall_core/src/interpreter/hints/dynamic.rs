@@ -6,7 +6,8 @@ use crate::{
             compute_context_field_with_separate_value,
             compute_fold_specific_field_with_separate_value, QueryCarrier,
         },
-        hints::Range,
+        filtering::RegexCache,
+        hints::{Range, StringPattern},
         Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, InterpretedQuery, TaggedValue,
         VertexIterator,
     },
@@ -223,7 +224,7 @@ impl<'a> DynamicallyResolvedValue<'a> {
                     let field_ref = self.field;
                     self.compute_candidate_from_tagged_value_with_imported_tags(field_ref, contexts)
                 } else {
-                    self.resolve_fold_specific_field(fold_field, contexts)
+                    self.resolve_fold_specific_field(fold_field, adapter, contexts)
                 }
             }
         }
@@ -263,7 +264,8 @@ impl<'a> DynamicallyResolvedValue<'a> {
         adapter: &AdapterT,
         contexts: ContextIterator<'vertex, V>,
     ) -> ContextOutcomeIterator<'vertex, V, CandidateValue<FieldValue>> {
-        let mut carrier = QueryCarrier { query: Some(self.query) };
+        let mut carrier =
+            QueryCarrier { query: Some(self.query), regex_cache: RegexCache::default() };
         let iterator = compute_context_field_with_separate_value(
             adapter,
             &mut carrier,
@@ -312,12 +314,22 @@ impl<'a> DynamicallyResolvedValue<'a> {
         )
     }
 
-    fn resolve_fold_specific_field<'vertex, VertexT: Debug + Clone + 'vertex>(
+    fn resolve_fold_specific_field<
+        'vertex,
+        AdapterT: Adapter<'vertex>,
+        V: AsVertex<AdapterT::Vertex> + 'vertex,
+    >(
         self,
         fold_field: &'a FoldSpecificField,
-        contexts: ContextIterator<'vertex, VertexT>,
-    ) -> ContextOutcomeIterator<'vertex, VertexT, CandidateValue<FieldValue>> {
+        adapter: &AdapterT,
+        contexts: ContextIterator<'vertex, V>,
+    ) -> ContextOutcomeIterator<'vertex, V, CandidateValue<FieldValue>> {
+        let mut carrier =
+            QueryCarrier { query: Some(self.query), regex_cache: RegexCache::default() };
         let iterator = compute_fold_specific_field_with_separate_value(
+            adapter,
+            &mut carrier,
+            self.resolve_on_component,
             fold_field.fold_eid,
             &fold_field.kind,
             contexts,
@@ -361,7 +373,7 @@ impl<'a> DynamicallyResolvedValue<'a> {
             }
             Operation::GreaterThanOrEqual(_, _) => {
                 resolve_fold_specific_field!(iterator, initial_candidate, candidate, value, {
-                    candidate.intersect(CandidateValue::Range(Range::with_end(
+                    candidate.intersect(CandidateValue::Range(Range::with_start(
                         Bound::Included(value),
                         false,
                     )));
@@ -434,7 +446,7 @@ fn compute_candidate_from_operation<'vertex, Vertex: Debug + Clone + 'vertex>(
         }
         Operation::GreaterThanOrEqual(_, _) => {
             compute_candidate_from_tagged_value!(iterator, initial_candidate, candidate, value, {
-                candidate.intersect(CandidateValue::Range(Range::with_end(
+                candidate.intersect(CandidateValue::Range(Range::with_start(
                     Bound::Included(value),
                     true, // nullability is handled in the initial_candidate
                 )));
@@ -455,6 +467,33 @@ field {} of type {} produced an invalid value when resolving @tag: {value:?}",
                 candidate.intersect(CandidateValue::Multiple(values));
             })
         }
+        Operation::HasPrefix(_, _) => {
+            compute_candidate_from_tagged_value!(iterator, initial_candidate, candidate, value, {
+                candidate.intersect(string_pattern_candidate(value, StringPattern::with_prefix));
+            })
+        }
+        Operation::HasSuffix(_, _) => {
+            compute_candidate_from_tagged_value!(iterator, initial_candidate, candidate, value, {
+                candidate.intersect(string_pattern_candidate(value, StringPattern::with_suffix));
+            })
+        }
+        Operation::HasSubstring(_, _) => {
+            compute_candidate_from_tagged_value!(iterator, initial_candidate, candidate, value, {
+                candidate.intersect(string_pattern_candidate(value, StringPattern::with_substring));
+            })
+        }
         _ => unreachable!("unsupported 'operation': {:?}", operation,),
     }
 }
+
+fn string_pattern_candidate(
+    value: FieldValue,
+    make_pattern: impl FnOnce(FieldValue) -> StringPattern<FieldValue>,
+) -> CandidateValue<FieldValue> {
+    if matches!(value, FieldValue::Null) {
+        // String filters with a null operand never match.
+        CandidateValue::Impossible
+    } else {
+        CandidateValue::StringPattern(make_pattern(value))
+    }
+}
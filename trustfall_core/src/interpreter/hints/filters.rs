@@ -4,7 +4,7 @@ use itertools::Itertools;
 
 use crate::ir::{Argument, FieldValue, FoldSpecificFieldKind, IRFold, Operation};
 
-use super::{candidates::NullableValue, CandidateValue, Range};
+use super::{candidates::NullableValue, CandidateValue, Range, StringPattern};
 
 pub(super) fn candidate_from_statically_evaluated_filters<'a, 'b, T: Debug + Clone + Eq + 'a>(
     relevant_filters: impl Iterator<Item = &'a Operation<T, Argument>>,
@@ -36,6 +36,15 @@ pub(super) fn candidate_from_statically_evaluated_filters<'a, 'b, T: Debug + Clo
                 (Operation::GreaterThanOrEqual(_, _), Some(value)) => itertools::Either::Left(
                     CandidateValue::Range(Range::with_start(Bound::Included(value), true)),
                 ),
+                (Operation::Between(_, lower, upper), _) => match (
+                    lower.evaluate_statically(query_variables),
+                    upper.evaluate_statically(query_variables),
+                ) {
+                    (Some(lower), Some(upper)) => itertools::Either::Left(CandidateValue::Range(
+                        Range::new(Bound::Included(lower), Bound::Included(upper), true),
+                    )),
+                    _ => itertools::Either::Right(op),
+                },
                 (Operation::OneOf(_, _), Some(value)) => {
                     itertools::Either::Left(CandidateValue::Multiple(
                         value
@@ -44,6 +53,24 @@ pub(super) fn candidate_from_statically_evaluated_filters<'a, 'b, T: Debug + Clo
                             .expect("query variable was not list-typed"),
                     ))
                 }
+                (
+                    Operation::HasPrefix(_, _)
+                    | Operation::HasSuffix(_, _)
+                    | Operation::HasSubstring(_, _),
+                    Some(value),
+                ) if value.is_null() => {
+                    // Null operands make string filters always false.
+                    itertools::Either::Left(CandidateValue::Impossible)
+                }
+                (Operation::HasPrefix(_, _), Some(value)) => itertools::Either::Left(
+                    CandidateValue::StringPattern(StringPattern::with_prefix(value)),
+                ),
+                (Operation::HasSuffix(_, _), Some(value)) => itertools::Either::Left(
+                    CandidateValue::StringPattern(StringPattern::with_suffix(value)),
+                ),
+                (Operation::HasSubstring(_, _), Some(value)) => itertools::Either::Left(
+                    CandidateValue::StringPattern(StringPattern::with_substring(value)),
+                ),
                 (Operation::NotEquals(_, _), Some(value)) if value.is_null() => {
                     // Special case: `!= null` can generate candidates;
                     // it's the only `!=` operand for which this is true.
@@ -135,6 +162,7 @@ pub(super) fn fold_requires_at_least_one_element(
                 Bound::Excluded(inc) => inc.as_u64().is_some(), // any u64 is >= 0
                 Bound::Unbounded => false,
             },
+            CandidateValue::StringPattern(_) => false, // "count" is never a string
             CandidateValue::All => false,
         }
     })
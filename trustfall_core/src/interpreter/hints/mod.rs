@@ -2,10 +2,10 @@ use std::{collections::BTreeMap, fmt::Debug, ops::Bound, sync::Arc};
 
 use self::vertex_info::InternalVertexInfo;
 
-use super::InterpretedQuery;
+use super::{execution::get_fold_element_limit, InterpretedQuery};
 use crate::ir::{
-    EdgeKind, EdgeParameters, Eid, FieldValue, IREdge, IRFold, IRQueryComponent, IRVertex, Output,
-    Recursive, Vid,
+    EdgeKind, EdgeParameters, Eid, FieldValue, IREdge, IRFold, IRQueryComponent, IRVertex,
+    IndexedQuery, Output, Recursive, Vid,
 };
 
 mod candidates;
@@ -14,7 +14,7 @@ mod filters;
 mod sealed;
 mod vertex_info;
 
-pub use candidates::{CandidateValue, Range};
+pub use candidates::{CandidateValue, Range, StringPattern};
 pub use dynamic::DynamicallyResolvedValue;
 pub use vertex_info::{RequiredProperty, VertexInfo};
 
@@ -43,6 +43,27 @@ impl<'a> QueryInfo<'a> {
     pub fn variables(&self) -> &Arc<BTreeMap<Arc<str>, FieldValue>> {
         &self.query.arguments
     }
+
+    /// The maximum number of results the query will produce, if limited with `@limit`.
+    ///
+    /// The limit applies to the query's final results, not to the vertices produced by
+    /// any individual resolver: filters and edges may discard or multiply the results
+    /// that a vertex contributes to. Adapters should only rely on it to stop work early
+    /// when they can prove that additional vertices will not be needed.
+    #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
+    #[inline]
+    pub fn limit(&self) -> Option<usize> {
+        self.query.indexed_query.ir_query.limit.map(|limit| limit.get())
+    }
+
+    /// The number of results the query will skip before producing any, if set with `@offset`.
+    ///
+    /// Like the [`limit()`](Self::limit), the offset applies to the query's final results.
+    #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
+    #[inline]
+    pub fn offset(&self) -> Option<usize> {
+        self.query.indexed_query.ir_query.offset
+    }
 }
 
 /// Enables adapter optimizations by showing how a query uses a vertex. Implements [`VertexInfo`].
@@ -73,6 +94,12 @@ impl ResolveInfo {
         self.query
     }
 
+    #[cfg(feature = "plugins")]
+    #[inline]
+    pub(crate) fn vertex_completed(&self) -> bool {
+        self.vertex_completed
+    }
+
     /// Get information about the overall query being executed.
     #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
     #[inline]
@@ -143,6 +170,7 @@ impl InternalVertexInfo for ResolveInfo {
             optional: edge.optional,
             recursive: edge.recursive.clone(),
             folded: FoldState::None,
+            fold_element_limit: None,
             destination: neighboring_info,
         }
     }
@@ -170,6 +198,7 @@ impl InternalVertexInfo for ResolveInfo {
             } else {
                 FoldState::FoldedOptional
             },
+            fold_element_limit: get_fold_element_limit(&self.query, fold),
             destination: neighboring_info,
         }
     }
@@ -215,6 +244,16 @@ impl ResolveEdgeInfo {
         self.target_vid
     }
 
+    /// The query in which this edge is being expanded.
+    pub(crate) fn indexed_query(&self) -> &IndexedQuery {
+        &self.query.indexed_query
+    }
+
+    /// The IR of the vertex to which this edge points.
+    pub(crate) fn destination_vertex(&self) -> &IRVertex {
+        &self.query.indexed_query.vids[&self.target_vid].vertices[&self.target_vid]
+    }
+
     /// Info about the destination vertex of the edge being expanded where this value was provided.
     #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
     #[inline]
@@ -240,6 +279,7 @@ impl ResolveEdgeInfo {
                     optional: regular.optional,
                     recursive: regular.recursive.clone(),
                     folded: FoldState::None,
+                    fold_element_limit: None,
                     destination: NeighborInfo {
                         query: self.query.clone(),
                         execution_frontier: Bound::Excluded(self.target_vid),
@@ -272,6 +312,7 @@ impl ResolveEdgeInfo {
                     optional: false,
                     recursive: None,
                     folded,
+                    fold_element_limit: get_fold_element_limit(&self.query, fold),
                     destination: NeighborInfo {
                         query: self.query.clone(),
                         execution_frontier: Bound::Excluded(self.target_vid),
@@ -296,6 +337,7 @@ pub struct EdgeInfo {
     optional: bool,
     recursive: Option<Recursive>,
     folded: FoldState,
+    fold_element_limit: Option<usize>,
     destination: NeighborInfo,
 }
 
@@ -321,6 +363,28 @@ impl EdgeInfo {
         &self.destination
     }
 
+    /// The edge's `@recurse` directive, if it has one.
+    #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
+    #[inline]
+    pub fn recursive(&self) -> Option<&Recursive> {
+        self.recursive.as_ref()
+    }
+
+    /// For a folded edge, how many of the fold's elements can affect the query's results,
+    /// if that number is limited. `None` if the edge is not folded, or has no such limit.
+    ///
+    /// The fold's elements are the edge's neighbors that satisfy everything the query
+    /// requires of them inside the fold. For example, a fold that must be empty is discarded
+    /// as soon as it has one element, so its remaining elements don't matter.
+    /// If nothing inside the fold can discard a neighbor, adapters may use this to avoid
+    /// loading more neighbors than needed, such as by limiting how many pages of
+    /// an API's results they request.
+    #[allow(dead_code)] // false-positive: dead in the bin target, not dead in the lib
+    #[inline]
+    pub fn fold_element_limit(&self) -> Option<usize> {
+        self.fold_element_limit
+    }
+
     /// Whether this edge is required to exist, or else the computed row will be discarded.
     #[inline]
     pub fn is_mandatory(&self) -> bool {
@@ -413,6 +477,7 @@ impl InternalVertexInfo for NeighborInfo {
             optional: edge.optional,
             recursive: edge.recursive.clone(),
             folded: FoldState::None,
+            fold_element_limit: None,
             destination: neighboring_info,
         }
     }
@@ -442,12 +507,13 @@ impl InternalVertexInfo for NeighborInfo {
             } else {
                 FoldState::FoldedOptional
             },
+            fold_element_limit: get_fold_element_limit(&self.query, fold),
             destination: neighboring_info,
         }
     }
 }
 
-/// For recursive edges to depth 2+, filter operations at the destination
+/// For recursive edges to depth 2+ or of unbounded depth, filter operations at the destination
 /// do not affect whether the edge is taken or not.
 ///
 /// The query semantics state that recursive edge traversals happen first, then filters are applied.
@@ -455,7 +521,7 @@ impl InternalVertexInfo for NeighborInfo {
 /// With recursions to depth 2+, there are "middle" layers of vertices that don't have to satisfy
 /// the filters (and will be filtered out) but can still have more edge expansions in the recursion.
 fn check_locally_non_binding_filters_for_edge(edge: &IREdge) -> bool {
-    edge.recursive.as_ref().map(|r| r.depth.get() >= 2).unwrap_or(false)
+    edge.recursive.as_ref().map(|r| r.depth.map_or(true, |d| d.get() >= 2)).unwrap_or(false)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -184,6 +184,33 @@ fn root_coercion() {
     assert_eq!(adapter.on_starting_vertices.borrow()[&vid(1)].calls, 1);
 }
 
+#[test]
+fn limit_and_offset() {
+    let input_name = "limit_and_offset";
+
+    let adapter = TestAdapter {
+        on_starting_vertices: btreemap! {
+            vid(1) => TrackCalls::<ResolveInfoFn>::new_underlying(Box::new(|info| {
+                assert_eq!(info.query().limit(), Some(3));
+                assert_eq!(info.query().offset(), Some(2));
+            })),
+        }
+        .into(),
+        on_edge_resolver: btreemap! {
+            eid(1) => TrackCalls::<ResolveEdgeInfoFn>::new_underlying(Box::new(|info| {
+                assert_eq!(info.query().limit(), Some(3));
+                assert_eq!(info.query().offset(), Some(2));
+            })),
+        }
+        .into(),
+        ..Default::default()
+    };
+
+    let adapter = run_query(adapter, input_name);
+    assert_eq!(adapter.on_starting_vertices.borrow()[&vid(1)].calls, 1);
+    assert_eq!(adapter.on_edge_resolver.borrow()[&eid(1)].calls, 1);
+}
+
 #[test]
 fn duplicated_edge() {
     let input_name = "duplicated_edge";
@@ -308,7 +335,7 @@ fn recurse_directive() {
                 assert!(edge.parameters().is_empty());
                 assert!(!edge.optional);
                 assert_eq!(edge.folded, FoldState::None);
-                assert_eq!(edge.recursive, Some(Recursive::new(NonZeroUsize::new(3).unwrap(), None)));
+                assert_eq!(edge.recursive, Some(Recursive::new(NonZeroUsize::new(3), None)));
                 assert_eq!(edge.destination().vid(), vid(2));
 
                 // The "first_edge()" method produces the same outcome
@@ -318,7 +345,8 @@ fn recurse_directive() {
                 // This edge is not mandatory, so it isn't returned.
                 assert!(info.first_mandatory_edge("successor").is_none())
             })),
-        }.into(),
+        }
+        .into(),
         on_edge_resolver: btreemap! {
             eid(1) => TrackCalls::<ResolveEdgeInfoFn>::new_underlying(Box::new(|info| {
                 assert_eq!(info.origin_vid(), vid(1));
@@ -326,7 +354,8 @@ fn recurse_directive() {
                 let destination = info.destination();
                 assert_eq!(destination.vid(), vid(2));
             })),
-        }.into(),
+        }
+        .into(),
         ..Default::default()
     };
 
@@ -433,6 +462,43 @@ fn coercion_on_folded_edge() {
     assert_eq!(adapter.on_type_coercion.borrow()[&vid(2)].calls, 7);
 }
 
+#[test]
+fn fold_element_limit() {
+    let test_cases = [
+        // No filters on the fold's count, so all its elements matter.
+        ("fold_directive", "multiple", None),
+        // Counts other than two fail the filter, and a third element shows the count isn't two.
+        ("fold_count_filter", "primeFactor", Some(3)),
+        // Only the count is observed, and a third element shows the count isn't two.
+        ("fold_count_not_equals_filter", "divisor", Some(3)),
+        // The first element discards the result.
+        ("absent_edge", "primeFactor", Some(1)),
+    ];
+
+    for (input_name, edge_name, expected_limit) in test_cases {
+        let adapter = TestAdapter {
+            on_starting_vertices: btreemap! {
+                vid(1) => TrackCalls::<ResolveInfoFn>::new_underlying(Box::new(move |info| {
+                    let edge = info.first_edge(edge_name).expect("no edge returned");
+                    assert_eq!(expected_limit, edge.fold_element_limit(), "{input_name}");
+                })),
+            }
+            .into(),
+            on_edge_resolver: btreemap! {
+                eid(1) => TrackCalls::<ResolveEdgeInfoFn>::new_underlying(Box::new(move |info| {
+                    assert_eq!(expected_limit, info.edge().fold_element_limit(), "{input_name}");
+                })),
+            }
+            .into(),
+            ..Default::default()
+        };
+
+        let adapter = run_query(adapter, input_name);
+        assert_eq!(adapter.on_starting_vertices.borrow()[&vid(1)].calls, 1);
+        assert!(adapter.on_edge_resolver.borrow()[&eid(1)].calls > 0);
+    }
+}
+
 #[test]
 fn recurse_then_required_edge_depth_one() {
     let input_name = "recurse_then_required_edge_depth_one";
@@ -1247,7 +1313,7 @@ mod dynamic_property_values {
 
     use crate::{
         interpreter::{
-            hints::{CandidateValue, Range},
+            hints::{CandidateValue, Range, StringPattern},
             Adapter, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
             VertexInfo, VertexIterator,
         },
@@ -1477,8 +1543,12 @@ mod dynamic_property_values {
                 let destination = info.destination();
 
                 let expected_values = [
-                    CandidateValue::Single(FieldValue::Int64(3)),
-                    CandidateValue::Multiple(vec![FieldValue::Int64(3), FieldValue::Int64(4)]),
+                    CandidateValue::Multiple(vec![
+                        FieldValue::Int64(3),
+                        FieldValue::Int64(4),
+                        FieldValue::Int64(5),
+                    ]),
+                    CandidateValue::Multiple(vec![FieldValue::Int64(4), FieldValue::Int64(5)]),
                 ];
                 let value_candidate = destination.dynamically_required_property("value");
                 Box::new(
@@ -1516,6 +1586,88 @@ mod dynamic_property_values {
         assert_eq!(adapter.on_edge_resolver.borrow()[&eid(1)].calls, 1);
     }
 
+    /// Ensure that `>=` and `has_prefix` filters on tagged values
+    /// produce ranges and string patterns respectively.
+    #[test]
+    fn dynamic_string_pattern_and_range_filters() {
+        let input_name = "dynamic_string_pattern_and_range_filters";
+
+        struct EdgeResolver;
+
+        impl ResolveEdgeInfoFn for EdgeResolver {
+            fn call<V: AsVertex<NumbersVertex> + 'static>(
+                &mut self,
+                adapter: &NumbersAdapter,
+                ctxs: ContextIterator<'static, V>,
+                info: &ResolveEdgeInfo,
+            ) -> ContextIterator<'static, V> {
+                assert_eq!(eid(1), info.eid());
+                let destination = info.destination();
+
+                let expected_values = [
+                    CandidateValue::Range(Range::with_start(
+                        Bound::Included(FieldValue::Int64(2)),
+                        true,
+                    )),
+                    CandidateValue::Range(Range::with_start(
+                        Bound::Included(FieldValue::Int64(3)),
+                        true,
+                    )),
+                ];
+                let value_candidate = destination.dynamically_required_property("value");
+                let ctxs: ContextIterator<'static, V> = Box::new(
+                    value_candidate
+                        .expect("no dynamic candidate for 'value' property")
+                        .resolve(adapter, ctxs)
+                        .zip_longest(expected_values)
+                        .map(move |data| {
+                            if let EitherOrBoth::Both((ctx, value), expected_value) = data {
+                                assert_eq!(expected_value, value);
+                                ctx
+                            } else {
+                                panic!("unexpected iterator outcome: {data:?}")
+                            }
+                        }),
+                );
+
+                let expected_names = [
+                    CandidateValue::StringPattern(StringPattern::with_prefix(FieldValue::String(
+                        "two".into(),
+                    ))),
+                    CandidateValue::StringPattern(StringPattern::with_prefix(FieldValue::String(
+                        "three".into(),
+                    ))),
+                ];
+                let name_candidate = destination.dynamically_required_property("name");
+                Box::new(
+                    name_candidate
+                        .expect("no dynamic candidate for 'name' property")
+                        .resolve(adapter, ctxs)
+                        .zip_longest(expected_names)
+                        .map(move |data| {
+                            if let EitherOrBoth::Both((ctx, value), expected_value) = data {
+                                assert_eq!(expected_value, value);
+                                ctx
+                            } else {
+                                panic!("unexpected iterator outcome: {data:?}")
+                            }
+                        }),
+                )
+            }
+        }
+
+        let adapter: DynamicTestAdapter<(), (), EdgeResolver, ()> = DynamicTestAdapter {
+            on_edge_resolver: btreemap! {
+                eid(1) => TrackCalls::new_underlying(EdgeResolver),
+            }
+            .into(),
+            ..Default::default()
+        };
+
+        let adapter = run_query(adapter, input_name);
+        assert_eq!(adapter.on_edge_resolver.borrow()[&eid(1)].calls, 1);
+    }
+
     /// The filters are binding since the recursion is depth 1.
     /// This is the analogous dynamic case of the static-only test case
     /// in super::static_property_values::recurse_then_filter_depth_one().
@@ -50,7 +50,7 @@ pub trait VertexInfo: super::sealed::__Sealed {
     fn required_properties(&self) -> Box<dyn Iterator<Item = RequiredProperty> + '_>;
 
     /// Check whether the query demands this vertex property to have specific values:
-    /// a single value, or one of a set or range of values. The candidate values
+    /// a single value, one of a set or range of values, or a string pattern. The candidate values
     /// are known *statically*: up-front, without executing any of the query.
     ///
     /// For example, filtering a property based on a query variable (e.g.
@@ -65,7 +65,7 @@ pub trait VertexInfo: super::sealed::__Sealed {
     fn statically_required_property(&self, name: &str) -> Option<CandidateValue<FieldValue>>;
 
     /// Check whether the query demands this vertex property to have specific values:
-    /// a single value, or one of a set or range of values. The candidate values
+    /// a single value, one of a set or range of values, or a string pattern. The candidate values
     /// are only known *dynamically* i.e. require some of the query
     /// to have already been executed at the point when this method is called.
     ///
@@ -177,21 +177,32 @@ impl<T: InternalVertexInfo + super::sealed::__Sealed> VertexInfo for T {
                 .map(|f| RequiredProperty::new(f.left().field_name.clone())),
         );
 
-        let properties = properties.chain(current_component.vertices.values().flat_map(|v| {
-            v.filters
-                .iter()
-                .filter_map(|f| match f.right() {
-                    Some(Argument::Tag(FieldRef::ContextField(ctx))) => {
-                        if current_vertex.vid == ctx.vertex_id {
-                            Some(ctx.field_name.clone())
-                        } else {
-                            None
-                        }
+        // Tags may be used anywhere in the query, including inside folds nested within
+        // the vertex's component, so look for uses of this vertex's tags in every component.
+        let mut components = vec![&self.query().indexed_query.ir_query.root_component];
+        let mut tag_arguments = vec![];
+        while let Some(component) = components.pop() {
+            for vertex in component.vertices.values() {
+                tag_arguments.extend(vertex.filters.iter().flat_map(|f| f.arguments()));
+            }
+            for fold in component.folds.values() {
+                tag_arguments.extend(fold.post_filters.iter().flat_map(|f| f.arguments()));
+                components.push(&fold.component);
+            }
+        }
+        let properties = properties.chain(
+            tag_arguments
+                .into_iter()
+                .filter_map(|argument| match argument {
+                    Argument::Tag(FieldRef::ContextField(ctx))
+                        if current_vertex.vid == ctx.vertex_id =>
+                    {
+                        Some(ctx.field_name.clone())
                     }
                     _ => None,
                 })
-                .map(RequiredProperty::new)
-        }));
+                .map(RequiredProperty::new),
+        );
 
         let mut seen_property = HashSet::new();
         Box::new(properties.filter(move |r| seen_property.insert(r.name.clone())))
@@ -217,9 +228,9 @@ impl<T: InternalVertexInfo + super::sealed::__Sealed> VertexInfo for T {
         // - statically-resolvable, i.e. do not depend on tagged arguments
         let mut relevant_filters = filters_on_local_property(self.current_vertex(), property)
             .filter(|op| {
-                // Either there's no "right-hand side" in the operator (as in "is_not_null"),
-                // or the right-hand side is a variable.
-                matches!(op.right(), None | Some(Argument::Variable(..)))
+                // Either there are no arguments to the operator (as in "is_not_null"),
+                // or all of them are variables.
+                op.arguments().all(|argument| matches!(argument, Argument::Variable(..)))
             })
             .peekable();
 
@@ -272,6 +283,9 @@ impl<T: InternalVertexInfo + super::sealed::__Sealed> VertexInfo for T {
                         | Operation::GreaterThan(..)
                         | Operation::GreaterThanOrEqual(..)
                         | Operation::OneOf(..)
+                        | Operation::HasPrefix(..)
+                        | Operation::HasSuffix(..)
+                        | Operation::HasSubstring(..)
                 ) && match op.right() {
                     Some(Argument::Tag(FieldRef::ContextField(ctx))) => {
                         // Ensure the vertex holding the @tag has already been computed.
@@ -304,6 +318,7 @@ impl<T: InternalVertexInfo + super::sealed::__Sealed> VertexInfo for T {
         // - an `=` filter
         // - a `one_of` filter
         // - a `< / <= / > / >=` filter
+        // - a `has_prefix / has_suffix / has_substring` filter
         // - a `!=` filter,
         // breaking ties based on which filter was specified first.
         let filter_to_use = {
@@ -324,6 +339,16 @@ impl<T: InternalVertexInfo + super::sealed::__Sealed> VertexInfo for T {
                                             | Operation::GreaterThanOrEqual(..)
                                     )
                                 })
+                                .or_else(|| {
+                                    relevant_filters.iter().find(|op| {
+                                        matches!(
+                                            op,
+                                            Operation::HasPrefix(..)
+                                                | Operation::HasSuffix(..)
+                                                | Operation::HasSubstring(..)
+                                        )
+                                    })
+                                })
                                 .unwrap_or(first_filter)
                         })
                 },
@@ -389,7 +414,12 @@ fn filters_on_local_property<'a: 'b, 'b>(
     vertex: &'a IRVertex,
     property_name: &'b str,
 ) -> impl Iterator<Item = &'a Operation<LocalField, Argument>> + 'b {
-    vertex.filters.iter().filter(move |op| op.left().field_name.as_ref() == property_name)
+    // Filters on transformed values of the property, like `@transform(op: "lowercase")`,
+    // say nothing about which values the property itself may have.
+    vertex.filters.iter().filter(move |op| {
+        let field = op.left();
+        field.field_name.as_ref() == property_name && field.transforms.is_empty()
+    })
 }
 
 fn compute_statically_known_candidate<'a, 'b>(
@@ -411,7 +441,7 @@ mod tests {
 
     use crate::{
         interpreter::hints::{
-            vertex_info::compute_statically_known_candidate, CandidateValue, Range,
+            vertex_info::compute_statically_known_candidate, CandidateValue, Range, StringPattern,
         },
         ir::{Argument, FieldValue, LocalField, Operation, Type, VariableRef},
     };
@@ -436,6 +466,10 @@ mod tests {
             variable_name: second.clone(),
             variable_type: int_type.clone(),
         });
+        let third_var = Argument::Variable(VariableRef {
+            variable_name: third.clone(),
+            variable_type: int_type.clone(),
+        });
         let null_var = Argument::Variable(VariableRef {
             variable_name: null.clone(),
             variable_type: nullable_int_type.clone(),
@@ -449,8 +483,11 @@ mod tests {
             variable_type: list_int_type.clone(),
         });
 
-        let local_field =
-            LocalField { field_name: Arc::from("my_field"), field_type: nullable_int_type.clone() };
+        let local_field = LocalField {
+            field_name: Arc::from("my_field"),
+            field_type: nullable_int_type.clone(),
+            transforms: vec![],
+        };
 
         let variables = btreemap! {
             first => FieldValue::Int64(1),
@@ -559,6 +596,30 @@ mod tests {
                 ))),
             ),
             //
+            // `between [1, 3]` produces the inclusive 1..=3 range
+            (
+                vec![Operation::Between(local_field.clone(), first_var.clone(), third_var.clone())],
+                Some(CandidateValue::Range(Range::new(
+                    Bound::Included(&variables["first"]),
+                    Bound::Included(&variables["third"]),
+                    true,
+                ))),
+            ),
+            //
+            // `between [1, 3]` and `!= 3` and `is_not_null` produces the non-null 1..3 range
+            (
+                vec![
+                    Operation::Between(local_field.clone(), first_var.clone(), third_var.clone()),
+                    Operation::NotEquals(local_field.clone(), third_var.clone()),
+                    Operation::IsNotNull(local_field.clone()),
+                ],
+                Some(CandidateValue::Range(Range::new(
+                    Bound::Included(&variables["first"]),
+                    Bound::Excluded(&variables["third"]),
+                    false,
+                ))),
+            ),
+            //
             // `is_not_null` by itself only eliminates null
             (
                 vec![Operation::IsNotNull(local_field.clone())],
@@ -592,6 +653,7 @@ mod tests {
         drop((
             first_var,
             second_var,
+            third_var,
             null_var,
             list_var,
             longer_list_var,
@@ -612,8 +674,11 @@ mod tests {
             variable_type: int_type.clone(),
         });
 
-        let local_field =
-            LocalField { field_name: Arc::from("my_field"), field_type: int_type.clone() };
+        let local_field = LocalField {
+            field_name: Arc::from("my_field"),
+            field_type: int_type.clone(),
+            transforms: vec![],
+        };
 
         let variables = btreemap! {
             first => FieldValue::Int64(1),
@@ -665,4 +730,102 @@ mod tests {
         // Explicitly drop these values, so clippy stops complaining about unneccessary clones earlier.
         drop((first_var, local_field, int_type));
     }
+
+    #[test]
+    fn string_pattern_candidates() {
+        let prefix: Arc<str> = Arc::from("prefix");
+        let suffix: Arc<str> = Arc::from("suffix");
+        let value: Arc<str> = Arc::from("value");
+        let other: Arc<str> = Arc::from("other");
+        let null: Arc<str> = Arc::from("null");
+        let string_type = Type::parse("String!").unwrap();
+        let nullable_string_type = Type::parse("String").unwrap();
+
+        let make_var = |name: &Arc<str>, variable_type: &Type| {
+            Argument::Variable(VariableRef {
+                variable_name: name.clone(),
+                variable_type: variable_type.clone(),
+            })
+        };
+        let prefix_var = make_var(&prefix, &string_type);
+        let suffix_var = make_var(&suffix, &string_type);
+        let value_var = make_var(&value, &string_type);
+        let other_var = make_var(&other, &string_type);
+        let null_var = make_var(&null, &nullable_string_type);
+
+        let local_field = LocalField {
+            field_name: Arc::from("my_field"),
+            field_type: nullable_string_type.clone(),
+            transforms: vec![],
+        };
+
+        let variables = btreemap! {
+            prefix => FieldValue::String("ab".into()),
+            suffix => FieldValue::String("yz".into()),
+            value => FieldValue::String("abcxyz".into()),
+            other => FieldValue::String("xyz".into()),
+            null => FieldValue::Null,
+        };
+
+        let test_data = [
+            // `has_prefix` by itself produces a pattern
+            (
+                vec![Operation::HasPrefix(local_field.clone(), prefix_var.clone())],
+                Some(CandidateValue::StringPattern(StringPattern::with_prefix(
+                    &variables["prefix"],
+                ))),
+            ),
+            //
+            // `has_prefix` and `has_suffix` and `has_substring` combine into a single pattern
+            (
+                vec![
+                    Operation::HasPrefix(local_field.clone(), prefix_var.clone()),
+                    Operation::HasSuffix(local_field.clone(), suffix_var.clone()),
+                    Operation::HasSubstring(local_field.clone(), other_var.clone()),
+                ],
+                Some(CandidateValue::StringPattern(StringPattern::new(
+                    Some(&variables["prefix"]),
+                    Some(&variables["suffix"]),
+                    vec![&variables["other"]],
+                ))),
+            ),
+            //
+            // `=` and a matching `has_prefix` produce the `=` value
+            (
+                vec![
+                    Operation::HasPrefix(local_field.clone(), prefix_var.clone()),
+                    Operation::Equals(local_field.clone(), value_var.clone()),
+                ],
+                Some(CandidateValue::Single(&variables["value"])),
+            ),
+            //
+            // `=` and a non-matching `has_prefix` are impossible to satisfy simultaneously
+            (
+                vec![
+                    Operation::Equals(local_field.clone(), other_var.clone()),
+                    Operation::HasPrefix(local_field.clone(), prefix_var.clone()),
+                ],
+                Some(CandidateValue::Impossible),
+            ),
+            //
+            // `has_substring null` never matches
+            (
+                vec![Operation::HasSubstring(local_field.clone(), null_var.clone())],
+                Some(CandidateValue::Impossible),
+            ),
+            //
+            // `not_has_prefix` doesn't produce any candidates
+            (vec![Operation::NotHasPrefix(local_field.clone(), prefix_var.clone())], None),
+        ];
+
+        for (filters, expected_output) in test_data {
+            assert_eq!(
+                expected_output,
+                compute_statically_known_candidate(&local_field, filters.iter(), &variables)
+                    .as_ref()
+                    .map(|x| x.as_deref()),
+                "with {filters:?}",
+            );
+        }
+    }
 }
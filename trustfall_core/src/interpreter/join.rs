@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::ir::{FieldValue, JoinedQuery};
+
+use super::{
+    error::QueryArgumentsError,
+    execution::{compute_final_results, resolve_starting_vertices},
+    resolve_parameter_variables, validate_arguments, Adapter, InterpretedQuery,
+};
+
+type ResultRow = BTreeMap<Arc<str>, FieldValue>;
+
+/// Run a query with multiple root fields, producing the combined results of its root fields.
+///
+/// Root fields are run in the order in which they appear in the query. Each later root field
+/// is run once for each combined result of the root fields before it, using the values
+/// that result tagged for it. Results are produced lazily, like those of [`interpret_ir`].
+///
+/// Since later root fields are run many times, they may resolve the same vertices, properties,
+/// and neighbors many times. To resolve each of them only once, use this together with
+/// a [`CachingAdapter`].
+///
+/// [`interpret_ir`]: super::execution::interpret_ir
+/// [`CachingAdapter`]: super::CachingAdapter
+#[allow(clippy::type_complexity)]
+pub fn interpret_joined<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    joined_query: Arc<JoinedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'query>, QueryArgumentsError>
+{
+    let regex_variables = joined_query
+        .roots
+        .iter()
+        .flat_map(|root| root.query.ir_query.root_component.regex_variables())
+        .collect();
+    let arguments = validate_arguments(
+        &joined_query.variables,
+        &joined_query.variable_defaults,
+        &regex_variables,
+        arguments,
+    )?;
+
+    Ok(join_root(adapter, joined_query, arguments, 0, BTreeMap::new()))
+}
+
+/// Extend the combined result of the root fields before `root_index` with the results
+/// of the remaining root fields.
+fn join_root<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    joined_query: Arc<JoinedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    root_index: usize,
+    mut row: ResultRow,
+) -> Box<dyn Iterator<Item = ResultRow> + 'query> {
+    let Some(root) = joined_query.roots.get(root_index) else {
+        row.retain(|name, _| !joined_query.hidden_outputs.contains(name));
+        return Box::new(std::iter::once(row));
+    };
+
+    // The root field's variables are either imported tags, or the query's own variables.
+    let root_arguments = root
+        .query
+        .ir_query
+        .variables
+        .keys()
+        .map(|name| {
+            let value =
+                if root.imported_tags.contains(name) { &row[name] } else { &arguments[name] };
+            (name.clone(), value.clone())
+        })
+        .collect();
+    let indexed_query = resolve_parameter_variables(root.query.clone(), &root_arguments);
+    let query = InterpretedQuery { indexed_query, arguments: Arc::new(root_arguments) };
+
+    let (starting_vertices, query) = resolve_starting_vertices(adapter.as_ref(), query);
+    let results = compute_final_results(adapter.clone(), query, starting_vertices);
+    Box::new(results.flat_map(move |root_row| {
+        let mut combined_row = row.clone();
+        combined_row.extend(root_row);
+        join_root(
+            adapter.clone(),
+            joined_query.clone(),
+            arguments.clone(),
+            root_index + 1,
+            combined_row,
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use crate::{
+        frontend::parse_joined,
+        interpreter::{error::QueryArgumentsError, execution::interpret_joined},
+        ir::FieldValue,
+        numbers_interpreter::NumbersAdapter,
+    };
+
+    fn run_joined(query: &str, arguments: BTreeMap<Arc<str>, FieldValue>) -> Vec<(i64, i64)> {
+        let adapter = Arc::new(NumbersAdapter::new());
+        let joined_query = parse_joined(adapter.schema(), query).unwrap();
+        interpret_joined(adapter, joined_query, Arc::new(arguments))
+            .unwrap()
+            .map(|row| {
+                assert_eq!(
+                    vec!["big", "small"],
+                    row.keys().map(|k| k.as_ref()).collect::<Vec<_>>()
+                );
+                (row["small"].as_i64().unwrap(), row["big"].as_i64().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unrelated_root_fields_produce_all_combinations() {
+        let query = r#"
+{
+    Number(min: 1, max: 2) {
+        small: value @output
+    }
+    Number(min: 3, max: 4) {
+        big: value @output
+    }
+}"#;
+        let expected = vec![(1, 3), (1, 4), (2, 3), (2, 4)];
+        assert_eq!(expected, run_joined(query, BTreeMap::new()));
+    }
+
+    #[test]
+    fn root_fields_joined_by_tags() {
+        let query = r#"
+{
+    Number(min: 2, max: 5) {
+        small: value @output @tag
+    }
+    Number(min: 1, max: 10) {
+        big: value @output @filter(op: ">", value: ["%small"])
+                           @filter(op: "<", value: ["$max"])
+    }
+}"#;
+        let arguments = BTreeMap::from([(Arc::from("max"), FieldValue::Int64(5))]);
+        let expected = vec![(2, 3), (2, 4), (3, 4)];
+        assert_eq!(expected, run_joined(query, arguments));
+    }
+
+    #[test]
+    fn joined_query_arguments_are_validated() {
+        let query = r#"
+{
+    Number(min: 2, max: 5) {
+        small: value @output @filter(op: "<", value: ["$max"])
+    }
+    Number(min: 1, max: 10) {
+        big: value @output @filter(op: "<", value: ["$max"])
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let joined_query = parse_joined(adapter.schema(), query).unwrap();
+        assert!(interpret_joined(adapter, joined_query, Arc::new(BTreeMap::new())).is_err());
+    }
+
+    #[test]
+    fn joined_query_regex_arguments_are_validated() {
+        let query = r#"
+{
+    Number(min: 2, max: 5) {
+        small: value @output
+    }
+    Number(min: 1, max: 10) {
+        big: value @output
+        name @filter(op: "regex", value: ["$pattern"])
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let joined_query = parse_joined(adapter.schema(), query).unwrap();
+        let arguments = BTreeMap::from([(Arc::from("pattern"), FieldValue::from("(unclosed"))]);
+        let Err(error) = interpret_joined(adapter, joined_query, Arc::new(arguments)) else {
+            panic!("invalid regex argument was accepted");
+        };
+        assert!(
+            matches!(&error, QueryArgumentsError::InvalidRegexArgument(name, _) if name == "pattern"),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,1046 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    sync::Arc,
+};
+
+use async_graphql_parser::types::BaseType;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    ir::{EdgeParameters, FieldValue},
+    schema::{get_builtin_scalars, Schema},
+};
+
+use super::{
+    helpers::{resolve_coercion_with, resolve_neighbors_with, resolve_property_with},
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
+    VertexIterator,
+};
+
+/// An [`Adapter`] over JSON documents, such as the contents of JSON or JSON Lines files.
+///
+/// Each of the schema's root edges is a named collection of JSON object documents.
+/// Object keys are the properties and edges of the vertices: JSON objects and arrays of objects
+/// are edges to other vertices, and all other values are properties.
+/// Missing keys and keys whose values don't match their use in the schema resolve to `null`
+/// properties and to no neighbors.
+///
+/// The schema may be supplied up front, or inferred from the documents
+/// with [`JsonAdapter::infer`]. Any data that implements [`Serialize`] can be queried
+/// by way of its JSON representation with [`JsonAdapter::from_serialize`].
+///
+/// Vertices are of the type their schema edge points to. When that type is an interface,
+/// documents may be assigned a more specific type using a `"__typename"` key
+/// naming one of the interface's subtypes.
+///
+/// # Examples
+/// ```
+/// # use std::{collections::BTreeMap, sync::Arc};
+/// # use trustfall_core::{
+/// #     frontend::parse,
+/// #     interpreter::{execution::interpret_ir, JsonAdapter},
+/// # };
+/// let documents = JsonAdapter::parse_documents(
+///     r#"
+///     {"name": "trustfall", "authors": [{"name": "Predrag"}]}
+///     {"name": "serde", "authors": [{"name": "David"}, {"name": "Erick"}]}
+///     "#,
+/// ).expect("invalid JSON");
+/// let adapter = JsonAdapter::infer([("Crate", documents)]).expect("failed to infer schema");
+///
+/// let query = r#"
+/// {
+///     Crate {
+///         name @filter(op: "=", value: ["$name"])
+///         authors @fold @transform(op: "count") @output(name: "author_count")
+///     }
+/// }"#;
+/// let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+/// let arguments = Arc::new(BTreeMap::from([(Arc::from("name"), "serde".into())]));
+/// let results: Vec<_> = interpret_ir(Arc::new(adapter), indexed_query, arguments)
+///     .expect("invalid arguments")
+///     .collect();
+///
+/// assert_eq!(results[0]["author_count"], 2u64.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonAdapter {
+    schema: Arc<Schema>,
+    collections: BTreeMap<Arc<str>, Vec<Arc<Value>>>,
+}
+
+/// A vertex produced by [`JsonAdapter`] or [`GraphQLAdapter`](super::GraphQLAdapter):
+/// a JSON object within a JSON document.
+#[derive(Debug, Clone)]
+pub struct JsonVertex {
+    typename: Arc<str>,
+    document: Arc<Value>,
+
+    /// The location of this vertex's object within the document, as a JSON Pointer.
+    pointer: Arc<str>,
+}
+
+impl JsonVertex {
+    pub(super) fn new(typename: Arc<str>, document: Arc<Value>, pointer: Arc<str>) -> Self {
+        Self { typename, document, pointer }
+    }
+
+    /// The name of this vertex's type in the schema.
+    #[inline]
+    pub fn typename(&self) -> &str {
+        &self.typename
+    }
+
+    /// The JSON value this vertex represents.
+    pub fn value(&self) -> &Value {
+        self.document.pointer(&self.pointer).expect("vertex pointer is no longer valid")
+    }
+
+    pub(super) fn neighbors(
+        &self,
+        key: &str,
+        neighbor_type: &Arc<str>,
+        schema: &Schema,
+    ) -> Vec<JsonVertex> {
+        let key_pointer = format!("{}/{}", self.pointer, escape_pointer_token(key));
+        let neighbor = |pointer: String, value: &Value| {
+            value.is_object().then(|| JsonVertex {
+                typename: vertex_typename(value, neighbor_type, schema),
+                document: self.document.clone(),
+                pointer: pointer.into(),
+            })
+        };
+
+        match self.value().get(key) {
+            Some(value @ Value::Object(..)) => neighbor(key_pointer, value).into_iter().collect(),
+            Some(Value::Array(elements)) => elements
+                .iter()
+                .enumerate()
+                .filter_map(|(index, value)| neighbor(format!("{key_pointer}/{index}"), value))
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// Errors that may arise while inferring a schema from JSON documents.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JsonSchemaInferenceError {
+    #[error("Collection name \"{0}\" is not a valid schema field name.")]
+    InvalidCollectionName(String),
+
+    #[error("Collection \"{0}\" contains a document that is not a JSON object: {1}")]
+    NonObjectDocument(String, String),
+
+    #[error(
+        "Key \"{1}\" of type \"{0}\" holds values of incompatible kinds: \
+        both {2} and {3} values were found."
+    )]
+    IncompatibleValues(String, String, String, String),
+
+    #[error(
+        "Key \"{1}\" of type \"{0}\" holds arrays nested inside arrays of objects, \
+        which cannot be represented as an edge."
+    )]
+    NestedArrayOfObjects(String, String),
+
+    #[error("Collection \"{0}\" could not be serialized into JSON: {1}")]
+    SerializationFailed(String, String),
+}
+
+impl JsonAdapter {
+    /// Make an adapter over the given schema, without any documents yet.
+    ///
+    /// Use [`JsonAdapter::with_documents`] to add documents to its collections.
+    pub fn new(schema: Schema) -> Self {
+        Self { schema: Arc::new(schema), collections: Default::default() }
+    }
+
+    /// Make an adapter whose schema is inferred from the given collections of documents.
+    ///
+    /// Each collection becomes a root edge of the schema.
+    /// See [`JsonAdapter::infer_schema`] for how the schema is inferred.
+    pub fn infer(
+        collections: impl IntoIterator<Item = (impl Into<Arc<str>>, Vec<Value>)>,
+    ) -> Result<Self, JsonSchemaInferenceError> {
+        let collections: BTreeMap<Arc<str>, Vec<Value>> =
+            collections.into_iter().map(|(name, documents)| (name.into(), documents)).collect();
+        let schema_text = Self::infer_schema(
+            collections.iter().map(|(name, documents)| (name.as_ref(), documents.as_slice())),
+        )?;
+        let schema = Schema::parse(schema_text).expect("inferred schema was not valid");
+
+        Ok(collections.into_iter().fold(Self::new(schema), |adapter, (name, documents)| {
+            adapter.with_documents(name, documents)
+        }))
+    }
+
+    /// Make an adapter for querying the JSON representation of the given value,
+    /// with a schema inferred from it.
+    ///
+    /// The value becomes a collection with the given name. If it serializes into
+    /// a JSON array, each of its elements is a document in that collection.
+    /// Otherwise, the value itself is the collection's only document.
+    ///
+    /// ```
+    /// # use std::{collections::BTreeMap, sync::Arc};
+    /// # use serde::Serialize;
+    /// # use trustfall_core::{
+    /// #     frontend::parse,
+    /// #     interpreter::{execution::interpret_ir, JsonAdapter},
+    /// # };
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     servers: Vec<Server>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = Config {
+    ///     name: "production".into(),
+    ///     servers: vec![
+    ///         Server { host: "a.example.com".into(), port: 80 },
+    ///         Server { host: "b.example.com".into(), port: 8080 },
+    ///     ],
+    /// };
+    /// let adapter = JsonAdapter::from_serialize("Config", &config).expect("failed to infer schema");
+    ///
+    /// let query = r#"
+    /// {
+    ///     Config {
+    ///         servers {
+    ///             host @output
+    ///             port @filter(op: "!=", value: ["$port"])
+    ///         }
+    ///     }
+    /// }"#;
+    /// let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+    /// let arguments = Arc::new(BTreeMap::from([(Arc::from("port"), 80.into())]));
+    /// let results: Vec<_> = interpret_ir(Arc::new(adapter), indexed_query, arguments)
+    ///     .expect("invalid arguments")
+    ///     .collect();
+    ///
+    /// assert_eq!(results[0]["host"], "b.example.com".into());
+    /// ```
+    pub fn from_serialize(
+        collection: impl Into<Arc<str>>,
+        value: &impl Serialize,
+    ) -> Result<Self, JsonSchemaInferenceError> {
+        let collection = collection.into();
+        let documents = match serde_json::to_value(value) {
+            Ok(Value::Array(elements)) => elements,
+            Ok(document) => vec![document],
+            Err(e) => {
+                return Err(JsonSchemaInferenceError::SerializationFailed(
+                    collection.to_string(),
+                    e.to_string(),
+                ))
+            }
+        };
+        Self::infer([(collection, documents)])
+    }
+
+    /// Add documents to the collection represented by the given root edge of the schema.
+    ///
+    /// Panics if the schema has no such root edge.
+    pub fn with_documents(
+        mut self,
+        collection: impl Into<Arc<str>>,
+        documents: impl IntoIterator<Item = Value>,
+    ) -> Self {
+        let collection = collection.into();
+        let query_type = self.schema.query_type_name();
+        assert!(
+            self.schema.fields.contains_key(&(Arc::from(query_type), collection.clone())),
+            "collection {collection} is not an edge of the schema's root type {query_type}",
+        );
+
+        self.collections.entry(collection).or_default().extend(documents.into_iter().map(Arc::new));
+        self
+    }
+
+    /// The schema this adapter serves.
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Parse the contents of a JSON or JSON Lines file into a list of documents.
+    ///
+    /// A top-level JSON array is treated as a list of documents.
+    /// Otherwise, the input is treated as a sequence of whitespace-separated documents,
+    /// which includes both a single JSON document and the JSON Lines format.
+    pub fn parse_documents(input: &str) -> Result<Vec<Value>, serde_json::Error> {
+        if input.trim_start().starts_with('[') {
+            serde_json::from_str(input)
+        } else {
+            serde_json::Deserializer::from_str(input).into_iter().collect()
+        }
+    }
+
+    /// Infer a schema describing the given collections of documents.
+    ///
+    /// Each collection becomes a root edge of the schema, pointing to a type of the same name.
+    /// The type of each nested object is named after the type containing it and its key:
+    /// for example, the `address` objects within the `User` collection are of type `UserAddress`.
+    ///
+    /// All properties are nullable, since any document may omit any of them.
+    /// Properties that only ever hold `null` are assumed to be strings.
+    /// Keys that aren't valid schema field names are omitted from the schema.
+    pub fn infer_schema<'a>(
+        collections: impl IntoIterator<Item = (&'a str, &'a [Value])>,
+    ) -> Result<String, JsonSchemaInferenceError> {
+        let mut inference = SchemaInference::default();
+        let mut root_edges = vec![];
+        for (name, documents) in collections {
+            if !is_valid_name(name) {
+                return Err(JsonSchemaInferenceError::InvalidCollectionName(name.to_string()));
+            }
+
+            let type_index = inference.add_type(name);
+            for document in documents {
+                let Value::Object(object) = document else {
+                    return Err(JsonSchemaInferenceError::NonObjectDocument(
+                        name.to_string(),
+                        document.to_string(),
+                    ));
+                };
+                inference.infer_object(type_index, object)?;
+            }
+            root_edges.push((name, type_index));
+        }
+
+        inference.schema_text(&root_edges)
+    }
+}
+
+impl<'vertex> Adapter<'vertex> for JsonAdapter {
+    type Vertex = JsonVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        _resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let query_type: Arc<str> = Arc::from(self.schema.query_type_name());
+        let vertex_type = field_type_name(&self.schema, &query_type, edge_name);
+        let documents = self.collections.get(edge_name).cloned().unwrap_or_default();
+        let schema = self.schema.clone();
+
+        Box::new(documents.into_iter().filter(|document| document.is_object()).map(
+            move |document| JsonVertex {
+                typename: vertex_typename(&document, &vertex_type, &schema),
+                document,
+                pointer: Arc::from(""),
+            },
+        ))
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            return resolve_property_with(contexts, |vertex: &JsonVertex| {
+                vertex.typename.clone().into()
+            });
+        }
+
+        let numbers_as_floats =
+            field_type_name(&self.schema, type_name, property_name).as_ref() == "Float";
+        let property_name = property_name.clone();
+        resolve_property_with(contexts, move |vertex: &JsonVertex| {
+            vertex
+                .value()
+                .get(property_name.as_ref())
+                .map(|value| json_to_field_value(value, numbers_as_floats))
+                .unwrap_or(FieldValue::Null)
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        _resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let neighbor_type = field_type_name(&self.schema, type_name, edge_name);
+        let edge_name = edge_name.clone();
+        let schema = self.schema.clone();
+        resolve_neighbors_with(contexts, move |vertex: &JsonVertex| {
+            Box::new(vertex.neighbors(&edge_name, &neighbor_type, &schema).into_iter())
+        })
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        _type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let coerce_to_type = coerce_to_type.clone();
+        let schema = self.schema.clone();
+        resolve_coercion_with(contexts, move |vertex: &JsonVertex| {
+            schema.is_named_type_subtype(&coerce_to_type, &vertex.typename)
+        })
+    }
+}
+
+/// The name of the type of the given schema field, ignoring any list and non-null modifiers.
+pub(super) fn field_type_name(
+    schema: &Schema,
+    type_name: &Arc<str>,
+    field_name: &Arc<str>,
+) -> Arc<str> {
+    let field = schema
+        .fields
+        .get(&(type_name.clone(), field_name.clone()))
+        .unwrap_or_else(|| panic!("type {type_name} has no field {field_name}"));
+
+    let mut base_type = &field.ty.node.base;
+    loop {
+        match base_type {
+            BaseType::Named(name) => return Arc::from(name.as_str()),
+            BaseType::List(inner) => base_type = &inner.base,
+        }
+    }
+}
+
+/// The type of a vertex reached through an edge to the given type:
+/// the type named by the document's `"__typename"` key if that's one of its subtypes,
+/// or otherwise the edge's type itself.
+fn vertex_typename(value: &Value, edge_type: &Arc<str>, schema: &Schema) -> Arc<str> {
+    match value.get("__typename") {
+        Some(Value::String(name))
+            if schema.vertex_types.contains_key(name.as_str())
+                && schema.is_named_type_subtype(edge_type, name) =>
+        {
+            Arc::from(name.as_str())
+        }
+        _ => edge_type.clone(),
+    }
+}
+
+pub(super) fn json_to_field_value(value: &Value, numbers_as_floats: bool) -> FieldValue {
+    match value {
+        Value::Null | Value::Object(..) => FieldValue::Null,
+        Value::Bool(b) => FieldValue::Boolean(*b),
+        Value::String(s) => s.as_str().into(),
+        Value::Number(n) if numbers_as_floats => {
+            n.as_f64().map(FieldValue::Float64).unwrap_or(FieldValue::Null)
+        }
+        Value::Number(n) => {
+            if let Some(signed) = n.as_i64() {
+                FieldValue::Int64(signed)
+            } else if let Some(unsigned) = n.as_u64() {
+                FieldValue::Uint64(unsigned)
+            } else {
+                n.as_f64().map(FieldValue::Float64).unwrap_or(FieldValue::Null)
+            }
+        }
+        Value::Array(elements) => FieldValue::List(
+            elements
+                .iter()
+                .map(|element| json_to_field_value(element, numbers_as_floats))
+                .collect(),
+        ),
+    }
+}
+
+/// Escape an object key for use within a JSON Pointer, as described in RFC 6901.
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        && !name.starts_with("__")
+}
+
+/// The kinds of values found under a JSON object key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    /// Only `null` values have been found so far.
+    Null,
+    Boolean,
+    Int,
+    Float,
+    String,
+    List(Box<Shape>),
+
+    /// Objects of the inferred type with the given index.
+    Object(usize),
+}
+
+impl Shape {
+    fn description(&self) -> &'static str {
+        match self {
+            Shape::Null => "null",
+            Shape::Boolean => "boolean",
+            Shape::Int => "integer",
+            Shape::Float => "floating-point",
+            Shape::String => "string",
+            Shape::List(..) => "array",
+            Shape::Object(..) => "object",
+        }
+    }
+
+    /// The schema type of a property holding values of this shape.
+    fn property_type(&self) -> String {
+        match self {
+            Shape::Null | Shape::String => "String".to_string(),
+            Shape::Boolean => "Boolean".to_string(),
+            Shape::Int => "Int".to_string(),
+            Shape::Float => "Float".to_string(),
+            Shape::List(inner) => format!("[{}]", inner.property_type()),
+            Shape::Object(..) => unreachable!("objects are edges, not properties"),
+        }
+    }
+
+    fn contains_object(&self) -> bool {
+        match self {
+            Shape::Object(..) => true,
+            Shape::List(inner) => inner.contains_object(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InferredType {
+    name: String,
+    fields: BTreeMap<String, Shape>,
+
+    /// Indexes of the types of objects nested within this type's objects, by key.
+    nested_types: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Default)]
+struct SchemaInference {
+    types: Vec<InferredType>,
+    used_names: BTreeSet<String>,
+}
+
+impl SchemaInference {
+    fn add_type(&mut self, suggested_name: &str) -> usize {
+        let mut name = suggested_name.to_string();
+        let mut suffix = 2usize;
+        while self.used_names.contains(&name)
+            || get_builtin_scalars().contains(name.as_str())
+            || name == "RootSchemaQuery"
+        {
+            name = format!("{suggested_name}{suffix}");
+            suffix += 1;
+        }
+
+        self.used_names.insert(name.clone());
+        self.types.push(InferredType {
+            name,
+            fields: Default::default(),
+            nested_types: Default::default(),
+        });
+        self.types.len() - 1
+    }
+
+    fn nested_type(&mut self, type_index: usize, key: &str) -> usize {
+        if let Some(index) = self.types[type_index].nested_types.get(key) {
+            return *index;
+        }
+
+        let mut chars = key.chars();
+        let capitalized_key: String =
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect();
+        let suggested_name = format!("{}{capitalized_key}", self.types[type_index].name);
+        let nested_index = self.add_type(&suggested_name);
+        self.types[type_index].nested_types.insert(key.to_string(), nested_index);
+        nested_index
+    }
+
+    fn infer_object(
+        &mut self,
+        type_index: usize,
+        object: &Map<String, Value>,
+    ) -> Result<(), JsonSchemaInferenceError> {
+        for (key, value) in object {
+            if !is_valid_name(key) {
+                continue;
+            }
+
+            let shape = self.infer_value(type_index, key, value)?;
+            let merged = match self.types[type_index].fields.remove(key) {
+                Some(existing) => self.merge_shapes(type_index, key, existing, shape)?,
+                None => shape,
+            };
+            self.types[type_index].fields.insert(key.clone(), merged);
+        }
+
+        Ok(())
+    }
+
+    fn infer_value(
+        &mut self,
+        type_index: usize,
+        key: &str,
+        value: &Value,
+    ) -> Result<Shape, JsonSchemaInferenceError> {
+        Ok(match value {
+            Value::Null => Shape::Null,
+            Value::Bool(..) => Shape::Boolean,
+            Value::Number(n) if n.is_f64() => Shape::Float,
+            Value::Number(..) => Shape::Int,
+            Value::String(..) => Shape::String,
+            Value::Object(object) => {
+                let nested_index = self.nested_type(type_index, key);
+                self.infer_object(nested_index, object)?;
+                Shape::Object(nested_index)
+            }
+            Value::Array(elements) => {
+                let mut element_shape = Shape::Null;
+                for element in elements {
+                    let shape = self.infer_value(type_index, key, element)?;
+                    element_shape = self.merge_shapes(type_index, key, element_shape, shape)?;
+                }
+                Shape::List(Box::new(element_shape))
+            }
+        })
+    }
+
+    fn merge_shapes(
+        &self,
+        type_index: usize,
+        key: &str,
+        left: Shape,
+        right: Shape,
+    ) -> Result<Shape, JsonSchemaInferenceError> {
+        match (left, right) {
+            (Shape::Null, shape) | (shape, Shape::Null) => Ok(shape),
+            (Shape::Int, Shape::Float) | (Shape::Float, Shape::Int) => Ok(Shape::Float),
+            (Shape::List(l), Shape::List(r)) => {
+                Ok(Shape::List(Box::new(self.merge_shapes(type_index, key, *l, *r)?)))
+            }
+            (l, r) if l == r => Ok(l),
+            (l, r) => Err(JsonSchemaInferenceError::IncompatibleValues(
+                self.types[type_index].name.clone(),
+                key.to_string(),
+                l.description().to_string(),
+                r.description().to_string(),
+            )),
+        }
+    }
+
+    fn schema_text(
+        &self,
+        root_edges: &[(&str, usize)],
+    ) -> Result<String, JsonSchemaInferenceError> {
+        let mut text = String::new();
+        writeln!(text, "schema {{\n    query: RootSchemaQuery\n}}").expect("write failed");
+        writeln!(text, "{}", Schema::ALL_DIRECTIVE_DEFINITIONS.trim()).expect("write failed");
+
+        writeln!(text, "\ntype RootSchemaQuery {{").expect("write failed");
+        for (name, type_index) in root_edges {
+            writeln!(text, "    {name}: [{}!]!", self.types[*type_index].name)
+                .expect("write failed");
+        }
+        writeln!(text, "}}").expect("write failed");
+
+        for inferred in &self.types {
+            if inferred.fields.is_empty() {
+                // Types of objects without any keys have no fields at all,
+                // so they are written without a field list.
+                writeln!(text, "\ntype {}", inferred.name).expect("write failed");
+                continue;
+            }
+
+            writeln!(text, "\ntype {} {{", inferred.name).expect("write failed");
+            for (key, shape) in &inferred.fields {
+                let field_type = match shape {
+                    Shape::Object(index) => self.types[*index].name.clone(),
+                    Shape::List(inner) => match inner.as_ref() {
+                        Shape::Object(index) => format!("[{}!]", self.types[*index].name),
+                        inner if inner.contains_object() => {
+                            return Err(JsonSchemaInferenceError::NestedArrayOfObjects(
+                                inferred.name.clone(),
+                                key.clone(),
+                            ));
+                        }
+                        _ => shape.property_type(),
+                    },
+                    _ => shape.property_type(),
+                };
+                writeln!(text, "    {key}: {field_type}").expect("write failed");
+            }
+            writeln!(text, "}}").expect("write failed");
+        }
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use serde_json::json;
+
+    use super::{JsonAdapter, JsonSchemaInferenceError};
+    use crate::{
+        frontend::parse,
+        interpreter::{execution::interpret_ir, helpers::check_adapter_invariants},
+        ir::FieldValue,
+        schema::Schema,
+    };
+
+    fn run_query(
+        adapter: JsonAdapter,
+        query: &str,
+        arguments: BTreeMap<Arc<str>, FieldValue>,
+    ) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+        interpret_ir(Arc::new(adapter), indexed_query, Arc::new(arguments))
+            .expect("invalid arguments")
+            .collect()
+    }
+
+    fn users() -> Vec<serde_json::Value> {
+        JsonAdapter::parse_documents(
+            r#"
+            {"name": "alice", "score": 1, "tags": ["a", "b"], "address": {"city": "Paris"}}
+            {"name": "bob", "score": 2.5, "pets": [{"name": "rex", "legs": 4}, {"name": "tweety"}]}
+            {"name": "carol", "nickname": null, "home/page": "example.com", "settings": {}}
+            "#,
+        )
+        .expect("invalid JSON")
+    }
+
+    #[test]
+    fn parse_documents_formats() {
+        let expected = vec![json!({"a": 1}), json!({"a": 2})];
+        assert_eq!(expected, JsonAdapter::parse_documents(r#"[{"a": 1}, {"a": 2}]"#).unwrap());
+        assert_eq!(expected, JsonAdapter::parse_documents("{\"a\": 1}\n{\"a\": 2}\n").unwrap());
+        assert_eq!(vec![json!({"a": 1})], JsonAdapter::parse_documents(r#"{"a": 1}"#).unwrap());
+        assert!(JsonAdapter::parse_documents(r#"{"a": 1"#).is_err());
+    }
+
+    #[test]
+    fn inferred_schema() {
+        let users = users();
+        let schema = JsonAdapter::infer_schema([("User", users.as_slice())]).unwrap();
+        let expected = format!(
+            "\
+schema {{
+    query: RootSchemaQuery
+}}
+{}
+
+type RootSchemaQuery {{
+    User: [User!]!
+}}
+
+type User {{
+    address: UserAddress
+    name: String
+    nickname: String
+    pets: [UserPets!]
+    score: Float
+    settings: UserSettings
+    tags: [String]
+}}
+
+type UserAddress {{
+    city: String
+}}
+
+type UserPets {{
+    legs: Int
+    name: String
+}}
+
+type UserSettings
+",
+            Schema::ALL_DIRECTIVE_DEFINITIONS.trim(),
+        );
+        similar_asserts::assert_eq!(expected, schema);
+        Schema::parse(schema).expect("inferred schema is not valid");
+    }
+
+    #[test]
+    fn inferred_type_names_avoid_conflicts() {
+        let documents = [json!({"name": "x", "string": {"value": 1}})];
+        let schema = JsonAdapter::infer_schema([("", &documents[..0]), ("Root", &documents[..])]);
+        assert_eq!(Err(JsonSchemaInferenceError::InvalidCollectionName("".into())), schema);
+
+        let strings = [json!({"a": 1})];
+        let schema = JsonAdapter::infer_schema([
+            ("String", &strings[..]),
+            ("Root", &documents[..]),
+            ("RootString", &strings[..]),
+        ])
+        .unwrap();
+        let schema = Schema::parse(schema).expect("inferred schema is not valid");
+        let root_edges: BTreeMap<_, _> = schema
+            .fields
+            .iter()
+            .filter(|((type_name, _), _)| type_name.as_ref() == "RootSchemaQuery")
+            .map(|((_, edge), field)| (edge.to_string(), field.ty.node.to_string()))
+            .collect();
+        assert_eq!(
+            BTreeMap::from([
+                ("Root".to_string(), "[Root!]!".to_string()),
+                ("RootString".to_string(), "[RootString2!]!".to_string()),
+                ("String".to_string(), "[String2!]!".to_string()),
+            ]),
+            root_edges,
+        );
+    }
+
+    #[test]
+    fn inference_errors() {
+        let documents = [json!({"a": 1}), json!({"a": "one"})];
+        assert_eq!(
+            Err(JsonSchemaInferenceError::IncompatibleValues(
+                "Root".into(),
+                "a".into(),
+                "integer".into(),
+                "string".into(),
+            )),
+            JsonAdapter::infer_schema([("Root", &documents[..])]),
+        );
+
+        let documents = [json!({"a": [{"b": 1}, 2]})];
+        assert_eq!(
+            Err(JsonSchemaInferenceError::IncompatibleValues(
+                "Root".into(),
+                "a".into(),
+                "object".into(),
+                "integer".into(),
+            )),
+            JsonAdapter::infer_schema([("Root", &documents[..])]),
+        );
+
+        let documents = [json!({"a": [[{"b": 1}]]})];
+        assert_eq!(
+            Err(JsonSchemaInferenceError::NestedArrayOfObjects("Root".into(), "a".into())),
+            JsonAdapter::infer_schema([("Root", &documents[..])]),
+        );
+
+        let documents = [json!([1, 2])];
+        assert_eq!(
+            Err(JsonSchemaInferenceError::NonObjectDocument("Root".into(), "[1,2]".into())),
+            JsonAdapter::infer_schema([("Root", &documents[..])]),
+        );
+    }
+
+    #[test]
+    fn query_inferred_schema() {
+        let adapter = JsonAdapter::infer([("User", users())]).unwrap();
+        let query = r#"
+        {
+            User {
+                name @output
+                score @output @filter(op: "<", value: ["$max_score"])
+                tags @output
+
+                pets @fold {
+                    pet: name @output
+                    legs @output
+                }
+            }
+        }"#;
+        let arguments = BTreeMap::from([(Arc::from("max_score"), FieldValue::Float64(3.0))]);
+        let results = run_query(adapter, query, arguments);
+
+        let expected: Vec<BTreeMap<Arc<str>, FieldValue>> = vec![
+            BTreeMap::from([
+                ("name".into(), "alice".into()),
+                ("score".into(), FieldValue::Float64(1.0)),
+                ("tags".into(), vec!["a", "b"].into()),
+                ("pet".into(), FieldValue::List(Arc::new([]))),
+                ("legs".into(), FieldValue::List(Arc::new([]))),
+            ]),
+            BTreeMap::from([
+                ("name".into(), "bob".into()),
+                ("score".into(), FieldValue::Float64(2.5)),
+                ("tags".into(), FieldValue::Null),
+                ("pet".into(), vec!["rex", "tweety"].into()),
+                (
+                    "legs".into(),
+                    FieldValue::List(Arc::new([FieldValue::Int64(4), FieldValue::Null])),
+                ),
+            ]),
+        ];
+        assert_eq!(expected, results);
+    }
+
+    #[test]
+    fn query_nested_object_and_typename() {
+        let schema = Schema::parse(format!(
+            "\
+schema {{ query: RootSchemaQuery }}
+{}
+
+type RootSchemaQuery {{
+    Animal: [Animal!]!
+}}
+
+interface Animal {{
+    name: String
+    friend: Animal
+}}
+
+type Dog implements Animal {{
+    name: String
+    friend: Animal
+    goodBoy: Boolean
+}}
+
+type Cat implements Animal {{
+    name: String
+    friend: Animal
+}}
+",
+            Schema::ALL_DIRECTIVE_DEFINITIONS,
+        ))
+        .expect("invalid schema");
+        let adapter = JsonAdapter::new(schema).with_documents(
+            "Animal",
+            [
+                json!({"__typename": "Dog", "name": "rex", "goodBoy": true}),
+                json!({"__typename": "Cat", "name": "tom", "friend": {"__typename": "Dog", "name": "spike", "goodBoy": false}}),
+                json!({"__typename": "Unicorn", "name": "sparkle"}),
+            ],
+        );
+        let query = r#"
+        {
+            Animal {
+                __typename @output
+                name @output
+
+                friend @optional {
+                    ... on Dog {
+                        friend: name @output
+                        friendIsGood: goodBoy @output
+                    }
+                }
+            }
+        }"#;
+        let results = run_query(adapter, query, BTreeMap::new());
+
+        let expected: Vec<BTreeMap<Arc<str>, FieldValue>> = vec![
+            BTreeMap::from([
+                ("__typename".into(), "Dog".into()),
+                ("name".into(), "rex".into()),
+                ("friend".into(), FieldValue::Null),
+                ("friendIsGood".into(), FieldValue::Null),
+            ]),
+            BTreeMap::from([
+                ("__typename".into(), "Cat".into()),
+                ("name".into(), "tom".into()),
+                ("friend".into(), "spike".into()),
+                ("friendIsGood".into(), false.into()),
+            ]),
+            BTreeMap::from([
+                ("__typename".into(), "Animal".into()),
+                ("name".into(), "sparkle".into()),
+                ("friend".into(), FieldValue::Null),
+                ("friendIsGood".into(), FieldValue::Null),
+            ]),
+        ];
+        assert_eq!(expected, results);
+    }
+
+    #[test]
+    fn query_serialized_values() {
+        #[derive(serde::Serialize)]
+        struct Package {
+            name: &'static str,
+            version: (u64, u64, u64),
+            features: BTreeMap<&'static str, Vec<&'static str>>,
+            dependencies: Vec<Dependency>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Dependency {
+            name: &'static str,
+            optional: bool,
+        }
+
+        let package = Package {
+            name: "trustfall",
+            version: (0, 8, 1),
+            features: BTreeMap::from([("default", vec![])]),
+            dependencies: vec![
+                Dependency { name: "serde", optional: false },
+                Dependency { name: "regex", optional: true },
+            ],
+        };
+        let adapter = JsonAdapter::from_serialize("Package", &package).unwrap();
+        let query = r#"
+        {
+            Package {
+                name @output
+                version @output
+
+                dependencies {
+                    dependency: name @output
+                    optional @filter(op: "=", value: ["$optional"])
+                }
+            }
+        }"#;
+        let arguments = BTreeMap::from([(Arc::from("optional"), FieldValue::Boolean(false))]);
+        let results = run_query(adapter, query, arguments);
+
+        let expected: Vec<BTreeMap<Arc<str>, FieldValue>> = vec![BTreeMap::from([
+            ("name".into(), "trustfall".into()),
+            (
+                "version".into(),
+                FieldValue::List(Arc::new([
+                    FieldValue::Int64(0),
+                    FieldValue::Int64(8),
+                    FieldValue::Int64(1),
+                ])),
+            ),
+            ("dependency".into(), "serde".into()),
+        ])];
+        assert_eq!(expected, results);
+
+        // Arrays become collections of multiple documents.
+        let adapter = JsonAdapter::from_serialize("Dependency", &package.dependencies).unwrap();
+        let results = run_query(adapter, "{ Dependency { name @output } }", BTreeMap::new());
+        let names: Vec<_> = results.iter().map(|row| row["name"].clone()).collect();
+        assert_eq!(vec![FieldValue::from("serde"), FieldValue::from("regex")], names);
+
+        let unserializable = BTreeMap::from([((1, 2), "tuple keys aren't valid JSON")]);
+        assert!(matches!(
+            JsonAdapter::from_serialize("Root", &unserializable),
+            Err(JsonSchemaInferenceError::SerializationFailed(..)),
+        ));
+        assert_eq!(
+            Err(JsonSchemaInferenceError::NonObjectDocument("Root".into(), "1".into())),
+            JsonAdapter::from_serialize("Root", &1).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn adapter_invariants() {
+        let adapter = JsonAdapter::infer([("User", users())]).unwrap();
+        let schema = adapter.schema().clone();
+        check_adapter_invariants(&schema, adapter);
+    }
+}
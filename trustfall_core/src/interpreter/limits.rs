@@ -0,0 +1,423 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use crate::ir::{EdgeKind, EdgeParameters, FieldValue, IndexedQuery};
+
+use super::{
+    error::LimitExceeded, Adapter, AsVertex, CancellationToken, ContextIterator,
+    ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo, ResolveInfo, VertexIdentifier,
+    VertexIterator,
+};
+
+/// Limits on the resources a query may use while executing.
+///
+/// Useful when executing untrusted queries, which might otherwise load huge amounts of data.
+/// Limits that are `None` are not enforced. Use with [`interpret_ir_with_limits()`] or
+/// the `execute_query_with_limits()` function of the `trustfall` crate.
+///
+/// [`interpret_ir_with_limits()`]: super::execution::interpret_ir_with_limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// The maximum number of vertices the adapter may produce, either as starting vertices
+    /// or as neighbors. Each such vertex becomes a new context in the query's execution.
+    pub max_contexts: Option<usize>,
+
+    /// The maximum number of elements in any single `@fold`, counted before applying
+    /// any filters within the fold.
+    pub max_fold_elements: Option<usize>,
+
+    /// The maximum number of vertices that `@recurse` edges may produce, in total.
+    ///
+    /// This also stops `@recurse` edges without a depth limit from running forever
+    /// when the data they recurse through contains cycles.
+    pub max_recursion_expansions: Option<usize>,
+
+    /// The maximum number of times the adapter may be asked to resolve a property of a vertex,
+    /// the neighbors of a vertex along an edge, or whether a vertex can be coerced to a type.
+    pub max_resolver_calls: Option<usize>,
+}
+
+/// Counts the resources used by a query, and records the first limit the query exceeded.
+///
+/// Exceeding a limit cancels the tracker's token, which stops the query from loading
+/// any more vertices.
+pub(super) struct LimitTracker {
+    limits: ExecutionLimits,
+    contexts: AtomicUsize,
+    recursion_expansions: AtomicUsize,
+    resolver_calls: AtomicUsize,
+    exceeded: OnceLock<LimitExceeded>,
+    token: CancellationToken,
+}
+
+impl LimitTracker {
+    pub(super) fn new(limits: ExecutionLimits) -> Self {
+        Self {
+            limits,
+            contexts: Default::default(),
+            recursion_expansions: Default::default(),
+            resolver_calls: Default::default(),
+            exceeded: Default::default(),
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// The token that is cancelled once any limit is exceeded.
+    pub(super) fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// The first limit that was exceeded, if any.
+    pub(super) fn exceeded(&self) -> Option<LimitExceeded> {
+        self.exceeded.get().copied()
+    }
+
+    fn exceed(&self, error: LimitExceeded) {
+        // Only the first exceeded limit is reported.
+        let _ = self.exceeded.set(error);
+        self.token.cancel();
+    }
+
+    /// Add `amount` to the counter, returning `false` if that exceeds the limit.
+    fn count(
+        &self,
+        counter: &AtomicUsize,
+        amount: usize,
+        limit: Option<usize>,
+        error: fn(usize) -> LimitExceeded,
+    ) -> bool {
+        let total = counter.fetch_add(amount, Ordering::Relaxed).saturating_add(amount);
+        match limit {
+            Some(limit) if total > limit => {
+                self.exceed(error(limit));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn count_context(&self) -> bool {
+        self.count(&self.contexts, 1, self.limits.max_contexts, LimitExceeded::MaxContexts)
+    }
+
+    fn count_recursion_expansion(&self) -> bool {
+        self.count(
+            &self.recursion_expansions,
+            1,
+            self.limits.max_recursion_expansions,
+            LimitExceeded::MaxRecursionExpansions,
+        )
+    }
+
+    fn count_resolver_calls(&self, amount: usize) -> bool {
+        self.count(
+            &self.resolver_calls,
+            amount,
+            self.limits.max_resolver_calls,
+            LimitExceeded::MaxResolverCalls,
+        )
+    }
+}
+
+/// Which limits, beyond the overall number of contexts, apply to the vertices of an edge.
+#[derive(Debug, Clone, Copy)]
+enum EdgeLimits {
+    Regular,
+    Fold(Option<usize>),
+    Recursive,
+}
+
+/// An [`Adapter`] that counts the resources used by a query, enforcing its [`ExecutionLimits`].
+///
+/// The vertex that would exceed a limit is not produced, and no further vertices are produced
+/// after it. Stopping the other resolvers is left to the tracker's [`CancellationToken`].
+pub(super) struct LimitedAdapter<AdapterT> {
+    inner: Arc<AdapterT>,
+    query: Arc<IndexedQuery>,
+    tracker: Arc<LimitTracker>,
+}
+
+impl<AdapterT> LimitedAdapter<AdapterT> {
+    pub(super) fn new(
+        inner: Arc<AdapterT>,
+        query: Arc<IndexedQuery>,
+        tracker: Arc<LimitTracker>,
+    ) -> Self {
+        Self { inner, query, tracker }
+    }
+}
+
+/// Count the vertices produced by the iterator, stopping at the first one that exceeds a limit.
+fn count_vertices<'a, Vertex: 'a>(
+    mut vertices: VertexIterator<'a, Vertex>,
+    tracker: Arc<LimitTracker>,
+    edge_limits: EdgeLimits,
+) -> VertexIterator<'a, Vertex> {
+    let mut fold_elements = 0usize;
+    Box::new(std::iter::from_fn(move || {
+        let vertex = vertices.next()?;
+        let within_limits = tracker.count_context()
+            && match edge_limits {
+                EdgeLimits::Regular => true,
+                EdgeLimits::Fold(max_fold_elements) => {
+                    fold_elements += 1;
+                    match max_fold_elements {
+                        Some(limit) if fold_elements > limit => {
+                            tracker.exceed(LimitExceeded::MaxFoldElements(limit));
+                            false
+                        }
+                        _ => true,
+                    }
+                }
+                EdgeLimits::Recursive => tracker.count_recursion_expansion(),
+            };
+        within_limits.then_some(vertex)
+    }))
+}
+
+/// Count the resolver calls made for the contexts that have an active vertex.
+///
+/// Every context is still passed along, since the adapter must produce an outcome for each one.
+fn count_resolver_calls<'a, Vertex, V>(
+    contexts: ContextIterator<'a, V>,
+    tracker: Arc<LimitTracker>,
+    calls_per_vertex: usize,
+) -> ContextIterator<'a, V>
+where
+    Vertex: Clone + Debug + 'a,
+    V: AsVertex<Vertex> + 'a,
+{
+    Box::new(contexts.inspect(move |ctx| {
+        if ctx.active_vertex::<Vertex>().is_some() {
+            tracker.count_resolver_calls(calls_per_vertex);
+        }
+    }))
+}
+
+impl<'vertex, AdapterT: Adapter<'vertex> + 'vertex> Adapter<'vertex> for LimitedAdapter<AdapterT> {
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        count_vertices(
+            self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info),
+            self.tracker.clone(),
+            EdgeLimits::Regular,
+        )
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let contexts = count_resolver_calls::<Self::Vertex, _>(contexts, self.tracker.clone(), 1);
+        self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let contexts = count_resolver_calls::<Self::Vertex, _>(
+            contexts,
+            self.tracker.clone(),
+            properties.len(),
+        );
+        self.inner.resolve_properties(contexts, type_name, properties, resolve_info)
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let edge_limits = match &self.query.eids[&resolve_info.eid()] {
+            EdgeKind::Fold(_) => EdgeLimits::Fold(self.tracker.limits.max_fold_elements),
+            EdgeKind::Regular(edge) if edge.recursive.is_some() => EdgeLimits::Recursive,
+            EdgeKind::Regular(_) => EdgeLimits::Regular,
+        };
+
+        let contexts = count_resolver_calls::<Self::Vertex, _>(contexts, self.tracker.clone(), 1);
+        let neighbors =
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info);
+
+        let tracker = self.tracker.clone();
+        Box::new(neighbors.map(move |(ctx, neighbors)| {
+            (ctx, count_vertices(neighbors, tracker.clone(), edge_limits))
+        }))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let contexts = count_resolver_calls::<Self::Vertex, _>(contexts, self.tracker.clone(), 1);
+        self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::ExecutionLimits;
+    use crate::{
+        interpreter::{
+            error::LimitExceeded,
+            execution::{interpret_ir, interpret_ir_with_limits},
+        },
+        numbers_interpreter::NumbersAdapter,
+    };
+
+    /// Check that the query's results with the given limits are a prefix of its full results,
+    /// followed by the given error.
+    fn assert_limit_exceeded(query: &str, limits: ExecutionLimits, expected_error: LimitExceeded) {
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+        let arguments = Arc::new(BTreeMap::new());
+
+        let expected: Vec<_> =
+            interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                .unwrap()
+                .map(Ok)
+                .collect();
+        let produced: Vec<_> =
+            interpret_ir_with_limits(adapter, indexed_query, arguments, limits).unwrap().collect();
+
+        let (last, partial) = produced.split_last().unwrap();
+        assert_eq!(&Err(expected_error), last);
+        assert!(partial.len() < expected.len());
+        assert_eq!(&expected[..partial.len()], partial);
+    }
+
+    #[test]
+    fn queries_within_limits_produce_all_results() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        multiple(max: 3) @fold {
+            multiples: value @output
+        }
+        successor @recurse(depth: 3) {
+            successors: value @output
+        }
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+        let arguments = Arc::new(BTreeMap::new());
+
+        let expected: Vec<_> =
+            interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                .unwrap()
+                .map(Ok)
+                .collect();
+        assert!(!expected.is_empty());
+
+        let limits = ExecutionLimits {
+            max_contexts: Some(1000),
+            max_fold_elements: Some(3),
+            max_recursion_expansions: Some(100),
+            max_resolver_calls: Some(1000),
+        };
+        let produced: Vec<_> = interpret_ir_with_limits(
+            adapter.clone(),
+            indexed_query.clone(),
+            arguments.clone(),
+            limits,
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(expected, produced);
+
+        let produced: Vec<_> =
+            interpret_ir_with_limits(adapter, indexed_query, arguments, ExecutionLimits::default())
+                .unwrap()
+                .collect();
+        assert_eq!(expected, produced);
+    }
+
+    #[test]
+    fn max_contexts() {
+        let query = r#"
+{
+    Number(min: 0, max: 100) {
+        value @output
+    }
+}"#;
+        let limits = ExecutionLimits { max_contexts: Some(10), ..Default::default() };
+        assert_limit_exceeded(query, limits, LimitExceeded::MaxContexts(10));
+    }
+
+    #[test]
+    fn max_fold_elements() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        multiple(max: 10) @fold {
+            multiples: value @output
+        }
+    }
+}"#;
+        let limits = ExecutionLimits { max_fold_elements: Some(5), ..Default::default() };
+        assert_limit_exceeded(query, limits, LimitExceeded::MaxFoldElements(5));
+    }
+
+    #[test]
+    fn max_recursion_expansions() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        successor @recurse(depth: 5) {
+            successor: value @output
+        }
+    }
+}"#;
+        let limits = ExecutionLimits { max_recursion_expansions: Some(12), ..Default::default() };
+        assert_limit_exceeded(query, limits, LimitExceeded::MaxRecursionExpansions(12));
+    }
+
+    #[test]
+    fn max_resolver_calls() {
+        let query = r#"
+{
+    Number(min: 0, max: 100) {
+        value @output
+        name @output
+    }
+}"#;
+        let limits = ExecutionLimits { max_resolver_calls: Some(50), ..Default::default() };
+        assert_limit_exceeded(query, limits, LimitExceeded::MaxResolverCalls(50));
+    }
+}
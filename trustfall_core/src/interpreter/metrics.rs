@@ -0,0 +1,384 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::ir::{EdgeParameters, Eid, FieldValue, Vid};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo,
+    ResolveInfo, VertexIdentifier, VertexInfo, VertexIterator,
+};
+
+/// The adapter resolver method whose call is described by a [`ResolverMetrics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolverCall {
+    StartingVertices { edge_name: Arc<str> },
+    Property { type_name: Arc<str>, property_name: Arc<str> },
+    Properties { type_name: Arc<str>, property_names: Vec<Arc<str>> },
+    Neighbors { type_name: Arc<str>, edge_name: Arc<str>, eid: Eid },
+    Coercion { type_name: Arc<str>, coerce_to_type: Arc<str> },
+}
+
+/// Measurements of a single call to one of an adapter's resolver methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolverMetrics {
+    /// Which resolver method was called, and with which arguments.
+    pub call: ResolverCall,
+
+    /// The vertex in the query at which the resolver was called.
+    /// For neighbors, this is the vertex at which the edge starts.
+    pub vid: Vid,
+
+    /// The number of contexts the resolver processed.
+    /// For starting vertices, the number of vertices the resolver produced instead.
+    pub contexts: usize,
+
+    /// The time spent inside the resolver, including in the iterators it returned.
+    ///
+    /// Does not include the time spent producing the resolver's input contexts,
+    /// since that time is part of the metrics of earlier resolver calls.
+    pub elapsed: Duration,
+}
+
+/// An [`Adapter`] that reports metrics about each call to another adapter's resolvers.
+///
+/// Resolvers process their inputs lazily, so a call's metrics are only final
+/// once query execution is done with all iterators that the call returned.
+/// The callback is invoked at that point, once per call.
+///
+/// This is meant to be lightweight enough for production use,
+/// such as to record resolver latencies in a metrics system or as `tracing` events.
+/// Measuring time requires [`Instant`], which is not available on all platforms.
+pub struct InstrumentedAdapter<'vertex, AdapterT, CallbackT>
+where
+    AdapterT: Adapter<'vertex>,
+    CallbackT: Fn(&ResolverMetrics) + 'vertex,
+{
+    inner: Arc<AdapterT>,
+    callback: Arc<CallbackT>,
+    _phantom: PhantomData<&'vertex ()>,
+}
+
+impl<'vertex, AdapterT, CallbackT> InstrumentedAdapter<'vertex, AdapterT, CallbackT>
+where
+    AdapterT: Adapter<'vertex>,
+    CallbackT: Fn(&ResolverMetrics) + 'vertex,
+{
+    /// Wrap an adapter, invoking the callback with the metrics of each of its resolver calls.
+    pub fn new(adapter: AdapterT, callback: CallbackT) -> Self {
+        Self::from_arc(Arc::new(adapter), callback)
+    }
+
+    /// Wrap an adapter that is also used elsewhere, sharing it instead of taking ownership.
+    pub fn from_arc(adapter: Arc<AdapterT>, callback: CallbackT) -> Self {
+        Self { inner: adapter, callback: Arc::new(callback), _phantom: PhantomData }
+    }
+
+    /// The adapter whose resolver calls are being measured.
+    pub fn inner(&self) -> &AdapterT {
+        &self.inner
+    }
+
+    fn start_call(&self, call: ResolverCall, vid: Vid) -> Rc<CallRecorder<CallbackT>> {
+        Rc::new(CallRecorder {
+            callback: self.callback.clone(),
+            metrics: RefCell::new(ResolverMetrics {
+                call,
+                vid,
+                contexts: 0,
+                elapsed: Duration::ZERO,
+            }),
+            upstream: Cell::new(Duration::ZERO),
+        })
+    }
+}
+
+impl<'vertex, AdapterT, CallbackT> Debug for InstrumentedAdapter<'vertex, AdapterT, CallbackT>
+where
+    AdapterT: Adapter<'vertex> + Debug,
+    CallbackT: Fn(&ResolverMetrics) + 'vertex,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedAdapter").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+/// Accumulates the metrics of one resolver call, reporting them once it's dropped:
+/// that is, once the call and all the iterators it produced are done.
+struct CallRecorder<CallbackT: Fn(&ResolverMetrics)> {
+    callback: Arc<CallbackT>,
+    metrics: RefCell<ResolverMetrics>,
+
+    /// The time spent producing input contexts, which is not part of this call's elapsed time.
+    upstream: Cell<Duration>,
+}
+
+impl<CallbackT: Fn(&ResolverMetrics)> CallRecorder<CallbackT> {
+    /// Run the function, adding the time it took to this call's elapsed time.
+    fn timed<T>(&self, func: impl FnOnce() -> T) -> T {
+        let upstream_before = self.upstream.get();
+        let start = Instant::now();
+        let value = func();
+        let upstream_during = self.upstream.get() - upstream_before;
+        self.metrics.borrow_mut().elapsed += start.elapsed().saturating_sub(upstream_during);
+        value
+    }
+
+    fn count_context(&self) {
+        self.metrics.borrow_mut().contexts += 1;
+    }
+}
+
+impl<CallbackT: Fn(&ResolverMetrics)> Drop for CallRecorder<CallbackT> {
+    fn drop(&mut self) {
+        (self.callback)(&self.metrics.borrow());
+    }
+}
+
+/// Count the input contexts, keeping track of the time spent producing them.
+fn record_contexts<'vertex, V: 'vertex, CallbackT: Fn(&ResolverMetrics) + 'vertex>(
+    mut contexts: ContextIterator<'vertex, V>,
+    recorder: Rc<CallRecorder<CallbackT>>,
+) -> ContextIterator<'vertex, V> {
+    Box::new(std::iter::from_fn(move || {
+        let start = Instant::now();
+        let context = contexts.next();
+        recorder.upstream.set(recorder.upstream.get() + start.elapsed());
+        if context.is_some() {
+            recorder.count_context();
+        }
+        context
+    }))
+}
+
+/// Time the resolver's work that happens while producing the items of the iterator.
+fn record_outputs<'vertex, T: 'vertex, CallbackT: Fn(&ResolverMetrics) + 'vertex>(
+    mut iterator: Box<dyn Iterator<Item = T> + 'vertex>,
+    recorder: Rc<CallRecorder<CallbackT>>,
+) -> Box<dyn Iterator<Item = T> + 'vertex> {
+    Box::new(std::iter::from_fn(move || recorder.timed(|| iterator.next())))
+}
+
+impl<'vertex, AdapterT, CallbackT> Adapter<'vertex>
+    for InstrumentedAdapter<'vertex, AdapterT, CallbackT>
+where
+    AdapterT: Adapter<'vertex> + 'vertex,
+    CallbackT: Fn(&ResolverMetrics) + 'vertex,
+{
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let recorder = self.start_call(
+            ResolverCall::StartingVertices { edge_name: edge_name.clone() },
+            resolve_info.vid(),
+        );
+        let vertices = recorder
+            .timed(|| self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info));
+
+        let counter = recorder.clone();
+        record_outputs(Box::new(vertices.inspect(move |_| counter.count_context())), recorder)
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let recorder = self.start_call(
+            ResolverCall::Property {
+                type_name: type_name.clone(),
+                property_name: property_name.clone(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = record_contexts(contexts, recorder.clone());
+        let outcomes = recorder.timed(|| {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        });
+        record_outputs(outcomes, recorder)
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let recorder = self.start_call(
+            ResolverCall::Properties {
+                type_name: type_name.clone(),
+                property_names: properties.iter().map(|property| property.name.clone()).collect(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = record_contexts(contexts, recorder.clone());
+        let outcomes = recorder
+            .timed(|| self.inner.resolve_properties(contexts, type_name, properties, resolve_info));
+        record_outputs(outcomes, recorder)
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let recorder = self.start_call(
+            ResolverCall::Neighbors {
+                type_name: type_name.clone(),
+                edge_name: edge_name.clone(),
+                eid: resolve_info.eid(),
+            },
+            resolve_info.origin_vid(),
+        );
+        let contexts = record_contexts(contexts, recorder.clone());
+        let outcomes = recorder.timed(|| {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        });
+
+        // Neighbors are also loaded lazily, so their iterators are timed as well.
+        let neighbor_recorder = recorder.clone();
+        let outcomes = Box::new(outcomes.map(move |(ctx, neighbors)| {
+            (ctx, record_outputs(neighbors, neighbor_recorder.clone()))
+        }));
+        record_outputs(outcomes, recorder)
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let recorder = self.start_call(
+            ResolverCall::Coercion {
+                type_name: type_name.clone(),
+                coerce_to_type: coerce_to_type.clone(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = record_contexts(contexts, recorder.clone());
+        let outcomes = recorder.timed(|| {
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+        });
+        record_outputs(outcomes, recorder)
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::{InstrumentedAdapter, ResolverCall, ResolverMetrics};
+    use crate::{
+        interpreter::execution::interpret_ir, ir::Vid, numbers_interpreter::NumbersAdapter,
+    };
+
+    #[test]
+    fn reports_each_resolver_call() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        successor {
+            ... on Prime {
+                next: value @output
+            }
+        }
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+        let arguments = Arc::new(BTreeMap::new());
+        let expected: Vec<_> =
+            interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                .unwrap()
+                .collect();
+
+        let reported: Arc<Mutex<Vec<ResolverMetrics>>> = Default::default();
+        let recorded = reported.clone();
+        let instrumented = Arc::new(InstrumentedAdapter::from_arc(adapter, move |metrics| {
+            recorded.lock().unwrap().push(metrics.clone());
+        }));
+        let results: Vec<_> =
+            interpret_ir(instrumented.clone(), indexed_query, arguments).unwrap().collect();
+        assert_eq!(expected, results);
+
+        // Dropping the results iterator also dropped all resolvers' iterators.
+        let mut reported: Vec<_> = reported
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|metrics| (metrics.call.clone(), metrics.vid, metrics.contexts))
+            .collect();
+        reported.sort_by_key(|(_, vid, _)| *vid);
+
+        let kinds: Vec<_> = reported
+            .iter()
+            .map(|(call, _, _)| match call {
+                ResolverCall::StartingVertices { .. } => "starting",
+                ResolverCall::Property { .. } => "property",
+                ResolverCall::Neighbors { .. } => "neighbors",
+                ResolverCall::Coercion { .. } => "coercion",
+                _ => unreachable!("{call:?}"),
+            })
+            .collect();
+        assert_eq!("starting", kinds[0]);
+        assert_eq!(5, kinds.len(), "{reported:?}");
+        assert_eq!(2, kinds.iter().filter(|kind| **kind == "property").count());
+
+        let vid = |n| Vid::new(std::num::NonZeroUsize::new(n).unwrap());
+        let starting = &reported[0];
+        assert_eq!((vid(1), 10), (starting.1, starting.2));
+        for (call, call_vid, contexts) in &reported[1..] {
+            match call {
+                // Every starting vertex has one successor, which is checked for being prime.
+                ResolverCall::Neighbors { edge_name, .. } => {
+                    assert_eq!(
+                        ("successor", vid(1), 10),
+                        (edge_name.as_ref(), *call_vid, *contexts)
+                    );
+                }
+                ResolverCall::Coercion { coerce_to_type, .. } => {
+                    assert_eq!(
+                        ("Prime", vid(2), 10),
+                        (coerce_to_type.as_ref(), *call_vid, *contexts)
+                    );
+                }
+                ResolverCall::Property { property_name, .. } if *call_vid == vid(2) => {
+                    assert_eq!(("value", expected.len()), (property_name.as_ref(), *contexts));
+                }
+                ResolverCall::Property { property_name, .. } => {
+                    assert_eq!(("value", vid(1)), (property_name.as_ref(), *call_vid));
+                }
+                _ => unreachable!("{call:?}"),
+            }
+        }
+    }
+}
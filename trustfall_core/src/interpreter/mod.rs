@@ -1,28 +1,84 @@
-use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    sync::Arc,
+};
 
 use itertools::Itertools;
+use regex::RegexBuilder;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
     ir::{EdgeParameters, Eid, FieldRef, FieldValue, IndexedQuery, Type, Vid},
-    util::BTreeMapTryInsertExt,
+    util::closest_name,
 };
 
-use self::error::QueryArgumentsError;
+use self::{
+    error::{MissingArgument, QueryArgumentsError, UnusedArgument},
+    recorded_vertices::RecordedVertices,
+};
 
+#[cfg(feature = "async")]
+mod async_adapter;
 pub mod basic_adapter;
+mod batch;
+mod caching_adapter;
+mod cancellation;
+mod composite_adapter;
+#[cfg(feature = "csv-adapter")]
+mod csv_adapter;
+mod cycle_detection;
+mod dyn_adapter;
 pub mod error;
 pub mod execution;
+pub mod explain;
+#[cfg(feature = "filesystem-adapter")]
+mod filesystem_adapter;
 mod filtering;
+#[cfg(feature = "graphql-adapter")]
+mod graphql_adapter;
+mod hash_join;
 pub mod helpers;
 mod hints;
+mod join;
+#[cfg(feature = "json-adapter")]
+mod json_adapter;
+mod limits;
+mod metrics;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+mod recorded_vertices;
 pub mod replay;
+mod schedule;
+#[cfg(feature = "tracing")]
+mod spans;
+mod threaded;
 pub mod trace;
 
+#[cfg(feature = "async")]
+pub use async_adapter::AsyncAdapter;
+pub use caching_adapter::CachingAdapter;
+pub use cancellation::CancellationToken;
+pub use composite_adapter::{CompositeAdapter, CompositeVertex};
+#[cfg(feature = "csv-adapter")]
+pub use csv_adapter::{CsvAdapter, CsvParseError, CsvTable, CsvVertex};
+pub use cycle_detection::{CycleDetectingAdapter, CycleDetectingVertex};
+pub use dyn_adapter::{DynAdapter, DynContextVertex, DynVertex, ObjectSafeAdapter};
+#[cfg(feature = "filesystem-adapter")]
+pub use filesystem_adapter::{FilesystemAdapter, FilesystemEntry};
+#[cfg(feature = "graphql-adapter")]
+pub use graphql_adapter::{GraphQLAdapter, GraphQLIntrospectionError, GraphQLTransport};
+pub use hash_join::HashJoinAdapter;
 pub use hints::{
     CandidateValue, DynamicallyResolvedValue, EdgeInfo, NeighborInfo, QueryInfo, Range,
-    RequiredProperty, ResolveEdgeInfo, ResolveInfo, VertexInfo,
+    RequiredProperty, ResolveEdgeInfo, ResolveInfo, StringPattern, VertexInfo,
 };
+#[cfg(feature = "json-adapter")]
+pub use json_adapter::{JsonAdapter, JsonSchemaInferenceError, JsonVertex};
+pub use limits::ExecutionLimits;
+pub use metrics::{InstrumentedAdapter, ResolverCall, ResolverMetrics};
+pub use schedule::{ExecutionSchedule, ExecutionStrategy, ScheduleEvent, ScheduleStep};
 
 /// An iterator of vertices representing data points we are querying.
 pub type VertexIterator<'vertex, VertexT> = Box<dyn Iterator<Item = VertexT> + 'vertex>;
@@ -56,6 +112,91 @@ pub trait Typename {
     fn typename(&self) -> &'static str;
 }
 
+/// Identifies the underlying data that a vertex represents.
+///
+/// Distinct vertex values may represent the same data: for example, two vertices
+/// may describe the same file on disk even if they were loaded separately.
+/// Implementing this trait allows engine features that need to know when two vertices
+/// are "the same", such as [`CycleDetectingAdapter`], to work with an adapter's vertices.
+pub trait VertexIdentity {
+    /// An opaque key, which is equal for two vertices if and only if they represent the same data.
+    type Identity: Hash + Eq + Clone + Debug;
+
+    /// Returns the key identifying the data this vertex represents.
+    fn identity(&self) -> Self::Identity;
+}
+
+/// Tells apart the vertices of an [`Adapter`], so that `@recurse(dedupe: true)` can omit
+/// the vertices its recursion has already visited.
+///
+/// Produced by [`Adapter::vertex_identifier()`].
+pub struct VertexIdentifier<'vertex, Vertex> {
+    #[allow(clippy::type_complexity)]
+    start: Arc<dyn Fn() -> Box<dyn FnMut(&Vertex) -> bool + 'vertex> + 'vertex>,
+}
+
+impl<'vertex, Vertex: 'vertex> VertexIdentifier<'vertex, Vertex> {
+    /// Identify each vertex by the key produced by the given function.
+    ///
+    /// Two vertices are the same if and only if their keys are equal.
+    pub fn new<K: Hash + Eq + 'vertex>(identify: impl Fn(&Vertex) -> K + 'vertex) -> Self {
+        let identify = Arc::new(identify);
+        Self::from_visitors(move || {
+            let identify = identify.clone();
+            let mut visited = HashSet::new();
+            Box::new(move |vertex| visited.insert(identify(vertex)))
+        })
+    }
+
+    /// Identify each vertex by its [`VertexIdentity`].
+    pub fn from_vertex_identity() -> Self
+    where
+        Vertex: VertexIdentity,
+        Vertex::Identity: 'vertex,
+    {
+        Self::new(Vertex::identity)
+    }
+
+    /// Identify the vertices of another type by the vertices they contain, as when wrapping
+    /// another adapter's vertices in an adapter of a different vertex type.
+    pub fn map<Outer: 'vertex>(
+        self,
+        project: impl Fn(&Outer) -> &Vertex + 'vertex,
+    ) -> VertexIdentifier<'vertex, Outer> {
+        let project = Arc::new(project);
+        VertexIdentifier::from_visitors(move || {
+            let project = project.clone();
+            let mut visit = self.start_visiting();
+            Box::new(move |vertex| visit(project(vertex)))
+        })
+    }
+
+    /// Use the given function to produce a fresh visitor for each recursion.
+    pub(crate) fn from_visitors(
+        start: impl Fn() -> Box<dyn FnMut(&Vertex) -> bool + 'vertex> + 'vertex,
+    ) -> Self {
+        Self { start: Arc::new(start) }
+    }
+
+    /// Start keeping track of visited vertices. The returned function marks
+    /// the given vertex as visited, returning whether it wasn't visited before.
+    pub(crate) fn start_visiting(&self) -> Box<dyn FnMut(&Vertex) -> bool + 'vertex> {
+        (self.start)()
+    }
+}
+
+impl<Vertex> Clone for VertexIdentifier<'_, Vertex> {
+    fn clone(&self) -> Self {
+        Self { start: self.start.clone() }
+    }
+}
+
+impl<Vertex> Debug for VertexIdentifier<'_, Vertex> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VertexIdentifier").finish_non_exhaustive()
+    }
+}
+
 /// A tagged value captured and imported from another query component.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum TaggedValue {
@@ -71,11 +212,13 @@ pub(crate) enum TaggedValue {
 #[derive(Debug, Clone)]
 pub struct DataContext<Vertex> {
     active_vertex: Option<Vertex>,
-    vertices: BTreeMap<Vid, Option<Vertex>>,
+    vertices: RecordedVertices<Vertex>,
     values: Vec<FieldValue>,
     suspended_vertices: Vec<Option<Vertex>>,
     folded_contexts: BTreeMap<Eid, Option<Vec<DataContext<Vertex>>>>,
     folded_values: BTreeMap<(Eid, Arc<str>), Option<ValueOrVec>>,
+    folded_property_values: BTreeMap<(Eid, FieldRef), Vec<FieldValue>>,
+    missing_optional_edges: BTreeSet<Eid>,
     piggyback: Option<Vec<DataContext<Vertex>>>,
     imported_tags: BTreeMap<FieldRef, TaggedValue>,
 }
@@ -121,7 +264,12 @@ impl<Vertex> DataContext<Vertex> {
     pub fn map<Other>(self, mapper: &mut impl FnMut(Vertex) -> Other) -> DataContext<Other> {
         DataContext {
             active_vertex: self.active_vertex.map(&mut *mapper),
-            vertices: self.vertices.into_iter().map(|(k, v)| (k, v.map(&mut *mapper))).collect(),
+            vertices: self
+                .vertices
+                .into_vec()
+                .into_iter()
+                .map(|(k, v)| (k, v.map(&mut *mapper)))
+                .collect(),
             values: self.values,
             suspended_vertices: self
                 .suspended_vertices
@@ -136,6 +284,8 @@ impl<Vertex> DataContext<Vertex> {
                 })
                 .collect(),
             folded_values: self.folded_values,
+            folded_property_values: self.folded_property_values,
+            missing_optional_edges: self.missing_optional_edges,
             piggyback: self
                 .piggyback
                 .map(|v| v.into_iter().map(|ctx| ctx.map(&mut *mapper)).collect()),
@@ -158,9 +308,10 @@ impl<Vertex> DataContext<Vertex> {
             active_vertex: self.active_vertex.and_then(&mut *mapper),
             vertices: self
                 .vertices
+                .into_vec()
                 .into_iter()
                 .map(|(k, v)| (k, v.and_then(&mut *mapper)))
-                .collect::<BTreeMap<Vid, Option<T>>>(),
+                .collect(),
             values: self.values,
             suspended_vertices: self
                 .suspended_vertices
@@ -175,6 +326,8 @@ impl<Vertex> DataContext<Vertex> {
                 })
                 .collect(),
             folded_values: self.folded_values,
+            folded_property_values: self.folded_property_values,
+            missing_optional_edges: self.missing_optional_edges,
             piggyback: self
                 .piggyback
                 .map(|v| v.into_iter().map(|ctx| ctx.flat_map(&mut *mapper)).collect()),
@@ -225,6 +378,15 @@ struct SerializableContext<Vertex> {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     folded_values: BTreeMap<(Eid, Arc<str>), Option<ValueOrVec>>,
 
+    /// Values of folded properties that are aggregated, e.g. by `@transform(op: "sum")`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    folded_property_values: BTreeMap<(Eid, FieldRef), Vec<FieldValue>>,
+
+    /// `@optional` edges that must be treated as nonexistent, since another edge
+    /// in their `@optional(group: "...")` does not exist.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    missing_optional_edges: BTreeSet<Eid>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     piggyback: Option<Vec<DataContext<Vertex>>>,
 
@@ -237,11 +399,13 @@ impl<Vertex> From<SerializableContext<Vertex>> for DataContext<Vertex> {
     fn from(context: SerializableContext<Vertex>) -> Self {
         Self {
             active_vertex: context.active_vertex,
-            vertices: context.vertices,
+            vertices: context.vertices.into_iter().collect(),
             values: context.values,
             suspended_vertices: context.suspended_vertices,
             folded_contexts: context.folded_contexts,
             folded_values: context.folded_values,
+            folded_property_values: context.folded_property_values,
+            missing_optional_edges: context.missing_optional_edges,
             piggyback: context.piggyback,
             imported_tags: context.imported_tags,
         }
@@ -252,11 +416,13 @@ impl<Vertex> From<DataContext<Vertex>> for SerializableContext<Vertex> {
     fn from(context: DataContext<Vertex>) -> Self {
         Self {
             active_vertex: context.active_vertex,
-            vertices: context.vertices,
+            vertices: context.vertices.into_vec().into_iter().collect(),
             values: context.values,
             suspended_vertices: context.suspended_vertices,
             folded_contexts: context.folded_contexts,
             folded_values: context.folded_values,
+            folded_property_values: context.folded_property_values,
+            missing_optional_edges: context.missing_optional_edges,
             piggyback: context.piggyback,
             imported_tags: context.imported_tags,
         }
@@ -273,12 +439,14 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
             suspended_vertices: Default::default(),
             folded_contexts: Default::default(),
             folded_values: Default::default(),
+            folded_property_values: Default::default(),
+            missing_optional_edges: Default::default(),
             imported_tags: Default::default(),
         }
     }
 
     fn record_vertex(&mut self, vid: Vid) {
-        self.vertices.insert_or_error(vid, self.active_vertex.clone()).unwrap();
+        self.vertices.insert(vid, self.active_vertex.clone());
     }
 
     fn activate_vertex(self, vid: &Vid) -> DataContext<Vertex> {
@@ -289,6 +457,8 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
             suspended_vertices: self.suspended_vertices,
             folded_contexts: self.folded_contexts,
             folded_values: self.folded_values,
+            folded_property_values: self.folded_property_values,
+            missing_optional_edges: self.missing_optional_edges,
             piggyback: self.piggyback,
             imported_tags: self.imported_tags,
         }
@@ -302,6 +472,8 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
             suspended_vertices: self.suspended_vertices.clone(),
             folded_contexts: self.folded_contexts.clone(),
             folded_values: self.folded_values.clone(),
+            folded_property_values: self.folded_property_values.clone(),
+            missing_optional_edges: self.missing_optional_edges.clone(),
             piggyback: None,
             imported_tags: self.imported_tags.clone(),
         }
@@ -315,6 +487,8 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
             suspended_vertices: self.suspended_vertices,
             folded_contexts: self.folded_contexts,
             folded_values: self.folded_values,
+            folded_property_values: self.folded_property_values,
+            missing_optional_edges: self.missing_optional_edges,
             piggyback: self.piggyback,
             imported_tags: self.imported_tags,
         }
@@ -330,6 +504,8 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
                 suspended_vertices: self.suspended_vertices,
                 folded_contexts: self.folded_contexts,
                 folded_values: self.folded_values,
+                folded_property_values: self.folded_property_values,
+                missing_optional_edges: self.missing_optional_edges,
                 piggyback: self.piggyback,
                 imported_tags: self.imported_tags,
             }
@@ -349,6 +525,8 @@ impl<Vertex: Clone + Debug> DataContext<Vertex> {
                     suspended_vertices: self.suspended_vertices,
                     folded_contexts: self.folded_contexts,
                     folded_values: self.folded_values,
+                    folded_property_values: self.folded_property_values,
+                    missing_optional_edges: self.missing_optional_edges,
                     piggyback: self.piggyback,
                     imported_tags: self.imported_tags,
                 }
@@ -410,48 +588,127 @@ impl InterpretedQuery {
         indexed_query: Arc<IndexedQuery>,
         arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
     ) -> Result<Self, QueryArgumentsError> {
-        let mut errors = vec![];
-
-        let mut missing_arguments = vec![];
-        for (variable_name, variable_type) in &indexed_query.ir_query.variables {
-            match arguments.get(variable_name) {
-                Some(argument_value) => {
-                    // Ensure the provided argument value is valid for the variable's inferred type.
-                    if let Err(e) = validate_argument_type(
-                        variable_name.as_ref(),
-                        variable_type,
-                        argument_value,
-                    ) {
-                        errors.push(e);
-                    }
-                }
-                None => {
-                    missing_arguments.push(variable_name.as_ref());
+        let arguments = validate_arguments(
+            &indexed_query.ir_query.variables,
+            &indexed_query.ir_query.variable_defaults,
+            &indexed_query.ir_query.root_component.regex_variables(),
+            arguments,
+        )?;
+        let indexed_query = resolve_parameter_variables(indexed_query, &arguments);
+        Ok(Self { indexed_query, arguments })
+    }
+}
+
+/// Set the edge parameters whose values are query variables to the values of those variables,
+/// so that adapters only ever see the values of edge parameters.
+///
+/// The arguments must already have been validated against the query's variables.
+pub(super) fn resolve_parameter_variables(
+    indexed_query: Arc<IndexedQuery>,
+    arguments: &BTreeMap<Arc<str>, FieldValue>,
+) -> Arc<IndexedQuery> {
+    let ir_query = &indexed_query.ir_query;
+    let root_component = ir_query.root_component.resolve_parameter_variables(arguments);
+    if root_component.is_none() && ir_query.root_parameters.variables.is_empty() {
+        return indexed_query;
+    }
+
+    let mut ir_query = ir_query.clone();
+    ir_query.root_parameters = ir_query.root_parameters.resolve_variables(arguments);
+    if let Some(root_component) = root_component {
+        ir_query.root_component = Arc::new(root_component);
+    }
+    Arc::new(ir_query.try_into().expect("resolving edge parameters produced an invalid query"))
+}
+
+/// Check that the arguments supply exactly the given variables, with values of the right types,
+/// and that the values of variables used as regex patterns are valid regexes.
+///
+/// Returns the arguments together with the default values of any variables they don't supply.
+pub(super) fn validate_arguments(
+    variables: &BTreeMap<Arc<str>, Type>,
+    defaults: &BTreeMap<Arc<str>, FieldValue>,
+    regex_variables: &BTreeSet<(Arc<str>, bool)>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<Arc<BTreeMap<Arc<str>, FieldValue>>, QueryArgumentsError> {
+    let mut errors = vec![];
+
+    // Variables with default values only need to be supplied as arguments
+    // to override their defaults.
+    let arguments = if defaults.keys().all(|name| arguments.contains_key(name)) {
+        arguments
+    } else {
+        let mut arguments = arguments.as_ref().clone();
+        for (name, value) in defaults {
+            arguments.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+        Arc::new(arguments)
+    };
+
+    let mut missing_arguments = vec![];
+    for (variable_name, variable_type) in variables {
+        match arguments.get(variable_name) {
+            Some(argument_value) => {
+                // Ensure the provided argument value is valid for the variable's inferred type.
+                if let Err(e) =
+                    validate_argument_type(variable_name.as_ref(), variable_type, argument_value)
+                {
+                    errors.push(e);
                 }
             }
+            None => {
+                missing_arguments.push((variable_name.as_ref(), variable_type));
+            }
         }
-        if !missing_arguments.is_empty() {
-            errors.push(QueryArgumentsError::MissingArguments(
-                missing_arguments.into_iter().map(|x| x.to_string()).collect(),
-            ));
+    }
+    for (variable_name, case_insensitive) in regex_variables {
+        if let Some(FieldValue::String(pattern)) = arguments.get(variable_name) {
+            if let Err(e) = RegexBuilder::new(pattern).case_insensitive(*case_insensitive).build() {
+                errors.push(QueryArgumentsError::InvalidRegexArgument(
+                    variable_name.to_string(),
+                    e.to_string(),
+                ));
+            }
         }
+    }
+    let unused_arguments = arguments
+        .keys()
+        .map(|x| x.as_ref())
+        .filter(|arg| !variables.contains_key(*arg))
+        .collect_vec();
 
-        let unused_arguments = arguments
-            .keys()
-            .map(|x| x.as_ref())
-            .filter(|arg| !indexed_query.ir_query.variables.contains_key(*arg))
-            .collect_vec();
-        if !unused_arguments.is_empty() {
-            errors.push(QueryArgumentsError::UnusedArguments(
-                unused_arguments.into_iter().map(|x| x.to_string()).collect(),
-            ));
-        }
+    // Missing and unused arguments with similar names are likely to be misspellings
+    // of each other, so point them out.
+    if !missing_arguments.is_empty() {
+        errors.push(QueryArgumentsError::MissingArguments(
+            missing_arguments
+                .iter()
+                .map(|(name, expected_type)| MissingArgument {
+                    name: name.to_string(),
+                    expected_type: (*expected_type).clone(),
+                    suggestion: closest_name(name, unused_arguments.iter().copied())
+                        .map(String::from),
+                })
+                .collect(),
+        ));
+    }
+    if !unused_arguments.is_empty() {
+        errors.push(QueryArgumentsError::UnusedArguments(
+            unused_arguments
+                .iter()
+                .map(|name| UnusedArgument {
+                    name: name.to_string(),
+                    suggestion: closest_name(name, missing_arguments.iter().map(|(m, _)| *m))
+                        .map(String::from),
+                })
+                .collect(),
+        ));
+    }
 
-        if errors.is_empty() {
-            Ok(Self { indexed_query, arguments })
-        } else {
-            Err(errors.into())
-        }
+    if errors.is_empty() {
+        Ok(arguments)
+    } else {
+        Err(errors.into())
     }
 }
 
@@ -606,6 +863,51 @@ pub trait Adapter<'vertex> {
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'vertex, V, FieldValue>;
 
+    /// Resolve several properties of the same vertex type in a single call.
+    ///
+    /// When the query needs more than one property of a vertex, Trustfall calls this method
+    /// instead of calling [`Adapter::resolve_property`] once per property.
+    /// Adapters backed by databases or remote APIs can override it to load all
+    /// the requested properties with a single request, avoiding "N+1 queries" patterns.
+    ///
+    /// The default implementation resolves each property in turn
+    /// using [`Adapter::resolve_property`].
+    ///
+    /// # Preconditions and postconditions
+    ///
+    /// The caller guarantees that each of the `properties` satisfies
+    /// the preconditions of [`Adapter::resolve_property`], and that they are all distinct.
+    ///
+    /// The returned iterator must satisfy these properties:
+    /// - Produce `(context, property_values)` tuples with one value per requested property,
+    ///   in the same order as the `properties` parameter.
+    /// - Produce contexts in the same order as the input `contexts` iterator produced them.
+    /// - Produce property values whose types match the properties' types defined in the schema.
+    /// - When a context's active vertex is `None`, all its property values are
+    ///   [`FieldValue::Null`].
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let mut iterator = contexts;
+        for property in properties {
+            let values = self.resolve_property(iterator, type_name, &property.name, resolve_info);
+            iterator = Box::new(values.map(|(mut context, value)| {
+                context.values.push(value);
+                context
+            }));
+        }
+
+        let count = properties.len();
+        Box::new(iterator.map(move |mut context| {
+            let values = context.values.split_off(context.values.len() - count);
+            (context, values)
+        }))
+    }
+
     /// Resolve the neighboring vertices across an edge.
     ///
     /// Each [`DataContext`] in the `contexts` parameter has an active vertex
@@ -747,6 +1049,22 @@ pub trait Adapter<'vertex> {
         coerce_to_type: &Arc<str>,
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'vertex, V, bool>;
+
+    /// Tell apart the vertices this adapter produces, if possible.
+    ///
+    /// Queries that use `@recurse(dedupe: true)` need to know when two vertices are the same,
+    /// so that they can omit the vertices their recursion has already visited.
+    /// If the vertex type implements [`VertexIdentity`], return
+    /// `Some(VertexIdentifier::from_vertex_identity())`.
+    ///
+    /// The default implementation returns `None`, and executing queries that use
+    /// `@recurse(dedupe: true)` then returns a [`QueryArgumentsError::UnidentifiableVertices`]
+    /// error.
+    ///
+    /// [`QueryArgumentsError::UnidentifiableVertices`]: error::QueryArgumentsError::UnidentifiableVertices
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        None
+    }
 }
 
 /// Attempt to dereference a value to a `&V`, returning `None` if the value did not contain a `V`.
@@ -0,0 +1,999 @@
+//! Adapters that run as plugins, in a separate process from the one executing the query.
+//!
+//! Plugins let tools query data sources they weren't compiled with. A plugin is any program
+//! that reads [`PluginRequest`] messages from its standard input and writes a [`PluginResponse`]
+//! to its standard output for each of them. Each message is a single line of JSON.
+//! Since the messages are the only interface between the two processes, plugins may be
+//! written in any language and built with any compiler version.
+//!
+//! Rust plugins wrap their [`Adapter`] in a [`PluginServer`] and [serve](PluginServer::serve)
+//! requests from standard input. Query executors load a plugin with [`PluginAdapter::spawn()`]
+//! and execute queries over the resulting [`PluginAdapter`] like over any other adapter.
+//!
+//! # Protocol
+//!
+//! The executor first sends a `handshake` request, to which the plugin responds with
+//! the protocol version it implements and the text of its schema.
+//!
+//! Before resolving data for a query, the executor sends a `set_query` request with
+//! the query's IR and arguments. Resolver requests then refer to the query's vertices and edges
+//! by their IDs, allowing the plugin to see the same hints it would see if the query was
+//! executed in-process. The query stays the same until the next `set_query` request.
+//!
+//! Resolver requests carry the vertices whose data is being resolved, in the form
+//! that the plugin produced them. The plugin must respond with exactly one result
+//! for each of them, in the same order. Contexts without an active vertex are handled
+//! by the executor and never sent to the plugin.
+//!
+//! If a request fails, the plugin responds with an `error` response describing the problem.
+//! Since [`Adapter`] methods cannot return errors, the executor records the failure
+//! and stops sending requests to the plugin, resolving the remaining data as if it didn't exist.
+//! Queries run with [`PluginAdapter::execute_query()`] stop loading vertices once a request fails
+//! and report the failure in place of their remaining results. The results of queries executed
+//! over the adapter by other means should be passed through [`PluginAdapter::check_results()`],
+//! which does the same. The failure may also be returned by [`PluginAdapter::take_error()`].
+//!
+//! Plugins can't be dynamic libraries or WASM components loaded into the executor's process:
+//! loading a dynamic library requires `unsafe` code, which this crate forbids, and
+//! loading a WASM component requires embedding a WASM runtime. Such plugins may instead be
+//! hosted by a small program that loads them and serves this protocol on their behalf.
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    io::{BufRead, BufReader, Write},
+    panic::AssertUnwindSafe,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ir::{EdgeParameters, Eid, FieldValue, IRQuery, IndexedQuery, Vid},
+    schema::{error::InvalidSchemaError, Schema},
+};
+
+use super::{
+    error::QueryArgumentsError, execution::interpret_ir_with_cancellation, Adapter, AsVertex,
+    CancellationToken, ContextIterator, ContextOutcomeIterator, DataContext, InterpretedQuery,
+    ResolveEdgeInfo, ResolveInfo, VertexIdentifier, VertexInfo, VertexIterator,
+};
+
+/// The version of the plugin protocol implemented by this crate.
+///
+/// Plugins that implement a different version of the protocol are rejected when loaded.
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+/// The maximum number of vertices sent to a plugin in a single resolver request.
+const BATCH_SIZE: usize = 256;
+
+/// A request from a query executor to a plugin.
+#[non_exhaustive]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PluginRequest {
+    /// Ask for the plugin's protocol version and schema.
+    Handshake { protocol_version: u32 },
+
+    /// Set the query whose data subsequent requests resolve.
+    SetQuery { query: IRQuery, arguments: BTreeMap<Arc<str>, FieldValue> },
+
+    ResolveStartingVertices {
+        edge_name: Arc<str>,
+        parameters: EdgeParameters,
+        vid: Vid,
+        vertex_completed: bool,
+    },
+
+    ResolveProperty {
+        type_name: Arc<str>,
+        property_name: Arc<str>,
+        vid: Vid,
+        vertex_completed: bool,
+        vertices: Vec<Value>,
+    },
+
+    ResolveNeighbors {
+        type_name: Arc<str>,
+        edge_name: Arc<str>,
+        parameters: EdgeParameters,
+        origin_vid: Vid,
+        destination_vid: Vid,
+        eid: Eid,
+        vertices: Vec<Value>,
+    },
+
+    ResolveCoercion {
+        type_name: Arc<str>,
+        coerce_to_type: Arc<str>,
+        vid: Vid,
+        vertex_completed: bool,
+        vertices: Vec<Value>,
+    },
+}
+
+/// A plugin's response to a [`PluginRequest`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginResponse {
+    /// The response to [`PluginRequest::Handshake`].
+    Handshake { protocol_version: u32, schema: String },
+
+    /// The response to [`PluginRequest::SetQuery`].
+    Ok,
+
+    /// The response to [`PluginRequest::ResolveStartingVertices`].
+    Vertices(Vec<Value>),
+
+    /// The response to [`PluginRequest::ResolveProperty`]: each vertex's property value.
+    Values(Vec<FieldValue>),
+
+    /// The response to [`PluginRequest::ResolveNeighbors`]: each vertex's neighbors.
+    Neighbors(Vec<Vec<Value>>),
+
+    /// The response to [`PluginRequest::ResolveCoercion`]: whether each vertex can be coerced.
+    Coercions(Vec<bool>),
+
+    /// The request could not be fulfilled.
+    Error(String),
+}
+
+/// Errors that may arise while communicating with a plugin.
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    #[error("Failed to communicate with the plugin: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("The plugin exited unexpectedly.")]
+    Disconnected,
+
+    #[error("The plugin sent an invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("The plugin reported an error: {0}")]
+    Plugin(String),
+
+    #[error(
+        "The plugin implements protocol version {0}, \
+        but version {PLUGIN_PROTOCOL_VERSION} is required."
+    )]
+    UnsupportedProtocolVersion(u32),
+
+    #[error("The plugin's schema is invalid: {0}")]
+    InvalidSchema(InvalidSchemaError),
+}
+
+/// A means of exchanging messages with a plugin.
+///
+/// Each request and response is a single line of JSON.
+pub trait PluginConnection {
+    /// Send a request to the plugin and return its response.
+    fn call(&mut self, request: &str) -> Result<String, PluginError>;
+}
+
+/// A plugin running in a child process, exchanging messages via its standard input and output.
+///
+/// The process is killed when the `PluginProcess` is dropped.
+#[derive(Debug)]
+pub struct PluginProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl PluginProcess {
+    /// Start the plugin by running the given command.
+    pub fn spawn(mut command: Command) -> Result<Self, PluginError> {
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+        let stdin = child.stdin.take().expect("stdin was not piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout was not piped"));
+        Ok(Self { child, stdin, stdout })
+    }
+}
+
+impl PluginConnection for PluginProcess {
+    fn call(&mut self, request: &str) -> Result<String, PluginError> {
+        writeln!(self.stdin, "{request}")?;
+        self.stdin.flush()?;
+
+        let mut response = String::new();
+        if self.stdout.read_line(&mut response)? == 0 {
+            return Err(PluginError::Disconnected);
+        }
+        Ok(response)
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        // The process may have already exited, in which case there's nothing left to do.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A vertex produced by a plugin, in the form in which the plugin produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginVertex(Arc<Value>);
+
+impl PluginVertex {
+    /// The plugin's representation of this vertex.
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+}
+
+struct PluginState {
+    connection: Box<dyn PluginConnection + Send>,
+
+    /// The query most recently sent to the plugin with [`PluginRequest::SetQuery`].
+    query: Option<InterpretedQuery>,
+
+    /// The failure of a request, which hasn't been reported yet.
+    /// No more requests are sent to the plugin until it has been.
+    failure: Option<PluginError>,
+
+    /// Cancelled when a request fails, stopping queries run with
+    /// [`PluginAdapter::execute_query()`]. Replaced once the failure is reported.
+    failed: CancellationToken,
+}
+
+impl PluginState {
+    fn send(&mut self, request: &PluginRequest) -> Result<PluginResponse, PluginError> {
+        let request = serde_json::to_string(request).expect("failed to serialize request");
+        let response = self.connection.call(&request)?;
+        match serde_json::from_str(&response) {
+            Ok(PluginResponse::Error(message)) => Err(PluginError::Plugin(message)),
+            Ok(response) => Ok(response),
+            Err(e) => Err(PluginError::InvalidResponse(e.to_string())),
+        }
+    }
+
+    /// Take the unreported failure, if any, allowing requests to be sent again.
+    fn take_failure(&mut self) -> Option<PluginError> {
+        let failure = self.failure.take();
+        if failure.is_some() {
+            self.failed = CancellationToken::new();
+        }
+        failure
+    }
+
+    fn set_query(&mut self, query: &InterpretedQuery) -> Result<(), PluginError> {
+        let is_current = self.query.as_ref().is_some_and(|current| {
+            Arc::ptr_eq(&current.indexed_query, &query.indexed_query)
+                && Arc::ptr_eq(&current.arguments, &query.arguments)
+        });
+        if !is_current {
+            self.query = None;
+            let request = PluginRequest::SetQuery {
+                query: query.indexed_query.ir_query.clone(),
+                arguments: query.arguments.as_ref().clone(),
+            };
+            match self.send(&request)? {
+                PluginResponse::Ok => {}
+                response => return Err(unexpected_response(&response)),
+            }
+            self.query = Some(query.clone());
+        }
+        Ok(())
+    }
+}
+
+fn unexpected_response(response: &PluginResponse) -> PluginError {
+    PluginError::InvalidResponse(format!("unexpected response {response:?}"))
+}
+
+/// An [`Adapter`] that resolves queries' data by sending requests to a plugin.
+///
+/// See the [module-level documentation](self) for details.
+pub struct PluginAdapter {
+    schema: Schema,
+    state: Arc<Mutex<PluginState>>,
+}
+
+impl PluginAdapter {
+    /// Start a plugin by running the given command, and load its schema.
+    pub fn spawn(command: Command) -> Result<Self, PluginError> {
+        Self::new(PluginProcess::spawn(command)?)
+    }
+
+    /// Load the schema of the plugin at the other end of the given connection.
+    pub fn new(connection: impl PluginConnection + Send + 'static) -> Result<Self, PluginError> {
+        let mut state = PluginState {
+            connection: Box::new(connection),
+            query: None,
+            failure: None,
+            failed: CancellationToken::new(),
+        };
+        let schema = match state
+            .send(&PluginRequest::Handshake { protocol_version: PLUGIN_PROTOCOL_VERSION })?
+        {
+            PluginResponse::Handshake { protocol_version, schema } => {
+                if protocol_version != PLUGIN_PROTOCOL_VERSION {
+                    return Err(PluginError::UnsupportedProtocolVersion(protocol_version));
+                }
+                Schema::parse(schema).map_err(PluginError::InvalidSchema)?
+            }
+            response => return Err(unexpected_response(&response)),
+        };
+
+        Ok(Self { schema, state: Arc::new(Mutex::new(state)) })
+    }
+
+    /// The plugin's schema, against which queries should be written.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Take the failure of a request to the plugin, if one failed since the last time
+    /// a failure was taken.
+    ///
+    /// Once a request fails, no more requests are sent to the plugin until its failure is taken,
+    /// and the data they would have resolved is treated as nonexistent. Query results computed
+    /// after a failure may therefore be incomplete and should be discarded.
+    pub fn take_error(&self) -> Option<PluginError> {
+        self.state.lock().expect("plugin lock was poisoned").take_failure()
+    }
+
+    /// Run the query over this adapter, ending with an error if a request to the plugin fails.
+    ///
+    /// Once a request fails, the query stops loading vertices from the plugin and the results
+    /// iterator produces the failure, after all the results produced before it. Those results
+    /// are complete and correct: results computed after the failure are discarded, so data
+    /// the plugin failed to provide is never reported as missing, e.g. as a `@fold` count of 0.
+    #[allow(clippy::type_complexity)]
+    pub fn execute_query(
+        self: &Arc<Self>,
+        indexed_query: Arc<IndexedQuery>,
+        arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    ) -> Result<
+        Box<dyn Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, PluginError>>>,
+        QueryArgumentsError,
+    > {
+        let token = self.state.lock().expect("plugin lock was poisoned").failed.clone();
+        let results =
+            interpret_ir_with_cancellation(self.clone(), indexed_query, arguments, token)?;
+        let adapter = self.clone();
+        Ok(Box::new(results.map(move |row| {
+            row.map_err(|_| adapter.take_error().expect("query was cancelled without a failure"))
+        })))
+    }
+
+    /// Produce the results of a query executed over this adapter,
+    /// ending with an error if a request to the plugin fails.
+    ///
+    /// Results computed after the failure may be missing data, so they are discarded.
+    /// Results produced before it are unaffected.
+    pub fn check_results<'a>(
+        &self,
+        mut results: impl Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'a,
+    ) -> impl Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, PluginError>> + 'a {
+        let state = self.state.clone();
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            let row = results.next();
+            if let Some(error) = state.lock().expect("plugin lock was poisoned").take_failure() {
+                finished = true;
+                return Some(Err(error));
+            }
+            finished = row.is_none();
+            row.map(Ok)
+        })
+    }
+}
+
+impl Debug for PluginAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginAdapter").finish_non_exhaustive()
+    }
+}
+
+/// Send a request about the given query to the plugin, and `extract` the result
+/// from its response.
+///
+/// If the request fails, its failure is recorded and `None` is returned.
+/// No request is sent while an earlier failure hasn't been reported.
+fn call_plugin<T>(
+    state: &Mutex<PluginState>,
+    query: &InterpretedQuery,
+    request: &PluginRequest,
+    extract: impl FnOnce(PluginResponse) -> Result<T, PluginError>,
+) -> Option<T> {
+    let mut state = state.lock().expect("plugin lock was poisoned");
+    if state.failure.is_some() {
+        return None;
+    }
+    match state.set_query(query).and_then(|()| state.send(request)).and_then(extract) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            state.failure = Some(e);
+            state.failed.cancel();
+            None
+        }
+    }
+}
+
+/// Resolve the contexts' outcomes in batches, sending only the contexts' active vertices
+/// to the plugin and using `missing` as the outcome for contexts without an active vertex.
+///
+/// The plugin must produce one outcome for each vertex. If its request fails,
+/// all the batch's contexts get the `missing` outcome.
+fn resolve_in_batches<'vertex, V, OutcomeT>(
+    mut contexts: ContextIterator<'vertex, V>,
+    state: Arc<Mutex<PluginState>>,
+    query: InterpretedQuery,
+    mut make_request: impl FnMut(Vec<Value>) -> PluginRequest + 'vertex,
+    extract: impl Fn(PluginResponse) -> Result<Vec<OutcomeT>, PluginResponse> + 'vertex,
+    missing: impl Fn() -> OutcomeT + 'vertex,
+) -> ContextOutcomeIterator<'vertex, V, OutcomeT>
+where
+    V: AsVertex<PluginVertex> + 'vertex,
+    OutcomeT: 'vertex,
+{
+    let batches = std::iter::from_fn(move || {
+        let batch: Vec<DataContext<V>> = contexts.by_ref().take(BATCH_SIZE).collect();
+        if batch.is_empty() {
+            return None;
+        }
+
+        let vertices: Vec<Value> = batch
+            .iter()
+            .filter_map(|ctx| ctx.active_vertex::<PluginVertex>())
+            .map(|vertex| vertex.value().clone())
+            .collect();
+        let vertex_count = vertices.len();
+        let outcomes = if vertices.is_empty() {
+            None
+        } else {
+            call_plugin(&state, &query, &make_request(vertices), |response| {
+                let outcomes =
+                    extract(response).map_err(|response| unexpected_response(&response))?;
+                if outcomes.len() != vertex_count {
+                    return Err(PluginError::InvalidResponse(format!(
+                        "{} results for {vertex_count} vertices",
+                        outcomes.len(),
+                    )));
+                }
+                Ok(outcomes)
+            })
+        };
+
+        let mut outcomes = outcomes.into_iter().flatten();
+        let batch: Vec<_> = batch
+            .into_iter()
+            .map(|ctx| {
+                let outcome = match ctx.active_vertex::<PluginVertex>() {
+                    Some(..) => outcomes.next().unwrap_or_else(&missing),
+                    None => missing(),
+                };
+                (ctx, outcome)
+            })
+            .collect();
+        Some(batch)
+    });
+    Box::new(batches.flatten())
+}
+
+impl<'vertex> Adapter<'vertex> for PluginAdapter {
+    type Vertex = PluginVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let request = PluginRequest::ResolveStartingVertices {
+            edge_name: edge_name.clone(),
+            parameters: parameters.clone(),
+            vid: resolve_info.vid(),
+            vertex_completed: resolve_info.vertex_completed(),
+        };
+        let query = resolve_info.clone().into_inner();
+        let vertices = call_plugin(&self.state, &query, &request, |response| match response {
+            PluginResponse::Vertices(vertices) => Ok(vertices),
+            response => Err(unexpected_response(&response)),
+        });
+        Box::new(vertices.into_iter().flatten().map(|vertex| PluginVertex(Arc::new(vertex))))
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let type_name = type_name.clone();
+        let property_name = property_name.clone();
+        let vid = resolve_info.vid();
+        let vertex_completed = resolve_info.vertex_completed();
+        resolve_in_batches(
+            contexts,
+            self.state.clone(),
+            resolve_info.clone().into_inner(),
+            move |vertices| PluginRequest::ResolveProperty {
+                type_name: type_name.clone(),
+                property_name: property_name.clone(),
+                vid,
+                vertex_completed,
+                vertices,
+            },
+            |response| match response {
+                PluginResponse::Values(values) => Ok(values),
+                response => Err(response),
+            },
+            || FieldValue::Null,
+        )
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let type_name = type_name.clone();
+        let edge_name = edge_name.clone();
+        let parameters = parameters.clone();
+        let origin_vid = resolve_info.origin_vid();
+        let destination_vid = resolve_info.destination_vid();
+        let eid = resolve_info.eid();
+        resolve_in_batches(
+            contexts,
+            self.state.clone(),
+            resolve_info.clone().into_inner(),
+            move |vertices| PluginRequest::ResolveNeighbors {
+                type_name: type_name.clone(),
+                edge_name: edge_name.clone(),
+                parameters: parameters.clone(),
+                origin_vid,
+                destination_vid,
+                eid,
+                vertices,
+            },
+            |response| match response {
+                PluginResponse::Neighbors(neighbors) => Ok(neighbors
+                    .into_iter()
+                    .map(|neighbors| -> VertexIterator<'vertex, Self::Vertex> {
+                        Box::new(neighbors.into_iter().map(|vertex| PluginVertex(Arc::new(vertex))))
+                    })
+                    .collect()),
+                response => Err(response),
+            },
+            || -> VertexIterator<'vertex, Self::Vertex> { Box::new(std::iter::empty()) },
+        )
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let type_name = type_name.clone();
+        let coerce_to_type = coerce_to_type.clone();
+        let vid = resolve_info.vid();
+        let vertex_completed = resolve_info.vertex_completed();
+        resolve_in_batches(
+            contexts,
+            self.state.clone(),
+            resolve_info.clone().into_inner(),
+            move |vertices| PluginRequest::ResolveCoercion {
+                type_name: type_name.clone(),
+                coerce_to_type: coerce_to_type.clone(),
+                vid,
+                vertex_completed,
+                vertices,
+            },
+            |response| match response {
+                PluginResponse::Coercions(coercions) => Ok(coercions),
+                response => Err(response),
+            },
+            || false,
+        )
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        // Vertices with the same serialized form represent the same data.
+        Some(VertexIdentifier::new(|vertex: &PluginVertex| vertex.value().to_string()))
+    }
+}
+
+/// Serves requests from query executors on behalf of an [`Adapter`], making it a plugin.
+///
+/// The adapter's vertices are sent to the executor in their serialized form,
+/// and deserialized again when the executor asks for their data.
+///
+/// Usually, a plugin's `main()` function serves requests arriving on standard input:
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use serde::{de::DeserializeOwned, Serialize};
+/// # use trustfall_core::interpreter::{plugin::PluginServer, Adapter};
+/// # fn main_with<AdapterT>(adapter: Arc<AdapterT>, schema: &str)
+/// # where
+/// #     AdapterT: Adapter<'static>,
+/// #     AdapterT::Vertex: Serialize + DeserializeOwned,
+/// # {
+/// PluginServer::new(adapter, schema)
+///     .serve(std::io::stdin().lock(), std::io::stdout().lock())
+///     .expect("failed to serve plugin requests");
+/// # }
+/// ```
+pub struct PluginServer<AdapterT> {
+    adapter: Arc<AdapterT>,
+    schema: String,
+    query: Option<InterpretedQuery>,
+}
+
+impl<AdapterT> PluginServer<AdapterT>
+where
+    AdapterT: Adapter<'static>,
+    AdapterT::Vertex: Serialize + DeserializeOwned,
+{
+    /// Serve requests for the given adapter, which implements the schema with the given text.
+    pub fn new(adapter: Arc<AdapterT>, schema: impl Into<String>) -> Self {
+        Self { adapter, schema: schema.into(), query: None }
+    }
+
+    /// Respond to requests read from `input` by writing responses to `output`,
+    /// until `input` runs out.
+    pub fn serve(&mut self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for request in input.lines() {
+            let response = self.handle_message(&request?);
+            writeln!(output, "{response}")?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Respond to a single serialized request with a serialized response.
+    pub fn handle_message(&mut self, request: &str) -> String {
+        let response = match serde_json::from_str(request) {
+            Ok(request) => {
+                // Report panics to the executor instead of exiting,
+                // so that a failed request doesn't also fail all later ones.
+                std::panic::catch_unwind(AssertUnwindSafe(|| self.handle(request))).unwrap_or_else(
+                    |panic| {
+                        let message = panic
+                            .downcast_ref::<&str>()
+                            .map(|message| message.to_string())
+                            .or_else(|| panic.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "the adapter panicked".to_string());
+                        PluginResponse::Error(message)
+                    },
+                )
+            }
+            Err(e) => PluginResponse::Error(format!("invalid request: {e}")),
+        };
+        serde_json::to_string(&response).expect("failed to serialize response")
+    }
+
+    /// Respond to a single request.
+    pub fn handle(&mut self, request: PluginRequest) -> PluginResponse {
+        self.try_handle(request).unwrap_or_else(PluginResponse::Error)
+    }
+
+    fn try_handle(&mut self, request: PluginRequest) -> Result<PluginResponse, String> {
+        match request {
+            PluginRequest::Handshake { .. } => Ok(PluginResponse::Handshake {
+                protocol_version: PLUGIN_PROTOCOL_VERSION,
+                schema: self.schema.clone(),
+            }),
+            PluginRequest::SetQuery { query, arguments } => {
+                let indexed_query =
+                    IndexedQuery::try_from(query).map_err(|e| format!("invalid query: {e:?}"))?;
+                let query = InterpretedQuery::from_query_and_arguments(
+                    Arc::new(indexed_query),
+                    Arc::new(arguments),
+                )
+                .map_err(|e| format!("invalid query arguments: {e:?}"))?;
+                self.query = Some(query);
+                Ok(PluginResponse::Ok)
+            }
+            PluginRequest::ResolveStartingVertices {
+                edge_name,
+                parameters,
+                vid,
+                vertex_completed,
+            } => {
+                let resolve_info = ResolveInfo::new(self.current_query()?, vid, vertex_completed);
+                self.adapter
+                    .resolve_starting_vertices(&edge_name, &parameters, &resolve_info)
+                    .map(serialize_vertex)
+                    .collect::<Result<_, _>>()
+                    .map(PluginResponse::Vertices)
+            }
+            PluginRequest::ResolveProperty {
+                type_name,
+                property_name,
+                vid,
+                vertex_completed,
+                vertices,
+            } => {
+                let resolve_info = ResolveInfo::new(self.current_query()?, vid, vertex_completed);
+                let contexts = deserialize_contexts::<AdapterT::Vertex>(vertices)?;
+                let values = self
+                    .adapter
+                    .resolve_property(contexts, &type_name, &property_name, &resolve_info)
+                    .map(|(_, value)| value)
+                    .collect();
+                Ok(PluginResponse::Values(values))
+            }
+            PluginRequest::ResolveNeighbors {
+                type_name,
+                edge_name,
+                parameters,
+                origin_vid,
+                destination_vid,
+                eid,
+                vertices,
+            } => {
+                let resolve_info =
+                    ResolveEdgeInfo::new(self.current_query()?, origin_vid, destination_vid, eid);
+                let contexts = deserialize_contexts::<AdapterT::Vertex>(vertices)?;
+                self.adapter
+                    .resolve_neighbors(contexts, &type_name, &edge_name, &parameters, &resolve_info)
+                    .map(|(_, neighbors)| neighbors.map(serialize_vertex).collect())
+                    .collect::<Result<_, _>>()
+                    .map(PluginResponse::Neighbors)
+            }
+            PluginRequest::ResolveCoercion {
+                type_name,
+                coerce_to_type,
+                vid,
+                vertex_completed,
+                vertices,
+            } => {
+                let resolve_info = ResolveInfo::new(self.current_query()?, vid, vertex_completed);
+                let contexts = deserialize_contexts::<AdapterT::Vertex>(vertices)?;
+                let coercions = self
+                    .adapter
+                    .resolve_coercion(contexts, &type_name, &coerce_to_type, &resolve_info)
+                    .map(|(_, can_coerce)| can_coerce)
+                    .collect();
+                Ok(PluginResponse::Coercions(coercions))
+            }
+        }
+    }
+
+    fn current_query(&self) -> Result<InterpretedQuery, String> {
+        self.query.clone().ok_or_else(|| "no query was set".to_string())
+    }
+}
+
+impl<AdapterT> PluginConnection for PluginServer<AdapterT>
+where
+    AdapterT: Adapter<'static>,
+    AdapterT::Vertex: Serialize + DeserializeOwned,
+{
+    fn call(&mut self, request: &str) -> Result<String, PluginError> {
+        Ok(self.handle_message(request))
+    }
+}
+
+fn serialize_vertex<T: Serialize>(vertex: T) -> Result<Value, String> {
+    serde_json::to_value(vertex).map_err(|e| format!("failed to serialize vertex: {e}"))
+}
+
+fn deserialize_contexts<T: DeserializeOwned + Debug + Clone + 'static>(
+    vertices: Vec<Value>,
+) -> Result<ContextIterator<'static, T>, String> {
+    let contexts = vertices
+        .into_iter()
+        .map(|vertex| {
+            serde_json::from_value(vertex)
+                .map(|vertex| DataContext::new(Some(vertex)))
+                .map_err(|e| format!("invalid vertex: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Box::new(contexts.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use trustfall_filetests_macros::parameterize;
+
+    use super::{
+        PluginAdapter, PluginConnection, PluginError, PluginServer, PLUGIN_PROTOCOL_VERSION,
+    };
+    use crate::{
+        frontend::parse,
+        interpreter::execution::interpret_ir,
+        ir::{FieldValue, IndexedQuery},
+        nullables_interpreter::NullablesAdapter,
+        numbers_interpreter::NumbersAdapter,
+        schema::Schema,
+        test_types::{TestIRQueryResult, TestInterpreterOutputData},
+    };
+
+    fn numbers_plugin() -> PluginAdapter {
+        let schema = include_str!("../../test_data/schemas/numbers.graphql");
+        PluginAdapter::new(PluginServer::new(Arc::new(NumbersAdapter::new()), schema))
+            .expect("failed to load plugin")
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.ir.ron"));
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+        let test_query = test_query.unwrap();
+        let adapter = match test_query.schema_name.as_str() {
+            "numbers" => numbers_plugin(),
+            "nullables" => PluginAdapter::new(PluginServer::new(
+                Arc::new(NullablesAdapter),
+                include_str!("../../test_data/schemas/nullables.graphql"),
+            ))
+            .expect("failed to load plugin"),
+            _ => return,
+        };
+
+        let mut output_path = PathBuf::from(base);
+        output_path.push(format!("{stem}.output.ron"));
+        let output_data = fs::read_to_string(output_path).unwrap();
+        let expected_outputs: TestInterpreterOutputData = ron::from_str(&output_data).unwrap();
+
+        let arguments: BTreeMap<Arc<str>, FieldValue> =
+            test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+        let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+
+        let results: Vec<_> =
+            interpret_ir(Arc::new(adapter), indexed_query.into(), arguments.into())
+                .expect("execution error")
+                .collect();
+        assert_eq!(expected_outputs.results, results);
+    }
+
+    #[test]
+    fn plugin_serves_requests_from_input() {
+        let schema = include_str!("../../test_data/schemas/numbers.graphql");
+        let mut server = PluginServer::new(Arc::new(NumbersAdapter::new()), schema);
+
+        let input = format!(
+            "{}\n{}\n",
+            serde_json::json!({"op": "handshake", "protocol_version": PLUGIN_PROTOCOL_VERSION}),
+            serde_json::json!({"op": "resolve_starting_vertices"}),
+        );
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).expect("failed to serve requests");
+
+        let responses: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(2, responses.len());
+        assert_eq!(
+            serde_json::json!({
+                "handshake": {"protocol_version": PLUGIN_PROTOCOL_VERSION, "schema": schema},
+            }),
+            responses[0],
+        );
+        assert!(responses[1]["error"].as_str().unwrap().starts_with("invalid request"));
+    }
+
+    #[test]
+    fn plugin_errors_are_reported() {
+        let mut server = PluginServer::new(Arc::new(NumbersAdapter::new()), "");
+        let response = server.handle_message(
+            r#"{"op": "resolve_starting_vertices", "edge_name": "Zero", "parameters": {"contents": {}}, "vid": 1, "vertex_completed": false}"#,
+        );
+        assert_eq!(r#"{"error":"no query was set"}"#, response);
+    }
+
+    struct OutdatedPlugin;
+
+    impl PluginConnection for OutdatedPlugin {
+        fn call(&mut self, _request: &str) -> Result<String, PluginError> {
+            Ok(serde_json::json!({
+                "handshake": {"protocol_version": 0, "schema": ""},
+            })
+            .to_string())
+        }
+    }
+
+    /// A plugin whose process exits after serving the given number of requests.
+    struct ExitingPlugin {
+        server: PluginServer<NumbersAdapter>,
+        remaining_requests: usize,
+    }
+
+    impl PluginConnection for ExitingPlugin {
+        fn call(&mut self, request: &str) -> Result<String, PluginError> {
+            if self.remaining_requests == 0 {
+                return Err(PluginError::Disconnected);
+            }
+            self.remaining_requests -= 1;
+            self.server.call(request)
+        }
+    }
+
+    #[test]
+    fn failed_requests_are_reported_instead_of_remaining_results() {
+        let schema = include_str!("../../test_data/schemas/numbers.graphql");
+        let query = "{ Number(min: 1, max: 3) { value @output } }";
+
+        // The plugin exits after the handshake, the query, and the starting vertices requests,
+        // before resolving the vertices' values.
+        let adapter = Arc::new(
+            PluginAdapter::new(ExitingPlugin {
+                server: PluginServer::new(Arc::new(NumbersAdapter::new()), schema),
+                remaining_requests: 3,
+            })
+            .expect("failed to load plugin"),
+        );
+        let indexed_query = parse(adapter.schema(), query).expect("invalid query");
+        let results = interpret_ir(adapter.clone(), indexed_query, Default::default())
+            .expect("execution error");
+        let results: Vec<_> = adapter.check_results(results).collect();
+
+        assert_eq!(1, results.len(), "{results:?}");
+        assert!(matches!(results[0], Err(PluginError::Disconnected)), "{results:?}");
+        assert!(adapter.take_error().is_none());
+    }
+
+    #[test]
+    fn failed_requests_under_fold_counts_are_not_reported_as_empty_folds() {
+        let schema = include_str!("../../test_data/schemas/numbers.graphql");
+        let query = r#"
+{
+    Number(min: 1, max: 300) {
+        value @output
+        multiple(max: 4) @fold @transform(op: "count") @output
+    }
+}"#;
+        let indexed_query = parse(&Schema::parse(schema).unwrap(), query).expect("invalid query");
+        let expected: Vec<_> =
+            interpret_ir(Arc::new(NumbersAdapter::new()), indexed_query.clone(), Default::default())
+                .expect("execution error")
+                .collect();
+
+        // The plugin exits after the handshake, the query, the starting vertices, and
+        // the first batches of neighbors and values, before resolving the second batch's neighbors.
+        let adapter = Arc::new(
+            PluginAdapter::new(ExitingPlugin {
+                server: PluginServer::new(Arc::new(NumbersAdapter::new()), schema),
+                remaining_requests: 5,
+            })
+            .expect("failed to load plugin"),
+        );
+        let results: Vec<_> = adapter
+            .execute_query(indexed_query, Default::default())
+            .expect("execution error")
+            .collect();
+
+        let (last, produced) = results.split_last().expect("no results");
+        assert!(matches!(last, Err(PluginError::Disconnected)), "{last:?}");
+        assert!(!produced.is_empty() && produced.len() < expected.len(), "{}", produced.len());
+        // Rows whose neighbors the plugin failed to resolve would have a count of 0.
+        // They are discarded instead of produced.
+        for (row, expected_row) in produced.iter().zip(&expected) {
+            assert_eq!(expected_row, row.as_ref().expect("unexpected error"));
+        }
+        assert!(adapter.take_error().is_none());
+    }
+
+    #[test]
+    fn plugins_with_unsupported_protocol_versions_are_rejected() {
+        let error = PluginAdapter::new(OutdatedPlugin).expect_err("loaded an outdated plugin");
+        assert!(matches!(error, PluginError::UnsupportedProtocolVersion(0)), "{error:?}");
+    }
+}
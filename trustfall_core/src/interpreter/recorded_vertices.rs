@@ -0,0 +1,181 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::Index,
+    sync::{Arc, OnceLock},
+};
+
+use crate::ir::Vid;
+
+/// The vertices recorded by a [`DataContext`](super::DataContext), keyed by their [`Vid`].
+///
+/// Each context that expands an edge is split into one context per neighbor, and all of them
+/// share the vertices recorded up to that point. Instead of copying those vertices into
+/// each new context, the recorded vertices form a persistent list whose tail is shared
+/// between all contexts split from the same one. Splitting a context is then just
+/// a reference count increment, and recording a vertex only allocates one list node.
+///
+/// Queries record one vertex per [`Vid`], of which there are only a few,
+/// so looking up a vertex by walking the list is cheap.
+///
+/// Lists can only be cloned if their vertices can, and cloning a list records how to clone
+/// its vertices in both the original and the clone. Taking the vertices out of a list
+/// whose nodes are shared then clones them, without requiring `Vertex: Clone` of its callers.
+pub(super) struct RecordedVertices<Vertex> {
+    head: Option<Arc<Node<Vertex>>>,
+    clone_vertex: OnceLock<fn(&Vertex) -> Vertex>,
+}
+
+struct Node<Vertex> {
+    vid: Vid,
+    vertex: Option<Vertex>,
+    rest: Option<Arc<Node<Vertex>>>,
+}
+
+impl<Vertex> RecordedVertices<Vertex> {
+    pub(super) fn get(&self, vid: &Vid) -> Option<&Option<Vertex>> {
+        self.iter().find_map(|(recorded_vid, vertex)| (recorded_vid == vid).then_some(vertex))
+    }
+
+    /// Record the vertex with the given [`Vid`].
+    ///
+    /// Panics if a vertex with that [`Vid`] was already recorded.
+    pub(super) fn insert(&mut self, vid: Vid, vertex: Option<Vertex>) {
+        assert!(self.get(&vid).is_none(), "vertex {vid:?} was already recorded");
+        let rest = self.head.take();
+        self.head = Some(Arc::new(Node { vid, vertex, rest }));
+    }
+
+    /// Iterate over the recorded vertices, most recently recorded first.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&Vid, &Option<Vertex>)> {
+        let mut next = self.head.as_deref();
+        std::iter::from_fn(move || {
+            let node = next?;
+            next = node.rest.as_deref();
+            Some((&node.vid, &node.vertex))
+        })
+    }
+
+    /// Take the recorded vertices in the order they were recorded.
+    ///
+    /// Vertices that aren't shared with any other context are moved instead of cloned.
+    pub(super) fn into_vec(self) -> Vec<(Vid, Option<Vertex>)> {
+        let clone_vertex = self.clone_vertex.get().copied();
+        let mut recorded = vec![];
+        let mut next = self.head;
+        while let Some(node) = next {
+            match Arc::try_unwrap(node) {
+                Ok(mut node) => {
+                    next = node.rest.take();
+                    recorded.push((node.vid, node.vertex.take()));
+                }
+                Err(node) => {
+                    let clone_vertex =
+                        clone_vertex.expect("recorded vertices were shared without being cloned");
+                    next = node.rest.clone();
+                    recorded.push((node.vid, node.vertex.as_ref().map(clone_vertex)));
+                }
+            }
+        }
+        recorded.reverse();
+        recorded
+    }
+}
+
+impl<Vertex> Default for RecordedVertices<Vertex> {
+    fn default() -> Self {
+        Self { head: None, clone_vertex: OnceLock::new() }
+    }
+}
+
+impl<Vertex: Clone> Clone for RecordedVertices<Vertex> {
+    fn clone(&self) -> Self {
+        let clone_vertex = *self.clone_vertex.get_or_init(|| Vertex::clone);
+        Self { head: self.head.clone(), clone_vertex: OnceLock::from(clone_vertex) }
+    }
+}
+
+impl<Vertex: Debug> Debug for RecordedVertices<Vertex> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.iter().collect::<BTreeMap<_, _>>().fmt(f)
+    }
+}
+
+impl<Vertex: PartialEq> PartialEq for RecordedVertices<Vertex> {
+    fn eq(&self, other: &Self) -> bool {
+        let left: BTreeMap<_, _> = self.iter().collect();
+        let right: BTreeMap<_, _> = other.iter().collect();
+        left == right
+    }
+}
+
+impl<Vertex: Eq> Eq for RecordedVertices<Vertex> {}
+
+impl<Vertex> Index<&Vid> for RecordedVertices<Vertex> {
+    type Output = Option<Vertex>;
+
+    fn index(&self, vid: &Vid) -> &Self::Output {
+        self.get(vid).unwrap_or_else(|| panic!("vertex {vid:?} was not recorded"))
+    }
+}
+
+impl<Vertex> FromIterator<(Vid, Option<Vertex>)> for RecordedVertices<Vertex> {
+    fn from_iter<T: IntoIterator<Item = (Vid, Option<Vertex>)>>(iter: T) -> Self {
+        let mut recorded = Self::default();
+        for (vid, vertex) in iter {
+            recorded.insert(vid, vertex);
+        }
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::Vid;
+
+    use super::RecordedVertices;
+
+    fn vid(n: usize) -> Vid {
+        Vid::new(n.try_into().unwrap())
+    }
+
+    #[test]
+    fn split_contexts_share_previously_recorded_vertices() {
+        let mut parent: RecordedVertices<i64> = RecordedVertices::default();
+        parent.insert(vid(1), Some(1));
+
+        let mut first = parent.clone();
+        let mut second = parent.clone();
+        first.insert(vid(2), Some(2));
+        second.insert(vid(2), None);
+
+        assert_eq!(&Some(1), &parent[&vid(1)]);
+        assert_eq!(None, parent.get(&vid(2)));
+        assert_eq!(vec![(vid(1), Some(1)), (vid(2), Some(2))], first.clone().into_vec());
+        assert_eq!(vec![(vid(1), Some(1)), (vid(2), None)], second.clone().into_vec());
+        assert_ne!(first, second);
+
+        // Equality doesn't depend on the order in which vertices were recorded.
+        let reordered: RecordedVertices<i64> =
+            [(vid(2), Some(2)), (vid(1), Some(1))].into_iter().collect();
+        assert_eq!(first, reordered);
+    }
+
+    #[test]
+    fn vertices_that_cannot_be_cloned_can_be_taken() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Unique(i64);
+
+        let recorded: RecordedVertices<Unique> =
+            [(vid(1), Some(Unique(1))), (vid(2), None)].into_iter().collect();
+        assert_eq!(vec![(vid(1), Some(Unique(1))), (vid(2), None)], recorded.into_vec());
+    }
+
+    #[test]
+    #[should_panic(expected = "was already recorded")]
+    fn recording_a_vertex_twice_panics() {
+        let mut recorded: RecordedVertices<i64> = RecordedVertices::default();
+        recorded.insert(vid(1), Some(1));
+        recorded.insert(vid(1), Some(2));
+    }
+}
@@ -15,10 +15,11 @@ use crate::{
 };
 
 use super::{
+    error::QueryArgumentsError,
     execution::interpret_ir,
     trace::{FunctionCall, Opid, Trace, TraceOp, TraceOpContent, YieldValue},
     Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, DataContext, ResolveEdgeInfo,
-    ResolveInfo, VertexIterator,
+    ResolveInfo, VertexIdentifier, VertexIterator,
 };
 
 #[derive(Clone, Debug)]
@@ -510,66 +511,86 @@ where
             unreachable!()
         }
     }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'trace, Self::Vertex>> {
+        // Only the recorded vertices are available while replaying, so vertices
+        // are told apart by their serialized form.
+        Some(VertexIdentifier::new(|vertex: &Vertex| {
+            serde_json::to_string(vertex).expect("failed to serialize vertex")
+        }))
+    }
 }
 
-#[allow(dead_code)]
-pub fn assert_interpreted_results<'query, 'trace, Vertex>(
-    trace: &Trace<Vertex>,
-    expected_results: &[BTreeMap<Arc<str>, FieldValue>],
-    complete: bool,
-) where
-    Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned + 'query,
-    'trace: 'query,
+/// Re-run the query recorded in the trace, loading all its data from the trace.
+///
+/// This makes it possible to test a query without access to the data source
+/// it was recorded against, such as a live API. The replayed results are checked
+/// against the results recorded in the trace as they are produced.
+///
+/// Panics if query execution diverges from the trace: for example, if the trace was recorded
+/// by a version of Trustfall that executes queries differently, or has been edited by hand.
+#[allow(clippy::type_complexity)]
+pub fn replay_trace<'trace, Vertex>(
+    trace: &'trace Trace<Vertex>,
+) -> Result<Box<dyn Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'trace>, QueryArgumentsError>
+where
+    Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned + 'trace,
 {
     let next_op = Rc::new(RefCell::new(trace.ops.iter()));
     let trace_reader_adapter = Arc::new(TraceReaderAdapter { next_op: next_op.clone() });
 
-    let query: Arc<IndexedQuery> = Arc::new(trace.ir_query.clone().try_into().unwrap());
+    let query: Arc<IndexedQuery> =
+        Arc::new(trace.ir_query.clone().try_into().expect("trace contains an invalid query"));
     let arguments = Arc::new(
         trace.arguments.iter().map(|(k, v)| (Arc::from(k.to_owned()), v.clone())).collect(),
     );
-    let mut trace_iter = interpret_ir(trace_reader_adapter, query, arguments).unwrap();
-    let mut expected_iter = expected_results.iter();
-
-    loop {
-        let expected_row = expected_iter.next();
-        let trace_row = trace_iter.next();
-
-        if let Some(expected_row_content) = expected_row {
-            let trace_expected_row = {
-                let mut next_op_ref = next_op.borrow_mut();
-                let Some((_, trace_op)) = next_op_ref.next() else {
-                    panic!("Reached the end of the trace without producing result {trace_row:#?}");
-                };
-                let TraceOpContent::ProduceQueryResult(expected_result) = &trace_op.content else {
-                    panic!("Expected the trace to produce a result {trace_row:#?} but got another type of operation instead: {trace_op:#?}");
-                };
-                drop(next_op_ref);
-
-                expected_result
-            };
-            assert_eq!(
-                trace_expected_row, expected_row_content,
-                "This trace is self-inconsistent: trace produces row {trace_expected_row:#?} \
-                but results have row {expected_row_content:#?}",
-            );
+    let mut results = interpret_ir(trace_reader_adapter, query, arguments)?;
 
-            assert_eq!(expected_row, trace_row.as_ref());
-        } else {
-            if complete {
-                assert_eq!(None, trace_row);
-            }
-            return;
-        }
+    Ok(Box::new(std::iter::from_fn(move || {
+        let row = results.next()?;
+
+        let Some((_, trace_op)) = advance_ref_iter(next_op.as_ref()) else {
+            panic!("Reached the end of the trace without producing result {row:#?}");
+        };
+        let TraceOpContent::ProduceQueryResult(recorded_row) = &trace_op.content else {
+            panic!("Expected the trace to produce a result {row:#?} but got another type of operation instead: {trace_op:#?}");
+        };
+        assert_eq!(
+            recorded_row, &row,
+            "Replaying the trace produced row {row:#?} but the trace recorded row {recorded_row:#?}",
+        );
+
+        Some(row)
+    })))
+}
+
+/// Replay the trace, asserting that it produces the expected results.
+///
+/// If `complete` is set, also asserts that the trace produces no other results.
+pub fn assert_interpreted_results<Vertex>(
+    trace: &Trace<Vertex>,
+    expected_results: &[BTreeMap<Arc<str>, FieldValue>],
+    complete: bool,
+) where
+    Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned,
+{
+    let mut trace_iter = replay_trace(trace).expect("trace contains invalid arguments");
+    for expected_row in expected_results {
+        assert_eq!(Some(expected_row), trace_iter.next().as_ref());
+    }
+    if complete {
+        assert_eq!(None, trace_iter.next());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::BTreeMap,
         fmt::Debug,
         fs,
         path::{Path, PathBuf},
+        sync::{Arc, Mutex},
     };
 
     use serde::{de::DeserializeOwned, Serialize};
@@ -577,8 +598,13 @@ mod tests {
 
     use crate::{
         filesystem_interpreter::FilesystemVertex,
-        interpreter::replay::assert_interpreted_results,
-        numbers_interpreter::NumbersVertex,
+        interpreter::{
+            execution::interpret_ir,
+            replay::{assert_interpreted_results, replay_trace},
+            trace::{tap_results, AdapterTap, Trace},
+        },
+        nullables_interpreter::NullablesVertex,
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
         test_types::{
             TestIRQuery, TestIRQueryResult, TestInterpreterOutputData, TestInterpreterOutputTrace,
         },
@@ -632,6 +658,55 @@ mod tests {
         }
     }
 
+    fn check_nullables_trace(
+        expected_ir: TestIRQuery,
+        input_data: &str,
+        test_outputs: TestInterpreterOutputData,
+    ) {
+        match ron::from_str::<TestInterpreterOutputTrace<NullablesVertex>>(input_data) {
+            Ok(test_data) => {
+                assert_eq!(expected_ir.schema_name, "nullables");
+                assert_eq!(test_data.schema_name, "nullables");
+                check_trace(expected_ir, test_data, test_outputs);
+            }
+            Err(e) => {
+                unreachable!("failed to parse trace file: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn recorded_trace_replays_after_serialization() {
+        let query = r#"
+{
+    Number(min: 1, max: 5) {
+        value @output
+
+        successor {
+            ... on Prime {
+                next: value @output
+            }
+        }
+    }
+}"#;
+        let adapter = NumbersAdapter::new();
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+
+        let tracer =
+            Arc::new(Mutex::new(Trace::new(indexed_query.ir_query.clone(), BTreeMap::new())));
+        let adapter_tap = Arc::new(AdapterTap::new(adapter, tracer.clone()));
+        let results =
+            interpret_ir(adapter_tap.clone(), indexed_query, Arc::new(BTreeMap::new())).unwrap();
+        let results: Vec<_> = tap_results(adapter_tap, results).collect();
+        assert!(!results.is_empty());
+
+        let serialized = ron::to_string(&*tracer.lock().unwrap()).unwrap();
+        let trace: Trace<NumbersVertex> = ron::from_str(&serialized).unwrap();
+
+        let replayed: Vec<_> = replay_trace(&trace).unwrap().collect();
+        assert_eq!(results, replayed);
+    }
+
     #[parameterize("trustfall_core/test_data/tests/valid_queries")]
     fn parameterized_tester(base: &Path, stem: &str) {
         let mut input_path = PathBuf::from(base);
@@ -655,6 +730,7 @@ mod tests {
         match expected_ir.schema_name.as_str() {
             "filesystem" => check_filesystem_trace(expected_ir, input_data.as_str(), test_outputs),
             "numbers" => check_numbers_trace(expected_ir, input_data.as_str(), test_outputs),
+            "nullables" => check_nullables_trace(expected_ir, input_data.as_str(), test_outputs),
             _ => unreachable!("{}", expected_ir.schema_name),
         }
     }
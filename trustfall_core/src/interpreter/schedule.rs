@@ -0,0 +1,396 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::ir::{EdgeParameters, FieldValue, IndexedQuery, Vid};
+
+use super::{
+    error::QueryArgumentsError, execution::interpret_ir, Adapter, AsVertex, ContextIterator,
+    ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo, ResolveInfo, ResolverCall,
+    VertexIdentifier, VertexInfo, VertexIterator,
+};
+
+/// How [`interpret_ir_debug`] interleaves the work of the adapter's resolver calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionStrategy {
+    /// Pull each context through the resolvers as late as possible, as [`interpret_ir`] does.
+    ///
+    /// [`interpret_ir`]: super::execution::interpret_ir
+    #[default]
+    Lazy,
+
+    /// Finish each resolver call before any later call produces its first output.
+    ///
+    /// Each call processes all of its input contexts at once, so that each level of the query
+    /// is resolved as a single batch. Results are the same as with the lazy strategy,
+    /// but the adapter sees a much simpler schedule of work.
+    BatchPerLevel,
+}
+
+/// One event in an [`ExecutionSchedule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScheduleEvent {
+    /// A resolver method was called at the given vertex in the query.
+    /// For neighbors, this is the vertex at which the edge starts.
+    Call { call: ResolverCall, vid: Vid },
+
+    /// The resolver pulled one of its input contexts.
+    Input,
+
+    /// The resolver produced one of its outputs: a vertex for starting vertices,
+    /// and a context together with its outcome for all other resolvers.
+    Output,
+
+    /// One of the neighbor iterators returned by a neighbors resolver produced a vertex.
+    Neighbor,
+}
+
+/// An event in an [`ExecutionSchedule`], attributed to the resolver call it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleStep {
+    /// The resolver call to which the event belongs, numbered in the order the calls were made.
+    pub call: usize,
+
+    pub event: ScheduleEvent,
+}
+
+/// The order in which resolver calls were made and did their work while executing a query.
+///
+/// For the same query, arguments, and strategy, and an adapter that behaves deterministically,
+/// the schedule is always the same. Comparing schedules across adapter or interpreter versions
+/// with [`Self::first_divergence`] pinpoints where their behavior started to differ.
+/// The [`Display`] implementation describes one step per line, for use in logs.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExecutionSchedule {
+    pub steps: Vec<ScheduleStep>,
+}
+
+impl ExecutionSchedule {
+    /// The index of the first step at which the two schedules differ,
+    /// or `None` if they are identical.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        let common = self.steps.iter().zip(&other.steps).position(|(left, right)| left != right);
+        common.or_else(|| {
+            (self.steps.len() != other.steps.len()).then(|| self.steps.len().min(other.steps.len()))
+        })
+    }
+}
+
+impl Display for ExecutionSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            write!(f, "#{} ", step.call)?;
+            match &step.event {
+                ScheduleEvent::Call { call, vid } => {
+                    match call {
+                        ResolverCall::StartingVertices { edge_name } => {
+                            write!(f, "resolve_starting_vertices {edge_name}")?
+                        }
+                        ResolverCall::Property { type_name, property_name } => {
+                            write!(f, "resolve_property {type_name}.{property_name}")?
+                        }
+                        ResolverCall::Properties { type_name, property_names } => {
+                            write!(f, "resolve_properties {type_name}.{property_names:?}")?
+                        }
+                        ResolverCall::Neighbors { type_name, edge_name, eid } => write!(
+                            f,
+                            "resolve_neighbors {type_name}.{edge_name} eid={}",
+                            eid.get()
+                        )?,
+                        ResolverCall::Coercion { type_name, coerce_to_type } => {
+                            write!(f, "resolve_coercion {type_name} -> {coerce_to_type}")?
+                        }
+                    }
+                    writeln!(f, " vid={}", vid.get())?;
+                }
+                ScheduleEvent::Input => writeln!(f, "input")?,
+                ScheduleEvent::Output => writeln!(f, "output")?,
+                ScheduleEvent::Neighbor => writeln!(f, "neighbor")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the query to completion, recording the schedule of the adapter's resolver calls.
+///
+/// Meant for reproducing and bisecting misbehavior of the interpreter or an adapter:
+/// a deterministic adapter always produces the same results and the same schedule,
+/// and the [`ExecutionStrategy::BatchPerLevel`] strategy rules out
+/// the lazy interleaving of resolver calls as the cause of a problem.
+#[allow(clippy::type_complexity)]
+pub fn interpret_ir_debug<'query, AdapterT: Adapter<'query> + 'query>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+    strategy: ExecutionStrategy,
+) -> Result<(Vec<BTreeMap<Arc<str>, FieldValue>>, ExecutionSchedule), QueryArgumentsError> {
+    let steps: Rc<RefCell<Vec<ScheduleStep>>> = Default::default();
+    let adapter = Arc::new(SchedulingAdapter {
+        inner: adapter,
+        strategy,
+        steps: steps.clone(),
+        next_call: Cell::new(1),
+    });
+    let results = interpret_ir(adapter, indexed_query, arguments)?.collect();
+
+    // All resolver iterators were dropped together with the results iterator.
+    let steps = steps.take();
+    Ok((results, ExecutionSchedule { steps }))
+}
+
+struct SchedulingAdapter<AdapterT> {
+    inner: Arc<AdapterT>,
+    strategy: ExecutionStrategy,
+    steps: Rc<RefCell<Vec<ScheduleStep>>>,
+    next_call: Cell<usize>,
+}
+
+/// Records the steps of one resolver call.
+#[derive(Clone)]
+struct CallSchedule {
+    call: usize,
+    steps: Rc<RefCell<Vec<ScheduleStep>>>,
+}
+
+impl CallSchedule {
+    fn record(&self, event: ScheduleEvent) {
+        self.steps.borrow_mut().push(ScheduleStep { call: self.call, event });
+    }
+
+    fn record_each<'vertex, T: 'vertex>(
+        &self,
+        iterator: Box<dyn Iterator<Item = T> + 'vertex>,
+        event: ScheduleEvent,
+    ) -> Box<dyn Iterator<Item = T> + 'vertex> {
+        let schedule = self.clone();
+        Box::new(iterator.inspect(move |_| schedule.record(event.clone())))
+    }
+}
+
+impl<AdapterT> SchedulingAdapter<AdapterT> {
+    fn start_call(&self, call: ResolverCall, vid: Vid) -> CallSchedule {
+        let schedule = CallSchedule { call: self.next_call.get(), steps: self.steps.clone() };
+        self.next_call.set(schedule.call + 1);
+        schedule.record(ScheduleEvent::Call { call, vid });
+        schedule
+    }
+
+    /// Apply the strategy to an iterator of the call's outputs.
+    fn outputs<'vertex, T: 'vertex>(
+        &self,
+        iterator: Box<dyn Iterator<Item = T> + 'vertex>,
+    ) -> Box<dyn Iterator<Item = T> + 'vertex> {
+        match self.strategy {
+            ExecutionStrategy::Lazy => iterator,
+            ExecutionStrategy::BatchPerLevel => {
+                // Produce all outputs as soon as the first one is needed.
+                let mut pending = Some(iterator);
+                let mut batch = VecDeque::new();
+                Box::new(std::iter::from_fn(move || {
+                    if let Some(iterator) = pending.take() {
+                        batch.extend(iterator);
+                    }
+                    batch.pop_front()
+                }))
+            }
+        }
+    }
+}
+
+impl<'vertex, AdapterT: Adapter<'vertex> + 'vertex> Adapter<'vertex>
+    for SchedulingAdapter<AdapterT>
+{
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let schedule = self.start_call(
+            ResolverCall::StartingVertices { edge_name: edge_name.clone() },
+            resolve_info.vid(),
+        );
+        let vertices = self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info);
+        self.outputs(schedule.record_each(vertices, ScheduleEvent::Output))
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let schedule = self.start_call(
+            ResolverCall::Property {
+                type_name: type_name.clone(),
+                property_name: property_name.clone(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = schedule.record_each(contexts, ScheduleEvent::Input);
+        let outcomes =
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info);
+        self.outputs(schedule.record_each(outcomes, ScheduleEvent::Output))
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let schedule = self.start_call(
+            ResolverCall::Properties {
+                type_name: type_name.clone(),
+                property_names: properties.iter().map(|property| property.name.clone()).collect(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = schedule.record_each(contexts, ScheduleEvent::Input);
+        let outcomes = self.inner.resolve_properties(contexts, type_name, properties, resolve_info);
+        self.outputs(schedule.record_each(outcomes, ScheduleEvent::Output))
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let schedule = self.start_call(
+            ResolverCall::Neighbors {
+                type_name: type_name.clone(),
+                edge_name: edge_name.clone(),
+                eid: resolve_info.eid(),
+            },
+            resolve_info.origin_vid(),
+        );
+        let contexts = schedule.record_each(contexts, ScheduleEvent::Input);
+        let outcomes =
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info);
+
+        let neighbor_schedule = schedule.clone();
+        let strategy = self.strategy;
+        let outcomes = Box::new(outcomes.map(move |(ctx, neighbors)| {
+            let neighbors = neighbor_schedule.record_each(neighbors, ScheduleEvent::Neighbor);
+            let neighbors: VertexIterator<'vertex, Self::Vertex> = match strategy {
+                ExecutionStrategy::Lazy => neighbors,
+                ExecutionStrategy::BatchPerLevel => {
+                    Box::new(neighbors.collect::<Vec<_>>().into_iter())
+                }
+            };
+            (ctx, neighbors)
+        }));
+        self.outputs(schedule.record_each(outcomes, ScheduleEvent::Output))
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let schedule = self.start_call(
+            ResolverCall::Coercion {
+                type_name: type_name.clone(),
+                coerce_to_type: coerce_to_type.clone(),
+            },
+            resolve_info.vid(),
+        );
+        let contexts = schedule.record_each(contexts, ScheduleEvent::Input);
+        let outcomes =
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info);
+        self.outputs(schedule.record_each(outcomes, ScheduleEvent::Output))
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::{interpret_ir_debug, ExecutionStrategy, ScheduleEvent};
+    use crate::{interpreter::execution::interpret_ir, numbers_interpreter::NumbersAdapter};
+
+    #[test]
+    fn schedules_are_deterministic_and_strategies_agree() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        successor {
+            ... on Prime {
+                next: value @output
+            }
+        }
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+        let arguments = Arc::new(BTreeMap::new());
+        let expected: Vec<_> =
+            interpret_ir(adapter.clone(), indexed_query.clone(), arguments.clone())
+                .unwrap()
+                .collect();
+
+        let run = |strategy| {
+            interpret_ir_debug(adapter.clone(), indexed_query.clone(), arguments.clone(), strategy)
+                .unwrap()
+        };
+        let (lazy_results, lazy) = run(ExecutionStrategy::Lazy);
+        let (batch_results, batch) = run(ExecutionStrategy::BatchPerLevel);
+        assert_eq!(expected, lazy_results);
+        assert_eq!(expected, batch_results);
+
+        assert_eq!(lazy, run(ExecutionStrategy::Lazy).1);
+        assert_eq!(None, batch.first_divergence(&run(ExecutionStrategy::BatchPerLevel).1));
+        assert!(lazy.first_divergence(&batch).is_some());
+
+        // The same calls are made, in the same order, with either strategy.
+        let calls = |schedule: &super::ExecutionSchedule| -> Vec<_> {
+            schedule
+                .steps
+                .iter()
+                .filter(|step| matches!(step.event, ScheduleEvent::Call { .. }))
+                .cloned()
+                .collect()
+        };
+        assert_eq!(calls(&lazy), calls(&batch));
+        assert_eq!(
+            "#1 resolve_starting_vertices Number vid=1",
+            batch.to_string().lines().next().unwrap(),
+        );
+
+        // When batching, each call's outputs are all produced before those of any later call.
+        // Lazily, the calls' outputs are interleaved instead.
+        let output_calls = |schedule: &super::ExecutionSchedule| -> Vec<_> {
+            schedule
+                .steps
+                .iter()
+                .filter(|step| step.event == ScheduleEvent::Output)
+                .map(|step| step.call)
+                .collect()
+        };
+        let batch_outputs = output_calls(&batch);
+        assert!(batch_outputs.windows(2).all(|pair| pair[0] <= pair[1]), "{batch}");
+        let lazy_outputs = output_calls(&lazy);
+        assert!(lazy_outputs.windows(2).any(|pair| pair[0] > pair[1]), "{lazy}");
+    }
+}
@@ -0,0 +1,325 @@
+use std::{cell::Cell, rc::Rc, sync::Arc};
+
+use tracing::{field::Empty, Span};
+
+use crate::ir::{EdgeParameters, FieldValue};
+
+use super::{
+    Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, RequiredProperty, ResolveEdgeInfo,
+    ResolveInfo, VertexIdentifier, VertexInfo, VertexIterator,
+};
+
+/// An [`Adapter`] that describes each call to another adapter's resolvers as a `tracing` span.
+///
+/// Resolvers process their inputs lazily, so each span is entered whenever the iterators
+/// returned by its call are advanced. Once the call and its iterators are done, the span
+/// records the number of contexts the call processed, or the number of vertices
+/// it produced in the case of starting vertices.
+pub(super) struct TracingAdapter<AdapterT> {
+    inner: Arc<AdapterT>,
+}
+
+impl<AdapterT> TracingAdapter<AdapterT> {
+    pub(super) fn new(inner: Arc<AdapterT>) -> Self {
+        Self { inner }
+    }
+}
+
+/// The span of one resolver call, recording its number of contexts once it's dropped:
+/// that is, once the call and all the iterators it produced are done.
+struct CallSpan {
+    span: Span,
+    contexts: Cell<usize>,
+}
+
+impl CallSpan {
+    fn new(span: Span) -> Rc<Self> {
+        Rc::new(Self { span, contexts: Cell::new(0) })
+    }
+
+    fn count_context(&self) {
+        self.contexts.set(self.contexts.get() + 1);
+    }
+}
+
+impl Drop for CallSpan {
+    fn drop(&mut self) {
+        self.span.record("contexts", self.contexts.get());
+    }
+}
+
+fn count_contexts<'vertex, V: 'vertex>(
+    contexts: ContextIterator<'vertex, V>,
+    call: Rc<CallSpan>,
+) -> ContextIterator<'vertex, V> {
+    Box::new(contexts.inspect(move |_| call.count_context()))
+}
+
+/// Enter the call's span while producing each item of the iterator.
+pub(super) fn in_span<'vertex, T: 'vertex>(
+    mut iterator: Box<dyn Iterator<Item = T> + 'vertex>,
+    span: Span,
+) -> Box<dyn Iterator<Item = T> + 'vertex> {
+    Box::new(std::iter::from_fn(move || span.in_scope(|| iterator.next())))
+}
+
+fn in_call_span<'vertex, T: 'vertex>(
+    mut iterator: Box<dyn Iterator<Item = T> + 'vertex>,
+    call: Rc<CallSpan>,
+) -> Box<dyn Iterator<Item = T> + 'vertex> {
+    Box::new(std::iter::from_fn(move || call.span.in_scope(|| iterator.next())))
+}
+
+impl<'vertex, AdapterT: Adapter<'vertex> + 'vertex> Adapter<'vertex> for TracingAdapter<AdapterT> {
+    type Vertex = AdapterT::Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let call = CallSpan::new(tracing::debug_span!(
+            "resolve_starting_vertices",
+            vid = resolve_info.vid().get(),
+            edge_name = %edge_name,
+            contexts = Empty,
+        ));
+        let vertices = call
+            .span
+            .in_scope(|| self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info));
+
+        let counter = call.clone();
+        in_call_span(Box::new(vertices.inspect(move |_| counter.count_context())), call)
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        let call = CallSpan::new(tracing::debug_span!(
+            "resolve_property",
+            vid = resolve_info.vid().get(),
+            type_name = %type_name,
+            property_name = %property_name,
+            contexts = Empty,
+        ));
+        let contexts = count_contexts(contexts, call.clone());
+        let outcomes = call.span.in_scope(|| {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        });
+        in_call_span(outcomes, call)
+    }
+
+    fn resolve_properties<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        properties: &[RequiredProperty],
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, Vec<FieldValue>> {
+        let property_names: Vec<&str> =
+            properties.iter().map(|property| property.name.as_ref()).collect();
+        let call = CallSpan::new(tracing::debug_span!(
+            "resolve_properties",
+            vid = resolve_info.vid().get(),
+            type_name = %type_name,
+            property_names = ?property_names,
+            contexts = Empty,
+        ));
+        let contexts = count_contexts(contexts, call.clone());
+        let outcomes = call.span.in_scope(|| {
+            self.inner.resolve_properties(contexts, type_name, properties, resolve_info)
+        });
+        in_call_span(outcomes, call)
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let call = CallSpan::new(tracing::debug_span!(
+            "resolve_neighbors",
+            vid = resolve_info.origin_vid().get(),
+            eid = resolve_info.eid().get(),
+            type_name = %type_name,
+            edge_name = %edge_name,
+            contexts = Empty,
+        ));
+        let contexts = count_contexts(contexts, call.clone());
+        let outcomes = call.span.in_scope(|| {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        });
+
+        // Neighbors are also loaded lazily, so that work is part of the call's span as well.
+        let neighbors_call = call.clone();
+        let outcomes =
+            Box::new(outcomes.map(move |(ctx, neighbors)| {
+                (ctx, in_call_span(neighbors, neighbors_call.clone()))
+            }));
+        in_call_span(outcomes, call)
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let call = CallSpan::new(tracing::debug_span!(
+            "resolve_coercion",
+            vid = resolve_info.vid().get(),
+            type_name = %type_name,
+            coerce_to_type = %coerce_to_type,
+            contexts = Empty,
+        ));
+        let contexts = count_contexts(contexts, call.clone());
+        let outcomes = call.span.in_scope(|| {
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+        });
+        in_call_span(outcomes, call)
+    }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fmt::Debug,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    use crate::{interpreter::execution::interpret_ir, numbers_interpreter::NumbersAdapter};
+
+    #[derive(Debug, Default)]
+    struct RecordedSpan {
+        name: &'static str,
+        parent: Option<u64>,
+        fields: BTreeMap<&'static str, String>,
+    }
+
+    impl Visit for RecordedSpan {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.fields.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    /// Records all spans with their fields, and which span was entered when each was created.
+    #[derive(Default)]
+    struct RecordingSubscriber {
+        spans: Mutex<Vec<RecordedSpan>>,
+        entered: Mutex<Vec<u64>>,
+    }
+
+    impl Subscriber for RecordingSubscriber {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+            let mut span = RecordedSpan {
+                name: attributes.metadata().name(),
+                parent: self.entered.lock().unwrap().last().copied(),
+                fields: Default::default(),
+            };
+            attributes.record(&mut span);
+
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span);
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            values.record(&mut self.spans.lock().unwrap()[span.into_u64() as usize - 1]);
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            self.entered.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, _span: &Id) {
+            self.entered.lock().unwrap().pop();
+        }
+    }
+
+    #[test]
+    fn spans_for_query_and_each_resolver_call() {
+        let query = r#"
+{
+    Number(min: 1, max: 10) {
+        value @output
+
+        successor {
+            ... on Prime {
+                next: value @output
+            }
+        }
+    }
+}"#;
+        let adapter = Arc::new(NumbersAdapter::new());
+        let indexed_query = crate::frontend::parse(adapter.schema(), query).unwrap();
+
+        let subscriber = Arc::new(RecordingSubscriber::default());
+        let results: Vec<_> = tracing::subscriber::with_default(subscriber.clone(), || {
+            interpret_ir(adapter, indexed_query, Arc::new(BTreeMap::new())).unwrap().collect()
+        });
+
+        // All iterators, and therefore all spans, were dropped once the results were collected.
+        let spans = subscriber.spans.lock().unwrap();
+        let summary: Vec<_> = spans
+            .iter()
+            .map(|span| {
+                let fields: Vec<_> = ["vid", "eid", "contexts"]
+                    .into_iter()
+                    .filter_map(|name| Some(format!("{name}={}", span.fields.get(name)?)))
+                    .collect();
+                (span.name, span.parent, fields.join(" "))
+            })
+            .collect();
+
+        assert_eq!("interpret_ir", summary[0].0);
+        assert_eq!(None, summary[0].1);
+        assert_eq!(Some("Number"), spans[0].fields.get("root_edge").map(String::as_str));
+
+        // Resolver calls are made while setting up the query's execution, within its span.
+        let resolver_calls: Vec<_> = summary[1..]
+            .iter()
+            .map(|(name, parent, fields)| {
+                assert_eq!(Some(1), *parent, "{name} {fields}");
+                (*name, fields.as_str())
+            })
+            .collect();
+        // Outputs are resolved last, only for the contexts whose successor is prime.
+        assert_eq!(5, results.len());
+        let expected = vec![
+            ("resolve_starting_vertices", "vid=1 contexts=10"),
+            ("resolve_neighbors", "vid=1 eid=1 contexts=10"),
+            ("resolve_coercion", "vid=2 contexts=10"),
+            ("resolve_property", "vid=2 contexts=5"),
+            ("resolve_property", "vid=1 contexts=5"),
+        ];
+        assert_eq!(expected, resolver_calls, "{summary:?}");
+    }
+}
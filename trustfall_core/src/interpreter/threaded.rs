@@ -0,0 +1,363 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{mpsc, Arc},
+    thread,
+};
+
+use crate::ir::{FieldValue, IndexedQuery};
+
+use super::{
+    cancellation::CancellationToken, error::QueryArgumentsError,
+    execution::interpret_ir_with_cancellation, Adapter, InterpretedQuery,
+};
+
+/// The maximum number of results the execution thread computes
+/// ahead of the ones the results iterator has produced so far.
+const RESULTS_AHEAD: usize = 64;
+
+/// Execute a query on a separate thread, producing its results via an iterator that is [`Send`].
+///
+/// The iterator returned by [`interpret_ir()`] can't be sent across threads,
+/// since the iterators produced by [`Adapter`] resolvers aren't required to be [`Send`].
+/// Here, the query is executed on a thread of its own and only the results cross threads,
+/// so the results can be consumed from any thread, for example inside an async task.
+/// The adapter must be thread-safe, but its vertex type doesn't need to be.
+///
+/// The query's arguments are validated before any adapter methods are called,
+/// the same way as in [`interpret_ir()`].
+///
+/// Each call spawns a new thread, which runs until the query has produced all its results
+/// or the iterator is dropped. Execution runs at most a few dozen results ahead of the ones
+/// the iterator has produced so far. If execution panics, the panic is propagated to the thread
+/// consuming the results.
+///
+/// Getting the next result blocks the calling thread until that result is ready.
+/// In async code, consume the iterator from a context where blocking is allowed,
+/// such as `tokio::task::spawn_blocking()`, rather than directly inside an async task.
+///
+/// Dropping the iterator cancels the query: the execution thread stops loading vertices
+/// from the adapter and exits soon after, even if it hasn't found another result yet.
+/// Dropping the iterator doesn't wait for the thread to exit.
+///
+/// [`interpret_ir()`]: super::execution::interpret_ir
+pub fn interpret_ir_threaded<AdapterT>(
+    adapter: Arc<AdapterT>,
+    indexed_query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<ThreadedQueryResults, QueryArgumentsError>
+where
+    AdapterT: Adapter<'static> + Send + Sync + 'static,
+{
+    InterpretedQuery::from_query_and_arguments(indexed_query.clone(), arguments.clone())?;
+
+    let token = CancellationToken::new();
+    let (sender, receiver) = mpsc::sync_channel(RESULTS_AHEAD);
+    let execution = thread::spawn({
+        let token = token.clone();
+        move || {
+            let results = interpret_ir_with_cancellation(adapter, indexed_query, arguments, token)
+                .expect("query arguments were already validated");
+            // The query is only cancelled once the results iterator is dropped.
+            for row in results.map_while(Result::ok) {
+                if sender.send(row).is_err() {
+                    // The results iterator was dropped. Nobody is interested in more results.
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(ThreadedQueryResults { receiver, execution: Some(execution), token })
+}
+
+/// The results of a query executed on a separate thread, produced by [`interpret_ir_threaded()`].
+pub struct ThreadedQueryResults {
+    receiver: mpsc::Receiver<BTreeMap<Arc<str>, FieldValue>>,
+    execution: Option<thread::JoinHandle<()>>,
+    token: CancellationToken,
+}
+
+impl Drop for ThreadedQueryResults {
+    fn drop(&mut self) {
+        // Stop the execution thread even if it's busy looking for its next result,
+        // instead of only once it tries to send that result.
+        self.token.cancel();
+    }
+}
+
+impl Debug for ThreadedQueryResults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThreadedQueryResults")
+            .field("finished", &self.execution.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Iterator for ThreadedQueryResults {
+    type Item = BTreeMap<Arc<str>, FieldValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.receiver.recv() {
+            Ok(row) => Some(row),
+            Err(_) => {
+                // The sender has been dropped, so the execution thread is done
+                // or about to be done.
+                if let Some(execution) = self.execution.take() {
+                    if let Err(panic) = execution.join() {
+                        std::panic::resume_unwind(panic);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, OnceLock,
+        },
+        time::{Duration, Instant},
+    };
+
+    use maplit::btreemap;
+    use trustfall_filetests_macros::parameterize;
+
+    use super::interpret_ir_threaded;
+    use crate::{
+        interpreter::{
+            Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo,
+            ResolveInfo, VertexIterator,
+        },
+        ir::{EdgeParameters, FieldValue, IndexedQuery},
+        numbers_interpreter::{NumbersAdapter, NumbersVertex},
+        test_types::{TestIRQueryResult, TestInterpreterOutputData},
+    };
+
+    fn get_adapter() -> Arc<NumbersAdapter> {
+        static ADAPTER: OnceLock<Arc<NumbersAdapter>> = OnceLock::new();
+        ADAPTER.get_or_init(|| Arc::new(NumbersAdapter::new())).clone()
+    }
+
+    #[parameterize("trustfall_core/test_data/tests/valid_queries")]
+    fn parameterized_tester(base: &Path, stem: &str) {
+        let mut input_path = PathBuf::from(base);
+        input_path.push(format!("{stem}.ir.ron"));
+        let input_data = fs::read_to_string(input_path).unwrap();
+        let test_query: TestIRQueryResult = ron::from_str(&input_data).unwrap();
+        let test_query = test_query.unwrap();
+        if test_query.schema_name != "numbers" {
+            return;
+        }
+
+        let mut output_path = PathBuf::from(base);
+        output_path.push(format!("{stem}.output.ron"));
+        let output_data = fs::read_to_string(output_path).unwrap();
+        let expected_outputs: TestInterpreterOutputData = ron::from_str(&output_data).unwrap();
+
+        let arguments: BTreeMap<Arc<str>, FieldValue> =
+            test_query.arguments.into_iter().map(|(k, v)| (Arc::from(k), v)).collect();
+        let indexed_query: IndexedQuery = test_query.ir_query.try_into().unwrap();
+
+        let results = interpret_ir_threaded(get_adapter(), indexed_query.into(), arguments.into())
+            .expect("execution error");
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&results);
+
+        // Consume the results on yet another thread.
+        let results: Vec<_> = std::thread::spawn(move || results.collect()).join().unwrap();
+        assert_eq!(expected_outputs.results, results);
+    }
+
+    #[test]
+    fn invalid_arguments_are_reported_before_execution() {
+        let test_query: TestIRQueryResult = ron::from_str(
+            &fs::read_to_string("test_data/tests/valid_queries/filter_op_greater_than.ir.ron")
+                .unwrap(),
+        )
+        .unwrap();
+        let indexed_query: IndexedQuery = test_query.unwrap().ir_query.try_into().unwrap();
+
+        let outcome =
+            interpret_ir_threaded(get_adapter(), indexed_query.into(), Arc::new(BTreeMap::new()));
+        assert!(outcome.is_err(), "{outcome:?}");
+    }
+
+    #[test]
+    fn dropping_the_results_stops_execution() {
+        let query = r#"
+{
+    Number(min: 0, max: 1000) {
+        value @output
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().schema(), query).unwrap();
+
+        let mut results =
+            interpret_ir_threaded(get_adapter(), indexed_query, Arc::new(BTreeMap::new())).unwrap();
+        assert_eq!(Some(&FieldValue::Int64(0)), results.next().as_ref().map(|row| &row["value"]));
+
+        // The execution thread notices the results are no longer needed and winds down,
+        // instead of computing all the remaining results.
+        drop(results);
+    }
+
+    /// Produces the starting vertices of the underlying [`NumbersAdapter`] over and over,
+    /// counting how many it has produced.
+    struct EndlessAdapter {
+        inner: NumbersAdapter,
+        produced: Arc<AtomicUsize>,
+    }
+
+    impl Adapter<'static> for EndlessAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'static, Self::Vertex> {
+            let vertices: Vec<_> =
+                self.inner.resolve_starting_vertices(edge_name, parameters, resolve_info).collect();
+            let produced = self.produced.clone();
+            Box::new(vertices.into_iter().cycle().inspect(move |_| {
+                produced.fetch_add(1, Ordering::Relaxed);
+            }))
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            contexts: ContextIterator<'static, V>,
+            type_name: &Arc<str>,
+            property_name: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'static, V, FieldValue> {
+            self.inner.resolve_property(contexts, type_name, property_name, resolve_info)
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            contexts: ContextIterator<'static, V>,
+            type_name: &Arc<str>,
+            edge_name: &Arc<str>,
+            parameters: &EdgeParameters,
+            resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'static, V, VertexIterator<'static, Self::Vertex>> {
+            self.inner.resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            contexts: ContextIterator<'static, V>,
+            type_name: &Arc<str>,
+            coerce_to_type: &Arc<str>,
+            resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'static, V, bool> {
+            self.inner.resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+        }
+    }
+
+    #[test]
+    fn dropping_the_results_stops_execution_between_results() {
+        // None of the endless starting vertices pass the filter, so the execution thread
+        // never has another result to send.
+        let query = r#"
+{
+    Number(min: 1, max: 3) {
+        value @output
+        name @filter(op: "=", value: ["$name"])
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().schema(), query).unwrap();
+        let arguments = Arc::new(btreemap! { "name".into() => "four".into() });
+
+        let produced = Arc::new(AtomicUsize::new(0));
+        let adapter =
+            Arc::new(EndlessAdapter { inner: NumbersAdapter::new(), produced: produced.clone() });
+        let results = interpret_ir_threaded(adapter, indexed_query, arguments).unwrap();
+        drop(results);
+
+        // Once the execution thread stops, no more vertices are produced.
+        let deadline = Instant::now() + Duration::from_secs(30);
+        loop {
+            let before = produced.load(Ordering::Relaxed);
+            std::thread::sleep(Duration::from_millis(100));
+            if produced.load(Ordering::Relaxed) == before {
+                break;
+            }
+            assert!(Instant::now() < deadline, "the execution thread is still running");
+        }
+    }
+
+    /// Panics when resolving the starting vertices.
+    struct PanickingAdapter;
+
+    impl Adapter<'static> for PanickingAdapter {
+        type Vertex = NumbersVertex;
+
+        fn resolve_starting_vertices(
+            &self,
+            _edge_name: &Arc<str>,
+            _parameters: &EdgeParameters,
+            _resolve_info: &ResolveInfo,
+        ) -> VertexIterator<'static, Self::Vertex> {
+            panic!("resolver panicked")
+        }
+
+        fn resolve_property<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            _contexts: ContextIterator<'static, V>,
+            _type_name: &Arc<str>,
+            _property_name: &Arc<str>,
+            _resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'static, V, FieldValue> {
+            unreachable!()
+        }
+
+        fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            _contexts: ContextIterator<'static, V>,
+            _type_name: &Arc<str>,
+            _edge_name: &Arc<str>,
+            _parameters: &EdgeParameters,
+            _resolve_info: &ResolveEdgeInfo,
+        ) -> ContextOutcomeIterator<'static, V, VertexIterator<'static, Self::Vertex>> {
+            unreachable!()
+        }
+
+        fn resolve_coercion<V: AsVertex<Self::Vertex> + 'static>(
+            &self,
+            _contexts: ContextIterator<'static, V>,
+            _type_name: &Arc<str>,
+            _coerce_to_type: &Arc<str>,
+            _resolve_info: &ResolveInfo,
+        ) -> ContextOutcomeIterator<'static, V, bool> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "resolver panicked")]
+    fn execution_panics_are_propagated() {
+        let query = r#"
+{
+    Number(max: 3) {
+        value @output
+    }
+}"#;
+        let indexed_query = crate::frontend::parse(get_adapter().schema(), query).unwrap();
+
+        let results =
+            interpret_ir_threaded(Arc::new(PanickingAdapter), indexed_query, Default::default())
+                .unwrap();
+        let _ = results.count();
+    }
+}
@@ -1,6 +1,9 @@
 use std::{
-    cell::RefCell, collections::BTreeMap, fmt::Debug, marker::PhantomData, num::NonZeroUsize,
-    rc::Rc, sync::Arc,
+    collections::BTreeMap,
+    fmt::Debug,
+    marker::PhantomData,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
 };
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -12,13 +15,18 @@ use crate::{
 };
 
 use super::{
-    AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo, VertexInfo,
-    VertexIterator,
+    AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
+    VertexIdentifier, VertexInfo, VertexIterator,
 };
 
+/// Identifies an operation within a [`Trace`], in the order operations were recorded.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Opid(pub NonZeroUsize); // operation ID
 
+/// A record of all adapter operations performed while executing a query, and of its results.
+///
+/// Traces are recorded with an [`AdapterTap`] and can be serialized, then later replayed
+/// with [`replay_trace()`](super::replay::replay_trace) to re-run the query without the adapter.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "Vertex: Debug + Clone + Serialize + DeserializeOwned")]
 pub struct Trace<Vertex> {
@@ -34,11 +42,12 @@ impl<Vertex> Trace<Vertex>
 where
     Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned,
 {
-    #[allow(dead_code)]
+    /// Make an empty trace for executing the given query with the given arguments.
     pub fn new(ir_query: IRQuery, arguments: BTreeMap<String, FieldValue>) -> Self {
         Self { ops: Default::default(), ir_query, arguments }
     }
 
+    /// Append an operation to the trace, returning its ID.
     pub fn record(&mut self, content: TraceOpContent<Vertex>, parent: Option<Opid>) -> Opid {
         let next_opid = Opid(NonZeroUsize::new(self.ops.len() + 1).unwrap());
 
@@ -48,6 +57,7 @@ where
     }
 }
 
+/// A single operation recorded in a [`Trace`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "Vertex: Debug + Clone + Serialize + DeserializeOwned")]
 pub struct TraceOp<Vertex> {
@@ -57,6 +67,8 @@ pub struct TraceOp<Vertex> {
     pub content: TraceOpContent<Vertex>,
 }
 
+/// What happened in a [`TraceOp`]: an adapter call, a step of one of the iterators
+/// involved in that call, or a query result being produced.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "Vertex: Debug + Clone + Serialize + DeserializeOwned")]
 pub enum TraceOpContent<Vertex> {
@@ -73,6 +85,7 @@ pub enum TraceOpContent<Vertex> {
     ProduceQueryResult(BTreeMap<Arc<str>, FieldValue>),
 }
 
+/// An adapter method call recorded in a [`Trace`].
 #[allow(clippy::enum_variant_names)] // the variant names match the functions they represent
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FunctionCall {
@@ -82,6 +95,7 @@ pub enum FunctionCall {
     ResolveCoercion(Vid, Arc<str>, Arc<str>), // vertex ID + current type + coerced-to type
 }
 
+/// A value produced by one of the iterators returned by an adapter method.
 #[allow(clippy::enum_variant_names)] // the variant names match the functions they represent
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "Vertex: Debug + Clone + Serialize + DeserializeOwned")]
@@ -163,7 +177,7 @@ where
     AdapterT: Adapter<'vertex>,
     AdapterT::Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned + 'vertex,
 {
-    tracer: Rc<RefCell<Trace<AdapterT::Vertex>>>,
+    tracer: Arc<Mutex<Trace<AdapterT::Vertex>>>,
     inner: AdapterT,
     _phantom: PhantomData<&'vertex ()>,
 }
@@ -173,19 +187,23 @@ where
     AdapterT: Adapter<'vertex>,
     AdapterT::Vertex: Clone + Debug + PartialEq + Eq + Serialize + DeserializeOwned + 'vertex,
 {
-    pub fn new(adapter: AdapterT, tracer: Rc<RefCell<Trace<AdapterT::Vertex>>>) -> Self {
+    /// Wrap an adapter, recording its operations into the given trace.
+    pub fn new(adapter: AdapterT, tracer: Arc<Mutex<Trace<AdapterT::Vertex>>>) -> Self {
         Self { tracer, inner: adapter, _phantom: PhantomData }
     }
 
+    /// Stop recording, returning the recorded trace.
     pub fn finish(self) -> Trace<AdapterT::Vertex> {
         // Ensure nothing is reading the trace i.e. we can safely stop interpreting.
-        let trace_ref = self.tracer.borrow_mut();
+        let mut trace_ref = self.tracer.lock().expect("trace lock was poisoned");
         let new_trace = Trace::new(trace_ref.ir_query.clone(), trace_ref.arguments.clone());
-        drop(trace_ref);
-        self.tracer.replace(new_trace)
+        std::mem::replace(&mut *trace_ref, new_trace)
     }
 }
 
+/// Record the query results produced by executing a query over the [`AdapterTap`].
+///
+/// Results must be recorded for the trace to be replayable.
 pub fn tap_results<'vertex, AdapterT>(
     adapter_tap: Arc<AdapterTap<'vertex, AdapterT>>,
     result_iter: impl Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'vertex,
@@ -197,7 +215,8 @@ where
     result_iter.inspect(move |result| {
         adapter_tap
             .tracer
-            .borrow_mut()
+            .lock()
+            .expect("trace lock was poisoned")
             .record(TraceOpContent::ProduceQueryResult(result.clone()), None);
     })
 }
@@ -215,7 +234,7 @@ where
         parameters: &EdgeParameters,
         resolve_info: &ResolveInfo,
     ) -> VertexIterator<'vertex, Self::Vertex> {
-        let mut trace = self.tracer.borrow_mut();
+        let mut trace = self.tracer.lock().expect("trace lock was poisoned");
         let call_opid = trace.record(
             TraceOpContent::Call(FunctionCall::ResolveStartingVertices(resolve_info.vid())),
             None,
@@ -228,11 +247,12 @@ where
         Box::new(
             make_iter_with_end_action(inner_iter, move || {
                 tracer_ref_1
-                    .borrow_mut()
+                    .lock()
+                    .expect("trace lock was poisoned")
                     .record(TraceOpContent::OutputIteratorExhausted, Some(call_opid));
             })
             .inspect(move |vertex| {
-                tracer_ref_2.borrow_mut().record(
+                tracer_ref_2.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldFrom(YieldValue::ResolveStartingVertices(vertex.clone())),
                     Some(call_opid),
                 );
@@ -247,7 +267,7 @@ where
         property_name: &Arc<str>,
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
-        let mut trace = self.tracer.borrow_mut();
+        let mut trace = self.tracer.lock().expect("trace lock was poisoned");
         let call_opid = trace.record(
             TraceOpContent::Call(FunctionCall::ResolveProperty(
                 resolve_info.vid(),
@@ -265,17 +285,19 @@ where
             make_iter_with_end_action(
                 make_iter_with_pre_action(contexts, move || {
                     tracer_ref_1
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::AdvanceInputIterator, Some(call_opid));
                 }),
                 move || {
                     tracer_ref_2
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::InputIteratorExhausted, Some(call_opid));
                 },
             )
             .inspect(move |context| {
-                tracer_ref_3.borrow_mut().record(
+                tracer_ref_3.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldInto(context.clone().flat_map(&mut |v| v.into_vertex())),
                     Some(call_opid),
                 );
@@ -289,11 +311,12 @@ where
         Box::new(
             make_iter_with_end_action(inner_iter, move || {
                 tracer_ref_4
-                    .borrow_mut()
+                    .lock()
+                    .expect("trace lock was poisoned")
                     .record(TraceOpContent::OutputIteratorExhausted, Some(call_opid));
             })
             .map(move |(context, value)| {
-                tracer_ref_5.borrow_mut().record(
+                tracer_ref_5.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldFrom(YieldValue::ResolveProperty(
                         context.clone().flat_map(&mut |v| v.into_vertex()),
                         value.clone(),
@@ -314,7 +337,7 @@ where
         parameters: &EdgeParameters,
         resolve_info: &ResolveEdgeInfo,
     ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
-        let mut trace = self.tracer.borrow_mut();
+        let mut trace = self.tracer.lock().expect("trace lock was poisoned");
         let call_opid = trace.record(
             TraceOpContent::Call(FunctionCall::ResolveNeighbors(
                 resolve_info.origin_vid(),
@@ -332,17 +355,19 @@ where
             make_iter_with_end_action(
                 make_iter_with_pre_action(contexts, move || {
                     tracer_ref_1
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::AdvanceInputIterator, Some(call_opid));
                 }),
                 move || {
                     tracer_ref_2
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::InputIteratorExhausted, Some(call_opid));
                 },
             )
             .inspect(move |context| {
-                tracer_ref_3.borrow_mut().record(
+                tracer_ref_3.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldInto(context.clone().flat_map(&mut |v| v.into_vertex())),
                     Some(call_opid),
                 );
@@ -361,11 +386,12 @@ where
         Box::new(
             make_iter_with_end_action(inner_iter, move || {
                 tracer_ref_4
-                    .borrow_mut()
+                    .lock()
+                    .expect("trace lock was poisoned")
                     .record(TraceOpContent::OutputIteratorExhausted, Some(call_opid));
             })
             .map(move |(context, neighbor_iter)| {
-                let mut trace = tracer_ref_5.borrow_mut();
+                let mut trace = tracer_ref_5.lock().expect("trace lock was poisoned");
                 let outer_iterator_opid = trace.record(
                     TraceOpContent::YieldFrom(YieldValue::ResolveNeighborsOuter(
                         context.clone().flat_map(&mut |v| v.into_vertex()),
@@ -376,7 +402,7 @@ where
 
                 let tracer_ref_6 = tracer_ref_5.clone();
                 let tapped_neighbor_iter = neighbor_iter.enumerate().map(move |(pos, vertex)| {
-                    tracer_ref_6.borrow_mut().record(
+                    tracer_ref_6.lock().expect("trace lock was poisoned").record(
                         TraceOpContent::YieldFrom(YieldValue::ResolveNeighborsInner(
                             pos,
                             vertex.clone(),
@@ -390,7 +416,7 @@ where
                 let tracer_ref_7 = tracer_ref_5.clone();
                 let final_neighbor_iter: VertexIterator<'vertex, Self::Vertex> =
                     Box::new(make_iter_with_end_action(tapped_neighbor_iter, move || {
-                        tracer_ref_7.borrow_mut().record(
+                        tracer_ref_7.lock().expect("trace lock was poisoned").record(
                             TraceOpContent::OutputIteratorExhausted,
                             Some(outer_iterator_opid),
                         );
@@ -408,7 +434,7 @@ where
         coerce_to_type: &Arc<str>,
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'vertex, V, bool> {
-        let mut trace = self.tracer.borrow_mut();
+        let mut trace = self.tracer.lock().expect("trace lock was poisoned");
         let call_opid = trace.record(
             TraceOpContent::Call(FunctionCall::ResolveCoercion(
                 resolve_info.vid(),
@@ -426,17 +452,19 @@ where
             make_iter_with_end_action(
                 make_iter_with_pre_action(contexts, move || {
                     tracer_ref_1
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::AdvanceInputIterator, Some(call_opid));
                 }),
                 move || {
                     tracer_ref_2
-                        .borrow_mut()
+                        .lock()
+                        .expect("trace lock was poisoned")
                         .record(TraceOpContent::InputIteratorExhausted, Some(call_opid));
                 },
             )
             .inspect(move |context| {
-                tracer_ref_3.borrow_mut().record(
+                tracer_ref_3.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldInto(context.clone().flat_map(&mut |v| v.into_vertex())),
                     Some(call_opid),
                 );
@@ -450,11 +478,12 @@ where
         Box::new(
             make_iter_with_end_action(inner_iter, move || {
                 tracer_ref_4
-                    .borrow_mut()
+                    .lock()
+                    .expect("trace lock was poisoned")
                     .record(TraceOpContent::OutputIteratorExhausted, Some(call_opid));
             })
             .map(move |(context, can_coerce)| {
-                tracer_ref_5.borrow_mut().record(
+                tracer_ref_5.lock().expect("trace lock was poisoned").record(
                     TraceOpContent::YieldFrom(YieldValue::ResolveCoercion(
                         context.clone().flat_map(&mut |v| v.into_vertex()),
                         can_coerce,
@@ -466,4 +495,8 @@ where
             }),
         )
     }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'vertex, Self::Vertex>> {
+        self.inner.vertex_identifier()
+    }
 }
@@ -0,0 +1,511 @@
+//! Constructing queries programmatically, without writing query text.
+//!
+//! A [`FieldBuilder`] corresponds to one field in the text of a query: it can set edge parameters,
+//! coerce the field's type, select further fields within it, and apply directives.
+//! Directives apply in the order they are added, just like in query text, so outputs, tags,
+//! and filters added after a transform apply to the transformed value.
+//!
+//! Building a query applies the same validation as parsing the equivalent query text.
+use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
+
+use async_graphql_parser::Pos;
+
+use crate::{
+    frontend::{error::FrontendError, make_ir_for_query},
+    graphql_query::{
+        directives::{
+            check_transform_argument_count, AbsentDirective, DedupeDirective, FilterDirective,
+            FoldDirective, LimitDirective, OffsetDirective, OptionalDirective, OrderDirective,
+            OutputDirective, RecurseDirective, TagDirective, TransformDirective,
+        },
+        error::ParseError,
+        query::{
+            make_edge_connection, make_vertex_node, FieldConnection, FieldNode, ParsedDirective,
+            Query, VariableDefinition,
+        },
+    },
+    schema::Schema,
+};
+
+use super::{
+    FieldValue, IRQuery, IndexedQuery, Operation, OrderDirection, TransformationKind, Type,
+};
+
+pub use crate::graphql_query::directives::OperatorArgument;
+
+/// A query under construction, starting from the given root edge.
+///
+/// # Example
+/// ```
+/// # use trustfall_core::{
+/// #     frontend::parse_to_ir,
+/// #     ir::{builder::{FieldBuilder, OperatorArgument, QueryBuilder}, Operation},
+/// #     schema::Schema,
+/// # };
+/// # let schema = Schema::parse(r#"
+/// #     schema { query: RootSchemaQuery }
+/// #     type RootSchemaQuery { Number(max: Int!): [Number!]! }
+/// #     type Number { value: Int!, name: String }
+/// # "#).unwrap();
+/// let query = QueryBuilder::new(
+///     FieldBuilder::new("Number").parameter("max", 10).field(
+///         FieldBuilder::new("value")
+///             .filter(Operation::GreaterThan((), OperatorArgument::VariableRef("min".into())))
+///             .output(),
+///     ),
+/// )
+/// .build_ir(&schema)
+/// .expect("invalid query");
+///
+/// let parsed = parse_to_ir(&schema, r#"
+/// {
+///     Number(max: 10) {
+///         value @filter(op: ">", value: ["$min"]) @output
+///     }
+/// }"#).unwrap();
+/// assert_eq!(parsed, query);
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    root: FieldBuilder,
+    variable_defaults: Vec<(Arc<str>, Type, FieldValue)>,
+    limit: Option<NonZeroUsize>,
+    offset: Option<usize>,
+    dedupe: bool,
+}
+
+impl QueryBuilder {
+    /// Start a query at the given root edge.
+    ///
+    /// The root field may not have any directives, since none of them apply to root edges.
+    pub fn new(root: FieldBuilder) -> Self {
+        Self { root, variable_defaults: vec![], limit: None, offset: None, dedupe: false }
+    }
+
+    /// Give the named variable a default value, like `query ($name: Type = value)` does.
+    pub fn variable_default(
+        mut self,
+        name: impl Into<Arc<str>>,
+        declared_type: Type,
+        default_value: impl Into<FieldValue>,
+    ) -> Self {
+        self.variable_defaults.push((name.into(), declared_type, default_value.into()));
+        self
+    }
+
+    /// Produce at most this many results, like the `@limit` directive.
+    pub fn limit(mut self, count: NonZeroUsize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    /// Skip this many results, like the `@offset` directive.
+    pub fn offset(mut self, count: usize) -> Self {
+        self.offset = Some(count);
+        self
+    }
+
+    /// Omit duplicate results, like the `@dedupe` directive.
+    pub fn dedupe(mut self) -> Self {
+        self.dedupe = true;
+        self
+    }
+
+    /// Validate the query against the schema and convert it to IR,
+    /// as [`parse_to_ir`](crate::frontend::parse_to_ir) does for query text.
+    pub fn build_ir(&self, schema: &Schema) -> Result<IRQuery, FrontendError> {
+        make_ir_for_query(schema, &self.make_query()?)
+    }
+
+    /// Validate the query against the schema and convert it to indexed IR,
+    /// as [`parse`](crate::frontend::parse) does for query text.
+    pub fn build(&self, schema: &Schema) -> Result<Arc<IndexedQuery>, FrontendError> {
+        let ir_query = self.build_ir(schema)?;
+
+        // Freshly-generated IRQuery objects are always valid to convert to IndexedQuery.
+        let indexed_query: IndexedQuery = ir_query.try_into().unwrap();
+        Ok(Arc::new(indexed_query))
+    }
+
+    fn make_query(&self) -> Result<Query, ParseError> {
+        if let Some(directive) = self.root.directives.first() {
+            return Err(ParseError::DirectiveNotInsideQueryRoot(
+                directive.kind().trim_start_matches('@').to_string(),
+                directive.pos(),
+            ));
+        }
+
+        let mut variable_definitions: Vec<VariableDefinition> = vec![];
+        for (name, declared_type, default_value) in &self.variable_defaults {
+            if variable_definitions.iter().any(|defn| &defn.name == name) {
+                return Err(ParseError::DuplicatedVariableDefinition(
+                    name.to_string(),
+                    Pos::default(),
+                ));
+            }
+            variable_definitions.push(VariableDefinition {
+                position: Pos::default(),
+                name: name.clone(),
+                declared_type: declared_type.clone(),
+                default_value: default_value.clone(),
+            });
+        }
+
+        Ok(Query {
+            variable_definitions,
+            root_connection: self.root.make_connection()?,
+            root_field: self.root.make_node()?,
+            limit: self.limit.map(|count| LimitDirective { count }),
+            offset: self.offset.map(|count| OffsetDirective { count }),
+            dedupe: self.dedupe.then_some(DedupeDirective {}),
+        })
+    }
+}
+
+/// A field of a query under construction: either a property or an edge,
+/// depending on the schema and on whether any fields are selected within it.
+#[derive(Debug, Clone)]
+pub struct FieldBuilder {
+    name: Arc<str>,
+    alias: Option<Arc<str>>,
+    parameters: BTreeMap<Arc<str>, FieldValue>,
+    variable_parameters: BTreeMap<Arc<str>, Arc<str>>,
+    tag_parameters: BTreeMap<Arc<str>, Arc<str>>,
+    coerced_to: Option<Arc<str>>,
+    directives: Vec<ParsedDirective>,
+    fields: Vec<FieldBuilder>,
+}
+
+impl FieldBuilder {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Self {
+            name: name.into(),
+            alias: None,
+            parameters: Default::default(),
+            variable_parameters: Default::default(),
+            tag_parameters: Default::default(),
+            coerced_to: None,
+            directives: vec![],
+            fields: vec![],
+        }
+    }
+
+    /// Refer to the field by a different name, like `alias: field` in query text.
+    pub fn alias(mut self, alias: impl Into<Arc<str>>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Set an edge parameter, replacing any value previously set for it.
+    pub fn parameter(mut self, name: impl Into<Arc<str>>, value: impl Into<FieldValue>) -> Self {
+        let name = name.into();
+        self.variable_parameters.remove(&name);
+        self.tag_parameters.remove(&name);
+        self.parameters.insert(name, value.into());
+        self
+    }
+
+    /// Set an edge parameter to the value of a query variable, like `param: $variable`
+    /// in query text, replacing any value previously set for it.
+    pub fn variable_parameter(
+        mut self,
+        name: impl Into<Arc<str>>,
+        variable: impl Into<Arc<str>>,
+    ) -> Self {
+        let name = name.into();
+        self.parameters.remove(&name);
+        self.tag_parameters.remove(&name);
+        self.variable_parameters.insert(name, variable.into());
+        self
+    }
+
+    /// Set an edge parameter to a tagged value, like `param: "%tag"` in query text,
+    /// replacing any value previously set for it.
+    pub fn tag_parameter(mut self, name: impl Into<Arc<str>>, tag: impl Into<Arc<str>>) -> Self {
+        let name = name.into();
+        self.parameters.remove(&name);
+        self.variable_parameters.remove(&name);
+        self.tag_parameters.insert(name, tag.into());
+        self
+    }
+
+    /// Only continue with vertices of the given type, like `... on Type` in query text.
+    /// The selected fields are then fields of that type.
+    pub fn coerce_to(mut self, type_name: impl Into<Arc<str>>) -> Self {
+        self.coerced_to = Some(type_name.into());
+        self
+    }
+
+    /// Select a field of the vertex this edge points to.
+    pub fn field(mut self, field: FieldBuilder) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Apply an `@output` directive, using the field's default output name.
+    pub fn output(self) -> Self {
+        self.directive(ParsedDirective::Output(OutputDirective { name: None }, Pos::default()))
+    }
+
+    /// Apply an `@output` directive with the given output name.
+    pub fn output_as(self, name: impl Into<Arc<str>>) -> Self {
+        let output = OutputDirective { name: Some(name.into()) };
+        self.directive(ParsedDirective::Output(output, Pos::default()))
+    }
+
+    /// Apply a `@tag` directive, using the field's default tag name.
+    pub fn tag(self) -> Self {
+        self.directive(ParsedDirective::Tag(TagDirective { name: None }, Pos::default()))
+    }
+
+    /// Apply a `@tag` directive with the given tag name.
+    pub fn tag_as(self, name: impl Into<Arc<str>>) -> Self {
+        let tag = TagDirective { name: Some(name.into()) };
+        self.directive(ParsedDirective::Tag(tag, Pos::default()))
+    }
+
+    /// Apply a `@filter` directive with the given operation.
+    pub fn filter(self, operation: Operation<(), OperatorArgument>) -> Self {
+        self.directive(ParsedDirective::Filter(FilterDirective { operation }, Pos::default()))
+    }
+
+    /// Apply a `@transform` directive. Arithmetic transforms require an operand,
+    /// and other transforms must not have one.
+    pub fn transform(self, kind: TransformationKind, operand: Option<OperatorArgument>) -> Self {
+        let transform = TransformDirective { kind, argument: operand };
+        self.directive(ParsedDirective::Transform(transform, Pos::default()))
+    }
+
+    /// Apply an `@order` directive.
+    pub fn order(self, direction: OrderDirection) -> Self {
+        self.directive(ParsedDirective::Order(OrderDirective { direction }, Pos::default()))
+    }
+
+    /// Apply an `@optional` directive.
+    pub fn optional(self) -> Self {
+        self.directive(ParsedDirective::Optional(OptionalDirective { group: None }, Pos::default()))
+    }
+
+    /// Apply an `@optional` directive that belongs to the given group.
+    pub fn optional_in_group(self, group: impl Into<Arc<str>>) -> Self {
+        let optional = OptionalDirective { group: Some(group.into()) };
+        self.directive(ParsedDirective::Optional(optional, Pos::default()))
+    }
+
+    /// Apply a `@recurse` directive, recursing without a limit if `depth` is `None`.
+    pub fn recurse(self, depth: Option<NonZeroUsize>, dedupe: bool) -> Self {
+        let recurse = RecurseDirective { depth, dedupe };
+        self.directive(ParsedDirective::Recurse(recurse, Pos::default()))
+    }
+
+    /// Apply a `@fold` directive.
+    pub fn fold(self) -> Self {
+        self.directive(ParsedDirective::Fold(FoldDirective {}, Pos::default()))
+    }
+
+    /// Apply an `@absent` directive.
+    pub fn absent(self) -> Self {
+        self.directive(ParsedDirective::Absent(AbsentDirective {}, Pos::default()))
+    }
+
+    fn directive(mut self, directive: ParsedDirective) -> Self {
+        self.directives.push(directive);
+        self
+    }
+
+    fn checked_directives(&self) -> Result<Vec<ParsedDirective>, ParseError> {
+        for directive in &self.directives {
+            if let ParsedDirective::Transform(transform, pos) = directive {
+                let arg_count = usize::from(transform.argument.is_some());
+                check_transform_argument_count(&transform.kind, arg_count, *pos)?;
+            }
+        }
+        Ok(self.directives.clone())
+    }
+
+    fn make_connection(&self) -> Result<FieldConnection, ParseError> {
+        make_edge_connection(
+            Pos::default(),
+            self.name.clone(),
+            self.alias.clone(),
+            self.parameters.clone(),
+            self.variable_parameters.clone(),
+            self.tag_parameters.clone(),
+            self.checked_directives()?,
+            !self.fields.is_empty() || self.coerced_to.is_some(),
+        )
+    }
+
+    fn make_node(&self) -> Result<FieldNode, ParseError> {
+        let mut vertex = make_vertex_node(
+            Pos::default(),
+            self.name.clone(),
+            self.alias.clone(),
+            self.coerced_to.clone(),
+            self.checked_directives()?,
+        )?;
+        for field in &self.fields {
+            vertex.connections.push((field.make_connection()?, field.make_node()?));
+        }
+        Ok(vertex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{
+        frontend::{error::FrontendError, parse_to_ir},
+        graphql_query::error::ParseError,
+        ir::{Operation, OrderDirection, TransformationKind, Type},
+        schema::Schema,
+    };
+
+    use super::{FieldBuilder, OperatorArgument, QueryBuilder};
+
+    fn numbers_schema() -> Schema {
+        Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema")
+    }
+
+    #[test]
+    fn built_queries_match_parsed_queries() {
+        let schema = numbers_schema();
+        let variable = |name: &str| OperatorArgument::VariableRef(name.into());
+        let tag = |name: &str| OperatorArgument::TagRef(name.into());
+
+        let built = QueryBuilder::new(
+            FieldBuilder::new("Number")
+                .parameter("max", 10)
+                .field(
+                    FieldBuilder::new("value")
+                        .tag_as("start")
+                        .output()
+                        .order(OrderDirection::Descending),
+                )
+                .field(
+                    FieldBuilder::new("successor").recurse(NonZeroUsize::new(2), false).field(
+                        FieldBuilder::new("value")
+                            .transform(TransformationKind::Multiply, Some(tag("start")))
+                            .output_as("scaled"),
+                    ),
+                )
+                .field(
+                    FieldBuilder::new("predecessor").optional().coerce_to("Prime").field(
+                        FieldBuilder::new("name")
+                            .filter(Operation::HasPrefix((), variable("prefix")))
+                            .output_as("prime"),
+                    ),
+                )
+                .field(
+                    FieldBuilder::new("multiple")
+                        .alias("tagged")
+                        .tag_parameter("max", "start")
+                        .field(FieldBuilder::new("value").output_as("tagged_multiple")),
+                )
+                .field(
+                    FieldBuilder::new("multiple")
+                        .variable_parameter("max", "bound")
+                        .fold()
+                        .transform(TransformationKind::Count, None)
+                        .filter(Operation::GreaterThan((), variable("count")))
+                        .output()
+                        .field(FieldBuilder::new("value").output_as("multiple")),
+                )
+                .field(FieldBuilder::new("predecessor").alias("prior").absent()),
+        )
+        .variable_default("count", Type::parse("Int!").unwrap(), 1)
+        .limit(NonZeroUsize::new(5).unwrap())
+        .offset(1)
+        .dedupe()
+        .build_ir(&schema)
+        .expect("invalid query");
+
+        let parsed = parse_to_ir(
+            &schema,
+            r#"
+query ($count: Int! = 1) {
+    Number(max: 10) @limit(count: 5) @offset(count: 1) @dedupe {
+        value @tag(name: "start") @output @order(direction: "desc")
+        successor @recurse(depth: 2) {
+            value @transform(op: "*", value: ["%start"]) @output(name: "scaled")
+        }
+        predecessor @optional {
+            ... on Prime {
+                name @filter(op: "has_prefix", value: ["$prefix"]) @output(name: "prime")
+            }
+        }
+        tagged: multiple(max: "%start") {
+            value @output(name: "tagged_multiple")
+        }
+        multiple(max: $bound) @fold @transform(op: "count") @filter(op: ">", value: ["$count"]) @output {
+            value @output(name: "multiple")
+        }
+        prior: predecessor @absent
+    }
+}"#,
+        )
+        .expect("invalid query");
+
+        assert_eq!(parsed, built);
+    }
+
+    #[test]
+    fn built_queries_are_validated_like_parsed_queries() {
+        // Built queries have no source text, so their errors only differ in positions.
+        let schema = numbers_schema();
+
+        let unknown_field = QueryBuilder::new(
+            FieldBuilder::new("Number")
+                .parameter("max", 10)
+                .field(FieldBuilder::new("nonexistent").output()),
+        );
+        let query = "{ Number(max: 10) { nonexistent @output } }";
+        assert_eq!(
+            parse_to_ir(&schema, query).unwrap_err().to_string(),
+            unknown_field.build(&schema).unwrap_err().to_string(),
+        );
+
+        let invalid_parameter = QueryBuilder::new(
+            FieldBuilder::new("Number")
+                .parameter("max", "ten")
+                .field(FieldBuilder::new("value").output()),
+        );
+        let query = r#"{ Number(max: "ten") { value @output } }"#;
+        assert_eq!(
+            parse_to_ir(&schema, query).unwrap_err().to_string(),
+            invalid_parameter.build(&schema).unwrap_err().to_string(),
+        );
+    }
+
+    #[test]
+    fn invalid_directives_are_rejected() {
+        let schema = numbers_schema();
+
+        let root_directive = QueryBuilder::new(FieldBuilder::new("Zero").optional());
+        assert!(matches!(
+            root_directive.build(&schema),
+            Err(FrontendError::ParseError(ParseError::DirectiveNotInsideQueryRoot(name, _)))
+                if name == "optional",
+        ));
+
+        let missing_operand =
+            QueryBuilder::new(FieldBuilder::new("Zero").field(
+                FieldBuilder::new("value").transform(TransformationKind::Add, None).output(),
+            ));
+        assert!(matches!(
+            missing_operand.build(&schema),
+            Err(FrontendError::ParseError(ParseError::OtherError(..))),
+        ));
+
+        let misplaced_directive =
+            QueryBuilder::new(FieldBuilder::new("Zero").field(
+                FieldBuilder::new("value").transform(TransformationKind::Abs, None).optional(),
+            ));
+        assert!(matches!(
+            misplaced_directive.build(&schema),
+            Err(FrontendError::ParseError(ParseError::UnsupportedDirectivePosition(..))),
+        ));
+    }
+}
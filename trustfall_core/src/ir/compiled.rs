@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use crate::schema::Schema;
+
+#[cfg(feature = "serialization")]
+use super::IRQuery;
+use super::IndexedQuery;
+
+/// Identifies the data as a serialized compiled Trustfall query.
+#[cfg(feature = "serialization")]
+const MAGIC: &[u8; 8] = b"TFCQUERY";
+
+/// Bump this whenever the serialized representation of the query IR changes in any way,
+/// so that data serialized in the old representation is rejected instead of misread.
+#[cfg(feature = "serialization")]
+const FORMAT_VERSION: u32 = 3;
+
+/// The length of the header preceding the serialized query: magic, version, and fingerprint.
+#[cfg(feature = "serialization")]
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8;
+
+/// A query that was parsed and validated against a schema ahead of time.
+///
+/// With the `serialization` feature, serialize it with `CompiledQuery::to_bytes()`,
+/// for example at build time, and load it with `CompiledQuery::from_bytes()` to execute it
+/// later without parsing and validating it again. The serialized query records
+/// the [fingerprint](Schema::fingerprint) of its schema, and can only be loaded
+/// for a schema with the same fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledQuery {
+    query: Arc<IndexedQuery>,
+    schema_fingerprint: u64,
+}
+
+/// An error produced when loading a compiled query with [`CompiledQuery::from_bytes`].
+#[cfg(feature = "serialization")]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CompiledQueryError {
+    #[error("The data is not a serialized compiled Trustfall query.")]
+    NotACompiledQuery,
+
+    #[error(
+        "The query was serialized in format version {0}, which this version of Trustfall \
+        cannot read. Compile the query again to get format version {1}."
+    )]
+    UnsupportedFormatVersion(u32, u32),
+
+    #[error(
+        "The query was compiled against a schema with fingerprint {0:#018x}, \
+        but the given schema has fingerprint {1:#018x}. Compile the query again \
+        against the given schema."
+    )]
+    SchemaMismatch(u64, u64),
+
+    #[error("The serialized query data is invalid: {0}")]
+    InvalidData(String),
+}
+
+impl CompiledQuery {
+    /// Wrap a query that was parsed against the given schema,
+    /// such as one produced by [`frontend::parse`](crate::frontend::parse).
+    pub fn new(schema: &Schema, query: Arc<IndexedQuery>) -> Self {
+        Self { query, schema_fingerprint: schema.fingerprint() }
+    }
+
+    /// The compiled query, ready for execution.
+    pub fn query(&self) -> &Arc<IndexedQuery> {
+        &self.query
+    }
+
+    /// The fingerprint of the schema against which the query was compiled.
+    pub fn schema_fingerprint(&self) -> u64 {
+        self.schema_fingerprint
+    }
+
+    /// Serialize the compiled query into a versioned binary representation.
+    #[cfg(feature = "serialization")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1024);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.schema_fingerprint.to_le_bytes());
+        ciborium::into_writer(&self.query.ir_query, &mut bytes).expect("failed to serialize query");
+        bytes
+    }
+
+    /// Load a query serialized with [`CompiledQuery::to_bytes`],
+    /// checking that it was compiled against the given schema.
+    #[cfg(feature = "serialization")]
+    pub fn from_bytes(schema: &Schema, bytes: &[u8]) -> Result<Self, CompiledQueryError> {
+        if bytes.len() < HEADER_LEN || !bytes.starts_with(MAGIC) {
+            return Err(CompiledQueryError::NotACompiledQuery);
+        }
+        let (version, rest) = bytes[MAGIC.len()..].split_at(4);
+        let (fingerprint, payload) = rest.split_at(8);
+
+        let version = u32::from_le_bytes(version.try_into().expect("not 4 bytes"));
+        if version != FORMAT_VERSION {
+            return Err(CompiledQueryError::UnsupportedFormatVersion(version, FORMAT_VERSION));
+        }
+
+        let schema_fingerprint = u64::from_le_bytes(fingerprint.try_into().expect("not 8 bytes"));
+        let expected_fingerprint = schema.fingerprint();
+        if schema_fingerprint != expected_fingerprint {
+            return Err(CompiledQueryError::SchemaMismatch(
+                schema_fingerprint,
+                expected_fingerprint,
+            ));
+        }
+
+        let ir_query: IRQuery = ciborium::from_reader(payload)
+            .map_err(|e| CompiledQueryError::InvalidData(e.to_string()))?;
+        let query: IndexedQuery = ir_query
+            .try_into()
+            .map_err(|e| CompiledQueryError::InvalidData(format!("invalid query IR: {e:?}")))?;
+
+        Ok(Self { query: Arc::new(query), schema_fingerprint })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{frontend::parse, schema::Schema};
+
+    use super::{CompiledQuery, CompiledQueryError, FORMAT_VERSION};
+
+    const QUERY: &str = r#"
+{
+    Number(max: 10) {
+        ... on Prime {
+            value @output @filter(op: ">", value: ["$min"])
+
+            successor {
+                next: value @output
+            }
+        }
+    }
+}"#;
+
+    fn numbers_schema() -> Schema {
+        Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema")
+    }
+
+    #[test]
+    fn roundtrip() {
+        let schema = numbers_schema();
+        let compiled = CompiledQuery::new(&schema, parse(&schema, QUERY).expect("valid query"));
+
+        let bytes = compiled.to_bytes();
+        let loaded = CompiledQuery::from_bytes(&schema, &bytes).expect("valid compiled query");
+        assert_eq!(compiled, loaded);
+        assert_eq!(bytes, loaded.to_bytes());
+    }
+
+    #[test]
+    fn schema_mismatch() {
+        let schema = numbers_schema();
+        let compiled = CompiledQuery::new(&schema, parse(&schema, QUERY).expect("valid query"));
+
+        let other_schema = Schema::parse(include_str!("../../test_data/schemas/nullables.graphql"))
+            .expect("valid schema");
+        assert_eq!(
+            CompiledQueryError::SchemaMismatch(schema.fingerprint(), other_schema.fingerprint()),
+            CompiledQuery::from_bytes(&other_schema, &compiled.to_bytes()).unwrap_err(),
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let schema = numbers_schema();
+        let bytes =
+            CompiledQuery::new(&schema, parse(&schema, QUERY).expect("valid query")).to_bytes();
+
+        assert_eq!(
+            CompiledQueryError::NotACompiledQuery,
+            CompiledQuery::from_bytes(&schema, QUERY.as_bytes()).unwrap_err(),
+        );
+        assert_eq!(
+            CompiledQueryError::NotACompiledQuery,
+            CompiledQuery::from_bytes(&schema, &bytes[..12]).unwrap_err(),
+        );
+
+        let mut other_version = bytes.clone();
+        other_version[8..12].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(
+            CompiledQueryError::UnsupportedFormatVersion(0, FORMAT_VERSION),
+            CompiledQuery::from_bytes(&schema, &other_version).unwrap_err(),
+        );
+
+        let truncated = &bytes[..bytes.len() - 10];
+        assert!(matches!(
+            CompiledQuery::from_bytes(&schema, truncated),
+            Err(CompiledQueryError::InvalidData(_))
+        ));
+    }
+}
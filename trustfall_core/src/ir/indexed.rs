@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::util::BTreeMapTryInsertExt;
 
-use super::{Argument, Eid, IREdge, IRFold, IRQuery, IRQueryComponent, Type, Vid};
+use super::{
+    Argument, Eid, FieldRef, FoldSpecificFieldKind, IREdge, IRFold, IRQuery, IRQueryComponent,
+    Type, ValueTransform, Vid,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IndexedQuery {
@@ -19,6 +22,13 @@ pub struct IndexedQuery {
     pub eids: BTreeMap<Eid, EdgeKind>,
 
     pub outputs: BTreeMap<Arc<str>, Output>,
+
+    /// The folds whose contents only affect the query's results through filters
+    /// on the number of elements in the fold.
+    ///
+    /// Such folds only need to be expanded until the outcome of those filters is known.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub count_only_folds: BTreeSet<Eid>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -30,6 +40,126 @@ pub struct Output {
     pub vid: Vid,
 }
 
+/// Metadata about the outputs of a query, such as their names and types.
+///
+/// Obtained via [`IndexedQuery::query_outputs()`]. Outputs are ordered by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryOutputs {
+    outputs: BTreeMap<Arc<str>, OutputMetadata>,
+}
+
+impl QueryOutputs {
+    /// The metadata of the output with the given name, if the query has such an output.
+    pub fn get(&self, name: &str) -> Option<&OutputMetadata> {
+        self.outputs.get(name)
+    }
+
+    /// The metadata of each output, in order of output names.
+    pub fn iter(&self) -> impl Iterator<Item = &OutputMetadata> + '_ {
+        self.outputs.values()
+    }
+
+    /// The names of the outputs, in order.
+    pub fn names(&self) -> impl Iterator<Item = &Arc<str>> + '_ {
+        self.outputs.keys()
+    }
+
+    /// The number of outputs.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Whether the query has no outputs.
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a QueryOutputs {
+    type Item = &'a OutputMetadata;
+    type IntoIter = std::collections::btree_map::Values<'a, Arc<str>, OutputMetadata>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.outputs.values()
+    }
+}
+
+/// Metadata about a single query output.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputMetadata {
+    /// The name of the output, as it appears in each query result.
+    pub name: Arc<str>,
+
+    /// The type of the output's values.
+    ///
+    /// Outputs within `@fold` are lists, with one level of nesting per enclosing `@fold`.
+    /// Outputs that may be `null`, for example due to `@optional`, have nullable types.
+    pub value_type: Type,
+
+    /// The `@fold` edges enclosing the output, outermost first.
+    ///
+    /// Each of them wraps the output's values in a list.
+    pub folds: Vec<Eid>,
+
+    /// Whether the output is a property of a `@fold` itself, such as `@transform(op: "count")`,
+    /// rather than a property of a vertex.
+    pub fold_specific: bool,
+}
+
+impl OutputMetadata {
+    /// Whether the output's values may be `null`.
+    pub fn is_nullable(&self) -> bool {
+        self.value_type.nullable()
+    }
+
+    /// Whether the output's values are lists, due to being within a `@fold`.
+    pub fn is_folded(&self) -> bool {
+        !self.folds.is_empty()
+    }
+}
+
+impl IndexedQuery {
+    /// Metadata about the query's outputs, such as their names and types.
+    ///
+    /// Useful for describing the shape of query results, for example
+    /// to produce column headers or response schemas.
+    pub fn query_outputs(&self) -> QueryOutputs {
+        let mut outputs = BTreeMap::new();
+        self.collect_output_metadata(&self.ir_query.root_component, &mut vec![], &mut outputs);
+        QueryOutputs { outputs }
+    }
+
+    fn collect_output_metadata(
+        &self,
+        component: &IRQueryComponent,
+        folds: &mut Vec<Eid>,
+        outputs: &mut BTreeMap<Arc<str>, OutputMetadata>,
+    ) {
+        let fold_specific_outputs = component
+            .folds
+            .values()
+            .flat_map(|fold| fold.fold_specific_outputs.keys().map(|name| (name, true)));
+        for (name, fold_specific) in
+            component.outputs.keys().map(|name| (name, false)).chain(fold_specific_outputs)
+        {
+            let metadata = OutputMetadata {
+                name: name.clone(),
+                value_type: self.outputs[name].value_type.clone(),
+                folds: folds.clone(),
+                fold_specific,
+            };
+            outputs.insert(name.clone(), metadata);
+        }
+
+        for (eid, fold) in &component.folds {
+            folds.push(*eid);
+            self.collect_output_metadata(&fold.component, folds, outputs);
+            folds.pop().expect("pushed value is no longer present");
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InvalidIRQueryError {
     GetBetterVariant(i32),
@@ -73,10 +203,106 @@ impl TryFrom<IRQuery> for IndexedQuery {
             &mut vec![],
         )?;
 
-        Ok(Self { ir_query, vids, eids, outputs })
+        let count_only_folds = get_count_only_folds(&ir_query.root_component);
+
+        Ok(Self { ir_query, vids, eids, outputs, count_only_folds })
+    }
+}
+
+fn get_count_only_folds(root_component: &IRQueryComponent) -> BTreeSet<Eid> {
+    // Folds with any tagged fold-specific values can affect the query in other ways,
+    // such as by having their count compared to a property elsewhere in the query.
+    let mut tagged_folds = BTreeSet::new();
+    collect_tagged_folds(root_component, &mut tagged_folds);
+
+    let mut count_only_folds = BTreeSet::new();
+    collect_count_only_folds(root_component, &tagged_folds, &mut count_only_folds);
+    count_only_folds
+}
+
+/// Collect the folds whose fold-specific values, like their element counts, are used as tags
+/// anywhere in the component or its subcomponents.
+pub(crate) fn collect_tagged_folds(component: &IRQueryComponent, tagged_folds: &mut BTreeSet<Eid>) {
+    for vertex in component.vertices.values() {
+        for filter in &vertex.filters {
+            collect_tagged_folds_in_transforms(&filter.left().transforms, tagged_folds);
+            for argument in filter.arguments() {
+                collect_tagged_folds_in_argument(argument, tagged_folds);
+            }
+        }
+    }
+    let edge_arguments = component.edges.values().flat_map(|edge| &edge.filters);
+    let fold_arguments = component.folds.values().flat_map(|fold| &fold.post_filters);
+    let arguments = edge_arguments
+        .flat_map(|filter| filter.arguments())
+        .chain(fold_arguments.flat_map(|filter| filter.arguments()));
+    for argument in arguments {
+        collect_tagged_folds_in_argument(argument, tagged_folds);
+    }
+    for output in component.outputs.values() {
+        collect_tagged_folds_in_transforms(&output.transforms, tagged_folds);
+    }
+
+    for fold in component.folds.values() {
+        let fold_specific_fields = fold
+            .fold_specific_outputs
+            .values()
+            .chain(fold.post_filters.iter().map(|filter| filter.left()));
+        for kind in fold_specific_fields {
+            collect_tagged_folds_in_transforms(kind.value_transforms(), tagged_folds);
+        }
+        collect_tagged_folds(&fold.component, tagged_folds);
+    }
+}
+
+fn collect_tagged_folds_in_argument(argument: &Argument, tagged_folds: &mut BTreeSet<Eid>) {
+    match argument {
+        Argument::Tag(FieldRef::FoldSpecificField(field)) => {
+            tagged_folds.insert(field.fold_eid);
+            collect_tagged_folds_in_transforms(field.kind.value_transforms(), tagged_folds);
+        }
+        Argument::Tag(FieldRef::ContextField(field)) => {
+            collect_tagged_folds_in_transforms(&field.transforms, tagged_folds);
+        }
+        Argument::Variable(_) => {}
+    }
+}
+
+fn collect_tagged_folds_in_transforms(
+    transforms: &[ValueTransform],
+    tagged_folds: &mut BTreeSet<Eid>,
+) {
+    for operand in transforms.iter().filter_map(|transform| transform.operand()) {
+        collect_tagged_folds_in_argument(operand, tagged_folds);
     }
 }
 
+fn collect_count_only_folds(
+    component: &IRQueryComponent,
+    tagged_folds: &BTreeSet<Eid>,
+    count_only_folds: &mut BTreeSet<Eid>,
+) {
+    for (eid, fold) in &component.folds {
+        let is_count_only = fold.fold_specific_outputs.is_empty()
+            && !tagged_folds.contains(eid)
+            && fold.post_filters.iter().all(|f| *f.left() == FoldSpecificFieldKind::Count)
+            && !component_has_outputs(&fold.component);
+        if is_count_only {
+            count_only_folds.insert(*eid);
+        }
+
+        collect_count_only_folds(&fold.component, tagged_folds, count_only_folds);
+    }
+}
+
+/// Whether the component or any of its folds, however deeply nested, produce any outputs.
+pub(crate) fn component_has_outputs(component: &IRQueryComponent) -> bool {
+    !component.outputs.is_empty()
+        || component.folds.values().any(|fold| {
+            !fold.fold_specific_outputs.is_empty() || component_has_outputs(&fold.component)
+        })
+}
+
 fn get_optional_vertices_in_component(component: &Arc<IRQueryComponent>) -> BTreeSet<Vid> {
     let mut output = BTreeSet::new();
     for edge in component.edges.values() {
@@ -124,9 +350,9 @@ fn add_data_from_component(
             return Err(InvalidIRQueryError::GetBetterVariant(0));
         }
 
-        for filter in &vertex.filters {
-            match filter.right() {
-                Some(Argument::Variable(vref)) => {
+        for argument in vertex.filters.iter().flat_map(|filter| filter.arguments()) {
+            match argument {
+                Argument::Variable(vref) => {
                     match variables.get(&vref.variable_name) {
                         Some(var_type) => {
                             // The variable type at top level must be a subtype of (or same type as)
@@ -148,7 +374,7 @@ fn add_data_from_component(
                         }
                     }
                 }
-                Some(Argument::Tag(..)) | None => {}
+                Argument::Tag(..) => {}
             }
         }
     }
@@ -0,0 +1,55 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{FieldValue, IndexedQuery, Output, Type};
+
+/// A query with multiple root fields, whose results are joined together.
+///
+/// Each root field is a separate [IndexedQuery]. Each result of the joined query combines
+/// one result of each root field, in the order in which the root fields appear in the query.
+/// Values tagged in one root field and used in a later one are passed to the later root field
+/// as arguments, through outputs that are not part of the joined query's results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinedQuery {
+    /// The root fields of the query, in the order in which they appear in the query.
+    pub roots: Vec<JoinedRoot>,
+
+    /// The variables of the query, across all its root fields.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<Arc<str>, Type>,
+
+    /// Default values of the query's variables, used if the variable's value is not provided.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variable_defaults: BTreeMap<Arc<str>, FieldValue>,
+
+    /// Outputs that carry tagged values from one root field to later ones.
+    ///
+    /// They are not part of the joined query's results.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub hidden_outputs: BTreeSet<Arc<str>>,
+}
+
+/// One root field of a [JoinedQuery].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinedRoot {
+    pub query: Arc<IndexedQuery>,
+
+    /// Variables of this root field's query whose values are the hidden outputs
+    /// of the same name produced by earlier root fields.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub imported_tags: BTreeSet<Arc<str>>,
+}
+
+impl JoinedQuery {
+    /// The outputs included in the joined query's results, across all its root fields.
+    pub fn outputs(&self) -> impl Iterator<Item = &Output> + '_ {
+        self.roots
+            .iter()
+            .flat_map(|root| root.query.outputs.values())
+            .filter(|output| !self.hidden_outputs.contains(&output.name))
+    }
+}
@@ -3,7 +3,7 @@
 use std::{
     borrow::Cow,
     cmp::Ordering,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::Debug,
     num::NonZeroUsize,
     ops::Index,
@@ -12,11 +12,24 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-pub use self::indexed::{EdgeKind, IndexedQuery, InvalidIRQueryError, Output};
+pub use self::compiled::CompiledQuery;
+#[cfg(feature = "serialization")]
+pub use self::compiled::CompiledQueryError;
+pub(crate) use self::indexed::{collect_tagged_folds, component_has_outputs};
+pub use self::indexed::{
+    EdgeKind, IndexedQuery, InvalidIRQueryError, Output, OutputMetadata, QueryOutputs,
+};
+pub use self::joined::{JoinedQuery, JoinedRoot};
 pub use self::types::{NamedTypedValue, Type};
-pub use self::value::{FieldValue, TransparentValue};
+pub use self::value::{DateTime, FieldValue, TransparentValue};
 
+pub mod builder;
+mod compiled;
 mod indexed;
+mod joined;
+pub mod optimize;
+mod query_text;
+mod result_types;
 mod types;
 pub mod value;
 
@@ -31,23 +44,71 @@ pub(crate) fn get_typename_meta_field() -> &'static Arc<str> {
 /// Unique vertex ID identifying a specific vertex in a Trustfall query
 #[doc(alias("vertex", "node"))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct Vid(pub(crate) NonZeroUsize);
+pub struct Vid(#[serde(deserialize_with = "deserialize_id")] pub(crate) NonZeroUsize);
 
 impl Vid {
     pub fn new(id: NonZeroUsize) -> Vid {
         Vid(id)
     }
+
+    /// The numeric value of this vertex ID.
+    pub fn get(self) -> NonZeroUsize {
+        self.0
+    }
 }
 
 /// Unique edge ID identifying a specific edge in a Trustfall query
 #[doc(alias = "edge")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct Eid(pub(crate) NonZeroUsize);
+pub struct Eid(#[serde(deserialize_with = "deserialize_id")] pub(crate) NonZeroUsize);
 
 impl Eid {
     pub fn new(id: NonZeroUsize) -> Eid {
         Eid(id)
     }
+
+    /// The numeric value of this edge ID.
+    pub fn get(self) -> NonZeroUsize {
+        self.0
+    }
+}
+
+/// Deserialize a vertex or edge ID from either a number or a string of digits.
+///
+/// IDs are serialized as strings when they are keys of a map in formats like JSON,
+/// whose maps only allow string keys.
+fn deserialize_id<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NonZeroUsize, D::Error> {
+    struct IdVisitor;
+
+    impl serde::de::Visitor<'_> for IdVisitor {
+        type Value = NonZeroUsize;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a nonzero usize")
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            usize::try_from(v)
+                .ok()
+                .and_then(NonZeroUsize::new)
+                .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            usize::try_from(v)
+                .ok()
+                .and_then(NonZeroUsize::new)
+                .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.parse().map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+        }
+    }
+
+    deserializer.deserialize_any(IdVisitor)
 }
 
 /// Parameter values for an edge expansion.
@@ -57,16 +118,73 @@ impl Eid {
 /// all edge parameters marked as required in the schema are included in
 /// the [`EdgeParameters`] value.
 ///
+/// Each value matches the parameter's type in the schema. Values of list-typed parameters
+/// are always [`FieldValue::List`] or [`FieldValue::Null`]: if the query supplied a single value
+/// for a list-typed parameter, it is wrapped in a list as in GraphQL.
+///
+/// Parameters whose values are query variables, like `edge(param: $var)`, are only known
+/// once the query's arguments are supplied. Until then, they are listed by
+/// [`EdgeParameters::variables`] instead. When the query is executed, they are replaced by
+/// the variables' values, so adapters never see them.
+///
+/// Parameters may also be tagged values from earlier in the query, like `edge(param: "%tag")`.
+/// Their values may differ for each vertex whose neighbors are resolved, so they are listed by
+/// [`EdgeParameters::tags`]. While the query is executing, consecutive vertices with the same
+/// tagged values have their neighbors along such an edge resolved together, with those
+/// parameters set to their tagged values.
+/// Within [`ResolveEdgeInfo`], which describes the edge independently of any vertex,
+/// those parameters only appear in [`EdgeParameters::tags`].
+///
 /// [`Adapter::resolve_starting_vertices`]: crate::interpreter::Adapter::resolve_neighbors
 /// [`Adapter::resolve_neighbors`]: crate::interpreter::Adapter::resolve_neighbors
+/// [`ResolveEdgeInfo`]: crate::interpreter::ResolveEdgeInfo
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EdgeParameters {
     pub(crate) contents: Arc<BTreeMap<Arc<str>, FieldValue>>,
+
+    /// Parameters whose values are the values of the given query variables.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) variables: BTreeMap<Arc<str>, VariableRef>,
+
+    /// Parameters whose values are the given tagged values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) tags: BTreeMap<Arc<str>, FieldRef>,
+
+    /// Parameters whose values are tagged values, but which are non-nullable in the schema.
+    /// If such a parameter's tagged value is null, the vertex has no neighbors along the edge.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) required_tags: BTreeSet<Arc<str>>,
 }
 
 impl EdgeParameters {
     pub(crate) fn new(contents: Arc<BTreeMap<Arc<str>, FieldValue>>) -> Self {
-        Self { contents }
+        Self {
+            contents,
+            variables: Default::default(),
+            tags: Default::default(),
+            required_tags: Default::default(),
+        }
+    }
+
+    /// Replace the parameters whose values are query variables with the values
+    /// of those variables in the given query arguments.
+    pub(crate) fn resolve_variables(&self, arguments: &BTreeMap<Arc<str>, FieldValue>) -> Self {
+        let mut contents = self.contents.as_ref().clone();
+        for (name, variable) in &self.variables {
+            let value = &arguments[&variable.variable_name];
+            contents.insert(name.clone(), value.clone());
+        }
+        Self { contents: Arc::new(contents), variables: Default::default(), ..self.clone() }
+    }
+
+    /// Set the parameters whose values are tagged values to the given values,
+    /// which are listed in the same order as [`EdgeParameters::tags`].
+    pub(crate) fn with_tag_values(&self, values: impl IntoIterator<Item = FieldValue>) -> Self {
+        let mut contents = self.contents.as_ref().clone();
+        for (name, value) in self.tags.keys().zip(values) {
+            contents.insert(name.clone(), value);
+        }
+        Self { contents: Arc::new(contents), ..self.clone() }
     }
 
     /// Gets the value of the edge parameter by this name.
@@ -83,9 +201,28 @@ impl EdgeParameters {
         self.contents.iter()
     }
 
-    /// Returns `true` if the edge has any parameters, and `false` otherwise.
+    /// Iterates through the edge parameters whose values are query variables,
+    /// together with those variables.
+    ///
+    /// This is always empty while the query is executing, since by then
+    /// those parameters are set to the variables' values.
+    pub fn variables(&self) -> impl Iterator<Item = (&'_ Arc<str>, &'_ VariableRef)> + '_ {
+        self.variables.iter()
+    }
+
+    /// Iterates through the edge parameters whose values are tagged values,
+    /// together with the fields those tags refer to.
+    ///
+    /// Unlike parameters set by query variables, these parameters remain listed here
+    /// while the query is executing. When resolving the neighbors of a particular vertex,
+    /// their values for that vertex are also available via [`EdgeParameters::get`].
+    pub fn tags(&self) -> impl Iterator<Item = (&'_ Arc<str>, &'_ FieldRef)> + '_ {
+        self.tags.iter()
+    }
+
+    /// Returns `true` if the edge has no parameters, and `false` otherwise.
     pub fn is_empty(&self) -> bool {
-        self.contents.is_empty()
+        self.contents.is_empty() && self.variables.is_empty() && self.tags.is_empty()
     }
 }
 
@@ -118,6 +255,70 @@ pub struct IRQueryComponent {
 
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub outputs: BTreeMap<Arc<str>, ContextField>,
+
+    /// Groups of sibling `@optional` edges that either all exist, or are all treated as missing.
+    ///
+    /// Corresponds to `@optional(group: "...")` directives. The edges in each group
+    /// are listed in increasing order of their [Eid].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub optional_groups: BTreeMap<Arc<str>, Vec<Eid>>,
+}
+
+impl IRQueryComponent {
+    /// Replace the edge parameters whose values are query variables with the values
+    /// of those variables, both in this component and within its folds.
+    ///
+    /// Returns `None` if none of the component's edge parameters are query variables.
+    pub(crate) fn resolve_parameter_variables(
+        &self,
+        arguments: &BTreeMap<Arc<str>, FieldValue>,
+    ) -> Option<Self> {
+        let mut resolved: Option<Self> = None;
+        for (eid, edge) in &self.edges {
+            if !edge.parameters.variables.is_empty() {
+                let mut edge = edge.as_ref().clone();
+                edge.parameters = edge.parameters.resolve_variables(arguments);
+                resolved.get_or_insert_with(|| self.clone()).edges.insert(*eid, Arc::new(edge));
+            }
+        }
+        for (eid, fold) in &self.folds {
+            let component = fold.component.resolve_parameter_variables(arguments);
+            if component.is_some() || !fold.parameters.variables.is_empty() {
+                let mut fold = fold.as_ref().clone();
+                fold.parameters = fold.parameters.resolve_variables(arguments);
+                if let Some(component) = component {
+                    fold.component = Arc::new(component);
+                }
+                resolved.get_or_insert_with(|| self.clone()).folds.insert(*eid, Arc::new(fold));
+            }
+        }
+        resolved
+    }
+
+    /// The query variables used as regex patterns by filters in this component
+    /// or within its folds, each with whether the pattern is matched case-insensitively.
+    pub(crate) fn regex_variables(&self) -> BTreeSet<(Arc<str>, bool)> {
+        let mut regex_variables = BTreeSet::new();
+        self.collect_regex_variables(&mut regex_variables);
+        regex_variables
+    }
+
+    fn collect_regex_variables(&self, regex_variables: &mut BTreeSet<(Arc<str>, bool)>) {
+        for filter in self.vertices.values().flat_map(|vertex| &vertex.filters) {
+            let case_insensitive = match filter {
+                Operation::RegexMatches(..) | Operation::NotRegexMatches(..) => false,
+                Operation::RegexMatchesIgnoreCase(..)
+                | Operation::NotRegexMatchesIgnoreCase(..) => true,
+                _ => continue,
+            };
+            if let Some(Argument::Variable(variable)) = filter.right() {
+                regex_variables.insert((variable.variable_name.clone(), case_insensitive));
+            }
+        }
+        for fold in self.folds.values() {
+            fold.component.collect_regex_variables(regex_variables);
+        }
+    }
 }
 
 /// Intermediate representation of a query
@@ -132,6 +333,54 @@ pub struct IRQuery {
 
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub variables: BTreeMap<Arc<str>, Type>,
+
+    /// Values for variables that the query doesn't require to be supplied as arguments.
+    ///
+    /// Corresponds to variable definitions with default values, like `query($limit: Int = 10)`.
+    /// Every variable here is also in `variables`, and its value is valid for that type.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variable_defaults: BTreeMap<Arc<str>, FieldValue>,
+
+    /// Outputs by which the query's results are sorted, from most to least significant.
+    ///
+    /// Corresponds to the `@order` directive. If empty, results are produced in
+    /// the order in which the adapter resolved them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order_by: Vec<OutputOrdering>,
+
+    /// The maximum number of results the query produces.
+    ///
+    /// Corresponds to the `@limit` directive on the query's root field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<NonZeroUsize>,
+
+    /// The number of results to skip before producing any results.
+    ///
+    /// Corresponds to the `@offset` directive on the query's root field.
+    /// Results are skipped after any `@order` sorting is applied, and before the `@limit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+
+    /// Whether the query only produces distinct results, discarding any duplicate rows.
+    ///
+    /// Corresponds to the `@dedupe` directive on the query's root field.
+    /// Duplicates are discarded before the `@offset` and `@limit` are applied.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub dedupe: bool,
+}
+
+/// Sorting of query results according to the value of one of the query's outputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputOrdering {
+    pub output_name: Arc<str>,
+    pub direction: OrderDirection,
+}
+
+/// The direction in which query results are sorted, as specified by the `@order` directive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderDirection {
+    Ascending,
+    Descending,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -152,6 +401,12 @@ pub struct IREdge {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub recursive: Option<Recursive>,
+
+    /// Filters on the existence of this edge, from `@filter` directives applied to the edge.
+    ///
+    /// Only `is_null` and `is_not_null` are allowed here, and only on `@optional` edges.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<Operation<(), Argument>>,
 }
 
 fn default_optional() -> bool {
@@ -164,15 +419,49 @@ fn is_false(b: &bool) -> bool {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Recursive {
-    pub depth: NonZeroUsize,
+    /// The maximum depth of the recursion, or `None` if it is unbounded.
+    ///
+    /// Unbounded recursion continues until no more vertices are reachable.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "recursion_depth")]
+    pub depth: Option<NonZeroUsize>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub coerce_to: Option<Arc<str>>,
+
+    /// Whether each recursion should skip vertices it has already visited.
+    ///
+    /// Corresponds to the `dedupe` argument of the `@recurse` directive.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub dedupe: bool,
 }
 
 impl Recursive {
-    pub fn new(depth: NonZeroUsize, coerce_to: Option<Arc<str>>) -> Self {
-        Self { depth, coerce_to }
+    pub fn new(depth: Option<NonZeroUsize>, coerce_to: Option<Arc<str>>) -> Self {
+        Self { depth, coerce_to, dedupe: false }
+    }
+}
+
+/// Serializes a recursion depth as a plain number, so that bounded recursion
+/// looks the same as it did before unbounded recursion was supported.
+///
+/// Use together with `#[serde(default, skip_serializing_if = "Option::is_none")]`,
+/// so that unbounded recursion is represented by the absence of a depth.
+pub(crate) mod recursion_depth {
+    use std::num::NonZeroUsize;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        depth: &Option<NonZeroUsize>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        depth.expect("unbounded depth should have been skipped").serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<NonZeroUsize>, D::Error> {
+        NonZeroUsize::deserialize(deserializer).map(Some)
     }
 }
 
@@ -214,36 +503,161 @@ pub struct IRFold {
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub post_filters: Vec<Operation<FoldSpecificFieldKind, Argument>>,
+
+    /// Corresponds to the `@absent` directive: only results for which this fold has
+    /// no elements are kept, as if it had a `@filter` requiring its count to equal zero.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub absent: bool,
 }
 
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FoldSpecificFieldKind {
     Count, // Represents the number of elements in an IRFold's component.
+
+    // Aggregations over the values of a numeric property of vertices in an IRFold's component.
+    // `null` property values are ignored.
+    Sum(ContextField), // The sum of the values, or zero if there are none.
+    Min(ContextField), // The smallest value, or `null` if there are none.
+    Max(ContextField), // The largest value, or `null` if there are none.
+    Avg(ContextField), // The arithmetic mean of the values, or `null` if there are none.
+
+    // One of the above values, with value transforms applied to it in order, like
+    // `@fold @transform(op: "count") @transform(op: "*", value: ["$factor"])`.
+    Transformed {
+        base: Box<FoldSpecificFieldKind>, // Never itself a `Transformed` value.
+        transforms: Vec<ValueTransform>,
+        field_type: Type, // The type of the value after applying the `transforms`.
+    },
 }
 
 static NON_NULL_INT_TYPE: OnceLock<Type> = OnceLock::new();
+static NON_NULL_FLOAT_TYPE: OnceLock<Type> = OnceLock::new();
+static NULLABLE_INT_TYPE: OnceLock<Type> = OnceLock::new();
+static NULLABLE_FLOAT_TYPE: OnceLock<Type> = OnceLock::new();
 
 impl FoldSpecificFieldKind {
     pub fn field_type(&self) -> &Type {
         match self {
             Self::Count => NON_NULL_INT_TYPE.get_or_init(|| Type::new_named_type("Int", false)),
+            Self::Sum(field) => {
+                if field.field_type.base_type() == "Int" {
+                    NON_NULL_INT_TYPE.get_or_init(|| Type::new_named_type("Int", false))
+                } else {
+                    NON_NULL_FLOAT_TYPE.get_or_init(|| Type::new_named_type("Float", false))
+                }
+            }
+            Self::Min(field) | Self::Max(field) => {
+                if field.field_type.base_type() == "Int" {
+                    NULLABLE_INT_TYPE.get_or_init(|| Type::new_named_type("Int", true))
+                } else {
+                    NULLABLE_FLOAT_TYPE.get_or_init(|| Type::new_named_type("Float", true))
+                }
+            }
+            Self::Avg(..) => {
+                NULLABLE_FLOAT_TYPE.get_or_init(|| Type::new_named_type("Float", true))
+            }
+            Self::Transformed { field_type, .. } => field_type,
         }
     }
 
     pub fn field_name(&self) -> &str {
         match self {
             FoldSpecificFieldKind::Count => "@fold.count",
+            FoldSpecificFieldKind::Sum(..) => "@fold.sum",
+            FoldSpecificFieldKind::Min(..) => "@fold.min",
+            FoldSpecificFieldKind::Max(..) => "@fold.max",
+            FoldSpecificFieldKind::Avg(..) => "@fold.avg",
+            FoldSpecificFieldKind::Transformed { base, .. } => base.field_name(),
         }
     }
 
     pub fn transform_suffix(&self) -> &str {
         match self {
             FoldSpecificFieldKind::Count => "count",
+            FoldSpecificFieldKind::Sum(..) => "sum",
+            FoldSpecificFieldKind::Min(..) => "min",
+            FoldSpecificFieldKind::Max(..) => "max",
+            FoldSpecificFieldKind::Avg(..) => "avg",
+            FoldSpecificFieldKind::Transformed { transforms, .. } => {
+                transforms.last().expect("no transforms").output_suffix()
+            }
+        }
+    }
+
+    /// The folded property whose values are aggregated, if this is an aggregation
+    /// or a transformed aggregation.
+    pub fn aggregated_field(&self) -> Option<&ContextField> {
+        match self {
+            FoldSpecificFieldKind::Count => None,
+            FoldSpecificFieldKind::Sum(field)
+            | FoldSpecificFieldKind::Min(field)
+            | FoldSpecificFieldKind::Max(field)
+            | FoldSpecificFieldKind::Avg(field) => Some(field),
+            FoldSpecificFieldKind::Transformed { base, .. } => base.aggregated_field(),
+        }
+    }
+
+    /// The value before applying any value transforms to it.
+    pub fn untransformed(&self) -> &FoldSpecificFieldKind {
+        match self {
+            FoldSpecificFieldKind::Transformed { base, .. } => base,
+            _ => self,
+        }
+    }
+
+    /// The value transforms applied to the value, if any.
+    pub fn value_transforms(&self) -> &[ValueTransform] {
+        match self {
+            FoldSpecificFieldKind::Transformed { transforms, .. } => transforms,
+            _ => &[],
+        }
+    }
+
+    /// Apply another value transform to the value, producing a value of the given type.
+    pub fn transformed(self, transform: ValueTransform, field_type: Type) -> Self {
+        match self {
+            FoldSpecificFieldKind::Transformed { base, mut transforms, .. } => {
+                transforms.push(transform);
+                FoldSpecificFieldKind::Transformed { base, transforms, field_type }
+            }
+            base => FoldSpecificFieldKind::Transformed {
+                base: Box::new(base),
+                transforms: vec![transform],
+                field_type,
+            },
         }
     }
 }
 
+impl Ord for FoldSpecificFieldKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let discriminant = |kind: &Self| match kind.untransformed() {
+            FoldSpecificFieldKind::Count => 0,
+            FoldSpecificFieldKind::Sum(..) => 1,
+            FoldSpecificFieldKind::Min(..) => 2,
+            FoldSpecificFieldKind::Max(..) => 3,
+            FoldSpecificFieldKind::Avg(..) => 4,
+            FoldSpecificFieldKind::Transformed { .. } => unreachable!("nested transformed value"),
+        };
+        let field_key = |kind: &Self| {
+            kind.aggregated_field()
+                .map(|field| (field.vertex_id, field.field_name.clone(), field.transforms.clone()))
+        };
+
+        discriminant(self)
+            .cmp(&discriminant(other))
+            .then_with(|| field_key(self).cmp(&field_key(other)))
+            .then_with(|| self.value_transforms().cmp(other.value_transforms()))
+    }
+}
+
+impl PartialOrd for FoldSpecificFieldKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FoldSpecificField {
     // uniquely identifies the fold
@@ -260,6 +674,116 @@ pub struct FoldSpecificField {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransformationKind {
     Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+    Lowercase,
+    Uppercase,
+    Trim,
+    Len,
+    Abs,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl TransformationKind {
+    /// Whether the transformation aggregates the values of a property within a `@fold`,
+    /// or counts the elements of the `@fold` itself.
+    pub fn is_aggregation(&self) -> bool {
+        matches!(
+            self,
+            TransformationKind::Count
+                | TransformationKind::Sum
+                | TransformationKind::Min
+                | TransformationKind::Max
+                | TransformationKind::Avg
+        )
+    }
+
+    /// The transformation's equivalent [`ValueTransform`],
+    /// if it applies to each property value individually and does not take an operand.
+    pub fn value_transform(&self) -> Option<ValueTransform> {
+        match self {
+            TransformationKind::Lowercase => Some(ValueTransform::Lowercase),
+            TransformationKind::Uppercase => Some(ValueTransform::Uppercase),
+            TransformationKind::Trim => Some(ValueTransform::Trim),
+            TransformationKind::Len => Some(ValueTransform::Len),
+            TransformationKind::Abs => Some(ValueTransform::Abs),
+            TransformationKind::Count
+            | TransformationKind::Sum
+            | TransformationKind::Min
+            | TransformationKind::Max
+            | TransformationKind::Avg
+            | TransformationKind::Add
+            | TransformationKind::Subtract
+            | TransformationKind::Multiply
+            | TransformationKind::Divide => None,
+        }
+    }
+}
+
+/// A transformation applied to each individual property value,
+/// like `@transform(op: "lowercase")`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ValueTransform {
+    Lowercase, // String -> String
+    Uppercase, // String -> String
+    Trim,      // String -> String, removing leading and trailing whitespace
+    Len,       // String or list -> Int, the number of characters or list elements
+    Abs,       // Int -> Int or Float -> Float, the absolute value
+
+    // Arithmetic with the value of a variable or tag, like `@transform(op: "*", value: ["$x"])`.
+    // Int with Int -> Int, and Int or Float with Float -> Float, or `null` if the operation fails.
+    Add(Argument),
+    Subtract(Argument),
+    Multiply(Argument),
+    Divide(Argument), // Int division rounds toward zero.
+}
+
+impl ValueTransform {
+    pub fn op_name(&self) -> &'static str {
+        match self {
+            ValueTransform::Lowercase => "lowercase",
+            ValueTransform::Uppercase => "uppercase",
+            ValueTransform::Trim => "trim",
+            ValueTransform::Len => "len",
+            ValueTransform::Abs => "abs",
+            ValueTransform::Add(..) => "+",
+            ValueTransform::Subtract(..) => "-",
+            ValueTransform::Multiply(..) => "*",
+            ValueTransform::Divide(..) => "/",
+        }
+    }
+
+    /// The suffix this transform adds to implicitly-named outputs of the transformed value.
+    pub fn output_suffix(&self) -> &'static str {
+        match self {
+            ValueTransform::Add(..) => "add",
+            ValueTransform::Subtract(..) => "sub",
+            ValueTransform::Multiply(..) => "mul",
+            ValueTransform::Divide(..) => "div",
+            _ => self.op_name(),
+        }
+    }
+
+    /// The variable or tag whose value is the transform's operand, if it has one.
+    pub fn operand(&self) -> Option<&Argument> {
+        match self {
+            ValueTransform::Add(operand)
+            | ValueTransform::Subtract(operand)
+            | ValueTransform::Multiply(operand)
+            | ValueTransform::Divide(operand) => Some(operand),
+            ValueTransform::Lowercase
+            | ValueTransform::Uppercase
+            | ValueTransform::Trim
+            | ValueTransform::Len
+            | ValueTransform::Abs => None,
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -275,7 +799,8 @@ impl Ord for FieldRef {
             (FieldRef::ContextField(f1), FieldRef::ContextField(f2)) => f1
                 .vertex_id
                 .cmp(&f2.vertex_id)
-                .then(f1.field_name.as_ref().cmp(f2.field_name.as_ref())),
+                .then(f1.field_name.as_ref().cmp(f2.field_name.as_ref()))
+                .then(f1.transforms.cmp(&f2.transforms)),
             (FieldRef::ContextField(_), FieldRef::FoldSpecificField(_)) => Ordering::Less,
             (FieldRef::FoldSpecificField(_), FieldRef::ContextField(_)) => Ordering::Greater,
             (FieldRef::FoldSpecificField(f1), FieldRef::FoldSpecificField(f2)) => {
@@ -333,6 +858,26 @@ pub enum Argument {
     Variable(VariableRef),
 }
 
+impl Ord for Argument {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Argument::Tag(t1), Argument::Tag(t2)) => t1.cmp(t2),
+            (Argument::Tag(_), Argument::Variable(_)) => Ordering::Less,
+            (Argument::Variable(_), Argument::Tag(_)) => Ordering::Greater,
+            (Argument::Variable(v1), Argument::Variable(v2)) => v1
+                .variable_name
+                .cmp(&v2.variable_name)
+                .then_with(|| v1.variable_type.to_string().cmp(&v2.variable_type.to_string())),
+        }
+    }
+}
+
+impl PartialOrd for Argument {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Argument {
     pub(crate) fn as_tag(&self) -> Option<&FieldRef> {
         match self {
@@ -376,6 +921,10 @@ where
     LessThanOrEqual(LeftT, RightT),
     GreaterThan(LeftT, RightT),
     GreaterThanOrEqual(LeftT, RightT),
+    /// The value is between the lower and upper bounds, in that order, including both of them.
+    Between(LeftT, RightT, RightT),
+    /// The value is either less than the lower bound, or greater than the upper bound.
+    NotBetween(LeftT, RightT, RightT),
     Contains(LeftT, RightT),
     NotContains(LeftT, RightT),
     OneOf(LeftT, RightT),
@@ -388,6 +937,16 @@ where
     NotHasSubstring(LeftT, RightT),
     RegexMatches(LeftT, RightT),
     NotRegexMatches(LeftT, RightT),
+    EqualsIgnoreCase(LeftT, RightT),
+    NotEqualsIgnoreCase(LeftT, RightT),
+    HasPrefixIgnoreCase(LeftT, RightT),
+    NotHasPrefixIgnoreCase(LeftT, RightT),
+    HasSuffixIgnoreCase(LeftT, RightT),
+    NotHasSuffixIgnoreCase(LeftT, RightT),
+    HasSubstringIgnoreCase(LeftT, RightT),
+    NotHasSubstringIgnoreCase(LeftT, RightT),
+    RegexMatchesIgnoreCase(LeftT, RightT),
+    NotRegexMatchesIgnoreCase(LeftT, RightT),
 }
 
 impl<LeftT, RightT> Operation<LeftT, RightT>
@@ -405,6 +964,8 @@ where
             Operation::LessThanOrEqual(left, _) => left,
             Operation::GreaterThan(left, _) => left,
             Operation::GreaterThanOrEqual(left, _) => left,
+            Operation::Between(left, _, _) => left,
+            Operation::NotBetween(left, _, _) => left,
             Operation::Contains(left, _) => left,
             Operation::NotContains(left, _) => left,
             Operation::OneOf(left, _) => left,
@@ -417,12 +978,27 @@ where
             Operation::NotHasSubstring(left, _) => left,
             Operation::RegexMatches(left, _) => left,
             Operation::NotRegexMatches(left, _) => left,
+            Operation::EqualsIgnoreCase(left, _) => left,
+            Operation::NotEqualsIgnoreCase(left, _) => left,
+            Operation::HasPrefixIgnoreCase(left, _) => left,
+            Operation::NotHasPrefixIgnoreCase(left, _) => left,
+            Operation::HasSuffixIgnoreCase(left, _) => left,
+            Operation::NotHasSuffixIgnoreCase(left, _) => left,
+            Operation::HasSubstringIgnoreCase(left, _) => left,
+            Operation::NotHasSubstringIgnoreCase(left, _) => left,
+            Operation::RegexMatchesIgnoreCase(left, _) => left,
+            Operation::NotRegexMatchesIgnoreCase(left, _) => left,
         }
     }
 
+    /// The operand of an operation that compares the value to a single other operand.
+    ///
+    /// Operations with no operands, like `is_null`, and ones with multiple operands,
+    /// like `between`, return None. Use [`Operation::arguments`] to get all operands.
     pub(crate) fn right(&self) -> Option<&RightT> {
         match self {
             Operation::IsNull(_) | Operation::IsNotNull(_) => None,
+            Operation::Between(..) | Operation::NotBetween(..) => None,
             Operation::Equals(_, right) => Some(right),
             Operation::NotEquals(_, right) => Some(right),
             Operation::LessThan(_, right) => Some(right),
@@ -441,9 +1017,30 @@ where
             Operation::NotHasSubstring(_, right) => Some(right),
             Operation::RegexMatches(_, right) => Some(right),
             Operation::NotRegexMatches(_, right) => Some(right),
+            Operation::EqualsIgnoreCase(_, right) => Some(right),
+            Operation::NotEqualsIgnoreCase(_, right) => Some(right),
+            Operation::HasPrefixIgnoreCase(_, right) => Some(right),
+            Operation::NotHasPrefixIgnoreCase(_, right) => Some(right),
+            Operation::HasSuffixIgnoreCase(_, right) => Some(right),
+            Operation::NotHasSuffixIgnoreCase(_, right) => Some(right),
+            Operation::HasSubstringIgnoreCase(_, right) => Some(right),
+            Operation::NotHasSubstringIgnoreCase(_, right) => Some(right),
+            Operation::RegexMatchesIgnoreCase(_, right) => Some(right),
+            Operation::NotRegexMatchesIgnoreCase(_, right) => Some(right),
         }
     }
 
+    /// All operands of the operation other than the value being filtered, in order.
+    pub(crate) fn arguments(&self) -> impl Iterator<Item = &RightT> {
+        let (first, second) = match self {
+            Operation::Between(_, lower, upper) | Operation::NotBetween(_, lower, upper) => {
+                (Some(lower), Some(upper))
+            }
+            _ => (self.right(), None),
+        };
+        first.into_iter().chain(second)
+    }
+
     /// The operation name, as it would have appeared in the `@filter` directive `op` argument.
     pub(crate) fn operation_name(&self) -> &'static str {
         match self {
@@ -455,6 +1052,8 @@ where
             Operation::LessThanOrEqual(..) => "<=",
             Operation::GreaterThan(..) => ">",
             Operation::GreaterThanOrEqual(..) => ">=",
+            Operation::Between(..) => "between",
+            Operation::NotBetween(..) => "not_between",
             Operation::Contains(..) => "contains",
             Operation::NotContains(..) => "not_contains",
             Operation::OneOf(..) => "one_of",
@@ -467,19 +1066,29 @@ where
             Operation::NotHasSubstring(..) => "not_has_substring",
             Operation::RegexMatches(..) => "regex",
             Operation::NotRegexMatches(..) => "not_regex",
+            Operation::EqualsIgnoreCase(..) => "=i",
+            Operation::NotEqualsIgnoreCase(..) => "!=i",
+            Operation::HasPrefixIgnoreCase(..) => "has_prefix_i",
+            Operation::NotHasPrefixIgnoreCase(..) => "not_has_prefix_i",
+            Operation::HasSuffixIgnoreCase(..) => "has_suffix_i",
+            Operation::NotHasSuffixIgnoreCase(..) => "not_has_suffix_i",
+            Operation::HasSubstringIgnoreCase(..) => "has_substring_i",
+            Operation::NotHasSubstringIgnoreCase(..) => "not_has_substring_i",
+            Operation::RegexMatchesIgnoreCase(..) => "regex_i",
+            Operation::NotRegexMatchesIgnoreCase(..) => "not_regex_i",
         }
     }
 
     pub(crate) fn map<'a, LeftF, LeftOutT, RightF, RightOutT>(
         &'a self,
         map_left: LeftF,
-        map_right: RightF,
+        mut map_right: RightF,
     ) -> Operation<LeftOutT, RightOutT>
     where
         LeftOutT: Debug + Clone + PartialEq + Eq,
         RightOutT: Debug + Clone + PartialEq + Eq,
         LeftF: FnOnce(&'a LeftT) -> LeftOutT,
-        RightF: FnOnce(&'a RightT) -> RightOutT,
+        RightF: FnMut(&'a RightT) -> RightOutT,
     {
         match self {
             Operation::IsNull(left) => Operation::IsNull(map_left(left)),
@@ -500,6 +1109,12 @@ where
             Operation::GreaterThanOrEqual(left, right) => {
                 Operation::GreaterThanOrEqual(map_left(left), map_right(right))
             }
+            Operation::Between(left, lower, upper) => {
+                Operation::Between(map_left(left), map_right(lower), map_right(upper))
+            }
+            Operation::NotBetween(left, lower, upper) => {
+                Operation::NotBetween(map_left(left), map_right(lower), map_right(upper))
+            }
             Operation::Contains(left, right) => {
                 Operation::Contains(map_left(left), map_right(right))
             }
@@ -534,19 +1149,49 @@ where
             Operation::NotRegexMatches(left, right) => {
                 Operation::NotRegexMatches(map_left(left), map_right(right))
             }
+            Operation::EqualsIgnoreCase(left, right) => {
+                Operation::EqualsIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::NotEqualsIgnoreCase(left, right) => {
+                Operation::NotEqualsIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::HasPrefixIgnoreCase(left, right) => {
+                Operation::HasPrefixIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::NotHasPrefixIgnoreCase(left, right) => {
+                Operation::NotHasPrefixIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::HasSuffixIgnoreCase(left, right) => {
+                Operation::HasSuffixIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::NotHasSuffixIgnoreCase(left, right) => {
+                Operation::NotHasSuffixIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::HasSubstringIgnoreCase(left, right) => {
+                Operation::HasSubstringIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::NotHasSubstringIgnoreCase(left, right) => {
+                Operation::NotHasSubstringIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::RegexMatchesIgnoreCase(left, right) => {
+                Operation::RegexMatchesIgnoreCase(map_left(left), map_right(right))
+            }
+            Operation::NotRegexMatchesIgnoreCase(left, right) => {
+                Operation::NotRegexMatchesIgnoreCase(map_left(left), map_right(right))
+            }
         }
     }
 
     pub(crate) fn try_map<LeftF, LeftOutT, RightF, RightOutT, Err>(
         &self,
         map_left: LeftF,
-        map_right: RightF,
+        mut map_right: RightF,
     ) -> Result<Operation<LeftOutT, RightOutT>, Err>
     where
         LeftOutT: Debug + Clone + PartialEq + Eq,
         RightOutT: Debug + Clone + PartialEq + Eq,
         LeftF: FnOnce(&LeftT) -> Result<LeftOutT, Err>,
-        RightF: FnOnce(&RightT) -> Result<RightOutT, Err>,
+        RightF: FnMut(&RightT) -> Result<RightOutT, Err>,
     {
         Ok(match self {
             Operation::IsNull(left) => Operation::IsNull(map_left(left)?),
@@ -567,6 +1212,12 @@ where
             Operation::GreaterThanOrEqual(left, right) => {
                 Operation::GreaterThanOrEqual(map_left(left)?, map_right(right)?)
             }
+            Operation::Between(left, lower, upper) => {
+                Operation::Between(map_left(left)?, map_right(lower)?, map_right(upper)?)
+            }
+            Operation::NotBetween(left, lower, upper) => {
+                Operation::NotBetween(map_left(left)?, map_right(lower)?, map_right(upper)?)
+            }
             Operation::Contains(left, right) => {
                 Operation::Contains(map_left(left)?, map_right(right)?)
             }
@@ -601,6 +1252,36 @@ where
             Operation::NotRegexMatches(left, right) => {
                 Operation::NotRegexMatches(map_left(left)?, map_right(right)?)
             }
+            Operation::EqualsIgnoreCase(left, right) => {
+                Operation::EqualsIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::NotEqualsIgnoreCase(left, right) => {
+                Operation::NotEqualsIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::HasPrefixIgnoreCase(left, right) => {
+                Operation::HasPrefixIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::NotHasPrefixIgnoreCase(left, right) => {
+                Operation::NotHasPrefixIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::HasSuffixIgnoreCase(left, right) => {
+                Operation::HasSuffixIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::NotHasSuffixIgnoreCase(left, right) => {
+                Operation::NotHasSuffixIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::HasSubstringIgnoreCase(left, right) => {
+                Operation::HasSubstringIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::NotHasSubstringIgnoreCase(left, right) => {
+                Operation::NotHasSubstringIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::RegexMatchesIgnoreCase(left, right) => {
+                Operation::RegexMatchesIgnoreCase(map_left(left)?, map_right(right)?)
+            }
+            Operation::NotRegexMatchesIgnoreCase(left, right) => {
+                Operation::NotRegexMatchesIgnoreCase(map_left(left)?, map_right(right)?)
+            }
         })
     }
 }
@@ -611,14 +1292,24 @@ pub struct ContextField {
 
     pub field_name: Arc<str>,
 
+    /// The type of the field's value, after applying any `transforms`.
     pub field_type: Type,
+
+    /// Transformations applied in order to the property's value, if any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<ValueTransform>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LocalField {
     pub field_name: Arc<str>,
 
+    /// The type of the field's value, after applying any `transforms`.
     pub field_type: Type,
+
+    /// Transformations applied in order to the property's value, if any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<ValueTransform>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
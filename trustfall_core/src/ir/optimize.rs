@@ -0,0 +1,487 @@
+//! Rewriting queries into equivalent queries that may be cheaper to execute.
+//!
+//! An [`Optimizer`] runs a sequence of [`OptimizationPass`]es over a query's IR.
+//! It comes with built-in passes, and embedders may add their own.
+use std::{collections::BTreeSet, fmt::Debug, sync::Arc};
+
+use crate::schema::Schema;
+
+use super::{
+    collect_tagged_folds, component_has_outputs, Argument, Eid, IRQuery, IRQueryComponent,
+    IndexedQuery, InvalidIRQueryError, LocalField, Operation, Type,
+};
+
+/// A rewrite of a query's IR that must not change the results the query produces.
+pub trait OptimizationPass: Send + Sync {
+    /// A short name identifying the pass, such as for logging.
+    fn name(&self) -> &str;
+
+    /// Rewrite the query in place. The schema is the one the query was compiled against.
+    fn run(&self, query: &mut IRQuery, schema: &Schema);
+}
+
+/// Runs optimization passes over queries, in the order in which they were added.
+#[derive(Clone)]
+pub struct Optimizer {
+    passes: Vec<Arc<dyn OptimizationPass>>,
+}
+
+impl Debug for Optimizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Optimizer").field("passes", &self.passes().collect::<Vec<_>>()).finish()
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimizer {
+    /// An optimizer that runs all the built-in passes.
+    pub fn new() -> Self {
+        Self::without_passes()
+            .with_pass(RemoveUnusedFolds)
+            .with_pass(RemoveRedundantCoercions)
+            .with_pass(ReorderFilters)
+    }
+
+    /// An optimizer that doesn't run any passes until some are added with [`Self::with_pass`].
+    pub fn without_passes() -> Self {
+        Self { passes: vec![] }
+    }
+
+    /// Run the given pass after all passes added so far.
+    pub fn with_pass(mut self, pass: impl OptimizationPass + 'static) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+
+    /// The names of the passes this optimizer runs, in order.
+    pub fn passes(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    /// Run all passes over the query's IR.
+    pub fn optimize_ir(&self, query: &mut IRQuery, schema: &Schema) {
+        for pass in &self.passes {
+            pass.run(query, schema);
+        }
+    }
+
+    /// Run all passes over a copy of the query, and index the result so it can be executed.
+    ///
+    /// Fails if a pass produced invalid IR.
+    pub fn optimize(
+        &self,
+        query: &IndexedQuery,
+        schema: &Schema,
+    ) -> Result<Arc<IndexedQuery>, InvalidIRQueryError> {
+        let mut ir_query = query.ir_query.clone();
+        self.optimize_ir(&mut ir_query, schema);
+        Ok(Arc::new(ir_query.try_into()?))
+    }
+}
+
+fn for_each_component(
+    component: &mut Arc<IRQueryComponent>,
+    visitor: &mut impl FnMut(&mut IRQueryComponent),
+) {
+    let component = Arc::make_mut(component);
+    visitor(component);
+    for fold in component.folds.values_mut() {
+        for_each_component(&mut Arc::make_mut(fold).component, visitor);
+    }
+}
+
+/// Removes `@fold` edges with no effect on the query's results: ones without outputs,
+/// filters on their elements' count or aggregated values, or tags used elsewhere in the query.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveUnusedFolds;
+
+impl OptimizationPass for RemoveUnusedFolds {
+    fn name(&self) -> &str {
+        "remove_unused_folds"
+    }
+
+    fn run(&self, query: &mut IRQuery, _schema: &Schema) {
+        let mut tagged_folds: BTreeSet<Eid> = Default::default();
+        collect_tagged_folds(&query.root_component, &mut tagged_folds);
+
+        for_each_component(&mut query.root_component, &mut |component| {
+            component.folds.retain(|eid, fold| {
+                !fold.fold_specific_outputs.is_empty()
+                    || !fold.post_filters.is_empty()
+                    || fold.absent
+                    || tagged_folds.contains(eid)
+                    || component_has_outputs(&fold.component)
+            });
+        });
+    }
+}
+
+/// Removes type coercions that the schema guarantees will always succeed,
+/// because the edge only points to vertices of the coerced-to type or its subtypes.
+///
+/// Queries can't be written with such coercions, but they may appear when optimizing a query
+/// against a newer version of its schema that narrowed the types of some edges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveRedundantCoercions;
+
+impl OptimizationPass for RemoveRedundantCoercions {
+    fn name(&self) -> &str {
+        "remove_redundant_coercions"
+    }
+
+    fn run(&self, query: &mut IRQuery, schema: &Schema) {
+        let edge_type = |from_type: &str, edge_name: &str| {
+            schema
+                .fields
+                .get(&(from_type.into(), edge_name.into()))
+                .map(|defn| Type::from_type(&defn.ty.node).base_type().to_string())
+        };
+        let is_redundant = |edge_type: Option<String>, coerced_to: &str| {
+            edge_type.is_some_and(|edge_type| schema.is_named_type_subtype(coerced_to, &edge_type))
+        };
+
+        let root_type = edge_type(schema.query_type_name(), &query.root_name);
+        let root_component = Arc::make_mut(&mut query.root_component);
+        let root_vertex = root_component.vertices.get_mut(&root_component.root).expect("no root");
+        if is_redundant(root_type, &root_vertex.type_name) {
+            root_vertex.coerced_from_type = None;
+        }
+
+        for_each_component(&mut query.root_component, &mut |component| {
+            // Vertices reached by recursion may have been reached through the edge's
+            // implicit coercions, so we leave their coercions as they are.
+            let edges = component.edges.values().filter(|edge| edge.recursive.is_none());
+            let redundant_coercions: Vec<_> = edges
+                .filter(|edge| {
+                    let from_type = &component.vertices[&edge.from_vid].type_name;
+                    let to_type = &component.vertices[&edge.to_vid].type_name;
+                    is_redundant(edge_type(from_type, &edge.edge_name), to_type)
+                })
+                .map(|edge| edge.to_vid)
+                .collect();
+            for vid in redundant_coercions {
+                component.vertices.get_mut(&vid).expect("no vertex").coerced_from_type = None;
+            }
+
+            for fold in component.folds.values_mut() {
+                let from_type = &component.vertices[&fold.from_vid].type_name;
+                let fold_type = edge_type(from_type, &fold.edge_name);
+                let fold = Arc::make_mut(fold);
+                let fold_component = Arc::make_mut(&mut fold.component);
+                let to_vertex = fold_component.vertices.get_mut(&fold.to_vid).expect("no vertex");
+                if is_redundant(fold_type, &to_vertex.type_name) {
+                    to_vertex.coerced_from_type = None;
+                }
+            }
+        });
+    }
+}
+
+/// Orders each vertex's filters so that ones that are cheaper to evaluate are applied first,
+/// letting vertices that fail them skip the more expensive filters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReorderFilters;
+
+impl OptimizationPass for ReorderFilters {
+    fn name(&self) -> &str {
+        "reorder_filters"
+    }
+
+    fn run(&self, query: &mut IRQuery, _schema: &Schema) {
+        for_each_component(&mut query.root_component, &mut |component| {
+            for vertex in component.vertices.values_mut() {
+                vertex.filters.sort_by_key(filter_cost);
+            }
+        });
+    }
+}
+
+/// A rough relative cost of evaluating a filter, for ordering filters.
+fn filter_cost(filter: &Operation<LocalField, Argument>) -> usize {
+    let operation_cost = match filter {
+        Operation::IsNull(..) | Operation::IsNotNull(..) => 0,
+        Operation::Equals(..)
+        | Operation::NotEquals(..)
+        | Operation::LessThan(..)
+        | Operation::LessThanOrEqual(..)
+        | Operation::GreaterThan(..)
+        | Operation::GreaterThanOrEqual(..) => 1,
+        Operation::Between(..)
+        | Operation::NotBetween(..)
+        | Operation::Contains(..)
+        | Operation::NotContains(..)
+        | Operation::OneOf(..)
+        | Operation::NotOneOf(..)
+        | Operation::HasPrefix(..)
+        | Operation::NotHasPrefix(..)
+        | Operation::HasSuffix(..)
+        | Operation::NotHasSuffix(..)
+        | Operation::HasSubstring(..)
+        | Operation::NotHasSubstring(..) => 2,
+        Operation::EqualsIgnoreCase(..)
+        | Operation::NotEqualsIgnoreCase(..)
+        | Operation::HasPrefixIgnoreCase(..)
+        | Operation::NotHasPrefixIgnoreCase(..)
+        | Operation::HasSuffixIgnoreCase(..)
+        | Operation::NotHasSuffixIgnoreCase(..)
+        | Operation::HasSubstringIgnoreCase(..)
+        | Operation::NotHasSubstringIgnoreCase(..) => 3,
+        Operation::RegexMatches(..)
+        | Operation::NotRegexMatches(..)
+        | Operation::RegexMatchesIgnoreCase(..)
+        | Operation::NotRegexMatchesIgnoreCase(..) => 4,
+    };
+    operation_cost + filter.left().transforms.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{
+        frontend::parse,
+        interpreter::execution::interpret_ir,
+        ir::{FieldValue, IRQuery, IndexedQuery, Operation},
+        numbers_interpreter::NumbersAdapter,
+        schema::Schema,
+    };
+
+    use super::{
+        OptimizationPass, Optimizer, RemoveRedundantCoercions, RemoveUnusedFolds, ReorderFilters,
+    };
+
+    fn numbers_schema() -> Schema {
+        Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema")
+    }
+
+    /// Optimizes the query, checking that the optimized query produces the same results.
+    fn optimize(
+        query: &str,
+        arguments: BTreeMap<Arc<str>, FieldValue>,
+        optimizer: &Optimizer,
+    ) -> (Arc<IndexedQuery>, Arc<IndexedQuery>) {
+        optimize_with_schema(query, arguments, optimizer, &numbers_schema())
+    }
+
+    /// Like [`optimize`], but optimizes against a different schema than the one
+    /// the query was compiled against.
+    fn optimize_with_schema(
+        query: &str,
+        arguments: BTreeMap<Arc<str>, FieldValue>,
+        optimizer: &Optimizer,
+        schema: &Schema,
+    ) -> (Arc<IndexedQuery>, Arc<IndexedQuery>) {
+        let query = parse(&numbers_schema(), query).expect("valid query");
+        let optimized = optimizer.optimize(&query, schema).expect("valid optimized query");
+
+        let arguments = Arc::new(arguments);
+        let run = |query: &Arc<IndexedQuery>| -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+            let adapter = Arc::new(NumbersAdapter::new());
+            interpret_ir(adapter, query.clone(), arguments.clone())
+                .expect("valid arguments")
+                .collect()
+        };
+        let expected = run(&query);
+        assert!(!expected.is_empty());
+        assert_eq!(expected, run(&optimized));
+
+        (query, optimized)
+    }
+
+    #[test]
+    fn unused_folds_are_removed() {
+        let query = r#"
+{
+    Number(max: 12) {
+        value @output
+
+        multiple(max: 3) @fold {
+            value @filter(op: ">", value: ["$min"])
+        }
+        multiple(max: 3) @fold @transform(op: "count") @filter(op: ">=", value: ["$min"]) {
+            value
+        }
+        multiple(max: 3) @fold @transform(op: "count") @tag(name: "count") {
+            value
+        }
+        successor {
+            value @filter(op: ">", value: ["%count"])
+        }
+        predecessor @fold {
+            value @output(name: "predecessors")
+        }
+    }
+}"#;
+        let arguments = btreemap! { "min".into() => 0.into() };
+        let optimizer = Optimizer::without_passes().with_pass(RemoveUnusedFolds);
+        let (original, optimized) = optimize(query, arguments, &optimizer);
+
+        let original_folds = &original.ir_query.root_component.folds;
+        let optimized_folds = &optimized.ir_query.root_component.folds;
+        assert_eq!(4, original_folds.len());
+        assert_eq!(3, optimized_folds.len());
+
+        let unused_eid = original_folds.keys().next().expect("no folds");
+        assert!(!optimized_folds.contains_key(unused_eid));
+    }
+
+    #[test]
+    fn filters_are_reordered_by_cost() {
+        let query = r#"
+{
+    Number(max: 20) {
+        name @filter(op: "regex", value: ["$pattern"])
+             @filter(op: "is_not_null")
+             @output
+        value @filter(op: "one_of", value: ["$values"])
+              @filter(op: "<", value: ["$max"])
+              @output
+    }
+}"#;
+        let arguments = btreemap! {
+            "pattern".into() => "e".into(),
+            "values".into() => vec![2, 3, 7, 15].into(),
+            "max".into() => 10.into(),
+        };
+        let optimizer = Optimizer::without_passes().with_pass(ReorderFilters);
+        let (_, optimized) = optimize(query, arguments, &optimizer);
+
+        let component = &optimized.ir_query.root_component;
+        let filters = &component.vertices[&component.root].filters;
+        assert!(matches!(
+            filters.as_slice(),
+            [
+                Operation::IsNotNull(..),
+                Operation::LessThan(..),
+                Operation::OneOf(..),
+                Operation::RegexMatches(..),
+            ]
+        ));
+    }
+
+    #[test]
+    fn redundant_coercions_are_removed() {
+        // Queries can't contain coercions that always succeed, but a newer version
+        // of their schema may narrow the types of edges they use, making them redundant.
+        let schema_text = include_str!("../../test_data/schemas/numbers.graphql");
+        let narrowed_schema_text =
+            schema_text.replace("max: Int!): [Number!]", "max: Int!): [Prime!]");
+        let type_definition = "type Prime implements Number & Named {";
+        let (before, after) = narrowed_schema_text.split_once(type_definition).unwrap();
+        let after = after.replacen("predecessor: Number", "predecessor: Prime", 1).replacen(
+            "successor: Number!",
+            "successor: Prime!",
+            1,
+        );
+        let narrowed_schema =
+            Schema::parse(format!("{before}{type_definition}{after}")).expect("valid schema");
+
+        let query = r#"
+{
+    Number(min: 2, max: 2) {
+        ... on Prime {
+            two: value @output
+
+            successor {
+                ... on Prime {
+                    three: value @output
+
+                    predecessor @fold {
+                        ... on Prime {
+                            predecessors: value @output
+                        }
+                    }
+                    multiple(max: 3) @fold {
+                        successor {
+                            ... on Prime {
+                                primes_after_multiples: value @output
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}"#;
+        let optimizer = Optimizer::without_passes().with_pass(RemoveRedundantCoercions);
+        let (original, optimized) =
+            optimize_with_schema(query, BTreeMap::new(), &optimizer, &narrowed_schema);
+
+        let coercions = |query: &IndexedQuery| -> usize {
+            let vertices = query.vids.iter().map(|(vid, component)| &component.vertices[vid]);
+            vertices.filter(|vertex| vertex.coerced_from_type.is_some()).count()
+        };
+        assert_eq!(4, coercions(&original));
+        assert_eq!(1, coercions(&optimized));
+
+        // The query doesn't contain any redundant coercions under its original schema.
+        let (_, unchanged) = optimize(query, BTreeMap::new(), &optimizer);
+        assert_eq!(original.ir_query, unchanged.ir_query);
+    }
+
+    struct LoggingPass {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl OptimizationPass for LoggingPass {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn run(&self, _query: &mut IRQuery, _schema: &Schema) {
+            self.log.lock().unwrap().push(self.name);
+        }
+    }
+
+    #[test]
+    fn custom_passes_run_in_order() {
+        let log: Arc<Mutex<Vec<&'static str>>> = Default::default();
+        let optimizer = Optimizer::new()
+            .with_pass(LoggingPass { name: "first", log: log.clone() })
+            .with_pass(LoggingPass { name: "second", log: log.clone() });
+        assert_eq!(
+            vec![
+                "remove_unused_folds",
+                "remove_redundant_coercions",
+                "reorder_filters",
+                "first",
+                "second"
+            ],
+            optimizer.passes().collect::<Vec<_>>(),
+        );
+
+        let query = "{ Number(max: 3) { value @output } }";
+        optimize(query, BTreeMap::new(), &optimizer);
+        assert_eq!(vec!["first", "second"], *log.lock().unwrap());
+    }
+
+    struct RemoveEverything;
+
+    impl OptimizationPass for RemoveEverything {
+        fn name(&self) -> &str {
+            "remove_everything"
+        }
+
+        fn run(&self, query: &mut IRQuery, _schema: &Schema) {
+            Arc::make_mut(&mut query.root_component).vertices.clear();
+        }
+    }
+
+    #[test]
+    fn invalid_optimized_queries_are_errors() {
+        let schema = numbers_schema();
+        let query = parse(&schema, "{ Number(max: 3) { value @output } }").expect("valid query");
+        let optimizer = Optimizer::without_passes().with_pass(RemoveEverything);
+        optimizer.optimize(&query, &schema).expect_err("invalid IR was accepted");
+    }
+}
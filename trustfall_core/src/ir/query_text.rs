@@ -0,0 +1,585 @@
+//! Printing a query's intermediate representation as query text.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Debug, Write},
+};
+
+use super::{
+    get_typename_meta_field, Argument, ContextField, Eid, FieldRef, FieldValue, FoldSpecificField,
+    FoldSpecificFieldKind, IREdge, IRFold, IRQuery, IRQueryComponent, IndexedQuery, Operation,
+    OrderDirection, ValueTransform, Vid,
+};
+
+const INDENT: &str = "    ";
+
+impl IRQuery {
+    /// Query text which parses into this same intermediate representation.
+    ///
+    /// The text is laid out in the canonical style of
+    /// [`format_query`](crate::graphql_query::format_query). Each output, tag, and filter
+    /// is applied to a separate copy of its property, and all outputs are explicitly named.
+    /// Edge parameters are all written out, including ones that have default values.
+    /// Tag names aren't part of the intermediate representation, so tags are named
+    /// after the values they hold. Variables only need to be declared if they have
+    /// a default value, so other variables are left undeclared.
+    pub fn to_query_text(&self) -> String {
+        QueryTextWriter::new(self).write_query()
+    }
+}
+
+impl IndexedQuery {
+    /// Query text which parses into this same query. See [`IRQuery::to_query_text`].
+    pub fn to_query_text(&self) -> String {
+        self.ir_query.to_query_text()
+    }
+}
+
+/// An edge or a fold, whichever the edge at some [`Eid`] is.
+enum Neighbor<'a> {
+    Edge(&'a IREdge),
+    Fold(&'a IRFold),
+}
+
+struct QueryTextWriter<'a> {
+    query: &'a IRQuery,
+    tag_names: BTreeMap<&'a FieldRef, String>,
+    orderings: BTreeMap<&'a str, OrderDirection>,
+    /// The index in the query's `order_by` of the next ordered output to write.
+    next_ordering: usize,
+    written_orderings: BTreeSet<usize>,
+    output: String,
+}
+
+impl<'a> QueryTextWriter<'a> {
+    fn new(query: &'a IRQuery) -> Self {
+        let mut tags = vec![];
+        collect_component_tags(&query.root_component, &mut tags);
+
+        let mut used_names = BTreeSet::new();
+        let mut tag_names = BTreeMap::new();
+        for tag in tags {
+            if tag_names.contains_key(tag) {
+                continue;
+            }
+            let base_name = tag_base_name(tag);
+            let name = (1..)
+                .map(|i| if i == 1 { base_name.clone() } else { format!("{base_name}_{i}") })
+                .find(|name| !used_names.contains(name))
+                .expect("no available tag name");
+            used_names.insert(name.clone());
+            tag_names.insert(tag, name);
+        }
+
+        let orderings = query
+            .order_by
+            .iter()
+            .map(|ordering| (ordering.output_name.as_ref(), ordering.direction))
+            .collect();
+
+        Self {
+            query,
+            tag_names,
+            orderings,
+            next_ordering: 0,
+            written_orderings: BTreeSet::new(),
+            output: String::new(),
+        }
+    }
+
+    fn write_query(mut self) -> String {
+        let query = self.query;
+        if !query.variable_defaults.is_empty() {
+            let variables: Vec<_> = query
+                .variable_defaults
+                .iter()
+                .map(|(name, value)| {
+                    format!("${name}: {} = {}", query.variables[name], value_text(value))
+                })
+                .collect();
+            write!(self.output, "query({}) ", variables.join(", ")).expect("write failed");
+        }
+        self.output.push_str("{\n");
+
+        let mut root_line =
+            format!("{}{}", query.root_name, self.parameters_text(&query.root_parameters));
+        if let Some(limit) = query.limit {
+            write!(root_line, " @limit(count: {limit})").expect("write failed");
+        }
+        if let Some(offset) = query.offset {
+            write!(root_line, " @offset(count: {offset})").expect("write failed");
+        }
+        if query.dedupe {
+            root_line.push_str(" @dedupe");
+        }
+        self.write_neighbor_block(
+            root_line,
+            &query.root_component,
+            None,
+            query.root_component.root,
+            1,
+        );
+
+        self.output.push_str("}\n");
+        self.output
+    }
+
+    fn write_line(&mut self, depth: usize, line: &str) {
+        self.output.push_str(&INDENT.repeat(depth));
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Write the given edge's line, followed by the vertex the edge points to.
+    fn write_neighbor_block(
+        &mut self,
+        edge_line: String,
+        component: &'a IRQueryComponent,
+        fold: Option<&'a IRFold>,
+        vid: Vid,
+        depth: usize,
+    ) {
+        self.write_line(depth, &format!("{edge_line} {{"));
+        let vertex = &component.vertices[&vid];
+        if vertex.coerced_from_type.is_some() {
+            self.write_line(depth + 1, &format!("... on {} {{", vertex.type_name));
+            self.write_vertex(component, fold, vid, depth + 2);
+            self.write_line(depth + 1, "}");
+        } else {
+            self.write_vertex(component, fold, vid, depth + 1);
+        }
+        self.write_line(depth, "}");
+    }
+
+    /// Write the contents of the vertex, which is part of the given component.
+    ///
+    /// If the component is the contents of a fold, aggregations of the vertex's properties
+    /// are part of that fold.
+    fn write_vertex(
+        &mut self,
+        component: &'a IRQueryComponent,
+        fold: Option<&'a IRFold>,
+        vid: Vid,
+        depth: usize,
+    ) {
+        let vertex = &component.vertices[&vid];
+        let mut lines = vec![];
+
+        // Tags come first, so that they are defined before any use at this same vertex.
+        for (tag, name) in &self.tag_names {
+            if let FieldRef::ContextField(field) = tag {
+                if field.vertex_id == vid {
+                    lines.push(format!(
+                        "{}{} @tag(name: {})",
+                        field.field_name,
+                        self.transforms_text(&field.transforms),
+                        quoted(name),
+                    ));
+                }
+            }
+        }
+        for filter in &vertex.filters {
+            let field = filter.left();
+            lines.push(format!(
+                "{}{} {}",
+                field.field_name,
+                self.transforms_text(&field.transforms),
+                self.filter_text(filter),
+            ));
+        }
+        for (name, field) in &component.outputs {
+            if field.vertex_id == vid && !self.orderings.contains_key(name.as_ref()) {
+                lines.push(self.output_line(name, field));
+            }
+        }
+
+        if let Some(fold) = fold {
+            let is_aggregated_here = |kind: &FoldSpecificFieldKind| {
+                kind.aggregated_field().map(|f| f.vertex_id) == Some(vid)
+            };
+            for filter in
+                fold.post_filters.iter().filter(|filter| is_aggregated_here(filter.left()))
+            {
+                lines.push(format!(
+                    "{} {}",
+                    self.aggregation_text(filter.left()),
+                    self.filter_text(filter)
+                ));
+            }
+            for (name, kind) in
+                fold.fold_specific_outputs.iter().filter(|(_, kind)| is_aggregated_here(kind))
+            {
+                lines.push(format!("{} {}", self.aggregation_text(kind), self.output_text(name)));
+            }
+            for (tag, name) in &self.tag_names {
+                if let FieldRef::FoldSpecificField(FoldSpecificField { fold_eid, kind, .. }) = tag {
+                    if *fold_eid == fold.eid && is_aggregated_here(kind) {
+                        lines.push(format!(
+                            "{} @tag(name: {})",
+                            self.aggregation_text(kind),
+                            quoted(name)
+                        ));
+                    }
+                }
+            }
+        }
+
+        let start = self.output.len();
+        for line in lines {
+            self.write_line(depth, &line);
+        }
+
+        let neighbors: BTreeMap<Eid, Neighbor<'a>> = component
+            .edges
+            .values()
+            .filter(|edge| edge.from_vid == vid)
+            .map(|edge| (edge.eid, Neighbor::Edge(edge)))
+            .chain(
+                component
+                    .folds
+                    .values()
+                    .filter(|fold| fold.from_vid == vid)
+                    .map(|fold| (fold.eid, Neighbor::Fold(fold))),
+            )
+            .collect();
+        for neighbor in neighbors.into_values() {
+            self.write_next_orderings(component, vid, depth);
+            match neighbor {
+                Neighbor::Edge(edge) => {
+                    let line = self.edge_line(component, edge);
+                    self.write_neighbor_block(line, component, fold, edge.to_vid, depth);
+                }
+                Neighbor::Fold(fold) => {
+                    let line = self.fold_line(fold);
+                    self.write_neighbor_block(
+                        line,
+                        &fold.component,
+                        Some(fold),
+                        fold.to_vid,
+                        depth,
+                    );
+                }
+            }
+        }
+        self.write_next_orderings(component, vid, depth);
+
+        // Any remaining ordered outputs at this vertex can't be written in order,
+        // since the query text of some other vertex must come between them.
+        for (index, ordering) in self.query.order_by.iter().enumerate() {
+            let field = component.outputs.get(&ordering.output_name);
+            if index >= self.next_ordering
+                && field.is_some_and(|field| field.vertex_id == vid)
+                && self.written_orderings.insert(index)
+            {
+                let line = self.output_line(&ordering.output_name, field.expect("no field"));
+                self.write_line(depth, &line);
+            }
+        }
+
+        // Selections can't be empty, so select a property without using it.
+        if self.output.len() == start {
+            self.write_line(depth, get_typename_meta_field());
+        }
+    }
+
+    /// Write the ordered outputs at the given vertex whose turn it is, if any.
+    ///
+    /// Earlier `@order` directives take precedence over later ones,
+    /// so ordered outputs are written in the order of their significance.
+    fn write_next_orderings(&mut self, component: &IRQueryComponent, vid: Vid, depth: usize) {
+        while let Some(ordering) = self.query.order_by.get(self.next_ordering) {
+            if self.written_orderings.contains(&self.next_ordering) {
+                self.next_ordering += 1;
+                continue;
+            }
+            match component.outputs.get(&ordering.output_name) {
+                Some(field) if field.vertex_id == vid => {
+                    let line = self.output_line(&ordering.output_name, field);
+                    self.write_line(depth, &line);
+                    self.written_orderings.insert(self.next_ordering);
+                    self.next_ordering += 1;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn output_line(&self, name: &str, field: &ContextField) -> String {
+        format!(
+            "{}{} {}",
+            field.field_name,
+            self.transforms_text(&field.transforms),
+            self.output_text(name),
+        )
+    }
+
+    fn edge_line(&self, component: &IRQueryComponent, edge: &IREdge) -> String {
+        let mut line = format!("{}{}", edge.edge_name, self.parameters_text(&edge.parameters));
+        if edge.optional {
+            let group = component.optional_groups.iter().find(|(_, eids)| eids.contains(&edge.eid));
+            match group {
+                Some((group, _)) => {
+                    write!(line, " @optional(group: {})", quoted(group)).expect("write failed")
+                }
+                None => line.push_str(" @optional"),
+            }
+        }
+        if let Some(recursive) = &edge.recursive {
+            let mut arguments = vec![];
+            if let Some(depth) = recursive.depth {
+                arguments.push(format!("depth: {depth}"));
+            }
+            if recursive.dedupe {
+                arguments.push("dedupe: true".to_string());
+            }
+            if arguments.is_empty() {
+                line.push_str(" @recurse");
+            } else {
+                write!(line, " @recurse({})", arguments.join(", ")).expect("write failed");
+            }
+        }
+        for filter in &edge.filters {
+            write!(line, " {}", self.filter_text(filter)).expect("write failed");
+        }
+        line
+    }
+
+    fn fold_line(&self, fold: &'a IRFold) -> String {
+        let mut line = format!("{}{}", fold.edge_name, self.parameters_text(&fold.parameters));
+        line.push_str(if fold.absent { " @absent" } else { " @fold" });
+
+        // The fold's count, if used, is the value of a `@transform` on the folded edge.
+        // Any value transforms applied to it follow in a single chain of `@transform`s,
+        // each followed by the directives using the value it produces.
+        let mut directives: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        let mut chain: &[ValueTransform] = &[];
+        let mut add_directive = |kind: &'a FoldSpecificFieldKind, directive: String| {
+            let transforms = kind.value_transforms();
+            if transforms.len() > chain.len() {
+                chain = transforms;
+            }
+            directives.entry(transforms.len()).or_default().push(directive);
+        };
+        for filter in &fold.post_filters {
+            if filter.left().aggregated_field().is_none() {
+                add_directive(filter.left(), self.filter_text(filter));
+            }
+        }
+        for (name, kind) in &fold.fold_specific_outputs {
+            if kind.aggregated_field().is_none() {
+                add_directive(kind, self.output_text(name));
+            }
+        }
+        for (tag, name) in &self.tag_names {
+            if let FieldRef::FoldSpecificField(FoldSpecificField { fold_eid, kind, .. }) = tag {
+                if *fold_eid == fold.eid && kind.aggregated_field().is_none() {
+                    add_directive(kind, format!("@tag(name: {})", quoted(name)));
+                }
+            }
+        }
+        if !directives.is_empty() {
+            line.push_str(" @transform(op: \"count\")");
+            for length in 0..=chain.len() {
+                if length > 0 {
+                    line.push_str(&self.transforms_text(&chain[length - 1..length]));
+                }
+                for directive in directives.get(&length).into_iter().flatten() {
+                    write!(line, " {directive}").expect("write failed");
+                }
+            }
+        }
+        line
+    }
+
+    /// The aggregated property, followed by the transforms that produce the aggregated value
+    /// and then by any value transforms applied to the aggregated value.
+    fn aggregation_text(&self, kind: &FoldSpecificFieldKind) -> String {
+        let field = kind.aggregated_field().expect("not an aggregation");
+        format!(
+            "{}{} @transform(op: {}){}",
+            field.field_name,
+            self.transforms_text(&field.transforms),
+            quoted(kind.untransformed().transform_suffix()),
+            self.transforms_text(kind.value_transforms()),
+        )
+    }
+
+    fn transforms_text(&self, transforms: &[ValueTransform]) -> String {
+        let mut text = String::new();
+        for transform in transforms {
+            write!(text, " @transform(op: {}", quoted(transform.op_name())).expect("write failed");
+            if let Some(operand) = transform.operand() {
+                write!(text, ", value: [{}]", quoted(&self.argument_text(operand)))
+                    .expect("write failed");
+            }
+            text.push(')');
+        }
+        text
+    }
+
+    fn filter_text<LeftT: Debug + Clone + PartialEq + Eq>(
+        &self,
+        filter: &Operation<LeftT, Argument>,
+    ) -> String {
+        let arguments: Vec<_> =
+            filter.arguments().map(|argument| quoted(&self.argument_text(argument))).collect();
+        if arguments.is_empty() {
+            format!("@filter(op: {})", quoted(filter.operation_name()))
+        } else {
+            format!(
+                "@filter(op: {}, value: [{}])",
+                quoted(filter.operation_name()),
+                arguments.join(", "),
+            )
+        }
+    }
+
+    fn output_text(&self, name: &str) -> String {
+        let mut text = format!("@output(name: {})", quoted(name));
+        if let Some(direction) = self.orderings.get(name) {
+            let direction = match direction {
+                OrderDirection::Ascending => "asc",
+                OrderDirection::Descending => "desc",
+            };
+            write!(text, " @order(direction: {})", quoted(direction)).expect("write failed");
+        }
+        text
+    }
+
+    fn argument_text(&self, argument: &Argument) -> String {
+        match argument {
+            Argument::Variable(variable) => format!("${}", variable.variable_name),
+            Argument::Tag(field) => format!("%{}", self.tag_names[field]),
+        }
+    }
+
+    fn parameters_text(&self, parameters: &super::EdgeParameters) -> String {
+        if parameters.is_empty() {
+            return String::new();
+        }
+        let mut arguments: BTreeMap<_, _> =
+            parameters.iter().map(|(name, value)| (name, value_text(value))).collect();
+        for (name, variable) in parameters.variables() {
+            arguments.insert(name, format!("${}", variable.variable_name));
+        }
+        for (name, tag) in parameters.tags() {
+            arguments.insert(name, quoted(&format!("%{}", self.tag_names[tag])));
+        }
+        let arguments: Vec<_> =
+            arguments.into_iter().map(|(name, text)| format!("{name}: {text}")).collect();
+        format!("({})", arguments.join(", "))
+    }
+}
+
+/// Collect the fields used as tags in the component and its subcomponents,
+/// in the order in which they are used.
+fn collect_component_tags<'a>(component: &'a IRQueryComponent, tags: &mut Vec<&'a FieldRef>) {
+    for vertex in component.vertices.values() {
+        for filter in &vertex.filters {
+            collect_transform_tags(&filter.left().transforms, tags);
+            for argument in filter.arguments() {
+                collect_argument_tags(argument, tags);
+            }
+        }
+    }
+    for output in component.outputs.values() {
+        collect_transform_tags(&output.transforms, tags);
+    }
+    for edge in component.edges.values() {
+        edge.parameters.tags().for_each(|(_, field)| collect_field_tags(field, tags));
+    }
+    for fold in component.folds.values() {
+        fold.parameters.tags().for_each(|(_, field)| collect_field_tags(field, tags));
+        collect_component_tags(&fold.component, tags);
+        for filter in &fold.post_filters {
+            collect_aggregation_tags(filter.left(), tags);
+            for argument in filter.arguments() {
+                collect_argument_tags(argument, tags);
+            }
+        }
+        for kind in fold.fold_specific_outputs.values() {
+            collect_aggregation_tags(kind, tags);
+        }
+    }
+}
+
+fn collect_aggregation_tags<'a>(kind: &'a FoldSpecificFieldKind, tags: &mut Vec<&'a FieldRef>) {
+    if let Some(field) = kind.aggregated_field() {
+        collect_transform_tags(&field.transforms, tags);
+    }
+    collect_transform_tags(kind.value_transforms(), tags);
+}
+
+fn collect_transform_tags<'a>(transforms: &'a [ValueTransform], tags: &mut Vec<&'a FieldRef>) {
+    for operand in transforms.iter().filter_map(|transform| transform.operand()) {
+        collect_argument_tags(operand, tags);
+    }
+}
+
+fn collect_argument_tags<'a>(argument: &'a Argument, tags: &mut Vec<&'a FieldRef>) {
+    if let Argument::Tag(field) = argument {
+        collect_field_tags(field, tags);
+    }
+}
+
+fn collect_field_tags<'a>(field: &'a FieldRef, tags: &mut Vec<&'a FieldRef>) {
+    // The tagged value may itself be computed using other tags.
+    match field {
+        FieldRef::ContextField(field) => collect_transform_tags(&field.transforms, tags),
+        FieldRef::FoldSpecificField(field) => collect_aggregation_tags(&field.kind, tags),
+    }
+    tags.push(field);
+}
+
+/// A tag name describing the tagged value, in the style of implicit output names.
+fn tag_base_name(field: &FieldRef) -> String {
+    let (field_name, suffixes): (Option<&str>, Vec<&str>) = match field {
+        FieldRef::ContextField(field) => {
+            (Some(&field.field_name), field.transforms.iter().map(|t| t.output_suffix()).collect())
+        }
+        FieldRef::FoldSpecificField(field) => {
+            let aggregated = field.kind.aggregated_field();
+            let suffixes = aggregated
+                .iter()
+                .flat_map(|aggregated| aggregated.transforms.iter())
+                .map(|t| t.output_suffix())
+                .chain(std::iter::once(field.kind.untransformed().transform_suffix()))
+                .chain(field.kind.value_transforms().iter().map(|t| t.output_suffix()))
+                .collect();
+            (aggregated.map(|aggregated| aggregated.field_name.as_ref()), suffixes)
+        }
+    };
+
+    let mut name =
+        field_name.map(|name| name.trim_start_matches('_')).unwrap_or_default().to_string();
+    for suffix in suffixes {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.push_str(suffix);
+    }
+    name
+}
+
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).expect("failed to quote string")
+}
+
+/// The value as a GraphQL literal, which parses back into an equal value of the same type.
+fn value_text(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Null => "null".to_string(),
+        FieldValue::Int64(v) => v.to_string(),
+        FieldValue::Uint64(v) => v.to_string(),
+        FieldValue::Float64(v) => format!("{v:?}"),
+        FieldValue::String(v) => quoted(v),
+        FieldValue::Boolean(v) => v.to_string(),
+        FieldValue::Enum(v) => v.to_string(),
+        FieldValue::List(values) => {
+            let values: Vec<_> = values.iter().map(value_text).collect();
+            format!("[{}]", values.join(", "))
+        }
+        FieldValue::DateTime(v) => quoted(&v.to_string()),
+        #[cfg(feature = "num-bigint")]
+        FieldValue::BigInt(v) => quoted(&v.to_string()),
+    }
+}
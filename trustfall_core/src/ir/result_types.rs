@@ -0,0 +1,97 @@
+//! Describing the shape of query results in other type systems.
+use std::fmt::Write;
+
+use serde_json::{json, Map, Value};
+
+use super::{QueryOutputs, Type};
+
+impl QueryOutputs {
+    /// A [JSON Schema](https://json-schema.org/) describing each of the query's results,
+    /// when serialized to JSON as a map of [`TransparentValue`](super::TransparentValue).
+    ///
+    /// `DateTime` and `BigInt` values are serialized as strings. Values of other custom scalar
+    /// types may be serialized in any way, so the schema doesn't constrain them.
+    pub fn result_json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .iter()
+            .map(|output| (output.name.to_string(), json_schema_for_type(&output.value_type)))
+            .collect();
+        let required: Vec<&str> = self.names().map(|name| name.as_ref()).collect();
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    /// TypeScript source code for an interface named `interface_name`,
+    /// describing each of the query's results in the same way as [`Self::result_json_schema`].
+    ///
+    /// Values of custom scalar types other than `DateTime` and `BigInt` have the `unknown` type.
+    pub fn result_typescript_interface(&self, interface_name: &str) -> String {
+        let mut interface = format!("export interface {interface_name} {{\n");
+        for output in self {
+            let name = if is_typescript_identifier(&output.name) {
+                output.name.to_string()
+            } else {
+                serde_json::to_string(output.name.as_ref()).expect("failed to quote output name")
+            };
+            writeln!(interface, "  {name}: {};", typescript_type(&output.value_type))
+                .expect("failed to write interface");
+        }
+        interface.push_str("}\n");
+        interface
+    }
+}
+
+fn json_schema_for_type(value_type: &Type) -> Value {
+    let (type_name, mut schema) = match value_type.as_list() {
+        Some(element_type) => ("array", json!({ "items": json_schema_for_type(&element_type) })),
+        None => match value_type.base_type() {
+            "Int" => ("integer", json!({})),
+            "Float" => ("number", json!({})),
+            "String" | "ID" | "BigInt" => ("string", json!({})),
+            "DateTime" => ("string", json!({ "format": "date-time" })),
+            "Boolean" => ("boolean", json!({})),
+            _ => return json!({}),
+        },
+    };
+
+    schema["type"] =
+        if value_type.nullable() { json!([type_name, "null"]) } else { json!(type_name) };
+    schema
+}
+
+fn typescript_type(value_type: &Type) -> String {
+    let non_null = match value_type.as_list() {
+        Some(element_type) => {
+            let element = typescript_type(&element_type);
+            if element_type.nullable() {
+                format!("({element})[]")
+            } else {
+                format!("{element}[]")
+            }
+        }
+        None => match value_type.base_type() {
+            "Int" | "Float" => "number".to_string(),
+            "String" | "ID" | "BigInt" | "DateTime" => "string".to_string(),
+            "Boolean" => "boolean".to_string(),
+            _ => return "unknown".to_string(),
+        },
+    };
+
+    if value_type.nullable() {
+        format!("{non_null} | null")
+    } else {
+        non_null
+    }
+}
+
+fn is_typescript_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
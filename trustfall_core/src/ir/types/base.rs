@@ -352,8 +352,8 @@ looping through all list types: {ty:?} {base:?}"
                 self.nullable()
             }
             FieldValue::Int64(_) | FieldValue::Uint64(_) => {
-                // This is a valid value only if the type is Int, ignoring nullability.
-                !self.is_list() && self.base_type() == "Int"
+                // This is a valid value only if the type is Int or BigInt, ignoring nullability.
+                !self.is_list() && matches!(self.base_type(), "Int" | "BigInt")
             }
             FieldValue::Float64(_) => {
                 // This is a valid value only if the type is Float, ignoring nullability.
@@ -367,6 +367,15 @@ looping through all list types: {ty:?} {base:?}"
                 // This is a valid value only if the type is Boolean, ignoring nullability.
                 !self.is_list() && self.base_type() == "Boolean"
             }
+            FieldValue::DateTime(_) => {
+                // This is a valid value only if the type is DateTime, ignoring nullability.
+                !self.is_list() && self.base_type() == "DateTime"
+            }
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(_) => {
+                // This is a valid value only if the type is BigInt, ignoring nullability.
+                !self.is_list() && self.base_type() == "BigInt"
+            }
             FieldValue::List(contents) => {
                 // This is a valid value only if the type is a list, and all the inner elements
                 // are valid instances of the type inside the list.
@@ -382,6 +391,65 @@ looping through all list types: {ty:?} {base:?}"
         }
     }
 
+    /// Coerce a value written in a query or schema, such as an edge parameter's value,
+    /// to this type. Returns `None` if the value is not valid for this type.
+    ///
+    /// Follows the GraphQL rules for coercing input values: in addition to all values
+    /// for which [`Type::is_valid_value`] returns `true`, a single non-null value is accepted
+    /// where a list is expected, and becomes a list containing just that value.
+    /// Items of a list value are not wrapped this way, so `[1, 2]` is not a valid `[[Int]]`.
+    ///
+    /// Since queries and schemas have no syntax for date-time values, strings holding
+    /// RFC 3339 timestamps are also accepted where a `DateTime` is expected.
+    /// Similarly, with the `num-bigint` feature, strings of decimal digits are accepted
+    /// where a `BigInt` is expected, since integer literals must fit in 64 bits.
+    /// ```rust
+    /// use trustfall_core::ir::{FieldValue, Type};
+    ///
+    /// let ty = Type::parse("[[String!]]!").unwrap();
+    /// let value = FieldValue::String("abc".into());
+    /// let expected = FieldValue::List([
+    ///     FieldValue::List([value.clone()].as_slice().into()),
+    /// ].as_slice().into());
+    /// assert_eq!(Some(expected), ty.coerce_input_value(&value));
+    ///
+    /// let nested_value = FieldValue::List([value].as_slice().into());
+    /// assert_eq!(None, ty.coerce_input_value(&nested_value));
+    /// ```
+    pub fn coerce_input_value(&self, value: &FieldValue) -> Option<FieldValue> {
+        self.coerce_input_value_inner(value, true)
+    }
+
+    fn coerce_input_value_inner(
+        &self,
+        value: &FieldValue,
+        allow_list_wrapping: bool,
+    ) -> Option<FieldValue> {
+        match value {
+            FieldValue::Null => self.nullable().then_some(FieldValue::Null),
+            FieldValue::List(items) => {
+                let item_type = self.as_list()?;
+                items.iter().map(|item| item_type.coerce_input_value_inner(item, false)).collect()
+            }
+            // Enums are not currently supported, so no type accepts them.
+            FieldValue::Enum(_) => None,
+            FieldValue::String(s) if !self.is_list() && self.base_type() == "DateTime" => {
+                s.parse().ok().map(FieldValue::DateTime)
+            }
+            #[cfg(feature = "num-bigint")]
+            FieldValue::String(s) if !self.is_list() && self.base_type() == "BigInt" => {
+                s.parse::<num_bigint::BigInt>().ok().map(FieldValue::from)
+            }
+            _ => match self.as_list() {
+                Some(item_type) if allow_list_wrapping => item_type
+                    .coerce_input_value_inner(value, true)
+                    .map(|item| FieldValue::List([item].as_slice().into())),
+                Some(_) => None,
+                None => self.is_valid_value(value).then(|| value.clone()),
+            },
+        }
+    }
+
     /// Returns `true` if values of this type can be compared using operators like `<`.
     ///
     /// In Rust terms, this checks for `PartialOrd` on this `Type`.
@@ -389,7 +457,7 @@ looping through all list types: {ty:?} {base:?}"
     /// Lists (including nested lists) are orderable if the type they contain is orderable.
     /// Lists use lexicographic ordering, i.e. `[1, 2, 3] < [3]`.
     pub(crate) fn is_orderable(&self) -> bool {
-        matches!(self.base_type(), "Int" | "Float" | "String")
+        matches!(self.base_type(), "Int" | "Float" | "String" | "DateTime" | "BigInt")
     }
 
     /// Check for scalar-only subtyping.
@@ -500,7 +568,7 @@ impl<'de> Deserialize<'de> for Type {
 mod test {
     use itertools::Itertools;
 
-    use crate::ir::{FieldValue, Type};
+    use crate::ir::{DateTime, FieldValue, Type};
 
     use super::Modifiers;
 
@@ -709,6 +777,56 @@ mod test {
         }
     }
 
+    #[test]
+    fn datetime_values_are_valid_only_for_datetime_type_regardless_of_nullability() {
+        let matching_types = [Type::parse("DateTime").unwrap(), Type::parse("DateTime!").unwrap()];
+        let non_matching_types = [
+            Type::parse("String").unwrap(),
+            Type::parse("Int").unwrap(),
+            Type::parse("[DateTime!]").unwrap(),
+            Type::parse("[[DateTime]!]").unwrap(),
+        ];
+        let values = [
+            FieldValue::DateTime(DateTime::UNIX_EPOCH),
+            FieldValue::DateTime(DateTime::from_unix_seconds(-86_400)),
+        ];
+
+        for value in &values {
+            for matching_type in &matching_types {
+                assert!(matching_type.is_valid_value(value), "{matching_type} {value:?}",);
+            }
+            for non_matching_type in &non_matching_types {
+                assert!(!non_matching_type.is_valid_value(value), "{non_matching_type} {value:?}",);
+            }
+        }
+
+        // Strings are not date-time values, even if they hold a valid timestamp.
+        let string = FieldValue::String("1970-01-01T00:00:00Z".into());
+        for datetime_type in &matching_types {
+            assert!(!datetime_type.is_valid_value(&string), "{datetime_type}");
+        }
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn integer_values_of_any_size_are_valid_for_bigint_type() {
+        let matching_types = [Type::parse("BigInt").unwrap(), Type::parse("BigInt!").unwrap()];
+        let big = FieldValue::from(num_bigint::BigInt::from(u128::MAX));
+        for value in [FieldValue::Int64(-1), FieldValue::Uint64(u64::MAX), big.clone()] {
+            for matching_type in &matching_types {
+                assert!(matching_type.is_valid_value(&value), "{matching_type} {value:?}");
+            }
+        }
+
+        // `Int` values must fit in 64 bits.
+        assert!(!Type::parse("Int").unwrap().is_valid_value(&big));
+
+        let coerced = Type::parse("[BigInt!]")
+            .unwrap()
+            .coerce_input_value(&FieldValue::String(u128::MAX.to_string().into()));
+        assert_eq!(Some(FieldValue::List(vec![big].into())), coerced);
+    }
+
     #[test]
     fn list_types_correctly_check_contents_of_list() {
         let non_nullable_contents_matching_types =
@@ -783,6 +901,65 @@ mod test {
         }
     }
 
+    #[test]
+    fn coerce_input_values() {
+        fn list(items: Vec<FieldValue>) -> FieldValue {
+            FieldValue::List(items.into())
+        }
+        let one = FieldValue::Int64(1);
+        let two = FieldValue::Uint64(2);
+        let null = FieldValue::Null;
+        let epoch = FieldValue::DateTime(DateTime::UNIX_EPOCH);
+
+        let test_data = [
+            // Values that are already valid are unchanged.
+            (
+                "[Int]",
+                list(vec![one.clone(), two.clone()]),
+                Some(list(vec![one.clone(), two.clone()])),
+            ),
+            ("[Int]", list(vec![null.clone()]), Some(list(vec![null.clone()]))),
+            ("[Int!]!", list(vec![]), Some(list(vec![]))),
+            (
+                "[[Int]]",
+                list(vec![null.clone(), list(vec![])]),
+                Some(list(vec![null.clone(), list(vec![])])),
+            ),
+            ("[Int]", null.clone(), Some(null.clone())),
+            ("Int!", one.clone(), Some(one.clone())),
+            // Single values are wrapped into lists, as many times as needed.
+            ("[Int]", one.clone(), Some(list(vec![one.clone()]))),
+            ("[Int!]!", one.clone(), Some(list(vec![one.clone()]))),
+            ("[[Int]!]", one.clone(), Some(list(vec![list(vec![one.clone()])]))),
+            // Null is never wrapped.
+            ("[Int]!", null.clone(), None),
+            ("[Int!]", list(vec![null.clone()]), None),
+            ("Int!", null.clone(), None),
+            // List items are not wrapped.
+            ("[[Int]]", list(vec![one.clone(), two.clone()]), None),
+            // Mismatched types are invalid, no matter how deeply nested.
+            ("[Int]", FieldValue::String("one".into()), None),
+            ("[[Int]]", list(vec![list(vec![one.clone(), FieldValue::Boolean(true)])]), None),
+            ("Int", list(vec![one.clone()]), None),
+            ("[Int]", FieldValue::Enum("ONE".into()), None),
+            // Strings holding RFC 3339 timestamps are coerced into date-time values.
+            ("DateTime!", FieldValue::String("1970-01-01T00:00:00Z".into()), Some(epoch.clone())),
+            (
+                "[DateTime]",
+                FieldValue::String("1970-01-01T01:00:00+01:00".into()),
+                Some(list(vec![epoch.clone()])),
+            ),
+            ("DateTime", epoch.clone(), Some(epoch.clone())),
+            ("DateTime", FieldValue::String("yesterday".into()), None),
+            ("String", epoch.clone(), None),
+        ];
+
+        for (type_str, value, expected) in test_data {
+            let ty = Type::parse(type_str).unwrap();
+            assert_eq!(expected, ty.coerce_input_value(&value), "{type_str} {value:?}");
+        }
+    }
+
     #[test]
     fn round_trip_serialization_and_creation() {
         let test_data = [
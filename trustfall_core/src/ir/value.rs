@@ -1,9 +1,19 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 /// IR of the values of Trustfall fields.
 use async_graphql_value::{ConstValue, Number, Value};
 use serde::{Deserialize, Serialize};
 
+pub use self::datetime::{DateTime, DateTimeOutOfRangeError, DateTimeParseError};
+
+#[cfg(feature = "num-bigint")]
+mod bigint;
+mod datetime;
+
 /// Values of fields in Trustfall.
 ///
 /// For version that is serialized as an untagged enum, see [TransparentValue].
@@ -31,6 +41,19 @@ pub enum FieldValue {
     Boolean(bool),
     Enum(Arc<str>),
     List(Arc<[FieldValue]>),
+
+    /// Corresponds to schemas' `DateTime` type, which schemas must declare
+    /// as a custom scalar with `scalar DateTime`.
+    DateTime(DateTime),
+
+    /// An integer of any size, requiring the `num-bigint` feature.
+    /// Corresponds to schemas' `BigInt` type, which schemas must declare
+    /// as a custom scalar with `scalar BigInt`.
+    ///
+    /// Compares equal to `Int64` and `Uint64` values of the same integer.
+    #[cfg(feature = "num-bigint")]
+    #[serde(serialize_with = "bigint::serialize", deserialize_with = "bigint::deserialize")]
+    BigInt(Arc<num_bigint::BigInt>),
 }
 
 impl FieldValue {
@@ -72,6 +95,16 @@ pub enum TransparentValue {
     Boolean(bool),
     Enum(Arc<str>),
     List(Arc<[TransparentValue]>),
+
+    /// Serialized as an RFC 3339 string. Since deserialization attempts `String` first,
+    /// strings are never deserialized into this variant.
+    DateTime(DateTime),
+
+    /// Serialized as a string of decimal digits, since many formats cannot represent
+    /// integers of arbitrary size. Like `DateTime`, never produced by deserialization.
+    #[cfg(feature = "num-bigint")]
+    #[serde(serialize_with = "bigint::serialize", deserialize_with = "bigint::deserialize")]
+    BigInt(Arc<num_bigint::BigInt>),
 }
 
 impl From<FieldValue> for TransparentValue {
@@ -87,6 +120,9 @@ impl From<FieldValue> for TransparentValue {
             FieldValue::List(x) => TransparentValue::List(
                 x.iter().map(|v| v.clone().into()).collect::<Vec<_>>().into(),
             ),
+            FieldValue::DateTime(x) => TransparentValue::DateTime(x),
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(x) => TransparentValue::BigInt(x),
         }
     }
 }
@@ -104,6 +140,9 @@ impl From<TransparentValue> for FieldValue {
             TransparentValue::List(x) => {
                 FieldValue::List(x.iter().map(|v| v.clone().into()).collect::<Vec<_>>().into())
             }
+            TransparentValue::DateTime(x) => FieldValue::DateTime(x),
+            #[cfg(feature = "num-bigint")]
+            TransparentValue::BigInt(x) => FieldValue::BigInt(x),
         }
     }
 }
@@ -120,6 +159,9 @@ impl FieldValue {
             Self::Boolean(..) => 5,
             Self::Enum(..) => 6,
             Self::List(..) => 7,
+            Self::DateTime(..) => 8,
+            #[cfg(feature = "num-bigint")]
+            Self::BigInt(..) => 9,
         }
     }
 
@@ -127,12 +169,15 @@ impl FieldValue {
         match self {
             FieldValue::Uint64(u) => (*u).try_into().ok(),
             FieldValue::Int64(i) => Some(*i),
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(v) => num_traits::ToPrimitive::to_i64(v.as_ref()),
             FieldValue::Null
             | FieldValue::Float64(_)
             | FieldValue::String(_)
             | FieldValue::Boolean(_)
             | FieldValue::List(_)
-            | FieldValue::Enum(_) => None,
+            | FieldValue::Enum(_)
+            | FieldValue::DateTime(_) => None,
         }
     }
 
@@ -140,12 +185,15 @@ impl FieldValue {
         match self {
             FieldValue::Uint64(u) => Some(*u),
             FieldValue::Int64(i) => (*i).try_into().ok(),
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(v) => num_traits::ToPrimitive::to_u64(v.as_ref()),
             FieldValue::Null
             | FieldValue::Float64(_)
             | FieldValue::String(_)
             | FieldValue::Boolean(_)
             | FieldValue::List(_)
-            | FieldValue::Enum(_) => None,
+            | FieldValue::Enum(_)
+            | FieldValue::DateTime(_) => None,
         }
     }
 
@@ -153,12 +201,15 @@ impl FieldValue {
         match self {
             FieldValue::Uint64(u) => (*u).try_into().ok(),
             FieldValue::Int64(i) => (*i).try_into().ok(),
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(v) => num_traits::ToPrimitive::to_usize(v.as_ref()),
             FieldValue::Null
             | FieldValue::Float64(_)
             | FieldValue::String(_)
             | FieldValue::Boolean(_)
             | FieldValue::List(_)
-            | FieldValue::Enum(_) => None,
+            | FieldValue::Enum(_)
+            | FieldValue::DateTime(_) => None,
         }
     }
 
@@ -190,6 +241,13 @@ impl FieldValue {
         }
     }
 
+    pub fn as_datetime(&self) -> Option<DateTime> {
+        match self {
+            FieldValue::DateTime(d) => Some(*d),
+            _ => None,
+        }
+    }
+
     pub fn as_slice(&self) -> Option<&[FieldValue]> {
         match self {
             FieldValue::List(l) => Some(l.as_ref()),
@@ -230,6 +288,9 @@ impl FieldValue {
             (Self::Boolean(l0), Self::Boolean(r0)) => l0 == r0,
             (Self::List(l0), Self::List(r0)) => l0 == r0,
             (Self::Enum(l0), Self::Enum(r0)) => l0 == r0,
+            (Self::DateTime(l0), Self::DateTime(r0)) => l0 == r0,
+            #[cfg(feature = "num-bigint")]
+            (Self::BigInt(l0), Self::BigInt(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -254,6 +315,11 @@ impl FieldValue {
 
 impl PartialOrd for FieldValue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        #[cfg(feature = "num-bigint")]
+        if let Some(ordering) = bigint::compare_mixed_integers(self, other) {
+            return Some(ordering);
+        }
+
         if let (Self::Int64(l), Self::Uint64(r)) = (self, other) {
             Some(FieldValue::compare_i64_to_u64(*l, *r))
         } else if let (Self::Uint64(l), Self::Int64(r)) = (self, other) {
@@ -271,6 +337,7 @@ impl PartialOrd for FieldValue {
                 (Self::Boolean(l0), Self::Boolean(r0)) => l0.partial_cmp(r0),
                 (Self::List(l0), Self::List(r0)) => l0.partial_cmp(r0),
                 (Self::Enum(l0), Self::Enum(r0)) => l0.partial_cmp(r0),
+                (Self::DateTime(l0), Self::DateTime(r0)) => l0.partial_cmp(r0),
                 _ => self.discriminant().partial_cmp(&other.discriminant()),
             }
         }
@@ -279,6 +346,11 @@ impl PartialOrd for FieldValue {
 
 impl PartialEq for FieldValue {
     fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "num-bigint")]
+        if let Some(ordering) = bigint::compare_mixed_integers(self, other) {
+            return ordering.is_eq();
+        }
+
         if let (Self::Int64(l), Self::Uint64(r)) = (self, other) {
             FieldValue::compare_i64_to_u64(*l, *r).is_eq()
         } else if let (Self::Uint64(..), Self::Int64(..)) = (self, other) {
@@ -291,6 +363,50 @@ impl PartialEq for FieldValue {
 
 impl Eq for FieldValue {}
 
+impl Hash for FieldValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Values that compare equal must hash the same, so signed and unsigned integers
+        // share a hash representation, and so do `0.0` and `-0.0`.
+        match self {
+            Self::Null => 0u8.hash(state),
+            Self::Int64(v) => {
+                1u8.hash(state);
+                i128::from(*v).hash(state);
+            }
+            Self::Uint64(v) => {
+                1u8.hash(state);
+                i128::from(*v).hash(state);
+            }
+            Self::Float64(v) => {
+                2u8.hash(state);
+                if *v == 0.0 { 0.0f64 } else { *v }.to_bits().hash(state);
+            }
+            Self::String(v) => {
+                3u8.hash(state);
+                v.hash(state);
+            }
+            Self::Boolean(v) => {
+                4u8.hash(state);
+                v.hash(state);
+            }
+            Self::Enum(v) => {
+                5u8.hash(state);
+                v.hash(state);
+            }
+            Self::List(v) => {
+                6u8.hash(state);
+                v.hash(state);
+            }
+            Self::DateTime(v) => {
+                7u8.hash(state);
+                v.hash(state);
+            }
+            #[cfg(feature = "num-bigint")]
+            Self::BigInt(v) => bigint::hash_bigint(v, state),
+        }
+    }
+}
+
 impl AsRef<FieldValue> for FieldValue {
     fn as_ref(&self) -> &FieldValue {
         self
@@ -327,6 +443,32 @@ impl From<bool> for FieldValue {
     }
 }
 
+impl From<DateTime> for FieldValue {
+    fn from(v: DateTime) -> Self {
+        Self::DateTime(v)
+    }
+}
+
+impl From<std::time::SystemTime> for FieldValue {
+    fn from(v: std::time::SystemTime) -> Self {
+        Self::DateTime(v.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for FieldValue {
+    fn from(v: chrono::DateTime<Tz>) -> Self {
+        Self::DateTime(v.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for FieldValue {
+    fn from(v: time::OffsetDateTime) -> Self {
+        Self::DateTime(v.into())
+    }
+}
+
 /// Represents a finite (non-infinite, not-NaN) [f64] value
 pub struct FiniteF64(f64);
 impl From<FiniteF64> for FieldValue {
@@ -536,7 +678,7 @@ impl TryFrom<ConstValue> for FieldValue {
 
 #[cfg(test)]
 mod tests {
-    use super::{FieldValue, FiniteF64};
+    use super::{DateTime, FieldValue, FiniteF64, TransparentValue};
 
     #[test]
     fn test_field_value_into() {
@@ -565,5 +707,99 @@ mod tests {
             ["a String".to_string()].as_slice(),
             FieldValue::List(vec![FieldValue::String("a String".to_string().into())].into()),
         );
+
+        test(DateTime::UNIX_EPOCH, FieldValue::DateTime(DateTime::UNIX_EPOCH));
+        test(
+            std::time::UNIX_EPOCH + std::time::Duration::from_millis(1),
+            FieldValue::DateTime(DateTime::from_unix_nanos(1_000_000)),
+        );
+    }
+
+    #[test]
+    fn datetime_serialization() {
+        let value = FieldValue::DateTime("2024-01-31T14:30:00+02:00".parse().unwrap());
+
+        let serialized = ron::to_string(&value).unwrap();
+        assert_eq!(r#"DateTime("2024-01-31T12:30:00Z")"#, serialized);
+        assert_eq!(value, ron::from_str::<FieldValue>(&serialized).unwrap());
+
+        let transparent = TransparentValue::from(value.clone());
+        assert_eq!(r#""2024-01-31T12:30:00Z""#, serde_json::to_string(&transparent).unwrap());
+        assert_eq!(value, FieldValue::from(transparent));
+
+        assert!(ron::from_str::<FieldValue>(r#"DateTime("2024-01-32T00:00:00Z")"#).is_err());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn bigint_values() {
+        use std::hash::{BuildHasher, RandomState};
+
+        use num_bigint::BigInt;
+
+        let hasher = RandomState::new();
+        let big = FieldValue::from(BigInt::from(u128::MAX));
+        let small = FieldValue::from(BigInt::from(-7));
+
+        // Integers that fit in 64 bits are converted into the usual variants.
+        assert!(matches!(FieldValue::from(-7i128), FieldValue::Int64(-7)));
+        assert!(matches!(FieldValue::from(u128::from(u64::MAX)), FieldValue::Uint64(u64::MAX)));
+        assert_eq!(big, FieldValue::from(u128::MAX));
+
+        // Big integers are equal to, and hash the same as, other integers of the same value.
+        assert_eq!(FieldValue::Int64(-7), small);
+        assert_eq!(hasher.hash_one(FieldValue::Int64(-7)), hasher.hash_one(&small));
+        assert_ne!(FieldValue::Uint64(u64::MAX), big);
+        assert_eq!(Some(-7), small.as_i64());
+        assert_eq!(None, big.as_u64());
+        assert_eq!(Some(BigInt::from(5)), FieldValue::Uint64(5).as_bigint());
+
+        // They are ordered among other integers by value.
+        assert!(FieldValue::Uint64(u64::MAX) < big);
+        assert!(small < FieldValue::Int64(0));
+        assert!(FieldValue::Int64(i64::MIN) < small);
+
+        let serialized = ron::to_string(&big).unwrap();
+        assert_eq!(format!("BigInt(\"{}\")", u128::MAX), serialized);
+        assert_eq!(big, ron::from_str::<FieldValue>(&serialized).unwrap());
+
+        let transparent = TransparentValue::from(big.clone());
+        assert_eq!(format!("\"{}\"", u128::MAX), serde_json::to_string(&transparent).unwrap());
+        assert_eq!(big, FieldValue::from(transparent));
+    }
+
+    #[test]
+    fn datetime_ordering() {
+        let earlier = FieldValue::DateTime(DateTime::from_unix_seconds(-1));
+        let later = FieldValue::DateTime(DateTime::from_unix_seconds(1));
+        assert!(earlier < later);
+
+        // Values of different kinds are ordered by kind, and date-times come last.
+        assert!(FieldValue::List(vec![].into()) < earlier);
+        assert!(FieldValue::Null < earlier);
+    }
+
+    #[test]
+    fn equal_values_hash_equally() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let hasher = RandomState::new();
+        let equal_pairs = [
+            (FieldValue::Int64(7), FieldValue::Uint64(7)),
+            (FieldValue::Float64(0.0), FieldValue::Float64(-0.0)),
+            (
+                FieldValue::List(vec![FieldValue::Int64(1)].into()),
+                FieldValue::List(vec![FieldValue::Uint64(1)].into()),
+            ),
+        ];
+        for (left, right) in equal_pairs {
+            assert_eq!(left, right);
+            assert_eq!(hasher.hash_one(&left), hasher.hash_one(&right));
+        }
+
+        assert_ne!(
+            hasher.hash_one(FieldValue::Int64(-1)),
+            hasher.hash_one(FieldValue::Uint64(u64::MAX))
+        );
     }
 }
@@ -0,0 +1,97 @@
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use super::FieldValue;
+
+/// Converts integers of any size into the smallest [`FieldValue`] variant that fits them.
+pub(super) fn field_value_from_bigint(value: BigInt) -> FieldValue {
+    if let Some(v) = value.to_i64() {
+        FieldValue::Int64(v)
+    } else if let Some(v) = value.to_u64() {
+        FieldValue::Uint64(v)
+    } else {
+        FieldValue::BigInt(Arc::new(value))
+    }
+}
+
+fn integer_as_bigint(value: &FieldValue) -> Option<BigInt> {
+    match value {
+        FieldValue::Int64(v) => Some(BigInt::from(*v)),
+        FieldValue::Uint64(v) => Some(BigInt::from(*v)),
+        FieldValue::BigInt(v) => Some(v.as_ref().clone()),
+        _ => None,
+    }
+}
+
+/// Compares a [`FieldValue::BigInt`] with another integer value.
+///
+/// Returns `None` if neither value is a `BigInt`, or if the other value isn't an integer.
+pub(super) fn compare_mixed_integers(left: &FieldValue, right: &FieldValue) -> Option<Ordering> {
+    match (left, right) {
+        (FieldValue::BigInt(l), FieldValue::BigInt(r)) => Some(l.cmp(r)),
+        (FieldValue::BigInt(l), _) => integer_as_bigint(right).map(|r| l.as_ref().cmp(&r)),
+        (_, FieldValue::BigInt(r)) => integer_as_bigint(left).map(|l| l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// Hashes a `BigInt` the same way as an equal `Int64` or `Uint64` value, if one exists.
+pub(super) fn hash_bigint<H: Hasher>(value: &BigInt, state: &mut H) {
+    if let Some(v) = value.to_i128() {
+        1u8.hash(state);
+        v.hash(state);
+    } else {
+        8u8.hash(state);
+        value.hash(state);
+    }
+}
+
+/// Serializes a `BigInt` as a string of decimal digits, since many formats,
+/// including JSON, cannot represent integers of arbitrary size.
+pub(super) fn serialize<S: Serializer>(
+    value: &Arc<BigInt>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Arc<BigInt>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map(Arc::new).map_err(de::Error::custom)
+}
+
+impl From<BigInt> for FieldValue {
+    fn from(v: BigInt) -> Self {
+        Self::BigInt(Arc::new(v))
+    }
+}
+
+impl From<i128> for FieldValue {
+    fn from(v: i128) -> Self {
+        field_value_from_bigint(v.into())
+    }
+}
+
+impl From<u128> for FieldValue {
+    fn from(v: u128) -> Self {
+        field_value_from_bigint(v.into())
+    }
+}
+
+impl FieldValue {
+    /// Returns the value of any integer, regardless of its size.
+    ///
+    /// Returns `None` for values that aren't integers.
+    pub fn as_bigint(&self) -> Option<BigInt> {
+        integer_as_bigint(self)
+    }
+}
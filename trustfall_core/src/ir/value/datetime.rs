@@ -0,0 +1,436 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_DAY: i128 = 86_400 * NANOS_PER_SECOND;
+
+/// A point in time, stored with nanosecond precision as an offset from the Unix epoch.
+///
+/// Values are instants: they do not remember the time zone or UTC offset they were
+/// created with, so two values for the same instant are equal even if they were written
+/// with different offsets. Values are displayed and serialized as RFC 3339 timestamps in UTC,
+/// such as `2024-01-31T12:30:00.250Z`.
+///
+/// Years outside the `0000` to `9999` range are written with a sign and at least six digits,
+/// such as `-000001-01-01T00:00:00Z`, as in ISO 8601's expanded representation.
+///
+/// Conversions from `chrono::DateTime` and `time::OffsetDateTime` values are available
+/// by enabling the `chrono` and `time` features respectively.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    unix_nanos: i128,
+}
+
+impl DateTime {
+    /// The Unix epoch, `1970-01-01T00:00:00Z`.
+    pub const UNIX_EPOCH: Self = Self { unix_nanos: 0 };
+
+    /// The instant the given number of nanoseconds after the Unix epoch,
+    /// or before it if the number is negative.
+    pub const fn from_unix_nanos(unix_nanos: i128) -> Self {
+        Self { unix_nanos }
+    }
+
+    /// The instant the given number of seconds after the Unix epoch,
+    /// or before it if the number is negative.
+    pub const fn from_unix_seconds(unix_seconds: i64) -> Self {
+        Self { unix_nanos: unix_seconds as i128 * NANOS_PER_SECOND }
+    }
+
+    /// The number of nanoseconds between the Unix epoch and this instant,
+    /// negative if this instant is before the epoch.
+    pub const fn unix_nanos(&self) -> i128 {
+        self.unix_nanos
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = civil_from_days(self.unix_nanos.div_euclid(NANOS_PER_DAY));
+        let nanos_of_day = self.unix_nanos.rem_euclid(NANOS_PER_DAY);
+        let seconds_of_day = nanos_of_day / NANOS_PER_SECOND;
+        let (hour, minute, second) =
+            (seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60);
+        let nanos = nanos_of_day % NANOS_PER_SECOND;
+
+        if (0..=9999).contains(&year) {
+            write!(f, "{year:04}")?;
+        } else {
+            write!(f, "{year:+07}")?;
+        }
+        write!(f, "-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}")?;
+
+        // Use the shortest of millisecond, microsecond, or nanosecond precision
+        // that represents the value exactly.
+        if nanos != 0 {
+            if nanos % 1_000_000 == 0 {
+                write!(f, ".{:03}", nanos / 1_000_000)?;
+            } else if nanos % 1_000 == 0 {
+                write!(f, ".{:06}", nanos / 1_000)?;
+            } else {
+                write!(f, ".{nanos:09}")?;
+            }
+        }
+        f.write_str("Z")
+    }
+}
+
+impl Debug for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DateTime({self})")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DateTimeParseError {
+    invalid_value: String,
+}
+
+impl Display for DateTimeParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not a valid RFC 3339 date-time value", &self.invalid_value)
+    }
+}
+
+impl std::error::Error for DateTimeParseError {}
+
+impl FromStr for DateTime {
+    type Err = DateTimeParseError;
+
+    /// Parses an RFC 3339 timestamp like `2024-01-31T12:30:00Z` or `2024-01-31T14:30:00+02:00`.
+    ///
+    /// Leap seconds are not supported. Years may also use the same expanded representation
+    /// that is used when displaying values, such as `+010000-01-01T00:00:00Z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rfc3339(s.as_bytes())
+            .ok_or_else(|| DateTimeParseError { invalid_value: s.to_string() })
+    }
+}
+
+/// The error produced when converting a [`DateTime`] into a type that cannot represent it.
+#[derive(Debug, Clone)]
+pub struct DateTimeOutOfRangeError {
+    value: DateTime,
+}
+
+impl Display for DateTimeOutOfRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "date-time value {} is outside the range of the target type", &self.value)
+    }
+}
+
+impl std::error::Error for DateTimeOutOfRangeError {}
+
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+impl From<SystemTime> for DateTime {
+    fn from(value: SystemTime) -> Self {
+        let (duration, sign) = match value.duration_since(UNIX_EPOCH) {
+            Ok(duration) => (duration, 1),
+            Err(e) => (e.duration(), -1),
+        };
+        let nanos = i128::try_from(duration.as_nanos()).expect("duration does not fit in i128");
+        Self::from_unix_nanos(sign * nanos)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for DateTime {
+    fn from(value: chrono::DateTime<Tz>) -> Self {
+        Self::from_unix_nanos(
+            i128::from(value.timestamp()) * NANOS_PER_SECOND
+                + i128::from(value.timestamp_subsec_nanos()),
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::DateTime<chrono::Utc> {
+    type Error = DateTimeOutOfRangeError;
+
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        i64::try_from(value.unix_nanos.div_euclid(NANOS_PER_SECOND))
+            .ok()
+            .and_then(|seconds| {
+                let nanos = value.unix_nanos.rem_euclid(NANOS_PER_SECOND) as u32;
+                chrono::DateTime::from_timestamp(seconds, nanos)
+            })
+            .ok_or(DateTimeOutOfRangeError { value })
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for DateTime {
+    fn from(value: time::OffsetDateTime) -> Self {
+        Self::from_unix_nanos(value.unix_timestamp_nanos())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::OffsetDateTime {
+    type Error = DateTimeOutOfRangeError;
+
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(value.unix_nanos)
+            .map_err(|_| DateTimeOutOfRangeError { value })
+    }
+}
+
+/// The number of days between the Unix epoch and the given proleptic Gregorian calendar date.
+///
+/// Uses Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i128, month: i128, day: i128) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The proleptic Gregorian calendar date the given number of days after the Unix epoch,
+/// as a `(year, month, day)` tuple. The inverse of [`days_from_civil`].
+fn civil_from_days(days: i128) -> (i128, i128, i128) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i128, month: i128) -> i128 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+struct Cursor<'a> {
+    rest: &'a [u8],
+}
+
+impl Cursor<'_> {
+    fn next_if(&mut self, accept: impl Fn(u8) -> bool) -> Option<u8> {
+        let (&first, rest) = self.rest.split_first()?;
+        accept(first).then(|| {
+            self.rest = rest;
+            first
+        })
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.next_if(|b| b == byte).map(|_| ())
+    }
+
+    /// Consumes between `min` and `max` decimal digits, returning their value
+    /// and how many digits were consumed.
+    fn digits(&mut self, min: usize, max: usize) -> Option<(i128, usize)> {
+        let mut value = 0i128;
+        let mut count = 0;
+        while count < max {
+            let Some(digit) = self.next_if(|b| b.is_ascii_digit()) else {
+                break;
+            };
+            value = value * 10 + i128::from(digit - b'0');
+            count += 1;
+        }
+        (count >= min).then_some((value, count))
+    }
+
+    fn number(&mut self, len: usize, max: i128) -> Option<i128> {
+        self.digits(len, len).map(|(value, _)| value).filter(|value| *value <= max)
+    }
+}
+
+fn parse_rfc3339(input: &[u8]) -> Option<DateTime> {
+    let mut cursor = Cursor { rest: input };
+
+    let year = match cursor.next_if(|b| b == b'+' || b == b'-') {
+        // Expanded years carry a sign and have at least four digits. The limit on their length
+        // ensures the computations below cannot overflow.
+        Some(sign) => {
+            let (year, _) = cursor.digits(4, 24)?;
+            if sign == b'-' {
+                -year
+            } else {
+                year
+            }
+        }
+        None => cursor.number(4, 9999)?,
+    };
+    cursor.expect(b'-')?;
+    let month = cursor.number(2, 12).filter(|month| *month >= 1)?;
+    cursor.expect(b'-')?;
+    let day = cursor.number(2, days_in_month(year, month)).filter(|day| *day >= 1)?;
+    cursor.next_if(|b| matches!(b, b'T' | b't' | b' '))?;
+    let hour = cursor.number(2, 23)?;
+    cursor.expect(b':')?;
+    let minute = cursor.number(2, 59)?;
+    cursor.expect(b':')?;
+    let second = cursor.number(2, 59)?;
+
+    let mut nanos = 0;
+    if cursor.expect(b'.').is_some() {
+        let (fraction, digit_count) = cursor.digits(1, 9)?;
+        nanos = fraction * 10i128.pow(9 - digit_count as u32);
+    }
+
+    let offset_seconds = match cursor.next_if(|b| matches!(b, b'Z' | b'z' | b'+' | b'-'))? {
+        b'Z' | b'z' => 0,
+        sign => {
+            let offset_hour = cursor.number(2, 23)?;
+            cursor.expect(b':')?;
+            let offset_minute = cursor.number(2, 59)?;
+            let offset = offset_hour * 3600 + offset_minute * 60;
+            if sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+    if !cursor.rest.is_empty() {
+        return None;
+    }
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+        - offset_seconds;
+
+    // Near the lower end of the range, `seconds * NANOS_PER_SECOND` alone may not fit
+    // even though the final value does, so round the seconds up first in that case.
+    let unix_nanos = if seconds < 0 && nanos > 0 {
+        (seconds + 1).checked_mul(NANOS_PER_SECOND)?.checked_sub(NANOS_PER_SECOND - nanos)?
+    } else {
+        seconds.checked_mul(NANOS_PER_SECOND)?.checked_add(nanos)?
+    };
+    Some(DateTime::from_unix_nanos(unix_nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateTime;
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (-1, "1969-12-31T23:59:59.999999999Z"),
+            (951_827_696_250_000_000, "2000-02-29T12:34:56.250Z"),
+            (1_706_704_200_000_001_000, "2024-01-31T12:30:00.000001Z"),
+            (-62_167_219_200_000_000_000, "0000-01-01T00:00:00Z"),
+            (-62_198_755_200_000_000_000, "-000001-01-01T00:00:00Z"),
+            (253_402_300_800_000_000_000, "+010000-01-01T00:00:00Z"),
+        ];
+        for (nanos, text) in cases {
+            let value = DateTime::from_unix_nanos(nanos);
+            assert_eq!(text, value.to_string());
+            assert_eq!(value, text.parse::<DateTime>().expect("failed to parse"));
+        }
+        for nanos in [i128::MIN, i128::MIN + 1, i128::MAX] {
+            let value = DateTime::from_unix_nanos(nanos);
+            assert_eq!(value, value.to_string().parse::<DateTime>().expect("failed to parse"));
+        }
+    }
+
+    #[test]
+    fn parse_offsets_and_separators() {
+        let expected: DateTime = "2024-01-31T12:30:00Z".parse().unwrap();
+        for text in [
+            "2024-01-31T14:30:00+02:00",
+            "2024-01-31T07:00:00-05:30",
+            "2024-01-31t12:30:00z",
+            "2024-01-31 12:30:00.000Z",
+            "2024-02-01T00:30:00+12:00",
+        ] {
+            assert_eq!(expected, text.parse::<DateTime>().expect(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn parse_invalid_values() {
+        for text in [
+            "",
+            "2024-01-31",
+            "2024-01-31T12:30Z",
+            "2024-01-31T12:30:00",
+            "2024-01-31T12:30:00+0200",
+            "2024-1-31T12:30:00Z",
+            "2023-02-29T12:30:00Z",
+            "2024-13-01T12:30:00Z",
+            "2024-00-01T12:30:00Z",
+            "2024-01-31T24:00:00Z",
+            "2024-01-31T23:59:60Z",
+            "2024-01-31T12:30:00.Z",
+            "2024-01-31T12:30:00.1234567890Z",
+            "2024-01-31T12:30:00Z ",
+            "+24-01-31T12:30:00Z",
+            "+9999999999999999999999-01-01T00:00:00Z",
+        ] {
+            assert!(text.parse::<DateTime>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn ordering_follows_time() {
+        let earlier: DateTime = "2024-01-31T23:00:00-02:00".parse().unwrap();
+        let later: DateTime = "2024-02-01T01:30:00Z".parse().unwrap();
+        assert!(earlier < later);
+        assert!(DateTime::from_unix_seconds(-1) < DateTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn system_time_conversion() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let after = UNIX_EPOCH + Duration::from_nanos(1_500);
+        assert_eq!(DateTime::from_unix_nanos(1_500), DateTime::from(after));
+        let before = UNIX_EPOCH - Duration::from_secs(60);
+        assert_eq!(DateTime::from_unix_seconds(-60), DateTime::from(before));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversion() {
+        let value = chrono::DateTime::parse_from_rfc3339("2024-01-31T14:30:00.5+02:00").unwrap();
+        let converted = DateTime::from(value);
+        assert_eq!("2024-01-31T12:30:00.500Z", converted.to_string());
+        assert_eq!(value, chrono::DateTime::<chrono::Utc>::try_from(converted).unwrap());
+        assert!(chrono::DateTime::<chrono::Utc>::try_from(DateTime::from_unix_nanos(i128::MAX))
+            .is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_conversion() {
+        let value =
+            time::OffsetDateTime::from_unix_timestamp_nanos(1_706_704_200_000_000_001).unwrap();
+        let converted = DateTime::from(value);
+        assert_eq!("2024-01-31T12:30:00.000000001Z", converted.to_string());
+        assert_eq!(value, time::OffsetDateTime::try_from(converted).unwrap());
+        assert!(time::OffsetDateTime::try_from(DateTime::from_unix_nanos(i128::MAX)).is_err());
+    }
+}
@@ -9,13 +9,15 @@ extern crate maplit;
 
 pub mod frontend;
 pub mod graphql_query;
+#[cfg(feature = "graphql-server")]
+pub mod graphql_server;
 pub mod interpreter;
 pub mod ir;
 pub mod schema;
 mod serialization;
 mod util;
 
-pub use serialization::TryIntoStruct;
+pub use serialization::{check_output_struct, OutputStructError, TryIntoStruct};
 
 // Test-only uses. `#[doc(hidden)]` items are not part of public API
 // and are not subject to semantic versioning rules.
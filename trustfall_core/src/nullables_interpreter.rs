@@ -4,14 +4,98 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     interpreter::{
+        helpers::{resolve_neighbors_with, resolve_property_with},
         Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
         VertexIterator,
     },
     ir::{EdgeParameters, FieldValue},
 };
 
+/// The number of `MainType` vertices in the dataset.
+const VERTEX_COUNT: usize = 4;
+
+/// A `MainType` vertex, identified by its index in the dataset.
+///
+/// The dataset mixes null and non-null values, so that queries can check how
+/// filters and other operations treat nulls. For vertex `0` through `3`:
+/// - `integer` is `null`, `1`, `2`, `null`, and `nonNullInteger` is `1`, `1`, `2`, `3`;
+/// - `float` is `null`, `1.5`, `null`, `3.5`, and `nonNullFloat` is `0.5`, `1.5`, `2.5`, `3.5`;
+/// - `string` is `null`, `"one"`, `"two"`, `null`,
+///   and `nonNullString` is `"zero"`, `"one"`, `"two"`, `"three"`;
+/// - `bool` is `null`, `true`, `false`, `null`, and `nonNullBool` is `false`, `true`, `false`, `true`;
+/// - the list properties contain a mix of null lists, empty lists, and null elements.
+///
+/// The `neighbor` edge points to the next vertex, if any, and the `nonNullNeighbor` edge
+/// wraps around to vertex `0` after the last one. The list-typed edges point to all vertices
+/// with a smaller index.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct NullablesVertex;
+pub struct NullablesVertex(usize);
+
+impl NullablesVertex {
+    fn integer(&self) -> Option<i64> {
+        [None, Some(1), Some(2), None][self.0]
+    }
+
+    fn non_null_integer(&self) -> i64 {
+        [1, 1, 2, 3][self.0]
+    }
+
+    fn float(&self) -> Option<f64> {
+        [None, Some(1.5), None, Some(3.5)][self.0]
+    }
+
+    fn non_null_float(&self) -> f64 {
+        [0.5, 1.5, 2.5, 3.5][self.0]
+    }
+
+    fn string(&self) -> Option<&'static str> {
+        [None, Some("one"), Some("two"), None][self.0]
+    }
+
+    fn non_null_string(&self) -> &'static str {
+        ["zero", "one", "two", "three"][self.0]
+    }
+
+    fn bool(&self) -> Option<bool> {
+        [None, Some(true), Some(false), None][self.0]
+    }
+
+    fn non_null_bool(&self) -> bool {
+        [false, true, false, true][self.0]
+    }
+
+    fn int_list(&self) -> Option<Vec<Option<i64>>> {
+        [None, Some(vec![Some(1), None]), Some(vec![]), None][self.0].clone()
+    }
+
+    fn non_null_int_list(&self) -> Option<Vec<i64>> {
+        [None, Some(vec![1]), Some(vec![2, 3]), None][self.0].clone()
+    }
+
+    fn int_non_null_list(&self) -> Vec<Option<i64>> {
+        [vec![], vec![None], vec![Some(1), None], vec![Some(3)]][self.0].clone()
+    }
+
+    fn non_null_int_and_list(&self) -> Vec<i64> {
+        [vec![], vec![1], vec![1, 2], vec![3]][self.0].clone()
+    }
+
+    fn neighbor(&self) -> Option<Self> {
+        (self.0 + 1 < VERTEX_COUNT).then(|| Self(self.0 + 1))
+    }
+
+    fn non_null_neighbor(&self) -> Self {
+        Self((self.0 + 1) % VERTEX_COUNT)
+    }
+
+    fn preceding(&self) -> impl Iterator<Item = Self> {
+        (0..self.0).map(Self)
+    }
+}
+
+fn float_value(value: f64) -> FieldValue {
+    FieldValue::Float64(value)
+}
 
 #[derive(Debug, Clone)]
 pub struct NullablesAdapter;
@@ -26,7 +110,13 @@ impl<'a> Adapter<'a> for NullablesAdapter {
         parameters: &EdgeParameters,
         resolve_info: &ResolveInfo,
     ) -> VertexIterator<'a, Self::Vertex> {
-        Box::new(std::iter::empty())
+        match edge_name.as_ref() {
+            "MainType" | "NonNullMainType" => Box::new(std::iter::once(NullablesVertex(0))),
+            "List" | "NonNullList" | "NonNullListAndType" => {
+                Box::new((0..VERTEX_COUNT).map(NullablesVertex))
+            }
+            _ => unimplemented!("{edge_name}"),
+        }
     }
 
     fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
@@ -36,7 +126,40 @@ impl<'a> Adapter<'a> for NullablesAdapter {
         property_name: &Arc<str>,
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'a, V, FieldValue> {
-        unimplemented!()
+        match (type_name.as_ref(), property_name.as_ref()) {
+            (_, "__typename") => resolve_property_with(contexts, |_| "MainType".into()),
+            ("MainType", "integer") => resolve_property_with(contexts, |v| v.integer().into()),
+            ("MainType", "nonNullInteger") => {
+                resolve_property_with(contexts, |v| v.non_null_integer().into())
+            }
+            ("MainType", "float") => {
+                resolve_property_with(contexts, |v| v.float().map(float_value).into())
+            }
+            ("MainType", "nonNullFloat") => {
+                resolve_property_with(contexts, |v| float_value(v.non_null_float()))
+            }
+            ("MainType", "string") => resolve_property_with(contexts, |v| v.string().into()),
+            ("MainType", "nonNullString") => {
+                resolve_property_with(contexts, |v| v.non_null_string().into())
+            }
+            ("MainType", "bool") => resolve_property_with(contexts, |v| v.bool().into()),
+            ("MainType", "nonNullBool") => {
+                resolve_property_with(contexts, |v| v.non_null_bool().into())
+            }
+            ("MainType", "intList") => resolve_property_with(contexts, |v| v.int_list().into()),
+            ("MainType", "nonNullIntList") => {
+                resolve_property_with(contexts, |v| v.non_null_int_list().into())
+            }
+            ("MainType", "intNonNullList") => {
+                resolve_property_with(contexts, |v| v.int_non_null_list().into())
+            }
+            ("MainType", "nonNullIntAndList") => {
+                resolve_property_with(contexts, |v| v.non_null_int_and_list().into())
+            }
+            (type_name, property_name) => {
+                unreachable!("failed to resolve type {type_name} property {property_name}")
+            }
+        }
     }
 
     fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
@@ -47,7 +170,20 @@ impl<'a> Adapter<'a> for NullablesAdapter {
         parameters: &EdgeParameters,
         resolve_info: &ResolveEdgeInfo,
     ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
-        unimplemented!()
+        match (type_name.as_ref(), edge_name.as_ref()) {
+            ("MainType", "neighbor") => {
+                resolve_neighbors_with(contexts, |v| Box::new(v.neighbor().into_iter()))
+            }
+            ("MainType", "nonNullNeighbor") => resolve_neighbors_with(contexts, |v| {
+                Box::new(std::iter::once(v.non_null_neighbor()))
+            }),
+            ("MainType", "neighborList" | "neighborNonNullList" | "nonNullNeighborAndList") => {
+                resolve_neighbors_with(contexts, |v| Box::new(v.preceding()))
+            }
+            (type_name, edge_name) => {
+                unreachable!("failed to resolve type {type_name} edge {edge_name}")
+            }
+        }
     }
 
     fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
@@ -57,6 +193,6 @@ impl<'a> Adapter<'a> for NullablesAdapter {
         coerce_to_type: &Arc<str>,
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'a, V, bool> {
-        unimplemented!()
+        unreachable!("the nullables schema has no subtypes: {type_name} -> {coerce_to_type}")
     }
 }
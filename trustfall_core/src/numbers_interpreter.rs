@@ -9,13 +9,13 @@ use crate::{
         self,
         helpers::{resolve_coercion_with, resolve_neighbors_with, resolve_property_with},
         Adapter, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
-        Typename, VertexIterator,
+        Typename, VertexIdentifier, VertexIdentity, VertexIterator,
     },
     ir::{EdgeParameters, FieldValue},
     schema::Schema,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NumbersVertex {
     Neither(NeitherNumber), // zero and one
     Prime(PrimeNumber),
@@ -34,6 +34,14 @@ impl Typename for NumbersVertex {
     }
 }
 
+impl VertexIdentity for NumbersVertex {
+    type Identity = NumbersVertex;
+
+    fn identity(&self) -> Self::Identity {
+        self.clone()
+    }
+}
+
 fn number_name(number: i64) -> Option<&'static str> {
     match number {
         0 => Some("zero"),
@@ -80,7 +88,7 @@ trait Number {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NeitherNumber(i64);
 
 impl Number for NeitherNumber {
@@ -97,7 +105,7 @@ impl Number for NeitherNumber {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PrimeNumber(i64);
 
 impl Number for PrimeNumber {
@@ -114,7 +122,7 @@ impl Number for PrimeNumber {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CompositeNumber(i64, BTreeSet<i64>);
 
 impl Number for CompositeNumber {
@@ -160,7 +168,7 @@ impl Number for NumbersVertex {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Letter {
     name: String,
 }
@@ -268,6 +276,15 @@ impl<'a> Adapter<'a> for NumbersAdapter {
                     )
                 }
             }
+            "Factorizable" => {
+                let max_value = parameters["max"].as_i64().unwrap();
+                Box::new(
+                    (2..=max_value)
+                        .map(move |n| make_number_vertex(&mut primes, n))
+                        .collect_vec()
+                        .into_iter(),
+                )
+            }
             _ => unimplemented!("{edge_name}"),
         }
     }
@@ -422,14 +439,16 @@ impl<'a> Adapter<'a> for NumbersAdapter {
         resolve_info: &ResolveInfo,
     ) -> ContextOutcomeIterator<'a, V, bool> {
         match (type_name.as_ref(), coerce_to_type.as_ref()) {
-            ("Number" | "Named", "Prime") => {
+            ("Number" | "Named" | "Factorizable", "Prime") => {
                 resolve_coercion_with(contexts, |vertex| matches!(vertex, NumbersVertex::Prime(..)))
             }
-            ("Number" | "Named", "Composite") => resolve_coercion_with(contexts, |vertex| {
-                matches!(vertex, NumbersVertex::Composite(..))
-            }),
+            ("Number" | "Named" | "Factorizable", "Composite") => {
+                resolve_coercion_with(contexts, |vertex| {
+                    matches!(vertex, NumbersVertex::Composite(..))
+                })
+            }
             ("Number" | "Named", "Neither") => resolve_coercion_with(contexts, |vertex| {
-                matches!(vertex, NumbersVertex::Composite(..))
+                matches!(vertex, NumbersVertex::Neither(..))
             }),
             ("Named", "Letter") => resolve_coercion_with(contexts, |vertex| {
                 matches!(vertex, NumbersVertex::Letter(..))
@@ -445,4 +464,8 @@ impl<'a> Adapter<'a> for NumbersAdapter {
             _ => unimplemented!("Unexpected coercion attempted: {} {}", type_name, coerce_to_type),
         }
     }
+
+    fn vertex_identifier(&self) -> Option<VertexIdentifier<'a, Self::Vertex>> {
+        Some(VertexIdentifier::from_vertex_identity())
+    }
 }
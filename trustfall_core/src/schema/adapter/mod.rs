@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use async_graphql_parser::types::{
-    BaseType, FieldDefinition, InputValueDefinition, TypeDefinition, TypeKind,
+    BaseType, DirectiveDefinition, DirectiveLocation, FieldDefinition, InputValueDefinition,
+    TypeDefinition, TypeKind,
 };
 
 use crate::{
@@ -113,9 +114,16 @@ fn vertex_type_iter(
 }
 
 fn entrypoints_iter(schema: &Schema) -> VertexIterator<'_, SchemaVertex<'_>> {
-    Box::new(
-        schema.query_type.fields.iter().map(|field| SchemaVertex::Edge(Edge::new(&field.node))),
-    )
+    Box::new(schema.query_type.fields.iter().map(|field| {
+        let docs = schema.field_docs(schema.query_type_name(), &field.node.name.node);
+        SchemaVertex::Edge(Edge::new(&field.node, docs))
+    }))
+}
+
+fn directives_iter(schema: &Schema) -> VertexIterator<'_, SchemaVertex<'_>> {
+    let mut directives: Vec<_> = schema.directives.values().collect();
+    directives.sort_unstable_by_key(|defn| defn.name.node.as_str());
+    Box::new(directives.into_iter().map(|defn| SchemaVertex::Directive(Directive::new(defn))))
 }
 
 #[derive(Debug, Clone)]
@@ -124,6 +132,8 @@ pub enum SchemaVertex<'a> {
     Property(Property<'a>),
     Edge(Edge<'a>),
     EdgeParameter(EdgeParameter<'a>),
+    Directive(Directive<'a>),
+    DirectiveArgument(DirectiveArgument<'a>),
     Schema,
 }
 
@@ -159,6 +169,22 @@ impl<'a> SchemaVertex<'a> {
             _ => None,
         }
     }
+
+    #[inline(always)]
+    fn as_directive(&self) -> Option<&Directive<'a>> {
+        match self {
+            Self::Directive(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn as_directive_argument(&self) -> Option<&DirectiveArgument<'a>> {
+        match self {
+            Self::DirectiveArgument(a) => Some(a),
+            _ => None,
+        }
+    }
 }
 
 impl Typename for SchemaVertex<'_> {
@@ -169,6 +195,8 @@ impl Typename for SchemaVertex<'_> {
             SchemaVertex::Property(..) => "Property",
             SchemaVertex::Edge(..) => "Edge",
             SchemaVertex::EdgeParameter(..) => "EdgeParameter",
+            SchemaVertex::Directive(..) => "Directive",
+            SchemaVertex::DirectiveArgument(..) => "DirectiveArgument",
             SchemaVertex::Schema => "Schema",
         }
     }
@@ -206,25 +234,33 @@ pub struct Property<'a> {
     parent: &'a TypeDefinition,
     name: &'a str,
     docs: Option<&'a str>,
+    deprecation_reason: Option<&'a str>,
     type_: Type,
 }
 
 impl<'a> Property<'a> {
     #[inline(always)]
-    fn new(parent: &'a TypeDefinition, name: &'a str, docs: Option<&'a str>, type_: Type) -> Self {
-        Self { parent, name, docs, type_ }
+    fn new(
+        parent: &'a TypeDefinition,
+        name: &'a str,
+        docs: Option<&'a str>,
+        deprecation_reason: Option<&'a str>,
+        type_: Type,
+    ) -> Self {
+        Self { parent, name, docs, deprecation_reason, type_ }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Edge<'a> {
     defn: &'a FieldDefinition,
+    docs: Option<&'a str>,
 }
 
 impl<'a> Edge<'a> {
     #[inline(always)]
-    fn new(defn: &'a FieldDefinition) -> Self {
-        Self { defn }
+    fn new(defn: &'a FieldDefinition, docs: Option<&'a str>) -> Self {
+        Self { defn, docs }
     }
 
     #[inline(always)]
@@ -234,7 +270,12 @@ impl<'a> Edge<'a> {
 
     #[inline(always)]
     fn docs(&self) -> Option<&'a str> {
-        self.defn.description.as_ref().map(|x| x.node.as_str())
+        self.docs
+    }
+
+    #[inline(always)]
+    fn deprecation_reason(&self) -> Option<&'a str> {
+        super::get_deprecation_reason(self.defn)
     }
 
     #[inline(always)]
@@ -273,6 +314,123 @@ impl<'a> EdgeParameter<'a> {
     fn type_(&self) -> String {
         self.defn.ty.node.to_string()
     }
+
+    #[inline(always)]
+    fn default(&self) -> Option<String> {
+        input_value_default(self.defn)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Directive<'a> {
+    defn: &'a DirectiveDefinition,
+}
+
+impl<'a> Directive<'a> {
+    #[inline(always)]
+    fn new(defn: &'a DirectiveDefinition) -> Self {
+        Self { defn }
+    }
+
+    #[inline(always)]
+    fn name(&self) -> &'a str {
+        &self.defn.name.node
+    }
+
+    #[inline(always)]
+    fn docs(&self) -> Option<&'a str> {
+        self.defn.description.as_ref().map(|pos| pos.node.as_str())
+    }
+
+    #[inline(always)]
+    fn repeatable(&self) -> bool {
+        self.defn.is_repeatable
+    }
+
+    fn location(&self) -> FieldValue {
+        self.defn
+            .locations
+            .iter()
+            .map(|location| directive_location_name(&location.node))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectiveArgument<'a> {
+    defn: &'a InputValueDefinition,
+}
+
+impl<'a> DirectiveArgument<'a> {
+    #[inline(always)]
+    fn new(defn: &'a InputValueDefinition) -> Self {
+        Self { defn }
+    }
+
+    #[inline(always)]
+    fn name(&self) -> &'a str {
+        &self.defn.name.node
+    }
+
+    #[inline(always)]
+    fn docs(&self) -> Option<&'a str> {
+        self.defn.description.as_ref().map(|pos| pos.node.as_str())
+    }
+
+    #[inline(always)]
+    fn type_(&self) -> String {
+        self.defn.ty.node.to_string()
+    }
+
+    #[inline(always)]
+    fn default(&self) -> Option<String> {
+        input_value_default(self.defn)
+    }
+}
+
+/// The JSON serialized representation of the default value of an edge parameter
+/// or directive argument, if any.
+fn input_value_default(defn: &InputValueDefinition) -> Option<String> {
+    defn.default_value
+        .as_ref()
+        .map(|v| {
+            let value = &v.node;
+            value.clone().try_into().expect("failed to convert ConstValue")
+        })
+        .or_else(|| {
+            // Nullable values have an implicit default value of `null`.
+            defn.ty.node.nullable.then_some(FieldValue::NULL)
+        })
+        .map(|value| {
+            let transparent = TransparentValue::from(value);
+            serde_json::to_string(&transparent).expect("serde_json failed to serialize value")
+        })
+}
+
+/// The name of the directive location, as it is written in the directive's definition.
+fn directive_location_name(location: &DirectiveLocation) -> &'static str {
+    match location {
+        DirectiveLocation::Query => "QUERY",
+        DirectiveLocation::Mutation => "MUTATION",
+        DirectiveLocation::Subscription => "SUBSCRIPTION",
+        DirectiveLocation::Field => "FIELD",
+        DirectiveLocation::FragmentDefinition => "FRAGMENT_DEFINITION",
+        DirectiveLocation::FragmentSpread => "FRAGMENT_SPREAD",
+        DirectiveLocation::InlineFragment => "INLINE_FRAGMENT",
+        DirectiveLocation::Schema => "SCHEMA",
+        DirectiveLocation::Scalar => "SCALAR",
+        DirectiveLocation::Object => "OBJECT",
+        DirectiveLocation::FieldDefinition => "FIELD_DEFINITION",
+        DirectiveLocation::ArgumentDefinition => "ARGUMENT_DEFINITION",
+        DirectiveLocation::Interface => "INTERFACE",
+        DirectiveLocation::Union => "UNION",
+        DirectiveLocation::Enum => "ENUM",
+        DirectiveLocation::EnumValue => "ENUM_VALUE",
+        DirectiveLocation::InputObject => "INPUT_OBJECT",
+        DirectiveLocation::InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+        DirectiveLocation::VariableDefinition => "VARIABLE_DEFINITION",
+    }
 }
 
 impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
@@ -290,6 +448,7 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
                 vertex_type_iter(self.schema, name)
             }
             "Entrypoint" => entrypoints_iter(self.schema),
+            "Directive" => directives_iter(self.schema),
             "Schema" => Box::new(std::iter::once(SchemaVertex::Schema)),
             _ => unreachable!("unexpected starting edge: {edge_name}"),
         }
@@ -321,6 +480,16 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
             "Property" => match property_name.as_ref() {
                 "name" => resolve_property_with(contexts, field_property!(as_property, name)),
                 "docs" => resolve_property_with(contexts, field_property!(as_property, docs)),
+                "is_deprecated" => resolve_property_with(
+                    contexts,
+                    field_property!(as_property, deprecation_reason, {
+                        deprecation_reason.is_some().into()
+                    }),
+                ),
+                "deprecation_reason" => resolve_property_with(
+                    contexts,
+                    field_property!(as_property, deprecation_reason),
+                ),
                 "type" => resolve_property_with(
                     contexts,
                     field_property!(as_property, type_, { type_.to_string().into() }),
@@ -330,6 +499,15 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
             "Edge" => match property_name.as_ref() {
                 "name" => resolve_property_with(contexts, accessor_property!(as_edge, name)),
                 "docs" => resolve_property_with(contexts, accessor_property!(as_edge, docs)),
+                "is_deprecated" => resolve_property_with(
+                    contexts,
+                    accessor_property!(as_edge, deprecation_reason, {
+                        deprecation_reason.is_some().into()
+                    }),
+                ),
+                "deprecation_reason" => {
+                    resolve_property_with(contexts, accessor_property!(as_edge, deprecation_reason))
+                }
                 "to_many" => resolve_property_with(contexts, accessor_property!(as_edge, to_many)),
                 "at_least_one" => {
                     resolve_property_with(contexts, accessor_property!(as_edge, at_least_one))
@@ -346,29 +524,39 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
                 "type" => {
                     resolve_property_with(contexts, accessor_property!(as_edge_parameter, type_))
                 }
-                "default" => resolve_property_with(contexts, |vertex| {
-                    let vertex = vertex.as_edge_parameter().expect("not an EdgeParameter");
-                    vertex
-                        .defn
-                        .default_value
-                        .as_ref()
-                        .map(|v| {
-                            let value = &v.node;
-                            value.clone().try_into().expect("failed to convert ConstValue")
-                        })
-                        .or_else(|| {
-                            // Nullable edge parameters have an implicit default value of `null`.
-                            vertex.defn.ty.node.nullable.then_some(FieldValue::NULL)
-                        })
-                        .map(|value| {
-                            let transparent = TransparentValue::from(value);
-                            serde_json::to_string(&transparent)
-                                .expect("serde_json failed to serialize value")
-                        })
-                        .into()
+                "default" => {
+                    resolve_property_with(contexts, accessor_property!(as_edge_parameter, default))
+                }
+                _ => unreachable!("unexpected property name on type {type_name}: {property_name}"),
+            },
+            "Directive" => match property_name.as_ref() {
+                "name" => resolve_property_with(contexts, accessor_property!(as_directive, name)),
+                "docs" => resolve_property_with(contexts, accessor_property!(as_directive, docs)),
+                "repeatable" => {
+                    resolve_property_with(contexts, accessor_property!(as_directive, repeatable))
+                }
+                "location" => resolve_property_with(contexts, |vertex| {
+                    vertex.as_directive().expect("not a Directive").location()
                 }),
                 _ => unreachable!("unexpected property name on type {type_name}: {property_name}"),
             },
+            "DirectiveArgument" => match property_name.as_ref() {
+                "name" => {
+                    resolve_property_with(contexts, accessor_property!(as_directive_argument, name))
+                }
+                "docs" => {
+                    resolve_property_with(contexts, accessor_property!(as_directive_argument, docs))
+                }
+                "type" => resolve_property_with(
+                    contexts,
+                    accessor_property!(as_directive_argument, type_),
+                ),
+                "default" => resolve_property_with(
+                    contexts,
+                    accessor_property!(as_directive_argument, default),
+                ),
+                _ => unreachable!("unexpected property name on type {type_name}: {property_name}"),
+            },
             _ => unreachable!("unexpected type name: {type_name}"),
         }
     }
@@ -423,6 +611,15 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
                 }),
                 _ => unreachable!("unexpected edge name on type {type_name}: {edge_name}"),
             },
+            "Directive" => match edge_name.as_ref() {
+                "argument" => resolve_neighbors_with(contexts, move |vertex| {
+                    let vertex = vertex.as_directive().expect("not a Directive");
+                    Box::new(vertex.defn.arguments.iter().map(|inp| {
+                        SchemaVertex::DirectiveArgument(DirectiveArgument::new(&inp.node))
+                    }))
+                }),
+                _ => unreachable!("unexpected edge name on type {type_name}: {edge_name}"),
+            },
             "Schema" => match edge_name.as_ref() {
                 "vertex_type" => {
                     let schema = self.schema;
@@ -440,6 +637,10 @@ impl<'a> crate::interpreter::Adapter<'a> for SchemaAdapter<'a> {
                     let schema = self.schema;
                     resolve_neighbors_with(contexts, move |_| entrypoints_iter(schema))
                 }
+                "directive" => {
+                    let schema = self.schema;
+                    resolve_neighbors_with(contexts, move |_| directives_iter(schema))
+                }
                 _ => unreachable!("unexpected property name on type {type_name}: {edge_name}"),
             },
             _ => unreachable!("unexpected type name: {type_name}"),
@@ -512,7 +713,8 @@ fn resolve_vertex_type_property_edge<'a>(
             Some(SchemaVertex::Property(Property::new(
                 parent_defn,
                 field.name.node.as_str(),
-                field.description.as_ref().map(|x| x.node.as_str()),
+                schema.field_docs(&parent_defn.name.node, &field.name.node),
+                super::get_deprecation_reason(field),
                 field_ty,
             )))
         } else {
@@ -529,13 +731,15 @@ fn resolve_vertex_type_edge_edge<'a>(
     let vertex = vertex.as_vertex_type().expect("not a VertexType");
     let fields = super::get_vertex_type_fields(vertex.defn);
 
+    let parent_defn = vertex.defn;
     Box::new(fields.iter().filter_map(move |p| {
         let field = &p.node;
         let field_ty = Type::from_type(&field.ty.node);
         let base_ty = field_ty.base_type();
 
         if schema.vertex_types.contains_key(base_ty) {
-            Some(SchemaVertex::Edge(Edge::new(field)))
+            let docs = schema.field_docs(&parent_defn.name.node, &field.name.node);
+            Some(SchemaVertex::Edge(Edge::new(field, docs)))
         } else {
             None
         }
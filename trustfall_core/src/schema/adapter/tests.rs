@@ -93,7 +93,9 @@ fn check_vertex_type_properties_using_one_of() {
     rows.sort_unstable();
 
     let expected_rows = [
+        Output { name: "Property".into(), property: "deprecation_reason".into() },
         Output { name: "Property".into(), property: "docs".into() },
+        Output { name: "Property".into(), property: "is_deprecated".into() },
         Output { name: "Property".into(), property: "name".into() },
         Output { name: "Property".into(), property: "type".into() },
         Output { name: "VertexType".into(), property: "docs".into() },
@@ -140,7 +142,9 @@ fn check_schema_then_vertex_type() {
     assert_eq!(
         rows,
         vec![
+            Output { name: "Property".to_owned(), property: "deprecation_reason".to_owned() },
             Output { name: "Property".to_owned(), property: "docs".to_owned() },
+            Output { name: "Property".to_owned(), property: "is_deprecated".to_owned() },
             Output { name: "Property".to_owned(), property: "name".to_owned() },
             Output { name: "Property".to_owned(), property: "type".to_owned() },
             Output { name: "VertexType".to_owned(), property: "docs".to_owned() },
@@ -190,23 +194,33 @@ fn use_vertex_type_in_schema_edge_multiple_times() {
     assert_eq!(
         rows,
         vec![
-            Output { name: "Property".to_owned(), property: "docs".to_owned(), other_vertices: 4 },
-            Output { name: "Property".to_owned(), property: "name".to_owned(), other_vertices: 4 },
-            Output { name: "Property".to_owned(), property: "type".to_owned(), other_vertices: 4 },
+            Output {
+                name: "Property".to_owned(),
+                property: "deprecation_reason".to_owned(),
+                other_vertices: 6
+            },
+            Output { name: "Property".to_owned(), property: "docs".to_owned(), other_vertices: 6 },
+            Output {
+                name: "Property".to_owned(),
+                property: "is_deprecated".to_owned(),
+                other_vertices: 6
+            },
+            Output { name: "Property".to_owned(), property: "name".to_owned(), other_vertices: 6 },
+            Output { name: "Property".to_owned(), property: "type".to_owned(), other_vertices: 6 },
             Output {
                 name: "VertexType".to_owned(),
                 property: "docs".to_owned(),
-                other_vertices: 4
+                other_vertices: 6
             },
             Output {
                 name: "VertexType".to_owned(),
                 property: "is_interface".to_owned(),
-                other_vertices: 4
+                other_vertices: 6
             },
             Output {
                 name: "VertexType".to_owned(),
                 property: "name".to_owned(),
-                other_vertices: 4
+                other_vertices: 6
             }
         ]
     );
@@ -385,6 +399,135 @@ and continuous text after it."
     similar_asserts::assert_eq!(expected_rows.as_slice(), rows);
 }
 
+#[test]
+fn check_directives() {
+    // `repeatable` is not checked here: the schema parser currently reports every
+    // directive definition as repeatable, whether or not it is declared as such.
+    let query = r#"
+{
+    Directive {
+        name @output
+        location @output
+
+        argument @fold {
+            argument_name: name @output
+            argument_type: type @output
+            argument_default: default @output
+        }
+    }
+}"#;
+    let args = BTreeMap::new().into();
+
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize)]
+    struct Output {
+        name: String,
+        location: Vec<String>,
+        argument_name: Vec<String>,
+        argument_type: Vec<String>,
+        argument_default: Vec<Option<String>>,
+    }
+
+    let test_schema =
+        Schema::parse(include_str!("../../../test_data/schemas/numbers.graphql")).unwrap();
+    let adapter = Arc::new(SchemaAdapter::new(&test_schema));
+
+    let indexed = crate::frontend::parse(get_schema(), query).expect("not a valid query");
+    let rows: Vec<Output> = crate::interpreter::execution::interpret_ir(adapter, indexed, args)
+        .expect("execution error")
+        .map(|row| row.try_into_struct().expect("result shape did not match"))
+        .collect();
+
+    let null = Some("null".to_string());
+    let expected_rows = [
+        Output {
+            name: "absent".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec![],
+            argument_type: vec![],
+            argument_default: vec![],
+        },
+        Output {
+            name: "dedupe".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec![],
+            argument_type: vec![],
+            argument_default: vec![],
+        },
+        Output {
+            name: "filter".into(),
+            location: vec!["FIELD".into(), "INLINE_FRAGMENT".into()],
+            argument_name: vec!["op".into(), "value".into()],
+            argument_type: vec!["String!".into(), "[String!]".into()],
+            argument_default: vec![None, null.clone()],
+        },
+        Output {
+            name: "fold".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec![],
+            argument_type: vec![],
+            argument_default: vec![],
+        },
+        Output {
+            name: "limit".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["count".into()],
+            argument_type: vec!["Int!".into()],
+            argument_default: vec![None],
+        },
+        Output {
+            name: "offset".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["count".into()],
+            argument_type: vec!["Int!".into()],
+            argument_default: vec![None],
+        },
+        Output {
+            name: "optional".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["group".into()],
+            argument_type: vec!["String".into()],
+            argument_default: vec![null.clone()],
+        },
+        Output {
+            name: "order".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["direction".into()],
+            argument_type: vec!["String!".into()],
+            argument_default: vec![None],
+        },
+        Output {
+            name: "output".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["name".into()],
+            argument_type: vec!["String".into()],
+            argument_default: vec![null.clone()],
+        },
+        Output {
+            name: "recurse".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["depth".into(), "dedupe".into()],
+            argument_type: vec!["Int".into(), "Boolean".into()],
+            argument_default: vec![null.clone(), null.clone()],
+        },
+        Output {
+            name: "tag".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["name".into()],
+            argument_type: vec!["String".into()],
+            argument_default: vec![null.clone()],
+        },
+        Output {
+            name: "transform".into(),
+            location: vec!["FIELD".into()],
+            argument_name: vec!["op".into(), "value".into()],
+            argument_type: vec!["String!".into(), "[String!]".into()],
+            argument_default: vec![None, null],
+        },
+    ];
+
+    similar_asserts::assert_eq!(expected_rows.as_slice(), rows);
+}
+
 fn check_entrypoint_docs() {
     let query = r#"
 {
@@ -530,9 +673,118 @@ fn check_edge_docs() {
         docs: "\
 Subtypes of this vertex type.
 
-If this is not an interface type, this edge is guaranteed to be empty."
+If this is not an interface or union type, this edge is guaranteed to be empty."
             .into(),
     }];
 
     assert_eq!(expected_rows.as_slice(), rows);
 }
+
+#[test]
+fn fields_inherit_interface_docs() {
+    let schema = Schema::parse(crate::schema::tests::DOCUMENTED_SCHEMA).unwrap();
+    let query = r#"
+{
+    VertexType {
+        name @filter(op: "=", value: ["$name"])
+
+        property {
+            property: name @output
+            property_docs: docs @output
+        }
+        edge {
+            edge: name @output
+            edge_docs: docs @output
+        }
+    }
+}"#;
+    let args: Arc<BTreeMap<Arc<str>, FieldValue>> = btreemap! {
+        "name".into() => "Prime".into(),
+    }
+    .into();
+    let adapter = Arc::new(SchemaAdapter::new(&schema));
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct Output {
+        property: String,
+        property_docs: Option<String>,
+        edge: String,
+        edge_docs: Option<String>,
+    }
+
+    let indexed = crate::frontend::parse(get_schema(), query).expect("not a valid query");
+    let rows: Vec<Output> = crate::interpreter::execution::interpret_ir(adapter, indexed, args)
+        .expect("execution error")
+        .map(|row| row.try_into_struct().expect("invalid result shape"))
+        .collect();
+
+    let expected_rows = [Output {
+        property: "value".into(),
+        property_docs: Some("The value of the number.".into()),
+        edge: "successor".into(),
+        edge_docs: Some("The next prime number, not just the next number.".into()),
+    }];
+    assert_eq!(expected_rows.as_slice(), rows);
+}
+
+#[test]
+fn check_deprecated_fields() {
+    let schema = Schema::parse(crate::schema::tests::DEPRECATED_SCHEMA).unwrap();
+    let query = r#"
+{
+    VertexType {
+        name @filter(op: "=", value: ["$name"])
+
+        property {
+            property: name @output
+            property_deprecated: is_deprecated @output
+            property_reason: deprecation_reason @output
+        }
+        edge {
+            edge: name @output
+            edge_deprecated: is_deprecated @output
+            edge_reason: deprecation_reason @output
+        }
+    }
+}"#;
+    let args: Arc<BTreeMap<Arc<str>, FieldValue>> = btreemap! {
+        "name".into() => "Number".into(),
+    }
+    .into();
+    let adapter = Arc::new(SchemaAdapter::new(&schema));
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct Output {
+        property: String,
+        property_deprecated: bool,
+        property_reason: Option<String>,
+        edge: String,
+        edge_deprecated: bool,
+        edge_reason: Option<String>,
+    }
+
+    let indexed = crate::frontend::parse(get_schema(), query).expect("not a valid query");
+    let mut rows: Vec<Output> = crate::interpreter::execution::interpret_ir(adapter, indexed, args)
+        .expect("execution error")
+        .map(|row| row.try_into_struct().expect("invalid result shape"))
+        .collect();
+    rows.sort_unstable();
+
+    // Each row combines one of the type's properties with one of its edges.
+    let properties = [("oldValue", Some("No longer supported")), ("value", None)];
+    let edges = [("next", Some("Use `successor` instead.")), ("successor", None)];
+    let expected_rows: Vec<_> = properties
+        .into_iter()
+        .flat_map(|(property, property_reason)| {
+            edges.into_iter().map(move |(edge, edge_reason)| Output {
+                property: property.into(),
+                property_deprecated: property_reason.is_some(),
+                property_reason: property_reason.map(Into::into),
+                edge: edge.into(),
+                edge_deprecated: edge_reason.is_some(),
+                edge_reason: edge_reason.map(Into::into),
+            })
+        })
+        .collect();
+    assert_eq!(expected_rows.as_slice(), rows);
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeSet;
+
+use async_graphql_parser::types::TypeKind;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::ir::Type;
+
+use super::{get_vertex_type_fields, Schema};
+
+/// A likely mistake in a schema that is valid, as reported by [`Schema::analyze`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, thiserror::Error)]
+pub enum SchemaWarning {
+    #[error(
+        "Type \"{0}\" cannot be reached from any edge of the root query type, \
+        so no query can ever use it. Consider removing it, or adding an edge that points to it."
+    )]
+    UnreachableVertexType(String),
+
+    #[error(
+        "Interface \"{0}\" is not implemented by any type, so no vertex can ever be of \
+        that type. Consider removing it, or implementing it in the types it was meant for."
+    )]
+    InterfaceWithoutImplementations(String),
+}
+
+impl Schema {
+    /// Check the schema for vertex types that no query can ever use,
+    /// and for interfaces that no type implements.
+    ///
+    /// A vertex type is considered reachable if a query can reach a vertex of that type or
+    /// of one of its subtypes, starting from an edge of the root query type and using edges
+    /// and type coercions. Interfaces that are only implemented by reachable types are
+    /// therefore not reported, since queries can still use the properties and edges they define.
+    ///
+    /// Fields whose types are not defined in the schema are not reported here, since
+    /// such schemas fail to parse with [`InvalidSchemaError::UnknownPropertyOrEdgeType`].
+    ///
+    /// Returns the (possibly empty) list of warnings, ordered by type name.
+    ///
+    /// [`InvalidSchemaError::UnknownPropertyOrEdgeType`]: super::error::InvalidSchemaError::UnknownPropertyOrEdgeType
+    pub fn analyze(&self) -> Vec<SchemaWarning> {
+        let reachable = self.reachable_vertex_types();
+
+        let query_type = self.query_type_name();
+        let mut warnings = vec![];
+        for (type_name, defn) in self.vertex_types.iter().sorted_by_key(|(name, _)| *name) {
+            if type_name.as_ref() == query_type {
+                continue;
+            }
+
+            if !reachable.iter().any(|reached| self.is_named_type_subtype(type_name, reached)) {
+                warnings.push(SchemaWarning::UnreachableVertexType(type_name.to_string()));
+            }
+
+            if matches!(defn.kind, TypeKind::Interface(..))
+                && !self
+                    .vertex_types
+                    .keys()
+                    .any(|other| other != type_name && self.is_named_type_subtype(type_name, other))
+            {
+                warnings
+                    .push(SchemaWarning::InterfaceWithoutImplementations(type_name.to_string()));
+            }
+        }
+
+        warnings
+    }
+
+    /// The vertex types that queries can reach, starting from the edges of the root query type
+    /// and then repeatedly expanding edges and applying type coercions.
+    fn reachable_vertex_types(&self) -> BTreeSet<&str> {
+        let mut reachable: BTreeSet<&str> = Default::default();
+        let mut queue: Vec<&str> = self
+            .query_type
+            .fields
+            .iter()
+            .map(|field| Type::from_type(&field.node.ty.node).base_type().to_owned())
+            .filter_map(|name| self.vertex_types.get_key_value(name.as_str()))
+            .map(|(name, _)| name.as_ref())
+            .collect();
+
+        while let Some(type_name) = queue.pop() {
+            if !reachable.insert(type_name) {
+                continue;
+            }
+
+            for field in get_vertex_type_fields(&self.vertex_types[type_name]) {
+                let field_type = Type::from_type(&field.node.ty.node);
+                if let Some((neighbor, _)) = self.vertex_types.get_key_value(field_type.base_type())
+                {
+                    queue.push(neighbor.as_ref());
+                }
+            }
+
+            queue.extend(
+                self.vertex_types
+                    .keys()
+                    .filter(|other| self.is_named_type_subtype(type_name, other))
+                    .map(|other| other.as_ref()),
+            );
+        }
+
+        reachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Schema, SchemaWarning};
+
+    fn make_schema(types: &str) -> Schema {
+        Schema::parse(format!(
+            "schema {{ query: RootSchemaQuery }}\n{}\n{types}",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        ))
+        .expect("invalid schema")
+    }
+
+    #[test]
+    fn test_schemas() {
+        let filesystem = Schema::parse(include_str!("../../test_data/schemas/filesystem.graphql"))
+            .expect("valid schema");
+        assert_eq!(Vec::<SchemaWarning>::new(), filesystem.analyze());
+
+        // No edge points to `Letter` or to its `Named` interface.
+        let numbers = Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema");
+        assert_eq!(vec![SchemaWarning::UnreachableVertexType("Letter".into())], numbers.analyze());
+    }
+
+    #[test]
+    fn unreachable_types() {
+        let schema = make_schema(
+            "\
+type RootSchemaQuery { Start: [Start!]! }
+type Start { name: String next: Next }
+type Next { cycle: Start }
+type Orphan { start: Start self: Orphan }
+type Other { name: String }",
+        );
+
+        assert_eq!(
+            vec![
+                SchemaWarning::UnreachableVertexType("Orphan".into()),
+                SchemaWarning::UnreachableVertexType("Other".into()),
+            ],
+            schema.analyze(),
+        );
+    }
+
+    #[test]
+    fn types_reachable_through_coercions() {
+        let schema = make_schema(
+            "\
+type RootSchemaQuery { Item: [Item!]! Member: Members }
+interface Named { name: String }
+interface Item implements Named { name: String }
+type Book implements Item & Named { name: String author: Author }
+type Author { name: String }
+union Members = Member
+type Member { name: String }",
+        );
+
+        assert_eq!(Vec::<SchemaWarning>::new(), schema.analyze());
+    }
+
+    #[test]
+    fn unimplemented_interfaces() {
+        let schema = make_schema(
+            "\
+type RootSchemaQuery { Item: [Item!]! }
+interface Item { name: String }
+interface Unused { name: String }",
+        );
+
+        assert_eq!(
+            vec![
+                SchemaWarning::InterfaceWithoutImplementations("Item".into()),
+                SchemaWarning::UnreachableVertexType("Unused".into()),
+                SchemaWarning::InterfaceWithoutImplementations("Unused".into()),
+            ],
+            schema.analyze(),
+        );
+    }
+}
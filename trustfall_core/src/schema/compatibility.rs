@@ -0,0 +1,694 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_graphql_parser::types::FieldDefinition;
+
+use crate::{
+    frontend::determine_transformed_field_type,
+    ir::{
+        Argument, ContextField, EdgeParameters, FieldRef, FoldSpecificFieldKind, IRQuery,
+        IRQueryComponent, IRVertex, Type, ValueTransform, Vid, TYPENAME_META_FIELD,
+    },
+};
+
+use super::{error::QueryCompatibilityError, Schema};
+
+impl Schema {
+    /// Check whether a query, which may have been compiled against a different version
+    /// of this schema, is also valid against this schema.
+    ///
+    /// The query is valid if every vertex type, edge, and property it uses exists in this schema,
+    /// every edge parameter it sets exists and accepts the value it was given, no edge
+    /// requires a parameter the query does not set, and each edge and property has a type
+    /// that produces only values the query was compiled to expect.
+    pub fn check_query(&self, query: &IRQuery) -> Result<(), QueryCompatibilityError> {
+        let mut checker = CompatibilityChecker {
+            schema: self,
+            vertex_types: Default::default(),
+            errors: Default::default(),
+        };
+        checker.collect_vertex_types(&query.root_component);
+
+        let query_type_name = self.query_type_name();
+        let root_vertex = &query.root_component.vertices[&query.root_component.root];
+        if self.query_type.fields.iter().any(|f| f.node.name.node == query.root_name.as_ref()) {
+            checker.check_edge(
+                query_type_name,
+                &query.root_name,
+                &query.root_parameters,
+                root_vertex,
+            );
+        } else {
+            checker.record(QueryCompatibilityError::MissingRootEdge(query.root_name.to_string()));
+        }
+        checker.check_component(&query.root_component);
+
+        if checker.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(checker.errors.into())
+        }
+    }
+
+    /// Check a query against each of several schemas, such as multiple versions of one schema.
+    ///
+    /// Returns the result of [`Schema::check_query`] for each schema, in the same order.
+    pub fn check_query_compatibility(
+        schemas: &[Schema],
+        query: &IRQuery,
+    ) -> Vec<Result<(), QueryCompatibilityError>> {
+        schemas.iter().map(|schema| schema.check_query(query)).collect()
+    }
+}
+
+struct CompatibilityChecker<'a> {
+    schema: &'a Schema,
+    vertex_types: BTreeMap<Vid, &'a str>,
+    errors: Vec<QueryCompatibilityError>,
+}
+
+impl<'a> CompatibilityChecker<'a> {
+    fn record(&mut self, error: QueryCompatibilityError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    fn collect_vertex_types(&mut self, component: &'a IRQueryComponent) {
+        for vertex in component.vertices.values() {
+            self.vertex_types.insert(vertex.vid, vertex.type_name.as_ref());
+        }
+        for fold in component.folds.values() {
+            self.collect_vertex_types(&fold.component);
+        }
+    }
+
+    /// The type of the given vertex, if that type exists in the schema.
+    ///
+    /// Missing types are reported when checking the vertex itself,
+    /// so their edges and properties are not checked separately.
+    fn known_vertex_type(&self, vid: Vid) -> Option<&'a str> {
+        let type_name = self.vertex_types[&vid];
+        self.schema.vertex_types.contains_key(type_name).then_some(type_name)
+    }
+
+    fn field_definition(
+        &self,
+        type_name: &str,
+        field_name: &Arc<str>,
+    ) -> Option<&'a FieldDefinition> {
+        self.schema.fields.get(&(Arc::from(type_name), field_name.clone()))
+    }
+
+    fn check_component(&mut self, component: &IRQueryComponent) {
+        for vertex in component.vertices.values() {
+            if !self.schema.vertex_types.contains_key(vertex.type_name.as_ref()) {
+                self.record(QueryCompatibilityError::MissingVertexType(
+                    vertex.type_name.to_string(),
+                ));
+            }
+            for filter in &vertex.filters {
+                let field = filter.left();
+                self.check_property(
+                    vertex.vid,
+                    &field.field_name,
+                    &field.field_type,
+                    &field.transforms,
+                );
+                for argument in filter.arguments() {
+                    self.check_argument(argument);
+                }
+            }
+        }
+
+        for edge in component.edges.values() {
+            if let Some(from_type) = self.known_vertex_type(edge.from_vid) {
+                let to_vertex = &component.vertices[&edge.to_vid];
+                self.check_edge(from_type, &edge.edge_name, &edge.parameters, to_vertex);
+            }
+            for argument in edge.filters.iter().flat_map(|filter| filter.arguments()) {
+                self.check_argument(argument);
+            }
+        }
+
+        for fold in component.folds.values() {
+            if let Some(from_type) = self.known_vertex_type(fold.from_vid) {
+                let to_vertex = &fold.component.vertices[&fold.to_vid];
+                self.check_edge(from_type, &fold.edge_name, &fold.parameters, to_vertex);
+            }
+            for tag in &fold.imported_tags {
+                self.check_field_ref(tag);
+            }
+            let fold_specific_fields = fold
+                .fold_specific_outputs
+                .values()
+                .chain(fold.post_filters.iter().map(|filter| filter.left()));
+            for kind in fold_specific_fields {
+                self.check_fold_specific_field(kind);
+            }
+            for argument in fold.post_filters.iter().flat_map(|filter| filter.arguments()) {
+                self.check_argument(argument);
+            }
+            self.check_component(&fold.component);
+        }
+
+        for output in component.outputs.values() {
+            self.check_context_field(output);
+        }
+    }
+
+    fn check_edge(
+        &mut self,
+        from_type: &str,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        to_vertex: &IRVertex,
+    ) {
+        let Some(defn) = self.field_definition(from_type, edge_name) else {
+            self.record(QueryCompatibilityError::MissingField(
+                from_type.to_string(),
+                edge_name.to_string(),
+            ));
+            return;
+        };
+        let edge_type = Type::from_type(&defn.ty.node);
+        let target_type = edge_type.base_type();
+        if !self.schema.vertex_types.contains_key(target_type) {
+            self.record(QueryCompatibilityError::NotAnEdge(
+                from_type.to_string(),
+                edge_name.to_string(),
+                edge_type.to_string(),
+            ));
+            return;
+        }
+
+        self.check_edge_parameters(from_type, defn, parameters);
+
+        if !self.schema.vertex_types.contains_key(to_vertex.type_name.as_ref()) {
+            return;
+        }
+        let expected_type = to_vertex.coerced_from_type.as_deref().unwrap_or(&to_vertex.type_name);
+        if !self.schema.is_named_type_subtype(expected_type, target_type) {
+            self.record(QueryCompatibilityError::IncompatibleEdgeType(
+                from_type.to_string(),
+                edge_name.to_string(),
+                expected_type.to_string(),
+                target_type.to_string(),
+            ));
+        } else if !self.schema.is_named_type_subtype(expected_type, &to_vertex.type_name) {
+            self.record(QueryCompatibilityError::InvalidTypeCoercion(
+                expected_type.to_string(),
+                to_vertex.type_name.to_string(),
+            ));
+        }
+    }
+
+    fn check_edge_parameters(
+        &mut self,
+        from_type: &str,
+        defn: &FieldDefinition,
+        parameters: &EdgeParameters,
+    ) {
+        let edge_name = defn.name.node.as_str();
+        for (name, value) in parameters.iter() {
+            let Some(param) = defn.arguments.iter().find(|p| p.node.name.node == name.as_ref())
+            else {
+                self.record(QueryCompatibilityError::UnexpectedEdgeParameter(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                ));
+                continue;
+            };
+            let param_type = Type::from_type(&param.node.ty.node);
+            if !param_type.is_valid_value(value) {
+                self.record(QueryCompatibilityError::InvalidEdgeParameterValue(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                    param_type.to_string(),
+                    format!("{value:?}"),
+                ));
+            }
+        }
+
+        // Parameters whose values are query variables accept any value of the variable's type.
+        for (name, variable) in parameters.variables() {
+            let Some(param) = defn.arguments.iter().find(|p| p.node.name.node == name.as_ref())
+            else {
+                self.record(QueryCompatibilityError::UnexpectedEdgeParameter(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                ));
+                continue;
+            };
+            let param_type = Type::from_type(&param.node.ty.node);
+            if !param_type.is_scalar_only_subtype(&variable.variable_type) {
+                self.record(QueryCompatibilityError::InvalidEdgeParameterValue(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                    param_type.to_string(),
+                    format!("${}", variable.variable_name),
+                ));
+            }
+        }
+
+        // Parameters whose values are tagged values accept any value of the tag's type.
+        // Nullable tags may be used for non-nullable parameters only if the query was compiled
+        // to skip vertices whose tagged value is null, which it records in `required_tags`.
+        for (name, tag) in parameters.tags() {
+            self.check_field_ref(tag);
+            let Some(param) = defn.arguments.iter().find(|p| p.node.name.node == name.as_ref())
+            else {
+                self.record(QueryCompatibilityError::UnexpectedEdgeParameter(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                ));
+                continue;
+            };
+            let param_type = Type::from_type(&param.node.ty.node);
+            let accepted_type = if parameters.required_tags.contains(name) {
+                param_type.with_nullability(true)
+            } else {
+                param_type.clone()
+            };
+            if !accepted_type.is_scalar_only_subtype(tag.field_type()) {
+                self.record(QueryCompatibilityError::InvalidEdgeParameterValue(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    name.to_string(),
+                    param_type.to_string(),
+                    format!("tagged value of type {}", tag.field_type()),
+                ));
+            }
+        }
+
+        // Nullable parameters without an explicit default value have an implicit `null` default.
+        for param in &defn.arguments {
+            let param = &param.node;
+            let is_required = !param.ty.node.nullable && param.default_value.is_none();
+            let name = param.name.node.as_str();
+            if is_required
+                && parameters.get(name).is_none()
+                && !parameters.variables.contains_key(name)
+                && !parameters.tags.contains_key(name)
+            {
+                self.record(QueryCompatibilityError::MissingRequiredEdgeParameter(
+                    from_type.to_string(),
+                    edge_name.to_string(),
+                    param.name.node.to_string(),
+                ));
+            }
+        }
+    }
+
+    fn check_argument(&mut self, argument: &Argument) {
+        match argument {
+            Argument::Tag(tag) => self.check_field_ref(tag),
+            Argument::Variable(_) => {}
+        }
+    }
+
+    fn check_field_ref(&mut self, field: &FieldRef) {
+        match field {
+            FieldRef::ContextField(field) => self.check_context_field(field),
+            FieldRef::FoldSpecificField(field) => self.check_fold_specific_field(&field.kind),
+        }
+    }
+
+    fn check_fold_specific_field(&mut self, kind: &FoldSpecificFieldKind) {
+        if let Some(field) = kind.aggregated_field() {
+            self.check_context_field(field);
+        }
+        for operand in kind.value_transforms().iter().filter_map(|transform| transform.operand()) {
+            self.check_argument(operand);
+        }
+    }
+
+    fn check_context_field(&mut self, field: &ContextField) {
+        self.check_property(
+            field.vertex_id,
+            &field.field_name,
+            &field.field_type,
+            &field.transforms,
+        );
+    }
+
+    fn check_property(
+        &mut self,
+        vid: Vid,
+        field_name: &Arc<str>,
+        field_type: &Type,
+        transforms: &[ValueTransform],
+    ) {
+        for operand in transforms.iter().filter_map(|transform| transform.operand()) {
+            self.check_argument(operand);
+        }
+
+        if field_name.as_ref() == TYPENAME_META_FIELD {
+            return;
+        }
+        let Some(type_name) = self.known_vertex_type(vid) else {
+            return;
+        };
+        let Some(defn) = self.field_definition(type_name, field_name) else {
+            self.record(QueryCompatibilityError::MissingField(
+                type_name.to_string(),
+                field_name.to_string(),
+            ));
+            return;
+        };
+
+        let property_type = Type::from_type(&defn.ty.node);
+        if self.schema.vertex_types.contains_key(property_type.base_type()) {
+            self.record(QueryCompatibilityError::NotAProperty(
+                type_name.to_string(),
+                field_name.to_string(),
+                property_type.to_string(),
+            ));
+            return;
+        }
+
+        let transformed_type =
+            transforms.iter().try_fold(property_type.clone(), |ty, transform| {
+                determine_transformed_field_type(transform, &ty)
+            });
+        if !transformed_type.is_some_and(|ty| field_type.is_scalar_only_subtype(&ty)) {
+            self.record(QueryCompatibilityError::IncompatiblePropertyType(
+                type_name.to_string(),
+                field_name.to_string(),
+                property_type.to_string(),
+                field_type.to_string(),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        frontend::parse,
+        ir::IRQuery,
+        schema::{error::QueryCompatibilityError, Schema},
+    };
+
+    fn make_schema(types: &str) -> Schema {
+        Schema::parse(format!(
+            "schema {{ query: RootSchemaQuery }}\n{}\n{types}",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        ))
+        .expect("invalid schema")
+    }
+
+    fn make_query(schema: &Schema, query: &str) -> IRQuery {
+        parse(schema, query).expect("invalid query").ir_query.clone()
+    }
+
+    const BASE_TYPES: &str = "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }
+type Leaf implements Node { name: String size: Int neighbor: [Node!] weight: Float }";
+
+    const QUERY: &str = r#"
+{
+    Node(limit: 5) {
+        name @output @filter(op: "has_prefix", value: ["$prefix"])
+        size @transform(op: "abs") @output(name: "abs_size")
+
+        neighbor @fold {
+            ... on Leaf {
+                weight @output
+            }
+        }
+    }
+}"#;
+
+    #[test]
+    fn query_is_compatible_with_compatible_schemas() {
+        let schema = make_schema(BASE_TYPES);
+        let query = make_query(&schema, QUERY);
+        assert_eq!(Ok(()), schema.check_query(&query));
+
+        let evolved = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int!, offset: Int = 0, extra: String): [Leaf!]! Other: [Leaf] }
+interface Node { name: String! size: Int! neighbor: [Node!] }
+type Leaf implements Node { name: String! size: Int! neighbor: [Leaf!] weight: Float! id: ID }",
+        );
+        assert_eq!(Ok(()), evolved.check_query(&query));
+    }
+
+    #[test]
+    fn missing_and_mistyped_fields() {
+        let schema = make_schema(BASE_TYPES);
+        let query = make_query(&schema, QUERY);
+
+        let missing_root_edge = make_schema(
+            "\
+type RootSchemaQuery { Nodes: [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }
+type Leaf implements Node { name: String size: Int neighbor: [Node!] weight: Float }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::MissingRootEdge("Node".into())),
+            missing_root_edge.check_query(&query),
+        );
+
+        let changed_fields = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: [String] neighbor: Node }
+type Leaf implements Node { name: [String] neighbor: Node size: String weight: Float }",
+        );
+        assert_eq!(
+            Err(vec![
+                QueryCompatibilityError::IncompatiblePropertyType(
+                    "Node".into(),
+                    "name".into(),
+                    "[String]".into(),
+                    "String".into(),
+                ),
+                QueryCompatibilityError::MissingField("Node".into(), "size".into()),
+            ]
+            .into()),
+            changed_fields.check_query(&query),
+        );
+
+        let missing_type = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+type Node { name: String size: Float neighbor: [Node!] }",
+        );
+        assert_eq!(
+            Err(vec![
+                QueryCompatibilityError::MissingVertexType("Leaf".into()),
+                QueryCompatibilityError::IncompatiblePropertyType(
+                    "Node".into(),
+                    "size".into(),
+                    "Float".into(),
+                    "Int".into(),
+                ),
+            ]
+            .into()),
+            missing_type.check_query(&query),
+        );
+    }
+
+    #[test]
+    fn edges_and_their_parameters() {
+        let schema = make_schema(BASE_TYPES);
+        let query = make_query(&schema, QUERY);
+
+        let changed_params = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: String, required: Int!): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }
+type Leaf implements Node { name: String size: Int neighbor: [Node!] weight: Float }",
+        );
+        assert_eq!(
+            Err(vec![
+                QueryCompatibilityError::InvalidEdgeParameterValue(
+                    "RootSchemaQuery".into(),
+                    "Node".into(),
+                    "limit".into(),
+                    "String".into(),
+                    "Int64(5)".into(),
+                ),
+                QueryCompatibilityError::MissingRequiredEdgeParameter(
+                    "RootSchemaQuery".into(),
+                    "Node".into(),
+                    "required".into(),
+                ),
+            ]
+            .into()),
+            changed_params.check_query(&query),
+        );
+
+        let changed_edges = make_schema(
+            "\
+type RootSchemaQuery { Node: [Node!]! }
+interface Node { name: String size: Int neighbor: Int }
+type Leaf { name: String size: Int neighbor: Int weight: Float }",
+        );
+        assert_eq!(
+            Err(vec![
+                QueryCompatibilityError::UnexpectedEdgeParameter(
+                    "RootSchemaQuery".into(),
+                    "Node".into(),
+                    "limit".into(),
+                ),
+                QueryCompatibilityError::NotAnEdge("Node".into(), "neighbor".into(), "Int".into()),
+            ]
+            .into()),
+            changed_edges.check_query(&query),
+        );
+
+        let widened_edge = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Named!] }
+interface Named { id: ID }
+type Leaf implements Node & Named { name: String size: Int neighbor: [Named!] weight: Float id: ID }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::IncompatibleEdgeType(
+                "Node".into(),
+                "neighbor".into(),
+                "Node".into(),
+                "Named".into(),
+            )),
+            widened_edge.check_query(&query),
+        );
+
+        let removed_subtype = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }
+type Leaf { name: String size: Int neighbor: [Node!] weight: Float }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::InvalidTypeCoercion("Node".into(), "Leaf".into())),
+            removed_subtype.check_query(&query),
+        );
+    }
+
+    #[test]
+    fn edge_parameters_from_variables() {
+        let schema = make_schema(BASE_TYPES);
+        let query = make_query(&schema, "{ Node(limit: $limit) { name @output } }");
+        assert_eq!(Ok(()), schema.check_query(&query));
+
+        let widened_param = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int, offset: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }",
+        );
+        assert_eq!(Ok(()), widened_param.check_query(&query));
+
+        let narrowed_param = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int!): [Node!]! Other(limit: String): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::InvalidEdgeParameterValue(
+                "RootSchemaQuery".into(),
+                "Node".into(),
+                "limit".into(),
+                "Int!".into(),
+                "$limit".into(),
+            )),
+            narrowed_param.check_query(&query),
+        );
+
+        let removed_param = make_schema(
+            "\
+type RootSchemaQuery { Node: [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::UnexpectedEdgeParameter(
+                "RootSchemaQuery".into(),
+                "Node".into(),
+                "limit".into(),
+            )),
+            removed_param.check_query(&query),
+        );
+    }
+
+    #[test]
+    fn edge_parameters_from_tags() {
+        let schema = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor(limit: Int): [Node!] }",
+        );
+        let query = make_query(
+            &schema,
+            r#"{ Node { size @tag neighbor(limit: "%size") { name @output } } }"#,
+        );
+        assert_eq!(Ok(()), schema.check_query(&query));
+
+        let non_null_param = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor(limit: Int!): [Node!] }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::InvalidEdgeParameterValue(
+                "Node".into(),
+                "neighbor".into(),
+                "limit".into(),
+                "Int!".into(),
+                "tagged value of type Int".into(),
+            )),
+            non_null_param.check_query(&query),
+        );
+
+        // Compiled against the non-nullable parameter, the query skips null tagged values.
+        let query = make_query(
+            &non_null_param,
+            r#"{ Node { size @tag neighbor(limit: "%size") { name @output } } }"#,
+        );
+        assert_eq!(Ok(()), non_null_param.check_query(&query));
+        assert_eq!(Ok(()), schema.check_query(&query));
+
+        let removed_param = make_schema(
+            "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }",
+        );
+        assert_eq!(
+            Err(QueryCompatibilityError::UnexpectedEdgeParameter(
+                "Node".into(),
+                "neighbor".into(),
+                "limit".into(),
+            )),
+            removed_param.check_query(&query),
+        );
+    }
+
+    #[test]
+    fn check_query_against_multiple_schemas() {
+        let schemas = [
+            make_schema(BASE_TYPES),
+            make_schema(
+                "\
+type RootSchemaQuery { Node(limit: Int): [Node!]! }
+interface Node { name: String size: Int neighbor: [Node!] }
+type Leaf implements Node { name: String size: Int neighbor: [Node!] }",
+            ),
+        ];
+        let query = make_query(&schemas[0], QUERY);
+
+        assert_eq!(
+            vec![
+                Ok(()),
+                Err(QueryCompatibilityError::MissingField("Leaf".into(), "weight".into()))
+            ],
+            Schema::check_query_compatibility(&schemas, &query),
+        );
+    }
+}
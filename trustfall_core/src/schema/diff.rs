@@ -0,0 +1,609 @@
+use std::collections::BTreeMap;
+
+use async_graphql_parser::types::{FieldDefinition, InputValueDefinition};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::ir::Type;
+
+use super::{get_vertex_type_fields, Schema};
+
+/// The differences between two versions of a schema, as reported by [`Schema::diff`].
+///
+/// Changes are split by whether they might cause a query that was valid against the old schema
+/// to become invalid against the new one, or to produce outputs of a different type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub breaking_changes: Vec<SchemaChange>,
+    pub non_breaking_changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    /// Whether any of the changes might break existing queries.
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking_changes.is_empty()
+    }
+
+    /// Whether the two schemas are equivalent for the purposes of querying.
+    pub fn is_empty(&self) -> bool {
+        self.breaking_changes.is_empty() && self.non_breaking_changes.is_empty()
+    }
+
+    fn record(&mut self, breaking: bool, change: SchemaChange) {
+        if breaking {
+            self.breaking_changes.push(change);
+        } else {
+            self.non_breaking_changes.push(change);
+        }
+    }
+}
+
+/// A single difference between two versions of a schema.
+///
+/// Root edges are reported as fields of the root query type.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchemaChange {
+    VertexTypeAdded {
+        type_name: String,
+    },
+    VertexTypeRemoved {
+        type_name: String,
+    },
+    /// The vertex type `subtype` became a subtype of `supertype`,
+    /// either by implementing it or by becoming a member of it if it's a union.
+    SubtypeAdded {
+        supertype: String,
+        subtype: String,
+    },
+    /// The vertex type `subtype` is no longer a subtype of `supertype`,
+    /// so type coercions from the latter to the former are no longer valid.
+    SubtypeRemoved {
+        supertype: String,
+        subtype: String,
+    },
+    FieldAdded {
+        type_name: String,
+        field_name: String,
+    },
+    FieldRemoved {
+        type_name: String,
+        field_name: String,
+    },
+    FieldTypeChanged {
+        type_name: String,
+        field_name: String,
+        old_type: String,
+        new_type: String,
+    },
+    EdgeParameterAdded {
+        type_name: String,
+        edge_name: String,
+        parameter_name: String,
+        required: bool,
+    },
+    EdgeParameterRemoved {
+        type_name: String,
+        edge_name: String,
+        parameter_name: String,
+    },
+    EdgeParameterTypeChanged {
+        type_name: String,
+        edge_name: String,
+        parameter_name: String,
+        old_type: String,
+        new_type: String,
+    },
+    EdgeParameterDefaultChanged {
+        type_name: String,
+        edge_name: String,
+        parameter_name: String,
+        old_default: Option<String>,
+        new_default: Option<String>,
+    },
+}
+
+impl Schema {
+    /// Find the differences between two versions of a schema, and determine which of them
+    /// might break queries written against the old version.
+    ///
+    /// Breaking changes include removed vertex types, fields, edge parameters, and subtyping
+    /// relationships; property types that may now produce values the old type didn't allow;
+    /// edges that may now point to vertices outside the old edge type; edge parameters that
+    /// no longer accept all the values they used to; and newly-required edge parameters.
+    pub fn diff(old: &Schema, new: &Schema) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+
+        let old_query_type = old.query_type_name();
+        let new_query_type = new.query_type_name();
+        let old_types: BTreeMap<&str, _> = old
+            .vertex_types
+            .iter()
+            .map(|(name, defn)| (name.as_ref(), defn))
+            .filter(|(name, _)| *name != old_query_type)
+            .collect();
+        let new_types: BTreeMap<&str, _> = new
+            .vertex_types
+            .iter()
+            .map(|(name, defn)| (name.as_ref(), defn))
+            .filter(|(name, _)| *name != new_query_type)
+            .collect();
+
+        for &type_name in old_types.keys().filter(|name| !new_types.contains_key(*name)) {
+            diff.record(true, SchemaChange::VertexTypeRemoved { type_name: type_name.to_string() });
+        }
+        for &type_name in new_types.keys().filter(|name| !old_types.contains_key(*name)) {
+            diff.record(false, SchemaChange::VertexTypeAdded { type_name: type_name.to_string() });
+        }
+
+        let common_types = old_types.keys().filter(|name| new_types.contains_key(*name)).copied();
+        for (supertype, subtype) in common_types.clone().cartesian_product(common_types.clone()) {
+            if supertype == subtype {
+                continue;
+            }
+            let was_subtype = old.is_named_type_subtype(supertype, subtype);
+            let is_subtype = new.is_named_type_subtype(supertype, subtype);
+            if was_subtype != is_subtype {
+                let supertype = supertype.to_string();
+                let subtype = subtype.to_string();
+                if was_subtype {
+                    diff.record(true, SchemaChange::SubtypeRemoved { supertype, subtype });
+                } else {
+                    diff.record(false, SchemaChange::SubtypeAdded { supertype, subtype });
+                }
+            }
+        }
+
+        diff_fields(
+            old,
+            new,
+            old_query_type,
+            &old.query_type.fields.iter().map(|f| &f.node).collect_vec(),
+            &new.query_type.fields.iter().map(|f| &f.node).collect_vec(),
+            &mut diff,
+        );
+        for type_name in common_types {
+            diff_fields(
+                old,
+                new,
+                type_name,
+                &get_vertex_type_fields(old_types[type_name]).iter().map(|f| &f.node).collect_vec(),
+                &get_vertex_type_fields(new_types[type_name]).iter().map(|f| &f.node).collect_vec(),
+                &mut diff,
+            );
+        }
+
+        diff
+    }
+}
+
+fn diff_fields(
+    old: &Schema,
+    new: &Schema,
+    type_name: &str,
+    old_fields: &[&FieldDefinition],
+    new_fields: &[&FieldDefinition],
+    diff: &mut SchemaDiff,
+) {
+    let old_fields: BTreeMap<&str, _> =
+        old_fields.iter().map(|defn| (defn.name.node.as_str(), *defn)).collect();
+    let new_fields: BTreeMap<&str, _> =
+        new_fields.iter().map(|defn| (defn.name.node.as_str(), *defn)).collect();
+
+    for (&field_name, old_defn) in &old_fields {
+        let Some(new_defn) = new_fields.get(field_name) else {
+            diff.record(
+                true,
+                SchemaChange::FieldRemoved {
+                    type_name: type_name.to_string(),
+                    field_name: field_name.to_string(),
+                },
+            );
+            continue;
+        };
+
+        let old_type = &old_defn.ty.node;
+        let new_type = &new_defn.ty.node;
+        let old_is_edge = old.vertex_types.contains_key(Type::from_type(old_type).base_type());
+        let new_is_edge = new.vertex_types.contains_key(Type::from_type(new_type).base_type());
+
+        if old_type != new_type {
+            // Properties may narrow their type, since all values of the new type
+            // are valid values of the old type. Edges may narrow their type to a subtype,
+            // since subtypes have all the fields of their supertypes.
+            let compatible = match (old_is_edge, new_is_edge) {
+                (false, false) => {
+                    Type::from_type(old_type).is_scalar_only_subtype(&Type::from_type(new_type))
+                }
+                (true, true) => new.is_subtype(old_type, new_type),
+                _ => false,
+            };
+            diff.record(
+                !compatible,
+                SchemaChange::FieldTypeChanged {
+                    type_name: type_name.to_string(),
+                    field_name: field_name.to_string(),
+                    old_type: old_type.to_string(),
+                    new_type: new_type.to_string(),
+                },
+            );
+        }
+
+        if old_is_edge && new_is_edge {
+            diff_edge_parameters(
+                type_name,
+                field_name,
+                &old_defn.arguments.iter().map(|p| &p.node).collect_vec(),
+                &new_defn.arguments.iter().map(|p| &p.node).collect_vec(),
+                diff,
+            );
+        }
+    }
+
+    for &field_name in new_fields.keys().filter(|name| !old_fields.contains_key(*name)) {
+        diff.record(
+            false,
+            SchemaChange::FieldAdded {
+                type_name: type_name.to_string(),
+                field_name: field_name.to_string(),
+            },
+        );
+    }
+}
+
+fn diff_edge_parameters(
+    type_name: &str,
+    edge_name: &str,
+    old_params: &[&InputValueDefinition],
+    new_params: &[&InputValueDefinition],
+    diff: &mut SchemaDiff,
+) {
+    // Nullable parameters without an explicit default value have an implicit `null` default.
+    let is_required =
+        |param: &InputValueDefinition| !param.ty.node.nullable && param.default_value.is_none();
+    let default_value =
+        |param: &InputValueDefinition| param.default_value.as_ref().map(|v| v.node.to_string());
+
+    let old_params: BTreeMap<&str, _> =
+        old_params.iter().map(|defn| (defn.name.node.as_str(), *defn)).collect();
+    let new_params: BTreeMap<&str, _> =
+        new_params.iter().map(|defn| (defn.name.node.as_str(), *defn)).collect();
+
+    for (&parameter_name, old_param) in &old_params {
+        let Some(new_param) = new_params.get(parameter_name) else {
+            diff.record(
+                true,
+                SchemaChange::EdgeParameterRemoved {
+                    type_name: type_name.to_string(),
+                    edge_name: edge_name.to_string(),
+                    parameter_name: parameter_name.to_string(),
+                },
+            );
+            continue;
+        };
+
+        let old_type = &old_param.ty.node;
+        let new_type = &new_param.ty.node;
+        if old_type != new_type {
+            // Parameters may widen their type, since all values of the old type
+            // are valid values of the new type.
+            let compatible =
+                Type::from_type(new_type).is_scalar_only_subtype(&Type::from_type(old_type));
+            diff.record(
+                !compatible,
+                SchemaChange::EdgeParameterTypeChanged {
+                    type_name: type_name.to_string(),
+                    edge_name: edge_name.to_string(),
+                    parameter_name: parameter_name.to_string(),
+                    old_type: old_type.to_string(),
+                    new_type: new_type.to_string(),
+                },
+            );
+        }
+
+        let old_default = default_value(old_param);
+        let new_default = default_value(new_param);
+        if old_default != new_default {
+            diff.record(
+                is_required(new_param) && !is_required(old_param),
+                SchemaChange::EdgeParameterDefaultChanged {
+                    type_name: type_name.to_string(),
+                    edge_name: edge_name.to_string(),
+                    parameter_name: parameter_name.to_string(),
+                    old_default,
+                    new_default,
+                },
+            );
+        }
+    }
+
+    for (&parameter_name, new_param) in
+        new_params.iter().filter(|(name, _)| !old_params.contains_key(*name))
+    {
+        let required = is_required(new_param);
+        diff.record(
+            required,
+            SchemaChange::EdgeParameterAdded {
+                type_name: type_name.to_string(),
+                edge_name: edge_name.to_string(),
+                parameter_name: parameter_name.to_string(),
+                required,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Schema, SchemaChange};
+
+    fn make_schema(types: &str) -> Schema {
+        Schema::parse(format!(
+            "schema {{ query: RootSchemaQuery }}\n{}\n{types}",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        ))
+        .expect("invalid schema")
+    }
+
+    #[test]
+    fn identical_schemas_have_no_differences() {
+        let schema = Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema");
+        assert!(Schema::diff(&schema, &schema.clone()).is_empty());
+    }
+
+    #[test]
+    fn removals_are_breaking_and_additions_are_not() {
+        let old = make_schema(
+            "\
+type RootSchemaQuery { Foo: [Foo!]! Bar: [Bar!]! }
+interface Foo { name: String }
+type Bar implements Foo { name: String count: Int }",
+        );
+        let new = make_schema(
+            "\
+type RootSchemaQuery { Bar: [Bar!]! Baz: Baz }
+type Bar { name: String other: [Baz] }
+type Baz { id: ID! }",
+        );
+
+        let diff = Schema::diff(&old, &new);
+        assert!(diff.is_breaking());
+        assert_eq!(
+            vec![
+                SchemaChange::VertexTypeRemoved { type_name: "Foo".into() },
+                SchemaChange::FieldRemoved {
+                    type_name: "RootSchemaQuery".into(),
+                    field_name: "Foo".into(),
+                },
+                SchemaChange::FieldRemoved { type_name: "Bar".into(), field_name: "count".into() },
+            ],
+            diff.breaking_changes,
+        );
+        assert_eq!(
+            vec![
+                SchemaChange::VertexTypeAdded { type_name: "Baz".into() },
+                SchemaChange::FieldAdded {
+                    type_name: "RootSchemaQuery".into(),
+                    field_name: "Baz".into(),
+                },
+                SchemaChange::FieldAdded { type_name: "Bar".into(), field_name: "other".into() },
+            ],
+            diff.non_breaking_changes,
+        );
+    }
+
+    #[test]
+    fn removed_subtype_relationships_are_breaking() {
+        let old = make_schema(
+            "\
+type RootSchemaQuery { Node: [Node!]! }
+interface Node { id: ID! }
+type Leaf implements Node { id: ID! }
+type Other { id: ID! }
+union Any = Leaf",
+        );
+        let new = make_schema(
+            "\
+type RootSchemaQuery { Node: [Node!]! }
+interface Node { id: ID! }
+type Leaf { id: ID! }
+type Other implements Node { id: ID! }
+union Any = Leaf",
+        );
+
+        let diff = Schema::diff(&old, &new);
+        assert_eq!(
+            vec![SchemaChange::SubtypeRemoved { supertype: "Node".into(), subtype: "Leaf".into() }],
+            diff.breaking_changes,
+        );
+        assert_eq!(
+            vec![SchemaChange::SubtypeAdded { supertype: "Node".into(), subtype: "Other".into() }],
+            diff.non_breaking_changes,
+        );
+    }
+
+    #[test]
+    fn narrowing_field_types_is_not_breaking() {
+        let old = make_schema(
+            "\
+type RootSchemaQuery { Node: [Node!]! }
+interface Node { name: String tags: [String] next: Node size: Int }
+type Leaf implements Node { name: String tags: [String] next: Node size: Int }",
+        );
+        let new = make_schema(
+            "\
+type RootSchemaQuery { Node: [Leaf!]! }
+interface Node { name: String! tags: [String!]! next: Leaf! size: String }
+type Leaf implements Node { name: String! tags: [String!]! next: Leaf! size: String }",
+        );
+
+        let diff = Schema::diff(&old, &new);
+        let field_change = |type_name: &str, field_name: &str, old_type: &str, new_type: &str| {
+            SchemaChange::FieldTypeChanged {
+                type_name: type_name.into(),
+                field_name: field_name.into(),
+                old_type: old_type.into(),
+                new_type: new_type.into(),
+            }
+        };
+        assert_eq!(
+            vec![
+                field_change("Leaf", "size", "Int", "String"),
+                field_change("Node", "size", "Int", "String"),
+            ],
+            diff.breaking_changes,
+        );
+        assert_eq!(
+            vec![
+                field_change("RootSchemaQuery", "Node", "[Node!]!", "[Leaf!]!"),
+                field_change("Leaf", "name", "String", "String!"),
+                field_change("Leaf", "next", "Node", "Leaf!"),
+                field_change("Leaf", "tags", "[String]", "[String!]!"),
+                field_change("Node", "name", "String", "String!"),
+                field_change("Node", "next", "Node", "Leaf!"),
+                field_change("Node", "tags", "[String]", "[String!]!"),
+            ],
+            diff.non_breaking_changes,
+        );
+    }
+
+    #[test]
+    fn widening_field_types_is_breaking() {
+        let old = make_schema(
+            "\
+type RootSchemaQuery { Leaf: [Leaf!]! }
+interface Node { name: String! }
+type Leaf implements Node { name: String! next: Leaf }",
+        );
+        let new = make_schema(
+            "\
+type RootSchemaQuery { Leaf: [Node!]! }
+interface Node { name: String }
+type Leaf implements Node { name: String next: Node }",
+        );
+
+        let diff = Schema::diff(&old, &new);
+        assert_eq!(
+            vec![
+                SchemaChange::FieldTypeChanged {
+                    type_name: "RootSchemaQuery".into(),
+                    field_name: "Leaf".into(),
+                    old_type: "[Leaf!]!".into(),
+                    new_type: "[Node!]!".into(),
+                },
+                SchemaChange::FieldTypeChanged {
+                    type_name: "Leaf".into(),
+                    field_name: "name".into(),
+                    old_type: "String!".into(),
+                    new_type: "String".into(),
+                },
+                SchemaChange::FieldTypeChanged {
+                    type_name: "Leaf".into(),
+                    field_name: "next".into(),
+                    old_type: "Leaf".into(),
+                    new_type: "Node".into(),
+                },
+                SchemaChange::FieldTypeChanged {
+                    type_name: "Node".into(),
+                    field_name: "name".into(),
+                    old_type: "String!".into(),
+                    new_type: "String".into(),
+                },
+            ],
+            diff.breaking_changes,
+        );
+        assert!(diff.non_breaking_changes.is_empty());
+    }
+
+    #[test]
+    fn edge_parameter_changes() {
+        let old = make_schema(
+            "\
+type RootSchemaQuery {
+    Number(min: Int!, max: Int = 10, step: Int!, removed: String, newly_required: Int! = 1): [Number!]!
+}
+type Number { value: Int }",
+        );
+        let new = make_schema(
+            "\
+type RootSchemaQuery {
+    Number(
+        min: Int,
+        max: Int = 20,
+        step: String!,
+        newly_required: Int!,
+        optional: Int,
+        defaulted: Int! = 0,
+        required: Boolean!,
+    ): [Number!]!
+}
+type Number { value: Int }",
+        );
+
+        let diff = Schema::diff(&old, &new);
+        let type_name = "RootSchemaQuery".to_string();
+        let edge_name = "Number".to_string();
+        assert_eq!(
+            vec![
+                SchemaChange::EdgeParameterDefaultChanged {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "newly_required".into(),
+                    old_default: Some("1".into()),
+                    new_default: None,
+                },
+                SchemaChange::EdgeParameterRemoved {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "removed".into(),
+                },
+                SchemaChange::EdgeParameterTypeChanged {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "step".into(),
+                    old_type: "Int!".into(),
+                    new_type: "String!".into(),
+                },
+                SchemaChange::EdgeParameterAdded {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "required".into(),
+                    required: true,
+                },
+            ],
+            diff.breaking_changes,
+        );
+        assert_eq!(
+            vec![
+                SchemaChange::EdgeParameterDefaultChanged {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "max".into(),
+                    old_default: Some("10".into()),
+                    new_default: Some("20".into()),
+                },
+                SchemaChange::EdgeParameterTypeChanged {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "min".into(),
+                    old_type: "Int!".into(),
+                    new_type: "Int".into(),
+                },
+                SchemaChange::EdgeParameterAdded {
+                    type_name: type_name.clone(),
+                    edge_name: edge_name.clone(),
+                    parameter_name: "defaulted".into(),
+                    required: false,
+                },
+                SchemaChange::EdgeParameterAdded {
+                    type_name,
+                    edge_name,
+                    parameter_name: "optional".into(),
+                    required: false,
+                },
+            ],
+            diff.non_breaking_changes,
+        );
+    }
+}
@@ -129,6 +129,15 @@ pub enum InvalidSchemaError {
     )]
     ImplementingNonInterface(String, String),
 
+    #[error("Union \"{0}\" has member type \"{1}\" which is not defined in this schema.")]
+    NonExistentUnionMember(String, String),
+
+    #[error(
+        "Union \"{0}\" has non-object member type \"{1}\". \
+        Only object types can be members of unions."
+    )]
+    NonObjectUnionMember(String, String),
+
     #[error("Type \"{0}\" defines the field \"{1}\" multiple times.")]
     DuplicateFieldDefinition(String, String),
 
@@ -136,6 +145,122 @@ pub enum InvalidSchemaError {
     DuplicateTypeOrInterfaceDefinition(String),
 }
 
+/// An error produced when deserializing a schema with [`Schema::from_bytes`](super::Schema::from_bytes).
+#[cfg(feature = "serialization")]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaBytesError {
+    #[error("The data is not a serialized Trustfall schema.")]
+    NotASchema,
+
+    #[error(
+        "The schema was serialized in format version {0}, which this version of Trustfall \
+        cannot read. Serialize the schema again to get format version {1}."
+    )]
+    UnsupportedFormatVersion(u32, u32),
+
+    #[error("The serialized schema data is invalid: {0}")]
+    InvalidData(String),
+}
+
+/// An error produced when combining schemas with [`Schema::merge`](super::Schema::merge).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaMergeError {
+    #[error("No schemas were provided to merge.")]
+    NoSchemasToMerge,
+
+    #[error("Vertex type \"{0}\" is defined by more than one of the schemas being merged.")]
+    DuplicateVertexType(String),
+
+    #[error("Root edge \"{0}\" is defined by more than one of the schemas being merged.")]
+    DuplicateRootEdge(String),
+
+    #[error(
+        "Type \"{0}\" is a scalar in one of the schemas being merged, \
+        but a vertex type in another."
+    )]
+    ScalarAndVertexTypeWithSameName(String),
+
+    #[error("The merged schema is not valid: {0}")]
+    InvalidMergedSchema(#[from] InvalidSchemaError),
+}
+
+/// A reason why a query cannot be executed against a schema, as reported by
+/// [`Schema::check_query`](super::Schema::check_query).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum QueryCompatibilityError {
+    #[error("Multiple incompatibilities: {0}")]
+    MultipleErrors(DisplayVec<QueryCompatibilityError>),
+
+    #[error("The schema has no root edge named \"{0}\".")]
+    MissingRootEdge(String),
+
+    #[error("The schema has no vertex type named \"{0}\".")]
+    MissingVertexType(String),
+
+    #[error("Type \"{0}\" has no field named \"{1}\".")]
+    MissingField(String, String),
+
+    #[error(
+        "The query uses field \"{1}\" on type \"{0}\" as a property, \
+        but in this schema it is an edge of type {2}."
+    )]
+    NotAProperty(String, String, String),
+
+    #[error(
+        "The query uses field \"{1}\" on type \"{0}\" as an edge, \
+        but in this schema it is a property of type {2}."
+    )]
+    NotAnEdge(String, String, String),
+
+    #[error(
+        "Property \"{1}\" on type \"{0}\" has type {2} in this schema, which is not compatible \
+        with the type {3} the query expects for it after applying any transforms."
+    )]
+    IncompatiblePropertyType(String, String, String, String),
+
+    #[error(
+        "Edge \"{1}\" on type \"{0}\" points to type {3} in this schema, \
+        which is not a subtype of the type {2} the query expects."
+    )]
+    IncompatibleEdgeType(String, String, String, String),
+
+    #[error(
+        "Type \"{1}\" is not a subtype of \"{0}\" in this schema, so it cannot be coerced to."
+    )]
+    InvalidTypeCoercion(String, String),
+
+    #[error(
+        "The query sets parameter \"{2}\" on edge \"{1}\" of type \"{0}\", \
+        but that edge has no such parameter."
+    )]
+    UnexpectedEdgeParameter(String, String, String),
+
+    #[error(
+        "Edge \"{1}\" on type \"{0}\" requires parameter \"{2}\", \
+        but the query does not set it."
+    )]
+    MissingRequiredEdgeParameter(String, String, String),
+
+    #[error(
+        "The query sets parameter \"{2}\" on edge \"{1}\" of type \"{0}\" to {4}, \
+        which is not a valid value for its type {3}."
+    )]
+    InvalidEdgeParameterValue(String, String, String, String, String),
+}
+
+impl From<Vec<QueryCompatibilityError>> for QueryCompatibilityError {
+    fn from(mut errors: Vec<QueryCompatibilityError>) -> Self {
+        if errors.len() == 1 {
+            errors.pop().expect("no error present")
+        } else {
+            Self::MultipleErrors(DisplayVec(errors))
+        }
+    }
+}
+
 impl From<Vec<InvalidSchemaError>> for InvalidSchemaError {
     fn from(v: Vec<InvalidSchemaError>) -> Self {
         assert!(!v.is_empty());
@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_graphql_parser::{
+    types::{ObjectType, ServiceDocument, TypeDefinition, TypeKind, TypeSystemDefinition},
+    Pos, Positioned,
+};
+use itertools::Itertools;
+
+use super::{error::SchemaMergeError, Schema};
+
+impl Schema {
+    /// Combine several schemas into a single schema that can query all of their data.
+    ///
+    /// The merged schema contains all vertex types of all the given schemas,
+    /// and its root query type contains all of their root edges. The root query type
+    /// takes its name from the first schema's root query type.
+    ///
+    /// Each vertex type and each root edge must be defined by exactly one of the given schemas.
+    /// Custom scalars and directives may be defined by more than one schema,
+    /// in which case the first definition is used.
+    ///
+    /// To execute queries against the merged schema, combine the schemas' adapters
+    /// with [`CompositeAdapter`](crate::interpreter::CompositeAdapter).
+    pub fn merge(schemas: &[Schema]) -> Result<Schema, SchemaMergeError> {
+        let first = schemas.first().ok_or(SchemaMergeError::NoSchemasToMerge)?;
+        let query_type_name = first.query_type_name();
+
+        let mut root_edges: BTreeMap<&str, Positioned<_>> = Default::default();
+        let mut vertex_types: BTreeMap<&str, &TypeDefinition> = Default::default();
+        let mut scalars: BTreeMap<&str, &TypeDefinition> = Default::default();
+        let mut directives: BTreeMap<&str, _> = Default::default();
+
+        for schema in schemas {
+            let schema_query_type_name = schema.query_type_name();
+
+            for field in &schema.query_type.fields {
+                let edge_name = field.node.name.node.as_str();
+                if root_edges.insert(edge_name, field.clone()).is_some() {
+                    return Err(SchemaMergeError::DuplicateRootEdge(edge_name.to_string()));
+                }
+            }
+
+            for (type_name, defn) in schema.vertex_types.iter().sorted_by_key(|(name, _)| *name) {
+                let type_name = type_name.as_ref();
+                if type_name == schema_query_type_name {
+                    continue;
+                }
+                if type_name == query_type_name || vertex_types.insert(type_name, defn).is_some() {
+                    return Err(SchemaMergeError::DuplicateVertexType(type_name.to_string()));
+                }
+            }
+
+            for (scalar_name, defn) in schema.scalars.iter().sorted_by_key(|(name, _)| *name) {
+                scalars.entry(scalar_name.as_ref()).or_insert(defn);
+            }
+            for (directive_name, defn) in schema.directives.iter().sorted_by_key(|(name, _)| *name)
+            {
+                directives.entry(directive_name.as_ref()).or_insert(defn);
+            }
+        }
+
+        let vertex_type_names: BTreeSet<&str> = vertex_types.keys().copied().collect();
+        if let Some(name) = scalars.keys().find(|name| vertex_type_names.contains(*name)) {
+            return Err(SchemaMergeError::ScalarAndVertexTypeWithSameName(name.to_string()));
+        }
+
+        let mut query_type_defn = first.vertex_types[query_type_name].clone();
+        query_type_defn.kind = TypeKind::Object(ObjectType {
+            implements: vec![],
+            fields: root_edges.into_values().collect(),
+        });
+
+        let mut definitions = vec![TypeSystemDefinition::Schema(Positioned::new(
+            first.schema.clone(),
+            Pos::default(),
+        ))];
+        definitions.extend(directives.into_values().map(|defn| {
+            TypeSystemDefinition::Directive(Positioned::new(defn.clone(), Pos::default()))
+        }));
+        definitions.extend(
+            std::iter::once(&query_type_defn)
+                .chain(scalars.into_values())
+                .chain(vertex_types.into_values())
+                .map(|defn| {
+                    TypeSystemDefinition::Type(Positioned::new(defn.clone(), Pos::default()))
+                }),
+        );
+
+        Ok(Schema::new(ServiceDocument { definitions })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{error::SchemaMergeError, Schema};
+
+    fn make_schema(types: &str) -> Schema {
+        Schema::parse(format!(
+            "schema {{ query: RootSchemaQuery }}\n{}\n{types}",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        ))
+        .expect("invalid schema")
+    }
+
+    #[test]
+    fn merge_combines_vertex_types_and_root_edges() {
+        let numbers = Schema::parse(include_str!("../../test_data/schemas/numbers.graphql"))
+            .expect("valid schema");
+        let filesystem = Schema::parse(include_str!("../../test_data/schemas/filesystem.graphql"))
+            .expect("valid schema");
+
+        let merged = Schema::merge(&[numbers.clone(), filesystem.clone()]).expect("merge failed");
+        assert_eq!("RootSchemaQuery", merged.query_type_name());
+        assert_eq!(
+            numbers.query_type.fields.len() + filesystem.query_type.fields.len(),
+            merged.query_type.fields.len(),
+        );
+        for schema in [&numbers, &filesystem] {
+            for ((type_name, field_name), defn) in &schema.fields {
+                let merged_defn = &merged.fields[&(type_name.clone(), field_name.clone())];
+                assert_eq!(defn.ty.node, merged_defn.ty.node);
+            }
+        }
+        assert_eq!(
+            numbers.vertex_types.len() + filesystem.vertex_types.len() - 1,
+            merged.vertex_types.len(),
+        );
+
+        let number_subtypes = merged.subtypes("Number").unwrap().collect::<Vec<_>>();
+        assert_eq!(vec!["Composite", "Neither", "Number", "Prime"], number_subtypes);
+    }
+
+    #[test]
+    fn merge_uses_first_root_query_type_name() {
+        let first = make_schema("type RootSchemaQuery { Foo: Foo }\ntype Foo { value: Int }");
+        let second = Schema::parse(format!(
+            "schema {{ query: Query }}\n{}\ntype Query {{ Bar: [Bar!] }}\ntype Bar {{ value: Int }}",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        ))
+        .expect("invalid schema");
+
+        let merged = Schema::merge(&[first, second]).expect("merge failed");
+        assert_eq!("RootSchemaQuery", merged.query_type_name());
+        assert!(!merged.vertex_types.contains_key("Query"));
+        assert!(merged.fields.contains_key(&("RootSchemaQuery".into(), "Bar".into())));
+    }
+
+    #[test]
+    fn merge_detects_conflicts() {
+        let foo = make_schema("type RootSchemaQuery { Foo: Foo }\ntype Foo { value: Int }");
+        let other_foo = make_schema("type RootSchemaQuery { Bar: Foo }\ntype Foo { value: Int }");
+        let foo_edge = make_schema("type RootSchemaQuery { Foo: Bar }\ntype Bar { value: Int }");
+        let foo_scalar =
+            make_schema("scalar Foo\ntype RootSchemaQuery { Bar: Bar }\ntype Bar { value: Int }");
+
+        assert_eq!(Err(SchemaMergeError::NoSchemasToMerge), Schema::merge(&[]).map(|_| ()));
+        assert_eq!(
+            Err(SchemaMergeError::DuplicateVertexType("Foo".into())),
+            Schema::merge(&[foo.clone(), other_foo]).map(|_| ())
+        );
+        assert_eq!(
+            Err(SchemaMergeError::DuplicateRootEdge("Foo".into())),
+            Schema::merge(&[foo.clone(), foo_edge]).map(|_| ())
+        );
+        assert_eq!(
+            Err(SchemaMergeError::ScalarAndVertexTypeWithSameName("Foo".into())),
+            Schema::merge(&[foo, foo_scalar]).map(|_| ())
+        );
+    }
+}
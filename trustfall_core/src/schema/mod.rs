@@ -15,7 +15,7 @@ use async_graphql_parser::{
 };
 
 pub use ::async_graphql_parser::Error;
-use async_graphql_value::Name;
+use async_graphql_value::{ConstValue, Name};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
@@ -25,9 +25,16 @@ use crate::util::{BTreeMapTryInsertExt, HashMapTryInsertExt};
 use self::error::InvalidSchemaError;
 
 mod adapter;
+mod analysis;
+mod compatibility;
+mod diff;
 pub mod error;
+mod merge;
+mod serialization;
 
 pub use adapter::SchemaAdapter;
+pub use analysis::SchemaWarning;
+pub use diff::{SchemaChange, SchemaDiff};
 
 #[derive(Debug, Clone)]
 pub struct Schema {
@@ -38,6 +45,9 @@ pub struct Schema {
     pub(crate) vertex_types: HashMap<Arc<str>, TypeDefinition>,
     pub(crate) fields: HashMap<(Arc<str>, Arc<str>), FieldDefinition>,
     pub(crate) field_origins: BTreeMap<(Arc<str>, Arc<str>), FieldOrigin>,
+
+    /// Computed on first use by [`Schema::fingerprint`].
+    pub(crate) fingerprint: OnceLock<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -89,15 +99,23 @@ pub(crate) fn get_builtin_scalars() -> &'static HashSet<&'static str> {
 
 const RESERVED_PREFIX: &str = "__";
 
+/// The deprecation reason of fields deprecated without stating one, as in the GraphQL spec.
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
 impl Schema {
     pub const ALL_DIRECTIVE_DEFINITIONS: &'static str = "
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 ";
 
     pub fn parse(input: impl AsRef<str>) -> Result<Self, InvalidSchemaError> {
@@ -147,7 +165,7 @@ directive @transform(op: String!) on FIELD
                         TypeKind::Scalar => {
                             scalars.insert_or_error(type_name.clone(), node.clone()).unwrap();
                         }
-                        TypeKind::Object(_) | TypeKind::Interface(_) => {
+                        TypeKind::Object(_) | TypeKind::Interface(_) | TypeKind::Union(_) => {
                             match vertex_types.insert_or_error(type_name.clone(), node.clone()) {
                                 Ok(_) => {}
                                 Err(err) => {
@@ -161,7 +179,6 @@ directive @transform(op: String!) on FIELD
                             }
                         }
                         TypeKind::Enum(_) => unimplemented!("Trustfall does not support enum's"),
-                        TypeKind::Union(_) => unimplemented!("Trustfall does not support unions's"),
                         TypeKind::InputObject(_) => {
                             unimplemented!("Trustfall does not support input objects's")
                         }
@@ -211,18 +228,25 @@ directive @transform(op: String!) on FIELD
         if let Err(e) = check_required_transitive_implementations(&vertex_types) {
             errors.extend(e);
         }
+        if let Err(e) = check_union_members(&vertex_types) {
+            errors.extend(e);
+        }
         if let Err(e) = check_field_type_narrowing(&vertex_types, &fields) {
             errors.extend(e);
         }
         if let Err(e) = check_fields_required_by_interface_implementations(&vertex_types, &fields) {
             errors.extend(e);
         }
-        if let Err(e) =
-            check_type_and_property_and_edge_invariants(query_type_definition, &vertex_types)
-        {
+        if let Err(e) = check_type_and_property_and_edge_invariants(
+            query_type_definition,
+            &vertex_types,
+            &scalars,
+        ) {
             errors.extend(e);
         }
-        if let Err(e) = check_root_query_type_invariants(query_type_definition, &query_type) {
+        if let Err(e) =
+            check_root_query_type_invariants(query_type_definition, &query_type, &scalars)
+        {
             errors.extend(e);
         }
 
@@ -248,6 +272,7 @@ directive @transform(op: String!) on FIELD
                 vertex_types,
                 fields,
                 field_origins: field_origins.expect("no field origins but also no errors"),
+                fingerprint: OnceLock::new(),
             })
         } else {
             Err(errors.into())
@@ -264,17 +289,63 @@ directive @transform(op: String!) on FIELD
             return None;
         }
 
-        Some(self.vertex_types.iter().sorted_by_key(|(name, _)| *name).filter_map(
-            move |(name, defn)| {
-                if name.as_ref() == type_name
-                    || get_vertex_type_implements(defn).iter().any(|x| x.node.as_ref() == type_name)
-                {
-                    Some(name.as_ref())
-                } else {
-                    None
-                }
-            },
-        ))
+        Some(self.vertex_types.keys().sorted().filter_map(move |name| {
+            if is_named_type_subtype(&self.vertex_types, type_name, name) {
+                Some(name.as_ref())
+            } else {
+                None
+            }
+        }))
+    }
+
+    /// The documentation of the named vertex type or scalar, from its description
+    /// in the schema text. Returns None if the type is undefined or undocumented.
+    pub fn type_docs(&self, type_name: &str) -> Option<&str> {
+        let defn = self.vertex_types.get(type_name).or_else(|| self.scalars.get(type_name))?;
+        defn.description.as_ref().map(|docs| docs.node.as_str())
+    }
+
+    /// The documentation of the named property or edge of the named vertex type,
+    /// including the edges of the root query type.
+    ///
+    /// If the vertex type's own definition of the field is undocumented,
+    /// it inherits the documentation of the interface that first defined the field, if any.
+    pub fn field_docs(&self, type_name: &str, field_name: &str) -> Option<&str> {
+        let key = (Arc::from(type_name), Arc::from(field_name));
+        let defn = self.fields.get(&key)?;
+        if let Some(docs) = &defn.description {
+            return Some(docs.node.as_str());
+        }
+
+        match self.field_origins.get(&key)? {
+            FieldOrigin::SingleAncestor(origin) if origin.as_ref() != type_name => {
+                self.field_docs(origin, field_name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The documentation of the named parameter of an edge of the named vertex type,
+    /// including the edges of the root query type.
+    pub fn edge_parameter_docs(
+        &self,
+        type_name: &str,
+        edge_name: &str,
+        parameter_name: &str,
+    ) -> Option<&str> {
+        let defn = self.fields.get(&(Arc::from(type_name), Arc::from(edge_name)))?;
+        let parameter = defn.arguments.iter().find(|arg| arg.node.name.node == parameter_name)?;
+        parameter.node.description.as_ref().map(|docs| docs.node.as_str())
+    }
+
+    /// The reason the named property or edge of the named vertex type is deprecated,
+    /// from its `@deprecated(reason: "...")` directive in the schema text.
+    ///
+    /// Fields deprecated without a stated reason use the GraphQL default reason,
+    /// "No longer supported". Returns None if the field is undefined or not deprecated.
+    pub fn field_deprecation_reason(&self, type_name: &str, field_name: &str) -> Option<&str> {
+        let defn = self.fields.get(&(Arc::from(type_name), Arc::from(field_name)))?;
+        get_deprecation_reason(defn)
     }
 
     pub(crate) fn query_type_name(&self) -> &str {
@@ -301,13 +372,15 @@ directive @transform(op: String!) on FIELD
 fn check_root_query_type_invariants(
     query_type_definition: &TypeDefinition,
     query_type: &ObjectType,
+    scalars: &HashMap<Arc<str>, TypeDefinition>,
 ) -> Result<(), Vec<InvalidSchemaError>> {
     let mut errors: Vec<InvalidSchemaError> = vec![];
 
     for field_defn in &query_type.fields {
         let field_type = Type::from_type(&field_defn.node.ty.node);
         let base_named_type = field_type.base_type();
-        if get_builtin_scalars().contains(base_named_type) {
+        if get_builtin_scalars().contains(base_named_type) || scalars.contains_key(base_named_type)
+        {
             errors.push(InvalidSchemaError::PropertyFieldOnRootQueryType(
                 query_type_definition.name.node.to_string(),
                 field_defn.node.name.node.to_string(),
@@ -333,6 +406,7 @@ fn check_root_query_type_invariants(
 fn check_type_and_property_and_edge_invariants(
     query_type_definition: &TypeDefinition,
     vertex_types: &HashMap<Arc<str>, TypeDefinition>,
+    scalars: &HashMap<Arc<str>, TypeDefinition>,
 ) -> Result<(), Vec<InvalidSchemaError>> {
     let mut errors: Vec<InvalidSchemaError> = vec![];
 
@@ -357,8 +431,10 @@ fn check_type_and_property_and_edge_invariants(
             let field_type = Type::from_type(field_type);
 
             let base_named_type = field_type.base_type();
-            if get_builtin_scalars().contains(base_named_type) {
-                // We're looking at a property field.
+            if get_builtin_scalars().contains(base_named_type)
+                || scalars.contains_key(base_named_type)
+            {
+                // We're looking at a property field, whose type is a built-in or custom scalar.
                 if !field_defn.arguments.is_empty() {
                     errors.push(InvalidSchemaError::PropertyFieldWithParameters(
                         type_name.to_string(),
@@ -383,7 +459,10 @@ fn check_type_and_property_and_edge_invariants(
                             let param_type = &param_defn.node.ty.node;
                             match value.node.clone().try_into() {
                                 Ok(value) => {
-                                    if !Type::from_type(param_type).is_valid_value(&value) {
+                                    if Type::from_type(param_type)
+                                        .coerce_input_value(&value)
+                                        .is_none()
+                                    {
                                         errors.push(InvalidSchemaError::InvalidDefaultValueForFieldParameter(
                                             type_name.to_string(),
                                             field_defn.name.node.to_string(),
@@ -441,23 +520,27 @@ fn is_named_type_subtype(
     parent_type: &str,
     maybe_subtype: &str,
 ) -> bool {
-    let parent_is_vertex = vertex_types.contains_key(parent_type);
+    let parent_vertex = vertex_types.get(parent_type);
     let maybe_sub = vertex_types.get(maybe_subtype);
 
-    match (parent_is_vertex, maybe_sub) {
-        (false, None) => {
+    match (parent_vertex, maybe_sub) {
+        (None, None) => {
             // The types could both be scalars, which have no inheritance hierarchy.
             // Any type is a subtype of itself, so we check equality.
             parent_type == maybe_subtype
         }
-        (true, Some(maybe_subtype_vertex)) => {
+        (Some(parent_vertex), Some(maybe_subtype_vertex)) => {
             // Both types are vertex types. We have a subtype relationship if
             // - the two types are actually the same type, or if
-            // - the "maybe subtype" implements the parent type.
+            // - the "maybe subtype" implements the parent type, or if
+            // - the parent type is a union that has the "maybe subtype" as a member.
             parent_type == maybe_subtype
                 || get_vertex_type_implements(maybe_subtype_vertex)
                     .iter()
                     .any(|pos| pos.node.as_ref() == parent_type)
+                || get_union_members(parent_vertex)
+                    .iter()
+                    .any(|pos| pos.node.as_ref() == maybe_subtype)
         }
         _ => {
             // One type is a vertex type, the other should be a scalar.
@@ -580,6 +663,45 @@ fn check_required_transitive_implementations(
     }
 }
 
+/// Check the member types of union type definitions.
+///
+/// Checked invariants:
+/// - Union members must be defined in the schema.
+/// - Union members must be object types.
+fn check_union_members(
+    vertex_types: &HashMap<Arc<str>, TypeDefinition>,
+) -> Result<(), Vec<InvalidSchemaError>> {
+    let mut errors: Vec<InvalidSchemaError> = vec![];
+
+    for (type_name, type_defn) in vertex_types.iter().sorted_by_key(|(name, _)| *name) {
+        for member in get_union_members(type_defn) {
+            let member = member.node.as_ref();
+            match vertex_types.get(member) {
+                Some(member_defn) => {
+                    if !matches!(member_defn.kind, TypeKind::Object(..)) {
+                        errors.push(InvalidSchemaError::NonObjectUnionMember(
+                            type_name.to_string(),
+                            member.to_string(),
+                        ));
+                    }
+                }
+                None => {
+                    errors.push(InvalidSchemaError::NonExistentUnionMember(
+                        type_name.to_string(),
+                        member.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 fn check_fields_required_by_interface_implementations(
     vertex_types: &HashMap<Arc<str>, TypeDefinition>,
     fields: &HashMap<(Arc<str>, Arc<str>), FieldDefinition>,
@@ -729,10 +851,20 @@ fn check_field_type_narrowing(
     }
 }
 
-fn get_vertex_type_fields(vertex: &TypeDefinition) -> &[Positioned<FieldDefinition>] {
+pub(crate) fn get_deprecation_reason(field: &FieldDefinition) -> Option<&str> {
+    let directive =
+        field.directives.iter().find(|directive| directive.node.name.node == "deprecated")?;
+    match directive.node.get_argument("reason").map(|reason| &reason.node) {
+        Some(ConstValue::String(reason)) => Some(reason.as_str()),
+        _ => Some(DEFAULT_DEPRECATION_REASON),
+    }
+}
+
+pub(crate) fn get_vertex_type_fields(vertex: &TypeDefinition) -> &[Positioned<FieldDefinition>] {
     match &vertex.kind {
         TypeKind::Object(obj) => &obj.fields,
         TypeKind::Interface(iface) => &iface.fields,
+        TypeKind::Union(_) => &[],
         _ => unreachable!(),
     }
 }
@@ -741,10 +873,18 @@ fn get_vertex_type_implements(vertex: &TypeDefinition) -> &[Positioned<Name>] {
     match &vertex.kind {
         TypeKind::Object(obj) => &obj.implements,
         TypeKind::Interface(iface) => &iface.implements,
+        TypeKind::Union(_) => &[],
         _ => unreachable!(),
     }
 }
 
+fn get_union_members(vertex: &TypeDefinition) -> &[Positioned<Name>] {
+    match &vertex.kind {
+        TypeKind::Union(union) => &union.members,
+        _ => &[],
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn get_field_origins(
     vertex_types: &HashMap<Arc<str>, TypeDefinition>,
@@ -860,7 +1000,10 @@ mod tests {
     use itertools::Itertools;
     use trustfall_filetests_macros::parameterize;
 
-    use super::{error::InvalidSchemaError, Schema};
+    use super::{
+        error::{InvalidSchemaError, SchemaBytesError},
+        Schema,
+    };
 
     #[parameterize("trustfall_core/test_data/tests/schema_errors", "*.graphql")]
     fn schema_errors(base: &Path, stem: &str) {
@@ -894,12 +1037,22 @@ mod tests {
         // Ensure all test schemas contain the directive definitions this module promises are valid.
         assert!(input_data.contains(Schema::ALL_DIRECTIVE_DEFINITIONS));
 
-        match Schema::parse(input_data) {
-            Ok(_) => {}
+        let schema = match Schema::parse(input_data) {
+            Ok(schema) => schema,
             Err(e) => {
                 panic!("{}", e);
             }
-        }
+        };
+
+        let bytes = schema.to_bytes();
+        let deserialized = Schema::from_bytes(&bytes).expect("failed to deserialize schema");
+        assert_eq!(bytes, deserialized.to_bytes());
+        assert_eq!(format!("{:?}", schema.query_type), format!("{:?}", deserialized.query_type));
+        assert_eq!(
+            format!("{:?}", schema.fields.iter().sorted_by_key(|(k, _)| *k).collect_vec()),
+            format!("{:?}", deserialized.fields.iter().sorted_by_key(|(k, _)| *k).collect_vec()),
+        );
+        assert!(Schema::diff(&schema, &deserialized).is_empty());
     }
 
     #[test]
@@ -915,5 +1068,164 @@ mod tests {
         let mut number_subtypes = schema.subtypes("Number").unwrap().collect_vec();
         number_subtypes.sort_unstable();
         assert_eq!(vec!["Composite", "Neither", "Number", "Prime"], number_subtypes);
+
+        let union_subtypes = schema.subtypes("Factorizable").unwrap().collect_vec();
+        assert_eq!(vec!["Composite", "Factorizable", "Prime"], union_subtypes);
+        assert!(schema.is_named_type_subtype("Factorizable", "Prime"));
+        assert!(!schema.is_named_type_subtype("Factorizable", "Neither"));
+        assert!(!schema.is_named_type_subtype("Number", "Factorizable"));
+    }
+
+    pub(super) const DOCUMENTED_SCHEMA: &str = r#"
+schema {
+    query: RootSchemaQuery
+}
+
+type RootSchemaQuery {
+    """
+    Numbers, starting from zero.
+    """
+    Number(
+        """
+        The largest number to produce.
+        """
+        max: Int!
+        min: Int = 0
+    ): [Number!]!
+}
+
+"""
+A number and its neighbors.
+"""
+interface Number {
+    """
+    The value of the number.
+    """
+    value: Int!
+
+    """
+    The next number.
+    """
+    successor: Number!
+}
+
+type Prime implements Number {
+    value: Int!
+
+    """
+    The next prime number, not just the next number.
+    """
+    successor: Number!
+}
+
+"""
+Milliseconds since the epoch.
+"""
+scalar Timestamp
+"#;
+
+    pub(super) const DEPRECATED_SCHEMA: &str = r#"
+schema {
+    query: RootSchemaQuery
+}
+
+type RootSchemaQuery {
+    Number(max: Int!): [Number!]!
+}
+
+type Number {
+    value: Int
+    oldValue: Int @deprecated
+    next: Number @deprecated(reason: "Use `successor` instead.")
+    successor: Number
+}
+"#;
+
+    #[test]
+    fn schema_deprecations() {
+        let schema = Schema::parse(DEPRECATED_SCHEMA).expect("valid schema");
+
+        assert_eq!(None, schema.field_deprecation_reason("Number", "value"));
+        assert_eq!(
+            Some("No longer supported"),
+            schema.field_deprecation_reason("Number", "oldValue")
+        );
+        assert_eq!(
+            Some("Use `successor` instead."),
+            schema.field_deprecation_reason("Number", "next")
+        );
+        assert_eq!(None, schema.field_deprecation_reason("Number", "nonexistent"));
+    }
+
+    #[test]
+    fn schema_docs() {
+        let schema = Schema::parse(DOCUMENTED_SCHEMA).expect("valid schema");
+
+        assert_eq!(Some("A number and its neighbors."), schema.type_docs("Number"));
+        assert_eq!(Some("Milliseconds since the epoch."), schema.type_docs("Timestamp"));
+        assert_eq!(None, schema.type_docs("Prime"));
+        assert_eq!(None, schema.type_docs("Nonexistent"));
+
+        assert_eq!(
+            Some("Numbers, starting from zero."),
+            schema.field_docs("RootSchemaQuery", "Number")
+        );
+        assert_eq!(Some("The value of the number."), schema.field_docs("Number", "value"));
+        assert_eq!(Some("The value of the number."), schema.field_docs("Prime", "value"));
+        assert_eq!(
+            Some("The next prime number, not just the next number."),
+            schema.field_docs("Prime", "successor")
+        );
+        assert_eq!(None, schema.field_docs("Prime", "nonexistent"));
+
+        assert_eq!(
+            Some("The largest number to produce."),
+            schema.edge_parameter_docs("RootSchemaQuery", "Number", "max")
+        );
+        assert_eq!(None, schema.edge_parameter_docs("RootSchemaQuery", "Number", "min"));
+        assert_eq!(None, schema.edge_parameter_docs("RootSchemaQuery", "Number", "nonexistent"));
+    }
+
+    #[test]
+    fn schema_bytes_roundtrip() {
+        let schema = Schema::parse(DOCUMENTED_SCHEMA).expect("valid schema");
+        let deserialized = Schema::from_bytes(&schema.to_bytes()).expect("valid schema bytes");
+
+        assert_eq!(Some("A number and its neighbors."), deserialized.type_docs("Number"));
+        assert_eq!(Some("The value of the number."), deserialized.field_docs("Prime", "value"));
+        assert_eq!(
+            Some("The largest number to produce."),
+            deserialized.edge_parameter_docs("RootSchemaQuery", "Number", "max")
+        );
+        assert_eq!(vec!["Number", "Prime"], deserialized.subtypes("Number").unwrap().collect_vec());
+
+        let schema = Schema::parse(DEPRECATED_SCHEMA).expect("valid schema");
+        let deserialized = Schema::from_bytes(&schema.to_bytes()).expect("valid schema bytes");
+        assert_eq!(
+            Some("Use `successor` instead."),
+            deserialized.field_deprecation_reason("Number", "next")
+        );
+    }
+
+    #[test]
+    fn schema_bytes_errors() {
+        let schema = Schema::parse(DOCUMENTED_SCHEMA).expect("valid schema");
+        let bytes = schema.to_bytes();
+
+        assert_eq!(
+            SchemaBytesError::NotASchema,
+            Schema::from_bytes(DOCUMENTED_SCHEMA.as_bytes()).unwrap_err()
+        );
+        assert_eq!(SchemaBytesError::NotASchema, Schema::from_bytes(&bytes[..10]).unwrap_err());
+
+        let mut other_version = bytes.clone();
+        other_version[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Schema::from_bytes(&other_version),
+            Err(SchemaBytesError::UnsupportedFormatVersion(u32::MAX, _))
+        ));
+
+        let truncated = &bytes[..bytes.len() / 2];
+        assert!(matches!(Schema::from_bytes(truncated), Err(SchemaBytesError::InvalidData(_))));
     }
 }
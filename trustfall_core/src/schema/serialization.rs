@@ -0,0 +1,590 @@
+use std::{collections::BTreeMap, sync::Arc};
+#[cfg(feature = "serialization")]
+use std::{collections::HashMap, sync::OnceLock};
+
+use async_graphql_parser::{
+    types::{
+        BaseType, ConstDirective, DirectiveDefinition, DirectiveLocation, FieldDefinition,
+        InputValueDefinition, InterfaceType, ObjectType, SchemaDefinition, Type, TypeDefinition,
+        TypeKind, UnionType,
+    },
+    Pos, Positioned,
+};
+use async_graphql_value::{ConstValue, Name, Number};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serialization")]
+use super::error::SchemaBytesError;
+use super::{FieldOrigin, Schema};
+
+/// Identifies the data as a serialized Trustfall schema.
+#[cfg(feature = "serialization")]
+const MAGIC: &[u8; 8] = b"TFSCHEMA";
+
+/// Bump this whenever the serialized representation changes in any way,
+/// so that data serialized in the old representation is rejected instead of misread.
+#[cfg(feature = "serialization")]
+const FORMAT_VERSION: u32 = 2;
+
+impl Schema {
+    /// Serialize this schema into a compact binary representation,
+    /// which [`Schema::from_bytes`] can turn back into an equivalent schema
+    /// without parsing or validating the schema text again.
+    ///
+    /// The representation is deterministic, so serializing the same schema
+    /// always produces the same bytes.
+    #[cfg(feature = "serialization")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1024);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        ciborium::into_writer(&self.serialized(), &mut bytes).expect("failed to serialize schema");
+        bytes
+    }
+
+    /// A fingerprint identifying this schema, computed from the same representation
+    /// that `Schema::to_bytes()` serializes, so it is the same for schemas
+    /// that serialize to the same bytes.
+    ///
+    /// The fingerprint is stable across runs and platforms.
+    /// It is computed on first use, and reused afterward.
+    pub fn fingerprint(&self) -> u64 {
+        // 64-bit FNV-1a, since the standard library's hashers are not guaranteed to be stable.
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        *self.fingerprint.get_or_init(|| {
+            // JSON is always available, unlike the binary format behind the optional
+            // `serialization` feature, so fingerprints don't depend on enabled features.
+            serde_json::to_vec(&self.serialized())
+                .expect("failed to serialize schema")
+                .into_iter()
+                .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+        })
+    }
+
+    fn serialized(&self) -> SerializedSchema {
+        SerializedSchema {
+            schema: (&self.schema).into(),
+            directives: sorted_values(self.directives.iter()).map(Into::into).collect(),
+            scalars: sorted_values(self.scalars.iter()).map(Into::into).collect(),
+            vertex_types: sorted_values(self.vertex_types.iter()).map(Into::into).collect(),
+            field_origins: self
+                .field_origins
+                .iter()
+                .map(|((type_name, field_name), origin)| {
+                    (type_name.clone(), field_name.clone(), origin.clone())
+                })
+                .collect(),
+        }
+    }
+
+    /// Deserialize a schema from bytes produced by [`Schema::to_bytes`].
+    ///
+    /// Data serialized by a version of Trustfall with a different serialized representation
+    /// is rejected with [`SchemaBytesError::UnsupportedFormatVersion`].
+    ///
+    /// The schema is assumed to be valid, since it was validated before it was serialized,
+    /// so only use bytes from a trusted source such as a cache the application itself wrote.
+    #[cfg(feature = "serialization")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SchemaBytesError> {
+        let payload = bytes.strip_prefix(MAGIC.as_slice()).ok_or(SchemaBytesError::NotASchema)?;
+        if payload.len() < 4 {
+            return Err(SchemaBytesError::NotASchema);
+        }
+        let (version, payload) = payload.split_at(4);
+        let version = u32::from_le_bytes(version.try_into().expect("not 4 bytes"));
+        if version != FORMAT_VERSION {
+            return Err(SchemaBytesError::UnsupportedFormatVersion(version, FORMAT_VERSION));
+        }
+
+        let serialized: SerializedSchema = ciborium::from_reader(payload)
+            .map_err(|e| SchemaBytesError::InvalidData(e.to_string()))?;
+
+        let schema: SchemaDefinition = serialized.schema.into();
+        let directives = serialized
+            .directives
+            .into_iter()
+            .map(|directive| {
+                let directive: DirectiveDefinition = directive.into();
+                (Arc::from(directive.name.node.as_str()), directive)
+            })
+            .collect();
+        let scalars = serialized
+            .scalars
+            .into_iter()
+            .map(|scalar| {
+                let scalar: TypeDefinition = scalar.into();
+                (Arc::from(scalar.name.node.as_str()), scalar)
+            })
+            .collect();
+
+        let mut vertex_types = HashMap::with_capacity(serialized.vertex_types.len());
+        let mut fields = HashMap::new();
+        for vertex_type in serialized.vertex_types {
+            let vertex_type: TypeDefinition = vertex_type.into();
+            let type_name: Arc<str> = Arc::from(vertex_type.name.node.as_str());
+            let field_defs = match &vertex_type.kind {
+                TypeKind::Object(object) => object.fields.as_slice(),
+                TypeKind::Interface(interface) => interface.fields.as_slice(),
+                _ => &[],
+            };
+            for field in field_defs {
+                let field_name = Arc::from(field.node.name.node.as_str());
+                fields.insert((type_name.clone(), field_name), field.node.clone());
+            }
+            vertex_types.insert(type_name, vertex_type);
+        }
+
+        let query_type_name = schema
+            .query
+            .as_ref()
+            .ok_or_else(|| SchemaBytesError::InvalidData("no query type".to_string()))?
+            .node
+            .as_str();
+        let query_type = match vertex_types.get(query_type_name).map(|defn| &defn.kind) {
+            Some(TypeKind::Object(object)) => object.clone(),
+            _ => {
+                return Err(SchemaBytesError::InvalidData(format!(
+                    "query type {query_type_name} is not an object type"
+                )))
+            }
+        };
+
+        Ok(Self {
+            schema,
+            query_type,
+            directives,
+            scalars,
+            vertex_types,
+            fields,
+            field_origins: serialized
+                .field_origins
+                .into_iter()
+                .map(|(type_name, field_name, origin)| ((type_name, field_name), origin))
+                .collect(),
+            fingerprint: OnceLock::new(),
+        })
+    }
+}
+
+fn sorted_values<'a, V: 'a>(
+    iter: impl Iterator<Item = (&'a Arc<str>, &'a V)>,
+) -> impl Iterator<Item = &'a V> {
+    let sorted: BTreeMap<_, _> = iter.collect();
+    sorted.into_values()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedSchema {
+    schema: SerializedSchemaDefinition,
+    directives: Vec<SerializedDirectiveDefinition>,
+    scalars: Vec<SerializedTypeDefinition>,
+    vertex_types: Vec<SerializedTypeDefinition>,
+    // Type name, field name, and origin. A list rather than a map,
+    // since JSON maps only support string keys.
+    field_origins: Vec<(Arc<str>, Arc<str>, FieldOrigin)>,
+}
+
+/// A value together with its line and column in the schema text.
+#[derive(Debug, Serialize, Deserialize)]
+struct Spanned<T>(T, usize, usize);
+
+impl<T> Spanned<T> {
+    fn from_positioned<'a, U>(value: &'a Positioned<U>, f: impl FnOnce(&'a U) -> T) -> Self {
+        Self(f(&value.node), value.pos.line, value.pos.column)
+    }
+
+    fn into_positioned<U>(self, f: impl FnOnce(T) -> U) -> Positioned<U> {
+        Positioned::new(f(self.0), Pos { line: self.1, column: self.2 })
+    }
+}
+
+fn spanned_name(name: &Positioned<Name>) -> Spanned<String> {
+    Spanned::from_positioned(name, |name| name.to_string())
+}
+
+fn positioned_name(name: Spanned<String>) -> Positioned<Name> {
+    name.into_positioned(Name::new)
+}
+
+fn spanned_description(description: &Option<Positioned<String>>) -> Option<Spanned<String>> {
+    description.as_ref().map(|docs| Spanned::from_positioned(docs, Clone::clone))
+}
+
+fn positioned_description(description: Option<Spanned<String>>) -> Option<Positioned<String>> {
+    description.map(|docs| docs.into_positioned(|docs| docs))
+}
+
+fn spanned_all<'a, T, U: From<&'a T>>(values: &'a [Positioned<T>]) -> Vec<Spanned<U>> {
+    values.iter().map(|value| Spanned::from_positioned(value, U::from)).collect()
+}
+
+fn positioned_all<T, U: From<T>>(values: Vec<Spanned<T>>) -> Vec<Positioned<U>> {
+    values.into_iter().map(|value| value.into_positioned(U::from)).collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedSchemaDefinition {
+    directives: Vec<Spanned<SerializedDirective>>,
+    query: Option<Spanned<String>>,
+    mutation: Option<Spanned<String>>,
+    subscription: Option<Spanned<String>>,
+}
+
+impl From<&SchemaDefinition> for SerializedSchemaDefinition {
+    fn from(value: &SchemaDefinition) -> Self {
+        Self {
+            directives: spanned_all(&value.directives),
+            query: value.query.as_ref().map(spanned_name),
+            mutation: value.mutation.as_ref().map(spanned_name),
+            subscription: value.subscription.as_ref().map(spanned_name),
+        }
+    }
+}
+
+impl From<SerializedSchemaDefinition> for SchemaDefinition {
+    fn from(value: SerializedSchemaDefinition) -> Self {
+        Self {
+            extend: false,
+            directives: positioned_all(value.directives),
+            query: value.query.map(positioned_name),
+            mutation: value.mutation.map(positioned_name),
+            subscription: value.subscription.map(positioned_name),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedDirectiveDefinition {
+    description: Option<Spanned<String>>,
+    name: Spanned<String>,
+    arguments: Vec<Spanned<SerializedInputValueDefinition>>,
+    is_repeatable: bool,
+    locations: Vec<Spanned<SerializedDirectiveLocation>>,
+}
+
+impl From<&DirectiveDefinition> for SerializedDirectiveDefinition {
+    fn from(value: &DirectiveDefinition) -> Self {
+        Self {
+            description: spanned_description(&value.description),
+            name: spanned_name(&value.name),
+            arguments: spanned_all(&value.arguments),
+            is_repeatable: value.is_repeatable,
+            locations: spanned_all(&value.locations),
+        }
+    }
+}
+
+impl From<SerializedDirectiveDefinition> for DirectiveDefinition {
+    fn from(value: SerializedDirectiveDefinition) -> Self {
+        Self {
+            description: positioned_description(value.description),
+            name: positioned_name(value.name),
+            arguments: positioned_all(value.arguments),
+            is_repeatable: value.is_repeatable,
+            locations: positioned_all(value.locations),
+        }
+    }
+}
+
+macro_rules! mirror_directive_locations {
+    ($($variant:ident),* $(,)?) => {
+        #[derive(Debug, Serialize, Deserialize)]
+        enum SerializedDirectiveLocation {
+            $($variant,)*
+        }
+
+        impl From<&DirectiveLocation> for SerializedDirectiveLocation {
+            fn from(value: &DirectiveLocation) -> Self {
+                match value {
+                    $(DirectiveLocation::$variant => Self::$variant,)*
+                }
+            }
+        }
+
+        impl From<SerializedDirectiveLocation> for DirectiveLocation {
+            fn from(value: SerializedDirectiveLocation) -> Self {
+                match value {
+                    $(SerializedDirectiveLocation::$variant => Self::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+mirror_directive_locations!(
+    Query,
+    Mutation,
+    Subscription,
+    Field,
+    FragmentDefinition,
+    FragmentSpread,
+    InlineFragment,
+    Schema,
+    Scalar,
+    Object,
+    FieldDefinition,
+    ArgumentDefinition,
+    Interface,
+    Union,
+    Enum,
+    EnumValue,
+    InputObject,
+    InputFieldDefinition,
+    VariableDefinition,
+);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedTypeDefinition {
+    description: Option<Spanned<String>>,
+    name: Spanned<String>,
+    directives: Vec<Spanned<SerializedDirective>>,
+    kind: SerializedTypeKind,
+}
+
+/// Only the kinds of types that valid schemas may contain.
+#[derive(Debug, Serialize, Deserialize)]
+enum SerializedTypeKind {
+    Scalar,
+    Object { implements: Vec<Spanned<String>>, fields: Vec<Spanned<SerializedFieldDefinition>> },
+    Interface { implements: Vec<Spanned<String>>, fields: Vec<Spanned<SerializedFieldDefinition>> },
+    Union { members: Vec<Spanned<String>> },
+}
+
+impl From<&TypeDefinition> for SerializedTypeDefinition {
+    fn from(value: &TypeDefinition) -> Self {
+        let kind = match &value.kind {
+            TypeKind::Scalar => SerializedTypeKind::Scalar,
+            TypeKind::Object(object) => SerializedTypeKind::Object {
+                implements: object.implements.iter().map(spanned_name).collect(),
+                fields: spanned_all(&object.fields),
+            },
+            TypeKind::Interface(interface) => SerializedTypeKind::Interface {
+                implements: interface.implements.iter().map(spanned_name).collect(),
+                fields: spanned_all(&interface.fields),
+            },
+            TypeKind::Union(union) => SerializedTypeKind::Union {
+                members: union.members.iter().map(spanned_name).collect(),
+            },
+            TypeKind::Enum(_) | TypeKind::InputObject(_) => {
+                unreachable!("schema contains unsupported type kind: {value:?}")
+            }
+        };
+
+        Self {
+            description: spanned_description(&value.description),
+            name: spanned_name(&value.name),
+            directives: spanned_all(&value.directives),
+            kind,
+        }
+    }
+}
+
+impl From<SerializedTypeDefinition> for TypeDefinition {
+    fn from(value: SerializedTypeDefinition) -> Self {
+        let kind = match value.kind {
+            SerializedTypeKind::Scalar => TypeKind::Scalar,
+            SerializedTypeKind::Object { implements, fields } => TypeKind::Object(ObjectType {
+                implements: implements.into_iter().map(positioned_name).collect(),
+                fields: positioned_all(fields),
+            }),
+            SerializedTypeKind::Interface { implements, fields } => {
+                TypeKind::Interface(InterfaceType {
+                    implements: implements.into_iter().map(positioned_name).collect(),
+                    fields: positioned_all(fields),
+                })
+            }
+            SerializedTypeKind::Union { members } => TypeKind::Union(UnionType {
+                members: members.into_iter().map(positioned_name).collect(),
+            }),
+        };
+
+        Self {
+            extend: false,
+            description: positioned_description(value.description),
+            name: positioned_name(value.name),
+            directives: positioned_all(value.directives),
+            kind,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedFieldDefinition {
+    description: Option<Spanned<String>>,
+    name: Spanned<String>,
+    arguments: Vec<Spanned<SerializedInputValueDefinition>>,
+    ty: Spanned<SerializedType>,
+    directives: Vec<Spanned<SerializedDirective>>,
+}
+
+impl From<&FieldDefinition> for SerializedFieldDefinition {
+    fn from(value: &FieldDefinition) -> Self {
+        Self {
+            description: spanned_description(&value.description),
+            name: spanned_name(&value.name),
+            arguments: spanned_all(&value.arguments),
+            ty: Spanned::from_positioned(&value.ty, SerializedType::from),
+            directives: spanned_all(&value.directives),
+        }
+    }
+}
+
+impl From<SerializedFieldDefinition> for FieldDefinition {
+    fn from(value: SerializedFieldDefinition) -> Self {
+        Self {
+            description: positioned_description(value.description),
+            name: positioned_name(value.name),
+            arguments: positioned_all(value.arguments),
+            ty: value.ty.into_positioned(Into::into),
+            directives: positioned_all(value.directives),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedInputValueDefinition {
+    description: Option<Spanned<String>>,
+    name: Spanned<String>,
+    ty: Spanned<SerializedType>,
+    default_value: Option<Spanned<SerializedValue>>,
+    directives: Vec<Spanned<SerializedDirective>>,
+}
+
+impl From<&InputValueDefinition> for SerializedInputValueDefinition {
+    fn from(value: &InputValueDefinition) -> Self {
+        Self {
+            description: spanned_description(&value.description),
+            name: spanned_name(&value.name),
+            ty: Spanned::from_positioned(&value.ty, SerializedType::from),
+            default_value: value
+                .default_value
+                .as_ref()
+                .map(|default| Spanned::from_positioned(default, SerializedValue::from)),
+            directives: spanned_all(&value.directives),
+        }
+    }
+}
+
+impl From<SerializedInputValueDefinition> for InputValueDefinition {
+    fn from(value: SerializedInputValueDefinition) -> Self {
+        Self {
+            description: positioned_description(value.description),
+            name: positioned_name(value.name),
+            ty: value.ty.into_positioned(Into::into),
+            default_value: value.default_value.map(|default| default.into_positioned(Into::into)),
+            directives: positioned_all(value.directives),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedDirective {
+    name: Spanned<String>,
+    arguments: Vec<(Spanned<String>, Spanned<SerializedValue>)>,
+}
+
+impl From<&ConstDirective> for SerializedDirective {
+    fn from(value: &ConstDirective) -> Self {
+        Self {
+            name: spanned_name(&value.name),
+            arguments: value
+                .arguments
+                .iter()
+                .map(|(name, value)| {
+                    (spanned_name(name), Spanned::from_positioned(value, SerializedValue::from))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<SerializedDirective> for ConstDirective {
+    fn from(value: SerializedDirective) -> Self {
+        Self {
+            name: positioned_name(value.name),
+            arguments: value
+                .arguments
+                .into_iter()
+                .map(|(name, value)| (positioned_name(name), value.into_positioned(Into::into)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum SerializedType {
+    Named(String, bool),
+    List(Box<SerializedType>, bool),
+}
+
+impl From<&Type> for SerializedType {
+    fn from(value: &Type) -> Self {
+        match &value.base {
+            BaseType::Named(name) => Self::Named(name.to_string(), value.nullable),
+            BaseType::List(inner) => Self::List(Box::new(inner.as_ref().into()), value.nullable),
+        }
+    }
+}
+
+impl From<SerializedType> for Type {
+    fn from(value: SerializedType) -> Self {
+        match value {
+            SerializedType::Named(name, nullable) => {
+                Self { base: BaseType::Named(Name::new(name)), nullable }
+            }
+            SerializedType::List(inner, nullable) => {
+                Self { base: BaseType::List(Box::new((*inner).into())), nullable }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum SerializedValue {
+    Null,
+    Number(Number),
+    String(String),
+    Boolean(bool),
+    Binary(Vec<u8>),
+    Enum(String),
+    List(Vec<SerializedValue>),
+    Object(Vec<(String, SerializedValue)>),
+}
+
+impl From<&ConstValue> for SerializedValue {
+    fn from(value: &ConstValue) -> Self {
+        match value {
+            ConstValue::Null => Self::Null,
+            ConstValue::Number(n) => Self::Number(n.clone()),
+            ConstValue::String(s) => Self::String(s.clone()),
+            ConstValue::Boolean(b) => Self::Boolean(*b),
+            ConstValue::Binary(bytes) => Self::Binary(bytes.to_vec()),
+            ConstValue::Enum(name) => Self::Enum(name.to_string()),
+            ConstValue::List(values) => Self::List(values.iter().map(Into::into).collect()),
+            ConstValue::Object(values) => Self::Object(
+                values.iter().map(|(key, value)| (key.to_string(), value.into())).collect(),
+            ),
+        }
+    }
+}
+
+impl From<SerializedValue> for ConstValue {
+    fn from(value: SerializedValue) -> Self {
+        match value {
+            SerializedValue::Null => Self::Null,
+            SerializedValue::Number(n) => Self::Number(n),
+            SerializedValue::String(s) => Self::String(s),
+            SerializedValue::Boolean(b) => Self::Boolean(b),
+            SerializedValue::Binary(bytes) => Self::Binary(bytes.into()),
+            SerializedValue::Enum(name) => Self::Enum(Name::new(name)),
+            SerializedValue::List(values) => {
+                Self::List(values.into_iter().map(Into::into).collect())
+            }
+            SerializedValue::Object(values) => Self::Object(
+                values.into_iter().map(|(key, value)| (Name::new(key), value.into())).collect(),
+            ),
+        }
+    }
+}
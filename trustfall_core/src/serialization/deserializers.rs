@@ -250,6 +250,18 @@ impl<'de> de::Deserializer<'de> for FieldValueDeserializer {
             FieldValue::Boolean(v) => visitor.visit_bool(v),
             FieldValue::Enum(_) => todo!(),
             FieldValue::List(v) => visitor.visit_seq(v.to_vec().into_deserializer()),
+            FieldValue::DateTime(v) => visitor.visit_str(&v.to_string()),
+            #[cfg(feature = "num-bigint")]
+            FieldValue::BigInt(v) => {
+                use num_traits::ToPrimitive;
+                if let Some(v) = v.to_i128() {
+                    visitor.visit_i128(v)
+                } else if let Some(v) = v.to_u128() {
+                    visitor.visit_u128(v)
+                } else {
+                    visitor.visit_str(&v.to_string())
+                }
+            }
         }
     }
 
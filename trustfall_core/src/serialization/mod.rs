@@ -4,7 +4,10 @@ use serde::de::DeserializeOwned;
 
 use crate::ir::FieldValue;
 
+pub use validation::{check_output_struct, OutputStructError};
+
 mod deserializers;
+mod validation;
 
 #[cfg(test)]
 mod tests;
@@ -81,6 +84,9 @@ mod tests;
 /// );
 /// ```
 ///
+/// To find mismatches between the struct and the query's outputs before running the query,
+/// instead of while deserializing its results, use [`check_output_struct`].
+///
 /// # Use with edge parameters
 ///
 /// Edges defined in Trustfall schemas may take parameters, for example:
@@ -1,9 +1,12 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc};
 
 use serde::Deserialize;
 
-use super::TryIntoStruct;
-use crate::ir::FieldValue;
+use super::{check_output_struct, OutputStructError, TryIntoStruct};
+use crate::{
+    ir::{FieldValue, Output, Type, Vid},
+    util::DisplayVec,
+};
 
 #[test]
 fn deserialize_simple() {
@@ -178,3 +181,118 @@ fn deserialize_adjust_numeric_type_signedness() {
         output_value
     );
 }
+
+fn outputs(outputs: &[(&str, &str)]) -> BTreeMap<Arc<str>, Output> {
+    outputs
+        .iter()
+        .map(|(name, ty)| {
+            let output = Output {
+                name: Arc::from(*name),
+                value_type: Type::parse(ty).expect("not a valid type"),
+                vid: Vid::new(NonZeroUsize::new(1).unwrap()),
+            };
+            (output.name.clone(), output)
+        })
+        .collect()
+}
+
+#[test]
+fn check_matching_output_struct() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Output {
+        id: u32,
+        name: Option<String>,
+        #[serde(rename = "scoreValue")]
+        score: f64,
+        tags: Vec<Option<String>>,
+        pair: (i64, i64),
+        anything: serde_json::Value,
+
+        // Not part of the query's outputs, but not required either.
+        nickname: Option<String>,
+        #[serde(default)]
+        count: i64,
+    }
+
+    let outputs = outputs(&[
+        ("id", "ID"),
+        ("id", "Int!"),
+        ("name", "String"),
+        ("scoreValue", "Int!"),
+        ("tags", "[String]!"),
+        ("pair", "[Int!]!"),
+        ("anything", "[Float]"),
+    ]);
+    check_output_struct::<Output>(&outputs).expect("struct did not match outputs");
+}
+
+#[test]
+fn check_mismatched_output_struct() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    enum Kind {
+        Big,
+        Small,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Output {
+        name: String,
+        size: i64,
+        kind: Kind,
+        tags: Vec<String>,
+        missing: bool,
+        ratio: i64,
+        also_missing: Vec<u8>,
+    }
+
+    let outputs = outputs(&[
+        ("name", "String"),
+        ("size", "Int!"),
+        ("kind", "String!"),
+        ("tags", "[String]!"),
+        ("ratio", "Float!"),
+        ("extra", "Boolean"),
+    ]);
+    let expected = OutputStructError::MultipleErrors(DisplayVec(vec![
+        OutputStructError::IncompatibleFieldType(
+            "name".to_string(),
+            "String".to_string(),
+            "string".to_string(),
+        ),
+        OutputStructError::IncompatibleFieldType(
+            "kind".to_string(),
+            "String!".to_string(),
+            "enum".to_string(),
+        ),
+        OutputStructError::IncompatibleFieldType(
+            "tags".to_string(),
+            "[String]!".to_string(),
+            "Vec<string>".to_string(),
+        ),
+        OutputStructError::MissingOutput("missing".to_string()),
+        OutputStructError::IncompatibleFieldType(
+            "ratio".to_string(),
+            "Float!".to_string(),
+            "integer".to_string(),
+        ),
+        OutputStructError::MissingOutput("also_missing".to_string()),
+        OutputStructError::UnexpectedOutput("extra".to_string()),
+    ]));
+    assert_eq!(Err(expected), check_output_struct::<Output>(&outputs));
+}
+
+#[test]
+fn check_output_struct_of_non_struct() {
+    let outputs = outputs(&[("name", "String")]);
+    assert_eq!(
+        Err(OutputStructError::NotAStruct(
+            "alloc::collections::btree::map::BTreeMap<alloc::string::String, \
+            core::option::Option<alloc::string::String>>"
+                .to_string()
+        )),
+        check_output_struct::<BTreeMap<String, Option<String>>>(&outputs),
+    );
+}
@@ -0,0 +1,585 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    sync::Arc,
+};
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer};
+
+use crate::{
+    ir::{Output, Type},
+    util::DisplayVec,
+};
+
+/// Ways in which a struct may fail to match the outputs of a query.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OutputStructError {
+    #[error("Multiple errors: {0}")]
+    MultipleErrors(DisplayVec<OutputStructError>),
+
+    #[error("Type {0} is not a struct, so its fields cannot be checked against query outputs.")]
+    NotAStruct(String),
+
+    #[error(
+        "Struct field \"{0}\" is required, but the query has no output by that name. \
+        Either add such an output to the query, or make the field an Option."
+    )]
+    MissingOutput(String),
+
+    #[error(
+        "The query's output \"{0}\" has no corresponding struct field. \
+        Either remove the output from the query, or add such a field to the struct."
+    )]
+    UnexpectedOutput(String),
+
+    #[error(
+        "The query's output \"{0}\" has type {1}, which cannot be deserialized into \
+        the struct field of type {2}."
+    )]
+    IncompatibleFieldType(String, String, String),
+}
+
+impl From<Vec<OutputStructError>> for OutputStructError {
+    fn from(mut errors: Vec<OutputStructError>) -> Self {
+        if errors.len() == 1 {
+            errors.pop().expect("no error present")
+        } else {
+            Self::MultipleErrors(DisplayVec(errors))
+        }
+    }
+}
+
+/// Check that the results of a query with the given outputs can be deserialized
+/// into the struct `S` using [`TryIntoStruct`](super::TryIntoStruct).
+///
+/// Every output must have a struct field by the same name, and vice versa.
+/// Struct fields that are `Option` values need no corresponding output.
+/// Each field's type must be able to hold all values of its output's type: for example,
+/// outputs of nullable type require `Option` fields, and list outputs require `Vec` fields.
+///
+/// Deserializing results may still fail if a value doesn't fit into its field,
+/// for example for integers too large for an `i8` field.
+///
+/// # Example
+/// ```
+/// # use trustfall_core::{check_output_struct, frontend::parse, schema::Schema};
+/// # let schema = Schema::parse(r#"
+/// #     schema { query: RootSchemaQuery }
+/// #     type RootSchemaQuery { Number: [Number!]! }
+/// #     type Number { value: Int!, name: String }
+/// # "#).unwrap();
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Output {
+///     value: i64,
+///     name: String,
+/// }
+///
+/// let query = parse(&schema, r#"
+/// {
+///     Number {
+///         value @output
+///     }
+/// }"#).expect("not a valid query");
+///
+/// let error = check_output_struct::<Output>(&query.outputs).unwrap_err();
+/// assert_eq!(
+///     "Struct field \"name\" is required, but the query has no output by that name. \
+///     Either add such an output to the query, or make the field an Option.",
+///     error.to_string(),
+/// );
+/// ```
+pub fn check_output_struct<S: DeserializeOwned>(
+    outputs: &BTreeMap<Arc<str>, Output>,
+) -> Result<(), OutputStructError> {
+    let Some(probe) = probe_struct::<S>(outputs) else {
+        return Err(OutputStructError::NotAStruct(std::any::type_name::<S>().to_string()));
+    };
+
+    let mut errors = vec![];
+    for field in probe.fields {
+        match outputs.get(*field) {
+            Some(output) => {
+                let shape = &probe.shapes[field];
+                if !shape.accepts(&output.value_type) {
+                    errors.push(OutputStructError::IncompatibleFieldType(
+                        field.to_string(),
+                        output.value_type.to_string(),
+                        shape.to_string(),
+                    ));
+                }
+            }
+            None if probe.required.contains(field) => {
+                errors.push(OutputStructError::MissingOutput(field.to_string()));
+            }
+            None => {}
+        }
+    }
+    for output in outputs.keys() {
+        if !probe.fields.contains(&output.as_ref()) {
+            errors.push(OutputStructError::UnexpectedOutput(output.to_string()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.into())
+    }
+}
+
+/// What the fields of a struct look like, as seen by deserializing it.
+struct StructProbe {
+    fields: &'static [&'static str],
+
+    /// The shapes of the fields the struct was seen deserializing.
+    shapes: BTreeMap<&'static str, Shape>,
+
+    /// Fields without an output that the struct failed to deserialize without.
+    required: BTreeSet<&'static str>,
+}
+
+/// Deserialize `S` from made-up values to discover its fields and their types.
+///
+/// Returns `None` if `S` isn't deserialized as a struct.
+fn probe_struct<S: DeserializeOwned>(outputs: &BTreeMap<Arc<str>, Output>) -> Option<StructProbe> {
+    let mut fields = None;
+    let mut shapes = BTreeMap::new();
+    let mut required = BTreeSet::new();
+    let mut untraceable = BTreeSet::new();
+
+    // Each attempt may uncover another required field without an output, or a field
+    // whose type can't be made up. Retry until the struct deserializes successfully,
+    // or until the fields it requires can't be provided.
+    loop {
+        let provided = |field: &&'static str| {
+            (outputs.contains_key(*field) || required.contains(field))
+                && !untraceable.contains(field)
+        };
+        let deserializer =
+            StructDeserializer { fields: &mut fields, provided, shapes: &mut shapes };
+        match S::deserialize(deserializer) {
+            Ok(_) => break,
+            Err(ProbeError::UntraceableField(field)) => {
+                untraceable.insert(field);
+            }
+            Err(ProbeError::MissingField(field))
+                if !outputs.contains_key(field)
+                    && !required.contains(field)
+                    && !untraceable.contains(field) =>
+            {
+                required.insert(field);
+            }
+            Err(_) => break,
+        }
+    }
+
+    fields.map(|fields| StructProbe { fields, shapes, required })
+}
+
+/// The kinds of values a struct field is able to hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    /// The field has not yet been deserialized.
+    Unknown,
+
+    /// The field accepts any kind of value.
+    Any,
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Option(Box<Shape>),
+    List(Box<Shape>),
+    Tuple(usize),
+
+    /// A kind of value that query results can't be deserialized into, like maps or enums.
+    Other(&'static str),
+}
+
+impl Shape {
+    /// Whether the field can hold all values of the given type.
+    fn accepts(&self, ty: &Type) -> bool {
+        match self {
+            Shape::Unknown | Shape::Any => true,
+            Shape::Option(inner) => inner.accepts(&ty.with_nullability(false)),
+            _ if ty.nullable() => false,
+            Shape::List(inner) => ty.as_list().is_some_and(|element| inner.accepts(&element)),
+            Shape::Tuple(..) => ty.is_list(),
+            _ if ty.is_list() => false,
+            Shape::Boolean => ty.base_type() == "Boolean",
+            Shape::Integer => ty.base_type() == "Int",
+            Shape::Float => matches!(ty.base_type(), "Float" | "Int"),
+            Shape::String => !matches!(ty.base_type(), "Boolean" | "Int" | "Float"),
+            Shape::Other(..) => false,
+        }
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shape::Unknown | Shape::Any => write!(f, "any value"),
+            Shape::Boolean => write!(f, "bool"),
+            Shape::Integer => write!(f, "integer"),
+            Shape::Float => write!(f, "float"),
+            Shape::String => write!(f, "string"),
+            Shape::Option(inner) => write!(f, "Option<{inner}>"),
+            Shape::List(inner) => write!(f, "Vec<{inner}>"),
+            Shape::Tuple(len) => write!(f, "{len}-tuple"),
+            Shape::Other(kind) => write!(f, "{kind}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+enum ProbeError {
+    #[error("missing field {0}")]
+    MissingField(&'static str),
+
+    #[error("unable to make up a value for field {0}")]
+    UntraceableField(&'static str),
+
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl de::Error for ProbeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::MissingField(field)
+    }
+}
+
+/// Deserializes a struct, recording its fields.
+struct StructDeserializer<'a, P> {
+    fields: &'a mut Option<&'static [&'static str]>,
+    provided: P,
+    shapes: &'a mut BTreeMap<&'static str, Shape>,
+}
+
+impl<'de, P: Fn(&&'static str) -> bool> de::Deserializer<'de> for StructDeserializer<'_, P> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(ProbeError::Custom("not a struct".to_string()))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.fields = Some(fields);
+        let provided = self.provided;
+        visitor.visit_map(FieldsAccess {
+            fields: fields.iter().copied().filter(move |field| provided(field)),
+            current: None,
+            shapes: self.shapes,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Provides the fields of a struct, with made-up values that record the fields' shapes.
+struct FieldsAccess<'a, I> {
+    fields: I,
+    current: Option<&'static str>,
+    shapes: &'a mut BTreeMap<&'static str, Shape>,
+}
+
+impl<'de, I: Iterator<Item = &'static str>> de::MapAccess<'de> for FieldsAccess<'_, I> {
+    type Error = ProbeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.current = self.fields.next();
+        self.current.map(|field| seed.deserialize(field.into_deserializer())).transpose()
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let field = self.current.take().expect("called next_value_seed out of order");
+        let shape = self.shapes.entry(field).or_insert(Shape::Unknown);
+        seed.deserialize(ShapeDeserializer { shape })
+            .map_err(|_| ProbeError::UntraceableField(field))
+    }
+}
+
+/// Produces a made-up value of whatever kind is requested, recording that kind.
+struct ShapeDeserializer<'a> {
+    shape: &'a mut Shape,
+}
+
+impl<'de> de::Deserializer<'de> for ShapeDeserializer<'_> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Any;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Boolean;
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // One, rather than zero, so that types like `NonZeroU64` accept the value too.
+        *self.shape = Shape::Integer;
+        visitor.visit_u64(1)
+    }
+
+    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Float;
+        visitor.visit_f64(1.0)
+    }
+
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::String;
+        visitor.visit_char('a')
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::String;
+        visitor.visit_str("")
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Option(Box::new(Shape::Unknown));
+        let Shape::Option(inner) = self.shape else { unreachable!() };
+        visitor.visit_some(ShapeDeserializer { shape: inner })
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::List(Box::new(Shape::Unknown));
+        let Shape::List(element) = self.shape else { unreachable!() };
+        visitor.visit_seq(ElementsAccess { shapes: vec![element.as_mut()].into_iter() })
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Tuple(len);
+        visit_made_up_seq(len, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("newtype struct");
+        visitor.visit_newtype_struct(ShapeDeserializer { shape: &mut Shape::Unknown })
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("byte array");
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("unit");
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("unit struct");
+        visitor.visit_unit()
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("tuple struct");
+        visit_made_up_seq(len, visitor)
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("map");
+        visit_made_up_map(&[], visitor)
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("struct");
+        visit_made_up_map(fields, visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.shape = Shape::Other("enum");
+        visitor.visit_enum(MadeUpVariant)
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+}
+
+/// Provides one made-up element for each of the given shapes.
+struct ElementsAccess<'a> {
+    shapes: std::vec::IntoIter<&'a mut Shape>,
+}
+
+impl<'de> de::SeqAccess<'de> for ElementsAccess<'_> {
+    type Error = ProbeError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.shapes.next().map(|shape| seed.deserialize(ShapeDeserializer { shape })).transpose()
+    }
+}
+
+/// Visit a sequence of the given number of made-up elements.
+fn visit_made_up_seq<'de, V: de::Visitor<'de>>(
+    len: usize,
+    visitor: V,
+) -> Result<V::Value, ProbeError> {
+    let mut elements = vec![Shape::Unknown; len];
+    visitor
+        .visit_seq(ElementsAccess { shapes: elements.iter_mut().collect::<Vec<_>>().into_iter() })
+}
+
+/// Visit a map with made-up values for the given keys.
+fn visit_made_up_map<'de, V: de::Visitor<'de>>(
+    keys: &'static [&'static str],
+    visitor: V,
+) -> Result<V::Value, ProbeError> {
+    visitor.visit_map(FieldsAccess {
+        fields: keys.iter().copied(),
+        current: None,
+        shapes: &mut BTreeMap::new(),
+    })
+}
+
+/// The first variant of an enum, with made-up contents.
+struct MadeUpVariant;
+
+impl<'de> de::EnumAccess<'de> for MadeUpVariant {
+    type Error = ProbeError;
+    type Variant = MadeUpVariant;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        Ok((seed.deserialize(0u32.into_deserializer())?, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for MadeUpVariant {
+    type Error = ProbeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(ShapeDeserializer { shape: &mut Shape::Unknown })
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_made_up_seq(len, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visit_made_up_map(fields, visitor)
+    }
+}
@@ -136,3 +136,41 @@ impl<K: Ord, V> BTreeMapTryInsertExt<K, V> for BTreeMap<K, V> {
         }
     }
 }
+
+/// The candidate most similar to the given name, if any is similar enough
+/// that the name may be a misspelling of it.
+///
+/// Names are compared by their edit distance, ignoring differences in letter case.
+/// Ties are broken in favor of the candidate that comes first.
+pub(crate) fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let max_distance = (name.len() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let candidate_chars: Vec<char> = candidate.to_lowercase().chars().collect();
+            let distance = edit_distance(&name, &candidate_chars);
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two sequences of characters.
+fn edit_distance(left: &[char], right: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_char) in left.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[right.len()]
+}
@@ -20,6 +20,9 @@
 //!     Message(Rc<Message>),
 //! }
 //! ```
+//!
+//! The structs inside the vertex's variants can then derive `TrustfallProperties`
+//! to resolve their fields as properties.
 #![forbid(unsafe_code)]
 #![forbid(unused_lifetimes)]
 #![forbid(elided_lifetimes_in_paths)]
@@ -29,6 +32,8 @@ use syn::punctuated::Punctuated;
 
 const TRUSTFALL_ATTRIBUTE: &str = "trustfall";
 const SKIP_CONVERSION_ATTRIBUTE: &str = "skip_conversion";
+const SKIP_ATTRIBUTE: &str = "skip";
+const RENAME_ATTRIBUTE: &str = "rename";
 
 /// Adds the [`Typename`] trait and `as_<variant>()` methods on an enum used as a Trustfall vertex.
 ///
@@ -164,6 +169,102 @@ pub fn typename_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     .into()
 }
 
+/// Adds a `resolve_property()` method that resolves properties from a struct's fields.
+///
+/// Each field is resolved as the property of the same name, by cloning the field's value
+/// and converting it into a [`FieldValue`]. This replaces one [`field_property!`] invocation
+/// per property in the adapter's `resolve_property()` implementation.
+///
+/// For example:
+/// ```rust
+/// # use trustfall_derive::TrustfallProperties;
+/// #
+/// #[derive(Debug, Clone, TrustfallProperties)]
+/// struct User {
+///     name: String,
+///     #[trustfall(rename = "createdAt")]
+///     created_at: i64,
+///     #[trustfall(skip)]
+///     cache_key: u64,
+/// }
+/// ```
+/// will get the following implementation:
+/// ```rust
+/// # use trustfall::FieldValue;
+/// #
+/// # #[derive(Debug, Clone)]
+/// # struct User {
+/// #     name: String,
+/// #     created_at: i64,
+/// #     cache_key: u64,
+/// # }
+/// #
+/// impl User {
+///     fn resolve_property(&self, property_name: &str) -> FieldValue {
+///         match property_name {
+///             "name" => self.name.clone().into(),
+///             "createdAt" => self.created_at.clone().into(),
+///             _ => unreachable!("unexpected property {property_name} on User"),
+///         }
+///     }
+/// }
+/// ```
+///
+/// A field can be resolved under a different property name with `#[trustfall(rename = "...")]`,
+/// and can be excluded from the generated properties with `#[trustfall(skip)]`.
+///
+/// Inside an adapter, the method is used together with the vertex's conversion methods.
+/// For example, with the above `User` type as the `Vertex::User` variant:
+/// ```rust
+/// # use std::{rc::Rc, sync::Arc};
+/// # use trustfall::{
+/// #     provider::{resolve_property_with, AsVertex, ContextIterator, ContextOutcomeIterator},
+/// #     FieldValue,
+/// # };
+/// # use trustfall_derive::{TrustfallEnumVertex, TrustfallProperties};
+/// #
+/// # #[derive(Debug, Clone, TrustfallProperties)]
+/// # struct User {
+/// #     name: String,
+/// # }
+/// #
+/// # #[derive(Debug, Clone, TrustfallEnumVertex)]
+/// # enum Vertex {
+/// #     User(Rc<User>),
+/// # }
+/// #
+/// # struct Adapter<'a>(&'a ());
+/// # impl<'a> Adapter<'a> {
+/// // Inside implementation of `Adapter`:
+/// fn resolve_property<V: AsVertex<Vertex> + 'a>(
+///     &self,
+///     contexts: ContextIterator<'a, V>,
+///     type_name: &str,
+///     property_name: &str,
+/// ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+///     let property_name: Arc<str> = property_name.into();
+///     match type_name {
+///         "User" => resolve_property_with(contexts, move |vertex: &Vertex| {
+///             vertex.as_user().expect("not a User").resolve_property(&property_name)
+///         }),
+///         // ...
+///         _ => unreachable!()
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`FieldValue`]: https://docs.rs/trustfall/latest/trustfall/enum.FieldValue.html
+/// [`field_property!`]: https://docs.rs/trustfall/latest/trustfall/provider/macro.field_property.html
+#[proc_macro_derive(TrustfallProperties, attributes(trustfall))]
+pub fn trustfall_properties_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match syn::parse(input) {
+        Ok(ast) => impl_trustfall_properties(&ast).unwrap_or_else(syn::Error::into_compile_error),
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
 fn impl_typename_derive(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
@@ -237,6 +338,91 @@ fn impl_trustfall_enum_vertex(ast: &syn::DeriveInput) -> syn::Result<proc_macro2
     Ok(gen)
 }
 
+fn impl_trustfall_properties(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => {
+            &fields.named
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ast,
+                "only structs with named fields can derive TrustfallProperties",
+            ))
+        }
+    };
+
+    let mut arms = proc_macro2::TokenStream::new();
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("named field had no name");
+        let Some(property_name) = property_name_for_field(field)? else {
+            continue;
+        };
+        let property_name = proc_macro2::Literal::string(&property_name);
+        arms.extend(quote! {
+            #property_name => self.#field_ident.clone().into(),
+        });
+    }
+
+    let type_name = name.to_string();
+    let gen = quote! {
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub(crate) fn resolve_property(&self, property_name: &str) -> ::trustfall::FieldValue {
+                match property_name {
+                    #arms
+                    _ => unreachable!("unexpected property {property_name} on {}", #type_name),
+                }
+            }
+        }
+    };
+    Ok(gen)
+}
+
+/// Returns the name of the property resolved from this field,
+/// or `None` if the field has a `#[trustfall(skip)]` attribute.
+fn property_name_for_field(field: &syn::Field) -> syn::Result<Option<String>> {
+    let field_ident = field.ident.as_ref().expect("named field had no name");
+    let mut property_name = field_ident.to_string().trim_start_matches("r#").to_string();
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident(TRUSTFALL_ATTRIBUTE) {
+            // Not one of our attributes, skip.
+            continue;
+        }
+
+        let unexpected_attr = || {
+            syn::Error::new_spanned(
+                attr,
+                "unexpected attribute, did you mean `#[trustfall(skip)]` \
+                or `#[trustfall(rename = \"...\")]`?",
+            )
+        };
+        let mut skip = false;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(SKIP_ATTRIBUTE) && !meta.input.peek(syn::Token![=]) {
+                skip = true;
+                Ok(())
+            } else if meta.path.is_ident(RENAME_ATTRIBUTE) {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                property_name = value.value();
+                Ok(())
+            } else {
+                Err(unexpected_attr())
+            }
+        })
+        .map_err(|_| unexpected_attr())?;
+
+        if skip {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(property_name))
+}
+
 fn generate_typename_arm(variant: &syn::Variant) -> proc_macro2::TokenStream {
     let variant_ident = &variant.ident;
     let variant_name = variant_ident.to_string();
@@ -0,0 +1,11 @@
+use trustfall_derive::TrustfallProperties;
+
+#[derive(Debug, Clone, TrustfallProperties)]
+enum Vertex {
+    First,
+}
+
+#[derive(Debug, Clone, TrustfallProperties)]
+struct TupleVertex(i64);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use trustfall_derive::TrustfallProperties;
+
+#[derive(Debug, Clone, TrustfallProperties)]
+struct Vertex {
+    first: i64,
+    #[trustfall(rename)]
+    second: i64,
+}
+
+fn main() {}
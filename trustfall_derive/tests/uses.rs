@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
-use trustfall::provider::Typename;
-use trustfall_derive::TrustfallEnumVertex;
+use trustfall::{provider::Typename, FieldValue};
+use trustfall_derive::{TrustfallEnumVertex, TrustfallProperties};
 
 #[test]
 fn empty_enum() {
@@ -234,6 +234,61 @@ fn typename() {
     assert_eq!("Second", second.typename());
 }
 
+#[test]
+fn properties() {
+    #[derive(Debug, Clone, TrustfallProperties)]
+    struct User {
+        name: String,
+        age: Option<i64>,
+        #[trustfall(rename = "emailAddresses")]
+        email_addresses: Vec<String>,
+        r#type: &'static str,
+        #[allow(dead_code)]
+        #[trustfall(skip)]
+        cache_key: u64,
+    }
+
+    let user = User {
+        name: "Alice".into(),
+        age: None,
+        email_addresses: vec!["alice@example.com".into()],
+        r#type: "admin",
+        cache_key: 42,
+    };
+    assert_eq!(FieldValue::String("Alice".into()), user.resolve_property("name"));
+    assert_eq!(FieldValue::Null, user.resolve_property("age"));
+    assert_eq!(
+        FieldValue::List(vec![FieldValue::String("alice@example.com".into())].into()),
+        user.resolve_property("emailAddresses"),
+    );
+    assert_eq!(FieldValue::String("admin".into()), user.resolve_property("type"));
+}
+
+#[test]
+#[should_panic(expected = "unexpected property cache_key on User")]
+fn skipped_property() {
+    #[allow(dead_code)]
+    #[derive(Debug, Clone, TrustfallProperties)]
+    struct User {
+        name: String,
+        #[trustfall(skip)]
+        cache_key: u64,
+    }
+
+    let user = User { name: "Alice".into(), cache_key: 42 };
+    user.resolve_property("cache_key");
+}
+
+#[test]
+fn generic_properties() {
+    #[derive(Debug, Clone, TrustfallProperties)]
+    struct Wrapper<T: Clone + Into<FieldValue>> {
+        value: T,
+    }
+
+    assert_eq!(FieldValue::Int64(7), Wrapper { value: 7i64 }.resolve_property("value"));
+}
+
 #[test]
 fn ui() {
     let t = trybuild::TestCases::new();
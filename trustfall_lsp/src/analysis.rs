@@ -0,0 +1,602 @@
+//! Completions, hover info, and diagnostics for query text, independent of the LSP protocol.
+//!
+//! Queries being edited are usually not valid, so completions and hover info are based on
+//! a lenient scan of the query text rather than on the query's parsed representation.
+use trustfall_core::{
+    frontend::{lint, parse, Diagnostic},
+    schema::Schema,
+};
+
+use crate::index::SchemaIndex;
+
+/// A location in the query text, with zero-based line and character numbers.
+///
+/// Characters are Unicode scalar values, not bytes or UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// A range of the query text, from `start` up to but not including `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Property,
+    Edge,
+    VertexType,
+    Directive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+
+    /// A short description, like the property's type.
+    pub detail: Option<String>,
+    pub documentation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    /// Markdown describing the hovered element.
+    pub contents: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryDiagnostic {
+    pub range: Range,
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+/// Analyzes queries against a particular schema.
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    schema: Schema,
+    index: SchemaIndex,
+}
+
+impl Analyzer {
+    pub fn new(schema: Schema) -> Self {
+        let index = SchemaIndex::new(&schema);
+        Self { schema, index }
+    }
+
+    /// Suggestions for what could be written at the given position in the query.
+    pub fn completions(&self, text: &str, position: Position) -> Vec<Completion> {
+        let offset = char_offset(text, position);
+        let chars: Vec<char> = text.chars().take(offset).collect();
+        let word_start = identifier_start(&chars, chars.len());
+
+        if word_start > 0 && chars[word_start - 1] == '@' {
+            return DIRECTIVES
+                .iter()
+                .map(|(name, definition)| Completion {
+                    label: name.to_string(),
+                    kind: CompletionKind::Directive,
+                    detail: Some(definition.to_string()),
+                    documentation: None,
+                })
+                .collect();
+        }
+
+        let scope = self.scan(&chars[..word_start]);
+        if scope.paren_depth > 0 {
+            return vec![];
+        }
+        let Some(current) = scope.stack.last() else {
+            return vec![];
+        };
+
+        if scope.expecting_coercion_type {
+            let Some(vertex_type) = current.as_ref().and_then(|t| t.vertex_type()) else {
+                return vec![];
+            };
+            let Some(info) = self.index.vertex_types.get(vertex_type) else {
+                return vec![];
+            };
+            return info
+                .subtypes
+                .iter()
+                .map(|subtype| Completion {
+                    label: subtype.clone(),
+                    kind: CompletionKind::VertexType,
+                    detail: None,
+                    documentation: self
+                        .index
+                        .vertex_types
+                        .get(subtype)
+                        .and_then(|info| info.docs.clone()),
+                })
+                .collect();
+        }
+
+        match current {
+            Some(ScopeType::QueryRoot) => {
+                self.index.entrypoints.iter().map(edge_completion).collect()
+            }
+            Some(ScopeType::Vertex(vertex_type)) => {
+                let Some(info) = self.index.vertex_types.get(vertex_type) else {
+                    return vec![];
+                };
+                let typename = Completion {
+                    label: "__typename".to_string(),
+                    kind: CompletionKind::Property,
+                    detail: Some("String!".to_string()),
+                    documentation: Some("The name of the vertex's type.".to_string()),
+                };
+                info.properties
+                    .iter()
+                    .map(|property| Completion {
+                        label: property.name.clone(),
+                        kind: CompletionKind::Property,
+                        detail: Some(property.type_name.clone()),
+                        documentation: property.docs.clone(),
+                    })
+                    .chain(std::iter::once(typename))
+                    .chain(info.edges.iter().map(edge_completion))
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+
+    /// Describes the property, edge, type, or directive at the given position in the query.
+    pub fn hover(&self, text: &str, position: Position) -> Option<Hover> {
+        let offset = char_offset(text, position);
+        let chars: Vec<char> = text.chars().collect();
+        let start = identifier_start(&chars, offset.min(chars.len()));
+        let end = identifier_end(&chars, offset.min(chars.len()));
+        if start == end {
+            return None;
+        }
+        let word: String = chars[start..end].iter().collect();
+        let range = Range { start: position_at(&chars, start), end: position_at(&chars, end) };
+
+        if start > 0 && chars[start - 1] == '@' {
+            let (_, definition) = DIRECTIVES.iter().find(|(name, _)| *name == word)?;
+            return Some(Hover { contents: format!("```graphql\n{definition}\n```"), range });
+        }
+
+        let scope = self.scan(&chars[..start]);
+        let current = scope.stack.last()?.as_ref()?;
+
+        let contents = if scope.expecting_coercion_type {
+            let info = self.index.vertex_types.get(&word)?;
+            with_docs(format!("```graphql\ntype {word}\n```"), info.docs.as_deref())
+        } else if scope.paren_depth > 0 {
+            // Hovering over an edge parameter's name.
+            let edge = self.index.edge(current.vertex_type(), scope.pending_field.as_ref()?)?;
+            let (name, ty) = edge.parameters.iter().find(|(name, _)| *name == word)?;
+            format!("```graphql\n{name}: {ty}\n```")
+        } else if let Some(edge) = self.index.edge(current.vertex_type(), &word) {
+            with_docs(format!("```graphql\n{}\n```", edge.signature()), edge.docs.as_deref())
+        } else {
+            let info = self.index.vertex_types.get(current.vertex_type()?)?;
+            if word == "__typename" {
+                "```graphql\n__typename: String!\n```".to_string()
+            } else {
+                let property = info.properties.iter().find(|p| p.name == word)?;
+                with_docs(
+                    format!("```graphql\n{}: {}\n```", property.name, property.type_name),
+                    property.docs.as_deref(),
+                )
+            }
+        };
+
+        Some(Hover { contents, range })
+    }
+
+    /// Errors that prevent the query from running, or else warnings about likely mistakes.
+    pub fn diagnostics(&self, text: &str) -> Vec<QueryDiagnostic> {
+        let start_of_query = Range {
+            start: Position { line: 0, character: 0 },
+            end: Position { line: 0, character: 0 },
+        };
+
+        match parse(&self.schema, text) {
+            Err(error) => Diagnostic::from_frontend_error(&error, text)
+                .into_iter()
+                .flat_map(|diagnostic| {
+                    let ranges: Vec<_> = if diagnostic.spans.is_empty() {
+                        vec![start_of_query]
+                    } else {
+                        diagnostic
+                            .spans
+                            .iter()
+                            .map(|span| Range {
+                                start: Position {
+                                    line: span.start.line.saturating_sub(1),
+                                    character: span.start.column.saturating_sub(1),
+                                },
+                                end: Position {
+                                    line: span.end.line.saturating_sub(1),
+                                    character: span.end.column.saturating_sub(1),
+                                },
+                            })
+                            .collect()
+                    };
+                    ranges.into_iter().map(move |range| QueryDiagnostic {
+                        range,
+                        severity: Severity::Error,
+                        code: Some(diagnostic.code.clone()),
+                        message: diagnostic.message.clone(),
+                    })
+                })
+                .collect(),
+            Ok(_) => lint(&self.schema, text)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|warning| QueryDiagnostic {
+                    range: start_of_query,
+                    severity: Severity::Warning,
+                    code: None,
+                    message: warning.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+const DIRECTIVES: &[(&str, &str)] = &[
+    (
+        "filter",
+        "directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT",
+    ),
+    ("tag", "directive @tag(name: String) on FIELD"),
+    ("output", "directive @output(name: String) on FIELD"),
+    ("optional", "directive @optional(group: String) on FIELD"),
+    ("recurse", "directive @recurse(depth: Int, dedupe: Boolean) on FIELD"),
+    ("fold", "directive @fold on FIELD"),
+    ("transform", "directive @transform(op: String!, value: [String!]) on FIELD"),
+    ("order", "directive @order(direction: String!) on FIELD"),
+    ("limit", "directive @limit(count: Int!) on FIELD"),
+    ("offset", "directive @offset(count: Int!) on FIELD"),
+    ("dedupe", "directive @dedupe on FIELD"),
+    ("absent", "directive @absent on FIELD"),
+];
+
+fn edge_completion(edge: &crate::index::EdgeInfo) -> Completion {
+    Completion {
+        label: edge.name.clone(),
+        kind: CompletionKind::Edge,
+        detail: Some(edge.signature()),
+        documentation: edge.docs.clone(),
+    }
+}
+
+fn with_docs(signature: String, docs: Option<&str>) -> String {
+    match docs {
+        Some(docs) => format!("{signature}\n\n{docs}"),
+        None => signature,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScopeType {
+    QueryRoot,
+    Vertex(String),
+}
+
+impl ScopeType {
+    fn vertex_type(&self) -> Option<&str> {
+        match self {
+            ScopeType::QueryRoot => None,
+            ScopeType::Vertex(name) => Some(name),
+        }
+    }
+}
+
+/// The state of the query at some position in its text.
+#[derive(Debug, Default)]
+struct Scope {
+    /// The type of each enclosing selection set, from outermost to innermost.
+    /// `None` if the type could not be determined.
+    stack: Vec<Option<ScopeType>>,
+
+    /// The number of enclosing parentheses, e.g. inside an edge's or directive's arguments.
+    paren_depth: usize,
+
+    /// The most recent field name in the innermost selection set.
+    pending_field: Option<String>,
+
+    /// The type of the most recent `... on Type` coercion.
+    pending_coercion: Option<String>,
+
+    /// Whether we are right after `... on`.
+    expecting_coercion_type: bool,
+}
+
+impl Analyzer {
+    fn scan(&self, chars: &[char]) -> Scope {
+        let mut scope = Scope::default();
+        let mut expecting_on = false;
+        let mut directive_next = false;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '#' {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            if c == '"' {
+                i = skip_string(chars, i);
+                continue;
+            }
+            if c == '(' {
+                scope.paren_depth += 1;
+            } else if c == ')' {
+                scope.paren_depth = scope.paren_depth.saturating_sub(1);
+            } else if scope.paren_depth > 0 {
+                // Argument values don't affect the enclosing scope.
+            } else if is_identifier_char(c) {
+                let end = identifier_end(chars, i);
+                let word: String = chars[i..end].iter().collect();
+                i = end;
+                if directive_next {
+                    directive_next = false;
+                } else if expecting_on {
+                    expecting_on = false;
+                    scope.expecting_coercion_type = word == "on";
+                } else if scope.expecting_coercion_type {
+                    scope.expecting_coercion_type = false;
+                    scope.pending_coercion = Some(word);
+                } else if !scope.stack.is_empty() {
+                    scope.pending_field = Some(word);
+                }
+                continue;
+            } else if chars[i..].starts_with(&['.', '.', '.']) {
+                expecting_on = true;
+                i += 3;
+                continue;
+            } else if c == '@' {
+                directive_next = true;
+            } else if c == '{' {
+                let next = if scope.stack.is_empty() {
+                    Some(ScopeType::QueryRoot)
+                } else if let Some(coercion) = scope.pending_coercion.take() {
+                    Some(ScopeType::Vertex(coercion))
+                } else {
+                    let field = scope.pending_field.take();
+                    let current = scope.stack.last().cloned().flatten();
+                    field
+                        .zip(current)
+                        .and_then(|(field, current)| self.index.edge(current.vertex_type(), &field))
+                        .map(|edge| ScopeType::Vertex(edge.target.clone()))
+                };
+                scope.stack.push(next);
+            } else if c == '}' {
+                scope.stack.pop();
+                scope.pending_field = None;
+            }
+            i += 1;
+        }
+
+        scope
+    }
+}
+
+fn skip_string(chars: &[char], start: usize) -> usize {
+    if chars[start..].starts_with(&['"', '"', '"']) {
+        let mut i = start + 3;
+        while i < chars.len() && !chars[i..].starts_with(&['"', '"', '"']) {
+            i += 1;
+        }
+        return (i + 3).min(chars.len());
+    }
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != '"' && chars[i] != '\n' {
+        if chars[i] == '\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    (i + 1).min(chars.len())
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn identifier_start(chars: &[char], mut offset: usize) -> usize {
+    while offset > 0 && is_identifier_char(chars[offset - 1]) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn identifier_end(chars: &[char], mut offset: usize) -> usize {
+    while offset < chars.len() && is_identifier_char(chars[offset]) {
+        offset += 1;
+    }
+    offset
+}
+
+/// The offset in characters from the start of the text to the given position,
+/// clamped to the end of the position's line.
+fn char_offset(text: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (line_number, line) in text.split('\n').enumerate() {
+        let length = line.chars().count();
+        if line_number == position.line {
+            return offset + position.character.min(length);
+        }
+        offset += length + 1;
+    }
+    text.chars().count()
+}
+
+fn position_at(chars: &[char], offset: usize) -> Position {
+    let before = &chars[..offset];
+    let line = before.iter().filter(|c| **c == '\n').count();
+    let line_start = before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1);
+    Position { line, character: offset - line_start }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::OnceLock};
+
+    use trustfall_core::schema::Schema;
+
+    use super::{Analyzer, CompletionKind, Position, Severity};
+
+    static ANALYZER: OnceLock<Analyzer> = OnceLock::new();
+
+    fn get_analyzer() -> &'static Analyzer {
+        ANALYZER.get_or_init(|| {
+            let path = "../trustfall_core/test_data/schemas/numbers.graphql";
+            Analyzer::new(Schema::parse(fs::read_to_string(path).unwrap()).unwrap())
+        })
+    }
+
+    /// Splits the query at the `|` marker, returning the query without it and the marker's position.
+    fn at_marker(query: &str) -> (String, Position) {
+        let (before, after) = query.split_once('|').expect("no marker");
+        let line = before.matches('\n').count();
+        let character = before.rsplit('\n').next().unwrap().chars().count();
+        (format!("{before}{after}"), Position { line, character })
+    }
+
+    fn completion_labels(query: &str) -> Vec<String> {
+        let (text, position) = at_marker(query);
+        get_analyzer().completions(&text, position).into_iter().map(|c| c.label).collect()
+    }
+
+    #[test]
+    fn completes_entrypoints() {
+        let labels = completion_labels("{\n    Num|\n}");
+        assert!(labels.contains(&"Number".to_string()), "{labels:?}");
+        assert!(labels.contains(&"Zero".to_string()), "{labels:?}");
+        assert!(!labels.contains(&"value".to_string()), "{labels:?}");
+    }
+
+    #[test]
+    fn completes_properties_and_edges() {
+        let labels = completion_labels(
+            r#"
+{
+    Number(max: 10) {
+        value @output
+        successor {
+            |
+        }
+    }
+}"#,
+        );
+        for expected in ["value", "name", "__typename", "successor", "predecessor", "multiple"] {
+            assert!(labels.contains(&expected.to_string()), "{expected} missing from {labels:?}");
+        }
+    }
+
+    #[test]
+    fn completes_inside_coercion() {
+        let labels = completion_labels(
+            r#"
+{
+    Number(max: 10) {
+        ... on Composite {
+            pri|
+        }
+    }
+}"#,
+        );
+        assert!(labels.contains(&"primeFactor".to_string()), "{labels:?}");
+    }
+
+    #[test]
+    fn completes_coercion_types() {
+        let labels = completion_labels("{\n    Number(max: 10) {\n        ... on |\n    }\n}");
+        assert_eq!(vec!["Composite", "Neither", "Prime"], labels);
+    }
+
+    #[test]
+    fn completes_directives() {
+        let (text, position) = at_marker("{\n    Number(max: 10) {\n        value @out|\n    }\n}");
+        let completions = get_analyzer().completions(&text, position);
+        assert!(completions.iter().all(|c| c.kind == CompletionKind::Directive));
+        assert!(completions.iter().any(|c| c.label == "output"));
+    }
+
+    #[test]
+    fn no_completions_inside_arguments() {
+        let labels = completion_labels(
+            "{\n    Number(max: 10) {\n        value @filter(op: \"=\", value: [\"$x|\"])\n    }\n}",
+        );
+        assert!(labels.is_empty(), "{labels:?}");
+    }
+
+    #[test]
+    fn hover_describes_fields() {
+        let (text, position) = at_marker(
+            r#"
+{
+    Number(max: 10) {
+        multi|ple(max: 3) {
+            val|ue @output
+        }
+    }
+}"#,
+        );
+        let (text, value_position) = at_marker(&text);
+
+        let hover = get_analyzer().hover(&text, position).expect("no hover info");
+        assert!(hover.contents.contains("multiple(max: Int!): [Composite]"), "{}", hover.contents);
+        assert_eq!(8, hover.range.start.character);
+        assert_eq!(16, hover.range.end.character);
+
+        let hover = get_analyzer().hover(&text, value_position).expect("no hover info");
+        assert!(hover.contents.contains("value: Int"), "{}", hover.contents);
+    }
+
+    #[test]
+    fn hover_describes_edge_parameters() {
+        let (text, position) =
+            at_marker("{\n    Number(m|ax: 10) {\n        value @output\n    }\n}");
+        let hover = get_analyzer().hover(&text, position).expect("no hover info");
+        assert!(hover.contents.contains("max: Int!"), "{}", hover.contents);
+    }
+
+    #[test]
+    fn diagnostics_for_invalid_query() {
+        let text = "{\n    Number(max: 10) {\n        nonexistent @output\n    }\n}";
+        let diagnostics = get_analyzer().diagnostics(text);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Error, diagnostics[0].severity);
+        assert_eq!(Some("NonExistentPath"), diagnostics[0].code.as_deref());
+        assert_eq!(Position { line: 2, character: 8 }, diagnostics[0].range.start);
+        assert_eq!(Position { line: 2, character: 19 }, diagnostics[0].range.end);
+    }
+
+    #[test]
+    fn diagnostics_include_lint_warnings() {
+        let text = "{\n    Number(max: 10) {\n        value @output\n        successor @recurse(depth: 1) {\n            name @output\n        }\n    }\n}";
+        let diagnostics = get_analyzer().diagnostics(text);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Warning, diagnostics[0].severity);
+    }
+
+    #[test]
+    fn valid_query_has_no_diagnostics() {
+        let text = "{\n    Number(max: 10) {\n        value @output\n    }\n}";
+        assert!(get_analyzer().diagnostics(text).is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::Deserialize;
+use trustfall_core::{
+    frontend::parse,
+    interpreter::execution::interpret_ir,
+    ir::FieldValue,
+    schema::{Schema, SchemaAdapter},
+    TryIntoStruct,
+};
+
+/// The information about a schema needed for completions and hover info,
+/// looked up ahead of time by querying the schema with [`SchemaAdapter`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SchemaIndex {
+    pub(crate) entrypoints: Vec<EdgeInfo>,
+    pub(crate) vertex_types: BTreeMap<String, VertexTypeInfo>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VertexTypeInfo {
+    pub(crate) docs: Option<String>,
+    pub(crate) properties: Vec<PropertyInfo>,
+    pub(crate) edges: Vec<EdgeInfo>,
+
+    /// The names of the types that implement this type, not including itself.
+    pub(crate) subtypes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct PropertyInfo {
+    pub(crate) name: String,
+    pub(crate) docs: Option<String>,
+    #[serde(rename = "type")]
+    pub(crate) type_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EdgeInfo {
+    pub(crate) name: String,
+    pub(crate) docs: Option<String>,
+    pub(crate) target: String,
+    pub(crate) to_many: bool,
+
+    /// The edge's parameters' names and types.
+    pub(crate) parameters: Vec<(String, String)>,
+}
+
+impl EdgeInfo {
+    /// A GraphQL-like description of the edge, like `multiple(max: Int!): [Composite]`.
+    pub(crate) fn signature(&self) -> String {
+        let parameters = if self.parameters.is_empty() {
+            String::new()
+        } else {
+            let parameters: Vec<_> =
+                self.parameters.iter().map(|(name, ty)| format!("{name}: {ty}")).collect();
+            format!("({})", parameters.join(", "))
+        };
+        let target =
+            if self.to_many { format!("[{}]", self.target) } else { self.target.to_string() };
+        format!("{}{parameters}: {target}", self.name)
+    }
+}
+
+impl SchemaIndex {
+    pub(crate) fn new(schema: &Schema) -> Self {
+        let schema_schema = Schema::parse(SchemaAdapter::schema_text())
+            .expect("the schema of schemas is not valid");
+        let adapter = Arc::new(SchemaAdapter::new(schema));
+
+        let mut vertex_types: BTreeMap<String, VertexTypeInfo> = BTreeMap::new();
+        for row in run_query::<TypeRow>(&schema_schema, &adapter, VERTEX_TYPES_QUERY) {
+            let subtypes = row.subtypes.into_iter().filter(|name| *name != row.name).collect();
+            vertex_types.insert(
+                row.name,
+                VertexTypeInfo { docs: row.docs, subtypes, ..Default::default() },
+            );
+        }
+        for row in run_query::<PropertyRow>(&schema_schema, &adapter, PROPERTIES_QUERY) {
+            if let Some(vertex_type) = vertex_types.get_mut(&row.vertex_type) {
+                vertex_type.properties.push(row.property);
+            }
+        }
+        for row in run_query::<EdgeRow>(&schema_schema, &adapter, EDGES_QUERY) {
+            if let Some(vertex_type) = vertex_types.get_mut(&row.vertex_type) {
+                vertex_type.edges.push(row.edge.into());
+            }
+        }
+        let entrypoints = run_query::<EdgeFields>(&schema_schema, &adapter, ENTRYPOINTS_QUERY)
+            .into_iter()
+            .map(EdgeInfo::from)
+            .collect();
+
+        for vertex_type in vertex_types.values_mut() {
+            vertex_type.properties.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+            vertex_type.edges.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+            vertex_type.subtypes.sort_unstable();
+        }
+
+        Self { entrypoints, vertex_types }
+    }
+
+    /// The edge with the given name at the given vertex type, if any.
+    ///
+    /// `None` as the type name refers to the schema's query root type, i.e. the entrypoints.
+    pub(crate) fn edge(&self, vertex_type: Option<&str>, edge: &str) -> Option<&EdgeInfo> {
+        let edges = match vertex_type {
+            None => &self.entrypoints,
+            Some(vertex_type) => &self.vertex_types.get(vertex_type)?.edges,
+        };
+        edges.iter().find(|e| e.name == edge)
+    }
+}
+
+fn run_query<T: for<'de> Deserialize<'de>>(
+    schema_schema: &Schema,
+    adapter: &Arc<SchemaAdapter<'_>>,
+    query: &str,
+) -> Vec<T> {
+    let indexed_query = parse(schema_schema, query).expect("schema query is not valid");
+    let arguments: BTreeMap<Arc<str>, FieldValue> = BTreeMap::new();
+    interpret_ir(adapter.clone(), indexed_query, Arc::new(arguments))
+        .expect("schema query arguments are not valid")
+        .map(|row| row.try_into_struct().expect("unexpected schema query result shape"))
+        .collect()
+}
+
+const VERTEX_TYPES_QUERY: &str = r#"
+{
+    VertexType {
+        name @output
+        docs @output
+
+        implementer @fold {
+            subtypes: name @output
+        }
+    }
+}"#;
+
+const PROPERTIES_QUERY: &str = r#"
+{
+    VertexType {
+        vertex_type: name @output
+
+        property {
+            name @output
+            docs @output
+            type @output
+        }
+    }
+}"#;
+
+const EDGES_QUERY: &str = r#"
+{
+    VertexType {
+        vertex_type: name @output
+
+        edge {
+            name @output
+            docs @output
+            to_many @output
+
+            target {
+                target: name @output
+            }
+
+            parameter @fold {
+                parameter_names: name @output
+                parameter_types: type @output
+            }
+        }
+    }
+}"#;
+
+const ENTRYPOINTS_QUERY: &str = r#"
+{
+    Entrypoint {
+        name @output
+        docs @output
+        to_many @output
+
+        target {
+            target: name @output
+        }
+
+        parameter @fold {
+            parameter_names: name @output
+            parameter_types: type @output
+        }
+    }
+}"#;
+
+#[derive(Debug, Deserialize)]
+struct TypeRow {
+    name: String,
+    docs: Option<String>,
+    subtypes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PropertyRow {
+    vertex_type: String,
+    #[serde(flatten)]
+    property: PropertyInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct EdgeRow {
+    vertex_type: String,
+    #[serde(flatten)]
+    edge: EdgeFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct EdgeFields {
+    name: String,
+    docs: Option<String>,
+    to_many: bool,
+    target: String,
+    parameter_names: Vec<String>,
+    parameter_types: Vec<String>,
+}
+
+impl From<EdgeFields> for EdgeInfo {
+    fn from(value: EdgeFields) -> Self {
+        Self {
+            name: value.name,
+            docs: value.docs,
+            target: value.target,
+            to_many: value.to_many,
+            parameters: value.parameter_names.into_iter().zip(value.parameter_types).collect(),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! # trustfall_lsp
+//!
+//! A language server for Trustfall queries written against a given schema.
+//! It offers completion of edges, properties, type coercions, and directives,
+//! hover info describing them, and live diagnostics for invalid queries
+//! as well as warnings about queries that are likely to contain mistakes.
+//!
+//! Start the server with: `trustfall_lsp <your_schema.graphql>`
+//! It communicates over stdin and stdout, as most editors expect.
+//!
+//! The query analysis itself is available without the protocol layer via [`Analyzer`].
+#![forbid(unsafe_code)]
+
+mod analysis;
+mod index;
+mod server;
+
+pub use analysis::{
+    Analyzer, Completion, CompletionKind, Hover, Position, QueryDiagnostic, Range, Severity,
+};
+pub use server::run;
@@ -0,0 +1,18 @@
+use std::{env, fs, io};
+
+use anyhow::Context;
+use trustfall_core::schema::Schema;
+use trustfall_lsp::Analyzer;
+
+fn main() -> anyhow::Result<()> {
+    let mut args = env::args().skip(1);
+    let (Some(schema_path), None) = (args.next(), args.next()) else {
+        anyhow::bail!("usage: trustfall_lsp <schema.graphql>");
+    };
+
+    let schema_text = fs::read_to_string(&schema_path)
+        .with_context(|| format!("failed to read schema file {schema_path}"))?;
+    let schema = Schema::parse(schema_text).context("schema is not valid")?;
+
+    trustfall_lsp::run(Analyzer::new(schema), io::stdin().lock(), io::stdout().lock())
+}
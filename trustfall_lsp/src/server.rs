@@ -0,0 +1,355 @@
+//! A minimal implementation of the Language Server Protocol over JSON-RPC.
+//!
+//! Supports full-text document synchronization, completion, hover, and published diagnostics.
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use anyhow::{bail, Context};
+use serde_json::{json, Value};
+
+use crate::analysis::{Analyzer, CompletionKind, Position, Range, Severity};
+
+/// JSON-RPC error code for requests whose method the server does not support.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Serves LSP requests read from the `input` stream, writing responses and notifications
+/// to the `output` stream, until the client sends the `exit` notification.
+pub fn run(
+    analyzer: Analyzer,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> anyhow::Result<()> {
+    let mut server = Server { analyzer, documents: HashMap::new(), outgoing: vec![] };
+
+    while let Some(message) = read_message(&mut input)? {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        if method == "exit" {
+            break;
+        }
+        server.handle(&message);
+        for outgoing in server.outgoing.drain(..) {
+            write_message(&mut output, &outgoing)?;
+        }
+    }
+
+    Ok(())
+}
+
+struct Server {
+    analyzer: Analyzer,
+    documents: HashMap<String, String>,
+    outgoing: Vec<Value>,
+}
+
+impl Server {
+    fn handle(&mut self, message: &Value) {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    // Full-text synchronization: each change contains the entire document.
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": ["@", "{"] },
+                    "hoverProvider": true,
+                },
+                "serverInfo": {
+                    "name": "trustfall_lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update_document(uri, text.to_string());
+                None
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) =
+                    changes.and_then(|c| c.last()).and_then(|change| change["text"].as_str())
+                {
+                    self.update_document(uri, text.to_string());
+                }
+                None
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                self.notify(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                );
+                None
+            }
+            "textDocument/completion" => Some(self.completion(&params)),
+            "textDocument/hover" => Some(self.hover(&params)),
+            _ => None,
+        };
+
+        // Notifications have no `id`, and must not be responded to.
+        let Some(id) = message.get("id") else {
+            return;
+        };
+        let response = match result {
+            Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            None => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": METHOD_NOT_FOUND,
+                    "message": format!("unsupported method: {method}"),
+                },
+            }),
+        };
+        self.outgoing.push(response);
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.outgoing.push(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn update_document(&mut self, uri: &str, text: String) {
+        let diagnostics: Vec<Value> = self
+            .analyzer
+            .diagnostics(&text)
+            .into_iter()
+            .map(|diagnostic| {
+                let severity = match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                };
+                json!({
+                    "range": to_lsp_range(&text, diagnostic.range),
+                    "severity": severity,
+                    "code": diagnostic.code,
+                    "source": "trustfall",
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        self.documents.insert(uri.to_string(), text);
+        self.notify(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        );
+    }
+
+    /// The document text and the position in it referenced by the request's parameters.
+    fn document_position(&self, params: &Value) -> Option<(&str, Position)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let text = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let character = params["position"]["character"].as_u64()? as usize;
+        Some((text, from_lsp_position(text, line, character)))
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let Some((text, position)) = self.document_position(params) else {
+            return json!([]);
+        };
+        let items: Vec<Value> = self
+            .analyzer
+            .completions(text, position)
+            .into_iter()
+            .map(|completion| {
+                // LSP `CompletionItemKind` values.
+                let kind = match completion.kind {
+                    CompletionKind::Property => 10,
+                    CompletionKind::Edge => 5,
+                    CompletionKind::VertexType => 7,
+                    CompletionKind::Directive => 14,
+                };
+                let mut item = json!({ "label": completion.label, "kind": kind });
+                if let Some(detail) = completion.detail {
+                    item["detail"] = detail.into();
+                }
+                if let Some(documentation) = completion.documentation {
+                    item["documentation"] = json!({ "kind": "markdown", "value": documentation });
+                }
+                item
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some((text, position)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        match self.analyzer.hover(text, position) {
+            Some(hover) => json!({
+                "contents": { "kind": "markdown", "value": hover.contents },
+                "range": to_lsp_range(text, hover.range),
+            }),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Converts an LSP position, whose `character` counts UTF-16 code units, into a [`Position`].
+fn from_lsp_position(text: &str, line: usize, character: usize) -> Position {
+    let line_text = text.split('\n').nth(line).unwrap_or_default();
+    let mut utf16_units = 0;
+    let mut chars = 0;
+    for c in line_text.chars() {
+        if utf16_units >= character {
+            break;
+        }
+        utf16_units += c.len_utf16();
+        chars += 1;
+    }
+    Position { line, character: chars }
+}
+
+fn to_lsp_position(text: &str, position: Position) -> Value {
+    let line_text = text.split('\n').nth(position.line).unwrap_or_default();
+    let character: usize = line_text.chars().take(position.character).map(char::len_utf16).sum();
+    json!({ "line": position.line, "character": character })
+}
+
+fn to_lsp_range(text: &str, range: Range) -> Value {
+    json!({ "start": to_lsp_position(text, range.start), "end": to_lsp_position(text, range.end) })
+}
+
+/// Reads one `Content-Length`-delimited JSON-RPC message, or `None` at the end of the input.
+fn read_message(input: &mut impl BufRead) -> anyhow::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length =
+                    Some(value.trim().parse::<usize>().context("invalid Content-Length header")?);
+            }
+        }
+    }
+
+    let Some(length) = content_length else {
+        bail!("message is missing a Content-Length header");
+    };
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body).context("message is not valid JSON")?))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> anyhow::Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::{json, Value};
+    use trustfall_core::schema::Schema;
+
+    use crate::analysis::Analyzer;
+
+    fn frame(message: Value) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    fn parse_output(mut output: &[u8]) -> Vec<Value> {
+        let mut messages = vec![];
+        while let Some(message) = super::read_message(&mut output).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn session() {
+        let path = "../trustfall_core/test_data/schemas/numbers.graphql";
+        let analyzer = Analyzer::new(Schema::parse(fs::read_to_string(path).unwrap()).unwrap());
+
+        let uri = "file:///query.graphql";
+        let text = "{\n    Number(max: 10) {\n        nonexistent @output\n    }\n}";
+        let fixed_text = "{\n    Number(max: 10) {\n        value @output\n    }\n}";
+        let input: String = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": { "uri": uri, "text": text } },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didChange",
+                "params": {
+                    "textDocument": { "uri": uri },
+                    "contentChanges": [{ "text": fixed_text }],
+                },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "textDocument/hover",
+                "params": {
+                    "textDocument": { "uri": uri },
+                    "position": { "line": 2, "character": 10 },
+                },
+            }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "workspace/symbol", "params": {} }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]
+        .into_iter()
+        .map(frame)
+        .collect();
+
+        let mut output = vec![];
+        super::run(analyzer, input.as_bytes(), &mut output).unwrap();
+        let messages = parse_output(&output);
+
+        assert_eq!(6, messages.len(), "{messages:#?}");
+        assert_eq!(json!(1), messages[0]["id"]);
+        assert_eq!(json!(true), messages[0]["result"]["capabilities"]["hoverProvider"]);
+
+        assert_eq!("textDocument/publishDiagnostics", messages[1]["method"]);
+        let diagnostics = messages[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(json!({ "line": 2, "character": 8 }), diagnostics[0]["range"]["start"]);
+
+        assert_eq!("textDocument/publishDiagnostics", messages[2]["method"]);
+        assert_eq!(json!([]), messages[2]["params"]["diagnostics"]);
+
+        assert_eq!(json!(2), messages[3]["id"]);
+        let hover = messages[3]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(hover.contains("value: Int"), "{hover}");
+
+        assert_eq!(json!(3), messages[4]["id"]);
+        assert_eq!(json!(super::METHOD_NOT_FOUND), messages[4]["error"]["code"]);
+
+        assert_eq!(json!(4), messages[5]["id"]);
+        assert_eq!(Value::Null, messages[5]["result"]);
+    }
+
+    #[test]
+    fn utf16_positions() {
+        let text = "{ \"é😀\" value }";
+        let position = super::from_lsp_position(text, 0, 8);
+        assert_eq!(7, position.character);
+        assert_eq!(json!({ "line": 0, "character": 8 }), super::to_lsp_position(text, position));
+    }
+}
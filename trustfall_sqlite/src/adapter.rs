@@ -0,0 +1,755 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use rusqlite::{types::Value, Connection};
+use trustfall::{
+    provider::{
+        candidate_to_sql_condition, resolve_coercion_with, resolve_property_with, Adapter,
+        AsVertex, CandidateValue, ContextIterator, ContextOutcomeIterator, EdgeParameters,
+        ResolveEdgeInfo, ResolveInfo, VertexInfo, VertexIterator,
+    },
+    FieldValue, Schema,
+};
+
+use crate::schema::{Column, ColumnType, Database, Table};
+
+/// The number of vertices whose neighbors are looked up with a single SQL query.
+const NEIGHBORS_BATCH_SIZE: usize = 64;
+
+/// An [`Adapter`] over the tables of a SQLite database, where each row is a vertex.
+///
+/// See the [crate documentation](crate) for the schema it serves.
+///
+/// Filters on a vertex's properties are pushed down into the SQL queries that look up its rows,
+/// except for filters on `Boolean` properties. The filters are still checked on the resulting rows,
+/// so filters that SQL can't express precisely, like case-sensitive `LIKE` patterns,
+/// only need to narrow down the rows rather than match exactly.
+///
+/// Column values that don't match the column's type in the schema are `null`,
+/// such as a `TEXT` value stored in an `INTEGER` column. `Boolean` columns are `true`
+/// for any nonzero integer value.
+///
+/// # Errors
+///
+/// Since [`Adapter`] methods cannot return errors, a failed SQL query is recorded and
+/// no more queries are run until the failure is reported, with the data they would have read
+/// treated as nonexistent. The failure is then reported by [`SqliteAdapter::check_results()`]
+/// in place of the query's remaining results, or returned by [`SqliteAdapter::take_error()`].
+#[derive(Debug)]
+pub struct SqliteAdapter {
+    schema: Schema,
+    database: Arc<Database>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    connection: Connection,
+
+    /// The failure of a SQL query, which hasn't been reported yet.
+    /// No more SQL queries are run until it has been.
+    failure: Option<rusqlite::Error>,
+}
+
+/// A vertex produced by [`SqliteAdapter`]: a row of one of the database's tables.
+#[derive(Debug, Clone)]
+pub struct SqliteVertex {
+    table: Arc<Table>,
+
+    /// The row's value in each of the table's columns that is part of the schema.
+    values: Arc<[Value]>,
+}
+
+/// Errors that may arise while making a [`SqliteAdapter`].
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteAdapterError {
+    #[error("Failed to read the database's tables: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("The database has no tables that can be queried.")]
+    NoTables,
+
+    #[error("The database's tables could not be described by a valid schema: {0}")]
+    InvalidSchema(String),
+}
+
+/// A SQL condition on a table's rows, together with the values of its `?` placeholders.
+#[derive(Debug)]
+struct Condition {
+    sql: String,
+    parameters: Vec<Value>,
+}
+
+/// A column value compared for equality the way SQL compares it,
+/// where an integer and a real number of the same value are equal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Integer(i64),
+    Real(u64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqliteAdapter {
+    /// Make an adapter over the tables of the database with the given connection.
+    pub fn new(connection: Connection) -> Result<Self, SqliteAdapterError> {
+        let database = Database::introspect(&connection)?;
+        let schema = database.schema()?;
+        Ok(Self {
+            schema,
+            database: Arc::new(database),
+            state: Arc::new(Mutex::new(State { connection, failure: None })),
+        })
+    }
+
+    /// Open the database file at the given path, and make an adapter over its tables.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteAdapterError> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// The schema describing the database's tables.
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Take the failure of a SQL query, if one failed since the last time a failure was taken.
+    ///
+    /// Once a SQL query fails, no more are run until its failure is taken, and the data
+    /// they would have read is treated as nonexistent. Query results computed after a failure
+    /// may therefore be incomplete and should be discarded.
+    pub fn take_error(&self) -> Option<rusqlite::Error> {
+        self.state.lock().expect("SQLite connection lock was poisoned").failure.take()
+    }
+
+    /// Produce the results of a query executed over this adapter,
+    /// ending with an error if a SQL query fails.
+    ///
+    /// Results computed after the failure may be missing data, so they are discarded.
+    /// Results produced before it are unaffected.
+    pub fn check_results<'a>(
+        &self,
+        mut results: impl Iterator<Item = BTreeMap<Arc<str>, FieldValue>> + 'a,
+    ) -> impl Iterator<Item = Result<BTreeMap<Arc<str>, FieldValue>, rusqlite::Error>> + 'a {
+        let state = self.state.clone();
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            let row = results.next();
+            if let Some(error) =
+                state.lock().expect("SQLite connection lock was poisoned").failure.take()
+            {
+                finished = true;
+                return Some(Err(error));
+            }
+            finished = row.is_none();
+            row.map(Ok)
+        })
+    }
+}
+
+impl SqliteVertex {
+    /// The name of the table containing this row, which is also the vertex's type name.
+    #[inline]
+    pub fn table(&self) -> &str {
+        &self.table.name
+    }
+
+    /// The value of this row in the named column, if the column is part of the schema.
+    pub fn get(&self, column: &str) -> Option<&Value> {
+        self.table.column(column).map(|index| &self.values[index])
+    }
+}
+
+impl<'vertex> Adapter<'vertex> for SqliteAdapter {
+    type Vertex = SqliteVertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'vertex, Self::Vertex> {
+        let table = self
+            .database
+            .tables
+            .get(edge_name)
+            .unwrap_or_else(|| panic!("{edge_name} is not a table in the database"))
+            .clone();
+        let conditions = pushdown_conditions(&table, resolve_info);
+        let state = self.state.clone();
+
+        // Only read the rows once the first of them is needed.
+        Box::new(
+            std::iter::once(()).flat_map(move |()| read_rows(&state, &table, conditions.iter())),
+        )
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            return resolve_property_with(contexts, |vertex: &SqliteVertex| {
+                vertex.table.name.clone().into()
+            });
+        }
+
+        let table = &self.database.tables[type_name];
+        let index = table
+            .column(property_name)
+            .unwrap_or_else(|| panic!("type {type_name} has no property {property_name}"));
+        let ty = table.columns[index].ty;
+        resolve_property_with(contexts, move |vertex: &SqliteVertex| {
+            to_field_value(&vertex.values[index], ty)
+        })
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        mut contexts: ContextIterator<'vertex, V>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        _parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, VertexIterator<'vertex, Self::Vertex>> {
+        let edge = self
+            .database
+            .edges
+            .get(&(type_name.clone(), edge_name.clone()))
+            .unwrap_or_else(|| panic!("type {type_name} has no edge {edge_name}"))
+            .clone();
+        let table = self.database.tables[&edge.to_table].clone();
+        // Vertices reached partway through a recursion don't need to match the filters
+        // of the edge's destination, so they can't be pushed down.
+        let conditions = if resolve_info.edge().recursive().is_some() {
+            vec![]
+        } else {
+            pushdown_conditions(&table, &resolve_info.destination())
+        };
+        let state = self.state.clone();
+
+        let batches = std::iter::from_fn(move || {
+            let batch: Vec<_> = contexts.by_ref().take(NEIGHBORS_BATCH_SIZE).collect();
+            if batch.is_empty() {
+                return None;
+            }
+
+            let keys: BTreeSet<Key> = batch
+                .iter()
+                .filter_map(|ctx| ctx.active_vertex::<SqliteVertex>())
+                .filter_map(|vertex| to_key(&vertex.values[edge.from_column]))
+                .collect();
+            let mut neighbors: BTreeMap<Key, Vec<SqliteVertex>> = BTreeMap::new();
+            if !keys.is_empty() {
+                let join = Condition {
+                    sql: format!(
+                        "{} IN ({})",
+                        quote(&table.columns[edge.to_column].name),
+                        vec!["?"; keys.len()].join(", "),
+                    ),
+                    parameters: keys.into_iter().map(Key::into_value).collect(),
+                };
+                for row in read_rows(&state, &table, std::iter::once(&join).chain(&conditions)) {
+                    if let Some(key) = to_key(&row.values[edge.to_column]) {
+                        neighbors.entry(key).or_default().push(row);
+                    }
+                }
+            }
+
+            let outcomes: Vec<_> = batch
+                .into_iter()
+                .map(|ctx| {
+                    let rows = ctx
+                        .active_vertex::<SqliteVertex>()
+                        .and_then(|vertex| to_key(&vertex.values[edge.from_column]))
+                        .and_then(|key| neighbors.get(&key).cloned())
+                        .unwrap_or_default();
+                    let rows: VertexIterator<'vertex, Self::Vertex> = Box::new(rows.into_iter());
+                    (ctx, rows)
+                })
+                .collect();
+            Some(outcomes)
+        });
+        Box::new(batches.flatten())
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'vertex>(
+        &self,
+        contexts: ContextIterator<'vertex, V>,
+        _type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'vertex, V, bool> {
+        let coerce_to_type = coerce_to_type.clone();
+        resolve_coercion_with(contexts, move |vertex: &SqliteVertex| {
+            vertex.table.name == coerce_to_type
+        })
+    }
+}
+
+impl Key {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Integer(value) => Value::Integer(value),
+            Self::Real(bits) => Value::Real(f64::from_bits(bits)),
+            Self::Text(value) => Value::Text(value),
+            Self::Blob(value) => Value::Blob(value),
+        }
+    }
+}
+
+/// Read the rows of the table that satisfy all the conditions.
+///
+/// If the SQL query fails, its failure is recorded and no rows are returned.
+/// No SQL query is run while an earlier failure hasn't been reported.
+fn read_rows<'a>(
+    state: &Mutex<State>,
+    table: &Arc<Table>,
+    conditions: impl Iterator<Item = &'a Condition>,
+) -> Vec<SqliteVertex> {
+    let mut state = state.lock().expect("SQLite connection lock was poisoned");
+    if state.failure.is_some() {
+        return vec![];
+    }
+
+    let columns: Vec<_> = table.columns.iter().map(|column| quote(&column.name)).collect();
+    let mut sql = format!("SELECT {} FROM {}", columns.join(", "), quote(&table.name));
+    let mut parameters = vec![];
+    for (index, condition) in conditions.enumerate() {
+        sql.push_str(if index == 0 { " WHERE " } else { " AND " });
+        sql.push_str(&condition.sql);
+        parameters.extend(&condition.parameters);
+    }
+
+    let rows = state.connection.prepare_cached(&sql).and_then(|mut statement| {
+        statement
+            .query_map(rusqlite::params_from_iter(parameters), |row| {
+                (0..table.columns.len()).map(|index| row.get(index)).collect()
+            })?
+            .map(|values| values.map(|values| SqliteVertex { table: table.clone(), values }))
+            .collect()
+    });
+    match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            state.failure = Some(e);
+            vec![]
+        }
+    }
+}
+
+/// The conditions on a table's rows required by the filters on the vertex's properties,
+/// for the filters that can be checked in SQL.
+fn pushdown_conditions(table: &Table, vertex: &impl VertexInfo) -> Vec<Condition> {
+    table
+        .columns
+        .iter()
+        .filter_map(|column| {
+            let candidate = vertex.statically_required_property(&column.name)?;
+            pushdown_condition(column, &candidate)
+        })
+        .collect()
+}
+
+/// The SQL condition on the column equivalent to the candidate values of its property.
+///
+/// Values that don't match the column's type are `null` properties just like SQL `NULL`s,
+/// so candidates that include `null` also match them. Candidates for `Boolean` columns
+/// aren't pushed down, since any nonzero value is `true`.
+fn pushdown_condition(
+    column: &Column,
+    candidate: &CandidateValue<FieldValue>,
+) -> Option<Condition> {
+    let null_included = match candidate {
+        CandidateValue::Impossible | CandidateValue::StringPattern(_) => false,
+        CandidateValue::Single(value) => matches!(value, FieldValue::Null),
+        CandidateValue::Multiple(values) => values.iter().any(|v| matches!(v, FieldValue::Null)),
+        CandidateValue::Range(range) => range.null_included(),
+        _ => return None,
+    };
+    // The storage classes of the values that aren't `null` properties.
+    let storage_classes = match column.ty {
+        ColumnType::Int => "'integer'",
+        ColumnType::Float => "'integer', 'real'",
+        ColumnType::String => "'text'",
+        ColumnType::Boolean => return None,
+    };
+
+    // Compare strings byte-wise like Trustfall does, regardless of the column's collation.
+    let column = quote(&column.name);
+    let sql = candidate_to_sql_condition(&format!("{column} COLLATE BINARY"), candidate);
+    let parameters = sql.parameters.iter().map(to_sql_value).collect::<Option<_>>()?;
+    let sql = if null_included {
+        format!("({} OR typeof({column}) NOT IN ({storage_classes}))", sql.condition)
+    } else {
+        sql.condition
+    };
+    Some(Condition { sql, parameters })
+}
+
+fn to_sql_value(value: &FieldValue) -> Option<Value> {
+    match value {
+        FieldValue::Int64(value) => Some(Value::Integer(*value)),
+        FieldValue::Uint64(value) => i64::try_from(*value).ok().map(Value::Integer),
+        FieldValue::Float64(value) => Some(Value::Real(*value)),
+        FieldValue::String(value) => Some(Value::Text(value.to_string())),
+        _ => None,
+    }
+}
+
+fn to_field_value(value: &Value, ty: ColumnType) -> FieldValue {
+    match (ty, value) {
+        (ColumnType::Int, Value::Integer(value)) => FieldValue::Int64(*value),
+        (ColumnType::Float, Value::Integer(value)) => FieldValue::Float64(*value as f64),
+        (ColumnType::Float, Value::Real(value)) => FieldValue::Float64(*value),
+        (ColumnType::String, Value::Text(value)) => value.as_str().into(),
+        (ColumnType::Boolean, Value::Integer(value)) => FieldValue::Boolean(*value != 0),
+        _ => FieldValue::Null,
+    }
+}
+
+fn to_key(value: &Value) -> Option<Key> {
+    match value {
+        Value::Null => None,
+        Value::Integer(value) => Some(Key::Integer(*value)),
+        Value::Real(value)
+            if value.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(value) =>
+        {
+            Some(Key::Integer(*value as i64))
+        }
+        Value::Real(value) => Some(Key::Real(value.to_bits())),
+        Value::Text(value) => Some(Key::Text(value.clone())),
+        Value::Blob(value) => Some(Key::Blob(value.clone())),
+    }
+}
+
+/// Quote a table or column name for use in SQL.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use rusqlite::{types::Value, Connection};
+    use trustfall::{
+        execute_query,
+        provider::{check_adapter_invariants, CandidateValue},
+        FieldValue,
+    };
+
+    use super::{pushdown_condition, SqliteAdapter, SqliteAdapterError};
+    use crate::schema::{Column, ColumnType};
+
+    const DATABASE: &str = "
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL COLLATE NOCASE,
+    score REAL,
+    admin BOOLEAN,
+    avatar BLOB
+);
+CREATE TABLE posts (
+    id INTEGER PRIMARY KEY,
+    title VARCHAR(100) NOT NULL,
+    author_id INTEGER REFERENCES users,
+    reviewer INT REFERENCES users (id)
+);
+CREATE TABLE \"odd name\" (value INTEGER);
+
+INSERT INTO users VALUES (1, 'ada', 9.5, TRUE, x'00'), (2, 'Grace', 7, FALSE, NULL),
+    (3, 'Linus', 'n/a', NULL, NULL);
+INSERT INTO posts VALUES (1, 'Notes', 1, 2), (2, 'Compilers', 2, NULL), (3, 'Engines', 1, 1),
+    (4, 'Orphan', NULL, NULL);
+";
+
+    fn adapter() -> Arc<SqliteAdapter> {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(DATABASE).unwrap();
+        Arc::new(SqliteAdapter::new(connection).unwrap())
+    }
+
+    fn run_query(
+        adapter: &Arc<SqliteAdapter>,
+        query: &str,
+        variables: BTreeMap<&str, FieldValue>,
+    ) -> Vec<BTreeMap<Arc<str>, FieldValue>> {
+        let results =
+            execute_query(adapter.schema(), adapter.clone(), query, variables).expect("bad query");
+        adapter.check_results(results).collect::<Result<_, _>>().expect("query failed")
+    }
+
+    fn row<const N: usize>(values: [(&str, FieldValue); N]) -> BTreeMap<Arc<str>, FieldValue> {
+        values.into_iter().map(|(name, value)| (Arc::from(name), value)).collect()
+    }
+
+    #[test]
+    fn schema_describes_tables_and_foreign_keys() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(DATABASE).unwrap();
+        let database = crate::schema::Database::introspect(&connection).unwrap();
+
+        let expected = format!(
+            "\
+schema {{
+    query: RootSchemaQuery
+}}
+{}
+
+type RootSchemaQuery {{
+    posts: [posts!]!
+    users: [users!]!
+}}
+
+type posts {{
+    id: Int!
+    title: String!
+    author_id: Int
+    reviewer: Int
+    author: users
+    reviewer_users: users
+}}
+
+type users {{
+    id: Int!
+    name: String!
+    score: Float
+    admin: Boolean
+    posts_by_author: [posts!]
+    posts_by_reviewer_users: [posts!]
+}}
+",
+            trustfall::Schema::ALL_DIRECTIVE_DEFINITIONS,
+        );
+        similar_asserts::assert_eq!(expected, database.schema_text());
+    }
+
+    #[test]
+    fn databases_without_tables_are_rejected() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch("CREATE TABLE files (contents BLOB);").unwrap();
+
+        assert!(matches!(SqliteAdapter::new(connection), Err(SqliteAdapterError::NoTables)));
+    }
+
+    #[test]
+    fn adapter_invariants() {
+        let adapter = Arc::into_inner(adapter()).unwrap();
+        let schema = adapter.schema().clone();
+        check_adapter_invariants(&schema, adapter);
+    }
+
+    #[test]
+    fn foreign_keys_are_edges_in_both_directions() {
+        let adapter = adapter();
+        let query = r#"
+{
+    posts {
+        title @output
+        author @optional {
+            name @output
+        }
+        reviewer_users @optional {
+            reviewer: name @output
+            posts_by_author @fold {
+                reviewed_author_titles: title @output
+            }
+        }
+    }
+}"#;
+
+        let results = run_query(&adapter, query, BTreeMap::new());
+        let titles = |titles: &[&str]| -> FieldValue {
+            titles.iter().map(|title| FieldValue::from(*title)).collect::<Vec<_>>().into()
+        };
+        assert_eq!(
+            vec![
+                row([
+                    ("title", "Notes".into()),
+                    ("name", "ada".into()),
+                    ("reviewer", "Grace".into()),
+                    ("reviewed_author_titles", titles(&["Compilers"])),
+                ]),
+                row([
+                    ("title", "Compilers".into()),
+                    ("name", "Grace".into()),
+                    ("reviewer", FieldValue::Null),
+                    ("reviewed_author_titles", FieldValue::Null),
+                ]),
+                row([
+                    ("title", "Engines".into()),
+                    ("name", "ada".into()),
+                    ("reviewer", "ada".into()),
+                    ("reviewed_author_titles", titles(&["Notes", "Engines"])),
+                ]),
+                row([
+                    ("title", "Orphan".into()),
+                    ("name", FieldValue::Null),
+                    ("reviewer", FieldValue::Null),
+                    ("reviewed_author_titles", FieldValue::Null),
+                ]),
+            ],
+            results,
+        );
+    }
+
+    #[test]
+    fn values_are_converted_to_the_column_type() {
+        let adapter = adapter();
+        let query = r#"
+{
+    users {
+        __typename @output
+        id @output
+        score @output
+        admin @output
+    }
+}"#;
+
+        let results = run_query(&adapter, query, BTreeMap::new());
+        assert_eq!(
+            vec![
+                row([
+                    ("__typename", "users".into()),
+                    ("id", 1.into()),
+                    ("score", FieldValue::Float64(9.5)),
+                    ("admin", true.into()),
+                ]),
+                row([
+                    ("__typename", "users".into()),
+                    ("id", 2.into()),
+                    ("score", FieldValue::Float64(7.0)),
+                    ("admin", false.into()),
+                ]),
+                // The text stored in the `REAL` column isn't a valid `Float` value.
+                row([
+                    ("__typename", "users".into()),
+                    ("id", 3.into()),
+                    ("score", FieldValue::Null),
+                    ("admin", FieldValue::Null),
+                ]),
+            ],
+            results,
+        );
+    }
+
+    #[test]
+    fn pushed_down_filters_match_like_trustfall_filters() {
+        let adapter = adapter();
+        let names = |query: &str, value: FieldValue| -> Vec<FieldValue> {
+            run_query(&adapter, query, BTreeMap::from([("value", value)]))
+                .into_iter()
+                .map(|mut row| row.remove("name").unwrap())
+                .collect()
+        };
+
+        // Trustfall compares strings case-sensitively,
+        // regardless of the column's collation or SQL's `LIKE` semantics.
+        let equal_name = r#"{ users { name @output @filter(op: "=", value: ["$value"]) } }"#;
+        assert_eq!(Vec::<FieldValue>::new(), names(equal_name, "ADA".into()));
+        assert_eq!(vec![FieldValue::from("ada")], names(equal_name, "ada".into()));
+        let prefix = r#"{ users { name @output @filter(op: "has_prefix", value: ["$value"]) } }"#;
+        assert_eq!(vec![FieldValue::from("Grace")], names(prefix, "G".into()));
+        assert_eq!(Vec::<FieldValue>::new(), names(prefix, "g".into()));
+
+        let min_score = r#"
+{
+    users {
+        name @output
+        score @filter(op: ">", value: ["$value"])
+    }
+}"#;
+        assert_eq!(vec![FieldValue::from("ada")], names(min_score, FieldValue::Float64(8.0)));
+
+        let post_title = r#"
+{
+    users {
+        name @output
+        posts_by_author {
+            title @filter(op: "has_prefix", value: ["$value"])
+        }
+    }
+}"#;
+        assert_eq!(vec![FieldValue::from("ada")], names(post_title, "E".into()));
+
+        // `null` properties include values that don't match the column's type.
+        let null_score = r#"
+{
+    users {
+        name @output
+        score @filter(op: "=", value: ["$value"])
+    }
+}"#;
+        let null_reviewer = r#"
+{
+    users {
+        name @output
+        posts_by_author {
+            reviewer @filter(op: "=", value: ["$value"])
+        }
+    }
+}"#;
+        assert_eq!(vec![FieldValue::from("Linus")], names(null_score, FieldValue::Null));
+        assert_eq!(vec![FieldValue::from("Grace")], names(null_reviewer, FieldValue::Null));
+    }
+
+    #[test]
+    fn candidates_are_pushed_down_as_sql_conditions() {
+        let column = |ty| Column { name: Arc::from("value"), ty, nullable: true };
+
+        let condition =
+            pushdown_condition(&column(ColumnType::Int), &CandidateValue::Single(3.into()))
+                .unwrap();
+        assert_eq!("\"value\" COLLATE BINARY = ?", condition.sql);
+        assert_eq!(vec![Value::Integer(3)], condition.parameters);
+
+        let null_included = CandidateValue::Multiple(vec!["a".into(), FieldValue::Null]);
+        let condition = pushdown_condition(&column(ColumnType::String), &null_included).unwrap();
+        assert_eq!(
+            "((\"value\" COLLATE BINARY IN (?) OR \"value\" COLLATE BINARY IS NULL) \
+            OR typeof(\"value\") NOT IN ('text'))",
+            condition.sql,
+        );
+        assert_eq!(vec![Value::Text("a".to_string())], condition.parameters);
+
+        let boolean = CandidateValue::Single(true.into());
+        assert!(pushdown_condition(&column(ColumnType::Boolean), &boolean).is_none());
+        let too_large = CandidateValue::Single(FieldValue::Uint64(u64::MAX));
+        assert!(pushdown_condition(&column(ColumnType::Int), &too_large).is_none());
+    }
+
+    #[test]
+    fn failed_queries_are_reported_instead_of_remaining_results() {
+        let path = std::env::temp_dir().join(format!("trustfall_sqlite_{}.db", std::process::id()));
+        let connection = Connection::open(&path).unwrap();
+        connection.execute_batch(DATABASE).unwrap();
+        let adapter = Arc::new(SqliteAdapter::open(&path).unwrap());
+        connection.execute_batch("DROP TABLE posts;").unwrap();
+
+        let query = r#"{ users { name @output posts_by_author { title @output } } }"#;
+        let results = execute_query(
+            adapter.schema(),
+            adapter.clone(),
+            query,
+            BTreeMap::<&str, FieldValue>::new(),
+        )
+        .unwrap();
+        let results: Vec<_> = adapter.check_results(results).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(1, results.len(), "{results:?}");
+        assert!(results[0].is_err(), "{results:?}");
+        assert!(adapter.take_error().is_none());
+    }
+}
@@ -0,0 +1,59 @@
+//! Query SQLite databases with Trustfall.
+//!
+//! [`SqliteAdapter`] reads the tables and foreign keys of a database,
+//! and serves a schema describing them:
+//! - Each table is a vertex type of the same name, and a root edge pointing to its rows.
+//! - Each column is a property of the table's type. Columns are `Int`, `String`, `Float`,
+//!   or `Boolean` depending on their declared type, and are non-null if declared `NOT NULL`.
+//!   Columns with `BLOB` affinity aren't supported.
+//! - Each single-column foreign key is an edge from the referencing row to the row it refers to,
+//!   and a list-typed edge in the opposite direction. For a foreign key from `posts.author_id`
+//!   to `users.id`, `posts` has an `author` edge and `users` has a `posts_by_author` edge.
+//!   The `_id` suffix is dropped from the column's name if it has one; otherwise the edge is
+//!   named `{column}_{table}`, such as `reviewer_users` for `posts.reviewer`.
+//!
+//! Tables and columns whose names aren't valid in a schema are left out, as are edges whose
+//! names would clash with a column or another edge.
+//!
+//! # Examples
+//! ```
+//! use std::{collections::BTreeMap, sync::Arc};
+//!
+//! use trustfall::{execute_query, FieldValue};
+//! use trustfall_sqlite::{rusqlite::Connection, SqliteAdapter};
+//!
+//! let connection = Connection::open_in_memory()?;
+//! connection.execute_batch(
+//!     "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+//!     CREATE TABLE posts (title TEXT NOT NULL, author_id INTEGER REFERENCES users (id));
+//!     INSERT INTO users VALUES (1, 'Ada'), (2, 'Grace');
+//!     INSERT INTO posts VALUES ('Notes', 1), ('Compilers', 2);",
+//! )?;
+//! let adapter = Arc::new(SqliteAdapter::new(connection)?);
+//!
+//! let query = r#"
+//! {
+//!     posts {
+//!         title @output
+//!         author {
+//!             name @filter(op: "=", value: ["$name"])
+//!         }
+//!     }
+//! }"#;
+//! let variables = BTreeMap::from([("name", "Grace")]);
+//! let results: Vec<_> =
+//!     adapter.check_results(execute_query(adapter.schema(), adapter.clone(), query, variables)?)
+//!         .collect::<Result<_, _>>()?;
+//!
+//! assert_eq!(results, vec![BTreeMap::from([(Arc::from("title"), FieldValue::from("Compilers"))])]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+#![forbid(unsafe_code)]
+
+mod adapter;
+mod schema;
+
+pub use adapter::{SqliteAdapter, SqliteAdapterError, SqliteVertex};
+
+// The `rusqlite` version used by this crate, for opening database connections.
+pub use rusqlite;
@@ -0,0 +1,273 @@
+//! Introspecting a SQLite database's tables and foreign keys,
+//! and describing them as a Trustfall schema.
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use rusqlite::Connection;
+use trustfall::Schema;
+
+use crate::SqliteAdapterError;
+
+/// The name of the schema's root query type, which no table may share.
+const QUERY_TYPE: &str = "RootSchemaQuery";
+
+/// Names of the schema's built-in types, which no table may share.
+const BUILTIN_TYPES: [&str; 6] = [QUERY_TYPE, "Boolean", "Float", "ID", "Int", "String"];
+
+/// The tables and foreign keys of a database that can be queried.
+#[derive(Debug)]
+pub(crate) struct Database {
+    pub(crate) tables: BTreeMap<Arc<str>, Arc<Table>>,
+
+    /// The edges of each table's vertex type, keyed by the table's and the edge's names.
+    pub(crate) edges: BTreeMap<(Arc<str>, Arc<str>), Edge>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Table {
+    pub(crate) name: Arc<str>,
+    pub(crate) columns: Vec<Column>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Column {
+    pub(crate) name: Arc<str>,
+    pub(crate) ty: ColumnType,
+    pub(crate) nullable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnType {
+    Int,
+    Float,
+    String,
+    Boolean,
+}
+
+/// An edge following a foreign key in either direction: its neighbors are the rows of
+/// `to_table` whose `to_column` value is equal to the vertex's `from_column` value.
+#[derive(Debug, Clone)]
+pub(crate) struct Edge {
+    pub(crate) from_column: usize,
+    pub(crate) to_table: Arc<str>,
+    pub(crate) to_column: usize,
+    pub(crate) is_list: bool,
+}
+
+/// A single-column foreign key, as declared in the database.
+struct ForeignKey {
+    from_table: Arc<str>,
+    from_column: String,
+    to_table: String,
+
+    /// `None` if the foreign key refers to the primary key of `to_table`.
+    to_column: Option<String>,
+}
+
+impl Table {
+    pub(crate) fn column(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name.as_ref() == name)
+    }
+}
+
+impl ColumnType {
+    /// The type of the values in a column with the given declared type, if it's supported.
+    ///
+    /// Follows the rules SQLite uses to determine the column's type affinity,
+    /// except that `BOOL`-typed columns are `Boolean` rather than `Float`.
+    /// Columns with `BLOB` affinity aren't supported.
+    fn from_declared(declared: &str) -> Option<Self> {
+        let declared = declared.to_ascii_uppercase();
+        let contains_any = |parts: &[&str]| parts.iter().any(|part| declared.contains(part));
+        if declared.contains("INT") {
+            Some(Self::Int)
+        } else if contains_any(&["CHAR", "CLOB", "TEXT"]) {
+            Some(Self::String)
+        } else if declared.is_empty() || declared.contains("BLOB") {
+            None
+        } else if contains_any(&["REAL", "FLOA", "DOUB"]) {
+            Some(Self::Float)
+        } else if declared.contains("BOOL") {
+            Some(Self::Boolean)
+        } else {
+            Some(Self::Float)
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Int => "Int",
+            Self::Float => "Float",
+            Self::String => "String",
+            Self::Boolean => "Boolean",
+        }
+    }
+}
+
+impl Database {
+    pub(crate) fn introspect(connection: &Connection) -> Result<Self, SqliteAdapterError> {
+        let table_names: Vec<String> = connection
+            .prepare(
+                "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') ORDER BY name",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        let mut tables = BTreeMap::new();
+        let mut primary_keys = BTreeMap::new();
+        let mut foreign_keys = vec![];
+        for table_name in table_names {
+            if table_name.starts_with("sqlite_")
+                || !is_valid_name(&table_name)
+                || BUILTIN_TYPES.contains(&table_name.as_str())
+            {
+                continue;
+            }
+            let table_name: Arc<str> = Arc::from(table_name);
+
+            let mut columns = vec![];
+            let mut primary_key = vec![];
+            let mut statement = connection
+                .prepare("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?1)")?;
+            let mut rows = statement.query([table_name.as_ref()])?;
+            while let Some(row) = rows.next()? {
+                let name: String = row.get(0)?;
+                let declared: String = row.get(1)?;
+                let not_null: bool = row.get(2)?;
+                let primary_key_index: i64 = row.get(3)?;
+                if primary_key_index > 0 {
+                    primary_key.push(name.clone());
+                }
+
+                let Some(ty) = ColumnType::from_declared(&declared) else {
+                    continue;
+                };
+                if !is_valid_name(&name) {
+                    continue;
+                }
+                // A column declared `INTEGER PRIMARY KEY` is an alias for the row ID,
+                // so it's never null even if not declared `NOT NULL`.
+                let row_id = primary_key_index > 0 && declared.eq_ignore_ascii_case("INTEGER");
+                columns.push(Column { name: Arc::from(name), ty, nullable: !not_null && !row_id });
+            }
+            if columns.is_empty() {
+                continue;
+            }
+            if let [primary_key] = primary_key.as_slice() {
+                primary_keys.insert(table_name.clone(), primary_key.clone());
+            }
+
+            let mut columns_by_key: BTreeMap<i64, Vec<ForeignKey>> = BTreeMap::new();
+            let mut statement = connection.prepare(
+                "SELECT id, \"table\", \"from\", \"to\" FROM pragma_foreign_key_list(?1)",
+            )?;
+            let mut rows = statement.query([table_name.as_ref()])?;
+            while let Some(row) = rows.next()? {
+                columns_by_key.entry(row.get(0)?).or_default().push(ForeignKey {
+                    from_table: table_name.clone(),
+                    to_table: row.get(1)?,
+                    from_column: row.get(2)?,
+                    to_column: row.get(3)?,
+                });
+            }
+            // Foreign keys of multiple columns aren't supported.
+            foreign_keys
+                .extend(columns_by_key.into_values().filter(|key| key.len() == 1).flatten());
+
+            tables.insert(table_name.clone(), Table { name: table_name, columns });
+        }
+
+        let mut edges = BTreeMap::new();
+        for key in foreign_keys {
+            let Some(to_column) =
+                key.to_column.or_else(|| primary_keys.get(&*key.to_table).cloned())
+            else {
+                continue;
+            };
+            let (Some(from_table), Some(to_table)) =
+                (tables.get(&key.from_table), tables.get(key.to_table.as_str()))
+            else {
+                continue;
+            };
+            let (Some(from_index), Some(to_index)) =
+                (from_table.column(&key.from_column), to_table.column(&to_column))
+            else {
+                continue;
+            };
+
+            let forward_name = match key.from_column.strip_suffix("_id") {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => format!("{}_{}", key.from_column, to_table.name),
+            };
+            let reverse_name = format!("{}_by_{forward_name}", from_table.name);
+
+            let forward = Edge {
+                from_column: from_index,
+                to_table: to_table.name.clone(),
+                to_column: to_index,
+                is_list: false,
+            };
+            let reverse = Edge {
+                from_column: to_index,
+                to_table: from_table.name.clone(),
+                to_column: from_index,
+                is_list: true,
+            };
+            for (table, name, edge) in
+                [(from_table, forward_name, forward), (to_table, reverse_name, reverse)]
+            {
+                // Edges whose names clash with a column or another edge are left out.
+                let key = (table.name.clone(), Arc::from(name));
+                if table.column(&key.1).is_none() && !edges.contains_key(&key) {
+                    edges.insert(key, edge);
+                }
+            }
+        }
+
+        let tables = tables.into_iter().map(|(name, table)| (name, Arc::new(table))).collect();
+        Ok(Self { tables, edges })
+    }
+
+    pub(crate) fn schema(&self) -> Result<Schema, SqliteAdapterError> {
+        if self.tables.is_empty() {
+            return Err(SqliteAdapterError::NoTables);
+        }
+        Schema::parse(self.schema_text())
+            .map_err(|e| SqliteAdapterError::InvalidSchema(e.to_string()))
+    }
+
+    pub(crate) fn schema_text(&self) -> String {
+        let mut text = format!(
+            "schema {{\n    query: {QUERY_TYPE}\n}}\n{}\n\ntype {QUERY_TYPE} {{\n",
+            Schema::ALL_DIRECTIVE_DEFINITIONS
+        );
+        for name in self.tables.keys() {
+            writeln!(text, "    {name}: [{name}!]!").expect("write failed");
+        }
+        text.push_str("}\n");
+
+        for (name, table) in &self.tables {
+            writeln!(text, "\ntype {name} {{").expect("write failed");
+            for column in &table.columns {
+                let non_null = if column.nullable { "" } else { "!" };
+                writeln!(text, "    {}: {}{non_null}", column.name, column.ty.name())
+                    .expect("write failed");
+            }
+            for ((_, edge_name), edge) in self.edges.iter().filter(|((from, _), _)| from == name) {
+                let to_table = &edge.to_table;
+                let ty = if edge.is_list { format!("[{to_table}!]") } else { to_table.to_string() };
+                writeln!(text, "    {edge_name}: {ty}").expect("write failed");
+            }
+            text.push_str("}\n");
+        }
+        text
+    }
+}
+
+/// Whether the name can be used for a type or field in the schema.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_validly = chars.next().is_some_and(|c| c == '_' || c.is_ascii_alphabetic());
+    starts_validly
+        && !name.starts_with("__")
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
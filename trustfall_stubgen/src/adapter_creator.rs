@@ -8,7 +8,7 @@ use quote::quote;
 use trustfall::{Schema, SchemaAdapter, TryIntoStruct};
 
 use super::{
-    root::RustFile,
+    root::{RustFile, StubgenConfig},
     util::{parse_import, property_resolver_fn_name, type_edge_resolver_fn_name},
 };
 
@@ -17,35 +17,18 @@ pub(super) fn make_adapter_file(
     adapter: Arc<SchemaAdapter<'_>>,
     adapter_file: &mut RustFile,
     entrypoint_match_arms: proc_macro2::TokenStream,
+    config: &StubgenConfig,
 ) {
-    let static_defn = quote! {
-        static SCHEMA: OnceLock<Schema> = OnceLock::new();
-    };
-
-    let adapter_defn = quote! {
-        #[non_exhaustive]
-        #[derive(Debug)]
-        pub struct Adapter {}
-    };
-
-    adapter_file.builtin_imports.insert(parse_import("std::sync::OnceLock"));
-    adapter_file.external_imports.insert(parse_import("trustfall::Schema"));
-
-    adapter_file.internal_imports.insert(parse_import("super::vertex::Vertex"));
-
-    let adapter_impl = quote! {
-        impl Adapter {
-            pub const SCHEMA_TEXT: &'static str = include_str!("./schema.graphql");
-
-            pub fn schema() -> &'static Schema {
-                SCHEMA.get_or_init(|| Schema::parse(Self::SCHEMA_TEXT).expect("not a valid schema"))
-            }
+    if config.basic_adapter {
+        return make_basic_adapter_file(
+            querying_schema,
+            adapter,
+            adapter_file,
+            entrypoint_match_arms,
+        );
+    }
 
-            pub fn new() -> Self {
-                Self {}
-            }
-        }
-    };
+    let (static_defn, adapter_defn, adapter_impl) = emit_adapter_struct(adapter_file);
 
     let entrypoint_resolver_fn = emit_entrypoint_handling(
         entrypoint_match_arms,
@@ -91,6 +74,127 @@ pub(super) fn make_adapter_file(
     ]);
 }
 
+fn emit_adapter_struct(
+    adapter_file: &mut RustFile,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let static_defn = quote! {
+        static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    };
+
+    let adapter_defn = quote! {
+        #[non_exhaustive]
+        #[derive(Debug)]
+        pub struct Adapter {}
+    };
+
+    adapter_file.builtin_imports.insert(parse_import("std::sync::OnceLock"));
+    adapter_file.external_imports.insert(parse_import("trustfall::Schema"));
+
+    adapter_file.internal_imports.insert(parse_import("super::vertex::Vertex"));
+
+    let adapter_impl = quote! {
+        impl Adapter {
+            pub const SCHEMA_TEXT: &'static str = include_str!("./schema.graphql");
+
+            pub fn schema() -> &'static Schema {
+                SCHEMA.get_or_init(|| Schema::parse(Self::SCHEMA_TEXT).expect("not a valid schema"))
+            }
+
+            pub fn new() -> Self {
+                Self {}
+            }
+        }
+    };
+
+    (static_defn, adapter_defn, adapter_impl)
+}
+
+fn make_basic_adapter_file(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+    adapter_file: &mut RustFile,
+    entrypoint_match_arms: proc_macro2::TokenStream,
+) {
+    let (static_defn, adapter_defn, adapter_impl) = emit_adapter_struct(adapter_file);
+
+    let external_imports = &mut adapter_file.external_imports;
+    external_imports.insert(parse_import("trustfall::provider::AsVertex"));
+    external_imports.insert(parse_import("trustfall::provider::BasicAdapter"));
+    external_imports.insert(parse_import("trustfall::provider::ContextIterator"));
+    external_imports.insert(parse_import("trustfall::provider::ContextOutcomeIterator"));
+    external_imports.insert(parse_import("trustfall::provider::EdgeParameters"));
+    external_imports.insert(parse_import("trustfall::provider::VertexIterator"));
+    external_imports.insert(parse_import("trustfall::provider::resolve_coercion_using_schema"));
+    external_imports.insert(parse_import("trustfall::FieldValue"));
+
+    let property_arms = make_property_match_arms(querying_schema, adapter.clone(), |ident| {
+        quote! { super::properties::#ident(contexts, property_name) }
+    });
+    let edge_arms = make_edge_match_arms(querying_schema, adapter, |ident| {
+        quote! { super::edges::#ident(contexts, edge_name, parameters) }
+    });
+
+    // `BasicAdapter` resolves the `__typename` property on its own,
+    // so unlike the full `Adapter` stub, we don't need to handle it here.
+    let basic_adapter_impl = quote! {
+        impl<'a> BasicAdapter<'a> for Adapter {
+            type Vertex = Vertex;
+
+            fn resolve_starting_vertices(
+                &self,
+                edge_name: &str,
+                parameters: &EdgeParameters,
+            ) -> VertexIterator<'a, Self::Vertex> {
+                match edge_name {
+                    #entrypoint_match_arms
+                    _ => unreachable!("attempted to resolve starting vertices for unexpected edge name: {edge_name}"),
+                }
+            }
+
+            fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &str,
+                property_name: &str,
+            ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+                match type_name {
+                    #property_arms
+                    _ => unreachable!("attempted to read property '{property_name}' on unexpected type: {type_name}"),
+                }
+            }
+
+            fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                type_name: &str,
+                edge_name: &str,
+                parameters: &EdgeParameters,
+            ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+                match type_name {
+                    #edge_arms
+                    _ => unreachable!("attempted to resolve edge '{edge_name}' on unexpected type: {type_name}"),
+                }
+            }
+
+            fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+                &self,
+                contexts: ContextIterator<'a, V>,
+                _type_name: &str,
+                coerce_to_type: &str,
+            ) -> ContextOutcomeIterator<'a, V, bool> {
+                resolve_coercion_using_schema(contexts, Self::schema(), coerce_to_type)
+            }
+        }
+    };
+
+    adapter_file.top_level_items.extend([
+        static_defn,
+        adapter_defn,
+        adapter_impl,
+        basic_adapter_impl,
+    ]);
+}
+
 fn emit_entrypoint_handling(
     entrypoint_match_arms: proc_macro2::TokenStream,
     builtin_imports: &mut BTreeSet<Vec<String>>,
@@ -122,37 +226,9 @@ fn emit_property_handling(
     builtin_imports: &mut BTreeSet<Vec<String>>,
     external_imports: &mut BTreeSet<Vec<String>>,
 ) -> proc_macro2::TokenStream {
-    let query = r#"
-{
-    VertexType {
-        name @output
-
-        property @fold @transform(op: "count") @filter(op: ">", value: ["$zero"])
-    }
-}"#;
-    let variables: BTreeMap<Arc<str>, i64> = btreemap! {
-        "zero".into() => 0,
-    };
-
-    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
-    struct ResultRow {
-        name: String,
-    }
-
-    let mut arms = proc_macro2::TokenStream::new();
-    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
-        .expect("invalid query")
-        .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
-        .collect();
-    rows.sort_unstable();
-    for row in rows {
-        let name = &row.name;
-        let ident =
-            syn::Ident::new(&property_resolver_fn_name(name), proc_macro2::Span::call_site());
-        arms.extend(quote! {
-            #name => super::properties::#ident(contexts, property_name.as_ref(), resolve_info),
-        });
-    }
+    let arms = make_property_match_arms(querying_schema, adapter, |ident| {
+        quote! { super::properties::#ident(contexts, property_name.as_ref(), resolve_info) }
+    });
 
     builtin_imports.insert(parse_import("std::sync::Arc"));
     external_imports.insert(parse_import("trustfall::provider::AsVertex"));
@@ -189,37 +265,9 @@ fn emit_edge_handling(
     builtin_imports: &mut BTreeSet<Vec<String>>,
     external_imports: &mut BTreeSet<Vec<String>>,
 ) -> proc_macro2::TokenStream {
-    let query = r#"
-{
-    VertexType {
-        name @output
-
-        edge @fold @transform(op: "count") @filter(op: ">", value: ["$zero"])
-    }
-}"#;
-    let variables: BTreeMap<Arc<str>, i64> = btreemap! {
-        "zero".into() => 0,
-    };
-
-    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
-    struct ResultRow {
-        name: String,
-    }
-
-    let mut arms = proc_macro2::TokenStream::new();
-    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
-        .expect("invalid query")
-        .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
-        .collect();
-    rows.sort_unstable();
-    for row in rows {
-        let name = &row.name;
-        let ident =
-            syn::Ident::new(&type_edge_resolver_fn_name(name), proc_macro2::Span::call_site());
-        arms.extend(quote! {
-            #name => super::edges::#ident(contexts, edge_name.as_ref(), parameters, resolve_info),
-        });
-    }
+    let arms = make_edge_match_arms(querying_schema, adapter, |ident| {
+        quote! { super::edges::#ident(contexts, edge_name.as_ref(), parameters, resolve_info) }
+    });
 
     builtin_imports.insert(parse_import("std::sync::Arc"));
     external_imports.insert(parse_import("trustfall::provider::AsVertex"));
@@ -270,3 +318,87 @@ fn emit_coercion_handling(
         }
     }
 }
+
+/// Match arms dispatching property resolution to each vertex type's resolver function.
+fn make_property_match_arms(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+    make_call: impl Fn(syn::Ident) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let query = r#"
+{
+    VertexType {
+        name @output
+
+        property @fold @transform(op: "count") @filter(op: ">", value: ["$zero"])
+    }
+}"#;
+    let variables: BTreeMap<Arc<str>, i64> = btreemap! {
+        "zero".into() => 0,
+    };
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct ResultRow {
+        name: String,
+    }
+
+    let mut arms = proc_macro2::TokenStream::new();
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    for row in rows {
+        let name = &row.name;
+        let ident =
+            syn::Ident::new(&property_resolver_fn_name(name), proc_macro2::Span::call_site());
+        let call = make_call(ident);
+        arms.extend(quote! {
+            #name => #call,
+        });
+    }
+
+    arms
+}
+
+/// Match arms dispatching edge resolution to each vertex type's resolver function.
+fn make_edge_match_arms(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+    make_call: impl Fn(syn::Ident) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let query = r#"
+{
+    VertexType {
+        name @output
+
+        edge @fold @transform(op: "count") @filter(op: ">", value: ["$zero"])
+    }
+}"#;
+    let variables: BTreeMap<Arc<str>, i64> = btreemap! {
+        "zero".into() => 0,
+    };
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct ResultRow {
+        name: String,
+    }
+
+    let mut arms = proc_macro2::TokenStream::new();
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    for row in rows {
+        let name = &row.name;
+        let ident =
+            syn::Ident::new(&type_edge_resolver_fn_name(name), proc_macro2::Span::call_site());
+        let call = make_call(ident);
+        arms.extend(quote! {
+            #name => #call,
+        });
+    }
+
+    arms
+}
@@ -9,7 +9,7 @@ use crate::util::upper_case_variant_name;
 use super::util::escaped_rust_name;
 
 use super::{
-    root::RustFile,
+    root::{RustFile, StubgenConfig},
     util::{
         field_value_to_rust_type, parse_import, to_lower_snake_case, trustfall_type_to_rust_type,
         type_edge_resolver_fn_name,
@@ -20,6 +20,7 @@ pub(super) fn make_edges_file(
     querying_schema: &Schema,
     adapter: Arc<SchemaAdapter<'_>>,
     edges_file: &mut RustFile,
+    config: &StubgenConfig,
 ) {
     let query = r#"
 {
@@ -57,12 +58,13 @@ pub(super) fn make_edges_file(
         let mut edges: Vec<(String, Vec<(String, String)>)> = row
             .edge_name
             .into_iter()
-            .zip(row.parameter_name.into_iter().zip(row.parameter_type.into_iter()))
-            .map(|(edge, (param, ty))| (edge, param.into_iter().zip(ty.into_iter()).collect()))
+            .zip(row.parameter_name.into_iter().zip(row.parameter_type))
+            .map(|(edge, (param, ty))| (edge, param.into_iter().zip(ty).collect()))
             .collect();
         edges.sort_unstable();
 
-        let (type_edge_resolver_fn, type_edge_mod) = make_type_edge_resolver(&row.name, edges);
+        let (type_edge_resolver_fn, type_edge_mod) =
+            make_type_edge_resolver(&row.name, edges, config);
         edges_file.top_level_items.push(type_edge_resolver_fn);
         edges_file.top_level_items.push(type_edge_mod);
     }
@@ -71,7 +73,9 @@ pub(super) fn make_edges_file(
     edges_file.external_imports.insert(parse_import("trustfall::provider::ContextIterator"));
     edges_file.external_imports.insert(parse_import("trustfall::provider::ContextOutcomeIterator"));
     edges_file.external_imports.insert(parse_import("trustfall::provider::EdgeParameters"));
-    edges_file.external_imports.insert(parse_import("trustfall::provider::ResolveEdgeInfo"));
+    if !config.basic_adapter {
+        edges_file.external_imports.insert(parse_import("trustfall::provider::ResolveEdgeInfo"));
+    }
     edges_file.external_imports.insert(parse_import("trustfall::provider::VertexIterator"));
 
     edges_file.internal_imports.insert(parse_import("super::vertex::Vertex"));
@@ -80,6 +84,7 @@ pub(super) fn make_edges_file(
 fn make_type_edge_resolver(
     type_name: &str,
     edges: Vec<(String, Vec<(String, String)>)>,
+    config: &StubgenConfig,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let lower_type_name = to_lower_snake_case(type_name);
     let mod_name = syn::Ident::new(
@@ -91,7 +96,7 @@ fn make_type_edge_resolver(
     let mut edge_resolvers = proc_macro2::TokenStream::new();
     for (edge_name, params) in edges {
         let (arm, resolver) =
-            make_edge_resolver_and_call(type_name, &edge_name, &params, &mod_name);
+            make_edge_resolver_and_call(type_name, &edge_name, &params, &mod_name, config);
         arms.extend(arm);
         edge_resolvers.extend(resolver);
     }
@@ -100,12 +105,18 @@ fn make_type_edge_resolver(
     let ident = syn::Ident::new(&type_edge_resolver_fn, proc_macro2::Span::call_site());
     let unreachable_msg =
         format!("attempted to resolve unexpected edge '{{edge_name}}' on type '{type_name}'");
+    // `BasicAdapter` edge resolvers don't receive a `ResolveEdgeInfo` value.
+    let resolve_info_param = if config.basic_adapter {
+        quote! {}
+    } else {
+        quote! { resolve_info: &ResolveEdgeInfo, }
+    };
     let type_edge_resolver = quote! {
         pub(super) fn #ident<'a, V: AsVertex<Vertex> + 'a>(
             contexts: ContextIterator<'a, V>,
             edge_name: &str,
             parameters: &EdgeParameters,
-            resolve_info: &ResolveEdgeInfo,
+            #resolve_info_param
         ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
             match edge_name {
                 #arms
@@ -114,12 +125,23 @@ fn make_type_edge_resolver(
         }
     };
 
-    let type_edge_mod = quote! {
-        mod #mod_name {
+    let mod_imports = if config.basic_adapter {
+        quote! {
+            use trustfall::provider::{
+                resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator, VertexIterator,
+            };
+        }
+    } else {
+        quote! {
             use trustfall::provider::{
                 resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo,
                 VertexIterator,
             };
+        }
+    };
+    let type_edge_mod = quote! {
+        mod #mod_name {
+            #mod_imports
 
             use super::super::vertex::Vertex;
 
@@ -135,6 +157,7 @@ fn make_edge_resolver_and_call(
     edge_name: &str,
     parameters: &[(String, String)],
     mod_name: &proc_macro2::Ident,
+    config: &StubgenConfig,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let FnCall { fn_params, fn_args, fn_arg_prep } = prepare_call_parameters(
         parameters,
@@ -150,11 +173,16 @@ fn make_edge_resolver_and_call(
     let conversion_fn_ident = syn::Ident::new(&conversion_fn_name, proc_macro2::Span::call_site());
     let expect_msg = format!("conversion failed, vertex was not a {type_name}");
     let todo_msg = format!("get neighbors along edge '{edge_name}' for type '{type_name}'");
+    let (resolve_info_arg, resolve_info_param) = if config.basic_adapter {
+        (quote! {}, quote! {})
+    } else {
+        (quote! { resolve_info }, quote! { _resolve_info: &ResolveEdgeInfo, })
+    };
     let resolver = quote! {
         pub(super) fn #resolver_fn_ident<'a, V: AsVertex<Vertex> + 'a>(
             contexts: ContextIterator<'a, V>,
             #fn_params
-            _resolve_info: &ResolveEdgeInfo,
+            #resolve_info_param
         ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
             resolve_neighbors_with(contexts, move |vertex| {
                 let vertex = vertex.#conversion_fn_ident().expect(#expect_msg);
@@ -165,13 +193,13 @@ fn make_edge_resolver_and_call(
 
     let match_arm = if parameters.is_empty() {
         quote! {
-            #edge_name => #mod_name::#resolver_fn_ident(contexts, resolve_info),
+            #edge_name => #mod_name::#resolver_fn_ident(contexts, #resolve_info_arg),
         }
     } else {
         quote! {
             #edge_name => {
                 #fn_arg_prep
-                #mod_name::#resolver_fn_ident(contexts, #fn_args resolve_info)
+                #mod_name::#resolver_fn_ident(contexts, #fn_args #resolve_info_arg)
             }
         }
     };
@@ -10,7 +10,7 @@ use crate::{
 };
 
 use super::{
-    root::RustFile,
+    root::{RustFile, StubgenConfig},
     util::{parse_import, to_lower_snake_case},
 };
 
@@ -19,6 +19,7 @@ pub(super) fn make_entrypoints_file(
     adapter: Arc<SchemaAdapter<'_>>,
     entrypoints_file: &mut RustFile,
     entrypoints_match_arms: &mut proc_macro2::TokenStream,
+    config: &StubgenConfig,
 ) {
     assert!(entrypoints_match_arms.is_empty());
 
@@ -48,16 +49,17 @@ pub(super) fn make_entrypoints_file(
         .collect();
     rows.sort_unstable();
     for row in rows {
-        let parameters: Vec<_> =
-            row.parameter_name.into_iter().zip(row.parameter_type.into_iter()).collect();
+        let parameters: Vec<_> = row.parameter_name.into_iter().zip(row.parameter_type).collect();
 
-        let (match_arm, entrypoint_fn) = make_entrypoint_fn(&row.name, &parameters);
+        let (match_arm, entrypoint_fn) = make_entrypoint_fn(&row.name, &parameters, config);
         entrypoints_file.top_level_items.push(entrypoint_fn);
         entrypoints_match_arms.extend(match_arm);
     }
 
     entrypoints_file.external_imports.insert(parse_import("trustfall::provider::VertexIterator"));
-    entrypoints_file.external_imports.insert(parse_import("trustfall::provider::ResolveInfo"));
+    if !config.basic_adapter {
+        entrypoints_file.external_imports.insert(parse_import("trustfall::provider::ResolveInfo"));
+    }
 
     entrypoints_file.internal_imports.insert(parse_import("super::vertex::Vertex"));
 }
@@ -65,6 +67,7 @@ pub(super) fn make_entrypoints_file(
 fn make_entrypoint_fn(
     entrypoint: &str,
     parameters: &[(String, String)],
+    config: &StubgenConfig,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let FnCall { fn_params, fn_args, fn_arg_prep } = prepare_call_parameters(
         parameters,
@@ -78,15 +81,22 @@ fn make_entrypoint_fn(
     let todo_msg =
         format!("implement resolving starting vertices for entrypoint edge '{entrypoint}'");
 
+    // `BasicAdapter` entrypoints don't receive a `ResolveInfo` value.
+    let (resolve_info_arg, resolve_info_param) = if config.basic_adapter {
+        (quote! {}, quote! {})
+    } else {
+        (quote! { resolve_info }, quote! { _resolve_info: &ResolveInfo, })
+    };
+
     let match_arm = if parameters.is_empty() {
         quote! {
-            #entrypoint => super::entrypoints::#ident(resolve_info),
+            #entrypoint => super::entrypoints::#ident(#resolve_info_arg),
         }
     } else {
         quote! {
             #entrypoint => {
                 #fn_arg_prep
-                super::entrypoints::#ident(#fn_args resolve_info)
+                super::entrypoints::#ident(#fn_args #resolve_info_arg)
             }
         }
     };
@@ -94,7 +104,7 @@ fn make_entrypoint_fn(
     let resolver = quote! {
         pub(super) fn #ident<'a>(
             #fn_params
-            _resolve_info: &ResolveInfo,
+            #resolve_info_param
         ) -> VertexIterator<'a, Vertex> {
             todo!(#todo_msg)
         }
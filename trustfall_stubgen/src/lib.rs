@@ -12,6 +12,22 @@
 //! This crate can also be used as a library, so you can call that function directly from
 //! your own code without going through the CLI.
 //!
+//! By default, the stub implements the full `Adapter` trait. To generate a stub implementing
+//! the simpler `BasicAdapter` trait instead, pass `--basic-adapter` to the CLI
+//! or use [`generate_rust_stub_with_config`] with [`StubgenConfig::basic_adapter`].
+//!
+//! When the schema changes, rerun with `--update` (or [`StubgenConfig::update_existing`])
+//! to add the schema's new types, properties, and edges to a previously-generated stub
+//! without overwriting the code implemented since then.
+//!
+//! Each vertex type in the schema gets a variant of the generated `Vertex` enum, with a `()`
+//! placeholder payload. To use an existing Rust type as the payload instead, pass
+//! `--vertex-type SchemaType=path::to::RustType` (or use [`StubgenConfig::vertex_type`]).
+//!
+//! The generated tests check the adapter's invariants. Pass `--test-scaffolding`
+//! (or use [`StubgenConfig::test_scaffolding`]) to also generate tests checking that
+//! the schema is valid, together with a sample query for each of the schema's entrypoints.
+//!
 //! The generated Trustfall adapter stub has the following structure:
 //!
 //! | file name               | purpose                                                |
@@ -23,6 +39,16 @@
 //! | adapter/entrypoints.rs  | contains the entry points where all queries must start |
 //! | adapter/properties.rs   | contains the property implementations                  |
 //! | adapter/edges.rs        | contains the edge implementations                      |
+//! | adapter/tests.rs        | contains test code                                     |
+//!
+//! To generate an adapter stub for the `trustfall` Python package instead, pass
+//! `--language python` (or use [`generate_python_stub`]). It has the same structure,
+//! with `.py` files in place of `.rs` ones and `adapter/__init__.py` in place of `adapter/mod.rs`,
+//! but without generated tests.
+//!
+//! Similarly, pass `--language typescript` (or use [`generate_typescript_stub`]) to generate
+//! a TypeScript adapter stub for the `trustfall_wasm` package, with a discriminated union
+//! of vertex types and `.ts` files in place of `.rs` ones.
 //!
 //! See an example of
 //! [a generated adapter stub](https://github.com/obi1kenobi/trustfall/tree/main/trustfall_stubgen/test_data/expected_outputs/hackernews/adapter)
@@ -34,11 +60,16 @@
 mod adapter_creator;
 mod edges_creator;
 mod entrypoints_creator;
+mod merge;
 mod properties_creator;
+mod python_creator;
 mod root;
+mod typescript_creator;
 mod util;
 
 #[cfg(test)]
 mod tests;
 
-pub use root::generate_rust_stub;
+pub use python_creator::generate_python_stub;
+pub use root::{generate_rust_stub, generate_rust_stub_with_config, StubgenConfig};
+pub use typescript_creator::generate_typescript_stub;
@@ -5,7 +5,7 @@
 use std::path::PathBuf;
 
 use anyhow::Context;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 /// Generate a Trustfall adapter stub implementation for a given schema.
 #[derive(Parser, Debug)]
@@ -23,9 +23,64 @@ struct Cli {
     /// which will be created if it does not exist.
     ///
     /// If any of the generated stub files have the same name as existing files,
-    /// the existing files will be overwritten.
+    /// the existing files will be overwritten, unless `--update` is set.
     #[arg(short, long, value_name = "DIR")]
     target: PathBuf,
+
+    /// Generate a stub implementing the simpler `BasicAdapter` trait
+    /// instead of the full `Adapter` trait.
+    ///
+    /// `BasicAdapter` resolvers do not receive query hints,
+    /// which makes them easier to implement but harder to optimize.
+    #[arg(long)]
+    basic_adapter: bool,
+
+    /// Update a stub previously generated in the target directory, instead of overwriting it.
+    ///
+    /// New types, properties, and edges are added to the existing stub files,
+    /// while already-implemented code is left intact. Generated code that could not be merged
+    /// is listed in the "adapter/stubgen_conflicts.txt" file in the target directory.
+    #[arg(long)]
+    update: bool,
+
+    /// Generate a more complete test suite for the adapter.
+    ///
+    /// In addition to checking the adapter's invariants, the generated tests check
+    /// that the schema is valid, and that a sample query starting at each entrypoint
+    /// is valid for the schema.
+    #[arg(long)]
+    test_scaffolding: bool,
+
+    /// Use an existing Rust type as the payload of the vertex enum variant for a schema type,
+    /// in the form `SchemaType=path::to::RustType`. May be repeated.
+    ///
+    /// Schema types without a configured Rust type get a `()` placeholder payload.
+    #[arg(long, value_name = "TYPE=PATH", value_parser = parse_vertex_type)]
+    vertex_type: Vec<(String, String)>,
+
+    /// The programming language in which to generate the adapter stub.
+    ///
+    /// Python stubs implement the `Adapter` class of the `trustfall` Python package,
+    /// and TypeScript stubs implement the `Adapter` interface of the `trustfall_wasm` package.
+    /// Neither supports any of the options that configure Rust stubs.
+    #[arg(long, value_enum, default_value_t = Language::Rust)]
+    language: Language,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Language {
+    Rust,
+    Python,
+    Typescript,
+}
+
+fn parse_vertex_type(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((type_name, rust_path)) if !type_name.is_empty() && !rust_path.is_empty() => {
+            Ok((type_name.trim().to_string(), rust_path.trim().to_string()))
+        }
+        _ => Err(format!("expected `SchemaType=path::to::RustType`, but got `{value}`")),
+    }
 }
 
 fn main() -> Result<(), anyhow::Error> {
@@ -56,7 +111,43 @@ fn main() -> Result<(), anyhow::Error> {
     let target = &cli.target;
     std::fs::create_dir_all(target).context("failed to create target directory")?;
 
-    trustfall_stubgen::generate_rust_stub(&schema_text, target)?;
+    if cli.language != Language::Rust
+        && (cli.basic_adapter || cli.update || cli.test_scaffolding || !cli.vertex_type.is_empty())
+    {
+        anyhow::bail!(
+            "--basic-adapter, --update, --test-scaffolding, and --vertex-type \
+            are only supported for Rust stubs"
+        );
+    }
+
+    match cli.language {
+        Language::Rust => {}
+        Language::Python => {
+            trustfall_stubgen::generate_python_stub(&schema_text, target)?;
+
+            println!("Successfully created stub! Don't forget to:");
+            println!(" - add `trustfall` to your Python dependencies");
+            println!(" - import `Adapter` and `SCHEMA` from the generated `adapter` package");
+            return Ok(());
+        }
+        Language::Typescript => {
+            trustfall_stubgen::generate_typescript_stub(&schema_text, target)?;
+
+            println!("Successfully created stub! Don't forget to:");
+            println!(" - add `trustfall_wasm` to your dependencies");
+            println!(" - import `Adapter` and `SCHEMA_TEXT` from the generated `adapter` module");
+            return Ok(());
+        }
+    }
+
+    let mut config = trustfall_stubgen::StubgenConfig::new()
+        .basic_adapter(cli.basic_adapter)
+        .update_existing(cli.update)
+        .test_scaffolding(cli.test_scaffolding);
+    for (type_name, rust_path) in cli.vertex_type {
+        config = config.vertex_type(type_name, rust_path);
+    }
+    trustfall_stubgen::generate_rust_stub_with_config(&schema_text, target, &config)?;
 
     println!("Successfully created stub! Don't forget to:");
     println!(" - add `trustfall` to your dependencies");
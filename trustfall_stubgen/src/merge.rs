@@ -0,0 +1,523 @@
+//! Merging a freshly-generated stub file into a previously-generated version of that file.
+//!
+//! The previously-generated file may contain user edits, such as implemented resolvers.
+//! Instead of re-printing the merged syntax tree (which would lose comments and formatting),
+//! the newly-generated items, match arms, and enum variants are spliced into the text
+//! of the existing file. Everything already present in the existing file is left untouched.
+use anyhow::Context;
+use proc_macro2::LineColumn;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+/// The result of merging a generated file into its existing version.
+#[derive(Debug)]
+pub(crate) struct MergeOutcome {
+    /// The contents of the existing file, with the newly-generated code added.
+    pub(crate) merged: String,
+
+    /// Descriptions of generated code that could not be merged automatically,
+    /// and needs to be reconciled by hand.
+    pub(crate) conflicts: Vec<String>,
+}
+
+/// Add the items, match arms, and enum variants present only in the `generated` file
+/// to the `existing` file's contents.
+///
+/// Functions present in both files keep their existing implementation.
+/// If a function's signature has changed, the existing function is kept as-is
+/// and the difference is reported as a conflict.
+pub(crate) fn merge_rust_file(
+    file_name: &str,
+    existing: &str,
+    generated: &str,
+) -> anyhow::Result<MergeOutcome> {
+    let existing_file = syn::parse_file(existing)
+        .with_context(|| format!("failed to parse existing file {file_name}"))?;
+    let generated_file = syn::parse_file(generated).expect("generated code was not valid Rust");
+
+    let mut merger = Merger {
+        existing: SourceText::new(existing),
+        generated: SourceText::new(generated),
+        insertions: vec![],
+        conflicts: vec![],
+    };
+    merger.merge_items(file_name, &existing_file.items, &generated_file.items, None);
+
+    Ok(merger.finish())
+}
+
+struct Merger<'a> {
+    existing: SourceText<'a>,
+    generated: SourceText<'a>,
+
+    /// Text to insert into the existing file, keyed by byte offset.
+    insertions: Vec<(usize, String)>,
+    conflicts: Vec<String>,
+}
+
+impl Merger<'_> {
+    fn finish(mut self) -> MergeOutcome {
+        // Insert from the back, so that earlier offsets remain valid.
+        // The sort is stable, so text inserted at the same offset keeps its relative order.
+        self.insertions.sort_by_key(|(offset, _)| *offset);
+        let mut merged = self.existing.text.to_string();
+        for (offset, text) in self.insertions.iter().rev() {
+            merged.insert_str(*offset, text);
+        }
+        MergeOutcome { merged, conflicts: self.conflicts }
+    }
+
+    /// Merge the generated items into the existing ones.
+    ///
+    /// New items are added before `closing_brace` if it's set, or at the end of the file if not.
+    fn merge_items(
+        &mut self,
+        context: &str,
+        existing: &[syn::Item],
+        generated: &[syn::Item],
+        closing_brace: Option<LineColumn>,
+    ) {
+        for generated_item in generated {
+            let Some(key) = item_key(generated_item) else {
+                // Items like imports aren't merged.
+                continue;
+            };
+            let existing_item = existing.iter().find(|item| item_key(item).as_ref() == Some(&key));
+            let context = format!("{context}: {key}");
+
+            match (existing_item, generated_item) {
+                (None, _) => {
+                    let snippet = self.generated.snippet(generated_item.span());
+                    match closing_brace {
+                        Some(brace) => self.insert_before_closing_brace(brace, &snippet, true),
+                        None => {
+                            let text = format!("\n{}\n", snippet.reindent(""));
+                            self.insertions.push((self.existing.text.len(), text));
+                        }
+                    }
+                }
+                (Some(syn::Item::Fn(existing_fn)), syn::Item::Fn(generated_fn)) => {
+                    self.merge_fn(
+                        &context,
+                        &existing_fn.sig,
+                        &existing_fn.block,
+                        &generated_fn.sig,
+                        &generated_fn.block,
+                    );
+                }
+                (Some(syn::Item::Mod(existing_mod)), syn::Item::Mod(generated_mod)) => {
+                    if let (Some((brace, existing_items)), Some((_, generated_items))) =
+                        (&existing_mod.content, &generated_mod.content)
+                    {
+                        self.merge_items(
+                            &context,
+                            existing_items,
+                            generated_items,
+                            Some(brace.span.close().start()),
+                        );
+                    }
+                }
+                (Some(syn::Item::Enum(existing_enum)), syn::Item::Enum(generated_enum)) => {
+                    self.merge_enum(existing_enum, generated_enum);
+                }
+                (Some(syn::Item::Impl(existing_impl)), syn::Item::Impl(generated_impl)) => {
+                    self.merge_impl(&context, existing_impl, generated_impl);
+                }
+                // All other items are kept exactly as they are in the existing file.
+                _ => {}
+            }
+        }
+    }
+
+    fn merge_impl(&mut self, context: &str, existing: &syn::ItemImpl, generated: &syn::ItemImpl) {
+        for generated_item in &generated.items {
+            let syn::ImplItem::Fn(generated_fn) = generated_item else {
+                continue;
+            };
+            let name = &generated_fn.sig.ident;
+            let existing_fn = existing.items.iter().find_map(|item| match item {
+                syn::ImplItem::Fn(existing_fn) if &existing_fn.sig.ident == name => {
+                    Some(existing_fn)
+                }
+                _ => None,
+            });
+
+            match existing_fn {
+                None => {
+                    let snippet = self.generated.snippet(generated_fn.span());
+                    self.insert_before_closing_brace(
+                        existing.brace_token.span.close().start(),
+                        &snippet,
+                        true,
+                    );
+                }
+                Some(existing_fn) => self.merge_fn(
+                    &format!("{context}: fn {name}"),
+                    &existing_fn.sig,
+                    &existing_fn.block,
+                    &generated_fn.sig,
+                    &generated_fn.block,
+                ),
+            }
+        }
+    }
+
+    fn merge_fn(
+        &mut self,
+        context: &str,
+        existing_sig: &syn::Signature,
+        existing_block: &syn::Block,
+        generated_sig: &syn::Signature,
+        generated_block: &syn::Block,
+    ) {
+        let existing_sig_text = existing_sig.to_token_stream().to_string();
+        let generated_sig_text = generated_sig.to_token_stream().to_string();
+        if existing_sig_text != generated_sig_text {
+            self.conflicts.push(format!(
+                "{context}: the signature has changed, and the existing function was kept. \
+                The newly-generated signature is:\n    {}",
+                self.generated.snippet(generated_sig.span()).text,
+            ));
+            return;
+        }
+
+        let (Some(existing_match), Some(generated_match)) =
+            (top_level_match(existing_block), top_level_match(generated_block))
+        else {
+            return;
+        };
+
+        let existing_patterns: Vec<String> =
+            existing_match.arms.iter().map(|arm| arm.pat.to_token_stream().to_string()).collect();
+        let wildcard_arm =
+            existing_match.arms.iter().find(|arm| matches!(arm.pat, syn::Pat::Wild(_)));
+
+        for generated_arm in &generated_match.arms {
+            if matches!(generated_arm.pat, syn::Pat::Wild(_)) {
+                continue;
+            }
+            let pattern = generated_arm.pat.to_token_stream().to_string();
+            if existing_patterns.contains(&pattern) {
+                continue;
+            }
+
+            let snippet = self.generated.snippet(generated_arm.span());
+            match wildcard_arm {
+                Some(wildcard_arm) => self.insert_before(wildcard_arm.span().start(), &snippet),
+                None => self.insert_before_closing_brace(
+                    existing_match.brace_token.span.close().start(),
+                    &snippet,
+                    false,
+                ),
+            }
+        }
+    }
+
+    fn merge_enum(&mut self, existing: &syn::ItemEnum, generated: &syn::ItemEnum) {
+        let closing_brace = existing.brace_token.span.close().start();
+
+        let mut needs_comma = !existing.variants.empty_or_trailing();
+        for generated_variant in &generated.variants {
+            if existing.variants.iter().any(|variant| variant.ident == generated_variant.ident) {
+                continue;
+            }
+
+            if needs_comma {
+                let last = existing.variants.last().expect("no variants");
+                let offset = self.existing.offset(last.span().end());
+                self.insertions.push((offset, ",".to_string()));
+                needs_comma = false;
+            }
+
+            let snippet = self.generated.snippet(generated_variant.span());
+            let text = format!("{},", snippet.text);
+            let snippet = Snippet { text: &text, column: snippet.column };
+            self.insert_before_closing_brace(closing_brace, &snippet, false);
+        }
+    }
+
+    /// Insert the text on its own line(s), right before the line containing `position`
+    /// and matching that line's indentation.
+    fn insert_before(&mut self, position: LineColumn, snippet: &Snippet<'_>) {
+        let line_start = self.existing.line_start(position.line);
+        let indent = self.existing.indentation(position.line);
+        let text = format!("{}\n", snippet.reindent(&indent));
+        self.insertions.push((line_start, text));
+    }
+
+    /// Insert the text as the last element of the block closed by the brace at `closing_brace`,
+    /// optionally separated from the preceding elements by a blank line.
+    fn insert_before_closing_brace(
+        &mut self,
+        closing_brace: LineColumn,
+        snippet: &Snippet<'_>,
+        blank: bool,
+    ) {
+        let line_start = self.existing.line_start(closing_brace.line);
+        let brace_offset = self.existing.offset(closing_brace);
+        let outer_indent = self.existing.indentation(closing_brace.line);
+        let indent = format!("{outer_indent}    ");
+        let separator = if blank { "\n" } else { "" };
+
+        if self.existing.text[line_start..brace_offset].trim().is_empty() {
+            // The closing brace is on its own line.
+            let text = format!("{separator}{}\n", snippet.reindent(&indent));
+            self.insertions.push((line_start, text));
+        } else {
+            // The block is on a single line, like `pub struct Adapter {}`.
+            let text = format!("\n{}\n{outer_indent}", snippet.reindent(&indent));
+            self.insertions.push((brace_offset, text));
+        }
+    }
+}
+
+/// The key by which items in the generated and existing files are matched up,
+/// or `None` for items that aren't merged.
+fn item_key(item: &syn::Item) -> Option<String> {
+    match item {
+        syn::Item::Fn(item) => Some(format!("fn {}", item.sig.ident)),
+        syn::Item::Mod(item) => Some(format!("mod {}", item.ident)),
+        syn::Item::Enum(item) => Some(format!("enum {}", item.ident)),
+        syn::Item::Struct(item) => Some(format!("struct {}", item.ident)),
+        syn::Item::Static(item) => Some(format!("static {}", item.ident)),
+        syn::Item::Const(item) => Some(format!("const {}", item.ident)),
+        syn::Item::Impl(item) => {
+            let self_ty = item.self_ty.to_token_stream();
+            Some(match &item.trait_ {
+                Some((_, path, _)) => format!("impl {} for {self_ty}", path.to_token_stream()),
+                None => format!("impl {self_ty}"),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The `match` expression that is a statement directly inside the given block, if any.
+fn top_level_match(block: &syn::Block) -> Option<&syn::ExprMatch> {
+    block.stmts.iter().find_map(|stmt| match stmt {
+        syn::Stmt::Expr(syn::Expr::Match(expr_match), _) => Some(expr_match),
+        _ => None,
+    })
+}
+
+/// A snippet of generated code, together with the column at which it started.
+struct Snippet<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+impl Snippet<'_> {
+    /// The snippet's text, reindented to start at the given indentation.
+    ///
+    /// The snippet's first line starts at a token, so it has no leading whitespace.
+    /// The remaining lines are indented relative to the column where the snippet started.
+    fn reindent(&self, indent: &str) -> String {
+        let mut lines = self.text.lines();
+        let mut result = format!("{indent}{}", lines.next().unwrap_or_default());
+        for line in lines {
+            result.push('\n');
+            if !line.trim().is_empty() {
+                let leading_whitespace = line.len() - line.trim_start().len();
+                result.push_str(indent);
+                result.push_str(&line[leading_whitespace.min(self.column)..]);
+            }
+        }
+        result
+    }
+}
+
+struct SourceText<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceText<'a> {
+    fn new(text: &'a str) -> Self {
+        let line_starts =
+            std::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        Self { text, line_starts }
+    }
+
+    /// The byte offset of the start of the given 1-based line.
+    fn line_start(&self, line: usize) -> usize {
+        self.line_starts[line - 1]
+    }
+
+    /// The byte offset of the given position, whose column is counted in characters.
+    fn offset(&self, position: LineColumn) -> usize {
+        let line_start = self.line_start(position.line);
+        self.text[line_start..]
+            .char_indices()
+            .nth(position.column)
+            .map(|(i, _)| line_start + i)
+            .unwrap_or(self.text.len())
+    }
+
+    /// The leading whitespace of the given 1-based line.
+    fn indentation(&self, line: usize) -> String {
+        let line_text = &self.text[self.line_start(line)..];
+        line_text.chars().take_while(|c| *c == ' ' || *c == '\t').collect()
+    }
+
+    fn snippet(&self, span: proc_macro2::Span) -> Snippet<'a> {
+        let text = &self.text[self.offset(span.start())..self.offset(span.end())];
+        Snippet { text, column: span.start().column }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_rust_file;
+
+    #[test]
+    fn implemented_code_is_preserved() {
+        let existing = r#"use trustfall::FieldValue;
+
+pub(super) fn resolve_user_property(property_name: &str) -> FieldValue {
+    match property_name {
+        // Users always have a name.
+        "name" => "implemented".into(),
+        _ => unreachable!("unexpected property '{property_name}'"),
+    }
+}
+
+#[derive(Debug)]
+pub enum Vertex {
+    User(std::rc::Rc<String>)
+}
+"#;
+        let generated = r#"use trustfall::FieldValue;
+
+pub(super) fn resolve_user_property(property_name: &str) -> FieldValue {
+    match property_name {
+        "email" => todo!("implement property 'email'"),
+        "name" => todo!("implement property 'name'"),
+        _ => unreachable!("unexpected property '{property_name}'"),
+    }
+}
+
+pub(super) fn resolve_story_property(property_name: &str) -> FieldValue {
+    match property_name {
+        "title" => todo!("implement property 'title'"),
+        _ => unreachable!("unexpected property '{property_name}'"),
+    }
+}
+
+#[derive(Debug)]
+pub enum Vertex {
+    Story(()),
+    User(()),
+}
+"#;
+        let expected = r#"use trustfall::FieldValue;
+
+pub(super) fn resolve_user_property(property_name: &str) -> FieldValue {
+    match property_name {
+        // Users always have a name.
+        "name" => "implemented".into(),
+        "email" => todo!("implement property 'email'"),
+        _ => unreachable!("unexpected property '{property_name}'"),
+    }
+}
+
+#[derive(Debug)]
+pub enum Vertex {
+    User(std::rc::Rc<String>),
+    Story(()),
+}
+
+pub(super) fn resolve_story_property(property_name: &str) -> FieldValue {
+    match property_name {
+        "title" => todo!("implement property 'title'"),
+        _ => unreachable!("unexpected property '{property_name}'"),
+    }
+}
+"#;
+
+        let outcome = merge_rust_file("properties.rs", existing, generated).expect("merge failed");
+        similar_asserts::assert_eq!(expected, outcome.merged);
+        assert!(outcome.conflicts.is_empty(), "{:?}", outcome.conflicts);
+    }
+
+    #[test]
+    fn nested_items_are_merged() {
+        let existing = r#"mod comment {
+    pub(super) fn by_user() -> i64 {
+        1
+    }
+}
+
+impl Adapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+"#;
+        let generated = r#"mod comment {
+    pub(super) fn by_user() -> i64 {
+        todo!()
+    }
+
+    pub(super) fn link() -> i64 {
+        todo!()
+    }
+}
+
+impl Adapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn schema() -> &'static str {
+        todo!()
+    }
+}
+"#;
+        let expected = r#"mod comment {
+    pub(super) fn by_user() -> i64 {
+        1
+    }
+
+    pub(super) fn link() -> i64 {
+        todo!()
+    }
+}
+
+impl Adapter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn schema() -> &'static str {
+        todo!()
+    }
+}
+"#;
+
+        let outcome = merge_rust_file("edges.rs", existing, generated).expect("merge failed");
+        similar_asserts::assert_eq!(expected, outcome.merged);
+        assert!(outcome.conflicts.is_empty(), "{:?}", outcome.conflicts);
+    }
+
+    #[test]
+    fn changed_signatures_are_conflicts() {
+        let existing = r#"pub(super) fn item(id: i64) -> i64 {
+    id
+}
+"#;
+        let generated = r#"pub(super) fn item(id: i64, max: Option<i64>) -> i64 {
+    todo!()
+}
+"#;
+
+        let outcome = merge_rust_file("entrypoints.rs", existing, generated).expect("merge failed");
+        assert_eq!(existing, outcome.merged);
+        assert_eq!(
+            vec!["entrypoints.rs: fn item: the signature has changed, and the existing function \
+                was kept. The newly-generated signature is:\n    \
+                fn item(id: i64, max: Option<i64>) -> i64"
+                .to_string()],
+            outcome.conflicts,
+        );
+    }
+}
@@ -5,7 +5,7 @@ use quote::quote;
 use trustfall::{Schema, SchemaAdapter, TryIntoStruct};
 
 use super::{
-    root::RustFile,
+    root::{RustFile, StubgenConfig},
     util::{parse_import, property_resolver_fn_name},
 };
 
@@ -13,6 +13,7 @@ pub(super) fn make_properties_file(
     querying_schema: &Schema,
     adapter: Arc<SchemaAdapter<'_>>,
     properties_file: &mut RustFile,
+    config: &StubgenConfig,
 ) {
     let query = r#"
 {
@@ -47,7 +48,7 @@ pub(super) fn make_properties_file(
         .collect();
     rows.sort_unstable();
     for row in rows {
-        let resolver = make_resolver_fn(&row.name, &row.properties);
+        let resolver = make_resolver_fn(&row.name, &row.properties, config);
         properties_file.top_level_items.push(resolver);
     }
 
@@ -56,13 +57,19 @@ pub(super) fn make_properties_file(
     properties_file
         .external_imports
         .insert(parse_import("trustfall::provider::ContextOutcomeIterator"));
-    properties_file.external_imports.insert(parse_import("trustfall::provider::ResolveInfo"));
+    if !config.basic_adapter {
+        properties_file.external_imports.insert(parse_import("trustfall::provider::ResolveInfo"));
+    }
     properties_file.external_imports.insert(parse_import("trustfall::FieldValue"));
 
     properties_file.internal_imports.insert(parse_import("super::vertex::Vertex"));
 }
 
-fn make_resolver_fn(type_name: &str, properties: &[String]) -> proc_macro2::TokenStream {
+fn make_resolver_fn(
+    type_name: &str,
+    properties: &[String],
+    config: &StubgenConfig,
+) -> proc_macro2::TokenStream {
     let resolver_name = property_resolver_fn_name(type_name);
     let ident = syn::Ident::new(&resolver_name, proc_macro2::Span::call_site());
     let mut arms: proc_macro2::TokenStream = proc_macro2::TokenStream::new();
@@ -76,11 +83,17 @@ fn make_resolver_fn(type_name: &str, properties: &[String]) -> proc_macro2::Toke
 
     let unreachable_msg =
         format!("attempted to read unexpected property '{{property_name}}' on type '{type_name}'");
+    // `BasicAdapter` property resolvers don't receive a `ResolveInfo` value.
+    let resolve_info_param = if config.basic_adapter {
+        quote! {}
+    } else {
+        quote! { _resolve_info: &ResolveInfo, }
+    };
     quote! {
         pub(super) fn #ident<'a, V: AsVertex<Vertex> + 'a>(
             contexts: ContextIterator<'a, V>,
             property_name: &str,
-            _resolve_info: &ResolveInfo,
+            #resolve_info_param
         ) -> ContextOutcomeIterator<'a, V, FieldValue> {
             match property_name {
                 #arms
@@ -0,0 +1,544 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use trustfall::{Schema, SchemaAdapter, TryIntoStruct};
+
+use crate::{
+    root::{ensure_no_field_name_conflicts_on_vertex_type, ensure_no_vertex_name_conflicts},
+    util::to_lower_snake_case,
+};
+
+/// Given a schema, make a Python adapter stub for it in the given directory.
+///
+/// The stub implements the `Adapter` class of the `trustfall` Python package,
+/// dispatching each resolver call to a per-type function based on tables keyed
+/// by type and field name.
+///
+/// Generated code structure:
+/// - adapter/__init__.py        exports the adapter and vertex types
+/// - adapter/schema.graphql     contains the schema for the adapter
+/// - adapter/adapter_impl.py    contains the adapter implementation
+/// - adapter/vertex.py          contains the vertex type definitions
+/// - adapter/entrypoints.py     contains the entry points where all queries must start
+/// - adapter/properties.py      contains the property implementations
+/// - adapter/edges.py           contains the edge implementations
+///
+/// # Example
+/// ```no_run
+/// # use std::path::Path;
+/// #
+/// # use trustfall_stubgen::generate_python_stub;
+/// #
+/// # fn main() {
+/// let schema_text = std::fs::read_to_string("./schema.graphql").expect("failed to read schema");
+/// generate_python_stub(&schema_text, Path::new("package/with/generated/stubs"))
+///     .expect("stub generation failed");
+/// # }
+/// ```
+pub fn generate_python_stub(schema: &str, target: &Path) -> anyhow::Result<()> {
+    let target_schema = Schema::parse(schema)?;
+
+    let querying_schema =
+        Schema::parse(SchemaAdapter::schema_text()).expect("schema querying schema was not valid");
+    let schema_adapter = Arc::new(SchemaAdapter::new(&target_schema));
+
+    ensure_no_vertex_name_conflicts(&querying_schema, schema_adapter.clone());
+    ensure_no_field_name_conflicts_on_vertex_type(&querying_schema, schema_adapter.clone());
+
+    let vertex_types = get_vertex_types(&querying_schema, schema_adapter.clone());
+    let entrypoints = get_entrypoints(&querying_schema, schema_adapter);
+
+    let files = [
+        ("__init__.py", make_init_file()),
+        ("schema.graphql", schema.to_string()),
+        ("adapter_impl.py", make_adapter_file()),
+        ("vertex.py", make_vertex_file(&target_schema, &vertex_types)),
+        ("entrypoints.py", make_entrypoints_file(&entrypoints)),
+        ("properties.py", make_properties_file(&vertex_types)),
+        ("edges.py", make_edges_file(&vertex_types)),
+    ];
+
+    let mut path_buf = target.to_path_buf();
+    path_buf.push("adapter");
+    std::fs::create_dir_all(&path_buf)?;
+    for (file_name, contents) in files {
+        path_buf.push(file_name);
+        std::fs::write(path_buf.as_path(), contents)?;
+        path_buf.pop();
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+struct VertexTypeRow {
+    name: String,
+    is_interface: bool,
+    property_name: Vec<String>,
+    property_type: Vec<String>,
+    edge_name: Vec<String>,
+    edge_target: Vec<String>,
+}
+
+fn get_vertex_types(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+) -> Vec<VertexTypeRow> {
+    let query = r#"
+{
+    VertexType {
+        name @output
+        is_interface @output
+
+        property_: property @fold {
+            name @output
+            type @output
+        }
+        edge_: edge @fold {
+            name @output
+
+            target {
+                target: name @output
+            }
+        }
+    }
+}"#;
+    let variables: BTreeMap<String, String> = Default::default();
+
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<VertexTypeRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    rows
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+struct EntrypointRow {
+    name: String,
+    parameter_name: Vec<String>,
+    parameter_type: Vec<String>,
+}
+
+fn get_entrypoints(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+) -> Vec<EntrypointRow> {
+    let query = r#"
+{
+    Entrypoint {
+        name @output
+
+        parameter_: parameter @fold {
+            name @output
+            type @output
+        }
+    }
+}"#;
+    let variables: BTreeMap<String, String> = Default::default();
+
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<EntrypointRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    rows
+}
+
+fn escaped_python_name(name: String) -> String {
+    // https://docs.python.org/3/reference/lexical_analysis.html#keywords
+    match name.as_str() {
+        "False" | "None" | "True" | "and" | "as" | "assert" | "async" | "await" | "break"
+        | "class" | "continue" | "def" | "del" | "elif" | "else" | "except" | "finally" | "for"
+        | "from" | "global" | "if" | "import" | "in" | "is" | "lambda" | "nonlocal" | "not"
+        | "or" | "pass" | "raise" | "return" | "try" | "while" | "with" | "yield" => name + "_",
+        _ => name,
+    }
+}
+
+/// The name of the Python class for the given vertex type.
+fn class_name(type_name: &str) -> String {
+    escaped_python_name(type_name.to_string())
+}
+
+/// The name of the Python function resolving the given field of the given vertex type.
+fn field_fn_name(type_name: &str, field_name: &str) -> String {
+    escaped_python_name(format!(
+        "{}_{}",
+        to_lower_snake_case(type_name),
+        to_lower_snake_case(field_name)
+    ))
+}
+
+/// The Python type hint for values of the given Trustfall type.
+fn python_type(trustfall_type: &str) -> String {
+    let (inner, nullable) = match trustfall_type.strip_suffix('!') {
+        Some(inner) => (inner, false),
+        None => (trustfall_type, true),
+    };
+
+    let ty = if let Some(partial) = inner.strip_prefix('[') {
+        let element = partial.strip_suffix(']').unwrap_or_else(|| {
+            panic!("invalid Trustfall type started with `[` without matching `]`: {trustfall_type}")
+        });
+        format!("List[{}]", python_type(element))
+    } else {
+        match inner {
+            "Int" => "int",
+            "Float" => "float",
+            "Boolean" => "bool",
+            "String" | "ID" => "str",
+            _ => "FieldValue",
+        }
+        .to_string()
+    };
+
+    if nullable {
+        format!("Optional[{ty}]")
+    } else {
+        ty
+    }
+}
+
+/// The parameter type hint for vertices of the given type: interfaces can be any vertex.
+fn vertex_param_type(vertex_type: &VertexTypeRow) -> String {
+    if vertex_type.is_interface {
+        "Vertex".to_string()
+    } else {
+        class_name(&vertex_type.name)
+    }
+}
+
+fn make_init_file() -> String {
+    "\
+from .adapter_impl import SCHEMA, Adapter
+from .vertex import Vertex
+
+__all__ = [\"SCHEMA\", \"Adapter\", \"Vertex\"]
+"
+    .to_string()
+}
+
+fn make_adapter_file() -> String {
+    r#"from pathlib import Path
+from typing import Any, Dict, Iterable, Iterator, Mapping, Tuple, TypeVar
+
+from trustfall import Adapter as BaseAdapter
+from trustfall import Context, FieldValue, Schema
+
+from .edges import EDGES
+from .entrypoints import ENTRYPOINTS
+from .properties import PROPERTIES
+from .vertex import SUBTYPES, Vertex
+
+SCHEMA_TEXT = (Path(__file__).parent / "schema.graphql").read_text()
+SCHEMA = Schema(SCHEMA_TEXT)
+
+T = TypeVar("T")
+
+
+def _lookup(table: Dict[str, Dict[str, T]], type_name: str, field_name: str, kind: str) -> T:
+    resolver = table.get(type_name, {}).get(field_name)
+    if resolver is None:
+        raise NotImplementedError(
+            f"attempted to resolve {kind} '{field_name}' on unexpected type: {type_name}"
+        )
+    return resolver
+
+
+class Adapter(BaseAdapter[Vertex]):
+    def resolve_starting_vertices(
+        self,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Vertex]:
+        resolver = ENTRYPOINTS.get(edge_name)
+        if resolver is None:
+            raise NotImplementedError(
+                f"attempted to resolve starting vertices for unexpected edge name: {edge_name}"
+            )
+        return resolver(parameters)
+
+    def resolve_property(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        property_name: str,
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], FieldValue]]:
+        if property_name == "__typename":
+            for context in contexts:
+                vertex = context.active_vertex
+                yield (context, None if vertex is None else vertex.TYPENAME)
+            return
+
+        resolver = _lookup(PROPERTIES, type_name, property_name, "property")
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, None if vertex is None else resolver(vertex))
+
+    def resolve_neighbors(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], Iterable[Vertex]]]:
+        resolver = _lookup(EDGES, type_name, edge_name, "edge")
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, [] if vertex is None else resolver(vertex, parameters))
+
+    def resolve_coercion(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        coerce_to_type: str,
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], bool]]:
+        subtypes = SUBTYPES[coerce_to_type]
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, vertex is not None and vertex.TYPENAME in subtypes)
+"#
+    .to_string()
+}
+
+fn make_vertex_file(schema: &Schema, vertex_types: &[VertexTypeRow]) -> String {
+    let mut file = String::from(
+        "\
+from dataclasses import dataclass
+from typing import ClassVar, Dict, FrozenSet, Union
+",
+    );
+
+    // Like the Rust stub's `Vertex` enum, every vertex type gets its own class,
+    // interfaces included, since an interface need not have any implementers.
+    let type_names: Vec<&str> =
+        vertex_types.iter().map(|vertex_type| vertex_type.name.as_str()).collect();
+
+    for type_name in &type_names {
+        write!(
+            file,
+            "
+
+@dataclass
+class {}:
+    \"\"\"A vertex of type `{type_name}`.
+
+    Add the fields needed to resolve its properties and edges.
+    \"\"\"
+
+    TYPENAME: ClassVar[str] = \"{type_name}\"
+",
+            class_name(type_name)
+        )
+        .expect("failed to write");
+    }
+
+    let variants: Vec<_> = type_names.iter().map(|name| class_name(name)).collect();
+    write!(
+        file,
+        "
+
+Vertex = Union[{}]
+\"\"\"The type of vertices in the dataset implemented by this adapter.\"\"\"
+
+SUBTYPES: Dict[str, FrozenSet[str]] = {{
+",
+        variants.join(", ")
+    )
+    .expect("failed to write");
+
+    for vertex_type in vertex_types {
+        let subtypes: BTreeSet<&str> =
+            schema.subtypes(&vertex_type.name).expect("not a vertex type").collect();
+        let subtypes: Vec<_> =
+            subtypes.into_iter().map(|subtype| format!("\"{subtype}\"")).collect();
+        writeln!(file, "    \"{}\": frozenset({{{}}}),", vertex_type.name, subtypes.join(", "))
+            .expect("failed to write");
+    }
+    file.push_str(
+        "}\n\"\"\"The names of the vertex types that are subtypes of each type, including itself.\"\"\"\n",
+    );
+
+    file
+}
+
+fn make_entrypoints_file(entrypoints: &[EntrypointRow]) -> String {
+    let mut file = String::from(
+        "\
+from typing import Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import Vertex
+",
+    );
+
+    let mut table = String::new();
+    for entrypoint in entrypoints {
+        let fn_name = escaped_python_name(to_lower_snake_case(&entrypoint.name));
+        let docs = if entrypoint.parameter_name.is_empty() {
+            String::new()
+        } else {
+            let mut docs = format!(
+                "    \"\"\"Resolve the starting vertices of the `{}` entrypoint edge.\n\n    \
+                Parameters:\n",
+                entrypoint.name
+            );
+            for (name, ty) in entrypoint.parameter_name.iter().zip(&entrypoint.parameter_type) {
+                writeln!(docs, "    - `{name}` of type `{ty}`").expect("failed to write");
+            }
+            docs.push_str("    \"\"\"\n");
+            docs
+        };
+        write!(
+            file,
+            "
+
+def {fn_name}(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+{docs}    raise NotImplementedError(
+        \"implement resolving starting vertices for entrypoint edge '{}'\"
+    )
+",
+            entrypoint.name
+        )
+        .expect("failed to write");
+        writeln!(table, "    \"{}\": {fn_name},", entrypoint.name).expect("failed to write");
+    }
+
+    write!(
+        file,
+        "
+
+ENTRYPOINTS: Dict[str, Callable[[Mapping[str, FieldValue]], Iterable[Vertex]]] = {{
+{table}}}
+"
+    )
+    .expect("failed to write");
+
+    file
+}
+
+fn make_properties_file(vertex_types: &[VertexTypeRow]) -> String {
+    let mut functions = String::new();
+    let mut table = String::new();
+    let mut imports = BTreeSet::new();
+    for vertex_type in vertex_types {
+        if vertex_type.property_name.is_empty() {
+            continue;
+        }
+
+        let param_type = vertex_param_type(vertex_type);
+        writeln!(table, "    \"{}\": {{", vertex_type.name).expect("failed to write");
+        for (property_name, property_type) in
+            vertex_type.property_name.iter().zip(&vertex_type.property_type)
+        {
+            let fn_name = field_fn_name(&vertex_type.name, property_name);
+            write!(
+                functions,
+                "
+
+def {fn_name}(vertex: {param_type}) -> {}:
+    raise NotImplementedError(\"implement property '{property_name}' of type '{}'\")
+",
+                python_type(property_type),
+                vertex_type.name,
+            )
+            .expect("failed to write");
+            writeln!(table, "        \"{property_name}\": {fn_name},").expect("failed to write");
+        }
+        table.push_str("    },\n");
+        imports.insert(param_type);
+    }
+
+    let mut file = String::from(
+        "\
+from typing import Any, Callable, Dict, List, Optional
+
+from trustfall import FieldValue
+",
+    );
+    if !imports.is_empty() {
+        write!(
+            file,
+            "\nfrom .vertex import {}\n",
+            imports.into_iter().collect::<Vec<_>>().join(", ")
+        )
+        .expect("failed to write");
+    }
+    write!(
+        file,
+        "{functions}
+
+PROPERTIES: Dict[str, Dict[str, Callable[[Any], FieldValue]]] = {{
+{table}}}
+"
+    )
+    .expect("failed to write");
+
+    file
+}
+
+fn make_edges_file(vertex_types: &[VertexTypeRow]) -> String {
+    let mut functions = String::new();
+    let mut table = String::new();
+    let mut imports = BTreeSet::from(["Vertex".to_string()]);
+    for vertex_type in vertex_types {
+        if vertex_type.edge_name.is_empty() {
+            continue;
+        }
+
+        let param_type = vertex_param_type(vertex_type);
+        imports.insert(param_type.clone());
+        writeln!(table, "    \"{}\": {{", vertex_type.name).expect("failed to write");
+        for (edge_name, edge_target) in vertex_type.edge_name.iter().zip(&vertex_type.edge_target) {
+            let fn_name = field_fn_name(&vertex_type.name, edge_name);
+            write!(
+                functions,
+                "
+
+def {fn_name}(
+    vertex: {param_type}, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    \"\"\"Resolve the neighboring `{edge_target}` vertices across the `{edge_name}` edge.\"\"\"
+    raise NotImplementedError(\"implement edge '{edge_name}' of type '{}'\")
+",
+                vertex_type.name,
+            )
+            .expect("failed to write");
+            writeln!(table, "        \"{edge_name}\": {fn_name},").expect("failed to write");
+        }
+        table.push_str("    },\n");
+    }
+
+    format!(
+        "\
+from typing import Any, Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import {}
+{functions}
+
+EDGES: Dict[str, Dict[str, Callable[[Any, Mapping[str, FieldValue]], Iterable[Vertex]]]] = {{
+{table}}}
+",
+        imports.into_iter().collect::<Vec<_>>().join(", ")
+    )
+}
@@ -10,6 +10,7 @@ use quote::quote;
 use regex::Regex;
 use trustfall::{Schema, SchemaAdapter, TryIntoStruct};
 
+use crate::merge::merge_rust_file;
 use crate::util::{escaped_rust_name, parse_import, to_lower_snake_case, upper_case_variant_name};
 
 use super::{
@@ -17,6 +18,99 @@ use super::{
     entrypoints_creator::make_entrypoints_file, properties_creator::make_properties_file,
 };
 
+/// Options that control what kind of adapter stub is generated.
+///
+/// # Example
+/// ```no_run
+/// # use std::path::Path;
+/// #
+/// # use trustfall_stubgen::{generate_rust_stub_with_config, StubgenConfig};
+/// #
+/// # fn main() {
+/// let schema_text = std::fs::read_to_string("./schema.graphql").expect("failed to read schema");
+/// let config = StubgenConfig::new().basic_adapter(true);
+/// generate_rust_stub_with_config(&schema_text, Path::new("crate/with/generated/stubs/src"), &config)
+///     .expect("stub generation failed");
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Default)]
+pub struct StubgenConfig {
+    /// Whether to implement the `BasicAdapter` trait instead of the full `Adapter` trait.
+    pub basic_adapter: bool,
+
+    /// Whether to merge the stub into previously-generated files instead of overwriting them.
+    pub update_existing: bool,
+
+    /// Whether to generate a schema test and a sample query test per entrypoint,
+    /// in addition to the adapter invariants test.
+    pub test_scaffolding: bool,
+
+    /// Paths of existing Rust types to use as the payloads of `Vertex` enum variants,
+    /// keyed by the name of the corresponding schema type.
+    ///
+    /// Schema types not in this map get a `()` placeholder payload.
+    pub vertex_types: BTreeMap<String, String>,
+}
+
+/// The file listing generated code that couldn't be merged into existing files.
+const CONFLICTS_FILE_NAME: &str = "stubgen_conflicts.txt";
+
+impl StubgenConfig {
+    /// The default configuration, which generates a full `Adapter` implementation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate an implementation of the simpler `BasicAdapter` trait instead of
+    /// the full `Adapter` trait.
+    ///
+    /// `BasicAdapter` resolvers take `&str` names and do not receive query hints
+    /// in the form of `ResolveInfo` and `ResolveEdgeInfo` values,
+    /// so the entrypoint, property, and edge functions in the stub are simpler as well.
+    pub fn basic_adapter(mut self, basic_adapter: bool) -> Self {
+        self.basic_adapter = basic_adapter;
+        self
+    }
+
+    /// Update a previously-generated stub in the target directory instead of overwriting it.
+    ///
+    /// Types, properties, and edges that are new in the schema are added to the existing files,
+    /// while already-implemented code is left intact. Functions whose generated signature
+    /// has changed since the previous generation are not modified. Instead, they are listed
+    /// in the `adapter/stubgen_conflicts.txt` file, to be updated by hand.
+    pub fn update_existing(mut self, update_existing: bool) -> Self {
+        self.update_existing = update_existing;
+        self
+    }
+
+    /// Generate a more complete test suite in `adapter/tests.rs`.
+    ///
+    /// In addition to checking the adapter's invariants, the tests check that the schema is valid,
+    /// and that a sample query starting at each entrypoint is valid for the schema.
+    /// Each sample query can then be executed and its results checked
+    /// once its entrypoint is implemented.
+    pub fn test_scaffolding(mut self, test_scaffolding: bool) -> Self {
+        self.test_scaffolding = test_scaffolding;
+        self
+    }
+
+    /// Use the existing Rust type at the given path, such as `crate::model::User`,
+    /// as the payload of the `Vertex` enum variant for the named schema type.
+    ///
+    /// The generated `adapter/vertex.rs` file imports the type, unless its name
+    /// would clash with another imported type, in which case its full path is used instead.
+    /// The type must implement `Debug` and `Clone`, like the `Vertex` enum itself.
+    pub fn vertex_type(
+        mut self,
+        type_name: impl Into<String>,
+        rust_path: impl Into<String>,
+    ) -> Self {
+        self.vertex_types.insert(type_name.into(), rust_path.into());
+        self
+    }
+}
+
 /// Given a schema, make a Rust adapter stub for it in the given directory.
 ///
 /// Generated code structure:
@@ -29,6 +123,8 @@ use super::{
 /// - adapter/edges.rs        contains the edge implementations
 /// - adapter/tests.rs        contains test code
 ///
+/// To configure the generated stub, use [`generate_rust_stub_with_config`] instead.
+///
 /// # Example
 /// ```no_run
 /// # use std::path::Path;
@@ -42,6 +138,18 @@ use super::{
 /// # }
 /// ```
 pub fn generate_rust_stub(schema: &str, target: &Path) -> anyhow::Result<()> {
+    generate_rust_stub_with_config(schema, target, &StubgenConfig::default())
+}
+
+/// Given a schema, make a Rust adapter stub for it in the given directory,
+/// using the given configuration.
+///
+/// The generated code has the same structure as in [`generate_rust_stub`].
+pub fn generate_rust_stub_with_config(
+    schema: &str,
+    target: &Path,
+    config: &StubgenConfig,
+) -> anyhow::Result<()> {
     let target_schema = Schema::parse(schema)?;
 
     let querying_schema =
@@ -55,24 +163,26 @@ pub fn generate_rust_stub(schema: &str, target: &Path) -> anyhow::Result<()> {
     ensure_no_vertex_name_conflicts(&querying_schema, schema_adapter.clone());
     ensure_no_field_name_conflicts_on_vertex_type(&querying_schema, schema_adapter.clone());
 
-    make_vertex_file(&querying_schema, schema_adapter.clone(), &mut stub.vertex);
+    make_vertex_file(&querying_schema, schema_adapter.clone(), &mut stub.vertex, config)?;
     make_entrypoints_file(
         &querying_schema,
         schema_adapter.clone(),
         &mut stub.entrypoints,
         &mut entrypoint_match_arms,
+        config,
     );
-    make_properties_file(&querying_schema, schema_adapter.clone(), &mut stub.properties);
-    make_edges_file(&querying_schema, schema_adapter.clone(), &mut stub.edges);
+    make_properties_file(&querying_schema, schema_adapter.clone(), &mut stub.properties, config);
+    make_edges_file(&querying_schema, schema_adapter.clone(), &mut stub.edges, config);
     make_adapter_file(
         &querying_schema,
         schema_adapter.clone(),
         &mut stub.adapter_impl,
         entrypoint_match_arms,
+        config,
     );
-    make_tests_file(&mut stub.tests);
+    make_tests_file(&querying_schema, schema_adapter, &mut stub.tests, config);
 
-    stub.write_to_directory(target)
+    stub.write_to_directory(target, config)
 }
 
 #[derive(Debug, Default)]
@@ -84,7 +194,7 @@ pub(crate) struct RustFile {
 }
 
 impl RustFile {
-    fn write_to_file(self, target: &Path) -> anyhow::Result<()> {
+    fn render(self) -> anyhow::Result<String> {
         let mut buffer: Vec<u8> = Vec::with_capacity(8192);
 
         write_import_tree(&mut buffer, &self.builtin_imports)?;
@@ -104,7 +214,7 @@ impl RustFile {
 
         let mut item_iter = self.top_level_items.into_iter();
         let Some(first_item) = item_iter.next() else {
-            return std::fs::write(target, "").map_err(Into::into);
+            return Ok(String::new());
         };
         Self::pretty_print_item(&mut buffer, first_item)?;
 
@@ -113,9 +223,7 @@ impl RustFile {
             Self::pretty_print_item(&mut buffer, item)?;
         }
 
-        std::fs::write(target, buffer)?;
-
-        Ok(())
+        Ok(String::from_utf8(buffer).expect("generated code was not valid UTF-8"))
     }
 
     /// Pretty-print an item into the buffer.
@@ -304,7 +412,7 @@ impl<'a> AdapterStub<'a> {
         }
     }
 
-    fn write_to_directory(self, target: &Path) -> anyhow::Result<()> {
+    fn write_to_directory(self, target: &Path, config: &StubgenConfig) -> anyhow::Result<()> {
         let mut path_buf = target.to_path_buf();
         path_buf.push("adapter");
         std::fs::create_dir_all(&path_buf)?;
@@ -313,39 +421,61 @@ impl<'a> AdapterStub<'a> {
         std::fs::write(path_buf.as_path(), self.schema)?;
         path_buf.pop();
 
-        path_buf.push("mod.rs");
-        self.mod_.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
-
-        path_buf.push("adapter_impl.rs");
-        self.adapter_impl.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
-
-        path_buf.push("vertex.rs");
-        self.vertex.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
-
-        path_buf.push("entrypoints.rs");
-        self.entrypoints.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
-
-        path_buf.push("properties.rs");
-        self.properties.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
-
-        path_buf.push("edges.rs");
-        self.edges.write_to_file(path_buf.as_path())?;
-        path_buf.pop();
+        let files = [
+            ("mod.rs", self.mod_),
+            ("adapter_impl.rs", self.adapter_impl),
+            ("vertex.rs", self.vertex),
+            ("entrypoints.rs", self.entrypoints),
+            ("properties.rs", self.properties),
+            ("edges.rs", self.edges),
+            ("tests.rs", self.tests),
+        ];
+        let mut conflicts = vec![];
+        for (file_name, file) in files {
+            path_buf.push(file_name);
+            let generated = file.render()?;
+            let contents = if config.update_existing && path_buf.is_file() {
+                let existing = std::fs::read_to_string(path_buf.as_path())?;
+                let outcome = merge_rust_file(file_name, &existing, &generated)?;
+                conflicts.extend(outcome.conflicts);
+                outcome.merged
+            } else {
+                generated
+            };
+            std::fs::write(path_buf.as_path(), contents)?;
+            path_buf.pop();
+        }
 
-        path_buf.push("tests.rs");
-        self.tests.write_to_file(path_buf.as_path())?;
+        // Record any conflicts from this update, replacing the ones from a previous update.
+        path_buf.push(CONFLICTS_FILE_NAME);
+        if conflicts.is_empty() {
+            if path_buf.is_file() {
+                std::fs::remove_file(path_buf.as_path())?;
+            }
+        } else {
+            let mut contents = String::from(
+                "The following generated code could not be merged into the existing adapter.\n\
+                Please update the adapter by hand, then delete this file.\n",
+            );
+            for conflict in conflicts {
+                contents.push('\n');
+                contents.push_str(&conflict);
+                contents.push('\n');
+            }
+            std::fs::write(path_buf.as_path(), contents)?;
+        }
         path_buf.pop();
 
         Ok(())
     }
 }
 
-fn make_tests_file(tests_file: &mut RustFile) {
+fn make_tests_file(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+    tests_file: &mut RustFile,
+    config: &StubgenConfig,
+) {
     tests_file
         .external_imports
         .insert(parse_import("trustfall::provider::check_adapter_invariants"));
@@ -360,13 +490,108 @@ fn make_tests_file(tests_file: &mut RustFile) {
             check_adapter_invariants(schema, adapter);
         }
     });
+
+    if !config.test_scaffolding {
+        return;
+    }
+
+    tests_file.external_imports.insert(parse_import("trustfall::Schema"));
+    tests_file.top_level_items.push(quote! {
+        #[test]
+        fn schema_is_valid() {
+            Schema::parse(Adapter::SCHEMA_TEXT).expect("not a valid schema");
+        }
+    });
+
+    let query = r#"
+{
+    Entrypoint {
+        name @output
+
+        parameter_: parameter @fold {
+            name @output
+            type @output
+            default @output
+        }
+    }
+}"#;
+    let variables: BTreeMap<String, String> = Default::default();
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+    struct ResultRow {
+        name: String,
+        parameter_name: Vec<String>,
+        parameter_type: Vec<String>,
+        parameter_default: Vec<Option<String>>,
+    }
+
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    for row in rows {
+        let arguments: Vec<_> = row
+            .parameter_name
+            .iter()
+            .zip(&row.parameter_type)
+            .zip(&row.parameter_default)
+            .filter_map(|((name, ty), default)| {
+                // Parameters that are nullable or have default values can be omitted.
+                if default.is_some() || !ty.ends_with('!') {
+                    None
+                } else {
+                    Some(format!("{name}: {}", sample_parameter_value(ty)))
+                }
+            })
+            .collect();
+        let arguments = if arguments.is_empty() {
+            String::new()
+        } else {
+            format!("({})", arguments.join(", "))
+        };
+
+        let sample_query = format!(
+            "r#\"\n{{\n    {}{arguments} {{\n        __typename @output\n    }}\n}}\"#",
+            row.name
+        );
+        let sample_query: syn::LitStr =
+            syn::parse_str(&sample_query).expect("not a valid string literal");
+
+        let test_name = format!("{}_sample_query_is_valid", to_lower_snake_case(&row.name));
+        let ident = syn::Ident::new(&test_name, proc_macro2::Span::call_site());
+        let doc = format!(" Once the `{}` entrypoint is implemented, execute this query", row.name);
+        tests_file.top_level_items.push(quote! {
+            #[doc = #doc]
+            #[doc = " with `trustfall::execute_query()` and check its results."]
+            #[test]
+            fn #ident() {
+                let query = #sample_query;
+                trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+            }
+        });
+    }
+}
+
+/// A value for a non-nullable edge parameter of the given type, to use in a sample query.
+fn sample_parameter_value(parameter_type: &str) -> &'static str {
+    if parameter_type.starts_with('[') {
+        return "[]";
+    }
+    match parameter_type.trim_end_matches('!') {
+        "Int" => "0",
+        "Float" => "0.0",
+        "Boolean" => "false",
+        _ => "\"\"",
+    }
 }
 
 fn make_vertex_file(
     querying_schema: &Schema,
     adapter: Arc<SchemaAdapter<'_>>,
     vertex_file: &mut RustFile,
-) {
+    config: &StubgenConfig,
+) -> anyhow::Result<()> {
     let query = r#"
 {
     VertexType {
@@ -380,17 +605,39 @@ fn make_vertex_file(
         name: String,
     }
 
-    let mut variants = proc_macro2::TokenStream::new();
     let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
         .expect("invalid query")
         .map(|x| x.try_into_struct::<ResultRow>().expect("invalid conversion"))
         .collect();
     rows.sort_unstable();
+
+    let payloads =
+        resolve_vertex_payloads(&rows.iter().map(|row| row.name.as_str()).collect(), config)?;
+
+    let mut variants = proc_macro2::TokenStream::new();
     for row in rows {
         let name = &escaped_rust_name(upper_case_variant_name(&row.name));
         let ident = syn::Ident::new(name.as_str(), proc_macro2::Span::call_site());
+        let payload = match payloads.get(row.name.as_str()) {
+            Some(VertexPayload { path, import: true }) => {
+                let type_ident = path.segments.last().expect("empty path").ident.clone();
+                if path.leading_colon.is_none()
+                    && matches!(
+                        path.segments.first().map(|segment| segment.ident.to_string()).as_deref(),
+                        Some("crate" | "super" | "self")
+                    )
+                {
+                    vertex_file.internal_imports.insert(path_to_import(path));
+                } else {
+                    vertex_file.external_imports.insert(path_to_import(path));
+                }
+                quote! { #type_ident }
+            }
+            Some(VertexPayload { path, import: false }) => quote! { #path },
+            None => quote! { () },
+        };
         variants.extend(quote! {
-            #ident(()),
+            #ident(#payload),
         });
     }
 
@@ -403,9 +650,64 @@ fn make_vertex_file(
     };
 
     vertex_file.top_level_items.push(vertex);
+    Ok(())
+}
+
+/// A configured Rust type to use as the payload of a `Vertex` enum variant.
+struct VertexPayload {
+    path: syn::Path,
+
+    /// Whether to import the type and refer to it by name, instead of using its full path.
+    import: bool,
+}
+
+fn resolve_vertex_payloads<'a>(
+    schema_types: &BTreeSet<&str>,
+    config: &'a StubgenConfig,
+) -> anyhow::Result<BTreeMap<&'a str, VertexPayload>> {
+    let mut payloads = BTreeMap::new();
+    for (type_name, rust_path) in &config.vertex_types {
+        if !schema_types.contains(type_name.as_str()) {
+            anyhow::bail!(
+                "a Rust type was configured for vertex type '{type_name}', \
+                but the schema does not define such a vertex type"
+            );
+        }
+        let path: syn::Path = syn::parse_str(rust_path).map_err(|e| {
+            anyhow::anyhow!(
+                "the Rust type '{rust_path}' configured for vertex type '{type_name}' \
+                is not a valid path: {e}"
+            )
+        })?;
+        payloads.insert(type_name.as_str(), VertexPayload { path, import: true });
+    }
+
+    // Types whose names clash with each other or with the `Vertex` enum are not imported.
+    let mut name_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for payload in payloads.values() {
+        let name = payload.path.segments.last().expect("empty path").ident.to_string();
+        *name_counts.entry(name).or_default() += 1;
+    }
+    for payload in payloads.values_mut() {
+        let last_segment = payload.path.segments.last().expect("empty path");
+        let name = last_segment.ident.to_string();
+        payload.import = payload.path.segments.len() > 1
+            && last_segment.arguments.is_none()
+            && name != "Vertex"
+            && name_counts[&name] == 1;
+    }
+
+    Ok(payloads)
+}
+
+fn path_to_import(path: &syn::Path) -> Vec<String> {
+    path.segments.iter().map(|segment| segment.ident.to_string()).collect()
 }
 
-fn ensure_no_vertex_name_conflicts(querying_schema: &Schema, adapter: Arc<SchemaAdapter<'_>>) {
+pub(crate) fn ensure_no_vertex_name_conflicts(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+) {
     let query = r#"
 {
     VertexType {
@@ -442,7 +744,7 @@ fn ensure_no_vertex_name_conflicts(querying_schema: &Schema, adapter: Arc<Schema
     }
 }
 
-fn ensure_no_field_name_conflicts_on_vertex_type(
+pub(crate) fn ensure_no_field_name_conflicts_on_vertex_type(
     querying_schema: &Schema,
     adapter: Arc<SchemaAdapter<'_>>,
 ) {
@@ -4,7 +4,9 @@ use std::{
     process::Command,
 };
 
-use super::generate_rust_stub;
+use super::{
+    generate_python_stub, generate_rust_stub_with_config, generate_typescript_stub, StubgenConfig,
+};
 
 /// Write the given contents to a file, asserting that the file did not previously exist.
 fn write_new_file(path: &Path, contents: &str) {
@@ -104,7 +106,7 @@ fn get_relevant_files_from_dir(path: &Path) -> BTreeMap<PathBuf, PathBuf> {
             }
 
             let extension = pathbuf.extension().and_then(|x| x.to_str()).unwrap_or_default();
-            if matches!(extension, "rs" | "graphql") {
+            if matches!(extension, "rs" | "py" | "ts" | "graphql") {
                 let mut matched_filepath = pathbuf.to_str().expect("failed to make str");
                 matched_filepath = matched_filepath.strip_prefix("./").unwrap_or(matched_filepath);
 
@@ -151,8 +153,12 @@ fn assert_generated_code_is_unchanged(test_dir: &Path, expected_dir: &Path) {
 }
 
 fn test_schema(name: &str) {
+    test_schema_with_config(name, name, &StubgenConfig::default())
+}
+
+fn test_schema_with_config(name: &str, expected_output_name: &str, config: &StubgenConfig) {
     let mut test_dir = Path::new("/tmp/trustfall_stubgen/tests").to_path_buf();
-    test_dir.push(name);
+    test_dir.push(expected_output_name);
     let _ = std::fs::remove_dir_all(&test_dir); // it's fine if the dir didn't exist
 
     let mut schema_path = Path::new("./test_data").to_path_buf();
@@ -161,19 +167,150 @@ fn test_schema(name: &str) {
 
     let mut test_src_dir = test_dir.clone();
     test_src_dir.push("src");
-    generate_rust_stub(&schema, &test_src_dir).expect("failed to generate stub");
+    generate_rust_stub_with_config(&schema, &test_src_dir, config)
+        .expect("failed to generate stub");
 
     let mut expected_dir = Path::new("./test_data/expected_outputs").to_path_buf();
-    expected_dir.push(name);
+    expected_dir.push(expected_output_name);
     assert_generated_code_compiles(&test_dir);
     assert_generated_code_is_unchanged(&test_src_dir, &expected_dir);
 }
 
+fn assert_generated_python_compiles(path: &Path) {
+    let mut adapter_dir = PathBuf::from(path);
+    adapter_dir.push("adapter");
+    let mut files: Vec<_> = std::fs::read_dir(&adapter_dir)
+        .expect("failed to list dir")
+        .map(|entry| entry.expect("failed to read dir entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "py"))
+        .collect();
+    files.sort_unstable();
+    assert!(!files.is_empty());
+
+    // Check that the generated code is syntactically valid, without needing the `trustfall`
+    // Python package to be installed. Compile in memory so as not to create
+    // a `__pycache__` directory next to the generated code.
+    for file in files {
+        let output = Command::new("python3")
+            .arg("-c")
+            .arg("import sys; compile(open(sys.argv[1]).read(), sys.argv[1], 'exec')")
+            .arg(&file)
+            .output()
+            .expect("failed to execute python3");
+        assert!(
+            output.status.success(),
+            "{}\nstdout:\n{}\n\nstderr:\n{}",
+            file.display(),
+            std::str::from_utf8(&output.stdout).expect("invalid utf-8"),
+            std::str::from_utf8(&output.stderr).expect("invalid utf-8"),
+        );
+    }
+}
+
+fn test_python_schema(name: &str, expected_output_name: &str) {
+    let mut test_dir = Path::new("/tmp/trustfall_stubgen/tests").to_path_buf();
+    test_dir.push(expected_output_name);
+    let _ = std::fs::remove_dir_all(&test_dir); // it's fine if the dir didn't exist
+
+    let mut schema_path = Path::new("./test_data").to_path_buf();
+    schema_path.push(format!("{name}.graphql"));
+    let schema = std::fs::read_to_string(&schema_path).expect("failed to read schema file");
+
+    generate_python_stub(&schema, &test_dir).expect("failed to generate stub");
+
+    let mut expected_dir = Path::new("./test_data/expected_outputs").to_path_buf();
+    expected_dir.push(expected_output_name);
+    assert_generated_python_compiles(&test_dir);
+    assert_generated_code_is_unchanged(&test_dir, &expected_dir);
+}
+
+#[test]
+fn hackernews_schema_python() {
+    test_python_schema("hackernews", "hackernews_python")
+}
+
+#[test]
+fn no_edges_schema_python() {
+    test_python_schema("no_edges", "no_edges_python")
+}
+
+fn test_typescript_schema(name: &str, expected_output_name: &str) {
+    let mut test_dir = Path::new("/tmp/trustfall_stubgen/tests").to_path_buf();
+    test_dir.push(expected_output_name);
+    let _ = std::fs::remove_dir_all(&test_dir); // it's fine if the dir didn't exist
+
+    let mut schema_path = Path::new("./test_data").to_path_buf();
+    schema_path.push(format!("{name}.graphql"));
+    let schema = std::fs::read_to_string(&schema_path).expect("failed to read schema file");
+
+    generate_typescript_stub(&schema, &test_dir).expect("failed to generate stub");
+
+    let mut expected_dir = Path::new("./test_data/expected_outputs").to_path_buf();
+    expected_dir.push(expected_output_name);
+    assert_generated_code_is_unchanged(&test_dir, &expected_dir);
+}
+
+#[test]
+fn hackernews_schema_typescript() {
+    test_typescript_schema("hackernews", "hackernews_typescript")
+}
+
+#[test]
+fn no_edges_schema_typescript() {
+    test_typescript_schema("no_edges", "no_edges_typescript")
+}
+
 #[test]
 fn hackernews_schema() {
     test_schema("hackernews")
 }
 
+#[test]
+fn hackernews_schema_basic_adapter() {
+    test_schema_with_config(
+        "hackernews",
+        "hackernews_basic_adapter",
+        &StubgenConfig::new().basic_adapter(true),
+    )
+}
+
+#[test]
+fn hackernews_schema_test_scaffolding() {
+    test_schema_with_config(
+        "hackernews",
+        "hackernews_test_scaffolding",
+        &StubgenConfig::new().test_scaffolding(true),
+    )
+}
+
+#[test]
+fn hackernews_schema_vertex_types() {
+    let config = StubgenConfig::new()
+        .vertex_type("Story", "std::path::PathBuf")
+        .vertex_type("User", "std::ffi::OsString")
+        .vertex_type("Job", "std::collections::BTreeMap<String, String>")
+        .vertex_type("Comment", "String");
+    test_schema_with_config("hackernews", "hackernews_vertex_types", &config)
+}
+
+#[test]
+#[should_panic(
+    expected = "a Rust type was configured for vertex type 'Missing', but the schema does not define such a vertex type"
+)]
+fn vertex_type_not_in_schema() {
+    let config = StubgenConfig::new().vertex_type("Missing", "std::path::PathBuf");
+    test_schema_with_config("hackernews", "vertex_type_not_in_schema", &config)
+}
+
+#[test]
+fn no_edges_schema_basic_adapter() {
+    test_schema_with_config(
+        "no_edges",
+        "no_edges_basic_adapter",
+        &StubgenConfig::new().basic_adapter(true),
+    )
+}
+
 #[test]
 fn use_reserved_rust_names_in_schema() {
     test_schema("use_reserved_rust_names_in_schema");
@@ -215,3 +352,50 @@ fn vertextype_with_type_and_typeunderscore_edges() {
 fn vertextype_with_type_and_typeunderscore_properties() {
     test_schema("vertextype_with_type_and_typeunderscore_properties");
 }
+
+#[test]
+fn update_existing_stub_preserves_implemented_code() {
+    let test_dir = Path::new("/tmp/trustfall_stubgen/tests/update_existing_stub");
+    let _ = std::fs::remove_dir_all(test_dir); // it's fine if the dir didn't exist
+    let test_src_dir = test_dir.join("src");
+    let properties_path = test_src_dir.join("adapter").join("properties.rs");
+
+    let schema =
+        std::fs::read_to_string("./test_data/hackernews.graphql").expect("failed to read schema");
+    generate_rust_stub_with_config(&schema, &test_src_dir, &StubgenConfig::default())
+        .expect("failed to generate stub");
+
+    // Implement a property, and remove another property's arm as if it were new in the schema.
+    let implemented_arm = "\"byUsername\" => {\n            // Implemented by hand.\n            unimplemented!()\n        }";
+    let removed_arm =
+        "\"id\" => todo!(\"implement property 'id' in fn `resolve_comment_property()`\"),";
+    let original = std::fs::read_to_string(&properties_path).expect("failed to read file");
+    let edited = original
+        .replace(
+            "\"byUsername\" => {\n            todo!(\"implement property 'byUsername' in fn `resolve_comment_property()`\")\n        }",
+            implemented_arm,
+        )
+        .replace(&format!("\n        {removed_arm}"), "");
+    assert!(edited.contains(implemented_arm));
+    assert!(!edited.contains(removed_arm));
+    std::fs::write(&properties_path, &edited).expect("failed to write file");
+
+    let config = StubgenConfig::new().update_existing(true);
+    generate_rust_stub_with_config(&schema, &test_src_dir, &config).expect("failed to update stub");
+
+    let updated = std::fs::read_to_string(&properties_path).expect("failed to read file");
+    assert!(updated.contains(implemented_arm), "{updated}");
+    assert!(updated.contains(removed_arm), "{updated}");
+    assert!(!test_src_dir.join("adapter").join("stubgen_conflicts.txt").exists());
+
+    // Files without edits are unchanged by the update.
+    for file_name in ["adapter_impl.rs", "edges.rs", "entrypoints.rs", "vertex.rs"] {
+        let expected_path = Path::new("./test_data/expected_outputs/hackernews/adapter");
+        let expected =
+            std::fs::read_to_string(expected_path.join(file_name)).expect("failed to read file");
+        let actual = std::fs::read_to_string(test_src_dir.join("adapter").join(file_name))
+            .expect("failed to read file");
+        similar_asserts::assert_eq!(expected, actual);
+    }
+    assert_generated_code_compiles(test_dir);
+}
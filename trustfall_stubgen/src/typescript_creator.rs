@@ -0,0 +1,552 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use trustfall::{Schema, SchemaAdapter, TryIntoStruct};
+
+use crate::{
+    root::{ensure_no_field_name_conflicts_on_vertex_type, ensure_no_vertex_name_conflicts},
+    util::to_lower_snake_case,
+};
+
+/// Given a schema, make a TypeScript adapter stub for it in the given directory.
+///
+/// The stub implements the `Adapter` interface of the `trustfall_wasm` package,
+/// dispatching each resolver call to a per-type function based on tables keyed
+/// by type and field name. Vertices are a discriminated union on their `__typename`.
+///
+/// Generated code structure:
+/// - adapter/index.ts           exports the adapter and vertex types
+/// - adapter/schema.ts          contains the schema for the adapter
+/// - adapter/adapter.ts         contains the adapter implementation
+/// - adapter/vertex.ts          contains the vertex type definitions
+/// - adapter/entrypoints.ts     contains the entry points where all queries must start
+/// - adapter/properties.ts      contains the property implementations
+/// - adapter/edges.ts           contains the edge implementations
+///
+/// # Example
+/// ```no_run
+/// # use std::path::Path;
+/// #
+/// # use trustfall_stubgen::generate_typescript_stub;
+/// #
+/// # fn main() {
+/// let schema_text = std::fs::read_to_string("./schema.graphql").expect("failed to read schema");
+/// generate_typescript_stub(&schema_text, Path::new("src/with/generated/stubs"))
+///     .expect("stub generation failed");
+/// # }
+/// ```
+pub fn generate_typescript_stub(schema: &str, target: &Path) -> anyhow::Result<()> {
+    let target_schema = Schema::parse(schema)?;
+
+    let querying_schema =
+        Schema::parse(SchemaAdapter::schema_text()).expect("schema querying schema was not valid");
+    let schema_adapter = Arc::new(SchemaAdapter::new(&target_schema));
+
+    ensure_no_vertex_name_conflicts(&querying_schema, schema_adapter.clone());
+    ensure_no_field_name_conflicts_on_vertex_type(&querying_schema, schema_adapter.clone());
+
+    let vertex_types = get_vertex_types(&querying_schema, schema_adapter.clone());
+    let entrypoints = get_entrypoints(&querying_schema, schema_adapter);
+
+    let files = [
+        ("index.ts", make_index_file()),
+        ("schema.ts", make_schema_file(schema)),
+        ("adapter.ts", make_adapter_file()),
+        ("vertex.ts", make_vertex_file(&target_schema, &vertex_types)),
+        ("entrypoints.ts", make_entrypoints_file(&entrypoints)),
+        ("properties.ts", make_properties_file(&vertex_types)),
+        ("edges.ts", make_edges_file(&vertex_types)),
+    ];
+
+    let mut path_buf = target.to_path_buf();
+    path_buf.push("adapter");
+    std::fs::create_dir_all(&path_buf)?;
+    for (file_name, contents) in files {
+        path_buf.push(file_name);
+        std::fs::write(path_buf.as_path(), contents)?;
+        path_buf.pop();
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+struct VertexTypeRow {
+    name: String,
+    is_interface: bool,
+    property_name: Vec<String>,
+    property_type: Vec<String>,
+    edge_name: Vec<String>,
+    edge_target: Vec<String>,
+}
+
+fn get_vertex_types(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+) -> Vec<VertexTypeRow> {
+    let query = r#"
+{
+    VertexType {
+        name @output
+        is_interface @output
+
+        property_: property @fold {
+            name @output
+            type @output
+        }
+        edge_: edge @fold {
+            name @output
+
+            target {
+                target: name @output
+            }
+        }
+    }
+}"#;
+    let variables: BTreeMap<String, String> = Default::default();
+
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<VertexTypeRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    rows
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+struct EntrypointRow {
+    name: String,
+    parameter_name: Vec<String>,
+    parameter_type: Vec<String>,
+}
+
+fn get_entrypoints(
+    querying_schema: &Schema,
+    adapter: Arc<SchemaAdapter<'_>>,
+) -> Vec<EntrypointRow> {
+    let query = r#"
+{
+    Entrypoint {
+        name @output
+
+        parameter_: parameter @fold {
+            name @output
+            type @output
+        }
+    }
+}"#;
+    let variables: BTreeMap<String, String> = Default::default();
+
+    let mut rows: Vec<_> = trustfall::execute_query(querying_schema, adapter, query, variables)
+        .expect("invalid query")
+        .map(|x| x.try_into_struct::<EntrypointRow>().expect("invalid conversion"))
+        .collect();
+    rows.sort_unstable();
+    rows
+}
+
+fn escaped_typescript_name(name: String) -> String {
+    // Reserved words, strict mode reserved words, and TypeScript's predefined type names:
+    // https://github.com/microsoft/TypeScript/issues/2536
+    match name.as_str() {
+        "break" | "case" | "catch" | "class" | "const" | "continue" | "debugger" | "default"
+        | "delete" | "do" | "else" | "enum" | "export" | "extends" | "false" | "finally"
+        | "for" | "function" | "if" | "import" | "in" | "instanceof" | "new" | "null"
+        | "return" | "super" | "switch" | "this" | "throw" | "true" | "try" | "typeof" | "var"
+        | "void" | "while" | "with" | "implements" | "interface" | "let" | "package"
+        | "private" | "protected" | "public" | "static" | "yield" | "any" | "bigint"
+        | "boolean" | "never" | "number" | "object" | "string" | "symbol" | "undefined"
+        | "unknown" => name + "_",
+        _ => name,
+    }
+}
+
+fn to_lower_camel_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for (index, word) in to_lower_snake_case(value).split('_').enumerate() {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            if index == 0 {
+                result.push(first);
+            } else {
+                result.extend(first.to_uppercase());
+            }
+            result.extend(chars);
+        }
+    }
+    result
+}
+
+/// The name of the TypeScript interface for the given vertex type.
+fn interface_name(type_name: &str) -> String {
+    escaped_typescript_name(type_name.to_string())
+}
+
+/// The name of the TypeScript function resolving the given field of the given vertex type.
+fn field_fn_name(type_name: &str, field_name: &str) -> String {
+    escaped_typescript_name(to_lower_camel_case(&format!("{type_name}_{field_name}")))
+}
+
+/// The TypeScript type of values of the given Trustfall type.
+fn typescript_type(trustfall_type: &str) -> String {
+    let (inner, nullable) = match trustfall_type.strip_suffix('!') {
+        Some(inner) => (inner, false),
+        None => (trustfall_type, true),
+    };
+
+    let ty = if let Some(partial) = inner.strip_prefix('[') {
+        let element = partial.strip_suffix(']').unwrap_or_else(|| {
+            panic!("invalid Trustfall type started with `[` without matching `]`: {trustfall_type}")
+        });
+        if element.ends_with('!') {
+            format!("{}[]", typescript_type(element))
+        } else {
+            format!("({})[]", typescript_type(element))
+        }
+    } else {
+        match inner {
+            "Int" | "Float" => "number",
+            "Boolean" => "boolean",
+            "String" | "ID" => "string",
+            _ => "JsFieldValue",
+        }
+        .to_string()
+    };
+
+    if nullable {
+        format!("{ty} | null")
+    } else {
+        ty
+    }
+}
+
+/// The parameter type for vertices of the given type: interfaces can be any vertex.
+fn vertex_param_type(vertex_type: &VertexTypeRow) -> String {
+    if vertex_type.is_interface {
+        "Vertex".to_string()
+    } else {
+        interface_name(&vertex_type.name)
+    }
+}
+
+fn make_index_file() -> String {
+    "\
+export { Adapter } from \"./adapter\";
+export { SCHEMA_TEXT } from \"./schema\";
+export type { Vertex } from \"./vertex\";
+"
+    .to_string()
+}
+
+fn make_schema_file(schema: &str) -> String {
+    let escaped = schema.replace('\\', "\\\\").replace('`', "\\`").replace("${", "\\${");
+    format!(
+        "\
+/** The schema for the adapter. Pass it to `Schema.parse()` from the `trustfall_wasm` package. */
+export const SCHEMA_TEXT = `{escaped}`;
+"
+    )
+}
+
+fn make_adapter_file() -> String {
+    r#"import type {
+  Adapter as TrustfallAdapter,
+  ContextAndBool,
+  ContextAndNeighborsIterator,
+  ContextAndValue,
+  JsContext,
+  JsEdgeParameters,
+} from "trustfall_wasm";
+
+import { EDGES } from "./edges";
+import { ENTRYPOINTS } from "./entrypoints";
+import { PROPERTIES } from "./properties";
+import { SUBTYPES, type Vertex } from "./vertex";
+
+function lookup<T>(
+  table: Record<string, Record<string, T>>,
+  typeName: string,
+  fieldName: string,
+  kind: string,
+): T {
+  const resolver = table[typeName]?.[fieldName];
+  if (resolver === undefined) {
+    throw new Error(`attempted to resolve ${kind} '${fieldName}' on unexpected type: ${typeName}`);
+  }
+  return resolver;
+}
+
+function* iterate<T>(iterable: Iterable<T>): IterableIterator<T> {
+  yield* iterable;
+}
+
+export class Adapter implements TrustfallAdapter<Vertex> {
+  resolveStartingVertices(edge: string, parameters: JsEdgeParameters): IterableIterator<Vertex> {
+    const resolver = ENTRYPOINTS[edge];
+    if (resolver === undefined) {
+      throw new Error(`attempted to resolve starting vertices for unexpected edge name: ${edge}`);
+    }
+    return iterate(resolver(parameters));
+  }
+
+  *resolveProperty(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    field_name: string,
+  ): IterableIterator<ContextAndValue> {
+    if (field_name === "__typename") {
+      for (const context of contexts) {
+        const vertex = context.activeVertex;
+        yield { localId: context.localId, value: vertex === null ? null : vertex.__typename };
+      }
+      return;
+    }
+
+    const resolver = lookup(PROPERTIES, type_name, field_name, "property");
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      yield { localId: context.localId, value: vertex === null ? null : resolver(vertex) };
+    }
+  }
+
+  *resolveNeighbors(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    edge_name: string,
+    parameters: JsEdgeParameters,
+  ): IterableIterator<ContextAndNeighborsIterator<Vertex>> {
+    const resolver = lookup(EDGES, type_name, edge_name, "edge");
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      const neighbors = vertex === null ? [] : resolver(vertex, parameters);
+      yield { localId: context.localId, neighbors: iterate(neighbors) };
+    }
+  }
+
+  *resolveCoercion(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    coerce_to_type: string,
+  ): IterableIterator<ContextAndBool> {
+    const subtypes = SUBTYPES[coerce_to_type];
+    if (subtypes === undefined) {
+      throw new Error(
+        `attempted to coerce type '${type_name}' to unexpected type: ${coerce_to_type}`,
+      );
+    }
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      yield { localId: context.localId, value: vertex !== null && subtypes.has(vertex.__typename) };
+    }
+  }
+}
+"#
+    .to_string()
+}
+
+fn make_vertex_file(schema: &Schema, vertex_types: &[VertexTypeRow]) -> String {
+    let mut file = String::new();
+
+    // Like the Rust stub's `Vertex` enum, every vertex type gets its own interface,
+    // interfaces included, since an interface need not have any implementers.
+    for vertex_type in vertex_types {
+        let type_name = &vertex_type.name;
+        write!(
+            file,
+            "\
+/**
+ * A vertex of type `{type_name}`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface {} {{
+  readonly __typename: \"{type_name}\";
+}}
+
+",
+            interface_name(type_name)
+        )
+        .expect("failed to write");
+    }
+
+    let variants: Vec<_> = vertex_types.iter().map(|row| interface_name(&row.name)).collect();
+    write!(
+        file,
+        "\
+/** The type of vertices in the dataset implemented by this adapter. */
+export type Vertex = {};
+
+/** The names of the vertex types that are subtypes of each type, including itself. */
+export const SUBTYPES: Record<string, ReadonlySet<string>> = {{
+",
+        variants.join(" | ")
+    )
+    .expect("failed to write");
+
+    for vertex_type in vertex_types {
+        let subtypes: BTreeSet<&str> =
+            schema.subtypes(&vertex_type.name).expect("not a vertex type").collect();
+        let subtypes: Vec<_> =
+            subtypes.into_iter().map(|subtype| format!("\"{subtype}\"")).collect();
+        writeln!(file, "  {}: new Set([{}]),", vertex_type.name, subtypes.join(", "))
+            .expect("failed to write");
+    }
+    file.push_str("};\n");
+
+    file
+}
+
+fn make_entrypoints_file(entrypoints: &[EntrypointRow]) -> String {
+    let mut file = String::from(
+        "\
+import type { JsEdgeParameters } from \"trustfall_wasm\";
+
+import type { Vertex } from \"./vertex\";
+",
+    );
+
+    let mut table = String::new();
+    for entrypoint in entrypoints {
+        let fn_name = escaped_typescript_name(to_lower_camel_case(&entrypoint.name));
+        let mut docs = format!(
+            "/**\n * Resolve the starting vertices of the `{}` entrypoint edge.\n",
+            entrypoint.name
+        );
+        if !entrypoint.parameter_name.is_empty() {
+            docs.push_str(" *\n * Parameters:\n");
+            for (name, ty) in entrypoint.parameter_name.iter().zip(&entrypoint.parameter_type) {
+                writeln!(docs, " * - `{name}` of type `{ty}`").expect("failed to write");
+            }
+        }
+        docs.push_str(" */\n");
+        write!(
+            file,
+            "
+{docs}export function {fn_name}(parameters: JsEdgeParameters): Iterable<Vertex> {{
+  throw new Error(\"implement resolving starting vertices for entrypoint edge '{}'\");
+}}
+",
+            entrypoint.name
+        )
+        .expect("failed to write");
+        writeln!(table, "  {}: {fn_name},", entrypoint.name).expect("failed to write");
+    }
+
+    write!(
+        file,
+        "
+export const ENTRYPOINTS: Record<string, (parameters: JsEdgeParameters) => Iterable<Vertex>> = {{
+{table}}};
+"
+    )
+    .expect("failed to write");
+
+    file
+}
+
+fn make_properties_file(vertex_types: &[VertexTypeRow]) -> String {
+    let mut functions = String::new();
+    let mut table = String::new();
+    let mut imports = BTreeSet::new();
+    for vertex_type in vertex_types {
+        if vertex_type.property_name.is_empty() {
+            continue;
+        }
+
+        let param_type = vertex_param_type(vertex_type);
+        writeln!(table, "  {}: {{", vertex_type.name).expect("failed to write");
+        for (property_name, property_type) in
+            vertex_type.property_name.iter().zip(&vertex_type.property_type)
+        {
+            let fn_name = field_fn_name(&vertex_type.name, property_name);
+            write!(
+                functions,
+                "
+export function {fn_name}(vertex: {param_type}): {} {{
+  throw new Error(\"implement property '{property_name}' of type '{}'\");
+}}
+",
+                typescript_type(property_type),
+                vertex_type.name,
+            )
+            .expect("failed to write");
+            writeln!(table, "    {property_name}: {fn_name},").expect("failed to write");
+        }
+        table.push_str("  },\n");
+        imports.insert(param_type);
+    }
+
+    let mut file = String::from("import type { JsFieldValue } from \"trustfall_wasm\";\n");
+    if !imports.is_empty() {
+        write!(
+            file,
+            "\nimport type {{ {} }} from \"./vertex\";\n",
+            imports.into_iter().collect::<Vec<_>>().join(", ")
+        )
+        .expect("failed to write");
+    }
+    write!(
+        file,
+        "{functions}
+// Each resolver is only called with vertices of the type it is registered for.
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export const PROPERTIES: Record<string, Record<string, (vertex: any) => JsFieldValue>> = {{
+{table}}};
+"
+    )
+    .expect("failed to write");
+
+    file
+}
+
+fn make_edges_file(vertex_types: &[VertexTypeRow]) -> String {
+    let mut functions = String::new();
+    let mut table = String::new();
+    let mut imports = BTreeSet::from(["Vertex".to_string()]);
+    for vertex_type in vertex_types {
+        if vertex_type.edge_name.is_empty() {
+            continue;
+        }
+
+        let param_type = vertex_param_type(vertex_type);
+        imports.insert(param_type.clone());
+        writeln!(table, "  {}: {{", vertex_type.name).expect("failed to write");
+        for (edge_name, edge_target) in vertex_type.edge_name.iter().zip(&vertex_type.edge_target) {
+            let fn_name = field_fn_name(&vertex_type.name, edge_name);
+            write!(
+                functions,
+                "
+/** Resolve the neighboring `{edge_target}` vertices across the `{edge_name}` edge. */
+export function {fn_name}(vertex: {param_type}, parameters: JsEdgeParameters): Iterable<Vertex> {{
+  throw new Error(\"implement edge '{edge_name}' of type '{}'\");
+}}
+",
+                vertex_type.name,
+            )
+            .expect("failed to write");
+            writeln!(table, "    {edge_name}: {fn_name},").expect("failed to write");
+        }
+        table.push_str("  },\n");
+    }
+
+    format!(
+        "\
+import type {{ JsEdgeParameters }} from \"trustfall_wasm\";
+
+import type {{ {} }} from \"./vertex\";
+{functions}
+export const EDGES: Record<
+  string,
+  // Each resolver is only called with vertices of the type it is registered for.
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  Record<string, (vertex: any, parameters: JsEdgeParameters) => Iterable<Vertex>>
+> = {{
+{table}}};
+",
+        imports.into_iter().collect::<Vec<_>>().join(", ")
+    )
+}
@@ -0,0 +1,245 @@
+use trustfall::provider::{AsVertex, ContextIterator, ContextOutcomeIterator, EdgeParameters, VertexIterator};
+
+use super::vertex::Vertex;
+
+pub(super) fn resolve_comment_edge<'a, V: AsVertex<Vertex> + 'a>(
+    contexts: ContextIterator<'a, V>,
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+    match edge_name {
+        "byUser" => comment::by_user(contexts),
+        "link" => comment::link(contexts),
+        "parent" => comment::parent(contexts),
+        "reply" => comment::reply(contexts),
+        _ => {
+            unreachable!(
+                "attempted to resolve unexpected edge '{edge_name}' on type 'Comment'"
+            )
+        }
+    }
+}
+
+mod comment {
+    use trustfall::provider::{
+        resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator,
+        VertexIterator,
+    };
+
+    use super::super::vertex::Vertex;
+
+    pub(super) fn by_user<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_comment()
+                    .expect("conversion failed, vertex was not a Comment");
+                todo!("get neighbors along edge 'byUser' for type 'Comment'")
+            },
+        )
+    }
+
+    pub(super) fn link<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_comment()
+                    .expect("conversion failed, vertex was not a Comment");
+                todo!("get neighbors along edge 'link' for type 'Comment'")
+            },
+        )
+    }
+
+    pub(super) fn parent<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_comment()
+                    .expect("conversion failed, vertex was not a Comment");
+                todo!("get neighbors along edge 'parent' for type 'Comment'")
+            },
+        )
+    }
+
+    pub(super) fn reply<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_comment()
+                    .expect("conversion failed, vertex was not a Comment");
+                todo!("get neighbors along edge 'reply' for type 'Comment'")
+            },
+        )
+    }
+}
+
+pub(super) fn resolve_job_edge<'a, V: AsVertex<Vertex> + 'a>(
+    contexts: ContextIterator<'a, V>,
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+    match edge_name {
+        "link" => job::link(contexts),
+        _ => {
+            unreachable!(
+                "attempted to resolve unexpected edge '{edge_name}' on type 'Job'"
+            )
+        }
+    }
+}
+
+mod job {
+    use trustfall::provider::{
+        resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator,
+        VertexIterator,
+    };
+
+    use super::super::vertex::Vertex;
+
+    pub(super) fn link<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_job()
+                    .expect("conversion failed, vertex was not a Job");
+                todo!("get neighbors along edge 'link' for type 'Job'")
+            },
+        )
+    }
+}
+
+pub(super) fn resolve_story_edge<'a, V: AsVertex<Vertex> + 'a>(
+    contexts: ContextIterator<'a, V>,
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+    match edge_name {
+        "byUser" => story::by_user(contexts),
+        "comment" => story::comment(contexts),
+        "link" => story::link(contexts),
+        _ => {
+            unreachable!(
+                "attempted to resolve unexpected edge '{edge_name}' on type 'Story'"
+            )
+        }
+    }
+}
+
+mod story {
+    use trustfall::provider::{
+        resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator,
+        VertexIterator,
+    };
+
+    use super::super::vertex::Vertex;
+
+    pub(super) fn by_user<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_story()
+                    .expect("conversion failed, vertex was not a Story");
+                todo!("get neighbors along edge 'byUser' for type 'Story'")
+            },
+        )
+    }
+
+    pub(super) fn comment<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_story()
+                    .expect("conversion failed, vertex was not a Story");
+                todo!("get neighbors along edge 'comment' for type 'Story'")
+            },
+        )
+    }
+
+    pub(super) fn link<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_story()
+                    .expect("conversion failed, vertex was not a Story");
+                todo!("get neighbors along edge 'link' for type 'Story'")
+            },
+        )
+    }
+}
+
+pub(super) fn resolve_user_edge<'a, V: AsVertex<Vertex> + 'a>(
+    contexts: ContextIterator<'a, V>,
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+    match edge_name {
+        "link" => user::link(contexts),
+        "submitted" => user::submitted(contexts),
+        _ => {
+            unreachable!(
+                "attempted to resolve unexpected edge '{edge_name}' on type 'User'"
+            )
+        }
+    }
+}
+
+mod user {
+    use trustfall::provider::{
+        resolve_neighbors_with, AsVertex, ContextIterator, ContextOutcomeIterator,
+        VertexIterator,
+    };
+
+    use super::super::vertex::Vertex;
+
+    pub(super) fn link<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_user()
+                    .expect("conversion failed, vertex was not a User");
+                todo!("get neighbors along edge 'link' for type 'User'")
+            },
+        )
+    }
+
+    pub(super) fn submitted<'a, V: AsVertex<Vertex> + 'a>(
+        contexts: ContextIterator<'a, V>,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Vertex>> {
+        resolve_neighbors_with(
+            contexts,
+            move |vertex| {
+                let vertex = vertex
+                    .as_user()
+                    .expect("conversion failed, vertex was not a User");
+                todo!("get neighbors along edge 'submitted' for type 'User'")
+            },
+        )
+    }
+}
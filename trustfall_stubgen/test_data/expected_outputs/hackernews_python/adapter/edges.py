@@ -0,0 +1,97 @@
+from typing import Any, Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import Comment, Job, Story, User, Vertex
+
+
+def comment_by_user(
+    vertex: Comment, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `User` vertices across the `byUser` edge."""
+    raise NotImplementedError("implement edge 'byUser' of type 'Comment'")
+
+
+def comment_reply(
+    vertex: Comment, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Comment` vertices across the `reply` edge."""
+    raise NotImplementedError("implement edge 'reply' of type 'Comment'")
+
+
+def comment_link(
+    vertex: Comment, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Webpage` vertices across the `link` edge."""
+    raise NotImplementedError("implement edge 'link' of type 'Comment'")
+
+
+def comment_parent(
+    vertex: Comment, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Item` vertices across the `parent` edge."""
+    raise NotImplementedError("implement edge 'parent' of type 'Comment'")
+
+
+def job_link(
+    vertex: Job, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Webpage` vertices across the `link` edge."""
+    raise NotImplementedError("implement edge 'link' of type 'Job'")
+
+
+def story_by_user(
+    vertex: Story, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `User` vertices across the `byUser` edge."""
+    raise NotImplementedError("implement edge 'byUser' of type 'Story'")
+
+
+def story_comment(
+    vertex: Story, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Comment` vertices across the `comment` edge."""
+    raise NotImplementedError("implement edge 'comment' of type 'Story'")
+
+
+def story_link(
+    vertex: Story, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Webpage` vertices across the `link` edge."""
+    raise NotImplementedError("implement edge 'link' of type 'Story'")
+
+
+def user_submitted(
+    vertex: User, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Item` vertices across the `submitted` edge."""
+    raise NotImplementedError("implement edge 'submitted' of type 'User'")
+
+
+def user_link(
+    vertex: User, parameters: Mapping[str, FieldValue]
+) -> Iterable[Vertex]:
+    """Resolve the neighboring `Webpage` vertices across the `link` edge."""
+    raise NotImplementedError("implement edge 'link' of type 'User'")
+
+
+EDGES: Dict[str, Dict[str, Callable[[Any, Mapping[str, FieldValue]], Iterable[Vertex]]]] = {
+    "Comment": {
+        "byUser": comment_by_user,
+        "reply": comment_reply,
+        "link": comment_link,
+        "parent": comment_parent,
+    },
+    "Job": {
+        "link": job_link,
+    },
+    "Story": {
+        "byUser": story_by_user,
+        "comment": story_comment,
+        "link": story_link,
+    },
+    "User": {
+        "submitted": user_submitted,
+        "link": user_link,
+    },
+}
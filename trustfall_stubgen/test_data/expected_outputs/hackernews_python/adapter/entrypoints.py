@@ -0,0 +1,160 @@
+from typing import Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import Vertex
+
+
+def ask_hn(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `AskHN` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'AskHN'"
+    )
+
+
+def best(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Best` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Best'"
+    )
+
+
+def front_page(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'FrontPage'"
+    )
+
+
+def item(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Item` entrypoint edge.
+
+    Parameters:
+    - `id` of type `Int!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Item'"
+    )
+
+
+def latest(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Latest` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Latest'"
+    )
+
+
+def recent_job(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `RecentJob` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'RecentJob'"
+    )
+
+
+def search_by_date(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `SearchByDate` entrypoint edge.
+
+    Parameters:
+    - `query` of type `String!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'SearchByDate'"
+    )
+
+
+def search_by_relevance(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `SearchByRelevance` entrypoint edge.
+
+    Parameters:
+    - `query` of type `String!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'SearchByRelevance'"
+    )
+
+
+def show_hn(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `ShowHN` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'ShowHN'"
+    )
+
+
+def top(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Top` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Top'"
+    )
+
+
+def updated_item(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `UpdatedItem` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'UpdatedItem'"
+    )
+
+
+def updated_user_profile(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `UpdatedUserProfile` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'UpdatedUserProfile'"
+    )
+
+
+def user(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `User` entrypoint edge.
+
+    Parameters:
+    - `name` of type `String!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'User'"
+    )
+
+
+ENTRYPOINTS: Dict[str, Callable[[Mapping[str, FieldValue]], Iterable[Vertex]]] = {
+    "AskHN": ask_hn,
+    "Best": best,
+    "FrontPage": front_page,
+    "Item": item,
+    "Latest": latest,
+    "RecentJob": recent_job,
+    "SearchByDate": search_by_date,
+    "SearchByRelevance": search_by_relevance,
+    "ShowHN": show_hn,
+    "Top": top,
+    "UpdatedItem": updated_item,
+    "UpdatedUserProfile": updated_user_profile,
+    "User": user,
+}
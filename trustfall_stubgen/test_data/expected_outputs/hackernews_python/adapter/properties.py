@@ -0,0 +1,176 @@
+from typing import Any, Callable, Dict, List, Optional
+
+from trustfall import FieldValue
+
+from .vertex import Comment, Job, Story, User, Vertex
+
+
+def comment_id(vertex: Comment) -> int:
+    raise NotImplementedError("implement property 'id' of type 'Comment'")
+
+
+def comment_unix_time(vertex: Comment) -> int:
+    raise NotImplementedError("implement property 'unixTime' of type 'Comment'")
+
+
+def comment_url(vertex: Comment) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Comment'")
+
+
+def comment_text_html(vertex: Comment) -> str:
+    raise NotImplementedError("implement property 'textHtml' of type 'Comment'")
+
+
+def comment_text_plain(vertex: Comment) -> str:
+    raise NotImplementedError("implement property 'textPlain' of type 'Comment'")
+
+
+def comment_by_username(vertex: Comment) -> str:
+    raise NotImplementedError("implement property 'byUsername' of type 'Comment'")
+
+
+def item_id(vertex: Vertex) -> int:
+    raise NotImplementedError("implement property 'id' of type 'Item'")
+
+
+def item_unix_time(vertex: Vertex) -> int:
+    raise NotImplementedError("implement property 'unixTime' of type 'Item'")
+
+
+def item_url(vertex: Vertex) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Item'")
+
+
+def job_id(vertex: Job) -> int:
+    raise NotImplementedError("implement property 'id' of type 'Job'")
+
+
+def job_unix_time(vertex: Job) -> int:
+    raise NotImplementedError("implement property 'unixTime' of type 'Job'")
+
+
+def job_url(vertex: Job) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Job'")
+
+
+def job_title(vertex: Job) -> str:
+    raise NotImplementedError("implement property 'title' of type 'Job'")
+
+
+def job_score(vertex: Job) -> int:
+    raise NotImplementedError("implement property 'score' of type 'Job'")
+
+
+def job_submitted_url(vertex: Job) -> str:
+    raise NotImplementedError("implement property 'submittedUrl' of type 'Job'")
+
+
+def story_id(vertex: Story) -> int:
+    raise NotImplementedError("implement property 'id' of type 'Story'")
+
+
+def story_unix_time(vertex: Story) -> int:
+    raise NotImplementedError("implement property 'unixTime' of type 'Story'")
+
+
+def story_url(vertex: Story) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Story'")
+
+
+def story_by_username(vertex: Story) -> str:
+    raise NotImplementedError("implement property 'byUsername' of type 'Story'")
+
+
+def story_score(vertex: Story) -> int:
+    raise NotImplementedError("implement property 'score' of type 'Story'")
+
+
+def story_text_html(vertex: Story) -> Optional[str]:
+    raise NotImplementedError("implement property 'textHtml' of type 'Story'")
+
+
+def story_text_plain(vertex: Story) -> Optional[str]:
+    raise NotImplementedError("implement property 'textPlain' of type 'Story'")
+
+
+def story_title(vertex: Story) -> str:
+    raise NotImplementedError("implement property 'title' of type 'Story'")
+
+
+def story_submitted_url(vertex: Story) -> Optional[str]:
+    raise NotImplementedError("implement property 'submittedUrl' of type 'Story'")
+
+
+def user_id(vertex: User) -> str:
+    raise NotImplementedError("implement property 'id' of type 'User'")
+
+
+def user_karma(vertex: User) -> int:
+    raise NotImplementedError("implement property 'karma' of type 'User'")
+
+
+def user_about_html(vertex: User) -> Optional[str]:
+    raise NotImplementedError("implement property 'aboutHtml' of type 'User'")
+
+
+def user_about_plain(vertex: User) -> Optional[str]:
+    raise NotImplementedError("implement property 'aboutPlain' of type 'User'")
+
+
+def user_unix_created_at(vertex: User) -> int:
+    raise NotImplementedError("implement property 'unixCreatedAt' of type 'User'")
+
+
+def user_url(vertex: User) -> str:
+    raise NotImplementedError("implement property 'url' of type 'User'")
+
+
+def webpage_url(vertex: Vertex) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Webpage'")
+
+
+PROPERTIES: Dict[str, Dict[str, Callable[[Any], FieldValue]]] = {
+    "Comment": {
+        "id": comment_id,
+        "unixTime": comment_unix_time,
+        "url": comment_url,
+        "textHtml": comment_text_html,
+        "textPlain": comment_text_plain,
+        "byUsername": comment_by_username,
+    },
+    "Item": {
+        "id": item_id,
+        "unixTime": item_unix_time,
+        "url": item_url,
+    },
+    "Job": {
+        "id": job_id,
+        "unixTime": job_unix_time,
+        "url": job_url,
+        "title": job_title,
+        "score": job_score,
+        "submittedUrl": job_submitted_url,
+    },
+    "Story": {
+        "id": story_id,
+        "unixTime": story_unix_time,
+        "url": story_url,
+        "byUsername": story_by_username,
+        "score": story_score,
+        "textHtml": story_text_html,
+        "textPlain": story_text_plain,
+        "title": story_title,
+        "submittedUrl": story_submitted_url,
+    },
+    "User": {
+        "id": user_id,
+        "karma": user_karma,
+        "aboutHtml": user_about_html,
+        "aboutPlain": user_about_plain,
+        "unixCreatedAt": user_unix_created_at,
+        "url": user_url,
+    },
+    "Webpage": {
+        "url": webpage_url,
+    },
+}
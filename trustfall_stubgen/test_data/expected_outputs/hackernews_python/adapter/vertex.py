@@ -0,0 +1,76 @@
+from dataclasses import dataclass
+from typing import ClassVar, Dict, FrozenSet, Union
+
+
+@dataclass
+class Comment:
+    """A vertex of type `Comment`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Comment"
+
+
+@dataclass
+class Item:
+    """A vertex of type `Item`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Item"
+
+
+@dataclass
+class Job:
+    """A vertex of type `Job`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Job"
+
+
+@dataclass
+class Story:
+    """A vertex of type `Story`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Story"
+
+
+@dataclass
+class User:
+    """A vertex of type `User`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "User"
+
+
+@dataclass
+class Webpage:
+    """A vertex of type `Webpage`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Webpage"
+
+
+Vertex = Union[Comment, Item, Job, Story, User, Webpage]
+"""The type of vertices in the dataset implemented by this adapter."""
+
+SUBTYPES: Dict[str, FrozenSet[str]] = {
+    "Comment": frozenset({"Comment"}),
+    "Item": frozenset({"Comment", "Item", "Job", "Story"}),
+    "Job": frozenset({"Job"}),
+    "Story": frozenset({"Story"}),
+    "User": frozenset({"User"}),
+    "Webpage": frozenset({"Comment", "Item", "Job", "Story", "User", "Webpage"}),
+}
+"""The names of the vertex types that are subtypes of each type, including itself."""
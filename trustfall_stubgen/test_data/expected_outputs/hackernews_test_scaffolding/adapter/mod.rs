@@ -0,0 +1,11 @@
+mod adapter_impl;
+mod vertex;
+mod entrypoints;
+mod properties;
+mod edges;
+
+#[cfg(test)]
+mod tests;
+
+pub use adapter_impl::Adapter;
+pub use vertex::Vertex;
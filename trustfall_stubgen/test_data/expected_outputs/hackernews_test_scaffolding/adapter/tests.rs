@@ -0,0 +1,184 @@
+use trustfall::{Schema, provider::check_adapter_invariants};
+
+use super::Adapter;
+
+#[test]
+fn adapter_satisfies_trustfall_invariants() {
+    let adapter = Adapter::new();
+    let schema = Adapter::schema();
+    check_adapter_invariants(schema, adapter);
+}
+
+#[test]
+fn schema_is_valid() {
+    Schema::parse(Adapter::SCHEMA_TEXT).expect("not a valid schema");
+}
+
+/// Once the `AskHN` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn ask_hn_sample_query_is_valid() {
+    let query = r#"
+{
+    AskHN {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `Best` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn best_sample_query_is_valid() {
+    let query = r#"
+{
+    Best {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `FrontPage` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn front_page_sample_query_is_valid() {
+    let query = r#"
+{
+    FrontPage {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `Item` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn item_sample_query_is_valid() {
+    let query = r#"
+{
+    Item(id: 0) {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `Latest` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn latest_sample_query_is_valid() {
+    let query = r#"
+{
+    Latest {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `RecentJob` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn recent_job_sample_query_is_valid() {
+    let query = r#"
+{
+    RecentJob {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `SearchByDate` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn search_by_date_sample_query_is_valid() {
+    let query = r#"
+{
+    SearchByDate(query: "") {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `SearchByRelevance` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn search_by_relevance_sample_query_is_valid() {
+    let query = r#"
+{
+    SearchByRelevance(query: "") {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `ShowHN` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn show_hn_sample_query_is_valid() {
+    let query = r#"
+{
+    ShowHN {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `Top` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn top_sample_query_is_valid() {
+    let query = r#"
+{
+    Top {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `UpdatedItem` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn updated_item_sample_query_is_valid() {
+    let query = r#"
+{
+    UpdatedItem {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `UpdatedUserProfile` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn updated_user_profile_sample_query_is_valid() {
+    let query = r#"
+{
+    UpdatedUserProfile {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
+
+/// Once the `User` entrypoint is implemented, execute this query
+/// with `trustfall::execute_query()` and check its results.
+#[test]
+fn user_sample_query_is_valid() {
+    let query = r#"
+{
+    User(name: "") {
+        __typename @output
+    }
+}"#;
+    trustfall::query_outputs(Adapter::schema(), query).expect("not a valid query");
+}
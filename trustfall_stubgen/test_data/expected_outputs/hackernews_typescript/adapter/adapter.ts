@@ -0,0 +1,91 @@
+import type {
+  Adapter as TrustfallAdapter,
+  ContextAndBool,
+  ContextAndNeighborsIterator,
+  ContextAndValue,
+  JsContext,
+  JsEdgeParameters,
+} from "trustfall_wasm";
+
+import { EDGES } from "./edges";
+import { ENTRYPOINTS } from "./entrypoints";
+import { PROPERTIES } from "./properties";
+import { SUBTYPES, type Vertex } from "./vertex";
+
+function lookup<T>(
+  table: Record<string, Record<string, T>>,
+  typeName: string,
+  fieldName: string,
+  kind: string,
+): T {
+  const resolver = table[typeName]?.[fieldName];
+  if (resolver === undefined) {
+    throw new Error(`attempted to resolve ${kind} '${fieldName}' on unexpected type: ${typeName}`);
+  }
+  return resolver;
+}
+
+function* iterate<T>(iterable: Iterable<T>): IterableIterator<T> {
+  yield* iterable;
+}
+
+export class Adapter implements TrustfallAdapter<Vertex> {
+  resolveStartingVertices(edge: string, parameters: JsEdgeParameters): IterableIterator<Vertex> {
+    const resolver = ENTRYPOINTS[edge];
+    if (resolver === undefined) {
+      throw new Error(`attempted to resolve starting vertices for unexpected edge name: ${edge}`);
+    }
+    return iterate(resolver(parameters));
+  }
+
+  *resolveProperty(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    field_name: string,
+  ): IterableIterator<ContextAndValue> {
+    if (field_name === "__typename") {
+      for (const context of contexts) {
+        const vertex = context.activeVertex;
+        yield { localId: context.localId, value: vertex === null ? null : vertex.__typename };
+      }
+      return;
+    }
+
+    const resolver = lookup(PROPERTIES, type_name, field_name, "property");
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      yield { localId: context.localId, value: vertex === null ? null : resolver(vertex) };
+    }
+  }
+
+  *resolveNeighbors(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    edge_name: string,
+    parameters: JsEdgeParameters,
+  ): IterableIterator<ContextAndNeighborsIterator<Vertex>> {
+    const resolver = lookup(EDGES, type_name, edge_name, "edge");
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      const neighbors = vertex === null ? [] : resolver(vertex, parameters);
+      yield { localId: context.localId, neighbors: iterate(neighbors) };
+    }
+  }
+
+  *resolveCoercion(
+    contexts: IterableIterator<JsContext<Vertex>>,
+    type_name: string,
+    coerce_to_type: string,
+  ): IterableIterator<ContextAndBool> {
+    const subtypes = SUBTYPES[coerce_to_type];
+    if (subtypes === undefined) {
+      throw new Error(
+        `attempted to coerce type '${type_name}' to unexpected type: ${coerce_to_type}`,
+      );
+    }
+    for (const context of contexts) {
+      const vertex = context.activeVertex;
+      yield { localId: context.localId, value: vertex !== null && subtypes.has(vertex.__typename) };
+    }
+  }
+}
@@ -0,0 +1,79 @@
+import type { JsEdgeParameters } from "trustfall_wasm";
+
+import type { Comment, Job, Story, User, Vertex } from "./vertex";
+
+/** Resolve the neighboring `User` vertices across the `byUser` edge. */
+export function commentByUser(vertex: Comment, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'byUser' of type 'Comment'");
+}
+
+/** Resolve the neighboring `Comment` vertices across the `reply` edge. */
+export function commentReply(vertex: Comment, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'reply' of type 'Comment'");
+}
+
+/** Resolve the neighboring `Webpage` vertices across the `link` edge. */
+export function commentLink(vertex: Comment, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'link' of type 'Comment'");
+}
+
+/** Resolve the neighboring `Item` vertices across the `parent` edge. */
+export function commentParent(vertex: Comment, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'parent' of type 'Comment'");
+}
+
+/** Resolve the neighboring `Webpage` vertices across the `link` edge. */
+export function jobLink(vertex: Job, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'link' of type 'Job'");
+}
+
+/** Resolve the neighboring `User` vertices across the `byUser` edge. */
+export function storyByUser(vertex: Story, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'byUser' of type 'Story'");
+}
+
+/** Resolve the neighboring `Comment` vertices across the `comment` edge. */
+export function storyComment(vertex: Story, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'comment' of type 'Story'");
+}
+
+/** Resolve the neighboring `Webpage` vertices across the `link` edge. */
+export function storyLink(vertex: Story, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'link' of type 'Story'");
+}
+
+/** Resolve the neighboring `Item` vertices across the `submitted` edge. */
+export function userSubmitted(vertex: User, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'submitted' of type 'User'");
+}
+
+/** Resolve the neighboring `Webpage` vertices across the `link` edge. */
+export function userLink(vertex: User, parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement edge 'link' of type 'User'");
+}
+
+export const EDGES: Record<
+  string,
+  // Each resolver is only called with vertices of the type it is registered for.
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  Record<string, (vertex: any, parameters: JsEdgeParameters) => Iterable<Vertex>>
+> = {
+  Comment: {
+    byUser: commentByUser,
+    reply: commentReply,
+    link: commentLink,
+    parent: commentParent,
+  },
+  Job: {
+    link: jobLink,
+  },
+  Story: {
+    byUser: storyByUser,
+    comment: storyComment,
+    link: storyLink,
+  },
+  User: {
+    submitted: userSubmitted,
+    link: userLink,
+  },
+};
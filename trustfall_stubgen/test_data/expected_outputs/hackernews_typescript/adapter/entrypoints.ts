@@ -0,0 +1,146 @@
+import type { JsEdgeParameters } from "trustfall_wasm";
+
+import type { Vertex } from "./vertex";
+
+/**
+ * Resolve the starting vertices of the `AskHN` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function askHn(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'AskHN'");
+}
+
+/**
+ * Resolve the starting vertices of the `Best` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function best(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Best'");
+}
+
+/**
+ * Resolve the starting vertices of the `FrontPage` entrypoint edge.
+ */
+export function frontPage(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'FrontPage'");
+}
+
+/**
+ * Resolve the starting vertices of the `Item` entrypoint edge.
+ *
+ * Parameters:
+ * - `id` of type `Int!`
+ */
+export function item(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Item'");
+}
+
+/**
+ * Resolve the starting vertices of the `Latest` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function latest(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Latest'");
+}
+
+/**
+ * Resolve the starting vertices of the `RecentJob` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function recentJob(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'RecentJob'");
+}
+
+/**
+ * Resolve the starting vertices of the `SearchByDate` entrypoint edge.
+ *
+ * Parameters:
+ * - `query` of type `String!`
+ */
+export function searchByDate(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'SearchByDate'");
+}
+
+/**
+ * Resolve the starting vertices of the `SearchByRelevance` entrypoint edge.
+ *
+ * Parameters:
+ * - `query` of type `String!`
+ */
+export function searchByRelevance(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'SearchByRelevance'");
+}
+
+/**
+ * Resolve the starting vertices of the `ShowHN` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function showHn(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'ShowHN'");
+}
+
+/**
+ * Resolve the starting vertices of the `Top` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function top(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Top'");
+}
+
+/**
+ * Resolve the starting vertices of the `UpdatedItem` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function updatedItem(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'UpdatedItem'");
+}
+
+/**
+ * Resolve the starting vertices of the `UpdatedUserProfile` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function updatedUserProfile(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'UpdatedUserProfile'");
+}
+
+/**
+ * Resolve the starting vertices of the `User` entrypoint edge.
+ *
+ * Parameters:
+ * - `name` of type `String!`
+ */
+export function user(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'User'");
+}
+
+export const ENTRYPOINTS: Record<string, (parameters: JsEdgeParameters) => Iterable<Vertex>> = {
+  AskHN: askHn,
+  Best: best,
+  FrontPage: frontPage,
+  Item: item,
+  Latest: latest,
+  RecentJob: recentJob,
+  SearchByDate: searchByDate,
+  SearchByRelevance: searchByRelevance,
+  ShowHN: showHn,
+  Top: top,
+  UpdatedItem: updatedItem,
+  UpdatedUserProfile: updatedUserProfile,
+  User: user,
+};
@@ -0,0 +1,3 @@
+export { Adapter } from "./adapter";
+export { SCHEMA_TEXT } from "./schema";
+export type { Vertex } from "./vertex";
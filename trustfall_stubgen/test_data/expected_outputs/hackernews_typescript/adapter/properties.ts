@@ -0,0 +1,175 @@
+import type { JsFieldValue } from "trustfall_wasm";
+
+import type { Comment, Job, Story, User, Vertex } from "./vertex";
+
+export function commentId(vertex: Comment): number {
+  throw new Error("implement property 'id' of type 'Comment'");
+}
+
+export function commentUnixTime(vertex: Comment): number {
+  throw new Error("implement property 'unixTime' of type 'Comment'");
+}
+
+export function commentUrl(vertex: Comment): string {
+  throw new Error("implement property 'url' of type 'Comment'");
+}
+
+export function commentTextHtml(vertex: Comment): string {
+  throw new Error("implement property 'textHtml' of type 'Comment'");
+}
+
+export function commentTextPlain(vertex: Comment): string {
+  throw new Error("implement property 'textPlain' of type 'Comment'");
+}
+
+export function commentByUsername(vertex: Comment): string {
+  throw new Error("implement property 'byUsername' of type 'Comment'");
+}
+
+export function itemId(vertex: Vertex): number {
+  throw new Error("implement property 'id' of type 'Item'");
+}
+
+export function itemUnixTime(vertex: Vertex): number {
+  throw new Error("implement property 'unixTime' of type 'Item'");
+}
+
+export function itemUrl(vertex: Vertex): string {
+  throw new Error("implement property 'url' of type 'Item'");
+}
+
+export function jobId(vertex: Job): number {
+  throw new Error("implement property 'id' of type 'Job'");
+}
+
+export function jobUnixTime(vertex: Job): number {
+  throw new Error("implement property 'unixTime' of type 'Job'");
+}
+
+export function jobUrl(vertex: Job): string {
+  throw new Error("implement property 'url' of type 'Job'");
+}
+
+export function jobTitle(vertex: Job): string {
+  throw new Error("implement property 'title' of type 'Job'");
+}
+
+export function jobScore(vertex: Job): number {
+  throw new Error("implement property 'score' of type 'Job'");
+}
+
+export function jobSubmittedUrl(vertex: Job): string {
+  throw new Error("implement property 'submittedUrl' of type 'Job'");
+}
+
+export function storyId(vertex: Story): number {
+  throw new Error("implement property 'id' of type 'Story'");
+}
+
+export function storyUnixTime(vertex: Story): number {
+  throw new Error("implement property 'unixTime' of type 'Story'");
+}
+
+export function storyUrl(vertex: Story): string {
+  throw new Error("implement property 'url' of type 'Story'");
+}
+
+export function storyByUsername(vertex: Story): string {
+  throw new Error("implement property 'byUsername' of type 'Story'");
+}
+
+export function storyScore(vertex: Story): number {
+  throw new Error("implement property 'score' of type 'Story'");
+}
+
+export function storyTextHtml(vertex: Story): string | null {
+  throw new Error("implement property 'textHtml' of type 'Story'");
+}
+
+export function storyTextPlain(vertex: Story): string | null {
+  throw new Error("implement property 'textPlain' of type 'Story'");
+}
+
+export function storyTitle(vertex: Story): string {
+  throw new Error("implement property 'title' of type 'Story'");
+}
+
+export function storySubmittedUrl(vertex: Story): string | null {
+  throw new Error("implement property 'submittedUrl' of type 'Story'");
+}
+
+export function userId(vertex: User): string {
+  throw new Error("implement property 'id' of type 'User'");
+}
+
+export function userKarma(vertex: User): number {
+  throw new Error("implement property 'karma' of type 'User'");
+}
+
+export function userAboutHtml(vertex: User): string | null {
+  throw new Error("implement property 'aboutHtml' of type 'User'");
+}
+
+export function userAboutPlain(vertex: User): string | null {
+  throw new Error("implement property 'aboutPlain' of type 'User'");
+}
+
+export function userUnixCreatedAt(vertex: User): number {
+  throw new Error("implement property 'unixCreatedAt' of type 'User'");
+}
+
+export function userUrl(vertex: User): string {
+  throw new Error("implement property 'url' of type 'User'");
+}
+
+export function webpageUrl(vertex: Vertex): string {
+  throw new Error("implement property 'url' of type 'Webpage'");
+}
+
+// Each resolver is only called with vertices of the type it is registered for.
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export const PROPERTIES: Record<string, Record<string, (vertex: any) => JsFieldValue>> = {
+  Comment: {
+    id: commentId,
+    unixTime: commentUnixTime,
+    url: commentUrl,
+    textHtml: commentTextHtml,
+    textPlain: commentTextPlain,
+    byUsername: commentByUsername,
+  },
+  Item: {
+    id: itemId,
+    unixTime: itemUnixTime,
+    url: itemUrl,
+  },
+  Job: {
+    id: jobId,
+    unixTime: jobUnixTime,
+    url: jobUrl,
+    title: jobTitle,
+    score: jobScore,
+    submittedUrl: jobSubmittedUrl,
+  },
+  Story: {
+    id: storyId,
+    unixTime: storyUnixTime,
+    url: storyUrl,
+    byUsername: storyByUsername,
+    score: storyScore,
+    textHtml: storyTextHtml,
+    textPlain: storyTextPlain,
+    title: storyTitle,
+    submittedUrl: storySubmittedUrl,
+  },
+  User: {
+    id: userId,
+    karma: userKarma,
+    aboutHtml: userAboutHtml,
+    aboutPlain: userAboutPlain,
+    unixCreatedAt: userUnixCreatedAt,
+    url: userUrl,
+  },
+  Webpage: {
+    url: webpageUrl,
+  },
+};
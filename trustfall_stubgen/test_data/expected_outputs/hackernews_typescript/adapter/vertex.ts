@@ -0,0 +1,66 @@
+/**
+ * A vertex of type `Comment`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Comment {
+  readonly __typename: "Comment";
+}
+
+/**
+ * A vertex of type `Item`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Item {
+  readonly __typename: "Item";
+}
+
+/**
+ * A vertex of type `Job`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Job {
+  readonly __typename: "Job";
+}
+
+/**
+ * A vertex of type `Story`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Story {
+  readonly __typename: "Story";
+}
+
+/**
+ * A vertex of type `User`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface User {
+  readonly __typename: "User";
+}
+
+/**
+ * A vertex of type `Webpage`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Webpage {
+  readonly __typename: "Webpage";
+}
+
+/** The type of vertices in the dataset implemented by this adapter. */
+export type Vertex = Comment | Item | Job | Story | User | Webpage;
+
+/** The names of the vertex types that are subtypes of each type, including itself. */
+export const SUBTYPES: Record<string, ReadonlySet<string>> = {
+  Comment: new Set(["Comment"]),
+  Item: new Set(["Comment", "Item", "Job", "Story"]),
+  Job: new Set(["Job"]),
+  Story: new Set(["Story"]),
+  User: new Set(["User"]),
+  Webpage: new Set(["Comment", "Item", "Job", "Story", "User", "Webpage"]),
+};
@@ -0,0 +1,12 @@
+use std::{ffi::OsString, path::PathBuf};
+
+#[non_exhaustive]
+#[derive(Debug, Clone, trustfall::provider::TrustfallEnumVertex)]
+pub enum Vertex {
+    Comment(String),
+    Item(()),
+    Job(std::collections::BTreeMap<String, String>),
+    Story(PathBuf),
+    User(OsString),
+    Webpage(()),
+}
@@ -0,0 +1,140 @@
+use std::sync::OnceLock;
+
+use trustfall::{FieldValue, Schema, provider::{AsVertex, BasicAdapter, ContextIterator, ContextOutcomeIterator, EdgeParameters, VertexIterator, resolve_coercion_using_schema}};
+
+use super::vertex::Vertex;
+
+static SCHEMA: OnceLock<Schema> = OnceLock::new();
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct Adapter {}
+
+impl Adapter {
+    pub const SCHEMA_TEXT: &'static str = include_str!("./schema.graphql");
+
+    pub fn schema() -> &'static Schema {
+        SCHEMA
+            .get_or_init(|| {
+                Schema::parse(Self::SCHEMA_TEXT).expect("not a valid schema")
+            })
+    }
+
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'a> BasicAdapter<'a> for Adapter {
+    type Vertex = Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'a, Self::Vertex> {
+        match edge_name {
+            "FrontPage" => super::entrypoints::front_page(),
+            "Item" => {
+                let id: i64 = parameters
+                    .get("id")
+                    .expect(
+                        "failed to find parameter 'id' when resolving 'Item' starting vertices",
+                    )
+                    .as_i64()
+                    .expect(
+                        "unexpected null or other incorrect datatype for Trustfall type 'Int!'",
+                    );
+                super::entrypoints::item(id)
+            }
+            "SearchByDate" => {
+                let query: &str = parameters
+                    .get("query")
+                    .expect(
+                        "failed to find parameter 'query' when resolving 'SearchByDate' starting vertices",
+                    )
+                    .as_str()
+                    .expect(
+                        "unexpected null or other incorrect datatype for Trustfall type 'String!'",
+                    );
+                super::entrypoints::search_by_date(query)
+            }
+            "SearchByRelevance" => {
+                let query: &str = parameters
+                    .get("query")
+                    .expect(
+                        "failed to find parameter 'query' when resolving 'SearchByRelevance' starting vertices",
+                    )
+                    .as_str()
+                    .expect(
+                        "unexpected null or other incorrect datatype for Trustfall type 'String!'",
+                    );
+                super::entrypoints::search_by_relevance(query)
+            }
+            "Top" => {
+                let max: Option<i64> = parameters
+                    .get("max")
+                    .expect(
+                        "failed to find parameter 'max' when resolving 'Top' starting vertices",
+                    )
+                    .as_i64();
+                super::entrypoints::top(max)
+            }
+            "UpdatedItem" => {
+                let max: Option<i64> = parameters
+                    .get("max")
+                    .expect(
+                        "failed to find parameter 'max' when resolving 'UpdatedItem' starting vertices",
+                    )
+                    .as_i64();
+                super::entrypoints::updated_item(max)
+            }
+            _ => {
+                unreachable!(
+                    "attempted to resolve starting vertices for unexpected edge name: {edge_name}"
+                )
+            }
+        }
+    }
+
+    fn resolve_property<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &str,
+        property_name: &str,
+    ) -> ContextOutcomeIterator<'a, V, FieldValue> {
+        match type_name {
+            "Item" => super::properties::resolve_item_property(contexts, property_name),
+            _ => {
+                unreachable!(
+                    "attempted to read property '{property_name}' on unexpected type: {type_name}"
+                )
+            }
+        }
+    }
+
+    fn resolve_neighbors<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        type_name: &str,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+    ) -> ContextOutcomeIterator<'a, V, VertexIterator<'a, Self::Vertex>> {
+        match type_name {
+            _ => {
+                unreachable!(
+                    "attempted to resolve edge '{edge_name}' on unexpected type: {type_name}"
+                )
+            }
+        }
+    }
+
+    fn resolve_coercion<V: AsVertex<Self::Vertex> + 'a>(
+        &self,
+        contexts: ContextIterator<'a, V>,
+        _type_name: &str,
+        coerce_to_type: &str,
+    ) -> ContextOutcomeIterator<'a, V, bool> {
+        resolve_coercion_using_schema(contexts, Self::schema(), coerce_to_type)
+    }
+}
@@ -0,0 +1,5 @@
+#[non_exhaustive]
+#[derive(Debug, Clone, trustfall::provider::TrustfallEnumVertex)]
+pub enum Vertex {
+    Item(()),
+}
@@ -0,0 +1,4 @@
+from .adapter_impl import SCHEMA, Adapter
+from .vertex import Vertex
+
+__all__ = ["SCHEMA", "Adapter", "Vertex"]
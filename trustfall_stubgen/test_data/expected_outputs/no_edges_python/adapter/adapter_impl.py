@@ -0,0 +1,90 @@
+from pathlib import Path
+from typing import Any, Dict, Iterable, Iterator, Mapping, Tuple, TypeVar
+
+from trustfall import Adapter as BaseAdapter
+from trustfall import Context, FieldValue, Schema
+
+from .edges import EDGES
+from .entrypoints import ENTRYPOINTS
+from .properties import PROPERTIES
+from .vertex import SUBTYPES, Vertex
+
+SCHEMA_TEXT = (Path(__file__).parent / "schema.graphql").read_text()
+SCHEMA = Schema(SCHEMA_TEXT)
+
+T = TypeVar("T")
+
+
+def _lookup(table: Dict[str, Dict[str, T]], type_name: str, field_name: str, kind: str) -> T:
+    resolver = table.get(type_name, {}).get(field_name)
+    if resolver is None:
+        raise NotImplementedError(
+            f"attempted to resolve {kind} '{field_name}' on unexpected type: {type_name}"
+        )
+    return resolver
+
+
+class Adapter(BaseAdapter[Vertex]):
+    def resolve_starting_vertices(
+        self,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Vertex]:
+        resolver = ENTRYPOINTS.get(edge_name)
+        if resolver is None:
+            raise NotImplementedError(
+                f"attempted to resolve starting vertices for unexpected edge name: {edge_name}"
+            )
+        return resolver(parameters)
+
+    def resolve_property(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        property_name: str,
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], FieldValue]]:
+        if property_name == "__typename":
+            for context in contexts:
+                vertex = context.active_vertex
+                yield (context, None if vertex is None else vertex.TYPENAME)
+            return
+
+        resolver = _lookup(PROPERTIES, type_name, property_name, "property")
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, None if vertex is None else resolver(vertex))
+
+    def resolve_neighbors(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        edge_name: str,
+        parameters: Mapping[str, FieldValue],
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], Iterable[Vertex]]]:
+        resolver = _lookup(EDGES, type_name, edge_name, "edge")
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, [] if vertex is None else resolver(vertex, parameters))
+
+    def resolve_coercion(
+        self,
+        contexts: Iterator[Context[Vertex]],
+        type_name: str,
+        coerce_to_type: str,
+        /,
+        *args: Any,
+        **kwargs: Any,
+    ) -> Iterable[Tuple[Context[Vertex], bool]]:
+        subtypes = SUBTYPES[coerce_to_type]
+        for context in contexts:
+            vertex = context.active_vertex
+            yield (context, vertex is not None and vertex.TYPENAME in subtypes)
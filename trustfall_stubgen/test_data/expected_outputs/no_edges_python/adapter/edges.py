@@ -0,0 +1,9 @@
+from typing import Any, Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import Vertex
+
+
+EDGES: Dict[str, Dict[str, Callable[[Any, Mapping[str, FieldValue]], Iterable[Vertex]]]] = {
+}
@@ -0,0 +1,76 @@
+from typing import Callable, Dict, Iterable, Mapping
+
+from trustfall import FieldValue
+
+from .vertex import Vertex
+
+
+def front_page(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'FrontPage'"
+    )
+
+
+def item(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Item` entrypoint edge.
+
+    Parameters:
+    - `id` of type `Int!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Item'"
+    )
+
+
+def search_by_date(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `SearchByDate` entrypoint edge.
+
+    Parameters:
+    - `query` of type `String!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'SearchByDate'"
+    )
+
+
+def search_by_relevance(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `SearchByRelevance` entrypoint edge.
+
+    Parameters:
+    - `query` of type `String!`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'SearchByRelevance'"
+    )
+
+
+def top(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `Top` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'Top'"
+    )
+
+
+def updated_item(parameters: Mapping[str, FieldValue]) -> Iterable[Vertex]:
+    """Resolve the starting vertices of the `UpdatedItem` entrypoint edge.
+
+    Parameters:
+    - `max` of type `Int`
+    """
+    raise NotImplementedError(
+        "implement resolving starting vertices for entrypoint edge 'UpdatedItem'"
+    )
+
+
+ENTRYPOINTS: Dict[str, Callable[[Mapping[str, FieldValue]], Iterable[Vertex]]] = {
+    "FrontPage": front_page,
+    "Item": item,
+    "SearchByDate": search_by_date,
+    "SearchByRelevance": search_by_relevance,
+    "Top": top,
+    "UpdatedItem": updated_item,
+}
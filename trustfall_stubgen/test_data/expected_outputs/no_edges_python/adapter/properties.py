@@ -0,0 +1,26 @@
+from typing import Any, Callable, Dict, List, Optional
+
+from trustfall import FieldValue
+
+from .vertex import Vertex
+
+
+def item_id(vertex: Vertex) -> int:
+    raise NotImplementedError("implement property 'id' of type 'Item'")
+
+
+def item_unix_time(vertex: Vertex) -> int:
+    raise NotImplementedError("implement property 'unixTime' of type 'Item'")
+
+
+def item_url(vertex: Vertex) -> str:
+    raise NotImplementedError("implement property 'url' of type 'Item'")
+
+
+PROPERTIES: Dict[str, Dict[str, Callable[[Any], FieldValue]]] = {
+    "Item": {
+        "id": item_id,
+        "unixTime": item_unix_time,
+        "url": item_url,
+    },
+}
@@ -0,0 +1,21 @@
+from dataclasses import dataclass
+from typing import ClassVar, Dict, FrozenSet, Union
+
+
+@dataclass
+class Item:
+    """A vertex of type `Item`.
+
+    Add the fields needed to resolve its properties and edges.
+    """
+
+    TYPENAME: ClassVar[str] = "Item"
+
+
+Vertex = Union[Item]
+"""The type of vertices in the dataset implemented by this adapter."""
+
+SUBTYPES: Dict[str, FrozenSet[str]] = {
+    "Item": frozenset({"Item"}),
+}
+"""The names of the vertex types that are subtypes of each type, including itself."""
@@ -0,0 +1,11 @@
+import type { JsEdgeParameters } from "trustfall_wasm";
+
+import type { Vertex } from "./vertex";
+
+export const EDGES: Record<
+  string,
+  // Each resolver is only called with vertices of the type it is registered for.
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  Record<string, (vertex: any, parameters: JsEdgeParameters) => Iterable<Vertex>>
+> = {
+};
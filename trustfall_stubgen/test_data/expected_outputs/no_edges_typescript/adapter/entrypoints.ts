@@ -0,0 +1,69 @@
+import type { JsEdgeParameters } from "trustfall_wasm";
+
+import type { Vertex } from "./vertex";
+
+/**
+ * Resolve the starting vertices of the `FrontPage` entrypoint edge.
+ */
+export function frontPage(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'FrontPage'");
+}
+
+/**
+ * Resolve the starting vertices of the `Item` entrypoint edge.
+ *
+ * Parameters:
+ * - `id` of type `Int!`
+ */
+export function item(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Item'");
+}
+
+/**
+ * Resolve the starting vertices of the `SearchByDate` entrypoint edge.
+ *
+ * Parameters:
+ * - `query` of type `String!`
+ */
+export function searchByDate(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'SearchByDate'");
+}
+
+/**
+ * Resolve the starting vertices of the `SearchByRelevance` entrypoint edge.
+ *
+ * Parameters:
+ * - `query` of type `String!`
+ */
+export function searchByRelevance(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'SearchByRelevance'");
+}
+
+/**
+ * Resolve the starting vertices of the `Top` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function top(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'Top'");
+}
+
+/**
+ * Resolve the starting vertices of the `UpdatedItem` entrypoint edge.
+ *
+ * Parameters:
+ * - `max` of type `Int`
+ */
+export function updatedItem(parameters: JsEdgeParameters): Iterable<Vertex> {
+  throw new Error("implement resolving starting vertices for entrypoint edge 'UpdatedItem'");
+}
+
+export const ENTRYPOINTS: Record<string, (parameters: JsEdgeParameters) => Iterable<Vertex>> = {
+  FrontPage: frontPage,
+  Item: item,
+  SearchByDate: searchByDate,
+  SearchByRelevance: searchByRelevance,
+  Top: top,
+  UpdatedItem: updatedItem,
+};
@@ -0,0 +1,25 @@
+import type { JsFieldValue } from "trustfall_wasm";
+
+import type { Vertex } from "./vertex";
+
+export function itemId(vertex: Vertex): number {
+  throw new Error("implement property 'id' of type 'Item'");
+}
+
+export function itemUnixTime(vertex: Vertex): number {
+  throw new Error("implement property 'unixTime' of type 'Item'");
+}
+
+export function itemUrl(vertex: Vertex): string {
+  throw new Error("implement property 'url' of type 'Item'");
+}
+
+// Each resolver is only called with vertices of the type it is registered for.
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export const PROPERTIES: Record<string, Record<string, (vertex: any) => JsFieldValue>> = {
+  Item: {
+    id: itemId,
+    unixTime: itemUnixTime,
+    url: itemUrl,
+  },
+};
@@ -0,0 +1,16 @@
+/**
+ * A vertex of type `Item`.
+ *
+ * Add the fields needed to resolve its properties and edges.
+ */
+export interface Item {
+  readonly __typename: "Item";
+}
+
+/** The type of vertices in the dataset implemented by this adapter. */
+export type Vertex = Item;
+
+/** The names of the vertex types that are subtypes of each type, including itself. */
+export const SUBTYPES: Record<string, ReadonlySet<string>> = {
+  Item: new Set(["Item"]),
+};
@@ -1,18 +1,17 @@
 #![forbid(unsafe_code)]
 #![forbid(unused_lifetimes)]
 #![forbid(elided_lifetimes_in_paths)]
+#![allow(clippy::result_large_err)]
 
 use anyhow::Context as _;
 use std::{
-    cell::RefCell,
     collections::{BTreeMap, BTreeSet},
     env,
     fmt::Debug,
     fs,
     path::PathBuf,
-    rc::Rc,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use async_graphql_parser::{parse_query, parse_schema};
@@ -182,7 +181,7 @@ fn trace_with_adapter<'a, AdapterT>(
     );
 
     let tracer =
-        Rc::new(RefCell::new(Trace::new(test_query.ir_query.clone(), test_query.arguments)));
+        Arc::new(Mutex::new(Trace::new(test_query.ir_query.clone(), test_query.arguments)));
     let mut adapter_tap = Arc::new(AdapterTap::new(adapter, tracer));
 
     let execution_result = execution::interpret_ir(adapter_tap.clone(), query, arguments);
@@ -327,6 +326,30 @@ fn corpus_graphql(path: &str, schema_name: &str) {
     println!("{output_data}");
 }
 
+/// Print a query and its arguments in the input format of the `execution` fuzz target:
+/// the query text, a NUL byte, then the arguments in RON format.
+fn corpus_execution(path: &str, schema_name: &str) {
+    let input_data = fs::read_to_string(path).unwrap();
+
+    let (prefix, last_extension) = path.rsplit_once('.').unwrap();
+    assert_eq!(last_extension, "ron");
+
+    let output_data = match prefix.rsplit_once('.') {
+        Some((_, "graphql")) => {
+            let test_query: TestGraphQLQuery = ron::from_str(&input_data).unwrap();
+            if test_query.schema_name != schema_name {
+                return;
+            }
+            let arguments = ron::to_string(&test_query.arguments).unwrap();
+            format!("{}\0{arguments}", test_query.query.replace("    ", " "))
+        }
+        Some((_, ext)) => unreachable!("{}", ext),
+        None => unreachable!("{}", path),
+    };
+
+    print!("{output_data}");
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     let mut reversed_args: Vec<_> = args.iter().map(|x| x.as_str()).rev().collect();
@@ -388,6 +411,15 @@ fn main() {
                 corpus_graphql(path, schema_name)
             }
         },
+        Some("corpus_execution") => match reversed_args.pop() {
+            None => panic!("No filename provided"),
+            Some(path) => {
+                let schema_name = reversed_args.pop().expect("schema name");
+
+                assert!(reversed_args.is_empty());
+                corpus_execution(path, schema_name)
+            }
+        },
         Some("check_fuzzed") => match reversed_args.pop() {
             None => panic!("No filename provided"),
             Some(path) => {
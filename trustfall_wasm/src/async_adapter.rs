@@ -0,0 +1,224 @@
+use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
+
+use gloo_utils::format::JsValueSerdeExt;
+use js_sys::{Array, Promise};
+use trustfall_core::{
+    interpreter::{execution::interpret_ir_async, AsyncAdapter, ResolveEdgeInfo, ResolveInfo},
+    ir::{EdgeParameters as CoreEdgeParameters, FieldValue, IndexedQuery},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    shim::{JsEdgeParameters, JsFieldValue},
+    QueryArgumentsError,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    /// A JS adapter whose resolvers are asynchronous.
+    ///
+    /// Each resolver returns a `Promise` of an iterable, an async iterable, or a plain iterable.
+    /// Instead of iterators of contexts, resolvers receive arrays of vertices and must produce
+    /// exactly one outcome per vertex, in the same order as the vertices.
+    #[derive(Clone)]
+    pub type JsAsyncAdapter;
+
+    #[wasm_bindgen(structural, method, js_name = "resolveStartingVertices")]
+    pub fn resolve_starting_vertices(
+        this: &JsAsyncAdapter,
+        edge: &str,
+        parameters: JsValue,
+    ) -> JsValue;
+
+    #[wasm_bindgen(structural, method, js_name = "resolveProperty")]
+    pub fn resolve_property(
+        this: &JsAsyncAdapter,
+        vertices: Array,
+        type_name: &str,
+        field_name: &str,
+    ) -> JsValue;
+
+    #[wasm_bindgen(structural, method, js_name = "resolveNeighbors")]
+    pub fn resolve_neighbors(
+        this: &JsAsyncAdapter,
+        vertices: Array,
+        type_name: &str,
+        edge_name: &str,
+        parameters: JsValue,
+    ) -> JsValue;
+
+    #[wasm_bindgen(structural, method, js_name = "resolveCoercion")]
+    pub fn resolve_coercion(
+        this: &JsAsyncAdapter,
+        vertices: Array,
+        type_name: &str,
+        coerce_to_type: &str,
+    ) -> JsValue;
+}
+
+#[wasm_bindgen(inline_js = "
+    export async function collectValues(value) {
+        const resolved = await value;
+        if (resolved != null && typeof resolved[Symbol.asyncIterator] === 'function') {
+            const values = [];
+            for await (const item of resolved) {
+                values.push(item);
+            }
+            return values;
+        }
+        return Array.from(resolved);
+    }
+")]
+extern "C" {
+    #[wasm_bindgen(js_name = "collectValues")]
+    fn collect_values(value: &JsValue) -> Promise;
+}
+
+/// Wraps a [`JsAsyncAdapter`] as an [`AsyncAdapter`].
+///
+/// JS resolvers may reject their `Promise`, which [`AsyncAdapter`] resolvers cannot express.
+/// The first such error is recorded here and reported once the query is done executing;
+/// until then, resolvers produce empty outcomes without calling the JS adapter again.
+struct AsyncAdapterShim {
+    inner: JsAsyncAdapter,
+    error: RefCell<Option<JsValue>>,
+}
+
+impl AsyncAdapterShim {
+    /// Await the outcome of a JS resolver call, recording its error if it failed.
+    async fn collect(&self, make_call: impl FnOnce() -> JsValue) -> Option<Array> {
+        if self.error.borrow().is_some() {
+            return None;
+        }
+
+        match JsFuture::from(collect_values(&make_call())).await {
+            Ok(values) => Some(values.into()),
+            Err(e) => {
+                self.error.borrow_mut().get_or_insert(e);
+                None
+            }
+        }
+    }
+
+    /// Produce one outcome per vertex by calling a JS resolver with the given vertices.
+    async fn resolve_each<OutcomeT>(
+        &self,
+        vertices: Vec<JsValue>,
+        default_outcome: impl Fn() -> OutcomeT,
+        make_call: impl FnOnce(Array) -> JsValue,
+        convert: impl Fn(JsValue) -> OutcomeT,
+    ) -> Vec<OutcomeT> {
+        let count = vertices.len();
+        let vertices: Array = vertices.into_iter().collect();
+        match self.collect(|| make_call(vertices)).await {
+            Some(outcomes) => outcomes.into_iter().map(convert).collect(),
+            None => std::iter::repeat_with(default_outcome).take(count).collect(),
+        }
+    }
+}
+
+impl AsyncAdapter for AsyncAdapterShim {
+    type Vertex = JsValue;
+
+    async fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &CoreEdgeParameters,
+        _resolve_info: &ResolveInfo,
+    ) -> Vec<Self::Vertex> {
+        let parameters: JsEdgeParameters = parameters.into();
+        let vertices = self
+            .collect(|| {
+                self.inner.resolve_starting_vertices(edge_name.as_ref(), parameters.into_js_dict())
+            })
+            .await;
+        vertices.map(|vertices| vertices.into_iter().collect()).unwrap_or_default()
+    }
+
+    async fn resolve_property(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> Vec<FieldValue> {
+        self.resolve_each(
+            vertices,
+            || FieldValue::Null,
+            |vertices| {
+                self.inner.resolve_property(vertices, type_name.as_ref(), property_name.as_ref())
+            },
+            |value| {
+                value
+                    .into_serde::<JsFieldValue>()
+                    .expect("async resolveProperty() produced an invalid property value")
+                    .into()
+            },
+        )
+        .await
+    }
+
+    async fn resolve_neighbors(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &CoreEdgeParameters,
+        _resolve_info: &ResolveEdgeInfo,
+    ) -> Vec<Vec<Self::Vertex>> {
+        let parameters: JsEdgeParameters = parameters.into();
+        self.resolve_each(
+            vertices,
+            Vec::new,
+            |vertices| {
+                self.inner.resolve_neighbors(
+                    vertices,
+                    type_name.as_ref(),
+                    edge_name.as_ref(),
+                    parameters.into_js_dict(),
+                )
+            },
+            |neighbors| Array::from(&neighbors).into_iter().collect(),
+        )
+        .await
+    }
+
+    async fn resolve_coercion(
+        &self,
+        vertices: Vec<Self::Vertex>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> Vec<bool> {
+        self.resolve_each(
+            vertices,
+            || false,
+            |vertices| {
+                self.inner.resolve_coercion(vertices, type_name.as_ref(), coerce_to_type.as_ref())
+            },
+            |value| value.as_bool().expect("async resolveCoercion() produced a non-boolean value"),
+        )
+        .await
+    }
+}
+
+/// Execute a query over an async JS adapter, producing all of the query's results.
+///
+/// If any JS resolver rejects its `Promise`, the query produces that error instead.
+pub(crate) async fn execute_query_async(
+    adapter: JsAsyncAdapter,
+    query: Arc<IndexedQuery>,
+    arguments: Arc<BTreeMap<Arc<str>, FieldValue>>,
+) -> Result<Vec<BTreeMap<Arc<str>, FieldValue>>, JsValue> {
+    #[allow(clippy::arc_with_non_send_sync)]
+    let shim = Arc::new(AsyncAdapterShim { inner: adapter, error: Default::default() });
+
+    let rows = interpret_ir_async(shim.clone(), query, arguments)
+        .await
+        .map_err(QueryArgumentsError::from)?;
+    match shim.error.take() {
+        Some(e) => Err(e),
+        None => Ok(rows),
+    }
+}
@@ -0,0 +1,157 @@
+//! Errors in queries and query arguments, with structured information about what went wrong.
+use gloo_utils::format::JsValueSerdeExt;
+use serde::Serialize;
+use trustfall_core::{
+    frontend::{error::FrontendError as BaseFrontendError, Diagnostic},
+    interpreter::error::QueryArgumentsError as BaseQueryArgumentsError,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::shim::JsFieldValue;
+
+/// Defines an error type describing a problem with the query text,
+/// exposing its diagnostics (error codes and positions in the query) to JavaScript.
+macro_rules! make_query_error_struct {
+    ($id:ident) => {
+        #[wasm_bindgen]
+        #[derive(Debug, Clone)]
+        pub struct $id {
+            message: String,
+            diagnostics: Vec<Diagnostic>,
+        }
+
+        #[wasm_bindgen]
+        impl $id {
+            #[wasm_bindgen(getter)]
+            pub fn message(&self) -> String {
+                self.message.clone()
+            }
+
+            /// An array of `{ code, message, spans }` objects, one per error. The `spans` of each
+            /// are `{ start, end }` ranges of `{ line, column }` positions in the query.
+            #[wasm_bindgen(getter)]
+            pub fn diagnostics(&self) -> JsValue {
+                JsValue::from_serde(&self.diagnostics).expect("serde conversion failed")
+            }
+
+            #[wasm_bindgen(js_name = "toString")]
+            pub fn to_js_string(&self) -> String {
+                self.message.clone()
+            }
+        }
+    };
+}
+
+make_query_error_struct!(ParseError);
+make_query_error_struct!(ValidationError);
+make_query_error_struct!(FrontendError);
+
+/// Converts an error from parsing the given query into the matching JavaScript error type.
+pub(crate) fn frontend_error_to_js(error: BaseFrontendError, query: &str) -> JsValue {
+    let diagnostics = Diagnostic::from_frontend_error(&error, query);
+    match error {
+        BaseFrontendError::ParseError(e) => {
+            ParseError { message: e.to_string(), diagnostics }.into()
+        }
+        BaseFrontendError::ValidationError(e) => {
+            ValidationError { message: e.to_string(), diagnostics }.into()
+        }
+        _ => FrontendError { message: error.to_string(), diagnostics }.into(),
+    }
+}
+
+/// A query argument whose value could not be converted to the type the query requires.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArgumentTypeError {
+    pub argument_name: String,
+    pub expected_type: String,
+    pub value: JsFieldValue,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct QueryArgumentsError {
+    message: String,
+    missing_arguments: Vec<String>,
+    unused_arguments: Vec<String>,
+    type_errors: Vec<ArgumentTypeError>,
+    invalid_regex_arguments: Vec<String>,
+}
+
+impl QueryArgumentsError {
+    fn collect(&mut self, error: BaseQueryArgumentsError) {
+        match error {
+            BaseQueryArgumentsError::MissingArguments(arguments) => {
+                self.missing_arguments.extend(arguments.into_iter().map(|argument| argument.name));
+            }
+            BaseQueryArgumentsError::UnusedArguments(arguments) => {
+                self.unused_arguments.extend(arguments.into_iter().map(|argument| argument.name));
+            }
+            BaseQueryArgumentsError::ArgumentTypeError(argument_name, expected_type, value) => {
+                self.type_errors.push(ArgumentTypeError {
+                    argument_name,
+                    expected_type,
+                    value: value.into(),
+                });
+            }
+            BaseQueryArgumentsError::InvalidRegexArgument(argument_name, _) => {
+                self.invalid_regex_arguments.push(argument_name);
+            }
+            // Only described by the error's message.
+            BaseQueryArgumentsError::UnidentifiableVertices(_) => {}
+            BaseQueryArgumentsError::MultipleErrors(errors) => {
+                for inner in errors.0 {
+                    self.collect(inner);
+                }
+            }
+        }
+    }
+}
+
+impl From<BaseQueryArgumentsError> for QueryArgumentsError {
+    fn from(error: BaseQueryArgumentsError) -> Self {
+        let mut converted = Self {
+            message: error.to_string(),
+            missing_arguments: vec![],
+            unused_arguments: vec![],
+            type_errors: vec![],
+            invalid_regex_arguments: vec![],
+        };
+        converted.collect(error);
+        converted
+    }
+}
+
+#[wasm_bindgen]
+impl QueryArgumentsError {
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = "missingArguments")]
+    pub fn missing_arguments(&self) -> JsValue {
+        JsValue::from_serde(&self.missing_arguments).expect("serde conversion failed")
+    }
+
+    #[wasm_bindgen(getter, js_name = "unusedArguments")]
+    pub fn unused_arguments(&self) -> JsValue {
+        JsValue::from_serde(&self.unused_arguments).expect("serde conversion failed")
+    }
+
+    #[wasm_bindgen(getter, js_name = "typeErrors")]
+    pub fn type_errors(&self) -> JsValue {
+        JsValue::from_serde(&self.type_errors).expect("serde conversion failed")
+    }
+
+    #[wasm_bindgen(getter, js_name = "invalidRegexArguments")]
+    pub fn invalid_regex_arguments(&self) -> JsValue {
+        JsValue::from_serde(&self.invalid_regex_arguments).expect("serde conversion failed")
+    }
+
+    #[wasm_bindgen(js_name = "toString")]
+    pub fn to_js_string(&self) -> String {
+        self.message.clone()
+    }
+}
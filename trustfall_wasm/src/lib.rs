@@ -10,14 +10,19 @@ use wasm_bindgen::prelude::*;
 
 use crate::{
     adapter::{AdapterShim, JsAdapter},
+    async_adapter::JsAsyncAdapter,
     shim::{JsFieldValue, QueryResultIterator},
 };
 
 #[macro_use]
 pub mod util;
 pub mod adapter;
+pub mod async_adapter;
+pub mod errors;
 pub mod shim;
 
+pub use errors::{FrontendError, ParseError, QueryArgumentsError, ValidationError};
+
 // Schema
 make_wasm_bindgen_struct_with_debug_clone!(Schema, trustfall_core::schema::Schema);
 
@@ -26,26 +31,10 @@ make_boxed_wasm_bindgen_struct_with_debug_clone!(
     InvalidSchemaError,
     trustfall_core::schema::error::InvalidSchemaError
 );
-make_boxed_wasm_bindgen_struct_with_debug_clone!(
-    ParseError,
-    trustfall_core::graphql_query::error::ParseError
-);
-make_boxed_wasm_bindgen_struct_with_debug_clone!(
-    ValidationError,
-    trustfall_core::frontend::error::ValidationError
-);
-make_boxed_wasm_bindgen_struct_with_debug_clone!(
-    FrontendError,
-    trustfall_core::frontend::error::FrontendError
-);
 make_boxed_wasm_bindgen_struct_with_debug_clone!(
     InvalidIRQueryError,
     trustfall_core::ir::InvalidIRQueryError
 );
-make_boxed_wasm_bindgen_struct_with_debug_clone!(
-    QueryArgumentsError,
-    trustfall_core::interpreter::error::QueryArgumentsError
-);
 
 #[wasm_bindgen]
 impl Schema {
@@ -69,6 +58,20 @@ impl Schema {
 
         set
     }
+
+    /// Serialize the schema into a compact binary representation,
+    /// which `Schema.fromBytes()` loads faster than parsing the schema text.
+    #[wasm_bindgen(js_name = "toBytes")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    #[wasm_bindgen(js_name = "fromBytes")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Schema, JsValue> {
+        trustfall_core::schema::Schema::from_bytes(bytes)
+            .map(Schema::new)
+            .map_err(|e| js_sys::Error::new(&e.to_string()).into())
+    }
 }
 
 pub fn from_js_args(args: JsValue) -> Result<Arc<BTreeMap<Arc<str>, FieldValue>>, String> {
@@ -89,18 +92,70 @@ pub fn execute_query(
     adapter: JsAdapter,
     query: &str,
     args: JsValue,
-) -> Result<QueryResultIterator, String> {
-    // TODO: add a proper error type
+) -> Result<QueryResultIterator, JsValue> {
+    // TODO: add a proper error type for arguments that cannot be deserialized
     let args = from_js_args(args)?;
 
-    let query = trustfall_core::frontend::parse(schema, query).map_err(|e| format!("{e}"))?;
+    let query = trustfall_core::frontend::parse(schema, query)
+        .map_err(|e| errors::frontend_error_to_js(e, query))?;
 
     #[allow(clippy::arc_with_non_send_sync)]
     let wrapped_adapter = Arc::new(AdapterShim::new(adapter));
 
     let results_iter =
         trustfall_core::interpreter::execution::interpret_ir(wrapped_adapter, query, args)
-            .map_err(|e| format!("{e}"))?;
+            .map_err(QueryArgumentsError::from)?;
 
     Ok(QueryResultIterator::new(results_iter))
 }
+
+/// Get a JSON Schema describing each result of the query, without executing it.
+#[wasm_bindgen(js_name = "queryResultJsonSchema")]
+pub fn query_result_json_schema(schema: &Schema, query: &str) -> Result<JsValue, JsValue> {
+    let parsed = trustfall_core::frontend::parse(schema, query)
+        .map_err(|e| errors::frontend_error_to_js(e, query))?;
+    let json_schema = parsed.query_outputs().result_json_schema();
+    Ok(JsValue::from_serde(&json_schema).expect("serde conversion failed"))
+}
+
+/// Get TypeScript source code for an interface describing each result of the query,
+/// without executing it.
+#[wasm_bindgen(js_name = "queryResultTypeScript")]
+pub fn query_result_typescript(
+    schema: &Schema,
+    query: &str,
+    interface_name: &str,
+) -> Result<String, JsValue> {
+    let parsed = trustfall_core::frontend::parse(schema, query)
+        .map_err(|e| errors::frontend_error_to_js(e, query))?;
+    Ok(parsed.query_outputs().result_typescript_interface(interface_name))
+}
+
+/// Execute a query over an adapter whose resolvers are asynchronous.
+///
+/// Returns a `Promise` of an array of all the query's results.
+#[wasm_bindgen(js_name = "executeQueryAsync")]
+pub fn execute_query_async(
+    schema: &Schema,
+    adapter: JsAsyncAdapter,
+    query: &str,
+    args: JsValue,
+) -> js_sys::Promise {
+    let args = match from_js_args(args) {
+        Ok(args) => args,
+        Err(e) => return js_sys::Promise::reject(&e.into()),
+    };
+    let query = match trustfall_core::frontend::parse(schema, query) {
+        Ok(query) => query,
+        Err(e) => return js_sys::Promise::reject(&errors::frontend_error_to_js(e, query)),
+    };
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let rows = async_adapter::execute_query_async(adapter, query, args).await?;
+        let rows: Vec<BTreeMap<Arc<str>, JsFieldValue>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|(k, v)| (k, v.into())).collect())
+            .collect();
+        Ok(JsValue::from_serde(&rows).expect("serde conversion failed"))
+    })
+}
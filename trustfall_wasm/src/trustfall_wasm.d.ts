@@ -49,6 +49,44 @@ export interface Adapter<T> {
     ): IterableIterator<ContextAndBool>;
 }
 
+/**
+ * The outcome of an `AsyncAdapter` resolver: a `Promise` of an iterable, an async iterable,
+ * or a plain iterable.
+ */
+export type AsyncOutcome<T> = Promise<Iterable<T>> | AsyncIterable<T> | Iterable<T>;
+
+/**
+ * An adapter whose resolvers are asynchronous, for use with `executeQueryAsync`.
+ *
+ * Instead of iterators of contexts, resolvers receive arrays of vertices and must produce
+ * exactly one outcome per vertex, in the same order as the vertices.
+ */
+export interface AsyncAdapter<T> {
+    resolveStartingVertices(
+        edge: string,
+        parameters: JsEdgeParameters,
+    ): AsyncOutcome<T>;
+
+    resolveProperty(
+        vertices: T[],
+        type_name: string,
+        field_name: string,
+    ): AsyncOutcome<JsFieldValue>;
+
+    resolveNeighbors(
+        vertices: T[],
+        type_name: string,
+        edge_name: string,
+        parameters: JsEdgeParameters,
+    ): AsyncOutcome<Iterable<T>>;
+
+    resolveCoercion(
+        vertices: T[],
+        type_name: string,
+        coerce_to_type: string,
+    ): AsyncOutcome<boolean>;
+}
+
 export class Schema {
     free(): void;
 
@@ -59,7 +97,7 @@ export class Schema {
      * to the specified type.
      * @param {string} type_name
      * @returns {Set<string>}
-     * @throws if `type_name` is not an interface or object type in this schema.
+     * @throws if `type_name` is not an interface, union, or object type in this schema.
      */
     subtypes(type_name: string): Set<string>;
 
@@ -68,6 +106,80 @@ export class Schema {
     * @returns {Schema}
     */
     static parse(input: string): Schema;
+
+    /**
+     * Serialize the schema into a compact binary representation,
+     * which `Schema.fromBytes()` loads faster than parsing the schema text.
+     * @returns {Uint8Array}
+     */
+    toBytes(): Uint8Array;
+
+    /**
+     * Load a schema serialized with `Schema.toBytes()`.
+     * @param {Uint8Array} bytes
+     * @returns {Schema}
+     * @throws if the bytes are not a schema serialized by this version of `trustfall_wasm`.
+     */
+    static fromBytes(bytes: Uint8Array): Schema;
+}
+
+export interface Position {
+    line: number;
+    column: number;
+}
+
+export interface Span {
+    start: Position;
+    end: Position;
+}
+
+export interface Diagnostic {
+    /** The name of the error variant, like `"UnsupportedEdgeFilter"`. */
+    code: string;
+    message: string;
+    /** The parts of the query responsible for the error. May be empty. */
+    spans: Span[];
+}
+
+/** The query is not syntactically valid. */
+export class ParseError {
+    free(): void;
+
+    readonly message: string;
+    readonly diagnostics: Diagnostic[];
+}
+
+/** The query is syntactically valid, but does not match the schema. */
+export class ValidationError {
+    free(): void;
+
+    readonly message: string;
+    readonly diagnostics: Diagnostic[];
+}
+
+/** The query matches the schema, but the operations it attempts are not supported or not valid. */
+export class FrontendError {
+    free(): void;
+
+    readonly message: string;
+    readonly diagnostics: Diagnostic[];
+}
+
+export interface ArgumentTypeError {
+    argumentName: string;
+    expectedType: string;
+    value: JsFieldValue;
+}
+
+/** The query cannot be executed with the provided arguments. */
+export class QueryArgumentsError {
+    free(): void;
+
+    readonly message: string;
+    readonly missingArguments: string[];
+    readonly unusedArguments: string[];
+    readonly typeErrors: ArgumentTypeError[];
+    readonly invalidRegexArguments: string[];
 }
 
 /**
@@ -76,6 +188,8 @@ export class Schema {
 * @param {string} query
 * @param {Record<string, JsFieldValue>} args
 * @returns {IterableIterator<Record<string, JsFieldValue>>}
+* @throws {ParseError | ValidationError | FrontendError} if the query is not valid.
+* @throws {QueryArgumentsError} if the query cannot be executed with the given arguments.
 */
 export function executeQuery<T>(
     schema: Schema,
@@ -84,4 +198,44 @@ export function executeQuery<T>(
     args: Record<string, JsFieldValue>,
 ): IterableIterator<Record<string, JsFieldValue>>;
 
+/**
+* Execute a query over an adapter whose resolvers are asynchronous.
+*
+* Resolvers are called with batches of at most 64 vertices, and up to 16 resolver calls
+* are awaited at the same time. Each vertex is only passed to the adapter once at each place
+* in the query. If any resolver's `Promise` is rejected, the returned `Promise` is rejected
+* with the same error.
+* @param {Schema} schema
+* @param {AsyncAdapter<T>} adapter
+* @param {string} query
+* @param {Record<string, JsFieldValue>} args
+* @returns {Promise<Record<string, JsFieldValue>[]>}
+*/
+export function executeQueryAsync<T>(
+    schema: Schema,
+    adapter: AsyncAdapter<T>,
+    query: string,
+    args: Record<string, JsFieldValue>,
+): Promise<Record<string, JsFieldValue>[]>;
+
+/**
+* Get a JSON Schema describing each result of the query, without executing it.
+* @param {Schema} schema
+* @param {string} query
+* @returns {object}
+* @throws {ParseError | ValidationError | FrontendError} if the query is not valid.
+*/
+export function queryResultJsonSchema(schema: Schema, query: string): object;
+
+/**
+* Get TypeScript source code for an interface describing each result of the query,
+* without executing it.
+* @param {Schema} schema
+* @param {string} query
+* @param {string} interfaceName
+* @returns {string}
+* @throws {ParseError | ValidationError | FrontendError} if the query is not valid.
+*/
+export function queryResultTypeScript(schema: Schema, query: string, interfaceName: string): string;
+
 export function initialize(): void;
@@ -176,10 +176,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type RootSchemaQuery {
     Number(max: Int!): [Number!]
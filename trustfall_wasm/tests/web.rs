@@ -1,7 +1,12 @@
-use common::{make_test_schema, run_numbers_query};
-use trustfall_core::ir::FieldValue;
-use trustfall_wasm::shim::JsFieldValue;
-use wasm_bindgen::prelude::*;
+use std::collections::BTreeMap;
+
+use common::{make_adapter, make_test_schema, run_numbers_query};
+use gloo_utils::format::JsValueSerdeExt;
+use trustfall_core::{frontend::Diagnostic, ir::FieldValue};
+use trustfall_wasm::{
+    async_adapter::JsAsyncAdapter, shim::JsFieldValue, ParseError, QueryArgumentsError,
+};
+use wasm_bindgen::{convert::TryFromJsValue, prelude::*};
 use wasm_bindgen_test::wasm_bindgen_test;
 
 #[macro_use]
@@ -39,10 +44,15 @@ schema {
 directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
 directive @tag(name: String) on FIELD
 directive @output(name: String) on FIELD
-directive @optional on FIELD
-directive @recurse(depth: Int!) on FIELD
+directive @optional(group: String) on FIELD
+directive @recurse(depth: Int, dedupe: Boolean) on FIELD
 directive @fold on FIELD
-directive @transform(op: String!) on FIELD
+directive @transform(op: String!, value: [String!]) on FIELD
+directive @order(direction: String!) on FIELD
+directive @limit(count: Int!) on FIELD
+directive @offset(count: Int!) on FIELD
+directive @dedupe on FIELD
+directive @absent on FIELD
 
 type RootSchemaQuery {
     Number(min: Int = 0, max: Int!): [Number!]
@@ -304,3 +314,116 @@ pub fn test_execute_query_with_traversal_and_coercion() {
 
     assert_eq!(expected_results.as_slice(), actual_results);
 }
+
+#[wasm_bindgen_test]
+pub fn test_parse_error_diagnostics() {
+    let schema = make_test_schema();
+    let error = trustfall_wasm::execute_query(
+        &schema,
+        make_adapter(),
+        "this isn't valid syntax",
+        js_sys::Object::new().into(),
+    )
+    .err()
+    .expect("query unexpectedly succeeded");
+
+    let error = ParseError::try_from_js_value(error).expect("not a ParseError");
+    let diagnostics: Vec<Diagnostic> =
+        error.diagnostics().into_serde().expect("diagnostics were not valid");
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("InvalidGraphQL", diagnostics[0].code);
+    assert_eq!(1, diagnostics[0].spans[0].start.line);
+    assert_eq!(1, diagnostics[0].spans[0].start.column);
+}
+
+#[wasm_bindgen_test]
+pub fn test_query_arguments_error_details() {
+    let schema = make_test_schema();
+    let query = r#"
+{
+    Number(max: 10) {
+        value @output @filter(op: ">", value: ["$required"])
+    }
+}"#;
+    let args = JsValue::from_serde(&btreemap! { "not_used" => 42 }).expect("invalid args");
+    let error = trustfall_wasm::execute_query(&schema, make_adapter(), query, args)
+        .err()
+        .expect("query unexpectedly succeeded");
+
+    let error = QueryArgumentsError::try_from_js_value(error).expect("not a QueryArgumentsError");
+    let missing: Vec<String> = error.missing_arguments().into_serde().expect("invalid value");
+    let unused: Vec<String> = error.unused_arguments().into_serde().expect("invalid value");
+
+    assert_eq!(vec!["required".to_string()], missing);
+    assert_eq!(vec!["not_used".to_string()], unused);
+}
+
+#[wasm_bindgen(inline_js = r#"
+    export function makeAsyncAdapter() {
+        const delay = () => new Promise((resolve) => setTimeout(resolve, 0));
+        return {
+            async resolveStartingVertices(edge, parameters) {
+                await delay();
+                return Array.from({ length: parameters["max"] }, (_, i) => i + 1);
+            },
+            async *resolveProperty(vertices, type_name, field_name) {
+                for (const vertex of vertices) {
+                    await delay();
+                    yield vertex;
+                }
+            },
+            async resolveNeighbors(vertices, type_name, edge_name, parameters) {
+                await delay();
+                return vertices.map((vertex) => [vertex + 1]);
+            },
+            async resolveCoercion(vertices, type_name, coerce_to_type) {
+                throw `unexpected coercion to ${coerce_to_type}`;
+            },
+        };
+    }
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeAsyncAdapter")]
+    fn make_async_adapter() -> JsAsyncAdapter;
+}
+
+#[wasm_bindgen_test]
+pub async fn test_execute_query_async() {
+    let schema = make_test_schema();
+    let query = r#"
+{
+    Number(max: 100) {
+        value @output @filter(op: ">", value: ["$min"])
+
+        successor {
+            next: value @output
+        }
+    }
+}"#;
+    let args = JsValue::from_serde(&btreemap! { "min" => 97 }).expect("invalid args");
+
+    let promise = trustfall_wasm::execute_query_async(&schema, make_async_adapter(), query, args);
+    let results: Vec<BTreeMap<String, JsFieldValue>> =
+        wasm_bindgen_futures::JsFuture::from(promise)
+            .await
+            .expect("query failed")
+            .into_serde()
+            .expect("results were not valid");
+
+    let expected_results = [
+        btreemap! {
+            String::from("value") => JsFieldValue::Integer(98),
+            String::from("next") => JsFieldValue::Integer(99),
+        },
+        btreemap! {
+            String::from("value") => JsFieldValue::Integer(99),
+            String::from("next") => JsFieldValue::Integer(100),
+        },
+        btreemap! {
+            String::from("value") => JsFieldValue::Integer(100),
+            String::from("next") => JsFieldValue::Integer(101),
+        },
+    ];
+    assert_eq!(expected_results.as_slice(), results);
+}